@@ -3,7 +3,7 @@
 //!
 //! The MPC-VM then executes the bytecode and performs the [witness extension](https://docs.circom.io/getting-started/computing-the-witness/) in MPC (Multiparty Computation).
 //!
-//! The compiler and the VM are generic over a [`Pairing`](https://docs.rs/ark-ec/latest/ark_ec/pairing/trait.Pairing.html). Currently, we support the curves `bn254` and `bls12-381`.
+//! The compiler and the VM are generic over a [`Pairing`](https://docs.rs/ark-ec/latest/ark_ec/pairing/trait.Pairing.html). Currently, we support the curves `bn254`, `bls12-381` and `bls12-377`.
 //!
 //! The [`CoCircomCompiler`], provides two methods for interacting with circom files
 //!     * [`CoCircomCompiler::parse`] - to parse a circuit
@@ -39,6 +39,7 @@ use circom_program_structure::{
 use circom_type_analysis::check_types;
 use circom_types::traits::CircomArkworksPairingBridge;
 use circom_types::traits::CircomArkworksPrimeFieldBridge;
+use co_circom_snarks::InputSchema;
 use eyre::eyre;
 use eyre::{bail, Result};
 use itertools::Itertools;
@@ -626,6 +627,29 @@ where
         Self::new(file, config).get_public_inputs_inner()
     }
 
+    /// Returns a `Result<InputSchema>` describing the input signals of the provided .circom file,
+    /// i.e., their names, their (flattened) sizes, and whether they are public.
+    ///
+    /// This method is useful to validate the input before secret-sharing it. In contrast to
+    /// [`get_public_inputs`](Self::get_public_inputs), it has to build the circuit.
+    ///
+    /// # Params
+    /// * **file** - a `String` denoting the path to circom file.
+    /// * **config** - the [CompilerConfig]
+    /// # Returns
+    ///
+    /// Returns a `Result` where:
+    ///
+    /// - `Ok(schema)` contains the [`InputSchema`] of the circuit.
+    /// - `Err(err)` indicates an error occurred during parsing or compilation.
+    pub fn get_input_schema<Pth>(file: Pth, config: CompilerConfig) -> Result<InputSchema>
+    where
+        PathBuf: From<Pth>,
+        Pth: std::fmt::Debug,
+    {
+        Self::new(file, config).get_input_schema_inner()
+    }
+
     /// Parsed the circuit provided by `file` and returns a `Result` of [`CoCircomCompilerParsed`].
     ///
     /// # Params
@@ -656,6 +680,22 @@ where
         Ok(program_archive.public_inputs)
     }
 
+    fn get_input_schema_inner(self) -> Result<InputSchema> {
+        let program_archive = self.get_program_archive()?;
+        let public_inputs = program_archive.public_inputs.clone();
+        let (circuit, _) = self.build_circuit(program_archive)?;
+        let schema = InputSchema::new(
+            circuit
+                .c_producer
+                .main_input_list
+                .into_iter()
+                .map(|x| (x.name, x.size)),
+            &public_inputs,
+        );
+        tracing::debug!("input schema: {schema:?}");
+        Ok(schema)
+    }
+
     fn parse_inner(mut self) -> Result<CoCircomCompilerParsed<P::ScalarField>> {
         tracing::debug!("compiler starts parsing..");
         let program_archive = self.get_program_archive()?;
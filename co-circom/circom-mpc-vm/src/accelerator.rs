@@ -5,6 +5,9 @@ use eyre::bail;
 
 use crate::mpc::VmCircomWitnessExtension;
 
+/// The name of the template which is accelerated with an oblivious shuffle.
+pub(crate) const SHUFFLE_TEMPLATE: &str = "MpcShuffle";
+
 type AcceleratorFunction<F, C> = Box<
     dyn Fn(
             &mut C,
@@ -42,6 +45,21 @@ pub struct MpcAcceleratorConfig {
     /// Whether to use the pre-defined ISZERO accelerator
     /// Default: true
     pub(crate) iszero: bool,
+    /// Whether to use the pre-defined SHUFFLE accelerator
+    /// Default: true
+    pub(crate) shuffle: bool,
+    /// Whether to use the pre-defined LUT accelerator
+    /// Default: true
+    pub(crate) lut: bool,
+    /// Whether to use the pre-defined LUT_WRITE accelerator
+    /// Default: true
+    pub(crate) lut_write: bool,
+    /// Whether to use the pre-defined KECCAK accelerator
+    /// Default: true
+    pub(crate) keccak: bool,
+    /// Whether to use the pre-defined MUL_TRUNC accelerator
+    /// Default: true
+    pub(crate) mul_trunc: bool,
 }
 
 impl Default for MpcAcceleratorConfig {
@@ -51,6 +69,11 @@ impl Default for MpcAcceleratorConfig {
             num2bits: true,
             addbits: true,
             iszero: true,
+            shuffle: true,
+            lut: true,
+            lut_write: true,
+            keccak: true,
+            mul_trunc: true,
         }
     }
 }
@@ -81,6 +104,11 @@ impl MpcAcceleratorConfig {
     /// - NUM2BITS
     /// - ADDBITS
     /// - ISZERO
+    /// - SHUFFLE
+    /// - LUT
+    /// - LUT_WRITE
+    /// - KECCAK
+    /// - MUL_TRUNC
     ///
     /// Possible values for the boolean variables are: "1", "true", "on", "0", "false", "off"
     pub fn from_env() -> Self {
@@ -97,6 +125,21 @@ impl MpcAcceleratorConfig {
             iszero: std::env::var("CIRCOM_MPC_ACCELERATOR_ISZERO")
                 .map(|x| map_env_string_to_bool(&x))
                 .unwrap_or(true),
+            shuffle: std::env::var("CIRCOM_MPC_ACCELERATOR_SHUFFLE")
+                .map(|x| map_env_string_to_bool(&x))
+                .unwrap_or(true),
+            lut: std::env::var("CIRCOM_MPC_ACCELERATOR_LUT")
+                .map(|x| map_env_string_to_bool(&x))
+                .unwrap_or(true),
+            lut_write: std::env::var("CIRCOM_MPC_ACCELERATOR_LUT_WRITE")
+                .map(|x| map_env_string_to_bool(&x))
+                .unwrap_or(true),
+            keccak: std::env::var("CIRCOM_MPC_ACCELERATOR_KECCAK")
+                .map(|x| map_env_string_to_bool(&x))
+                .unwrap_or(true),
+            mul_trunc: std::env::var("CIRCOM_MPC_ACCELERATOR_MUL_TRUNC")
+                .map(|x| map_env_string_to_bool(&x))
+                .unwrap_or(true),
         }
     }
 }
@@ -134,6 +177,21 @@ impl<F: PrimeField, C: VmCircomWitnessExtension<F>> MpcAccelerator<F, C> {
         if config.iszero {
             accelerator.register_iszero();
         }
+        if config.shuffle {
+            accelerator.register_shuffle();
+        }
+        if config.lut {
+            accelerator.register_lut();
+        }
+        if config.lut_write {
+            accelerator.register_lut_write();
+        }
+        if config.keccak {
+            accelerator.register_keccak();
+        }
+        if config.mul_trunc {
+            accelerator.register_mul_trunc();
+        }
         accelerator
     }
 
@@ -221,6 +279,91 @@ impl<F: PrimeField, C: VmCircomWitnessExtension<F>> MpcAccelerator<F, C> {
         });
     }
 
+    /// Registers the accelerator for the `MpcShuffle(n)` template, which has an input `in[n]` and an output `out[n]`. In the MPC-VM, the output is an oblivious shuffle of the input. Since a shuffle cannot be constrained, the template itself is expected to just copy the input using `out[i] <-- in[i]`.
+    fn register_shuffle(&mut self) {
+        self.register_component(SHUFFLE_TEMPLATE.to_string(), |protocol, args, amount_outputs| {
+            tracing::debug!("calling pre-defined MpcShuffle accelerator");
+            if args.len() != amount_outputs {
+                bail!("Calling MpcShuffle accelerator with a different number of inputs and outputs!");
+            }
+            Ok(ComponentAcceleratorOutput {
+                output: protocol.shuffle(args.to_vec())?,
+                intermediate: Vec::new(),
+            })
+        });
+    }
+
+    /// Registers the accelerator for the `MpcLut(n, m)` template, which has the inputs `table[n]` and `index[m]` and an output `out[m]`. In the MPC-VM, `out[i]` is obliviously read from the table at the (possibly secret-shared) `index[i]`. The template itself is expected to compute `out[i] <-- table[index[i]]` and to constrain the result.
+    fn register_lut(&mut self) {
+        self.register_component("MpcLut".to_string(), |protocol, args, amount_outputs| {
+            tracing::debug!("calling pre-defined MpcLut accelerator");
+            if args.len() <= amount_outputs {
+                bail!("Calling MpcLut accelerator without a table!");
+            }
+            let (table, indices) = args.split_at(args.len() - amount_outputs);
+            Ok(ComponentAcceleratorOutput {
+                output: protocol.lut(table.to_vec(), indices.to_vec())?,
+                intermediate: Vec::new(),
+            })
+        });
+    }
+
+    /// Registers the accelerator for the `MpcLutWrite(n, m)` template, which has the inputs `table[n]`, `index[m]` and `value[m]` and an output `out[n]`. In the MPC-VM, `out` is the table after obliviously writing `value[i]` at the (possibly secret-shared) `index[i]` for all i in order. The template itself is expected to compute the writes with `<--` and to constrain the result.
+    fn register_lut_write(&mut self) {
+        self.register_component(
+            "MpcLutWrite".to_string(),
+            |protocol, args, amount_outputs| {
+                tracing::debug!("calling pre-defined MpcLutWrite accelerator");
+                if args.len() < amount_outputs || (args.len() - amount_outputs) % 2 != 0 {
+                    bail!("Calling MpcLutWrite accelerator with an invalid number of arguments!");
+                }
+                let (table, writes) = args.split_at(amount_outputs);
+                let (indices, values) = writes.split_at(writes.len() / 2);
+                Ok(ComponentAcceleratorOutput {
+                    output: protocol.lut_write(
+                        table.to_vec(),
+                        indices.to_vec(),
+                        values.to_vec(),
+                    )?,
+                    intermediate: Vec::new(),
+                })
+            },
+        );
+    }
+
+    /// Registers the accelerator for the `MpcKeccakf1600()` template, which has an input `in[1600]` and an output `out[1600]` of bits. The bits are ordered by lane and bit, starting with the least significant bit of each lane, where lane (x, y) is at position x + 5y. In the MPC-VM, `out` is the Keccak-f\[1600\] permutation of `in`, evaluated on bit-sliced binary shares. The template itself is expected to compute the permutation with `<--` and to constrain the result.
+    fn register_keccak(&mut self) {
+        self.register_component(
+            "MpcKeccakf1600".to_string(),
+            |protocol, args, amount_outputs| {
+                tracing::debug!("calling pre-defined MpcKeccakf1600 accelerator");
+                if args.len() != amount_outputs {
+                    bail!("Calling MpcKeccakf1600 accelerator with a different number of inputs and outputs!");
+                }
+                Ok(ComponentAcceleratorOutput {
+                    output: protocol.keccak_f1600(args.to_vec())?,
+                    intermediate: Vec::new(),
+                })
+            },
+        );
+    }
+
+    /// Registers the accelerator for the `mpc_mul_trunc(a, b, frac_bits)` function, which returns the product of the signed fixed-point numbers a and b truncated by the public number of bits frac_bits. In the MPC-VM, the multiplication and the truncation are fused into a single communication round (see [`VmCircomWitnessExtension::mul_trunc`]).
+    fn register_mul_trunc(&mut self) {
+        self.register_function("mpc_mul_trunc_0".to_owned(), |protocol, args| {
+            tracing::debug!("calling pre-defined mpc_mul_trunc accelerator");
+            if args.len() != 3 {
+                bail!("Calling mpc_mul_trunc accelerator with a number of arguments other than three!");
+            }
+            let frac_bits = protocol.to_index(args[2].to_owned())?;
+            Ok(vec![protocol.mul_trunc(
+                args[0].to_owned(),
+                args[1].to_owned(),
+                frac_bits,
+            )?])
+        });
+    }
+
     pub(crate) fn run_cmp_accelerator(
         &self,
         name: &str,
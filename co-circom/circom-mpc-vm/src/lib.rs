@@ -12,6 +12,9 @@
 //! run-time optimization. We eagerly communicate after every non-linear operation and perform many unnecessary conversions between A and B shares.
 //!
 //! Major changes and optimizations are expected in the near future.
+//!
+//! Additionally, the VM can be executed with a [semi-honest two-party protocol](mpc_core::protocols::twopc) (see [`TwoPcWitnessExtension`](mpc_vm::TwoPcWitnessExtension)). It supports all operations of the VM with the exception of
+//! the `MpcShuffle` accelerator, since an oblivious shuffle cannot be computed with the OT-based two-party primitives. Circuits using this template are rejected when the VM is created.
 
 mod accelerator;
 /// This module contains the MPC-VM witness extension trait
@@ -25,3 +28,4 @@ mod stack;
 pub mod types;
 
 pub use mpc::rep3::Rep3VmType;
+pub use mpc::twopc::TwoPcVmType;
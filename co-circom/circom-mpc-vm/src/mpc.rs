@@ -6,6 +6,7 @@ use std::fmt;
 
 pub(crate) mod plain;
 pub(crate) mod rep3;
+pub(crate) mod twopc;
 
 /// This trait represents the operations used during witness extension by the co-circom MPC-VM
 pub trait VmCircomWitnessExtension<F: PrimeField> {
@@ -23,6 +24,14 @@ pub trait VmCircomWitnessExtension<F: PrimeField> {
     /// Multiply two VM-types: c = a * b.
     fn mul(&mut self, a: Self::VmType, b: Self::VmType) -> Result<Self::VmType>;
 
+    /// Multiply two VM-types and truncate the product by frac_bits bits, i.e., c = (a * b) >> frac_bits, where a and b are interpreted as signed fixed-point numbers. For secret-shared inputs, the multiplication and truncation are fused into a single communication round and the truncation is probabilistic, i.e., the result can be larger than the exact result by up to 3 in the least significant bit.
+    fn mul_trunc(
+        &mut self,
+        a: Self::VmType,
+        b: Self::VmType,
+        frac_bits: usize,
+    ) -> Result<Self::VmType>;
+
     /// Divide the VM-type a by the VM-type b: c = a / b. In finite fields, this is equivalent to multiplying a by the inverse of b.
     fn div(&mut self, a: Self::VmType, b: Self::VmType) -> Result<Self::VmType>;
 
@@ -103,6 +112,31 @@ pub trait VmCircomWitnessExtension<F: PrimeField> {
     /// Opens the VM-type a. If a is secret shared, it gets reconstructed.
     fn open(&mut self, a: Self::VmType) -> Result<F>;
 
+    /// Opens the VM-types towards the party with the given id. Only this party learns the reconstructed values,
+    /// all other parties get `None`.
+    fn open_to(&mut self, a: Vec<Self::VmType>, party_id: usize) -> Result<Option<Vec<F>>>;
+
+    /// Obliviously shuffles the VM-types with a random permutation that is unknown to all parties. The result is always secret-shared, so public inputs are hidden as well.
+    fn shuffle(&mut self, a: Vec<Self::VmType>) -> Result<Vec<Self::VmType>>;
+
+    /// Reads the table at the given indices, which may be secret-shared. The result is secret-shared if the table or the index is secret-shared. Fails if a public index is out of range, while secret-shared indices have to be in range for the result to be specified.
+    fn lut(
+        &mut self,
+        table: Vec<Self::VmType>,
+        indices: Vec<Self::VmType>,
+    ) -> Result<Vec<Self::VmType>>;
+
+    /// Writes the values to the table at the given indices and returns the updated table. The indices and values may be secret-shared, and the writes are applied in order. Writing at a secret-shared index makes the whole table secret-shared. Fails if a public index is out of range, while secret-shared indices have to be in range for the result to be specified.
+    fn lut_write(
+        &mut self,
+        table: Vec<Self::VmType>,
+        indices: Vec<Self::VmType>,
+        values: Vec<Self::VmType>,
+    ) -> Result<Vec<Self::VmType>>;
+
+    /// Computes the Keccak-f\[1600\] permutation on the given states, which consist of 1600 bits each. The bits of a state are ordered by lane and bit, starting with the least significant bit of each lane, where lane (x, y) is at position x + 5y. The inputs have to be bits, for secret-shared inputs the result is not specified otherwise.
+    fn keccak_f1600(&mut self, bits: Vec<Self::VmType>) -> Result<Vec<Self::VmType>>;
+
     /// Transforms a VM-type into a secret-shared value.
     fn to_share(&mut self, a: Self::VmType) -> Result<Self::ArithmeticShare>;
 
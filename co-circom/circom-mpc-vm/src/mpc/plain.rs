@@ -1,8 +1,10 @@
 use super::VmCircomWitnessExtension;
 use crate::mpc_vm::VMConfig;
 use ark_ff::{One, PrimeField};
+use eyre::bail;
 use eyre::eyre;
 use eyre::Result;
+use mpc_core::protocols::rep3::gadgets::keccak;
 use num_bigint::BigUint;
 use num_traits::cast::ToPrimitive;
 
@@ -96,6 +98,24 @@ impl<F: PrimeField> VmCircomWitnessExtension<F> for CircomPlainVmWitnessExtensio
         Ok(a * b)
     }
 
+    fn mul_trunc(
+        &mut self,
+        a: Self::VmType,
+        b: Self::VmType,
+        frac_bits: usize,
+    ) -> Result<Self::VmType> {
+        // rounds down, i.e., towards negative infinity for negative products
+        let product: BigUint = (a * b).into();
+        let modulus: BigUint = F::MODULUS.into();
+        if product > &modulus >> 1 {
+            let abs = modulus - product;
+            let rounding = (BigUint::one() << frac_bits) - BigUint::one();
+            Ok(-F::from((abs + rounding) >> frac_bits))
+        } else {
+            Ok(F::from(product >> frac_bits))
+        }
+    }
+
     fn neg(&mut self, a: Self::VmType) -> Result<Self::VmType> {
         Ok(-a)
     }
@@ -231,6 +251,83 @@ impl<F: PrimeField> VmCircomWitnessExtension<F> for CircomPlainVmWitnessExtensio
         Ok(a)
     }
 
+    fn open_to(&mut self, a: Vec<Self::VmType>, _party_id: usize) -> Result<Option<Vec<F>>> {
+        Ok(Some(a))
+    }
+
+    fn shuffle(&mut self, a: Vec<Self::VmType>) -> Result<Vec<Self::VmType>> {
+        // in plain, there is nothing to hide, so any permutation is a valid shuffle
+        Ok(a)
+    }
+
+    fn lut(
+        &mut self,
+        table: Vec<Self::VmType>,
+        indices: Vec<Self::VmType>,
+    ) -> Result<Vec<Self::VmType>> {
+        indices
+            .into_iter()
+            .map(|index| {
+                let index = to_usize!(index);
+                table.get(index).copied().ok_or_else(|| {
+                    eyre!(
+                        "Index {index} out of range for lookup table of size {}",
+                        table.len()
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn lut_write(
+        &mut self,
+        mut table: Vec<Self::VmType>,
+        indices: Vec<Self::VmType>,
+        values: Vec<Self::VmType>,
+    ) -> Result<Vec<Self::VmType>> {
+        if indices.len() != values.len() {
+            bail!("The number of indices and values of the lookup table writes do not match");
+        }
+        for (index, value) in indices.into_iter().zip(values) {
+            let index = to_usize!(index);
+            let len = table.len();
+            let Some(entry) = table.get_mut(index) else {
+                bail!("Index {index} out of range for lookup table of size {len}");
+            };
+            *entry = value;
+        }
+        Ok(table)
+    }
+
+    fn keccak_f1600(&mut self, bits: Vec<Self::VmType>) -> Result<Vec<Self::VmType>> {
+        if bits.len() % keccak::STATE_BITS != 0 {
+            bail!(
+                "Keccak-f[1600] requires a multiple of {} bits",
+                keccak::STATE_BITS
+            );
+        }
+        let mut result = Vec::with_capacity(bits.len());
+        for bits in bits.chunks_exact(keccak::STATE_BITS) {
+            let mut state = [0u64; 25];
+            for (lane, bits) in state.iter_mut().zip(bits.chunks_exact(64)) {
+                for (i, bit) in bits.iter().enumerate() {
+                    if bit.is_one() {
+                        *lane |= 1 << i;
+                    } else if !bit.is_zero() {
+                        bail!("Keccak-f[1600] input is not a bit");
+                    }
+                }
+            }
+            keccak::keccak_f1600(&mut state);
+            result.extend(
+                state
+                    .iter()
+                    .flat_map(|lane| (0..64).map(move |i| F::from((lane >> i) & 1))),
+            );
+        }
+        Ok(result)
+    }
+
     fn to_share(&mut self, a: Self::VmType) -> Result<Self::ArithmeticShare> {
         Ok(a)
     }
@@ -10,9 +10,13 @@ use mpc_core::protocols::rep3::{
     arithmetic::{self, promote_to_trivial_share},
     binary,
     conversion::{self, bit_inject_many},
+    fixed_point::{self, FixedPointConfig},
+    gadgets::{keccak, lut, shuffle},
+    id::PartyID,
     network::{IoContext, Rep3Network},
     Rep3PrimeFieldShare,
 };
+use mpc_core::protocols::rep3_ring::{self, casts, ring::ring_impl::RingElement};
 use num_bigint::BigUint;
 use num_traits::cast::ToPrimitive;
 use std::io;
@@ -62,20 +66,50 @@ pub struct CircomRep3VmWitnessExtension<F: PrimeField, N: Rep3Network> {
     io_context0: IoContext<N>,
     io_context1: IoContext<N>,
     plain: CircomPlainVmWitnessExtension<F>,
+    bitwise_in_ring: bool,
 }
 
 impl<F: PrimeField, N: Rep3Network> CircomRep3VmWitnessExtension<F, N> {
-    pub fn from_network(network: N, a2b_type: conversion::A2BType) -> io::Result<Self> {
+    pub fn from_network(
+        network: N,
+        a2b_type: conversion::A2BType,
+        a2b_auto_network: conversion::A2BAutoNetwork,
+        bitwise_in_ring: bool,
+    ) -> io::Result<Self> {
         let mut io_context = IoContext::init(network)?;
         io_context.set_a2b_type(a2b_type);
+        io_context.set_a2b_auto_network(a2b_auto_network);
         let io_context_fork = io_context.fork()?;
         Ok(Self {
             io_context0: io_context,
             io_context1: io_context_fork,
             plain: CircomPlainVmWitnessExtension::default(),
+            bitwise_in_ring,
         })
     }
 
+    /// Returns the public value as an element of the 64-bit ring if bitwise operations are performed in the ring and the value fits.
+    fn ring_public(&self, value: F) -> Option<RingElement<u64>> {
+        if !self.bitwise_in_ring {
+            return None;
+        }
+        let value: BigUint = value.into();
+        value.to_u64().map(RingElement)
+    }
+
+    /// Converts both shared operands to binary shares of the 64-bit ring in parallel.
+    fn convert_to_binary_ring(
+        &mut self,
+        a: ArithmeticShare<F>,
+        b: ArithmeticShare<F>,
+    ) -> io::Result<(rep3_ring::Rep3RingShare<u64>, rep3_ring::Rep3RingShare<u64>)> {
+        let (a, b) = join!(
+            casts::field_to_binary_ring(a, &mut self.io_context0),
+            casts::field_to_binary_ring(b, &mut self.io_context1)
+        );
+        Ok((a?, b?))
+    }
+
     pub fn get_network(self) -> N {
         self.io_context0.network
     }
@@ -93,6 +127,28 @@ impl<F: PrimeField, N: Rep3Network> CircomRep3VmWitnessExtension<F, N> {
         let p_half_plus_one = F::from(modulus / two + one);
         arithmetic::sub_shared_by_public(z, p_half_plus_one, self.io_context0.id)
     }
+
+    /// Obliviously writes the values to the table at the shared indices. The table stays untouched if there is nothing to write.
+    fn lut_write_shared(
+        &mut self,
+        table: Vec<Rep3VmType<F>>,
+        indices: &[ArithmeticShare<F>],
+        values: &[ArithmeticShare<F>],
+    ) -> eyre::Result<Vec<Rep3VmType<F>>> {
+        if indices.is_empty() {
+            return Ok(table);
+        }
+        let table = table
+            .into_iter()
+            .map(|x| self.to_share(x))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        Ok(
+            lut::write_lut_many(&table, indices, values, &mut self.io_context0)?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        )
+    }
 }
 
 impl<F: PrimeField, N: Rep3Network> VmCircomWitnessExtension<F>
@@ -143,6 +199,38 @@ impl<F: PrimeField, N: Rep3Network> VmCircomWitnessExtension<F>
         }
     }
 
+    fn mul_trunc(
+        &mut self,
+        a: Self::VmType,
+        b: Self::VmType,
+        frac_bits: usize,
+    ) -> eyre::Result<Self::VmType> {
+        // the largest encoding supported by the field, such that only frac_bits is relevant
+        let bit_size = FixedPointConfig::max_bit_size::<F>();
+        if frac_bits >= bit_size {
+            bail!(
+                "Cannot truncate by {frac_bits} bits in a field with {} bits",
+                F::MODULUS_BIT_SIZE
+            );
+        }
+        let config = FixedPointConfig::new(bit_size, frac_bits);
+        match (a, b) {
+            (Rep3VmType::Public(a), Rep3VmType::Public(b)) => {
+                Ok(self.plain.mul_trunc(a, b, frac_bits)?.into())
+            }
+            (Rep3VmType::Public(b), Rep3VmType::Arithmetic(a))
+            | (Rep3VmType::Arithmetic(a), Rep3VmType::Public(b)) => Ok(fixed_point::truncate(
+                arithmetic::mul_public(a, b),
+                &config,
+                &mut self.io_context0,
+            )?
+            .into()),
+            (Rep3VmType::Arithmetic(a), Rep3VmType::Arithmetic(b)) => {
+                Ok(fixed_point::mul_trunc(a, b, &config, &mut self.io_context0)?.into())
+            }
+        }
+    }
+
     fn div(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
         match (a, b) {
             (Rep3VmType::Public(a), Rep3VmType::Public(b)) => Ok(self.plain.div(a, b)?.into()),
@@ -432,11 +520,29 @@ impl<F: PrimeField, N: Rep3Network> VmCircomWitnessExtension<F>
             (Rep3VmType::Public(a), Rep3VmType::Public(b)) => Ok(self.plain.bit_xor(a, b)?.into()),
             (Rep3VmType::Public(b), Rep3VmType::Arithmetic(a))
             | (Rep3VmType::Arithmetic(a), Rep3VmType::Public(b)) => {
+                if let Some(b) = self.ring_public(b) {
+                    let a = casts::field_to_binary_ring(a, &mut self.io_context0)?;
+                    let binary = rep3_ring::binary::xor_public(&a, &b, self.io_context0.id);
+                    return Ok(casts::binary_ring_to_field::<_, F, _>(
+                        &binary,
+                        &mut self.io_context0,
+                    )?
+                    .into());
+                }
                 let a = conversion::a2b_selector(a, &mut self.io_context0)?;
                 let binary = binary::xor_public(&a, &b.into_bigint().into(), self.io_context0.id);
                 Ok(conversion::b2a_selector(&binary, &mut self.io_context0)?.into())
             }
             (Rep3VmType::Arithmetic(a), Rep3VmType::Arithmetic(b)) => {
+                if self.bitwise_in_ring {
+                    let (a, b) = self.convert_to_binary_ring(a, b)?;
+                    let binary = rep3_ring::binary::xor(&a, &b);
+                    return Ok(casts::binary_ring_to_field::<_, F, _>(
+                        &binary,
+                        &mut self.io_context0,
+                    )?
+                    .into());
+                }
                 let (a, b) = join!(
                     conversion::a2b_selector(a, &mut self.io_context0),
                     conversion::a2b_selector(b, &mut self.io_context1)
@@ -452,12 +558,30 @@ impl<F: PrimeField, N: Rep3Network> VmCircomWitnessExtension<F>
             (Rep3VmType::Public(a), Rep3VmType::Public(b)) => Ok(self.plain.bit_or(a, b)?.into()),
             (Rep3VmType::Public(b), Rep3VmType::Arithmetic(a))
             | (Rep3VmType::Arithmetic(a), Rep3VmType::Public(b)) => {
+                if let Some(b) = self.ring_public(b) {
+                    let a = casts::field_to_binary_ring(a, &mut self.io_context0)?;
+                    let binary = rep3_ring::binary::or_public(&a, &b, self.io_context0.id);
+                    return Ok(casts::binary_ring_to_field::<_, F, _>(
+                        &binary,
+                        &mut self.io_context0,
+                    )?
+                    .into());
+                }
                 let a = conversion::a2b_selector(a, &mut self.io_context0)?;
                 let binary = binary::or_public(&a, &b.into_bigint().into(), self.io_context0.id);
                 let result = conversion::b2a_selector(&binary, &mut self.io_context0)?;
                 Ok(result.into())
             }
             (Rep3VmType::Arithmetic(a), Rep3VmType::Arithmetic(b)) => {
+                if self.bitwise_in_ring {
+                    let (a, b) = self.convert_to_binary_ring(a, b)?;
+                    let binary = rep3_ring::binary::or(&a, &b, &mut self.io_context0)?;
+                    return Ok(casts::binary_ring_to_field::<_, F, _>(
+                        &binary,
+                        &mut self.io_context0,
+                    )?
+                    .into());
+                }
                 let (a, b) = join!(
                     conversion::a2b_selector(a, &mut self.io_context0),
                     conversion::a2b_selector(b, &mut self.io_context1)
@@ -474,12 +598,30 @@ impl<F: PrimeField, N: Rep3Network> VmCircomWitnessExtension<F>
             (Rep3VmType::Public(a), Rep3VmType::Public(b)) => Ok(self.plain.bit_and(a, b)?.into()),
             (Rep3VmType::Public(b), Rep3VmType::Arithmetic(a))
             | (Rep3VmType::Arithmetic(a), Rep3VmType::Public(b)) => {
+                if let Some(b) = self.ring_public(b) {
+                    let a = casts::field_to_binary_ring(a, &mut self.io_context0)?;
+                    let binary = rep3_ring::binary::and_with_public(&a, &b);
+                    return Ok(casts::binary_ring_to_field::<_, F, _>(
+                        &binary,
+                        &mut self.io_context0,
+                    )?
+                    .into());
+                }
                 let a = conversion::a2b_selector(a, &mut self.io_context0)?;
                 let binary = binary::and_with_public(&a, &b.into_bigint().into());
                 let result = conversion::b2a_selector(&binary, &mut self.io_context0)?;
                 Ok(result.into())
             }
             (Rep3VmType::Arithmetic(a), Rep3VmType::Arithmetic(b)) => {
+                if self.bitwise_in_ring {
+                    let (a, b) = self.convert_to_binary_ring(a, b)?;
+                    let binary = rep3_ring::binary::and(&a, &b, &mut self.io_context0)?;
+                    return Ok(casts::binary_ring_to_field::<_, F, _>(
+                        &binary,
+                        &mut self.io_context0,
+                    )?
+                    .into());
+                }
                 let (a, b) = join!(
                     conversion::a2b_selector(a, &mut self.io_context0),
                     conversion::a2b_selector(b, &mut self.io_context1)
@@ -523,6 +665,145 @@ impl<F: PrimeField, N: Rep3Network> VmCircomWitnessExtension<F>
         }
     }
 
+    fn open_to(&mut self, a: Vec<Self::VmType>, party_id: usize) -> eyre::Result<Option<Vec<F>>> {
+        let target = PartyID::try_from(party_id)?;
+        let shares = a
+            .into_iter()
+            .map(|a| self.to_share(a))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        Ok(arithmetic::open_vec_to(
+            &shares,
+            target,
+            &mut self.io_context0,
+        )?)
+    }
+
+    fn shuffle(&mut self, a: Vec<Self::VmType>) -> eyre::Result<Vec<Self::VmType>> {
+        let shares = a
+            .into_iter()
+            .map(|a| self.to_share(a))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        Ok(shuffle::shuffle(&shares, &mut self.io_context0)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    fn lut(
+        &mut self,
+        table: Vec<Self::VmType>,
+        indices: Vec<Self::VmType>,
+    ) -> eyre::Result<Vec<Self::VmType>> {
+        // public indices are looked up directly, only the shared ones are batched into the oblivious lookup
+        let mut result = Vec::with_capacity(indices.len());
+        let mut shared_indices = Vec::new();
+        let mut shared_positions = Vec::new();
+        for (i, index) in indices.into_iter().enumerate() {
+            match index {
+                Rep3VmType::Public(index) => {
+                    let index = to_usize!(index);
+                    let Some(value) = table.get(index) else {
+                        bail!(
+                            "Index {index} out of range for lookup table of size {}",
+                            table.len()
+                        );
+                    };
+                    result.push(value.to_owned());
+                }
+                Rep3VmType::Arithmetic(index) => {
+                    shared_indices.push(index);
+                    shared_positions.push(i);
+                    result.push(self.public_zero());
+                }
+            }
+        }
+        if shared_indices.is_empty() {
+            return Ok(result);
+        }
+        let looked_up = if table.iter().all(|x| matches!(x, Rep3VmType::Public(_))) {
+            let table = table
+                .into_iter()
+                .map(|x| match x {
+                    Rep3VmType::Public(x) => x,
+                    Rep3VmType::Arithmetic(_) => unreachable!(),
+                })
+                .collect_vec();
+            lut::read_public_lut_many(&table, &shared_indices, &mut self.io_context0)?
+        } else {
+            let table = table
+                .into_iter()
+                .map(|x| self.to_share(x))
+                .collect::<eyre::Result<Vec<_>>>()?;
+            lut::read_shared_lut_many(&table, &shared_indices, &mut self.io_context0)?
+        };
+        for (position, value) in shared_positions.into_iter().zip(looked_up) {
+            result[position] = value.into();
+        }
+        Ok(result)
+    }
+
+    fn lut_write(
+        &mut self,
+        mut table: Vec<Self::VmType>,
+        indices: Vec<Self::VmType>,
+        values: Vec<Self::VmType>,
+    ) -> eyre::Result<Vec<Self::VmType>> {
+        if indices.len() != values.len() {
+            bail!("The number of indices and values of the lookup table writes do not match");
+        }
+        // consecutive writes at shared indices are batched, writes at public indices are applied directly
+        let mut shared_indices = Vec::new();
+        let mut shared_values = Vec::new();
+        for (index, value) in indices.into_iter().zip(values) {
+            match index {
+                Rep3VmType::Public(index) => {
+                    table = self.lut_write_shared(table, &shared_indices, &shared_values)?;
+                    shared_indices.clear();
+                    shared_values.clear();
+                    let index = to_usize!(index);
+                    let len = table.len();
+                    let Some(entry) = table.get_mut(index) else {
+                        bail!("Index {index} out of range for lookup table of size {len}");
+                    };
+                    *entry = value;
+                }
+                Rep3VmType::Arithmetic(index) => {
+                    shared_indices.push(index);
+                    shared_values.push(self.to_share(value)?);
+                }
+            }
+        }
+        self.lut_write_shared(table, &shared_indices, &shared_values)
+    }
+
+    fn keccak_f1600(&mut self, bits: Vec<Self::VmType>) -> eyre::Result<Vec<Self::VmType>> {
+        if bits.iter().all(|x| matches!(x, Rep3VmType::Public(_))) {
+            let bits = bits
+                .into_iter()
+                .map(|x| match x {
+                    Rep3VmType::Public(x) => x,
+                    Rep3VmType::Arithmetic(_) => unreachable!(),
+                })
+                .collect_vec();
+            return Ok(self
+                .plain
+                .keccak_f1600(bits)?
+                .into_iter()
+                .map(Into::into)
+                .collect());
+        }
+        let bits = bits
+            .into_iter()
+            .map(|x| self.to_share(x))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        Ok(
+            keccak::keccak_f1600_bits_many(&bits, &mut self.io_context0)?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        )
+    }
+
     fn to_share(&mut self, a: Self::VmType) -> eyre::Result<Self::ArithmeticShare> {
         match a {
             Rep3VmType::Public(a) => {
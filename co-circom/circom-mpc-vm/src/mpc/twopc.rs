@@ -0,0 +1,847 @@
+use super::{
+    plain::{to_usize, CircomPlainVmWitnessExtension},
+    VmCircomWitnessExtension,
+};
+use crate::mpc_vm::VMConfig;
+use ark_ff::{One, PrimeField};
+use eyre::{bail, eyre};
+use itertools::Itertools;
+use mpc_core::protocols::shamir::network::ShamirNetwork;
+use mpc_core::protocols::twopc::{
+    arithmetic::{self, promote_to_trivial_share},
+    binary, conversion,
+    gadgets::{keccak, lut},
+    TwoPcPrimeFieldShare, TwoPcProtocol,
+};
+use num_bigint::BigUint;
+use num_traits::cast::ToPrimitive;
+
+type ArithmeticShare<F> = TwoPcPrimeFieldShare<F>;
+
+/// This type represents a public or an arithmetic share type used in the co-circom MPC-VM with the 2PC protocol
+#[derive(Clone)]
+pub enum TwoPcVmType<F: PrimeField> {
+    /// The public variant
+    Public(F),
+    /// The arithemtic share variant
+    Arithmetic(ArithmeticShare<F>),
+}
+
+impl<F: PrimeField> From<F> for TwoPcVmType<F> {
+    fn from(value: F) -> Self {
+        Self::Public(value)
+    }
+}
+
+impl<F: PrimeField> From<ArithmeticShare<F>> for TwoPcVmType<F> {
+    fn from(value: ArithmeticShare<F>) -> Self {
+        Self::Arithmetic(value)
+    }
+}
+
+impl<F: PrimeField> Default for TwoPcVmType<F> {
+    fn default() -> Self {
+        Self::Public(F::zero())
+    }
+}
+
+pub struct CircomTwoPcVmWitnessExtension<F: PrimeField, N: ShamirNetwork> {
+    protocol: TwoPcProtocol<N>,
+    plain: CircomPlainVmWitnessExtension<F>,
+}
+
+impl<F: PrimeField, N: ShamirNetwork> CircomTwoPcVmWitnessExtension<F, N> {
+    pub fn from_network(network: N) -> eyre::Result<Self> {
+        Ok(Self {
+            protocol: TwoPcProtocol::new(network)?,
+            plain: CircomPlainVmWitnessExtension::default(),
+        })
+    }
+
+    pub fn get_network(self) -> N {
+        self.protocol.network
+    }
+
+    /// Shifts the input by p/2 + 1 to the left, such that shared values can be compared as unsigned numbers (see the REP3 driver for details).
+    #[inline(always)]
+    fn val(&self, z: ArithmeticShare<F>) -> ArithmeticShare<F> {
+        let modulus: BigUint = F::MODULUS.into();
+        let one = BigUint::one();
+        let two = BigUint::from(2u64);
+        let p_half_plus_one = F::from(modulus / two + one);
+        arithmetic::sub_shared_by_public(z, p_half_plus_one, self.protocol.id())
+    }
+
+    /// Converts both shared operands to binary shares at once.
+    fn a2b_pair(
+        &mut self,
+        a: ArithmeticShare<F>,
+        b: ArithmeticShare<F>,
+    ) -> eyre::Result<(BigUint, BigUint)> {
+        let mut bits = conversion::a2b_many(&[a, b], &mut self.protocol)?;
+        let b = bits.pop().expect("two results");
+        let a = bits.pop().expect("two results");
+        Ok((a, b))
+    }
+
+    /// Converts a binary share of a value of the field's bit size back to an arithmetic share.
+    fn b2a(&mut self, x: &BigUint) -> eyre::Result<ArithmeticShare<F>> {
+        Ok(conversion::b2a(
+            x,
+            F::MODULUS_BIT_SIZE as usize,
+            &mut self.protocol,
+        )?)
+    }
+
+    /// Truncates a shared product by frac_bits bits, rounding towards negative infinity as the plain VM does. The value and the rounded-up absolute value of its negation are shifted in one batched binary conversion, and the sign selects the result.
+    fn truncate(
+        &mut self,
+        product: ArithmeticShare<F>,
+        frac_bits: usize,
+    ) -> eyre::Result<ArithmeticShare<F>> {
+        let bitlen = F::MODULUS_BIT_SIZE as usize;
+        if frac_bits >= bitlen - 1 {
+            bail!("Cannot truncate by {frac_bits} bits in a field with {bitlen} bits");
+        }
+        let id = self.protocol.id();
+        let rounding = F::from((BigUint::one() << frac_bits) - BigUint::one());
+        let abs = arithmetic::add_public(arithmetic::neg(product), rounding, id);
+        let bits = conversion::a2b_many(&[product, abs], &mut self.protocol)?;
+        // the product is negative iff it is larger than p/2
+        let modulus: BigUint = F::MODULUS.into();
+        let half = binary::promote_to_trivial_share(id, &((modulus >> 1) + BigUint::one()));
+        let is_neg = binary::unsigned_ge_many(&bits[..1], &[half], bitlen, &mut self.protocol)?;
+        let is_neg = conversion::bit_inject(&is_neg[0], &mut self.protocol)?;
+        let shifted = bits.iter().map(|x| x >> frac_bits).collect_vec();
+        let shifted = conversion::b2a_many(&shifted, bitlen - frac_bits, &mut self.protocol)?;
+        let (pos, neg) = (shifted[0], arithmetic::neg(shifted[1]));
+        let diff = arithmetic::mul(is_neg, arithmetic::sub(neg, pos), &mut self.protocol)?;
+        Ok(arithmetic::add(pos, diff))
+    }
+
+    /// Computes the quotient and the remainder of the integer division of a by b, where at least one of them is shared.
+    fn divmod(
+        &mut self,
+        a: TwoPcVmType<F>,
+        b: TwoPcVmType<F>,
+    ) -> eyre::Result<(ArithmeticShare<F>, ArithmeticShare<F>)> {
+        let a = self.to_share(a)?;
+        let b = self.to_share(b)?;
+        let (a, b) = self.a2b_pair(a, b)?;
+        let bitlen = F::MODULUS_BIT_SIZE as usize;
+        let (quotient, remainder) = binary::divmod_many(&[a], &[b], bitlen, &mut self.protocol)?;
+        let mut res =
+            conversion::b2a_many(&[quotient, remainder].concat(), bitlen, &mut self.protocol)?;
+        let remainder = res.pop().expect("two results");
+        let quotient = res.pop().expect("two results");
+        Ok((quotient, remainder))
+    }
+
+    /// Obliviously writes the values to the table at the shared indices. The table stays untouched if there is nothing to write.
+    fn lut_write_shared(
+        &mut self,
+        table: Vec<TwoPcVmType<F>>,
+        indices: &[ArithmeticShare<F>],
+        values: &[ArithmeticShare<F>],
+    ) -> eyre::Result<Vec<TwoPcVmType<F>>> {
+        if indices.is_empty() {
+            return Ok(table);
+        }
+        let table = table
+            .into_iter()
+            .map(|x| self.to_share(x))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        Ok(
+            lut::write_lut_many(&table, indices, values, &mut self.protocol)?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        )
+    }
+
+    /// Returns the public values if none of the values is shared.
+    fn all_public(values: &[TwoPcVmType<F>]) -> Option<Vec<F>> {
+        values
+            .iter()
+            .map(|x| match x {
+                TwoPcVmType::Public(x) => Some(*x),
+                TwoPcVmType::Arithmetic(_) => None,
+            })
+            .collect()
+    }
+}
+
+impl<F: PrimeField, N: ShamirNetwork> VmCircomWitnessExtension<F>
+    for CircomTwoPcVmWitnessExtension<F, N>
+{
+    type ArithmeticShare = ArithmeticShare<F>;
+
+    type VmType = TwoPcVmType<F>;
+
+    fn add(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.add(a, b)?.into()),
+            (TwoPcVmType::Public(b), TwoPcVmType::Arithmetic(a))
+            | (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                Ok(arithmetic::add_public(a, b, self.protocol.id()).into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                Ok(arithmetic::add(a, b).into())
+            }
+        }
+    }
+
+    fn sub(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.sub(a, b)?.into()),
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                Ok(arithmetic::sub_shared_by_public(a, b, self.protocol.id()).into())
+            }
+            (TwoPcVmType::Public(a), TwoPcVmType::Arithmetic(b)) => {
+                Ok(arithmetic::sub_public_by_shared(a, b, self.protocol.id()).into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                Ok(arithmetic::sub(a, b).into())
+            }
+        }
+    }
+
+    fn mul(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.mul(a, b)?.into()),
+            (TwoPcVmType::Public(b), TwoPcVmType::Arithmetic(a))
+            | (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                Ok(arithmetic::mul_public(a, b).into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                Ok(arithmetic::mul(a, b, &mut self.protocol)?.into())
+            }
+        }
+    }
+
+    fn mul_trunc(
+        &mut self,
+        a: Self::VmType,
+        b: Self::VmType,
+        frac_bits: usize,
+    ) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => {
+                Ok(self.plain.mul_trunc(a, b, frac_bits)?.into())
+            }
+            (TwoPcVmType::Public(b), TwoPcVmType::Arithmetic(a))
+            | (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => Ok(self
+                .truncate(arithmetic::mul_public(a, b), frac_bits)?
+                .into()),
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                let product = arithmetic::mul(a, b, &mut self.protocol)?;
+                Ok(self.truncate(product, frac_bits)?.into())
+            }
+        }
+    }
+
+    fn div(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.div(a, b)?.into()),
+            (TwoPcVmType::Public(a), TwoPcVmType::Arithmetic(b)) => {
+                let b = arithmetic::inv(b, &mut self.protocol)?;
+                Ok(arithmetic::mul_public(b, a).into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                if b.is_zero() {
+                    bail!("Cannot invert zero");
+                }
+                Ok(arithmetic::mul_public(a, b.inverse().unwrap()).into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                let b = arithmetic::inv(b, &mut self.protocol)?;
+                Ok(arithmetic::mul(a, b, &mut self.protocol)?.into())
+            }
+        }
+    }
+
+    fn int_div(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => {
+                Ok(self.plain.int_div(a, b)?.into())
+            }
+            (a, b) => {
+                let (quotient, _) = self.divmod(a, b)?;
+                Ok(quotient.into())
+            }
+        }
+    }
+
+    fn pow(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.pow(a, b)?.into()),
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                if b.is_zero() {
+                    return Ok(TwoPcVmType::Public(F::one()));
+                }
+                Ok(arithmetic::pow_public(a, b, &mut self.protocol)?.into())
+            }
+            (TwoPcVmType::Public(a), TwoPcVmType::Arithmetic(b)) => {
+                Ok(arithmetic::pow_public_base(a, b, &mut self.protocol)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                Ok(arithmetic::pow_shared_exponent(a, b, &mut self.protocol)?.into())
+            }
+        }
+    }
+
+    fn modulo(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.modulo(a, b)?.into()),
+            (a, b) => {
+                let (_, remainder) = self.divmod(a, b)?;
+                Ok(remainder.into())
+            }
+        }
+    }
+
+    fn sqrt(&mut self, a: Self::VmType) -> eyre::Result<Self::VmType> {
+        match a {
+            TwoPcVmType::Public(a) => Ok(self.plain.sqrt(a)?.into()),
+            TwoPcVmType::Arithmetic(a) => {
+                let sqrt = arithmetic::sqrt(a, &mut self.protocol)?;
+                // Correction to give the result closest to 0
+                // I.e., 2 * is_pos * sqrt - sqrt
+                let sqrt_val = self.val(sqrt);
+                let zero_val = self.plain.val(F::zero());
+                let is_pos = arithmetic::ge_public(sqrt_val, zero_val, &mut self.protocol)?;
+                let mul = arithmetic::mul(sqrt, is_pos, &mut self.protocol)?;
+                Ok(arithmetic::sub(mul + mul, sqrt).into())
+            }
+        }
+    }
+
+    fn neg(&mut self, a: Self::VmType) -> eyre::Result<Self::VmType> {
+        match a {
+            TwoPcVmType::Public(a) => Ok(self.plain.neg(a)?.into()),
+            TwoPcVmType::Arithmetic(a) => Ok(arithmetic::neg(a).into()),
+        }
+    }
+
+    fn lt(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.lt(a, b)?.into()),
+            (TwoPcVmType::Public(a), TwoPcVmType::Arithmetic(b)) => {
+                let a = self.plain.val(a);
+                let b = self.val(b);
+                Ok(arithmetic::gt_public(b, a, &mut self.protocol)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                let a = self.val(a);
+                let b = self.plain.val(b);
+                Ok(arithmetic::lt_public(a, b, &mut self.protocol)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                let a = self.val(a);
+                let b = self.val(b);
+                Ok(arithmetic::lt(a, b, &mut self.protocol)?.into())
+            }
+        }
+    }
+
+    fn le(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.le(a, b)?.into()),
+            (TwoPcVmType::Public(a), TwoPcVmType::Arithmetic(b)) => {
+                let a = self.plain.val(a);
+                let b = self.val(b);
+                Ok(arithmetic::ge_public(b, a, &mut self.protocol)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                let a = self.val(a);
+                let b = self.plain.val(b);
+                Ok(arithmetic::le_public(a, b, &mut self.protocol)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                let a = self.val(a);
+                let b = self.val(b);
+                Ok(arithmetic::le(a, b, &mut self.protocol)?.into())
+            }
+        }
+    }
+
+    fn gt(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.gt(a, b)?.into()),
+            (TwoPcVmType::Public(a), TwoPcVmType::Arithmetic(b)) => {
+                let a = self.plain.val(a);
+                let b = self.val(b);
+                Ok(arithmetic::lt_public(b, a, &mut self.protocol)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                let a = self.val(a);
+                let b = self.plain.val(b);
+                Ok(arithmetic::gt_public(a, b, &mut self.protocol)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                let a = self.val(a);
+                let b = self.val(b);
+                Ok(arithmetic::gt(a, b, &mut self.protocol)?.into())
+            }
+        }
+    }
+
+    fn ge(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.ge(a, b)?.into()),
+            (TwoPcVmType::Public(a), TwoPcVmType::Arithmetic(b)) => {
+                let a = self.plain.val(a);
+                let b = self.val(b);
+                Ok(arithmetic::le_public(b, a, &mut self.protocol)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                let a = self.val(a);
+                let b = self.plain.val(b);
+                Ok(arithmetic::ge_public(a, b, &mut self.protocol)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                let a = self.val(a);
+                let b = self.val(b);
+                Ok(arithmetic::ge(a, b, &mut self.protocol)?.into())
+            }
+        }
+    }
+
+    fn eq(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.eq(a, b)?.into()),
+            (TwoPcVmType::Public(b), TwoPcVmType::Arithmetic(a))
+            | (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                Ok(arithmetic::eq_public(a, b, &mut self.protocol)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                Ok(arithmetic::eq(a, b, &mut self.protocol)?.into())
+            }
+        }
+    }
+
+    fn neq(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.neq(a, b)?.into()),
+            (TwoPcVmType::Public(b), TwoPcVmType::Arithmetic(a))
+            | (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                Ok(arithmetic::neq_public(a, b, &mut self.protocol)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                Ok(arithmetic::neq(a, b, &mut self.protocol)?.into())
+            }
+        }
+    }
+
+    fn shift_r(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => {
+                Ok(self.plain.shift_r(a, b)?.into())
+            }
+            (TwoPcVmType::Public(a), TwoPcVmType::Arithmetic(_)) if a.is_zero() => {
+                Ok(TwoPcVmType::Public(F::zero()))
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                let shift = to_usize!(b);
+                let bits = conversion::a2b(a, &mut self.protocol)?;
+                Ok(self.b2a(&(bits >> shift))?.into())
+            }
+            (a, TwoPcVmType::Arithmetic(b)) => {
+                let a = self.to_share(a)?;
+                let (a, b) = self.a2b_pair(a, b)?;
+                let bits = binary::shift_r_by_shared(
+                    &a,
+                    &b,
+                    F::MODULUS_BIT_SIZE as usize,
+                    &mut self.protocol,
+                )?;
+                Ok(self.b2a(&bits)?.into())
+            }
+        }
+    }
+
+    fn shift_l(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => {
+                Ok(self.plain.shift_l(a, b)?.into())
+            }
+            (TwoPcVmType::Public(a), TwoPcVmType::Arithmetic(_)) if a.is_zero() => {
+                Ok(TwoPcVmType::Public(F::zero()))
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                Ok(arithmetic::pow_2_public(a, b).into())
+            }
+            (a, TwoPcVmType::Arithmetic(b)) => {
+                // a << b = a * 2^b
+                let pow = arithmetic::pow_public_base(F::from(2u64), b, &mut self.protocol)?;
+                self.mul(a, pow.into())
+            }
+        }
+    }
+
+    fn bool_not(&mut self, a: Self::VmType) -> eyre::Result<Self::VmType> {
+        match a {
+            TwoPcVmType::Public(a) => Ok(self.plain.bool_not(a)?.into()),
+            TwoPcVmType::Arithmetic(a) => {
+                Ok(arithmetic::sub_public_by_shared(F::one(), a, self.protocol.id()).into())
+            }
+        }
+    }
+
+    fn bool_and(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => {
+                Ok(self.plain.bool_and(a, b)?.into())
+            }
+            (a, b) => self.mul(a, b),
+        }
+    }
+
+    fn bool_or(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => {
+                Ok(self.plain.bool_or(a, b)?.into())
+            }
+            (TwoPcVmType::Public(b), TwoPcVmType::Arithmetic(a))
+            | (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                let mul = arithmetic::mul_public(a, b);
+                let add = arithmetic::add_public(a, b, self.protocol.id());
+                Ok(arithmetic::sub(add, mul).into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                let mul = arithmetic::mul(a, b, &mut self.protocol)?;
+                let add = arithmetic::add(a, b);
+                Ok(arithmetic::sub(add, mul).into())
+            }
+        }
+    }
+
+    fn cmux(
+        &mut self,
+        cond: Self::VmType,
+        truthy: Self::VmType,
+        falsy: Self::VmType,
+    ) -> eyre::Result<Self::VmType> {
+        match (cond, truthy, falsy) {
+            (TwoPcVmType::Public(cond), truthy, falsy) => {
+                assert!(cond.is_one() || cond.is_zero());
+                if cond.is_one() {
+                    Ok(truthy)
+                } else {
+                    Ok(falsy)
+                }
+            }
+            (TwoPcVmType::Arithmetic(cond), truthy, falsy) => {
+                let b_min_a = self.sub(truthy, falsy.clone())?;
+                let d = self.mul(cond.into(), b_min_a)?;
+                self.add(falsy, d)
+            }
+        }
+    }
+
+    fn bit_xor(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => {
+                Ok(self.plain.bit_xor(a, b)?.into())
+            }
+            (TwoPcVmType::Public(b), TwoPcVmType::Arithmetic(a))
+            | (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                let a = conversion::a2b(a, &mut self.protocol)?;
+                let binary = binary::xor_public(&a, &b.into(), self.protocol.id());
+                Ok(self.b2a(&binary)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                let (a, b) = self.a2b_pair(a, b)?;
+                let binary = binary::xor(&a, &b);
+                Ok(self.b2a(&binary)?.into())
+            }
+        }
+    }
+
+    fn bit_or(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => Ok(self.plain.bit_or(a, b)?.into()),
+            (TwoPcVmType::Public(b), TwoPcVmType::Arithmetic(a))
+            | (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                let a = conversion::a2b(a, &mut self.protocol)?;
+                let binary = binary::or_public(&a, &b.into(), self.protocol.id());
+                Ok(self.b2a(&binary)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                let (a, b) = self.a2b_pair(a, b)?;
+                let binary = binary::or(&a, &b, F::MODULUS_BIT_SIZE as usize, &mut self.protocol)?;
+                Ok(self.b2a(&binary)?.into())
+            }
+        }
+    }
+
+    fn bit_and(&mut self, a: Self::VmType, b: Self::VmType) -> eyre::Result<Self::VmType> {
+        match (a, b) {
+            (TwoPcVmType::Public(a), TwoPcVmType::Public(b)) => {
+                Ok(self.plain.bit_and(a, b)?.into())
+            }
+            (TwoPcVmType::Public(b), TwoPcVmType::Arithmetic(a))
+            | (TwoPcVmType::Arithmetic(a), TwoPcVmType::Public(b)) => {
+                let a = conversion::a2b(a, &mut self.protocol)?;
+                let binary = binary::and_with_public(&a, &b.into());
+                Ok(self.b2a(&binary)?.into())
+            }
+            (TwoPcVmType::Arithmetic(a), TwoPcVmType::Arithmetic(b)) => {
+                let (a, b) = self.a2b_pair(a, b)?;
+                let binary = binary::and(&a, &b, F::MODULUS_BIT_SIZE as usize, &mut self.protocol)?;
+                Ok(self.b2a(&binary)?.into())
+            }
+        }
+    }
+
+    fn is_zero(&mut self, a: Self::VmType, allow_secret_inputs: bool) -> eyre::Result<bool> {
+        if !allow_secret_inputs && self.is_shared(&a)? {
+            bail!("allow_secret_inputs is false and input is shared");
+        }
+        match a {
+            TwoPcVmType::Public(a) => Ok(self.plain.is_zero(a, allow_secret_inputs)?),
+            TwoPcVmType::Arithmetic(a) => Ok(arithmetic::is_zero(a, &mut self.protocol)?),
+        }
+    }
+
+    fn is_shared(&mut self, a: &Self::VmType) -> eyre::Result<bool> {
+        match a {
+            TwoPcVmType::Public(_) => Ok(false),
+            TwoPcVmType::Arithmetic(_) => Ok(true),
+        }
+    }
+
+    fn to_index(&mut self, a: Self::VmType) -> eyre::Result<usize> {
+        if let TwoPcVmType::Public(a) = a {
+            Ok(to_usize!(a))
+        } else {
+            bail!("ToIndex called on shared value!")
+        }
+    }
+
+    fn open(&mut self, a: Self::VmType) -> eyre::Result<F> {
+        match a {
+            TwoPcVmType::Public(a) => Ok(a),
+            TwoPcVmType::Arithmetic(a) => Ok(arithmetic::open(a, &mut self.protocol)?),
+        }
+    }
+
+    fn open_to(&mut self, a: Vec<Self::VmType>, party_id: usize) -> eyre::Result<Option<Vec<F>>> {
+        let shares = a
+            .into_iter()
+            .map(|a| self.to_share(a))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        Ok(arithmetic::open_vec_to(
+            &shares,
+            party_id,
+            &mut self.protocol,
+        )?)
+    }
+
+    fn shuffle(&mut self, _a: Vec<Self::VmType>) -> eyre::Result<Vec<Self::VmType>> {
+        bail!("Oblivious shuffling is not supported in 2PC")
+    }
+
+    fn lut(
+        &mut self,
+        table: Vec<Self::VmType>,
+        indices: Vec<Self::VmType>,
+    ) -> eyre::Result<Vec<Self::VmType>> {
+        // public indices are looked up directly, only the shared ones are batched into the oblivious lookup
+        let mut result = Vec::with_capacity(indices.len());
+        let mut shared_indices = Vec::new();
+        let mut shared_positions = Vec::new();
+        for (i, index) in indices.into_iter().enumerate() {
+            match index {
+                TwoPcVmType::Public(index) => {
+                    let index = to_usize!(index);
+                    let Some(value) = table.get(index) else {
+                        bail!(
+                            "Index {index} out of range for lookup table of size {}",
+                            table.len()
+                        );
+                    };
+                    result.push(value.to_owned());
+                }
+                TwoPcVmType::Arithmetic(index) => {
+                    shared_indices.push(index);
+                    shared_positions.push(i);
+                    result.push(self.public_zero());
+                }
+            }
+        }
+        if shared_indices.is_empty() {
+            return Ok(result);
+        }
+        let looked_up = if let Some(table) = Self::all_public(&table) {
+            lut::read_public_lut_many(&table, &shared_indices, &mut self.protocol)?
+        } else {
+            let table = table
+                .into_iter()
+                .map(|x| self.to_share(x))
+                .collect::<eyre::Result<Vec<_>>>()?;
+            lut::read_shared_lut_many(&table, &shared_indices, &mut self.protocol)?
+        };
+        for (position, value) in shared_positions.into_iter().zip(looked_up) {
+            result[position] = value.into();
+        }
+        Ok(result)
+    }
+
+    fn lut_write(
+        &mut self,
+        mut table: Vec<Self::VmType>,
+        indices: Vec<Self::VmType>,
+        values: Vec<Self::VmType>,
+    ) -> eyre::Result<Vec<Self::VmType>> {
+        if indices.len() != values.len() {
+            bail!("The number of indices and values of the lookup table writes do not match");
+        }
+        // consecutive writes at shared indices are batched, writes at public indices are applied directly
+        let mut shared_indices = Vec::new();
+        let mut shared_values = Vec::new();
+        for (index, value) in indices.into_iter().zip(values) {
+            match index {
+                TwoPcVmType::Public(index) => {
+                    table = self.lut_write_shared(table, &shared_indices, &shared_values)?;
+                    shared_indices.clear();
+                    shared_values.clear();
+                    let index = to_usize!(index);
+                    let len = table.len();
+                    let Some(entry) = table.get_mut(index) else {
+                        bail!("Index {index} out of range for lookup table of size {len}");
+                    };
+                    *entry = value;
+                }
+                TwoPcVmType::Arithmetic(index) => {
+                    shared_indices.push(index);
+                    shared_values.push(self.to_share(value)?);
+                }
+            }
+        }
+        self.lut_write_shared(table, &shared_indices, &shared_values)
+    }
+
+    fn keccak_f1600(&mut self, bits: Vec<Self::VmType>) -> eyre::Result<Vec<Self::VmType>> {
+        if let Some(bits) = Self::all_public(&bits) {
+            return Ok(self
+                .plain
+                .keccak_f1600(bits)?
+                .into_iter()
+                .map(Into::into)
+                .collect());
+        }
+        let bits = bits
+            .into_iter()
+            .map(|x| self.to_share(x))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        Ok(keccak::keccak_f1600_bits_many(&bits, &mut self.protocol)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    fn to_share(&mut self, a: Self::VmType) -> eyre::Result<Self::ArithmeticShare> {
+        match a {
+            TwoPcVmType::Public(a) => Ok(promote_to_trivial_share(self.protocol.id(), a)),
+            TwoPcVmType::Arithmetic(a) => Ok(a),
+        }
+    }
+
+    fn public_one(&self) -> Self::VmType {
+        F::one().into()
+    }
+
+    fn public_zero(&self) -> Self::VmType {
+        F::zero().into()
+    }
+
+    fn compare_vm_config(&mut self, config: &VMConfig) -> eyre::Result<()> {
+        let ser = bincode::serialize(&config)?;
+        let other = self.protocol.other_id();
+        self.protocol.network.send(other, ser)?;
+        let rcv: Vec<u8> = self.protocol.network.recv(other)?;
+        let deser = bincode::deserialize(&rcv)?;
+        if config != &deser {
+            bail!("VM Config does not match: {:?} != {:?}", config, deser);
+        }
+
+        Ok(())
+    }
+
+    fn num2bits(&mut self, a: Self::VmType, bits: usize) -> eyre::Result<Vec<Self::VmType>> {
+        match a {
+            TwoPcVmType::Public(a) => Ok(self
+                .plain
+                .num2bits(a, bits)?
+                .into_iter()
+                .map(Into::into)
+                .collect()),
+            TwoPcVmType::Arithmetic(a) => {
+                let a_bits = conversion::a2b(a, &mut self.protocol)?;
+                let a_bits_split = (0..bits)
+                    .map(|i| (&a_bits >> i) & BigUint::one())
+                    .collect_vec();
+                Ok(
+                    conversion::bit_inject_many(&a_bits_split, &mut self.protocol)?
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    fn addbits(
+        &mut self,
+        a: Vec<Self::VmType>,
+        b: Vec<Self::VmType>,
+    ) -> eyre::Result<(Vec<Self::VmType>, Self::VmType)> {
+        assert!(a.len() == b.len());
+        let bitlen = a.len();
+        assert!(bitlen < F::MODULUS_BIT_SIZE as usize - 1);
+        let id = self.protocol.id();
+        let a = a.into_iter().map(|x| match x {
+            TwoPcVmType::Public(x) => promote_to_trivial_share(id, x),
+            TwoPcVmType::Arithmetic(x) => x,
+        });
+        let b = b.into_iter().map(|x| match x {
+            TwoPcVmType::Public(x) => promote_to_trivial_share(id, x),
+            TwoPcVmType::Arithmetic(x) => x,
+        });
+
+        let a_sum = a.fold(TwoPcPrimeFieldShare::zero_share(), |acc, x| acc + acc + x);
+        let b_sum = b.fold(TwoPcPrimeFieldShare::zero_share(), |acc, x| acc + acc + x);
+
+        let sum = a_sum + b_sum;
+
+        let sum_bits = conversion::a2b(sum, &mut self.protocol)?;
+        let individual_bits = (0..bitlen + 1)
+            .map(|i| (&sum_bits >> i) & BigUint::one())
+            .collect_vec();
+        let mut result = conversion::bit_inject_many(&individual_bits, &mut self.protocol)?;
+        let carry = result.pop().unwrap();
+        result.reverse();
+        Ok((result.into_iter().map(Into::into).collect(), carry.into()))
+    }
+}
+
+impl<F: PrimeField> std::fmt::Debug for TwoPcVmType<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Public(field) => f.debug_tuple("Public").field(field).finish(),
+            Self::Arithmetic(share) => f.debug_tuple("Arithmetic").field(share).finish(),
+        }
+    }
+}
+
+impl<F: PrimeField> std::fmt::Display for TwoPcVmType<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Public(field) => f.write_str(&format!("Public ({field})")),
+            Self::Arithmetic(arithmetic) => {
+                f.write_str(&format!("Arithmetic (a: {})", arithmetic.inner()))
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use crate::accelerator::MpcAcceleratorConfig;
+use crate::accelerator::{MpcAcceleratorConfig, SHUFFLE_TEMPLATE};
 use crate::mpc::plain::CircomPlainVmWitnessExtension;
 use crate::mpc::rep3::{CircomRep3VmWitnessExtension, Rep3VmType};
+use crate::mpc::twopc::{CircomTwoPcVmWitnessExtension, TwoPcVmType};
 use crate::types::{CoCircomCompilerParsed, FunDecl, InputList, OutputMapping, TemplateDecl};
 
 use super::accelerator::MpcAccelerator;
@@ -10,13 +11,15 @@ use super::{
 };
 use crate::mpc::VmCircomWitnessExtension;
 use ark_ff::PrimeField;
-use co_circom_snarks::{SharedInput, SharedWitness};
+use co_circom_snarks::{OutputDisclosure, OutputPolicy, SharedInput, SharedWitness};
 use core::panic;
 use eyre::{bail, eyre, Result};
 use itertools::{izip, Itertools};
-use mpc_core::protocols::rep3::conversion::A2BType;
+use mpc_core::protocols::rep3::conversion::{A2BAutoNetwork, A2BType};
 use mpc_core::protocols::rep3::network::{Rep3MpcNet, Rep3Network};
 use mpc_core::protocols::rep3::Rep3PrimeFieldShare;
+use mpc_core::protocols::shamir::network::{ShamirMpcNet, ShamirNetwork};
+use mpc_core::protocols::twopc::TwoPcPrimeFieldShare;
 use mpc_net::config::NetworkConfig;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -31,6 +34,21 @@ pub struct VMConfig {
     /// Define the implementation of the arithmetic/binary conversions.
     #[serde(default)]
     pub a2b_type: A2BType,
+    /// The network assumed when the arithmetic/binary conversions are chosen automatically, i.e., if `a2b_type` is
+    /// `Auto`. All parties have to use the same values.
+    #[serde(default)]
+    pub a2b_auto_network: A2BAutoNetwork,
+    /// Keep the public inputs secret-shared after the witness extension. They are only revealed by the prover.
+    #[serde(default)]
+    pub hide_public_inputs: bool,
+    /// Which parties may learn the reconstructed outputs. If the policy does not reveal all outputs to all parties,
+    /// the public inputs are kept secret-shared in the witness, as with `hide_public_inputs`.
+    #[serde(default)]
+    pub output_policy: OutputPolicy,
+    /// Perform the bitwise operations `&`, `|` and `^` on shared values on binary shares of the 64-bit ring instead of the full field elements.
+    /// Only correct if all shared operands of these operations are smaller than 2^64, e.g., for the words of hash functions.
+    #[serde(default)]
+    pub bitwise_in_ring: bool,
 }
 
 /// The MPC-VM that performs the witness extension.
@@ -58,10 +76,13 @@ pub struct WitnessExtension<F: PrimeField, C: VmCircomWitnessExtension<F>> {
 pub type PlainWitnessExtension<F> = WitnessExtension<F, CircomPlainVmWitnessExtension<F>>;
 
 /// Shorthand type for the MPC-VM instantiated with a `Rep3` protocol.
-///
-/// This is the only supported protocol at the moment.
 pub type Rep3WitnessExtension<F, N> = WitnessExtension<F, CircomRep3VmWitnessExtension<F, N>>;
 
+/// Shorthand type for the MPC-VM instantiated with the semi-honest two-party protocol.
+///
+/// Operations which require integer division, square roots or lookups at shared indices are not supported by this protocol.
+pub type TwoPcWitnessExtension<F, N> = WitnessExtension<F, CircomTwoPcVmWitnessExtension<F, N>>;
+
 type ConsumedFunCtx<T> = (usize, usize, Vec<T>, Arc<CodeBlock>, Vec<(T, Vec<T>)>);
 
 #[derive(Default, Clone)]
@@ -859,23 +880,94 @@ impl<F: PrimeField, C: VmCircomWitnessExtension<F>> WitnessExtension<F, C> {
         amount_public_inputs: usize,
     ) -> Result<FinalizedWitnessExtension<F, C>> {
         let total_public_amount = self.main_outputs + amount_public_inputs + 1;
-        let mut public_inputs = Vec::with_capacity(total_public_amount);
+        let reveals_all = self.config.output_policy.reveals_all();
+        // reveal the outputs according to the policy. We sort by name, as all parties have to open in the same order
+        let revealed_outputs = if reveals_all {
+            None
+        } else {
+            let mut revealed_outputs = HashMap::new();
+            for (name, (offset, size)) in self.output_mapping.iter().sorted() {
+                let signals = self.signal_to_witness[*offset..*offset + *size]
+                    .iter()
+                    .map(|idx| self.ctx.signals[*idx].clone())
+                    .collect_vec();
+                match self.config.output_policy.disclosure(name).clone() {
+                    OutputDisclosure::All => {
+                        let values = signals
+                            .into_iter()
+                            .map(|signal| self.driver.open(signal))
+                            .collect::<Result<Vec<_>>>()?;
+                        revealed_outputs.insert(name.clone(), values);
+                    }
+                    OutputDisclosure::Parties(parties) => {
+                        for party_id in parties.into_iter().sorted().dedup() {
+                            if let Some(values) = self.driver.open_to(signals.clone(), party_id)? {
+                                revealed_outputs.insert(name.clone(), values);
+                            }
+                        }
+                    }
+                    OutputDisclosure::None => {}
+                }
+            }
+            Some(revealed_outputs)
+        };
+        // if the public inputs are hidden, only the constant 1 is opened
+        let num_revealed = if self.config.hide_public_inputs || !reveals_all {
+            1
+        } else {
+            total_public_amount
+        };
+        let mut public_inputs = Vec::with_capacity(num_revealed);
+        let mut shared_public_inputs = Vec::with_capacity(total_public_amount - num_revealed);
         let mut witness = Vec::with_capacity(self.signal_to_witness.len() - total_public_amount);
         for (count, idx) in self.signal_to_witness.iter().enumerate() {
             // the +1 here is for the constant 1 which always is at position 0.
-            if count < total_public_amount {
+            if count < num_revealed {
                 public_inputs.push(self.driver.open(self.ctx.signals[*idx].clone())?);
+            } else if count < total_public_amount {
+                shared_public_inputs.push(self.driver.to_share(self.ctx.signals[*idx].clone())?);
             } else {
                 witness.push(self.driver.to_share(self.ctx.signals[*idx].clone())?);
             }
         }
+        // if no signal of the witness is secret-shared, every party knows the whole witness and can prove on its own
+        let mut fully_public = true;
+        for idx in self.signal_to_witness.iter() {
+            if self.driver.is_shared(&self.ctx.signals[*idx])? {
+                fully_public = false;
+                break;
+            }
+        }
+        let public_witness = if fully_public {
+            let mut values = self
+                .signal_to_witness
+                .iter()
+                .map(|idx| self.driver.open(self.ctx.signals[*idx].clone()))
+                .collect::<Result<Vec<_>>>()?;
+            let witness = values.split_off(total_public_amount);
+            Some(SharedWitness {
+                public_inputs: values,
+                shared_public_inputs: vec![],
+                witness,
+            })
+        } else {
+            None
+        };
+        let output_disclosures = self
+            .config
+            .output_policy
+            .resolve(&self.output_mapping, total_public_amount)?;
         Ok(FinalizedWitnessExtension {
             shared_witness: SharedWitness {
                 public_inputs,
+                shared_public_inputs,
                 witness,
             },
+            public_witness,
             // TODO take instead of clone? or consume self again and close network in here?
             output_mapping: self.output_mapping.clone(),
+            revealed_outputs,
+            output_disclosures,
         })
     }
 
@@ -995,7 +1087,10 @@ impl<F: PrimeField, C: VmCircomWitnessExtension<F>> WitnessExtension<F, C> {
 /// If you want to retrieve the shared witness, call [`into_shared_witness()`](FinalizedWitnessExtension::into_shared_witness()).
 pub struct FinalizedWitnessExtension<F: PrimeField, C: VmCircomWitnessExtension<F>> {
     shared_witness: SharedWitness<F, C::ArithmeticShare>,
+    public_witness: Option<SharedWitness<F, F>>,
     output_mapping: OutputMapping,
+    revealed_outputs: Option<HashMap<String, Vec<F>>>,
+    output_disclosures: Vec<OutputDisclosure>,
 }
 
 impl<F: PrimeField, C: VmCircomWitnessExtension<F>> From<FinalizedWitnessExtension<F, C>>
@@ -1012,6 +1107,22 @@ impl<F: PrimeField, C: VmCircomWitnessExtension<F>> FinalizedWitnessExtension<F,
         self.shared_witness
     }
 
+    /// Returns the witness in plain, if none of its signals is secret-shared, e.g., because all inputs were public.
+    ///
+    /// All parties know such a witness, so the proof can be created by a single party with the plain prover
+    /// (e.g., `Groth16::plain_prove_with_public_inputs`) instead of running the MPC protocol. The resulting proof has the
+    /// same format as the one created in MPC. In contrast to the shared witness, all public inputs are contained in
+    /// [`SharedWitness::public_inputs`], as hiding them is pointless if every party already knows them.
+    pub fn public_witness(&self) -> Option<&SharedWitness<F, F>> {
+        self.public_witness.as_ref()
+    }
+
+    /// Consumes self and returns the witness in plain, if none of its signals is secret-shared. See
+    /// [`public_witness()`](FinalizedWitnessExtension::public_witness()).
+    pub fn into_public_witness(self) -> Option<SharedWitness<F, F>> {
+        self.public_witness
+    }
+
     /// Returns the signals associated with the provided name in the circom file.
     ///
     /// # Example
@@ -1034,12 +1145,23 @@ impl<F: PrimeField, C: VmCircomWitnessExtension<F>> FinalizedWitnessExtension<F,
     ///
     /// # Returns
     /// Returns an `Option<Vec<F>>` containing the signals associated with the requested output.
-    /// Returns `None` if the name is not known.
+    /// Returns `None` if the name is not known, or if the [`OutputPolicy`](co_circom_snarks::OutputPolicy) of the
+    /// [`VMConfig`] does not reveal the output to this party.
     pub fn get_output(&self, name: &str) -> Option<Vec<F>> {
+        if let Some(revealed_outputs) = &self.revealed_outputs {
+            return revealed_outputs.get(name).cloned();
+        }
         self.output_mapping.get(name).map(|(offset, amount)| {
             self.shared_witness.public_inputs[*offset..*offset + *amount].to_vec()
         })
     }
+
+    /// Returns the [`OutputPolicy`](co_circom_snarks::OutputPolicy) of the [`VMConfig`] resolved to one
+    /// [`OutputDisclosure`] per public input (excluding the constant 1). This can be passed to the prover,
+    /// to open the hidden public inputs according to the policy after the proof is assembled.
+    pub fn output_disclosures(&self) -> &[OutputDisclosure] {
+        &self.output_disclosures
+    }
 }
 
 impl<F: PrimeField> PlainWitnessExtension<F> {
@@ -1074,7 +1196,12 @@ impl<F: PrimeField, N: Rep3Network> Rep3WitnessExtension<F, N> {
         mpc_accelerator: MpcAccelerator<F, CircomRep3VmWitnessExtension<F, N>>,
         config: VMConfig,
     ) -> Result<Self> {
-        let driver = CircomRep3VmWitnessExtension::from_network(network, config.a2b_type)?;
+        let driver = CircomRep3VmWitnessExtension::from_network(
+            network,
+            config.a2b_type,
+            config.a2b_auto_network,
+            config.bitwise_in_ring,
+        )?;
         let mut signals = vec![Rep3VmType::default(); parser.amount_signals];
         signals[0] = Rep3VmType::Public(F::one());
         let constant_table = parser
@@ -1146,3 +1273,80 @@ impl<F: PrimeField> Rep3WitnessExtension<F, Rep3MpcNet> {
         ))
     }
 }
+
+impl<F: PrimeField, N: ShamirNetwork> TwoPcWitnessExtension<F, N> {
+    pub(crate) fn from_network(
+        parser: CoCircomCompilerParsed<F>,
+        network: N,
+        mpc_accelerator: MpcAccelerator<F, CircomTwoPcVmWitnessExtension<F, N>>,
+        config: VMConfig,
+    ) -> Result<Self> {
+        // reject circuits requiring an oblivious shuffle before setting up the protocol
+        if mpc_accelerator.has_cmp_accelerator(SHUFFLE_TEMPLATE)
+            && parser
+                .templ_decls
+                .values()
+                .any(|templ| templ.component_name == SHUFFLE_TEMPLATE)
+        {
+            bail!("The circuit uses the {SHUFFLE_TEMPLATE} template, but oblivious shuffling is not supported in 2PC");
+        }
+        let driver = CircomTwoPcVmWitnessExtension::from_network(network)?;
+        let mut signals = vec![TwoPcVmType::default(); parser.amount_signals];
+        signals[0] = TwoPcVmType::Public(F::one());
+        let constant_table = parser
+            .constant_table
+            .into_iter()
+            .map(TwoPcVmType::Public)
+            .collect_vec();
+        Ok(Self {
+            driver,
+            signal_to_witness: parser.signal_to_witness,
+            main: parser.main,
+            ctx: WitnessExtensionCtx::new(
+                signals,
+                constant_table,
+                parser.fun_decls,
+                parser.templ_decls,
+                parser.string_table,
+                mpc_accelerator,
+            ),
+            main_inputs: parser.main_inputs,
+            main_outputs: parser.main_outputs,
+            main_input_list: parser.main_input_list,
+            output_mapping: parser.output_mapping,
+            config,
+        })
+    }
+}
+
+impl<F: PrimeField> TwoPcWitnessExtension<F, ShamirMpcNet> {
+    pub(crate) fn new(
+        parser: CoCircomCompilerParsed<F>,
+        network_config: NetworkConfig,
+        mpc_accelerator: MpcAccelerator<F, CircomTwoPcVmWitnessExtension<F, ShamirMpcNet>>,
+        config: VMConfig,
+    ) -> Result<Self> {
+        let network = ShamirMpcNet::new_two_party(network_config)?;
+        Self::from_network(parser, network, mpc_accelerator, config)
+    }
+
+    /// Starts the execution of the MPC-VM with the provided [SharedInput], consumes `self` and returns the [`ShamirMpcNet`].
+    ///
+    /// See [`Rep3WitnessExtension::run_and_get_network`] for details.
+    #[expect(clippy::type_complexity)]
+    pub fn run_and_get_network(
+        mut self,
+        input_signals: SharedInput<F, TwoPcPrimeFieldShare<F>>,
+    ) -> Result<(
+        FinalizedWitnessExtension<F, CircomTwoPcVmWitnessExtension<F, ShamirMpcNet>>,
+        ShamirMpcNet,
+    )> {
+        self.driver.compare_vm_config(&self.config)?;
+        let amount_public_inputs = self.set_input_signals(input_signals)?;
+        self.call_main_component()?;
+        Ok((
+            self.post_processing(amount_public_inputs)?,
+            self.driver.get_network(),
+        ))
+    }
+}
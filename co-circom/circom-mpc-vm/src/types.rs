@@ -2,12 +2,16 @@ use std::{collections::HashMap, sync::Arc};
 
 use ark_ff::PrimeField;
 use mpc_core::protocols::rep3::network::{Rep3MpcNet, Rep3Network};
+use mpc_core::protocols::shamir::network::{ShamirMpcNet, ShamirNetwork};
 use mpc_net::config::NetworkConfig;
 
 use crate::{
     accelerator::{MpcAccelerator, MpcAcceleratorConfig},
     mpc::plain::CircomPlainVmWitnessExtension,
-    mpc_vm::{PlainWitnessExtension, Rep3WitnessExtension, VMConfig, WitnessExtension},
+    mpc_vm::{
+        PlainWitnessExtension, Rep3WitnessExtension, TwoPcWitnessExtension, VMConfig,
+        WitnessExtension,
+    },
     op_codes::CodeBlock,
 };
 use eyre::Result;
@@ -201,4 +205,46 @@ impl<F: PrimeField> CoCircomCompilerParsed<F> {
             vm_config,
         )
     }
+
+    /// Consumes `self` and a [`NetworkConfig`], and constructs an instance of [`TwoPcWitnessExtension`].
+    ///
+    /// # Arguments
+    /// - `network_config`: A network configuration specifying how to connect to the other party.
+    ///
+    /// # Returns
+    /// - `Ok(TwoPcWitnessExtension)`: The MPC-VM capable of performing the witness extension using the 2PC protocol.
+    /// - `Err(err)`: An error indicating a failure, such as inability to connect to the other party.
+    pub fn to_twopc_vm(
+        self,
+        network_config: NetworkConfig,
+        vm_config: VMConfig,
+    ) -> Result<TwoPcWitnessExtension<F, ShamirMpcNet>> {
+        TwoPcWitnessExtension::new(
+            self,
+            network_config,
+            MpcAccelerator::from_config(MpcAcceleratorConfig::from_env()),
+            vm_config,
+        )
+    }
+
+    /// Consumes `self` and an already established [`ShamirNetwork`] of two parties, and constructs an instance of [`TwoPcWitnessExtension`].
+    ///
+    /// # Arguments
+    /// - `network`: An already established [`ShamirNetwork`].
+    ///
+    /// # Returns
+    /// - `Ok(TwoPcWitnessExtension)`: The MPC-VM capable of performing the witness extension using the 2PC protocol.
+    /// - `Err(err)`: An error indicating a failure.
+    pub fn to_twopc_vm_with_network<N: ShamirNetwork>(
+        self,
+        network: N,
+        vm_config: VMConfig,
+    ) -> Result<TwoPcWitnessExtension<F, N>> {
+        TwoPcWitnessExtension::from_network(
+            self,
+            network,
+            MpcAccelerator::from_config(MpcAcceleratorConfig::from_env()),
+            vm_config,
+        )
+    }
 }
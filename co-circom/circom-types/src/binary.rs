@@ -0,0 +1,308 @@
+//! This module defines a compact binary format for proofs, verification keys and public inputs, as an alternative to
+//! the JSON format of snarkjs with its decimal strings.
+//!
+//! The binary format starts with a version byte ([`BINARY_FORMAT_VERSION`]), followed by the elements in the compressed
+//! canonical serialization of arkworks. Elements which are fully determined by the others (e.g., the protocol name or
+//! the pairing of α and β in a Groth16 verification key) are not stored, so every value has exactly one encoding.
+use std::io::{Read, Write};
+
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::groth16::{
+    Groth16Proof, JsonPublicInput, JsonVerificationKey as Groth16JsonVerificationKey,
+};
+use crate::plonk::{JsonVerificationKey as PlonkJsonVerificationKey, PlonkProof};
+use crate::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
+
+/// The version of the binary format. It is the first byte of every serialized value.
+pub const BINARY_FORMAT_VERSION: u8 = 1;
+
+/// The errors that may arise while reading or writing the binary format.
+#[derive(Debug, Error)]
+pub enum BinaryFormatError {
+    /// Error during serialization of the elements
+    #[error(transparent)]
+    SerializationError(#[from] SerializationError),
+    /// Error during IO operations (reading/writing the version byte)
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    /// The value was written with an unknown version of the binary format
+    #[error("unsupported binary format version {0}, expected {BINARY_FORMAT_VERSION}")]
+    UnsupportedVersion(u8),
+    /// A verification key without the element for the constant 1
+    #[error("verification key without public input elements")]
+    EmptyVerificationKey,
+}
+
+type BinaryFormatResult<T> = Result<T, BinaryFormatError>;
+
+/// A type that can be written to and read from the binary format.
+pub trait BinaryFormat: Sized {
+    /// Writes the elements of the value, without the version byte.
+    fn write_elements<W: Write>(&self, writer: W) -> BinaryFormatResult<()>;
+
+    /// Reads the elements of the value, without the version byte.
+    fn read_elements<R: Read>(reader: R) -> BinaryFormatResult<Self>;
+
+    /// Writes the value in the binary format, starting with the version byte.
+    fn to_binary_writer<W: Write>(&self, mut writer: W) -> BinaryFormatResult<()> {
+        writer.write_all(&[BINARY_FORMAT_VERSION])?;
+        self.write_elements(writer)
+    }
+
+    /// Reads a value in the binary format, starting with the version byte.
+    fn from_binary_reader<R: Read>(mut reader: R) -> BinaryFormatResult<Self> {
+        let mut version = [0u8];
+        reader.read_exact(&mut version)?;
+        if version[0] != BINARY_FORMAT_VERSION {
+            return Err(BinaryFormatError::UnsupportedVersion(version[0]));
+        }
+        Self::read_elements(reader)
+    }
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> BinaryFormat for Groth16Proof<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    fn write_elements<W: Write>(&self, mut writer: W) -> BinaryFormatResult<()> {
+        self.pi_a.serialize_compressed(&mut writer)?;
+        self.pi_b.serialize_compressed(&mut writer)?;
+        self.pi_c.serialize_compressed(&mut writer)?;
+        Ok(())
+    }
+
+    fn read_elements<R: Read>(mut reader: R) -> BinaryFormatResult<Self> {
+        Ok(Self {
+            pi_a: P::G1Affine::deserialize_compressed(&mut reader)?,
+            pi_b: P::G2Affine::deserialize_compressed(&mut reader)?,
+            pi_c: P::G1Affine::deserialize_compressed(&mut reader)?,
+            protocol: "groth16".to_owned(),
+            curve: P::get_circom_name(),
+        })
+    }
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> BinaryFormat for Groth16JsonVerificationKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    fn write_elements<W: Write>(&self, mut writer: W) -> BinaryFormatResult<()> {
+        self.alpha_1.serialize_compressed(&mut writer)?;
+        self.beta_2.serialize_compressed(&mut writer)?;
+        self.gamma_2.serialize_compressed(&mut writer)?;
+        self.delta_2.serialize_compressed(&mut writer)?;
+        self.ic.serialize_compressed(&mut writer)?;
+        Ok(())
+    }
+
+    fn read_elements<R: Read>(mut reader: R) -> BinaryFormatResult<Self> {
+        let alpha_1 = P::G1Affine::deserialize_compressed(&mut reader)?;
+        let beta_2 = P::G2Affine::deserialize_compressed(&mut reader)?;
+        let gamma_2 = P::G2Affine::deserialize_compressed(&mut reader)?;
+        let delta_2 = P::G2Affine::deserialize_compressed(&mut reader)?;
+        let ic = Vec::<P::G1Affine>::deserialize_compressed(&mut reader)?;
+        if ic.is_empty() {
+            return Err(BinaryFormatError::EmptyVerificationKey);
+        }
+        Ok(Self {
+            protocol: "groth16".to_owned(),
+            n_public: ic.len() - 1,
+            alpha_1,
+            beta_2,
+            gamma_2,
+            delta_2,
+            alpha_beta_gt: P::pairing(alpha_1, beta_2).0,
+            ic,
+        })
+    }
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> BinaryFormat for PlonkProof<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    fn write_elements<W: Write>(&self, mut writer: W) -> BinaryFormatResult<()> {
+        for point in [
+            &self.a, &self.b, &self.c, &self.z, &self.t1, &self.t2, &self.t3, &self.wxi, &self.wxiw,
+        ] {
+            point.serialize_compressed(&mut writer)?;
+        }
+        for eval in [
+            &self.eval_a,
+            &self.eval_b,
+            &self.eval_c,
+            &self.eval_s1,
+            &self.eval_s2,
+            &self.eval_zw,
+        ] {
+            eval.serialize_compressed(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    fn read_elements<R: Read>(mut reader: R) -> BinaryFormatResult<Self> {
+        let mut point = || P::G1Affine::deserialize_compressed(&mut reader);
+        let (a, b, c, z) = (point()?, point()?, point()?, point()?);
+        let (t1, t2, t3, wxi, wxiw) = (point()?, point()?, point()?, point()?, point()?);
+        let mut eval = || P::ScalarField::deserialize_compressed(&mut reader);
+        let (eval_a, eval_b, eval_c) = (eval()?, eval()?, eval()?);
+        let (eval_s1, eval_s2, eval_zw) = (eval()?, eval()?, eval()?);
+        Ok(Self {
+            a,
+            b,
+            c,
+            z,
+            t1,
+            t2,
+            t3,
+            wxi,
+            wxiw,
+            eval_a,
+            eval_b,
+            eval_c,
+            eval_s1,
+            eval_s2,
+            eval_zw,
+            protocol: "plonk".to_owned(),
+            curve: P::get_circom_name(),
+        })
+    }
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> BinaryFormat for PlonkJsonVerificationKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    fn write_elements<W: Write>(&self, mut writer: W) -> BinaryFormatResult<()> {
+        u64::try_from(self.n_public)
+            .expect("fits into u64")
+            .serialize_compressed(&mut writer)?;
+        u64::try_from(self.power)
+            .expect("fits into u64")
+            .serialize_compressed(&mut writer)?;
+        self.k1.serialize_compressed(&mut writer)?;
+        self.k2.serialize_compressed(&mut writer)?;
+        for point in [
+            &self.qm, &self.ql, &self.qr, &self.qo, &self.qc, &self.s1, &self.s2, &self.s3,
+        ] {
+            point.serialize_compressed(&mut writer)?;
+        }
+        self.x2.serialize_compressed(&mut writer)?;
+        // snarkjs does not use the two-adic root of unity of arkworks for all curves, so we store it
+        self.w.serialize_compressed(&mut writer)?;
+        Ok(())
+    }
+
+    fn read_elements<R: Read>(mut reader: R) -> BinaryFormatResult<Self> {
+        let to_usize = |x: u64| usize::try_from(x).map_err(|_| SerializationError::InvalidData);
+        let n_public = to_usize(u64::deserialize_compressed(&mut reader)?)?;
+        let power = to_usize(u64::deserialize_compressed(&mut reader)?)?;
+        let k1 = P::ScalarField::deserialize_compressed(&mut reader)?;
+        let k2 = P::ScalarField::deserialize_compressed(&mut reader)?;
+        let mut point = || P::G1Affine::deserialize_compressed(&mut reader);
+        let (qm, ql, qr, qo, qc) = (point()?, point()?, point()?, point()?, point()?);
+        let (s1, s2, s3) = (point()?, point()?, point()?);
+        let x2 = P::G2Affine::deserialize_compressed(&mut reader)?;
+        let w = P::ScalarField::deserialize_compressed(&mut reader)?;
+        Ok(Self {
+            protocol: "plonk".to_owned(),
+            curve: P::get_circom_name(),
+            n_public,
+            power,
+            k1,
+            k2,
+            qm,
+            ql,
+            qr,
+            qo,
+            qc,
+            s1,
+            s2,
+            s3,
+            x2,
+            w,
+        })
+    }
+}
+
+impl<F: PrimeField + FromStr> BinaryFormat for JsonPublicInput<F> {
+    fn write_elements<W: Write>(&self, writer: W) -> BinaryFormatResult<()> {
+        self.values.serialize_compressed(writer)?;
+        Ok(())
+    }
+
+    fn read_elements<R: Read>(reader: R) -> BinaryFormatResult<Self> {
+        Ok(Self {
+            values: Vec::deserialize_compressed(reader)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+    use serde::{de::DeserializeOwned, Serialize};
+    use std::fmt::Debug;
+    use std::fs;
+
+    fn roundtrip<T: BinaryFormat + DeserializeOwned + Serialize + PartialEq + Debug>(path: &str) {
+        let json = fs::read(format!("../../test_vectors/{path}")).unwrap();
+        let value = serde_json::from_slice::<T>(&json).unwrap();
+        let mut bytes = Vec::new();
+        value.to_binary_writer(&mut bytes).unwrap();
+        assert_eq!(bytes[0], BINARY_FORMAT_VERSION);
+        assert!(bytes.len() < json.len());
+        assert_eq!(T::from_binary_reader(bytes.as_slice()).unwrap(), value);
+        // the value has exactly one encoding
+        let mut bytes_again = Vec::new();
+        value.to_binary_writer(&mut bytes_again).unwrap();
+        assert_eq!(bytes, bytes_again);
+    }
+
+    #[test]
+    fn binary_roundtrip_groth16() {
+        roundtrip::<Groth16Proof<Bn254>>("Groth16/bn254/poseidon/circom.proof");
+        roundtrip::<Groth16JsonVerificationKey<Bn254>>(
+            "Groth16/bn254/poseidon/verification_key.json",
+        );
+        roundtrip::<JsonPublicInput<ark_bn254::Fr>>("Groth16/bn254/poseidon/public.json");
+        roundtrip::<Groth16Proof<Bls12_381>>("Groth16/bls12_381/poseidon/circom.proof");
+        roundtrip::<Groth16JsonVerificationKey<Bls12_381>>(
+            "Groth16/bls12_381/poseidon/verification_key.json",
+        );
+    }
+
+    #[test]
+    fn binary_roundtrip_plonk() {
+        roundtrip::<PlonkProof<Bn254>>("Plonk/bn254/poseidon/circom.proof");
+        roundtrip::<PlonkJsonVerificationKey<Bn254>>("Plonk/bn254/poseidon/verification_key.json");
+        roundtrip::<PlonkProof<Bls12_381>>("Plonk/bls12_381/poseidon/circom.proof");
+        roundtrip::<PlonkJsonVerificationKey<Bls12_381>>(
+            "Plonk/bls12_381/poseidon/verification_key.json",
+        );
+    }
+
+    #[test]
+    fn binary_rejects_unknown_version() {
+        let json = fs::read("../../test_vectors/Groth16/bn254/poseidon/circom.proof").unwrap();
+        let proof = serde_json::from_slice::<Groth16Proof<Bn254>>(&json).unwrap();
+        let mut bytes = Vec::new();
+        proof.to_binary_writer(&mut bytes).unwrap();
+        bytes[0] = BINARY_FORMAT_VERSION + 1;
+        assert!(matches!(
+            Groth16Proof::<Bn254>::from_binary_reader(bytes.as_slice()),
+            Err(BinaryFormatError::UnsupportedVersion(v)) if v == BINARY_FORMAT_VERSION + 1
+        ));
+    }
+}
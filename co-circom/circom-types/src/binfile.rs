@@ -1,6 +1,7 @@
 use std::{
     io::{Cursor, Read},
     marker::PhantomData,
+    ops::Range,
 };
 
 use ark_ec::pairing::Pairing;
@@ -106,3 +107,58 @@ where
         std::mem::take(&mut self.sections[id - 1])
     }
 }
+
+/// Parses the section table of a bin file without copying the sections. Returns the range of every section within `bytes`, indexed by the section id minus one.
+pub(crate) fn section_ranges(bytes: &[u8]) -> ZKeyParserResult<Vec<Range<usize>>> {
+    tracing::debug!("reading section table of bin file");
+    let mut reader = Cursor::new(bytes);
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    let ftype = std::str::from_utf8(&magic[..])
+        .map_err(|_| ZKeyParserError::CorruptedBinFile("cannot parse magic number".to_owned()))?;
+    tracing::debug!("file type for binfile: \"{ftype}\"");
+    let version = reader.read_u32::<LittleEndian>()?;
+    tracing::debug!("binfile version {}", version);
+
+    let num_sections: usize = reader
+        .read_u32::<LittleEndian>()?
+        .try_into()
+        .expect("u32 fits into usize");
+    tracing::debug!("we got {} sections in binfile", num_sections);
+    let mut sections = vec![None; num_sections];
+    for _ in 0..num_sections {
+        let section_id: usize = reader
+            .read_u32::<LittleEndian>()?
+            .try_into()
+            .expect("u32 fits into usize");
+        let section_length: usize = reader
+            .read_u64::<LittleEndian>()?
+            .try_into()
+            .expect("u64 fits into usize");
+        let section = sections
+            .get_mut(section_id.wrapping_sub(1))
+            .ok_or_else(|| {
+                ZKeyParserError::CorruptedBinFile(format!("invalid section id {section_id}"))
+            })?;
+        if section.is_some() {
+            return Err(ZKeyParserError::CorruptedBinFile(format!(
+                "duplicate section {section_id}"
+            )));
+        }
+        let start = usize::try_from(reader.position()).expect("u64 fits into usize");
+        let end = start
+            .checked_add(section_length)
+            .filter(|end| *end <= bytes.len())
+            .ok_or_else(|| {
+                ZKeyParserError::CorruptedBinFile(format!("section {section_id} is truncated"))
+            })?;
+        *section = Some(start..end);
+        reader.set_position(end as u64);
+    }
+    tracing::debug!("successfully read section table of bin file!");
+    // missing sections are empty, such that parsing them fails
+    Ok(sections
+        .into_iter()
+        .map(|section| section.unwrap_or_default())
+        .collect())
+}
@@ -0,0 +1,626 @@
+//! This module converts Groth16 proofs and verification keys between the binary format of
+//! [gnark](https://github.com/Consensys/gnark) and the snarkjs JSON format of [`Groth16Proof`] and [`JsonVerificationKey`].
+//!
+//! gnark writes proofs and verification keys with `WriteTo` (compressed points) or `WriteRawTo` (uncompressed points).
+//! The coordinates are encoded big-endian and the most significant bits of the first byte of every point hold the
+//! encoding flags, so the encoding is detected on import. gnark optionally extends Groth16 with Pedersen commitments,
+//! which have no counterpart in snarkjs. Proofs and keys with commitments are therefore rejected.
+use std::io::{Read, Write};
+
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
+};
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use thiserror::Error;
+
+use crate::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
+
+use super::{Groth16Proof, JsonVerificationKey, ZKey};
+
+/// The errors that may arise while converting from or to gnark's format.
+#[derive(Debug, Error)]
+pub enum GnarkError {
+    /// Error during IO operations (reading/writing the binary format)
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    /// Error while parsing the snarkjs JSON format
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    /// A point has invalid flags, is not on the curve or not in the correct subgroup
+    #[error("invalid point encoding")]
+    InvalidPoint,
+    /// The proof or the verification key uses gnark's Pedersen commitments
+    #[error("gnark commitments are not supported, but found {0}")]
+    UnsupportedCommitments(usize),
+    /// The input continues after the proof or the verification key
+    #[error("{0} unexpected trailing bytes")]
+    TrailingBytes(usize),
+    /// The verification key does not contain the element for the constant 1
+    #[error("verification key without public input elements")]
+    EmptyVerificationKey,
+    /// The zkey does not belong to the verification key
+    #[error("zkey does not match the verification key")]
+    MismatchedZKey,
+}
+
+/// The point encoding of gnark's binary format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GnarkEncoding {
+    /// Compressed points, as written by gnark's `WriteTo`
+    Compressed,
+    /// Uncompressed points, as written by gnark's `WriteRawTo`
+    Raw,
+}
+
+/// The encoding flags in the most significant bits of a gnark point.
+struct GnarkFlags {
+    mask: u8,
+    uncompressed: u8,
+    uncompressed_infinity: Option<u8>,
+    compressed_smallest: u8,
+    compressed_largest: u8,
+    compressed_infinity: u8,
+}
+
+/// The flags of curves with only two unused bits in the base field (BN254). An uncompressed point at infinity is
+/// encoded as zeros.
+const TWO_BIT_FLAGS: GnarkFlags = GnarkFlags {
+    mask: 0b11 << 6,
+    uncompressed: 0b00 << 6,
+    uncompressed_infinity: None,
+    compressed_smallest: 0b10 << 6,
+    compressed_largest: 0b11 << 6,
+    compressed_infinity: 0b01 << 6,
+};
+
+/// The flags of curves with at least three unused bits in the base field (BLS12-381, BLS12-377, BW6-761).
+const THREE_BIT_FLAGS: GnarkFlags = GnarkFlags {
+    mask: 0b111 << 5,
+    uncompressed: 0b000 << 5,
+    uncompressed_infinity: Some(0b010 << 5),
+    compressed_smallest: 0b100 << 5,
+    compressed_largest: 0b101 << 5,
+    compressed_infinity: 0b110 << 5,
+};
+
+/// Bridge trait to read and write the points of a curve in gnark's binary format.
+pub trait GnarkPairingBridge: Pairing + CircomArkworksPairingBridge
+where
+    Self::BaseField: CircomArkworksPrimeFieldBridge,
+    Self::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Writes a G1 point in gnark's format.
+    fn g1_to_gnark(
+        p: &Self::G1Affine,
+        encoding: GnarkEncoding,
+        writer: impl Write,
+    ) -> std::io::Result<()>;
+    /// Writes a G2 point in gnark's format.
+    fn g2_to_gnark(
+        p: &Self::G2Affine,
+        encoding: GnarkEncoding,
+        writer: impl Write,
+    ) -> std::io::Result<()>;
+    /// Reads a G1 point in gnark's format. The encoding is detected from the flags of the point.
+    fn g1_from_gnark(reader: impl Read) -> Result<Self::G1Affine, GnarkError>;
+    /// Reads a G2 point in gnark's format. The encoding is detected from the flags of the point.
+    fn g2_from_gnark(reader: impl Read) -> Result<Self::G2Affine, GnarkError>;
+}
+
+macro_rules! impl_gnark_bridge {
+    ($curve: ident, $config: ident, $flags: expr) => {
+        impl GnarkPairingBridge for $curve::$config {
+            fn g1_to_gnark(
+                p: &Self::G1Affine,
+                encoding: GnarkEncoding,
+                writer: impl Write,
+            ) -> std::io::Result<()> {
+                write_point(p, encoding, &$flags, writer)
+            }
+
+            fn g2_to_gnark(
+                p: &Self::G2Affine,
+                encoding: GnarkEncoding,
+                writer: impl Write,
+            ) -> std::io::Result<()> {
+                write_point(p, encoding, &$flags, writer)
+            }
+
+            fn g1_from_gnark(reader: impl Read) -> Result<Self::G1Affine, GnarkError> {
+                read_point::<$curve::g1::Config>(&$flags, reader)
+            }
+
+            fn g2_from_gnark(reader: impl Read) -> Result<Self::G2Affine, GnarkError> {
+                read_point::<$curve::g2::Config>(&$flags, reader)
+            }
+        }
+    };
+}
+
+impl_gnark_bridge!(ark_bn254, Bn254, TWO_BIT_FLAGS);
+impl_gnark_bridge!(ark_bls12_381, Bls12_381, THREE_BIT_FLAGS);
+impl_gnark_bridge!(ark_bls12_377, Bls12_377, THREE_BIT_FLAGS);
+impl_gnark_bridge!(ark_bw6_761, BW6_761, THREE_BIT_FLAGS);
+
+fn prime_field_size<F: PrimeField>() -> usize {
+    F::MODULUS_BIT_SIZE.div_ceil(8) as usize
+}
+
+fn field_size<F: Field>() -> usize {
+    F::extension_degree() as usize * prime_field_size::<F::BasePrimeField>()
+}
+
+fn write_field<F: Field>(f: &F, bytes: &mut Vec<u8>) {
+    let size = prime_field_size::<F::BasePrimeField>();
+    let elements = f.to_base_prime_field_elements().collect::<Vec<_>>();
+    // gnark writes the coefficients of extension fields starting with the highest one
+    for element in elements.iter().rev() {
+        let element = element.into_bigint().to_bytes_be();
+        bytes.extend_from_slice(&element[element.len() - size..]);
+    }
+}
+
+fn read_field<F: Field>(bytes: &[u8]) -> Result<F, GnarkError> {
+    let size = prime_field_size::<F::BasePrimeField>();
+    let mut elements = bytes
+        .chunks_exact(size)
+        .map(|chunk| {
+            let element = F::BasePrimeField::from_be_bytes_mod_order(chunk);
+            // reject non-canonical encodings, which would otherwise be reduced silently
+            let canonical = element.into_bigint().to_bytes_be();
+            if &canonical[canonical.len() - size..] == chunk {
+                Ok(element)
+            } else {
+                Err(GnarkError::InvalidPoint)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    elements.reverse();
+    F::from_base_prime_field_elems(&elements).ok_or(GnarkError::InvalidPoint)
+}
+
+fn write_point<C: SWCurveConfig>(
+    p: &Affine<C>,
+    encoding: GnarkEncoding,
+    flags: &GnarkFlags,
+    mut writer: impl Write,
+) -> std::io::Result<()> {
+    let size = field_size::<C::BaseField>();
+    let mut bytes = Vec::with_capacity(2 * size);
+    match (p.xy(), encoding) {
+        (None, GnarkEncoding::Compressed) => {
+            bytes.resize(size, 0);
+            bytes[0] |= flags.compressed_infinity;
+        }
+        (None, GnarkEncoding::Raw) => {
+            bytes.resize(2 * size, 0);
+            bytes[0] |= flags.uncompressed_infinity.unwrap_or(flags.uncompressed);
+        }
+        (Some((x, y)), GnarkEncoding::Compressed) => {
+            write_field(x, &mut bytes);
+            bytes[0] |= if *y > -*y {
+                flags.compressed_largest
+            } else {
+                flags.compressed_smallest
+            };
+        }
+        (Some((x, y)), GnarkEncoding::Raw) => {
+            write_field(x, &mut bytes);
+            write_field(y, &mut bytes);
+            bytes[0] |= flags.uncompressed;
+        }
+    }
+    writer.write_all(&bytes)
+}
+
+fn read_point<C: SWCurveConfig>(
+    flags: &GnarkFlags,
+    mut reader: impl Read,
+) -> Result<Affine<C>, GnarkError> {
+    let size = field_size::<C::BaseField>();
+    let mut bytes = vec![0; size];
+    reader.read_exact(&mut bytes)?;
+    let flag = bytes[0] & flags.mask;
+    bytes[0] &= !flags.mask;
+    let point = if flag == flags.compressed_infinity {
+        if bytes.iter().any(|b| *b != 0) {
+            return Err(GnarkError::InvalidPoint);
+        }
+        Affine::identity()
+    } else if flag == flags.compressed_smallest || flag == flags.compressed_largest {
+        let x = read_field(&bytes)?;
+        Affine::get_point_from_x_unchecked(x, flag == flags.compressed_largest)
+            .ok_or(GnarkError::InvalidPoint)?
+    } else if flag == flags.uncompressed || Some(flag) == flags.uncompressed_infinity {
+        bytes.resize(2 * size, 0);
+        reader.read_exact(&mut bytes[size..])?;
+        let x = read_field::<C::BaseField>(&bytes[..size])?;
+        let y = read_field::<C::BaseField>(&bytes[size..])?;
+        if Some(flag) == flags.uncompressed_infinity || (x.is_zero() && y.is_zero()) {
+            if !x.is_zero() || !y.is_zero() {
+                return Err(GnarkError::InvalidPoint);
+            }
+            Affine::identity()
+        } else {
+            let p = Affine::new_unchecked(x, y);
+            if !p.is_on_curve() {
+                return Err(GnarkError::InvalidPoint);
+            }
+            p
+        }
+    } else {
+        return Err(GnarkError::InvalidPoint);
+    };
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(GnarkError::InvalidPoint);
+    }
+    Ok(point)
+}
+
+fn read_u32(mut reader: impl Read) -> Result<u32, GnarkError> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_be_bytes(bytes))
+}
+
+/// snarkjs writes JSON objects, whereas gnark's binary format never starts with `{`: with both flag layouts, the
+/// flags of that byte are invalid for the first point of a proof or a verification key.
+fn is_json(bytes: &[u8]) -> bool {
+    bytes
+        .iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .is_some_and(|b| *b == b'{')
+}
+
+impl<P: GnarkPairingBridge> Groth16Proof<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Reads a proof written by gnark's `Proof.WriteTo` or `Proof.WriteRawTo`. Proofs of gnark versions before the
+    /// introduction of commitments (without the trailing commitment section) are accepted as well.
+    pub fn from_gnark_reader(mut reader: impl Read) -> Result<Self, GnarkError> {
+        let pi_a = P::g1_from_gnark(&mut reader)?;
+        let pi_b = P::g2_from_gnark(&mut reader)?;
+        let pi_c = P::g1_from_gnark(&mut reader)?;
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest)?;
+        if !rest.is_empty() {
+            let mut rest = rest.as_slice();
+            let num_commitments = read_u32(&mut rest)?;
+            if num_commitments != 0 {
+                return Err(GnarkError::UnsupportedCommitments(num_commitments as usize));
+            }
+            // the proof of knowledge for the empty list of commitments
+            P::g1_from_gnark(&mut rest)?;
+            if !rest.is_empty() {
+                return Err(GnarkError::TrailingBytes(rest.len()));
+            }
+        }
+        Ok(Self {
+            pi_a,
+            pi_b,
+            pi_c,
+            protocol: "groth16".to_owned(),
+            curve: P::get_circom_name(),
+        })
+    }
+
+    /// Writes the proof like gnark's `Proof.WriteTo` ([`GnarkEncoding::Compressed`]) or `Proof.WriteRawTo`
+    /// ([`GnarkEncoding::Raw`]), including the empty commitment section of current gnark versions.
+    pub fn to_gnark_writer(
+        &self,
+        mut writer: impl Write,
+        encoding: GnarkEncoding,
+    ) -> std::io::Result<()> {
+        P::g1_to_gnark(&self.pi_a, encoding, &mut writer)?;
+        P::g2_to_gnark(&self.pi_b, encoding, &mut writer)?;
+        P::g1_to_gnark(&self.pi_c, encoding, &mut writer)?;
+        writer.write_all(&0u32.to_be_bytes())?;
+        P::g1_to_gnark(&P::G1Affine::zero(), encoding, &mut writer)
+    }
+
+    /// Parses a proof either in the snarkjs JSON format or in gnark's binary format, detecting the format and the
+    /// point encoding from the input.
+    pub fn from_snarkjs_or_gnark(bytes: &[u8]) -> Result<Self, GnarkError> {
+        if is_json(bytes) {
+            Ok(serde_json::from_slice(bytes)?)
+        } else {
+            Self::from_gnark_reader(bytes)
+        }
+    }
+}
+
+/// A Groth16 verification key as gnark stores it. In contrast to the snarkjs [`JsonVerificationKey`], it contains β and
+/// δ also in G1, but not the pairing of α and β.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GnarkVerifyingKey<P: Pairing> {
+    /// The element α ∈ G1
+    pub alpha_1: P::G1Affine,
+    /// The element β ∈ G1
+    pub beta_1: P::G1Affine,
+    /// The element β ∈ G2
+    pub beta_2: P::G2Affine,
+    /// The element γ ∈ G2
+    pub gamma_2: P::G2Affine,
+    /// The element δ ∈ G1
+    pub delta_1: P::G1Affine,
+    /// The element δ ∈ G2
+    pub delta_2: P::G2Affine,
+    /// Used to bind the public inputs to the proof (the `IC` of snarkjs)
+    pub k: Vec<P::G1Affine>,
+}
+
+impl<P: GnarkPairingBridge> GnarkVerifyingKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Builds the gnark verification key from a snarkjs verification key. β and δ in G1 are not part of the snarkjs
+    /// verification key, therefore they are taken from the [`ZKey`] of the circuit.
+    pub fn from_snarkjs(vk: &JsonVerificationKey<P>, zkey: &ZKey<P>) -> Result<Self, GnarkError> {
+        if zkey.alpha_g1 != vk.alpha_1
+            || zkey.beta_g2 != vk.beta_2
+            || zkey.delta_g2 != vk.delta_2
+            || zkey.n_public + 1 != vk.ic.len()
+        {
+            return Err(GnarkError::MismatchedZKey);
+        }
+        Ok(Self {
+            alpha_1: vk.alpha_1,
+            beta_1: zkey.beta_g1,
+            beta_2: vk.beta_2,
+            gamma_2: vk.gamma_2,
+            delta_1: zkey.delta_g1,
+            delta_2: vk.delta_2,
+            k: vk.ic.clone(),
+        })
+    }
+
+    /// Converts the key into a snarkjs verification key.
+    pub fn into_snarkjs(self) -> JsonVerificationKey<P> {
+        JsonVerificationKey {
+            protocol: "groth16".to_owned(),
+            n_public: self.k.len() - 1,
+            alpha_beta_gt: P::pairing(self.alpha_1, self.beta_2).0,
+            alpha_1: self.alpha_1,
+            beta_2: self.beta_2,
+            gamma_2: self.gamma_2,
+            delta_2: self.delta_2,
+            ic: self.k,
+        }
+    }
+
+    /// Reads a verification key written by gnark's `VerifyingKey.WriteTo` or `VerifyingKey.WriteRawTo`. Keys of gnark
+    /// versions before the introduction of commitments (without the trailing commitment section) are accepted as well.
+    pub fn from_reader(mut reader: impl Read) -> Result<Self, GnarkError> {
+        let alpha_1 = P::g1_from_gnark(&mut reader)?;
+        let beta_1 = P::g1_from_gnark(&mut reader)?;
+        let beta_2 = P::g2_from_gnark(&mut reader)?;
+        let gamma_2 = P::g2_from_gnark(&mut reader)?;
+        let delta_1 = P::g1_from_gnark(&mut reader)?;
+        let delta_2 = P::g2_from_gnark(&mut reader)?;
+        let len = read_u32(&mut reader)?;
+        if len == 0 {
+            return Err(GnarkError::EmptyVerificationKey);
+        }
+        let k = (0..len)
+            .map(|_| P::g1_from_gnark(&mut reader))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest)?;
+        if !rest.is_empty() {
+            let mut rest = rest.as_slice();
+            // the public inputs committed to by each commitment, followed by the number of commitment keys
+            let num_committed = read_u32(&mut rest)?;
+            if num_committed != 0 {
+                return Err(GnarkError::UnsupportedCommitments(num_committed as usize));
+            }
+            let num_commitments = read_u32(&mut rest)?;
+            if num_commitments != 0 {
+                return Err(GnarkError::UnsupportedCommitments(num_commitments as usize));
+            }
+            if !rest.is_empty() {
+                return Err(GnarkError::TrailingBytes(rest.len()));
+            }
+        }
+        Ok(Self {
+            alpha_1,
+            beta_1,
+            beta_2,
+            gamma_2,
+            delta_1,
+            delta_2,
+            k,
+        })
+    }
+
+    /// Writes the key like gnark's `VerifyingKey.WriteTo` ([`GnarkEncoding::Compressed`]) or
+    /// `VerifyingKey.WriteRawTo` ([`GnarkEncoding::Raw`]), including the empty commitment section of current gnark
+    /// versions.
+    pub fn to_writer(
+        &self,
+        mut writer: impl Write,
+        encoding: GnarkEncoding,
+    ) -> std::io::Result<()> {
+        P::g1_to_gnark(&self.alpha_1, encoding, &mut writer)?;
+        P::g1_to_gnark(&self.beta_1, encoding, &mut writer)?;
+        P::g2_to_gnark(&self.beta_2, encoding, &mut writer)?;
+        P::g2_to_gnark(&self.gamma_2, encoding, &mut writer)?;
+        P::g1_to_gnark(&self.delta_1, encoding, &mut writer)?;
+        P::g2_to_gnark(&self.delta_2, encoding, &mut writer)?;
+        let len = u32::try_from(self.k.len()).expect("fits into u32");
+        writer.write_all(&len.to_be_bytes())?;
+        for k in self.k.iter() {
+            P::g1_to_gnark(k, encoding, &mut writer)?;
+        }
+        // no committed public inputs and no commitment keys
+        writer.write_all(&0u32.to_be_bytes())?;
+        writer.write_all(&0u32.to_be_bytes())
+    }
+}
+
+impl<P: GnarkPairingBridge> JsonVerificationKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Parses a verification key either in the snarkjs JSON format or in gnark's binary format, detecting the format
+    /// and the point encoding from the input.
+    pub fn from_snarkjs_or_gnark(bytes: &[u8]) -> Result<Self, GnarkError> {
+        if is_json(bytes) {
+            Ok(serde_json::from_slice(bytes)?)
+        } else {
+            Ok(GnarkVerifyingKey::from_reader(bytes)?.into_snarkjs())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::CheckElement;
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+    use std::fs::{self, File};
+
+    fn proof_roundtrip<P: GnarkPairingBridge>(curve: &str, compressed_len: usize, raw_len: usize)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let json = fs::read(format!(
+            "../../test_vectors/Groth16/{curve}/multiplier2/circom.proof"
+        ))
+        .unwrap();
+        let proof = serde_json::from_slice::<Groth16Proof<P>>(&json).unwrap();
+        assert_eq!(Groth16Proof::from_snarkjs_or_gnark(&json).unwrap(), proof);
+        for (encoding, len) in [
+            (GnarkEncoding::Compressed, compressed_len),
+            (GnarkEncoding::Raw, raw_len),
+        ] {
+            let mut bytes = Vec::new();
+            proof.to_gnark_writer(&mut bytes, encoding).unwrap();
+            assert_eq!(bytes.len(), len);
+            assert_eq!(
+                Groth16Proof::from_gnark_reader(bytes.as_slice()).unwrap(),
+                proof
+            );
+            assert_eq!(Groth16Proof::from_snarkjs_or_gnark(&bytes).unwrap(), proof);
+        }
+    }
+
+    fn vk_roundtrip<P: GnarkPairingBridge>(curve: &str)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let dir = format!("../../test_vectors/Groth16/{curve}/multiplier2");
+        let json = fs::read(format!("{dir}/verification_key.json")).unwrap();
+        let vk = serde_json::from_slice::<JsonVerificationKey<P>>(&json).unwrap();
+        let zkey = ZKey::<P>::from_reader(
+            File::open(format!("{dir}/circuit.zkey")).unwrap(),
+            CheckElement::No,
+        )
+        .unwrap();
+        let gnark_vk = GnarkVerifyingKey::from_snarkjs(&vk, &zkey).unwrap();
+        for encoding in [GnarkEncoding::Compressed, GnarkEncoding::Raw] {
+            let mut bytes = Vec::new();
+            gnark_vk.to_writer(&mut bytes, encoding).unwrap();
+            assert_eq!(
+                GnarkVerifyingKey::from_reader(bytes.as_slice()).unwrap(),
+                gnark_vk
+            );
+            // the pairing of α and β is recomputed, so this also checks it against snarkjs
+            assert_eq!(
+                JsonVerificationKey::from_snarkjs_or_gnark(&bytes).unwrap(),
+                vk
+            );
+        }
+    }
+
+    #[test]
+    fn gnark_proof_roundtrip_bn254() {
+        proof_roundtrip::<Bn254>("bn254", 32 + 64 + 32 + 4 + 32, 64 + 128 + 64 + 4 + 64);
+    }
+
+    #[test]
+    fn gnark_proof_roundtrip_bls12_381() {
+        proof_roundtrip::<Bls12_381>("bls12_381", 48 + 96 + 48 + 4 + 48, 96 + 192 + 96 + 4 + 96);
+    }
+
+    #[test]
+    fn gnark_vk_roundtrip_bn254() {
+        vk_roundtrip::<Bn254>("bn254");
+    }
+
+    #[test]
+    fn gnark_vk_roundtrip_bls12_381() {
+        vk_roundtrip::<Bls12_381>("bls12_381");
+    }
+
+    #[test]
+    fn gnark_legacy_proof_without_commitments() {
+        let json = fs::read("../../test_vectors/Groth16/bn254/multiplier2/circom.proof").unwrap();
+        let proof = serde_json::from_slice::<Groth16Proof<Bn254>>(&json).unwrap();
+        let mut bytes = Vec::new();
+        proof
+            .to_gnark_writer(&mut bytes, GnarkEncoding::Compressed)
+            .unwrap();
+        assert_eq!(
+            Groth16Proof::<Bn254>::from_gnark_reader(&bytes[..128]).unwrap(),
+            proof
+        );
+
+        // a proof with a commitment cannot be converted
+        bytes[128..132].copy_from_slice(&1u32.to_be_bytes());
+        assert!(matches!(
+            Groth16Proof::<Bn254>::from_gnark_reader(bytes.as_slice()),
+            Err(GnarkError::UnsupportedCommitments(1))
+        ));
+    }
+
+    #[test]
+    fn gnark_point_encoding_bn254() {
+        // the generator (1, 2) has the smaller of the two y-coordinates
+        let mut bytes = Vec::new();
+        Bn254::g1_to_gnark(
+            &ark_bn254::G1Affine::generator(),
+            GnarkEncoding::Compressed,
+            &mut bytes,
+        )
+        .unwrap();
+        let mut expected = [0; 32];
+        expected[0] = 0b10 << 6;
+        expected[31] = 1;
+        assert_eq!(bytes, expected);
+
+        let mut bytes = Vec::new();
+        Bn254::g1_to_gnark(
+            &-ark_bn254::G1Affine::generator(),
+            GnarkEncoding::Compressed,
+            &mut bytes,
+        )
+        .unwrap();
+        expected[0] = 0b11 << 6;
+        assert_eq!(bytes, expected);
+        assert_eq!(
+            Bn254::g1_from_gnark(bytes.as_slice()).unwrap(),
+            -ark_bn254::G1Affine::generator()
+        );
+
+        let mut bytes = Vec::new();
+        Bn254::g1_to_gnark(&ark_bn254::G1Affine::zero(), GnarkEncoding::Raw, &mut bytes).unwrap();
+        assert_eq!(bytes, [0; 64]);
+        assert!(Bn254::g1_from_gnark(bytes.as_slice()).unwrap().is_zero());
+        // (1, 1) is not on the curve
+        bytes[31] = 1;
+        bytes[63] = 1;
+        assert!(matches!(
+            Bn254::g1_from_gnark(bytes.as_slice()),
+            Err(GnarkError::InvalidPoint)
+        ));
+    }
+}
@@ -0,0 +1,282 @@
+//! This module defines the [`MmapZKey`] struct, which memory-maps a circom zkey file and parses its sections lazily.
+//!
+//! [`ZKey::from_reader`] reads the whole file into memory before parsing it, so the raw sections and the parsed points are held at the same time. An [`MmapZKey`] instead parses the header when it is opened and every other section on first access, directly from the mapped file. The mapped pages are backed by the file and can be evicted by the OS, which roughly halves the peak memory for big circuits.
+use ark_ec::pairing::Pairing;
+use ark_serialize::SerializationError;
+use memmap2::Mmap;
+use rayon::prelude::*;
+
+use std::{fs::File, ops::Range, path::Path, sync::OnceLock};
+
+use super::zkey::{ConstraintMatrixAB, HeaderGroth};
+use crate::{
+    binfile::{self, ZKeyParserError, ZKeyParserResult},
+    groth16::{ConstraintMatrix, ZKey},
+    traits::{CheckElement, CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+};
+
+/// A memory-mapped zkey in the format defined by circom, whose sections are parsed on first access and cached afterwards. Use [`MmapZKey::into_zkey`] to obtain a [`ZKey`].
+pub struct MmapZKey<P: Pairing> {
+    /// amount of public inputs
+    pub n_public: usize,
+    /// domain size
+    pub pow: usize,
+    /// beta
+    pub beta_g1: P::G1Affine,
+    /// delta
+    pub delta_g1: P::G1Affine,
+    /// alpha_g1
+    pub alpha_g1: P::G1Affine,
+    /// beta_g2
+    pub beta_g2: P::G2Affine,
+    /// delta_g2
+    pub delta_g2: P::G2Affine,
+    n_vars: usize,
+    domain_size: usize,
+    check: CheckElement,
+    mmap: Mmap,
+    sections: Vec<Range<usize>>,
+    a_query: OnceLock<Vec<P::G1Affine>>,
+    b_g1_query: OnceLock<Vec<P::G1Affine>>,
+    b_g2_query: OnceLock<Vec<P::G2Affine>>,
+    h_query: OnceLock<Vec<P::G1Affine>>,
+    l_query: OnceLock<Vec<P::G1Affine>>,
+    matrices: OnceLock<ConstraintMatrixAB<P::ScalarField>>,
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> MmapZKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Memory-maps the zkey at the given path and parses its header.
+    ///
+    /// The file must not be modified while it is mapped, as the sections are parsed from the file on first access.
+    ///
+    /// See [`ZKey::from_reader`] for the meaning of `check`.
+    pub fn open(path: impl AsRef<Path>, check: CheckElement) -> ZKeyParserResult<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the file is only read, and we document that it must not be modified while it is mapped
+        let mmap = unsafe { Mmap::map(&file)? };
+        Self::from_mmap(mmap, check)
+    }
+
+    /// Parses the header of an already memory-mapped zkey.
+    ///
+    /// See [`ZKey::from_reader`] for the meaning of `check`.
+    pub fn from_mmap(mmap: Mmap, check: CheckElement) -> ZKeyParserResult<Self> {
+        let sections = binfile::section_ranges(&mmap)?;
+        let mut header_section = section(&mmap, &sections, 2);
+        let header = HeaderGroth::<P>::read(&mut header_section, check)?;
+        Ok(Self {
+            n_public: header.n_public,
+            pow: header.pow,
+            beta_g1: header.beta_g1,
+            delta_g1: header.delta_g1,
+            alpha_g1: header.alpha_g1,
+            beta_g2: header.beta_g2,
+            delta_g2: header.delta_g2,
+            n_vars: header.n_vars,
+            domain_size: usize::try_from(header.domain_size).expect("fits into usize"),
+            check,
+            mmap,
+            sections,
+            a_query: OnceLock::new(),
+            b_g1_query: OnceLock::new(),
+            b_g2_query: OnceLock::new(),
+            h_query: OnceLock::new(),
+            l_query: OnceLock::new(),
+            matrices: OnceLock::new(),
+        })
+    }
+
+    /// Returns the a_query, which is parsed on first access.
+    pub fn a_query(&self) -> ZKeyParserResult<&[P::G1Affine]> {
+        materialize(&self.a_query, || self.g1_vec(5, self.n_vars)).map(Vec::as_slice)
+    }
+
+    /// Returns the b_query in G1, which is parsed on first access.
+    pub fn b_g1_query(&self) -> ZKeyParserResult<&[P::G1Affine]> {
+        materialize(&self.b_g1_query, || self.g1_vec(6, self.n_vars)).map(Vec::as_slice)
+    }
+
+    /// Returns the b_query in G2, which is parsed on first access.
+    pub fn b_g2_query(&self) -> ZKeyParserResult<&[P::G2Affine]> {
+        materialize(&self.b_g2_query, || self.g2_vec(7, self.n_vars)).map(Vec::as_slice)
+    }
+
+    /// Returns the l_query, which is parsed on first access.
+    pub fn l_query(&self) -> ZKeyParserResult<&[P::G1Affine]> {
+        materialize(&self.l_query, || {
+            self.g1_vec(8, self.n_vars - self.n_public - 1)
+        })
+        .map(Vec::as_slice)
+    }
+
+    /// Returns the h_query, which is parsed on first access.
+    pub fn h_query(&self) -> ZKeyParserResult<&[P::G1Affine]> {
+        materialize(&self.h_query, || self.g1_vec(9, self.domain_size)).map(Vec::as_slice)
+    }
+
+    /// Returns the amount of constraints. The constraint matrices are parsed on first access.
+    pub fn num_constraints(&self) -> ZKeyParserResult<usize> {
+        Ok(self.matrices()?.0)
+    }
+
+    /// Returns the constraint matrix A, which is parsed on first access.
+    pub fn a_matrix(&self) -> ZKeyParserResult<&ConstraintMatrix<P::ScalarField>> {
+        Ok(&self.matrices()?.1)
+    }
+
+    /// Returns the constraint matrix B, which is parsed on first access.
+    pub fn b_matrix(&self) -> ZKeyParserResult<&ConstraintMatrix<P::ScalarField>> {
+        Ok(&self.matrices()?.2)
+    }
+
+    /// Parses all sections which were not accessed yet and returns the [`ZKey`]. The sections are parsed in parallel, as in [`ZKey::from_reader`].
+    pub fn into_zkey(self) -> ZKeyParserResult<ZKey<P>> {
+        tracing::debug!("parsing remaining zkey sections with rayon...");
+        let mut results = [Ok(()), Ok(()), Ok(()), Ok(()), Ok(()), Ok(())];
+        let [a, b_g1, b_g2, l, h, matrices] = &mut results;
+        rayon::scope(|s| {
+            s.spawn(|_| *a = self.a_query().map(drop));
+            s.spawn(|_| *b_g1 = self.b_g1_query().map(drop));
+            s.spawn(|_| *b_g2 = self.b_g2_query().map(drop));
+            s.spawn(|_| *l = self.l_query().map(drop));
+            s.spawn(|_| *h = self.h_query().map(drop));
+            s.spawn(|_| *matrices = self.matrices().map(drop));
+        });
+        results.into_iter().collect::<ZKeyParserResult<()>>()?;
+
+        // all sections are parsed, so the unwraps are fine
+        let (num_constraints, a_matrix, b_matrix) = self.matrices.into_inner().unwrap();
+        Ok(ZKey {
+            n_public: self.n_public,
+            pow: self.pow,
+            num_constraints,
+            beta_g1: self.beta_g1,
+            delta_g1: self.delta_g1,
+            a_query: self.a_query.into_inner().unwrap(),
+            b_g1_query: self.b_g1_query.into_inner().unwrap(),
+            b_g2_query: self.b_g2_query.into_inner().unwrap(),
+            h_query: self.h_query.into_inner().unwrap(),
+            l_query: self.l_query.into_inner().unwrap(),
+            alpha_g1: self.alpha_g1,
+            beta_g2: self.beta_g2,
+            delta_g2: self.delta_g2,
+            a_matrix,
+            b_matrix,
+        })
+    }
+
+    fn matrices(&self) -> ZKeyParserResult<&ConstraintMatrixAB<P::ScalarField>> {
+        materialize(&self.matrices, || {
+            ZKey::<P>::constraint_matrices(
+                self.domain_size,
+                self.n_public,
+                section(&self.mmap, &self.sections, 4),
+            )
+        })
+    }
+
+    fn g1_vec(&self, id: usize, num: usize) -> ZKeyParserResult<Vec<P::G1Affine>> {
+        let bytes = points(
+            section(&self.mmap, &self.sections, id),
+            P::G1_SERIALIZED_BYTE_SIZE_UNCOMPRESSED,
+            num,
+        )?;
+        // the points are parsed directly from the mapped file, without copying the section first
+        Ok(bytes
+            .par_chunks_exact(P::G1_SERIALIZED_BYTE_SIZE_UNCOMPRESSED)
+            .map(|chunk| P::g1_from_bytes(chunk, self.check))
+            .collect::<Result<Vec<_>, SerializationError>>()?)
+    }
+
+    fn g2_vec(&self, id: usize, num: usize) -> ZKeyParserResult<Vec<P::G2Affine>> {
+        let bytes = points(
+            section(&self.mmap, &self.sections, id),
+            P::G2_SERIALIZED_BYTE_SIZE_UNCOMPRESSED,
+            num,
+        )?;
+        Ok(bytes
+            .par_chunks_exact(P::G2_SERIALIZED_BYTE_SIZE_UNCOMPRESSED)
+            .map(|chunk| P::g2_from_bytes(chunk, self.check))
+            .collect::<Result<Vec<_>, SerializationError>>()?)
+    }
+}
+
+/// Returns the section with the given id, which is empty if the file does not contain it.
+fn section<'a>(mmap: &'a Mmap, sections: &[Range<usize>], id: usize) -> &'a [u8] {
+    sections
+        .get(id - 1)
+        .map(|range| &mmap[range.clone()])
+        .unwrap_or_default()
+}
+
+/// Returns the bytes of the first `num` points of the given size in the section.
+fn points(section: &[u8], size: usize, num: usize) -> ZKeyParserResult<&[u8]> {
+    section.get(..size * num).ok_or_else(|| {
+        ZKeyParserError::CorruptedBinFile(format!("section too short for {num} points"))
+    })
+}
+
+/// Returns the value of the cell, which is initialized with `init` if it is empty.
+fn materialize<T>(
+    cell: &OnceLock<T>,
+    init: impl FnOnce() -> ZKeyParserResult<T>,
+) -> ZKeyParserResult<&T> {
+    if let Some(value) = cell.get() {
+        return Ok(value);
+    }
+    let value = init()?;
+    Ok(cell.get_or_init(|| value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+    use std::fs::File;
+
+    fn assert_same_zkey<P: Pairing + CircomArkworksPairingBridge>(path: &str)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let zkey = ZKey::<P>::from_reader(File::open(path).unwrap(), CheckElement::Yes).unwrap();
+        let mmap_zkey = MmapZKey::<P>::open(path, CheckElement::Yes).unwrap();
+        // access some sections before the rest is parsed
+        assert_eq!(zkey.h_query, mmap_zkey.h_query().unwrap());
+        assert_eq!(zkey.num_constraints, mmap_zkey.num_constraints().unwrap());
+        let lazy = mmap_zkey.into_zkey().unwrap();
+        assert_eq!(zkey.n_public, lazy.n_public);
+        assert_eq!(zkey.pow, lazy.pow);
+        assert_eq!(zkey.num_constraints, lazy.num_constraints);
+        assert_eq!(zkey.alpha_g1, lazy.alpha_g1);
+        assert_eq!(zkey.beta_g1, lazy.beta_g1);
+        assert_eq!(zkey.beta_g2, lazy.beta_g2);
+        assert_eq!(zkey.delta_g1, lazy.delta_g1);
+        assert_eq!(zkey.delta_g2, lazy.delta_g2);
+        assert_eq!(zkey.a_query, lazy.a_query);
+        assert_eq!(zkey.b_g1_query, lazy.b_g1_query);
+        assert_eq!(zkey.b_g2_query, lazy.b_g2_query);
+        assert_eq!(zkey.h_query, lazy.h_query);
+        assert_eq!(zkey.l_query, lazy.l_query);
+        assert_eq!(zkey.a_matrix, lazy.a_matrix);
+        assert_eq!(zkey.b_matrix, lazy.b_matrix);
+    }
+
+    #[test]
+    fn mmap_matches_reader_bls12_381() {
+        assert_same_zkey::<Bls12_381>(
+            "../../test_vectors/Groth16/bls12_381/multiplier2/circuit.zkey",
+        );
+    }
+
+    #[test]
+    fn mmap_matches_reader_bn254() {
+        assert_same_zkey::<Bn254>("../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey");
+        assert_same_zkey::<Bn254>("../../test_vectors/Groth16/bn254/poseidon/circuit.zkey");
+    }
+}
@@ -1,9 +1,13 @@
 //! This module defines types related to Groth16 used in circom and utilities to read these types from files.
+mod gnark;
+mod mmap_zkey;
 mod proof;
 mod public_input;
 mod verification_key;
 mod zkey;
 
+pub use gnark::{GnarkEncoding, GnarkError, GnarkPairingBridge, GnarkVerifyingKey};
+pub use mmap_zkey::MmapZKey;
 pub use proof::Groth16Proof;
 pub use public_input::JsonPublicInput;
 pub use verification_key::JsonVerificationKey;
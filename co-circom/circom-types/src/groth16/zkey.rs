@@ -79,19 +79,19 @@ pub struct ZKey<P: Pairing> {
 
 /// A constraint matrix used in Groth16.
 pub type ConstraintMatrix<F> = Vec<Vec<(F, usize)>>;
-type ConstraintMatrixAB<F> = (usize, ConstraintMatrix<F>, ConstraintMatrix<F>);
+pub(super) type ConstraintMatrixAB<F> = (usize, ConstraintMatrix<F>, ConstraintMatrix<F>);
 
 #[derive(Clone, Debug)]
-struct HeaderGroth<P: Pairing> {
-    n_vars: usize,
-    n_public: usize,
-    domain_size: u32,
-    pow: usize,
-    alpha_g1: P::G1Affine,
-    beta_g1: P::G1Affine,
-    beta_g2: P::G2Affine,
-    delta_g1: P::G1Affine,
-    delta_g2: P::G2Affine,
+pub(super) struct HeaderGroth<P: Pairing> {
+    pub(super) n_vars: usize,
+    pub(super) n_public: usize,
+    pub(super) domain_size: u32,
+    pub(super) pow: usize,
+    pub(super) alpha_g1: P::G1Affine,
+    pub(super) beta_g1: P::G1Affine,
+    pub(super) beta_g2: P::G2Affine,
+    pub(super) delta_g1: P::G1Affine,
+    pub(super) delta_g2: P::G2Affine,
 }
 
 impl<P: Pairing + CircomArkworksPairingBridge> ZKey<P>
@@ -215,7 +215,7 @@ where
         Ok(P::g1_vec_from_reader(reader, n_vars, check)?)
     }
 
-    fn constraint_matrices<R: Read>(
+    pub(super) fn constraint_matrices<R: Read>(
         domain_size: usize,
         n_public: usize,
         mut matrices_section: R,
@@ -254,7 +254,7 @@ where
     P::BaseField: CircomArkworksPrimeFieldBridge,
     P::ScalarField: CircomArkworksPrimeFieldBridge,
 {
-    fn read<R: Read>(mut reader: &mut R, check: CheckElement) -> ZKeyParserResult<Self> {
+    pub(super) fn read<R: Read>(mut reader: &mut R, check: CheckElement) -> ZKeyParserResult<Self> {
         tracing::debug!("reading groth16 header..");
         let n8q: u32 = u32::deserialize_uncompressed(&mut reader)?;
         //modulus of BaseField
@@ -322,7 +322,10 @@ mod tests {
     use super::*;
     use ark_bls12_381::Bls12_381;
     use ark_bn254::{Bn254, Fq, Fq2, G1Affine, G1Projective, G2Affine, G2Projective};
-    use ark_ff::BigInteger256;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{BigInteger, BigInteger256, UniformRand};
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::rand::{rngs::StdRng, Rng, SeedableRng};
     use num_bigint::BigUint;
     use std::fs::File;
 
@@ -704,4 +707,198 @@ mod tests {
             assert_eq!(expected, de);
         }
     }
+
+    /// Serializes the coordinates of a point in montgomery form, as in a zkey.
+    macro_rules! montgomery_bytes {
+        ($($c: expr),*) => {{
+            let mut bytes = Vec::new();
+            // the inner big int of an arkworks field element is in montgomery form
+            $($c.0.serialize_uncompressed(&mut bytes).unwrap();)*
+            bytes
+        }};
+    }
+
+    /// Writes the sections of a zkey that are read by [`ZKey::from_reader`], with the points encoded by the given functions.
+    fn write_zkey<P: Pairing + CircomArkworksPairingBridge>(
+        zkey: &ZKey<P>,
+        g1_bytes: &impl Fn(&P::G1Affine) -> Vec<u8>,
+        g2_bytes: &impl Fn(&P::G2Affine) -> Vec<u8>,
+    ) -> Vec<u8>
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let n_vars = zkey.a_query.len();
+        let mut header = Vec::new();
+        header.extend(P::BaseField::MODULUS_BIT_SIZE.div_ceil(8).to_le_bytes());
+        P::BaseField::MODULUS
+            .serialize_uncompressed(&mut header)
+            .unwrap();
+        header.extend(P::ScalarField::MODULUS_BIT_SIZE.div_ceil(8).to_le_bytes());
+        P::ScalarField::MODULUS
+            .serialize_uncompressed(&mut header)
+            .unwrap();
+        for x in [n_vars, zkey.n_public, zkey.h_query.len()] {
+            header.extend(u32::try_from(x).unwrap().to_le_bytes());
+        }
+        header.extend(g1_bytes(&zkey.alpha_g1));
+        header.extend(g1_bytes(&zkey.beta_g1));
+        header.extend(g2_bytes(&zkey.beta_g2));
+        // gamma_g2 is only part of the verification key
+        header.extend(g2_bytes(&P::G2Affine::generator()));
+        header.extend(g1_bytes(&zkey.delta_g1));
+        header.extend(g2_bytes(&zkey.delta_g2));
+
+        // the coefficients are stored as v * R^2, where R is the montgomery radix
+        let radix = P::ScalarField::from(
+            BigUint::one() << (64 * <P::ScalarField as PrimeField>::BigInt::NUM_LIMBS),
+        );
+        let mut coeffs = Vec::new();
+        for (matrix, m) in [&zkey.a_matrix, &zkey.b_matrix].into_iter().enumerate() {
+            for (constraint, row) in m.iter().enumerate() {
+                for (value, signal) in row {
+                    coeffs.push((matrix, constraint, *signal, *value));
+                }
+            }
+        }
+        // the constraints of the public inputs
+        for signal in 0..=zkey.n_public {
+            coeffs.push((
+                0,
+                zkey.num_constraints + signal,
+                signal,
+                P::ScalarField::one(),
+            ));
+        }
+        let mut matrices = u32::try_from(coeffs.len()).unwrap().to_le_bytes().to_vec();
+        for (matrix, constraint, signal, value) in coeffs {
+            for x in [matrix, constraint, signal] {
+                matrices.extend(u32::try_from(x).unwrap().to_le_bytes());
+            }
+            (value * radix * radix)
+                .into_bigint()
+                .serialize_uncompressed(&mut matrices)
+                .unwrap();
+        }
+
+        let g1_section = |points: &[P::G1Affine]| points.iter().flat_map(g1_bytes).collect();
+        let sections: [Vec<u8>; 9] = [
+            // groth16
+            1u32.to_le_bytes().to_vec(),
+            header,
+            // the verification key is not read
+            vec![],
+            matrices,
+            g1_section(&zkey.a_query),
+            g1_section(&zkey.b_g1_query),
+            zkey.b_g2_query.iter().flat_map(g2_bytes).collect(),
+            g1_section(&zkey.l_query),
+            g1_section(&zkey.h_query),
+        ];
+        let mut bytes = b"zkey".to_vec();
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend(u32::try_from(sections.len()).unwrap().to_le_bytes());
+        for (id, section) in sections.into_iter().enumerate() {
+            bytes.extend(u32::try_from(id + 1).unwrap().to_le_bytes());
+            bytes.extend(u64::try_from(section.len()).unwrap().to_le_bytes());
+            bytes.extend(section);
+        }
+        bytes
+    }
+
+    fn zkey_roundtrip<P: Pairing + CircomArkworksPairingBridge>(
+        g1_bytes: impl Fn(&P::G1Affine) -> Vec<u8>,
+        g2_bytes: impl Fn(&P::G2Affine) -> Vec<u8>,
+    ) where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let mut rng = StdRng::seed_from_u64(0);
+        let n_vars = 5;
+        let n_public = 1;
+        let num_constraints = 2;
+        let domain_size = 4;
+        let g1 = |n: usize, rng: &mut StdRng| {
+            (0..n)
+                .map(|_| P::G1::rand(rng).into_affine())
+                .collect::<Vec<_>>()
+        };
+        let matrix = |rng: &mut StdRng| {
+            (0..num_constraints)
+                .map(|_| {
+                    (0..2)
+                        .map(|_| (P::ScalarField::rand(rng), rng.gen_range(0..n_vars)))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut a_query = g1(n_vars, &mut rng);
+        // unused signals are encoded as the point at infinity
+        a_query[n_vars - 1] = P::G1Affine::zero();
+        let zkey = ZKey::<P> {
+            n_public,
+            pow: 2,
+            num_constraints,
+            beta_g1: P::G1::rand(&mut rng).into_affine(),
+            delta_g1: P::G1::rand(&mut rng).into_affine(),
+            a_query,
+            b_g1_query: g1(n_vars, &mut rng),
+            b_g2_query: (0..n_vars)
+                .map(|_| P::G2::rand(&mut rng).into_affine())
+                .collect(),
+            h_query: g1(domain_size, &mut rng),
+            l_query: g1(n_vars - n_public - 1, &mut rng),
+            alpha_g1: P::G1::rand(&mut rng).into_affine(),
+            beta_g2: P::G2::rand(&mut rng).into_affine(),
+            delta_g2: P::G2::rand(&mut rng).into_affine(),
+            a_matrix: matrix(&mut rng),
+            b_matrix: matrix(&mut rng),
+        };
+        let bytes = write_zkey(&zkey, &g1_bytes, &g2_bytes);
+        for check in [CheckElement::Yes, CheckElement::No] {
+            let read = ZKey::<P>::from_reader(bytes.as_slice(), check).unwrap();
+            assert_eq!(read.n_public, zkey.n_public);
+            assert_eq!(read.pow, zkey.pow);
+            assert_eq!(read.num_constraints, zkey.num_constraints);
+            assert_eq!(read.alpha_g1, zkey.alpha_g1);
+            assert_eq!(read.beta_g1, zkey.beta_g1);
+            assert_eq!(read.beta_g2, zkey.beta_g2);
+            assert_eq!(read.delta_g1, zkey.delta_g1);
+            assert_eq!(read.delta_g2, zkey.delta_g2);
+            assert_eq!(read.a_query, zkey.a_query);
+            assert_eq!(read.b_g1_query, zkey.b_g1_query);
+            assert_eq!(read.b_g2_query, zkey.b_g2_query);
+            assert_eq!(read.h_query, zkey.h_query);
+            assert_eq!(read.l_query, zkey.l_query);
+            assert_eq!(read.a_matrix, zkey.a_matrix);
+            assert_eq!(read.b_matrix, zkey.b_matrix);
+        }
+        // a point which is not on the curve is rejected
+        let mut bytes = bytes;
+        let alpha_g1_offset = 4 + 4 + 4 // magic, version, number of sections
+            + 4 + 8 + 4 // section 1
+            + 4 + 8 // id and length of section 2
+            + 4 + P::BaseField::SERIALIZED_BYTE_SIZE
+            + 4 + P::ScalarField::SERIALIZED_BYTE_SIZE
+            + 4 + 4 + 4;
+        bytes[alpha_g1_offset] ^= 1;
+        assert!(ZKey::<P>::from_reader(bytes.as_slice(), CheckElement::Yes).is_err());
+    }
+
+    #[test]
+    fn zkey_roundtrip_bls12_377() {
+        zkey_roundtrip::<ark_bls12_377::Bls12_377>(
+            |p| montgomery_bytes!(p.x, p.y),
+            |p| montgomery_bytes!(p.x.c0, p.x.c1, p.y.c0, p.y.c1),
+        );
+    }
+
+    #[test]
+    fn zkey_roundtrip_bw6_761() {
+        // G2 is defined over the base field, so its points are encoded like points of G1
+        zkey_roundtrip::<ark_bw6_761::BW6_761>(
+            |p| montgomery_bytes!(p.x, p.y),
+            |p| montgomery_bytes!(p.x, p.y),
+        );
+    }
 }
@@ -1,5 +1,6 @@
 #![warn(missing_docs)]
 //! This crate defines types used in circom and utilities to read these types from files.
+pub mod binary;
 mod binfile;
 pub mod groth16;
 pub mod plonk;
@@ -79,7 +79,7 @@ where
     #[serde(rename = "w")]
     #[serde(serialize_with = "P::serialize_fr::<_>")]
     #[serde(deserialize_with = "P::deserialize_fr_element::<_>")]
-    w: P::ScalarField,
+    pub(crate) w: P::ScalarField,
 }
 
 #[cfg(test)]
@@ -428,3 +428,57 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plonk::JsonVerificationKey;
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+    use std::fs::File;
+
+    fn zkey_matches_vk<P: Pairing + CircomArkworksPairingBridge>(curve: &str, circuit: &str)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let dir = format!("../../test_vectors/Plonk/{curve}/{circuit}");
+        let vk: JsonVerificationKey<P> =
+            serde_json::from_reader(File::open(format!("{dir}/verification_key.json")).unwrap())
+                .unwrap();
+        for check in [CheckElement::Yes, CheckElement::No] {
+            let zkey =
+                ZKey::<P>::from_reader(File::open(format!("{dir}/circuit.zkey")).unwrap(), check)
+                    .unwrap();
+            assert_eq!(zkey.n_public, vk.n_public);
+            assert_eq!(zkey.pow, vk.power);
+            assert_eq!(zkey.domain_size, 1 << vk.power);
+            assert_eq!(zkey.lagrange.len(), zkey.n_public);
+            assert_eq!(zkey.p_tau.len(), zkey.domain_size + 6);
+            let zkey_vk = zkey.verifying_key;
+            assert_eq!(zkey_vk.k1, vk.k1);
+            assert_eq!(zkey_vk.k2, vk.k2);
+            assert_eq!(zkey_vk.qm, vk.qm);
+            assert_eq!(zkey_vk.ql, vk.ql);
+            assert_eq!(zkey_vk.qr, vk.qr);
+            assert_eq!(zkey_vk.qo, vk.qo);
+            assert_eq!(zkey_vk.qc, vk.qc);
+            assert_eq!(zkey_vk.s1, vk.s1);
+            assert_eq!(zkey_vk.s2, vk.s2);
+            assert_eq!(zkey_vk.s3, vk.s3);
+            assert_eq!(zkey_vk.x_2, vk.x2);
+        }
+    }
+
+    #[test]
+    fn zkey_matches_vk_bn254() {
+        zkey_matches_vk::<Bn254>("bn254", "multiplier2");
+        zkey_matches_vk::<Bn254>("bn254", "poseidon");
+    }
+
+    #[test]
+    fn zkey_matches_vk_bls12_381() {
+        zkey_matches_vk::<Bls12_381>("bls12_381", "multiplier2");
+        zkey_matches_vk::<Bls12_381>("bls12_381", "poseidon");
+    }
+}
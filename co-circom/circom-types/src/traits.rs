@@ -33,20 +33,24 @@ macro_rules! impl_bls12_381 {
     };
 }
 
-macro_rules! impl_serde_for_curve {
-    ($mod_name: ident, $config: ident, $curve: ident, $name: expr, $field_size: expr, $scalar_field_size: expr, $circom_name: expr) => {
-
-
-mod $mod_name {
-
-    use $curve::{$config, Fq, Fq2, Fr};
-    use ark_ff::BigInt;
-    use ark_serialize::{CanonicalDeserialize, SerializationError};
-    use serde::ser::SerializeSeq;
+macro_rules! impl_bls12_377 {
+    () => {
+        impl_serde_for_curve!(
+            bls12_377,
+            Bls12_377,
+            ark_bls12_377,
+            "bls12_377",
+            48,
+            32,
+            "bls12377"
+        );
+    };
+}
 
-    use super::*;
-        impl CircomArkworksPrimeFieldBridge for Fr {
-            const SERIALIZED_BYTE_SIZE: usize = $scalar_field_size;
+macro_rules! impl_prime_field_bridge {
+    ($field: ty, $size: expr) => {
+        impl CircomArkworksPrimeFieldBridge for $field {
+            const SERIALIZED_BYTE_SIZE: usize = $size;
             #[inline]
             fn from_reader(mut reader: impl Read) -> IoResult<Self> {
                 let mut buf = [0u8; Self::SERIALIZED_BYTE_SIZE];
@@ -58,38 +62,33 @@ mod $mod_name {
             fn montgomery_bigint_from_reader(mut reader: impl Read) -> IoResult<Self> {
                 let mut buf = [0u8; Self::SERIALIZED_BYTE_SIZE];
                 reader.read_exact(&mut buf[..])?;
-                Ok(Self::new_unchecked(BigInt::deserialize_uncompressed(
-                    buf.as_slice(),
-                )?))
+                Ok(Self::new_unchecked(
+                    ark_ff::BigInt::deserialize_uncompressed(buf.as_slice())?,
+                ))
             }
             #[inline]
             fn from_reader_for_groth16_zkey(reader: impl Read) -> IoResult<Self> {
-                Ok(Self::new_unchecked(Self::montgomery_bigint_from_reader(reader)?.into_bigint()))
+                Ok(Self::new_unchecked(
+                    Self::montgomery_bigint_from_reader(reader)?.into_bigint(),
+                ))
             }
-
         }
-        impl CircomArkworksPrimeFieldBridge for Fq {
-            const SERIALIZED_BYTE_SIZE: usize = $field_size;
-            #[inline]
-            fn from_reader(mut reader: impl Read) -> IoResult<Self> {
-                let mut buf = [0u8; Self::SERIALIZED_BYTE_SIZE];
-                reader.read_exact(&mut buf[..])?;
-                Ok(Self::from_le_bytes_mod_order(&buf))
-            }
+    };
+}
 
-            #[inline]
-            fn montgomery_bigint_from_reader(mut reader: impl Read) -> IoResult<Self> {
-                let mut buf = [0u8; Self::SERIALIZED_BYTE_SIZE];
-                reader.read_exact(&mut buf[..])?;
-                Ok(Self::new_unchecked(BigInt::deserialize_uncompressed(
-                    buf.as_slice(),
-                )?))
-            }
-            #[inline]
-            fn from_reader_for_groth16_zkey(reader: impl Read) -> IoResult<Self> {
-                Ok(Self::new_unchecked(Self::montgomery_bigint_from_reader(reader)?.into_bigint()))
-            }
-        }
+macro_rules! impl_serde_for_curve {
+    ($mod_name: ident, $config: ident, $curve: ident, $name: expr, $field_size: expr, $scalar_field_size: expr, $circom_name: expr) => {
+
+
+mod $mod_name {
+
+    use $curve::{$config, Fq, Fq2, Fr};
+    use ark_serialize::{CanonicalDeserialize, SerializationError};
+    use serde::ser::SerializeSeq;
+
+    use super::*;
+        impl_prime_field_bridge!(Fr, $scalar_field_size);
+        impl_prime_field_bridge!(Fq, $field_size);
 
         impl CircomArkworksPairingBridge for $config {
             const G1_SERIALIZED_BYTE_SIZE_COMPRESSED: usize = $field_size;
@@ -683,5 +682,329 @@ pub enum CheckElement {
     No,
 }
 
+/// BW6-761 differs from the other curves, as G2 is defined over the base field and the target field is a quadratic extension of a cubic extension. It is not supported by snarkjs, so we serialize elements of G2 like elements of G1 and elements of the target field as two sequences of three strings.
+mod bw6_761 {
+    use ark_bw6_761::{Fq, Fq3, Fq6, BW6_761};
+    use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+    use ark_serialize::{CanonicalDeserialize, SerializationError};
+    use serde::ser::SerializeSeq;
+
+    use super::*;
+
+    // the scalar field is the base field of BLS12-377 and already implements the bridge
+    impl_prime_field_bridge!(Fq, 96);
+
+    impl CircomArkworksPairingBridge for BW6_761 {
+        const G1_SERIALIZED_BYTE_SIZE_COMPRESSED: usize = Fq::SERIALIZED_BYTE_SIZE;
+        const G1_SERIALIZED_BYTE_SIZE_UNCOMPRESSED: usize = Fq::SERIALIZED_BYTE_SIZE * 2;
+        const G2_SERIALIZED_BYTE_SIZE_COMPRESSED: usize = Fq::SERIALIZED_BYTE_SIZE;
+        const G2_SERIALIZED_BYTE_SIZE_UNCOMPRESSED: usize = Fq::SERIALIZED_BYTE_SIZE * 2;
+        const GT_SERIALIZED_BYTE_SIZE_COMPRESSED: usize = 0;
+        const GT_SERIALIZED_BYTE_SIZE_UNCOMPRESSED: usize = 0;
+
+        fn get_circom_name() -> String {
+            "bw6761".to_owned()
+        }
+
+        fn g1_from_bytes(bytes: &[u8], check: CheckElement) -> IoResult<Self::G1Affine> {
+            let (x, y) = coordinates_from_bytes(bytes)?;
+            if x.is_zero() && y.is_zero() {
+                return Ok(Self::G1Affine::zero());
+            }
+            check_point(Self::G1Affine::new_unchecked(x, y), check)
+        }
+
+        fn g2_from_bytes(bytes: &[u8], check: CheckElement) -> IoResult<Self::G2Affine> {
+            let (x, y) = coordinates_from_bytes(bytes)?;
+            if x.is_zero() && y.is_zero() {
+                return Ok(Self::G2Affine::zero());
+            }
+            check_point(Self::G2Affine::new_unchecked(x, y), check)
+        }
+
+        fn g1_from_reader(mut reader: impl Read, check: CheckElement) -> IoResult<Self::G1Affine> {
+            let mut buf = [0u8; Self::G1_SERIALIZED_BYTE_SIZE_UNCOMPRESSED];
+            reader.read_exact(&mut buf)?;
+            Self::g1_from_bytes(&buf, check)
+        }
+
+        fn g2_from_reader(mut reader: impl Read, check: CheckElement) -> IoResult<Self::G2Affine> {
+            let mut buf = [0u8; Self::G2_SERIALIZED_BYTE_SIZE_UNCOMPRESSED];
+            reader.read_exact(&mut buf)?;
+            Self::g2_from_bytes(&buf, check)
+        }
+
+        fn g1_from_strings_projective(
+            x: &str,
+            y: &str,
+            z: &str,
+            check: CheckElement,
+        ) -> IoResult<Self::G1Affine> {
+            let p = Self::G1Affine::from(ark_bw6_761::G1Projective::new(
+                parse_field(x)?,
+                parse_field(y)?,
+                parse_field(z)?,
+            ));
+            if p.is_zero() {
+                return Ok(p);
+            }
+            check_point(p, check)
+        }
+
+        fn g1_to_strings_projective(p: &Self::G1Affine) -> Vec<String> {
+            point_to_strings(p.xy())
+        }
+
+        /// The coordinates are elements of the base field, so the second component of every coordinate must be zero.
+        fn g2_from_strings_projective(
+            x0: &str,
+            x1: &str,
+            y0: &str,
+            y1: &str,
+            z0: &str,
+            z1: &str,
+            check: CheckElement,
+        ) -> IoResult<Self::G2Affine> {
+            for c1 in [x1, y1, z1] {
+                if !parse_field(c1)?.is_zero() {
+                    return Err(SerializationError::InvalidData);
+                }
+            }
+            let p = Self::G2Affine::from(ark_bw6_761::G2Projective::new(
+                parse_field(x0)?,
+                parse_field(y0)?,
+                parse_field(z0)?,
+            ));
+            if p.is_zero() {
+                return Ok(p);
+            }
+            check_point(p, check)
+        }
+
+        fn deserialize_g2_element<'de, D>(deserializer: D) -> Result<Self::G2Affine, D::Error>
+        where
+            D: de::Deserializer<'de>,
+        {
+            let [x, y, z] = <[String; 3] as serde::Deserialize>::deserialize(deserializer)?;
+            Self::g2_from_strings_projective(&x, "0", &y, "0", &z, "0", CheckElement::Yes)
+                .map_err(|_| de::Error::custom("Invalid projective point on G2.".to_owned()))
+        }
+
+        fn serialize_g2<S: Serializer>(p: &Self::G2Affine, ser: S) -> Result<S::Ok, S::Error> {
+            let strings = point_to_strings(p.xy());
+            let mut seq = ser.serialize_seq(Some(strings.len()))?;
+            for ele in strings {
+                seq.serialize_element(&ele)?;
+            }
+            seq.end()
+        }
+
+        fn serialize_gt<S: Serializer>(p: &Self::TargetField, ser: S) -> Result<S::Ok, S::Error> {
+            let mut seq = ser.serialize_seq(Some(2))?;
+            for c in [p.c0, p.c1] {
+                seq.serialize_element(&[c.c0.to_string(), c.c1.to_string(), c.c2.to_string()])?;
+            }
+            seq.end()
+        }
+
+        fn serialize_fr<S: Serializer>(p: &Self::ScalarField, ser: S) -> Result<S::Ok, S::Error> {
+            ser.serialize_str(&p.to_string())
+        }
+
+        fn deserialize_gt_element<'de, D>(deserializer: D) -> Result<Self::TargetField, D::Error>
+        where
+            D: de::Deserializer<'de>,
+        {
+            deserializer.deserialize_seq(TargetGroupVisitor::<Self>::new())
+        }
+    }
+
+    impl<'de> de::Visitor<'de> for TargetGroupVisitor<BW6_761> {
+        type Value = Fq6;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str(
+                "An element of bw6_761::Fq6 represented as string with radix 10. Must be a sequence of form [[String; 3]; 2].",
+            )
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::SeqAccess<'de>,
+        {
+            let mut next = || {
+                let strings = seq.next_element::<[String; 3]>()?.ok_or(de::Error::custom(
+                    "expected elements target group in bw6_761 as sequence of sequences",
+                ))?;
+                let [c0, c1, c2] = strings.map(|s| parse_field(&s));
+                match (c0, c1, c2) {
+                    (Ok(c0), Ok(c1), Ok(c2)) => Ok(Fq3::new(c0, c1, c2)),
+                    _ => Err(de::Error::custom(
+                        "InvalidData for target group (cubic extension field)",
+                    )),
+                }
+            };
+            let c0 = next()?;
+            let c1 = next()?;
+            Ok(Fq6::new(c0, c1))
+        }
+    }
+
+    /// Reads the coordinates of a point, which are already in montgomery form.
+    fn coordinates_from_bytes(bytes: &[u8]) -> IoResult<(Fq, Fq)> {
+        let x = Fq::montgomery_bigint_from_reader(&bytes[..Fq::SERIALIZED_BYTE_SIZE])?;
+        let y = Fq::montgomery_bigint_from_reader(&bytes[Fq::SERIALIZED_BYTE_SIZE..])?;
+        Ok((x, y))
+    }
+
+    fn check_point<C: SWCurveConfig>(p: Affine<C>, check: CheckElement) -> IoResult<Affine<C>> {
+        let curve_checks = matches!(check, CheckElement::Yes);
+        if curve_checks && !p.is_on_curve() {
+            return Err(SerializationError::InvalidData);
+        }
+        if curve_checks && !p.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(p)
+    }
+
+    fn point_to_strings(xy: Option<(&Fq, &Fq)>) -> Vec<String> {
+        if let Some((x, y)) = xy {
+            vec![x.to_string(), y.to_string(), "1".to_owned()]
+        } else {
+            //point at infinity
+            vec!["0".to_owned(), "1".to_owned(), "0".to_owned()]
+        }
+    }
+
+    #[inline]
+    fn parse_field(string: &str) -> IoResult<Fq> {
+        Fq::from_str(string).map_err(|_| SerializationError::InvalidData)
+    }
+}
+
 impl_bn256!();
 impl_bls12_381!();
+impl_bls12_377!();
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::pairing::Pairing;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::UniformRand;
+    use ark_serialize::CanonicalSerialize;
+
+    use super::{CheckElement, CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
+    use crate::groth16::{Groth16Proof, JsonVerificationKey};
+
+    /// Serializes the coordinates of a point in montgomery form, as in a zkey.
+    macro_rules! montgomery_bytes {
+        ($($c: expr),*) => {{
+            let mut bytes = Vec::new();
+            // the inner big int of an arkworks field element is in montgomery form
+            $($c.0.serialize_uncompressed(&mut bytes).unwrap();)*
+            bytes
+        }};
+    }
+
+    fn roundtrip<P: Pairing + CircomArkworksPairingBridge>(
+        g1_coordinates: impl Fn(&P::G1Affine) -> Vec<u8>,
+        g2_coordinates: impl Fn(&P::G2Affine) -> Vec<u8>,
+    ) where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let mut rng = ark_std::test_rng();
+        let g1 = P::G1::rand(&mut rng).into_affine();
+        let g2 = P::G2::rand(&mut rng).into_affine();
+        let bytes = g1_coordinates(&g1);
+        assert_eq!(bytes.len(), P::G1_SERIALIZED_BYTE_SIZE_UNCOMPRESSED);
+        assert_eq!(P::g1_from_bytes(&bytes, CheckElement::Yes).unwrap(), g1);
+        let bytes = g2_coordinates(&g2);
+        assert_eq!(bytes.len(), P::G2_SERIALIZED_BYTE_SIZE_UNCOMPRESSED);
+        assert_eq!(P::g2_from_bytes(&bytes, CheckElement::Yes).unwrap(), g2);
+        // a point which is not on the curve is rejected
+        let mut bytes = g1_coordinates(&g1);
+        bytes[0] ^= 1;
+        assert!(P::g1_from_bytes(&bytes, CheckElement::Yes).is_err());
+
+        let proof = Groth16Proof::<P> {
+            pi_a: g1,
+            pi_b: g2,
+            pi_c: P::G1Affine::generator(),
+            protocol: "groth16".to_owned(),
+            curve: P::get_circom_name(),
+        };
+        let ser_proof = serde_json::to_string(&proof).unwrap();
+        let der_proof = serde_json::from_str::<Groth16Proof<P>>(&ser_proof).unwrap();
+        assert_eq!(der_proof, proof);
+
+        let vk = JsonVerificationKey::<P> {
+            protocol: "groth16".to_owned(),
+            n_public: 1,
+            alpha_1: g1,
+            beta_2: g2,
+            gamma_2: P::G2Affine::generator(),
+            delta_2: g2,
+            alpha_beta_gt: P::pairing(g1, g2).0,
+            ic: vec![g1, P::G1Affine::generator()],
+        };
+        let ser_vk = serde_json::to_string(&vk).unwrap();
+        let der_vk = serde_json::from_str::<JsonVerificationKey<P>>(&ser_vk).unwrap();
+        assert_eq!(der_vk, vk);
+    }
+
+    #[test]
+    fn roundtrip_bls12_377() {
+        roundtrip::<ark_bls12_377::Bls12_377>(
+            |p| montgomery_bytes!(p.x, p.y),
+            |p| montgomery_bytes!(p.x.c0, p.x.c1, p.y.c0, p.y.c1),
+        );
+    }
+
+    #[test]
+    fn roundtrip_bw6_761() {
+        roundtrip::<ark_bw6_761::BW6_761>(
+            |p| montgomery_bytes!(p.x, p.y),
+            |p| montgomery_bytes!(p.x, p.y),
+        );
+    }
+
+    #[test]
+    fn bw6_761_g2_like_g1() {
+        type P = ark_bw6_761::BW6_761;
+        assert_eq!(
+            P::G2_SERIALIZED_BYTE_SIZE_COMPRESSED,
+            P::G1_SERIALIZED_BYTE_SIZE_COMPRESSED
+        );
+        assert_eq!(
+            P::G2_SERIALIZED_BYTE_SIZE_UNCOMPRESSED,
+            P::G1_SERIALIZED_BYTE_SIZE_UNCOMPRESSED
+        );
+        assert_eq!(P::GT_SERIALIZED_BYTE_SIZE_COMPRESSED, 0);
+        assert_eq!(P::GT_SERIALIZED_BYTE_SIZE_UNCOMPRESSED, 0);
+
+        let mut rng = ark_std::test_rng();
+        let g2 = <P as Pairing>::G2::rand(&mut rng).into_affine();
+        let json = P::serialize_g2(&g2, serde_json::value::Serializer).unwrap();
+        let [x, y, z] = serde_json::from_value::<[String; 3]>(json.clone()).unwrap();
+        assert_eq!(P::deserialize_g2_element(json).unwrap(), g2);
+        assert_eq!(
+            P::g2_from_strings_projective(&x, "0", &y, "0", &z, "0", CheckElement::Yes).unwrap(),
+            g2
+        );
+        // the second components of the coordinates must be zero
+        for (x1, y1, z1) in [("1", "0", "0"), ("0", "1", "0"), ("0", "0", "1")] {
+            assert!(
+                P::g2_from_strings_projective(&x, x1, &y, y1, &z, z1, CheckElement::Yes).is_err()
+            );
+        }
+        // the point at infinity
+        let json = P::serialize_g2(
+            &<P as Pairing>::G2Affine::zero(),
+            serde_json::value::Serializer,
+        )
+        .unwrap();
+        assert!(P::deserialize_g2_element(json).unwrap().is_zero());
+    }
+}
@@ -0,0 +1,320 @@
+//! Compression of [SerializeableSharedRep3Witness]es. Compressed witnesses are shared seeded, their remaining
+//! field elements are deduplicated, and the result is framed with zstd.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use ark_ff::PrimeField;
+use eyre::Context;
+use mpc_core::protocols::rep3::{
+    Rep3PrimeFieldShare, Rep3ShareVecType, ReplicatedSeedType, SeededType,
+};
+use rand::{CryptoRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{SerializeableSharedRep3Witness, SHARE_FILE_VERSION};
+
+/// The compression used when sharing a witness with [SerializeableSharedRep3Witness::share_rep3].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompressionLevel {
+    /// The shares are stored fully expanded.
+    #[default]
+    None,
+    /// The shares are compressed using seeds.
+    Seeded,
+    /// The shares are compressed using seeds, the remaining field elements are deduplicated, and the result is
+    /// framed with zstd at the given level.
+    Zstd(i32),
+}
+
+impl CompressionLevel {
+    /// Returns `true` if the shares are compressed using seeds.
+    pub fn is_seeded(&self) -> bool {
+        !matches!(self, CompressionLevel::None)
+    }
+
+    /// Returns the zstd level, if the shares are framed with zstd.
+    pub fn zstd_level(&self) -> Option<i32> {
+        match self {
+            CompressionLevel::Zstd(level) => Some(*level),
+            _ => None,
+        }
+    }
+}
+
+/// A deduplicated vector of field elements. Every element is replaced by an index into a dictionary of distinct elements.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+struct DedupVec<F: PrimeField> {
+    #[serde(
+        serialize_with = "mpc_core::ark_se",
+        deserialize_with = "mpc_core::ark_de"
+    )]
+    dictionary: Vec<F>,
+    indices: Vec<u32>,
+}
+
+impl<F: PrimeField> DedupVec<F> {
+    fn new(values: &[F]) -> eyre::Result<Self> {
+        let mut positions = HashMap::new();
+        let mut dictionary = Vec::new();
+        let mut indices = Vec::with_capacity(values.len());
+        for value in values {
+            let index = *positions.entry(*value).or_insert_with(|| {
+                dictionary.push(*value);
+                dictionary.len() - 1
+            });
+            indices.push(u32::try_from(index).context("too many distinct values to deduplicate")?);
+        }
+        Ok(Self {
+            dictionary,
+            indices,
+        })
+    }
+
+    fn expand(self) -> eyre::Result<Vec<F>> {
+        self.indices
+            .into_iter()
+            .map(|index| {
+                self.dictionary
+                    .get(index as usize)
+                    .copied()
+                    .ok_or_else(|| eyre::eyre!("index {index} out of bounds of the dictionary"))
+            })
+            .collect()
+    }
+}
+
+/// Which share vector the values of a [CompressedRep3Witness] belong to.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+enum CompressedShares<U: Rng + SeedableRng + CryptoRng>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    /// Replicated shares, stored as interleaved `a`, `b` values.
+    Replicated,
+    /// Seeded replicated shares, where `a` and `b` are either seeds or stored in the values.
+    SeededReplicated(CompressedSeed<U>, CompressedSeed<U>),
+    /// Additive shares.
+    Additive,
+    /// Seeded additive shares, either a seed or stored in the values.
+    SeededAdditive(CompressedSeed<U>),
+}
+
+/// Either a seed with its length or a marker that the shares are stored in the values of the [CompressedRep3Witness].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+enum CompressedSeed<U: Rng + SeedableRng + CryptoRng>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    Values,
+    Seed(U::Seed, usize),
+}
+
+impl<U: Rng + SeedableRng + CryptoRng> CompressedSeed<U>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    fn compress<F: PrimeField>(seeded: SeededType<Vec<F>, U>, values: &mut Vec<F>) -> Self {
+        match seeded {
+            SeededType::Shares(shares) => {
+                values.extend(shares);
+                CompressedSeed::Values
+            }
+            SeededType::Seed(seed, len, _) => CompressedSeed::Seed(seed, len),
+        }
+    }
+
+    fn decompress<F: PrimeField>(self, values: &mut Vec<F>) -> SeededType<Vec<F>, U> {
+        match self {
+            CompressedSeed::Values => SeededType::Shares(std::mem::take(values)),
+            CompressedSeed::Seed(seed, len) => SeededType::Seed(seed, len, PhantomData),
+        }
+    }
+}
+
+/// The on-disk representation of a compressed [SerializeableSharedRep3Witness].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+struct CompressedRep3Witness<F: PrimeField, U: Rng + SeedableRng + CryptoRng>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    num_public_inputs: usize,
+    values: DedupVec<F>,
+    #[serde(
+        default,
+        serialize_with = "mpc_core::ark_se",
+        deserialize_with = "mpc_core::ark_de"
+    )]
+    shared_public_inputs: Vec<Rep3PrimeFieldShare<F>>,
+    shares: CompressedShares<U>,
+}
+
+/// A [CompressedRep3Witness] of a version 1 share file, which did not contain shared public inputs.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct CompressedRep3WitnessV1<F: PrimeField, U: Rng + SeedableRng + CryptoRng>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    num_public_inputs: usize,
+    values: DedupVec<F>,
+    shares: CompressedShares<U>,
+}
+
+impl<F: PrimeField, U: Rng + SeedableRng + CryptoRng> SerializeableSharedRep3Witness<F, U>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    /// Compresses the witness by deduplicating its field elements and framing the result with zstd at the given level.
+    /// Seeds are kept as they are, so the witness should already be shared with seeds for maximal compression.
+    pub fn compress(self, level: i32) -> eyre::Result<Vec<u8>> {
+        let num_public_inputs = self.public_inputs.len();
+        let mut values = self.public_inputs;
+        let shares = match self.witness {
+            Rep3ShareVecType::Replicated(vec) => {
+                values.extend(vec.into_iter().flat_map(|share| [share.a, share.b]));
+                CompressedShares::Replicated
+            }
+            Rep3ShareVecType::SeededReplicated(seeded) => {
+                let a = CompressedSeed::compress(seeded.a, &mut values);
+                let b = CompressedSeed::compress(seeded.b, &mut values);
+                CompressedShares::SeededReplicated(a, b)
+            }
+            Rep3ShareVecType::Additive(vec) => {
+                values.extend(vec);
+                CompressedShares::Additive
+            }
+            Rep3ShareVecType::SeededAdditive(seeded) => {
+                CompressedShares::SeededAdditive(CompressedSeed::compress(seeded, &mut values))
+            }
+        };
+        let compressed = CompressedRep3Witness::<F, U> {
+            num_public_inputs,
+            values: DedupVec::new(&values)?,
+            shared_public_inputs: self.shared_public_inputs,
+            shares,
+        };
+        let bytes = bincode::serialize(&compressed).context("while serializing witness")?;
+        zstd::encode_all(bytes.as_slice(), level).context("while compressing witness")
+    }
+
+    /// Decompresses a witness previously compressed with [SerializeableSharedRep3Witness::compress].
+    pub fn decompress(bytes: &[u8]) -> eyre::Result<Self> {
+        Self::decompress_with_version(bytes, SHARE_FILE_VERSION)
+    }
+
+    /// Decompresses a witness of a share file with the given version.
+    pub(crate) fn decompress_with_version(bytes: &[u8], version: u16) -> eyre::Result<Self> {
+        let bytes = zstd::decode_all(bytes).context("while decompressing witness")?;
+        let compressed: CompressedRep3Witness<F, U> = if version == 1 {
+            let v1: CompressedRep3WitnessV1<F, U> =
+                bincode::deserialize(&bytes).context("while deserializing witness")?;
+            CompressedRep3Witness {
+                num_public_inputs: v1.num_public_inputs,
+                values: v1.values,
+                shared_public_inputs: Vec::new(),
+                shares: v1.shares,
+            }
+        } else {
+            bincode::deserialize(&bytes).context("while deserializing witness")?
+        };
+        let mut values = compressed.values.expand()?;
+        if values.len() < compressed.num_public_inputs {
+            eyre::bail!("compressed witness contains less values than public inputs");
+        }
+        let mut rest = values.split_off(compressed.num_public_inputs);
+        let public_inputs = values;
+        let witness = match compressed.shares {
+            CompressedShares::Replicated => {
+                if rest.len() % 2 != 0 {
+                    eyre::bail!("compressed replicated witness has an odd number of values");
+                }
+                Rep3ShareVecType::Replicated(
+                    rest.chunks_exact(2)
+                        .map(|ab| Rep3PrimeFieldShare::new(ab[0], ab[1]))
+                        .collect(),
+                )
+            }
+            CompressedShares::SeededReplicated(a, b) => {
+                // if both a and b are stored as values, they are stored one after the other
+                let mut rest_b = match (&a, &b) {
+                    (CompressedSeed::Values, CompressedSeed::Values) => {
+                        rest.split_off(rest.len() / 2)
+                    }
+                    (_, CompressedSeed::Values) => std::mem::take(&mut rest),
+                    _ => Vec::new(),
+                };
+                let a = a.decompress(&mut rest);
+                let b = b.decompress(&mut rest_b);
+                Rep3ShareVecType::SeededReplicated(ReplicatedSeedType { a, b })
+            }
+            CompressedShares::Additive => Rep3ShareVecType::Additive(rest),
+            CompressedShares::SeededAdditive(seeded) => {
+                Rep3ShareVecType::SeededAdditive(seeded.decompress(&mut rest))
+            }
+        };
+        Ok(Self {
+            public_inputs,
+            shared_public_inputs: compressed.shared_public_inputs,
+            witness,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use circom_types::Witness;
+
+    use super::*;
+
+    type SeedRng = rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn compress_decompress_roundtrip() {
+        let mut rng = rand::thread_rng();
+        // many repeated values, as they appear in real witnesses
+        let values = (0..100)
+            .map(|i| {
+                if i % 3 == 0 {
+                    ark_bn254::Fr::rand(&mut rng)
+                } else {
+                    ark_bn254::Fr::from(i % 2)
+                }
+            })
+            .collect::<Vec<_>>();
+        for compression in [
+            CompressionLevel::None,
+            CompressionLevel::Seeded,
+            CompressionLevel::Zstd(3),
+        ] {
+            for additive in [false, true] {
+                let shares = SerializeableSharedRep3Witness::<_, SeedRng>::share_rep3(
+                    Witness {
+                        values: values.clone(),
+                    },
+                    10,
+                    &mut rng,
+                    compression,
+                    additive,
+                    false,
+                );
+                for share in shares {
+                    let should = bincode::serialize(&share).unwrap();
+                    let compressed = share.compress(3).unwrap();
+                    let decompressed =
+                        SerializeableSharedRep3Witness::<ark_bn254::Fr, SeedRng>::decompress(
+                            &compressed,
+                        )
+                        .unwrap();
+                    let is = bincode::serialize(&decompressed).unwrap();
+                    assert_eq!(is, should);
+                }
+            }
+        }
+    }
+}
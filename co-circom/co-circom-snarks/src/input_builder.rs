@@ -0,0 +1,183 @@
+//! A builder to programmatically construct the per-party [SerializeableSharedRep3Input]s of a data provider.
+
+use std::collections::BTreeMap;
+
+use ark_ff::PrimeField;
+use rand::{distributions::Standard, prelude::Distribution, CryptoRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::SerializeableSharedRep3Input;
+
+/// The sharing used for a secret input.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Sharing {
+    /// Fully expanded replicated shares.
+    #[default]
+    Replicated,
+    /// Replicated shares compressed using seeds.
+    SeededReplicated,
+    /// Fully expanded additive shares, which are reshared before the witness extension.
+    Additive,
+    /// Additive shares compressed using seeds, which are reshared before the witness extension.
+    SeededAdditive,
+    /// 3-party Shamir shares of degree 1, which are translated to Rep3 shares before the witness extension.
+    Shamir,
+}
+
+/// A builder for the three [SerializeableSharedRep3Input]s of a data provider.
+///
+/// ```ignore
+/// let [share0, share1, share2] = Rep3InputBuilder::new()
+///     .public("a", vals)
+///     .secret("b", vals, Sharing::SeededReplicated)
+///     .partial("m", indexed_vals)
+///     .build::<SeedRng, _>(&mut rng)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rep3InputBuilder<F: PrimeField> {
+    public_inputs: BTreeMap<String, Vec<F>>,
+    secret_inputs: BTreeMap<String, (Vec<F>, Sharing)>,
+    partial_inputs: BTreeMap<String, Vec<Option<F>>>,
+    duplicates: Vec<String>,
+}
+
+impl<F: PrimeField> Default for Rep3InputBuilder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: PrimeField> Rep3InputBuilder<F> {
+    /// Creates a new empty builder.
+    pub fn new() -> Self {
+        Self {
+            public_inputs: BTreeMap::new(),
+            secret_inputs: BTreeMap::new(),
+            partial_inputs: BTreeMap::new(),
+            duplicates: Vec::new(),
+        }
+    }
+
+    fn check_name(&mut self, name: &str) {
+        if self.public_inputs.contains_key(name)
+            || self.secret_inputs.contains_key(name)
+            || self.partial_inputs.contains_key(name)
+        {
+            self.duplicates.push(name.to_owned());
+        }
+    }
+
+    /// Adds a public input, which is stored in plain in all three files.
+    pub fn public(mut self, name: impl Into<String>, values: Vec<F>) -> Self {
+        let name = name.into();
+        self.check_name(&name);
+        self.public_inputs.insert(name, values);
+        self
+    }
+
+    /// Adds a secret input, which is shared using the given [Sharing].
+    pub fn secret(mut self, name: impl Into<String>, values: Vec<F>, sharing: Sharing) -> Self {
+        let name = name.into();
+        self.check_name(&name);
+        self.secret_inputs.insert(name, (values, sharing));
+        self
+    }
+
+    /// Adds a partially known secret input. The i-th entry is the value at index i, or `None` if the element is
+    /// provided by another data provider and merged later.
+    pub fn partial(mut self, name: impl Into<String>, values: Vec<Option<F>>) -> Self {
+        let name = name.into();
+        self.check_name(&name);
+        self.partial_inputs.insert(name, values);
+        self
+    }
+
+    /// Shares all inputs and returns the [SerializeableSharedRep3Input] of each party.
+    pub fn build<U, R>(self, rng: &mut R) -> eyre::Result<[SerializeableSharedRep3Input<F, U>; 3]>
+    where
+        U: Rng + SeedableRng + CryptoRng,
+        U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+        Standard: Distribution<U::Seed>,
+        R: Rng + CryptoRng,
+    {
+        if !self.duplicates.is_empty() {
+            eyre::bail!(
+                "inputs added multiple times: {}",
+                self.duplicates.join(", ")
+            );
+        }
+        let mut shares = [
+            SerializeableSharedRep3Input::<F, U>::default(),
+            SerializeableSharedRep3Input::<F, U>::default(),
+            SerializeableSharedRep3Input::<F, U>::default(),
+        ];
+        for (name, values) in self.public_inputs {
+            for share in shares.iter_mut() {
+                share.public_inputs.insert(name.clone(), values.clone());
+            }
+        }
+        for (name, (values, sharing)) in self.secret_inputs {
+            if sharing == Sharing::Shamir {
+                let shamir_shares =
+                    SerializeableSharedRep3Input::<F, U>::share_shamir(&values, 1, rng)?;
+                for (share, shamir_share) in shares.iter_mut().zip(shamir_shares) {
+                    share
+                        .shamir_shared_inputs
+                        .insert(name.clone(), shamir_share);
+                }
+                continue;
+            }
+            let seeded = matches!(sharing, Sharing::SeededReplicated | Sharing::SeededAdditive);
+            let additive = matches!(sharing, Sharing::Additive | Sharing::SeededAdditive);
+            let rep3_shares =
+                SerializeableSharedRep3Input::<F, U>::share_rep3(&values, rng, seeded, additive);
+            for (share, rep3_share) in shares.iter_mut().zip(rep3_shares) {
+                share.shared_inputs.insert(name.clone(), rep3_share);
+            }
+        }
+        for (name, values) in self.partial_inputs {
+            let maybe_shares =
+                SerializeableSharedRep3Input::<F, U>::maybe_share_rep3(&values, rng, false);
+            for (share, maybe_share) in shares.iter_mut().zip(maybe_shares) {
+                share.maybe_shared_inputs.insert(name.clone(), maybe_share);
+            }
+        }
+        Ok(shares)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use mpc_core::protocols::rep3::Rep3ShareVecType;
+
+    type SeedRng = rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn build_input_shares() {
+        let mut rng = rand::thread_rng();
+        let [share0, share1, share2] = Rep3InputBuilder::new()
+            .public("a", vec![Fr::from(1u64)])
+            .secret("b", vec![Fr::from(2u64); 3], Sharing::SeededReplicated)
+            .secret("c", vec![Fr::from(3u64)], Sharing::Shamir)
+            .partial("m", vec![Some(Fr::from(4u64)), None])
+            .build::<SeedRng, _>(&mut rng)
+            .unwrap();
+        for share in [&share0, &share1, &share2] {
+            assert_eq!(share.public_inputs["a"], vec![Fr::from(1u64)]);
+            assert!(matches!(
+                share.shared_inputs["b"],
+                Rep3ShareVecType::SeededReplicated(_)
+            ));
+            assert_eq!(share.shamir_shared_inputs["c"].len(), 1);
+            assert!(share.maybe_shared_inputs.contains_key("m"));
+        }
+
+        let duplicate = Rep3InputBuilder::new()
+            .public("a", vec![Fr::from(1u64)])
+            .secret("a", vec![Fr::from(1u64)], Sharing::Replicated)
+            .build::<SeedRng, _>(&mut rng);
+        assert!(duplicate.is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! Commitments binding the input of a data provider across multiple co-proving sessions. The data provider commits to
+//! its input once with [InputCommitment::commit] and publishes the commitment. In every session, it shares the
+//! commitment randomness together with its input (see [CommittedRep3Input]), and the parties check with
+//! [InputCommitment::verify_shared] that the shared input opens the published commitment.
+
+use std::collections::BTreeMap;
+
+use ark_ec::CurveGroup;
+use ark_ff::UniformRand;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mpc_core::protocols::rep3::{
+    self,
+    network::{IoContext, Rep3Network},
+    pointshare, Rep3PrimeFieldShare,
+};
+use rand::{CryptoRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+
+use crate::{
+    sharing_proof::{commit, pedersen_generators},
+    SerializeableSharedRep3Input, SharedInput,
+};
+
+/// A Pedersen commitment to the private inputs of a data provider.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct InputCommitment<C: CurveGroup> {
+    /// The names of the committed inputs, in the order they are committed.
+    pub names: Vec<String>,
+    /// The Pedersen commitment to the concatenation of the committed inputs.
+    pub commitment: C,
+}
+
+impl<C: CurveGroup> InputCommitment<C> {
+    /// Commits to the provided inputs. Returns the commitment and the commitment randomness, which the data
+    /// provider has to keep to share it in every session.
+    pub fn commit<R: Rng + CryptoRng>(
+        inputs: &BTreeMap<String, Vec<C::ScalarField>>,
+        rng: &mut R,
+    ) -> (Self, C::ScalarField) {
+        let values = inputs.values().flatten().copied().collect::<Vec<_>>();
+        let (generators, h) = pedersen_generators::<C>(values.len());
+        let randomness = C::ScalarField::rand(rng);
+        let commitment = Self {
+            names: inputs.keys().cloned().collect(),
+            commitment: commit(&values, randomness, &generators, &h),
+        };
+        (commitment, randomness)
+    }
+
+    /// Checks in plain that the provided inputs and randomness open the commitment.
+    pub fn verify_opening(
+        &self,
+        inputs: &BTreeMap<String, Vec<C::ScalarField>>,
+        randomness: C::ScalarField,
+    ) -> bool {
+        let values = self
+            .names
+            .iter()
+            .filter_map(|name| inputs.get(name))
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        let (generators, h) = pedersen_generators::<C>(values.len());
+        self.names.len() == inputs.len()
+            && commit::<C>(&values, randomness, &generators, &h) == self.commitment
+    }
+
+    /// Checks in MPC that the committed inputs in the provided [SharedInput] together with the shared randomness
+    /// open the commitment. The [SharedInput] may contain further inputs, e.g., of other data providers.
+    /// The parties additionally have to make sure that they use the same commitment, e.g., by comparing their
+    /// [InputCommitment::digest]s.
+    pub fn verify_shared<N: Rep3Network>(
+        &self,
+        input: &SharedInput<C::ScalarField, Rep3PrimeFieldShare<C::ScalarField>>,
+        randomness: Rep3PrimeFieldShare<C::ScalarField>,
+        io_context: &mut IoContext<N>,
+    ) -> eyre::Result<()> {
+        let mut values = Vec::new();
+        for name in self.names.iter() {
+            let shares = input
+                .shared_inputs
+                .get(name)
+                .ok_or_else(|| eyre::eyre!("committed input \"{name}\" is not a shared input"))?;
+            values.extend_from_slice(shares);
+        }
+        let (generators, h) = pedersen_generators::<C>(values.len());
+        if !pointshare::verify_pedersen_commitment(
+            &values,
+            randomness,
+            &generators,
+            &h,
+            &self.commitment,
+            io_context,
+        )? {
+            eyre::bail!("shared input does not match the input commitment");
+        }
+        Ok(())
+    }
+
+    /// Returns a digest of the commitment, which can be compared across parties and sessions.
+    pub fn digest(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.serialize_compressed(&mut bytes)
+            .expect("can serialize into vec");
+        Sha3_256::digest(&bytes).to_vec()
+    }
+}
+
+/// A [SerializeableSharedRep3Input] stored together with the [InputCommitment] to the input and a share of the
+/// commitment randomness.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CommittedRep3Input<C: CurveGroup, U: Rng + SeedableRng + CryptoRng>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    /// The shared input.
+    pub input: SerializeableSharedRep3Input<C::ScalarField, U>,
+    /// The commitment to the input.
+    #[serde(
+        serialize_with = "mpc_core::ark_se",
+        deserialize_with = "mpc_core::ark_de"
+    )]
+    pub commitment: InputCommitment<C>,
+    /// The share of the commitment randomness.
+    #[serde(
+        serialize_with = "mpc_core::ark_se",
+        deserialize_with = "mpc_core::ark_de"
+    )]
+    pub randomness: Rep3PrimeFieldShare<C::ScalarField>,
+}
+
+impl<C: CurveGroup, U: Rng + SeedableRng + CryptoRng> CommittedRep3Input<C, U>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    /// Attaches the commitment and a fresh sharing of the commitment randomness to the shares of the input.
+    pub fn attach<R: Rng + CryptoRng>(
+        shares: [SerializeableSharedRep3Input<C::ScalarField, U>; 3],
+        commitment: InputCommitment<C>,
+        randomness: C::ScalarField,
+        rng: &mut R,
+    ) -> [Self; 3] {
+        let mut randomness = rep3::share_field_element(randomness, rng).into_iter();
+        shares.map(|input| Self {
+            input,
+            commitment: commitment.clone(),
+            randomness: randomness.next().expect("three shares"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commit_and_open() {
+        let mut rng = rand::thread_rng();
+        let inputs = BTreeMap::from([
+            ("a".to_owned(), vec![ark_bn254::Fr::from(1u64)]),
+            (
+                "b".to_owned(),
+                vec![ark_bn254::Fr::from(2u64), ark_bn254::Fr::from(3u64)],
+            ),
+        ]);
+        let (commitment, randomness) =
+            InputCommitment::<ark_bn254::G1Projective>::commit(&inputs, &mut rng);
+        assert!(commitment.verify_opening(&inputs, randomness));
+
+        let mut other = inputs.clone();
+        other.get_mut("b").unwrap()[1] += ark_bn254::Fr::from(1u64);
+        assert!(!commitment.verify_opening(&other, randomness));
+    }
+}
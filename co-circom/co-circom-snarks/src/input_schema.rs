@@ -0,0 +1,105 @@
+//! The schema of the inputs of a circuit, used to validate inputs before the witness extension.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Describes a single input signal of the main component of a circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignalSchema {
+    /// The number of field elements of the signal. For arrays, this is the flattened length.
+    pub size: usize,
+    /// Whether the signal is a public input of the circuit.
+    pub public: bool,
+}
+
+/// The schema of the inputs of a circuit, mapping the names of the input signals to their [SignalSchema].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputSchema {
+    /// The input signals of the main component.
+    pub signals: BTreeMap<String, SignalSchema>,
+}
+
+impl InputSchema {
+    /// Creates a new [InputSchema] from a list of (name, size) pairs and the names of the public inputs.
+    pub fn new(
+        signals: impl IntoIterator<Item = (String, usize)>,
+        public_inputs: &[String],
+    ) -> Self {
+        let signals = signals
+            .into_iter()
+            .map(|(name, size)| {
+                let public = public_inputs.contains(&name);
+                (name, SignalSchema { size, public })
+            })
+            .collect();
+        Self { signals }
+    }
+
+    /// Returns the [SignalSchema] of the signal with the given name, if it exists.
+    pub fn get(&self, name: &str) -> Option<&SignalSchema> {
+        self.signals.get(name)
+    }
+
+    /// Returns `true` if the signal with the given name is a public input of the circuit.
+    pub fn is_public(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|signal| signal.public)
+    }
+
+    /// Checks that the circuit has an input signal with the given name and that it consists of `len` elements.
+    pub fn check_signal(&self, name: &str, len: usize) -> eyre::Result<&SignalSchema> {
+        let signal = self
+            .get(name)
+            .ok_or_else(|| eyre::eyre!("circuit has no input signal \"{name}\""))?;
+        if signal.size != len {
+            eyre::bail!(
+                "for input \"{name}\" expected {} elements, got {len}",
+                signal.size
+            );
+        }
+        Ok(signal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SharedInput;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn validate_shared_input() {
+        let schema = InputSchema::new(
+            [("a".to_owned(), 1), ("b".to_owned(), 3)],
+            &["a".to_owned()],
+        );
+        assert!(schema.is_public("a"));
+        assert!(!schema.is_public("b"));
+
+        let mut input = SharedInput::<Fr, Fr>::default();
+        input.add_public_input("a".to_owned(), vec![Fr::from(1u64)]);
+        input.add_shared_input("b".to_owned(), vec![Fr::from(2u64); 3]);
+        input.validate_against(&schema).unwrap();
+
+        // wrong array length
+        let mut wrong_len = input.clone();
+        wrong_len.add_shared_input("b".to_owned(), vec![Fr::from(2u64); 2]);
+        assert!(wrong_len.validate_against(&schema).is_err());
+
+        // private signal marked public
+        let mut wrong_public = SharedInput::<Fr, Fr>::default();
+        wrong_public.add_public_input("a".to_owned(), vec![Fr::from(1u64)]);
+        wrong_public.add_public_input("b".to_owned(), vec![Fr::from(2u64); 3]);
+        assert!(wrong_public.validate_against(&schema).is_err());
+
+        // unknown name
+        let mut unknown = input.clone();
+        unknown.add_shared_input("c".to_owned(), vec![Fr::from(2u64)]);
+        assert!(unknown.validate_against(&schema).is_err());
+
+        // missing signal
+        let mut missing = SharedInput::<Fr, Fr>::default();
+        missing.add_public_input("a".to_owned(), vec![Fr::from(1u64)]);
+        assert!(missing.validate_against(&schema).is_err());
+    }
+}
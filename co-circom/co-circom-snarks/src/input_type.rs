@@ -0,0 +1,118 @@
+//! Typed descriptors for structured inputs. Since circom 2.2, input signals can be buses, which are provided as
+//! (nested) JSON objects but flatten to multiple field elements. An [InputType] describes the structure of such an
+//! input, so that it is flattened in the same order on all parties.
+
+use serde::{Deserialize, Serialize};
+
+/// The type of an input signal of the main component.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InputType {
+    /// A single field element.
+    Field,
+    /// An array of `len` elements of the same type.
+    Array {
+        /// The number of elements.
+        len: usize,
+        /// The type of the elements.
+        element: Box<InputType>,
+    },
+    /// A bus (or struct) with named fields, flattened in the order of its fields.
+    Struct {
+        /// The fields in declaration order.
+        fields: Vec<InputField>,
+    },
+}
+
+/// A named field of an [InputType::Struct].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputField {
+    /// The name of the field.
+    pub name: String,
+    /// The type of the field.
+    #[serde(rename = "type")]
+    pub ty: InputType,
+}
+
+impl InputField {
+    /// Creates a new [InputField].
+    pub fn new(name: impl Into<String>, ty: InputType) -> Self {
+        Self {
+            name: name.into(),
+            ty,
+        }
+    }
+}
+
+impl InputType {
+    /// Returns an [InputType::Array] of `len` elements of type `element`.
+    pub fn array(len: usize, element: InputType) -> Self {
+        Self::Array {
+            len,
+            element: Box::new(element),
+        }
+    }
+
+    /// Returns the number of field elements an input of this type flattens to.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Field => 1,
+            Self::Array { len, element } => len * element.size(),
+            Self::Struct { fields } => fields.iter().map(|field| field.ty.size()).sum(),
+        }
+    }
+
+    /// Returns the names of the flattened field elements of an input `name` of this type, in flattening order,
+    /// e.g., `p.x`, `p.y` for a bus `p` or `a[0].x`, `a[1].x` for an array of buses.
+    pub fn flattened_names(&self, name: &str) -> Vec<String> {
+        let mut names = Vec::with_capacity(self.size());
+        self.push_names(name.to_owned(), &mut names);
+        names
+    }
+
+    fn push_names(&self, prefix: String, names: &mut Vec<String>) {
+        match self {
+            Self::Field => names.push(prefix),
+            Self::Array { len, element } => {
+                for i in 0..*len {
+                    element.push_names(format!("{prefix}[{i}]"), names);
+                }
+            }
+            Self::Struct { fields } => {
+                for field in fields {
+                    field
+                        .ty
+                        .push_names(format!("{prefix}.{}", field.name), names);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten_bus_array() {
+        let point = InputType::Struct {
+            fields: vec![
+                InputField::new("y", InputType::Field),
+                InputField::new("x", InputType::array(2, InputType::Field)),
+            ],
+        };
+        let ty = InputType::array(2, point);
+        assert_eq!(ty.size(), 6);
+        assert_eq!(
+            ty.flattened_names("p"),
+            vec![
+                "p[0].y",
+                "p[0].x[0]",
+                "p[0].x[1]",
+                "p[1].y",
+                "p[1].x[0]",
+                "p[1].x[1]"
+            ]
+        );
+    }
+}
@@ -7,13 +7,42 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use circom_types::Witness;
 use mpc_core::protocols::{
     rep3::{self, MaybeRep3ShareVecType, Rep3PrimeFieldShare, Rep3ShareVecType},
+    rss::{self, RssPrimeFieldShare},
     shamir::{self, ShamirPrimeFieldShare},
+    twopc::{self, TwoPcPrimeFieldShare},
 };
 use rand::{distributions::Standard, prelude::Distribution, CryptoRng, Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
 
+mod compression;
+mod input_builder;
+mod input_commitment;
+mod input_schema;
+mod input_type;
+mod output_policy;
+mod result_receiver;
+mod share_metadata;
+mod sharing_proof;
+mod witness_check;
+mod witness_handoff;
+
+pub use compression::CompressionLevel;
+pub use input_builder::{Rep3InputBuilder, Sharing};
+pub use input_commitment::{CommittedRep3Input, InputCommitment};
+pub use input_schema::{InputSchema, SignalSchema};
+pub use input_type::{InputField, InputType};
+pub use output_policy::{OutputDisclosure, OutputPolicy};
+pub use result_receiver::ResultReceiverKey;
+pub use share_metadata::{
+    ShareCurve, ShareKind, ShareMetadata, ShareProtocol, SHARE_FILE_MAGIC, SHARE_FILE_VERSION,
+};
+pub use sharing_proof::{share_rep3_with_proof, SharingProof};
+pub use witness_check::{UnsatisfiedConstraintsError, WitnessCheck};
+pub use witness_handoff::ShamirWitnessHandoff;
+
 /// This type represents the serialized version of a Rep3 witness. Its share can be either additive or replicated, and in both cases also compressed.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(bound = "")]
@@ -28,6 +57,14 @@ where
         deserialize_with = "mpc_core::ark_de"
     )]
     pub public_inputs: Vec<F>,
+    /// The secret-shared public inputs, if the public inputs are hidden during witness extension and proving.
+    /// They directly follow the entries of `public_inputs` and are only revealed once the proof is assembled.
+    #[serde(
+        default,
+        serialize_with = "mpc_core::ark_se",
+        deserialize_with = "mpc_core::ark_de"
+    )]
+    pub shared_public_inputs: Vec<Rep3PrimeFieldShare<F>>,
     /// The secret-shared witness elements.
     pub witness: Rep3ShareVecType<F, U>,
 }
@@ -40,9 +77,32 @@ where
     pub fn from_shared_witness(inp: SharedWitness<F, Rep3PrimeFieldShare<F>>) -> Self {
         Self {
             public_inputs: inp.public_inputs,
+            shared_public_inputs: inp.shared_public_inputs,
             witness: Rep3ShareVecType::Replicated(inp.witness),
         }
     }
+
+    /// Transforms the serializable version back into a shared witness, expanding compressed replicated shares.
+    /// This is the inverse of [SerializeableSharedRep3Witness::from_shared_witness]. Additive shares cannot be
+    /// expanded locally, in this case [ExpandWitnessError::ReshareRequired] is returned.
+    pub fn into_shared_witness(
+        self,
+    ) -> Result<SharedWitness<F, Rep3PrimeFieldShare<F>>, ExpandWitnessError> {
+        let witness = match self.witness {
+            Rep3ShareVecType::Replicated(vec) => vec,
+            Rep3ShareVecType::SeededReplicated(replicated_seed_type) => {
+                replicated_seed_type.expand_vec()?
+            }
+            Rep3ShareVecType::Additive(_) | Rep3ShareVecType::SeededAdditive(_) => {
+                return Err(ExpandWitnessError::ReshareRequired);
+            }
+        };
+        Ok(SharedWitness {
+            public_inputs: self.public_inputs,
+            shared_public_inputs: self.shared_public_inputs,
+            witness,
+        })
+    }
 }
 
 //TODO THE SECRETSHARED TRAIT IS REALLY BAD. WE DO WANT SOMETHING ELSE!
@@ -59,6 +119,14 @@ where
     /// The public inputs (which are the outputs of the circom circuit).
     /// This also includes the constant 1 at position 0.
     pub public_inputs: Vec<F>,
+    #[serde(
+        default,
+        serialize_with = "mpc_core::ark_se",
+        deserialize_with = "mpc_core::ark_de"
+    )]
+    /// The secret-shared public inputs, if the public inputs are hidden during witness extension and proving.
+    /// They directly follow the entries of `public_inputs` and are only revealed once the proof is assembled.
+    pub shared_public_inputs: Vec<S>,
     #[serde(
         serialize_with = "mpc_core::ark_se",
         deserialize_with = "mpc_core::ark_de"
@@ -88,6 +156,19 @@ where
     /// This is a BTreeMap because it implements Canonical(De)Serialize.
     #[serde(default)]
     pub maybe_shared_inputs: BTreeMap<String, MaybeRep3ShareVecType<F>>,
+    /// A map from variable names to 3-party Shamir shares of the field elements. These are translated to Rep3
+    /// shares before the witness extension.
+    /// This is a BTreeMap because it implements Canonical(De)Serialize.
+    #[serde(
+        default,
+        serialize_with = "mpc_core::ark_se",
+        deserialize_with = "mpc_core::ark_de"
+    )]
+    pub shamir_shared_inputs: BTreeMap<String, Vec<ShamirPrimeFieldShare<F>>>,
+    /// A map from variable names to the [InputType]s of structured inputs, e.g., buses. Inputs without an entry are
+    /// plain field elements or arrays thereof.
+    #[serde(default)]
+    pub input_types: BTreeMap<String, InputType>,
 }
 
 impl<F: PrimeField, U: Rng + SeedableRng + CryptoRng> Default for SerializeableSharedRep3Input<F, U>
@@ -99,6 +180,8 @@ where
             public_inputs: BTreeMap::new(),
             shared_inputs: BTreeMap::new(),
             maybe_shared_inputs: BTreeMap::new(),
+            shamir_shared_inputs: BTreeMap::new(),
+            input_types: BTreeMap::new(),
         }
     }
 }
@@ -170,11 +253,69 @@ where
         }
     }
 
+    /// Shares a given input with 3-party Shamir secret sharing using a polynomial of the given degree. The degree must
+    /// be at most 2, such that the shares can be translated to Rep3 shares before the witness extension, otherwise an
+    /// error is returned.
+    pub fn share_shamir<R: Rng + CryptoRng>(
+        input: &[F],
+        degree: usize,
+        rng: &mut R,
+    ) -> eyre::Result<[Vec<ShamirPrimeFieldShare<F>>; 3]> {
+        if degree > 2 {
+            eyre::bail!("degree must be at most 2 for 3 parties, but is {degree}");
+        }
+        let shares = shamir::share_field_elements(input, degree, 3, rng);
+        Ok(shares
+            .try_into()
+            .expect("we have three shares for three parties"))
+    }
+
+    /// Like [Self::share_rep3], but derives all randomness from the provided seed with ChaCha20, so the same seed always
+    /// produces the same shares. This is intended for reproducible test vectors and audits, never use it for real inputs.
+    pub fn share_rep3_deterministic(
+        input: &[F],
+        seed: [u8; 32],
+        seeded: bool,
+        additive: bool,
+    ) -> [Rep3ShareVecType<F, U>; 3] {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        Self::share_rep3(input, &mut rng, seeded, additive)
+    }
+
+    /// Like [Self::share_shamir], but derives all randomness from the provided seed with ChaCha20, so the same seed
+    /// always produces the same shares. This is intended for reproducible test vectors and audits, never use it for
+    /// real inputs.
+    pub fn share_shamir_deterministic(
+        input: &[F],
+        degree: usize,
+        seed: [u8; 32],
+    ) -> eyre::Result<[Vec<ShamirPrimeFieldShare<F>>; 3]> {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        Self::share_shamir(input, degree, &mut rng)
+    }
+
     /// Merges two [SerializeableSharedRep3Input]s into one, performing basic sanity checks.
     pub fn merge(self, other: Self) -> eyre::Result<Self> {
         let mut shared_inputs = self.shared_inputs;
         let maybe_shared_inputs = self.maybe_shared_inputs;
         let public_inputs = self.public_inputs;
+        let mut shamir_shared_inputs = self.shamir_shared_inputs;
+        let input_types = merge_input_types(self.input_types, other.input_types)?;
+
+        for (key, value) in other.shamir_shared_inputs {
+            if shamir_shared_inputs.contains_key(&key)
+                || shared_inputs.contains_key(&key)
+                || other.shared_inputs.contains_key(&key)
+            {
+                eyre::bail!("Input with name {} present in multiple input shares", key);
+            }
+            if public_inputs.contains_key(&key) || other.public_inputs.contains_key(&key) {
+                eyre::bail!(
+                    "Input name is once in shared inputs and once in public inputs: \"{key}\""
+                );
+            }
+            shamir_shared_inputs.insert(key, value);
+        }
 
         for (key, value) in other.public_inputs.iter() {
             if !public_inputs.contains_key(key) {
@@ -186,7 +327,7 @@ where
         }
 
         for (key, value) in other.shared_inputs {
-            if shared_inputs.contains_key(&key) {
+            if shared_inputs.contains_key(&key) || shamir_shared_inputs.contains_key(&key) {
                 eyre::bail!("Input with name {} present in multiple input shares", key);
             }
             if public_inputs.contains_key(&key) || other.public_inputs.contains_key(&key) {
@@ -260,10 +401,29 @@ where
             public_inputs,
             shared_inputs,
             maybe_shared_inputs: merged_maybe_shared_inputs,
+            shamir_shared_inputs,
+            input_types,
         })
     }
 }
 
+/// Merges the [InputType]s of two inputs. The same input may be described in both, but then the types must match.
+fn merge_input_types(
+    mut input_types: BTreeMap<String, InputType>,
+    other: BTreeMap<String, InputType>,
+) -> eyre::Result<BTreeMap<String, InputType>> {
+    for (key, ty) in other {
+        if input_types
+            .get(&key)
+            .is_some_and(|existing| *existing != ty)
+        {
+            eyre::bail!("Input \"{key}\" has different types in the input shares");
+        }
+        input_types.insert(key, ty);
+    }
+    Ok(input_types)
+}
+
 /// A shared input for a collaborative circom witness extension.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SharedInput<F: PrimeField, S>
@@ -284,6 +444,9 @@ where
     /// A map from variable names to the share of the field element.
     /// This is a BTreeMap because it implements Canonical(De)Serialize.
     pub shared_inputs: BTreeMap<String, Vec<S>>,
+    /// A map from variable names to the [InputType]s of structured inputs, e.g., buses.
+    #[serde(default)]
+    pub input_types: BTreeMap<String, InputType>,
 }
 
 /// We manually implement Clone here since it was not derived correctly and it added bounds on T, P which are not needed
@@ -294,6 +457,7 @@ where
     fn clone(&self) -> Self {
         Self {
             public_inputs: self.public_inputs.clone(),
+            shared_public_inputs: self.shared_public_inputs.clone(),
             witness: self.witness.clone(),
         }
     }
@@ -308,6 +472,7 @@ where
         Self {
             public_inputs: self.public_inputs.clone(),
             shared_inputs: self.shared_inputs.clone(),
+            input_types: self.input_types.clone(),
         }
     }
 }
@@ -320,10 +485,26 @@ where
         Self {
             public_inputs: BTreeMap::new(),
             shared_inputs: BTreeMap::new(),
+            input_types: BTreeMap::new(),
         }
     }
 }
 
+impl<F: PrimeField, S> SharedWitness<F, S>
+where
+    S: CanonicalSerialize + CanonicalDeserialize + Clone,
+{
+    /// Returns `true` if (some of) the public inputs are secret-shared.
+    pub fn has_hidden_public_inputs(&self) -> bool {
+        !self.shared_public_inputs.is_empty()
+    }
+
+    /// Returns the total number of public inputs, including the constant 1 at position 0 and the hidden public inputs.
+    pub fn num_public_inputs(&self) -> usize {
+        self.public_inputs.len() + self.shared_public_inputs.len()
+    }
+}
+
 impl<F: PrimeField, S> SharedInput<F, S>
 where
     S: CanonicalSerialize + CanonicalDeserialize + Clone,
@@ -338,34 +519,111 @@ where
         self.shared_inputs.insert(key, elements);
     }
 
+    /// Sets the [InputType] of a structured input with a given name.
+    pub fn set_input_type(&mut self, key: String, ty: InputType) {
+        self.input_types.insert(key, ty);
+    }
+
     /// Merges two [SharedInput]s into one, performing basic sanity checks.
     pub fn merge(self, other: Self) -> eyre::Result<Self> {
+        Self::check_mergeable(
+            |key| self.shared_inputs.contains_key(key),
+            &self.public_inputs,
+            &other,
+        )?;
         let mut shared_inputs = self.shared_inputs;
-        let public_inputs = self.public_inputs;
-        for (key, value) in other.shared_inputs {
-            if shared_inputs.contains_key(&key) {
+        shared_inputs.extend(other.shared_inputs);
+        let input_types = merge_input_types(self.input_types, other.input_types)?;
+
+        Ok(Self {
+            shared_inputs,
+            public_inputs: self.public_inputs,
+            input_types,
+        })
+    }
+
+    /// Performs the sanity checks of [SharedInput::merge] over an arbitrary slice of inputs without consuming them,
+    /// i.e., checks that the inputs could be merged in the given order. All problems are reported together with the
+    /// index of the input they belong to.
+    pub fn check_no_overlap(inputs: &[Self]) -> eyre::Result<()> {
+        let Some((first, rest)) = inputs.split_first() else {
+            return Ok(());
+        };
+        let mut seen = first
+            .shared_inputs
+            .keys()
+            .map(String::as_str)
+            .collect::<BTreeSet<_>>();
+        let mut problems = Vec::new();
+        for (i, other) in rest.iter().enumerate() {
+            if let Err(err) =
+                Self::check_mergeable(|key| seen.contains(key), &first.public_inputs, other)
+            {
+                problems.push(format!("input {}: {err}", i + 1));
+            }
+            seen.extend(other.shared_inputs.keys().map(String::as_str));
+        }
+        if !problems.is_empty() {
+            eyre::bail!("{}", problems.join("\n"));
+        }
+        Ok(())
+    }
+
+    fn check_mergeable(
+        is_shared: impl Fn(&str) -> bool,
+        public_inputs: &BTreeMap<String, Vec<F>>,
+        other: &Self,
+    ) -> eyre::Result<()> {
+        for key in other.shared_inputs.keys() {
+            if is_shared(key) {
                 eyre::bail!("Input with name {} present in multiple input shares", key);
             }
-            if public_inputs.contains_key(&key) || other.public_inputs.contains_key(&key) {
+            if public_inputs.contains_key(key) || other.public_inputs.contains_key(key) {
                 eyre::bail!(
                     "Input name is once in shared inputs and once in public inputs: \"{key}\""
                 );
             }
-            shared_inputs.insert(key, value);
         }
-        for (key, value) in other.public_inputs {
-            if !public_inputs.contains_key(&key) {
+        for (key, value) in other.public_inputs.iter() {
+            if !public_inputs.contains_key(key) {
                 eyre::bail!("Public input \"{key}\" must be present in all files");
             }
-            if public_inputs.get(&key).expect("is there we checked") != &value {
+            if public_inputs.get(key).expect("is there we checked") != value {
                 eyre::bail!("Public input \"{key}\" must be same in all files");
             }
         }
+        Ok(())
+    }
 
-        Ok(Self {
-            shared_inputs,
-            public_inputs,
-        })
+    /// Validates the [SharedInput] against the [InputSchema] of the circuit. Checks that all input signals of the circuit
+    /// are present with the correct length, that no unknown signals are provided, and that exactly the public inputs
+    /// of the circuit are provided as public inputs.
+    pub fn validate_against(&self, schema: &InputSchema) -> eyre::Result<()> {
+        for (name, elements) in self.public_inputs.iter() {
+            if !schema.check_signal(name, elements.len())?.public {
+                eyre::bail!(
+                    "Input \"{name}\" is provided as public input, but is private in the circuit"
+                );
+            }
+        }
+        for (name, elements) in self.shared_inputs.iter() {
+            if schema.check_signal(name, elements.len())?.public {
+                eyre::bail!(
+                    "Input \"{name}\" is provided as shared input, but is public in the circuit"
+                );
+            }
+        }
+        for (name, ty) in self.input_types.iter() {
+            schema
+                .check_signal(name, ty.size())
+                .map_err(|err| eyre::eyre!("Type of input \"{name}\" does not match: {err}"))?;
+        }
+        for name in schema.signals.keys() {
+            if !self.public_inputs.contains_key(name) && !self.shared_inputs.contains_key(name) {
+                eyre::bail!("Cannot find signal \"{name}\" in provided input");
+            }
+        }
+        Ok(())
     }
 }
 
@@ -375,34 +633,74 @@ where
 
     Standard: Distribution<U::Seed>,
 {
-    /// Shares a given witness and public input vector using the Rep3 protocol.
+    /// Shares a given witness and public input vector using the Rep3 protocol. All compression levels except
+    /// [CompressionLevel::None] share with seeds, the zstd framing of [CompressionLevel::Zstd] is applied when the share
+    /// is written with [SerializeableSharedRep3Witness::compress]. If `hide_public_inputs` is set, all public inputs
+    /// except the constant 1 at position 0 are secret-shared as well.
     pub fn share_rep3<R: Rng + CryptoRng>(
         witness: Witness<F>,
         num_pub_inputs: usize,
         rng: &mut R,
-        seeded: bool,
+        compression: CompressionLevel,
         additive: bool,
+        hide_public_inputs: bool,
     ) -> [Self; 3] {
-        let public_inputs = &witness.values[..num_pub_inputs];
+        let num_revealed = if hide_public_inputs {
+            1
+        } else {
+            num_pub_inputs
+        };
+        let public_inputs = &witness.values[..num_revealed];
+        let [shared_public1, shared_public2, shared_public3] =
+            rep3::share_field_elements(&witness.values[num_revealed..num_pub_inputs], rng);
         let witness = &witness.values[num_pub_inputs..];
 
-        let [share1, share2, share3] =
-            SerializeableSharedRep3Input::share_rep3(witness, rng, seeded, additive);
+        let [share1, share2, share3] = SerializeableSharedRep3Input::share_rep3(
+            witness,
+            rng,
+            compression.is_seeded(),
+            additive,
+        );
 
         let witness1 = Self {
             public_inputs: public_inputs.to_vec(),
+            shared_public_inputs: shared_public1,
             witness: share1,
         };
         let witness2 = Self {
             public_inputs: public_inputs.to_vec(),
+            shared_public_inputs: shared_public2,
             witness: share2,
         };
         let witness3 = Self {
             public_inputs: public_inputs.to_vec(),
+            shared_public_inputs: shared_public3,
             witness: share3,
         };
         [witness1, witness2, witness3]
     }
+
+    /// Like [Self::share_rep3], but derives all randomness from the provided seed with ChaCha20, so the same seed always
+    /// produces the same shares. This is intended for reproducible test vectors and audits, never use it for real
+    /// witnesses.
+    pub fn share_rep3_deterministic(
+        witness: Witness<F>,
+        num_pub_inputs: usize,
+        seed: [u8; 32],
+        compression: CompressionLevel,
+        additive: bool,
+        hide_public_inputs: bool,
+    ) -> [Self; 3] {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        Self::share_rep3(
+            witness,
+            num_pub_inputs,
+            &mut rng,
+            compression,
+            additive,
+            hide_public_inputs,
+        )
+    }
 }
 
 impl<F: PrimeField> SharedWitness<F, Rep3PrimeFieldShare<F>> {
@@ -417,14 +715,17 @@ impl<F: PrimeField> SharedWitness<F, Rep3PrimeFieldShare<F>> {
         let [share1, share2, share3] = rep3::share_field_elements(witness, rng);
         let witness1 = Self {
             public_inputs: public_inputs.to_vec(),
+            shared_public_inputs: vec![],
             witness: share1,
         };
         let witness2 = Self {
             public_inputs: public_inputs.to_vec(),
+            shared_public_inputs: vec![],
             witness: share2,
         };
         let witness3 = Self {
             public_inputs: public_inputs.to_vec(),
+            shared_public_inputs: vec![],
             witness: share3,
         };
         [witness1, witness2, witness3]
@@ -447,6 +748,62 @@ impl<F: PrimeField> SharedWitness<F, ShamirPrimeFieldShare<F>> {
             .into_iter()
             .map(|share| Self {
                 public_inputs: public_inputs.to_vec(),
+                shared_public_inputs: vec![],
+                witness: share,
+            })
+            .collect()
+    }
+
+    /// Like [Self::share_shamir], but derives all randomness from the provided seed with ChaCha20, so the same seed
+    /// always produces the same shares. This is intended for reproducible test vectors and audits, never use it for
+    /// real witnesses.
+    pub fn share_shamir_deterministic(
+        witness: Witness<F>,
+        num_pub_inputs: usize,
+        degree: usize,
+        num_parties: usize,
+        seed: [u8; 32],
+    ) -> Vec<Self> {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        Self::share_shamir(witness, num_pub_inputs, degree, num_parties, &mut rng)
+    }
+}
+
+impl<F: PrimeField> SharedWitness<F, TwoPcPrimeFieldShare<F>> {
+    /// Shares a given witness and public input vector additively between the two parties of the 2PC protocol.
+    pub fn share_twopc<R: Rng + CryptoRng>(
+        witness: Witness<F>,
+        num_pub_inputs: usize,
+        rng: &mut R,
+    ) -> [Self; 2] {
+        let public_inputs = &witness.values[..num_pub_inputs];
+        let witness = &witness.values[num_pub_inputs..];
+        let shares = twopc::share_field_elements(witness, rng);
+        shares.map(|share| Self {
+            public_inputs: public_inputs.to_vec(),
+            shared_public_inputs: vec![],
+            witness: share,
+        })
+    }
+}
+
+impl<F: PrimeField> SharedWitness<F, RssPrimeFieldShare<F>> {
+    /// Shares a given witness and public input vector using replicated secret sharing for `num_parties` parties tolerating `threshold` corrupted parties.
+    pub fn share_rss<R: Rng + CryptoRng>(
+        witness: Witness<F>,
+        num_pub_inputs: usize,
+        num_parties: usize,
+        threshold: usize,
+        rng: &mut R,
+    ) -> Vec<Self> {
+        let public_inputs = &witness.values[..num_pub_inputs];
+        let witness = &witness.values[num_pub_inputs..];
+        let shares = rss::share_field_elements(witness, num_parties, threshold, rng);
+        shares
+            .into_iter()
+            .map(|share| Self {
+                public_inputs: public_inputs.to_vec(),
+                shared_public_inputs: vec![],
                 witness: share,
             })
             .collect()
@@ -491,6 +848,41 @@ impl std::fmt::Display for VerificationError {
     }
 }
 
+/// The error type for expanding a [SerializeableSharedRep3Witness] into a [SharedWitness] without network.
+#[derive(Debug)]
+pub enum ExpandWitnessError {
+    /// The witness is additively shared, a resharing round is required to obtain replicated shares.
+    ReshareRequired,
+    /// Wraps an underlying error (e.g., malformed compressed shares)
+    Malformed(eyre::Report),
+}
+
+impl From<eyre::Report> for ExpandWitnessError {
+    fn from(error: eyre::Report) -> Self {
+        ExpandWitnessError::Malformed(error)
+    }
+}
+
+impl std::error::Error for ExpandWitnessError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ExpandWitnessError::Malformed(source) => Some(source.as_ref()),
+            ExpandWitnessError::ReshareRequired => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ExpandWitnessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExpandWitnessError::ReshareRequired => {
+                writeln!(f, "witness is additively shared, resharing is required")
+            }
+            ExpandWitnessError::Malformed(error) => writeln!(f, "cannot expand witness: {error}"),
+        }
+    }
+}
+
 /// Gathers utility methods for proving coSNARKs.
 pub mod utils {
     use ark_ff::{FftField, LegendreSymbol, PrimeField};
@@ -517,3 +909,137 @@ pub mod utils {
         (q, roots)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::UniformRand;
+
+    type SeedRng = rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn check_no_overlap() {
+        let mut first = SharedInput::<Fr, Fr>::default();
+        first.add_public_input("p".to_owned(), vec![Fr::from(1u64)]);
+        first.add_shared_input("a".to_owned(), vec![Fr::from(2u64)]);
+        let mut second = SharedInput::<Fr, Fr>::default();
+        second.add_public_input("p".to_owned(), vec![Fr::from(1u64)]);
+        second.add_shared_input("b".to_owned(), vec![Fr::from(3u64)]);
+        SharedInput::check_no_overlap(&[first.clone(), second.clone()]).unwrap();
+
+        let mut overlapping = SharedInput::<Fr, Fr>::default();
+        overlapping.add_shared_input("a".to_owned(), vec![Fr::from(4u64)]);
+        let mut wrong_public = SharedInput::<Fr, Fr>::default();
+        wrong_public.add_public_input("p".to_owned(), vec![Fr::from(5u64)]);
+        let err = SharedInput::check_no_overlap(&[first.clone(), overlapping, wrong_public])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("input 1:"));
+        assert!(err.contains("input 2:"));
+
+        let merged = first.merge(second).unwrap();
+        assert_eq!(merged.shared_inputs.len(), 2);
+    }
+
+    #[test]
+    fn into_shared_witness() {
+        let mut rng = rand::thread_rng();
+        let witness = Witness {
+            values: (0..10).map(|_| Fr::rand(&mut rng)).collect(),
+        };
+        for (compression, additive, expandable) in [
+            (CompressionLevel::None, false, true),
+            (CompressionLevel::Seeded, false, true),
+            (CompressionLevel::None, true, false),
+            (CompressionLevel::Seeded, true, false),
+        ] {
+            let [share0, share1, share2] =
+                SerializeableSharedRep3Witness::<Fr, SeedRng>::share_rep3(
+                    witness.clone(),
+                    3,
+                    &mut rng,
+                    compression,
+                    additive,
+                    false,
+                );
+            let expanded = [share0, share1, share2].map(|share| share.into_shared_witness());
+            if !expandable {
+                assert!(expanded
+                    .iter()
+                    .all(|res| matches!(res, Err(ExpandWitnessError::ReshareRequired))));
+                continue;
+            }
+            let [share0, share1, share2] = expanded.map(Result::unwrap);
+            assert_eq!(share0.public_inputs, witness.values[..3]);
+            let reconstructed =
+                rep3::combine_field_elements(&share0.witness, &share1.witness, &share2.witness);
+            assert_eq!(reconstructed, witness.values[3..]);
+        }
+    }
+
+    #[test]
+    fn deterministic_sharing() {
+        let input = (0..10u64).map(Fr::from).collect::<Vec<_>>();
+        let serialize = |seed: [u8; 32]| {
+            let shares = SerializeableSharedRep3Input::<Fr, SeedRng>::share_rep3_deterministic(
+                &input, seed, true, false,
+            );
+            let shamir_shares =
+                SerializeableSharedRep3Input::<Fr, SeedRng>::share_shamir_deterministic(
+                    &input, 1, seed,
+                )
+                .unwrap();
+            shares
+                .into_iter()
+                .zip(shamir_shares)
+                .map(|(share, shamir_share)| {
+                    let mut file = SerializeableSharedRep3Input::<Fr, SeedRng>::default();
+                    file.shared_inputs.insert("a".to_owned(), share);
+                    file.shamir_shared_inputs
+                        .insert("b".to_owned(), shamir_share);
+                    bincode::serialize(&file).unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(serialize([1; 32]), serialize([1; 32]));
+        assert_ne!(serialize([1; 32]), serialize([2; 32]));
+    }
+
+    #[test]
+    fn deterministic_witness_sharing() {
+        let witness = Witness {
+            values: (0..10u64).map(Fr::from).collect::<Vec<_>>(),
+        };
+        let serialize = |seed: [u8; 32]| {
+            let shares = SerializeableSharedRep3Witness::<Fr, SeedRng>::share_rep3_deterministic(
+                witness.clone(),
+                3,
+                seed,
+                CompressionLevel::Seeded,
+                false,
+                true,
+            );
+            let shamir_shares =
+                SharedWitness::<Fr, ShamirPrimeFieldShare<Fr>>::share_shamir_deterministic(
+                    witness.clone(),
+                    3,
+                    1,
+                    3,
+                    seed,
+                );
+            shares
+                .iter()
+                .zip(shamir_shares.iter())
+                .map(|(share, shamir_share)| {
+                    (
+                        bincode::serialize(share).unwrap(),
+                        bincode::serialize(shamir_share).unwrap(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(serialize([1; 32]), serialize([1; 32]));
+        assert_ne!(serialize([1; 32]), serialize([2; 32]));
+    }
+}
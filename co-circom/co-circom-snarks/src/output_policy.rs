@@ -0,0 +1,127 @@
+//! Policies describing which parties may learn the reconstructed outputs of a circuit.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+/// Describes who may learn the reconstructed value of an output signal.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputDisclosure {
+    /// All parties learn the value.
+    #[default]
+    All,
+    /// Only the parties with the given ids learn the value.
+    Parties(Vec<usize>),
+    /// No party learns the value, it stays secret-shared.
+    None,
+}
+
+impl OutputDisclosure {
+    /// Returns `true` if the party with the given id may learn the value.
+    pub fn reveals_to(&self, party_id: usize) -> bool {
+        match self {
+            OutputDisclosure::All => true,
+            OutputDisclosure::Parties(parties) => parties.contains(&party_id),
+            OutputDisclosure::None => false,
+        }
+    }
+}
+
+/// A policy specifying, per output signal, which parties may learn the reconstructed value.
+///
+/// Outputs which are not listed explicitly use the `default` disclosure. The default policy reveals
+/// all outputs to all parties, which mirrors the behavior without a policy.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct OutputPolicy {
+    /// The disclosure for outputs which are not listed in `outputs`.
+    #[serde(default)]
+    pub default: OutputDisclosure,
+    /// The disclosure per output signal name.
+    #[serde(default)]
+    pub outputs: BTreeMap<String, OutputDisclosure>,
+}
+
+impl OutputPolicy {
+    /// Returns the disclosure for the output with the given name.
+    pub fn disclosure(&self, name: &str) -> &OutputDisclosure {
+        self.outputs.get(name).unwrap_or(&self.default)
+    }
+
+    /// Returns `true` if the policy reveals all outputs to all parties.
+    pub fn reveals_all(&self) -> bool {
+        self.default == OutputDisclosure::All
+            && self
+                .outputs
+                .values()
+                .all(|disclosure| *disclosure == OutputDisclosure::All)
+    }
+
+    /// Resolves the policy to one [OutputDisclosure] per public input, excluding the constant 1 at position 0.
+    ///
+    /// The `output_mapping` maps the output names to their offset and size in the public inputs (including the
+    /// constant 1). Public inputs which are no outputs are known to all parties anyway and are therefore
+    /// resolved to [OutputDisclosure::All].
+    pub fn resolve(
+        &self,
+        output_mapping: &HashMap<String, (usize, usize)>,
+        num_public_inputs: usize,
+    ) -> eyre::Result<Vec<OutputDisclosure>> {
+        let mut resolved = vec![OutputDisclosure::All; num_public_inputs.saturating_sub(1)];
+        for (name, (offset, size)) in output_mapping {
+            if *offset == 0 || offset + size > num_public_inputs {
+                eyre::bail!("output {name} is not part of the public inputs");
+            }
+            resolved[offset - 1..offset - 1 + size].fill(self.disclosure(name).clone());
+        }
+        for name in self.outputs.keys() {
+            if !output_mapping.contains_key(name) {
+                eyre::bail!("output policy references unknown output {name}");
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_policy() {
+        let output_mapping = HashMap::from([
+            ("a".to_owned(), (1, 2)),
+            ("b".to_owned(), (3, 1)),
+            ("c".to_owned(), (4, 1)),
+        ]);
+        let policy = OutputPolicy {
+            default: OutputDisclosure::None,
+            outputs: BTreeMap::from([
+                ("a".to_owned(), OutputDisclosure::Parties(vec![0, 2])),
+                ("b".to_owned(), OutputDisclosure::All),
+            ]),
+        };
+        assert!(!policy.reveals_all());
+        assert!(policy.disclosure("a").reveals_to(2));
+        assert!(!policy.disclosure("a").reveals_to(1));
+        let resolved = policy.resolve(&output_mapping, 7).unwrap();
+        assert_eq!(
+            resolved,
+            vec![
+                OutputDisclosure::Parties(vec![0, 2]),
+                OutputDisclosure::Parties(vec![0, 2]),
+                OutputDisclosure::All,
+                OutputDisclosure::None,
+                OutputDisclosure::All,
+                OutputDisclosure::All,
+            ]
+        );
+
+        let unknown = OutputPolicy {
+            default: OutputDisclosure::All,
+            outputs: BTreeMap::from([("d".to_owned(), OutputDisclosure::None)]),
+        };
+        assert!(unknown.resolve(&output_mapping, 7).is_err());
+        assert!(OutputPolicy::default().reveals_all());
+    }
+}
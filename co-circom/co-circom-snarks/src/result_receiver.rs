@@ -0,0 +1,58 @@
+//! Key handling and decryption for an external result receiver. The receiver is not one of the computing parties. It
+//! supplies its public key to the parties, which encrypt their shares of the outputs under this key (see
+//! [open_vec_to_receiver](mpc_core::protocols::rep3::arithmetic::open_vec_to_receiver)).
+
+use ark_ec::CurveGroup;
+use ark_ff::{PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mpc_core::protocols::rep3::{receiver, EncryptedOutputShares};
+use rand::{CryptoRng, Rng};
+
+/// The secret key of a result receiver.
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ResultReceiverKey<C: CurveGroup> {
+    secret: C::ScalarField,
+}
+
+impl<C: CurveGroup> ResultReceiverKey<C> {
+    /// Generates a new random key.
+    pub fn generate<R: Rng + CryptoRng>(rng: &mut R) -> Self {
+        Self {
+            secret: C::ScalarField::rand(rng),
+        }
+    }
+
+    /// Returns the public key, which is supplied to the computing parties.
+    pub fn public_key(&self) -> C {
+        C::generator() * self.secret
+    }
+
+    /// Decrypts the [EncryptedOutputShares] of all three parties and reconstructs the outputs.
+    pub fn decrypt<F: PrimeField>(
+        &self,
+        shares: [EncryptedOutputShares<C, F>; 3],
+    ) -> eyre::Result<Vec<F>> {
+        let mut parties = shares
+            .iter()
+            .map(|share| share.party_id().map(usize::from))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        parties.sort_unstable();
+        parties.dedup();
+        if parties.len() != 3 {
+            eyre::bail!("expected encrypted shares of three distinct parties");
+        }
+        let len = shares[0].values.len();
+        if shares.iter().any(|share| share.values.len() != len) {
+            eyre::bail!("encrypted shares have different lengths");
+        }
+
+        let mut result = vec![F::zero(); len];
+        for share in shares {
+            let shared_key = share.ephemeral * self.secret;
+            for (i, (res, value)) in result.iter_mut().zip(share.values).enumerate() {
+                *res += value - receiver::output_mask::<C, F>(&shared_key, i);
+            }
+        }
+        Ok(result)
+    }
+}
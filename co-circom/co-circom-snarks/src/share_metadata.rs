@@ -0,0 +1,840 @@
+//! Share files written by co-circom start with a small header describing their content. This module
+//! contains the header type and the means to read it without touching the actual shares.
+//!
+//! Share files written before the header was introduced are still accepted by [ShareMetadata::probe_or_legacy]. Their
+//! payload has the layout of version 1 share files.
+
+use std::{
+    collections::BTreeMap,
+    io::{Chain, Cursor, Read, Write},
+};
+
+use ark_ff::PrimeField;
+use eyre::Context;
+use mpc_core::protocols::{
+    rep3::{MaybeRep3ShareVecType, Rep3ShareVecType},
+    shamir::ShamirPrimeFieldShare,
+};
+use rand::{CryptoRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{SerializeableSharedRep3Input, SerializeableSharedRep3Witness, SharedWitness};
+
+/// A reader yielding the payload of a share file, as returned by [ShareMetadata::probe_or_legacy].
+pub type ShareFilePayload<R> = Chain<Cursor<Vec<u8>>, R>;
+
+/// The magic bytes every share file starts with.
+pub const SHARE_FILE_MAGIC: [u8; 4] = *b"COSH";
+/// The current version of the share file format.
+///
+/// Version 2 added the shared public inputs to witness shares, and the Shamir shared inputs and input types to input
+/// shares. Version 3 added witness files containing a fully public witness in plain (see
+/// [ShareMetadata::public_witness]). Files of older versions and legacy files without header can still be read.
+pub const SHARE_FILE_VERSION: u16 = 3;
+
+/// The curve the shares in a share file are defined over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareCurve {
+    /// The BN254 curve.
+    Bn254,
+    /// The BLS12-381 curve.
+    Bls12_381,
+    /// The BLS12-377 curve.
+    Bls12_377,
+    /// The BW6-761 curve.
+    Bw6_761,
+}
+
+impl std::fmt::Display for ShareCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareCurve::Bn254 => write!(f, "BN254"),
+            ShareCurve::Bls12_381 => write!(f, "BLS12-381"),
+            ShareCurve::Bls12_377 => write!(f, "BLS12-377"),
+            ShareCurve::Bw6_761 => write!(f, "BW6-761"),
+        }
+    }
+}
+
+/// The MPC protocol the shares in a share file belong to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareProtocol {
+    /// Replicated secret sharing.
+    Rep3,
+    /// Shamir secret sharing.
+    Shamir,
+}
+
+impl std::fmt::Display for ShareProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareProtocol::Rep3 => write!(f, "REP3"),
+            ShareProtocol::Shamir => write!(f, "SHAMIR"),
+        }
+    }
+}
+
+/// Whether a share file contains a shared input or a shared witness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareKind {
+    /// A [SerializeableSharedRep3Input].
+    Input,
+    /// A [SerializeableSharedRep3Witness] or a [SharedWitness].
+    Witness,
+}
+
+impl std::fmt::Display for ShareKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareKind::Input => write!(f, "input"),
+            ShareKind::Witness => write!(f, "witness"),
+        }
+    }
+}
+
+/// The header of a share file. It describes the content of the file without containing any share values,
+/// and can therefore safely be inspected by operators and support tooling.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareMetadata {
+    /// The version of the share file format.
+    pub version: u16,
+    /// The curve the shares are defined over.
+    pub curve: ShareCurve,
+    /// The MPC protocol the shares belong to.
+    pub protocol: ShareProtocol,
+    /// Whether the file contains an input or a witness.
+    pub kind: ShareKind,
+    /// The number of public inputs. For witnesses this includes the constant 1 at position 0.
+    pub num_public_inputs: usize,
+    /// The number of public inputs which are secret-shared and only revealed once the proof is assembled.
+    pub num_shared_public_inputs: usize,
+    /// The number of secret-shared elements.
+    pub num_shared: usize,
+    /// Whether (some of) the shares are compressed as seeds.
+    pub seeded: bool,
+    /// Whether (some of) the shares are additive instead of replicated.
+    pub additive: bool,
+    /// Whether the payload is compressed.
+    pub compressed: bool,
+    /// Whether the file contains a fully public witness in plain instead of shares. Every party knows such a witness,
+    /// so the proof can be created locally without MPC.
+    pub public_witness: bool,
+}
+
+/// The header of version 2 share files, which could not contain a fully public witness.
+#[derive(Deserialize)]
+struct ShareMetadataV2 {
+    version: u16,
+    curve: ShareCurve,
+    protocol: ShareProtocol,
+    kind: ShareKind,
+    num_public_inputs: usize,
+    num_shared_public_inputs: usize,
+    num_shared: usize,
+    seeded: bool,
+    additive: bool,
+    compressed: bool,
+}
+
+impl From<ShareMetadataV2> for ShareMetadata {
+    fn from(v2: ShareMetadataV2) -> Self {
+        Self {
+            version: v2.version,
+            curve: v2.curve,
+            protocol: v2.protocol,
+            kind: v2.kind,
+            num_public_inputs: v2.num_public_inputs,
+            num_shared_public_inputs: v2.num_shared_public_inputs,
+            num_shared: v2.num_shared,
+            seeded: v2.seeded,
+            additive: v2.additive,
+            compressed: v2.compressed,
+            public_witness: false,
+        }
+    }
+}
+
+/// The header of version 1 share files, which did not contain the number of shared public inputs.
+#[derive(Deserialize)]
+struct ShareMetadataV1 {
+    version: u16,
+    curve: ShareCurve,
+    protocol: ShareProtocol,
+    kind: ShareKind,
+    num_public_inputs: usize,
+    num_shared: usize,
+    seeded: bool,
+    additive: bool,
+    compressed: bool,
+}
+
+impl From<ShareMetadataV1> for ShareMetadata {
+    fn from(v1: ShareMetadataV1) -> Self {
+        Self {
+            version: v1.version,
+            curve: v1.curve,
+            protocol: v1.protocol,
+            kind: v1.kind,
+            num_public_inputs: v1.num_public_inputs,
+            num_shared_public_inputs: 0,
+            num_shared: v1.num_shared,
+            seeded: v1.seeded,
+            additive: v1.additive,
+            compressed: v1.compressed,
+            public_witness: false,
+        }
+    }
+}
+
+impl ShareMetadata {
+    /// Reads the header of a share file from the provided [Read]er. Only the header is consumed, the shares
+    /// themselves are neither read nor deserialized. Afterwards, the reader points to the start of the payload.
+    pub fn probe<R: Read>(mut reader: R) -> eyre::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader
+            .read_exact(&mut magic)
+            .context("while reading share file header")?;
+        if magic != SHARE_FILE_MAGIC {
+            eyre::bail!(
+                "not a share file, or a legacy share file without header (see ShareMetadata::probe_or_legacy)"
+            );
+        }
+        Self::read_after_magic(reader)
+    }
+
+    /// Reads the header of a share file like [ShareMetadata::probe], but also accepts legacy share files, which were
+    /// written without a header. For those, `None` is returned instead of a header, and their protocol and kind cannot
+    /// be checked before parsing the payload. The returned reader yields the payload in both cases. Legacy payloads
+    /// have the layout of version 1 share files and can be read with the `read_legacy_payload` functions.
+    pub fn probe_or_legacy<R: Read>(
+        mut reader: R,
+    ) -> eyre::Result<(Option<Self>, ShareFilePayload<R>)> {
+        let mut magic = Vec::with_capacity(SHARE_FILE_MAGIC.len());
+        (&mut reader)
+            .take(SHARE_FILE_MAGIC.len() as u64)
+            .read_to_end(&mut magic)
+            .context("while reading share file header")?;
+        if magic == SHARE_FILE_MAGIC {
+            let metadata = Self::read_after_magic(&mut reader)?;
+            Ok((Some(metadata), Cursor::new(Vec::new()).chain(reader)))
+        } else {
+            // the bytes we read already belong to the payload
+            Ok((None, Cursor::new(magic).chain(reader)))
+        }
+    }
+
+    fn read_after_magic<R: Read>(mut reader: R) -> eyre::Result<Self> {
+        // the version is the first field of the header and determines its layout
+        let mut version = [0u8; 2];
+        reader
+            .read_exact(&mut version)
+            .context("while reading share file header")?;
+        let version_number = u16::from_le_bytes(version);
+        if version_number > SHARE_FILE_VERSION {
+            eyre::bail!(
+                "share file version {} is newer than the supported version {}",
+                version_number,
+                SHARE_FILE_VERSION
+            );
+        }
+        let reader = version.as_slice().chain(reader);
+        let metadata = match version_number {
+            1 => bincode::deserialize_from::<_, ShareMetadataV1>(reader)
+                .context("while parsing share file header")?
+                .into(),
+            2 => bincode::deserialize_from::<_, ShareMetadataV2>(reader)
+                .context("while parsing share file header")?
+                .into(),
+            _ => bincode::deserialize_from(reader).context("while parsing share file header")?,
+        };
+        Ok(metadata)
+    }
+
+    /// Writes the header to the provided [Write]r. The payload is expected to follow directly afterwards.
+    pub fn write<W: Write>(&self, mut writer: W) -> eyre::Result<()> {
+        writer
+            .write_all(&SHARE_FILE_MAGIC)
+            .context("while writing share file header")?;
+        bincode::serialize_into(writer, self).context("while writing share file header")?;
+        Ok(())
+    }
+
+    /// Checks that the header describes a file with the given protocol and kind.
+    pub fn expect(&self, protocol: ShareProtocol, kind: ShareKind) -> eyre::Result<()> {
+        if self.protocol != protocol {
+            eyre::bail!(
+                "expected a {protocol} share file, but file contains {} shares",
+                self.protocol
+            );
+        }
+        if self.kind != kind {
+            eyre::bail!(
+                "expected a {kind} share file, but file contains a {}",
+                self.kind
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The payload of version 1 Rep3 witness share files, which did not contain shared public inputs.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct Rep3WitnessV1<F: PrimeField, U: Rng + SeedableRng + CryptoRng>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    #[serde(deserialize_with = "mpc_core::ark_de")]
+    public_inputs: Vec<F>,
+    witness: Rep3ShareVecType<F, U>,
+}
+
+/// The payload of version 1 Shamir witness share files, which did not contain shared public inputs.
+#[derive(Deserialize)]
+struct ShamirWitnessV1<F: PrimeField> {
+    #[serde(deserialize_with = "mpc_core::ark_de")]
+    public_inputs: Vec<F>,
+    #[serde(deserialize_with = "mpc_core::ark_de")]
+    witness: Vec<ShamirPrimeFieldShare<F>>,
+}
+
+impl<F: PrimeField, U: Rng + SeedableRng + CryptoRng> SerializeableSharedRep3Witness<F, U>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    /// Reads the payload of a witness share file with the given header (see [ShareMetadata::probe]), decompressing it
+    /// if necessary. Files of all supported versions can be read, except for files containing a fully public witness
+    /// (see [SharedWitness::read_public_payload]).
+    pub fn read_payload<R: Read>(mut reader: R, metadata: &ShareMetadata) -> eyre::Result<Self> {
+        if metadata.public_witness {
+            eyre::bail!("witness file contains a fully public witness instead of shares");
+        }
+        if metadata.compressed {
+            let mut bytes = Vec::new();
+            reader
+                .read_to_end(&mut bytes)
+                .context("while reading witness share")?;
+            Self::decompress_with_version(&bytes, metadata.version)
+        } else if metadata.version == 1 {
+            Self::read_legacy_payload(reader)
+        } else {
+            bincode::deserialize_from(reader).context("while parsing witness share")
+        }
+    }
+
+    /// Reads the payload of a legacy witness share file without header (see [ShareMetadata::probe_or_legacy]).
+    pub fn read_legacy_payload<R: Read>(reader: R) -> eyre::Result<Self> {
+        let v1: Rep3WitnessV1<F, U> =
+            bincode::deserialize_from(reader).context("while parsing witness share")?;
+        Ok(Self {
+            public_inputs: v1.public_inputs,
+            shared_public_inputs: Vec::new(),
+            witness: v1.witness,
+        })
+    }
+}
+
+impl<F: PrimeField> SharedWitness<F, ShamirPrimeFieldShare<F>> {
+    /// Reads the payload of a Shamir witness share file with the given header (see [ShareMetadata::probe]). Files of
+    /// all supported versions can be read.
+    pub fn read_payload<R: Read>(reader: R, metadata: &ShareMetadata) -> eyre::Result<Self> {
+        if metadata.version == 1 {
+            Self::read_legacy_payload(reader)
+        } else {
+            bincode::deserialize_from(reader).context("while parsing witness share")
+        }
+    }
+
+    /// Reads the payload of a legacy Shamir witness share file without header (see [ShareMetadata::probe_or_legacy]).
+    pub fn read_legacy_payload<R: Read>(reader: R) -> eyre::Result<Self> {
+        let v1: ShamirWitnessV1<F> =
+            bincode::deserialize_from(reader).context("while parsing witness share")?;
+        Ok(Self {
+            public_inputs: v1.public_inputs,
+            shared_public_inputs: Vec::new(),
+            witness: v1.witness,
+        })
+    }
+}
+
+impl<F: PrimeField, U: Rng + SeedableRng + CryptoRng> SerializeableSharedRep3Input<F, U>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    /// Reads the payload of an input share file with the given header (see [ShareMetadata::probe]). Files of all
+    /// supported versions can be read.
+    pub fn read_payload<R: Read>(reader: R, metadata: &ShareMetadata) -> eyre::Result<Self> {
+        if metadata.version == 1 {
+            Self::read_legacy_payload(reader)
+        } else {
+            bincode::deserialize_from(reader).context("while parsing input share")
+        }
+    }
+
+    /// Reads the payload of a legacy input share file without header (see [ShareMetadata::probe_or_legacy]).
+    pub fn read_legacy_payload<R: Read>(reader: R) -> eyre::Result<Self> {
+        let v1: Rep3InputV1<F, U> =
+            bincode::deserialize_from(reader).context("while parsing input share")?;
+        Ok(Self {
+            public_inputs: v1.public_inputs,
+            shared_inputs: v1.shared_inputs,
+            maybe_shared_inputs: v1.maybe_shared_inputs,
+            ..Default::default()
+        })
+    }
+}
+
+/// The payload of version 1 input share files, which did not contain Shamir shared inputs and input types.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct Rep3InputV1<F: PrimeField, U: Rng + SeedableRng + CryptoRng>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    #[serde(deserialize_with = "mpc_core::ark_de")]
+    public_inputs: BTreeMap<String, Vec<F>>,
+    shared_inputs: BTreeMap<String, Rep3ShareVecType<F, U>>,
+    maybe_shared_inputs: BTreeMap<String, MaybeRep3ShareVecType<F>>,
+}
+
+/// Returns whether the share is seeded, whether it is additive, and its length.
+fn describe_share<F: PrimeField, U: Rng + SeedableRng + CryptoRng>(
+    share: &Rep3ShareVecType<F, U>,
+) -> (bool, bool, usize)
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    match share {
+        Rep3ShareVecType::Replicated(vec) => (false, false, vec.len()),
+        Rep3ShareVecType::SeededReplicated(seeded) => {
+            (true, false, seeded.a.length().max(seeded.b.length()))
+        }
+        Rep3ShareVecType::Additive(vec) => (false, true, vec.len()),
+        Rep3ShareVecType::SeededAdditive(seeded) => (true, true, seeded.length()),
+    }
+}
+
+impl<F: PrimeField, U: Rng + SeedableRng + CryptoRng> SerializeableSharedRep3Witness<F, U>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    /// Returns the [ShareMetadata] describing this witness share.
+    pub fn metadata(&self, curve: ShareCurve) -> ShareMetadata {
+        let (seeded, additive, num_shared) = describe_share(&self.witness);
+        ShareMetadata {
+            version: SHARE_FILE_VERSION,
+            curve,
+            protocol: ShareProtocol::Rep3,
+            kind: ShareKind::Witness,
+            num_public_inputs: self.public_inputs.len(),
+            num_shared_public_inputs: self.shared_public_inputs.len(),
+            num_shared,
+            seeded,
+            additive,
+            compressed: false,
+            public_witness: false,
+        }
+    }
+}
+
+impl<F: PrimeField, U: Rng + SeedableRng + CryptoRng> SerializeableSharedRep3Input<F, U>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    /// Returns the [ShareMetadata] describing this input share.
+    pub fn metadata(&self, curve: ShareCurve) -> ShareMetadata {
+        let mut seeded = false;
+        let mut additive = false;
+        let mut num_shared = 0;
+        for share in self.shared_inputs.values() {
+            let (s, a, len) = describe_share(share);
+            seeded |= s;
+            additive |= a;
+            num_shared += len;
+        }
+        num_shared += self
+            .shamir_shared_inputs
+            .values()
+            .map(Vec::len)
+            .sum::<usize>();
+        for share in self.maybe_shared_inputs.values() {
+            match share {
+                MaybeRep3ShareVecType::Replicated(vec) => num_shared += vec.len(),
+                MaybeRep3ShareVecType::Additive(vec) => {
+                    additive = true;
+                    num_shared += vec.len();
+                }
+            }
+        }
+        ShareMetadata {
+            version: SHARE_FILE_VERSION,
+            curve,
+            protocol: ShareProtocol::Rep3,
+            kind: ShareKind::Input,
+            num_public_inputs: self.public_inputs.values().map(Vec::len).sum(),
+            num_shared_public_inputs: 0,
+            num_shared,
+            seeded,
+            additive,
+            compressed: false,
+            public_witness: false,
+        }
+    }
+}
+
+impl<F: PrimeField> SharedWitness<F, ShamirPrimeFieldShare<F>> {
+    /// Returns the [ShareMetadata] describing this witness share.
+    pub fn metadata(&self, curve: ShareCurve) -> ShareMetadata {
+        ShareMetadata {
+            version: SHARE_FILE_VERSION,
+            curve,
+            protocol: ShareProtocol::Shamir,
+            kind: ShareKind::Witness,
+            num_public_inputs: self.public_inputs.len(),
+            num_shared_public_inputs: self.shared_public_inputs.len(),
+            num_shared: self.witness.len(),
+            seeded: false,
+            additive: false,
+            compressed: false,
+            public_witness: false,
+        }
+    }
+}
+
+impl<F: PrimeField> SharedWitness<F, F> {
+    /// Returns the [ShareMetadata] describing this fully public witness, which was created by the Rep3 witness
+    /// extension.
+    pub fn public_witness_metadata(&self, curve: ShareCurve) -> ShareMetadata {
+        ShareMetadata {
+            version: SHARE_FILE_VERSION,
+            curve,
+            protocol: ShareProtocol::Rep3,
+            kind: ShareKind::Witness,
+            num_public_inputs: self.public_inputs.len(),
+            num_shared_public_inputs: 0,
+            num_shared: 0,
+            seeded: false,
+            additive: false,
+            compressed: false,
+            public_witness: true,
+        }
+    }
+
+    /// Reads the payload of a witness file containing a fully public witness (see [ShareMetadata::public_witness]).
+    pub fn read_public_payload<R: Read>(reader: R, metadata: &ShareMetadata) -> eyre::Result<Self> {
+        if !metadata.public_witness {
+            eyre::bail!("witness file contains shares instead of a fully public witness");
+        }
+        bincode::deserialize_from(reader).context("while parsing public witness")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use circom_types::Witness;
+    use rand::thread_rng;
+
+    type SeedRng = rand_chacha::ChaCha12Rng;
+
+    #[derive(Serialize)]
+    struct Rep3WitnessV1Out<'a> {
+        #[serde(serialize_with = "mpc_core::ark_se")]
+        public_inputs: Vec<Fr>,
+        witness: &'a Rep3ShareVecType<Fr, SeedRng>,
+    }
+
+    #[derive(Serialize)]
+    struct Rep3InputV1Out<'a> {
+        #[serde(serialize_with = "mpc_core::ark_se")]
+        public_inputs: BTreeMap<String, Vec<Fr>>,
+        shared_inputs: &'a BTreeMap<String, Rep3ShareVecType<Fr, SeedRng>>,
+        maybe_shared_inputs: &'a BTreeMap<String, MaybeRep3ShareVecType<Fr>>,
+    }
+
+    fn witness() -> Witness<Fr> {
+        Witness {
+            values: (0..6).map(|i| Fr::from(i as u64 + 1)).collect(),
+        }
+    }
+
+    fn input_share() -> SerializeableSharedRep3Input<Fr, SeedRng> {
+        let values = (0..4).map(|i| Fr::from(i as u64)).collect::<Vec<_>>();
+        let [share, _, _] = SerializeableSharedRep3Input::<Fr, SeedRng>::share_rep3(
+            &values,
+            &mut thread_rng(),
+            false,
+            false,
+        );
+        let mut input = SerializeableSharedRep3Input::default();
+        input
+            .public_inputs
+            .insert("a".to_owned(), vec![Fr::from(42u64)]);
+        input.shared_inputs.insert("b".to_owned(), share);
+        input
+    }
+
+    #[derive(Serialize)]
+    struct ShamirWitnessV1Out {
+        #[serde(serialize_with = "mpc_core::ark_se")]
+        public_inputs: Vec<Fr>,
+        #[serde(serialize_with = "mpc_core::ark_se")]
+        witness: Vec<ShamirPrimeFieldShare<Fr>>,
+    }
+
+    #[test]
+    fn read_version_1_share_file() {
+        let witness = Witness {
+            values: (0..6).map(|i| Fr::from(i as u64 + 1)).collect(),
+        };
+        let shares = SharedWitness::share_shamir(witness, 2, 1, 3, &mut thread_rng());
+        let share = &shares[0];
+
+        let mut file = SHARE_FILE_MAGIC.to_vec();
+        let header = (
+            1u16,
+            ShareCurve::Bn254,
+            ShareProtocol::Shamir,
+            ShareKind::Witness,
+            share.public_inputs.len(),
+            share.witness.len(),
+            false,
+            false,
+            false,
+        );
+        bincode::serialize_into(&mut file, &header).unwrap();
+        let payload = ShamirWitnessV1Out {
+            public_inputs: share.public_inputs.clone(),
+            witness: share.witness.clone(),
+        };
+        bincode::serialize_into(&mut file, &payload).unwrap();
+
+        let mut reader = file.as_slice();
+        let metadata = ShareMetadata::probe(&mut reader).unwrap();
+        assert_eq!(metadata.version, 1);
+        assert_eq!(metadata.num_shared_public_inputs, 0);
+        assert_eq!(metadata.num_shared, share.witness.len());
+        let read = SharedWitness::<Fr, ShamirPrimeFieldShare<Fr>>::read_payload(reader, &metadata)
+            .unwrap();
+        assert_eq!(read.public_inputs, share.public_inputs);
+        assert!(read.shared_public_inputs.is_empty());
+        assert_eq!(read.witness, share.witness);
+    }
+
+    #[test]
+    fn read_version_2_share_file() {
+        let shares = SharedWitness::share_shamir(witness(), 2, 1, 3, &mut thread_rng());
+        let share = &shares[0];
+
+        let mut file = SHARE_FILE_MAGIC.to_vec();
+        let header = (
+            2u16,
+            ShareCurve::Bn254,
+            ShareProtocol::Shamir,
+            ShareKind::Witness,
+            share.public_inputs.len(),
+            share.shared_public_inputs.len(),
+            share.witness.len(),
+            false,
+            false,
+            false,
+        );
+        bincode::serialize_into(&mut file, &header).unwrap();
+        bincode::serialize_into(&mut file, share).unwrap();
+
+        let mut reader = file.as_slice();
+        let metadata = ShareMetadata::probe(&mut reader).unwrap();
+        assert_eq!(metadata.version, 2);
+        assert!(!metadata.public_witness);
+        assert_eq!(metadata.num_shared, share.witness.len());
+        let read = SharedWitness::<Fr, ShamirPrimeFieldShare<Fr>>::read_payload(reader, &metadata)
+            .unwrap();
+        assert_eq!(read.public_inputs, share.public_inputs);
+        assert_eq!(read.witness, share.witness);
+    }
+
+    #[test]
+    fn read_current_share_file() {
+        let witness = Witness {
+            values: (0..6).map(|i| Fr::from(i as u64 + 1)).collect(),
+        };
+        let shares = SharedWitness::share_shamir(witness, 2, 1, 3, &mut thread_rng());
+        let share = &shares[1];
+
+        let mut file = Vec::new();
+        share.metadata(ShareCurve::Bn254).write(&mut file).unwrap();
+        bincode::serialize_into(&mut file, share).unwrap();
+
+        let mut reader = file.as_slice();
+        let metadata = ShareMetadata::probe(&mut reader).unwrap();
+        assert_eq!(metadata, share.metadata(ShareCurve::Bn254));
+        let read = SharedWitness::<Fr, ShamirPrimeFieldShare<Fr>>::read_payload(reader, &metadata)
+            .unwrap();
+        assert_eq!(read.public_inputs, share.public_inputs);
+        assert_eq!(read.witness, share.witness);
+    }
+
+    #[test]
+    fn write_probe_expect_round_trip() {
+        let input = input_share();
+        let metadata = input.metadata(ShareCurve::Bls12_381);
+        assert_eq!(metadata.version, SHARE_FILE_VERSION);
+        assert_eq!(metadata.num_public_inputs, 1);
+        assert_eq!(metadata.num_shared, 4);
+
+        let mut file = Vec::new();
+        metadata.write(&mut file).unwrap();
+        let probed = ShareMetadata::probe(file.as_slice()).unwrap();
+        assert_eq!(probed, metadata);
+        probed
+            .expect(ShareProtocol::Rep3, ShareKind::Input)
+            .unwrap();
+        assert!(probed
+            .expect(ShareProtocol::Shamir, ShareKind::Input)
+            .is_err());
+        assert!(probed
+            .expect(ShareProtocol::Rep3, ShareKind::Witness)
+            .is_err());
+
+        for curve in [
+            ShareCurve::Bn254,
+            ShareCurve::Bls12_381,
+            ShareCurve::Bls12_377,
+            ShareCurve::Bw6_761,
+        ] {
+            let metadata = input.metadata(curve);
+            let mut file = Vec::new();
+            metadata.write(&mut file).unwrap();
+            assert_eq!(ShareMetadata::probe(file.as_slice()).unwrap().curve, curve);
+        }
+    }
+
+    #[test]
+    fn probe_reads_only_header() {
+        let metadata = input_share().metadata(ShareCurve::Bn254);
+        // the payload is never parsed, so it does not have to be a valid share
+        let payload = [1u8, 2, 3, 4, 5];
+        let mut file = Vec::new();
+        metadata.write(&mut file).unwrap();
+        file.extend_from_slice(&payload);
+
+        let mut reader = file.as_slice();
+        assert_eq!(ShareMetadata::probe(&mut reader).unwrap(), metadata);
+        assert_eq!(reader, payload);
+
+        let (probed, mut reader) = ShareMetadata::probe_or_legacy(file.as_slice()).unwrap();
+        assert_eq!(probed, Some(metadata));
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, payload);
+    }
+
+    #[test]
+    fn read_legacy_share_files() {
+        let [rep3, _, _] = SerializeableSharedRep3Witness::<Fr, SeedRng>::share_rep3(
+            witness(),
+            2,
+            &mut thread_rng(),
+            crate::CompressionLevel::None,
+            false,
+            false,
+        );
+        let file = bincode::serialize(&Rep3WitnessV1Out {
+            public_inputs: rep3.public_inputs.clone(),
+            witness: &rep3.witness,
+        })
+        .unwrap();
+        assert!(ShareMetadata::probe(file.as_slice()).is_err());
+        let (metadata, payload) = ShareMetadata::probe_or_legacy(file.as_slice()).unwrap();
+        assert!(metadata.is_none());
+        let read =
+            SerializeableSharedRep3Witness::<Fr, SeedRng>::read_legacy_payload(payload).unwrap();
+        assert_eq!(read.public_inputs, rep3.public_inputs);
+        assert!(read.shared_public_inputs.is_empty());
+        assert_eq!(
+            bincode::serialize(&read.witness).unwrap(),
+            bincode::serialize(&rep3.witness).unwrap()
+        );
+
+        let shamir = SharedWitness::share_shamir(witness(), 2, 1, 3, &mut thread_rng());
+        let file = bincode::serialize(&ShamirWitnessV1Out {
+            public_inputs: shamir[0].public_inputs.clone(),
+            witness: shamir[0].witness.clone(),
+        })
+        .unwrap();
+        let (metadata, payload) = ShareMetadata::probe_or_legacy(file.as_slice()).unwrap();
+        assert!(metadata.is_none());
+        let read =
+            SharedWitness::<Fr, ShamirPrimeFieldShare<Fr>>::read_legacy_payload(payload).unwrap();
+        assert_eq!(read.public_inputs, shamir[0].public_inputs);
+        assert_eq!(read.witness, shamir[0].witness);
+
+        let input = input_share();
+        let file = bincode::serialize(&Rep3InputV1Out {
+            public_inputs: input.public_inputs.clone(),
+            shared_inputs: &input.shared_inputs,
+            maybe_shared_inputs: &input.maybe_shared_inputs,
+        })
+        .unwrap();
+        let (metadata, payload) = ShareMetadata::probe_or_legacy(file.as_slice()).unwrap();
+        assert!(metadata.is_none());
+        let read =
+            SerializeableSharedRep3Input::<Fr, SeedRng>::read_legacy_payload(payload).unwrap();
+        assert_eq!(
+            bincode::serialize(&read).unwrap(),
+            bincode::serialize(&input).unwrap()
+        );
+    }
+
+    #[test]
+    fn read_public_witness_file() {
+        let mut values = witness().values;
+        let public_witness = SharedWitness::<Fr, Fr> {
+            witness: values.split_off(2),
+            public_inputs: values,
+            shared_public_inputs: Vec::new(),
+        };
+        let mut file = Vec::new();
+        public_witness
+            .public_witness_metadata(ShareCurve::Bn254)
+            .write(&mut file)
+            .unwrap();
+        bincode::serialize_into(&mut file, &public_witness).unwrap();
+
+        let mut reader = file.as_slice();
+        let metadata = ShareMetadata::probe(&mut reader).unwrap();
+        assert!(metadata.public_witness);
+        assert_eq!(metadata.num_public_inputs, 2);
+        assert_eq!(metadata.num_shared, 0);
+        metadata
+            .expect(ShareProtocol::Rep3, ShareKind::Witness)
+            .unwrap();
+        assert!(
+            SerializeableSharedRep3Witness::<Fr, SeedRng>::read_payload(reader, &metadata).is_err()
+        );
+        let read = SharedWitness::<Fr, Fr>::read_public_payload(reader, &metadata).unwrap();
+        assert_eq!(read.public_inputs, public_witness.public_inputs);
+        assert_eq!(read.witness, public_witness.witness);
+
+        let shares = SharedWitness::share_shamir(witness(), 2, 1, 3, &mut thread_rng());
+        assert!(SharedWitness::<Fr, Fr>::read_public_payload(
+            reader,
+            &shares[0].metadata(ShareCurve::Bn254)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn reject_newer_version() {
+        let mut metadata = input_share().metadata(ShareCurve::Bn254);
+        metadata.version = SHARE_FILE_VERSION + 1;
+        let mut file = Vec::new();
+        metadata.write(&mut file).unwrap();
+        let err = ShareMetadata::probe(file.as_slice()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("is newer than the supported version"));
+        assert!(ShareMetadata::probe_or_legacy(file.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+//! Proofs of correct sharing for data providers. When a data provider shares its input with
+//! [share_rep3_with_proof], it commits to the three additive components of the sharing with Pedersen
+//! commitments. Every party receives the commitments together with the openings of the two components it holds.
+//! The parties check their openings and that they all received the same commitments. The sum of the
+//! commitments is then a commitment to the shared value, so a provider cannot hand out inconsistent shares.
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::UniformRand;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mpc_core::protocols::rep3::{self, id::PartyID, Rep3PrimeFieldShare};
+use rand::{CryptoRng, Rng};
+use sha3::{Digest, Sha3_256, Sha3_512};
+
+const GENERATOR_DOMAIN: &[u8] = b"co-snarks sharing proof generators";
+
+/// The commitments to the three additive components of a Rep3 sharing, together with the openings
+/// of the two components held by one party.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SharingProof<C: CurveGroup> {
+    /// The Pedersen commitments to the three additive components.
+    pub commitments: [C; 3],
+    /// The commitment randomness of the `a` component of the share.
+    pub randomness_a: C::ScalarField,
+    /// The commitment randomness of the `b` component of the share.
+    pub randomness_b: C::ScalarField,
+}
+
+/// Deterministically derives `amount` Pedersen generators plus the generator for the randomness. The discrete
+/// logarithms between the generators are unknown, as they are obtained by hashing to the curve.
+pub(crate) fn pedersen_generators<C: CurveGroup>(amount: usize) -> (Vec<C::Affine>, C::Affine) {
+    let hash_to_curve = |index: u64| {
+        let mut counter = 0u64;
+        loop {
+            let mut hasher = Sha3_512::new();
+            hasher.update(GENERATOR_DOMAIN);
+            hasher.update(index.to_le_bytes());
+            hasher.update(counter.to_le_bytes());
+            if let Some(point) = C::Affine::from_random_bytes(&hasher.finalize()) {
+                let point = point.clear_cofactor();
+                if !point.is_zero() {
+                    return point;
+                }
+            }
+            counter += 1;
+        }
+    };
+    let generators = (0..amount as u64).map(hash_to_curve).collect();
+    (generators, hash_to_curve(u64::MAX))
+}
+
+pub(crate) fn commit<C: CurveGroup>(
+    values: &[C::ScalarField],
+    randomness: C::ScalarField,
+    generators: &[C::Affine],
+    h: &C::Affine,
+) -> C {
+    C::msm_unchecked(generators, values) + *h * randomness
+}
+
+/// Shares the input with Rep3 like [rep3::share_field_elements] and additionally produces a [SharingProof] for every party.
+#[expect(clippy::type_complexity)]
+pub fn share_rep3_with_proof<C: CurveGroup, R: Rng + CryptoRng>(
+    input: &[C::ScalarField],
+    rng: &mut R,
+) -> [(Vec<Rep3PrimeFieldShare<C::ScalarField>>, SharingProof<C>); 3] {
+    let shares = rep3::share_field_elements(input, rng);
+    let (generators, h) = pedersen_generators::<C>(input.len());
+    let randomness: [C::ScalarField; 3] = std::array::from_fn(|_| C::ScalarField::rand(rng));
+    // the a component of party i is the i-th additive component
+    let commitments: [C; 3] = std::array::from_fn(|i| {
+        let values = shares[i].iter().map(|share| share.a).collect::<Vec<_>>();
+        commit(&values, randomness[i], &generators, &h)
+    });
+    let mut i = 0;
+    shares.map(|share| {
+        let proof = SharingProof {
+            commitments,
+            randomness_a: randomness[i],
+            randomness_b: randomness[(i + 2) % 3],
+        };
+        i += 1;
+        (share, proof)
+    })
+}
+
+impl<C: CurveGroup> SharingProof<C> {
+    /// Verifies that the share of the party with the given id matches the commitments. The parties additionally have
+    /// to make sure that they received the same commitments, e.g., by comparing their [SharingProof::digest]s.
+    pub fn verify(
+        &self,
+        share: &[Rep3PrimeFieldShare<C::ScalarField>],
+        id: PartyID,
+    ) -> eyre::Result<()> {
+        let (generators, h) = pedersen_generators::<C>(share.len());
+        let (a, b): (Vec<_>, Vec<_>) = share.iter().map(|share| (share.a, share.b)).unzip();
+        let own = usize::from(id);
+        let prev = usize::from(id.prev_id());
+        if commit::<C>(&a, self.randomness_a, &generators, &h) != self.commitments[own] {
+            eyre::bail!("share of party {own} does not match its commitment");
+        }
+        if commit::<C>(&b, self.randomness_b, &generators, &h) != self.commitments[prev] {
+            eyre::bail!("share of party {own} does not match the commitment of party {prev}");
+        }
+        Ok(())
+    }
+
+    /// Returns the Pedersen commitment to the shared value.
+    pub fn commitment(&self) -> C {
+        self.commitments.iter().sum()
+    }
+
+    /// Returns a digest of the commitments, which the parties compare to make sure they received the same commitments.
+    pub fn digest(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for commitment in self.commitments.iter() {
+            commitment
+                .into_affine()
+                .serialize_compressed(&mut bytes)
+                .expect("can serialize into vec");
+        }
+        Sha3_256::digest(&bytes).to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sharing_proof_verifies() {
+        let mut rng = rand::thread_rng();
+        let input = (0..10)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let shares = share_rep3_with_proof::<ark_bn254::G1Projective, _>(&input, &mut rng);
+        let ids = [PartyID::ID0, PartyID::ID1, PartyID::ID2];
+        for ((share, proof), id) in shares.iter().zip(ids) {
+            proof.verify(share, id).unwrap();
+            assert_eq!(proof.digest(), shares[0].1.digest());
+        }
+
+        // a provider handing out an inconsistent share is caught
+        let (mut share, proof) = shares[1].clone();
+        share[3].b += ark_bn254::Fr::from(1u64);
+        assert!(proof.verify(&share, PartyID::ID1).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! The optional witness pre-check of the provers. Before spending time on the expensive parts of the proof generation,
+//! the parties can check in MPC that the shared witness actually satisfies the constraints of the circuit. The
+//! constraints are split into batches and for every batch a random linear combination of the evaluated constraints is
+//! opened. It is zero if all constraints in the batch are satisfied and non-zero with overwhelming probability otherwise.
+
+use std::ops::Range;
+
+use ark_ff::PrimeField;
+
+/// The parameters of the witness pre-check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WitnessCheck {
+    /// The number of constraints that are combined into a single opened value.
+    pub batch_size: usize,
+}
+
+impl Default for WitnessCheck {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_BATCH_SIZE)
+    }
+}
+
+/// The error returned by the witness pre-check if the witness does not satisfy the constraints of the circuit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsatisfiedConstraintsError {
+    /// The index of the first batch that is not satisfied.
+    pub batch: usize,
+    /// The constraints of that batch.
+    pub constraints: Range<usize>,
+}
+
+impl std::error::Error for UnsatisfiedConstraintsError {}
+
+impl std::fmt::Display for UnsatisfiedConstraintsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "witness does not satisfy the constraints of batch {} (constraints {}..{})",
+            self.batch, self.constraints.start, self.constraints.end
+        )
+    }
+}
+
+impl WitnessCheck {
+    /// The default number of constraints per batch.
+    pub const DEFAULT_BATCH_SIZE: usize = 1 << 16;
+
+    /// Creates a new [WitnessCheck] with the given batch size.
+    pub fn new(batch_size: usize) -> Self {
+        assert!(batch_size > 0, "batch size must be positive");
+        Self { batch_size }
+    }
+
+    /// Returns the ranges of the constraints in each batch.
+    pub fn batches(&self, num_constraints: usize) -> impl Iterator<Item = Range<usize>> + '_ {
+        (0..num_constraints)
+            .step_by(self.batch_size)
+            .map(move |start| start..num_constraints.min(start + self.batch_size))
+    }
+
+    /// Returns the coefficients of the random linear combinations, i.e., the powers of the jointly opened challenge.
+    pub fn coefficients<F: PrimeField>(&self, challenge: F, num_constraints: usize) -> Vec<F> {
+        let mut coefficients = Vec::with_capacity(num_constraints);
+        let mut current = F::ONE;
+        for _ in 0..num_constraints {
+            coefficients.push(current);
+            current *= challenge;
+        }
+        coefficients
+    }
+
+    /// Checks the opened random linear combinations of all batches and names the first batch that is not satisfied.
+    pub fn verify<F: PrimeField>(
+        &self,
+        opened: &[F],
+        num_constraints: usize,
+    ) -> Result<(), UnsatisfiedConstraintsError> {
+        match self
+            .batches(num_constraints)
+            .zip(opened)
+            .enumerate()
+            .find(|(_, (_, value))| **value != F::ZERO)
+        {
+            Some((batch, (constraints, _))) => {
+                Err(UnsatisfiedConstraintsError { batch, constraints })
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn names_first_unsatisfied_batch() {
+        let check = WitnessCheck::new(4);
+        assert_eq!(
+            check.batches(10).collect::<Vec<_>>(),
+            vec![0..4, 4..8, 8..10]
+        );
+        let opened = [Fr::from(0u64), Fr::from(3u64), Fr::from(5u64)];
+        assert_eq!(
+            check.verify(&opened, 10),
+            Err(UnsatisfiedConstraintsError {
+                batch: 1,
+                constraints: 4..8
+            })
+        );
+        assert!(check.verify(&[Fr::from(0u64); 3], 10).is_ok());
+    }
+}
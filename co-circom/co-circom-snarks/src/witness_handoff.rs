@@ -0,0 +1,141 @@
+//! The handoff of a Shamir-shared witness from one committee of MPC nodes to another. The parties of the old committee
+//! reshare their witness shares towards the new committee without reconstructing the witness (see
+//! [mpc_core::protocols::shamir::reshare]). Each party of the old committee produces one [ShamirWitnessHandoff] per
+//! party of the new committee, which can be serialized and delivered over an arbitrary channel.
+
+use ark_ff::PrimeField;
+use mpc_core::protocols::shamir::{
+    reshare::{self, ShamirReshareMessage},
+    ShamirPrimeFieldShare,
+};
+use rand::{CryptoRng, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::SharedWitness;
+
+/// The message a party of the old committee sends to a party of the new committee during the handoff of a witness.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShamirWitnessHandoff<F: PrimeField> {
+    /// The public inputs of the witness, including the constant 1 at position 0.
+    #[serde(
+        serialize_with = "mpc_core::ark_se",
+        deserialize_with = "mpc_core::ark_de"
+    )]
+    pub public_inputs: Vec<F>,
+    /// The number of shared public inputs, which precede the shared witness elements in the message.
+    pub num_shared_public_inputs: usize,
+    /// The reshared shared public inputs and witness elements.
+    #[serde(
+        serialize_with = "mpc_core::ark_se",
+        deserialize_with = "mpc_core::ark_de"
+    )]
+    pub message: ShamirReshareMessage<F>,
+}
+
+impl<F: PrimeField> SharedWitness<F, ShamirPrimeFieldShare<F>> {
+    /// Reshares the witness of the party with id `old_id` towards a new committee of `new_num_parties` parties with
+    /// threshold `new_threshold`. `old_committee` contains the ids of the threshold + 1 parties of the old committee
+    /// which take part in the handoff and has to be the same for all of them. Returns one message per party of the new
+    /// committee, where the i-th message has to be delivered to party i.
+    pub fn reshare_shamir<R: Rng + CryptoRng>(
+        &self,
+        old_id: usize,
+        old_committee: &[usize],
+        new_threshold: usize,
+        new_num_parties: usize,
+        rng: &mut R,
+    ) -> eyre::Result<Vec<ShamirWitnessHandoff<F>>> {
+        let mut shares = self.shared_public_inputs.clone();
+        shares.extend_from_slice(&self.witness);
+        let messages = reshare::reshare_field_elements(
+            &shares,
+            old_id,
+            old_committee,
+            new_threshold,
+            new_num_parties,
+            rng,
+        )?;
+        Ok(messages
+            .into_iter()
+            .map(|message| ShamirWitnessHandoff {
+                public_inputs: self.public_inputs.clone(),
+                num_shared_public_inputs: self.shared_public_inputs.len(),
+                message,
+            })
+            .collect())
+    }
+
+    /// Combines the handoff messages received by the party with id `new_id` of the new committee into its share of the
+    /// witness. Exactly one message of each of the `old_threshold` + 1 participating parties of the old committee is
+    /// required.
+    pub fn from_handoff_shamir(
+        handoffs: Vec<ShamirWitnessHandoff<F>>,
+        new_id: usize,
+        old_threshold: usize,
+    ) -> eyre::Result<Self> {
+        let Some(first) = handoffs.first() else {
+            eyre::bail!("no handoff messages received");
+        };
+        let public_inputs = first.public_inputs.clone();
+        let num_shared_public_inputs = first.num_shared_public_inputs;
+        if handoffs.iter().any(|h| {
+            h.public_inputs != public_inputs
+                || h.num_shared_public_inputs != num_shared_public_inputs
+        }) {
+            eyre::bail!("handoff messages disagree on the public inputs");
+        }
+        let messages = handoffs.into_iter().map(|h| h.message).collect::<Vec<_>>();
+        let mut witness = reshare::combine_reshare_messages(&messages, new_id, old_threshold)?;
+        if witness.len() < num_shared_public_inputs {
+            eyre::bail!("handoff messages contain too few shares");
+        }
+        let shared_public_inputs = witness.drain(..num_shared_public_inputs).collect();
+        Ok(Self {
+            public_inputs,
+            shared_public_inputs,
+            witness,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use circom_types::Witness;
+    use mpc_core::protocols::shamir;
+    use rand::thread_rng;
+
+    #[test]
+    fn handoff_to_larger_committee() {
+        let mut rng = thread_rng();
+        let values = (0..8).map(|i| Fr::from(i as u64 + 1)).collect::<Vec<_>>();
+        let witness = Witness {
+            values: values.clone(),
+        };
+        let old = SharedWitness::share_shamir(witness, 3, 1, 3, &mut rng);
+
+        // parties 0 and 2 of the old committee (n = 3, t = 1) hand off to a new committee with n = 5, t = 2
+        let old_committee = [0, 2];
+        let mut inboxes: Vec<Vec<ShamirWitnessHandoff<Fr>>> = vec![Vec::new(); 5];
+        for id in old_committee {
+            let handoffs = old[id]
+                .reshare_shamir(id, &old_committee, 2, 5, &mut rng)
+                .unwrap();
+            for (inbox, handoff) in inboxes.iter_mut().zip(handoffs) {
+                let bytes = bincode::serialize(&handoff).unwrap();
+                inbox.push(bincode::deserialize(&bytes).unwrap());
+            }
+        }
+        let new = inboxes
+            .into_iter()
+            .enumerate()
+            .map(|(id, inbox)| SharedWitness::from_handoff_shamir(inbox, id, 1).unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(new.iter().all(|w| w.public_inputs == values[..3]));
+        let shares = new.iter().map(|w| w.witness.clone()).collect::<Vec<_>>();
+        let reconstructed = shamir::combine_field_elements(&shares[2..], &[3, 4, 5], 2).unwrap();
+        assert_eq!(reconstructed, values[3..]);
+    }
+}
@@ -1,5 +1,7 @@
+use ark_bls12_377::Bls12_377;
 use ark_bls12_381::Bls12_381;
 use ark_bn254::Bn254;
+use ark_bw6_761::BW6_761;
 use ark_ec::pairing::Pairing;
 use ark_ff::PrimeField;
 use circom_types::R1CS;
@@ -7,8 +9,9 @@ use num_traits::Zero;
 use std::sync::Arc;
 
 use circom_types::{
+    binary::BinaryFormat,
     groth16::{
-        Groth16Proof, JsonVerificationKey as Groth16JsonVerificationKey, ZKey as Groth16ZKey,
+        Groth16Proof, JsonPublicInput, JsonVerificationKey as Groth16JsonVerificationKey, MmapZKey,
     },
     plonk::{JsonVerificationKey as PlonkJsonVerificationKey, PlonkProof, ZKey as PlonkZKey},
     traits::{CheckElement, CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
@@ -19,6 +22,8 @@ use co_circom::GenerateProofCli;
 use co_circom::GenerateProofConfig;
 use co_circom::GenerateWitnessCli;
 use co_circom::GenerateWitnessConfig;
+use co_circom::InspectShareCli;
+use co_circom::InspectShareConfig;
 use co_circom::MergeInputSharesCli;
 use co_circom::MergeInputSharesConfig;
 use co_circom::SplitInputCli;
@@ -29,9 +34,9 @@ use co_circom::TranslateWitnessCli;
 use co_circom::TranslateWitnessConfig;
 use co_circom::VerifyCli;
 use co_circom::VerifyConfig;
-use co_circom::{file_utils, MPCCurve, MPCProtocol, ProofSystem, SeedRng};
+use co_circom::{file_utils, MPCCurve, MPCProtocol, ProofFormat, ProofSystem, SeedRng};
 use co_circom_snarks::{
-    SerializeableSharedRep3Input, SerializeableSharedRep3Witness, SharedWitness, VerificationError,
+    SerializeableSharedRep3Witness, ShareCurve, ShareMetadata, SharedWitness, VerificationError,
 };
 use co_groth16::Groth16;
 use co_groth16::{Rep3CoGroth16, ShamirCoGroth16};
@@ -44,10 +49,12 @@ use mpc_core::protocols::{
     shamir::{ShamirPreprocessing, ShamirProtocol},
 };
 use mpc_core::protocols::{rep3::network::Rep3Network, shamir::ShamirPrimeFieldShare};
+use mpc_net::config::Phase;
+use serde::{de::DeserializeOwned, Serialize};
 use std::time::Instant;
 use std::{
     fs::File,
-    io::{BufReader, BufWriter},
+    io::{BufRead, BufReader, BufWriter, Read, Write},
     path::PathBuf,
     process::ExitCode,
 };
@@ -95,6 +102,8 @@ enum Commands {
     GenerateProof(GenerateProofCli),
     /// Verification of a circom proof.
     Verify(VerifyCli),
+    /// Prints the metadata of a share file, without revealing any shares
+    InspectShare(InspectShareCli),
 }
 
 fn main() -> color_eyre::Result<ExitCode> {
@@ -110,6 +119,8 @@ fn main() -> color_eyre::Result<ExitCode> {
             match config.curve {
                 MPCCurve::BN254 => run_split_witness::<Bn254>(config),
                 MPCCurve::BLS12_381 => run_split_witness::<Bls12_381>(config),
+                MPCCurve::BLS12_377 => run_split_witness::<Bls12_377>(config),
+                MPCCurve::BW6_761 => run_split_witness::<BW6_761>(config),
             }
         }
         Commands::SplitInput(cli) => {
@@ -117,6 +128,8 @@ fn main() -> color_eyre::Result<ExitCode> {
             match config.curve {
                 MPCCurve::BN254 => run_split_input::<Bn254>(config),
                 MPCCurve::BLS12_381 => run_split_input::<Bls12_381>(config),
+                MPCCurve::BLS12_377 => run_split_input::<Bls12_377>(config),
+                MPCCurve::BW6_761 => run_split_input::<BW6_761>(config),
             }
         }
         Commands::MergeInputShares(cli) => {
@@ -124,6 +137,8 @@ fn main() -> color_eyre::Result<ExitCode> {
             match config.curve {
                 MPCCurve::BN254 => run_merge_input_shares::<Bn254>(config),
                 MPCCurve::BLS12_381 => run_merge_input_shares::<Bls12_381>(config),
+                MPCCurve::BLS12_377 => run_merge_input_shares::<Bls12_377>(config),
+                MPCCurve::BW6_761 => run_merge_input_shares::<BW6_761>(config),
             }
         }
         Commands::GenerateWitness(cli) => {
@@ -131,6 +146,8 @@ fn main() -> color_eyre::Result<ExitCode> {
             match config.curve {
                 MPCCurve::BN254 => run_generate_witness::<Bn254>(config),
                 MPCCurve::BLS12_381 => run_generate_witness::<Bls12_381>(config),
+                MPCCurve::BLS12_377 => run_generate_witness::<Bls12_377>(config),
+                MPCCurve::BW6_761 => run_generate_witness::<BW6_761>(config),
             }
         }
         Commands::TranslateWitness(cli) => {
@@ -138,6 +155,8 @@ fn main() -> color_eyre::Result<ExitCode> {
             match config.curve {
                 MPCCurve::BN254 => run_translate_witness::<Bn254>(config),
                 MPCCurve::BLS12_381 => run_translate_witness::<Bls12_381>(config),
+                MPCCurve::BLS12_377 => run_translate_witness::<Bls12_377>(config),
+                MPCCurve::BW6_761 => run_translate_witness::<BW6_761>(config),
             }
         }
         Commands::GenerateProof(cli) => {
@@ -145,6 +164,8 @@ fn main() -> color_eyre::Result<ExitCode> {
             match config.curve {
                 MPCCurve::BN254 => run_generate_proof::<Bn254>(config),
                 MPCCurve::BLS12_381 => run_generate_proof::<Bls12_381>(config),
+                MPCCurve::BLS12_377 => run_generate_proof::<Bls12_377>(config),
+                MPCCurve::BW6_761 => run_generate_proof::<BW6_761>(config),
             }
         }
         Commands::Verify(cli) => {
@@ -152,8 +173,14 @@ fn main() -> color_eyre::Result<ExitCode> {
             match config.curve {
                 MPCCurve::BN254 => run_verify::<Bn254>(config),
                 MPCCurve::BLS12_381 => run_verify::<Bls12_381>(config),
+                MPCCurve::BLS12_377 => run_verify::<Bls12_377>(config),
+                MPCCurve::BW6_761 => run_verify::<BW6_761>(config),
             }
         }
+        Commands::InspectShare(cli) => {
+            let config = InspectShareConfig::parse(cli).context("while parsing config")?;
+            run_inspect_share(config)
+        }
     }
 }
 
@@ -165,9 +192,11 @@ where
     P::ScalarField: CircomArkworksPrimeFieldBridge,
     P::BaseField: CircomArkworksPrimeFieldBridge,
 {
+    let compression = config.compression();
     let witness_path = config.witness;
     let r1cs = config.r1cs;
     let protocol = config.protocol;
+    let curve = ShareCurve::from(config.curve);
     let out_dir = config.out_dir;
     let t = config.threshold;
     let n = config.num_parties;
@@ -202,8 +231,9 @@ where
                 witness,
                 r1cs.num_inputs,
                 &mut rng,
-                config.seeded,
+                compression,
                 config.additive,
+                config.hide_public_inputs,
             );
             let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
             tracing::info!("Sharing took {} ms", duration_ms);
@@ -214,16 +244,21 @@ where
                 .context("we have a file name")?
                 .to_str()
                 .context("witness file name is not valid UTF-8")?;
-            for (i, share) in shares.iter().enumerate() {
+            for (i, share) in shares.into_iter().enumerate() {
                 let path = out_dir.join(format!("{}.{}.shared", base_name, i));
                 let out_file =
                     BufWriter::new(File::create(&path).context("while creating output file")?);
-                bincode::serialize_into(out_file, share)
+                co_circom::write_witness_share_rep3(out_file, share, curve, compression)
                     .context("while serializing witness share")?;
                 tracing::info!("Wrote witness share {} to file {}", i, path.display());
             }
         }
         MPCProtocol::SHAMIR => {
+            if config.hide_public_inputs {
+                return Err(eyre!(
+                    "hiding public inputs is only supported for REP3, use translate-witness for SHAMIR"
+                ));
+            }
             // create witness shares
             let start = Instant::now();
             let shares =
@@ -247,7 +282,7 @@ where
                 let path = out_dir.join(format!("{}.{}.shared", base_name, i));
                 let out_file =
                     BufWriter::new(File::create(&path).context("while creating output file")?);
-                bincode::serialize_into(out_file, share)
+                co_circom::write_share_file(out_file, &share.metadata(curve), share)
                     .context("while serializing witness share")?;
                 tracing::info!("Wrote witness share {} to file {}", i, path.display());
             }
@@ -268,6 +303,7 @@ where
     let input = config.input;
     let circuit = config.circuit;
     let protocol = config.protocol;
+    let curve = ShareCurve::from(config.curve);
     let out_dir = config.out_dir;
 
     if protocol != MPCProtocol::REP3 {
@@ -287,6 +323,8 @@ where
         config.compiler,
         config.seeded,
         config.additive,
+        &config.shamir_inputs,
+        &config.input_types,
     )?;
     let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
     tracing::info!("Sharing took {} ms", duration_ms);
@@ -300,7 +338,8 @@ where
     for (i, share) in shares.iter().enumerate() {
         let path = out_dir.join(format!("{}.{}.shared", base_name, i));
         let out_file = BufWriter::new(File::create(&path).context("while creating output file")?);
-        bincode::serialize_into(out_file, share).context("while serializing witness share")?;
+        co_circom::write_share_file(out_file, &share.metadata(curve), share)
+            .context("while serializing input share")?;
         tracing::info!("Wrote input share {} to file {}", i, path.display());
     }
     tracing::info!("Split input into shares successfully");
@@ -332,7 +371,7 @@ where
         file_utils::check_file_exists(input)?;
     }
 
-    merge_input_shares::<P::ScalarField>(inputs, out)?;
+    merge_input_shares::<P::ScalarField>(inputs, out, ShareCurve::from(config.curve))?;
 
     Ok(ExitCode::SUCCESS)
 }
@@ -348,6 +387,7 @@ where
     let input = config.input.clone();
     let circuit = config.circuit.clone();
     let protocol = config.protocol;
+    let curve = ShareCurve::from(config.curve);
     let out = config.out.clone();
 
     if protocol != MPCProtocol::REP3 {
@@ -373,13 +413,28 @@ where
     let input_share = co_circom::parse_shared_input(input_share_file, &mut mpc_net)
         .context("while parsing input")?;
 
+    // check the proofs of correct sharing of the data providers
+    co_circom::verify_sharing_proofs::<P::G1, _>(
+        &input_share,
+        &config.sharing_proofs,
+        &mut mpc_net,
+    )
+    .context("while verifying sharing proofs")?;
+
+    // check the declared bit widths of the inputs
+    co_circom::range_check_shared_input(&input_share, &config.range_checks, &mut mpc_net)
+        .context("while range checking input")?;
+
     // Extend the witness
+    mpc_net
+        .start_phase(Phase::WitnessExtension)
+        .context("while starting witness extension")?;
     let result_witness_share =
         co_circom::generate_witness_rep3::<P, SeedRng>(circuit, input_share, mpc_net, config)?;
 
     // write result to output file
     let out_file = BufWriter::new(std::fs::File::create(&out)?);
-    bincode::serialize_into(out_file, &result_witness_share)?;
+    co_circom::write_generated_witness_rep3(out_file, &result_witness_share, curve)?;
     tracing::info!("Witness successfully written to {}", out.display());
     Ok(ExitCode::SUCCESS)
 }
@@ -395,6 +450,7 @@ where
     let witness = config.witness;
     let src_protocol = config.src_protocol;
     let target_protocol = config.target_protocol;
+    let curve = ShareCurve::from(config.curve);
     let out = config.out;
 
     if src_protocol != MPCProtocol::REP3 || target_protocol != MPCProtocol::SHAMIR {
@@ -419,7 +475,7 @@ where
 
     // init MPC protocol
     let threshold = 1;
-    let num_pairs = witness_share.witness.len();
+    let num_pairs = witness_share.witness.len() + witness_share.shared_public_inputs.len();
     let preprocessing = ShamirPreprocessing::new(threshold, net.to_shamir_net(), num_pairs)
         .context("while shamir preprocessing")?;
     let mut protocol = ShamirProtocol::from(preprocessing);
@@ -428,9 +484,13 @@ where
     let translated_witness = protocol
         .translate_primefield_addshare_vec(witness_share.witness)
         .context("while translating witness")?;
+    let translated_public_inputs = protocol
+        .translate_primefield_addshare_vec(witness_share.shared_public_inputs)
+        .context("while translating hidden public inputs")?;
     let shamir_witness_share: SharedWitness<P::ScalarField, ShamirPrimeFieldShare<P::ScalarField>> =
         SharedWitness {
             public_inputs: witness_share.public_inputs,
+            shared_public_inputs: translated_public_inputs,
             witness: translated_witness,
         };
     let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
@@ -438,11 +498,54 @@ where
 
     // write result to output file
     let out_file = BufWriter::new(std::fs::File::create(&out)?);
-    bincode::serialize_into(out_file, &shamir_witness_share)?;
+    co_circom::write_share_file(
+        out_file,
+        &shamir_witness_share.metadata(curve),
+        &shamir_witness_share,
+    )?;
     tracing::info!("Witness successfully written to {}", out.display());
     Ok(ExitCode::SUCCESS)
 }
 
+/// Writes a proof in the requested format.
+fn write_in_format<T: Serialize + BinaryFormat, W: Write>(
+    value: &T,
+    writer: W,
+    format: ProofFormat,
+) -> color_eyre::Result<()> {
+    match format {
+        ProofFormat::Json => serde_json::to_writer(writer, value)?,
+        ProofFormat::Binary => value.to_binary_writer(writer)?,
+    }
+    Ok(())
+}
+
+/// Reads a proof in the requested format.
+fn read_in_format<T: DeserializeOwned + BinaryFormat, R: Read>(
+    reader: R,
+    format: ProofFormat,
+) -> color_eyre::Result<T> {
+    Ok(match format {
+        ProofFormat::Json => serde_json::from_reader(reader)?,
+        ProofFormat::Binary => T::from_binary_reader(reader)?,
+    })
+}
+
+/// Reads a verification key, detecting whether it is in the JSON or the binary format.
+fn read_vk<T: DeserializeOwned + BinaryFormat, R: BufRead>(mut reader: R) -> color_eyre::Result<T> {
+    let is_json = reader
+        .fill_buf()?
+        .iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .is_some_and(|b| *b == b'{');
+    let format = if is_json {
+        ProofFormat::Json
+    } else {
+        ProofFormat::Binary
+    };
+    read_in_format(reader, format)
+}
+
 #[instrument(level = "debug", skip(config))]
 fn run_generate_proof<P: Pairing + CircomArkworksPairingBridge>(
     config: GenerateProofConfig,
@@ -458,17 +561,24 @@ where
     let out = config.out;
     let public_input_filename = config.public_input;
     let t = config.threshold;
+    let fft_mode = config.fft_mode;
+    let format = config.format;
 
     file_utils::check_file_exists(&witness)?;
     file_utils::check_file_exists(&zkey)?;
 
+    // a fully public witness is proven locally, without connecting to the other parties
+    let public_witness = co_circom::parse_public_witness_rep3::<_, P::ScalarField>(
+        BufReader::new(File::open(&witness).context("trying to open witness share file")?),
+    )?;
+    if public_witness.is_some() {
+        tracing::info!("witness is fully public, creating the proof locally without MPC");
+    }
+
     // parse witness shares
     let witness_file =
         BufReader::new(File::open(witness).context("trying to open witness share file")?);
 
-    // parse Circom zkey file
-    let zkey_file = File::open(zkey)?;
-
     let network_config = config
         .network
         .to_owned()
@@ -478,39 +588,42 @@ where
     let public_input = match proof_system {
         ProofSystem::Groth16 => {
             let zkey = Arc::new(
-                Groth16ZKey::<P>::from_reader(zkey_file, CheckElement::Yes)
+                // memory-mapping the zkey avoids holding the raw file and the parsed points at the same time
+                MmapZKey::<P>::open(&zkey, CheckElement::Yes)
+                    .and_then(MmapZKey::into_zkey)
                     .context("reading zkey")?,
             );
 
-            let (proof, public_input) = match protocol {
-                MPCProtocol::REP3 => {
+            let (proof, public_input) = match (protocol, public_witness) {
+                (_, Some(public_witness)) => {
+                    Groth16::<P>::plain_prove_with_public_inputs(zkey, public_witness)?
+                }
+                (MPCProtocol::REP3, None) => {
                     if t != 1 {
                         return Err(eyre!("REP3 only allows the threshold to be 1"));
                     }
 
                     let mut mpc_net = Rep3MpcNet::new(network_config)?;
+                    mpc_net.start_phase(Phase::ProofAssembly)?;
                     let witness_share =
                         co_circom::parse_witness_share_rep3(witness_file, &mut mpc_net)?;
-                    let public_input = witness_share.public_inputs.clone();
                     // connect to network
-                    let prover =
-                        Rep3CoGroth16::with_network(mpc_net).context("while building prover")?;
+                    let prover = Rep3CoGroth16::with_network(mpc_net)
+                        .context("while building prover")?
+                        .with_fft_mode(fft_mode.into());
 
                     // execute prover in MPC
-                    let proof = prover.prove(zkey, witness_share)?;
-                    (proof, public_input)
+                    prover.prove_with_public_inputs(zkey, witness_share)?
                 }
-                MPCProtocol::SHAMIR => {
+                (MPCProtocol::SHAMIR, None) => {
                     let witness_share = co_circom::parse_witness_share_shamir(witness_file)?;
-                    let public_input = witness_share.public_inputs.clone();
 
                     // connect to network
                     let prover = ShamirCoGroth16::with_network_config(t, network_config)
                         .context("while building prover")?;
 
                     // execute prover in MPC
-                    let proof = prover.prove(zkey, witness_share)?;
-                    (proof, public_input)
+                    prover.prove_with_public_inputs(zkey, witness_share)?
                 }
             };
 
@@ -520,49 +633,50 @@ where
                     std::fs::File::create(&out).context("while creating output file")?,
                 );
 
-                serde_json::to_writer(out_file, &proof)
-                    .context("while serializing proof to JSON file")?;
+                write_in_format(&proof, out_file, format)
+                    .context("while serializing proof to file")?;
                 tracing::info!("Wrote proof to file {}", out.display());
             }
             public_input
         }
         ProofSystem::Plonk => {
+            // parse Circom zkey file
+            let zkey_file = File::open(zkey)?;
             let zkey = Arc::new(
                 PlonkZKey::<P>::from_reader(zkey_file, CheckElement::Yes)
                     .context("while parsing zkey")?,
             );
 
-            let (proof, public_input) = match protocol {
-                MPCProtocol::REP3 => {
+            let (proof, public_input) = match (protocol, public_witness) {
+                (_, Some(public_witness)) => {
+                    Plonk::<P>::plain_prove_with_public_inputs(zkey, public_witness)?
+                }
+                (MPCProtocol::REP3, None) => {
                     if t != 1 {
                         return Err(eyre!("REP3 only allows the threshold to be 1"));
                     }
 
                     let mut mpc_net = Rep3MpcNet::new(network_config)?;
+                    mpc_net.start_phase(Phase::ProofAssembly)?;
                     let witness_share =
                         co_circom::parse_witness_share_rep3(witness_file, &mut mpc_net)?;
 
-                    let public_input = witness_share.public_inputs.clone();
-
                     //init prover
                     let prover =
                         Rep3CoPlonk::with_network(mpc_net).context("while building prover")?;
 
                     // execute prover in MPC
-                    let proof = prover.prove(zkey, witness_share)?;
-                    (proof, public_input)
+                    prover.prove_with_public_inputs(zkey, witness_share)?
                 }
-                MPCProtocol::SHAMIR => {
+                (MPCProtocol::SHAMIR, None) => {
                     let witness_share = co_circom::parse_witness_share_shamir(witness_file)?;
-                    let public_input = witness_share.public_inputs.clone();
 
                     //init prover
                     let prover = ShamirCoPlonk::with_network_config(t, network_config, &zkey)
                         .context("while building prover")?;
 
                     // execute prover in MPC
-                    let proof = prover.prove(zkey, witness_share)?;
-                    (proof, public_input)
+                    prover.prove_with_public_inputs(zkey, witness_share)?
                 }
             };
 
@@ -572,8 +686,8 @@ where
                     std::fs::File::create(&out).context("while creating output file")?,
                 );
 
-                serde_json::to_writer(out_file, &proof)
-                    .context("while serializing proof to JSON file")?;
+                write_in_format(&proof, out_file, format)
+                    .context("while serializing proof to file")?;
                 tracing::info!("Wrote proof to file {}", out.display());
             }
             public_input
@@ -582,23 +696,34 @@ where
 
     // write public input to output file
     if let Some(public_input_filename) = public_input_filename {
-        let public_input_as_strings = public_input
-            .iter()
-            .skip(1) // we skip the constant 1 at position 0
-            .map(|f| {
-                if f.is_zero() {
-                    "0".to_string()
-                } else {
-                    f.to_string()
-                }
-            })
-            .collect::<Vec<String>>();
         let public_input_file = BufWriter::new(
             std::fs::File::create(&public_input_filename)
                 .context("while creating public input file")?,
         );
-        serde_json::to_writer(public_input_file, &public_input_as_strings)
-            .context("while writing out public inputs to JSON file")?;
+        match format {
+            ProofFormat::Json => {
+                let public_input_as_strings = public_input
+                    .iter()
+                    .map(|f| {
+                        if f.is_zero() {
+                            "0".to_string()
+                        } else {
+                            f.to_string()
+                        }
+                    })
+                    .collect::<Vec<String>>();
+                serde_json::to_writer(public_input_file, &public_input_as_strings)
+                    .context("while writing out public inputs to JSON file")?;
+            }
+            ProofFormat::Binary => {
+                let public_input = JsonPublicInput::<P::ScalarField> {
+                    values: public_input,
+                };
+                public_input
+                    .to_binary_writer(public_input_file)
+                    .context("while writing out public inputs to binary file")?;
+            }
+        }
         tracing::info!(
             "Wrote public inputs to file {}",
             public_input_filename.display()
@@ -620,6 +745,7 @@ where
     let proof = config.proof;
     let vk = config.vk;
     let public_input = config.public_input;
+    let format = config.format;
 
     file_utils::check_file_exists(&proof)?;
     file_utils::check_file_exists(&vk)?;
@@ -634,28 +760,37 @@ where
     // parse public inputs
     let public_inputs_file =
         BufReader::new(File::open(&public_input).context("while opening public inputs file")?);
-    let public_inputs_as_strings: Vec<String> = serde_json::from_reader(public_inputs_file)
-        .context(
-            "while parsing public inputs, expect them to be array of stringified field elements",
-        )?;
-    // skip 1 atm
-    let public_inputs = public_inputs_as_strings
-        .into_iter()
-        .map(|s| {
-            s.parse::<P::ScalarField>()
-                .map_err(|_| eyre!("could not parse as field element: {}", s))
-        })
-        .collect::<Result<Vec<P::ScalarField>, _>>()
-        .context("while converting public input strings to field elements")?;
+    let public_inputs = match format {
+        ProofFormat::Json => {
+            let public_inputs_as_strings: Vec<String> =
+                serde_json::from_reader(public_inputs_file).context(
+                    "while parsing public inputs, expect them to be array of stringified field elements",
+                )?;
+            // skip 1 atm
+            public_inputs_as_strings
+                .into_iter()
+                .map(|s| {
+                    s.parse::<P::ScalarField>()
+                        .map_err(|_| eyre!("could not parse as field element: {}", s))
+                })
+                .collect::<Result<Vec<P::ScalarField>, _>>()
+                .context("while converting public input strings to field elements")?
+        }
+        ProofFormat::Binary => {
+            JsonPublicInput::<P::ScalarField>::from_binary_reader(public_inputs_file)
+                .context("while parsing public inputs from binary file")?
+                .values
+        }
+    };
 
     // verify proof
     let res = match proofsystem {
         ProofSystem::Groth16 => {
-            let proof: Groth16Proof<P> = serde_json::from_reader(proof_file)
+            let proof: Groth16Proof<P> = read_in_format(proof_file, format)
                 .context("while deserializing proof from file")?;
 
-            let vk: Groth16JsonVerificationKey<P> = serde_json::from_reader(vk_file)
-                .context("while deserializing verification key from file")?;
+            let vk: Groth16JsonVerificationKey<P> =
+                read_vk(vk_file).context("while deserializing verification key from file")?;
 
             // The actual verifier
             let start = Instant::now();
@@ -665,11 +800,11 @@ where
             res
         }
         ProofSystem::Plonk => {
-            let proof: PlonkProof<P> = serde_json::from_reader(proof_file)
+            let proof: PlonkProof<P> = read_in_format(proof_file, format)
                 .context("while deserializing proof from file")?;
 
-            let vk: PlonkJsonVerificationKey<P> = serde_json::from_reader(vk_file)
-                .context("while deserializing verification key from file")?;
+            let vk: PlonkJsonVerificationKey<P> =
+                read_vk(vk_file).context("while deserializing verification key from file")?;
 
             // The actual verifier
             let start = Instant::now();
@@ -693,16 +828,46 @@ where
     }
 }
 
-fn merge_input_shares<F: PrimeField>(inputs: Vec<PathBuf>, out: PathBuf) -> color_eyre::Result<()> {
+#[instrument(level = "debug", skip(config))]
+fn run_inspect_share(config: InspectShareConfig) -> color_eyre::Result<ExitCode> {
+    let share = config.share;
+    file_utils::check_file_exists(&share)?;
+
+    let share_file = BufReader::new(File::open(&share).context("while opening share file")?);
+    let (metadata, _) =
+        ShareMetadata::probe_or_legacy(share_file).context("while reading share file header")?;
+    tracing::info!("Share file {}:", share.display());
+    let Some(metadata) = metadata else {
+        tracing::info!("  legacy share file without header, written before version 1");
+        return Ok(ExitCode::SUCCESS);
+    };
+    tracing::info!("  version:           {}", metadata.version);
+    tracing::info!("  kind:              {}", metadata.kind);
+    tracing::info!("  curve:             {}", metadata.curve);
+    tracing::info!("  protocol:          {}", metadata.protocol);
+    tracing::info!("  public inputs:     {}", metadata.num_public_inputs);
+    tracing::info!("  shared public:     {}", metadata.num_shared_public_inputs);
+    tracing::info!("  shared elements:   {}", metadata.num_shared);
+    tracing::info!("  seeded:            {}", metadata.seeded);
+    tracing::info!("  additive:          {}", metadata.additive);
+    tracing::info!("  compressed:        {}", metadata.compressed);
+    tracing::info!("  public witness:    {}", metadata.public_witness);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn merge_input_shares<F: PrimeField>(
+    inputs: Vec<PathBuf>,
+    out: PathBuf,
+    curve: ShareCurve,
+) -> color_eyre::Result<()> {
     let start = Instant::now();
     let mut input_shares = inputs
         .iter()
         .map(|input| {
             let input_share_file =
                 BufReader::new(File::open(input).context("while opening input share file")?);
-            let input_share: SerializeableSharedRep3Input<F, SeedRng> =
-                bincode::deserialize_from(input_share_file)
-                    .context("trying to parse input share file")?;
+            let input_share =
+                co_circom::parse_serializeable_shared_input::<_, F>(input_share_file)?;
             color_eyre::Result::<_>::Ok(input_share)
         })
         .collect::<Result<Vec<_>, _>>()?;
@@ -714,7 +879,8 @@ fn merge_input_shares<F: PrimeField>(inputs: Vec<PathBuf>, out: PathBuf) -> colo
     tracing::info!("Merging took {} ms", duration_ms);
 
     let out_file = BufWriter::new(File::create(&out).context("while creating output file")?);
-    bincode::serialize_into(out_file, &merged).context("while serializing witness share")?;
+    co_circom::write_share_file(out_file, &merged.metadata(curve), &merged)
+        .context("while serializing input share")?;
     tracing::info!("Wrote merged input share to file {}", out.display());
     Ok(())
 }
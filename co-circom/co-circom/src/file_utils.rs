@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use ark_ff::PrimeField;
+use co_circom_snarks::InputType;
 use color_eyre::eyre::{self, Context, ContextCompat};
 use num_bigint::BigUint;
 use num_traits::Num;
@@ -109,6 +110,60 @@ pub(crate) fn parse_array<F: PrimeField>(
     Ok(field_elements)
 }
 
+/// Parses a structured input of the given [InputType] and flattens it in the order of the type, i.e., struct fields
+/// in the order of their declaration and not in the order of the JSON object.
+pub(crate) fn parse_typed<F: PrimeField>(
+    val: &serde_json::Value,
+    ty: &InputType,
+) -> color_eyre::Result<Vec<Option<F>>> {
+    match ty {
+        InputType::Field => {
+            if val.is_boolean() {
+                Ok(vec![Some(parse_boolean(val)?)])
+            } else if val.as_str().is_some_and(|e| e == "?") {
+                Ok(vec![None])
+            } else {
+                Ok(vec![Some(parse_field(val)?)])
+            }
+        }
+        InputType::Array { len, element } => {
+            let json_arr = val
+                .as_array()
+                .with_context(|| format!("expected input to be an array, got {val}"))?;
+            if json_arr.len() != *len {
+                eyre::bail!("expected array of length {len}, got {}", json_arr.len());
+            }
+            let mut field_elements = Vec::with_capacity(ty.size());
+            for ele in json_arr {
+                field_elements.extend(parse_typed::<F>(ele, element)?);
+            }
+            Ok(field_elements)
+        }
+        InputType::Struct { fields } => {
+            let json_obj = val
+                .as_object()
+                .with_context(|| format!("expected input to be an object, got {val}"))?;
+            if let Some(key) = json_obj
+                .keys()
+                .find(|key| !fields.iter().any(|field| &field.name == *key))
+            {
+                eyre::bail!("unknown field \"{key}\"");
+            }
+            let mut field_elements = Vec::with_capacity(ty.size());
+            for field in fields {
+                let ele = json_obj
+                    .get(&field.name)
+                    .with_context(|| format!("missing field \"{}\"", field.name))?;
+                field_elements.extend(
+                    parse_typed::<F>(ele, &field.ty)
+                        .with_context(|| format!("while parsing field \"{}\"", field.name))?,
+                );
+            }
+            Ok(field_elements)
+        }
+    }
+}
+
 pub(crate) fn parse_boolean<F: PrimeField>(val: &serde_json::Value) -> color_eyre::Result<F> {
     let bool = val
         .as_bool()
@@ -119,3 +174,66 @@ pub(crate) fn parse_boolean<F: PrimeField>(val: &serde_json::Value) -> color_eyr
         Ok(F::ZERO)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use co_circom_snarks::InputField;
+    use serde_json::json;
+
+    fn point_array() -> InputType {
+        let point = InputType::Struct {
+            fields: vec![
+                InputField::new("x", InputType::Field),
+                InputField::new("y", InputType::array(2, InputType::Field)),
+            ],
+        };
+        InputType::array(2, point)
+    }
+
+    #[test]
+    fn parse_typed_valid() {
+        // the fields are flattened in declaration order, regardless of their order in the JSON object
+        let val = json!([
+            { "y": ["2", "3"], "x": "1" },
+            { "x": "0x4", "y": [true, "?"] }
+        ]);
+        let parsed = parse_typed::<Fr>(&val, &point_array()).unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                Some(Fr::from(1u64)),
+                Some(Fr::from(2u64)),
+                Some(Fr::from(3u64)),
+                Some(Fr::from(4u64)),
+                Some(Fr::from(1u64)),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_typed_malformed() {
+        let ty = point_array();
+        for val in [
+            // wrong array length
+            json!([{ "x": "1", "y": ["2", "3"] }]),
+            // not an array
+            json!({ "x": "1", "y": ["2", "3"] }),
+            // not an object
+            json!(["1", "2"]),
+            // missing field
+            json!([{ "x": "1" }, { "x": "4", "y": ["5", "6"] }]),
+            // unknown field
+            json!([
+                { "x": "1", "y": ["2", "3"], "z": "0" },
+                { "x": "4", "y": ["5", "6"] }
+            ]),
+            // invalid field element
+            json!([{ "x": "abc", "y": ["2", "3"] }, { "x": "4", "y": ["5", "6"] }]),
+        ] {
+            assert!(parse_typed::<Fr>(&val, &ty).is_err(), "accepted {val}");
+        }
+    }
+}
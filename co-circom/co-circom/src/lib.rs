@@ -3,16 +3,20 @@
 use std::{
     collections::BTreeMap,
     fs::File,
-    io::{BufReader, Read},
+    io::{BufReader, Read, Write},
     path::PathBuf,
     sync::Arc,
     time::Instant,
 };
 
-use ark_ec::pairing::Pairing;
+use ark_ec::{pairing::Pairing, CurveGroup};
 use ark_ff::PrimeField;
+use ark_serialize::CanonicalDeserialize;
 use circom_mpc_compiler::{CoCircomCompiler, CompilerConfig, SimplificationLevel};
-use circom_mpc_vm::mpc_vm::VMConfig;
+use circom_mpc_vm::{
+    mpc::VmCircomWitnessExtension,
+    mpc_vm::{FinalizedWitnessExtension, VMConfig},
+};
 use circom_types::{
     groth16::{Groth16Proof, ZKey},
     traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
@@ -20,8 +24,11 @@ use circom_types::{
 use clap::Args;
 use clap::ValueEnum;
 use co_circom_snarks::{
-    SerializeableSharedRep3Input, SerializeableSharedRep3Witness, SharedInput, SharedWitness,
+    CompressionLevel, InputCommitment, InputType, SerializeableSharedRep3Input,
+    SerializeableSharedRep3Witness, ShareCurve, ShareKind, ShareMetadata, ShareProtocol,
+    SharedInput, SharedWitness, SharingProof,
 };
+use co_groth16::mpc::FftMode;
 use co_groth16::Rep3CoGroth16;
 use color_eyre::eyre::{bail, Context, ContextCompat};
 use figment::{
@@ -29,8 +36,10 @@ use figment::{
     Figment,
 };
 use mpc_core::protocols::{
+    bridges::translate_shamir_to_addshare_vec,
     rep3::{
-        network::{Rep3MpcNet, Rep3Network},
+        arithmetic,
+        network::{IoContext, Rep3MpcNet, Rep3Network},
         Rep3PrimeFieldShare, Rep3ShareVecType,
     },
     shamir::ShamirPrimeFieldShare,
@@ -47,7 +56,6 @@ pub mod file_utils;
 
 /// An enum representing the ZK proof system to use.
 #[derive(Debug, Clone, ValueEnum, Serialize, Deserialize)]
-#[clap(rename_all = "lower")]
 pub enum ProofSystem {
     /// The Groth16 proof system.
     Groth16,
@@ -71,17 +79,28 @@ pub enum MPCCurve {
     BN254,
     /// The BLS12_381 curve.
     BLS12_381,
+    /// The BLS12_377 curve, whose scalar field is the base field of [BW6_761](MPCCurve::BW6_761).
+    BLS12_377,
+    /// The BW6_761 curve, for the verification of BLS12_377 proofs in a circuit. It is not supported by the circom compiler, so the witness has to be generated with other tooling.
+    BW6_761,
 }
 
 impl ValueEnum for MPCCurve {
     fn value_variants<'a>() -> &'a [Self] {
-        &[MPCCurve::BN254, MPCCurve::BLS12_381]
+        &[
+            MPCCurve::BN254,
+            MPCCurve::BLS12_381,
+            MPCCurve::BLS12_377,
+            MPCCurve::BW6_761,
+        ]
     }
 
     fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
         match self {
             MPCCurve::BN254 => Some(clap::builder::PossibleValue::new("BN254")),
             MPCCurve::BLS12_381 => Some(clap::builder::PossibleValue::new("BLS12-381")),
+            MPCCurve::BLS12_377 => Some(clap::builder::PossibleValue::new("BLS12-377")),
+            MPCCurve::BW6_761 => Some(clap::builder::PossibleValue::new("BW6-761")),
         }
     }
 }
@@ -91,6 +110,19 @@ impl std::fmt::Display for MPCCurve {
         match self {
             MPCCurve::BN254 => write!(f, "BN254"),
             MPCCurve::BLS12_381 => write!(f, "BLS12-381"),
+            MPCCurve::BLS12_377 => write!(f, "BLS12-377"),
+            MPCCurve::BW6_761 => write!(f, "BW6-761"),
+        }
+    }
+}
+
+impl From<MPCCurve> for ShareCurve {
+    fn from(curve: MPCCurve) -> Self {
+        match curve {
+            MPCCurve::BN254 => ShareCurve::Bn254,
+            MPCCurve::BLS12_381 => ShareCurve::Bls12_381,
+            MPCCurve::BLS12_377 => ShareCurve::Bls12_377,
+            MPCCurve::BW6_761 => ShareCurve::Bw6_761,
         }
     }
 }
@@ -116,6 +148,37 @@ impl std::fmt::Display for MPCProtocol {
     }
 }
 
+/// An enum representing how the REP3 parties compute the FFTs of the H polynomial in the Groth16 prover.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Groth16FftMode {
+    /// Every party transforms only its own component of the shares and forwards it to the next party.
+    #[default]
+    Distributed,
+    /// Every party transforms both components of its shares without networking (the old behavior).
+    Replicated,
+}
+
+impl From<Groth16FftMode> for FftMode {
+    fn from(mode: Groth16FftMode) -> Self {
+        match mode {
+            Groth16FftMode::Distributed => FftMode::Distributed,
+            Groth16FftMode::Replicated => FftMode::Replicated,
+        }
+    }
+}
+
+/// An enum representing the file format of proofs, public inputs and verification keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofFormat {
+    /// The JSON format of snarkjs.
+    #[default]
+    Json,
+    /// The compact binary format of [`circom_types::binary`].
+    Binary,
+}
+
 /// Cli arguments for `split_witness`
 #[derive(Debug, Default, Serialize, Args)]
 pub struct SplitWitnessCli {
@@ -155,6 +218,13 @@ pub struct SplitWitnessCli {
     /// Share compressed as additive shares
     #[arg(short, long, default_value_t = false)]
     pub additive: bool,
+    /// Additionally deduplicate the shares and compress them with zstd at the given level (implies seeded)
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub zstd_level: Option<i32>,
+    /// Secret-share the public inputs as well. They are only revealed once the proof is assembled
+    #[arg(long, default_value_t = false)]
+    pub hide_public_inputs: bool,
 }
 
 /// Config for `split_witness`
@@ -178,6 +248,22 @@ pub struct SplitWitnessConfig {
     pub seeded: bool,
     /// Share compressed as additive shares
     pub additive: bool,
+    /// Additionally deduplicate the shares and compress them with zstd at the given level (implies seeded)
+    pub zstd_level: Option<i32>,
+    /// Secret-share the public inputs as well. They are only revealed once the proof is assembled
+    #[serde(default)]
+    pub hide_public_inputs: bool,
+}
+
+impl SplitWitnessConfig {
+    /// Returns the [CompressionLevel] selected by the config.
+    pub fn compression(&self) -> CompressionLevel {
+        match (self.zstd_level, self.seeded) {
+            (Some(level), _) => CompressionLevel::Zstd(level),
+            (None, true) => CompressionLevel::Seeded,
+            (None, false) => CompressionLevel::None,
+        }
+    }
 }
 
 /// Cli arguments for `split_input`
@@ -213,6 +299,10 @@ pub struct SplitInputCli {
     /// Share compressed as additive shares
     #[arg(short, long, default_value_t = false)]
     pub additive: bool,
+    /// The names of the private inputs which are shared with 3-party Shamir instead of Rep3
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub shamir_inputs: Option<Vec<String>>,
 }
 
 /// Config for `split_input`
@@ -235,6 +325,12 @@ pub struct SplitInputConfig {
     pub seeded: bool,
     /// Share compressed as additive shares
     pub additive: bool,
+    /// The names of the private inputs which are shared with 3-party Shamir instead of Rep3
+    #[serde(default)]
+    pub shamir_inputs: Vec<String>,
+    /// The types of structured inputs, e.g., circom buses, which are provided as JSON objects
+    #[serde(default)]
+    pub input_types: BTreeMap<String, InputType>,
 }
 
 /// Cli arguments for `merge_input_shares`
@@ -329,6 +425,14 @@ pub struct GenerateWitnessConfig {
     /// MPC VM config
     #[serde(default)]
     pub vm: VMConfig,
+    /// The declared bit widths of the shared inputs. The parties jointly check that the listed inputs are within
+    /// range before the witness extension.
+    #[serde(default)]
+    pub range_checks: BTreeMap<String, usize>,
+    /// The files containing the [SharingProof]s of the shared inputs, by input name. The proofs are verified before
+    /// the input is accepted.
+    #[serde(default)]
+    pub sharing_proofs: BTreeMap<String, PathBuf>,
     /// Network config
     pub network: NetworkConfigFile,
 }
@@ -416,6 +520,12 @@ pub struct GenerateProofCli {
     /// The threshold of tolerated colluding parties
     #[arg(short, long, default_value_t = 1)]
     pub threshold: usize,
+    /// How the REP3 parties compute the FFTs of the H polynomial in the Groth16 prover
+    #[arg(long, value_enum, default_value_t = Groth16FftMode::Distributed)]
+    pub fft_mode: Groth16FftMode,
+    /// The format in which the proof and the public inputs are written
+    #[arg(long, value_enum, default_value_t = ProofFormat::Json)]
+    pub format: ProofFormat,
 }
 
 /// Config for `generate_proof`
@@ -437,6 +547,12 @@ pub struct GenerateProofConfig {
     pub public_input: Option<PathBuf>,
     /// The threshold of tolerated colluding parties
     pub threshold: usize,
+    /// How the REP3 parties compute the FFTs of the H polynomial in the Groth16 prover
+    #[serde(default)]
+    pub fft_mode: Groth16FftMode,
+    /// The format in which the proof and the public inputs are written
+    #[serde(default)]
+    pub format: ProofFormat,
     /// Network config
     pub network: NetworkConfigFile,
 }
@@ -467,6 +583,9 @@ pub struct VerifyCli {
     #[arg(long)]
     #[serde(skip_serializing_if = "::std::option::Option::is_none")]
     pub public_input: Option<PathBuf>,
+    /// The format of the proof and the public inputs. The format of the verification key is detected automatically
+    #[arg(long, value_enum, default_value_t = ProofFormat::Json)]
+    pub format: ProofFormat,
 }
 
 /// Config for `verify`
@@ -482,6 +601,29 @@ pub struct VerifyConfig {
     pub vk: PathBuf,
     /// The path to the public input JSON file
     pub public_input: PathBuf,
+    /// The format of the proof and the public inputs. The format of the verification key is detected automatically
+    #[serde(default)]
+    pub format: ProofFormat,
+}
+
+/// Cli arguments for `inspect_share`
+#[derive(Debug, Serialize, Args)]
+pub struct InspectShareCli {
+    /// The path to the config file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub config: Option<PathBuf>,
+    /// The path to the share file
+    #[arg(long)]
+    #[serde(skip_serializing_if = "::std::option::Option::is_none")]
+    pub share: Option<PathBuf>,
+}
+
+/// Config for `inspect_share`
+#[derive(Debug, Deserialize)]
+pub struct InspectShareConfig {
+    /// The path to the share file
+    pub share: PathBuf,
 }
 
 /// Prefix for config env variables
@@ -520,6 +662,7 @@ impl_config!(MergeInputSharesCli, MergeInputSharesConfig);
 impl_config!(TranslateWitnessCli, TranslateWitnessConfig);
 impl_config!(GenerateProofCli, GenerateProofConfig);
 impl_config!(VerifyCli, VerifyConfig);
+impl_config!(InspectShareCli, InspectShareConfig);
 
 // manual one since this is a bit more complex
 impl GenerateWitnessConfig {
@@ -572,15 +715,148 @@ fn reshare_vec<F: PrimeField, N: Rep3Network>(
     Ok(shares)
 }
 
+/// Verifies the [SharingProof] a data provider produced for a shared input, before the input is accepted. Checks that
+/// the share matches the commitments and that all parties received the same commitments.
+pub fn verify_sharing_proof<C: CurveGroup, N: Rep3Network>(
+    share: &[Rep3PrimeFieldShare<C::ScalarField>],
+    proof: &SharingProof<C>,
+    mpc_net: &mut N,
+) -> color_eyre::Result<()> {
+    proof
+        .verify(share, mpc_net.get_id())
+        .context("while verifying sharing proof")?;
+    let digest = proof.digest();
+    mpc_net.send_next(digest.clone())?;
+    let prev_digest: Vec<u8> = mpc_net.recv_prev()?;
+    if digest != prev_digest {
+        bail!("parties received different commitments for the shared input");
+    }
+    Ok(())
+}
+
+/// Verifies the [SharingProof]s of the shared inputs listed in `proofs`, which maps the names of the inputs to the files
+/// containing the proofs the data providers produced when sharing them. Returns an error if one of the inputs is
+/// missing or one of the proofs does not verify, in which case the input must not be accepted.
+pub fn verify_sharing_proofs<C: CurveGroup, N: Rep3Network>(
+    input: &SharedInput<C::ScalarField, Rep3PrimeFieldShare<C::ScalarField>>,
+    proofs: &BTreeMap<String, PathBuf>,
+    mpc_net: &mut N,
+) -> color_eyre::Result<()> {
+    for (name, path) in proofs {
+        let share = input
+            .shared_inputs
+            .get(name)
+            .with_context(|| format!("sharing proof for unknown shared input {name}"))?;
+        let proof_file =
+            BufReader::new(File::open(path).context("while opening sharing proof file")?);
+        let proof = SharingProof::<C>::deserialize_compressed(proof_file)
+            .context("while parsing sharing proof")?;
+        verify_sharing_proof(share, &proof, mpc_net)
+            .with_context(|| format!("while verifying sharing proof of input {name}"))?;
+    }
+    Ok(())
+}
+
+/// Verifies that the shared input opens the [InputCommitment] a data provider published, so that the provider is
+/// bound to the same input across sessions. Checks that all parties use the same commitment before running the
+/// consistency check.
+pub fn verify_input_commitment<C: CurveGroup, N: Rep3Network>(
+    input: &SharedInput<C::ScalarField, Rep3PrimeFieldShare<C::ScalarField>>,
+    commitment: &InputCommitment<C>,
+    randomness: Rep3PrimeFieldShare<C::ScalarField>,
+    mpc_net: &mut N,
+) -> color_eyre::Result<()> {
+    let digest = commitment.digest();
+    mpc_net.send_next(digest.clone())?;
+    let prev_digest: Vec<u8> = mpc_net.recv_prev()?;
+    if digest != prev_digest {
+        bail!("parties use different input commitments");
+    }
+    let mut io_context = IoContext::init(mpc_net.fork()?)?;
+    commitment
+        .verify_shared(input, randomness, &mut io_context)
+        .context("while verifying input commitment")?;
+    Ok(())
+}
+
+/// Jointly checks that every element of the shared inputs listed in `bit_widths` lies within the declared bit width,
+/// so that an out-of-range value from a data provider is rejected before the witness extension. Only the result of
+/// the check is revealed.
+pub fn range_check_shared_input<F: PrimeField, N: Rep3Network>(
+    input: &SharedInput<F, Rep3PrimeFieldShare<F>>,
+    bit_widths: &BTreeMap<String, usize>,
+    mpc_net: &mut N,
+) -> color_eyre::Result<()> {
+    // batch all inputs with the same bit width
+    let mut batches = BTreeMap::<usize, (Vec<(&String, usize)>, Vec<_>)>::new();
+    for (name, bit_width) in bit_widths {
+        let shares = input
+            .shared_inputs
+            .get(name)
+            .with_context(|| format!("cannot find shared input \"{name}\" for range check"))?;
+        let (positions, values) = batches.entry(*bit_width).or_default();
+        positions.extend((0..shares.len()).map(|i| (name, i)));
+        values.extend_from_slice(shares);
+    }
+    if batches.is_empty() {
+        return Ok(());
+    }
+
+    let mut io_context = IoContext::init(mpc_net.fork()?)?;
+    for (bit_width, (positions, values)) in batches {
+        let in_range = arithmetic::range_check_vec(&values, bit_width, &mut io_context)?;
+        if let Some(((name, i), _)) = positions.into_iter().zip(in_range).find(|(_, ok)| !ok) {
+            bail!("shared input \"{name}\" at index {i} does not fit into {bit_width} bits");
+        }
+    }
+    Ok(())
+}
+
+/// Try to parse a [SerializeableSharedRep3Witness] from a [Read]er, decompressing it if necessary. Legacy share files
+/// without header are accepted as well.
+pub fn parse_serializeable_witness_share_rep3<R: Read, F: PrimeField>(
+    reader: R,
+) -> color_eyre::Result<SerializeableSharedRep3Witness<F, SeedRng>> {
+    match ShareMetadata::probe_or_legacy(reader)? {
+        (Some(metadata), payload) => {
+            metadata.expect(ShareProtocol::Rep3, ShareKind::Witness)?;
+            SerializeableSharedRep3Witness::read_payload(payload, &metadata)
+        }
+        (None, payload) => SerializeableSharedRep3Witness::read_legacy_payload(payload),
+    }
+    .context("trying to parse witness share file")
+}
+
+/// Writes a Rep3 witness share file, compressing the share if a zstd level is provided.
+pub fn write_witness_share_rep3<W: Write, F: PrimeField>(
+    mut writer: W,
+    share: SerializeableSharedRep3Witness<F, SeedRng>,
+    curve: ShareCurve,
+    compression: CompressionLevel,
+) -> color_eyre::Result<()> {
+    let mut metadata = share.metadata(curve);
+    if let Some(level) = compression.zstd_level() {
+        metadata.compressed = true;
+        metadata.write(&mut writer)?;
+        let bytes = share.compress(level)?;
+        writer
+            .write_all(&bytes)
+            .context("while writing compressed share")?;
+        Ok(())
+    } else {
+        write_share_file(writer, &metadata, &share)
+    }
+}
+
 /// Try to parse a [SharedWitness] from a [Read]er.
 pub fn parse_witness_share_rep3<R: Read, F: PrimeField>(
     reader: R,
     mpc_net: &mut Rep3MpcNet,
 ) -> color_eyre::Result<SharedWitness<F, Rep3PrimeFieldShare<F>>> {
-    let deserialized: SerializeableSharedRep3Witness<F, SeedRng> =
-        bincode::deserialize_from(reader).context("trying to parse witness share file")?;
+    let deserialized = parse_serializeable_witness_share_rep3::<_, F>(reader)?;
 
     let public_inputs = deserialized.public_inputs;
+    let shared_public_inputs = deserialized.shared_public_inputs;
     let witness = deserialized.witness;
     let witness = match witness {
         Rep3ShareVecType::Replicated(vec) => vec,
@@ -595,6 +871,7 @@ pub fn parse_witness_share_rep3<R: Read, F: PrimeField>(
 
     Ok(SharedWitness {
         public_inputs,
+        shared_public_inputs,
         witness,
     })
 }
@@ -603,10 +880,14 @@ pub fn parse_witness_share_rep3<R: Read, F: PrimeField>(
 pub fn parse_witness_share_rep3_as_additive<R: Read, F: PrimeField>(
     reader: R,
 ) -> color_eyre::Result<SharedWitness<F, F>> {
-    let deserialized: SerializeableSharedRep3Witness<F, SeedRng> =
-        bincode::deserialize_from(reader).context("trying to parse witness share file")?;
+    let deserialized = parse_serializeable_witness_share_rep3::<_, F>(reader)?;
 
     let public_inputs = deserialized.public_inputs;
+    let shared_public_inputs = deserialized
+        .shared_public_inputs
+        .into_iter()
+        .map(|x| x.a)
+        .collect();
     let witness = deserialized.witness;
     let witness = match witness {
         Rep3ShareVecType::Replicated(vec) => vec.into_iter().map(|x| x.a).collect::<Vec<_>>(),
@@ -619,15 +900,23 @@ pub fn parse_witness_share_rep3_as_additive<R: Read, F: PrimeField>(
 
     Ok(SharedWitness {
         public_inputs,
+        shared_public_inputs,
         witness,
     })
 }
 
-/// Try to parse a [SharedWitness] from a [Read]er.
+/// Try to parse a [SharedWitness] from a [Read]er. Legacy share files without header are accepted as well.
 pub fn parse_witness_share_shamir<R: Read, F: PrimeField>(
     reader: R,
 ) -> color_eyre::Result<SharedWitness<F, ShamirPrimeFieldShare<F>>> {
-    bincode::deserialize_from(reader).context("trying to parse witness share file")
+    match ShareMetadata::probe_or_legacy(reader)? {
+        (Some(metadata), payload) => {
+            metadata.expect(ShareProtocol::Shamir, ShareKind::Witness)?;
+            SharedWitness::read_payload(payload, &metadata)
+        }
+        (None, payload) => SharedWitness::read_legacy_payload(payload),
+    }
+    .context("trying to parse witness share file")
 }
 
 /// Splits the input according to the provided parameters.
@@ -637,15 +926,21 @@ pub fn split_input<P>(
     config: CompilerConfig,
     seeded: bool,
     additive: bool,
+    shamir_inputs: &[String],
+    input_types: &BTreeMap<String, InputType>,
 ) -> color_eyre::Result<[SerializeableSharedRep3Input<P::ScalarField, SeedRng>; 3]>
 where
     P: Pairing + CircomArkworksPairingBridge,
     P::BaseField: CircomArkworksPrimeFieldBridge,
     P::ScalarField: CircomArkworksPrimeFieldBridge,
 {
-    //get the public inputs if any from parser
-    let public_inputs = CoCircomCompiler::<P>::get_public_inputs(circuit_path, config)
-        .context("while reading public inputs from circuit")?;
+    // get the input signals and which of them are public from the circuit
+    let schema = CoCircomCompiler::<P>::get_input_schema(circuit_path, config)
+        .context("while reading input schema from circuit")?;
+
+    if let Some(name) = shamir_inputs.iter().find(|name| schema.is_public(name)) {
+        bail!("public input \"{name}\" cannot be Shamir shared");
+    }
 
     // read the input file
     let input_file = BufReader::new(File::open(&input).context("while opening input file")?);
@@ -662,14 +957,25 @@ where
 
     let mut rng = rand::thread_rng();
     for (name, val) in input_json {
-        let parsed_vals = if val.is_array() {
+        let parsed_vals = if let Some(ty) = input_types.get(&name) {
+            for share in shares.iter_mut() {
+                share.input_types.insert(name.clone(), ty.clone());
+            }
+            file_utils::parse_typed(&val, ty)
+                .with_context(|| format!("while parsing input \"{name}\""))?
+        } else if val.is_object() {
+            bail!("input \"{name}\" is a struct, but no type is provided for it");
+        } else if val.is_array() {
             file_utils::parse_array(&val)?
         } else if val.is_boolean() {
             vec![Some(file_utils::parse_boolean(&val)?)]
         } else {
             vec![Some(file_utils::parse_field(&val)?)]
         };
-        if public_inputs.contains(&name) {
+        schema
+            .check_signal(&name, parsed_vals.len())
+            .context("while validating input against circuit")?;
+        if schema.is_public(&name) {
             let parsed_vals = parsed_vals
                 .into_iter()
                 .collect::<Option<Vec<P::ScalarField>>>()
@@ -682,9 +988,23 @@ where
                 .insert(name.clone(), parsed_vals.clone());
             shares[2].public_inputs.insert(name.clone(), parsed_vals);
         } else {
+            if shamir_inputs.contains(&name) {
+                let parsed_vals = parsed_vals
+                    .into_iter()
+                    .collect::<Option<Vec<_>>>()
+                    .context("Shamir shared inputs must not be unknown")?;
+                let [share0, share1, share2] =
+                    SerializeableSharedRep3Input::<_, SeedRng>::share_shamir(
+                        &parsed_vals,
+                        1,
+                        &mut rng,
+                    )?;
+                shares[0].shamir_shared_inputs.insert(name.clone(), share0);
+                shares[1].shamir_shared_inputs.insert(name.clone(), share1);
+                shares[2].shamir_shared_inputs.insert(name.clone(), share2);
             // if all elements are Some, then we can share normally
             // else we can only share as Vec<Option<T>> and we have to merge unknown inputs later
-            if parsed_vals.iter().all(Option::is_some) {
+            } else if parsed_vals.iter().all(Option::is_some) {
                 let parsed_vals = parsed_vals
                     .into_iter()
                     .collect::<Option<Vec<_>>>()
@@ -714,20 +1034,109 @@ where
     Ok(shares)
 }
 
+/// Try to parse a [SerializeableSharedRep3Input] from a [Read]er, without resharing any additive shares. Legacy share
+/// files without header are accepted as well.
+pub fn parse_serializeable_shared_input<R: Read, F: PrimeField>(
+    reader: R,
+) -> color_eyre::Result<SerializeableSharedRep3Input<F, SeedRng>> {
+    match ShareMetadata::probe_or_legacy(reader)? {
+        (Some(metadata), payload) => {
+            metadata.expect(ShareProtocol::Rep3, ShareKind::Input)?;
+            SerializeableSharedRep3Input::read_payload(payload, &metadata)
+        }
+        (None, payload) => SerializeableSharedRep3Input::read_legacy_payload(payload),
+    }
+    .context("trying to parse input share file")
+}
+
+/// Writes a share file, consisting of the provided [ShareMetadata] header followed by the serialized share.
+pub fn write_share_file<W: Write, T: Serialize>(
+    mut writer: W,
+    metadata: &ShareMetadata,
+    share: &T,
+) -> color_eyre::Result<()> {
+    metadata.write(&mut writer)?;
+    bincode::serialize_into(writer, share).context("while serializing share")?;
+    Ok(())
+}
+
+/// Describes how a signal of a (possibly mixed-protocol) input share is routed to the Rep3 sharing used during the
+/// witness extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputRoute {
+    /// The signal is public and stays public.
+    Public,
+    /// The signal is Rep3 shared and is used directly.
+    Replicated,
+    /// The signal is additively shared and is reshared to a Rep3 share.
+    Reshare,
+    /// The signal is Shamir shared, is translated to an additive share and then reshared to a Rep3 share.
+    ShamirToRep3,
+}
+
+/// A plan routing each signal of an input share to the sharing required by the witness extension. Signals only
+/// pay for the conversions they actually need, e.g., public signals stay public.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputPlan {
+    /// The route per signal name.
+    pub routes: BTreeMap<String, InputRoute>,
+}
+
+impl InputPlan {
+    /// Creates the plan for the given input share.
+    pub fn new<F: PrimeField, U: Rng + SeedableRng + CryptoRng>(
+        input: &SerializeableSharedRep3Input<F, U>,
+    ) -> Self
+    where
+        U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+    {
+        let mut routes = BTreeMap::new();
+        for name in input.public_inputs.keys() {
+            routes.insert(name.clone(), InputRoute::Public);
+        }
+        for (name, share) in input.shared_inputs.iter() {
+            let route = match share {
+                Rep3ShareVecType::Replicated(_) | Rep3ShareVecType::SeededReplicated(_) => {
+                    InputRoute::Replicated
+                }
+                Rep3ShareVecType::Additive(_) | Rep3ShareVecType::SeededAdditive(_) => {
+                    InputRoute::Reshare
+                }
+            };
+            routes.insert(name.clone(), route);
+        }
+        for name in input.shamir_shared_inputs.keys() {
+            routes.insert(name.clone(), InputRoute::ShamirToRep3);
+        }
+        Self { routes }
+    }
+
+    /// Returns `true` if any signal has to be reshared, which requires a round of communication.
+    pub fn requires_reshare(&self) -> bool {
+        self.routes
+            .values()
+            .any(|route| matches!(route, InputRoute::Reshare | InputRoute::ShamirToRep3))
+    }
+}
+
 /// Try to parse a [SharedInput] from a [Read]er.
 pub fn parse_shared_input<R: Read, F: PrimeField, N: Rep3Network>(
     reader: R,
     mpc_net: &mut N,
 ) -> color_eyre::Result<SharedInput<F, Rep3PrimeFieldShare<F>>> {
-    let deserialized: SerializeableSharedRep3Input<F, SeedRng> =
-        bincode::deserialize_from(reader).context("trying to parse input share file")?;
+    let deserialized = parse_serializeable_shared_input::<_, F>(reader)?;
 
     if !deserialized.maybe_shared_inputs.is_empty() {
         bail!("still unmerged elements left");
     }
 
+    let plan = InputPlan::new(&deserialized);
+    tracing::debug!("input plan: {plan:?}");
+
     let public_inputs = deserialized.public_inputs;
     let shared_inputs_ = deserialized.shared_inputs;
+    let shamir_shared_inputs = deserialized.shamir_shared_inputs;
+    let input_types = deserialized.input_types;
 
     let mut shared_inputs = BTreeMap::new();
 
@@ -743,8 +1152,16 @@ pub fn parse_shared_input<R: Read, F: PrimeField, N: Rep3Network>(
             }
         }
     }
+    let id = mpc_net.get_id();
+    for share in shamir_shared_inputs.values() {
+        to_reshare.extend(translate_shamir_to_addshare_vec(share.to_owned(), id));
+    }
 
-    let mut reshared = reshare_vec(to_reshare, mpc_net)?;
+    let mut reshared = if plan.requires_reshare() {
+        reshare_vec(to_reshare, mpc_net)?
+    } else {
+        Vec::new()
+    };
 
     for (name, share) in shared_inputs_ {
         match share {
@@ -762,14 +1179,83 @@ pub fn parse_shared_input<R: Read, F: PrimeField, N: Rep3Network>(
             }
         }
     }
+    for (name, share) in shamir_shared_inputs {
+        shared_inputs.insert(name, reshared.drain(..share.len()).collect());
+    }
 
     Ok(SharedInput {
         public_inputs,
         shared_inputs,
+        input_types,
     })
 }
 
-/// Invoke the MPC witness generation process. It will return a [SharedWitness] if successful.
+/// The witness of a single party after the MPC witness extension, as returned by [generate_witness_rep3].
+pub enum Rep3WitnessShare<F: PrimeField, U: Rng + SeedableRng + CryptoRng>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    /// The share of a witness containing secret-shared values.
+    Shared(SerializeableSharedRep3Witness<F, U>),
+    /// The witness in plain, if none of its values is secret-shared. Every party knows this witness, so the proof can
+    /// be created locally without MPC.
+    Public(SharedWitness<F, F>),
+}
+
+impl<F: PrimeField, U: Rng + SeedableRng + CryptoRng> Rep3WitnessShare<F, U>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    /// Takes the witness of a finished Rep3 witness extension, keeping it in plain if it is fully public.
+    pub fn from_finalized<C>(finalized: FinalizedWitnessExtension<F, C>) -> Self
+    where
+        C: VmCircomWitnessExtension<F, ArithmeticShare = Rep3PrimeFieldShare<F>>,
+    {
+        if finalized.public_witness().is_some() {
+            Self::Public(finalized.into_public_witness().expect("checked above"))
+        } else {
+            Self::Shared(SerializeableSharedRep3Witness::from_shared_witness(
+                finalized.into_shared_witness(),
+            ))
+        }
+    }
+}
+
+/// Writes the result of [generate_witness_rep3] to a witness file. A fully public witness is written in plain (see
+/// [ShareMetadata::public_witness]), all other witnesses as share file.
+pub fn write_generated_witness_rep3<W: Write, F: PrimeField, U: Rng + SeedableRng + CryptoRng>(
+    writer: W,
+    witness: &Rep3WitnessShare<F, U>,
+    curve: ShareCurve,
+) -> color_eyre::Result<()>
+where
+    U::Seed: Serialize + for<'a> Deserialize<'a> + Clone + std::fmt::Debug,
+{
+    match witness {
+        Rep3WitnessShare::Shared(share) => write_share_file(writer, &share.metadata(curve), share),
+        Rep3WitnessShare::Public(witness) => {
+            write_share_file(writer, &witness.public_witness_metadata(curve), witness)
+        }
+    }
+}
+
+/// Try to parse a fully public witness (see [ShareMetadata::public_witness]) from a [Read]er. Returns `None` if the file
+/// contains shares instead, in which case the file has to be parsed again with one of the share parsing functions.
+pub fn parse_public_witness_rep3<R: Read, F: PrimeField>(
+    reader: R,
+) -> color_eyre::Result<Option<SharedWitness<F, F>>> {
+    match ShareMetadata::probe_or_legacy(reader)? {
+        (Some(metadata), payload) if metadata.public_witness => {
+            metadata.expect(ShareProtocol::Rep3, ShareKind::Witness)?;
+            let witness = SharedWitness::read_public_payload(payload, &metadata)
+                .context("trying to parse public witness file")?;
+            Ok(Some(witness))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Invoke the MPC witness generation process. It will return a [Rep3WitnessShare] if successful.
 /// It executes several steps:
 /// 1. Parse the circuit file.
 /// 2. Compile the circuit to MPC VM bytecode.
@@ -780,7 +1266,7 @@ pub fn generate_witness_rep3<P, U: Rng + SeedableRng + CryptoRng>(
     input_share: SharedInput<P::ScalarField, Rep3PrimeFieldShare<P::ScalarField>>,
     net: Rep3MpcNet,
     config: GenerateWitnessConfig,
-) -> color_eyre::Result<SerializeableSharedRep3Witness<P::ScalarField, U>>
+) -> color_eyre::Result<Rep3WitnessShare<P::ScalarField, U>>
 where
     P: Pairing + CircomArkworksPairingBridge,
     P::BaseField: CircomArkworksPrimeFieldBridge,
@@ -810,9 +1296,13 @@ where
     let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
     tracing::info!("Party {}: Witness extension took {} ms", id, duration_ms);
 
-    let res = SerializeableSharedRep3Witness::from_shared_witness(
-        result_witness_share.into_shared_witness(),
-    );
+    let res = Rep3WitnessShare::from_finalized(result_witness_share);
+    if let Rep3WitnessShare::Public(_) = res {
+        tracing::info!(
+            "Party {}: the witness is fully public, the proof will be created locally without MPC",
+            id
+        );
+    }
 
     Ok(res)
 }
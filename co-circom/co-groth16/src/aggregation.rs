@@ -0,0 +1,677 @@
+//! # Aggregation
+//!
+//! This module implements the aggregation of Groth16 proofs as in [SnarkPack](https://eprint.iacr.org/2021/529). Many proofs
+//! for the same verification key are compressed into a single [`AggregateProof`] with [`aggregate_proofs`], whose size and
+//! verification time with [`verify_aggregate_proof`] are logarithmic in the number of proofs. Hence, consumers of many
+//! co-circom proofs, e.g., rollups, can post a single object on-chain instead of every proof.
+//!
+//! The aggregation needs a structured reference string, the [`AggregationSrs`], which consists of the powers of two
+//! secrets. In production it has to be derived from two independent powers-of-tau ceremonies (see
+//! [`AggregationSrs::from_powers`]), since anyone who knows the secrets can forge aggregate proofs.
+//!
+//! Following the paper, the prover commits to the A, B and C elements of all proofs with pairing-based commitments. The
+//! proofs are combined with powers of a random challenge r, such that a single Groth16 equation for the aggregated
+//! values holds if and only if all proofs are valid (with overwhelming probability). The prover shows that the
+//! aggregated values are consistent with the commitments with an inner product argument (TIPP for the pairing product
+//! of A and B, MIPP for the multiexponentiation of C), which halves the committed vectors in every round. The keys the
+//! verifier would need to fold itself are opened with KZG proofs instead.
+//!
+//! The number of proofs is padded to the next power of two by repeating the last proof, which is done by the prover
+//! and the verifier alike.
+
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::scalar_mul::variable_base::VariableBaseMSM;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use circom_types::groth16::{Groth16Proof, JsonVerificationKey};
+use circom_types::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
+use co_circom_snarks::VerificationError;
+use eyre::Result;
+use rand::{CryptoRng, Rng};
+use rayon::prelude::*;
+use sha3::{Digest, Keccak256};
+
+/// A pair of commitments in the target group, one under each of the two secrets of the [`AggregationSrs`].
+type Commitment<P> = (PairingOutput<P>, PairingOutput<P>);
+
+/// The structured reference string of the aggregation, i.e., the powers of two secrets a and b in both groups.
+///
+/// It supports the aggregation of up to [`AggregationSrs::max_proofs`] proofs.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregationSrs<P: Pairing> {
+    g_alpha_powers: Vec<P::G1Affine>,
+    g_beta_powers: Vec<P::G1Affine>,
+    h_alpha_powers: Vec<P::G2Affine>,
+    h_beta_powers: Vec<P::G2Affine>,
+}
+
+/// The part of the [`AggregationSrs`] the verifier needs, which is independent of the number of proofs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregationVerifierKey<P: Pairing> {
+    g: P::G1Affine,
+    h: P::G2Affine,
+    g_alpha: P::G1Affine,
+    g_beta: P::G1Affine,
+    h_alpha: P::G2Affine,
+    h_beta: P::G2Affine,
+}
+
+impl<P: Pairing> AggregationSrs<P> {
+    /// Creates an SRS for up to `max_proofs` proofs from freshly sampled secrets.
+    ///
+    /// This is only meant for testing, since whoever runs the setup learns the secrets and can forge aggregate proofs.
+    pub fn insecure_setup<R: Rng + CryptoRng>(max_proofs: usize, rng: &mut R) -> Self {
+        let max_proofs = padded_len(max_proofs);
+        let alpha = P::ScalarField::rand(rng);
+        let beta = P::ScalarField::rand(rng);
+        let g = P::G1::rand(rng);
+        let h = P::G2::rand(rng);
+        Self {
+            g_alpha_powers: powers_of(g, alpha, 2 * max_proofs),
+            g_beta_powers: powers_of(g, beta, 2 * max_proofs),
+            h_alpha_powers: powers_of(h, alpha, max_proofs),
+            h_beta_powers: powers_of(h, beta, max_proofs),
+        }
+    }
+
+    /// Creates an SRS from the powers of two secrets a and b, e.g., taken from two independent powers-of-tau ceremonies.
+    ///
+    /// The powers in G1 need twice the length of the powers in G2, which has to be a power of two and is the maximum
+    /// number of proofs. The powers of both secrets have to start with the same generators.
+    pub fn from_powers(
+        g_alpha_powers: Vec<P::G1Affine>,
+        g_beta_powers: Vec<P::G1Affine>,
+        h_alpha_powers: Vec<P::G2Affine>,
+        h_beta_powers: Vec<P::G2Affine>,
+    ) -> Result<Self> {
+        let max_proofs = h_alpha_powers.len();
+        if !max_proofs.is_power_of_two() || max_proofs < 2 {
+            eyre::bail!("the number of powers in G2 must be a power of two, but is {max_proofs}");
+        }
+        if h_beta_powers.len() != max_proofs
+            || g_alpha_powers.len() != 2 * max_proofs
+            || g_beta_powers.len() != 2 * max_proofs
+        {
+            eyre::bail!(
+                "expected {max_proofs} powers in G2 and {} in G1",
+                2 * max_proofs
+            );
+        }
+        if g_alpha_powers[0] != g_beta_powers[0] || h_alpha_powers[0] != h_beta_powers[0] {
+            eyre::bail!("the powers of both secrets must start with the same generators");
+        }
+        Ok(Self {
+            g_alpha_powers,
+            g_beta_powers,
+            h_alpha_powers,
+            h_beta_powers,
+        })
+    }
+
+    /// Returns the maximum number of proofs which can be aggregated with this SRS.
+    pub fn max_proofs(&self) -> usize {
+        self.h_alpha_powers.len()
+    }
+
+    /// Returns the key the verifier needs.
+    pub fn verifier_key(&self) -> AggregationVerifierKey<P> {
+        AggregationVerifierKey {
+            g: self.g_alpha_powers[0],
+            h: self.h_alpha_powers[0],
+            g_alpha: self.g_alpha_powers[1],
+            g_beta: self.g_beta_powers[1],
+            h_alpha: self.h_alpha_powers[1],
+            h_beta: self.h_beta_powers[1],
+        }
+    }
+}
+
+/// Proves that the folded commitment keys of the inner product argument were computed correctly, by a KZG opening of
+/// the polynomial whose coefficients are the folding factors.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+struct KeyOpenings<P: Pairing> {
+    v_alpha: P::G2Affine,
+    v_beta: P::G2Affine,
+    w_alpha: P::G1Affine,
+    w_beta: P::G1Affine,
+}
+
+/// The messages of the inner product argument, one for every halving of the committed vectors.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+struct GipaProof<P: Pairing> {
+    /// The cross commitments to A and B of the left and right halves.
+    comms_ab: Vec<(Commitment<P>, Commitment<P>)>,
+    /// The cross commitments to C of the left and right halves.
+    comms_c: Vec<(Commitment<P>, Commitment<P>)>,
+    /// The cross pairing products of A and B of the left and right halves.
+    z_ab: Vec<(PairingOutput<P>, PairingOutput<P>)>,
+    /// The cross sums of C of the left and right halves.
+    z_c: Vec<(P::G1Affine, P::G1Affine)>,
+    final_a: P::G1Affine,
+    final_b: P::G2Affine,
+    final_c: P::G1Affine,
+    final_v: (P::G2Affine, P::G2Affine),
+    final_w: (P::G1Affine, P::G1Affine),
+}
+
+/// An aggregate of many Groth16 proofs for the same verification key, created with [`aggregate_proofs`].
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregateProof<P: Pairing> {
+    com_ab: Commitment<P>,
+    com_c: Commitment<P>,
+    /// The product of e(A_i, B_i)^(r^i).
+    ip_ab: PairingOutput<P>,
+    /// The sum of r^i * C_i.
+    agg_c: P::G1Affine,
+    gipa: GipaProof<P>,
+    openings: KeyOpenings<P>,
+}
+
+/// Aggregates the Groth16 proofs, each given with its public inputs (without the leading constant 1), into a single
+/// [`AggregateProof`]. The proofs must all be valid for the same verification key, otherwise the aggregate does not
+/// verify. The public inputs are bound to the aggregate, so the verifier has to provide the same ones in the same order.
+pub fn aggregate_proofs<P>(
+    srs: &AggregationSrs<P>,
+    proofs: &[(Groth16Proof<P>, Vec<P::ScalarField>)],
+) -> Result<AggregateProof<P>>
+where
+    P: Pairing + CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    if proofs.is_empty() {
+        eyre::bail!("cannot aggregate zero proofs");
+    }
+    let n = padded_len(proofs.len());
+    if n > srs.max_proofs() {
+        eyre::bail!(
+            "the SRS supports at most {} proofs, but got {}",
+            srs.max_proofs(),
+            proofs.len()
+        );
+    }
+    let padded = (0..n).map(|i| &proofs[i.min(proofs.len() - 1)]);
+    let a = padded
+        .clone()
+        .map(|(proof, _)| proof.pi_a)
+        .collect::<Vec<_>>();
+    let b = padded
+        .clone()
+        .map(|(proof, _)| proof.pi_b)
+        .collect::<Vec<_>>();
+    let c = padded
+        .clone()
+        .map(|(proof, _)| proof.pi_c)
+        .collect::<Vec<_>>();
+
+    let v_alpha = &srs.h_alpha_powers[..n];
+    let v_beta = &srs.h_beta_powers[..n];
+    let w_alpha = &srs.g_alpha_powers[n..2 * n];
+    let w_beta = &srs.g_beta_powers[n..2 * n];
+    let com_ab = commit_double::<P>(v_alpha, v_beta, w_alpha, w_beta, &a, &b);
+    let com_c = commit_single::<P>(v_alpha, v_beta, &c);
+
+    let public_inputs = proofs
+        .iter()
+        .map(|(_, inputs)| inputs.as_slice())
+        .collect::<Vec<_>>();
+    let mut transcript = Transcript::new(&public_inputs);
+    transcript.append(&com_ab);
+    transcript.append(&com_c);
+    let r = transcript.challenge::<P::ScalarField>();
+    let r_inv = r.inverse().expect("challenge is not zero");
+
+    // rescaling A and C by r^i and the keys by r^(-i) keeps the commitments unchanged
+    let r_powers = powers(r, n);
+    let r_inv_powers = powers(r_inv, n);
+    let a = scale::<P::G1>(&a, &r_powers);
+    let c = scale::<P::G1>(&c, &r_powers);
+    let v_alpha = scale::<P::G2>(v_alpha, &r_inv_powers);
+    let v_beta = scale::<P::G2>(v_beta, &r_inv_powers);
+    let ip_ab = P::multi_pairing(&a, &b);
+    let agg_c = c.iter().copied().sum::<P::G1>().into_affine();
+    transcript.append(&ip_ab);
+    transcript.append(&agg_c);
+
+    let (gipa, challenges) = prove_gipa::<P>(
+        &mut transcript,
+        a,
+        b,
+        c,
+        (v_alpha, v_beta),
+        (w_alpha.to_vec(), w_beta.to_vec()),
+    );
+    transcript.append(&gipa.final_v);
+    transcript.append(&gipa.final_w);
+    let z = transcript.challenge::<P::ScalarField>();
+
+    let challenges_inv = challenges
+        .iter()
+        .map(|x| x.inverse().expect("challenge is not zero"))
+        .collect::<Vec<_>>();
+    // the folded v are the commitments to the polynomial with the coefficients r^(-i) * prod x_j^(-bit_j(i))
+    let v_coeffs = folding_coefficients(&challenges_inv)
+        .into_iter()
+        .zip(r_inv_powers)
+        .map(|(coeff, r_inv)| coeff * r_inv)
+        .collect::<Vec<_>>();
+    // the folded w are the commitments to X^n times the polynomial with the coefficients prod x_j^bit_j(i)
+    let mut w_coeffs = vec![P::ScalarField::zero(); n];
+    w_coeffs.extend(folding_coefficients(&challenges));
+    let v_quotient = quotient(&v_coeffs, z);
+    let w_quotient = quotient(&w_coeffs, z);
+    let openings = KeyOpenings {
+        v_alpha: P::G2::msm_unchecked(&srs.h_alpha_powers, &v_quotient).into_affine(),
+        v_beta: P::G2::msm_unchecked(&srs.h_beta_powers, &v_quotient).into_affine(),
+        w_alpha: P::G1::msm_unchecked(&srs.g_alpha_powers, &w_quotient).into_affine(),
+        w_beta: P::G1::msm_unchecked(&srs.g_beta_powers, &w_quotient).into_affine(),
+    };
+
+    Ok(AggregateProof {
+        com_ab,
+        com_c,
+        ip_ab,
+        agg_c,
+        gipa,
+        openings,
+    })
+}
+
+/// Verifies an [`AggregateProof`] of Groth16 proofs for the verification key, given the public inputs of every
+/// aggregated proof (without the leading constant 1) in the order of aggregation.
+pub fn verify_aggregate_proof<P>(
+    agg_vk: &AggregationVerifierKey<P>,
+    vk: &JsonVerificationKey<P>,
+    public_inputs: &[Vec<P::ScalarField>],
+    proof: &AggregateProof<P>,
+) -> Result<(), VerificationError>
+where
+    P: Pairing + CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    if public_inputs.is_empty() {
+        return Err(eyre::eyre!("cannot verify an aggregate of zero proofs").into());
+    }
+    if let Some(inputs) = public_inputs
+        .iter()
+        .find(|inputs| inputs.len() + 1 != vk.ic.len())
+    {
+        return Err(eyre::eyre!(
+            "expected {} public inputs, but got {}",
+            vk.ic.len().saturating_sub(1),
+            inputs.len()
+        )
+        .into());
+    }
+    let n = padded_len(public_inputs.len());
+    let rounds = n.ilog2() as usize;
+    let gipa = &proof.gipa;
+    if [
+        gipa.comms_ab.len(),
+        gipa.comms_c.len(),
+        gipa.z_ab.len(),
+        gipa.z_c.len(),
+    ] != [rounds; 4]
+    {
+        return Err(eyre::eyre!("aggregate proof has the wrong number of rounds").into());
+    }
+
+    let mut transcript =
+        Transcript::new(&public_inputs.iter().map(Vec::as_slice).collect::<Vec<_>>());
+    transcript.append(&proof.com_ab);
+    transcript.append(&proof.com_c);
+    let r = transcript.challenge::<P::ScalarField>();
+    let r_inv = r.inverse().expect("challenge is not zero");
+    transcript.append(&proof.ip_ab);
+    transcript.append(&proof.agg_c);
+
+    // the Groth16 equation of the aggregated proofs
+    // prod e(A_i, B_i)^(r^i) = e(alpha, beta)^(sum r^i) * e(sum r^i * L_i, gamma) * e(sum r^i * C_i, delta)
+    let r_powers = powers(r, n);
+    let mut ic_coeffs = vec![P::ScalarField::zero(); vk.ic.len()];
+    let padded = (0..n).map(|i| &public_inputs[i.min(public_inputs.len() - 1)]);
+    for (r_power, inputs) in r_powers.iter().zip(padded) {
+        ic_coeffs[0] += r_power;
+        for (coeff, input) in ic_coeffs[1..].iter_mut().zip(inputs.iter()) {
+            *coeff += *r_power * input;
+        }
+    }
+    let l_acc = P::G1::msm_unchecked(&vk.ic, &ic_coeffs);
+    let groth16 = P::multi_pairing(
+        [vk.alpha_1 * ic_coeffs[0], l_acc, proof.agg_c.into_group()],
+        [vk.beta_2, vk.gamma_2, vk.delta_2],
+    );
+    if groth16 != proof.ip_ab {
+        return Err(VerificationError::InvalidProof);
+    }
+
+    // replay the inner product argument and fold the claimed values with the challenges
+    let mut com_ab = proof.com_ab;
+    let mut com_c = proof.com_c;
+    let mut z_ab = proof.ip_ab;
+    let mut z_c = proof.agg_c.into_group();
+    let mut challenges = Vec::with_capacity(rounds);
+    for (((com_ab_lr, com_c_lr), z_ab_lr), z_c_lr) in gipa
+        .comms_ab
+        .iter()
+        .zip(&gipa.comms_c)
+        .zip(&gipa.z_ab)
+        .zip(&gipa.z_c)
+    {
+        transcript.append(com_ab_lr);
+        transcript.append(com_c_lr);
+        transcript.append(z_ab_lr);
+        transcript.append(z_c_lr);
+        let x = transcript.challenge::<P::ScalarField>();
+        let x_inv = x.inverse().expect("challenge is not zero");
+        let ((ab_l, ab_r), (c_l, c_r)) = (com_ab_lr, com_c_lr);
+        com_ab.0 += ab_l.0 * x + ab_r.0 * x_inv;
+        com_ab.1 += ab_l.1 * x + ab_r.1 * x_inv;
+        com_c.0 += c_l.0 * x + c_r.0 * x_inv;
+        com_c.1 += c_l.1 * x + c_r.1 * x_inv;
+        z_ab += z_ab_lr.0 * x + z_ab_lr.1 * x_inv;
+        z_c += z_c_lr.0 * x + z_c_lr.1 * x_inv;
+        challenges.push(x);
+    }
+    transcript.append(&gipa.final_a);
+    transcript.append(&gipa.final_b);
+    transcript.append(&gipa.final_c);
+    transcript.append(&gipa.final_v);
+    transcript.append(&gipa.final_w);
+    let z = transcript.challenge::<P::ScalarField>();
+
+    // the folded vectors of length one have to match the folded claims
+    let (v_alpha, v_beta) = gipa.final_v;
+    let (w_alpha, w_beta) = gipa.final_w;
+    let challenges_inv = challenges
+        .iter()
+        .map(|x| x.inverse().expect("challenge is not zero"))
+        .collect::<Vec<_>>();
+    let s = challenges_inv
+        .iter()
+        .map(|x_inv| P::ScalarField::one() + x_inv)
+        .product::<P::ScalarField>();
+    let final_ab = (
+        P::multi_pairing([gipa.final_a, w_alpha], [v_alpha, gipa.final_b]),
+        P::multi_pairing([gipa.final_a, w_beta], [v_beta, gipa.final_b]),
+    );
+    let final_c = (
+        P::pairing(gipa.final_c, v_alpha),
+        P::pairing(gipa.final_c, v_beta),
+    );
+    if com_ab != final_ab
+        || com_c != final_c
+        || z_ab != P::pairing(gipa.final_a, gipa.final_b)
+        || z_c != gipa.final_c * s
+    {
+        return Err(VerificationError::InvalidProof);
+    }
+
+    // the folded keys have to be the commitments to the folding polynomials, which is checked at z
+    let v_eval = evaluate_folding(&challenges_inv, z * r_inv);
+    let w_eval = z.pow([n as u64]) * evaluate_folding(&challenges, z);
+    let openings = &proof.openings;
+    let g = agg_vk.g.into_group();
+    let h = agg_vk.h.into_group();
+    let kzg_checks = [
+        P::multi_pairing(
+            [g, g * z - agg_vk.g_alpha],
+            [
+                v_alpha.into_group() - h * v_eval,
+                openings.v_alpha.into_group(),
+            ],
+        ),
+        P::multi_pairing(
+            [g, g * z - agg_vk.g_beta],
+            [
+                v_beta.into_group() - h * v_eval,
+                openings.v_beta.into_group(),
+            ],
+        ),
+        P::multi_pairing(
+            [
+                w_alpha.into_group() - g * w_eval,
+                openings.w_alpha.into_group(),
+            ],
+            [h, h * z - agg_vk.h_alpha],
+        ),
+        P::multi_pairing(
+            [
+                w_beta.into_group() - g * w_eval,
+                openings.w_beta.into_group(),
+            ],
+            [h, h * z - agg_vk.h_beta],
+        ),
+    ];
+    if kzg_checks.iter().all(PairingOutput::is_zero) {
+        Ok(())
+    } else {
+        Err(VerificationError::InvalidProof)
+    }
+}
+
+/// Runs the inner product argument for the pairing product of A and B, and the sum of C, which halves the vectors and
+/// the keys in every round. Returns the proof and the challenges of the rounds.
+fn prove_gipa<P: Pairing>(
+    transcript: &mut Transcript,
+    mut a: Vec<P::G1Affine>,
+    mut b: Vec<P::G2Affine>,
+    mut c: Vec<P::G1Affine>,
+    (mut v_alpha, mut v_beta): (Vec<P::G2Affine>, Vec<P::G2Affine>),
+    (mut w_alpha, mut w_beta): (Vec<P::G1Affine>, Vec<P::G1Affine>),
+) -> (GipaProof<P>, Vec<P::ScalarField>) {
+    let rounds = a.len().ilog2() as usize;
+    let mut comms_ab = Vec::with_capacity(rounds);
+    let mut comms_c = Vec::with_capacity(rounds);
+    let mut z_ab = Vec::with_capacity(rounds);
+    let mut z_c = Vec::with_capacity(rounds);
+    let mut challenges = Vec::with_capacity(rounds);
+    // the (equal) entries of the vector of ones the C are summed with, folded as the vector
+    let mut s = P::ScalarField::one();
+    while a.len() > 1 {
+        let half = a.len() / 2;
+        let (a_l, a_r) = a.split_at(half);
+        let (b_l, b_r) = b.split_at(half);
+        let (c_l, c_r) = c.split_at(half);
+        let (v_alpha_l, v_alpha_r) = v_alpha.split_at(half);
+        let (v_beta_l, v_beta_r) = v_beta.split_at(half);
+        let (w_alpha_l, w_alpha_r) = w_alpha.split_at(half);
+        let (w_beta_l, w_beta_r) = w_beta.split_at(half);
+
+        let com_ab_lr = (
+            commit_double::<P>(v_alpha_l, v_beta_l, w_alpha_r, w_beta_r, a_r, b_l),
+            commit_double::<P>(v_alpha_r, v_beta_r, w_alpha_l, w_beta_l, a_l, b_r),
+        );
+        let com_c_lr = (
+            commit_single::<P>(v_alpha_l, v_beta_l, c_r),
+            commit_single::<P>(v_alpha_r, v_beta_r, c_l),
+        );
+        let z_ab_lr = (P::multi_pairing(a_r, b_l), P::multi_pairing(a_l, b_r));
+        let z_c_lr = (
+            (c_r.iter().copied().sum::<P::G1>() * s).into_affine(),
+            (c_l.iter().copied().sum::<P::G1>() * s).into_affine(),
+        );
+        transcript.append(&com_ab_lr);
+        transcript.append(&com_c_lr);
+        transcript.append(&z_ab_lr);
+        transcript.append(&z_c_lr);
+        let x = transcript.challenge::<P::ScalarField>();
+        let x_inv = x.inverse().expect("challenge is not zero");
+
+        a = fold::<P::G1>(a_l, a_r, x);
+        b = fold::<P::G2>(b_l, b_r, x_inv);
+        c = fold::<P::G1>(c_l, c_r, x);
+        v_alpha = fold::<P::G2>(v_alpha_l, v_alpha_r, x_inv);
+        v_beta = fold::<P::G2>(v_beta_l, v_beta_r, x_inv);
+        w_alpha = fold::<P::G1>(w_alpha_l, w_alpha_r, x);
+        w_beta = fold::<P::G1>(w_beta_l, w_beta_r, x);
+        s += s * x_inv;
+
+        comms_ab.push(com_ab_lr);
+        comms_c.push(com_c_lr);
+        z_ab.push(z_ab_lr);
+        z_c.push(z_c_lr);
+        challenges.push(x);
+    }
+    let proof = GipaProof {
+        comms_ab,
+        comms_c,
+        z_ab,
+        z_c,
+        final_a: a[0],
+        final_b: b[0],
+        final_c: c[0],
+        final_v: (v_alpha[0], v_beta[0]),
+        final_w: (w_alpha[0], w_beta[0]),
+    };
+    transcript.append(&proof.final_a);
+    transcript.append(&proof.final_b);
+    transcript.append(&proof.final_c);
+    (proof, challenges)
+}
+
+/// Commits to A and B under both secrets.
+fn commit_double<P: Pairing>(
+    v_alpha: &[P::G2Affine],
+    v_beta: &[P::G2Affine],
+    w_alpha: &[P::G1Affine],
+    w_beta: &[P::G1Affine],
+    a: &[P::G1Affine],
+    b: &[P::G2Affine],
+) -> Commitment<P> {
+    rayon::join(
+        || P::multi_pairing(a.iter().chain(w_alpha), v_alpha.iter().chain(b)),
+        || P::multi_pairing(a.iter().chain(w_beta), v_beta.iter().chain(b)),
+    )
+}
+
+/// Commits to C under both secrets.
+fn commit_single<P: Pairing>(
+    v_alpha: &[P::G2Affine],
+    v_beta: &[P::G2Affine],
+    c: &[P::G1Affine],
+) -> Commitment<P> {
+    rayon::join(
+        || P::multi_pairing(c, v_alpha),
+        || P::multi_pairing(c, v_beta),
+    )
+}
+
+/// Returns left + x * right.
+fn fold<C: CurveGroup>(
+    left: &[C::Affine],
+    right: &[C::Affine],
+    x: C::ScalarField,
+) -> Vec<C::Affine> {
+    let folded = left
+        .par_iter()
+        .zip(right)
+        .map(|(l, r)| *r * x + l)
+        .collect::<Vec<_>>();
+    C::normalize_batch(&folded)
+}
+
+/// Returns the points multiplied by the scalars.
+fn scale<C: CurveGroup>(points: &[C::Affine], scalars: &[C::ScalarField]) -> Vec<C::Affine> {
+    let scaled = points
+        .par_iter()
+        .zip(scalars)
+        .map(|(point, scalar)| *point * scalar)
+        .collect::<Vec<_>>();
+    C::normalize_batch(&scaled)
+}
+
+/// Returns base * secret^i for i in 0..len.
+fn powers_of<C: CurveGroup>(base: C, secret: C::ScalarField, len: usize) -> Vec<C::Affine> {
+    let points = powers(secret, len)
+        .into_par_iter()
+        .map(|power| base * power)
+        .collect::<Vec<_>>();
+    C::normalize_batch(&points)
+}
+
+/// Returns x^i for i in 0..len.
+fn powers<F: Field>(x: F, len: usize) -> Vec<F> {
+    std::iter::successors(Some(F::one()), |power| Some(*power * x))
+        .take(len)
+        .collect()
+}
+
+/// The number of proofs after padding, which is a power of two and at least two.
+fn padded_len(num_proofs: usize) -> usize {
+    num_proofs.next_power_of_two().max(2)
+}
+
+/// Returns the coefficients of prod_j (1 + x_j * X^(2^(k-1-j))) for the challenges x_0, ..., x_(k-1), i.e., the factor
+/// of the i-th element in the folded vector.
+fn folding_coefficients<F: Field>(challenges: &[F]) -> Vec<F> {
+    let mut coeffs = vec![F::one()];
+    for x in challenges.iter().rev() {
+        let high = coeffs.iter().map(|coeff| *coeff * x).collect::<Vec<_>>();
+        coeffs.extend(high);
+    }
+    coeffs
+}
+
+/// Evaluates prod_j (1 + x_j * X^(2^(k-1-j))) at the point.
+fn evaluate_folding<F: Field>(challenges: &[F], point: F) -> F {
+    let mut power = point;
+    let mut result = F::one();
+    for x in challenges.iter().rev() {
+        result *= F::one() + *x * power;
+        power.square_in_place();
+    }
+    result
+}
+
+/// Returns the coefficients of (f(X) - f(z)) / (X - z).
+fn quotient<F: Field>(coeffs: &[F], z: F) -> Vec<F> {
+    let mut quotient = vec![F::zero(); coeffs.len().saturating_sub(1)];
+    let mut acc = F::zero();
+    for (i, coeff) in coeffs.iter().enumerate().skip(1).rev() {
+        acc = acc * z + coeff;
+        quotient[i - 1] = acc;
+    }
+    quotient
+}
+
+/// The Fiat-Shamir transcript of the aggregation, which chains the hashes of all messages.
+struct Transcript {
+    state: [u8; 32],
+}
+
+impl Transcript {
+    /// Starts a transcript bound to the public inputs of the proofs before padding.
+    fn new<F: PrimeField>(public_inputs: &[&[F]]) -> Self {
+        let mut transcript = Self {
+            state: Keccak256::digest(b"co-groth16 aggregation").into(),
+        };
+        transcript.append(&(public_inputs.len() as u64));
+        for inputs in public_inputs {
+            transcript.append(&inputs.to_vec());
+        }
+        transcript
+    }
+
+    fn append<S: CanonicalSerialize>(&mut self, message: &S) {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.state);
+        message
+            .serialize_compressed(&mut hasher)
+            .expect("can serialize into a hasher");
+        self.state = hasher.finalize().into();
+    }
+
+    /// Derives a non-zero challenge from the messages so far.
+    fn challenge<F: PrimeField>(&mut self) -> F {
+        loop {
+            self.append(b"challenge");
+            let challenge = F::from_le_bytes_mod_order(&self.state);
+            if !challenge.is_zero() {
+                return challenge;
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! A pool of buffers, which are reused instead of being reallocated for every proof.
+
+/// A pool of buffers. Taking a buffer reuses the allocation of a previously returned buffer, if there is one.
+///
+/// The polynomial arithmetic of the prover works on a few vectors of the size of the evaluation domain. Reusing them
+/// across the proofs of a batch avoids that the allocator has to provide fresh memory for every proof.
+pub(crate) struct BufferPool<T> {
+    buffers: Vec<Vec<T>>,
+}
+
+impl<T> Default for BufferPool<T> {
+    fn default() -> Self {
+        Self {
+            buffers: Vec::new(),
+        }
+    }
+}
+
+impl<T> BufferPool<T> {
+    /// Takes an empty buffer with a capacity of at least `capacity` elements from the pool.
+    pub(crate) fn take(&mut self, capacity: usize) -> Vec<T> {
+        // prefer the smallest buffer which is large enough, so larger buffers stay available
+        let fitting = self
+            .buffers
+            .iter()
+            .enumerate()
+            .filter(|(_, buffer)| buffer.capacity() >= capacity)
+            .min_by_key(|(_, buffer)| buffer.capacity())
+            .map(|(i, _)| i);
+        match fitting {
+            Some(i) => self.buffers.swap_remove(i),
+            None => Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns a buffer to the pool, so its allocation can be reused.
+    pub(crate) fn give(&mut self, mut buffer: Vec<T>) {
+        buffer.clear();
+        self.buffers.push(buffer);
+    }
+}
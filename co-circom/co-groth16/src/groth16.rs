@@ -2,28 +2,35 @@
 use ark_ec::pairing::Pairing;
 use ark_ec::scalar_mul::variable_base::VariableBaseMSM;
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{FftField, PrimeField};
+use ark_ff::{FftField, Field, PrimeField, UniformRand, Zero};
 use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
-use circom_types::groth16::{ConstraintMatrix, Groth16Proof, ZKey};
+use circom_types::groth16::{ConstraintMatrix, Groth16Proof, JsonVerificationKey, ZKey};
 use circom_types::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge};
-use co_circom_snarks::SharedWitness;
+use circom_types::R1CS;
+use co_circom_snarks::{OutputDisclosure, SharedWitness, WitnessCheck};
 use eyre::Result;
-use mpc_core::protocols::rep3::network::{IoContext, Rep3MpcNet};
-use mpc_core::protocols::shamir::network::ShamirMpcNet;
-use mpc_core::protocols::shamir::{ShamirPreprocessing, ShamirProtocol};
-use mpc_net::config::NetworkConfig;
-use num_traits::identities::One;
+use mpc_core::protocols::rep3::network::{IoContext, Rep3MpcNet, Rep3Network};
+use mpc_core::protocols::shamir::network::{ShamirMpcNet, ShamirNetwork};
+use mpc_core::protocols::shamir::{ShamirPreprocessing, ShamirPrimeFieldShare, ShamirProtocol};
+use mpc_core::protocols::spdz::{SpdzPreprocessedData, SpdzProtocol};
+use mpc_core::protocols::twopc::TwoPcProtocol;
+use mpc_net::config::{NetworkConfig, Phase};
 use num_traits::ToPrimitive;
+use rand::{CryptoRng, Rng};
 use rayon::prelude::*;
+use std::collections::BTreeSet;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::oneshot;
 use tracing::instrument;
 
+use crate::buffer_pool::BufferPool;
 use crate::mpc::plain::PlainGroth16Driver;
-use crate::mpc::rep3::Rep3Groth16Driver;
+use crate::mpc::rep3::{FftMode, Rep3Groth16Driver};
 use crate::mpc::shamir::ShamirGroth16Driver;
+use crate::mpc::spdz::SpdzGroth16Driver;
+use crate::mpc::twopc::TwoPcGroth16Driver;
 use crate::mpc::CircomGroth16Prover;
 
 macro_rules! rayon_join {
@@ -49,6 +56,10 @@ pub type Groth16<P> = CoGroth16<P, PlainGroth16Driver>;
 pub type Rep3CoGroth16<P, N> = CoGroth16<P, Rep3Groth16Driver<N>>;
 /// A type alias for a [CoGroth16] protocol using shamir secret sharing.
 pub type ShamirCoGroth16<P, N> = CoGroth16<P, ShamirGroth16Driver<<P as Pairing>::ScalarField, N>>;
+/// A type alias for a [CoGroth16] protocol using SPDZ.
+pub type SpdzCoGroth16<P, N> = CoGroth16<P, SpdzGroth16Driver<<P as Pairing>::ScalarField, N>>;
+/// A type alias for a [CoGroth16] protocol using the semi-honest 2PC protocol.
+pub type TwoPcCoGroth16<P, N> = CoGroth16<P, TwoPcGroth16Driver<<P as Pairing>::ScalarField, N>>;
 
 /* old way of computing root of unity, does not work for bls12_381:
 let root_of_unity = {
@@ -85,6 +96,42 @@ fn root_of_unity_for_groth16<F: PrimeField + FftField>(
     }
 }
 
+/// The evaluation domain of the witness map and the powers of its root of unity. They only depend on the [ZKey], so they are computed once and shared by all proofs of a batch (see [CoGroth16::prove_batch]).
+#[derive(Clone)]
+struct WitnessMapDomain<F: PrimeField> {
+    domain: Arc<GeneralEvaluationDomain<F>>,
+    roots: Arc<Vec<F>>,
+}
+
+impl<F: PrimeField + FftField> WitnessMapDomain<F> {
+    fn new(power: usize, num_coeffs: usize) -> Result<Self> {
+        let mut domain = GeneralEvaluationDomain::<F>::new(num_coeffs)
+            .ok_or(eyre::eyre!("Polynomial Degree too large"))?;
+        let root_of_unity_span = tracing::debug_span!("root of unity computation").entered();
+        let root_of_unity = root_of_unity_for_groth16(power, &mut domain);
+        let mut roots = Vec::with_capacity(domain.size());
+        let mut c = F::one();
+        for _ in 0..domain.size() {
+            roots.push(c);
+            c *= root_of_unity;
+        }
+        root_of_unity_span.exit();
+        Ok(Self {
+            domain: Arc::new(domain),
+            roots: Arc::new(roots),
+        })
+    }
+}
+
+/// The pending MSMs of a proof which only depend on the witness (see [CoGroth16::spawn_witness_msms]). The terms
+/// depending on the randomness r and s are added once the MSMs are finished.
+struct WitnessMsms<P: Pairing, T: CircomGroth16Prover<P>> {
+    a: oneshot::Receiver<T::PointShare<P::G1>>,
+    b_g1: oneshot::Receiver<T::PointShare<P::G1>>,
+    b_g2: oneshot::Receiver<T::PointShare<P::G2>>,
+    l: oneshot::Receiver<T::PointShare<P::G1>>,
+}
+
 /// A Groth16 proof protocol that uses a collaborative MPC protocol to generate the proof.
 pub struct CoGroth16<P: Pairing, T: CircomGroth16Prover<P>> {
     pub(crate) driver: T,
@@ -106,40 +153,284 @@ where
 
     /// Execute the Groth16 prover using the internal MPC driver.
     /// This version takes the Circom-generated constraint matrices as input and does not re-calculate them.
-    #[instrument(level = "debug", name = "Groth16 - Proof", skip_all)]
     pub fn prove(
-        mut self,
+        self,
         zkey: Arc<ZKey<P>>,
         private_witness: SharedWitness<P::ScalarField, T::ArithmeticShare>,
     ) -> Result<Groth16Proof<P>> {
+        let (proof, _) = self.prove_with_public_inputs(zkey, private_witness)?;
+        Ok(proof)
+    }
+
+    /// Execute the Groth16 prover using the internal MPC driver and additionally return the public inputs
+    /// of the proof (without the leading constant 1).
+    ///
+    /// If the witness was shared with hidden public inputs (see [SharedWitness::shared_public_inputs]), the public inputs
+    /// are only opened after the proof is assembled. Until then they are treated like any other private witness value.
+    pub fn prove_with_public_inputs(
+        mut self,
+        zkey: Arc<ZKey<P>>,
+        private_witness: SharedWitness<P::ScalarField, T::ArithmeticShare>,
+    ) -> Result<(Groth16Proof<P>, Vec<P::ScalarField>)> {
+        self.prove_and_open(zkey, None, &mut BufferPool::default(), private_witness)
+    }
+
+    /// Execute the Groth16 prover for many witnesses of the same circuit and return the proofs along with their public
+    /// inputs (without the leading constant 1), as [CoGroth16::prove_with_public_inputs] does for a single witness.
+    ///
+    /// The parsed [ZKey], the evaluation domain of the witness map and the network of the MPC driver are shared by all
+    /// proofs, so the setup is only done once. The proofs are generated one after the other, therefore all parties have
+    /// to provide their shares of the witnesses in the same order. As the prover is not consumed, it can be reused for
+    /// the next batch. Drivers with preprocessed data, e.g., SPDZ, need enough preprocessed data for all proofs.
+    #[instrument(level = "debug", name = "Groth16 - Batch Proof", skip_all)]
+    #[expect(clippy::type_complexity)]
+    pub fn prove_batch(
+        &mut self,
+        zkey: Arc<ZKey<P>>,
+        private_witnesses: impl IntoIterator<Item = SharedWitness<P::ScalarField, T::ArithmeticShare>>,
+    ) -> Result<Vec<(Groth16Proof<P>, Vec<P::ScalarField>)>> {
+        let domain = WitnessMapDomain::new(zkey.pow, zkey.num_constraints + zkey.n_public + 1)?;
+        let mut buffers = BufferPool::default();
+        private_witnesses
+            .into_iter()
+            .map(|private_witness| {
+                self.prove_and_open(
+                    Arc::clone(&zkey),
+                    Some(&domain),
+                    &mut buffers,
+                    private_witness,
+                )
+            })
+            .collect()
+    }
+
+    /// Creates the proof and opens the hidden public inputs, if any.
+    fn prove_and_open(
+        &mut self,
+        zkey: Arc<ZKey<P>>,
+        domain: Option<&WitnessMapDomain<P::ScalarField>>,
+        buffers: &mut BufferPool<T::ArithmeticShare>,
+        private_witness: SharedWitness<P::ScalarField, T::ArithmeticShare>,
+    ) -> Result<(Groth16Proof<P>, Vec<P::ScalarField>)> {
+        let (proof, public_inputs, hidden_public_inputs) =
+            self.prove_inner(zkey, domain, buffers, private_witness)?;
+        let public_inputs = if hidden_public_inputs.is_empty() {
+            public_inputs[1..].to_vec()
+        } else {
+            tracing::debug!(
+                "opening {} hidden public inputs",
+                hidden_public_inputs.len()
+            );
+            self.driver.open_vec(&hidden_public_inputs)?
+        };
+        Ok((proof, public_inputs))
+    }
+
+    /// Execute the Groth16 prover using the internal MPC driver and additionally return the public inputs
+    /// of the proof (without the leading constant 1) this party may learn.
+    ///
+    /// The hidden public inputs (see [SharedWitness::shared_public_inputs]) are opened after the proof is assembled,
+    /// each only towards the parties given by its [OutputDisclosure]. Public inputs which are not hidden are known to
+    /// all parties anyway. The disclosures are usually obtained by resolving an
+    /// [OutputPolicy](co_circom_snarks::OutputPolicy) and must be the same for all parties.
+    #[expect(clippy::type_complexity)]
+    pub fn prove_with_output_policy(
+        mut self,
+        zkey: Arc<ZKey<P>>,
+        private_witness: SharedWitness<P::ScalarField, T::ArithmeticShare>,
+        disclosures: &[OutputDisclosure],
+    ) -> Result<(Groth16Proof<P>, Vec<Option<P::ScalarField>>)> {
+        let (proof, public_inputs, hidden_public_inputs) =
+            self.prove_inner(zkey, None, &mut BufferPool::default(), private_witness)?;
+        if hidden_public_inputs.is_empty() {
+            return Ok((
+                proof,
+                public_inputs[1..].iter().copied().map(Some).collect(),
+            ));
+        }
+        if disclosures.len() != hidden_public_inputs.len() {
+            eyre::bail!(
+                "expected {} output disclosures, but got {}",
+                hidden_public_inputs.len(),
+                disclosures.len()
+            );
+        }
+
+        let mut revealed = vec![None; hidden_public_inputs.len()];
+        let (to_all, shares): (Vec<_>, Vec<_>) = disclosures
+            .iter()
+            .zip(hidden_public_inputs.iter())
+            .enumerate()
+            .filter(|(_, (disclosure, _))| **disclosure == OutputDisclosure::All)
+            .map(|(i, (_, share))| (i, *share))
+            .unzip();
+        if !to_all.is_empty() {
+            let opened = self.driver.open_vec(&shares)?;
+            for (i, value) in to_all.into_iter().zip(opened) {
+                revealed[i] = Some(value);
+            }
+        }
+
+        // open towards the individual parties in ascending order, so that all parties perform the same steps
+        let targets = disclosures
+            .iter()
+            .filter_map(|disclosure| match disclosure {
+                OutputDisclosure::Parties(parties) => Some(parties.iter().copied()),
+                _ => None,
+            })
+            .flatten()
+            .collect::<BTreeSet<_>>();
+        for party_id in targets {
+            let (indices, shares): (Vec<_>, Vec<_>) = disclosures
+                .iter()
+                .zip(hidden_public_inputs.iter())
+                .enumerate()
+                .filter(|(_, (disclosure, _))| {
+                    matches!(disclosure, OutputDisclosure::Parties(parties) if parties.contains(&party_id))
+                })
+                .map(|(i, (_, share))| (i, *share))
+                .unzip();
+            if let Some(opened) = self.driver.open_vec_to(&shares, party_id)? {
+                for (i, value) in indices.into_iter().zip(opened) {
+                    revealed[i] = Some(value);
+                }
+            }
+        }
+        Ok((proof, revealed))
+    }
+
+    /// Checks in MPC that the shared witness satisfies the constraints of the provided R1CS. This optional phase is
+    /// much cheaper than the proof generation and can be run before [CoGroth16::prove] to abort early on a bad witness.
+    ///
+    /// For every batch of constraints, a random linear combination of A(w) * B(w) - C(w) is opened (see
+    /// [WitnessCheck]). If a batch does not open to zero, an
+    /// [UnsatisfiedConstraintsError](co_circom_snarks::UnsatisfiedConstraintsError) naming the batch is returned.
+    #[instrument(level = "debug", name = "Groth16 - witness check", skip_all)]
+    pub fn check_witness(
+        &mut self,
+        r1cs: &R1CS<P>,
+        private_witness: &SharedWitness<P::ScalarField, T::ArithmeticShare>,
+        check: WitnessCheck,
+    ) -> Result<()> {
+        let id = self.driver.get_party_id();
+        let start = Instant::now();
+        let public_inputs = &private_witness.public_inputs;
+        // hidden public inputs directly follow the constant 1, so they are the first elements of the private witness
+        let mut witness = private_witness.shared_public_inputs.clone();
+        witness.extend_from_slice(&private_witness.witness);
+
+        // the r1cs stores (index, coeff) pairs, whereas the zkey stores (coeff, index) pairs
+        let evaluate = |lc: &[(usize, P::ScalarField)]| {
+            let lc = lc
+                .iter()
+                .map(|(index, coeff)| (*coeff, *index))
+                .collect::<Vec<_>>();
+            T::evaluate_constraint(id, &lc, public_inputs, &witness)
+        };
+        let a = r1cs
+            .constraints
+            .par_iter()
+            .map(|(a, _, _)| evaluate(a))
+            .collect::<Vec<_>>();
+        let b = r1cs
+            .constraints
+            .par_iter()
+            .map(|(_, b, _)| evaluate(b))
+            .collect::<Vec<_>>();
+        let c = r1cs
+            .constraints
+            .par_iter()
+            .map(|(_, _, c)| evaluate(c))
+            .collect::<Vec<_>>();
+
+        let num_constraints = r1cs.constraints.len();
+        let challenge = self.driver.rand()?;
+        let challenge = self.driver.open_vec(&[challenge])?[0];
+        let coefficients = check.coefficients(challenge, num_constraints);
+        let ab = self.driver.local_mul_vec(&a, &b);
+        self.driver.verify_local_mul_vec(&a, &b, &ab)?;
+        let (ab_sums, c_sums): (Vec<_>, Vec<_>) = check
+            .batches(num_constraints)
+            .map(|batch| {
+                let ab_sum = ab[batch.clone()]
+                    .iter()
+                    .zip(&coefficients[batch.clone()])
+                    .map(|(ab, coeff)| *ab * coeff)
+                    .sum::<P::ScalarField>();
+                let mut c_sum = T::ArithmeticShare::default();
+                for (c, coeff) in c[batch.clone()].iter().zip(&coefficients[batch]) {
+                    let mut c = *c;
+                    c *= *coeff;
+                    c_sum += c;
+                }
+                (ab_sum, c_sum)
+            })
+            .unzip();
+        let ab_sums = self.driver.io_round_mul_vec(ab_sums)?;
+        let combinations = ab_sums
+            .into_iter()
+            .zip(c_sums)
+            .map(|(ab, c)| ab - c)
+            .collect::<Vec<_>>();
+        let opened = self.driver.open_vec(&combinations)?;
+        check.verify(&opened, num_constraints)?;
+
+        let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+        tracing::info!("Party {}: Witness check took {} ms", id, duration_ms);
+        Ok(())
+    }
+
+    /// Creates the proof and returns it along with the public inputs and the shares of the hidden public inputs. The
+    /// domain of the witness map is computed if it is not given.
+    #[instrument(level = "debug", name = "Groth16 - Proof", skip_all)]
+    #[expect(clippy::type_complexity)]
+    fn prove_inner(
+        &mut self,
+        zkey: Arc<ZKey<P>>,
+        domain: Option<&WitnessMapDomain<P::ScalarField>>,
+        buffers: &mut BufferPool<T::ArithmeticShare>,
+        private_witness: SharedWitness<P::ScalarField, T::ArithmeticShare>,
+    ) -> Result<(
+        Groth16Proof<P>,
+        Arc<Vec<P::ScalarField>>,
+        Vec<T::ArithmeticShare>,
+    )> {
         let id = self.driver.get_party_id();
         tracing::info!("Party {}: starting proof generation..", id);
         let start = Instant::now();
-        let public_inputs = Arc::new(private_witness.public_inputs);
-        if public_inputs.len() != zkey.n_public + 1 {
+        if private_witness.num_public_inputs() != zkey.n_public + 1 {
             eyre::bail!(
                 "amount of public inputs do not match with provided zkey! Expected {}, but got {}",
                 zkey.n_public + 1,
-                public_inputs.len()
+                private_witness.num_public_inputs()
             )
         }
+        if private_witness.has_hidden_public_inputs() && private_witness.public_inputs.len() != 1 {
+            eyre::bail!("hidden public inputs must either be all shared or all public");
+        }
 
-        let private_witness = Arc::new(private_witness.witness);
-        let h = self.witness_map_from_matrices(&zkey, &public_inputs, &private_witness)?;
+        let num_hidden = private_witness.shared_public_inputs.len();
+        let public_inputs = Arc::new(private_witness.public_inputs);
+        // hidden public inputs directly follow the constant 1, so they are the first elements of the private witness
+        let mut witness = private_witness.shared_public_inputs;
+        witness.extend(private_witness.witness);
+        let private_witness = Arc::new(witness);
+        // the MSMs over the witness do not depend on h, so they overlap with the network rounds of the witness map
+        let witness_msms = Self::spawn_witness_msms(id, &zkey, &public_inputs, &private_witness);
+        let h = self.witness_map_from_matrices(
+            &zkey,
+            domain,
+            buffers,
+            &public_inputs,
+            &private_witness,
+        )?;
         let (r, s) = (self.driver.rand()?, self.driver.rand()?);
 
-        let proof = self.create_proof_with_assignment(
-            Arc::clone(&zkey),
-            r,
-            s,
-            h,
-            public_inputs,
-            private_witness,
-        )?;
+        let proof = self.create_proof_with_assignment(Arc::clone(&zkey), r, s, h, witness_msms)?;
 
         let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
         tracing::info!("Party {}: Proof generation took {} ms", id, duration_ms);
-        Ok(proof)
+        Ok((proof, public_inputs, private_witness[..num_hidden].to_vec()))
     }
 
     fn evaluate_constraint(
@@ -148,12 +439,15 @@ where
         matrix: &ConstraintMatrix<P::ScalarField>,
         public_inputs: &[P::ScalarField],
         private_witness: &[T::ArithmeticShare],
+        mut result: Vec<T::ArithmeticShare>,
     ) -> Vec<T::ArithmeticShare> {
-        let mut result = matrix
+        // reserve the whole domain upfront, so padding the evaluations does not reallocate
+        result.reserve_exact(domain_size);
+        matrix
             .par_iter()
             .with_min_len(256)
             .map(|x| T::evaluate_constraint(party_id, x, public_inputs, private_witness))
-            .collect::<Vec<_>>();
+            .collect_into_vec(&mut result);
         result.resize(domain_size, T::ArithmeticShare::default());
         result
     }
@@ -162,32 +456,28 @@ where
     fn witness_map_from_matrices(
         &mut self,
         zkey: &ZKey<P>,
+        domain: Option<&WitnessMapDomain<P::ScalarField>>,
+        buffers: &mut BufferPool<T::ArithmeticShare>,
         public_inputs: &[P::ScalarField],
         private_witness: &[T::ArithmeticShare],
     ) -> Result<Vec<P::ScalarField>> {
         let num_constraints = zkey.num_constraints;
         let num_inputs = zkey.n_public + 1;
         let power = zkey.pow;
-        let mut domain =
-            GeneralEvaluationDomain::<P::ScalarField>::new(num_constraints + num_inputs)
-                .ok_or(eyre::eyre!("Polynomial Degree too large"))?;
-        let domain_size = domain.size();
+        let domain_size = match domain {
+            Some(domain) => domain.domain.size(),
+            None => GeneralEvaluationDomain::<P::ScalarField>::new(num_constraints + num_inputs)
+                .ok_or(eyre::eyre!("Polynomial Degree too large"))?
+                .size(),
+        };
         let party_id = self.driver.get_party_id();
+        let (a_buffer, b_buffer) = (buffers.take(domain_size), buffers.take(domain_size));
         let eval_constraint_span =
             tracing::debug_span!("evaluate constraints + root of unity computation").entered();
-        let (roots_to_power_domain, a, b) = rayon_join!(
-            {
-                let root_of_unity_span =
-                    tracing::debug_span!("root of unity computation").entered();
-                let root_of_unity = root_of_unity_for_groth16(power, &mut domain);
-                let mut roots = Vec::with_capacity(domain_size);
-                let mut c = P::ScalarField::one();
-                for _ in 0..domain_size {
-                    roots.push(c);
-                    c *= root_of_unity;
-                }
-                root_of_unity_span.exit();
-                Arc::new(roots)
+        let (domain, a, b) = rayon_join!(
+            match domain {
+                Some(domain) => Ok(domain.clone()),
+                None => WitnessMapDomain::new(power, num_constraints + num_inputs),
             },
             {
                 let eval_constraint_span_a =
@@ -198,8 +488,13 @@ where
                     &zkey.a_matrix,
                     public_inputs,
                     private_witness,
+                    a_buffer,
+                );
+                let mut promoted_public = T::promote_to_trivial_shares(party_id, public_inputs);
+                // hidden public inputs are the first elements of the private witness
+                promoted_public.extend_from_slice(
+                    &private_witness[..num_inputs.saturating_sub(public_inputs.len())],
                 );
-                let promoted_public = T::promote_to_trivial_shares(party_id, public_inputs);
                 result[num_constraints..num_constraints + num_inputs]
                     .clone_from_slice(&promoted_public[..num_inputs]);
                 eval_constraint_span_a.exit();
@@ -214,6 +509,7 @@ where
                     &zkey.b_matrix,
                     public_inputs,
                     private_witness,
+                    b_buffer,
                 );
                 eval_constraint_span_b.exit();
                 result
@@ -221,40 +517,19 @@ where
         );
 
         eval_constraint_span.exit();
-        let domain = Arc::new(domain);
+        let WitnessMapDomain {
+            domain,
+            roots: roots_to_power_domain,
+        } = domain?;
 
-        let (a_tx, a_rx) = oneshot::channel();
-        let (b_tx, b_rx) = oneshot::channel();
         let (c_tx, c_rx) = oneshot::channel();
-        let a_domain = Arc::clone(&domain);
-        let b_domain = Arc::clone(&domain);
         let c_domain = Arc::clone(&domain);
-        let mut a_result = a.clone();
-        let mut b_result = b.clone();
-        let a_roots = Arc::clone(&roots_to_power_domain);
-        let b_roots = Arc::clone(&roots_to_power_domain);
         let c_roots = Arc::clone(&roots_to_power_domain);
-        rayon::spawn(move || {
-            let a_span = tracing::debug_span!("a: distribute powers mul a (fft/ifft)").entered();
-            a_domain.ifft_in_place(&mut a_result);
-            T::distribute_powers_and_mul_by_const(&mut a_result, &a_roots);
-            a_domain.fft_in_place(&mut a_result);
-            a_tx.send(a_result).expect("channel not droped");
-            a_span.exit();
-        });
-
-        rayon::spawn(move || {
-            let b_span = tracing::debug_span!("b: distribute powers mul b (fft/ifft)").entered();
-            b_domain.ifft_in_place(&mut b_result);
-            T::distribute_powers_and_mul_by_const(&mut b_result, &b_roots);
-            b_domain.fft_in_place(&mut b_result);
-            b_tx.send(b_result).expect("channel not droped");
-            b_span.exit();
-        });
 
         let local_mul_vec_span = tracing::debug_span!("c: local_mul_vec").entered();
-        let mut ab = self.driver.local_mul_vec(a, b);
+        let mut ab = self.driver.local_mul_vec(&a, &b);
         local_mul_vec_span.exit();
+        self.driver.verify_local_mul_vec(&a, &b, &ab)?;
         rayon::spawn(move || {
             let ifft_span = tracing::debug_span!("c: ifft in dist pows").entered();
             c_domain.ifft_in_place(&mut ab);
@@ -273,14 +548,20 @@ where
             c_tx.send(ab).expect("channel not dropped");
         });
 
-        let a = a_rx.blocking_recv()?;
-        let b = b_rx.blocking_recv()?;
+        // A and B are transformed by the driver, as some drivers split the work between the parties
+        let (a, b) = self
+            .driver
+            .evaluate_on_coset(&domain, &roots_to_power_domain, a, b)?;
 
         let compute_ab_span = tracing::debug_span!("compute ab").entered();
         let local_ab_span = tracing::debug_span!("local part (mul and sub)").entered();
         // same as above. No IO task is run at the moment.
-        let mut ab = self.driver.local_mul_vec(a, b);
+        let mut ab = self.driver.local_mul_vec(&a, &b);
         local_ab_span.exit();
+        self.driver.verify_local_mul_vec(&a, &b, &ab)?;
+        // A and B are not needed anymore, so their buffers can be reused while C is still computed
+        buffers.give(a);
+        buffers.give(b);
         let c = c_rx.blocking_recv()?;
         ab.par_iter_mut()
             .zip_eq(c.par_iter())
@@ -294,7 +575,6 @@ where
 
     fn calculate_coeff<C>(
         id: T::PartyID,
-        initial: T::PointShare<C>,
         query: &[C::Affine],
         vk_param: C::Affine,
         input_assignment: &[P::ScalarField],
@@ -310,112 +590,107 @@ where
             || C::msm_unchecked(&query[1..=pub_len], input_assignment),
         );
 
-        let mut res = initial;
+        let mut res = priv_acc;
         T::add_assign_points_public(id, &mut res, &query[0].into_group());
         T::add_assign_points_public(id, &mut res, &vk_param.into_group());
         T::add_assign_points_public(id, &mut res, &pub_acc);
-        T::add_assign_points(&mut res, &priv_acc);
         res
     }
 
-    #[instrument(level = "debug", name = "create proof with assignment", skip_all)]
-    fn create_proof_with_assignment(
-        mut self,
-        zkey: Arc<ZKey<P>>,
-        r: T::ArithmeticShare,
-        s: T::ArithmeticShare,
-        h: Vec<P::ScalarField>,
-        input_assignment: Arc<Vec<P::ScalarField>>,
-        aux_assignment: Arc<Vec<T::ArithmeticShare>>,
-    ) -> Result<Groth16Proof<P>> {
-        let delta_g1 = zkey.delta_g1.into_group();
-        let (l_acc_tx, l_acc_rx) = oneshot::channel();
-        let (h_acc_tx, h_acc_rx) = oneshot::channel();
-        let h_query = Arc::clone(&zkey);
-        let l_query = Arc::clone(&zkey);
-
-        let party_id = self.driver.get_party_id();
-        let (r_g1_tx, r_g1_rx) = oneshot::channel();
-        let (s_g1_tx, s_g1_rx) = oneshot::channel();
-        let (s_g2_tx, s_g2_rx) = oneshot::channel();
-        let a_query = Arc::clone(&zkey);
-        let b_g1_query = Arc::clone(&zkey);
-        let b_g2_query = Arc::clone(&zkey);
-        let input_assignment1 = Arc::clone(&input_assignment);
-        let input_assignment2 = Arc::clone(&input_assignment);
-        let input_assignment3 = Arc::clone(&input_assignment);
-        let aux_assignment1 = Arc::clone(&aux_assignment);
-        let aux_assignment2 = Arc::clone(&aux_assignment);
-        let aux_assignment3 = Arc::clone(&aux_assignment);
-        let aux_assignment4 = Arc::clone(&aux_assignment);
-        let alpha_g1 = zkey.alpha_g1;
-        let beta_g1 = zkey.beta_g1;
-        let beta_g2 = zkey.beta_g2;
-        let delta_g2 = zkey.delta_g2.into_group();
+    /// Starts the MSMs which only depend on the witness. They do not need any network round, so they run in the
+    /// background while the parties communicate during the witness map.
+    fn spawn_witness_msms(
+        party_id: T::PartyID,
+        zkey: &Arc<ZKey<P>>,
+        input_assignment: &Arc<Vec<P::ScalarField>>,
+        aux_assignment: &Arc<Vec<T::ArithmeticShare>>,
+    ) -> WitnessMsms<P, T> {
+        // the l_query only covers the private witness, so we have to skip the hidden public inputs
+        let num_hidden = zkey.n_public + 1 - input_assignment.len();
+        let (a_tx, a) = oneshot::channel();
+        let (b_g1_tx, b_g1) = oneshot::channel();
+        let (b_g2_tx, b_g2) = oneshot::channel();
+        let (l_tx, l) = oneshot::channel();
+
+        let a_query = Arc::clone(zkey);
+        let b_g1_query = Arc::clone(zkey);
+        let b_g2_query = Arc::clone(zkey);
+        let l_query = Arc::clone(zkey);
+        let input_assignment1 = Arc::clone(input_assignment);
+        let input_assignment2 = Arc::clone(input_assignment);
+        let input_assignment3 = Arc::clone(input_assignment);
+        let aux_assignment1 = Arc::clone(aux_assignment);
+        let aux_assignment2 = Arc::clone(aux_assignment);
+        let aux_assignment3 = Arc::clone(aux_assignment);
+        let aux_assignment4 = Arc::clone(aux_assignment);
 
         rayon::spawn(move || {
-            let compute_a =
-                tracing::debug_span!("compute A in create proof with assignment").entered();
-            // Compute A
-            let r_g1 = T::scalar_mul_public_point(&delta_g1, r);
-            let r_g1 = Self::calculate_coeff(
+            let compute_a = tracing::debug_span!("msm a_query").entered();
+            let result = Self::calculate_coeff(
                 party_id,
-                r_g1,
                 &a_query.a_query,
-                alpha_g1,
+                a_query.alpha_g1,
                 &input_assignment1[1..],
                 &aux_assignment1,
             );
-            r_g1_tx.send(r_g1).expect("not dropped");
+            a_tx.send(result).expect("channel not dropped");
             compute_a.exit();
         });
 
         rayon::spawn(move || {
-            let compute_b =
-                tracing::debug_span!("compute B/G1 in create proof with assignment").entered();
-            // Compute B in G1
-            // In original implementation this is skipped if r==0, however r is shared in our case
-            let s_g1 = T::scalar_mul_public_point(&delta_g1, s);
-            let s_g1 = Self::calculate_coeff(
+            let compute_b = tracing::debug_span!("msm b_g1_query").entered();
+            let result = Self::calculate_coeff(
                 party_id,
-                s_g1,
                 &b_g1_query.b_g1_query,
-                beta_g1,
+                b_g1_query.beta_g1,
                 &input_assignment2[1..],
                 &aux_assignment2,
             );
-            s_g1_tx.send(s_g1).expect("not dropped");
+            b_g1_tx.send(result).expect("channel not dropped");
             compute_b.exit();
         });
 
         rayon::spawn(move || {
-            let compute_b =
-                tracing::debug_span!("compute B/G2 in create proof with assignment").entered();
-            // Compute B in G2
-            let s_g2 = T::scalar_mul_public_point(&delta_g2, s);
-            let s_g2 = Self::calculate_coeff(
+            let compute_b = tracing::debug_span!("msm b_g2_query").entered();
+            let result = Self::calculate_coeff(
                 party_id,
-                s_g2,
                 &b_g2_query.b_g2_query,
-                beta_g2,
+                b_g2_query.beta_g2,
                 &input_assignment3[1..],
                 &aux_assignment3,
             );
-            s_g2_tx.send(s_g2).expect("not dropped");
+            b_g2_tx.send(result).expect("channel not dropped");
             compute_b.exit();
         });
 
         rayon::spawn(move || {
             let msm_l_query = tracing::debug_span!("msm l_query").entered();
-            let result = T::msm_public_points(&l_query.l_query, &aux_assignment4);
-            l_acc_tx.send(result).expect("channel not dropped");
+            let result = T::msm_public_points(&l_query.l_query, &aux_assignment4[num_hidden..]);
+            l_tx.send(result).expect("channel not dropped");
             msm_l_query.exit();
         });
 
+        WitnessMsms { a, b_g1, b_g2, l }
+    }
+
+    #[instrument(level = "debug", name = "create proof with assignment", skip_all)]
+    fn create_proof_with_assignment(
+        &mut self,
+        zkey: Arc<ZKey<P>>,
+        r: T::ArithmeticShare,
+        s: T::ArithmeticShare,
+        h: Vec<P::ScalarField>,
+        witness_msms: WitnessMsms<P, T>,
+    ) -> Result<Groth16Proof<P>> {
+        let delta_g1 = zkey.delta_g1.into_group();
+        let delta_g2 = zkey.delta_g2.into_group();
+        let (h_acc_tx, h_acc_rx) = oneshot::channel();
+
+        // the MSM for h starts as soon as h is available, the other MSMs are already running since the witness map
         rayon::spawn(move || {
             let msm_h_query = tracing::debug_span!("msm h_query").entered();
             //perform the msm for h
-            let result = P::G1::msm_unchecked(&h_query.h_query, &h);
+            let result = P::G1::msm_unchecked(&zkey.h_query, &h);
             h_acc_tx.send(result).expect("channel not dropped");
             msm_h_query.exit();
         });
@@ -427,8 +702,13 @@ where
         let r_s_delta_g1 = T::scalar_mul_public_point(&delta_g1, rs);
         rs_span.exit();
 
-        let g_a = r_g1_rx.blocking_recv()?;
-        let g1_b = s_g1_rx.blocking_recv()?;
+        // Compute A
+        let mut g_a = T::scalar_mul_public_point(&delta_g1, r);
+        T::add_assign_points(&mut g_a, &witness_msms.a.blocking_recv()?);
+        // Compute B in G1
+        // In original implementation this is skipped if r==0, however r is shared in our case
+        let mut g1_b = T::scalar_mul_public_point(&delta_g1, s);
+        T::add_assign_points(&mut g1_b, &witness_msms.b_g1.blocking_recv()?);
 
         let network_round = tracing::debug_span!("network round after calc coeff").entered();
         let (g_a_opened, r_g1_b) = self.driver.open_point_and_scalar_mul(&g_a, &g1_b, r)?;
@@ -440,13 +720,15 @@ where
         let mut g_c = s_g_a;
         T::add_assign_points(&mut g_c, &r_g1_b);
         T::sub_assign_points(&mut g_c, &r_s_delta_g1);
-        let l_aux_acc = l_acc_rx.blocking_recv().expect("channel not dropped");
+        let l_aux_acc = witness_msms.l.blocking_recv()?;
         T::add_assign_points(&mut g_c, &l_aux_acc);
 
         let h_acc = h_acc_rx.blocking_recv()?;
         let g_c = T::add_points_half_share(g_c, &h_acc);
 
-        let g2_b = s_g2_rx.blocking_recv()?;
+        // Compute B in G2
+        let mut g2_b = T::scalar_mul_public_point(&delta_g2, s);
+        T::add_assign_points(&mut g2_b, &witness_msms.b_g2.blocking_recv()?);
         let (g_c_opened, g2_b_opened) = self.driver.open_two_points(g_c, g2_b)?;
         last_round.exit();
 
@@ -460,6 +742,20 @@ where
     }
 }
 
+impl<P: Pairing, N: Rep3Network + 'static> Rep3CoGroth16<P, N>
+where
+    P: CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Sets the [`FftMode`] used to compute the H polynomial. All parties have to use the same mode. Defaults to
+    /// [`FftMode::Distributed`], [`FftMode::Replicated`] avoids the additional communication round.
+    pub fn with_fft_mode(mut self, fft_mode: FftMode) -> Self {
+        self.driver.set_fft_mode(fft_mode);
+        self
+    }
+}
+
 impl<P: Pairing> Rep3CoGroth16<P, Rep3MpcNet>
 where
     P: CircomArkworksPairingBridge,
@@ -477,25 +773,48 @@ where
         })
     }
 
-    /// Create a new [Rep3CoGroth16] protocol with a given network configuration.
+    /// Create a new [Rep3CoGroth16] protocol with a given network configuration. The proof generation is bounded by the proof assembly timeout of the configuration.
     pub fn with_network_config(config: NetworkConfig) -> Result<Self> {
-        let mpc_net = Rep3MpcNet::new(config)?;
+        let mut mpc_net = Rep3MpcNet::new(config)?;
+        mpc_net.start_phase(Phase::ProofAssembly)?;
         Self::with_network(mpc_net)
     }
 }
 
+impl<P: Pairing, N: ShamirNetwork> ShamirCoGroth16<P, N>
+where
+    P: CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Checks in MPC that all shared values of the witness are shared with polynomials of degree threshold. This optional
+    /// phase can be run before [CoGroth16::prove] to detect malformed input shares early, instead of producing a
+    /// proof that does not verify.
+    #[instrument(level = "debug", name = "Groth16 - degree check", skip_all)]
+    pub fn check_witness_degree(
+        &mut self,
+        private_witness: &SharedWitness<P::ScalarField, ShamirPrimeFieldShare<P::ScalarField>>,
+    ) -> Result<()> {
+        let mut witness = private_witness.shared_public_inputs.clone();
+        witness.extend_from_slice(&private_witness.witness);
+        self.driver.verify_degree_vec(&witness)?;
+        Ok(())
+    }
+}
+
 impl<P: Pairing> ShamirCoGroth16<P, ShamirMpcNet>
 where
     P: CircomArkworksPairingBridge,
     P::BaseField: CircomArkworksPrimeFieldBridge,
     P::ScalarField: CircomArkworksPrimeFieldBridge,
 {
-    /// Create a new [ShamirCoGroth16] protocol with a given network configuration.
+    /// Create a new [ShamirCoGroth16] protocol with a given network configuration. The proof generation is bounded by the proof assembly timeout of the configuration.
     pub fn with_network_config(threshold: usize, config: NetworkConfig) -> Result<Self> {
         // we need 2 + 1 number of corr rand pairs. We need the values r/s (1 pair) and 2 muls (2
         // pairs)
         let num_pairs = 3;
-        let mpc_net = ShamirMpcNet::new(config)?;
+        let mut mpc_net = ShamirMpcNet::new(config)?;
+        mpc_net.start_phase(Phase::ProofAssembly)?;
         let preprocessing = ShamirPreprocessing::new(threshold, mpc_net, num_pairs)?;
         let mut protocol0 = ShamirProtocol::from(preprocessing);
         // the protocol1 is only used for scalar_mul and a field_mul which need 1 pair each (ergo 2
@@ -509,6 +828,46 @@ where
     }
 }
 
+impl<P: Pairing> SpdzCoGroth16<P, ShamirMpcNet>
+where
+    P: CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Create a new [SpdzCoGroth16] protocol with a given network configuration and the preprocessed data of the party.
+    /// The preprocessed data has to contain at least 2 * domain_size + 4 multiplication triples.
+    pub fn with_network_config(
+        config: NetworkConfig,
+        preprocessing: SpdzPreprocessedData<P::ScalarField>,
+    ) -> Result<Self> {
+        let mpc_net = ShamirMpcNet::new(config)?;
+        let protocol = SpdzProtocol::new(mpc_net, preprocessing)?;
+        let driver = SpdzGroth16Driver::new(protocol);
+        Ok(CoGroth16 {
+            driver,
+            phantom_data: PhantomData,
+        })
+    }
+}
+
+impl<P: Pairing> TwoPcCoGroth16<P, ShamirMpcNet>
+where
+    P: CircomArkworksPairingBridge,
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Create a new [TwoPcCoGroth16] protocol with a given network configuration for exactly two parties.
+    pub fn with_network_config(config: NetworkConfig) -> Result<Self> {
+        let mpc_net = ShamirMpcNet::new_two_party(config)?;
+        let protocol = TwoPcProtocol::new(mpc_net)?;
+        let driver = TwoPcGroth16Driver::new(protocol);
+        Ok(CoGroth16 {
+            driver,
+            phantom_data: PhantomData,
+        })
+    }
+}
+
 impl<P: Pairing> Groth16<P>
 where
     P: CircomArkworksPairingBridge,
@@ -529,4 +888,51 @@ where
         };
         prover.prove(zkey, private_witness)
     }
+
+    /// *Locally* create a `Groth16` proof and additionally return the public inputs of the proof (without the leading
+    /// constant 1), as [`CoGroth16::prove_with_public_inputs`] does in MPC.
+    ///
+    /// This is the fast path for a witness without any secret-shared values, e.g., when the MPC-VM reports a fully
+    /// public witness because all inputs were public. No network is needed, and the proof has the same format as a
+    /// proof created in MPC.
+    pub fn plain_prove_with_public_inputs(
+        zkey: Arc<ZKey<P>>,
+        private_witness: SharedWitness<P::ScalarField, P::ScalarField>,
+    ) -> Result<(Groth16Proof<P>, Vec<P::ScalarField>)> {
+        let prover = Self {
+            driver: PlainGroth16Driver,
+            phantom_data: PhantomData,
+        };
+        prover.prove_with_public_inputs(zkey, private_witness)
+    }
+
+    /// Rerandomizes a finished Groth16 proof with fresh randomness, without knowledge of the witness.
+    ///
+    /// The rerandomized proof is valid for the same public inputs, but cannot be linked to the original proof. Hence,
+    /// e.g., a relayer can submit the proof without revealing which proof the MPC parties produced.
+    pub fn rerandomize_proof<R: Rng + CryptoRng>(
+        vk: &JsonVerificationKey<P>,
+        proof: &Groth16Proof<P>,
+        rng: &mut R,
+    ) -> Groth16Proof<P> {
+        // with A' = A / r1, B' = r1 * B + r1 * r2 * delta and C' = C + r2 * A, the left side of the verification
+        // equation gains the factor e(A, delta)^r2, which is matched by the one of C'
+        let r1 = loop {
+            let r1 = P::ScalarField::rand(rng);
+            if !r1.is_zero() {
+                break r1;
+            }
+        };
+        let r2 = P::ScalarField::rand(rng);
+        let pi_a = proof.pi_a * r1.inverse().expect("r1 is not zero");
+        let pi_b = proof.pi_b * r1 + vk.delta_2 * (r1 * r2);
+        let pi_c = proof.pi_a * r2 + proof.pi_c;
+        Groth16Proof {
+            pi_a: pi_a.into_affine(),
+            pi_b: pi_b.into_affine(),
+            pi_c: pi_c.into_affine(),
+            protocol: proof.protocol.clone(),
+            curve: proof.curve.clone(),
+        }
+    }
 }
@@ -1,33 +1,50 @@
 //! A library for creating and verifying Groth16 proofs in a collaborative fashion using MPC.
 #![warn(missing_docs)]
+pub mod aggregation;
+mod buffer_pool;
 mod groth16;
 /// This module contains the Groth16 prover trait
 pub mod mpc;
 #[cfg(feature = "verifier")]
 mod verifier;
 
+#[cfg(feature = "verifier")]
+pub use ark_groth16::PreparedVerifyingKey;
 pub use groth16::CoGroth16;
 pub use groth16::Groth16;
 pub use groth16::Rep3CoGroth16;
 pub use groth16::ShamirCoGroth16;
+pub use groth16::SpdzCoGroth16;
+pub use groth16::TwoPcCoGroth16;
 
 #[cfg(test)]
 #[cfg(feature = "verifier")]
 mod tests {
     use ark_bls12_381::Bls12_381;
     use ark_bn254::Bn254;
+    use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+    use ark_ff::{Field, One, UniformRand, Zero};
+    use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
     use circom_types::{
-        groth16::{Groth16Proof, JsonPublicInput, JsonVerificationKey, ZKey},
-        traits::CheckElement,
-        Witness,
+        groth16::{ConstraintMatrix, Groth16Proof, JsonPublicInput, JsonVerificationKey, ZKey},
+        traits::{CheckElement, CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
+        Witness, R1CS,
+    };
+    use co_circom_snarks::{
+        SharedWitness, UnsatisfiedConstraintsError, VerificationError, WitnessCheck,
     };
-    use co_circom_snarks::SharedWitness;
     use std::{
         fs::{self, File},
         sync::Arc,
     };
 
+    use crate::aggregation::{
+        aggregate_proofs, verify_aggregate_proof, AggregateProof, AggregationSrs,
+    };
     use crate::groth16::Groth16;
+    use crate::mpc::PlainGroth16Driver;
+    use crate::PreparedVerifyingKey;
 
     #[test]
     fn create_proof_and_verify_bn254() {
@@ -46,6 +63,7 @@ mod tests {
             let public_input = witness.values[..=zkey.n_public].to_vec();
             let witness = SharedWitness {
                 public_inputs: public_input.clone(),
+                shared_public_inputs: vec![],
                 witness: witness.values[zkey.n_public + 1..].to_vec(),
             };
             let proof =
@@ -56,6 +74,71 @@ mod tests {
         }
     }
 
+    #[test]
+    fn create_proof_and_verify_hidden_public_inputs_bn254() {
+        let zkey_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey").unwrap();
+        let witness_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/witness.wtns").unwrap();
+        let vk_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/verification_key.json")
+                .unwrap();
+
+        let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+        let zkey = Arc::new(ZKey::<Bn254>::from_reader(zkey_file, CheckElement::Yes).unwrap());
+        let vk: JsonVerificationKey<Bn254> = serde_json::from_reader(vk_file).unwrap();
+        let public_input = witness.values[1..=zkey.n_public].to_vec();
+        let witness = SharedWitness {
+            public_inputs: witness.values[..1].to_vec(),
+            shared_public_inputs: public_input.clone(),
+            witness: witness.values[zkey.n_public + 1..].to_vec(),
+        };
+        let (proof, opened) = Groth16::<Bn254>::new(PlainGroth16Driver)
+            .prove_with_public_inputs(zkey, witness)
+            .expect("proof generation works");
+        assert_eq!(opened, public_input);
+        Groth16::verify(&vk, &proof, &opened).expect("can verify");
+    }
+
+    #[test]
+    fn create_proof_batch_and_verify_bn254() {
+        let zkey_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey").unwrap();
+        let witness_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/witness.wtns").unwrap();
+        let vk_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/verification_key.json")
+                .unwrap();
+
+        let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+        let zkey = Arc::new(ZKey::<Bn254>::from_reader(zkey_file, CheckElement::Yes).unwrap());
+        let vk: JsonVerificationKey<Bn254> = serde_json::from_reader(vk_file).unwrap();
+        let public_input = witness.values[1..=zkey.n_public].to_vec();
+        let witnesses = vec![
+            SharedWitness {
+                public_inputs: witness.values[..=zkey.n_public].to_vec(),
+                shared_public_inputs: vec![],
+                witness: witness.values[zkey.n_public + 1..].to_vec(),
+            },
+            SharedWitness {
+                public_inputs: witness.values[..1].to_vec(),
+                shared_public_inputs: public_input.clone(),
+                witness: witness.values[zkey.n_public + 1..].to_vec(),
+            },
+        ];
+        let mut prover = Groth16::<Bn254>::new(PlainGroth16Driver);
+        let proofs = prover
+            .prove_batch(Arc::clone(&zkey), witnesses)
+            .expect("proof generation works");
+        assert_eq!(proofs.len(), 2);
+        for (proof, opened) in proofs {
+            assert_eq!(opened, public_input);
+            Groth16::verify(&vk, &proof, &opened).expect("can verify");
+        }
+        // the prover can be reused for the next batch
+        assert!(prover.prove_batch(zkey, vec![]).unwrap().is_empty());
+    }
+
     #[test]
     fn verify_circom_proof_bn254() {
         let vk_string = fs::read_to_string(
@@ -75,6 +158,183 @@ mod tests {
         Groth16::<Bn254>::verify(&vk, &proof, &public_input.values).expect("can verify");
     }
 
+    #[test]
+    fn verify_batch_bn254() {
+        let zkey_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey").unwrap();
+        let witness_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/witness.wtns").unwrap();
+        let vk_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/verification_key.json")
+                .unwrap();
+        let proof_string =
+            fs::read_to_string("../../test_vectors/Groth16/bn254/multiplier2/circom.proof")
+                .unwrap();
+
+        let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+        let zkey = Arc::new(ZKey::<Bn254>::from_reader(zkey_file, CheckElement::Yes).unwrap());
+        let vk: JsonVerificationKey<Bn254> = serde_json::from_reader(vk_file).unwrap();
+        let shared_witness = SharedWitness {
+            public_inputs: witness.values[..=zkey.n_public].to_vec(),
+            shared_public_inputs: vec![],
+            witness: witness.values[zkey.n_public + 1..].to_vec(),
+        };
+        let mut proofs = Groth16::<Bn254>::new(PlainGroth16Driver)
+            .prove_batch(zkey, vec![shared_witness.clone(), shared_witness])
+            .expect("proof generation works");
+        // a proof of snarkjs can be verified in the same batch
+        let circom_proof = serde_json::from_str::<Groth16Proof<Bn254>>(&proof_string).unwrap();
+        proofs.push((circom_proof, proofs[0].1.clone()));
+        Groth16::<Bn254>::verify_batch(&vk, &[]).expect("can verify");
+        Groth16::<Bn254>::verify_batch(&vk, &proofs).expect("can verify");
+
+        // a single invalid proof rejects the batch
+        proofs[1].1[0] += ark_bn254::Fr::from(1u64);
+        assert!(matches!(
+            Groth16::<Bn254>::verify_batch(&vk, &proofs),
+            Err(VerificationError::InvalidProof)
+        ));
+        proofs[1].1.push(ark_bn254::Fr::from(1u64));
+        assert!(matches!(
+            Groth16::<Bn254>::verify_batch(&vk, &proofs),
+            Err(VerificationError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn verify_with_prepared_bn254() {
+        let vk_string =
+            fs::read_to_string("../../test_vectors/Groth16/bn254/poseidon/verification_key.json")
+                .unwrap();
+        let public_string =
+            fs::read_to_string("../../test_vectors/Groth16/bn254/poseidon/public.json").unwrap();
+        let proof_string =
+            fs::read_to_string("../../test_vectors/Groth16/bn254/poseidon/circom.proof").unwrap();
+
+        let vk = serde_json::from_str::<JsonVerificationKey<Bn254>>(&vk_string).unwrap();
+        let public_input =
+            serde_json::from_str::<JsonPublicInput<ark_bn254::Fr>>(&public_string).unwrap();
+        let proof = || serde_json::from_str::<Groth16Proof<Bn254>>(&proof_string).unwrap();
+
+        // the prepared verification key can be cached
+        let mut pvk_bytes = Vec::new();
+        Groth16::<Bn254>::prepare_verifying_key(&vk)
+            .serialize_compressed(&mut pvk_bytes)
+            .unwrap();
+        let pvk = PreparedVerifyingKey::<Bn254>::deserialize_compressed(pvk_bytes.as_slice())
+            .expect("can deserialize prepared verifying key");
+
+        Groth16::<Bn254>::verify_with_prepared(&pvk, &proof(), &public_input.values)
+            .expect("can verify");
+        let proofs = vec![
+            (proof(), public_input.values.clone()),
+            (proof(), public_input.values.clone()),
+        ];
+        Groth16::<Bn254>::verify_batch_with_prepared(&pvk, &proofs).expect("can verify");
+
+        let mut invalid_input = public_input.values.clone();
+        invalid_input[0] += ark_bn254::Fr::from(1u64);
+        assert!(matches!(
+            Groth16::<Bn254>::verify_with_prepared(&pvk, &proof(), &invalid_input),
+            Err(VerificationError::InvalidProof)
+        ));
+        assert!(matches!(
+            Groth16::<Bn254>::verify_batch_with_prepared(&pvk, &[(proof(), invalid_input)]),
+            Err(VerificationError::InvalidProof)
+        ));
+    }
+
+    #[test]
+    fn rerandomize_proof_and_verify_bn254() {
+        let vk_file =
+            File::open("../../test_vectors/Groth16/bn254/poseidon/verification_key.json").unwrap();
+        let public_string =
+            fs::read_to_string("../../test_vectors/Groth16/bn254/poseidon/public.json").unwrap();
+        let proof_string =
+            fs::read_to_string("../../test_vectors/Groth16/bn254/poseidon/circom.proof").unwrap();
+        let vk: JsonVerificationKey<Bn254> = serde_json::from_reader(vk_file).unwrap();
+        let public_input =
+            serde_json::from_str::<JsonPublicInput<ark_bn254::Fr>>(&public_string).unwrap();
+        let proof = serde_json::from_str::<Groth16Proof<Bn254>>(&proof_string).unwrap();
+
+        let rerandomized =
+            Groth16::<Bn254>::rerandomize_proof(&vk, &proof, &mut rand::thread_rng());
+        assert_ne!(proof.pi_a, rerandomized.pi_a);
+        assert_ne!(proof.pi_b, rerandomized.pi_b);
+        assert_ne!(proof.pi_c, rerandomized.pi_c);
+        Groth16::<Bn254>::verify(&vk, &rerandomized, &public_input.values).expect("can verify");
+    }
+
+    #[test]
+    fn plain_prove_with_public_inputs_multiplier2_bn254() {
+        let zkey_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey").unwrap();
+        let witness_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/witness.wtns").unwrap();
+        let vk_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/verification_key.json")
+                .unwrap();
+        let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+        let zkey = Arc::new(ZKey::<Bn254>::from_reader(zkey_file, CheckElement::Yes).unwrap());
+        let vk: JsonVerificationKey<Bn254> = serde_json::from_reader(vk_file).unwrap();
+        let public_input = witness.values[1..=zkey.n_public].to_vec();
+        let plain_witness = SharedWitness {
+            public_inputs: witness.values[..=zkey.n_public].to_vec(),
+            shared_public_inputs: vec![],
+            witness: witness.values[zkey.n_public + 1..].to_vec(),
+        };
+        let (proof, opened) = Groth16::<Bn254>::plain_prove_with_public_inputs(zkey, plain_witness)
+            .expect("proof generation works");
+        assert_eq!(opened, public_input);
+        let ser_proof = serde_json::to_string(&proof).unwrap();
+        let der_proof = serde_json::from_str::<Groth16Proof<Bn254>>(&ser_proof).unwrap();
+        Groth16::<Bn254>::verify(&vk, &der_proof, &opened).expect("can verify");
+    }
+
+    #[test]
+    fn aggregate_proofs_and_verify_bn254() {
+        let zkey_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/circuit.zkey").unwrap();
+        let witness_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/witness.wtns").unwrap();
+        let vk_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/verification_key.json")
+                .unwrap();
+
+        let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+        let zkey = Arc::new(ZKey::<Bn254>::from_reader(zkey_file, CheckElement::Yes).unwrap());
+        let vk: JsonVerificationKey<Bn254> = serde_json::from_reader(vk_file).unwrap();
+        let shared_witness = SharedWitness {
+            public_inputs: witness.values[..=zkey.n_public].to_vec(),
+            shared_public_inputs: vec![],
+            witness: witness.values[zkey.n_public + 1..].to_vec(),
+        };
+        let proofs = Groth16::<Bn254>::new(PlainGroth16Driver)
+            .prove_batch(zkey, vec![shared_witness; 3])
+            .expect("proof generation works");
+        let public_inputs = proofs
+            .iter()
+            .map(|(_, inputs)| inputs.clone())
+            .collect::<Vec<_>>();
+
+        let srs = AggregationSrs::<Bn254>::insecure_setup(4, &mut rand::thread_rng());
+        let agg_vk = srs.verifier_key();
+        let agg_proof = aggregate_proofs(&srs, &proofs).expect("can aggregate");
+        let mut bytes = Vec::new();
+        agg_proof.serialize_compressed(&mut bytes).unwrap();
+        let agg_proof = AggregateProof::<Bn254>::deserialize_compressed(bytes.as_slice()).unwrap();
+        verify_aggregate_proof(&agg_vk, &vk, &public_inputs, &agg_proof).expect("can verify");
+
+        // the aggregate is bound to the public inputs of every proof
+        let mut invalid_inputs = public_inputs.clone();
+        invalid_inputs[2][0] += ark_bn254::Fr::from(1u64);
+        assert!(matches!(
+            verify_aggregate_proof(&agg_vk, &vk, &invalid_inputs, &agg_proof),
+            Err(VerificationError::InvalidProof)
+        ));
+        assert!(verify_aggregate_proof(&agg_vk, &vk, &public_inputs[..2], &agg_proof).is_err());
+    }
+
     #[test]
     fn create_proof_and_verify_poseidon_hash_bn254() {
         for check in [CheckElement::Yes, CheckElement::No] {
@@ -91,6 +351,7 @@ mod tests {
             let public_input = witness.values[..=zkey.n_public].to_vec();
             let witness = SharedWitness {
                 public_inputs: public_input.clone(),
+                shared_public_inputs: vec![],
                 witness: witness.values[zkey.n_public + 1..].to_vec(),
             };
             let proof =
@@ -156,6 +417,7 @@ mod tests {
             let public_input = witness.values[..=zkey.n_public].to_vec();
             let witness = SharedWitness {
                 public_inputs: public_input.clone(),
+                shared_public_inputs: vec![],
                 witness: witness.values[zkey.n_public + 1..].to_vec(),
             };
 
@@ -184,6 +446,7 @@ mod tests {
             let public_input = witness.values[..=zkey.n_public].to_vec();
             let witness = SharedWitness {
                 public_inputs: public_input.clone(),
+                shared_public_inputs: vec![],
                 witness: witness.values[zkey.n_public + 1..].to_vec(),
             };
 
@@ -195,4 +458,184 @@ mod tests {
             Groth16::<Bn254>::verify(&vk, &der_proof, &public_input[1..]).expect("can verify");
         }
     }
+
+    #[test]
+    fn check_witness_bn254() {
+        let r1cs_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/circuit.r1cs").unwrap();
+        let witness_file =
+            File::open("../../test_vectors/Groth16/bn254/multiplier2/witness.wtns").unwrap();
+        let r1cs = R1CS::<Bn254>::from_reader(r1cs_file).unwrap();
+        let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+        let mut witness = SharedWitness {
+            public_inputs: witness.values[..r1cs.num_inputs].to_vec(),
+            shared_public_inputs: vec![],
+            witness: witness.values[r1cs.num_inputs..].to_vec(),
+        };
+        let mut prover = Groth16::<Bn254>::new(PlainGroth16Driver);
+        prover
+            .check_witness(&r1cs, &witness, WitnessCheck::new(1))
+            .expect("witness satisfies the constraints");
+
+        witness.witness[0] += ark_bn254::Fr::from(1u64);
+        let err = prover
+            .check_witness(&r1cs, &witness, WitnessCheck::new(1))
+            .unwrap_err();
+        assert!(err.downcast_ref::<UnsatisfiedConstraintsError>().is_some());
+    }
+
+    /// A trusted setup with known toxic waste in the conventions of snarkjs, for the curves without snarkjs test vectors. The constraints are given as rows of the matrices A, B and C over the signals, where the first `n_public + 1` signals are the constant 1 and the public inputs.
+    fn insecure_setup<P: Pairing + CircomArkworksPairingBridge>(
+        n_public: usize,
+        n_vars: usize,
+        a: ConstraintMatrix<P::ScalarField>,
+        b: ConstraintMatrix<P::ScalarField>,
+        c: ConstraintMatrix<P::ScalarField>,
+    ) -> (ZKey<P>, JsonVerificationKey<P>)
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        let mut rng = rand::thread_rng();
+        let num_constraints = a.len();
+        let domain =
+            GeneralEvaluationDomain::<P::ScalarField>::new(num_constraints + n_public + 1).unwrap();
+        let n = domain.size();
+        let pow = n.ilog2() as usize;
+        let (_, roots) = co_circom_snarks::utils::roots_of_unity::<P::ScalarField>();
+        // the prover evaluates the polynomials on the coset of the 2n-th root of unity
+        let (omega, g) = (roots[pow], roots[pow + 1]);
+        let [tau, alpha, beta, gamma, delta] =
+            std::array::from_fn(|_| P::ScalarField::rand(&mut rng));
+
+        // L_i(tau) for the i-th lagrange polynomial of the domain (or the coset) generated by omega with offset
+        let lagrange = |offset: P::ScalarField, i: usize| {
+            let x = offset * omega.pow([i as u64]);
+            let offset_n = offset.pow([n as u64]);
+            (tau.pow([n as u64]) - offset_n) * x
+                / (P::ScalarField::from(n as u64) * offset_n * (tau - x))
+        };
+        let polys = |m: &ConstraintMatrix<P::ScalarField>| {
+            let mut res = vec![P::ScalarField::zero(); n_vars];
+            for (i, row) in m.iter().enumerate() {
+                for (coeff, signal) in row {
+                    res[*signal] += *coeff * lagrange(P::ScalarField::one(), i);
+                }
+            }
+            res
+        };
+        let mut a_tau = polys(&a);
+        // the constraints of the public inputs
+        for (i, a_tau) in a_tau.iter_mut().enumerate().take(n_public + 1) {
+            *a_tau += lagrange(P::ScalarField::one(), num_constraints + i);
+        }
+        let b_tau = polys(&b);
+        let c_tau = polys(&c);
+        let k = a_tau
+            .iter()
+            .zip(&b_tau)
+            .zip(&c_tau)
+            .map(|((a, b), c)| beta * a + alpha * b + c)
+            .collect::<Vec<_>>();
+        // the prover computes the evaluations of A*B-C on the coset, i.e., the evaluations of h times Z(g) = g^n - 1
+        let z_tau = tau.pow([n as u64]) - P::ScalarField::one();
+        let z_g = g.pow([n as u64]) - P::ScalarField::one();
+        let h = (0..n)
+            .map(|i| lagrange(g, i) * z_tau / (z_g * delta))
+            .collect::<Vec<_>>();
+
+        let g1 = P::G1Affine::generator();
+        let g2 = P::G2Affine::generator();
+        let to_g1 = |xs: &[P::ScalarField]| {
+            xs.iter()
+                .map(|x| (g1 * x).into_affine())
+                .collect::<Vec<_>>()
+        };
+        let zkey = ZKey {
+            n_public,
+            pow,
+            num_constraints,
+            beta_g1: (g1 * beta).into_affine(),
+            delta_g1: (g1 * delta).into_affine(),
+            a_query: to_g1(&a_tau),
+            b_g1_query: to_g1(&b_tau),
+            b_g2_query: b_tau.iter().map(|x| (g2 * x).into_affine()).collect(),
+            h_query: to_g1(&h),
+            l_query: to_g1(
+                &k[n_public + 1..]
+                    .iter()
+                    .map(|k| *k / delta)
+                    .collect::<Vec<_>>(),
+            ),
+            alpha_g1: (g1 * alpha).into_affine(),
+            beta_g2: (g2 * beta).into_affine(),
+            delta_g2: (g2 * delta).into_affine(),
+            a_matrix: a,
+            b_matrix: b,
+        };
+        let vk = JsonVerificationKey {
+            protocol: "groth16".to_owned(),
+            n_public,
+            alpha_1: zkey.alpha_g1,
+            beta_2: zkey.beta_g2,
+            gamma_2: (g2 * gamma).into_affine(),
+            delta_2: zkey.delta_g2,
+            alpha_beta_gt: P::pairing(zkey.alpha_g1, zkey.beta_g2).0,
+            ic: to_g1(
+                &k[..=n_public]
+                    .iter()
+                    .map(|k| *k / gamma)
+                    .collect::<Vec<_>>(),
+            ),
+        };
+        (zkey, vk)
+    }
+
+    fn plain_prove_and_verify<P: Pairing + CircomArkworksPairingBridge>()
+    where
+        P::BaseField: CircomArkworksPrimeFieldBridge,
+        P::ScalarField: CircomArkworksPrimeFieldBridge,
+    {
+        // signals 1, out, a, b, c with c = a * b and out = c * b
+        let one = P::ScalarField::one();
+        let (zkey, vk) = insecure_setup::<P>(
+            1,
+            5,
+            vec![vec![(one, 2)], vec![(one, 4)]],
+            vec![vec![(one, 3)], vec![(one, 3)]],
+            vec![vec![(one, 4)], vec![(one, 1)]],
+        );
+        let (a, b) = (P::ScalarField::from(3u64), P::ScalarField::from(11u64));
+        let witness = SharedWitness {
+            public_inputs: vec![one, a * b * b],
+            shared_public_inputs: vec![],
+            witness: vec![a, b, a * b],
+        };
+        let (proof, public_inputs) =
+            Groth16::<P>::plain_prove_with_public_inputs(Arc::new(zkey), witness)
+                .expect("proof generation works");
+        assert_eq!(public_inputs, vec![a * b * b]);
+        let ser_proof = serde_json::to_string(&proof).unwrap();
+        let der_proof = serde_json::from_str::<Groth16Proof<P>>(&ser_proof).unwrap();
+        let ser_vk = serde_json::to_string(&vk).unwrap();
+        let der_vk = serde_json::from_str::<JsonVerificationKey<P>>(&ser_vk).unwrap();
+        Groth16::<P>::verify(&der_vk, &der_proof, &public_inputs).expect("can verify");
+        assert!(Groth16::<P>::verify(&der_vk, &der_proof, &[a * b]).is_err());
+    }
+
+    #[test]
+    fn plain_prove_and_verify_insecure_setup_bn254() {
+        // checks the setup against the curve with snarkjs test vectors
+        plain_prove_and_verify::<Bn254>();
+    }
+
+    #[test]
+    fn plain_prove_and_verify_bls12_377() {
+        plain_prove_and_verify::<ark_bls12_377::Bls12_377>();
+    }
+
+    #[test]
+    fn plain_prove_and_verify_bw6_761() {
+        plain_prove_and_verify::<ark_bw6_761::BW6_761>();
+    }
 }
@@ -2,16 +2,20 @@ use core::fmt;
 use std::fmt::Debug;
 
 use ark_ec::{pairing::Pairing, CurveGroup};
-use ark_poly::domain::DomainCoeff;
+use ark_poly::{domain::DomainCoeff, EvaluationDomain, GeneralEvaluationDomain};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 pub(crate) mod plain;
 pub(crate) mod rep3;
 pub(crate) mod shamir;
+pub(crate) mod spdz;
+pub(crate) mod twopc;
 
 pub use plain::PlainGroth16Driver;
-pub use rep3::Rep3Groth16Driver;
+pub use rep3::{FftMode, Rep3Groth16Driver};
 pub use shamir::ShamirGroth16Driver;
+pub use spdz::SpdzGroth16Driver;
+pub use twopc::TwoPcGroth16Driver;
 
 type IoResult<T> = std::io::Result<T>;
 
@@ -61,10 +65,25 @@ pub trait CircomGroth16Prover<P: Pairing>: Send + Sized {
     /// You must *NOT* perform additional non-linear operations on the result of this function.
     fn local_mul_vec(
         &mut self,
-        a: Vec<Self::ArithmeticShare>,
-        b: Vec<Self::ArithmeticShare>,
+        a: &[Self::ArithmeticShare],
+        b: &[Self::ArithmeticShare],
     ) -> Vec<P::ScalarField>;
 
+    /// Verifies that `ab` is the result of [`Self::local_mul_vec`] on `a` and `b`, i.e., that no party cheated during the
+    /// multiplication. Only drivers with an actively secure mode check anything, the default implementation does nothing.
+    fn verify_local_mul_vec(
+        &mut self,
+        _a: &[Self::ArithmeticShare],
+        _b: &[Self::ArithmeticShare],
+        _ab: &[P::ScalarField],
+    ) -> IoResult<()> {
+        Ok(())
+    }
+
+    /// Performs the communication round of a multiplication on the results of [`Self::local_mul_vec`], i.e., turns
+    /// them back into shares.
+    fn io_round_mul_vec(&mut self, a: Vec<P::ScalarField>) -> IoResult<Vec<Self::ArithmeticShare>>;
+
     /// Compute the msm of `h` and `h_query` and multiplication `r` * `s`.
     fn mul(
         &mut self,
@@ -78,6 +97,21 @@ pub trait CircomGroth16Prover<P: Pairing>: Send + Sized {
         roots: &[P::ScalarField],
     );
 
+    /// Transforms the shared evaluations of the polynomials A and B on the domain into their shared evaluations on the
+    /// coset, i.e., computes the iFFT, [`Self::distribute_powers_and_mul_by_const`] and the FFT for both vectors.
+    ///
+    /// The default implementation transforms the shares locally without networking.
+    #[expect(clippy::type_complexity)]
+    fn evaluate_on_coset(
+        &mut self,
+        domain: &GeneralEvaluationDomain<P::ScalarField>,
+        roots: &[P::ScalarField],
+        a: Vec<Self::ArithmeticShare>,
+        b: Vec<Self::ArithmeticShare>,
+    ) -> IoResult<(Vec<Self::ArithmeticShare>, Vec<Self::ArithmeticShare>)> {
+        Ok(evaluate_on_coset_locally::<P, Self>(domain, roots, a, b))
+    }
+
     /// Perform msm between `points` and `scalars`
     fn msm_public_points<C>(
         points: &[C::Affine],
@@ -107,6 +141,16 @@ pub trait CircomGroth16Prover<P: Pairing>: Send + Sized {
         b: &C,
     );
 
+    /// Reconstructs many shared values: a_i = Open(\[a_i\]).
+    fn open_vec(&mut self, a: &[Self::ArithmeticShare]) -> IoResult<Vec<P::ScalarField>>;
+
+    /// Reconstructs many shared values towards the party with the given id. Only this party learns a_i, all other parties get `None`.
+    fn open_vec_to(
+        &mut self,
+        a: &[Self::ArithmeticShare],
+        party_id: usize,
+    ) -> IoResult<Option<Vec<P::ScalarField>>>;
+
     /// Reconstructs a shared point: A = Open(\[A\]).
     fn open_point<C>(&mut self, a: &Self::PointShare<C>) -> IoResult<C>
     where
@@ -137,3 +181,33 @@ pub trait CircomGroth16Prover<P: Pairing>: Send + Sized {
         r: Self::ArithmeticShare,
     ) -> std::io::Result<(P::G1, Self::PointShare<P::G1>)>;
 }
+
+/// Transforms the shared evaluations of A and B on the domain into their shared evaluations on the coset, without
+/// networking. See [`CircomGroth16Prover::evaluate_on_coset`].
+pub(crate) fn evaluate_on_coset_locally<P: Pairing, T: CircomGroth16Prover<P>>(
+    domain: &GeneralEvaluationDomain<P::ScalarField>,
+    roots: &[P::ScalarField],
+    a: Vec<T::ArithmeticShare>,
+    b: Vec<T::ArithmeticShare>,
+) -> (Vec<T::ArithmeticShare>, Vec<T::ArithmeticShare>) {
+    let transform = |mut evals: Vec<T::ArithmeticShare>| {
+        domain.ifft_in_place(&mut evals);
+        T::distribute_powers_and_mul_by_const(&mut evals, roots);
+        domain.fft_in_place(&mut evals);
+        evals
+    };
+    rayon::join(
+        || {
+            let a_span = tracing::debug_span!("a: distribute powers mul a (fft/ifft)").entered();
+            let a = transform(a);
+            a_span.exit();
+            a
+        },
+        || {
+            let b_span = tracing::debug_span!("b: distribute powers mul b (fft/ifft)").entered();
+            let b = transform(b);
+            b_span.exit();
+            b
+        },
+    )
+}
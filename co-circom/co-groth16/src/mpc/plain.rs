@@ -56,12 +56,16 @@ impl<P: Pairing> CircomGroth16Prover<P> for PlainGroth16Driver {
 
     fn local_mul_vec(
         &mut self,
-        a: Vec<Self::ArithmeticShare>,
-        b: Vec<Self::ArithmeticShare>,
+        a: &[Self::ArithmeticShare],
+        b: &[Self::ArithmeticShare],
     ) -> Vec<P::ScalarField> {
         a.iter().zip(b.iter()).map(|(a, b)| *a * b).collect()
     }
 
+    fn io_round_mul_vec(&mut self, a: Vec<P::ScalarField>) -> IoResult<Vec<Self::ArithmeticShare>> {
+        Ok(a)
+    }
+
     fn mul(
         &mut self,
         r: Self::ArithmeticShare,
@@ -112,6 +116,18 @@ impl<P: Pairing> CircomGroth16Prover<P> for PlainGroth16Driver {
         *a += b;
     }
 
+    fn open_vec(&mut self, a: &[Self::ArithmeticShare]) -> super::IoResult<Vec<P::ScalarField>> {
+        Ok(a.to_vec())
+    }
+
+    fn open_vec_to(
+        &mut self,
+        a: &[Self::ArithmeticShare],
+        _party_id: usize,
+    ) -> super::IoResult<Option<Vec<P::ScalarField>>> {
+        Ok(Some(a.to_vec()))
+    }
+
     fn open_point<C>(&mut self, a: &Self::PointShare<C>) -> super::IoResult<C>
     where
         C: CurveGroup<ScalarField = P::ScalarField>,
@@ -1,7 +1,10 @@
 use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
 use mpc_core::protocols::rep3::{
     arithmetic,
     id::PartyID,
+    malicious::{self, SecurityLevel},
     network::{IoContext, Rep3Network},
     pointshare, Rep3PointShare, Rep3PrimeFieldShare,
 };
@@ -9,22 +12,47 @@ use rayon::prelude::*;
 
 use super::{CircomGroth16Prover, IoResult};
 
+/// How the parties transform the shared evaluations of A and B to the coset when computing the H polynomial.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FftMode {
+    /// Every party only transforms its own component of the replicated shares and forwards the result to the next
+    /// party, which holds the same component. This halves the FFTs of A and B per party, at the cost of one round in
+    /// which every party sends two field elements per evaluation point.
+    #[default]
+    Distributed,
+    /// Every party transforms both components of its replicated shares without networking. As every component is
+    /// held by two parties, every FFT of A and B is computed twice.
+    Replicated,
+}
+
 /// A Groth16 driver for REP3 secret sharing
 ///
 /// Contains two [`IoContext`]s, `io_context0` for the main execution and `io_context1` for parts that can run concurrently.
+///
+/// If the [`IoContext`]s use [`SecurityLevel::Malicious`], all multiplications and openings are checked (see
+/// [`malicious`]). The part of the proof computed from the quotient polynomial h is only additively shared, so a
+/// corrupted party can still cause an invalid proof without being detected. Thus, the parties have to verify the
+/// resulting proof before using it.
 pub struct Rep3Groth16Driver<N: Rep3Network> {
     io_context0: IoContext<N>,
     io_context1: IoContext<N>,
+    fft_mode: FftMode,
 }
 
 impl<N: Rep3Network> Rep3Groth16Driver<N> {
-    /// Create a new [`Rep3Groth16Driver`] with two [`IoContext`]s
+    /// Create a new [`Rep3Groth16Driver`] with two [`IoContext`]s, using [`FftMode::Distributed`]
     pub fn new(io_context0: IoContext<N>, io_context1: IoContext<N>) -> Self {
         Self {
             io_context0,
             io_context1,
+            fft_mode: FftMode::default(),
         }
     }
+
+    /// Sets the [`FftMode`] used to compute the H polynomial. All parties have to use the same mode.
+    pub fn set_fft_mode(&mut self, fft_mode: FftMode) {
+        self.fft_mode = fft_mode;
+    }
 }
 
 impl<P: Pairing, N: Rep3Network> CircomGroth16Prover<P> for Rep3Groth16Driver<N>
@@ -80,10 +108,28 @@ where
 
     fn local_mul_vec(
         &mut self,
-        a: Vec<Self::ArithmeticShare>,
-        b: Vec<Self::ArithmeticShare>,
+        a: &[Self::ArithmeticShare],
+        b: &[Self::ArithmeticShare],
     ) -> Vec<P::ScalarField> {
-        arithmetic::local_mul_vec(&a, &b, &mut self.io_context0.rngs)
+        arithmetic::local_mul_vec(a, b, &mut self.io_context0.rngs)
+    }
+
+    fn verify_local_mul_vec(
+        &mut self,
+        a: &[Self::ArithmeticShare],
+        b: &[Self::ArithmeticShare],
+        ab: &[P::ScalarField],
+    ) -> IoResult<()> {
+        if self.io_context0.security_level == SecurityLevel::Malicious {
+            // the local products are additive shares, so they are reshared to replicated shares for the check
+            let ab = arithmetic::io_mul_vec(ab.to_vec(), &mut self.io_context0)?;
+            malicious::verify_mul_triples(a, b, &ab, &mut self.io_context0)?;
+        }
+        Ok(())
+    }
+
+    fn io_round_mul_vec(&mut self, a: Vec<P::ScalarField>) -> IoResult<Vec<Self::ArithmeticShare>> {
+        arithmetic::io_mul_vec(a, &mut self.io_context0)
     }
 
     fn mul(
@@ -107,6 +153,44 @@ where
             })
     }
 
+    fn evaluate_on_coset(
+        &mut self,
+        domain: &GeneralEvaluationDomain<P::ScalarField>,
+        roots: &[P::ScalarField],
+        a: Vec<Self::ArithmeticShare>,
+        b: Vec<Self::ArithmeticShare>,
+    ) -> IoResult<(Vec<Self::ArithmeticShare>, Vec<Self::ArithmeticShare>)> {
+        if self.fft_mode == FftMode::Replicated {
+            return Ok(super::evaluate_on_coset_locally::<P, Self>(
+                domain, roots, a, b,
+            ));
+        }
+        // the transformation is linear, so every party transforms its component a of the shares, which is the
+        // component b of the next party. The next party learns nothing new, as it already holds this component.
+        let transform = |shares: Vec<Self::ArithmeticShare>| {
+            let mut evals = shares.into_iter().map(|share| share.a).collect::<Vec<_>>();
+            domain.ifft_in_place(&mut evals);
+            evals
+                .par_iter_mut()
+                .zip_eq(roots.par_iter())
+                .with_min_len(512)
+                .for_each(|(eval, pow)| *eval *= pow);
+            domain.fft_in_place(&mut evals);
+            evals
+        };
+        let (a, b) = rayon::join(|| transform(a), || transform(b));
+        // the vectors are sent separately, so no buffer for their concatenation is needed
+        let network = &mut self.io_context0.network;
+        network.send_next_many(&a)?;
+        network.send_next_many(&b)?;
+        let a_prev = network.recv_prev_many()?;
+        let b_prev = network.recv_prev_many()?;
+        Ok((
+            combine_components(a, a_prev)?,
+            combine_components(b, b_prev)?,
+        ))
+    }
+
     fn msm_public_points<C>(
         points: &[C::Affine],
         scalars: &[Self::ArithmeticShare],
@@ -142,6 +226,20 @@ where
         pointshare::add_assign_public(a, b, id)
     }
 
+    fn open_vec(&mut self, a: &[Self::ArithmeticShare]) -> IoResult<Vec<P::ScalarField>> {
+        arithmetic::open_vec(a, &mut self.io_context0)
+    }
+
+    fn open_vec_to(
+        &mut self,
+        a: &[Self::ArithmeticShare],
+        party_id: usize,
+    ) -> IoResult<Option<Vec<P::ScalarField>>> {
+        let target = PartyID::try_from(party_id)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+        arithmetic::open_vec_to(a, target, &mut self.io_context0)
+    }
+
     fn open_point<C>(&mut self, a: &Self::PointShare<C>) -> IoResult<C>
     where
         C: CurveGroup<ScalarField = P::ScalarField>,
@@ -170,17 +268,14 @@ where
         b: Self::PointShare<P::G2>,
     ) -> std::io::Result<(P::G1, P::G2)> {
         let mut s1 = a;
-        let s2 = b.b;
         let (r1, r2) = std::thread::scope(|s| {
             let r1 = s.spawn(|| self.io_context0.network.broadcast(s1));
-            let r2 = s.spawn(|| self.io_context1.network.reshare(s2));
+            let r2 = s.spawn(|| pointshare::open_point(&b, &mut self.io_context1));
             (r1.join().expect("can join"), r2.join().expect("can join"))
         });
         let (r1b, r1c) = r1?;
-        let mut r2 = r2?;
         s1 += r1b + r1c;
-        r2 += b.a + b.b;
-        Ok((s1, r2))
+        Ok((s1, r2?))
     }
 
     fn open_point_and_scalar_mul(
@@ -196,3 +291,21 @@ where
         })
     }
 }
+
+/// Combines the own components of the shares with the received components of the previous party to replicated shares.
+fn combine_components<F: PrimeField>(
+    own: Vec<F>,
+    prev: Vec<F>,
+) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
+    if own.len() != prev.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "During the distributed coset evaluation: Invalid number of elements received",
+        ));
+    }
+    Ok(own
+        .into_iter()
+        .zip(prev)
+        .map(|(a, b)| Rep3PrimeFieldShare::new(a, b))
+        .collect())
+}
@@ -23,6 +23,11 @@ impl<F: PrimeField, N: ShamirNetwork> ShamirGroth16Driver<F, N> {
             protocol1,
         }
     }
+
+    /// Verifies that the given values are shared with polynomials of degree threshold (see [`arithmetic::verify_degree_vec`]).
+    pub fn verify_degree_vec(&mut self, a: &[ShamirPrimeFieldShare<F>]) -> IoResult<()> {
+        arithmetic::verify_degree_vec(a, &mut self.protocol0)
+    }
 }
 
 impl<P: Pairing, N: ShamirNetwork> CircomGroth16Prover<P>
@@ -73,10 +78,14 @@ impl<P: Pairing, N: ShamirNetwork> CircomGroth16Prover<P>
 
     fn local_mul_vec(
         &mut self,
-        a: Vec<Self::ArithmeticShare>,
-        b: Vec<Self::ArithmeticShare>,
+        a: &[Self::ArithmeticShare],
+        b: &[Self::ArithmeticShare],
     ) -> Vec<P::ScalarField> {
-        arithmetic::local_mul_vec(&a, &b)
+        arithmetic::local_mul_vec(a, b)
+    }
+
+    fn io_round_mul_vec(&mut self, a: Vec<P::ScalarField>) -> IoResult<Vec<Self::ArithmeticShare>> {
+        self.protocol0.degree_reduce_vec(a)
     }
 
     fn mul(
@@ -133,6 +142,18 @@ impl<P: Pairing, N: ShamirNetwork> CircomGroth16Prover<P>
         pointshare::add_assign_public(a, b)
     }
 
+    fn open_vec(&mut self, a: &[Self::ArithmeticShare]) -> IoResult<Vec<P::ScalarField>> {
+        arithmetic::open_vec(a, &mut self.protocol0)
+    }
+
+    fn open_vec_to(
+        &mut self,
+        a: &[Self::ArithmeticShare],
+        party_id: usize,
+    ) -> IoResult<Option<Vec<P::ScalarField>>> {
+        arithmetic::open_vec_to(a, party_id, &mut self.protocol0)
+    }
+
     fn open_point<C>(&mut self, a: &Self::PointShare<C>) -> IoResult<C>
     where
         C: CurveGroup<ScalarField = P::ScalarField>,
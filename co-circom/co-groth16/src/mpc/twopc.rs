@@ -0,0 +1,226 @@
+use super::{CircomGroth16Prover, IoResult};
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::PrimeField;
+use mpc_core::protocols::{
+    shamir::network::ShamirNetwork,
+    twopc::{arithmetic, pointshare, TwoPcPointShare, TwoPcPrimeFieldShare, TwoPcProtocol},
+};
+use rayon::prelude::*;
+use std::marker::PhantomData;
+
+/// A Groth16 driver using the semi-honest 2PC protocol, which works with exactly two parties.
+pub struct TwoPcGroth16Driver<F: PrimeField, N: ShamirNetwork> {
+    protocol: TwoPcProtocol<N>,
+    // networking errors in local_mul_vec are returned by the next fallible call
+    deferred_error: Option<std::io::Error>,
+    phantom_data: PhantomData<F>,
+}
+
+impl<F: PrimeField, N: ShamirNetwork> TwoPcGroth16Driver<F, N> {
+    /// Create a new [`TwoPcGroth16Driver`] with a [`TwoPcProtocol`]
+    pub fn new(protocol: TwoPcProtocol<N>) -> Self {
+        Self {
+            protocol,
+            deferred_error: None,
+            phantom_data: PhantomData,
+        }
+    }
+
+    fn check_deferred_error(&mut self) -> IoResult<()> {
+        match self.deferred_error.take() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<P: Pairing, N: ShamirNetwork> CircomGroth16Prover<P>
+    for TwoPcGroth16Driver<P::ScalarField, N>
+{
+    type ArithmeticShare = TwoPcPrimeFieldShare<P::ScalarField>;
+    type PointShare<C>
+        = TwoPcPointShare<C>
+    where
+        C: CurveGroup;
+
+    type PartyID = usize;
+
+    fn rand(&mut self) -> IoResult<Self::ArithmeticShare> {
+        self.check_deferred_error()?;
+        Ok(self.protocol.rand())
+    }
+
+    fn get_party_id(&self) -> Self::PartyID {
+        self.protocol.id()
+    }
+
+    fn evaluate_constraint(
+        party_id: Self::PartyID,
+        lhs: &[(P::ScalarField, usize)],
+        public_inputs: &[P::ScalarField],
+        private_witness: &[Self::ArithmeticShare],
+    ) -> Self::ArithmeticShare {
+        let mut acc = Self::ArithmeticShare::default();
+        for (coeff, index) in lhs {
+            if index < &public_inputs.len() {
+                let val = public_inputs[*index];
+                let mul_result = val * coeff;
+                arithmetic::add_assign_public(&mut acc, mul_result, party_id);
+            } else {
+                let current_witness = private_witness[*index - public_inputs.len()];
+                arithmetic::add_assign(&mut acc, arithmetic::mul_public(current_witness, *coeff));
+            }
+        }
+        acc
+    }
+
+    fn promote_to_trivial_shares(
+        id: Self::PartyID,
+        public_values: &[P::ScalarField],
+    ) -> Vec<Self::ArithmeticShare> {
+        arithmetic::promote_to_trivial_shares(id, public_values)
+    }
+
+    /// In contrast to the other drivers, this function requires networking, since the product of two additively shared values is computed with oblivious transfers. It returns the additive shares of the products. If the networking fails, the error is returned by the next fallible call.
+    fn local_mul_vec(
+        &mut self,
+        a: &[Self::ArithmeticShare],
+        b: &[Self::ArithmeticShare],
+    ) -> Vec<P::ScalarField> {
+        if self.deferred_error.is_some() {
+            return vec![P::ScalarField::default(); a.len()];
+        }
+        match arithmetic::mul_vec(a, b, &mut self.protocol) {
+            Ok(res) => res.into_iter().map(|s| s.inner()).collect(),
+            Err(err) => {
+                self.deferred_error = Some(err);
+                vec![P::ScalarField::default(); a.len()]
+            }
+        }
+    }
+
+    fn io_round_mul_vec(&mut self, a: Vec<P::ScalarField>) -> IoResult<Vec<Self::ArithmeticShare>> {
+        self.check_deferred_error()?;
+        // the results of local_mul_vec already are additive shares
+        Ok(a.into_iter().map(TwoPcPrimeFieldShare::new).collect())
+    }
+
+    fn mul(
+        &mut self,
+        r: Self::ArithmeticShare,
+        s: Self::ArithmeticShare,
+    ) -> IoResult<Self::ArithmeticShare> {
+        self.check_deferred_error()?;
+        arithmetic::mul(r, s, &mut self.protocol)
+    }
+
+    fn distribute_powers_and_mul_by_const(
+        coeffs: &mut [Self::ArithmeticShare],
+        roots: &[P::ScalarField],
+    ) {
+        coeffs
+            .par_iter_mut()
+            .zip_eq(roots.par_iter())
+            .with_min_len(512)
+            .for_each(|(c, pow)| {
+                arithmetic::mul_assign_public(c, *pow);
+            })
+    }
+
+    fn msm_public_points<C>(
+        points: &[C::Affine],
+        scalars: &[Self::ArithmeticShare],
+    ) -> Self::PointShare<C>
+    where
+        C: CurveGroup<ScalarField = P::ScalarField>,
+    {
+        pointshare::msm_public_points(points, scalars)
+    }
+
+    fn scalar_mul_public_point<C>(a: &C, b: Self::ArithmeticShare) -> Self::PointShare<C>
+    where
+        C: CurveGroup<ScalarField = P::ScalarField>,
+    {
+        pointshare::scalar_mul_public_point(b, a)
+    }
+
+    fn add_assign_points<C: CurveGroup>(a: &mut Self::PointShare<C>, b: &Self::PointShare<C>) {
+        pointshare::add_assign(a, b)
+    }
+
+    fn sub_assign_points<C: CurveGroup>(a: &mut Self::PointShare<C>, b: &Self::PointShare<C>) {
+        pointshare::sub_assign(a, b);
+    }
+
+    fn add_points_half_share<C: CurveGroup>(a: Self::PointShare<C>, b: &C) -> C {
+        a.inner() + b
+    }
+
+    fn add_assign_points_public<C: CurveGroup>(
+        id: Self::PartyID,
+        a: &mut Self::PointShare<C>,
+        b: &C,
+    ) {
+        pointshare::add_assign_public(a, b, id)
+    }
+
+    fn open_vec(&mut self, a: &[Self::ArithmeticShare]) -> IoResult<Vec<P::ScalarField>> {
+        self.check_deferred_error()?;
+        arithmetic::open_vec(a, &mut self.protocol)
+    }
+
+    fn open_vec_to(
+        &mut self,
+        a: &[Self::ArithmeticShare],
+        party_id: usize,
+    ) -> IoResult<Option<Vec<P::ScalarField>>> {
+        self.check_deferred_error()?;
+        arithmetic::open_vec_to(a, party_id, &mut self.protocol)
+    }
+
+    fn open_point<C>(&mut self, a: &Self::PointShare<C>) -> IoResult<C>
+    where
+        C: CurveGroup<ScalarField = P::ScalarField>,
+    {
+        self.check_deferred_error()?;
+        pointshare::open_point(a, &mut self.protocol)
+    }
+
+    fn scalar_mul<C>(
+        &mut self,
+        a: &Self::PointShare<C>,
+        b: Self::ArithmeticShare,
+    ) -> IoResult<Self::PointShare<C>>
+    where
+        C: CurveGroup<ScalarField = P::ScalarField>,
+    {
+        self.check_deferred_error()?;
+        pointshare::scalar_mul(a, b, &mut self.protocol)
+    }
+
+    fn open_two_points(
+        &mut self,
+        a: P::G1,
+        b: Self::PointShare<P::G2>,
+    ) -> std::io::Result<(P::G1, P::G2)> {
+        self.check_deferred_error()?;
+        let rcv = self.protocol.network.broadcast((a, b.inner()))?;
+        Ok(rcv
+            .into_iter()
+            .fold((P::G1::default(), P::G2::default()), |acc, (r1, r2)| {
+                (acc.0 + r1, acc.1 + r2)
+            }))
+    }
+
+    fn open_point_and_scalar_mul(
+        &mut self,
+        g_a: &Self::PointShare<P::G1>,
+        g1_b: &Self::PointShare<P::G1>,
+        r: Self::ArithmeticShare,
+    ) -> super::IoResult<(P::G1, Self::PointShare<P::G1>)> {
+        self.check_deferred_error()?;
+        let opened = pointshare::open_point(g_a, &mut self.protocol)?;
+        let mul_result = pointshare::scalar_mul(g1_b, r, &mut self.protocol)?;
+        Ok((opened, mul_result))
+    }
+}
@@ -1,10 +1,20 @@
 //! This module implements the verification of a Groth16 proof on the [`Groth16`] type.
 //!
 //! We use [arkworks Groth16 implementation](https://docs.rs/ark-groth16/latest/ark_groth16/struct.Groth16.html#method.verify_proof)
-//! for verification.
+//! for verification. Many proofs for the same verification key can be verified at once with [`Groth16::verify_batch`].
+//!
+//! Services that verify many proofs for the same verification key should prepare it once with
+//! [`Groth16::prepare_verifying_key`] and use [`Groth16::verify_with_prepared`] and
+//! [`Groth16::verify_batch_with_prepared`]. The [`PreparedVerifyingKey`] implements
+//! [`CanonicalSerialize`](ark_serialize::CanonicalSerialize) and
+//! [`CanonicalDeserialize`](ark_serialize::CanonicalDeserialize), so it can also be cached across restarts.
 
 use crate::groth16::Groth16;
-use ark_ec::pairing::Pairing;
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::scalar_mul::variable_base::VariableBaseMSM;
+use ark_ec::CurveGroup;
+use ark_ff::{Field, Zero};
+use ark_groth16::PreparedVerifyingKey;
 use ark_groth16::Proof;
 use ark_groth16::VerifyingKey;
 use circom_types::groth16::{Groth16Proof, JsonVerificationKey};
@@ -12,6 +22,7 @@ use circom_types::traits::{CircomArkworksPairingBridge, CircomArkworksPrimeField
 
 use ark_groth16::Groth16 as ArkworksGroth16;
 use co_circom_snarks::VerificationError;
+use rand::Rng;
 
 impl<P: Pairing> Groth16<P>
 where
@@ -19,13 +30,11 @@ where
     P: Pairing + CircomArkworksPairingBridge,
     P::BaseField: CircomArkworksPrimeFieldBridge,
 {
-    /// Verify a Groth16 proof.
-    /// This method is a wrapper arkworks Groth16 and does not use MPC.
-    pub fn verify(
-        vk: &JsonVerificationKey<P>,
-        proof: &Groth16Proof<P>,
-        public_inputs: &[P::ScalarField],
-    ) -> Result<(), VerificationError> {
+    /// Prepares a verification key for [`Groth16::verify_with_prepared`] and [`Groth16::verify_batch_with_prepared`].
+    ///
+    /// This computes the pairing of α and β and the line coefficients of γ and δ, which are otherwise recomputed for
+    /// every verification.
+    pub fn prepare_verifying_key(vk: &JsonVerificationKey<P>) -> PreparedVerifyingKey<P> {
         let vk = VerifyingKey::<P> {
             alpha_g1: vk.alpha_1,
             beta_g2: vk.beta_2,
@@ -33,14 +42,33 @@ where
             delta_g2: vk.delta_2,
             gamma_abc_g1: vk.ic.clone(),
         };
+        ark_groth16::prepare_verifying_key(&vk)
+    }
+
+    /// Verify a Groth16 proof.
+    /// This method is a wrapper arkworks Groth16 and does not use MPC.
+    pub fn verify(
+        vk: &JsonVerificationKey<P>,
+        proof: &Groth16Proof<P>,
+        public_inputs: &[P::ScalarField],
+    ) -> Result<(), VerificationError> {
+        Self::verify_with_prepared(&Self::prepare_verifying_key(vk), proof, public_inputs)
+    }
+
+    /// Verify a Groth16 proof with a verification key from [`Groth16::prepare_verifying_key`].
+    /// This method is a wrapper arkworks Groth16 and does not use MPC.
+    pub fn verify_with_prepared(
+        pvk: &PreparedVerifyingKey<P>,
+        proof: &Groth16Proof<P>,
+        public_inputs: &[P::ScalarField],
+    ) -> Result<(), VerificationError> {
         let proof = Proof {
             a: proof.pi_a,
             b: proof.pi_b,
             c: proof.pi_c,
         };
 
-        let vk = ark_groth16::prepare_verifying_key(&vk);
-        let proof_valid = ArkworksGroth16::<P>::verify_proof(&vk, &proof, public_inputs)
+        let proof_valid = ArkworksGroth16::<P>::verify_proof(pvk, &proof, public_inputs)
             .map_err(eyre::Report::from)?;
         if proof_valid {
             Ok(())
@@ -48,4 +76,78 @@ where
             Err(VerificationError::InvalidProof)
         }
     }
+
+    /// Verify many Groth16 proofs for the same verification key, each given with its public inputs.
+    /// This method does not use MPC.
+    ///
+    /// Instead of checking the pairing equation of every proof on its own, the equations are combined with random
+    /// coefficients into a single pairing product, which is much cheaper for large batches. If any proof is invalid,
+    /// the batch is rejected with overwhelming probability, but it is not reported which one. Use [`Groth16::verify`]
+    /// to find the invalid proofs of a rejected batch.
+    pub fn verify_batch(
+        vk: &JsonVerificationKey<P>,
+        proofs: &[(Groth16Proof<P>, Vec<P::ScalarField>)],
+    ) -> Result<(), VerificationError> {
+        Self::verify_batch_with_prepared(&Self::prepare_verifying_key(vk), proofs)
+    }
+
+    /// Verify many Groth16 proofs like [`Groth16::verify_batch`], but with a verification key from
+    /// [`Groth16::prepare_verifying_key`].
+    pub fn verify_batch_with_prepared(
+        pvk: &PreparedVerifyingKey<P>,
+        proofs: &[(Groth16Proof<P>, Vec<P::ScalarField>)],
+    ) -> Result<(), VerificationError> {
+        let ic = &pvk.vk.gamma_abc_g1;
+        let mut rng = rand::thread_rng();
+        // e(A_i, B_i) = e(alpha, beta) * e(L_i, gamma) * e(C_i, delta) for every proof i, where L_i is the
+        // combination of the IC with the public inputs. Raising the equations to random powers r_i and multiplying them
+        // yields prod_i e(r_i * A_i, B_i) = e(sum_i r_i * alpha, beta) * e(sum_i r_i * L_i, gamma) * e(sum_i r_i * C_i, delta).
+        // The first factor on the right-hand side is the prepared e(alpha, beta) to the power of sum_i r_i.
+        let mut a_g1 = Vec::with_capacity(proofs.len() + 2);
+        let mut b_g2 = Vec::with_capacity(proofs.len() + 2);
+        let mut r_sum = P::ScalarField::zero();
+        let mut c_acc = P::G1::zero();
+        // the coefficients of the IC, such that sum_i r_i * L_i is a single MSM
+        let mut ic_coeffs = vec![P::ScalarField::zero(); ic.len()];
+        for (i, (proof, public_inputs)) in proofs.iter().enumerate() {
+            if public_inputs.len() + 1 != ic.len() {
+                return Err(eyre::eyre!(
+                    "expected {} public inputs, but proof {i} has {}",
+                    ic.len().saturating_sub(1),
+                    public_inputs.len()
+                )
+                .into());
+            }
+            // only the ratios of the coefficients matter, so the first one can be one
+            let r = if i == 0 {
+                P::ScalarField::ONE
+            } else {
+                P::ScalarField::from(rng.gen::<u128>())
+            };
+            r_sum += r;
+            ic_coeffs[0] += r;
+            for (coeff, input) in ic_coeffs[1..].iter_mut().zip(public_inputs) {
+                *coeff += r * input;
+            }
+            c_acc += proof.pi_c * r;
+            a_g1.push(proof.pi_a * r);
+            b_g2.push(P::G2Prepared::from(proof.pi_b));
+        }
+        if proofs.is_empty() {
+            return Ok(());
+        }
+        let l_acc = P::G1::msm_unchecked(ic, &ic_coeffs);
+        // gamma and delta are prepared negated
+        a_g1.push(l_acc);
+        b_g2.push(pvk.gamma_g2_neg_pc.clone());
+        a_g1.push(c_acc);
+        b_g2.push(pvk.delta_g2_neg_pc.clone());
+
+        let a_g1 = P::G1::normalize_batch(&a_g1);
+        if P::multi_pairing(a_g1, b_g2) == PairingOutput(pvk.alpha_g1_beta_g2) * r_sum {
+            Ok(())
+        } else {
+            Err(VerificationError::InvalidProof)
+        }
+    }
 }
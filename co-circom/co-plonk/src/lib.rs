@@ -2,19 +2,21 @@
 
 #![warn(missing_docs)]
 use ark_ec::pairing::Pairing;
+use ark_ff::Zero;
 use circom_types::plonk::PlonkProof;
 use circom_types::plonk::ZKey;
 use circom_types::traits::CircomArkworksPairingBridge;
 use circom_types::traits::CircomArkworksPrimeFieldBridge;
-use co_circom_snarks::SharedWitness;
+use co_circom_snarks::{SharedWitness, UnsatisfiedConstraintsError, WitnessCheck};
 use mpc::rep3::Rep3PlonkDriver;
 use mpc::shamir::ShamirPlonkDriver;
 use mpc::CircomPlonkProver;
 use mpc_core::protocols::rep3::network::IoContext;
-use mpc_core::protocols::rep3::network::Rep3MpcNet;
+use mpc_core::protocols::rep3::network::{Rep3MpcNet, Rep3Network};
+use mpc_core::protocols::shamir::network::ShamirNetwork;
 use mpc_core::protocols::shamir::ShamirPreprocessing;
 use mpc_core::protocols::shamir::{network::ShamirMpcNet, ShamirProtocol};
-use mpc_net::config::NetworkConfig;
+use mpc_net::config::{NetworkConfig, Phase};
 use round1::Round1;
 use std::io;
 use std::marker::PhantomData;
@@ -31,7 +33,7 @@ mod round4;
 mod round5;
 pub(crate) mod types;
 
-pub use plonk::Plonk;
+pub use plonk::{Plonk, PreparedVerifyingKey};
 
 type PlonkProofResult<T> = std::result::Result<T, PlonkProofError>;
 
@@ -56,6 +58,9 @@ pub enum PlonkProofError {
     /// An [io::Error]. Communication to another party failed.
     #[error(transparent)]
     IOError(#[from] io::Error),
+    /// Indicates that the witness pre-check found a batch of unsatisfied gates.
+    #[error(transparent)]
+    UnsatisfiedConstraints(#[from] UnsatisfiedConstraintsError),
 }
 
 /// A Plonk proof protocol that uses a collaborative MPC protocol to generate the proof.
@@ -85,6 +90,20 @@ where
         zkey: Arc<ZKey<P>>,
         witness: SharedWitness<P::ScalarField, T::ArithmeticShare>,
     ) -> PlonkProofResult<PlonkProof<P>> {
+        let (proof, _) = self.prove_with_public_inputs(zkey, witness)?;
+        Ok(proof)
+    }
+
+    /// Execute the PLONK prover using the internal MPC driver and additionally return the public inputs
+    /// of the proof (without the leading constant 1).
+    ///
+    /// In contrast to Groth16, the public inputs enter the Fiat-Shamir transcript in round 2. Hidden public
+    /// inputs (see [SharedWitness::shared_public_inputs]) are therefore opened before the first round.
+    pub fn prove_with_public_inputs(
+        mut self,
+        zkey: Arc<ZKey<P>>,
+        mut witness: SharedWitness<P::ScalarField, T::ArithmeticShare>,
+    ) -> PlonkProofResult<(PlonkProof<P>, Vec<P::ScalarField>)> {
         let id = self.driver.get_party_id();
         tracing::info!("Party {}: starting proof generation..", id);
         let start = Instant::now();
@@ -100,6 +119,16 @@ where
             zkey.n_vars,
             zkey.n_public
         );
+        if witness.has_hidden_public_inputs() {
+            tracing::debug!(
+                "opening {} hidden public inputs",
+                witness.shared_public_inputs.len()
+            );
+            let opened = self.driver.open_vec(&witness.shared_public_inputs)?;
+            witness.public_inputs.extend(opened);
+            witness.shared_public_inputs.clear();
+        }
+        let public_inputs = witness.public_inputs[1..].to_vec();
         let state = Round1::init_round(self.driver, zkey.as_ref(), witness)?;
         tracing::debug!("init round done..");
         let state = state.round1()?;
@@ -110,11 +139,87 @@ where
         tracing::debug!("round 3 done..");
         let state = state.round4()?;
         tracing::debug!("round 4 done..");
-        let result = state.round5();
+        let proof = state.round5()?;
         tracing::debug!("round 5 done! We are done!");
         let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
         tracing::info!("Party {}: Proof generation took {} ms", id, duration_ms);
-        result
+        Ok((proof, public_inputs))
+    }
+
+    /// Checks in MPC that the shared witness satisfies the gate constraints of the provided zkey. This optional phase is
+    /// much cheaper than the proof generation and can be run before [CoPlonk::prove] to abort early on a bad witness.
+    /// The copy constraints hold by construction, as the wires are read from the witness.
+    ///
+    /// For every batch of gates, a random linear combination of qM * a * b + qL * a + qR * b + qO * c + qC + PI is
+    /// opened (see [WitnessCheck]). If a batch does not open to zero,
+    /// [PlonkProofError::UnsatisfiedConstraints] naming the batch is returned. Hidden public inputs are opened, as
+    /// they would be by the prover.
+    pub fn check_witness(
+        &mut self,
+        zkey: &ZKey<P>,
+        witness: &SharedWitness<P::ScalarField, T::ArithmeticShare>,
+        check: WitnessCheck,
+    ) -> PlonkProofResult<()> {
+        let id = self.driver.get_party_id();
+        let start = Instant::now();
+        let mut witness = witness.clone();
+        if witness.has_hidden_public_inputs() {
+            let opened = self.driver.open_vec(&witness.shared_public_inputs)?;
+            witness.public_inputs.extend(opened);
+            witness.shared_public_inputs.clear();
+        }
+        let witness = Round1::calculate_additions(&mut self.driver, witness, zkey)?;
+        let wires = |map: &[usize]| {
+            map.iter()
+                .map(|index| plonk_utils::get_witness(id, &witness, zkey, *index))
+                .collect::<PlonkProofResult<Vec<_>>>()
+        };
+        let (a, b, c) = (
+            wires(&zkey.map_a)?,
+            wires(&zkey.map_b)?,
+            wires(&zkey.map_c)?,
+        );
+
+        let num_constraints = zkey.n_constraints;
+        let challenge = self.driver.rand()?;
+        let challenge = self.driver.open_vec(&[challenge])?[0];
+        let coefficients = check.coefficients(challenge, num_constraints);
+        let ab = self.driver.local_mul_vec(&a, &b);
+        self.driver.verify_local_mul_vec(&a, &b, &ab)?;
+        // the selectors are evaluated over the extended domain, so gate i is at index 4 * i
+        let (ab_sums, linear_sums): (Vec<_>, Vec<_>) = check
+            .batches(num_constraints)
+            .map(|batch| {
+                let mut ab_sum = P::ScalarField::zero();
+                let mut linear_sum = T::ArithmeticShare::default();
+                for i in batch {
+                    let j = 4 * i;
+                    ab_sum += coefficients[i] * zkey.qm_poly.evaluations[j] * ab[i];
+                    let mut gate = T::mul_with_public(a[i], zkey.ql_poly.evaluations[j]);
+                    gate = T::add(gate, T::mul_with_public(b[i], zkey.qr_poly.evaluations[j]));
+                    gate = T::add(gate, T::mul_with_public(c[i], zkey.qo_poly.evaluations[j]));
+                    gate = T::add_with_public(id, gate, zkey.qc_poly.evaluations[j]);
+                    if i < zkey.lagrange.len() {
+                        // PI(X) = -sum_j a_j * L_j(X)
+                        gate = T::sub(gate, a[i]);
+                    }
+                    linear_sum = T::add(linear_sum, T::mul_with_public(gate, coefficients[i]));
+                }
+                (ab_sum, linear_sum)
+            })
+            .unzip();
+        let ab_sums = self.driver.io_round_mul_vec(ab_sums)?;
+        let combinations = ab_sums
+            .into_iter()
+            .zip(linear_sums)
+            .map(|(ab, linear)| T::add(ab, linear))
+            .collect::<Vec<_>>();
+        let opened = self.driver.open_vec(&combinations)?;
+        check.verify(&opened, num_constraints)?;
+
+        let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
+        tracing::info!("Party {}: Witness check took {} ms", id, duration_ms);
+        Ok(())
     }
 }
 
@@ -230,15 +335,16 @@ impl<P: Pairing> Rep3CoPlonk<P> {
         })
     }
 
-    /// Create a new [Rep3CoPlonk] protocol with a given network configuration.
+    /// Create a new [Rep3CoPlonk] protocol with a given network configuration. The proof generation is bounded by the proof assembly timeout of the configuration.
     pub fn with_network_config(config: NetworkConfig) -> eyre::Result<Self> {
-        let mpc_net = Rep3MpcNet::new(config)?;
+        let mut mpc_net = Rep3MpcNet::new(config)?;
+        mpc_net.start_phase(Phase::ProofAssembly)?;
         Self::with_network(mpc_net)
     }
 }
 
 impl<P: Pairing> ShamirCoPlonk<P> {
-    /// Create a new [ShamirCoPlonk] protocol with a given network configuration.
+    /// Create a new [ShamirCoPlonk] protocol with a given network configuration. The proof generation is bounded by the proof assembly timeout of the configuration.
     pub fn with_network_config(
         threshold: usize,
         config: NetworkConfig,
@@ -247,7 +353,8 @@ impl<P: Pairing> ShamirCoPlonk<P> {
         let domain_size = zkey.domain_size;
         // TODO check and explain numbers
         let num_pairs = domain_size * 222 + 15;
-        let mpc_net = ShamirMpcNet::new(config)?;
+        let mut mpc_net = ShamirMpcNet::new(config)?;
+        mpc_net.start_phase(Phase::ProofAssembly)?;
         let preprocessing = ShamirPreprocessing::new(threshold, mpc_net, num_pairs)?;
         let mut protocol0 = ShamirProtocol::from(preprocessing);
         // TODO check and explain numbers
@@ -262,6 +369,7 @@ impl<P: Pairing> ShamirCoPlonk<P> {
 
 #[cfg(test)]
 mod tests {
+    use ark_bls12_381::Bls12_381;
     use ark_bn254::Bn254;
     use circom_types::groth16::JsonPublicInput;
     use circom_types::plonk::{JsonVerificationKey, ZKey};
@@ -272,7 +380,10 @@ mod tests {
 
     use circom_types::traits::CheckElement;
 
+    use crate::mpc::PlainPlonkDriver;
     use crate::plonk::Plonk;
+    use crate::PlonkProofError;
+    use co_circom_snarks::WitnessCheck;
 
     #[test]
     pub fn test_multiplier2_bn254() -> eyre::Result<()> {
@@ -284,6 +395,7 @@ mod tests {
 
             let witness = SharedWitness {
                 public_inputs: witness.values[..=zkey.n_public].to_vec(),
+                shared_public_inputs: vec![],
                 witness: witness.values[zkey.n_public + 1..].to_vec(),
             };
 
@@ -304,6 +416,29 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn test_check_witness_multiplier2_bn254() -> eyre::Result<()> {
+        let zkey_file = "../../test_vectors/Plonk/bn254/multiplier2/circuit.zkey";
+        let witness_file = "../../test_vectors/Plonk/bn254/multiplier2/witness.wtns";
+        let zkey = ZKey::<Bn254>::from_reader(File::open(zkey_file)?, CheckElement::No)?;
+        let witness = Witness::<ark_bn254::Fr>::from_reader(File::open(witness_file)?)?;
+        let mut witness = SharedWitness {
+            public_inputs: witness.values[..=zkey.n_public].to_vec(),
+            shared_public_inputs: vec![],
+            witness: witness.values[zkey.n_public + 1..].to_vec(),
+        };
+
+        let mut prover = Plonk::<Bn254>::new(PlainPlonkDriver);
+        prover.check_witness(&zkey, &witness, WitnessCheck::new(1))?;
+
+        witness.witness[0] += ark_bn254::Fr::from(1u64);
+        assert!(matches!(
+            prover.check_witness(&zkey, &witness, WitnessCheck::new(1)),
+            Err(PlonkProofError::UnsatisfiedConstraints(_))
+        ));
+        Ok(())
+    }
+
     #[test]
     pub fn test_poseidon_bn254() {
         for check in [CheckElement::Yes, CheckElement::No] {
@@ -317,6 +452,7 @@ mod tests {
             let public_input = witness.values[..=zkey.n_public].to_vec();
             let witness = SharedWitness {
                 public_inputs: public_input.clone(),
+                shared_public_inputs: vec![],
                 witness: witness.values[zkey.n_public + 1..].to_vec(),
             };
 
@@ -339,4 +475,68 @@ mod tests {
             Plonk::<Bn254>::verify(&vk, &proof, &public_inputs.values).unwrap();
         }
     }
+
+    #[test]
+    pub fn test_multiplier2_bls12_381() -> eyre::Result<()> {
+        for check in [CheckElement::Yes, CheckElement::No] {
+            let zkey_file = "../../test_vectors/Plonk/bls12_381/multiplier2/circuit.zkey";
+            let witness_file = "../../test_vectors/Plonk/bls12_381/multiplier2/witness.wtns";
+            let zkey = Arc::new(ZKey::<Bls12_381>::from_reader(
+                File::open(zkey_file)?,
+                check,
+            )?);
+            let witness = Witness::<ark_bls12_381::Fr>::from_reader(File::open(witness_file)?)?;
+
+            let witness = SharedWitness {
+                public_inputs: witness.values[..=zkey.n_public].to_vec(),
+                shared_public_inputs: vec![],
+                witness: witness.values[zkey.n_public + 1..].to_vec(),
+            };
+
+            let vk: JsonVerificationKey<Bls12_381> = serde_json::from_reader(File::open(
+                "../../test_vectors/Plonk/bls12_381/multiplier2/verification_key.json",
+            )?)?;
+
+            let public_input: JsonPublicInput<ark_bls12_381::Fr> = serde_json::from_reader(
+                File::open("../../test_vectors/Plonk/bls12_381/multiplier2/public.json")?,
+            )?;
+
+            let proof = Plonk::<Bls12_381>::plain_prove(zkey, witness)?;
+            Plonk::<Bls12_381>::verify(&vk, &proof, &public_input.values)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_poseidon_bls12_381() -> eyre::Result<()> {
+        let zkey_file = "../../test_vectors/Plonk/bls12_381/poseidon/circuit.zkey";
+        let witness_file = "../../test_vectors/Plonk/bls12_381/poseidon/witness.wtns";
+        let mut reader = BufReader::new(File::open(zkey_file)?);
+        let zkey = Arc::new(ZKey::<Bls12_381>::from_reader(
+            &mut reader,
+            CheckElement::No,
+        )?);
+        let witness = Witness::<ark_bls12_381::Fr>::from_reader(File::open(witness_file)?)?;
+        let witness = SharedWitness {
+            public_inputs: witness.values[..=zkey.n_public].to_vec(),
+            shared_public_inputs: vec![],
+            witness: witness.values[zkey.n_public + 1..].to_vec(),
+        };
+
+        let vk: JsonVerificationKey<Bls12_381> = serde_json::from_reader(File::open(
+            "../../test_vectors/Plonk/bls12_381/poseidon/verification_key.json",
+        )?)?;
+
+        let public_inputs: JsonPublicInput<ark_bls12_381::Fr> = serde_json::from_reader(
+            File::open("../../test_vectors/Plonk/bls12_381/poseidon/public.json")?,
+        )?;
+
+        let proof = Plonk::<Bls12_381>::plain_prove(zkey, witness)?;
+
+        // the proof has to survive the round trip through the snarkjs JSON format
+        let proof_bytes = serde_json::to_vec(&proof)?;
+        let proof = serde_json::from_slice(&proof_bytes)?;
+        Plonk::<Bls12_381>::verify(&vk, &proof, &public_inputs.values)?;
+        Ok(())
+    }
 }
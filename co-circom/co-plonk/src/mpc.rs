@@ -66,6 +66,18 @@ pub trait CircomPlonkProver<P: Pairing> {
         b: &[Self::ArithmeticShare],
     ) -> Vec<P::ScalarField>;
 
+    /// Verifies that `ab` is the result of [`CircomPlonkProver::local_mul_vec`] on `a` and `b`, i.e., that no party
+    /// cheated during the multiplication. Only drivers with an actively secure mode check anything, the default
+    /// implementation does nothing.
+    fn verify_local_mul_vec(
+        &mut self,
+        _a: &[Self::ArithmeticShare],
+        _b: &[Self::ArithmeticShare],
+        _ab: &[P::ScalarField],
+    ) -> IoResult<()> {
+        Ok(())
+    }
+
     /// Performs networking round of `local_mul_vec`
     fn io_round_mul_vec(&mut self, a: Vec<P::ScalarField>) -> IoResult<Vec<Self::ArithmeticShare>>;
 
@@ -137,6 +149,13 @@ pub trait CircomPlonkProver<P: Pairing> {
         domain: &D,
     ) -> Vec<Self::ArithmeticShare>;
 
+    /// Computes the inverse FFT of a vector of shared field elements in place, i.e., the buffer of the input is reused
+    /// for the result.
+    fn ifft_in_place<D: EvaluationDomain<P::ScalarField>>(
+        data: &mut Vec<Self::ArithmeticShare>,
+        domain: &D,
+    );
+
     /// Reconstructs a shared point: A = Open(\[A\]).
     fn open_point_g1(&mut self, a: Self::PointShareG1) -> IoResult<P::G1>;
 
@@ -151,6 +151,13 @@ impl<P: Pairing> CircomPlonkProver<P> for PlainPlonkDriver {
         domain.ifft(data)
     }
 
+    fn ifft_in_place<D: ark_poly::EvaluationDomain<P::ScalarField>>(
+        data: &mut Vec<Self::ArithmeticShare>,
+        domain: &D,
+    ) {
+        domain.ifft_in_place(data)
+    }
+
     fn open_point_g1(&mut self, a: Self::PointShareG1) -> IoResult<P::G1> {
         Ok(a)
     }
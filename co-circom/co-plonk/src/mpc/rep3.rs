@@ -3,6 +3,7 @@ use ark_poly::EvaluationDomain;
 use mpc_core::protocols::rep3::{
     arithmetic,
     id::PartyID,
+    malicious::{self, SecurityLevel},
     network::{IoContext, Rep3Network},
     pointshare, poly, Rep3PointShare, Rep3PrimeFieldShare,
 };
@@ -12,6 +13,9 @@ use super::{CircomPlonkProver, IoResult};
 /// A Plonk driver for REP3 secret sharing
 ///
 /// Contains two [`IoContext`]s, `io_context0` for the main execution and `io_context1` for parts that can run concurrently.
+///
+/// If the [`IoContext`]s use [`SecurityLevel::Malicious`], all multiplications and openings are checked (see
+/// [`malicious`]).
 pub struct Rep3PlonkDriver<N: Rep3Network> {
     io_context0: IoContext<N>,
     io_context1: IoContext<N>,
@@ -81,6 +85,20 @@ impl<P: Pairing, N: Rep3Network> CircomPlonkProver<P> for Rep3PlonkDriver<N> {
         arithmetic::local_mul_vec::<P::ScalarField>(a, b, &mut self.io_context0.rngs)
     }
 
+    fn verify_local_mul_vec(
+        &mut self,
+        a: &[Self::ArithmeticShare],
+        b: &[Self::ArithmeticShare],
+        ab: &[P::ScalarField],
+    ) -> IoResult<()> {
+        if self.io_context0.security_level == SecurityLevel::Malicious {
+            // the local products are additive shares, so they are reshared to replicated shares for the check
+            let ab = arithmetic::io_mul_vec(ab.to_vec(), &mut self.io_context0)?;
+            malicious::verify_mul_triples(a, b, &ab, &mut self.io_context0)?;
+        }
+        Ok(())
+    }
+
     fn io_round_mul_vec(&mut self, a: Vec<P::ScalarField>) -> IoResult<Vec<Self::ArithmeticShare>> {
         arithmetic::io_mul_vec(a, &mut self.io_context0)
     }
@@ -151,6 +169,13 @@ impl<P: Pairing, N: Rep3Network> CircomPlonkProver<P> for Rep3PlonkDriver<N> {
         domain.ifft(data)
     }
 
+    fn ifft_in_place<D: EvaluationDomain<P::ScalarField>>(
+        data: &mut Vec<Self::ArithmeticShare>,
+        domain: &D,
+    ) {
+        domain.ifft_in_place(data)
+    }
+
     fn open_point_g1(&mut self, a: Self::PointShareG1) -> IoResult<P::G1> {
         pointshare::open_point(&a, &mut self.io_context0)
     }
@@ -156,6 +156,13 @@ impl<P: Pairing, N: ShamirNetwork> CircomPlonkProver<P> for ShamirPlonkDriver<P:
         domain.ifft(data)
     }
 
+    fn ifft_in_place<D: EvaluationDomain<<P as Pairing>::ScalarField>>(
+        data: &mut Vec<Self::ArithmeticShare>,
+        domain: &D,
+    ) {
+        domain.ifft_in_place(data)
+    }
+
     fn open_point_g1(&mut self, a: Self::PointShareG1) -> IoResult<<P as Pairing>::G1> {
         pointshare::open_point(&a, &mut self.protocol0)
     }
@@ -2,14 +2,21 @@
 //! co-PLONK prover, see [CoPlonk].
 //!
 //! You will most likely need the plain PLONK implementation to verify a proof from co-PLONK. For that
-//! see the [`Plonk::verify`] method.
+//! see the [`Plonk::verify`] method. To verify many proofs for the same verification key, prepare it
+//! once as a [`PreparedVerifyingKey`] and use [`Plonk::verify_with_prepared`].
 
-use std::{marker::PhantomData, sync::Arc};
+use std::{
+    io::{Read, Write},
+    marker::PhantomData,
+    sync::Arc,
+};
 
 use crate::{mpc::plain::PlainPlonkDriver, plonk_utils, types::Domains, CoPlonk};
-use ark_ec::{pairing::Pairing, Group};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, Group};
 use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use circom_types::{
+    binary::{BinaryFormat, BinaryFormatError},
     plonk::{JsonVerificationKey, PlonkProof, ZKey},
     traits::{CircomArkworksPairingBridge, CircomArkworksPrimeFieldBridge},
 };
@@ -31,6 +38,58 @@ use crate::types::Keccak256Transcript;
 /// from snarkjs or one created by this project.
 pub type Plonk<P> = CoPlonk<P, PlainPlonkDriver>;
 
+/// A PLONK verification key whose G2 elements are prepared for the pairing check.
+///
+/// Verifying a proof with [`Plonk::verify_with_prepared`] skips this preparation, which pays off when many proofs
+/// are verified for the same key. The prepared key can be cached with its [`BinaryFormat`] implementation.
+#[derive(Debug)]
+pub struct PreparedVerifyingKey<P: Pairing + CircomArkworksPairingBridge>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// The verification key
+    pub vk: JsonVerificationKey<P>,
+    x2: P::G2Prepared,
+    g2: P::G2Prepared,
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> PreparedVerifyingKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    /// Prepares the verification key.
+    pub fn new(vk: JsonVerificationKey<P>) -> Self {
+        Self {
+            x2: vk.x2.into(),
+            g2: P::G2Affine::generator().into(),
+            vk,
+        }
+    }
+}
+
+impl<P: Pairing + CircomArkworksPairingBridge> BinaryFormat for PreparedVerifyingKey<P>
+where
+    P::BaseField: CircomArkworksPrimeFieldBridge,
+    P::ScalarField: CircomArkworksPrimeFieldBridge,
+{
+    fn write_elements<W: Write>(&self, mut writer: W) -> Result<(), BinaryFormatError> {
+        self.vk.write_elements(&mut writer)?;
+        self.x2.serialize_compressed(&mut writer)?;
+        self.g2.serialize_compressed(&mut writer)?;
+        Ok(())
+    }
+
+    fn read_elements<R: Read>(mut reader: R) -> Result<Self, BinaryFormatError> {
+        Ok(Self {
+            vk: JsonVerificationKey::read_elements(&mut reader)?,
+            x2: P::G2Prepared::deserialize_compressed(&mut reader)?,
+            g2: P::G2Prepared::deserialize_compressed(&mut reader)?,
+        })
+    }
+}
+
 pub(crate) struct VerifierChallenges<P: Pairing> {
     pub(super) alpha: P::ScalarField,
     pub(super) beta: P::ScalarField,
@@ -143,6 +202,27 @@ where
         P::BaseField: CircomArkworksPrimeFieldBridge,
         P::ScalarField: CircomArkworksPrimeFieldBridge,
     {
+        let x2 = vk.x2.into();
+        let g2 = P::G2Affine::generator().into();
+        Self::verify_inner(vk, &x2, &g2, proof, public_inputs)
+    }
+
+    /// Verifies a circom PLONK proof like [`Plonk::verify`], but with a [`PreparedVerifyingKey`].
+    pub fn verify_with_prepared(
+        pvk: &PreparedVerifyingKey<P>,
+        proof: &PlonkProof<P>,
+        public_inputs: &[P::ScalarField],
+    ) -> Result<(), VerificationError> {
+        Self::verify_inner(&pvk.vk, &pvk.x2, &pvk.g2, proof, public_inputs)
+    }
+
+    fn verify_inner(
+        vk: &JsonVerificationKey<P>,
+        x2: &P::G2Prepared,
+        g2: &P::G2Prepared,
+        proof: &PlonkProof<P>,
+        public_inputs: &[P::ScalarField],
+    ) -> Result<(), VerificationError> {
         if vk.n_public != public_inputs.len() {
             return Err(VerificationError::Malformed(eyre::eyre!(
                 "Invalid number of public inputs"
@@ -163,7 +243,7 @@ where
 
         let e = Plonk::<P>::calculate_e(proof, &challenges, r0);
         let f = Plonk::<P>::calculate_f(vk, proof, &challenges, d);
-        let valid = Plonk::<P>::valid_pairing(vk, proof, &challenges, e, f, &domains);
+        let valid = Plonk::<P>::valid_pairing(x2, g2, proof, &challenges, e, f, &domains);
 
         if valid {
             Ok(())
@@ -254,7 +334,8 @@ where
     }
 
     fn valid_pairing(
-        vk: &JsonVerificationKey<P>,
+        x2: &P::G2Prepared,
+        g2: &P::G2Prepared,
         proof: &PlonkProof<P>,
         challenges: &VerifierChallenges<P>,
         e: P::G1,
@@ -266,10 +347,9 @@ where
         let a1 = proof.wxi + proof.wxiw * challenges.u;
         let b1 = proof.wxi * challenges.xi + proof.wxiw * s - e + f;
 
-        let lhs = P::pairing(a1, vk.x2);
-        let rhs = P::pairing(b1, P::G2::generator());
-
-        lhs == rhs
+        // e(a1, x2) == e(b1, g2) <=> e(a1, x2) * e(-b1, g2) == 1
+        let g1 = P::G1::normalize_batch(&[a1, -b1]);
+        P::multi_pairing(g1, [x2.clone(), g2.clone()]).is_zero()
     }
 }
 
@@ -293,18 +373,38 @@ where
         };
         Ok(prover.prove(zkey, private_witness)?)
     }
+
+    /// *Locally* create a `Plonk` proof and additionally return the public inputs of the proof (without the leading
+    /// constant 1), as [`CoPlonk::prove_with_public_inputs`] does in MPC.
+    ///
+    /// This is the fast path for a witness without any secret-shared values, e.g., when the MPC-VM reports a fully
+    /// public witness because all inputs were public. No network is needed, and the proof has the same format as a
+    /// proof created in MPC.
+    pub fn plain_prove_with_public_inputs(
+        zkey: Arc<ZKey<P>>,
+        private_witness: SharedWitness<P::ScalarField, P::ScalarField>,
+    ) -> eyre::Result<(PlonkProof<P>, Vec<P::ScalarField>)> {
+        let prover = Self {
+            driver: PlainPlonkDriver,
+            phantom_data: PhantomData,
+        };
+        Ok(prover.prove_with_public_inputs(zkey, private_witness)?)
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
     use std::fs::File;
 
+    use ark_bls12_381::Bls12_381;
     use ark_bn254::Bn254;
     use circom_types::groth16::JsonPublicInput;
     use circom_types::plonk::{JsonVerificationKey, PlonkProof};
     use itertools::Itertools;
 
-    use super::{Plonk, VerifierChallenges};
+    use super::{Plonk, PreparedVerifyingKey, VerifierChallenges};
+    use circom_types::binary::BinaryFormat;
+    use co_circom_snarks::VerificationError;
     use std::str::FromStr;
     #[test]
     pub fn calculate_verifier_challenges() {
@@ -405,4 +505,71 @@ pub mod tests {
         .unwrap();
         Plonk::verify(&vk, &proof, &public_inputs.values).unwrap();
     }
+
+    #[test]
+    pub fn verify_multiplier2_from_circom_bls12_381() {
+        let vk: JsonVerificationKey<Bls12_381> = serde_json::from_reader(
+            File::open("../../test_vectors/Plonk/bls12_381/multiplier2/verification_key.json")
+                .unwrap(),
+        )
+        .unwrap();
+        let proof: PlonkProof<Bls12_381> = serde_json::from_reader(
+            File::open("../../test_vectors/Plonk/bls12_381/multiplier2/circom.proof").unwrap(),
+        )
+        .unwrap();
+        let public_inputs: JsonPublicInput<ark_bls12_381::Fr> = serde_json::from_reader(
+            File::open("../../test_vectors/Plonk/bls12_381/multiplier2/public.json").unwrap(),
+        )
+        .unwrap();
+        Plonk::verify(&vk, &proof, &public_inputs.values).unwrap();
+    }
+
+    #[test]
+    pub fn verify_poseidon_from_circom_bls12_381() {
+        let vk: JsonVerificationKey<Bls12_381> = serde_json::from_reader(
+            File::open("../../test_vectors/Plonk/bls12_381/poseidon/verification_key.json")
+                .unwrap(),
+        )
+        .unwrap();
+        let proof: PlonkProof<Bls12_381> = serde_json::from_reader(
+            File::open("../../test_vectors/Plonk/bls12_381/poseidon/circom.proof").unwrap(),
+        )
+        .unwrap();
+        let public_inputs: JsonPublicInput<ark_bls12_381::Fr> = serde_json::from_reader(
+            File::open("../../test_vectors/Plonk/bls12_381/poseidon/public.json").unwrap(),
+        )
+        .unwrap();
+        Plonk::verify(&vk, &proof, &public_inputs.values).unwrap();
+    }
+
+    #[test]
+    pub fn verify_with_prepared_bn254() {
+        let vk: JsonVerificationKey<Bn254> = serde_json::from_reader(
+            File::open("../../test_vectors/Plonk/bn254/poseidon/verification_key.json").unwrap(),
+        )
+        .unwrap();
+        let proof: PlonkProof<Bn254> = serde_json::from_reader(
+            File::open("../../test_vectors/Plonk/bn254/poseidon/circom.proof").unwrap(),
+        )
+        .unwrap();
+        let public_inputs: JsonPublicInput<ark_bn254::Fr> = serde_json::from_reader(
+            File::open("../../test_vectors/Plonk/bn254/poseidon/public.json").unwrap(),
+        )
+        .unwrap();
+
+        // the prepared verification key can be cached
+        let mut pvk_bytes = Vec::new();
+        PreparedVerifyingKey::new(vk)
+            .to_binary_writer(&mut pvk_bytes)
+            .unwrap();
+        let pvk = PreparedVerifyingKey::<Bn254>::from_binary_reader(pvk_bytes.as_slice()).unwrap();
+
+        Plonk::verify_with_prepared(&pvk, &proof, &public_inputs.values).unwrap();
+        let mut invalid_inputs = public_inputs.values.clone();
+        invalid_inputs[0] += ark_bn254::Fr::from(1u64);
+        assert!(matches!(
+            Plonk::verify_with_prepared(&pvk, &proof, &invalid_inputs),
+            Err(VerificationError::InvalidProof)
+        ));
+    }
 }
@@ -179,7 +179,7 @@ impl<'a, P: Pairing, T: CircomPlonkProver<P>> Round1<'a, P, T> {
 
     // Calculate the witnesses for the additions, since they are not part of the SharedWitness
     #[instrument(level = "debug", name = "calculate additions", skip_all)]
-    fn calculate_additions(
+    pub(super) fn calculate_additions(
         driver: &mut T,
         witness: SharedWitness<P::ScalarField, T::ArithmeticShare>,
         zkey: &ZKey<P>,
@@ -327,6 +327,7 @@ pub mod tests {
             let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
             let witness = SharedWitness {
                 public_inputs: witness.values[..=zkey.n_public].to_vec(),
+                shared_public_inputs: vec![],
                 witness: witness.values[zkey.n_public + 1..].to_vec(),
             };
             let challenges = Round1Challenges::deterministic(&mut driver);
@@ -372,6 +373,7 @@ pub mod tests {
             let public_input = witness.values[..=zkey.n_public].to_vec();
             let witness = SharedWitness {
                 public_inputs: public_input.clone(),
+                shared_public_inputs: vec![],
                 witness: witness.values[zkey.n_public + 1..].to_vec(),
             };
 
@@ -172,8 +172,9 @@ impl<'a, P: Pairing, T: CircomPlonkProver<P>> Round2<'a, P, T> {
 
         let fft_span = tracing::debug_span!("fft-ifft for z(x)").entered();
 
-        // Compute polynomial coefficients z(X) from buffer_z
-        let mut poly_z = T::ifft(&buffer_z, &domains.domain);
+        // Compute polynomial coefficients z(X) from buffer_z, reusing its buffer
+        T::ifft_in_place(&mut buffer_z, &domains.domain);
+        let mut poly_z = buffer_z;
 
         // Compute extended evaluations of z(X) polynomial
         let eval_z = T::fft(&poly_z, &domains.extended_domain);
@@ -287,6 +288,7 @@ pub mod tests {
             let public_input = witness.values[..=zkey.n_public].to_vec();
             let witness = SharedWitness {
                 public_inputs: public_input.clone(),
+                shared_public_inputs: vec![],
                 witness: witness.values[zkey.n_public + 1..].to_vec(),
             };
 
@@ -138,11 +138,21 @@ impl<P: Pairing> std::fmt::Display for Round3Proof<P> {
 }
 impl<P: Pairing, T: CircomPlonkProver<P>> FinalPolys<P, T> {
     fn new(
-        polys: Round2Polys<P, T>,
+        mut polys: Round2Polys<P, T>,
         t1: Vec<T::ArithmeticShare>,
         t2: Vec<T::ArithmeticShare>,
         t3: Vec<T::ArithmeticShare>,
     ) -> Self {
+        // the evaluations on the extended domain are only needed to compute t, so they are freed here instead of
+        // being carried through the remaining rounds
+        for poly in [
+            &mut polys.poly_eval_a,
+            &mut polys.poly_eval_b,
+            &mut polys.poly_eval_c,
+            &mut polys.z,
+        ] {
+            poly.eval = Vec::new();
+        }
         Self {
             a: polys.poly_eval_a,
             b: polys.poly_eval_b,
@@ -357,12 +367,16 @@ impl<'a, P: Pairing, T: CircomPlonkProver<P>> Round3<'a, P, T> {
             e3d.push(zw);
             w *= pow_plus2_root_of_unity;
         });
+        // free the products as early as possible to reduce the peak memory
+        drop((a_b, a_bp, ap_b, ap_bp));
 
         let [e2, e2z_0, e2z_1, e2z_2, e2z_3] =
             mul4vec!(driver, &e2a, &e2b, &e2c, &e2d, &ap, &bp, &cp, &zp, &domain1);
 
         let [e3, e3z_0, e3z_1, e3z_2, e3z_3] =
             mul4vec!(driver, &e3a, &e3b, &e3c, &e3d, &ap, &bp, &cp, &zwp, &domain1);
+        drop((e2a, e2b, e2c, e2d, e3a, e3b, e3c, e3d));
+        drop((ap, bp, cp, zwp));
 
         let mut t_vec = Vec::with_capacity(zkey.domain_size * 4);
         let mut tz_vec = Vec::with_capacity(zkey.domain_size * 4);
@@ -400,7 +414,10 @@ impl<'a, P: Pairing, T: CircomPlonkProver<P>> Round3<'a, P, T> {
             t_vec.push(t);
             tz_vec.push(tz);
         });
-        let mut coefficients_t = T::ifft(&t_vec, &domains.extended_domain);
+        drop((e1, e1z, e2, e3, zp));
+        drop((e2z_0, e2z_1, e2z_2, e2z_3, e3z_0, e3z_1, e3z_2, e3z_3));
+        T::ifft_in_place(&mut t_vec, &domains.extended_domain);
+        let mut coefficients_t = t_vec;
         driver.neg_vec_in_place(&mut coefficients_t[..zkey.domain_size]);
 
         // We do not want to have any network operation in here to reduce MPC rounds. To enforce this, we have a for_each loop here (Network operations require a result)
@@ -412,7 +429,8 @@ impl<'a, P: Pairing, T: CircomPlonkProver<P>> Round3<'a, P, T> {
             // Snarkjs is checking whether the poly was divisble by Zh, but we cannot do this here
         });
 
-        let coefficients_tz = T::ifft(&tz_vec, &domains.extended_domain);
+        T::ifft_in_place(&mut tz_vec, &domains.extended_domain);
+        let coefficients_tz = tz_vec;
 
         let mut t_final = izip!(coefficients_t.iter(), coefficients_tz.iter())
             .map(|(lhs, rhs)| T::add(*lhs, *rhs));
@@ -458,16 +476,17 @@ impl<'a, P: Pairing, T: CircomPlonkProver<P>> Round3<'a, P, T> {
         let challenges = Round3Challenges::new(challenges, alpha, alpha2);
 
         let [t1, t2, t3] = Self::compute_t(&mut driver, &domains, &challenges, data.zkey, &polys)?;
+        let polys = FinalPolys::new(polys, t1, t2, t3);
 
         tracing::debug!("committing to poly t (MSMs)");
         // Compute [T1]_1, [T2]_1, [T3]_1
-        let commit_t1 = T::msm_public_points_g1(&data.zkey.p_tau[..t1.len()], &t1);
-        let commit_t2 = T::msm_public_points_g1(&data.zkey.p_tau[..t2.len()], &t2);
-        let commit_t3 = T::msm_public_points_g1(&data.zkey.p_tau[..t3.len()], &t3);
+        let p_tau = &data.zkey.p_tau;
+        let commit_t1 = T::msm_public_points_g1(&p_tau[..polys.t1.len()], &polys.t1);
+        let commit_t2 = T::msm_public_points_g1(&p_tau[..polys.t2.len()], &polys.t2);
+        let commit_t3 = T::msm_public_points_g1(&p_tau[..polys.t3.len()], &polys.t3);
 
         let opened = driver.open_point_vec_g1(&[commit_t1, commit_t2, commit_t3])?;
 
-        let polys = FinalPolys::new(polys, t1, t2, t3);
         let proof = Round3Proof::new(proof, opened[0], opened[1], opened[2]);
         tracing::debug!("round3 result: {proof}");
         Ok(Round4 {
@@ -524,6 +543,7 @@ pub mod tests {
             let public_input = witness.values[..=zkey.n_public].to_vec();
             let witness = SharedWitness {
                 public_inputs: public_input.clone(),
+                shared_public_inputs: vec![],
                 witness: witness.values[zkey.n_public + 1..].to_vec(),
             };
 
@@ -175,11 +175,8 @@ where
 
         let mut poly_r_shared = vec![T::ArithmeticShare::default(); len];
 
-        for (inout, add) in poly_r_shared
-            .iter_mut()
-            .zip(polys.z.poly.clone().into_iter())
-        {
-            *inout = T::mul_with_public(add, e24);
+        for (inout, add) in poly_r_shared.iter_mut().zip(polys.z.poly.iter()) {
+            *inout = T::mul_with_public(*add, e24);
         }
 
         for (inout, add) in poly_r_shared.iter_mut().zip(poly_r.iter()) {
@@ -188,15 +185,15 @@ where
 
         let mut tmp_poly = vec![T::ArithmeticShare::default(); len];
         let xin2 = xin.square();
-        for (inout, add) in tmp_poly.iter_mut().zip(polys.t3.clone().into_iter()) {
-            *inout = T::mul_with_public(add, xin2);
+        for (inout, add) in tmp_poly.iter_mut().zip(polys.t3.iter()) {
+            *inout = T::mul_with_public(*add, xin2);
         }
-        for (inout, add) in tmp_poly.iter_mut().zip(polys.t2.clone().into_iter()) {
-            let tmp = T::mul_with_public(add, xin);
+        for (inout, add) in tmp_poly.iter_mut().zip(polys.t2.iter()) {
+            let tmp = T::mul_with_public(*add, xin);
             *inout = T::add(*inout, tmp);
         }
-        for (inout, add) in tmp_poly.iter_mut().zip(polys.t1.clone().into_iter()) {
-            *inout = T::add(*inout, add);
+        for (inout, add) in tmp_poly.iter_mut().zip(polys.t1.iter()) {
+            *inout = T::add(*inout, *add);
         }
         for inout in tmp_poly.iter_mut() {
             *inout = T::mul_with_public(*inout, zh);
@@ -231,18 +228,18 @@ where
             *inout = *add;
         }
         // A
-        for (inout, add) in res.iter_mut().zip(polys.a.poly.clone().into_iter()) {
-            let tmp = T::mul_with_public(add, challenges.v[0]);
+        for (inout, add) in res.iter_mut().zip(polys.a.poly.iter()) {
+            let tmp = T::mul_with_public(*add, challenges.v[0]);
             *inout = T::add(tmp, *inout);
         }
         // B
-        for (inout, add) in res.iter_mut().zip(polys.b.poly.clone().into_iter()) {
-            let tmp = T::mul_with_public(add, challenges.v[1]);
+        for (inout, add) in res.iter_mut().zip(polys.b.poly.iter()) {
+            let tmp = T::mul_with_public(*add, challenges.v[1]);
             *inout = T::add(tmp, *inout);
         }
         // C
-        for (inout, add) in res.iter_mut().zip(polys.c.poly.clone().into_iter()) {
-            let tmp = T::mul_with_public(add, challenges.v[2]);
+        for (inout, add) in res.iter_mut().zip(polys.c.poly.iter()) {
+            let tmp = T::mul_with_public(*add, challenges.v[2]);
             *inout = T::add(tmp, *inout);
         }
         // Sigma1
@@ -272,12 +269,13 @@ where
         domains: &Domains<P::ScalarField>,
         proof: &Round4Proof<P>,
         challenges: &Round5Challenges<P>,
-        polys: &FinalPolys<P, T>,
+        polys: FinalPolys<P, T>,
     ) -> Vec<T::ArithmeticShare> {
         tracing::debug!("computing wxiw polynomial...");
         let xiw = challenges.xi * domains.root_of_unity_pow;
 
-        let mut res = polys.z.poly.clone().into_iter().collect::<Vec<_>>();
+        // z is not needed anymore afterwards, so we divide its coefficients in place
+        let mut res = polys.z.poly;
         res[0] = T::add_with_public(driver.get_party_id(), res[0], -proof.eval_zw);
         Self::div_by_zerofier(&mut res, 1, xiw);
 
@@ -325,7 +323,7 @@ where
         let wxi = Self::compute_wxi(party_id, &proof, &challenges, &data, &polys, &r);
 
         //STEP 5.4 Compute opening proof polynomial Wxiw(X)
-        let wxiw = Self::compute_wxiw(&mut driver, &domains, &proof, &challenges, &polys);
+        let wxiw = Self::compute_wxiw(&mut driver, &domains, &proof, &challenges, polys);
         // Fifth output of the prover is ([Wxi]_1, [Wxiw]_1)
 
         let p_tau = &data.zkey.p_tau;
@@ -385,6 +383,7 @@ pub mod tests {
             let public_input = witness.values[..=zkey.n_public].to_vec();
             let witness = SharedWitness {
                 public_inputs: public_input.clone(),
+                shared_public_inputs: vec![],
                 witness: witness.values[zkey.n_public + 1..].to_vec(),
             };
 
@@ -3,11 +3,20 @@ use std::{fmt, io};
 use ark_ff::PrimeField;
 use co_brillig::mpc::BrilligDriver;
 use mpc_core::lut::LookupTableProvider;
+use mpc_core::protocols::rep3::gadgets::ecc::ShortWeierstrassCurve;
 
 pub(super) mod plain;
 pub(super) mod rep3;
 pub(super) mod shamir; // Does not support everything, but basic circuits can be build using Shamir (co-builder)
 
+/// The number of bits of each of the two limbs of a scalar of the embedded curve.
+pub(super) const EMBEDDED_CURVE_SCALAR_LIMB_BITS: usize = 128;
+
+/// Returns the embedded curve of Noir, which is Grumpkin y^2 = x^3 - 17 for BN254.
+pub(super) fn embedded_curve<F: PrimeField>() -> ShortWeierstrassCurve<F> {
+    ShortWeierstrassCurve::new(-F::from(17u64))
+}
+
 /// A trait representing the MPC operations required for extending the secret-shared Noir witness in MPC.
 /// The operations are generic over public and private (i.e., secret-shared) inputs.
 pub trait NoirWitnessExtensionProtocol<F: PrimeField> {
@@ -153,4 +162,39 @@ pub trait NoirWitnessExtensionProtocol<F: PrimeField> {
         inputs: &[Self::ArithmeticShare],
         bitsize: usize,
     ) -> std::io::Result<Vec<Self::ArithmeticShare>>;
+
+    /// Computes the Keccak-f\[1600\] permutation on a state of 25 lanes of 64 bits each, where lane (x, y) is at position x + 5y.
+    fn keccak_f1600(&mut self, state: Vec<Self::AcvmType>) -> std::io::Result<Vec<Self::AcvmType>>;
+
+    /// Computes the multi-scalar multiplication on the embedded curve. The points are given as consecutive triples (x, y, is_infinite) and the scalars as consecutive pairs (lo, hi) of 128-bit limbs. Returns the resulting point as (x, y, is_infinite).
+    fn multi_scalar_mul(
+        &mut self,
+        points: &[Self::AcvmType],
+        scalars: &[Self::AcvmType],
+    ) -> std::io::Result<(Self::AcvmType, Self::AcvmType, Self::AcvmType)>;
+
+    /// Adds two points on the embedded curve, which are given as (x, y, is_infinite). Returns the resulting point as (x, y, is_infinite).
+    fn embedded_curve_add(
+        &mut self,
+        point1: (Self::AcvmType, Self::AcvmType, Self::AcvmType),
+        point2: (Self::AcvmType, Self::AcvmType, Self::AcvmType),
+    ) -> std::io::Result<(Self::AcvmType, Self::AcvmType, Self::AcvmType)>;
+
+    /// Verifies an ECDSA signature over secp256k1. The public key coordinates and the hashed message are given as 32 big-endian bytes each and the signature as the 64 bytes of r and s. Returns 1 if the signature is valid and 0 otherwise.
+    fn ecdsa_secp256k1(
+        &mut self,
+        public_key_x: &[Self::AcvmType],
+        public_key_y: &[Self::AcvmType],
+        signature: &[Self::AcvmType],
+        hashed_message: &[Self::AcvmType],
+    ) -> std::io::Result<Self::AcvmType>;
+
+    /// Verifies an ECDSA signature over secp256r1. The inputs are given as in [`Self::ecdsa_secp256k1`].
+    fn ecdsa_secp256r1(
+        &mut self,
+        public_key_x: &[Self::AcvmType],
+        public_key_y: &[Self::AcvmType],
+        signature: &[Self::AcvmType],
+        hashed_message: &[Self::AcvmType],
+    ) -> std::io::Result<Self::AcvmType>;
 }
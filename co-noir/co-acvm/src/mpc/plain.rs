@@ -2,12 +2,14 @@ use std::collections::HashMap;
 use std::io;
 use std::marker::PhantomData;
 
+use ark_ec::short_weierstrass::SWCurveConfig;
 use ark_ff::{One, PrimeField};
 use co_brillig::mpc::{PlainBrilligDriver, PlainBrilligType};
 use mpc_core::lut::{LookupTableProvider, PlainLookupTableProvider};
+use mpc_core::protocols::rep3::gadgets::{ecc::ProjectivePoint, ecdsa, keccak};
 use num_bigint::BigUint;
 
-use super::NoirWitnessExtensionProtocol;
+use super::{embedded_curve, NoirWitnessExtensionProtocol, EMBEDDED_CURVE_SCALAR_LIMB_BITS};
 
 #[derive(Default)]
 pub struct PlainAcvmSolver<F: PrimeField> {
@@ -21,6 +23,117 @@ impl<F: PrimeField> PlainAcvmSolver<F> {
             phantom_data: Default::default(),
         }
     }
+
+    /// Checks that the number of coordinates and limbs match for a multi-scalar multiplication.
+    pub(super) fn check_msm_input_lengths(points: usize, scalars: usize) -> io::Result<()> {
+        if points % 3 != 0 || scalars % 2 != 0 || points / 3 != scalars / 2 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "multi-scalar multiplication requires three coordinates per point and two limbs per scalar",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Creates a point on the embedded curve from its affine coordinates and checks that it is on the curve.
+    pub(super) fn embedded_curve_point(
+        x: F,
+        y: F,
+        is_infinite: F,
+    ) -> io::Result<ProjectivePoint<F>> {
+        if !is_infinite.is_zero() && !is_infinite.is_one() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "is_infinite of an embedded curve point has to be a bit",
+            ));
+        }
+        let is_infinite = is_infinite.is_one();
+        if !is_infinite && y.square() != x.square() * x - F::from(17u64) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "point is not on the embedded curve",
+            ));
+        }
+        Ok(ProjectivePoint::from_affine(x, y, is_infinite))
+    }
+
+    fn embedded_curve_scalar(lo: F, hi: F) -> io::Result<BigUint> {
+        let lo: BigUint = lo.into();
+        let hi: BigUint = hi.into();
+        if lo.bits() > EMBEDDED_CURVE_SCALAR_LIMB_BITS as u64
+            || hi.bits() > EMBEDDED_CURVE_SCALAR_LIMB_BITS as u64
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "limb of an embedded curve scalar does not fit into 128 bits",
+            ));
+        }
+        Ok(lo + (hi << EMBEDDED_CURVE_SCALAR_LIMB_BITS))
+    }
+
+    fn embedded_curve_affine(point: &ProjectivePoint<F>) -> (F, F, F) {
+        let (x, y, is_infinite) = point.to_affine();
+        (x, y, F::from(is_infinite))
+    }
+
+    /// Checks the number of bytes of the inputs of an ECDSA signature verification.
+    pub(super) fn check_ecdsa_input_lengths(
+        public_key_x: usize,
+        public_key_y: usize,
+        signature: usize,
+        hashed_message: usize,
+    ) -> io::Result<()> {
+        if public_key_x != ecdsa::NUM_BYTES
+            || public_key_y != ecdsa::NUM_BYTES
+            || signature != 2 * ecdsa::NUM_BYTES
+            || hashed_message != ecdsa::NUM_BYTES
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "ECDSA verification requires 32 bytes per coordinate and hash and 64 bytes for the signature",
+            ));
+        }
+        Ok(())
+    }
+
+    fn ecdsa_bytes_to_biguint(bytes: &[F]) -> io::Result<BigUint> {
+        let bytes = bytes
+            .iter()
+            .map(|byte| {
+                let byte: BigUint = (*byte).into();
+                u8::try_from(byte).map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "ECDSA input is not a byte")
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(BigUint::from_bytes_be(&bytes))
+    }
+
+    pub(super) fn ecdsa_verify<P: SWCurveConfig>(
+        public_key_x: &[F],
+        public_key_y: &[F],
+        signature: &[F],
+        hashed_message: &[F],
+    ) -> io::Result<F>
+    where
+        P::BaseField: PrimeField,
+    {
+        Self::check_ecdsa_input_lengths(
+            public_key_x.len(),
+            public_key_y.len(),
+            signature.len(),
+            hashed_message.len(),
+        )?;
+        let (r, s) = signature.split_at(ecdsa::NUM_BYTES);
+        let valid = ecdsa::verify::<P>(
+            &Self::ecdsa_bytes_to_biguint(public_key_x)?,
+            &Self::ecdsa_bytes_to_biguint(public_key_y)?,
+            &Self::ecdsa_bytes_to_biguint(r)?,
+            &Self::ecdsa_bytes_to_biguint(s)?,
+            &Self::ecdsa_bytes_to_biguint(hashed_message)?,
+        );
+        Ok(F::from(valid))
+    }
 }
 
 impl<F: PrimeField> NoirWitnessExtensionProtocol<F> for PlainAcvmSolver<F> {
@@ -203,4 +316,87 @@ impl<F: PrimeField> NoirWitnessExtensionProtocol<F> for PlainAcvmSolver<F> {
         result.sort();
         Ok(result)
     }
+
+    fn keccak_f1600(&mut self, state: Vec<Self::AcvmType>) -> io::Result<Vec<Self::AcvmType>> {
+        let mut lanes: [u64; 25] = state
+            .into_iter()
+            .map(|x| {
+                let x: BigUint = x.into();
+                u64::try_from(x).map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "Keccak-f[1600] lane does not fit into 64 bits",
+                    )
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?
+            .try_into()
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Keccak-f[1600] requires a state of 25 lanes",
+                )
+            })?;
+        keccak::keccak_f1600(&mut lanes);
+        Ok(lanes.into_iter().map(F::from).collect())
+    }
+
+    fn multi_scalar_mul(
+        &mut self,
+        points: &[Self::AcvmType],
+        scalars: &[Self::AcvmType],
+    ) -> io::Result<(Self::AcvmType, Self::AcvmType, Self::AcvmType)> {
+        Self::check_msm_input_lengths(points.len(), scalars.len())?;
+        let points = points
+            .chunks_exact(3)
+            .map(|p| Self::embedded_curve_point(p[0], p[1], p[2]))
+            .collect::<io::Result<Vec<_>>>()?;
+        let scalars = scalars
+            .chunks_exact(2)
+            .map(|s| Self::embedded_curve_scalar(s[0], s[1]))
+            .collect::<io::Result<Vec<_>>>()?;
+        let res = embedded_curve().msm(&points, &scalars);
+        Ok(Self::embedded_curve_affine(&res))
+    }
+
+    fn embedded_curve_add(
+        &mut self,
+        point1: (Self::AcvmType, Self::AcvmType, Self::AcvmType),
+        point2: (Self::AcvmType, Self::AcvmType, Self::AcvmType),
+    ) -> io::Result<(Self::AcvmType, Self::AcvmType, Self::AcvmType)> {
+        let point1 = Self::embedded_curve_point(point1.0, point1.1, point1.2)?;
+        let point2 = Self::embedded_curve_point(point2.0, point2.1, point2.2)?;
+        let res = embedded_curve().add(&point1, &point2);
+        Ok(Self::embedded_curve_affine(&res))
+    }
+
+    fn ecdsa_secp256k1(
+        &mut self,
+        public_key_x: &[Self::AcvmType],
+        public_key_y: &[Self::AcvmType],
+        signature: &[Self::AcvmType],
+        hashed_message: &[Self::AcvmType],
+    ) -> io::Result<Self::AcvmType> {
+        Self::ecdsa_verify::<ark_secp256k1::Config>(
+            public_key_x,
+            public_key_y,
+            signature,
+            hashed_message,
+        )
+    }
+
+    fn ecdsa_secp256r1(
+        &mut self,
+        public_key_x: &[Self::AcvmType],
+        public_key_y: &[Self::AcvmType],
+        signature: &[Self::AcvmType],
+        hashed_message: &[Self::AcvmType],
+    ) -> io::Result<Self::AcvmType> {
+        Self::ecdsa_verify::<ark_secp256r1::Config>(
+            public_key_x,
+            public_key_y,
+            signature,
+            hashed_message,
+        )
+    }
 }
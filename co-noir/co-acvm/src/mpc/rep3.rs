@@ -1,9 +1,17 @@
 use std::marker::PhantomData;
 
-use ark_ff::PrimeField;
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::{One, PrimeField};
 use co_brillig::mpc::{Rep3BrilligDriver, Rep3BrilligType};
 use itertools::{izip, Itertools};
-use mpc_core::protocols::rep3::{arithmetic, yao};
+use mpc_core::protocols::rep3::{
+    arithmetic,
+    gadgets::{
+        ecc::{PointShare, ShortWeierstrassCurve},
+        ecdsa, keccak,
+    },
+    yao,
+};
 use mpc_core::protocols::rep3_ring::gadgets::sort::radix_sort_fields;
 use mpc_core::{
     lut::LookupTableProvider,
@@ -13,11 +21,12 @@ use mpc_core::{
         Rep3PrimeFieldShare,
     },
 };
+use num_bigint::BigUint;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use super::plain::PlainAcvmSolver;
-use super::NoirWitnessExtensionProtocol;
+use super::{embedded_curve, NoirWitnessExtensionProtocol, EMBEDDED_CURVE_SCALAR_LIMB_BITS};
 type ArithmeticShare<F> = Rep3PrimeFieldShare<F>;
 
 pub struct Rep3AcvmSolver<F: PrimeField, N: Rep3Network> {
@@ -44,6 +53,55 @@ impl<F: PrimeField, N: Rep3Network> Rep3AcvmSolver<F, N> {
     pub fn get_io_contexts(self) -> (IoContext<N>, IoContext<N>) {
         (self.io_context, self.lut_provider.get_io_context())
     }
+
+    fn to_shared(&self, a: &Rep3AcvmType<F>) -> ArithmeticShare<F> {
+        match a {
+            Rep3AcvmType::Public(public) => {
+                arithmetic::promote_to_trivial_share(self.io_context.id, *public)
+            }
+            Rep3AcvmType::Shared(shared) => *shared,
+        }
+    }
+
+    fn ecdsa_verify<P: SWCurveConfig>(
+        &mut self,
+        public_key_x: &[Rep3AcvmType<F>],
+        public_key_y: &[Rep3AcvmType<F>],
+        signature: &[Rep3AcvmType<F>],
+        hashed_message: &[Rep3AcvmType<F>],
+    ) -> std::io::Result<Rep3AcvmType<F>>
+    where
+        P::BaseField: PrimeField,
+    {
+        PlainAcvmSolver::<F>::check_ecdsa_input_lengths(
+            public_key_x.len(),
+            public_key_y.len(),
+            signature.len(),
+            hashed_message.len(),
+        )?;
+        let inputs = [public_key_x, public_key_y, signature, hashed_message];
+        let public = inputs.map(|input| {
+            input
+                .iter()
+                .map(Self::get_public)
+                .collect::<Option<Vec<_>>>()
+        });
+        if let [Some(x), Some(y), Some(signature), Some(hashed_message)] = public {
+            let valid =
+                PlainAcvmSolver::<F>::ecdsa_verify::<P>(&x, &y, &signature, &hashed_message)?;
+            return Ok(valid.into());
+        }
+        let [x, y, signature, hashed_message] =
+            inputs.map(|input| input.iter().map(|x| self.to_shared(x)).collect_vec());
+        let valid = ecdsa::rep3_verify_bytes::<P, F, N>(
+            &x.try_into().expect("checked length"),
+            &y.try_into().expect("checked length"),
+            &signature.try_into().expect("checked length"),
+            &hashed_message.try_into().expect("checked length"),
+            &mut self.io_context,
+        )?;
+        Ok(valid.into())
+    }
 }
 
 // TODO maybe we want to merge that with the Rep3VmType?? Atm we do not need
@@ -498,4 +556,167 @@ impl<F: PrimeField, N: Rep3Network> NoirWitnessExtensionProtocol<F> for Rep3Acvm
     ) -> std::io::Result<Vec<Self::ArithmeticShare>> {
         radix_sort_fields(inputs, &mut self.io_context, bitsize)
     }
+
+    fn keccak_f1600(&mut self, state: Vec<Self::AcvmType>) -> std::io::Result<Vec<Self::AcvmType>> {
+        if state.iter().all(|x| matches!(x, Rep3AcvmType::Public(_))) {
+            let state = state
+                .into_iter()
+                .map(|x| match x {
+                    Rep3AcvmType::Public(x) => x,
+                    Rep3AcvmType::Shared(_) => unreachable!(),
+                })
+                .collect();
+            return Ok(self
+                .plain_solver
+                .keccak_f1600(state)?
+                .into_iter()
+                .map(Rep3AcvmType::Public)
+                .collect());
+        }
+        let state: [ArithmeticShare<F>; 25] = state
+            .into_iter()
+            .map(|x| match x {
+                Rep3AcvmType::Public(x) => {
+                    arithmetic::promote_to_trivial_share(self.io_context.id, x)
+                }
+                Rep3AcvmType::Shared(x) => x,
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "Keccak-f[1600] requires a state of 25 lanes",
+                )
+            })?;
+        let result = keccak::keccak_f1600_many(&[state], &mut self.io_context)?;
+        Ok(result[0].into_iter().map(Rep3AcvmType::Shared).collect())
+    }
+
+    fn multi_scalar_mul(
+        &mut self,
+        points: &[Self::AcvmType],
+        scalars: &[Self::AcvmType],
+    ) -> std::io::Result<(Self::AcvmType, Self::AcvmType, Self::AcvmType)> {
+        PlainAcvmSolver::<F>::check_msm_input_lengths(points.len(), scalars.len())?;
+        let public_points = points
+            .iter()
+            .map(Self::get_public)
+            .collect::<Option<Vec<_>>>();
+        let public_scalars = scalars
+            .iter()
+            .map(Self::get_public)
+            .collect::<Option<Vec<_>>>();
+        if let (Some(points), Some(scalars)) = (&public_points, public_scalars) {
+            let (x, y, is_infinite) = self.plain_solver.multi_scalar_mul(points, &scalars)?;
+            return Ok((x.into(), y.into(), is_infinite.into()));
+        }
+
+        // lo * P + hi * 2^128 * P, such that all scalars have 128 bits
+        let curve = embedded_curve::<F>();
+        let id = self.io_context.id;
+        let scalars = scalars.iter().map(|s| self.to_shared(s)).collect_vec();
+        let res = if let Some(points) = public_points {
+            let shift = BigUint::one() << EMBEDDED_CURVE_SCALAR_LIMB_BITS;
+            let points = points
+                .chunks_exact(3)
+                .map(|p| PlainAcvmSolver::embedded_curve_point(p[0], p[1], p[2]))
+                .collect::<std::io::Result<Vec<_>>>()?
+                .into_iter()
+                .flat_map(|p| [p, curve.scalar_mul(&p, &shift)])
+                .collect_vec();
+            curve.rep3_msm_public_points(
+                &points,
+                &scalars,
+                EMBEDDED_CURVE_SCALAR_LIMB_BITS,
+                &mut self.io_context,
+            )?
+        } else {
+            let points = points
+                .chunks_exact(3)
+                .map(|p| {
+                    PointShare::from_affine_shared(
+                        self.to_shared(&p[0]),
+                        self.to_shared(&p[1]),
+                        self.to_shared(&p[2]),
+                        id,
+                    )
+                })
+                .collect_vec();
+            let mut shifted = points.clone();
+            for _ in 0..EMBEDDED_CURVE_SCALAR_LIMB_BITS {
+                shifted = curve.rep3_add_many(&shifted, &shifted, &mut self.io_context)?;
+            }
+            let points = izip!(points, shifted)
+                .flat_map(|(p, shifted)| [p, shifted])
+                .collect_vec();
+            curve.rep3_msm(
+                &points,
+                &scalars,
+                EMBEDDED_CURVE_SCALAR_LIMB_BITS,
+                &mut self.io_context,
+            )?
+        };
+        let (x, y, is_infinite) =
+            ShortWeierstrassCurve::rep3_to_affine_many(&[res], &mut self.io_context)?[0];
+        Ok((x.into(), y.into(), is_infinite.into()))
+    }
+
+    fn embedded_curve_add(
+        &mut self,
+        point1: (Self::AcvmType, Self::AcvmType, Self::AcvmType),
+        point2: (Self::AcvmType, Self::AcvmType, Self::AcvmType),
+    ) -> std::io::Result<(Self::AcvmType, Self::AcvmType, Self::AcvmType)> {
+        let coordinates = [
+            &point1.0, &point1.1, &point1.2, &point2.0, &point2.1, &point2.2,
+        ];
+        if let Some(c) = coordinates
+            .iter()
+            .map(|c| Self::get_public(c))
+            .collect::<Option<Vec<_>>>()
+        {
+            let (x, y, is_infinite) = self
+                .plain_solver
+                .embedded_curve_add((c[0], c[1], c[2]), (c[3], c[4], c[5]))?;
+            return Ok((x.into(), y.into(), is_infinite.into()));
+        }
+        let id = self.io_context.id;
+        let c = coordinates.map(|c| self.to_shared(c));
+        let point1 = PointShare::from_affine_shared(c[0], c[1], c[2], id);
+        let point2 = PointShare::from_affine_shared(c[3], c[4], c[5], id);
+        let res = embedded_curve::<F>().rep3_add(&point1, &point2, &mut self.io_context)?;
+        let (x, y, is_infinite) =
+            ShortWeierstrassCurve::rep3_to_affine_many(&[res], &mut self.io_context)?[0];
+        Ok((x.into(), y.into(), is_infinite.into()))
+    }
+
+    fn ecdsa_secp256k1(
+        &mut self,
+        public_key_x: &[Self::AcvmType],
+        public_key_y: &[Self::AcvmType],
+        signature: &[Self::AcvmType],
+        hashed_message: &[Self::AcvmType],
+    ) -> std::io::Result<Self::AcvmType> {
+        self.ecdsa_verify::<ark_secp256k1::Config>(
+            public_key_x,
+            public_key_y,
+            signature,
+            hashed_message,
+        )
+    }
+
+    fn ecdsa_secp256r1(
+        &mut self,
+        public_key_x: &[Self::AcvmType],
+        public_key_y: &[Self::AcvmType],
+        signature: &[Self::AcvmType],
+        hashed_message: &[Self::AcvmType],
+    ) -> std::io::Result<Self::AcvmType> {
+        self.ecdsa_verify::<ark_secp256r1::Config>(
+            public_key_x,
+            public_key_y,
+            signature,
+            hashed_message,
+        )
+    }
 }
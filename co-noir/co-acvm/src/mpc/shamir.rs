@@ -415,4 +415,47 @@ impl<F: PrimeField, N: ShamirNetwork> NoirWitnessExtensionProtocol<F> for Shamir
     ) -> std::io::Result<Vec<Self::ArithmeticShare>> {
         panic!("functionality sort not feasible for Shamir")
     }
+
+    fn keccak_f1600(
+        &mut self,
+        _state: Vec<Self::AcvmType>,
+    ) -> std::io::Result<Vec<Self::AcvmType>> {
+        panic!("functionality keccak_f1600 not feasible for Shamir")
+    }
+
+    fn multi_scalar_mul(
+        &mut self,
+        _points: &[Self::AcvmType],
+        _scalars: &[Self::AcvmType],
+    ) -> std::io::Result<(Self::AcvmType, Self::AcvmType, Self::AcvmType)> {
+        panic!("functionality multi_scalar_mul not feasible for Shamir")
+    }
+
+    fn embedded_curve_add(
+        &mut self,
+        _point1: (Self::AcvmType, Self::AcvmType, Self::AcvmType),
+        _point2: (Self::AcvmType, Self::AcvmType, Self::AcvmType),
+    ) -> std::io::Result<(Self::AcvmType, Self::AcvmType, Self::AcvmType)> {
+        panic!("functionality embedded_curve_add not feasible for Shamir")
+    }
+
+    fn ecdsa_secp256k1(
+        &mut self,
+        _public_key_x: &[Self::AcvmType],
+        _public_key_y: &[Self::AcvmType],
+        _signature: &[Self::AcvmType],
+        _hashed_message: &[Self::AcvmType],
+    ) -> std::io::Result<Self::AcvmType> {
+        panic!("functionality ecdsa_secp256k1 not feasible for Shamir")
+    }
+
+    fn ecdsa_secp256r1(
+        &mut self,
+        _public_key_x: &[Self::AcvmType],
+        _public_key_y: &[Self::AcvmType],
+        _signature: &[Self::AcvmType],
+        _hashed_message: &[Self::AcvmType],
+    ) -> std::io::Result<Self::AcvmType> {
+        panic!("functionality ecdsa_secp256r1 not feasible for Shamir")
+    }
 }
@@ -108,6 +108,103 @@ where
         Ok(())
     }
 
+    pub(crate) fn solve_keccakf1600(
+        &mut self,
+        inputs: &[FunctionInput<GenericFieldElement<F>>; 25],
+        outputs: &[Witness; 25],
+    ) -> CoAcvmResult<()> {
+        let initial_witness = &self.witness_map[self.function_index];
+        let state = inputs
+            .iter()
+            .map(|input| Self::input_to_value(initial_witness, *input, false))
+            .collect::<CoAcvmResult<Vec<_>>>()?;
+        let state = self.driver.keccak_f1600(state)?;
+        for (witness, value) in outputs.iter().zip(state) {
+            self.witness().insert(*witness, value);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn solve_multi_scalar_mul(
+        &mut self,
+        points: &[FunctionInput<GenericFieldElement<F>>],
+        scalars: &[FunctionInput<GenericFieldElement<F>>],
+        outputs: (Witness, Witness, Witness),
+    ) -> CoAcvmResult<()> {
+        let initial_witness = &self.witness_map[self.function_index];
+        let points = points
+            .iter()
+            .map(|input| Self::input_to_value(initial_witness, *input, false))
+            .collect::<CoAcvmResult<Vec<_>>>()?;
+        let scalars = scalars
+            .iter()
+            .map(|input| Self::input_to_value(initial_witness, *input, false))
+            .collect::<CoAcvmResult<Vec<_>>>()?;
+        let (x, y, is_infinite) = self.driver.multi_scalar_mul(&points, &scalars)?;
+        self.witness().insert(outputs.0, x);
+        self.witness().insert(outputs.1, y);
+        self.witness().insert(outputs.2, is_infinite);
+        Ok(())
+    }
+
+    pub(crate) fn solve_embedded_curve_add(
+        &mut self,
+        input1: &[FunctionInput<GenericFieldElement<F>>; 3],
+        input2: &[FunctionInput<GenericFieldElement<F>>; 3],
+        outputs: (Witness, Witness, Witness),
+    ) -> CoAcvmResult<()> {
+        let initial_witness = &self.witness_map[self.function_index];
+        let [x1, y1, inf1] =
+            input1.map(|input| Self::input_to_value(initial_witness, input, false));
+        let [x2, y2, inf2] =
+            input2.map(|input| Self::input_to_value(initial_witness, input, false));
+        let (x, y, is_infinite) = self
+            .driver
+            .embedded_curve_add((x1?, y1?, inf1?), (x2?, y2?, inf2?))?;
+        self.witness().insert(outputs.0, x);
+        self.witness().insert(outputs.1, y);
+        self.witness().insert(outputs.2, is_infinite);
+        Ok(())
+    }
+
+    pub(crate) fn solve_ecdsa(
+        &mut self,
+        public_key_x: &[FunctionInput<GenericFieldElement<F>>],
+        public_key_y: &[FunctionInput<GenericFieldElement<F>>],
+        signature: &[FunctionInput<GenericFieldElement<F>>],
+        hashed_message: &[FunctionInput<GenericFieldElement<F>>],
+        output: Witness,
+        secp256k1: bool,
+    ) -> CoAcvmResult<()> {
+        let initial_witness = &self.witness_map[self.function_index];
+        let [public_key_x, public_key_y, signature, hashed_message] =
+            [public_key_x, public_key_y, signature, hashed_message].map(|inputs| {
+                inputs
+                    .iter()
+                    .map(|input| Self::input_to_value(initial_witness, *input, false))
+                    .collect::<CoAcvmResult<Vec<_>>>()
+            });
+        let (public_key_x, public_key_y, signature, hashed_message) =
+            (public_key_x?, public_key_y?, signature?, hashed_message?);
+        let valid = if secp256k1 {
+            self.driver.ecdsa_secp256k1(
+                &public_key_x,
+                &public_key_y,
+                &signature,
+                &hashed_message,
+            )?
+        } else {
+            self.driver.ecdsa_secp256r1(
+                &public_key_x,
+                &public_key_y,
+                &signature,
+                &hashed_message,
+            )?
+        };
+        self.witness().insert(output, valid);
+        Ok(())
+    }
+
     pub(super) fn solve_blackbox(
         &mut self,
         bb_func: &BlackBoxFuncCall<GenericFieldElement<F>>,
@@ -129,6 +226,47 @@ where
 
         match bb_func {
             BlackBoxFuncCall::RANGE { input } => Self::solve_range_opcode(initial_witness, input)?,
+            BlackBoxFuncCall::Keccakf1600 { inputs, outputs } => {
+                self.solve_keccakf1600(inputs, outputs)?
+            }
+            BlackBoxFuncCall::MultiScalarMul {
+                points,
+                scalars,
+                outputs,
+            } => self.solve_multi_scalar_mul(points, scalars, *outputs)?,
+            BlackBoxFuncCall::EmbeddedCurveAdd {
+                input1,
+                input2,
+                outputs,
+            } => self.solve_embedded_curve_add(input1, input2, *outputs)?,
+            BlackBoxFuncCall::EcdsaSecp256k1 {
+                public_key_x,
+                public_key_y,
+                signature,
+                hashed_message,
+                output,
+            } => self.solve_ecdsa(
+                &public_key_x[..],
+                &public_key_y[..],
+                &signature[..],
+                &hashed_message[..],
+                *output,
+                true,
+            )?,
+            BlackBoxFuncCall::EcdsaSecp256r1 {
+                public_key_x,
+                public_key_y,
+                signature,
+                hashed_message,
+                output,
+            } => self.solve_ecdsa(
+                &public_key_x[..],
+                &public_key_y[..],
+                &signature[..],
+                &hashed_message[..],
+                *output,
+                false,
+            )?,
             _ => todo!("solve blackbox funciton {} not supported", bb_func.name()),
         }
 
@@ -37,6 +37,7 @@ use mpc_core::protocols::{
         ShamirPreprocessing, ShamirProtocol,
     },
 };
+use mpc_net::config::Phase;
 use sha3::Keccak256;
 use std::{
     array,
@@ -506,8 +507,10 @@ fn run_generate_witness(config: GenerateWitnessConfig) -> color_eyre::Result<Exi
         .to_owned()
         .try_into()
         .context("while converting network config")?;
-    let net = Rep3MpcNet::new(network_config).context("while connecting to network")?;
+    let mut net = Rep3MpcNet::new(network_config).context("while connecting to network")?;
     let id = usize::from(net.get_id());
+    net.start_phase(Phase::WitnessExtension)
+        .context("while starting witness extension")?;
 
     // init MPC protocol
     let rep3_vm = Rep3CoSolver::from_network_with_witness(net, compiled_program, input_share)
@@ -718,7 +721,8 @@ fn run_build_proving_key(config: BuildProvingKeyConfig) -> color_eyre::Result<Ex
             let witness_share = bincode::deserialize_from(witness_file)
                 .context("while deserializing witness share")?;
             // connect to network
-            let net = Rep3MpcNet::new(network_config)?;
+            let mut net = Rep3MpcNet::new(network_config)?;
+            net.start_phase(Phase::ProofAssembly)?;
             let id = net.get_id();
 
             // Create driver for circuit builder
@@ -761,7 +765,8 @@ fn run_build_proving_key(config: BuildProvingKeyConfig) -> color_eyre::Result<Ex
             let witness_share = bincode::deserialize_from(witness_file)
                 .context("while deserializing witness share")?;
             // connect to network
-            let net = ShamirMpcNet::new(network_config)?;
+            let mut net = ShamirMpcNet::new(network_config)?;
+            net.start_phase(Phase::ProofAssembly)?;
             let id = net.get_id();
 
             // Create driver for circuit builder
@@ -836,7 +841,8 @@ fn run_generate_proof(config: GenerateProofConfig) -> color_eyre::Result<ExitCod
             if t != 1 {
                 return Err(eyre!("REP3 only allows the threshold to be 1"));
             }
-            let net = Rep3MpcNet::new(network_config)?;
+            let mut net = Rep3MpcNet::new(network_config)?;
+            net.start_phase(Phase::ProofAssembly)?;
             let id = net.get_id();
 
             let mut io_context0 = IoContext::init(net)?;
@@ -876,7 +882,8 @@ fn run_generate_proof(config: GenerateProofConfig) -> color_eyre::Result<ExitCod
         }
         MPCProtocol::SHAMIR => {
             // connect to network
-            let net = ShamirMpcNet::new(network_config)?;
+            let mut net = ShamirMpcNet::new(network_config)?;
+            net.start_phase(Phase::ProofAssembly)?;
             let id = net.get_id();
 
             // Get the proving key and prover
@@ -1000,7 +1007,8 @@ fn run_build_and_generate_proof(
             let witness_share = bincode::deserialize_from(witness_file)
                 .context("while deserializing witness share")?;
             // connect to network
-            let net = Rep3MpcNet::new(network_config)?;
+            let mut net = Rep3MpcNet::new(network_config)?;
+            net.start_phase(Phase::ProofAssembly)?;
             let id = net.get_id();
 
             // Create driver for circuit builder
@@ -1074,7 +1082,8 @@ fn run_build_and_generate_proof(
             let witness_share = bincode::deserialize_from(witness_file)
                 .context("while deserializing witness share")?;
             // connect to network
-            let net = ShamirMpcNet::new(network_config)?;
+            let mut net = ShamirMpcNet::new(network_config)?;
+            net.start_phase(Phase::ProofAssembly)?;
             let id = net.get_id();
 
             // Create driver for circuit builder
@@ -19,7 +19,7 @@ use mpc_core::protocols::{
         self,
         network::{Rep3MpcNet, Rep3Network},
     },
-    shamir,
+    shamir::{self, network::ShamirNetwork, packed::ShamirPackedProtocol},
 };
 use mpc_net::config::NetworkConfigFile;
 use noirc_abi::Abi;
@@ -710,6 +710,71 @@ pub fn share_shamir<F: PrimeField, R: Rng + CryptoRng>(
     res
 }
 
+/// A witness shared with packed Shamir secret sharing. Public values are kept in place, while the shared values are packed into [`shamir::ShamirPackedShare`]s in the order in which they appear in the witness.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackedShamirWitness<F: PrimeField> {
+    /// The public values of the witness, where `None` marks a shared value
+    #[serde(
+        serialize_with = "mpc_core::ark_se",
+        deserialize_with = "mpc_core::ark_de"
+    )]
+    pub public: Vec<Option<F>>,
+    /// The packed shares of the shared values
+    #[serde(
+        serialize_with = "mpc_core::ark_se",
+        deserialize_with = "mpc_core::ark_de"
+    )]
+    pub shares: Vec<shamir::ShamirPackedShare<F>>,
+}
+
+pub fn share_shamir_packed<F: PrimeField, R: Rng + CryptoRng>(
+    witness: Vec<PubShared<F>>,
+    threshold: usize,
+    pack_size: usize,
+    num_parties: usize,
+    rng: &mut R,
+) -> Vec<PackedShamirWitness<F>> {
+    let mut public = Vec::with_capacity(witness.len());
+    let mut shared = Vec::new();
+    for witness in witness {
+        match witness {
+            PubShared::Public(f) => public.push(Some(f)),
+            PubShared::Shared(f) => {
+                public.push(None);
+                shared.push(f);
+            }
+        }
+    }
+    shamir::packed::share_field_elements(&shared, threshold, pack_size, num_parties, rng)
+        .into_iter()
+        .map(|shares| PackedShamirWitness {
+            public: public.clone(),
+            shares,
+        })
+        .collect()
+}
+
+pub fn unpack_shamir_witness<F: PrimeField, N: ShamirNetwork>(
+    witness: PackedShamirWitness<F>,
+    packed: &mut ShamirPackedProtocol<F, N>,
+) -> std::io::Result<Vec<ShamirAcvmType<F>>> {
+    let num_shared = witness.public.iter().filter(|p| p.is_none()).count();
+    let mut shares = shamir::packed::unpack_vec(&witness.shares, num_shared, packed)?.into_iter();
+    witness
+        .public
+        .into_iter()
+        .map(|p| match p {
+            Some(f) => Ok(ShamirAcvmType::from(f)),
+            None => shares.next().map(ShamirAcvmType::from).ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Not enough shares in packed witness",
+                )
+            }),
+        })
+        .collect()
+}
+
 pub fn share_input_rep3<P: Pairing, N: Rep3Network, R: Rng + CryptoRng>(
     initial_witness: BTreeMap<String, PublicMarker<GenericFieldElement<P::ScalarField>>>,
     rng: &mut R,
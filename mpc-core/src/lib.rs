@@ -5,6 +5,7 @@
 #![warn(missing_docs)]
 
 pub mod lut;
+pub mod ot;
 pub mod protocols;
 pub use protocols::serde_compat::{ark_de, ark_se};
 
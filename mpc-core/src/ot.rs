@@ -0,0 +1,404 @@
+//! Oblivious transfer
+//!
+//! This module contains oblivious transfers (OT) between two parties of a [`ShamirNetwork`], which are used as building block by the protocols which do not rely on correlated randomness from a dealer, e.g., the [2PC protocol](crate::protocols::twopc).
+//!
+//! The base OTs are computed with the protocol of [Chou and Orlandi](https://eprint.iacr.org/2015/267.pdf) on the secp256k1 curve. From the [`KAPPA`] base OTs, an arbitrary number of OTs is derived with the [IKNP](https://www.iacr.org/archive/crypto2003/27290145/27290145.pdf) OT extension, which only requires symmetric cryptography. The extended OTs are available as random OTs ([`OtExtension::random_ot`]) and correlated OTs ([`OtExtension::correlated_ot`], [`OtExtension::correlated_ot_bits`]).
+//!
+//! An [`OtExtension`] is bound to a single other party and runs the extension in both directions at once, such that both parties can send and receive OTs in the same round. The OTs are secure against semi-honest adversaries.
+
+use std::ops::{Add, Sub};
+
+use ark_ec::{CurveGroup, Group};
+use ark_ff::UniformRand;
+use ark_secp256k1::{Fr as BaseOtScalar, Projective as BaseOtPoint};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use itertools::izip;
+use rand::{CryptoRng, Rng, RngCore, SeedableRng};
+use rayon::prelude::*;
+use sha3::{Digest, Sha3_256, Sha3_512};
+
+use crate::{protocols::shamir::network::ShamirNetwork, RngType};
+
+type IoResult<T> = std::io::Result<T>;
+
+/// The computational security parameter, i.e., the number of base OTs per direction.
+pub const KAPPA: usize = 128;
+
+/// The output of the hash function, from which the messages of the extended OTs are derived.
+pub type Pad = [u8; 64];
+
+/// A key of a base OT.
+pub type BaseOtKey = [u8; 32];
+
+/// Computes base OTs with the protocol of Chou and Orlandi in both directions at once. We send num_send OTs, for which we get both keys, and receive choices.len() OTs, for which we get the key selected by the choice bit. The other party has to call this function with the swapped arguments.
+#[expect(clippy::type_complexity)]
+pub fn base_ots<N: ShamirNetwork, R: Rng + CryptoRng>(
+    network: &mut N,
+    other: usize,
+    num_send: usize,
+    choices: &[bool],
+    rng: &mut R,
+) -> IoResult<(Vec<(BaseOtKey, BaseOtKey)>, Vec<BaseOtKey>)> {
+    let generator = BaseOtPoint::generator();
+
+    // as sender, we send A = aG
+    let a = BaseOtScalar::rand(rng);
+    let a_point = generator * a;
+    network.send(other, a_point)?;
+    let other_a_point: BaseOtPoint = network.recv(other)?;
+
+    // as receiver, we send B = bG + c * A and get the key bA
+    let mut b_points = Vec::with_capacity(choices.len());
+    let mut received = Vec::with_capacity(choices.len());
+    for (i, c) in choices.iter().enumerate() {
+        let b = BaseOtScalar::rand(rng);
+        let mut b_point = generator * b;
+        if *c {
+            b_point += other_a_point;
+        }
+        b_points.push(b_point);
+        received.push(base_ot_key(i, other_a_point * b));
+    }
+    network.send_many(other, &b_points)?;
+    let other_b_points: Vec<BaseOtPoint> = network.recv_many(other)?;
+    if other_b_points.len() != num_send {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "During base OTs: Invalid number of elements received",
+        ));
+    }
+
+    // as sender, the keys are aB and a(B - A)
+    let sent = other_b_points
+        .into_iter()
+        .enumerate()
+        .map(|(i, b_point)| {
+            (
+                base_ot_key(i, b_point * a),
+                base_ot_key(i, (b_point - a_point) * a),
+            )
+        })
+        .collect();
+    Ok((sent, received))
+}
+
+/// The state of the IKNP OT extension with one other party for both directions. The OTs which we send are extended from the base OTs in which we were the receiver, and vice versa.
+pub struct OtExtension {
+    // the id of the other party
+    other: usize,
+    // the global correlation of the sender, i.e., the choice bits of the base OTs
+    s: u128,
+    // the PRGs seeded with the base OT keys selected by s
+    sender_prgs: Vec<RngType>,
+    // the PRGs seeded with both base OT keys
+    receiver_prgs: Vec<(RngType, RngType)>,
+    // the index of the next OT in each direction, which is used as tweak for the hash function
+    sender_counter: u64,
+    receiver_counter: u64,
+}
+
+/// The rows of the OT extension together with the index of the first OT.
+struct ExtendedOts {
+    index: u64,
+    rows: Vec<u128>,
+}
+
+impl OtExtension {
+    /// Computes the base OTs with the other party, which has to call this function at the same time.
+    pub fn setup<N: ShamirNetwork, R: Rng + CryptoRng>(
+        network: &mut N,
+        other: usize,
+        rng: &mut R,
+    ) -> IoResult<Self> {
+        if other == network.get_id() || other >= network.get_num_parties() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Invalid party id={other} for the OT extension"),
+            ));
+        }
+        let s: u128 = rng.gen();
+        let choices = (0..KAPPA).map(|i| (s >> i) & 1 == 1).collect::<Vec<_>>();
+        let (sent, received) = base_ots(network, other, KAPPA, &choices, rng)?;
+        let sender_prgs = received.into_iter().map(RngType::from_seed).collect();
+        let receiver_prgs = sent
+            .into_iter()
+            .map(|(k0, k1)| (RngType::from_seed(k0), RngType::from_seed(k1)))
+            .collect();
+
+        Ok(Self {
+            other,
+            s,
+            sender_prgs,
+            receiver_prgs,
+            sender_counter: 0,
+            receiver_counter: 0,
+        })
+    }
+
+    /// Returns the id of the other party.
+    pub fn other(&self) -> usize {
+        self.other
+    }
+
+    /// Extends the base OTs as receiver with the given choice bits. Returns the message for the sender and the rows t_j, such that the sender holds q_j = t_j ^ (c_j * s).
+    fn receive(&mut self, choices: &[bool]) -> (Vec<u8>, Vec<u128>) {
+        if choices.is_empty() {
+            return (Vec::new(), Vec::new());
+        }
+        let num_bytes = choices.len().div_ceil(8);
+        let packed = pack_bits(choices);
+        let mut msg = Vec::with_capacity(KAPPA * num_bytes);
+        let mut columns = Vec::with_capacity(KAPPA);
+        for (prg0, prg1) in self.receiver_prgs.iter_mut() {
+            let mut t = vec![0u8; num_bytes];
+            let mut u = vec![0u8; num_bytes];
+            prg0.fill_bytes(&mut t);
+            prg1.fill_bytes(&mut u);
+            for (u, t, c) in izip!(u.iter_mut(), &t, &packed) {
+                *u ^= t ^ c;
+            }
+            msg.extend(u);
+            columns.push(t);
+        }
+        (msg, transpose(&columns, choices.len()))
+    }
+
+    /// Extends the base OTs as sender with the message of the receiver. Returns the rows q_j.
+    fn send(&mut self, num: usize, msg: &[u8]) -> IoResult<Vec<u128>> {
+        let num_bytes = num.div_ceil(8);
+        if msg.len() != KAPPA * num_bytes {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "During OT extension: Invalid number of elements received",
+            ));
+        }
+        if num == 0 {
+            return Ok(Vec::new());
+        }
+        let mut columns = Vec::with_capacity(KAPPA);
+        for (i, (prg, u)) in self
+            .sender_prgs
+            .iter_mut()
+            .zip(msg.chunks_exact(num_bytes))
+            .enumerate()
+        {
+            let mut q = vec![0u8; num_bytes];
+            prg.fill_bytes(&mut q);
+            if (self.s >> i) & 1 == 1 {
+                for (q, u) in q.iter_mut().zip(u) {
+                    *q ^= u;
+                }
+            }
+            columns.push(q);
+        }
+        Ok(transpose(&columns, num))
+    }
+
+    /// Runs the OT extension in both directions. The other party has to receive num_send OTs and send choices.len() OTs.
+    fn extend<N: ShamirNetwork>(
+        &mut self,
+        network: &mut N,
+        num_send: usize,
+        choices: &[bool],
+    ) -> IoResult<(ExtendedOts, ExtendedOts)> {
+        let (msg, t_rows) = self.receive(choices);
+        network.send(self.other, msg)?;
+        let other_msg: Vec<u8> = network.recv(self.other)?;
+        let q_rows = self.send(num_send, &other_msg)?;
+
+        let sent = ExtendedOts {
+            index: self.sender_counter,
+            rows: q_rows,
+        };
+        let received = ExtendedOts {
+            index: self.receiver_counter,
+            rows: t_rows,
+        };
+        self.sender_counter += num_send as u64;
+        self.receiver_counter += choices.len() as u64;
+        Ok((sent, received))
+    }
+
+    /// Computes random OTs in both directions. As sender, we get two random pads for each of the num_send OTs, as receiver we get the pad selected by the choice bit. The other party has to call this function with choices of length num_send and num_send = choices.len().
+    ///
+    /// Returns the outputs of the OTs we sent and of the OTs we received.
+    #[expect(clippy::type_complexity)]
+    pub fn random_ot<N: ShamirNetwork>(
+        &mut self,
+        network: &mut N,
+        num_send: usize,
+        choices: &[bool],
+    ) -> IoResult<(Vec<(Pad, Pad)>, Vec<Pad>)> {
+        let (sent, received) = self.extend(network, num_send, choices)?;
+        let s = self.s;
+        let sender_output = sent
+            .rows
+            .into_par_iter()
+            .enumerate()
+            .map(|(j, q)| {
+                let index = sent.index + j as u64;
+                (hash(index, q), hash(index, q ^ s))
+            })
+            .collect();
+        let receiver_output = received
+            .rows
+            .into_par_iter()
+            .enumerate()
+            .map(|(j, t)| hash(received.index + j as u64, t))
+            .collect();
+        Ok((sender_output, receiver_output))
+    }
+
+    /// Computes correlated OTs in both directions. As sender, we input the correlations deltas, as receiver the choice bits, while the other party inputs the same number of choice bits, respectively correlations. For each OT, the sender gets a random x and the receiver gets x + c * delta, where c is the choice bit. The function from_pad derives a uniformly random element from a hash output.
+    ///
+    /// Returns the outputs of the OTs we sent and of the OTs we received.
+    pub fn correlated_ot<T, N, P>(
+        &mut self,
+        network: &mut N,
+        deltas: &[T],
+        choices: &[bool],
+        from_pad: P,
+    ) -> IoResult<(Vec<T>, Vec<T>)>
+    where
+        T: Copy
+            + Add<Output = T>
+            + Sub<Output = T>
+            + CanonicalSerialize
+            + CanonicalDeserialize
+            + Send
+            + Sync,
+        N: ShamirNetwork,
+        P: Fn(&Pad) -> T + Sync,
+    {
+        let (sent, received) = self.extend(network, deltas.len(), choices)?;
+        let s = self.s;
+
+        let (sender_output, corrections): (Vec<T>, Vec<T>) = (sent.rows, deltas)
+            .into_par_iter()
+            .enumerate()
+            .map(|(j, (q, delta))| {
+                let index = sent.index + j as u64;
+                let x0 = from_pad(&hash(index, q));
+                let x1 = from_pad(&hash(index, q ^ s));
+                (x0, x0 + *delta - x1)
+            })
+            .unzip();
+        network.send_many(self.other, &corrections)?;
+        let other_corrections: Vec<T> = network.recv_many(self.other)?;
+        if other_corrections.len() != choices.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "During correlated OT: Invalid number of elements received",
+            ));
+        }
+
+        let receiver_output = (received.rows, choices, other_corrections)
+            .into_par_iter()
+            .enumerate()
+            .map(|(j, (t, c, correction))| {
+                let x = from_pad(&hash(received.index + j as u64, t));
+                if *c {
+                    x + correction
+                } else {
+                    x
+                }
+            })
+            .collect();
+        Ok((sender_output, receiver_output))
+    }
+
+    /// Computes correlated OTs of bits in both directions. This is equivalent to [`correlated_ot`](Self::correlated_ot) where the bits are interpreted as elements of GF(2), but the corrections are packed before sending them.
+    pub fn correlated_ot_bits<N: ShamirNetwork>(
+        &mut self,
+        network: &mut N,
+        deltas: &[bool],
+        choices: &[bool],
+    ) -> IoResult<(Vec<bool>, Vec<bool>)> {
+        let (sent, received) = self.extend(network, deltas.len(), choices)?;
+        let s = self.s;
+
+        let (sender_output, corrections): (Vec<bool>, Vec<bool>) = (sent.rows, deltas)
+            .into_par_iter()
+            .enumerate()
+            .map(|(j, (q, delta))| {
+                let index = sent.index + j as u64;
+                let x0 = hash(index, q)[0] & 1 == 1;
+                let x1 = hash(index, q ^ s)[0] & 1 == 1;
+                (x0, x0 ^ delta ^ x1)
+            })
+            .unzip();
+        network.send(self.other, pack_bits(&corrections))?;
+        let other_corrections: Vec<u8> = network.recv(self.other)?;
+        if other_corrections.len() != choices.len().div_ceil(8) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "During correlated OT: Invalid number of elements received",
+            ));
+        }
+        let other_corrections = unpack_bits(&other_corrections, choices.len());
+
+        let receiver_output = (received.rows, choices, other_corrections)
+            .into_par_iter()
+            .enumerate()
+            .map(|(j, (t, c, correction))| {
+                let x = hash(received.index + j as u64, t)[0] & 1 == 1;
+                x ^ (c & correction)
+            })
+            .collect();
+        Ok((sender_output, receiver_output))
+    }
+}
+
+/// Packs the bits into bytes, starting with the least significant bit.
+pub fn pack_bits(bits: &[bool]) -> Vec<u8> {
+    let mut packed = vec![0u8; bits.len().div_ceil(8)];
+    for (i, bit) in bits.iter().enumerate() {
+        packed[i / 8] |= u8::from(*bit) << (i % 8);
+    }
+    packed
+}
+
+/// Unpacks the first num bits of the bytes, starting with the least significant bit.
+pub fn unpack_bits(packed: &[u8], num: usize) -> Vec<bool> {
+    (0..num)
+        .map(|i| (packed[i / 8] >> (i % 8)) & 1 == 1)
+        .collect()
+}
+
+/// Transposes the KAPPA columns of num bits each into num rows of KAPPA bits.
+fn transpose(columns: &[Vec<u8>], num: usize) -> Vec<u128> {
+    debug_assert_eq!(columns.len(), KAPPA);
+    (0..num)
+        .into_par_iter()
+        .map(|j| {
+            columns.iter().enumerate().fold(0u128, |row, (i, column)| {
+                row | (u128::from((column[j / 8] >> (j % 8)) & 1) << i)
+            })
+        })
+        .collect()
+}
+
+/// Hashes a row of the OT extension, where the index of the OT is used as tweak.
+fn hash(index: u64, row: u128) -> Pad {
+    let mut hasher = Sha3_512::new();
+    hasher.update(index.to_le_bytes());
+    hasher.update(row.to_le_bytes());
+    let mut pad = [0u8; 64];
+    pad.copy_from_slice(&hasher.finalize());
+    pad
+}
+
+/// Derives the key of the i-th base OT from the shared point.
+fn base_ot_key(index: usize, point: BaseOtPoint) -> BaseOtKey {
+    let mut bytes = Vec::new();
+    point
+        .into_affine()
+        .serialize_compressed(&mut bytes)
+        .expect("can serialize point");
+    let mut hasher = Sha3_256::new();
+    hasher.update((index as u64).to_le_bytes());
+    hasher.update(bytes);
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&hasher.finalize());
+    key
+}
@@ -1,9 +1,16 @@
 //! # MPC Protocols
 //!
-//! Contains the implementations of the different MPC protocols. Currently, semi-honest 3-party replicated sharing (REP3) and semi-honest n-party Shamir secret sharing are implemented.
+//! Contains the implementations of the different MPC protocols. Currently, semi-honest 3-party replicated sharing (REP3) and semi-honest n-party Shamir secret sharing are implemented. Additionally, the SPDZ protocol provides security against an active adversary corrupting all but one of n parties, a semi-honest 2-party protocol (2PC) based on oblivious transfer supports setups with exactly two parties, and semi-honest replicated sharing (RSS) generalizes REP3 to a small number of n > 3 parties.
 
 pub mod bridges;
+pub(crate) mod broadcast;
+pub mod cost;
+pub mod faults;
+pub mod provisioning;
 pub mod rep3;
 pub mod rep3_ring;
+pub mod rss;
 pub(crate) mod serde_compat;
 pub mod shamir;
+pub mod spdz;
+pub mod twopc;
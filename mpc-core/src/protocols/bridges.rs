@@ -1,6 +1,9 @@
 //! # MPC Bridges
 //!
-//! This module implements bridges between multiple MPC protocols. Currently, one can switch from Rep3 to a 3-party Shamir secret sharing protocol.
+//! This module implements bridges between multiple MPC protocols. Currently, one can switch from Rep3 to a 3-party Shamir secret sharing protocol, and translate 3-party Shamir shares into additive shares.
 
 pub mod network;
 mod rep3_to_shamir;
+mod shamir_to_rep3;
+
+pub use shamir_to_rep3::translate_shamir_to_addshare_vec;
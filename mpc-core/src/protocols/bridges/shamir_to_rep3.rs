@@ -0,0 +1,19 @@
+use crate::protocols::{
+    rep3::id::PartyID,
+    shamir::{core, ShamirPrimeFieldShare},
+};
+use ark_ff::PrimeField;
+
+/// Translate a 3-party Shamir prime field share vector into a 3-party additive prime field share vector, where the underlying sharing polynomial is of degree at most 2. This is a local operation, the resulting additive shares can be reshared to obtain Rep3 shares.
+pub fn translate_shamir_to_addshare_vec<F: PrimeField>(
+    input: Vec<ShamirPrimeFieldShare<F>>,
+    id: PartyID,
+) -> Vec<F> {
+    // party i holds the evaluation at i + 1
+    let lagrange = core::lagrange_from_coeff::<F>(&[1, 2, 3]);
+    let my_lagrange_coeff = lagrange[usize::from(id)];
+    input
+        .into_iter()
+        .map(|share| share.inner() * my_lagrange_coeff)
+        .collect()
+}
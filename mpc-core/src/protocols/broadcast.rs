@@ -0,0 +1,23 @@
+//! Helpers for the consistent broadcasts of the network traits (see [`Rep3Network::send_broadcast_many`](super::rep3::network::Rep3Network::send_broadcast_many) and [`ShamirNetwork::send_broadcast_many`](super::shamir::network::ShamirNetwork::send_broadcast_many)).
+
+use ark_serialize::CanonicalSerialize;
+use sha3::{Digest, Sha3_256};
+
+/// Hashes the broadcast data together with the id of its sender. All parties compare their digests, such that a corrupted sender cannot send different data to different parties.
+pub(crate) fn digest<F: CanonicalSerialize>(sender: usize, data: &[F]) -> std::io::Result<Vec<u8>> {
+    let mut ser_data = Vec::with_capacity(data.serialized_size(ark_serialize::Compress::No));
+    data.serialize_uncompressed(&mut ser_data)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let mut hasher = Sha3_256::new();
+    hasher.update((sender as u64).to_le_bytes());
+    hasher.update(ser_data);
+    Ok(hasher.finalize().to_vec())
+}
+
+/// The error if the digests of the parties do not match.
+pub(crate) fn inconsistent(sender: usize) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("Party {} broadcast inconsistent data", sender),
+    )
+}
@@ -0,0 +1,794 @@
+//! # Cost Estimation
+//!
+//! This module allows to estimate the communication cost of an MPC program before running it on an actual deployment. The [`Rep3CountingNetwork`] and [`ShamirCountingNetwork`] wrap any network of the respective protocol and tally the communication rounds, messages, elements, and bytes per phase in a [`CostTracker`]. Together with the in-process [`Rep3LocalNetwork`] and [`ShamirLocalNetwork`], a witness extension or proving job can be executed in a dry run on a single machine without any network infrastructure. The links between the local parties can be shaped with a [`LinkConfig`], which adds latency, jitter, a bandwidth cap, and dropped messages, such that the running time of a job over, e.g., a WAN can be measured as well.
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress};
+use mpc_net::{
+    abort::{Abort, AbortReason},
+    config::Phase,
+    stats::NetworkStats,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    rep3::{id::PartyID, network::Rep3Network},
+    shamir::network::ShamirNetwork,
+};
+
+/// The name of the phase which is tracked if no phase was set.
+pub const DEFAULT_PHASE: &str = "default";
+
+/// The communication cost of a single party in a phase.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostStats {
+    /// The number of communication rounds. Rounds of forks running in parallel are counted separately, so this is an upper bound.
+    pub rounds: usize,
+    /// The number of sent messages
+    pub messages_sent: usize,
+    /// The number of sent elements, e.g., field elements. For rep3, every multiplication of two shared values results in one element sent to the next party.
+    pub elements_sent: usize,
+    /// The number of sent bytes, including the length prefixes of the messages
+    pub bytes_sent: usize,
+    /// The number of received messages
+    pub messages_received: usize,
+    /// The number of received elements
+    pub elements_received: usize,
+}
+
+impl std::ops::AddAssign for CostStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.rounds += rhs.rounds;
+        self.messages_sent += rhs.messages_sent;
+        self.elements_sent += rhs.elements_sent;
+        self.bytes_sent += rhs.bytes_sent;
+        self.messages_received += rhs.messages_received;
+        self.elements_received += rhs.elements_received;
+    }
+}
+
+#[derive(Debug)]
+struct CostTrackerInner {
+    phase: String,
+    stats: BTreeMap<String, CostStats>,
+}
+
+/// Tallies the [`CostStats`] of a party per phase. The tracker can be cloned and is shared by all forks of a counting network.
+#[derive(Debug, Clone)]
+pub struct CostTracker {
+    inner: Arc<Mutex<CostTrackerInner>>,
+}
+
+impl Default for CostTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CostTracker {
+    /// Creates a new tracker in the [`DEFAULT_PHASE`].
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(CostTrackerInner {
+                phase: DEFAULT_PHASE.to_owned(),
+                stats: BTreeMap::new(),
+            })),
+        }
+    }
+
+    /// Sets the phase to which all following communication is attributed.
+    pub fn set_phase(&self, phase: impl Into<String>) {
+        self.lock().phase = phase.into();
+    }
+
+    /// Returns the current phase.
+    pub fn phase(&self) -> String {
+        self.lock().phase.clone()
+    }
+
+    /// Returns the tallied costs per phase.
+    pub fn report(&self) -> BTreeMap<String, CostStats> {
+        self.lock().stats.clone()
+    }
+
+    /// Returns the tallied costs of all phases.
+    pub fn total(&self) -> CostStats {
+        self.lock()
+            .stats
+            .values()
+            .fold(CostStats::default(), |mut acc, stats| {
+                acc += *stats;
+                acc
+            })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CostTrackerInner> {
+        // the tracker only contains counters, so it is fine to continue after a panic of another thread
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn record(&self, stats: CostStats) {
+        let mut inner = self.lock();
+        let phase = inner.phase.clone();
+        *inner.stats.entry(phase).or_default() += stats;
+    }
+}
+
+/// Keeps track of the rounds of a single network instance: A round is counted whenever a party starts receiving after it has sent data, or when it performs a combined send/receive operation.
+#[derive(Debug, Default)]
+struct RoundCounter {
+    receiving: bool,
+}
+
+impl RoundCounter {
+    fn send(&mut self) -> usize {
+        self.receiving = false;
+        0
+    }
+
+    fn recv(&mut self) -> usize {
+        let new_round = !self.receiving;
+        self.receiving = true;
+        usize::from(new_round)
+    }
+
+    fn send_and_recv(&mut self) -> usize {
+        self.receiving = false;
+        1
+    }
+}
+
+fn sent_stats<F: CanonicalSerialize>(data: &[F], messages: usize) -> CostStats {
+    CostStats {
+        messages_sent: messages,
+        elements_sent: data.len() * messages,
+        bytes_sent: data.serialized_size(Compress::No) * messages,
+        ..Default::default()
+    }
+}
+
+/// A [`Rep3Network`] which tallies the communication of the wrapped network in a [`CostTracker`].
+#[derive(Debug)]
+pub struct Rep3CountingNetwork<N: Rep3Network> {
+    inner: N,
+    tracker: CostTracker,
+    rounds: RoundCounter,
+}
+
+impl<N: Rep3Network> Rep3CountingNetwork<N> {
+    /// Wraps the network, tallying its communication in the given tracker.
+    pub fn new(inner: N, tracker: CostTracker) -> Self {
+        Self {
+            inner,
+            tracker,
+            rounds: RoundCounter::default(),
+        }
+    }
+
+    /// Returns the tracker of this network.
+    pub fn tracker(&self) -> &CostTracker {
+        &self.tracker
+    }
+
+    /// Returns the wrapped network.
+    pub fn into_inner(self) -> N {
+        self.inner
+    }
+}
+
+impl<N: Rep3Network> Rep3Network for Rep3CountingNetwork<N> {
+    fn get_id(&self) -> PartyID {
+        self.inner.get_id()
+    }
+
+    fn reshare_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<Vec<F>> {
+        let res = self.inner.reshare_many(data)?;
+        let mut stats = sent_stats(data, 1);
+        stats.rounds = self.rounds.send_and_recv();
+        stats.messages_received = 1;
+        stats.elements_received = res.len();
+        self.tracker.record(stats);
+        Ok(res)
+    }
+
+    fn broadcast_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<(Vec<F>, Vec<F>)> {
+        let (prev, next) = self.inner.broadcast_many(data)?;
+        let mut stats = sent_stats(data, 2);
+        stats.rounds = self.rounds.send_and_recv();
+        stats.messages_received = 2;
+        stats.elements_received = prev.len() + next.len();
+        self.tracker.record(stats);
+        Ok((prev, next))
+    }
+
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: PartyID,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        self.inner.send_many(target, data)?;
+        let mut stats = sent_stats(data, 1);
+        stats.rounds = self.rounds.send();
+        self.tracker.record(stats);
+        Ok(())
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(&mut self, from: PartyID) -> std::io::Result<Vec<F>> {
+        let res = self.inner.recv_many(from)?;
+        self.tracker.record(CostStats {
+            rounds: self.rounds.recv(),
+            messages_received: 1,
+            elements_received: res.len(),
+            ..Default::default()
+        });
+        Ok(res)
+    }
+
+    fn fork(&mut self) -> std::io::Result<Self> {
+        Ok(Self::new(self.inner.fork()?, self.tracker.clone()))
+    }
+
+    fn start_phase(&mut self, phase: Phase) -> std::io::Result<()> {
+        self.inner.start_phase(phase)
+    }
+
+    fn end_phase(&mut self) {
+        self.inner.end_phase()
+    }
+
+    fn network_stats(&self) -> NetworkStats {
+        self.inner.network_stats()
+    }
+
+    fn abort(&mut self, reason: AbortReason) {
+        self.inner.abort(reason)
+    }
+}
+
+/// A [`ShamirNetwork`] which tallies the communication of the wrapped network in a [`CostTracker`].
+#[derive(Debug)]
+pub struct ShamirCountingNetwork<N: ShamirNetwork> {
+    inner: N,
+    tracker: CostTracker,
+    rounds: RoundCounter,
+}
+
+impl<N: ShamirNetwork> ShamirCountingNetwork<N> {
+    /// Wraps the network, tallying its communication in the given tracker.
+    pub fn new(inner: N, tracker: CostTracker) -> Self {
+        Self {
+            inner,
+            tracker,
+            rounds: RoundCounter::default(),
+        }
+    }
+
+    /// Returns the tracker of this network.
+    pub fn tracker(&self) -> &CostTracker {
+        &self.tracker
+    }
+
+    /// Returns the wrapped network.
+    pub fn into_inner(self) -> N {
+        self.inner
+    }
+}
+
+impl<N: ShamirNetwork> ShamirNetwork for ShamirCountingNetwork<N> {
+    fn get_id(&self) -> usize {
+        self.inner.get_id()
+    }
+
+    fn get_num_parties(&self) -> usize {
+        self.inner.get_num_parties()
+    }
+
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: usize,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        self.inner.send_many(target, data)?;
+        let mut stats = sent_stats(data, 1);
+        stats.rounds = self.rounds.send();
+        self.tracker.record(stats);
+        Ok(())
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(&mut self, from: usize) -> std::io::Result<Vec<F>> {
+        let res = self.inner.recv_many(from)?;
+        self.tracker.record(CostStats {
+            rounds: self.rounds.recv(),
+            messages_received: 1,
+            elements_received: res.len(),
+            ..Default::default()
+        });
+        Ok(res)
+    }
+
+    fn broadcast<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+    ) -> std::io::Result<Vec<F>> {
+        let others = self.get_num_parties() - 1;
+        let mut stats = sent_stats(std::slice::from_ref(&data), others);
+        let res = self.inner.broadcast(data)?;
+        stats.rounds = self.rounds.send_and_recv();
+        stats.messages_received = others;
+        stats.elements_received = others;
+        self.tracker.record(stats);
+        Ok(res)
+    }
+
+    fn broadcast_next<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+        num: usize,
+    ) -> std::io::Result<Vec<F>> {
+        let others = num.saturating_sub(1);
+        let mut stats = sent_stats(std::slice::from_ref(&data), others);
+        let res = self.inner.broadcast_next(data, num)?;
+        stats.rounds = self.rounds.send_and_recv();
+        stats.messages_received = others;
+        stats.elements_received = others;
+        self.tracker.record(stats);
+        Ok(res)
+    }
+
+    fn send_and_recv_each_many<
+        F: CanonicalSerialize + CanonicalDeserialize + Clone + Send + 'static,
+    >(
+        &mut self,
+        data: Vec<Vec<F>>,
+    ) -> std::io::Result<Vec<Vec<F>>> {
+        let id = self.get_id();
+        let mut stats = CostStats::default();
+        for (_, data) in data.iter().enumerate().filter(|(i, _)| *i != id) {
+            stats += sent_stats(data, 1);
+        }
+        let res = self.inner.send_and_recv_each_many(data)?;
+        stats.rounds = self.rounds.send_and_recv();
+        for (_, res) in res.iter().enumerate().filter(|(i, _)| *i != id) {
+            stats.messages_received += 1;
+            stats.elements_received += res.len();
+        }
+        self.tracker.record(stats);
+        Ok(res)
+    }
+
+    fn fork(&mut self) -> std::io::Result<Self> {
+        Ok(Self::new(self.inner.fork()?, self.tracker.clone()))
+    }
+
+    fn start_phase(&mut self, phase: Phase) -> std::io::Result<()> {
+        self.inner.start_phase(phase)
+    }
+
+    fn end_phase(&mut self) {
+        self.inner.end_phase()
+    }
+
+    fn network_stats(&self) -> NetworkStats {
+        self.inner.network_stats()
+    }
+
+    fn abort(&mut self, reason: AbortReason) {
+        self.inner.abort(reason)
+    }
+}
+
+/// The characteristics of the simulated links between the parties of a [`Rep3LocalNetwork`] or [`ShamirLocalNetwork`]. The default is an ideal link, which delivers all messages immediately.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LinkConfig {
+    /// The one-way delay of every message.
+    pub latency: Duration,
+    /// The maximum additional delay of a message, which is drawn uniformly at random. Messages are still delivered in order.
+    pub jitter: Duration,
+    /// The bandwidth of every directed link in bytes per second, or `None` for an unlimited bandwidth. Forks of a network share the bandwidth of their links.
+    pub bandwidth: Option<u64>,
+    /// The probability in `[0, 1)` that a message is dropped. As the real transports are reliable, a dropped message is not lost, but retransmitted after a round trip, i.e., twice the latency.
+    pub drop_rate: f64,
+}
+
+impl LinkConfig {
+    /// Creates a link with the given one-way latency and bandwidth in bytes per second, e.g., to simulate a WAN.
+    pub fn new(latency: Duration, bandwidth: Option<u64>) -> Self {
+        Self {
+            latency,
+            bandwidth,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the jitter of the link.
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the probability that a message is dropped and has to be retransmitted.
+    pub fn with_drop_rate(mut self, drop_rate: f64) -> Self {
+        self.drop_rate = drop_rate;
+        self
+    }
+}
+
+/// The state of a directed link, which is shared by all forks of a network.
+#[derive(Debug)]
+struct LinkState {
+    config: LinkConfig,
+    // the time at which the previous message is completely transmitted
+    free_at: Instant,
+    // the time at which the previous message is delivered
+    delivered_at: Instant,
+}
+
+impl LinkState {
+    /// Returns the time at which a message of the given length sent now is delivered.
+    fn schedule(&mut self, len: usize) -> Instant {
+        let mut rng = rand::thread_rng();
+        let transmission = self.config.bandwidth.map_or(Duration::ZERO, |bandwidth| {
+            Duration::from_secs_f64(len as f64 / bandwidth as f64)
+        });
+        self.free_at = self.free_at.max(Instant::now()) + transmission;
+        let mut delay = self.config.latency + self.config.jitter.mul_f64(rng.gen());
+        while self.config.drop_rate > 0.0 && rng.gen_bool(self.config.drop_rate) {
+            delay += self.config.latency * 2;
+        }
+        self.delivered_at = self.delivered_at.max(self.free_at + delay);
+        self.delivered_at
+    }
+}
+
+#[derive(Debug)]
+enum LocalMsg {
+    Data(Vec<u8>, Option<Instant>),
+    Fork(Receiver<LocalMsg>),
+    Abort(AbortReason),
+}
+
+/// The channels of a party to all other parties, indexed by the id of the other party.
+#[derive(Debug)]
+struct LocalChannels {
+    senders: Vec<Option<Sender<LocalMsg>>>,
+    receivers: Vec<Option<Receiver<LocalMsg>>>,
+    // the outgoing links, if they are shaped
+    links: Vec<Option<Arc<Mutex<LinkState>>>>,
+}
+
+impl LocalChannels {
+    fn new_parties(num_parties: usize, link: LinkConfig) -> Vec<Self> {
+        assert!(
+            (0.0..1.0).contains(&link.drop_rate),
+            "the drop rate must be in [0, 1)"
+        );
+        let mut parties = (0..num_parties)
+            .map(|_| Self {
+                senders: (0..num_parties).map(|_| None).collect(),
+                receivers: (0..num_parties).map(|_| None).collect(),
+                links: (0..num_parties).map(|_| None).collect(),
+            })
+            .collect::<Vec<_>>();
+        for from in 0..num_parties {
+            for to in (0..num_parties).filter(|to| *to != from) {
+                let (sender, receiver) = mpsc::channel();
+                parties[from].senders[to] = Some(sender);
+                parties[to].receivers[from] = Some(receiver);
+                if link != LinkConfig::default() {
+                    let now = Instant::now();
+                    parties[from].links[to] = Some(Arc::new(Mutex::new(LinkState {
+                        config: link,
+                        free_at: now,
+                        delivered_at: now,
+                    })));
+                }
+            }
+        }
+        parties
+    }
+
+    fn send_many<F: CanonicalSerialize>(&self, target: usize, data: &[F]) -> std::io::Result<()> {
+        let mut bytes = Vec::with_capacity(data.serialized_size(Compress::No));
+        data.serialize_uncompressed(&mut bytes)
+            .map_err(std::io::Error::other)?;
+        let delivered_at = self
+            .links
+            .get(target)
+            .and_then(Option::as_ref)
+            .map(|link| link.lock().expect("not poisoned").schedule(bytes.len()));
+        self.send(target, LocalMsg::Data(bytes, delivered_at))
+    }
+
+    fn send(&self, target: usize, msg: LocalMsg) -> std::io::Result<()> {
+        self.senders
+            .get(target)
+            .and_then(Option::as_ref)
+            .ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid target party")
+            })?
+            .send(msg)
+            .map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::BrokenPipe, "other party disconnected")
+            })
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(&self, from: usize) -> std::io::Result<Vec<F>> {
+        match self.recv(from)? {
+            LocalMsg::Data(bytes, delivered_at) => {
+                if let Some(delay) =
+                    delivered_at.and_then(|at| at.checked_duration_since(Instant::now()))
+                {
+                    std::thread::sleep(delay);
+                }
+                Vec::<F>::deserialize_uncompressed(bytes.as_slice())
+                    .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+            }
+            LocalMsg::Fork(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "expected data, got a fork",
+            )),
+            LocalMsg::Abort(reason) => Err(Abort {
+                party: from,
+                reason,
+            }
+            .into()),
+        }
+    }
+
+    /// Sends the abort to all other parties. Only the receives of the other parties over these channels fail, not the ones over forks.
+    fn abort(&self, reason: AbortReason) {
+        for target in 0..self.senders.len() {
+            // the other party may already be gone
+            let _ = self.send(target, LocalMsg::Abort(reason.clone()));
+        }
+    }
+
+    fn recv(&self, from: usize) -> std::io::Result<LocalMsg> {
+        self.receivers
+            .get(from)
+            .and_then(Option::as_ref)
+            .ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid source party")
+            })?
+            .recv()
+            .map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "other party disconnected",
+                )
+            })
+    }
+
+    fn fork(&self) -> std::io::Result<Self> {
+        let num_parties = self.senders.len();
+        let mut senders = (0..num_parties).map(|_| None).collect::<Vec<_>>();
+        let mut receivers = (0..num_parties).map(|_| None).collect::<Vec<_>>();
+        for (to, sender) in senders.iter_mut().enumerate() {
+            if self.senders[to].is_some() {
+                let (new_sender, new_receiver) = mpsc::channel();
+                self.send(to, LocalMsg::Fork(new_receiver))?;
+                *sender = Some(new_sender);
+            }
+        }
+        for (from, receiver) in receivers.iter_mut().enumerate() {
+            if self.receivers[from].is_some() {
+                match self.recv(from)? {
+                    LocalMsg::Fork(new_receiver) => *receiver = Some(new_receiver),
+                    LocalMsg::Data(..) => {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "expected a fork, got data",
+                        ))
+                    }
+                    LocalMsg::Abort(reason) => {
+                        return Err(Abort {
+                            party: from,
+                            reason,
+                        }
+                        .into())
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            senders,
+            receivers,
+            links: self.links.clone(),
+        })
+    }
+}
+
+/// An in-process [`Rep3Network`], which connects three parties running in different threads of the same process via channels.
+#[derive(Debug)]
+pub struct Rep3LocalNetwork {
+    id: PartyID,
+    channels: LocalChannels,
+}
+
+impl Rep3LocalNetwork {
+    /// Creates the connected networks of the three parties, ordered by their ids.
+    pub fn new_parties() -> [Self; 3] {
+        Self::with_link(LinkConfig::default())
+    }
+
+    /// Creates the connected networks of the three parties, ordered by their ids, where all links between the parties are shaped according to the [`LinkConfig`].
+    pub fn with_link(link: LinkConfig) -> [Self; 3] {
+        let mut channels = LocalChannels::new_parties(3, link).into_iter();
+        [PartyID::ID0, PartyID::ID1, PartyID::ID2].map(|id| Self {
+            id,
+            channels: channels.next().expect("three parties"),
+        })
+    }
+}
+
+impl Rep3Network for Rep3LocalNetwork {
+    fn get_id(&self) -> PartyID {
+        self.id
+    }
+
+    fn reshare_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<Vec<F>> {
+        self.send_next_many(data)?;
+        self.recv_prev_many()
+    }
+
+    fn broadcast_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<(Vec<F>, Vec<F>)> {
+        self.send_many(self.id.next_id(), data)?;
+        self.send_many(self.id.prev_id(), data)?;
+        let prev = self.recv_many(self.id.prev_id())?;
+        let next = self.recv_many(self.id.next_id())?;
+        Ok((prev, next))
+    }
+
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: PartyID,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        self.channels.send_many(target.into(), data)
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(&mut self, from: PartyID) -> std::io::Result<Vec<F>> {
+        self.channels.recv_many(from.into())
+    }
+
+    fn fork(&mut self) -> std::io::Result<Self> {
+        Ok(Self {
+            id: self.id,
+            channels: self.channels.fork()?,
+        })
+    }
+
+    fn abort(&mut self, reason: AbortReason) {
+        self.channels.abort(reason)
+    }
+}
+
+/// An in-process [`ShamirNetwork`], which connects parties running in different threads of the same process via channels.
+#[derive(Debug)]
+pub struct ShamirLocalNetwork {
+    id: usize,
+    channels: LocalChannels,
+}
+
+impl ShamirLocalNetwork {
+    /// Creates the connected networks of `num_parties` parties, ordered by their ids.
+    pub fn new_parties(num_parties: usize) -> Vec<Self> {
+        Self::with_link(num_parties, LinkConfig::default())
+    }
+
+    /// Creates the connected networks of `num_parties` parties, ordered by their ids, where all links between the parties are shaped according to the [`LinkConfig`].
+    pub fn with_link(num_parties: usize, link: LinkConfig) -> Vec<Self> {
+        LocalChannels::new_parties(num_parties, link)
+            .into_iter()
+            .enumerate()
+            .map(|(id, channels)| Self { id, channels })
+            .collect()
+    }
+}
+
+impl ShamirNetwork for ShamirLocalNetwork {
+    fn get_id(&self) -> usize {
+        self.id
+    }
+
+    fn get_num_parties(&self) -> usize {
+        self.channels.senders.len()
+    }
+
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: usize,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        self.channels.send_many(target, data)
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(&mut self, from: usize) -> std::io::Result<Vec<F>> {
+        self.channels.recv_many(from)
+    }
+
+    fn broadcast<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+    ) -> std::io::Result<Vec<F>> {
+        let num_parties = self.get_num_parties();
+        self.broadcast_next(data, num_parties).map(|mut res| {
+            // broadcast_next orders the result relative to our id
+            res.reverse();
+            res.rotate_right(self.id + 1);
+            res
+        })
+    }
+
+    fn broadcast_next<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+        num: usize,
+    ) -> std::io::Result<Vec<F>> {
+        let num_parties = self.get_num_parties();
+        for i in 1..num {
+            self.send((self.id + i) % num_parties, data.to_owned())?;
+        }
+        let mut res = Vec::with_capacity(num);
+        res.push(data);
+        for i in 1..num {
+            res.push(self.recv((self.id + num_parties - i) % num_parties)?);
+        }
+        Ok(res)
+    }
+
+    fn send_and_recv_each_many<
+        F: CanonicalSerialize + CanonicalDeserialize + Clone + Send + 'static,
+    >(
+        &mut self,
+        data: Vec<Vec<F>>,
+    ) -> std::io::Result<Vec<Vec<F>>> {
+        debug_assert_eq!(data.len(), self.get_num_parties());
+        for (target, data) in data.iter().enumerate() {
+            if target != self.id {
+                self.send_many(target, data)?;
+            }
+        }
+        let mut res = Vec::with_capacity(data.len());
+        for (from, data) in data.into_iter().enumerate() {
+            if from == self.id {
+                res.push(data);
+            } else {
+                res.push(self.recv_many(from)?);
+            }
+        }
+        Ok(res)
+    }
+
+    fn fork(&mut self) -> std::io::Result<Self> {
+        Ok(Self {
+            id: self.id,
+            channels: self.channels.fork()?,
+        })
+    }
+
+    fn abort(&mut self, reason: AbortReason) {
+        self.channels.abort(reason)
+    }
+}
@@ -0,0 +1,372 @@
+//! # Fault Injection
+//!
+//! This module allows to exercise the error handling of MPC protocols in integration tests. The [`Rep3FaultyNetwork`] and [`ShamirFaultyNetwork`] wrap any network of the respective protocol and tamper with the received messages according to a deterministic [`FaultSchedule`], e.g., to check that a corrupted garbled circuit is detected by the consistency checks, or that a truncated message results in an error instead of a panic.
+//!
+//! The wrappers send every message as raw bytes, such that a message can be tampered with regardless of its type. Hence, all parties have to use a faulty network, the ones which should receive all messages unmodified with an empty schedule.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress};
+use mpc_net::{abort::AbortReason, config::Phase, stats::NetworkStats};
+
+use super::{
+    rep3::{id::PartyID, network::Rep3Network},
+    shamir::network::ShamirNetwork,
+};
+
+/// A fault which is injected into a received message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// XORs the mask into the byte at the offset of the serialized message, wrapping around at the end of the message. Note that a serialized vector starts with its length as 8 bytes.
+    Corrupt {
+        /// The offset of the byte
+        offset: usize,
+        /// The mask which is applied to the byte
+        mask: u8,
+    },
+    /// Truncates the serialized message to the given length.
+    Truncate {
+        /// The remaining length of the message
+        len: usize,
+    },
+    /// Drops the message, such that receiving it fails with [`std::io::ErrorKind::TimedOut`], as a real network with a round trip timeout would.
+    Drop,
+    /// Swaps the message with the next message from the same party.
+    Reorder,
+}
+
+/// The faults to inject into the messages received by a party. The faults are identified by the id of the sending party and the index of the message among all messages received from that party, which includes the messages received by forks of the network. Hence, the schedule is only deterministic if the forks do not receive from the same party concurrently.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FaultSchedule {
+    faults: BTreeMap<(usize, usize), Fault>,
+}
+
+impl FaultSchedule {
+    /// Creates an empty schedule, which injects no faults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Injects the fault into the message with the given index received from the given party.
+    pub fn with_fault(mut self, from: usize, index: usize, fault: Fault) -> Self {
+        self.faults.insert((from, index), fault);
+        self
+    }
+}
+
+#[derive(Debug)]
+struct FaultState {
+    schedule: FaultSchedule,
+    // the number of messages received from each party
+    received: BTreeMap<usize, usize>,
+}
+
+/// Applies a [`FaultSchedule`] to the messages received over the wrapped network. The state is shared by all forks, but every fork has its own queue of received messages.
+#[derive(Debug)]
+struct FaultInjector {
+    state: Arc<Mutex<FaultState>>,
+    // the messages received from each party, which were not returned yet, or `None` if they were dropped
+    queues: BTreeMap<usize, VecDeque<Option<Vec<u8>>>>,
+}
+
+impl FaultInjector {
+    fn new(schedule: FaultSchedule) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(FaultState {
+                schedule,
+                received: BTreeMap::new(),
+            })),
+            queues: BTreeMap::new(),
+        }
+    }
+
+    fn fork(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+            queues: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the fault of the next message from the party.
+    fn next_fault(&self, from: usize) -> Option<Fault> {
+        let mut state = self.state.lock().expect("not poisoned");
+        let index = state.received.entry(from).or_default();
+        let key = (from, *index);
+        *index += 1;
+        state.schedule.faults.get(&key).copied()
+    }
+
+    /// Receives the next message from the party and queues it after applying its fault.
+    fn fetch(
+        &mut self,
+        from: usize,
+        recv: &mut impl FnMut() -> std::io::Result<Vec<u8>>,
+    ) -> std::io::Result<()> {
+        let mut bytes = recv()?;
+        let message = match self.next_fault(from) {
+            None => Some(bytes),
+            Some(Fault::Corrupt { offset, mask }) => {
+                if !bytes.is_empty() {
+                    let offset = offset % bytes.len();
+                    bytes[offset] ^= mask;
+                }
+                Some(bytes)
+            }
+            Some(Fault::Truncate { len }) => {
+                bytes.truncate(len);
+                Some(bytes)
+            }
+            Some(Fault::Drop) => None,
+            Some(Fault::Reorder) => {
+                // queue the next message first
+                self.fetch(from, recv)?;
+                Some(bytes)
+            }
+        };
+        self.queues.entry(from).or_default().push_back(message);
+        Ok(())
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(
+        &mut self,
+        from: usize,
+        mut recv: impl FnMut() -> std::io::Result<Vec<u8>>,
+    ) -> std::io::Result<Vec<F>> {
+        if self.queues.get(&from).map_or(true, VecDeque::is_empty) {
+            self.fetch(from, &mut recv)?;
+        }
+        let message = self
+            .queues
+            .get_mut(&from)
+            .and_then(VecDeque::pop_front)
+            .expect("fetched a message");
+        let bytes = message.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("message from party {from} was dropped"),
+            )
+        })?;
+        Vec::<F>::deserialize_uncompressed(bytes.as_slice())
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+}
+
+fn serialize<F: CanonicalSerialize>(data: &[F]) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(data.serialized_size(Compress::No));
+    data.serialize_uncompressed(&mut bytes)
+        .map_err(std::io::Error::other)?;
+    Ok(bytes)
+}
+
+/// A [`Rep3Network`] which injects the faults of a [`FaultSchedule`] into the messages received over the wrapped network.
+#[derive(Debug)]
+pub struct Rep3FaultyNetwork<N: Rep3Network> {
+    inner: N,
+    injector: FaultInjector,
+}
+
+impl<N: Rep3Network> Rep3FaultyNetwork<N> {
+    /// Wraps the network, injecting the faults of the schedule into the received messages.
+    pub fn new(inner: N, schedule: FaultSchedule) -> Self {
+        Self {
+            inner,
+            injector: FaultInjector::new(schedule),
+        }
+    }
+
+    /// Returns the wrapped network.
+    pub fn into_inner(self) -> N {
+        self.inner
+    }
+}
+
+impl<N: Rep3Network> Rep3Network for Rep3FaultyNetwork<N> {
+    fn get_id(&self) -> PartyID {
+        self.inner.get_id()
+    }
+
+    fn reshare_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<Vec<F>> {
+        self.send_next_many(data)?;
+        self.recv_prev_many()
+    }
+
+    fn broadcast_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<(Vec<F>, Vec<F>)> {
+        let id = self.get_id();
+        self.send_many(id.next_id(), data)?;
+        self.send_many(id.prev_id(), data)?;
+        let prev = self.recv_many(id.prev_id())?;
+        let next = self.recv_many(id.next_id())?;
+        Ok((prev, next))
+    }
+
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: PartyID,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        self.inner.send_many(target, &serialize(data)?)
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(&mut self, from: PartyID) -> std::io::Result<Vec<F>> {
+        let inner = &mut self.inner;
+        self.injector
+            .recv_many(from.into(), || inner.recv_many::<u8>(from))
+    }
+
+    fn fork(&mut self) -> std::io::Result<Self> {
+        Ok(Self {
+            inner: self.inner.fork()?,
+            injector: self.injector.fork(),
+        })
+    }
+
+    fn start_phase(&mut self, phase: Phase) -> std::io::Result<()> {
+        self.inner.start_phase(phase)
+    }
+
+    fn end_phase(&mut self) {
+        self.inner.end_phase()
+    }
+
+    fn network_stats(&self) -> NetworkStats {
+        self.inner.network_stats()
+    }
+
+    fn abort(&mut self, reason: AbortReason) {
+        self.inner.abort(reason)
+    }
+}
+
+/// A [`ShamirNetwork`] which injects the faults of a [`FaultSchedule`] into the messages received over the wrapped network.
+#[derive(Debug)]
+pub struct ShamirFaultyNetwork<N: ShamirNetwork> {
+    inner: N,
+    injector: FaultInjector,
+}
+
+impl<N: ShamirNetwork> ShamirFaultyNetwork<N> {
+    /// Wraps the network, injecting the faults of the schedule into the received messages.
+    pub fn new(inner: N, schedule: FaultSchedule) -> Self {
+        Self {
+            inner,
+            injector: FaultInjector::new(schedule),
+        }
+    }
+
+    /// Returns the wrapped network.
+    pub fn into_inner(self) -> N {
+        self.inner
+    }
+}
+
+impl<N: ShamirNetwork> ShamirNetwork for ShamirFaultyNetwork<N> {
+    fn get_id(&self) -> usize {
+        self.inner.get_id()
+    }
+
+    fn get_num_parties(&self) -> usize {
+        self.inner.get_num_parties()
+    }
+
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: usize,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        self.inner.send_many(target, &serialize(data)?)
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(&mut self, from: usize) -> std::io::Result<Vec<F>> {
+        let inner = &mut self.inner;
+        self.injector
+            .recv_many(from, || inner.recv_many::<u8>(from))
+    }
+
+    fn broadcast<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+    ) -> std::io::Result<Vec<F>> {
+        let num_parties = self.get_num_parties();
+        let id = self.get_id();
+        self.broadcast_next(data, num_parties).map(|mut res| {
+            // broadcast_next orders the result relative to our id
+            res.reverse();
+            res.rotate_right(id + 1);
+            res
+        })
+    }
+
+    fn broadcast_next<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+        num: usize,
+    ) -> std::io::Result<Vec<F>> {
+        let num_parties = self.get_num_parties();
+        let id = self.get_id();
+        for i in 1..num {
+            self.send((id + i) % num_parties, data.to_owned())?;
+        }
+        let mut res = Vec::with_capacity(num);
+        res.push(data);
+        for i in 1..num {
+            res.push(self.recv((id + num_parties - i) % num_parties)?);
+        }
+        Ok(res)
+    }
+
+    fn send_and_recv_each_many<
+        F: CanonicalSerialize + CanonicalDeserialize + Clone + Send + 'static,
+    >(
+        &mut self,
+        data: Vec<Vec<F>>,
+    ) -> std::io::Result<Vec<Vec<F>>> {
+        debug_assert_eq!(data.len(), self.get_num_parties());
+        let id = self.get_id();
+        for (target, data) in data.iter().enumerate() {
+            if target != id {
+                self.send_many(target, data)?;
+            }
+        }
+        let mut res = Vec::with_capacity(data.len());
+        for (from, data) in data.into_iter().enumerate() {
+            if from == id {
+                res.push(data);
+            } else {
+                res.push(self.recv_many(from)?);
+            }
+        }
+        Ok(res)
+    }
+
+    fn fork(&mut self) -> std::io::Result<Self> {
+        Ok(Self {
+            inner: self.inner.fork()?,
+            injector: self.injector.fork(),
+        })
+    }
+
+    fn start_phase(&mut self, phase: Phase) -> std::io::Result<()> {
+        self.inner.start_phase(phase)
+    }
+
+    fn end_phase(&mut self) {
+        self.inner.end_phase()
+    }
+
+    fn network_stats(&self) -> NetworkStats {
+        self.inner.network_stats()
+    }
+
+    fn abort(&mut self, reason: AbortReason) {
+        self.inner.abort(reason)
+    }
+}
@@ -0,0 +1,278 @@
+//! # Provisioning
+//!
+//! This module implements a handshake the parties run before a job, to refuse jobs which would not finish in time on the actual deployment. Every party brings the [`CostStats`] of its part of the job, as tallied by a [`CostTracker`](super::cost::CostTracker) in a dry run. The parties exchange these schedules and check that they belong to the same job, i.e., that every sent message is received by another party. Afterwards, they measure the latency and bandwidth of their links with a short probe, agree on the worst measurements, and estimate the wall-clock time of the job. If the estimate exceeds the configured budget, all parties refuse to start the job with a [`ProvisioningError::BudgetExceeded`].
+//!
+//! The estimate is a simple model: the time of a party is its number of rounds times the latency plus the time to send its bytes over a single link, and the job takes as long as its slowest party. It neglects the computation, so the budget should leave some headroom.
+
+use std::time::{Duration, Instant};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use super::{cost::CostStats, rep3::network::Rep3Network, shamir::network::ShamirNetwork};
+
+/// The default number of bytes sent over every link to measure its bandwidth.
+pub const DEFAULT_PROBE_LEN: usize = 1 << 20;
+
+/// The default number of messages exchanged to measure the latency.
+pub const DEFAULT_LATENCY_PROBES: usize = 5;
+
+/// The configuration of the provisioning handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProvisioningConfig {
+    /// The maximum wall-clock time of the job.
+    pub budget: Duration,
+    /// The number of bytes sent over every link to measure its bandwidth.
+    pub probe_len: usize,
+    /// The number of messages exchanged to measure the latency. The fastest exchange is taken as the latency, since the first exchanges also include the time the parties wait for each other.
+    pub latency_probes: usize,
+}
+
+impl ProvisioningConfig {
+    /// Creates a configuration with the given budget and the default probes.
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            budget,
+            probe_len: DEFAULT_PROBE_LEN,
+            latency_probes: DEFAULT_LATENCY_PROBES,
+        }
+    }
+
+    /// Sets the number of bytes sent over every link to measure its bandwidth.
+    pub fn with_probe_len(mut self, probe_len: usize) -> Self {
+        self.probe_len = probe_len;
+        self
+    }
+
+    /// Sets the number of messages exchanged to measure the latency.
+    pub fn with_latency_probes(mut self, latency_probes: usize) -> Self {
+        self.latency_probes = latency_probes;
+        self
+    }
+}
+
+/// The outcome of a successful provisioning handshake, which is the same for all parties.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProvisioningReport {
+    /// The largest one-way latency measured by any party.
+    pub latency: Duration,
+    /// The smallest bandwidth of a link in bytes per second measured by any party.
+    pub bandwidth: f64,
+    /// The estimated wall-clock time of the job.
+    pub estimate: Duration,
+}
+
+/// The error returned if the parties refuse to start the job. Functions returning an [IoResult](std::io::Result) wrap it into an [std::io::Error], from which it can be recovered with [ProvisioningError::from_io_error].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProvisioningError {
+    /// The schedules of the parties do not belong to the same job, since the parties send a different number of messages or elements than they receive.
+    InconsistentSchedules,
+    /// The job is estimated to take longer than the budget.
+    BudgetExceeded {
+        /// The estimated wall-clock time of the job
+        estimate: Duration,
+        /// The configured budget
+        budget: Duration,
+    },
+}
+
+impl ProvisioningError {
+    /// Returns the [ProvisioningError] wrapped in the given error, if any.
+    pub fn from_io_error(err: &std::io::Error) -> Option<&Self> {
+        err.get_ref()?.downcast_ref()
+    }
+}
+
+impl std::fmt::Display for ProvisioningError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProvisioningError::InconsistentSchedules => write!(
+                f,
+                "The schedules of the parties do not belong to the same job!"
+            ),
+            ProvisioningError::BudgetExceeded { estimate, budget } => write!(
+                f,
+                "The job is estimated to take {estimate:?}, which exceeds the budget of {budget:?}!"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProvisioningError {}
+
+impl From<ProvisioningError> for std::io::Error {
+    fn from(err: ProvisioningError) -> Self {
+        let kind = match err {
+            ProvisioningError::InconsistentSchedules => std::io::ErrorKind::InvalidData,
+            ProvisioningError::BudgetExceeded { .. } => std::io::ErrorKind::Other,
+        };
+        std::io::Error::new(kind, err)
+    }
+}
+
+/// Runs the provisioning handshake over a [`Rep3Network`], where `schedule` is the expected communication of this party. All parties have to call this before the job.
+pub fn provision_rep3<N: Rep3Network>(
+    network: &mut N,
+    schedule: &CostStats,
+    config: &ProvisioningConfig,
+) -> std::io::Result<ProvisioningReport> {
+    provision(&mut Rep3Exchange(network), schedule, config)
+}
+
+/// Runs the provisioning handshake over a [`ShamirNetwork`], where `schedule` is the expected communication of this party. All parties have to call this before the job.
+pub fn provision_shamir<N: ShamirNetwork>(
+    network: &mut N,
+    schedule: &CostStats,
+    config: &ProvisioningConfig,
+) -> std::io::Result<ProvisioningReport> {
+    provision(&mut ShamirExchange(network), schedule, config)
+}
+
+/// Sends data to all other parties and receives theirs, which is all the handshake needs of a network.
+trait Exchange {
+    /// Returns the data of all parties, including our own.
+    fn exchange<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+    ) -> std::io::Result<Vec<F>>;
+}
+
+struct Rep3Exchange<'a, N>(&'a mut N);
+
+impl<N: Rep3Network> Exchange for Rep3Exchange<'_, N> {
+    fn exchange<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+    ) -> std::io::Result<Vec<F>> {
+        let (prev, next) = self.0.broadcast(data.clone())?;
+        Ok(vec![data, prev, next])
+    }
+}
+
+struct ShamirExchange<'a, N>(&'a mut N);
+
+impl<N: ShamirNetwork> Exchange for ShamirExchange<'_, N> {
+    fn exchange<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+    ) -> std::io::Result<Vec<F>> {
+        self.0.broadcast(data)
+    }
+}
+
+fn provision(
+    network: &mut impl Exchange,
+    schedule: &CostStats,
+    config: &ProvisioningConfig,
+) -> std::io::Result<ProvisioningReport> {
+    let schedules = network
+        .exchange(encode_schedule(schedule))?
+        .iter()
+        .map(|schedule| decode_schedule(schedule))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    let sent = schedules.iter().fold((0, 0), |(messages, elements), s| {
+        (messages + s.messages_sent, elements + s.elements_sent)
+    });
+    let received = schedules.iter().fold((0, 0), |(messages, elements), s| {
+        (
+            messages + s.messages_received,
+            elements + s.elements_received,
+        )
+    });
+    if sent != received {
+        return Err(ProvisioningError::InconsistentSchedules.into());
+    }
+
+    let mut latency = Duration::MAX;
+    for _ in 0..config.latency_probes.max(1) {
+        let start = Instant::now();
+        network.exchange(0u8)?;
+        latency = latency.min(start.elapsed());
+    }
+    let start = Instant::now();
+    network.exchange(vec![0u8; config.probe_len])?;
+    // the probe takes one latency until its first byte arrives
+    let transfer = start
+        .elapsed()
+        .saturating_sub(latency)
+        .max(Duration::from_micros(1));
+    let bandwidth = config.probe_len as f64 / transfer.as_secs_f64();
+
+    // agree on the worst link, such that all parties come to the same decision
+    let measurements = network.exchange(vec![
+        u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX),
+        bandwidth as u64,
+    ])?;
+    let mut latency = Duration::ZERO;
+    let mut bandwidth = u64::MAX;
+    for measurement in measurements {
+        let [their_latency, their_bandwidth] = measurement[..] else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "invalid link measurement",
+            ));
+        };
+        latency = latency.max(Duration::from_nanos(their_latency));
+        bandwidth = bandwidth.min(their_bandwidth.max(1));
+    }
+    let bandwidth = bandwidth as f64;
+
+    let estimate = schedules
+        .iter()
+        .map(|schedule| {
+            latency.saturating_mul(u32::try_from(schedule.rounds).unwrap_or(u32::MAX))
+                + Duration::from_secs_f64(schedule.bytes_sent as f64 / bandwidth)
+        })
+        .max()
+        .unwrap_or_default();
+    if estimate > config.budget {
+        return Err(ProvisioningError::BudgetExceeded {
+            estimate,
+            budget: config.budget,
+        }
+        .into());
+    }
+    Ok(ProvisioningReport {
+        latency,
+        bandwidth,
+        estimate,
+    })
+}
+
+fn encode_schedule(schedule: &CostStats) -> Vec<u64> {
+    [
+        schedule.rounds,
+        schedule.messages_sent,
+        schedule.elements_sent,
+        schedule.bytes_sent,
+        schedule.messages_received,
+        schedule.elements_received,
+    ]
+    .map(|value| value as u64)
+    .to_vec()
+}
+
+fn decode_schedule(schedule: &[u64]) -> std::io::Result<CostStats> {
+    let invalid = || {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "invalid schedule of a party",
+        )
+    };
+    let values = schedule
+        .iter()
+        .map(|value| usize::try_from(*value).map_err(|_| invalid()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    let [rounds, messages_sent, elements_sent, bytes_sent, messages_received, elements_received] =
+        values[..]
+    else {
+        return Err(invalid());
+    };
+    Ok(CostStats {
+        rounds,
+        messages_sent,
+        elements_sent,
+        bytes_sent,
+        messages_received,
+        elements_received,
+    })
+}
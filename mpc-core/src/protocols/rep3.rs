@@ -3,16 +3,24 @@
 //! This module implements the rep3 share and combine operations
 
 pub mod arithmetic;
+pub mod async_network;
 pub mod binary;
 pub mod conversion;
 mod detail;
+pub mod extension_field;
+pub mod fixed_point;
 pub mod gadgets;
 pub mod id;
 pub mod lut;
+pub mod malicious;
 pub mod network;
 pub mod pointshare;
 pub mod poly;
+pub mod preprocessing;
+pub mod receiver;
 pub mod rngs;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 pub mod yao;
 
 use std::marker::PhantomData;
@@ -21,12 +29,14 @@ use ark_ec::CurveGroup;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use num_bigint::BigUint;
 
-use ark_ff::{One, PrimeField};
+use ark_ff::{Field, One, PrimeField};
 use rand::{distributions::Standard, prelude::Distribution, CryptoRng, Rng, SeedableRng};
 
 pub use arithmetic::types::Rep3PrimeFieldShare;
 pub use binary::types::Rep3BigUintShare;
+pub use extension_field::Rep3ExtensionFieldShare;
 pub use pointshare::Rep3PointShare;
+pub use receiver::EncryptedOutputShares;
 use serde::{Deserialize, Serialize};
 
 pub(crate) type IoResult<T> = std::io::Result<T>;
@@ -504,6 +514,20 @@ pub fn share_curve_point<C: CurveGroup, R: Rng + CryptoRng>(
     [share1, share2, share3]
 }
 
+/// Secret shares an extension field element using replicated secret sharing and the provided random number generator. The extension field element is split into three additive shares, where each party holds two. The outputs are of type [Rep3ExtensionFieldShare].
+pub fn share_extension_field_element<E: Field, R: Rng + CryptoRng>(
+    val: E,
+    rng: &mut R,
+) -> [Rep3ExtensionFieldShare<E>; 3] {
+    let a = E::rand(rng);
+    let b = E::rand(rng);
+    let c = val - a - b;
+    let share1 = Rep3ExtensionFieldShare::new(a, c);
+    let share2 = Rep3ExtensionFieldShare::new(b, a);
+    let share3 = Rep3ExtensionFieldShare::new(c, b);
+    [share1, share2, share3]
+}
+
 /// Reconstructs a field element from its arithmetic replicated shares.
 pub fn combine_field_element<F: PrimeField>(
     share1: Rep3PrimeFieldShare<F>,
@@ -546,3 +570,12 @@ pub fn combine_curve_point<C: CurveGroup>(
 ) -> C {
     share1.a + share2.a + share3.a
 }
+
+/// Reconstructs an extension field element from its arithmetic replicated shares.
+pub fn combine_extension_field_element<E: Field>(
+    share1: Rep3ExtensionFieldShare<E>,
+    share2: Rep3ExtensionFieldShare<E>,
+    share3: Rep3ExtensionFieldShare<E>,
+) -> E {
+    share1.a + share2.a + share3.a
+}
@@ -5,18 +5,28 @@
 use core::panic;
 use num_traits::cast::ToPrimitive;
 
-use ark_ff::PrimeField;
+use ark_ec::CurveGroup;
+use ark_ff::{PrimeField, UniformRand};
 use itertools::{izip, Itertools};
 use num_bigint::BigUint;
 use num_traits::One;
 use num_traits::Zero;
+use rand::{CryptoRng, Rng};
 use types::Rep3PrimeFieldShare;
 
 use crate::protocols::rep3::{detail, id::PartyID, network::Rep3Network};
 use rayon::prelude::*;
 
 use super::{
-    binary, conversion, network::IoContext, rngs::Rep3CorrelatedRng, IoResult, Rep3BigUintShare,
+    binary,
+    conversion::{self, A2BType},
+    fixed_point::STATISTICAL_SECURITY,
+    malicious::{self, SecurityLevel},
+    network::IoContext,
+    preprocessing::Rep3PreprocessedBits,
+    receiver,
+    rngs::Rep3CorrelatedRng,
+    yao, EncryptedOutputShares, IoResult, Rep3BigUintShare,
 };
 
 /// Type alias for a [`Rep3PrimeFieldShare`]
@@ -107,10 +117,14 @@ pub fn mul<F: PrimeField, N: Rep3Network>(
 ) -> IoResult<FieldShare<F>> {
     let local_a = a * b + io_context.rngs.rand.masking_field_element::<F>();
     let local_b = io_context.network.reshare(local_a)?;
-    Ok(FieldShare {
+    let res = FieldShare {
         a: local_a,
         b: local_b,
-    })
+    };
+    if io_context.security_level == SecurityLevel::Malicious {
+        malicious::verify_mul_triples(&[a], &[b], &[res], io_context)?;
+    }
+    Ok(res)
 }
 
 /// Performs multiplication of a shared value and a public value.
@@ -177,7 +191,11 @@ pub fn mul_vec<F: PrimeField, N: Rep3Network>(
     let local_a = izip!(lhs.iter(), rhs.iter())
         .map(|(lhs, rhs)| lhs * rhs + io_context.rngs.rand.masking_field_element::<F>())
         .collect_vec();
-    io_mul_vec(local_a, io_context)
+    let res = io_mul_vec(local_a, io_context)?;
+    if io_context.security_level == SecurityLevel::Malicious {
+        malicious::verify_mul_triples(lhs, rhs, &res, io_context)?;
+    }
+    Ok(res)
 }
 
 /// Performs division of two shared values, returning a / b.
@@ -259,6 +277,9 @@ pub fn open<F: PrimeField, N: Rep3Network>(
     a: FieldShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<F> {
+    if io_context.security_level == SecurityLevel::Malicious {
+        return Ok(open_many_checked(&[a], io_context)?[0]);
+    }
     let c = io_context.network.reshare(a.b)?;
     Ok(a.a + a.b + c)
 }
@@ -268,6 +289,7 @@ pub fn open_bit<F: PrimeField, N: Rep3Network>(
     a: Rep3BigUintShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BigUint> {
+    malicious::ensure_semi_honest(io_context, "opening of binary shares")?;
     let c = io_context.network.reshare(a.b.to_owned())?;
     Ok(a.a ^ a.b ^ c)
 }
@@ -280,6 +302,9 @@ pub fn open_vec<F: PrimeField, N: Rep3Network>(
     // TODO think about something better... it is not so bad
     // because we use it exactly once in PLONK where we do it for 4
     // shares..
+    if io_context.security_level == SecurityLevel::Malicious {
+        return Ok(open_many_checked(a, io_context)?);
+    }
     let (a, b) = a
         .iter()
         .map(|share| (share.a, share.b))
@@ -288,6 +313,149 @@ pub fn open_vec<F: PrimeField, N: Rep3Network>(
     Ok(izip!(a, b, c).map(|(a, b, c)| a + b + c).collect_vec())
 }
 
+/// Performs the opening of a vector of shared values towards a single party. Only `target` learns the
+/// equivalent public values, all other parties return `None`.
+pub fn open_vec_to<F: PrimeField, N: Rep3Network>(
+    a: &[FieldShare<F>],
+    target: PartyID,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Option<Vec<F>>> {
+    // the target is missing the additive share held by its next party, which is also held by its previous party
+    let malicious = io_context.security_level == SecurityLevel::Malicious;
+    if io_context.id == target.prev_id() {
+        let b = a.iter().map(|share| share.b).collect_vec();
+        io_context.network.send_next_many(&b)?;
+        Ok(None)
+    } else if io_context.id == target {
+        let c = io_context.network.recv_prev_many::<F>()?;
+        if c.len() != a.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "received wrong number of shares while opening",
+            ));
+        }
+        // in the malicious mode, the next party sends the missing share as well, so the target can compare them
+        if malicious && io_context.network.recv_many::<F>(target.next_id())? != c {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "inconsistent shares during opening, a party cheated",
+            ));
+        }
+        Ok(Some(
+            izip!(a, c)
+                .map(|(share, c)| share.a + share.b + c)
+                .collect_vec(),
+        ))
+    } else {
+        if malicious {
+            let a = a.iter().map(|share| share.a).collect_vec();
+            io_context.network.send_many(target, &a)?;
+        }
+        Ok(None)
+    }
+}
+
+/// The error returned by [`open_many_checked`].
+#[derive(Debug)]
+pub enum CheckedOpenError {
+    /// The communication with the other parties failed.
+    Io(std::io::Error),
+    /// The two neighbors sent different values for the additive share this party is missing, i.e., at least one of them misbehaved.
+    /// Contains the indices of all inconsistent shares.
+    Inconsistent(Vec<usize>),
+}
+
+impl std::fmt::Display for CheckedOpenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckedOpenError::Io(err) => write!(f, "io error during checked opening: {err}"),
+            CheckedOpenError::Inconsistent(indices) => {
+                write!(
+                    f,
+                    "inconsistent shares during opening at indices {indices:?}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckedOpenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CheckedOpenError::Io(err) => Some(err),
+            CheckedOpenError::Inconsistent(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for CheckedOpenError {
+    fn from(err: std::io::Error) -> Self {
+        CheckedOpenError::Io(err)
+    }
+}
+
+/// Performs the opening of a vector of shared values in one round and checks the consistency of the received values.
+/// The additive share a party is missing is held by both of its neighbors, so it receives it from both and compares them.
+/// Returns [`CheckedOpenError::Inconsistent`] with the indices of all mismatching shares, which means that one of the other parties misbehaved.
+pub fn open_many_checked<F: PrimeField, N: Rep3Network>(
+    a: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> Result<Vec<F>, CheckedOpenError> {
+    let (a, b) = a
+        .iter()
+        .map(|share| (share.a, share.b))
+        .collect::<(Vec<F>, Vec<F>)>();
+    let next_id = io_context.id.next_id();
+    let prev_id = io_context.id.prev_id();
+    // our next party misses our a, our previous party misses our b
+    io_context.network.send_many(prev_id, &a)?;
+    io_context.network.send_many(next_id, &b)?;
+    let from_next = io_context.network.recv_many::<F>(next_id)?;
+    let from_prev = io_context.network.recv_many::<F>(prev_id)?;
+    if from_next.len() != a.len() || from_prev.len() != a.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "received wrong number of shares while opening",
+        )
+        .into());
+    }
+    let inconsistent = izip!(&from_next, &from_prev)
+        .enumerate()
+        .filter(|(_, (c0, c1))| c0 != c1)
+        .map(|(i, _)| i)
+        .collect_vec();
+    if !inconsistent.is_empty() {
+        return Err(CheckedOpenError::Inconsistent(inconsistent));
+    }
+    Ok(izip!(a, b, from_next)
+        .map(|(a, b, c)| a + b + c)
+        .collect_vec())
+}
+
+/// Performs the opening of a vector of shared values towards an external result receiver with the given public key.
+/// No communication between the parties is required. Each party encrypts its additive share under the key of the receiver,
+/// so none of the parties learns the equivalent public values. The receiver reconstructs them from the
+/// [`EncryptedOutputShares`] of all three parties.
+pub fn open_vec_to_receiver<F: PrimeField, C: CurveGroup, R: Rng + CryptoRng>(
+    a: &[FieldShare<F>],
+    receiver_key: &C,
+    id: PartyID,
+    rng: &mut R,
+) -> EncryptedOutputShares<C, F> {
+    let ephemeral_secret = C::ScalarField::rand(rng);
+    let shared_key = *receiver_key * ephemeral_secret;
+    let values = a
+        .iter()
+        .enumerate()
+        .map(|(i, share)| share.a + receiver::output_mask::<C, F>(&shared_key, i))
+        .collect();
+    EncryptedOutputShares {
+        party_id: id as u8,
+        ephemeral: C::generator() * ephemeral_secret,
+        values,
+    }
+}
+
 /// Computes a CMUX: If cond is 1, returns truthy, otherwise returns falsy.
 /// Implementations should not overwrite this method.
 pub fn cmux<F: PrimeField, N: Rep3Network>(
@@ -332,6 +500,10 @@ pub fn mul_open<F: PrimeField, N: Rep3Network>(
     b: FieldShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<F> {
+    if io_context.security_level == SecurityLevel::Malicious {
+        let c = mul(a, b, io_context)?;
+        return open(c, io_context);
+    }
     let a = a * b + io_context.rngs.rand.masking_field_element::<F>();
     let (b, c) = io_context.network.broadcast(a)?;
     Ok(a + b + c)
@@ -343,6 +515,10 @@ pub fn mul_open_vec<F: PrimeField, N: Rep3Network>(
     b: &[FieldShare<F>],
     io_context: &mut IoContext<N>,
 ) -> IoResult<Vec<F>> {
+    if io_context.security_level == SecurityLevel::Malicious {
+        let c = mul_vec(a, b, io_context)?;
+        return open_vec(&c, io_context);
+    }
     let mut a = izip!(a, b)
         .map(|(a, b)| a * b + io_context.rngs.rand.masking_field_element::<F>())
         .collect_vec();
@@ -351,6 +527,82 @@ pub fn mul_open_vec<F: PrimeField, N: Rep3Network>(
     Ok(a)
 }
 
+/// Checks in a batched fashion whether the shared values lie in the range [0, 2^bit_width) and returns the result
+/// for each value. Only the results are revealed. Depending on the `A2BType` of the io_context (see
+/// [`IoContext::select_a2b_type`]), the values are either shifted to the right by `bit_width` in a single garbled
+/// circuit, and each result is multiplied with a random shared value before it is opened (the opened product is zero
+/// iff the value is in range, and uniformly random otherwise), or the values are converted to binary sharings and
+/// the bits above `bit_width` are checked for zero using a packed AND tree, whose results are opened.
+pub fn range_check_vec<F: PrimeField, N: Rep3Network>(
+    a: &[FieldShare<F>],
+    bit_width: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<bool>> {
+    if bit_width >= F::MODULUS_BIT_SIZE as usize {
+        return Ok(vec![true; a.len()]);
+    }
+    if a.is_empty() {
+        return Ok(Vec::new());
+    }
+    match io_context.select_a2b_type(a.len(), F::MODULUS_BIT_SIZE as usize) {
+        A2BType::Direct | A2BType::Auto => {
+            let high = conversion::a2b_many(a, io_context)?
+                .iter()
+                .map(|x| x >> bit_width)
+                .collect_vec();
+            let is_zero = binary::is_zero_many(&high, io_context)?;
+            let opened = binary::open(&detail::pack(&is_zero, 1), io_context)?;
+            Ok((0..a.len() as u64).map(|i| opened.bit(i)).collect())
+        }
+        A2BType::Yao => {
+            let high = yao::field_int_div_power_2_many(a, io_context, bit_width)?;
+            let masks = (0..a.len()).map(|_| rand(io_context)).collect_vec();
+            let opened = mul_open_vec(&high, &masks, io_context)?;
+            Ok(opened.into_iter().map(|x| x.is_zero()).collect())
+        }
+    }
+}
+
+/// Decomposes a vector of shared values into their `num_bits` least significant bits, which are returned as arithmetic
+/// shares, least significant bit first. Depending on the `A2BType` of the io_context (see
+/// [`IoContext::select_a2b_type`]), all values are either decomposed in a single garbled circuit, or converted to
+/// binary sharings whose bits are then injected into the arithmetic domain. In both cases, the number of
+/// communication rounds does not depend on the number of values. Higher bits of the values are dropped. Returns an
+/// error if `num_bits` is zero or larger than the bit size of the field.
+pub fn to_bits_many<F: PrimeField, N: Rep3Network>(
+    a: &[FieldShare<F>],
+    num_bits: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Vec<FieldShare<F>>>> {
+    if num_bits == 0 || num_bits > F::MODULUS_BIT_SIZE as usize {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("num_bits must be in [1, {}]", F::MODULUS_BIT_SIZE),
+        ));
+    }
+    if a.is_empty() {
+        return Ok(Vec::new());
+    }
+    let bits = match io_context.select_a2b_type(a.len(), F::MODULUS_BIT_SIZE as usize) {
+        A2BType::Direct | A2BType::Auto => {
+            let bits = conversion::a2b_many(a, io_context)?
+                .iter()
+                .flat_map(|x| {
+                    (0..num_bits as u64).map(|i| {
+                        BinaryShare::new(
+                            BigUint::from(u8::from(x.a.bit(i))),
+                            BigUint::from(u8::from(x.b.bit(i))),
+                        )
+                    })
+                })
+                .collect_vec();
+            conversion::bit_inject_many(&bits, io_context)?
+        }
+        A2BType::Yao => yao::decompose_arithmetic_many(a, io_context, num_bits, 1)?,
+    };
+    Ok(bits.chunks(num_bits).map(<[_]>::to_vec).collect())
+}
+
 /// Generate a random [`FieldShare`].
 pub fn rand<F: PrimeField, N: Rep3Network>(io_context: &mut IoContext<N>) -> FieldShare<F> {
     let (a, b) = io_context.rngs.rand.random_fes();
@@ -373,18 +625,15 @@ pub fn sqrt<F: PrimeField, N: Rep3Network>(
     let mul = mul_vec(&lhs, &rhs, io_context)?;
 
     // Open mul
-    io_context
-        .network
-        .send_next_many(&mul.iter().map(|s| s.b.to_owned()).collect_vec())?;
-    let c = io_context.network.recv_prev_many::<F>()?;
-    if c.len() != 2 {
+    let opened = open_vec(&mul, io_context)?;
+    if opened.len() != 2 {
         return Err(std::io::Error::new(
             std::io::ErrorKind::InvalidData,
             "During execution of square root in MPC: invalid number of elements received",
         ));
     }
-    let y_sq = (mul[0].a + mul[0].b + c[0]).sqrt();
-    let y_inv = mul[1].a + mul[1].b + c[1];
+    let y_sq = opened[0].sqrt();
+    let y_inv = opened[1];
 
     // postprocess the square and inverse
     let y_sq = match y_sq {
@@ -418,17 +667,59 @@ pub fn pow_public<F: PrimeField, N: Rep3Network>(
     io_context: &mut IoContext<N>,
 ) -> IoResult<Rep3PrimeFieldShare<F>> {
     // TODO: are negative exponents allowed in circom?
-    let mut res = promote_to_trivial_share(io_context.id, F::one());
+    let res = pow_public_many(&[shared], public, io_context)?;
+    Ok(res[0])
+}
+
+/// Performs a pow operation on a slice of shared bases with the same public exponent.
+///
+/// Uses square-and-multiply, where the squaring of the bases and the multiplication into the result are batched into a single round per exponent bit. Thus, the number of rounds only depends on the bit length of the exponent and not on the number of bases.
+pub fn pow_public_many<F: PrimeField, N: Rep3Network>(
+    shared: &[FieldShare<F>],
+    public: F,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    let len = shared.len();
     let mut public: BigUint = public.into_bigint().into();
-    let mut shared: FieldShare<F> = shared;
+    let mut base = shared.to_vec();
+    // None represents the trivial result 1, which we do not have to multiply with
+    let mut res: Option<Vec<FieldShare<F>>> = None;
     while !public.is_zero() {
-        if public.bit(0) {
-            res = mul(res, shared, io_context)?;
-        }
-        shared = mul(shared, shared, io_context)?;
+        let bit = public.bit(0);
         public >>= 1;
+        let square = !public.is_zero();
+
+        let mut lhs = Vec::with_capacity(2 * len);
+        let mut rhs = Vec::with_capacity(2 * len);
+        let mul_res = match (bit, &res) {
+            (true, Some(res)) => {
+                lhs.extend_from_slice(res);
+                rhs.extend_from_slice(&base);
+                true
+            }
+            (true, None) => {
+                res = Some(base.clone());
+                false
+            }
+            (false, _) => false,
+        };
+        if square {
+            lhs.extend_from_slice(&base);
+            rhs.extend_from_slice(&base);
+        }
+        if lhs.is_empty() {
+            continue;
+        }
+
+        let mut products = mul_vec(&lhs, &rhs, io_context)?;
+        if square {
+            base = products.split_off(if mul_res { len } else { 0 });
+        }
+        if mul_res {
+            res = Some(products);
+        }
     }
-    mul(res, shared, io_context)
+    Ok(res.unwrap_or_else(|| vec![promote_to_trivial_share(io_context.id, F::one()); len]))
 }
 
 /// Returns 1 if lhs < rhs and 0 otherwise. Checks if one shared value is less than another shared value. The result is a shared value that has value 1 if the first shared value is less than the second shared value and 0 otherwise.
@@ -542,6 +833,151 @@ pub fn ge_public_bit<F: PrimeField, N: Rep3Network>(
     detail::unsigned_ge_const_rhs(lhs, rhs, io_context)
 }
 
+/// Returns 1 if lhs < rhs and 0 otherwise for each pair of shared values. In contrast to [`lt`], all comparisons are batched into a single garbled circuit or a single packed binary circuit, depending on the `A2BType` of the io_context (see [`IoContext::select_a2b_type`]), so the number of communication rounds does not depend on the number of values.
+pub fn lt_many<F: PrimeField, N: Rep3Network>(
+    lhs: &[FieldShare<F>],
+    rhs: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    // a < b is equivalent to !(a >= b)
+    let tmp = ge_many(lhs, rhs, io_context)?;
+    Ok(tmp
+        .into_iter()
+        .map(|x| sub_public_by_shared(F::one(), x, io_context.id))
+        .collect())
+}
+
+/// Returns 1 if lhs <= rhs and 0 otherwise for each pair of shared values. In contrast to [`le`], all comparisons are batched into a single garbled circuit or a single packed binary circuit, depending on the `A2BType` of the io_context (see [`IoContext::select_a2b_type`]), so the number of communication rounds does not depend on the number of values.
+pub fn le_many<F: PrimeField, N: Rep3Network>(
+    lhs: &[FieldShare<F>],
+    rhs: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    // a <= b is equivalent to b >= a
+    ge_many(rhs, lhs, io_context)
+}
+
+/// Returns 1 if lhs > rhs and 0 otherwise for each pair of shared values. In contrast to [`gt`], all comparisons are batched into a single garbled circuit or a single packed binary circuit, depending on the `A2BType` of the io_context (see [`IoContext::select_a2b_type`]), so the number of communication rounds does not depend on the number of values.
+pub fn gt_many<F: PrimeField, N: Rep3Network>(
+    lhs: &[FieldShare<F>],
+    rhs: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    // a > b is equivalent to b < a
+    lt_many(rhs, lhs, io_context)
+}
+
+/// Returns 1 if lhs >= rhs and 0 otherwise for each pair of shared values. In contrast to [`ge`], all comparisons are batched into a single garbled circuit or a single packed binary circuit, depending on the `A2BType` of the io_context (see [`IoContext::select_a2b_type`]), so the number of communication rounds does not depend on the number of values.
+pub fn ge_many<F: PrimeField, N: Rep3Network>(
+    lhs: &[FieldShare<F>],
+    rhs: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    assert_eq!(lhs.len(), rhs.len());
+    if lhs.is_empty() {
+        return Ok(Vec::new());
+    }
+    match io_context.select_a2b_type(2 * lhs.len(), F::MODULUS_BIT_SIZE as usize) {
+        A2BType::Direct | A2BType::Auto => {
+            let bits = conversion::a2b_many(&[lhs, rhs].concat(), io_context)?;
+            let (lhs, rhs) = bits.split_at(lhs.len());
+            let ge =
+                detail::unsigned_ge_packed(lhs, rhs, io_context, F::MODULUS_BIT_SIZE as usize)?;
+            let ge = (0..lhs.len() as u64)
+                .map(|i| {
+                    BinaryShare::new(
+                        BigUint::from(u8::from(ge.a.bit(i))),
+                        BigUint::from(u8::from(ge.b.bit(i))),
+                    )
+                })
+                .collect_vec();
+            conversion::bit_inject_many(&ge, io_context)
+        }
+        A2BType::Yao => yao::field_ge_many(lhs, rhs, io_context),
+    }
+}
+
+/// Returns 1 if lhs >= rhs and 0 otherwise for each pair of shared values in [0, 2^bitsize). Each comparison consumes one preprocessed edaBit of size `bitsize` and one daBit (see [`Rep3PreprocessedBits`]): The difference d = lhs - rhs + 2^bitsize is masked with the edaBit in the lower bits and with a statistical mask in the upper bits and opened. Then, d mod 2^bitsize is obtained by comparing the lower bits of the opened value to the binary part of the edaBit, and the result is the bit at position bitsize of d.
+///
+/// The field has to be large enough to hold the masked values, i.e., bitsize + [`STATISTICAL_SECURITY`] + 4 <= F::MODULUS_BIT_SIZE.
+pub fn ge_many_with_edabits<F: PrimeField, N: Rep3Network>(
+    lhs: &[FieldShare<F>],
+    rhs: &[FieldShare<F>],
+    bitsize: usize,
+    preprocessed: &mut Rep3PreprocessedBits<F>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    assert_eq!(lhs.len(), rhs.len());
+    assert!(
+        bitsize > 0 && bitsize + STATISTICAL_SECURITY + 4 <= F::MODULUS_BIT_SIZE as usize,
+        "bitsize is too large for the field"
+    );
+    if lhs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let id = io_context.id;
+    let edabits = preprocessed.take_edabits(lhs.len(), bitsize)?;
+    let pow = F::from(BigUint::one() << bitsize);
+
+    let diffs = izip!(lhs, rhs)
+        .map(|(lhs, rhs)| add_public(*lhs - *rhs, pow, id))
+        .collect_vec();
+    let masked = izip!(&diffs, &edabits)
+        .map(|(diff, edabit)| {
+            let (a, b) = io_context.rngs.rand.random_biguint(STATISTICAL_SECURITY);
+            let high = FieldShare::new(F::from(a), F::from(b));
+            *diff + edabit.arithmetic + high * pow
+        })
+        .collect_vec();
+    let low_mask = (BigUint::one() << bitsize) - BigUint::one();
+    let masked = open_vec(&masked, io_context)?
+        .into_iter()
+        .map(|c| {
+            let c: BigUint = c.into();
+            c & &low_mask
+        })
+        .collect_vec();
+
+    // [c mod 2^bitsize >= r] for the lower bits c of the opened value and the edaBit r
+    let edabits_binary = edabits.iter().map(|e| e.binary.to_owned()).collect_vec();
+    let ge = detail::unsigned_ge_const_lhs_packed(&masked, &edabits_binary, io_context, bitsize)?;
+    let ge = (0..lhs.len() as u64)
+        .map(|i| {
+            BinaryShare::new(
+                BigUint::from(u8::from(ge.a.bit(i))),
+                BigUint::from(u8::from(ge.b.bit(i))),
+            )
+        })
+        .collect_vec();
+    let ge = conversion::bit_inject_many_with_dabits(&ge, preprocessed, io_context)?;
+
+    let pow_inv = pow.inverse().expect("2^bitsize is invertible");
+    Ok(izip!(diffs, masked, edabits, ge)
+        .map(|(diff, c, edabit, ge)| {
+            // d mod 2^bitsize = c - r + 2^bitsize * [c < r]
+            let lt = sub_public_by_shared(F::one(), ge, id);
+            let diff_low = add_public(lt * pow - edabit.arithmetic, F::from(c), id);
+            (diff - diff_low) * pow_inv
+        })
+        .collect())
+}
+
+/// Returns 1 if lhs < rhs and 0 otherwise for each pair of shared values in [0, 2^bitsize). See [`ge_many_with_edabits`] for details.
+pub fn lt_many_with_edabits<F: PrimeField, N: Rep3Network>(
+    lhs: &[FieldShare<F>],
+    rhs: &[FieldShare<F>],
+    bitsize: usize,
+    preprocessed: &mut Rep3PreprocessedBits<F>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    // a < b is equivalent to !(a >= b)
+    let tmp = ge_many_with_edabits(lhs, rhs, bitsize, preprocessed, io_context)?;
+    Ok(tmp
+        .into_iter()
+        .map(|x| sub_public_by_shared(F::one(), x, io_context.id))
+        .collect())
+}
+
 //TODO FN REMARK - I think we can skip the bit_inject.
 //Circom has dedicated op codes for bool ops so we would know
 //for bool_and/bool_or etc that we are a boolean value (and therefore
@@ -622,6 +1058,27 @@ pub fn is_zero<F: PrimeField, N: Rep3Network>(
     Ok(x.is_one())
 }
 
+/// Checks for each shared value whether it is zero. The results are shared values that have value 1 if the input is zero and 0 otherwise. In contrast to calling [`eq`] for each value, all values are converted to binary sharings at once and share one AND tree, so the number of communication rounds does not depend on the number of values.
+pub fn is_zero_many<F: PrimeField, N: Rep3Network>(
+    a: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    let bits = conversion::a2y2b_many(a, io_context)?;
+    let is_zero = binary::is_zero_many(&bits, io_context)?;
+    conversion::bit_inject_many(&is_zero, io_context)
+}
+
+/// Checks for each pair of shared values whether they are equal. The results are shared values that have value 1 if the two values are equal and 0 otherwise. See [`is_zero_many`] for details.
+pub fn eq_many<F: PrimeField, N: Rep3Network>(
+    a: &[FieldShare<F>],
+    b: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    debug_assert_eq!(a.len(), b.len());
+    let diff = izip!(a, b).map(|(a, b)| sub(*a, *b)).collect_vec();
+    is_zero_many(&diff, io_context)
+}
+
 /// Computes `shared*2^public`. This is the same as `shared << public`.
 ///
 /// #Panics
@@ -650,6 +1107,7 @@ pub(crate) fn arithmetic_xor<F: PrimeField, N: Rep3Network>(
     y: Rep3PrimeFieldShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<Rep3PrimeFieldShare<F>> {
+    malicious::ensure_semi_honest(io_context, "arithmetic XOR of bits")?;
     let mut d = x * y + io_context.rngs.rand.masking_field_element::<F>();
     d.double_in_place();
     let e = x.a + y.a;
@@ -665,6 +1123,7 @@ pub(crate) fn arithmetic_xor_many<F: PrimeField, N: Rep3Network>(
     io_context: &mut IoContext<N>,
 ) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
     debug_assert_eq!(x.len(), y.len());
+    malicious::ensure_semi_honest(io_context, "arithmetic XOR of bits")?;
 
     let mut a = Vec::with_capacity(x.len());
     for (x, y) in x.iter().zip(y.iter()) {
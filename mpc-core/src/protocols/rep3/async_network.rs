@@ -0,0 +1,480 @@
+//! Rep3 Async Network
+//!
+//! This module contains an async (tokio) variant of the rep3 mpc network, an async [`IoContext`], and an adapter to run the existing blocking protocols on top of it. This allows embedding parties in async services without a dedicated blocking thread per MPC session.
+
+use std::{future::Future, sync::Arc, time::Instant};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use bytes::{Bytes, BytesMut};
+use eyre::{bail, eyre, Report};
+use mpc_net::{
+    abort::AbortReason, channel::ChannelHandle, config::NetworkConfig, stats::NetworkStats,
+    MpcNetworkHandler,
+};
+use tokio::runtime::Handle;
+
+use super::{
+    id::PartyID,
+    network::{IoContext, Rep3Network},
+    rngs::Rep3CorrelatedRngSeeds,
+    IoResult,
+};
+use crate::RngType;
+use rand::{Rng, SeedableRng};
+
+/// Serializes the data to be sent over the network.
+pub(crate) fn serialize<F: CanonicalSerialize + ?Sized>(data: &F) -> std::io::Result<Bytes> {
+    let size = data.serialized_size(ark_serialize::Compress::No);
+    let mut ser_data = Vec::with_capacity(size);
+    data.serialize_uncompressed(&mut ser_data)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    Ok(Bytes::from(ser_data))
+}
+
+/// Deserializes data received over the network.
+pub(crate) fn deserialize_many<F: CanonicalDeserialize>(data: &[u8]) -> std::io::Result<Vec<F>> {
+    Vec::<F>::deserialize_uncompressed(data)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Takes the single element out of the received vector.
+fn single<F>(mut res: Vec<F>) -> std::io::Result<F> {
+    if res.len() != 1 {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Expected 1 element, got more",
+        ))
+    } else {
+        //we checked that there is really one element
+        Ok(res.pop().unwrap())
+    }
+}
+
+/// This trait defines the async network interface for the REP3 protocol. It mirrors [`Rep3Network`], but only the transmission of bytes has to be implemented, the (de)serialization is done by the default implementations. The data is serialized before the returned futures are created, so they do not borrow it.
+pub trait Rep3AsyncNetwork: Send {
+    /// Returns the id of the party. The id is in the range 0 <= id < 3
+    fn get_id(&self) -> PartyID;
+
+    /// Sends bytes over the network to the target party.
+    fn send_bytes(
+        &mut self,
+        target: PartyID,
+        data: Bytes,
+    ) -> impl Future<Output = std::io::Result<()>> + Send;
+
+    /// Receives bytes over the network from the party with the given id.
+    fn recv_bytes(
+        &mut self,
+        from: PartyID,
+    ) -> impl Future<Output = std::io::Result<BytesMut>> + Send;
+
+    /// Fork the network into two separate instances with their own connections
+    fn fork(&mut self) -> impl Future<Output = std::io::Result<Self>> + Send
+    where
+        Self: Sized;
+
+    /// Returns a snapshot of the communication statistics of the party, which are shared by all forks of the network. The default implementation does not record any statistics.
+    fn network_stats(&self) -> NetworkStats {
+        NetworkStats::default()
+    }
+
+    /// Aborts the protocol with the given reason, such that the pending and future receives of all parties fail with the [`Abort`](mpc_net::abort::Abort). The default implementation does not propagate aborts.
+    fn abort(&mut self, _reason: AbortReason) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+
+    /// Sends data to the target party.
+    fn send<F: CanonicalSerialize>(
+        &mut self,
+        target: PartyID,
+        data: F,
+    ) -> impl Future<Output = std::io::Result<()>> + Send {
+        let data = serialize(&[data][..]);
+        async move { self.send_bytes(target, data?).await }
+    }
+
+    /// Sends a vector of data to the target party.
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: PartyID,
+        data: &[F],
+    ) -> impl Future<Output = std::io::Result<()>> + Send {
+        let data = serialize(data);
+        async move { self.send_bytes(target, data?).await }
+    }
+
+    /// Sends data to the party with id = next_id (i.e., my_id + 1 mod 3).
+    fn send_next<F: CanonicalSerialize>(
+        &mut self,
+        data: F,
+    ) -> impl Future<Output = std::io::Result<()>> + Send {
+        self.send(self.get_id().next_id(), data)
+    }
+
+    /// Sends a vector data to the party with id = next_id (i.e., my_id + 1 mod 3).
+    fn send_next_many<F: CanonicalSerialize>(
+        &mut self,
+        data: &[F],
+    ) -> impl Future<Output = std::io::Result<()>> + Send {
+        self.send_many(self.get_id().next_id(), data)
+    }
+
+    /// Receives data from the party with the given id. This function has a default implementation for calling [Rep3AsyncNetwork::recv_many] and checking for the correct length of 1.
+    fn recv<F: CanonicalDeserialize>(
+        &mut self,
+        from: PartyID,
+    ) -> impl Future<Output = std::io::Result<F>> + Send {
+        async move { single(self.recv_many(from).await?) }
+    }
+
+    /// Receives a vector of data from the party with the given id.
+    fn recv_many<F: CanonicalDeserialize>(
+        &mut self,
+        from: PartyID,
+    ) -> impl Future<Output = std::io::Result<Vec<F>>> + Send {
+        async move { deserialize_many(&self.recv_bytes(from).await?[..]) }
+    }
+
+    /// Receives data from the party with the id = prev_id (i.e., my_id + 2 mod 3).
+    fn recv_prev<F: CanonicalDeserialize>(
+        &mut self,
+    ) -> impl Future<Output = std::io::Result<F>> + Send {
+        self.recv(self.get_id().prev_id())
+    }
+
+    /// Receives a vector of data from the party with the id = prev_id (i.e., my_id + 2 mod 3).
+    fn recv_prev_many<F: CanonicalDeserialize>(
+        &mut self,
+    ) -> impl Future<Output = std::io::Result<Vec<F>>> + Send {
+        self.recv_many(self.get_id().prev_id())
+    }
+
+    /// Sends `data` to the next party and receives from the previous party.
+    fn reshare<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: F,
+    ) -> impl Future<Output = std::io::Result<F>> + Send {
+        let data = serialize(&[data][..]);
+        async move {
+            let id = self.get_id();
+            self.send_bytes(id.next_id(), data?).await?;
+            single(deserialize_many(&self.recv_bytes(id.prev_id()).await?[..])?)
+        }
+    }
+
+    /// Perform multiple reshares with one networking round
+    fn reshare_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> impl Future<Output = std::io::Result<Vec<F>>> + Send {
+        let data = serialize(data);
+        async move {
+            let id = self.get_id();
+            self.send_bytes(id.next_id(), data?).await?;
+            deserialize_many(&self.recv_bytes(id.prev_id()).await?[..])
+        }
+    }
+
+    /// Broadcast data to the other two parties and receive data from them
+    fn broadcast_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> impl Future<Output = std::io::Result<(Vec<F>, Vec<F>)>> + Send {
+        let data = serialize(data);
+        async move {
+            let id = self.get_id();
+            let data = data?;
+            self.send_bytes(id.next_id(), data.clone()).await?;
+            self.send_bytes(id.prev_id(), data).await?;
+            // Only deserialize after receiving, such that the future does not hold an `F`
+            let recv_next = self.recv_bytes(id.next_id()).await?;
+            let recv_prev = self.recv_bytes(id.prev_id()).await?;
+            Ok((deserialize_many(&recv_prev)?, deserialize_many(&recv_next)?))
+        }
+    }
+}
+
+/// This struct can be used to facilitate async network communication for the REP3 MPC protocol. In contrast to [`Rep3MpcNet`](super::network::Rep3MpcNet), it does not own a runtime, but runs on the tokio runtime of the caller.
+#[derive(Debug)]
+pub struct Rep3AsyncMpcNet {
+    pub(crate) id: PartyID,
+    pub(crate) chan_next: ChannelHandle<Bytes, BytesMut>,
+    pub(crate) chan_prev: ChannelHandle<Bytes, BytesMut>,
+    pub(crate) net_handler: Arc<MpcNetworkHandler>,
+}
+
+impl Rep3AsyncMpcNet {
+    /// Takes a [NetworkConfig] struct and constructs the network interface. The network needs to contain exactly 3 parties with ids 0, 1, and 2.
+    pub async fn new(config: NetworkConfig) -> Result<Self, Report> {
+        if config.parties.len() != 3 {
+            bail!("REP3 protocol requires exactly 3 parties")
+        }
+        let id = PartyID::try_from(config.my_id)?;
+        let net_handler = MpcNetworkHandler::establish(config).await?;
+        let mut channels = net_handler.get_managed_byte_channels().await?;
+        let chan_next = channels
+            .remove(&id.next_id().into())
+            .ok_or(eyre!("no next channel found"))?;
+        let chan_prev = channels
+            .remove(&id.prev_id().into())
+            .ok_or(eyre!("no prev channel found"))?;
+        if !channels.is_empty() {
+            bail!("unexpected channels found")
+        }
+
+        Ok(Self {
+            id,
+            chan_next,
+            chan_prev,
+            net_handler: Arc::new(net_handler),
+        })
+    }
+
+    /// Gracefully shuts down the connections to the other parties. This also affects all forks of the network.
+    pub async fn shutdown(self) -> std::io::Result<()> {
+        self.net_handler.shutdown().await
+    }
+}
+
+impl Rep3AsyncNetwork for Rep3AsyncMpcNet {
+    fn get_id(&self) -> PartyID {
+        self.id
+    }
+
+    async fn send_bytes(&mut self, target: PartyID, data: Bytes) -> std::io::Result<()> {
+        let len = data.len();
+        if target == self.id.next_id() {
+            std::mem::drop(self.chan_next.send(data).await);
+        } else if target == self.id.prev_id() {
+            std::mem::drop(self.chan_prev.send(data).await);
+        } else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Cannot send to self",
+            ));
+        }
+        self.net_handler.stats().record_sent(target.into(), len);
+        Ok(())
+    }
+
+    async fn recv_bytes(&mut self, from: PartyID) -> std::io::Result<BytesMut> {
+        let recv = if from == self.id.prev_id() {
+            self.chan_prev.recv().await
+        } else if from == self.id.next_id() {
+            self.chan_next.recv().await
+        } else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Cannot recv from self",
+            ));
+        };
+        let start = Instant::now();
+        let handler = &self.net_handler;
+        let recv = async {
+            match handler.recv_timeout() {
+                Some(timeout) => tokio::time::timeout(timeout, recv).await.map_err(|_| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        format!("no message from party {} in time", from),
+                    )
+                }),
+                None => Ok(recv.await),
+            }
+        };
+        // an abort takes precedence over the message, such that all parties unwind
+        let data = tokio::select! {
+            biased;
+            abort = handler.wait_for_abort() => Err(abort.into()),
+            data = recv => {
+                if data.is_err() {
+                    // let the other parties fail right away instead of after their own timeout
+                    let _ = handler.abort(AbortReason::Timeout).await;
+                }
+                data
+            }
+        }?;
+        let data = data.map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::BrokenPipe, "receive channel end died")
+        })??;
+        self.net_handler
+            .stats()
+            .record_received(from.into(), data.len(), start.elapsed());
+        Ok(data)
+    }
+
+    async fn fork(&mut self) -> std::io::Result<Self> {
+        let id = self.id;
+        let net_handler = Arc::clone(&self.net_handler);
+        let mut channels = net_handler.get_managed_byte_channels().await?;
+
+        let chan_next = channels
+            .remove(&id.next_id().into())
+            .expect("to find next channel");
+        let chan_prev = channels
+            .remove(&id.prev_id().into())
+            .expect("to find prev channel");
+        if !channels.is_empty() {
+            panic!("unexpected channels found")
+        }
+
+        Ok(Self {
+            id,
+            net_handler,
+            chan_next,
+            chan_prev,
+        })
+    }
+
+    fn network_stats(&self) -> NetworkStats {
+        self.net_handler.stats().snapshot()
+    }
+
+    async fn abort(&mut self, reason: AbortReason) {
+        // the abort is recorded even if it cannot be sent to all parties
+        let _ = self.net_handler.abort(reason).await;
+    }
+}
+
+/// This struct adapts a [`Rep3AsyncNetwork`] to the blocking [`Rep3Network`] by blocking on the tokio runtime given by the [`Handle`]. Since blocking inside of an async task panics, it may only be used outside of the runtime, e.g., in [`tokio::task::spawn_blocking`] (see [`AsyncIoContext::run_blocking`]).
+#[derive(Debug)]
+pub struct BlockingRep3Network<N: Rep3AsyncNetwork> {
+    inner: N,
+    runtime: Handle,
+}
+
+impl<N: Rep3AsyncNetwork> BlockingRep3Network<N> {
+    /// Wraps the async network, blocking on the given runtime.
+    pub fn new(inner: N, runtime: Handle) -> Self {
+        Self { inner, runtime }
+    }
+
+    /// Returns the wrapped async network.
+    pub fn into_inner(self) -> N {
+        self.inner
+    }
+}
+
+impl<N: Rep3AsyncNetwork> Rep3Network for BlockingRep3Network<N> {
+    fn get_id(&self) -> PartyID {
+        self.inner.get_id()
+    }
+
+    fn reshare_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<Vec<F>> {
+        self.runtime.block_on(self.inner.reshare_many(data))
+    }
+
+    fn broadcast_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<(Vec<F>, Vec<F>)> {
+        self.runtime.block_on(self.inner.broadcast_many(data))
+    }
+
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: PartyID,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        self.runtime.block_on(self.inner.send_many(target, data))
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(&mut self, from: PartyID) -> std::io::Result<Vec<F>> {
+        self.runtime.block_on(self.inner.recv_many(from))
+    }
+
+    fn fork(&mut self) -> std::io::Result<Self> {
+        let inner = self.runtime.block_on(self.inner.fork())?;
+        Ok(Self::new(inner, self.runtime.clone()))
+    }
+
+    fn network_stats(&self) -> NetworkStats {
+        self.inner.network_stats()
+    }
+
+    fn abort(&mut self, reason: AbortReason) {
+        self.runtime.block_on(self.inner.abort(reason))
+    }
+}
+
+/// The async variant of the [`IoContext`]. It holds the network and the correlated randomness of a party in between the computations, which are executed with the blocking protocols in [`Self::run_blocking`]. All functions have to be called within a tokio runtime.
+pub struct AsyncIoContext<N: Rep3AsyncNetwork> {
+    inner: IoContext<BlockingRep3Network<N>>,
+}
+
+impl<N: Rep3AsyncNetwork + 'static> AsyncIoContext<N> {
+    /// Construct a new [`AsyncIoContext`] with the given network (see [`IoContext::init`]).
+    pub async fn init(network: N) -> IoResult<Self> {
+        let network = BlockingRep3Network::new(network, Handle::current());
+        let inner = tokio::task::spawn_blocking(move || IoContext::init(network))
+            .await
+            .map_err(std::io::Error::other)??;
+        Ok(Self { inner })
+    }
+
+    /// Construct a new [`AsyncIoContext`] with the given network, whose correlated randomness was already set up in an offline phase (see [`IoContext::init_with_seeds`]).
+    pub fn init_with_seeds(network: N, seeds: &Rep3CorrelatedRngSeeds) -> Self {
+        let network = BlockingRep3Network::new(network, Handle::current());
+        Self {
+            inner: IoContext::init_with_seeds(network, seeds),
+        }
+    }
+
+    /// Returns the id of the party.
+    pub fn id(&self) -> PartyID {
+        self.inner.id
+    }
+
+    /// Returns a snapshot of the communication statistics of the party (see [`Rep3AsyncNetwork::network_stats`]).
+    pub fn network_stats(&self) -> NetworkStats {
+        self.inner.network_stats()
+    }
+
+    /// Aborts the protocol on all parties with the given reason (see [`Rep3AsyncNetwork::abort`]).
+    pub async fn abort(&mut self, reason: AbortReason) {
+        self.inner.network.inner.abort(reason).await
+    }
+
+    /// Returns the underlying async network, e.g., to send the results of a computation.
+    pub fn network(&mut self) -> &mut N {
+        &mut self.inner.network.inner
+    }
+
+    /// Construct a fork of the [`AsyncIoContext`] (see [`IoContext::fork`]). This fork can be used concurrently with its parent.
+    pub async fn fork(&mut self) -> IoResult<Self> {
+        let network = self.inner.network.inner.fork().await?;
+        let network = BlockingRep3Network::new(network, self.inner.network.runtime.clone());
+        let inner = IoContext {
+            id: self.inner.id,
+            rngs: self.inner.rngs.fork(),
+            rng: RngType::from_seed(self.inner.rng.gen()),
+            network,
+            a2b_type: self.inner.a2b_type,
+            a2b_auto_network: self.inner.a2b_auto_network,
+            security_level: self.inner.security_level,
+        };
+        Ok(Self { inner })
+    }
+
+    /// Runs the blocking computation `f`, e.g., a protocol from [`arithmetic`](super::arithmetic), on a thread of the blocking pool of tokio (see [`tokio::task::spawn_blocking`]) and returns its result together with the context for the next computation.
+    pub async fn run_blocking<T, F>(self, f: F) -> IoResult<(T, Self)>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut IoContext<BlockingRep3Network<N>>) -> T + Send + 'static,
+    {
+        let mut inner = self.inner;
+        tokio::task::spawn_blocking(move || {
+            let res = f(&mut inner);
+            (res, Self { inner })
+        })
+        .await
+        .map_err(std::io::Error::other)
+    }
+
+    /// Returns the blocking [`IoContext`], which may only be used outside of the runtime.
+    pub fn into_inner(self) -> IoContext<BlockingRep3Network<N>> {
+        self.inner
+    }
+}
@@ -11,6 +11,7 @@ use crate::protocols::rep3::{
     arithmetic::{self},
     conversion,
     id::PartyID,
+    malicious,
     network::Rep3Network,
 };
 
@@ -72,12 +73,25 @@ pub fn and<F: PrimeField, N: Rep3Network>(
     b: &BinaryShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BinaryShare<F>> {
-    debug_assert!(a.a.bits() <= u64::from(F::MODULUS_BIT_SIZE));
-    debug_assert!(b.a.bits() <= u64::from(F::MODULUS_BIT_SIZE));
-    let (mut mask, mask_b) = io_context
-        .rngs
-        .rand
-        .random_biguint(usize::try_from(F::MODULUS_BIT_SIZE).expect("u32 fits into usize"));
+    and_with_bitlen(
+        a,
+        b,
+        io_context,
+        usize::try_from(F::MODULUS_BIT_SIZE).expect("u32 fits into usize"),
+    )
+}
+
+/// Performs a bitwise AND operation on two shared values with up to `bitlen` bits, e.g., multiple packed values.
+pub(super) fn and_with_bitlen<F: PrimeField, N: Rep3Network>(
+    a: &BinaryShare<F>,
+    b: &BinaryShare<F>,
+    io_context: &mut IoContext<N>,
+    bitlen: usize,
+) -> IoResult<BinaryShare<F>> {
+    debug_assert!(a.a.bits() <= bitlen as u64);
+    debug_assert!(b.a.bits() <= bitlen as u64);
+    malicious::ensure_semi_honest(io_context, "AND of binary shares")?;
+    let (mut mask, mask_b) = io_context.rngs.rand.random_biguint(bitlen);
     mask ^= mask_b;
     let local_a = (a & b) ^ mask;
     let local_b = io_context.network.reshare(local_a.clone())?;
@@ -128,6 +142,7 @@ pub fn shift_l_public_by_shared<F: PrimeField, N: Rep3Network>(
     // This case is equivalent to a*2^b
     // Strategy: limit size of b to k bits
     // bit-decompose b into bits b_i
+    malicious::ensure_semi_honest(io_context, "shift by a shared value")?;
 
     // TODO: this sucks... we need something better here...
     let io_0 = io_context.fork()?;
@@ -206,6 +221,7 @@ pub fn open<F: PrimeField, N: Rep3Network>(
     a: &BinaryShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BigUint> {
+    malicious::ensure_semi_honest(io_context, "opening of binary shares")?;
     let c = io_context.network.reshare(a.b.clone())?;
     Ok(&a.a ^ &a.b ^ c)
 }
@@ -302,3 +318,60 @@ pub fn is_zero<F: PrimeField, N: Rep3Network>(
     // extract LSB
     Ok(x & BigUint::one())
 }
+
+/// Computes the same as [`is_zero`] for a vector of inputs. All inputs are packed into a single [`BinaryShare`] and share one AND tree, so the number of communication rounds does not depend on the number of inputs. The outputs are binary sharings of one bit each.
+pub fn is_zero_many<F: PrimeField, N: Rep3Network>(
+    x: &[BinaryShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<BinaryShare<F>>> {
+    if x.is_empty() {
+        return Ok(Vec::new());
+    }
+    let bit_len = F::MODULUS_BIT_SIZE as usize;
+    // one additional bit per slot for the padding
+    let width = bit_len + 1;
+    // The lowest `bits` bits of every slot
+    let slot_mask = |bits: usize| {
+        let slot = (BigUint::from(1u64) << bits) - BigUint::one();
+        (0..x.len()).fold(BigUint::ZERO, |acc, i| acc | (&slot << (i * width)))
+    };
+
+    // pack and negate
+    let mut packed = BinaryShare::zero_share();
+    for (i, x) in x.iter().enumerate() {
+        packed ^= x << (i * width);
+    }
+    let mut packed = packed ^ slot_mask(bit_len);
+
+    // do ands in a tree
+    let mut len = bit_len;
+    while len > 1 {
+        if len % 2 == 1 {
+            len += 1;
+            // pad with a 1 (= 1 xor 1 xor 1) in MSB position of each slot
+            for i in 0..x.len() {
+                packed.a.set_bit((i * width + len - 1) as u64, true);
+                packed.b.set_bit((i * width + len - 1) as u64, true);
+            }
+        }
+        len /= 2;
+        let mask = slot_mask(len);
+        let y = &packed >> len;
+        packed = and_with_bitlen(
+            &(&packed & &mask),
+            &(&y & &mask),
+            io_context,
+            x.len() * width,
+        )?;
+    }
+    // extract the LSB of each slot
+    Ok((0..x.len())
+        .map(|i| {
+            let lsb = (i * width) as u64;
+            BinaryShare::new(
+                BigUint::from(u8::from(packed.a.bit(lsb))),
+                BigUint::from(u8::from(packed.b.bit(lsb))),
+            )
+        })
+        .collect())
+}
@@ -3,9 +3,11 @@
 //! This module contains conversions between share types
 
 use super::{
-    arithmetic, detail,
+    arithmetic, binary, detail,
     id::PartyID,
+    malicious,
     network::{IoContext, Rep3Network},
+    preprocessing::Rep3PreprocessedBits,
     yao::{
         self, circuits::GarbledCircuits, evaluator::Rep3Evaluator, garbler::Rep3Garbler,
         streaming_evaluator::StreamingRep3Evaluator, streaming_garbler::StreamingRep3Garbler,
@@ -20,15 +22,64 @@ use num_bigint::BigUint;
 use serde::{Deserialize, Serialize};
 
 /// This enum defines which arithmetic-to-binary (and vice-versa) implementation of [ABY3](https://eprint.iacr.org/2018/403.pdf) is used.
+///
+/// The same choice is made for the batched comparisons (e.g., [`arithmetic::ge_many`](super::arithmetic::ge_many)), bit decompositions ([`arithmetic::to_bits_many`](super::arithmetic::to_bits_many)) and range checks ([`arithmetic::range_check_vec`](super::arithmetic::range_check_vec)), which are either evaluated in a garbled circuit or GMW-style on the binary sharings. The integer divisions in [`fixed_point`](super::fixed_point) always use garbled circuits.
 #[derive(
     Debug, Clone, Copy, Default, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash,
 )]
 pub enum A2BType {
-    /// The arithmetic-to-binary conversion is directly done using "Bit Decomposition", while the binary-to-arithmetic conversion is done using "Bit Composition". This process has a larger number of communication rounds with less communicated bytes.
+    /// The arithmetic-to-binary conversion is directly done using "Bit Decomposition", while the binary-to-arithmetic conversion is done using "Bit Composition". The adders are evaluated GMW-style on the replicated binary shares, i.e., each AND gate requires interaction. This process has a larger number of communication rounds with less communicated bytes.
     Direct,
     /// The arithmetic-to-binary conversion is done by "Arithmetic to Yao" followed by "Yao to Binary", while the binary-to-arithmetic conversion is done using "Binary to Yao" followed by "Yao to Arithmetic". This process has a low number of communication rounds with more communicated bytes.
     #[default]
     Yao,
+    /// Chooses between [`A2BType::Direct`] and [`A2BType::Yao`] for each conversion, depending on the number and the bit size of the converted values and the network the parties are connected with (see [`A2BType::select`] and [`A2BAutoNetwork`]). Garbled circuits are used for small batches, where the number of rounds dominates the runtime, while the GMW-style conversion is used for large batches, where the communicated bytes dominate.
+    Auto,
+}
+
+/// The network assumed by the heuristic of [`A2BType::Auto`]. Defaults to 1 ms latency and 1 Gbit/s bandwidth.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub struct A2BAutoNetwork {
+    /// The latency between the parties in microseconds.
+    pub latency_us: u64,
+    /// The bandwidth between the parties in Mbit/s.
+    pub bandwidth_mbit: u64,
+}
+
+impl Default for A2BAutoNetwork {
+    fn default() -> Self {
+        Self {
+            latency_us: 1_000,
+            bandwidth_mbit: 1_000,
+        }
+    }
+}
+
+impl A2BType {
+    /// Returns the conversion used for a batch of `num` values with `bitlen` bits. This is `self`, unless `self` is [`A2BType::Auto`], in which case the conversion with the lower estimated runtime on the given network is returned.
+    ///
+    /// The GMW-style conversion needs about log2(bitlen) + 2 rounds, in which about bitlen bits are sent per value. The garbled circuit needs a constant number of rounds, but the garbled adder and the wire labels amount to about 144 bytes per bit and value.
+    pub fn select(self, num: usize, bitlen: usize, network: &A2BAutoNetwork) -> Self {
+        match self {
+            A2BType::Auto => {
+                let latency_ms = network.latency_us as f64 / 1_000.0;
+                // in bytes per ms
+                let bandwidth = network.bandwidth_mbit.max(1) as f64 * 125.0;
+                let num = num as f64;
+                let bitlen = bitlen.max(1) as f64;
+                let direct_rounds = bitlen.log2().ceil() + 2.0;
+                let direct =
+                    direct_rounds * latency_ms + num * bitlen * direct_rounds / 8.0 / bandwidth;
+                let yao = 3.0 * latency_ms + num * bitlen * 144.0 / bandwidth;
+                if direct < yao {
+                    A2BType::Direct
+                } else {
+                    A2BType::Yao
+                }
+            }
+            a2b_type => a2b_type,
+        }
+    }
 }
 
 /// Depending on the `A2BType` of the io_context, this function selects the appropriate implementation for the arithmetic-to-binary conversion.
@@ -36,28 +87,68 @@ pub fn a2b_selector<F: PrimeField, N: Rep3Network>(
     x: Rep3PrimeFieldShare<F>,
     io_context: &mut IoContext<N>,
 ) -> std::io::Result<Rep3BigUintShare<F>> {
-    match io_context.a2b_type {
-        A2BType::Direct => a2b(x, io_context),
+    malicious::ensure_semi_honest(io_context, "a2b_selector")?;
+    match io_context.select_a2b_type(1, F::MODULUS_BIT_SIZE as usize) {
+        A2BType::Direct | A2BType::Auto => a2b(x, io_context),
         A2BType::Yao => a2y2b(x, io_context),
     }
 }
 
+/// Depending on the `A2BType` of the io_context, this function selects the appropriate implementation for the arithmetic-to-binary conversion of a vector of values.
+pub fn a2b_selector_many<F: PrimeField, N: Rep3Network>(
+    x: &[Rep3PrimeFieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> std::io::Result<Vec<Rep3BigUintShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "a2b_selector_many")?;
+    match io_context.select_a2b_type(x.len(), F::MODULUS_BIT_SIZE as usize) {
+        A2BType::Direct | A2BType::Auto => a2b_many(x, io_context),
+        A2BType::Yao => a2y2b_many(x, io_context),
+    }
+}
+
 /// Depending on the `A2BType` of the io_context, this function selects the appropriate implementation for the binary-to-arithmetic conversion.
 pub fn b2a_selector<F: PrimeField, N: Rep3Network>(
     x: &Rep3BigUintShare<F>,
     io_context: &mut IoContext<N>,
 ) -> std::io::Result<Rep3PrimeFieldShare<F>> {
-    match io_context.a2b_type {
-        A2BType::Direct => b2a(x, io_context),
+    malicious::ensure_semi_honest(io_context, "b2a_selector")?;
+    match io_context.select_a2b_type(1, F::MODULUS_BIT_SIZE as usize) {
+        A2BType::Direct | A2BType::Auto => b2a(x, io_context),
         A2BType::Yao => b2y2a(x, io_context),
     }
 }
 
+/// Selects the binary-to-arithmetic conversion used by a single call to [b2a_selector_many].
+#[derive(Debug)]
+pub enum B2AMethod<'a, F: PrimeField> {
+    /// Use the implementation selected by the `A2BType` of the io_context (see [b2a_selector]).
+    Context,
+    /// Use bit injection with the given preprocessed daBits (see [b2a_many_with_dabits]). Requires only a single communication round, but consumes `bitsize` daBits per value.
+    DaBits(&'a mut Rep3PreprocessedBits<F>),
+}
+
+/// Transforms a vector of binary sharings of values in [0, 2^bitsize) into arithmetic sharings using the conversion selected by `method`. This allows to use the preprocessed daBits only for the conversions where the saved rounds and bandwidth matter, e.g., in circuits that frequently switch between the arithmetic and the binary domain.
+pub fn b2a_selector_many<F: PrimeField, N: Rep3Network>(
+    x: &[Rep3BigUintShare<F>],
+    bitsize: usize,
+    method: B2AMethod<F>,
+    io_context: &mut IoContext<N>,
+) -> std::io::Result<Vec<Rep3PrimeFieldShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "b2a_selector_many")?;
+    match method {
+        B2AMethod::Context => x.iter().map(|x| b2a_selector(x, io_context)).collect(),
+        B2AMethod::DaBits(preprocessed) => {
+            b2a_many_with_dabits(x, bitsize, preprocessed, io_context)
+        }
+    }
+}
+
 /// Transforms the replicated shared value x from an arithmetic sharing to a binary sharing. I.e., x = x_1 + x_2 + x_3 gets transformed into x = x'_1 xor x'_2 xor x'_3.
 pub fn a2b<F: PrimeField, N: Rep3Network>(
     x: Rep3PrimeFieldShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<Rep3BigUintShare<F>> {
+    malicious::ensure_semi_honest(io_context, "a2b")?;
     let mut x01 = Rep3BigUintShare::zero_share();
     let mut x2 = Rep3BigUintShare::zero_share();
 
@@ -90,6 +181,63 @@ pub fn a2b<F: PrimeField, N: Rep3Network>(
     detail::low_depth_binary_add_mod_p::<F, N>(&x01, &x2, io_context, F::MODULUS_BIT_SIZE as usize)
 }
 
+/// Transforms a vector of replicated shared values from an arithmetic sharing to a binary sharing using "Bit Decomposition". In contrast to calling [a2b] for every element, all values are packed into a single binary share and added by one parallel-prefix (Kogge-Stone) adder, so the number of communication rounds is logarithmic in the bit size of the field and does not depend on the number of values.
+pub fn a2b_many<F: PrimeField, N: Rep3Network>(
+    x: &[Rep3PrimeFieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3BigUintShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "a2b_many")?;
+    if x.is_empty() {
+        return Ok(Vec::new());
+    }
+    let bitlen = F::MODULUS_BIT_SIZE as usize;
+    // one bit for the carry of the addition and one for the overflow of the subtraction of p
+    let width = bitlen + 2;
+
+    let mut x01 = Vec::with_capacity(x.len());
+    let mut x2 = Vec::with_capacity(x.len());
+    for x in x {
+        let mut x01_ = Rep3BigUintShare::<F>::zero_share();
+        let mut x2_ = Rep3BigUintShare::<F>::zero_share();
+
+        let (mut r, r2) = io_context.rngs.rand.random_biguint(bitlen);
+        r ^= r2;
+
+        match io_context.id {
+            PartyID::ID0 => {
+                x01_.a = r;
+                x2_.b = x.b.into();
+            }
+            PartyID::ID1 => {
+                let val: BigUint = (x.a + x.b).into();
+                x01_.a = val ^ r;
+            }
+            PartyID::ID2 => {
+                x01_.a = r;
+                x2_.a = x.a.into();
+            }
+        }
+        x01.push(x01_);
+        x2.push(x2_);
+    }
+    let mut x01 = detail::pack(&x01, width);
+    let x2 = detail::pack(&x2, width);
+
+    // reshare x01
+    io_context.network.send_next(x01.a.to_owned())?;
+    x01.b = io_context.network.recv_prev()?;
+
+    let res = detail::low_depth_binary_add_mod_p_packed::<F, N>(
+        &x01,
+        &x2,
+        io_context,
+        bitlen,
+        x.len(),
+        width,
+    )?;
+    Ok(detail::unpack(&res, x.len(), width, bitlen))
+}
+
 /// Transforms the replicated shared value x from a binary sharing to an arithmetic sharing. I.e., x = x_1 xor x_2 xor x_3 gets transformed into x = x'_1 + x'_2 + x'_3. This implementation currently works only for a binary sharing of a valid field element, i.e., x = x_1 xor x_2 xor x_3 < p.
 ///
 /// Keep in mind: Only works if the input is actually a binary sharing of a valid field element
@@ -98,6 +246,7 @@ pub fn b2a<F: PrimeField, N: Rep3Network>(
     x: &Rep3BigUintShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<Rep3PrimeFieldShare<F>> {
+    malicious::ensure_semi_honest(io_context, "b2a")?;
     let mut y = Rep3BigUintShare::zero_share();
     let mut res = Rep3PrimeFieldShare::zero_share();
 
@@ -167,6 +316,7 @@ pub fn bit_inject<F: PrimeField, N: Rep3Network>(
     x: &Rep3BigUintShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<Rep3PrimeFieldShare<F>> {
+    malicious::ensure_semi_honest(io_context, "bit_inject")?;
     // standard bit inject
     assert!(x.a.bits() <= 1);
 
@@ -199,6 +349,7 @@ pub fn bit_inject_many<F: PrimeField, N: Rep3Network>(
     x: &[Rep3BigUintShare<F>],
     io_context: &mut IoContext<N>,
 ) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "bit_inject_many")?;
     // standard bit inject
     assert!(x.iter().all(|a| a.a.bits() <= 1));
 
@@ -232,19 +383,126 @@ pub fn bit_inject_many<F: PrimeField, N: Rep3Network>(
     Ok(e)
 }
 
+/// Translates a vector of shared bits into a vector of arithmetic sharings of the same bits by consuming one preprocessed daBit per bit (see [`Rep3PreprocessedBits`]). The bits are masked with the binary parts of the daBits and opened, such that the arithmetic result can be computed locally from the arithmetic parts of the daBits. Thus, in contrast to [bit_inject_many], only a single communication round is required.
+pub fn bit_inject_many_with_dabits<F: PrimeField, N: Rep3Network>(
+    x: &[Rep3BigUintShare<F>],
+    preprocessed: &mut Rep3PreprocessedBits<F>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "bit_inject_many_with_dabits")?;
+    assert!(x.iter().all(|a| a.a.bits() <= 1));
+    if x.is_empty() {
+        return Ok(Vec::new());
+    }
+    let dabits = preprocessed.take_dabits(x.len())?;
+
+    // open all masked bits at once
+    let mut masked = Rep3BigUintShare::zero_share();
+    for (i, (x, dabit)) in izip!(x, &dabits).enumerate() {
+        masked ^= &(x ^ &dabit.binary) << i;
+    }
+    let masked = binary::open(&masked, io_context)?;
+
+    // x = c xor r = c + r - 2cr
+    Ok(dabits
+        .into_iter()
+        .enumerate()
+        .map(|(i, dabit)| {
+            if masked.bit(i as u64) {
+                arithmetic::sub_public_by_shared(F::one(), dabit.arithmetic, io_context.id)
+            } else {
+                dabit.arithmetic
+            }
+        })
+        .collect())
+}
+
+/// Transforms a vector of binary sharings of values in [0, 2^bitsize) into arithmetic sharings by consuming `bitsize` preprocessed daBits per value (see [`Rep3PreprocessedBits`]). All bits are injected at once using [bit_inject_many_with_dabits] and composed locally, so only a single communication round is required.
+pub fn b2a_many_with_dabits<F: PrimeField, N: Rep3Network>(
+    x: &[Rep3BigUintShare<F>],
+    bitsize: usize,
+    preprocessed: &mut Rep3PreprocessedBits<F>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "b2a_many_with_dabits")?;
+    assert!(
+        bitsize > 0 && bitsize <= F::MODULUS_BIT_SIZE as usize,
+        "bitsize must be in [1, {}]",
+        F::MODULUS_BIT_SIZE
+    );
+    let bits = x
+        .iter()
+        .flat_map(|x| {
+            (0..bitsize as u64).map(|i| {
+                Rep3BigUintShare::new(
+                    BigUint::from(u8::from(x.a.bit(i))),
+                    BigUint::from(u8::from(x.b.bit(i))),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    let bits = bit_inject_many_with_dabits(&bits, preprocessed, io_context)?;
+    Ok(bits
+        .chunks_exact(bitsize)
+        .map(|bits| {
+            bits.iter()
+                .rev()
+                .fold(Rep3PrimeFieldShare::zero_share(), |acc, bit| {
+                    acc + acc + *bit
+                })
+        })
+        .collect())
+}
+
 /// Transforms the replicated shared value x from an arithmetic sharing to a yao sharing. I.e., x = x_1 + x_2 + x_3 gets transformed into wires, such that the garbler have keys (k_0, delta) for each bit of x, while the evaluator has k_x = k_0 xor delta * x.
 pub fn a2y<F: PrimeField, N: Rep3Network>(
     x: Rep3PrimeFieldShare<F>,
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "a2y")?;
     let [x01, x2] = yao::joint_input_arithmetic_added(x, delta, io_context)?;
 
     let converted = match io_context.id {
         PartyID::ID0 => {
             let mut evaluator = Rep3Evaluator::new(io_context);
             evaluator.receive_circuit()?;
-            let res = GarbledCircuits::adder_mod_p::<_, F>(&mut evaluator, &x01, &x2);
+            let res = GarbledCircuits::adder_mod_p_cached::<_, F>(&mut evaluator, &x01, &x2);
+            GCUtils::garbled_circuits_error(res)?
+        }
+        PartyID::ID1 | PartyID::ID2 => {
+            let delta = match delta {
+                Some(delta) => delta,
+                None => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "No delta provided",
+                ))?,
+            };
+            let mut garbler = Rep3Garbler::new_with_delta(io_context, delta);
+            let res = GarbledCircuits::adder_mod_p_cached::<_, F>(&mut garbler, &x01, &x2);
+            let res = GCUtils::garbled_circuits_error(res)?;
+            garbler.send_circuit()?;
+            res
+        }
+    };
+
+    Ok(converted)
+}
+
+/// Transforms a vector of replicated shared values from an arithmetic sharing to a yao sharing in a single garbled circuit. The wires of the i-th value are the i-th chunk of [F::MODULUS_BIT_SIZE] wires of the resulting bundle.
+pub fn a2y_many<F: PrimeField, N: Rep3Network>(
+    x: &[Rep3PrimeFieldShare<F>],
+    delta: Option<WireMod2>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "a2y_many")?;
+    let [x01, x2] = yao::joint_input_arithmetic_added_many(x, delta, io_context)?;
+
+    let converted = match io_context.id {
+        PartyID::ID0 => {
+            let mut evaluator = Rep3Evaluator::new(io_context);
+            evaluator.receive_circuit()?;
+            let res = GarbledCircuits::adder_mod_p_many::<_, F>(&mut evaluator, &x01, &x2);
             GCUtils::garbled_circuits_error(res)?
         }
         PartyID::ID1 | PartyID::ID2 => {
@@ -256,7 +514,7 @@ pub fn a2y<F: PrimeField, N: Rep3Network>(
                 ))?,
             };
             let mut garbler = Rep3Garbler::new_with_delta(io_context, delta);
-            let res = GarbledCircuits::adder_mod_p::<_, F>(&mut garbler, &x01, &x2);
+            let res = GarbledCircuits::adder_mod_p_many::<_, F>(&mut garbler, &x01, &x2);
             let res = GCUtils::garbled_circuits_error(res)?;
             garbler.send_circuit()?;
             res
@@ -266,18 +524,55 @@ pub fn a2y<F: PrimeField, N: Rep3Network>(
     Ok(converted)
 }
 
+/// Transforms a vector of replicated shared values from an arithmetic sharing to a yao sharing in a single garbled circuit, like [a2y_many], but uses the Streaming Garbler/Evaluator. All values are packed into one garbled execution, such that the hash of the circuit is sent and checked only once.
+pub fn a2y_many_streaming<F: PrimeField, N: Rep3Network>(
+    x: &[Rep3PrimeFieldShare<F>],
+    delta: Option<WireMod2>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "a2y_many_streaming")?;
+    let [x01, x2] = yao::joint_input_arithmetic_added_many(x, delta, io_context)?;
+
+    let converted = match io_context.id {
+        PartyID::ID0 => {
+            let mut evaluator = StreamingRep3Evaluator::new(io_context);
+            let res = GarbledCircuits::adder_mod_p_many::<_, F>(&mut evaluator, &x01, &x2);
+            let res = GCUtils::garbled_circuits_error(res)?;
+            evaluator.receive_hash()?;
+            res
+        }
+        PartyID::ID1 | PartyID::ID2 => {
+            let delta = match delta {
+                Some(delta) => delta,
+                None => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "No delta provided",
+                ))?,
+            };
+            let mut garbler = StreamingRep3Garbler::new_with_delta(io_context, delta);
+            let res = GarbledCircuits::adder_mod_p_many::<_, F>(&mut garbler, &x01, &x2);
+            let res = GCUtils::garbled_circuits_error(res)?;
+            garbler.send_hash()?;
+            res
+        }
+    };
+
+    Ok(converted)
+}
+
 /// Transforms the replicated shared value x from an arithmetic sharing to a yao sharing. I.e., x = x_1 + x_2 + x_3 gets transformed into wires, such that the garbler have keys (k_0, delta) for each bit of x, while the evaluator has k_x = k_0 xor delta * x. Uses the Streaming Garbler/Evaluator.
 pub fn a2y_streaming<F: PrimeField, N: Rep3Network>(
     x: Rep3PrimeFieldShare<F>,
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "a2y_streaming")?;
     let [x01, x2] = yao::joint_input_arithmetic_added(x, delta, io_context)?;
 
     let converted = match io_context.id {
         PartyID::ID0 => {
             let mut evaluator = StreamingRep3Evaluator::new(io_context);
-            let res = GarbledCircuits::adder_mod_p::<_, F>(&mut evaluator, &x01, &x2);
+            let res = GarbledCircuits::adder_mod_p_cached::<_, F>(&mut evaluator, &x01, &x2);
             let res = GCUtils::garbled_circuits_error(res)?;
             evaluator.receive_hash()?;
             res
@@ -291,7 +586,7 @@ pub fn a2y_streaming<F: PrimeField, N: Rep3Network>(
                 ))?,
             };
             let mut garbler = StreamingRep3Garbler::new_with_delta(io_context, delta);
-            let res = GarbledCircuits::adder_mod_p::<_, F>(&mut garbler, &x01, &x2);
+            let res = GarbledCircuits::adder_mod_p_cached::<_, F>(&mut garbler, &x01, &x2);
             let res = GCUtils::garbled_circuits_error(res)?;
             garbler.send_hash()?;
             res
@@ -301,8 +596,47 @@ pub fn a2y_streaming<F: PrimeField, N: Rep3Network>(
     Ok(converted)
 }
 
+/// Transforms the replicated shared value x from an arithmetic sharing to a yao sharing. I.e., x = x_1 + x_2 + x_3 gets transformed into wires, such that the garbler have keys (k_0, delta) for each bit of x, while the evaluator has k_x = k_0 xor delta * x. The garbled circuit is pipelined in chunks of `chunk_size` blocks (see [Rep3Garbler::with_chunk_size]), such that the evaluator starts evaluating while the circuit is still being garbled and transmitted.
+pub fn a2y_pipelined<F: PrimeField, N: Rep3Network>(
+    x: Rep3PrimeFieldShare<F>,
+    delta: Option<WireMod2>,
+    chunk_size: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "a2y_pipelined")?;
+    let [x01, x2] = yao::joint_input_arithmetic_added(x, delta, io_context)?;
+
+    let converted = match io_context.id {
+        PartyID::ID0 => {
+            let mut evaluator = Rep3Evaluator::new(io_context).with_chunk_size(chunk_size);
+            evaluator.receive_circuit()?;
+            let res = GarbledCircuits::adder_mod_p_cached::<_, F>(&mut evaluator, &x01, &x2);
+            let res = GCUtils::garbled_circuits_error(res)?;
+            evaluator.finish_circuit()?;
+            res
+        }
+        PartyID::ID1 | PartyID::ID2 => {
+            let delta = match delta {
+                Some(delta) => delta,
+                None => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "No delta provided",
+                ))?,
+            };
+            let mut garbler =
+                Rep3Garbler::new_with_delta(io_context, delta).with_chunk_size(chunk_size);
+            let res = GarbledCircuits::adder_mod_p_cached::<_, F>(&mut garbler, &x01, &x2);
+            let res = GCUtils::garbled_circuits_error(res)?;
+            garbler.send_circuit()?;
+            res
+        }
+    };
+
+    Ok(converted)
+}
+
 macro_rules! y2a_impl_p1 {
-    ($garbler:ty,$x:expr,$delta:expr,$io_context:expr,$res:expr) => {{
+    ($garbler:ty,$x:expr,$delta:expr,$io_context:expr,$res:expr$(,$chunk_size:expr)?) => {{
         let delta = match $delta {
             Some(delta) => delta,
             None => Err(std::io::Error::new(
@@ -315,8 +649,8 @@ macro_rules! y2a_impl_p1 {
         $res.a = (k2.0 + k2.1 + k2.2).neg();
         let x23 = yao::input_field_id2::<F, _>(None, None, $io_context)?;
 
-        let mut garbler = <$garbler>::new_with_delta($io_context, delta);
-        let x1 = GarbledCircuits::adder_mod_p::<_, F>(&mut garbler, &$x, &x23);
+        let mut garbler = <$garbler>::new_with_delta($io_context, delta)$(.with_chunk_size($chunk_size))?;
+        let x1 = GarbledCircuits::adder_mod_p_cached::<_, F>(&mut garbler, &$x, &x23);
         let x1 = GCUtils::garbled_circuits_error(x1)?;
         let x1 = garbler.output_to_id0_and_id1(x1.wires())?;
         let x1 = match x1 {
@@ -331,7 +665,7 @@ macro_rules! y2a_impl_p1 {
 }
 
 macro_rules! y2a_impl_p2 {
-    ($garbler:ty,$x:expr,$delta:expr,$io_context:expr,$res:expr) => {{
+    ($garbler:ty,$x:expr,$delta:expr,$io_context:expr,$res:expr$(,$chunk_size:expr)?) => {{
         let delta = match $delta {
             Some(delta) => delta,
             None => Err(std::io::Error::new(
@@ -349,8 +683,8 @@ macro_rules! y2a_impl_p2 {
         $res.b = k2_comp.neg();
         let x23 = yao::input_field_id2(x23, Some(delta), $io_context)?;
 
-        let mut garbler = <$garbler>::new_with_delta($io_context, delta);
-        let x1 = GarbledCircuits::adder_mod_p::<_, F>(&mut garbler, &$x, &x23);
+        let mut garbler = <$garbler>::new_with_delta($io_context, delta)$(.with_chunk_size($chunk_size))?;
+        let x1 = GarbledCircuits::adder_mod_p_cached::<_, F>(&mut garbler, &$x, &x23);
         let x1 = GCUtils::garbled_circuits_error(x1)?;
         let x1 = garbler.output_to_id0_and_id1(x1.wires())?;
         if x1.is_some() {
@@ -371,6 +705,7 @@ pub fn y2a<F: PrimeField, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<Rep3PrimeFieldShare<F>> {
+    malicious::ensure_semi_honest(io_context, "y2a")?;
     let mut res = Rep3PrimeFieldShare::zero_share();
 
     match io_context.id {
@@ -381,7 +716,7 @@ pub fn y2a<F: PrimeField, N: Rep3Network>(
 
             let mut evaluator = Rep3Evaluator::new(io_context);
             evaluator.receive_circuit()?;
-            let x1 = GarbledCircuits::adder_mod_p::<_, F>(&mut evaluator, &x, &x23);
+            let x1 = GarbledCircuits::adder_mod_p_cached::<_, F>(&mut evaluator, &x, &x23);
             let x1 = GCUtils::garbled_circuits_error(x1)?;
             let x1 = evaluator.output_to_id0_and_id1(x1.wires())?;
             res.a = GCUtils::bits_to_field(&x1)?;
@@ -397,6 +732,112 @@ pub fn y2a<F: PrimeField, N: Rep3Network>(
     Ok(res)
 }
 
+/// Transforms a vector of shared values from a yao sharing to an arithmetic sharing in a single garbled circuit (see [y2a]). The wires of the i-th value are the i-th chunk of [F::MODULUS_BIT_SIZE] wires of `x`, as returned by [a2y_many]. In contrast to calling [y2a] for every value, the garbled circuit is sent and checked only once.
+///
+/// Keep in mind: Only works if the inputs are actually binary sharings of valid field elements
+pub fn y2a_many<F: PrimeField, N: Rep3Network>(
+    x: BinaryBundle<WireMod2>,
+    delta: Option<WireMod2>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "y2a_many")?;
+    let n_bits = F::MODULUS_BIT_SIZE as usize;
+    if x.size() % n_bits != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "During y2a_many: Invalid number of wires",
+        ));
+    }
+    let n_inputs = x.size() / n_bits;
+    let mut res = vec![Rep3PrimeFieldShare::zero_share(); n_inputs];
+    if n_inputs == 0 {
+        return Ok(res);
+    }
+
+    match io_context.id {
+        PartyID::ID0 => {
+            for res in res.iter_mut() {
+                let k3 = io_context.rngs.bitcomp2.random_fes_3keys::<F>();
+                res.b = (k3.0 + k3.1 + k3.2).neg();
+            }
+            let x23 = yao::input_field_id2_many::<F, _>(None, None, n_inputs, io_context)?;
+
+            let mut evaluator = Rep3Evaluator::new(io_context);
+            evaluator.receive_circuit()?;
+            let x1 = GarbledCircuits::adder_mod_p_many::<_, F>(&mut evaluator, &x, &x23);
+            let x1 = GCUtils::garbled_circuits_error(x1)?;
+            let x1 = evaluator.output_to_id0_and_id1(x1.wires())?;
+            for (res, x1) in izip!(res.iter_mut(), x1.chunks(n_bits)) {
+                res.a = GCUtils::bits_to_field(x1)?;
+            }
+        }
+        PartyID::ID1 => {
+            let delta = match delta {
+                Some(delta) => delta,
+                None => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "No delta provided",
+                ))?,
+            };
+
+            for res in res.iter_mut() {
+                let k2 = io_context.rngs.bitcomp1.random_fes_3keys::<F>();
+                res.a = (k2.0 + k2.1 + k2.2).neg();
+            }
+            let x23 = yao::input_field_id2_many::<F, _>(None, None, n_inputs, io_context)?;
+
+            let mut garbler = Rep3Garbler::new_with_delta(io_context, delta);
+            let x1 = GarbledCircuits::adder_mod_p_many::<_, F>(&mut garbler, &x, &x23);
+            let x1 = GCUtils::garbled_circuits_error(x1)?;
+            let x1 = garbler.output_to_id0_and_id1(x1.wires())?;
+            let x1 = match x1 {
+                Some(x1) => x1,
+                None => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "No output received",
+                ))?,
+            };
+            for (res, x1) in izip!(res.iter_mut(), x1.chunks(n_bits)) {
+                res.b = GCUtils::bits_to_field(x1)?;
+            }
+        }
+        PartyID::ID2 => {
+            let delta = match delta {
+                Some(delta) => delta,
+                None => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "No delta provided",
+                ))?,
+            };
+
+            let mut x23 = Vec::with_capacity(n_inputs);
+            for res in res.iter_mut() {
+                let k2 = io_context.rngs.bitcomp1.random_fes_3keys::<F>();
+                let k3 = io_context.rngs.bitcomp2.random_fes_3keys::<F>();
+                let k2_comp = k2.0 + k2.1 + k2.2;
+                let k3_comp = k3.0 + k3.1 + k3.2;
+                x23.push(k2_comp + k3_comp);
+                res.a = k3_comp.neg();
+                res.b = k2_comp.neg();
+            }
+            let x23 = yao::input_field_id2_many(Some(x23), Some(delta), n_inputs, io_context)?;
+
+            let mut garbler = Rep3Garbler::new_with_delta(io_context, delta);
+            let x1 = GarbledCircuits::adder_mod_p_many::<_, F>(&mut garbler, &x, &x23);
+            let x1 = GCUtils::garbled_circuits_error(x1)?;
+            let x1 = garbler.output_to_id0_and_id1(x1.wires())?;
+            if x1.is_some() {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Unexpected output received",
+                ))?;
+            }
+        }
+    };
+
+    Ok(res)
+}
+
 /// Transforms the shared value x from a yao sharing to an arithmetic sharing. I.e., the sharing such that the garbler have keys (k_0, delta) for each bit of x, while the evaluator has k_x = k_0 xor delta * x gets transformed into x = x_1 + x_2 + x_3. Uses the Streaming Garbler/Evaluator.
 ///
 /// Keep in mind: Only works if the input is actually a binary sharing of a valid field element
@@ -406,6 +847,7 @@ pub fn y2a_streaming<F: PrimeField, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<Rep3PrimeFieldShare<F>> {
+    malicious::ensure_semi_honest(io_context, "y2a_streaming")?;
     let mut res = Rep3PrimeFieldShare::zero_share();
 
     match io_context.id {
@@ -415,7 +857,7 @@ pub fn y2a_streaming<F: PrimeField, N: Rep3Network>(
             let x23 = yao::input_field_id2::<F, _>(None, None, io_context)?;
 
             let mut evaluator = StreamingRep3Evaluator::new(io_context);
-            let x1 = GarbledCircuits::adder_mod_p::<_, F>(&mut evaluator, &x, &x23);
+            let x1 = GarbledCircuits::adder_mod_p_cached::<_, F>(&mut evaluator, &x, &x23);
             let x1 = GCUtils::garbled_circuits_error(x1)?;
             let x1 = evaluator.output_to_id0_and_id1(x1.wires())?;
             res.a = GCUtils::bits_to_field(&x1)?;
@@ -431,6 +873,43 @@ pub fn y2a_streaming<F: PrimeField, N: Rep3Network>(
     Ok(res)
 }
 
+/// Transforms the shared value x from a yao sharing to an arithmetic sharing. I.e., the sharing such that the garbler have keys (k_0, delta) for each bit of x, while the evaluator has k_x = k_0 xor delta * x gets transformed into x = x_1 + x_2 + x_3. The garbled circuit is pipelined in chunks of `chunk_size` blocks (see [Rep3Garbler::with_chunk_size]).
+///
+/// Keep in mind: Only works if the input is actually a binary sharing of a valid field element
+/// If the input has the correct number of bits, but is >= P, then either x can be reduced with self.low_depth_sub_p_cmux(x) first, or self.low_depth_binary_add_2_mod_p(x, y) is extended to subtract 2P in parallel as well. The second solution requires another multiplexer in the end. These adaptions need to be encoded into a garbled circuit.
+pub fn y2a_pipelined<F: PrimeField, N: Rep3Network>(
+    x: BinaryBundle<WireMod2>,
+    delta: Option<WireMod2>,
+    chunk_size: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3PrimeFieldShare<F>> {
+    malicious::ensure_semi_honest(io_context, "y2a_pipelined")?;
+    let mut res = Rep3PrimeFieldShare::zero_share();
+
+    match io_context.id {
+        PartyID::ID0 => {
+            let k3 = io_context.rngs.bitcomp2.random_fes_3keys::<F>();
+            res.b = (k3.0 + k3.1 + k3.2).neg();
+            let x23 = yao::input_field_id2::<F, _>(None, None, io_context)?;
+
+            let mut evaluator = Rep3Evaluator::new(io_context).with_chunk_size(chunk_size);
+            evaluator.receive_circuit()?;
+            let x1 = GarbledCircuits::adder_mod_p_cached::<_, F>(&mut evaluator, &x, &x23);
+            let x1 = GCUtils::garbled_circuits_error(x1)?;
+            let x1 = evaluator.output_to_id0_and_id1(x1.wires())?;
+            res.a = GCUtils::bits_to_field(&x1)?;
+        }
+        PartyID::ID1 => {
+            y2a_impl_p1!(Rep3Garbler<N>, x, delta, io_context, res, chunk_size)
+        }
+        PartyID::ID2 => {
+            y2a_impl_p2!(Rep3Garbler<N>, x, delta, io_context, res, chunk_size)
+        }
+    };
+
+    Ok(res)
+}
+
 /// Transforms the replicated shared value x from a binary sharing to a yao sharing. I.e., x = x_1 xor x_2 xor x_3 gets transformed into wires, such that the garbler have keys (k_0, delta) for each bit of x, while the evaluator has k_x = k_0 xor delta * x.
 ///
 /// Keep in mind: Only works if the input is actually a binary sharing of a valid field element
@@ -440,6 +919,7 @@ pub fn b2y<F: PrimeField, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "b2y")?;
     let [x01, x2] =
         yao::joint_input_binary_xored(x, delta, io_context, F::MODULUS_BIT_SIZE as usize)?;
 
@@ -475,6 +955,7 @@ pub fn y2b<F: PrimeField, N: Rep3Network>(
     x: BinaryBundle<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<Rep3BigUintShare<F>> {
+    malicious::ensure_semi_honest(io_context, "y2b")?;
     let bitlen = x.size();
     let collapsed = GCUtils::collapse_bundle_to_lsb_bits_as_biguint(x);
 
@@ -508,21 +989,61 @@ pub fn a2y2b<F: PrimeField, N: Rep3Network>(
     x: Rep3PrimeFieldShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<Rep3BigUintShare<F>> {
+    malicious::ensure_semi_honest(io_context, "a2y2b")?;
     let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
     let y = a2y(x, delta, io_context)?;
     y2b(y, io_context)
 }
 
+/// Transforms a vector of replicated shared values from an arithmetic sharing to a binary sharing. In contrast to calling [a2y2b] for every element, all values are converted in a single garbled circuit, so the number of communication rounds does not depend on the number of values.
+pub fn a2y2b_many<F: PrimeField, N: Rep3Network>(
+    x: &[Rep3PrimeFieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3BigUintShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "a2y2b_many")?;
+    if x.is_empty() {
+        return Ok(Vec::new());
+    }
+    let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
+    let y = a2y_many(x, delta, io_context)?;
+    // y2b is linear, so we can convert all bits at once and split the result afterwards
+    let converted = y2b::<F, N>(y, io_context)?;
+    let bitlen = F::MODULUS_BIT_SIZE as usize;
+    let mask = (BigUint::from(1u64) << bitlen) - BigUint::from(1u64);
+    let res = (0..x.len())
+        .map(|i| {
+            Rep3BigUintShare::new(
+                (&converted.a >> (i * bitlen)) & &mask,
+                (&converted.b >> (i * bitlen)) & &mask,
+            )
+        })
+        .collect();
+    Ok(res)
+}
+
 /// Transforms the replicated shared value x from an arithmetic sharing to a binary sharing. I.e., x = x_1 + x_2 + x_3 gets transformed into x = x'_1 xor x'_2 xor x'_3. Uses the Streaming Garbler/Evaluator.
 pub fn a2y2b_streaming<F: PrimeField, N: Rep3Network>(
     x: Rep3PrimeFieldShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<Rep3BigUintShare<F>> {
+    malicious::ensure_semi_honest(io_context, "a2y2b_streaming")?;
     let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
     let y = a2y_streaming(x, delta, io_context)?;
     y2b(y, io_context)
 }
 
+/// Transforms the replicated shared value x from an arithmetic sharing to a binary sharing. I.e., x = x_1 + x_2 + x_3 gets transformed into x = x'_1 xor x'_2 xor x'_3. The garbled circuit is pipelined in chunks of `chunk_size` blocks (see [a2y_pipelined]).
+pub fn a2y2b_pipelined<F: PrimeField, N: Rep3Network>(
+    x: Rep3PrimeFieldShare<F>,
+    chunk_size: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3BigUintShare<F>> {
+    malicious::ensure_semi_honest(io_context, "a2y2b_pipelined")?;
+    let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
+    let y = a2y_pipelined(x, delta, chunk_size, io_context)?;
+    y2b(y, io_context)
+}
+
 /// Transforms the replicated shared value x from a binary sharing to an arithmetic sharing. I.e., x = x_1 xor x_2 xor x_3 gets transformed into x = x'_1 + x'_2 + x'_3. This implementations goes through the yao protocol and currently works only for a binary sharing of a valid field element, i.e., x = x_1 xor x_2 xor x_3 < p.
 ///
 /// Keep in mind: Only works if the input is actually a binary sharing of a valid field element
@@ -531,6 +1052,7 @@ pub fn b2y2a<F: PrimeField, N: Rep3Network>(
     x: &Rep3BigUintShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<Rep3PrimeFieldShare<F>> {
+    malicious::ensure_semi_honest(io_context, "b2y2a")?;
     let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
     let y = b2y(x, delta, io_context)?;
     y2a(y, delta, io_context)
@@ -544,7 +1066,23 @@ pub fn b2y2a_streaming<F: PrimeField, N: Rep3Network>(
     x: &Rep3BigUintShare<F>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<Rep3PrimeFieldShare<F>> {
+    malicious::ensure_semi_honest(io_context, "b2y2a_streaming")?;
     let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
     let y = b2y(x, delta, io_context)?;
     y2a_streaming(y, delta, io_context)
 }
+
+/// Transforms the replicated shared value x from a binary sharing to an arithmetic sharing. I.e., x = x_1 xor x_2 xor x_3 gets transformed into x = x'_1 + x'_2 + x'_3. This implementations goes through the yao protocol and currently works only for a binary sharing of a valid field element, i.e., x = x_1 xor x_2 xor x_3 < p. The garbled circuit is pipelined in chunks of `chunk_size` blocks (see [y2a_pipelined]).
+///
+/// Keep in mind: Only works if the input is actually a binary sharing of a valid field element
+/// If the input has the correct number of bits, but is >= P, then either x can be reduced with self.low_depth_sub_p_cmux(x) first, or self.low_depth_binary_add_2_mod_p(x, y) is extended to subtract 2P in parallel as well. The second solution requires another multiplexer in the end.
+pub fn b2y2a_pipelined<F: PrimeField, N: Rep3Network>(
+    x: &Rep3BigUintShare<F>,
+    chunk_size: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3PrimeFieldShare<F>> {
+    malicious::ensure_semi_honest(io_context, "b2y2a_pipelined")?;
+    let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
+    let y = b2y(x, delta, io_context)?;
+    y2a_pipelined(y, delta, chunk_size, io_context)
+}
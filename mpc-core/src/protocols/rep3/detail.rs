@@ -9,6 +9,7 @@ use crate::protocols::rep3::network::Rep3Network;
 
 use super::binary;
 use super::conversion;
+use super::malicious;
 use super::network::IoContext;
 use super::Rep3BigUintShare;
 use super::Rep3PrimeFieldShare;
@@ -42,6 +43,18 @@ fn kogge_stone_inner<F: PrimeField, N: Rep3Network>(
     g: &Rep3BigUintShare<F>,
     io_context: &mut IoContext<N>,
     bitlen: usize,
+) -> IoResult<Rep3BigUintShare<F>> {
+    kogge_stone_inner_packed(p, g, io_context, bitlen, 1, bitlen)
+}
+
+// The Kogge-Stone adder for `num` values packed into slots of `width` >= `bitlen` bits each. All slots are added in parallel, so the number of rounds only depends on `bitlen`.
+fn kogge_stone_inner_packed<F: PrimeField, N: Rep3Network>(
+    p: &Rep3BigUintShare<F>,
+    g: &Rep3BigUintShare<F>,
+    io_context: &mut IoContext<N>,
+    bitlen: usize,
+    num: usize,
+    width: usize,
 ) -> IoResult<Rep3BigUintShare<F>> {
     let d = ceil_log2(bitlen);
     let s_ = p;
@@ -50,14 +63,15 @@ fn kogge_stone_inner<F: PrimeField, N: Rep3Network>(
     for i in 0..d {
         // The loop looks slightly different to the one for rep3 rings to have the and gates at the LSBs of the storage
         let shift = 1 << i;
-        let mask = (BigUint::from(1u64) << (bitlen - shift)) - BigUint::one();
+        let mask = slot_mask(bitlen - shift, num, width);
         let p_ = &p & &mask;
         let g_ = &g & &mask;
+        // The bits shifted in from the next slot are removed by the masked operands of the and gates
         let p_shift = &p >> shift;
 
         // TODO: Make and more communication efficient, ATM we send the full element for each level, even though they reduce in size
         // maybe just input the mask into AND?
-        let (r1, r2) = and_twice(&p_shift, &g_, &p_, io_context, bitlen - shift)?;
+        let (r1, r2) = and_twice(&p_shift, &g_, &p_, io_context, num * width - shift)?;
         p = r2 << shift;
         g ^= &(r1 << shift);
     }
@@ -139,6 +153,7 @@ fn and_twice<F: PrimeField, N: Rep3Network>(
     debug_assert!(a.a.bits() <= bitlen as u64);
     debug_assert!(b1.a.bits() <= bitlen as u64);
     debug_assert!(b2.a.bits() <= bitlen as u64);
+    malicious::ensure_semi_honest(io_context, "AND of binary shares")?;
     let (mut mask1, mask_b) = io_context.rngs.rand.random_biguint(bitlen);
     mask1 ^= mask_b;
 
@@ -179,6 +194,88 @@ fn low_depth_binary_sub_p<F: PrimeField, N: Rep3Network>(
     kogge_stone_inner(&p, &g, io_context, bitlen)
 }
 
+// The lowest `bits` bits of each of the `num` slots of `width` bits
+fn slot_mask(bits: usize, num: usize, width: usize) -> BigUint {
+    let slot = (BigUint::from(1u64) << bits) - BigUint::one();
+    (0..num).fold(BigUint::zero(), |acc, i| acc | (&slot << (i * width)))
+}
+
+/// Packs the values into slots of `width` bits each, the i-th value is stored in the i-th slot starting from the LSB.
+pub(super) fn pack<F: PrimeField>(x: &[Rep3BigUintShare<F>], width: usize) -> Rep3BigUintShare<F> {
+    let mut packed = Rep3BigUintShare::zero_share();
+    for (i, x) in x.iter().enumerate() {
+        packed ^= &(x << (i * width));
+    }
+    packed
+}
+
+/// Splits a packed value into `num` values of `bitlen` bits from slots of `width` bits each.
+pub(super) fn unpack<F: PrimeField>(
+    packed: &Rep3BigUintShare<F>,
+    num: usize,
+    width: usize,
+    bitlen: usize,
+) -> Vec<Rep3BigUintShare<F>> {
+    let mask = (BigUint::from(1u64) << bitlen) - BigUint::one();
+    (0..num).map(|i| &(packed >> (i * width)) & &mask).collect()
+}
+
+/// Computes (x1 + x2) mod p for `num` values of `bitlen` bits packed into slots of `width` >= `bitlen` + 2 bits each (see [pack]). All slots share one Kogge-Stone adder, so the number of communication rounds is logarithmic in `bitlen` and independent of `num`.
+pub(super) fn low_depth_binary_add_mod_p_packed<F: PrimeField, N: Rep3Network>(
+    x1: &Rep3BigUintShare<F>,
+    x2: &Rep3BigUintShare<F>,
+    io_context: &mut IoContext<N>,
+    bitlen: usize,
+    num: usize,
+    width: usize,
+) -> IoResult<Rep3BigUintShare<F>> {
+    debug_assert!(width >= bitlen + 2);
+    // Add x1 + x2 via a packed Kogge-Stone adder
+    let p = x1 ^ x2;
+    let g = binary::and_with_bitlen(x1, x2, io_context, num * width)?;
+    let x = kogge_stone_inner_packed(&p, &g, io_context, bitlen, num, width)?;
+    low_depth_sub_p_cmux_packed::<F, N>(&x, io_context, bitlen + 1, num, width)
+}
+
+fn low_depth_sub_p_cmux_packed<F: PrimeField, N: Rep3Network>(
+    x: &Rep3BigUintShare<F>,
+    io_context: &mut IoContext<N>,
+    bitlen: usize,
+    num: usize,
+    width: usize,
+) -> IoResult<Rep3BigUintShare<F>> {
+    let original_bitlen = bitlen - 1; // before the potential overflow after an addition
+    let mask = slot_mask(original_bitlen, num, width);
+
+    // Add 2^bitlen - p to every slot
+    let p_ = (BigUint::from(1u64) << bitlen) - F::MODULUS.into();
+    let p_ = (0..num).fold(BigUint::zero(), |acc, i| acc | (&p_ << (i * width)));
+    let g = x & &p_;
+    let p = binary::xor_public(x, &p_, io_context.id);
+    let y = kogge_stone_inner_packed(&p, &g, io_context, bitlen, num, width)?;
+
+    // Spread the ov share of each slot to the whole slot
+    let slot = (BigUint::from(1u64) << original_bitlen) - BigUint::one();
+    let mut ov = Rep3BigUintShare::<F>::zero_share();
+    for i in 0..num {
+        let msb = (i * width + bitlen) as u64;
+        if y.a.bit(msb) {
+            ov.a |= &slot << (i * width);
+        }
+        if y.b.bit(msb) {
+            ov.b |= &slot << (i * width);
+        }
+    }
+    let x = x & &mask;
+    let y = &y & &mask;
+
+    // one big multiplexer
+    let xor = &x ^ &y;
+    let mut res = binary::and_with_bitlen(&ov, &xor, io_context, num * width)?;
+    res ^= &x;
+    Ok(res)
+}
+
 /// Computes a binary circuit to compare two shared values \[x\] > \[y\]. Thus, the inputs x and y are transformed from arithmetic to binary sharings using [Rep3Protocol::a2b] first. The output is a binary sharing of one bit.
 pub(crate) fn unsigned_ge<F: PrimeField, N: Rep3Network>(
     x: Rep3PrimeFieldShare<F>,
@@ -216,6 +313,93 @@ pub(crate) fn unsigned_ge_const_rhs<F: PrimeField, N: Rep3Network>(
     Ok(&(&diff >> F::MODULUS_BIT_SIZE as usize) & &BigUint::one())
 }
 
+/// Computes the bits x1_i >= x2_i for public values x1_i and shared values x2_i of `bitlen` bits each. All comparisons are packed into a single binary sharing with `bitlen + 1` bits per value and evaluated using one packed Kogge-Stone adder, which computes the carries of x1_i + !x2_i + 1. In the output, bit i is the result of the i-th comparison.
+pub(super) fn unsigned_ge_const_lhs_packed<F: PrimeField, N: Rep3Network>(
+    x1: &[BigUint],
+    x2: &[Rep3BigUintShare<F>],
+    io_context: &mut IoContext<N>,
+    bitlen: usize,
+) -> IoResult<Rep3BigUintShare<F>> {
+    debug_assert_eq!(x1.len(), x2.len());
+    let width = bitlen + 1;
+    let mut x1_packed = BigUint::zero();
+    let mut x2_packed = Rep3BigUintShare::zero_share();
+    for (i, (x1, x2)) in x1.iter().zip(x2.iter()).enumerate() {
+        x1_packed |= x1 << (i * width);
+        x2_packed ^= x2 << (i * width);
+    }
+    // bitnot of x2
+    let x2_packed = binary::xor_public(
+        &x2_packed,
+        &slot_mask(bitlen, x1.len(), width),
+        io_context.id,
+    );
+    // Now start the Kogge-Stone adder
+    let p = binary::xor_public(&x2_packed, &x1_packed, io_context.id);
+    let g = &x2_packed & &x1_packed;
+    ge_carries_packed(p, g, io_context, bitlen, x1.len(), width)
+}
+
+/// Computes the bits x1_i >= x2_i for shared values x1_i and x2_i of `bitlen` bits each. Same as [unsigned_ge_const_lhs_packed], but the generate bits of the packed Kogge-Stone adder require one additional AND gate.
+pub(super) fn unsigned_ge_packed<F: PrimeField, N: Rep3Network>(
+    x1: &[Rep3BigUintShare<F>],
+    x2: &[Rep3BigUintShare<F>],
+    io_context: &mut IoContext<N>,
+    bitlen: usize,
+) -> IoResult<Rep3BigUintShare<F>> {
+    debug_assert_eq!(x1.len(), x2.len());
+    let width = bitlen + 1;
+    let x1_packed = pack(x1, width);
+    let x2_packed = pack(x2, width);
+    // bitnot of x2
+    let x2_packed = binary::xor_public(
+        &x2_packed,
+        &slot_mask(bitlen, x2.len(), width),
+        io_context.id,
+    );
+    // Now start the Kogge-Stone adder
+    let p = &x2_packed ^ &x1_packed;
+    let g = binary::and_with_bitlen(&x2_packed, &x1_packed, io_context, x1.len() * width)?;
+    ge_carries_packed(p, g, io_context, bitlen, x1.len(), width)
+}
+
+// Computes the carries of the packed Kogge-Stone adder with carry_in = 1. In the output, bit i is the carry of the i-th slot.
+fn ge_carries_packed<F: PrimeField, N: Rep3Network>(
+    mut p: Rep3BigUintShare<F>,
+    mut g: Rep3BigUintShare<F>,
+    io_context: &mut IoContext<N>,
+    bitlen: usize,
+    num: usize,
+    width: usize,
+) -> IoResult<Rep3BigUintShare<F>> {
+    // Since carry_in = 1, we need to XOR the LSBs of x1 and x2 to g (i.e., xor the LSBs of p)
+    g ^= &p & &slot_mask(1, num, width);
+
+    for i in 0..ceil_log2(bitlen) {
+        let shift = 1 << i;
+        let mask = slot_mask(bitlen - shift, num, width);
+        let p_ = &p & &mask;
+        let g_ = &g & &mask;
+        let p_shift = &p >> shift;
+        let (r1, r2) = and_twice(&p_shift, &g_, &p_, io_context, num * width)?;
+        p = r2 << shift;
+        g ^= &(r1 << shift);
+    }
+
+    // The carry of each slot is the MSB of g
+    let mut res = Rep3BigUintShare::zero_share();
+    for i in 0..num {
+        let msb = (i * width + bitlen - 1) as u64;
+        if g.a.bit(msb) {
+            res.a |= BigUint::one() << i;
+        }
+        if g.b.bit(msb) {
+            res.b |= BigUint::one() << i;
+        }
+    }
+    Ok(res)
+}
+
 // Calculates 2^k + x1 - x2
 fn low_depth_binary_sub_by_const<F: PrimeField, N: Rep3Network>(
     x1: &Rep3BigUintShare<F>,
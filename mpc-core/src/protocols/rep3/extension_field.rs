@@ -0,0 +1,193 @@
+//! Extension field
+//!
+//! This module contains operations with shares of elements of extension fields, such as the towers Fp2, Fp6, and Fp12 of pairing-friendly curves. Since a replicated share of an extension field element consists of extension field elements itself, all Fp-linear operations, including the Frobenius map, are local. A multiplication requires resharing a single extension field element, independent of the degree of the extension, and is thus cheaper than multiplying the coefficients using base field multiplications.
+
+mod ops;
+mod types;
+
+use ark_ff::Field;
+use itertools::{izip, Itertools};
+pub use types::Rep3ExtensionFieldShare;
+
+use super::{
+    id::PartyID,
+    malicious,
+    network::{IoContext, Rep3Network},
+    IoResult, Rep3PrimeFieldShare,
+};
+
+/// Type alias for a [`Rep3PrimeFieldShare`]
+type FieldShare<F> = Rep3PrimeFieldShare<F>;
+/// Type alias for a [`Rep3ExtensionFieldShare`]
+type ExtensionFieldShare<E> = Rep3ExtensionFieldShare<E>;
+
+/// Performs addition between two shared values.
+pub fn add<E: Field>(
+    a: ExtensionFieldShare<E>,
+    b: ExtensionFieldShare<E>,
+) -> ExtensionFieldShare<E> {
+    a + b
+}
+
+/// Performs subtraction between two shared values.
+pub fn sub<E: Field>(
+    a: ExtensionFieldShare<E>,
+    b: ExtensionFieldShare<E>,
+) -> ExtensionFieldShare<E> {
+    a - b
+}
+
+/// Performs negation of a shared value.
+pub fn neg<E: Field>(a: ExtensionFieldShare<E>) -> ExtensionFieldShare<E> {
+    -a
+}
+
+/// Performs addition between a shared value and a public value.
+pub fn add_public<E: Field>(
+    shared: ExtensionFieldShare<E>,
+    public: E,
+    id: PartyID,
+) -> ExtensionFieldShare<E> {
+    let mut res = shared;
+    match id {
+        PartyID::ID0 => res.a += public,
+        PartyID::ID1 => res.b += public,
+        PartyID::ID2 => {}
+    }
+    res
+}
+
+/// Performs multiplication of a shared value and a public value.
+pub fn mul_public<E: Field>(shared: ExtensionFieldShare<E>, public: E) -> ExtensionFieldShare<E> {
+    shared * public
+}
+
+/// Performs multiplication of two shared values.
+pub fn mul<E: Field, N: Rep3Network>(
+    a: ExtensionFieldShare<E>,
+    b: ExtensionFieldShare<E>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<ExtensionFieldShare<E>> {
+    malicious::ensure_semi_honest(io_context, "multiplication of extension field shares")?;
+    let local_a = a * b + io_context.rngs.rand.masking_field_element::<E>();
+    let local_b = io_context.network.reshare(local_a)?;
+    Ok(ExtensionFieldShare::new(local_a, local_b))
+}
+
+/// Performs element-wise multiplication of two vectors of shared values in a single communication round.
+pub fn mul_vec<E: Field, N: Rep3Network>(
+    lhs: &[ExtensionFieldShare<E>],
+    rhs: &[ExtensionFieldShare<E>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<ExtensionFieldShare<E>>> {
+    malicious::ensure_semi_honest(io_context, "multiplication of extension field shares")?;
+    debug_assert_eq!(lhs.len(), rhs.len());
+    let local_a = izip!(lhs, rhs)
+        .map(|(lhs, rhs)| *lhs * *rhs + io_context.rngs.rand.masking_field_element::<E>())
+        .collect_vec();
+    let local_b = io_context.network.reshare_many(&local_a)?;
+    Ok(izip!(local_a, local_b)
+        .map(|(a, b)| ExtensionFieldShare::new(a, b))
+        .collect())
+}
+
+/// Computes the square of a shared value.
+pub fn square<E: Field, N: Rep3Network>(
+    a: ExtensionFieldShare<E>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<ExtensionFieldShare<E>> {
+    mul(a, a, io_context)
+}
+
+/// Applies the Frobenius map x -> x^(p^power) to a shared value. Since the map is linear over the base prime field, this is a local operation.
+pub fn frobenius_map<E: Field>(a: ExtensionFieldShare<E>, power: usize) -> ExtensionFieldShare<E> {
+    ExtensionFieldShare::new(a.a.frobenius_map(power), a.b.frobenius_map(power))
+}
+
+/// Computes the inverse of a shared value by opening a randomized version of it.
+pub fn inv<E: Field, N: Rep3Network>(
+    a: ExtensionFieldShare<E>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<ExtensionFieldShare<E>> {
+    let res = inv_vec(&[a], io_context)?;
+    Ok(res[0])
+}
+
+/// Computes the inverses of a vector of shared values in a single multiplication and opening.
+pub fn inv_vec<E: Field, N: Rep3Network>(
+    a: &[ExtensionFieldShare<E>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<ExtensionFieldShare<E>>> {
+    let r = (0..a.len()).map(|_| rand(io_context)).collect_vec();
+    let y = mul_vec(a, &r, io_context)?;
+    let y = open_vec(&y, io_context)?;
+    izip!(r, y)
+        .map(|(r, y)| {
+            if y.is_zero() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "During execution of inverse in MPC: cannot compute inverse of zero",
+                ));
+            }
+            let y_inv = y
+                .inverse()
+                .expect("we checked if y is zero. Must be possible to invert.");
+            Ok(r * y_inv)
+        })
+        .collect()
+}
+
+/// Opens a shared value.
+pub fn open<E: Field, N: Rep3Network>(
+    a: ExtensionFieldShare<E>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<E> {
+    malicious::ensure_semi_honest(io_context, "opening of extension field shares")?;
+    let c = io_context.network.reshare(a.b)?;
+    Ok(a.a + a.b + c)
+}
+
+/// Opens a vector of shared values.
+pub fn open_vec<E: Field, N: Rep3Network>(
+    a: &[ExtensionFieldShare<E>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<E>> {
+    malicious::ensure_semi_honest(io_context, "opening of extension field shares")?;
+    let bs = a.iter().map(|x| x.b).collect_vec();
+    let cs = io_context.network.reshare_many(&bs)?;
+    Ok(izip!(a, cs).map(|(x, c)| x.a + x.b + c).collect())
+}
+
+/// Generates a random shared value.
+pub fn rand<E: Field, N: Rep3Network>(io_context: &mut IoContext<N>) -> ExtensionFieldShare<E> {
+    let (a, b) = io_context.rngs.rand.random_fes::<E>();
+    ExtensionFieldShare::new(a, b)
+}
+
+/// Transforms a public value into a shared value: \[a\] = a.
+pub fn promote_to_trivial_share<E: Field>(id: PartyID, public_value: E) -> ExtensionFieldShare<E> {
+    add_public(ExtensionFieldShare::zero_share(), public_value, id)
+}
+
+/// Combines shares of the coefficients over the base prime field into a share of the extension field element. The coefficients are ordered as in [`Field::from_base_prime_field_elems`], e.g., c0.c0, c0.c1, c1.c0, ... for Fp4 = Fp2\[v\]. Returns `None` if the number of coefficients does not match the extension degree.
+pub fn from_base_field_shares<E: Field>(
+    coefficients: &[FieldShare<E::BasePrimeField>],
+) -> Option<ExtensionFieldShare<E>> {
+    let (a, b): (Vec<_>, Vec<_>) = coefficients.iter().map(|x| (x.a, x.b)).unzip();
+    Some(ExtensionFieldShare::new(
+        E::from_base_prime_field_elems(&a)?,
+        E::from_base_prime_field_elems(&b)?,
+    ))
+}
+
+/// Splits a shared extension field element into shares of its coefficients over the base prime field. This is the inverse of [`from_base_field_shares`].
+pub fn to_base_field_shares<E: Field>(
+    a: ExtensionFieldShare<E>,
+) -> Vec<FieldShare<E::BasePrimeField>> {
+    izip!(
+        a.a.to_base_prime_field_elements(),
+        a.b.to_base_prime_field_elements()
+    )
+    .map(|(a, b)| FieldShare::new(a, b))
+    .collect()
+}
@@ -0,0 +1,99 @@
+use ark_ff::Field;
+
+use super::Rep3ExtensionFieldShare;
+
+impl<E: Field> std::ops::Add for Rep3ExtensionFieldShare<E> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            a: self.a + rhs.a,
+            b: self.b + rhs.b,
+        }
+    }
+}
+
+impl<E: Field> std::ops::Add<&Rep3ExtensionFieldShare<E>> for &'_ Rep3ExtensionFieldShare<E> {
+    type Output = Rep3ExtensionFieldShare<E>;
+
+    fn add(self, rhs: &Rep3ExtensionFieldShare<E>) -> Self::Output {
+        Rep3ExtensionFieldShare::<E> {
+            a: self.a + rhs.a,
+            b: self.b + rhs.b,
+        }
+    }
+}
+
+impl<E: Field> std::ops::AddAssign for Rep3ExtensionFieldShare<E> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.a += rhs.a;
+        self.b += rhs.b;
+    }
+}
+
+impl<E: Field> std::ops::Sub for Rep3ExtensionFieldShare<E> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            a: self.a - rhs.a,
+            b: self.b - rhs.b,
+        }
+    }
+}
+
+impl<E: Field> std::ops::Sub<&Rep3ExtensionFieldShare<E>> for &'_ Rep3ExtensionFieldShare<E> {
+    type Output = Rep3ExtensionFieldShare<E>;
+
+    fn sub(self, rhs: &Rep3ExtensionFieldShare<E>) -> Self::Output {
+        Rep3ExtensionFieldShare::<E> {
+            a: self.a - rhs.a,
+            b: self.b - rhs.b,
+        }
+    }
+}
+
+impl<E: Field> std::ops::SubAssign for Rep3ExtensionFieldShare<E> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.a -= rhs.a;
+        self.b -= rhs.b;
+    }
+}
+
+impl<E: Field> std::ops::Neg for Rep3ExtensionFieldShare<E> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            a: -self.a,
+            b: -self.b,
+        }
+    }
+}
+
+impl<E: Field> std::ops::Mul<E> for Rep3ExtensionFieldShare<E> {
+    type Output = Self;
+
+    fn mul(self, rhs: E) -> Self::Output {
+        Self {
+            a: self.a * rhs,
+            b: self.b * rhs,
+        }
+    }
+}
+
+impl<E: Field> std::ops::MulAssign<E> for Rep3ExtensionFieldShare<E> {
+    fn mul_assign(&mut self, rhs: E) {
+        self.a *= rhs;
+        self.b *= rhs;
+    }
+}
+
+/// This is the first local step of a multiplication, which results in an additive share of the product.
+impl<E: Field> std::ops::Mul for Rep3ExtensionFieldShare<E> {
+    type Output = E;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.a * rhs.a + self.a * rhs.b + self.b * rhs.a
+    }
+}
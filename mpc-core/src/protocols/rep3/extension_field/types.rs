@@ -0,0 +1,28 @@
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/// This type represents a replicated shared element of an extension field, e.g., Fp2, Fp6, or Fp12. Since a replicated share contains additive shares of two parties, this type contains two extension field elements.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Rep3ExtensionFieldShare<E: Field> {
+    /// Share of this party
+    pub a: E,
+    /// Share of the prev party
+    pub b: E,
+}
+
+impl<E: Field> Rep3ExtensionFieldShare<E> {
+    /// Contruct a new [`Rep3ExtensionFieldShare`]
+    pub fn new(a: E, b: E) -> Self {
+        Self { a, b }
+    }
+
+    /// Unwraps the type into two additive shares.
+    pub fn ab(self) -> (E, E) {
+        (self.a, self.b)
+    }
+
+    /// Returns a share of zero.
+    pub fn zero_share() -> Self {
+        Self::default()
+    }
+}
@@ -0,0 +1,402 @@
+//! Fixed-Point Arithmetic
+//!
+//! This module contains operations on shared signed fixed-point numbers. A fixed-point number x is encoded as the field element round(x * 2^frac_bits), where negative numbers are represented by their additive inverse, i.e., the same way circom represents negative numbers. Addition, subtraction and multiplication with public integers do not change the scaling and can therefore be done with the functions in [`super::arithmetic`].
+
+use ark_ff::PrimeField;
+use itertools::{izip, Itertools};
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+
+use super::{
+    arithmetic,
+    id::PartyID,
+    malicious,
+    network::{IoContext, Rep3Network},
+    yao, IoResult, Rep3PrimeFieldShare,
+};
+
+/// Type alias for a [`Rep3PrimeFieldShare`] holding an encoded fixed-point number
+pub type Rep3FixedPointShare<F> = Rep3PrimeFieldShare<F>;
+
+/// The statistical security parameter used to mask values in the probabilistic truncation.
+pub const STATISTICAL_SECURITY: usize = 40;
+
+/// The parameters of a fixed-point encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedPointConfig {
+    /// The total number of bits of an encoded value, including the sign bit. All encoded values have to lie in [-2^(bit_size-1), 2^(bit_size-1)).
+    pub bit_size: usize,
+    /// The number of fractional bits.
+    pub frac_bits: usize,
+}
+
+impl Default for FixedPointConfig {
+    fn default() -> Self {
+        Self::new(64, 16)
+    }
+}
+
+impl FixedPointConfig {
+    /// Creates a new [`FixedPointConfig`].
+    ///
+    /// # Panics
+    /// If frac_bits is not smaller than bit_size.
+    pub fn new(bit_size: usize, frac_bits: usize) -> Self {
+        assert!(
+            frac_bits < bit_size,
+            "frac_bits must be smaller than bit_size"
+        );
+        Self {
+            bit_size,
+            frac_bits,
+        }
+    }
+
+    /// Checks whether the field is large enough for the multiplication of two encoded values, including the masks of the truncation.
+    pub fn is_supported<F: PrimeField>(&self) -> bool {
+        2 * self.bit_size + STATISTICAL_SECURITY + 3 < F::MODULUS_BIT_SIZE as usize
+    }
+
+    /// Returns the largest bit_size which is supported by the field, see [`Self::is_supported`].
+    pub fn max_bit_size<F: PrimeField>() -> usize {
+        (F::MODULUS_BIT_SIZE as usize).saturating_sub(STATISTICAL_SECURITY + 4) / 2
+    }
+
+    fn assert_supported<F: PrimeField>(&self) {
+        assert!(
+            self.is_supported::<F>(),
+            "fixed-point encoding with bit_size {} is too large for a field with {} bits",
+            self.bit_size,
+            F::MODULUS_BIT_SIZE
+        );
+    }
+
+    /// Returns the public scaling factor 2^frac_bits.
+    pub fn scale<F: PrimeField>(&self) -> F {
+        F::from(BigUint::from(1u64) << self.frac_bits)
+    }
+
+    /// Encodes a floating-point number as field element. The number is rounded to the nearest representable value.
+    pub fn encode<F: PrimeField>(&self, value: f64) -> F {
+        let scaled = (value * 2f64.powi(self.frac_bits as i32)).round();
+        let abs = F::from(scaled.abs() as u128);
+        if scaled.is_sign_negative() {
+            -abs
+        } else {
+            abs
+        }
+    }
+
+    /// Decodes a field element to a floating-point number.
+    pub fn decode<F: PrimeField>(&self, value: F) -> f64 {
+        let value: BigUint = value.into();
+        let modulus: BigUint = F::MODULUS.into();
+        let signed = if value > &modulus >> 1 {
+            -(modulus - value).to_f64().expect("BigUint converts to f64")
+        } else {
+            value.to_f64().expect("BigUint converts to f64")
+        };
+        signed / 2f64.powi(self.frac_bits as i32)
+    }
+}
+
+/// Returns a sharing of a random value r = r_0 + r_1 + r_2 with r_i in [0, 2^bitlen), where r_i is known to party i and party i-1 (in the indexing of [`Rep3PrimeFieldShare::a`]). Thus, no party knows r, but r is bounded by 3 * 2^bitlen.
+fn bounded_rand<F: PrimeField, N: Rep3Network>(
+    bitlen: usize,
+    io_context: &mut IoContext<N>,
+) -> Rep3PrimeFieldShare<F> {
+    let (a, b) = io_context.rngs.rand.random_biguint(bitlen);
+    Rep3PrimeFieldShare::new(F::from(a), F::from(b))
+}
+
+/// Shifts a shared fixed-point number to the unsigned range [0, 2^bit_size), which preserves the order.
+fn to_unsigned<F: PrimeField>(
+    x: Rep3FixedPointShare<F>,
+    config: &FixedPointConfig,
+    id: PartyID,
+) -> Rep3PrimeFieldShare<F> {
+    let offset = F::from(BigUint::from(1u64) << (config.bit_size - 1));
+    arithmetic::add_public(x, offset, id)
+}
+
+/// Truncates a vector of shared values by frac_bits bits, i.e., computes x / 2^frac_bits rounded down. The input values have to lie in [-2^(2*bit_size-1), 2^(2*bit_size-1)), which is the case for the product of two encoded fixed-point numbers.
+///
+/// This is a probabilistic truncation: The values are masked with random values which are bounded by the statistical security parameter and opened. Thus, the result can be larger than the exact result by up to 3 in the least significant bit.
+pub fn truncate_vec<F: PrimeField, N: Rep3Network>(
+    x: &[Rep3FixedPointShare<F>],
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3FixedPointShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "truncation of fixed-point shares")?;
+    config.assert_supported::<F>();
+    let frac_bits = config.frac_bits;
+    let input_bits = 2 * config.bit_size;
+
+    // shift the values to be positive, i.e., in [0, 2^input_bits)
+    let offset = F::from(BigUint::from(1u64) << (input_bits - 1));
+    let (masks_low, masks_high): (Vec<Rep3PrimeFieldShare<F>>, Vec<Rep3PrimeFieldShare<F>>) = (0
+        ..x.len())
+        .map(|_| {
+            (
+                bounded_rand(frac_bits, io_context),
+                bounded_rand(input_bits + STATISTICAL_SECURITY - frac_bits, io_context),
+            )
+        })
+        .unzip();
+    let scale = config.scale::<F>();
+    let masked = izip!(x, masks_low.iter(), masks_high.iter())
+        .map(|(x, low, high)| {
+            let shifted = arithmetic::add_public(*x, offset, io_context.id);
+            shifted + *low + *high * scale
+        })
+        .collect_vec();
+    let opened = arithmetic::open_vec(&masked, io_context)?;
+
+    // (x + offset + r_low - (c mod 2^frac_bits)) is divisible by 2^frac_bits
+    let scale_inv = scale.inverse().expect("scale is not zero");
+    let mod_mask = (BigUint::from(1u64) << frac_bits) - BigUint::from(1u64);
+    let shifted_offset = F::from(BigUint::from(1u64) << (input_bits - 1 - frac_bits));
+    let res = izip!(x, masks_low, opened)
+        .map(|(x, low, c)| {
+            let c: BigUint = c.into();
+            let c_low = F::from(c & &mod_mask);
+            let shifted = arithmetic::add_public(*x + low, offset - c_low, io_context.id);
+            arithmetic::add_public(shifted * scale_inv, -shifted_offset, io_context.id)
+        })
+        .collect();
+    Ok(res)
+}
+
+/// Truncates a shared value by frac_bits bits. See [`truncate_vec`] for details.
+pub fn truncate<F: PrimeField, N: Rep3Network>(
+    x: Rep3FixedPointShare<F>,
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3FixedPointShare<F>> {
+    let res = truncate_vec(&[x], config, io_context)?;
+    Ok(res[0])
+}
+
+/// Performs element-wise multiplication of two vectors of shared values, followed by a probabilistic truncation of the products by frac_bits bits (see [`truncate_vec`]), in a single communication round.
+///
+/// Instead of resharing the products and opening the masked products afterwards, the parties add their shares of the masks to their additive shares of the products and open the masked products directly. Since the truncated result only depends on the opened value and the high part of the mask, no replicated shares of the products are required.
+pub fn mul_trunc_vec<F: PrimeField, N: Rep3Network>(
+    lhs: &[Rep3PrimeFieldShare<F>],
+    rhs: &[Rep3PrimeFieldShare<F>],
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3FixedPointShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "multiplication of fixed-point shares")?;
+    assert_eq!(lhs.len(), rhs.len());
+    config.assert_supported::<F>();
+    let frac_bits = config.frac_bits;
+    let input_bits = 2 * config.bit_size;
+    let id = io_context.id;
+
+    // shift the products to be positive, i.e., in [0, 2^input_bits)
+    let offset = F::from(BigUint::from(1u64) << (input_bits - 1));
+    let scale = config.scale::<F>();
+    let mut masks_high = Vec::with_capacity(lhs.len());
+    let mut masked = Vec::with_capacity(lhs.len());
+    for (lhs, rhs) in izip!(lhs, rhs) {
+        let low: Rep3PrimeFieldShare<F> = bounded_rand(frac_bits, io_context);
+        let high = bounded_rand(input_bits + STATISTICAL_SECURITY - frac_bits, io_context);
+        let mask = arithmetic::add_public(low + high * scale, offset, id);
+        masked.push(lhs * rhs + mask.a + io_context.rngs.rand.masking_field_element::<F>());
+        masks_high.push(high);
+    }
+    let (b, c) = io_context.network.broadcast_many(&masked)?;
+
+    // (x + offset + r_low - (c mod 2^frac_bits)) / 2^frac_bits = (c - (c mod 2^frac_bits)) / 2^frac_bits - r_high
+    let shifted_offset = F::from(BigUint::from(1u64) << (input_bits - 1 - frac_bits));
+    let res = izip!(masked, b, c, masks_high)
+        .map(|(a, b, c, high)| {
+            let opened: BigUint = (a + b + c).into();
+            let high_part = F::from(&opened >> frac_bits);
+            arithmetic::add_public(-high, high_part - shifted_offset, id)
+        })
+        .collect();
+    Ok(res)
+}
+
+/// Performs multiplication of two shared values, followed by a probabilistic truncation of the product by frac_bits bits, in a single communication round. See [`mul_trunc_vec`] for details.
+pub fn mul_trunc<F: PrimeField, N: Rep3Network>(
+    a: Rep3PrimeFieldShare<F>,
+    b: Rep3PrimeFieldShare<F>,
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3FixedPointShare<F>> {
+    let res = mul_trunc_vec(&[a], &[b], config, io_context)?;
+    Ok(res[0])
+}
+
+/// Performs element-wise multiplication of two vectors of shared fixed-point numbers, followed by a probabilistic truncation (see [`mul_trunc_vec`]).
+pub fn mul_vec<F: PrimeField, N: Rep3Network>(
+    lhs: &[Rep3FixedPointShare<F>],
+    rhs: &[Rep3FixedPointShare<F>],
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3FixedPointShare<F>>> {
+    mul_trunc_vec(lhs, rhs, config, io_context)
+}
+
+/// Performs multiplication of two shared fixed-point numbers, followed by a probabilistic truncation (see [`mul_trunc_vec`]).
+pub fn mul<F: PrimeField, N: Rep3Network>(
+    a: Rep3FixedPointShare<F>,
+    b: Rep3FixedPointShare<F>,
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3FixedPointShare<F>> {
+    mul_trunc(a, b, config, io_context)
+}
+
+/// Performs multiplication of a shared fixed-point number and an encoded public fixed-point number, followed by a probabilistic truncation (see [`truncate_vec`]).
+pub fn mul_public<F: PrimeField, N: Rep3Network>(
+    shared: Rep3FixedPointShare<F>,
+    public: F,
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3FixedPointShare<F>> {
+    truncate(arithmetic::mul_public(shared, public), config, io_context)
+}
+
+/// Returns 1 if the shared fixed-point number is negative and 0 otherwise, for each element of the vector.
+pub fn is_negative_vec<F: PrimeField, N: Rep3Network>(
+    x: &[Rep3FixedPointShare<F>],
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
+    // the shifted value is smaller than 2^(bit_size-1) iff x is negative
+    let offset = F::from(BigUint::from(1u64) << (config.bit_size - 1));
+    let id = io_context.id;
+    let shifted = x.iter().map(|x| to_unsigned(*x, config, id)).collect_vec();
+    let offsets = vec![arithmetic::promote_to_trivial_share(id, offset); x.len()];
+    arithmetic::lt_many(&shifted, &offsets, io_context)
+}
+
+/// Performs element-wise division of two vectors of shared fixed-point numbers, i.e., computes lhs * 2^frac_bits / rhs rounded towards zero. The result is exact up to the rounding. A division by zero does not abort, but results in an unspecified value.
+pub fn div_vec<F: PrimeField, N: Rep3Network>(
+    lhs: &[Rep3FixedPointShare<F>],
+    rhs: &[Rep3FixedPointShare<F>],
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3FixedPointShare<F>>> {
+    assert_eq!(lhs.len(), rhs.len());
+    config.assert_supported::<F>();
+    let len = lhs.len();
+
+    // compute the absolute values as |x| = x - 2 * is_negative(x) * x
+    let inputs = lhs.iter().chain(rhs.iter()).copied().collect_vec();
+    let signs = is_negative_vec(&inputs, config, io_context)?;
+    let signed = arithmetic::mul_vec(&signs, &inputs, io_context)?;
+    let abs = izip!(inputs, signed).map(|(x, s)| x - s - s).collect_vec();
+    let (abs_lhs, abs_rhs) = abs.split_at(len);
+
+    // the integer division of the scaled dividend by the divisor
+    let scale = config.scale::<F>();
+    let dividends = abs_lhs
+        .iter()
+        .map(|x| arithmetic::mul_public(*x, scale))
+        .collect_vec();
+    let quotients = yao::field_int_div_many(
+        &dividends,
+        abs_rhs,
+        io_context,
+        config.bit_size + config.frac_bits,
+        config.bit_size,
+    )?;
+
+    // the sign of the result is the xor of the signs of the inputs
+    let (signs_lhs, signs_rhs) = signs.split_at(len);
+    let signs_and = arithmetic::mul_vec(signs_lhs, signs_rhs, io_context)?;
+    let signs = izip!(signs_lhs, signs_rhs, signs_and)
+        .map(|(a, b, and)| a + b - and - and)
+        .collect_vec();
+    let signed = arithmetic::mul_vec(&signs, &quotients, io_context)?;
+    Ok(izip!(quotients, signed).map(|(q, s)| q - s - s).collect())
+}
+
+/// Performs division of two shared fixed-point numbers. See [`div_vec`] for details.
+pub fn div<F: PrimeField, N: Rep3Network>(
+    lhs: Rep3FixedPointShare<F>,
+    rhs: Rep3FixedPointShare<F>,
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3FixedPointShare<F>> {
+    let res = div_vec(&[lhs], &[rhs], config, io_context)?;
+    Ok(res[0])
+}
+
+/// Returns 1 if lhs < rhs and 0 otherwise, where both values are interpreted as signed fixed-point numbers.
+pub fn lt<F: PrimeField, N: Rep3Network>(
+    lhs: Rep3FixedPointShare<F>,
+    rhs: Rep3FixedPointShare<F>,
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3PrimeFieldShare<F>> {
+    let id = io_context.id;
+    arithmetic::lt(
+        to_unsigned(lhs, config, id),
+        to_unsigned(rhs, config, id),
+        io_context,
+    )
+}
+
+/// Returns 1 if lhs <= rhs and 0 otherwise, where both values are interpreted as signed fixed-point numbers.
+pub fn le<F: PrimeField, N: Rep3Network>(
+    lhs: Rep3FixedPointShare<F>,
+    rhs: Rep3FixedPointShare<F>,
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3PrimeFieldShare<F>> {
+    let id = io_context.id;
+    arithmetic::le(
+        to_unsigned(lhs, config, id),
+        to_unsigned(rhs, config, id),
+        io_context,
+    )
+}
+
+/// Returns 1 if lhs > rhs and 0 otherwise, where both values are interpreted as signed fixed-point numbers.
+pub fn gt<F: PrimeField, N: Rep3Network>(
+    lhs: Rep3FixedPointShare<F>,
+    rhs: Rep3FixedPointShare<F>,
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3PrimeFieldShare<F>> {
+    lt(rhs, lhs, config, io_context)
+}
+
+/// Returns 1 if lhs >= rhs and 0 otherwise, where both values are interpreted as signed fixed-point numbers.
+pub fn ge<F: PrimeField, N: Rep3Network>(
+    lhs: Rep3FixedPointShare<F>,
+    rhs: Rep3FixedPointShare<F>,
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3PrimeFieldShare<F>> {
+    le(rhs, lhs, config, io_context)
+}
+
+/// Returns 1 if the shared fixed-point number is smaller than the encoded public fixed-point number and 0 otherwise.
+pub fn lt_public<F: PrimeField, N: Rep3Network>(
+    lhs: Rep3FixedPointShare<F>,
+    rhs: F,
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3PrimeFieldShare<F>> {
+    let offset = F::from(BigUint::from(1u64) << (config.bit_size - 1));
+    let id = io_context.id;
+    arithmetic::lt_public(to_unsigned(lhs, config, id), rhs + offset, io_context)
+}
+
+/// Returns 1 if the shared fixed-point number is greater than the encoded public fixed-point number and 0 otherwise.
+pub fn gt_public<F: PrimeField, N: Rep3Network>(
+    lhs: Rep3FixedPointShare<F>,
+    rhs: F,
+    config: &FixedPointConfig,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Rep3PrimeFieldShare<F>> {
+    let offset = F::from(BigUint::from(1u64) << (config.bit_size - 1));
+    let id = io_context.id;
+    arithmetic::gt_public(to_unsigned(lhs, config, id), rhs + offset, io_context)
+}
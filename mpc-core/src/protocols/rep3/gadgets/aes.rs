@@ -0,0 +1,325 @@
+//! AES
+//!
+//! This module contains the AES-128 encryption for the Rep3 protocol. The bytes of all encryptions are bit-sliced into packed binary shares, i.e., one binary share per byte position and bit, which packs the respective bit of all encryptions. Thus, ShiftRows, MixColumns, and AddRoundKey are local, while the S-boxes compute the inverse in GF(2^8) as x^254 using four multiplication rounds for all S-boxes of an AES round, including the ones of the key schedule.
+
+use crate::protocols::rep3::{
+    arithmetic::FieldShare,
+    binary, conversion,
+    id::PartyID,
+    network::{IoContext, Rep3Network},
+    IoResult, Rep3BigUintShare,
+};
+use ark_ff::{One, PrimeField, Zero};
+use itertools::{izip, Itertools};
+use num_bigint::BigUint;
+
+type BinaryShare<F> = Rep3BigUintShare<F>;
+
+/// The bits of a byte, starting with the least significant bit. Each binary share packs the respective bit of all encryptions.
+type Byte<F> = [BinaryShare<F>; 8];
+
+const ROUNDS: usize = 10;
+
+const RCON: [u8; ROUNDS] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+fn xor<F: PrimeField>(a: &Byte<F>, b: &Byte<F>) -> Byte<F> {
+    std::array::from_fn(|i| &a[i] ^ &b[i])
+}
+
+/// XORs the public byte to the shared byte of all encryptions, where `ones` has one bit set for each encryption.
+fn xor_public<F: PrimeField>(a: &Byte<F>, public: u8, ones: &BigUint, id: PartyID) -> Byte<F> {
+    std::array::from_fn(|i| {
+        if (public >> i) & 1 == 1 {
+            binary::xor_public(&a[i], ones, id)
+        } else {
+            a[i].to_owned()
+        }
+    })
+}
+
+/// Reduces a polynomial over GF(2) of degree less than 15 modulo the AES polynomial x^8 + x^4 + x^3 + x + 1.
+fn reduce<F: PrimeField>(mut c: Vec<BinaryShare<F>>) -> Byte<F> {
+    for k in (8..c.len()).rev() {
+        let high = std::mem::take(&mut c[k]);
+        for j in [k - 4, k - 5, k - 7, k - 8] {
+            c[j] ^= &high;
+        }
+    }
+    c.truncate(8);
+    c.try_into().expect("eight bits")
+}
+
+fn square<F: PrimeField>(a: &Byte<F>) -> Byte<F> {
+    let mut c = vec![BinaryShare::zero_share(); 15];
+    for (i, a) in a.iter().enumerate() {
+        c[2 * i] = a.to_owned();
+    }
+    reduce(c)
+}
+
+fn pow_2k<F: PrimeField>(a: &Byte<F>, k: usize) -> Byte<F> {
+    (0..k).fold(a.to_owned(), |acc, _| square(&acc))
+}
+
+/// Multiplies the byte by x in GF(2^8).
+fn xtime<F: PrimeField>(a: &Byte<F>) -> Byte<F> {
+    let mut c = vec![BinaryShare::zero_share(); 9];
+    for (i, a) in a.iter().enumerate() {
+        c[i + 1] = a.to_owned();
+    }
+    reduce(c)
+}
+
+/// Multiplies all pairs of bytes in GF(2^8) in a single communication round.
+fn mul_many<F: PrimeField, N: Rep3Network>(
+    lhs: &[Byte<F>],
+    rhs: &[Byte<F>],
+    num_instances: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Byte<F>>> {
+    debug_assert_eq!(lhs.len(), rhs.len());
+    let mut lhs_packed = BinaryShare::zero_share();
+    let mut rhs_packed = BinaryShare::zero_share();
+    for (l, (a, b)) in lhs.iter().zip(rhs.iter()).enumerate() {
+        for (i, a) in a.iter().enumerate() {
+            for (j, b) in b.iter().enumerate() {
+                let offset = (64 * l + 8 * i + j) * num_instances;
+                lhs_packed ^= a << offset;
+                rhs_packed ^= b << offset;
+            }
+        }
+    }
+    let bitlen = 64 * lhs.len() * num_instances;
+    let ands = binary::and_with_bitlen(&lhs_packed, &rhs_packed, io_context, bitlen)?;
+
+    let mask = (BigUint::one() << num_instances) - BigUint::one();
+    let res = (0..lhs.len())
+        .map(|l| {
+            let mut c = vec![BinaryShare::zero_share(); 15];
+            for i in 0..8 {
+                for j in 0..8 {
+                    let offset = (64 * l + 8 * i + j) * num_instances;
+                    c[i + j] ^= &(&ands >> offset) & &mask;
+                }
+            }
+            reduce(c)
+        })
+        .collect();
+    Ok(res)
+}
+
+/// Applies the AES S-box to all bytes. The inverse in GF(2^8) is computed as x^254 = (((x^3)^4 * x^3)^16 * x^12) * x^2, where all squarings are local.
+fn sub_bytes_many<F: PrimeField, N: Rep3Network>(
+    bytes: &[Byte<F>],
+    num_instances: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Byte<F>>> {
+    let x2 = bytes.iter().map(square).collect_vec();
+    let x3 = mul_many(&x2, bytes, num_instances, io_context)?;
+    let x12 = x3.iter().map(|x| pow_2k(x, 2)).collect_vec();
+    let x15 = mul_many(&x12, &x3, num_instances, io_context)?;
+    let x240 = x15.iter().map(|x| pow_2k(x, 4)).collect_vec();
+    let x252 = mul_many(&x240, &x12, num_instances, io_context)?;
+    let inv = mul_many(&x252, &x2, num_instances, io_context)?;
+
+    // Affine transformation
+    let ones = (BigUint::one() << num_instances) - BigUint::one();
+    let id = io_context.id;
+    Ok(inv
+        .iter()
+        .map(|b| {
+            let b = std::array::from_fn(|i| {
+                let mut res = b[i].to_owned();
+                for j in 4..8 {
+                    res ^= &b[(i + j) % 8];
+                }
+                res
+            });
+            xor_public(&b, 0x63, &ones, id)
+        })
+        .collect())
+}
+
+fn mix_columns<F: PrimeField>(state: &[Byte<F>]) -> Vec<Byte<F>> {
+    state
+        .chunks_exact(4)
+        .flat_map(|column| {
+            let doubled = column.iter().map(xtime).collect_vec();
+            (0..4)
+                .map(|r| {
+                    // 2 * a_r + 3 * a_{r + 1} + a_{r + 2} + a_{r + 3}
+                    let mut res = xor(&doubled[r], &doubled[(r + 1) % 4]);
+                    for j in 1..4 {
+                        res = xor(&res, &column[(r + j) % 4]);
+                    }
+                    res
+                })
+                .collect_vec()
+        })
+        .collect()
+}
+
+/// Computes the AES-128 encryption of `num_instances` plaintexts under the respective keys at once. The keys and plaintexts consist of 16 bytes each, where each byte is given by its bits, starting with the least significant bit. Each of the binary shares packs the respective bit of all encryptions, i.e., bit i belongs to the i-th encryption.
+pub fn aes128_encrypt_packed<F: PrimeField, N: Rep3Network>(
+    keys: &[Byte<F>; 16],
+    plaintexts: &[Byte<F>; 16],
+    num_instances: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<[Byte<F>; 16]> {
+    let n = num_instances;
+    let id = io_context.id;
+    let ones = (BigUint::one() << n) - BigUint::one();
+
+    // Byte r + 4c is the byte in row r and column c
+    let mut round_key = keys.to_vec();
+    let mut state = izip!(plaintexts, &round_key)
+        .map(|(p, k)| xor(p, k))
+        .collect_vec();
+    for (round, rcon) in RCON.into_iter().enumerate() {
+        // SubBytes of the state and SubWord(RotWord) of the last word of the key in one batch
+        let mut bytes = state;
+        bytes.extend([13, 14, 15, 12].map(|i| round_key[i].to_owned()));
+        let mut bytes = sub_bytes_many(&bytes, n, io_context)?;
+        let mut temp = bytes.split_off(16);
+        temp[0] = xor_public(&temp[0], rcon, &ones, id);
+
+        // Key schedule, each word is the XOR of the previous word and the respective word of the previous round key
+        let mut prev = temp;
+        for word in round_key.chunks_exact_mut(4) {
+            for (w, p) in word.iter_mut().zip(prev.iter()) {
+                *w = xor(w, p);
+            }
+            prev = word.to_vec();
+        }
+
+        // ShiftRows
+        let shifted = (0..16)
+            .map(|i| {
+                let (r, c) = (i % 4, i / 4);
+                bytes[r + 4 * ((c + r) % 4)].to_owned()
+            })
+            .collect_vec();
+        // MixColumns is skipped in the last round
+        let mixed = if round + 1 < ROUNDS {
+            mix_columns(&shifted)
+        } else {
+            shifted
+        };
+        // AddRoundKey
+        state = izip!(&mixed, &round_key).map(|(s, k)| xor(s, k)).collect();
+    }
+    Ok(state.try_into().expect("16 bytes"))
+}
+
+/// Converts binary shares of bytes, ordered by byte position and encryption, into the packed representation of [`aes128_encrypt_packed`].
+fn bytes_to_packed<F: PrimeField>(bytes: &[BinaryShare<F>], num_instances: usize) -> [Byte<F>; 16] {
+    let packed = bytes
+        .chunks_exact(num_instances)
+        .map(|bytes| {
+            std::array::from_fn(|k| {
+                let mut res = BinaryShare::zero_share();
+                for (i, byte) in bytes.iter().enumerate() {
+                    res ^= (&(byte >> k) & &BigUint::one()) << i;
+                }
+                res
+            })
+        })
+        .collect_vec();
+    packed.try_into().expect("16 bytes")
+}
+
+/// Converts the packed representation of [`aes128_encrypt_packed`] into arithmetic shares of the bytes of all encryptions.
+fn packed_to_arithmetic<F: PrimeField, N: Rep3Network>(
+    packed: &[Byte<F>; 16],
+    num_instances: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<[FieldShare<F>; 16]>> {
+    let bits = (0..num_instances)
+        .flat_map(|i| {
+            packed
+                .iter()
+                .flat_map(move |byte| byte.iter().map(move |bit| &(bit >> i) & &BigUint::one()))
+        })
+        .collect_vec();
+    let bits = conversion::bit_inject_many(&bits, io_context)?;
+    let bytes = bits
+        .chunks_exact(8)
+        .map(|bits| {
+            bits.iter()
+                .rev()
+                .fold(FieldShare::default(), |acc, bit| acc + acc + *bit)
+        })
+        .collect_vec();
+    Ok(bytes
+        .chunks_exact(16)
+        .map(|bytes| bytes.try_into().expect("16 bytes"))
+        .collect())
+}
+
+fn check_lengths(keys: usize, plaintexts: usize) -> IoResult<()> {
+    if keys != plaintexts {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "AES-128 encryption requires the same number of keys and plaintexts",
+        ));
+    }
+    Ok(())
+}
+
+/// Computes the AES-128 encryptions of the shared plaintexts under the respective shared keys. The keys, plaintexts, and resulting ciphertexts are given as arithmetic shares of bytes in the order defined by AES. The conversions to and from binary shares are batched for all encryptions.
+///
+/// All bytes have to be smaller than 256. Otherwise, the result is not specified.
+pub fn aes128_encrypt_many<F: PrimeField, N: Rep3Network>(
+    keys: &[[FieldShare<F>; 16]],
+    plaintexts: &[[FieldShare<F>; 16]],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<[FieldShare<F>; 16]>> {
+    check_lengths(keys.len(), plaintexts.len())?;
+    let n = keys.len();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    let bytes = (0..16)
+        .flat_map(|j| keys.iter().map(move |key| key[j]))
+        .chain((0..16).flat_map(|j| plaintexts.iter().map(move |p| p[j])))
+        .collect_vec();
+    let bytes = conversion::a2y2b_many(&bytes, io_context)?;
+    let (keys, plaintexts) = bytes.split_at(16 * n);
+    let keys = bytes_to_packed(keys, n);
+    let plaintexts = bytes_to_packed(plaintexts, n);
+    let ciphertexts = aes128_encrypt_packed(&keys, &plaintexts, n, io_context)?;
+    packed_to_arithmetic(&ciphertexts, n, io_context)
+}
+
+/// Computes the AES-128 encryptions of the public plaintexts under the respective shared keys. The keys and resulting ciphertexts are given as arithmetic shares of bytes in the order defined by AES. The conversions to and from binary shares are batched for all encryptions.
+///
+/// All key bytes have to be smaller than 256. Otherwise, the result is not specified.
+pub fn aes128_encrypt_public_plaintext_many<F: PrimeField, N: Rep3Network>(
+    keys: &[[FieldShare<F>; 16]],
+    plaintexts: &[[u8; 16]],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<[FieldShare<F>; 16]>> {
+    check_lengths(keys.len(), plaintexts.len())?;
+    let n = keys.len();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    let id = io_context.id;
+    let bytes = (0..16)
+        .flat_map(|j| keys.iter().map(move |key| key[j]))
+        .collect_vec();
+    let bytes = conversion::a2y2b_many(&bytes, io_context)?;
+    let keys = bytes_to_packed(&bytes, n);
+    let plaintexts = std::array::from_fn(|j| {
+        std::array::from_fn(|k| {
+            let bits = plaintexts
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| (p[j] >> k) & 1 == 1)
+                .fold(BigUint::zero(), |acc, (i, _)| acc | (BigUint::one() << i));
+            binary::promote_to_trivial_share(id, &bits)
+        })
+    });
+    let ciphertexts = aes128_encrypt_packed(&keys, &plaintexts, n, io_context)?;
+    packed_to_arithmetic(&ciphertexts, n, io_context)
+}
@@ -0,0 +1,374 @@
+//! Elliptic curves
+//!
+//! This module contains arithmetic on short Weierstrass curves y^2 = x^3 + b which are defined over the field of the shares, such as the embedded curve Grumpkin of BN254, for the Rep3 protocol. The coordinates of the points are shared. Points are represented in projective coordinates and added using the complete addition formulas of Renes, Costello, and Batina (Algorithm 7 of <https://eprint.iacr.org/2015/1060>), such that doublings and the point at infinity do not need to be handled separately. Each addition requires two communication rounds, which are batched for all additions that can be computed in parallel.
+
+use crate::protocols::rep3::{
+    arithmetic::{self, FieldShare},
+    id::PartyID,
+    network::{IoContext, Rep3Network},
+    IoResult,
+};
+use ark_ff::PrimeField;
+use itertools::{izip, Itertools};
+use num_bigint::BigUint;
+use std::ops::{Add, Mul, Sub};
+
+/// A point in projective coordinates (X : Y : Z), where the point at infinity is (0 : 1 : 0).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProjectivePoint<T> {
+    /// The X coordinate
+    pub x: T,
+    /// The Y coordinate
+    pub y: T,
+    /// The Z coordinate
+    pub z: T,
+}
+
+/// Type alias for a [`ProjectivePoint`] with shared coordinates
+pub type PointShare<F> = ProjectivePoint<FieldShare<F>>;
+
+impl<F: PrimeField> ProjectivePoint<F> {
+    /// Returns the point at infinity.
+    pub fn infinity() -> Self {
+        Self {
+            x: F::zero(),
+            y: F::one(),
+            z: F::zero(),
+        }
+    }
+
+    /// Creates a point from its affine coordinates. If `is_infinity` is set, the coordinates are ignored.
+    pub fn from_affine(x: F, y: F, is_infinity: bool) -> Self {
+        if is_infinity {
+            Self::infinity()
+        } else {
+            Self { x, y, z: F::one() }
+        }
+    }
+
+    /// Returns the affine coordinates of the point and whether it is the point at infinity, in which case both coordinates are zero.
+    pub fn to_affine(&self) -> (F, F, bool) {
+        match self.z.inverse() {
+            Some(z_inv) => (self.x * z_inv, self.y * z_inv, false),
+            None => (F::zero(), F::zero(), true),
+        }
+    }
+}
+
+impl<F: PrimeField> PointShare<F> {
+    /// Creates a shared point from shared affine coordinates and a shared bit, which indicates whether the point is the point at infinity. In the latter case, both coordinates have to be zero.
+    pub fn from_affine_shared(
+        x: FieldShare<F>,
+        y: FieldShare<F>,
+        is_infinity: FieldShare<F>,
+        id: PartyID,
+    ) -> Self {
+        Self {
+            x,
+            y: y + is_infinity,
+            z: arithmetic::add_public(-is_infinity, F::one(), id),
+        }
+    }
+
+    /// Transforms a public point into a shared point.
+    pub fn promote_to_trivial_share(id: PartyID, point: &ProjectivePoint<F>) -> Self {
+        Self {
+            x: arithmetic::promote_to_trivial_share(id, point.x),
+            y: arithmetic::promote_to_trivial_share(id, point.y),
+            z: arithmetic::promote_to_trivial_share(id, point.z),
+        }
+    }
+}
+
+/// A short Weierstrass curve y^2 = x^3 + b over the field of the shares.
+#[derive(Clone, Copy, Debug)]
+pub struct ShortWeierstrassCurve<F: PrimeField> {
+    // 3 * b as used by the complete addition formulas
+    b3: F,
+}
+
+impl<F: PrimeField> ShortWeierstrassCurve<F> {
+    /// Creates a new curve y^2 = x^3 + b. The complete addition formulas are only correct for curves of odd order, so `b` has to be chosen accordingly.
+    pub fn new(b: F) -> Self {
+        assert!(!b.is_zero());
+        Self { b3: b + b + b }
+    }
+
+    /// Returns the pairs of factors of the first multiplication round of an addition.
+    fn add_factors_first<T>(p: &ProjectivePoint<T>, q: &ProjectivePoint<T>) -> [(T, T); 6]
+    where
+        T: Copy + Add<Output = T>,
+    {
+        [
+            (p.x, q.x),
+            (p.y, q.y),
+            (p.z, q.z),
+            (p.x + p.y, q.x + q.y),
+            (p.y + p.z, q.y + q.z),
+            (p.x + p.z, q.x + q.z),
+        ]
+    }
+
+    /// Returns the pairs of factors of the second multiplication round of an addition given the products of the first round.
+    fn add_factors_second<T>(&self, products: [T; 6]) -> [(T, T); 6]
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<F, Output = T>,
+    {
+        let [xx, yy, zz, xy, yz, xz] = products;
+        let xy = xy - xx - yy;
+        let yz = yz - yy - zz;
+        let xz = xz - xx - zz;
+        let t0 = xx + xx + xx;
+        let b3zz = zz * self.b3;
+        let t1 = yy - b3zz;
+        let z3 = yy + b3zz;
+        let y3 = xz * self.b3;
+        [(xy, t1), (yz, y3), (t1, z3), (y3, t0), (z3, yz), (t0, xy)]
+    }
+
+    /// Returns the sum given the products of the second multiplication round of an addition.
+    fn add_finish<T>(products: [T; 6]) -> ProjectivePoint<T>
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T>,
+    {
+        let [a, b, c, d, e, f] = products;
+        ProjectivePoint {
+            x: a - b,
+            y: c + d,
+            z: e + f,
+        }
+    }
+
+    /// Adds two plain points.
+    pub fn add(&self, p: &ProjectivePoint<F>, q: &ProjectivePoint<F>) -> ProjectivePoint<F> {
+        let products = Self::add_factors_first(p, q).map(|(a, b)| a * b);
+        let products = self.add_factors_second(products).map(|(a, b)| a * b);
+        Self::add_finish(products)
+    }
+
+    /// Multiplies a plain point by a scalar using double-and-add.
+    pub fn scalar_mul(&self, p: &ProjectivePoint<F>, scalar: &BigUint) -> ProjectivePoint<F> {
+        (0..scalar.bits())
+            .rev()
+            .fold(ProjectivePoint::infinity(), |acc, i| {
+                let acc = self.add(&acc, &acc);
+                if scalar.bit(i) {
+                    self.add(&acc, p)
+                } else {
+                    acc
+                }
+            })
+    }
+
+    /// Computes the multi-scalar multiplication of plain points and scalars.
+    pub fn msm(&self, points: &[ProjectivePoint<F>], scalars: &[BigUint]) -> ProjectivePoint<F> {
+        debug_assert_eq!(points.len(), scalars.len());
+        izip!(points, scalars).fold(ProjectivePoint::infinity(), |acc, (p, s)| {
+            self.add(&acc, &self.scalar_mul(p, s))
+        })
+    }
+
+    /// Adds all pairs of shared points in two communication rounds.
+    pub fn rep3_add_many<N: Rep3Network>(
+        &self,
+        lhs: &[PointShare<F>],
+        rhs: &[PointShare<F>],
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Vec<PointShare<F>>> {
+        debug_assert_eq!(lhs.len(), rhs.len());
+        let (a, b): (Vec<_>, Vec<_>) = izip!(lhs, rhs)
+            .flat_map(|(p, q)| Self::add_factors_first(p, q))
+            .unzip();
+        let products = arithmetic::mul_vec(&a, &b, io_context)?;
+        let (a, b): (Vec<_>, Vec<_>) = products
+            .chunks_exact(6)
+            .flat_map(|products| {
+                self.add_factors_second(products.try_into().expect("six products"))
+            })
+            .unzip();
+        let products = arithmetic::mul_vec(&a, &b, io_context)?;
+        Ok(products
+            .chunks_exact(6)
+            .map(|products| Self::add_finish(products.try_into().expect("six products")))
+            .collect())
+    }
+
+    /// Adds two shared points.
+    pub fn rep3_add<N: Rep3Network>(
+        &self,
+        p: &PointShare<F>,
+        q: &PointShare<F>,
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<PointShare<F>> {
+        let res =
+            self.rep3_add_many(std::slice::from_ref(p), std::slice::from_ref(q), io_context)?;
+        Ok(res[0])
+    }
+
+    /// Sums up the points of each group using a binary tree, where the additions of all groups on the same level of the trees are batched.
+    fn rep3_sum_many<N: Rep3Network>(
+        &self,
+        mut groups: Vec<Vec<PointShare<F>>>,
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Vec<PointShare<F>>> {
+        while groups.iter().any(|group| group.len() > 1) {
+            let (lhs, rhs): (Vec<_>, Vec<_>) = groups
+                .iter()
+                .flat_map(|group| group.chunks_exact(2).map(|pair| (pair[0], pair[1])))
+                .unzip();
+            let mut sums = self.rep3_add_many(&lhs, &rhs, io_context)?.into_iter();
+            for group in groups.iter_mut() {
+                let remainder = (group.len() % 2 == 1).then(|| group[group.len() - 1]);
+                let mut next = sums.by_ref().take(group.len() / 2).collect_vec();
+                next.extend(remainder);
+                *group = next;
+            }
+        }
+        let id = io_context.id;
+        Ok(groups
+            .into_iter()
+            .map(|group| {
+                group.first().copied().unwrap_or_else(|| {
+                    PointShare::promote_to_trivial_share(id, &ProjectivePoint::infinity())
+                })
+            })
+            .collect())
+    }
+
+    /// Returns bit * point, i.e., the point if the shared bit is set and the point at infinity otherwise. This is a local operation for public points.
+    fn select_public_point(
+        bit: FieldShare<F>,
+        point: &ProjectivePoint<F>,
+        id: PartyID,
+    ) -> PointShare<F> {
+        PointShare {
+            x: bit * point.x,
+            y: arithmetic::add_public(bit * (point.y - F::one()), F::one(), id),
+            z: bit * point.z,
+        }
+    }
+
+    /// Returns the multiples 2^i * point for i < `num_bits`.
+    fn multiples(&self, point: &ProjectivePoint<F>, num_bits: usize) -> Vec<ProjectivePoint<F>> {
+        std::iter::successors(Some(*point), |p| Some(self.add(p, p)))
+            .take(num_bits)
+            .collect()
+    }
+
+    /// Multiplies all public points by the respective shared scalars, which have to be smaller than 2^`num_bits`. The scalars are decomposed into bits, which select the multiples of the points locally, and the selected points are summed up using a binary tree. All multiplications are batched, such that the number of communication rounds does not depend on the number of points.
+    pub fn rep3_scalar_mul_public_point_many<N: Rep3Network>(
+        &self,
+        points: &[ProjectivePoint<F>],
+        scalars: &[FieldShare<F>],
+        num_bits: usize,
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Vec<PointShare<F>>> {
+        debug_assert_eq!(points.len(), scalars.len());
+        let id = io_context.id;
+        let bits = arithmetic::to_bits_many(scalars, num_bits, io_context)?;
+        let groups = izip!(points, bits)
+            .map(|(point, bits)| {
+                izip!(self.multiples(point, num_bits), bits)
+                    .map(|(multiple, bit)| Self::select_public_point(bit, &multiple, id))
+                    .collect()
+            })
+            .collect();
+        self.rep3_sum_many(groups, io_context)
+    }
+
+    /// Computes the multi-scalar multiplication of public points and shared scalars, which have to be smaller than 2^`num_bits`. See [`Self::rep3_scalar_mul_public_point_many`] for details.
+    pub fn rep3_msm_public_points<N: Rep3Network>(
+        &self,
+        points: &[ProjectivePoint<F>],
+        scalars: &[FieldShare<F>],
+        num_bits: usize,
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<PointShare<F>> {
+        debug_assert_eq!(points.len(), scalars.len());
+        let id = io_context.id;
+        let bits = arithmetic::to_bits_many(scalars, num_bits, io_context)?;
+        let group = izip!(points, bits)
+            .flat_map(|(point, bits)| {
+                izip!(self.multiples(point, num_bits), bits)
+                    .map(|(multiple, bit)| Self::select_public_point(bit, &multiple, id))
+                    .collect_vec()
+            })
+            .collect();
+        let res = self.rep3_sum_many(vec![group], io_context)?;
+        Ok(res[0])
+    }
+
+    /// Computes the multi-scalar multiplication of shared points and shared scalars, which have to be smaller than 2^`num_bits`. In contrast to [`Self::rep3_msm_public_points`], the multiples of the points have to be computed by `num_bits - 1` batched doublings and selecting them requires one additional communication round.
+    pub fn rep3_msm<N: Rep3Network>(
+        &self,
+        points: &[PointShare<F>],
+        scalars: &[FieldShare<F>],
+        num_bits: usize,
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<PointShare<F>> {
+        debug_assert_eq!(points.len(), scalars.len());
+        let id = io_context.id;
+        let bits = arithmetic::to_bits_many(scalars, num_bits, io_context)?;
+
+        // multiples[i][j] = 2^i * points[j]
+        let mut multiples = vec![points.to_vec()];
+        for _ in 1..num_bits {
+            let last = multiples.last().expect("at least one multiple");
+            let doubled = self.rep3_add_many(last, last, io_context)?;
+            multiples.push(doubled);
+        }
+
+        // bit * (X, Y - 1, Z) + (0, 1, 0)
+        let (lhs, rhs): (Vec<_>, Vec<_>) = bits
+            .into_iter()
+            .enumerate()
+            .flat_map(|(j, bits)| {
+                let multiples = &multiples;
+                bits.into_iter().enumerate().flat_map(move |(i, bit)| {
+                    let p = multiples[i][j];
+                    [
+                        (bit, p.x),
+                        (bit, arithmetic::add_public(p.y, -F::one(), id)),
+                        (bit, p.z),
+                    ]
+                })
+            })
+            .unzip();
+        let selected = arithmetic::mul_vec(&lhs, &rhs, io_context)?;
+        let group = selected
+            .chunks_exact(3)
+            .map(|p| PointShare {
+                x: p[0],
+                y: arithmetic::add_public(p[1], F::one(), id),
+                z: p[2],
+            })
+            .collect();
+        let res = self.rep3_sum_many(vec![group], io_context)?;
+        Ok(res[0])
+    }
+
+    /// Computes the affine coordinates of all shared points and shared bits indicating whether the points are the point at infinity, in which case both coordinates are zero.
+    #[expect(clippy::type_complexity)]
+    pub fn rep3_to_affine_many<N: Rep3Network>(
+        points: &[PointShare<F>],
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Vec<(FieldShare<F>, FieldShare<F>, FieldShare<F>)>> {
+        let id = io_context.id;
+        let z = points.iter().map(|p| p.z).collect_vec();
+        let is_infinity = arithmetic::is_zero_many(&z, io_context)?;
+        // Replace Z by 1 for the point at infinity to be able to invert it
+        let z = izip!(z, &is_infinity).map(|(z, i)| z + *i).collect_vec();
+        let z_inv = arithmetic::inv_vec(&z, io_context)?;
+        let not_infinity = is_infinity
+            .iter()
+            .map(|i| arithmetic::add_public(-*i, F::one(), id))
+            .collect_vec();
+        let z_inv = arithmetic::mul_vec(&z_inv, &not_infinity, io_context)?;
+        let lhs = points.iter().flat_map(|p| [p.x, p.y]).collect_vec();
+        let rhs = z_inv.iter().flat_map(|z| [*z, *z]).collect_vec();
+        let coordinates = arithmetic::mul_vec(&lhs, &rhs, io_context)?;
+        Ok(izip!(coordinates.chunks_exact(2), is_infinity)
+            .map(|(c, i)| (c[0], c[1], i))
+            .collect())
+    }
+}
@@ -0,0 +1,683 @@
+//! ECDSA
+//!
+//! This module contains the ECDSA signature verification for the Rep3 protocol on short Weierstrass curves whose fields differ from the field of the shares, such as secp256k1 and secp256r1. The arithmetic in the scalar field of the curve is performed on arithmetic shares over that field and the scalar multiplications are computed on additively shared points using [`pointshare`]. Only converting the shared public key into a shared point and comparing the x-coordinate of the result with the signature requires arithmetic in the base field of the curve. This arithmetic is emulated on binary shares using packed Kogge-Stone adders, carry-save adders, and Barrett reduction, where the point additions use the complete formulas of Renes, Costello, and Batina (Algorithm 1 of <https://eprint.iacr.org/2015/1060>).
+//!
+//! To convert the public key Q into a shared point, the parties compute T = Q + rho * G for a random shared rho in the emulated base field and open T, which is uniformly random. Afterwards, Q = T - rho * G is a shared point.
+
+use crate::protocols::rep3::{
+    arithmetic::{self, FieldShare},
+    binary, conversion,
+    id::PartyID,
+    network::{IoContext, Rep3Network},
+    pointshare, IoResult, Rep3BigUintShare, Rep3PointShare,
+};
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    AffineRepr, CurveConfig, CurveGroup,
+};
+use ark_ff::{Field, One, PrimeField, Zero};
+use itertools::{izip, Itertools};
+use num_bigint::BigUint;
+
+type BinaryShare<F> = Rep3BigUintShare<F>;
+
+/// A point in projective coordinates (X : Y : Z), where each coordinate is binary shared.
+type Point<F> = [BinaryShare<F>; 3];
+
+/// The number of bytes of the coordinates of the public key, the components of the signature, and the hashed message.
+pub const NUM_BYTES: usize = 32;
+
+/// Returns a value with the lowest `bits` bits set.
+fn mask(bits: usize) -> BigUint {
+    (BigUint::one() << bits) - BigUint::one()
+}
+
+/// Returns a value which has the bits lo..hi of each of the `num_lanes` lanes of `width` bits set.
+fn lanes_mask(num_lanes: usize, width: usize, lo: usize, hi: usize) -> BigUint {
+    let lane = mask(hi) ^ mask(lo);
+    (0..num_lanes).fold(BigUint::zero(), |acc, i| acc | (&lane << (i * width)))
+}
+
+fn pack<F: PrimeField>(values: &[BinaryShare<F>], width: usize) -> BinaryShare<F> {
+    let mut res = BinaryShare::zero_share();
+    for (i, value) in values.iter().enumerate() {
+        res ^= value << (i * width);
+    }
+    res
+}
+
+fn unpack<F: PrimeField>(
+    value: &BinaryShare<F>,
+    count: usize,
+    width: usize,
+) -> Vec<BinaryShare<F>> {
+    let mask = mask(width);
+    (0..count)
+        .map(|i| &(value >> (i * width)) & &mask)
+        .collect()
+}
+
+/// Returns a sharing of the bit at position `pos`.
+fn bit<F: PrimeField>(x: &BinaryShare<F>, pos: usize) -> BinaryShare<F> {
+    &(x >> pos) & &BigUint::one()
+}
+
+/// Returns a sharing which has all `width` bits set to the bit at position `pos` of `x`. This is a local operation.
+fn broadcast<F: PrimeField>(x: &BinaryShare<F>, pos: usize, width: usize) -> BinaryShare<F> {
+    let ones = mask(width);
+    let f = |v: &BigUint| {
+        if v.bit(pos as u64) {
+            ones.to_owned()
+        } else {
+            BigUint::zero()
+        }
+    };
+    BinaryShare::new(f(&x.a), f(&x.b))
+}
+
+/// Reinterprets a binary share for a different field.
+fn cast<F: PrimeField, G: PrimeField>(x: &BinaryShare<F>) -> BinaryShare<G> {
+    BinaryShare::new(x.a.to_owned(), x.b.to_owned())
+}
+
+/// Computes the ANDs of all pairs of values of `width` bits in a single communication round.
+fn and_many<F: PrimeField, N: Rep3Network>(
+    lhs: &[BinaryShare<F>],
+    rhs: &[BinaryShare<F>],
+    width: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<BinaryShare<F>>> {
+    debug_assert_eq!(lhs.len(), rhs.len());
+    if lhs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let and = binary::and_with_bitlen(
+        &pack(lhs, width),
+        &pack(rhs, width),
+        io_context,
+        lhs.len() * width,
+    )?;
+    Ok(unpack(&and, lhs.len(), width))
+}
+
+/// Adds all pairs of values modulo 2^`width` using one packed Kogge-Stone adder.
+fn add_many<F: PrimeField, N: Rep3Network>(
+    lhs: &[BinaryShare<F>],
+    rhs: &[BinaryShare<F>],
+    width: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<BinaryShare<F>>> {
+    debug_assert_eq!(lhs.len(), rhs.len());
+    let n = lhs.len();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    let total = n * width;
+    let a = pack(lhs, width);
+    let b = pack(rhs, width);
+    let p = &a ^ &b;
+    let mut g = binary::and_with_bitlen(&a, &b, io_context, total)?;
+    let mut prop = p.to_owned();
+    let mut d = 1;
+    while d < width {
+        let shift_mask = lanes_mask(n, width, d, width);
+        let g_shifted = &(&g << d) & &shift_mask;
+        if 2 * d < width {
+            // Update generate and propagate in the same round
+            let p_shifted = &(&prop << d) & &shift_mask;
+            let lhs = &prop ^ &(&prop << total);
+            let rhs = &g_shifted ^ &(&p_shifted << total);
+            let and = binary::and_with_bitlen(&lhs, &rhs, io_context, 2 * total)?;
+            g ^= &(&and & &mask(total));
+            prop = &and >> total;
+        } else {
+            g ^= &binary::and_with_bitlen(&prop, &g_shifted, io_context, total)?;
+        }
+        d *= 2;
+    }
+    let carries = &(&g << 1) & &lanes_mask(n, width, 1, width);
+    Ok(unpack(&(&p ^ &carries), n, width))
+}
+
+/// Computes the sum of each group of values modulo 2^`width`. The groups are reduced to two values each using layers of carry-save adders, which are batched for all groups, followed by one packed Kogge-Stone adder.
+fn sum_many<F: PrimeField, N: Rep3Network>(
+    mut groups: Vec<Vec<BinaryShare<F>>>,
+    width: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<BinaryShare<F>>> {
+    let width_mask = mask(width);
+    while groups.iter().any(|group| group.len() > 2) {
+        // The carry of a full adder is the majority ((a ^ b) & (a ^ c)) ^ a
+        let (lhs, rhs): (Vec<_>, Vec<_>) = groups
+            .iter()
+            .filter(|group| group.len() > 2)
+            .flat_map(|group| {
+                group
+                    .chunks_exact(3)
+                    .map(|t| (&t[0] ^ &t[1], &t[0] ^ &t[2]))
+            })
+            .unzip();
+        let mut ands = and_many(&lhs, &rhs, width, io_context)?.into_iter();
+        for group in groups.iter_mut().filter(|group| group.len() > 2) {
+            let mut next = Vec::with_capacity(2 * group.len() / 3 + 2);
+            for t in group.chunks_exact(3) {
+                let majority = &ands.next().expect("one AND per full adder") ^ &t[0];
+                next.push(&(&t[0] ^ &t[1]) ^ &t[2]);
+                next.push(&(&majority << 1) & &width_mask);
+            }
+            next.extend(group.chunks_exact(3).remainder().iter().cloned());
+            *group = next;
+        }
+    }
+    let (lhs, rhs): (Vec<_>, Vec<_>) = groups
+        .into_iter()
+        .map(|group| {
+            let mut group = group.into_iter();
+            (
+                group.next().unwrap_or_default(),
+                group.next().unwrap_or_default(),
+            )
+        })
+        .unzip();
+    add_many(&lhs, &rhs, width, io_context)
+}
+
+/// Arithmetic modulo a public modulus on binary shares.
+struct Modulus {
+    modulus: BigUint,
+    // The number of bits of the modulus
+    bits: usize,
+    // The number of bits of the values that can be reduced
+    input_bits: usize,
+    // floor(2^input_bits / modulus) for the Barrett reduction
+    mu: BigUint,
+}
+
+impl Modulus {
+    fn new(modulus: BigUint) -> Self {
+        let bits = modulus.bits() as usize;
+        let input_bits = 2 * bits + 8;
+        let mu = (BigUint::one() << input_bits) / &modulus;
+        Self {
+            modulus,
+            bits,
+            input_bits,
+            mu,
+        }
+    }
+
+    /// Reduces the sum of each group of values modulo the modulus. The sums have to be smaller than 2^input_bits.
+    fn reduce_many<F: PrimeField, N: Rep3Network>(
+        &self,
+        groups: Vec<Vec<BinaryShare<F>>>,
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Vec<BinaryShare<F>>> {
+        let k = self.bits;
+        let m = self.input_bits;
+        let id = io_context.id;
+        let x = sum_many(groups, m, io_context)?;
+
+        // q = floor(floor(x / 2^(k - 1)) * mu / 2^(m - k + 1)) satisfies floor(x / p) - 2 <= q <= floor(x / p)
+        let groups = x
+            .iter()
+            .map(|x| {
+                let x = x >> (k - 1);
+                (0..self.mu.bits())
+                    .filter(|j| self.mu.bit(*j))
+                    .map(|j| &x << j as usize)
+                    .collect()
+            })
+            .collect();
+        let q = sum_many(groups, 2 * (m - k) + 3, io_context)?;
+
+        // r = x - q * p modulo 2^(k + 2), which is smaller than 3p
+        let r_mask = mask(k + 2);
+        let ones = (0..self.modulus.bits())
+            .filter(|j| self.modulus.bit(*j))
+            .collect_vec();
+        let groups =
+            izip!(&x, &q)
+                .map(|(x, q)| {
+                    let q = q >> (m - k + 1);
+                    let mut rows = vec![x & &r_mask];
+                    // -y = !y + 1 for each subtracted row
+                    rows.extend(ones.iter().map(|j| {
+                        binary::xor_public(&(&(&q << *j as usize) & &r_mask), &r_mask, id)
+                    }));
+                    rows.push(binary::promote_to_trivial_share(
+                        id,
+                        &(BigUint::from(ones.len()) & &r_mask),
+                    ));
+                    rows
+                })
+                .collect();
+        let r = sum_many(groups, k + 2, io_context)?;
+
+        // Subtract p or 2p if the result is not negative, i.e., the highest bit is not set
+        let d_width = k + 3;
+        let neg = [&self.modulus, &(&self.modulus << 1)]
+            .map(|x| binary::promote_to_trivial_share(id, &((BigUint::one() << d_width) - x)));
+        let (lhs, rhs): (Vec<_>, Vec<_>) = r
+            .iter()
+            .flat_map(|r| neg.iter().map(move |neg| (r.to_owned(), neg.to_owned())))
+            .unzip();
+        let d = add_many(&lhs, &rhs, d_width, io_context)?;
+        let res_mask = mask(k);
+        let (lhs, rhs): (Vec<_>, Vec<_>) = izip!(&r, d.chunks_exact(2))
+            .flat_map(|(r, d)| {
+                let [s1, s2] = [&d[0], &d[1]]
+                    .map(|d| binary::xor_public(&broadcast(d, d_width - 1, k), &res_mask, id));
+                [
+                    (s1, &(r ^ &d[0]) & &res_mask),
+                    (s2, &(&d[0] ^ &d[1]) & &res_mask),
+                ]
+            })
+            .unzip();
+        let selected = and_many(&lhs, &rhs, k, io_context)?;
+        Ok(izip!(r, selected.chunks_exact(2))
+            .map(|(r, s)| &(&(&r & &res_mask) ^ &s[0]) ^ &s[1])
+            .collect())
+    }
+
+    /// Computes the sum of the products of each group modulo the modulus. All factors have to be smaller than 2^(bits + 1) and each group may contain at most 64 products. All partial products are computed in a single communication round.
+    fn mul_sum_many<F: PrimeField, N: Rep3Network>(
+        &self,
+        groups: &[Vec<(BinaryShare<F>, BinaryShare<F>)>],
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Vec<BinaryShare<F>>> {
+        let width = self.bits + 1;
+        let (lhs, rhs): (Vec<_>, Vec<_>) = groups
+            .iter()
+            .flatten()
+            .flat_map(|(a, b)| (0..width).map(move |j| (a.to_owned(), broadcast(b, j, width))))
+            .unzip();
+        let mut rows = and_many(&lhs, &rhs, width, io_context)?.into_iter();
+        let mut row_groups = Vec::with_capacity(groups.len());
+        for group in groups {
+            debug_assert!(group.len() <= 64);
+            let mut shifted = Vec::with_capacity(group.len() * width);
+            for _ in group {
+                for j in 0..width {
+                    shifted.push(rows.next().expect("one row per bit") << j);
+                }
+            }
+            row_groups.push(shifted);
+        }
+        self.reduce_many(row_groups, io_context)
+    }
+
+    /// Computes the linear combinations of each group with public coefficients modulo the modulus. All values have to be smaller than 2^(bits + 1) and each group may contain at most 64 terms.
+    fn lincomb_many<F: PrimeField, N: Rep3Network>(
+        &self,
+        groups: &[Vec<(BigUint, &BinaryShare<F>)>],
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Vec<BinaryShare<F>>> {
+        let groups = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .flat_map(|(coeff, x)| {
+                        (0..coeff.bits())
+                            .filter(|j| coeff.bit(*j))
+                            .map(|j| *x << j as usize)
+                    })
+                    .collect()
+            })
+            .collect();
+        self.reduce_many(groups, io_context)
+    }
+}
+
+/// The arithmetic of a short Weierstrass curve, whose base field is emulated on binary shares.
+struct EmulatedCurve<P: SWCurveConfig>
+where
+    P::BaseField: PrimeField,
+{
+    field: Modulus,
+    a: P::BaseField,
+    b3: P::BaseField,
+}
+
+impl<P: SWCurveConfig> EmulatedCurve<P>
+where
+    P::BaseField: PrimeField,
+{
+    fn new() -> Self {
+        Self {
+            field: Modulus::new(P::BaseField::MODULUS.into()),
+            a: P::COEFF_A,
+            b3: P::COEFF_B + P::COEFF_B + P::COEFF_B,
+        }
+    }
+
+    /// Adds all pairs of points using the complete addition formulas, which requires two multiplications and one linear combination per addition, each of which are batched for all pairs.
+    fn add_many<F: PrimeField, N: Rep3Network>(
+        &self,
+        lhs: &[Point<F>],
+        rhs: &[Point<F>],
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Vec<Point<F>>> {
+        let groups = izip!(lhs, rhs)
+            .flat_map(|(p, q)| {
+                let pair = |i: usize, j: usize| (p[i].to_owned(), q[j].to_owned());
+                [
+                    vec![pair(0, 0)],
+                    vec![pair(1, 1)],
+                    vec![pair(2, 2)],
+                    vec![pair(0, 1), pair(1, 0)],
+                    vec![pair(0, 2), pair(2, 0)],
+                    vec![pair(1, 2), pair(2, 1)],
+                ]
+            })
+            .collect_vec();
+        let products = self.field.mul_sum_many(&groups, io_context)?;
+
+        let (a, b3) = (self.a, self.b3);
+        let coeff = |x: P::BaseField| -> BigUint { x.into() };
+        let groups = products
+            .chunks_exact(6)
+            .flat_map(|t| {
+                let [xx, yy, zz, _, xz, _] = [&t[0], &t[1], &t[2], &t[3], &t[4], &t[5]];
+                let one = P::BaseField::one();
+                let t4 = [(a, xx), (b3, xz), (-a * a, zz)];
+                [
+                    // X3a = YY - a * XZ - b3 * ZZ
+                    vec![(one, yy), (-a, xz), (-b3, zz)],
+                    // Z3b = YY + a * XZ + b3 * ZZ
+                    vec![(one, yy), (a, xz), (b3, zz)],
+                    // t1 = 3 * XX + a * ZZ
+                    vec![(one + one + one, xx), (a, zz)],
+                    // t4 = b3 * XZ + a * (XX - a * ZZ)
+                    t4.to_vec(),
+                    t4.map(|(c, x)| (-c, x)).to_vec(),
+                ]
+            })
+            .map(|group| {
+                group
+                    .into_iter()
+                    .filter(|(c, _)| !c.is_zero())
+                    .map(|(c, x)| (coeff(c), x))
+                    .collect_vec()
+            })
+            .collect_vec();
+        let factors = self.field.lincomb_many(&groups, io_context)?;
+
+        let groups = izip!(products.chunks_exact(6), factors.chunks_exact(5))
+            .flat_map(|(t, f)| {
+                let (xy, yz) = (&t[3], &t[5]);
+                let [x3a, z3b, t1, t4, neg_t4] = [&f[0], &f[1], &f[2], &f[3], &f[4]];
+                let pair = |a: &BinaryShare<F>, b: &BinaryShare<F>| (a.to_owned(), b.to_owned());
+                [
+                    // X3 = XY * X3a - YZ * t4
+                    vec![pair(xy, x3a), pair(yz, neg_t4)],
+                    // Y3 = X3a * Z3b + t1 * t4
+                    vec![pair(x3a, z3b), pair(t1, t4)],
+                    // Z3 = YZ * Z3b + XY * t1
+                    vec![pair(yz, z3b), pair(xy, t1)],
+                ]
+            })
+            .collect_vec();
+        let res = self.field.mul_sum_many(&groups, io_context)?;
+        Ok(res
+            .chunks_exact(3)
+            .map(|p| [p[0].to_owned(), p[1].to_owned(), p[2].to_owned()])
+            .collect())
+    }
+
+    /// Returns binary shares of the projective coordinates of the additive share of party `j` of a shared point, which is known to party j and the next party.
+    fn share_component<F: PrimeField>(
+        point: &Rep3PointShare<Projective<P>>,
+        j: PartyID,
+        id: PartyID,
+    ) -> Point<F> {
+        let coordinates = |p: &Projective<P>| -> [BigUint; 3] {
+            match p.into_affine().xy() {
+                Some((x, y)) => [(*x).into(), (*y).into(), BigUint::one()],
+                None => [BigUint::zero(), BigUint::one(), BigUint::zero()],
+            }
+        };
+        let zero = || [BigUint::zero(), BigUint::zero(), BigUint::zero()];
+        let a = if j == id {
+            coordinates(&point.a)
+        } else {
+            zero()
+        };
+        let b = if j == id.prev_id() {
+            coordinates(&point.b)
+        } else {
+            zero()
+        };
+        let [a0, a1, a2] = a;
+        let [b0, b1, b2] = b;
+        [
+            BinaryShare::new(a0, b0),
+            BinaryShare::new(a1, b1),
+            BinaryShare::new(a2, b2),
+        ]
+    }
+
+    /// Returns binary shares of the coordinates of the sum of the additive shares of the three parties.
+    fn combine_components<F: PrimeField, N: Rep3Network>(
+        &self,
+        point: &Rep3PointShare<Projective<P>>,
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Point<F>> {
+        let id = io_context.id;
+        let [c0, c1, c2] =
+            [PartyID::ID0, PartyID::ID1, PartyID::ID2].map(|j| Self::share_component(point, j, id));
+        let sum = self.add_many(&[c0], &[c1], io_context)?;
+        let sum = self.add_many(&sum, &[c2], io_context)?;
+        Ok(sum[0].to_owned())
+    }
+}
+
+/// Verifies an ECDSA signature on a plain public key, signature, and hashed message. Returns false for invalid public keys and signature components which are zero or not smaller than the group order. The hashed message is reduced modulo the group order.
+pub fn verify<P: SWCurveConfig>(
+    public_key_x: &BigUint,
+    public_key_y: &BigUint,
+    r: &BigUint,
+    s: &BigUint,
+    hashed_message: &BigUint,
+) -> bool
+where
+    P::BaseField: PrimeField,
+{
+    let p: BigUint = P::BaseField::MODULUS.into();
+    let n: BigUint = <P as CurveConfig>::ScalarField::MODULUS.into();
+    if public_key_x >= &p || public_key_y >= &p {
+        return false;
+    }
+    if r.is_zero() || r >= &n || s.is_zero() || s >= &n {
+        return false;
+    }
+    let public_key = Affine::<P>::new_unchecked(
+        P::BaseField::from(public_key_x.to_owned()),
+        P::BaseField::from(public_key_y.to_owned()),
+    );
+    if !public_key.is_on_curve() || !public_key.is_in_correct_subgroup_assuming_on_curve() {
+        return false;
+    }
+    let w = P::ScalarField::from(s.to_owned())
+        .inverse()
+        .expect("s is not zero");
+    let u1 = P::ScalarField::from(hashed_message.to_owned()) * w;
+    let u2 = P::ScalarField::from(r.to_owned()) * w;
+    let point = (P::GENERATOR * u1 + public_key * u2).into_affine();
+    match point.xy() {
+        Some((x, _)) => {
+            let x: BigUint = (*x).into();
+            x % n == *r
+        }
+        None => false,
+    }
+}
+
+/// Verifies an ECDSA signature on a shared public key, signature, and hashed message, all of which are given as binary shares of values of 256 bits. The result is a binary share of a single bit, which is set if the signature is valid. The group order of the curve has to have 256 bits as well.
+///
+/// See [`verify`] for the handling of invalid inputs. The only information revealed is whether the public key is a valid point, in which case the result is a trivial share of zero.
+pub fn rep3_verify<P, F, N>(
+    public_key_x: &BinaryShare<F>,
+    public_key_y: &BinaryShare<F>,
+    r: &BinaryShare<F>,
+    s: &BinaryShare<F>,
+    hashed_message: &BinaryShare<F>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<BinaryShare<F>>
+where
+    P: SWCurveConfig,
+    P::BaseField: PrimeField,
+    F: PrimeField,
+    N: Rep3Network,
+{
+    let id = io_context.id;
+    let curve = EmulatedCurve::<P>::new();
+    let p = curve.field.modulus.to_owned();
+    let n: BigUint = <P as CurveConfig>::ScalarField::MODULUS.into();
+    assert_eq!(n.bits(), 8 * NUM_BYTES as u64);
+    let k = curve.field.bits;
+
+    // x - y = x + 2^width - y is negative iff the highest bit is set
+    let width = (p.bits().max(n.bits()) + 2) as usize;
+    let neg = |y: &BigUint| binary::promote_to_trivial_share(id, &((BigUint::one() << width) - y));
+    // If p <= n, r + n is never smaller than p
+    let p_minus_n = if p > n { &p - &n } else { BigUint::zero() };
+    let lhs = [r, s, hashed_message, r, public_key_x, public_key_y].map(|x| x.to_owned());
+    let rhs = [neg(&n), neg(&n), neg(&n), neg(&p_minus_n), neg(&p), neg(&p)];
+    let diff = add_many(&lhs, &rhs, width, io_context)?;
+    let [r_lt_n, s_lt_n, _, r_lt_p_minus_n, x_lt_p, y_lt_p] =
+        [0, 1, 2, 3, 4, 5].map(|i| bit(&diff[i], width - 1));
+
+    // The hashed message is smaller than 2n, so it is reduced by subtracting n once if possible
+    let e_ge_n = binary::xor_public(&broadcast(&diff[2], width - 1, width), &mask(width), id);
+    let e_diff = &(hashed_message ^ &diff[2]) & &mask(width);
+    let e = hashed_message ^ &binary::and_with_bitlen(&e_ge_n, &e_diff, io_context, width)?;
+
+    // Inversion and multiplications in the scalar field
+    let rs_is_zero = binary::is_zero_many(&[cast::<F, P::BaseField>(r), cast(s)], io_context)?;
+    let [r_scalar, s_scalar, e_scalar] = [r, s, &e].map(cast::<F, P::ScalarField>);
+    let r_scalar = conversion::b2a(&r_scalar, io_context)?;
+    let s_scalar = conversion::b2a(&s_scalar, io_context)?;
+    let e_scalar = conversion::b2a(&e_scalar, io_context)?;
+    // The conversion is only correct for values smaller than n, in which case the signature is invalid anyways. Replace s = 0 mod n by 1 to be able to invert it.
+    let s_is_zero = arithmetic::is_zero_many(&[s_scalar], io_context)?;
+    let w = arithmetic::inv(s_scalar + s_is_zero[0], io_context)?;
+    let u = arithmetic::mul_vec(&[e_scalar, r_scalar], &[w, w], io_context)?;
+
+    // Convert the public key into a shared point by opening T = Q + rho * G
+    let generator = Projective::<P>::from(P::GENERATOR);
+    let rho = arithmetic::rand::<P::ScalarField, N>(io_context);
+    let rho_g = pointshare::scalar_mul_public_point(&generator, rho);
+    let [c0, c1, c2] = [PartyID::ID0, PartyID::ID1, PartyID::ID2]
+        .map(|j| EmulatedCurve::<P>::share_component::<F>(&rho_g, j, id));
+    let public_key = [
+        public_key_x.to_owned(),
+        public_key_y.to_owned(),
+        binary::promote_to_trivial_share(id, &BigUint::one()),
+    ];
+    let t = curve.add_many(&[public_key, c1], &[c0, c2], io_context)?;
+    let t = curve.add_many(&t[..1], &t[1..], io_context)?;
+    // Randomize the projective representation before opening
+    let (lambda_a, lambda_b) = io_context.rngs.rand.random_biguint(curve.field.input_bits);
+    let lambda = curve
+        .field
+        .reduce_many(vec![vec![BinaryShare::new(lambda_a, lambda_b)]], io_context)?;
+    let groups = t[0]
+        .iter()
+        .map(|c| vec![(c.to_owned(), lambda[0].to_owned())])
+        .collect_vec();
+    let t = curve.field.mul_sum_many(&groups, io_context)?;
+    let t = binary::open(&pack(&t, k), io_context)?;
+    let [t_x, t_y, t_z] = [0, 1, 2].map(|i| P::BaseField::from((&t >> (i * k)) & mask(k)));
+    let t = match t_z.inverse() {
+        Some(z_inv) => Affine::<P>::new_unchecked(t_x * z_inv, t_y * z_inv),
+        None => return Ok(BinaryShare::zero_share()),
+    };
+    if !t.is_on_curve() || !t.is_in_correct_subgroup_assuming_on_curve() {
+        return Ok(BinaryShare::zero_share());
+    }
+    let mut public_key = Rep3PointShare::new(-rho_g.a, -rho_g.b);
+    pointshare::add_assign_public(&mut public_key, &t.into(), id);
+
+    // R = u1 * G + u2 * Q
+    let mut point = pointshare::scalar_mul(&public_key, u[1], io_context)?;
+    pointshare::add_assign(
+        &mut point,
+        &pointshare::scalar_mul_public_point(&generator, u[0]),
+    );
+    let [x, _, z] = curve.combine_components::<F, N>(&point, io_context)?;
+
+    // x(R) mod n = r iff X = r * Z or, if r + n < p, X = (r + n) * Z
+    let r_plus_n = add_many(
+        &[r.to_owned()],
+        &[binary::promote_to_trivial_share(id, &n)],
+        k + 1,
+        io_context,
+    )?;
+    let groups = [r.to_owned(), r_plus_n[0].to_owned()].map(|r| vec![(r, z.to_owned())]);
+    let rz = curve.field.mul_sum_many(&groups, io_context)?;
+    let is_zero = binary::is_zero_many(
+        &[&x ^ &rz[0], &x ^ &rz[1], z].map(|x| cast::<F, P::BaseField>(&x)),
+        io_context,
+    )?;
+    let [eq1, eq2, z_is_zero] = [0, 1, 2].map(|i| cast::<P::BaseField, F>(&is_zero[i]));
+    let not = |x: &BinaryShare<F>| binary::xor_public(x, &BigUint::one(), id);
+    let [r_is_zero, s_is_zero] = [0, 1].map(|i| cast::<P::BaseField, F>(&rs_is_zero[i]));
+
+    // valid = x < p & y < p & r < n & s < n & r != 0 & s != 0 & Z != 0 & (eq1 | (eq2 & r < p - n))
+    let and = and_many(
+        &[eq2, r_lt_n, not(&r_is_zero), x_lt_p],
+        &[r_lt_p_minus_n, s_lt_n, not(&s_is_zero), y_lt_p],
+        1,
+        io_context,
+    )?;
+    let [eq2, range_ok, nonzero, key_ok] = [&and[0], &and[1], &and[2], &and[3]];
+    let and = and_many(
+        &[eq1.to_owned(), range_ok.to_owned(), key_ok.to_owned()],
+        &[eq2.to_owned(), nonzero.to_owned(), not(&z_is_zero)],
+        1,
+        io_context,
+    )?;
+    // a | b = a ^ b ^ (a & b)
+    let x_matches = &(&eq1 ^ eq2) ^ &and[0];
+    let checks = and_many(&[x_matches], &and[1..2], 1, io_context)?;
+    let valid = and_many(&checks, &and[2..], 1, io_context)?;
+    Ok(valid[0].to_owned())
+}
+
+/// Verifies an ECDSA signature as in [`rep3_verify`], where the public key coordinates, the signature (r, s), and the hashed message are given as shared big-endian bytes, e.g., as used in Noir's ECDSA blackbox functions. The bytes have to be smaller than 256. The result is an arithmetic share of a single bit, which is set if the signature is valid.
+pub fn rep3_verify_bytes<P, F, N>(
+    public_key_x: &[FieldShare<F>; NUM_BYTES],
+    public_key_y: &[FieldShare<F>; NUM_BYTES],
+    signature: &[FieldShare<F>; 2 * NUM_BYTES],
+    hashed_message: &[FieldShare<F>; NUM_BYTES],
+    io_context: &mut IoContext<N>,
+) -> IoResult<FieldShare<F>>
+where
+    P: SWCurveConfig,
+    P::BaseField: PrimeField,
+    F: PrimeField,
+    N: Rep3Network,
+{
+    let bytes = public_key_x
+        .iter()
+        .chain(public_key_y)
+        .chain(signature)
+        .chain(hashed_message)
+        .copied()
+        .collect_vec();
+    let bytes = conversion::a2y2b_many(&bytes, io_context)?;
+    let byte_mask = mask(8);
+    let values = bytes
+        .chunks_exact(NUM_BYTES)
+        .map(|chunk| {
+            chunk.iter().fold(BinaryShare::zero_share(), |acc, byte| {
+                &(acc << 8) ^ &(byte & &byte_mask)
+            })
+        })
+        .collect_vec();
+    let valid = rep3_verify::<P, F, N>(
+        &values[0], &values[1], &values[2], &values[3], &values[4], io_context,
+    )?;
+    conversion::bit_inject(&valid, io_context)
+}
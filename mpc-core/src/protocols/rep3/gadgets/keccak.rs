@@ -0,0 +1,268 @@
+//! Keccak
+//!
+//! This module contains the Keccak-f\[1600\] permutation for the Rep3 protocol. As for [SHA-256](super::sha256), the 64-bit lanes of all permutation calls are bit-sliced into packed binary shares, such that theta, rho, pi, and iota are local and the AND gates of chi require one communication round per round of the permutation for all calls at once. The conversions from and to arithmetic shares are only performed at the boundaries and are batched as well.
+
+use crate::protocols::rep3::{
+    arithmetic::FieldShare,
+    binary, conversion,
+    network::{IoContext, Rep3Network},
+    IoResult, Rep3BigUintShare,
+};
+use ark_ff::{One, PrimeField, Zero};
+use itertools::{izip, Itertools};
+use num_bigint::BigUint;
+
+type BinaryShare<F> = Rep3BigUintShare<F>;
+
+pub(crate) const LANE_BITS: usize = 64;
+
+/// The number of bits of the Keccak-f\[1600\] state.
+pub const STATE_BITS: usize = 25 * LANE_BITS;
+
+pub(crate) const RC: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+// The rotation offsets of rho, indexed by x + 5y
+pub(crate) const ROTATIONS: [usize; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// Computes the Keccak-f\[1600\] permutation on a plain state, where lane (x, y) is stored at index x + 5y.
+pub fn keccak_f1600(state: &mut [u64; 25]) {
+    for rc in RC {
+        // Theta
+        let c: [u64; 5] = std::array::from_fn(|x| {
+            state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20]
+        });
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5 * y] ^= d;
+            }
+        }
+        // Rho and pi
+        let mut b = [0u64; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                    state[x + 5 * y].rotate_left(ROTATIONS[x + 5 * y] as u32);
+            }
+        }
+        // Chi
+        for x in 0..5 {
+            for y in 0..5 {
+                state[x + 5 * y] =
+                    b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+        // Iota
+        state[0] ^= rc;
+    }
+}
+
+/// Returns a value which has the lowest `bits` bits of each of the `num_lanes` lanes set.
+pub(crate) fn lane_mask(num_lanes: usize, bits: usize) -> BigUint {
+    let lane = (BigUint::one() << bits) - BigUint::one();
+    (0..num_lanes).fold(BigUint::zero(), |acc, i| acc | (&lane << (i * LANE_BITS)))
+}
+
+/// Packs values of `num_lanes` lanes each into a single value.
+fn pack<F: PrimeField>(values: &[BinaryShare<F>], num_lanes: usize) -> BinaryShare<F> {
+    let mut res = BinaryShare::zero_share();
+    for (i, value) in values.iter().enumerate() {
+        res ^= value << (i * num_lanes * LANE_BITS);
+    }
+    res
+}
+
+/// Splits a value into `amount` values of `num_lanes` lanes each. Inverse of [`pack`].
+fn unpack<F: PrimeField>(
+    value: &BinaryShare<F>,
+    amount: usize,
+    num_lanes: usize,
+) -> Vec<BinaryShare<F>> {
+    let mask = (BigUint::one() << (num_lanes * LANE_BITS)) - BigUint::one();
+    (0..amount)
+        .map(|i| &(value >> (i * num_lanes * LANE_BITS)) & &mask)
+        .collect()
+}
+
+fn rotl<F: PrimeField>(x: &BinaryShare<F>, r: usize, num_lanes: usize) -> BinaryShare<F> {
+    if r == 0 {
+        return x.to_owned();
+    }
+    let low = (x & &lane_mask(num_lanes, LANE_BITS - r)) << r;
+    let high = &(x >> (LANE_BITS - r)) & &lane_mask(num_lanes, r);
+    low ^ high
+}
+
+/// Computes the Keccak-f\[1600\] permutation on `num_instances` states at once. Each of the given binary shares packs the respective lane of all states, i.e., lane j of state i is stored in bits 64i..64(i+1) of the j-th value, where lane (x, y) is stored at index x + 5y.
+pub fn keccak_f1600_packed<F: PrimeField, N: Rep3Network>(
+    state: &[BinaryShare<F>; 25],
+    num_instances: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<[BinaryShare<F>; 25]> {
+    let n = num_instances;
+    let id = io_context.id;
+    let ones = lane_mask(n, LANE_BITS);
+    let mut state = state.to_owned();
+    for rc in RC {
+        // Theta
+        let c: [BinaryShare<F>; 5] = std::array::from_fn(|x| {
+            (1..5).fold(state[x].to_owned(), |mut acc, y| {
+                acc ^= &state[x + 5 * y];
+                acc
+            })
+        });
+        for x in 0..5 {
+            let d = &c[(x + 4) % 5] ^ &rotl(&c[(x + 1) % 5], 1, n);
+            for y in 0..5 {
+                state[x + 5 * y] ^= &d;
+            }
+        }
+        // Rho and pi
+        let mut b: [BinaryShare<F>; 25] = Default::default();
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = rotl(&state[x + 5 * y], ROTATIONS[x + 5 * y], n);
+            }
+        }
+        // Chi, the ANDs of all lanes are computed in one communication round
+        let (lhs, rhs): (Vec<_>, Vec<_>) = (0..25)
+            .map(|i| {
+                let (x, y) = (i % 5, i / 5);
+                (
+                    binary::xor_public(&b[(x + 1) % 5 + 5 * y], &ones, id),
+                    b[(x + 2) % 5 + 5 * y].to_owned(),
+                )
+            })
+            .unzip();
+        let ands = binary::and_with_bitlen(
+            &pack(&lhs, n),
+            &pack(&rhs, n),
+            io_context,
+            25 * n * LANE_BITS,
+        )?;
+        for (state, b, and) in izip!(state.iter_mut(), b, unpack(&ands, 25, n)) {
+            *state = b ^ and;
+        }
+        // Iota
+        let rc = (0..n).fold(BigUint::zero(), |acc, i| {
+            acc | (BigUint::from(rc) << (i * LANE_BITS))
+        });
+        state[0] = binary::xor_public(&state[0], &rc, id);
+    }
+    Ok(state)
+}
+
+/// Converts arithmetic shares of 64-bit lanes into packed binary shares as used by [`keccak_f1600_packed`].
+fn lanes_to_packed<F: PrimeField, N: Rep3Network>(
+    states: &[[FieldShare<F>; 25]],
+    io_context: &mut IoContext<N>,
+) -> IoResult<[BinaryShare<F>; 25]> {
+    let n = states.len();
+    let lanes = (0..25)
+        .flat_map(|j| states.iter().map(move |state| state[j]))
+        .collect_vec();
+    let lanes = conversion::a2y2b_many(&lanes, io_context)?;
+    let mask = lane_mask(1, LANE_BITS);
+    let packed = lanes
+        .chunks_exact(n)
+        .map(|lanes| pack(&lanes.iter().map(|l| l & &mask).collect_vec(), 1))
+        .collect_vec();
+    Ok(packed.try_into().expect("25 lanes"))
+}
+
+/// Converts packed binary shares as used by [`keccak_f1600_packed`] into arithmetic shares of their bits. The bits are ordered by state, lane, and bit, starting with the least significant bit of each lane.
+fn packed_to_bits<F: PrimeField, N: Rep3Network>(
+    state: &[BinaryShare<F>; 25],
+    num_instances: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    let bits = (0..num_instances)
+        .flat_map(|i| {
+            state.iter().flat_map(move |lane| {
+                (0..LANE_BITS).map(move |j| &(lane >> (i * LANE_BITS + j)) & &BigUint::one())
+            })
+        })
+        .collect_vec();
+    conversion::bit_inject_many(&bits, io_context)
+}
+
+/// Composes arithmetic shares of bits into arithmetic shares of values of `bits.len()` bits, starting with the least significant bit.
+fn compose<F: PrimeField>(bits: &[FieldShare<F>]) -> FieldShare<F> {
+    bits.iter()
+        .rev()
+        .fold(FieldShare::default(), |acc, bit| acc + acc + *bit)
+}
+
+/// Computes the Keccak-f\[1600\] permutation on all states, which are given as arithmetic shares of 64-bit lanes, where lane (x, y) is stored at index x + 5y. All lanes are converted to binary shares in one batched conversion, the permutation is evaluated using [`keccak_f1600_packed`], and the resulting lanes are converted back to arithmetic shares in one batched conversion.
+///
+/// All lanes have to be smaller than 2^64. Otherwise, the result is not specified.
+pub fn keccak_f1600_many<F: PrimeField, N: Rep3Network>(
+    states: &[[FieldShare<F>; 25]],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<[FieldShare<F>; 25]>> {
+    let n = states.len();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    let state = lanes_to_packed(states, io_context)?;
+    let state = keccak_f1600_packed(&state, n, io_context)?;
+    let bits = packed_to_bits(&state, n, io_context)?;
+    let lanes = bits.chunks_exact(LANE_BITS).map(compose).collect_vec();
+    Ok(lanes
+        .chunks_exact(25)
+        .map(|lanes| lanes.try_into().expect("25 lanes"))
+        .collect())
+}
+
+/// Computes the Keccak-f\[1600\] permutation on all states, which are given as arithmetic shares of their [`STATE_BITS`] bits each. The bits of a state are ordered by lane and bit, starting with the least significant bit of each lane, where lane (x, y) is at position x + 5y. The result is given as arithmetic shares of bits in the same order.
+///
+/// The inputs have to be bits. Otherwise, the result is not specified.
+pub fn keccak_f1600_bits_many<F: PrimeField, N: Rep3Network>(
+    bits: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    if bits.len() % STATE_BITS != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Keccak-f[1600] requires a multiple of {STATE_BITS} bits"),
+        ));
+    }
+    let n = bits.len() / STATE_BITS;
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    let states = bits
+        .chunks_exact(STATE_BITS)
+        .map(|bits| std::array::from_fn(|j| compose(&bits[j * LANE_BITS..(j + 1) * LANE_BITS])))
+        .collect_vec();
+    let state = lanes_to_packed(&states, io_context)?;
+    let state = keccak_f1600_packed(&state, n, io_context)?;
+    packed_to_bits(&state, n, io_context)
+}
@@ -0,0 +1,197 @@
+//! Linear algebra
+//!
+//! This module contains matrix products and 2D convolutions for the Rep3 protocol, e.g., for the linear layers of neural networks. All matrices and tensors are stored in row-major order. Since replicated shares allow computing additive shares of a dot product locally, each output element requires resharing a single field element, independent of the length of the dot products, and all output elements of a layer are reshared in a single communication round.
+
+use crate::protocols::rep3::{
+    arithmetic::{self, FieldShare},
+    network::{IoContext, Rep3Network},
+    rngs::Rep3CorrelatedRng,
+    IoResult,
+};
+use ark_ff::PrimeField;
+
+/// Computes masked additive shares of the product of the m x k matrix `a` and the k x n matrix `b`. Afterwards, [`arithmetic::io_mul_vec`] transforms them into replicated shares. This allows batching the communication of several products into a single round.
+pub fn local_mat_mul<F: PrimeField>(
+    a: &[FieldShare<F>],
+    b: &[FieldShare<F>],
+    m: usize,
+    k: usize,
+    n: usize,
+    rngs: &mut Rep3CorrelatedRng,
+) -> Vec<F> {
+    assert_eq!(a.len(), m * k);
+    assert_eq!(b.len(), k * n);
+    // squeeze all random elements at once in the beginning for determinism
+    let mut res = rngs.rand.masking_field_elements_vec::<F>(m * n);
+    for (i, row) in res.chunks_exact_mut(n).enumerate() {
+        for (l, a) in a[i * k..(i + 1) * k].iter().enumerate() {
+            for (res, b) in row.iter_mut().zip(&b[l * n..(l + 1) * n]) {
+                *res += a * b;
+            }
+        }
+    }
+    res
+}
+
+/// Computes the product of the m x k matrix `a` and the k x n matrix `b` in a single communication round.
+pub fn mat_mul<F: PrimeField, N: Rep3Network>(
+    a: &[FieldShare<F>],
+    b: &[FieldShare<F>],
+    m: usize,
+    k: usize,
+    n: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    let local = local_mat_mul(a, b, m, k, n, &mut io_context.rngs);
+    arithmetic::io_mul_vec(local, io_context)
+}
+
+/// Computes the product of the m x k matrix `a` and the vector `x` of length k in a single communication round.
+pub fn mat_vec_mul<F: PrimeField, N: Rep3Network>(
+    a: &[FieldShare<F>],
+    x: &[FieldShare<F>],
+    m: usize,
+    k: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    mat_mul(a, x, m, k, 1, io_context)
+}
+
+/// Computes the product of the public m x k matrix `a` and the shared k x n matrix `b`. This is a local operation.
+pub fn mat_mul_public_lhs<F: PrimeField>(
+    a: &[F],
+    b: &[FieldShare<F>],
+    m: usize,
+    k: usize,
+    n: usize,
+) -> Vec<FieldShare<F>> {
+    assert_eq!(a.len(), m * k);
+    assert_eq!(b.len(), k * n);
+    let mut res = vec![FieldShare::zero_share(); m * n];
+    for (i, row) in res.chunks_exact_mut(n).enumerate() {
+        for (l, a) in a[i * k..(i + 1) * k].iter().enumerate() {
+            for (res, b) in row.iter_mut().zip(&b[l * n..(l + 1) * n]) {
+                *res += *b * *a;
+            }
+        }
+    }
+    res
+}
+
+/// Computes the product of the shared m x k matrix `a` and the public k x n matrix `b`. This is a local operation.
+pub fn mat_mul_public_rhs<F: PrimeField>(
+    a: &[FieldShare<F>],
+    b: &[F],
+    m: usize,
+    k: usize,
+    n: usize,
+) -> Vec<FieldShare<F>> {
+    assert_eq!(a.len(), m * k);
+    assert_eq!(b.len(), k * n);
+    let mut res = vec![FieldShare::zero_share(); m * n];
+    for (i, row) in res.chunks_exact_mut(n).enumerate() {
+        for (l, a) in a[i * k..(i + 1) * k].iter().enumerate() {
+            for (res, b) in row.iter_mut().zip(&b[l * n..(l + 1) * n]) {
+                *res += *a * *b;
+            }
+        }
+    }
+    res
+}
+
+/// The shape of a 2D convolution with zero padding. The input has the shape in_channels x height x width and the kernel the shape out_channels x in_channels x kernel_height x kernel_width. The output has the shape out_channels x [`Self::output_height`] x [`Self::output_width`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Conv2dShape {
+    /// The number of input channels
+    pub in_channels: usize,
+    /// The height of the input
+    pub height: usize,
+    /// The width of the input
+    pub width: usize,
+    /// The number of output channels
+    pub out_channels: usize,
+    /// The height of the kernel
+    pub kernel_height: usize,
+    /// The width of the kernel
+    pub kernel_width: usize,
+    /// The stride in both dimensions
+    pub stride: usize,
+    /// The number of zeros added to each side of the input in both dimensions
+    pub padding: usize,
+}
+
+impl Conv2dShape {
+    /// Returns the height of the output.
+    pub fn output_height(&self) -> usize {
+        (self.height + 2 * self.padding - self.kernel_height) / self.stride + 1
+    }
+
+    /// Returns the width of the output.
+    pub fn output_width(&self) -> usize {
+        (self.width + 2 * self.padding - self.kernel_width) / self.stride + 1
+    }
+
+    fn patch_size(&self) -> usize {
+        self.in_channels * self.kernel_height * self.kernel_width
+    }
+
+    /// Rearranges the input into a matrix with one column per output position, such that the convolution is the product of the kernel, viewed as an out_channels x (in_channels * kernel_height * kernel_width) matrix, with this matrix.
+    pub fn im2col<T: Copy>(&self, input: &[T], zero: T) -> Vec<T> {
+        assert_eq!(input.len(), self.in_channels * self.height * self.width);
+        let (out_height, out_width) = (self.output_height(), self.output_width());
+        let mut res = Vec::with_capacity(self.patch_size() * out_height * out_width);
+        for c in 0..self.in_channels {
+            for dy in 0..self.kernel_height {
+                for dx in 0..self.kernel_width {
+                    for oy in 0..out_height {
+                        for ox in 0..out_width {
+                            // Positions within the padding are out of range after the subtraction
+                            let y = (oy * self.stride + dy).wrapping_sub(self.padding);
+                            let x = (ox * self.stride + dx).wrapping_sub(self.padding);
+                            if y < self.height && x < self.width {
+                                res.push(input[(c * self.height + y) * self.width + x]);
+                            } else {
+                                res.push(zero);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        res
+    }
+}
+
+/// Computes the 2D convolution of a shared input with a shared kernel in a single communication round.
+pub fn conv2d<F: PrimeField, N: Rep3Network>(
+    input: &[FieldShare<F>],
+    kernel: &[FieldShare<F>],
+    shape: &Conv2dShape,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    let columns = shape.im2col(input, FieldShare::zero_share());
+    mat_mul(
+        kernel,
+        &columns,
+        shape.out_channels,
+        shape.patch_size(),
+        shape.output_height() * shape.output_width(),
+        io_context,
+    )
+}
+
+/// Computes the 2D convolution of a shared input with a public kernel. This is a local operation.
+pub fn conv2d_public_kernel<F: PrimeField>(
+    input: &[FieldShare<F>],
+    kernel: &[F],
+    shape: &Conv2dShape,
+) -> Vec<FieldShare<F>> {
+    let columns = shape.im2col(input, FieldShare::zero_share());
+    mat_mul_public_lhs(
+        kernel,
+        &columns,
+        shape.out_channels,
+        shape.patch_size(),
+        shape.output_height() * shape.output_width(),
+    )
+}
@@ -0,0 +1,183 @@
+//! Lookup tables
+//!
+//! This module contains oblivious lookup tables (LUTs) for the Rep3 protocol, i.e., reading from and writing to a public or shared table at secret-shared indices.
+
+use crate::protocols::rep3::{
+    arithmetic::{self, FieldShare},
+    network::{IoContext, Rep3Network},
+    IoResult,
+};
+use ark_ff::PrimeField;
+
+/// Returns the number of bits required to represent all indices of a table of size `len`.
+fn index_bits(len: usize) -> usize {
+    let bits = usize::BITS - (len - 1).leading_zeros();
+    (bits as usize).max(1)
+}
+
+/// Computes the one-hot encodings of the shared indices, i.e., for each index a vector of size 2^`num_bits` which is one at the position of the index and zero everywhere else. The encodings are built from the bit decompositions of the indices, which requires one multiplication round per bit.
+fn one_hot_many<F: PrimeField, N: Rep3Network>(
+    indices: &[FieldShare<F>],
+    num_bits: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Vec<FieldShare<F>>>> {
+    let id = io_context.id;
+    let bits = arithmetic::to_bits_many(indices, num_bits, io_context)?;
+
+    // The first bit splits the trivial one-hot encoding [1] without a multiplication
+    let mut one_hots = bits
+        .iter()
+        .map(|bits| {
+            vec![
+                arithmetic::sub_public_by_shared(F::one(), bits[0], id),
+                bits[0],
+            ]
+        })
+        .collect::<Vec<_>>();
+    for i in 1..num_bits {
+        let (lhs, rhs): (Vec<_>, Vec<_>) = one_hots
+            .iter()
+            .zip(bits.iter())
+            .flat_map(|(one_hot, bits)| one_hot.iter().map(|x| (*x, bits[i])))
+            .unzip();
+        let set = arithmetic::mul_vec(&lhs, &rhs, io_context)?;
+        let mut set = set.into_iter();
+        for one_hot in one_hots.iter_mut() {
+            let len = one_hot.len();
+            for j in 0..len {
+                let set = set.next().expect("one product per entry");
+                one_hot[j] -= set;
+                one_hot.push(set);
+            }
+        }
+    }
+    Ok(one_hots)
+}
+
+/// Reads the public table at the given shared indices. All lookups are batched, such that the number of communication rounds only depends on the size of the table.
+///
+/// The indices have to be smaller than the size of the table. Otherwise, the result is not specified.
+pub fn read_public_lut_many<F: PrimeField, N: Rep3Network>(
+    table: &[F],
+    indices: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    if table.is_empty() {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Cannot read from an empty lookup table",
+        ))?;
+    }
+    if indices.is_empty() {
+        return Ok(Vec::new());
+    }
+    let one_hots = one_hot_many(indices, index_bits(table.len()), io_context)?;
+    Ok(one_hots
+        .into_iter()
+        .map(|one_hot| {
+            one_hot
+                .into_iter()
+                .zip(table.iter())
+                .fold(FieldShare::default(), |acc, (bit, value)| {
+                    acc + arithmetic::mul_public(bit, *value)
+                })
+        })
+        .collect())
+}
+
+/// Reads the shared table at the given shared indices. All lookups are batched, such that the number of communication rounds only depends on the size of the table.
+///
+/// The indices have to be smaller than the size of the table. Otherwise, the result is not specified.
+pub fn read_shared_lut_many<F: PrimeField, N: Rep3Network>(
+    table: &[FieldShare<F>],
+    indices: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    if table.is_empty() {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Cannot read from an empty lookup table",
+        ))?;
+    }
+    if indices.is_empty() {
+        return Ok(Vec::new());
+    }
+    let one_hots = one_hot_many(indices, index_bits(table.len()), io_context)?;
+    // The inner products are computed locally and only need a single reshare
+    let local = one_hots
+        .into_iter()
+        .map(|one_hot| {
+            one_hot
+                .into_iter()
+                .zip(table.iter())
+                .fold(F::zero(), |acc, (bit, value)| acc + bit * *value)
+                + io_context.rngs.rand.masking_field_element::<F>()
+        })
+        .collect();
+    arithmetic::io_mul_vec(local, io_context)
+}
+
+/// Reads the public table at the given shared index. See [`read_public_lut_many`] for details.
+pub fn read_public_lut<F: PrimeField, N: Rep3Network>(
+    table: &[F],
+    index: FieldShare<F>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<FieldShare<F>> {
+    let result = read_public_lut_many(table, &[index], io_context)?;
+    Ok(result[0])
+}
+
+/// Reads the shared table at the given shared index. See [`read_shared_lut_many`] for details.
+pub fn read_shared_lut<F: PrimeField, N: Rep3Network>(
+    table: &[FieldShare<F>],
+    index: FieldShare<F>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<FieldShare<F>> {
+    let result = read_shared_lut_many(table, &[index], io_context)?;
+    Ok(result[0])
+}
+
+/// Writes the shared values to the shared table at the given shared indices and returns the updated table. Each write multiplexes between the old entries and the new value using the one-hot encoding (i.e., a demux vector) of its index. The writes are applied in order, such that later writes overwrite earlier writes to the same index. Thus, the one-hot encodings of all indices are computed at once, but every write requires an additional communication round.
+///
+/// The indices have to be smaller than the size of the table. Otherwise, the result is not specified.
+pub fn write_lut_many<F: PrimeField, N: Rep3Network>(
+    table: &[FieldShare<F>],
+    indices: &[FieldShare<F>],
+    values: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    if indices.len() != values.len() {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "The number of indices and values of the lookup table writes do not match",
+        ))?;
+    }
+    if table.is_empty() {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Cannot write to an empty lookup table",
+        ))?;
+    }
+    let mut table = table.to_vec();
+    if indices.is_empty() {
+        return Ok(table);
+    }
+    let one_hots = one_hot_many(indices, index_bits(table.len()), io_context)?;
+    for (mut one_hot, value) in one_hots.into_iter().zip(values.iter()) {
+        one_hot.truncate(table.len());
+        let diff = table.iter().map(|x| *value - *x).collect::<Vec<_>>();
+        let update = arithmetic::mul_vec(&one_hot, &diff, io_context)?;
+        arithmetic::add_vec_assign(&mut table, &update);
+    }
+    Ok(table)
+}
+
+/// Writes the shared value to the shared table at the given shared index and returns the updated table. See [`write_lut_many`] for details.
+pub fn write_lut<F: PrimeField, N: Rep3Network>(
+    table: &[FieldShare<F>],
+    index: FieldShare<F>,
+    value: FieldShare<F>,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    write_lut_many(table, &[index], &[value], io_context)
+}
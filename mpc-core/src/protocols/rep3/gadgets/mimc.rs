@@ -0,0 +1,104 @@
+//! MiMC
+//!
+//! This module contains a PRF based on the MiMC-7 block cipher for the Rep3 protocol. The PRF is evaluated on shared inputs under a shared key. Since the outputs are pseudorandom, they can be opened to find equal inputs, e.g., to deduplicate or join on private identifiers, without revealing anything else about the inputs.
+
+use super::poseidon::{plain_sbox, sbox_many};
+use crate::protocols::rep3::{
+    arithmetic::{self, FieldShare},
+    network::{IoContext, Rep3Network},
+    IoResult,
+};
+use ark_ff::PrimeField;
+use itertools::Itertools;
+use sha3::{Digest, Keccak256};
+
+const D: u64 = 7;
+
+/// The default seed for the round constants.
+pub const DEFAULT_SEED: &[u8] = b"mimc";
+
+/// A PRF based on the MiMC-7 block cipher. Each round computes x = (x + k + c_i)^7, and the key is added once more at the end, i.e., the output is E_k(x) + k.
+#[derive(Clone, Debug)]
+pub struct MimcPrf<F: PrimeField> {
+    round_constants: Vec<F>,
+}
+
+impl<F: PrimeField> Default for MimcPrf<F> {
+    fn default() -> Self {
+        Self::from_seed(DEFAULT_SEED, Self::default_rounds())
+    }
+}
+
+impl<F: PrimeField> MimcPrf<F> {
+    /// Creates a new PRF with the given round constants, one per round.
+    pub fn new(round_constants: Vec<F>) -> Self {
+        assert!(!round_constants.is_empty());
+        Self { round_constants }
+    }
+
+    /// Creates a new PRF with `rounds` rounds. The first round constant is zero, while the others are derived from a Keccak-256 hash chain starting with the hash of `seed`.
+    pub fn from_seed(seed: &[u8], rounds: usize) -> Self {
+        let mut round_constants = Vec::with_capacity(rounds);
+        round_constants.push(F::zero());
+        let mut digest = Keccak256::digest(seed);
+        for _ in 1..rounds {
+            digest = Keccak256::digest(digest);
+            round_constants.push(F::from_be_bytes_mod_order(&digest));
+        }
+        Self::new(round_constants)
+    }
+
+    /// Returns the number of rounds ceil(log_7(p)) required for the field.
+    pub fn default_rounds() -> usize {
+        (F::MODULUS_BIT_SIZE as f64 / (D as f64).log2()).ceil() as usize
+    }
+
+    /// Evaluates the PRF on a plain input and key.
+    pub fn evaluate(&self, key: F, input: F) -> F {
+        self.round_constants
+            .iter()
+            .fold(input, |x, c| plain_sbox::<F, D>(x + key + c))
+            + key
+    }
+
+    /// Evaluates the PRF on many shared inputs under the same shared key. The S-boxes of all inputs in a round are batched, such that the number of communication rounds does not depend on the number of inputs.
+    pub fn rep3_evaluate_many<N: Rep3Network>(
+        &self,
+        key: FieldShare<F>,
+        inputs: &[FieldShare<F>],
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Vec<FieldShare<F>>> {
+        let id = io_context.id;
+        let mut state = inputs.to_vec();
+        for c in self.round_constants.iter() {
+            let input = state
+                .iter()
+                .map(|x| arithmetic::add_public(*x + key, *c, id))
+                .collect_vec();
+            state = sbox_many::<F, N, D>(&input, io_context)?;
+        }
+        Ok(state.into_iter().map(|x| x + key).collect())
+    }
+
+    /// Evaluates the PRF on a single shared input under a shared key.
+    pub fn rep3_evaluate<N: Rep3Network>(
+        &self,
+        key: FieldShare<F>,
+        input: FieldShare<F>,
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<FieldShare<F>> {
+        let res = self.rep3_evaluate_many(key, &[input], io_context)?;
+        Ok(res[0])
+    }
+
+    /// Evaluates the PRF on many shared inputs under the same shared key and opens the outputs. Equal inputs result in equal outputs. The key should be sampled jointly, e.g., using [`arithmetic::rand`], and never be opened.
+    pub fn rep3_evaluate_and_open_many<N: Rep3Network>(
+        &self,
+        key: FieldShare<F>,
+        inputs: &[FieldShare<F>],
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Vec<F>> {
+        let res = self.rep3_evaluate_many(key, inputs, io_context)?;
+        arithmetic::open_vec(&res, io_context)
+    }
+}
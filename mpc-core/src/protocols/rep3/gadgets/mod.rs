@@ -2,4 +2,15 @@
 //!
 //! This module contains some commonly used gadgets for the Rep3 protocol.
 
+pub mod aes;
+pub mod ecc;
+pub mod ecdsa;
+pub mod keccak;
+pub mod linear_algebra;
+pub mod lut;
+pub mod mimc;
+pub mod poseidon;
+pub mod poseidon2;
+pub mod sha256;
+pub mod shuffle;
 pub mod sort;
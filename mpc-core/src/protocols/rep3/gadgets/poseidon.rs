@@ -0,0 +1,164 @@
+//! Poseidon
+//!
+//! This module contains the Poseidon permutation for the Rep3 protocol. The permutation is evaluated on many states in parallel, such that the number of communication rounds only depends on the number of rounds of the permutation and not on the number of states.
+
+use crate::protocols::rep3::{
+    arithmetic::{self, FieldShare},
+    network::{IoContext, Rep3Network},
+    IoResult,
+};
+use ark_ff::PrimeField;
+use itertools::{izip, Itertools};
+
+/// Computes x^D on a plain value.
+pub(super) fn plain_sbox<F: PrimeField, const D: u64>(input: F) -> F {
+    match D {
+        3 => input.square() * input,
+        5 => input.square().square() * input,
+        7 => {
+            let input2 = input.square();
+            input2.square() * input2 * input
+        }
+        _ => input.pow([D]),
+    }
+}
+
+/// Computes x^D on all shared values. For D in {3, 5, 7} this requires two, three, and three communication rounds, respectively.
+pub(super) fn sbox_many<F: PrimeField, N: Rep3Network, const D: u64>(
+    input: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    match D {
+        3 => {
+            let input2 = arithmetic::mul_vec(input, input, io_context)?;
+            arithmetic::mul_vec(input, &input2, io_context)
+        }
+        5 => {
+            let input2 = arithmetic::mul_vec(input, input, io_context)?;
+            let input4 = arithmetic::mul_vec(&input2, &input2, io_context)?;
+            arithmetic::mul_vec(input, &input4, io_context)
+        }
+        7 => {
+            // Compute x^4 and x^3 in the same round
+            let input2 = arithmetic::mul_vec(input, input, io_context)?;
+            let lhs = input2.iter().chain(input.iter()).copied().collect_vec();
+            let rhs = input2.iter().chain(input2.iter()).copied().collect_vec();
+            let mut input4 = arithmetic::mul_vec(&lhs, &rhs, io_context)?;
+            let input3 = input4.split_off(input.len());
+            arithmetic::mul_vec(&input4, &input3, io_context)
+        }
+        _ => arithmetic::pow_public_many(input, F::from(D), io_context),
+    }
+}
+
+/// The Poseidon permutation with state size T and S-box x^D.
+///
+/// Each round adds the round constants, applies the S-box (to the whole state in full rounds and to the first element in partial rounds), and multiplies the state with the MDS matrix. Half of the full rounds are performed before the partial rounds and the other half afterwards.
+#[derive(Clone, Debug)]
+pub struct Poseidon<F: PrimeField, const T: usize, const D: u64> {
+    rounds_f: usize,
+    rounds_p: usize,
+    mds: [[F; T]; T],
+    round_constants: Vec<[F; T]>,
+}
+
+impl<F: PrimeField, const T: usize, const D: u64> Poseidon<F, T, D> {
+    /// Creates a new Poseidon permutation. Panics if the number of round constants does not match the number of rounds or if `rounds_f` is odd.
+    pub fn new(
+        rounds_f: usize,
+        rounds_p: usize,
+        mds: [[F; T]; T],
+        round_constants: Vec<[F; T]>,
+    ) -> Self {
+        assert!(D % 2 == 1);
+        assert_eq!(rounds_f % 2, 0);
+        assert_eq!(round_constants.len(), rounds_f + rounds_p);
+        Self {
+            rounds_f,
+            rounds_p,
+            mds,
+            round_constants,
+        }
+    }
+
+    fn is_full_round(&self, round: usize) -> bool {
+        round < self.rounds_f / 2 || round >= self.rounds_f / 2 + self.rounds_p
+    }
+
+    fn matmul_mds<S>(&self, input: &mut [S; T])
+    where
+        S: Copy + ark_ff::Zero + std::ops::Mul<F, Output = S>,
+    {
+        let state = *input;
+        for (res, row) in input.iter_mut().zip(self.mds.iter()) {
+            *res = state
+                .iter()
+                .zip(row.iter())
+                .fold(S::zero(), |acc, (s, m)| acc + *s * *m);
+        }
+    }
+
+    /// Computes the permutation on a plain state.
+    pub fn permutation(&self, input: &[F; T]) -> [F; T] {
+        let mut state = *input;
+        self.permutation_in_place(&mut state);
+        state
+    }
+
+    /// Computes the permutation on a plain state in place.
+    pub fn permutation_in_place(&self, state: &mut [F; T]) {
+        for (r, rc) in self.round_constants.iter().enumerate() {
+            for (s, rc) in state.iter_mut().zip(rc.iter()) {
+                *s += rc;
+            }
+            if self.is_full_round(r) {
+                state.iter_mut().for_each(|s| *s = plain_sbox::<F, D>(*s));
+            } else {
+                state[0] = plain_sbox::<F, D>(state[0]);
+            }
+            self.matmul_mds(state);
+        }
+    }
+
+    /// Computes the permutation on many shared states. The S-boxes of all states in a round are batched, such that each round requires the communication rounds of one S-box.
+    pub fn rep3_permutation_many<N: Rep3Network>(
+        &self,
+        states: &[[FieldShare<F>; T]],
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Vec<[FieldShare<F>; T]>> {
+        let id = io_context.id;
+        let mut states = states.to_vec();
+        for (r, rc) in self.round_constants.iter().enumerate() {
+            for state in states.iter_mut() {
+                for (s, rc) in state.iter_mut().zip(rc.iter()) {
+                    arithmetic::add_assign_public(s, *rc, id);
+                }
+            }
+            if self.is_full_round(r) {
+                let input = states.iter().flatten().copied().collect_vec();
+                let sbox = sbox_many::<F, N, D>(&input, io_context)?;
+                for (state, sbox) in izip!(states.iter_mut(), sbox.chunks_exact(T)) {
+                    state.copy_from_slice(sbox);
+                }
+            } else {
+                let input = states.iter().map(|state| state[0]).collect_vec();
+                let sbox = sbox_many::<F, N, D>(&input, io_context)?;
+                for (state, sbox) in izip!(states.iter_mut(), sbox) {
+                    state[0] = sbox;
+                }
+            }
+            states.iter_mut().for_each(|state| self.matmul_mds(state));
+        }
+        Ok(states)
+    }
+
+    /// Computes the permutation on a single shared state.
+    pub fn rep3_permutation<N: Rep3Network>(
+        &self,
+        state: &[FieldShare<F>; T],
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<[FieldShare<F>; T]> {
+        let res = self.rep3_permutation_many(std::slice::from_ref(state), io_context)?;
+        Ok(res[0])
+    }
+}
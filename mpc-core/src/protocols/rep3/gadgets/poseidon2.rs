@@ -0,0 +1,240 @@
+//! Poseidon2
+//!
+//! This module contains the Poseidon2 permutation for the Rep3 protocol. The permutation is evaluated on many states in parallel, such that the number of communication rounds only depends on the number of rounds of the permutation and not on the number of states.
+
+use super::poseidon::{plain_sbox, sbox_many};
+use crate::protocols::rep3::{
+    arithmetic::{self, FieldShare},
+    network::{IoContext, Rep3Network},
+    IoResult,
+};
+use ark_ff::PrimeField;
+use itertools::{izip, Itertools};
+
+/// The Poseidon2 permutation with state size T and S-box x^D.
+#[derive(Clone, Debug)]
+pub struct Poseidon2<F: PrimeField, const T: usize, const D: u64> {
+    rounds_f_beginning: usize,
+    // The diagonal of the internal matrix, each element taken minus 1 for more efficient implementations
+    mat_internal_diag_m_1: [F; T],
+    round_constants_external: Vec<[F; T]>,
+    round_constants_internal: Vec<F>,
+}
+
+impl<F: PrimeField, const T: usize, const D: u64> Poseidon2<F, T, D> {
+    /// Creates a new Poseidon2 permutation. Panics if the state size is not supported, if the number of round constants does not match the number of rounds, or if `rounds_f` is odd.
+    pub fn new(
+        rounds_f: usize,
+        rounds_p: usize,
+        mat_internal_diag_m_1: [F; T],
+        round_constants_external: Vec<[F; T]>,
+        round_constants_internal: Vec<F>,
+    ) -> Self {
+        assert!(T == 2 || T == 3 || ((T <= 24) && (T % 4 == 0)));
+        assert!(D % 2 == 1);
+        assert_eq!(rounds_f % 2, 0);
+        assert_eq!(round_constants_external.len(), rounds_f);
+        assert_eq!(round_constants_internal.len(), rounds_p);
+        Self {
+            rounds_f_beginning: rounds_f / 2,
+            mat_internal_diag_m_1,
+            round_constants_external,
+            round_constants_internal,
+        }
+    }
+
+    /**
+     * hardcoded algorithm that evaluates matrix multiplication using the following MDS matrix:
+     * /         \
+     * | 5 7 1 3 |
+     * | 4 6 1 1 |
+     * | 1 3 5 7 |
+     * | 1 1 4 6 |
+     * \         /
+     *
+     * Algorithm is taken directly from the Poseidon2 paper.
+     */
+    fn matmul_m4<S: Copy + std::ops::Add<Output = S>>(input: &mut [S]) {
+        let t_0 = input[0] + input[1]; // A + B
+        let t_1 = input[2] + input[3]; // C + D
+        let t_2 = input[1] + input[1] + t_1; // 2B + C + D
+        let t_3 = input[3] + input[3] + t_0; // A + B + 2D
+        let t_1_2 = t_1 + t_1;
+        let t_4 = t_1_2 + t_1_2 + t_3; // A + B + 4C + 6D
+        let t_0_2 = t_0 + t_0;
+        let t_5 = t_0_2 + t_0_2 + t_2; // 4A + 6B + C + D
+        let t_6 = t_3 + t_5; // 5A + 7B + C + 3D
+        let t_7 = t_2 + t_4; // A + 3B + 5C + 7D
+        input[0] = t_6;
+        input[1] = t_5;
+        input[2] = t_7;
+        input[3] = t_4;
+    }
+
+    fn matmul_external<S: Copy + ark_ff::Zero + std::ops::AddAssign>(input: &mut [S; T]) {
+        match T {
+            2 => {
+                // Matrix circ(2, 1)
+                let sum = input[0] + input[1];
+                input[0] += sum;
+                input[1] += sum;
+            }
+            3 => {
+                // Matrix circ(2, 1, 1)
+                let sum = input[0] + input[1] + input[2];
+                input[0] += sum;
+                input[1] += sum;
+                input[2] += sum;
+            }
+            4 => {
+                Self::matmul_m4(input);
+            }
+            8 | 12 | 16 | 20 | 24 => {
+                // Applying cheap 4x4 MDS matrix to each 4-element part of the state
+                for state in input.chunks_exact_mut(4) {
+                    Self::matmul_m4(state);
+                }
+
+                // Applying second cheap matrix for t > 4
+                let mut stored = [S::zero(); 4];
+                for (l, stored) in stored.iter_mut().enumerate() {
+                    *stored = input[l];
+                    for j in 1..T / 4 {
+                        *stored += input[4 * j + l];
+                    }
+                }
+                for (i, s) in input.iter_mut().enumerate() {
+                    *s += stored[i % 4];
+                }
+            }
+            _ => {
+                panic!("Invalid Statesize");
+            }
+        }
+    }
+
+    fn matmul_internal<S>(&self, input: &mut [S; T])
+    where
+        S: Copy + ark_ff::Zero + std::ops::AddAssign + std::ops::MulAssign<F>,
+    {
+        // Compute input sum
+        let sum = input.iter().fold(S::zero(), |acc, s| acc + *s);
+        // Add sum + diag entry * element to each element
+        for (s, m) in input.iter_mut().zip(self.mat_internal_diag_m_1.iter()) {
+            *s *= *m;
+            *s += sum;
+        }
+    }
+
+    /// Computes the permutation on a plain state.
+    pub fn permutation(&self, input: &[F; T]) -> [F; T] {
+        let mut state = *input;
+        self.permutation_in_place(&mut state);
+        state
+    }
+
+    /// Computes the permutation on a plain state in place.
+    pub fn permutation_in_place(&self, state: &mut [F; T]) {
+        // Linear layer at beginning
+        Self::matmul_external(state);
+
+        // First set of external rounds
+        for rc in &self.round_constants_external[..self.rounds_f_beginning] {
+            for (s, rc) in state.iter_mut().zip(rc.iter()) {
+                *s = plain_sbox::<F, D>(*s + rc);
+            }
+            Self::matmul_external(state);
+        }
+
+        // Internal rounds
+        for rc in self.round_constants_internal.iter() {
+            state[0] = plain_sbox::<F, D>(state[0] + rc);
+            self.matmul_internal(state);
+        }
+
+        // Remaining external rounds
+        for rc in &self.round_constants_external[self.rounds_f_beginning..] {
+            for (s, rc) in state.iter_mut().zip(rc.iter()) {
+                *s = plain_sbox::<F, D>(*s + rc);
+            }
+            Self::matmul_external(state);
+        }
+    }
+
+    fn rep3_external_round<N: Rep3Network>(
+        states: &mut [[FieldShare<F>; T]],
+        rc: &[F; T],
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<()> {
+        let id = io_context.id;
+        let input = states
+            .iter()
+            .flat_map(|state| {
+                izip!(state.iter(), rc.iter()).map(|(s, rc)| arithmetic::add_public(*s, *rc, id))
+            })
+            .collect_vec();
+        let sbox = sbox_many::<F, N, D>(&input, io_context)?;
+        for (state, sbox) in izip!(states.iter_mut(), sbox.chunks_exact(T)) {
+            state.copy_from_slice(sbox);
+            Self::matmul_external(state);
+        }
+        Ok(())
+    }
+
+    fn rep3_internal_round<N: Rep3Network>(
+        &self,
+        states: &mut [[FieldShare<F>; T]],
+        rc: F,
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<()> {
+        let id = io_context.id;
+        let input = states
+            .iter()
+            .map(|state| arithmetic::add_public(state[0], rc, id))
+            .collect_vec();
+        let sbox = sbox_many::<F, N, D>(&input, io_context)?;
+        for (state, sbox) in izip!(states.iter_mut(), sbox) {
+            state[0] = sbox;
+            self.matmul_internal(state);
+        }
+        Ok(())
+    }
+
+    /// Computes the permutation on many shared states. The S-boxes of all states in a round are batched, such that each round requires the communication rounds of one S-box.
+    pub fn rep3_permutation_many<N: Rep3Network>(
+        &self,
+        states: &[[FieldShare<F>; T]],
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<Vec<[FieldShare<F>; T]>> {
+        let mut states = states.to_vec();
+
+        // Linear layer at beginning
+        states.iter_mut().for_each(Self::matmul_external);
+
+        // First set of external rounds
+        for rc in &self.round_constants_external[..self.rounds_f_beginning] {
+            Self::rep3_external_round(&mut states, rc, io_context)?;
+        }
+
+        // Internal rounds
+        for rc in self.round_constants_internal.iter() {
+            self.rep3_internal_round(&mut states, *rc, io_context)?;
+        }
+
+        // Remaining external rounds
+        for rc in &self.round_constants_external[self.rounds_f_beginning..] {
+            Self::rep3_external_round(&mut states, rc, io_context)?;
+        }
+        Ok(states)
+    }
+
+    /// Computes the permutation on a single shared state.
+    pub fn rep3_permutation<N: Rep3Network>(
+        &self,
+        state: &[FieldShare<F>; T],
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<[FieldShare<F>; T]> {
+        let res = self.rep3_permutation_many(std::slice::from_ref(state), io_context)?;
+        Ok(res[0])
+    }
+}
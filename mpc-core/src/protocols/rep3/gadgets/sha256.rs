@@ -0,0 +1,306 @@
+//! SHA-256
+//!
+//! This module contains the SHA-256 compression function for the Rep3 protocol. The 32-bit words of all compression function calls are bit-sliced into packed binary shares, such that all rotations and XORs are local and the additions of a step are evaluated in one packed adder for all calls at once. The conversions from and to arithmetic shares are only performed at the boundaries and are batched as well.
+
+use crate::protocols::rep3::{
+    arithmetic::FieldShare,
+    binary, conversion,
+    network::{IoContext, Rep3Network},
+    IoResult, Rep3BigUintShare,
+};
+use ark_ff::{One, PrimeField, Zero};
+use itertools::{izip, Itertools};
+use num_bigint::BigUint;
+
+type BinaryShare<F> = Rep3BigUintShare<F>;
+
+const WORD_BITS: usize = 32;
+
+/// The initial hash value of SHA-256.
+pub const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Returns a value which has the lowest `bits` bits of each of the `num_words` words set.
+fn word_mask(num_words: usize, bits: usize) -> BigUint {
+    let word = (BigUint::one() << bits) - BigUint::one();
+    (0..num_words).fold(BigUint::zero(), |acc, i| acc | (&word << (i * WORD_BITS)))
+}
+
+/// Packs values of `num_words` words each into a single value.
+fn pack<F: PrimeField>(values: &[BinaryShare<F>], num_words: usize) -> BinaryShare<F> {
+    let mut res = BinaryShare::zero_share();
+    for (i, value) in values.iter().enumerate() {
+        res ^= value << (i * num_words * WORD_BITS);
+    }
+    res
+}
+
+/// Splits a value into `amount` values of `num_words` words each. Inverse of [`pack`].
+fn unpack<F: PrimeField>(
+    value: &BinaryShare<F>,
+    amount: usize,
+    num_words: usize,
+) -> Vec<BinaryShare<F>> {
+    let mask = (BigUint::one() << (num_words * WORD_BITS)) - BigUint::one();
+    (0..amount)
+        .map(|i| &(value >> (i * num_words * WORD_BITS)) & &mask)
+        .collect()
+}
+
+fn rotr<F: PrimeField>(x: &BinaryShare<F>, r: usize, num_words: usize) -> BinaryShare<F> {
+    let low = &(x >> r) & &word_mask(num_words, WORD_BITS - r);
+    let high = (x & &word_mask(num_words, r)) << (WORD_BITS - r);
+    low ^ high
+}
+
+fn shr<F: PrimeField>(x: &BinaryShare<F>, r: usize, num_words: usize) -> BinaryShare<F> {
+    &(x >> r) & &word_mask(num_words, WORD_BITS - r)
+}
+
+fn big_sigma0<F: PrimeField>(x: &BinaryShare<F>, num_words: usize) -> BinaryShare<F> {
+    rotr(x, 2, num_words) ^ rotr(x, 13, num_words) ^ rotr(x, 22, num_words)
+}
+
+fn big_sigma1<F: PrimeField>(x: &BinaryShare<F>, num_words: usize) -> BinaryShare<F> {
+    rotr(x, 6, num_words) ^ rotr(x, 11, num_words) ^ rotr(x, 25, num_words)
+}
+
+fn small_sigma0<F: PrimeField>(x: &BinaryShare<F>, num_words: usize) -> BinaryShare<F> {
+    rotr(x, 7, num_words) ^ rotr(x, 18, num_words) ^ shr(x, 3, num_words)
+}
+
+fn small_sigma1<F: PrimeField>(x: &BinaryShare<F>, num_words: usize) -> BinaryShare<F> {
+    rotr(x, 17, num_words) ^ rotr(x, 19, num_words) ^ shr(x, 10, num_words)
+}
+
+/// Computes the ANDs of all pairs of values with `num_words` words each in a single communication round.
+fn and_many<F: PrimeField, N: Rep3Network>(
+    pairs: &[(BinaryShare<F>, BinaryShare<F>)],
+    num_words: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<BinaryShare<F>>> {
+    if pairs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (lhs, rhs): (Vec<_>, Vec<_>) = pairs.iter().cloned().unzip();
+    let bitlen = pairs.len() * num_words * WORD_BITS;
+    let res = binary::and_with_bitlen(
+        &pack(&lhs, num_words),
+        &pack(&rhs, num_words),
+        io_context,
+        bitlen,
+    )?;
+    Ok(unpack(&res, pairs.len(), num_words))
+}
+
+/// Adds the two values with `num_words` words each wordwise modulo 2^32 using a packed Kogge-Stone adder.
+fn add_packed<F: PrimeField, N: Rep3Network>(
+    x1: &BinaryShare<F>,
+    x2: &BinaryShare<F>,
+    num_words: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<BinaryShare<F>> {
+    let s = x1 ^ x2;
+    let mut p = s.clone();
+    let mut g = binary::and_with_bitlen(x1, x2, io_context, num_words * WORD_BITS)?;
+    let mut shift = 1;
+    while shift < WORD_BITS {
+        // Masking keeps the carries from crossing the word boundaries
+        let mask = word_mask(num_words, WORD_BITS - shift);
+        let p_ = &p & &mask;
+        let g_ = &g & &mask;
+        let p_shift = &p >> shift;
+        let mut res = and_many(
+            &[(p_shift.clone(), g_), (p_shift, p_)],
+            num_words,
+            io_context,
+        )?;
+        p = res.pop().expect("two results") << shift;
+        g ^= res.pop().expect("two results") << shift;
+        shift <<= 1;
+    }
+    // The carry out of the most significant bit of each word is discarded
+    Ok(s ^ ((&g & &word_mask(num_words, WORD_BITS - 1)) << 1))
+}
+
+/// Computes the wordwise sums modulo 2^32 of all lists of values with `num_words` words each. Each list is first reduced to two values using carry-save adders, where all reductions of a layer require one communication round. Afterwards, the remaining additions are evaluated in one packed Kogge-Stone adder.
+fn add_many<F: PrimeField, N: Rep3Network>(
+    mut sums: Vec<Vec<BinaryShare<F>>>,
+    num_words: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<BinaryShare<F>>> {
+    let carry_mask = word_mask(num_words, WORD_BITS - 1);
+    loop {
+        // Each triple a, b, c is replaced by a ^ b ^ c and the carries maj(a, b, c) = a ^ ((a ^ b) & (a ^ c))
+        let mut pairs = Vec::new();
+        let mut xors = Vec::new();
+        for (i, sum) in sums.iter_mut().enumerate() {
+            let num_triples = sum.len() / 3;
+            if sum.len() < 3 {
+                continue;
+            }
+            let triples = sum.split_off(sum.len() - 3 * num_triples);
+            for (a, b, c) in triples.into_iter().tuples() {
+                let ab = &a ^ &b;
+                let ac = &a ^ &c;
+                xors.push((i, &ab ^ &c, a));
+                pairs.push((ab, ac));
+            }
+        }
+        if pairs.is_empty() {
+            break;
+        }
+        let ands = and_many(&pairs, num_words, io_context)?;
+        for ((i, xor, a), and) in izip!(xors, ands) {
+            let maj = and ^ a;
+            sums[i].push(xor);
+            sums[i].push((&maj & &carry_mask) << 1);
+        }
+    }
+
+    let (lhs, rhs): (Vec<_>, Vec<_>) = sums
+        .into_iter()
+        .map(|mut sum| {
+            sum.resize(2, BinaryShare::zero_share());
+            let rhs = sum.pop().expect("two values");
+            let lhs = sum.pop().expect("two values");
+            (lhs, rhs)
+        })
+        .unzip();
+    let res = add_packed(
+        &pack(&lhs, num_words),
+        &pack(&rhs, num_words),
+        lhs.len() * num_words,
+        io_context,
+    )?;
+    Ok(unpack(&res, lhs.len(), num_words))
+}
+
+/// Computes the SHA-256 compression function on `num_instances` calls at once. Each of the given binary shares packs the respective word of all calls, i.e., word j of call i is stored in bits 32i..32(i+1) of the j-th value.
+pub fn compress_packed<F: PrimeField, N: Rep3Network>(
+    state: &[BinaryShare<F>; 8],
+    block: &[BinaryShare<F>; 16],
+    num_instances: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<[BinaryShare<F>; 8]> {
+    let n = num_instances;
+    let id = io_context.id;
+
+    // Message schedule, w[t] and w[t + 1] only depend on words before t
+    let mut w = block.to_vec();
+    for t in (16..64).step_by(2) {
+        let sums = (t..t + 2)
+            .map(|t| {
+                vec![
+                    small_sigma1(&w[t - 2], n),
+                    w[t - 7].clone(),
+                    small_sigma0(&w[t - 15], n),
+                    w[t - 16].clone(),
+                ]
+            })
+            .collect();
+        w.extend(add_many(sums, n, io_context)?);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state.clone();
+    for (k, w) in izip!(K, w) {
+        let k = (0..n).fold(BigUint::zero(), |acc, i| {
+            acc | (BigUint::from(k) << (i * WORD_BITS))
+        });
+        let k = binary::promote_to_trivial_share(id, &k);
+        // Ch(e, f, g) = g ^ (e & (f ^ g)) and Maj(a, b, c) = a ^ ((a ^ b) & (a ^ c))
+        let mut ands = and_many(&[(e.clone(), &f ^ &g), (&a ^ &b, &a ^ &c)], n, io_context)?;
+        let maj = &a ^ &ands.pop().expect("two results");
+        let ch = &g ^ &ands.pop().expect("two results");
+
+        // e' = d + T1 and a' = T1 + T2 with T1 = h + Σ1(e) + Ch(e, f, g) + k + w and T2 = Σ0(a) + Maj(a, b, c)
+        let t1 = vec![h, big_sigma1(&e, n), ch, k, w];
+        let mut new_e = t1.clone();
+        new_e.push(d);
+        let mut new_a = t1;
+        new_a.push(big_sigma0(&a, n));
+        new_a.push(maj);
+        let mut res = add_many(vec![new_e, new_a], n, io_context)?;
+        let new_a = res.pop().expect("two results");
+        let new_e = res.pop().expect("two results");
+
+        h = g;
+        g = f;
+        f = e;
+        e = new_e;
+        d = c;
+        c = b;
+        b = a;
+        a = new_a;
+    }
+
+    let sums = izip!(state.iter(), [a, b, c, d, e, f, g, h])
+        .map(|(state, x)| vec![state.clone(), x])
+        .collect();
+    let res = add_many(sums, n, io_context)?;
+    Ok(res.try_into().expect("eight results"))
+}
+
+/// Computes the SHA-256 compression function for all pairs of states and message blocks. The states and message blocks are given as arithmetic shares of 32-bit words, where the message words are in big-endian order as defined by SHA-256. All values are converted to binary shares in one batched conversion, the compression function is evaluated using [`compress_packed`], and the resulting states are converted back to arithmetic shares in one batched conversion.
+///
+/// All words have to be smaller than 2^32. Otherwise, the result is not specified.
+pub fn compress_many<F: PrimeField, N: Rep3Network>(
+    states: &[[FieldShare<F>; 8]],
+    blocks: &[[FieldShare<F>; 16]],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<[FieldShare<F>; 8]>> {
+    if states.len() != blocks.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "SHA-256 compression requires the same number of states and message blocks",
+        ));
+    }
+    let n = states.len();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+
+    let words = (0..8)
+        .flat_map(|j| states.iter().map(move |state| state[j]))
+        .chain((0..16).flat_map(|j| blocks.iter().map(move |block| block[j])))
+        .collect_vec();
+    let words = conversion::a2y2b_many(&words, io_context)?;
+    let mask = word_mask(1, WORD_BITS);
+    let packed = words
+        .chunks_exact(n)
+        .map(|words| pack(&words.iter().map(|w| w & &mask).collect_vec(), 1))
+        .collect_vec();
+    let state = packed[..8].to_vec().try_into().expect("eight words");
+    let block = packed[8..].to_vec().try_into().expect("sixteen words");
+
+    let res = compress_packed(&state, &block, n, io_context)?;
+
+    let bits = res
+        .iter()
+        .flat_map(|word| (0..n * WORD_BITS).map(move |i| &(word >> i) & &BigUint::one()))
+        .collect_vec();
+    let bits = conversion::bit_inject_many(&bits, io_context)?;
+    let words = bits
+        .chunks_exact(WORD_BITS)
+        .map(|bits| {
+            bits.iter()
+                .rev()
+                .fold(FieldShare::default(), |acc, bit| acc + acc + *bit)
+        })
+        .collect_vec();
+    Ok((0..n)
+        .map(|i| std::array::from_fn(|j| words[j * n + i]))
+        .collect())
+}
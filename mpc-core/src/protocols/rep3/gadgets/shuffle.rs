@@ -0,0 +1,170 @@
+//! Shuffle
+//!
+//! This module contains an oblivious shuffle for the Rep3 protocol.
+
+use crate::protocols::rep3::{
+    arithmetic::FieldShare,
+    id::PartyID,
+    malicious,
+    network::{IoContext, Rep3Network},
+    IoResult,
+};
+use ark_ff::PrimeField;
+
+/// Obliviously shuffles the inputs with a random permutation which is unknown to all parties.
+///
+/// The permutation is the composition of three random permutations, where each of them is known to two of the parties (see ABY3). Thus, no single party learns the permutation, and since the outputs are freshly reshared, the parties cannot link the outputs to the inputs.
+pub fn shuffle<F: PrimeField, N: Rep3Network>(
+    inputs: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    shuffle_rows(inputs, 1, io_context)
+}
+
+/// Obliviously shuffles the rows of a matrix with a random permutation which is unknown to all parties, i.e., all columns are shuffled with the same permutation. The matrix is given in row-major order with `width` columns. See [`shuffle`] for details.
+pub fn shuffle_rows<F: PrimeField, N: Rep3Network>(
+    inputs: &[FieldShare<F>],
+    width: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    assert!(width > 0, "width must be positive");
+    assert_eq!(inputs.len() % width, 0, "inputs must consist of full rows");
+    let len = inputs.len() / width;
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+    let unshuffled = (0..len).collect::<Vec<_>>();
+    let (perm_a, perm_b) = io_context.rngs.rand.random_perm(unshuffled);
+    shuffle_with_perm(&perm_a, &perm_b, inputs, width, io_context)
+}
+
+/// Permutes the rows of a matrix in row-major order, i.e., the i-th output row is the input row perm[i].
+fn permute_rows<T: Copy>(input: &[T], perm: &[usize], width: usize) -> Vec<T> {
+    perm.iter()
+        .flat_map(|pi| input[pi * width..(pi + 1) * width].iter().copied())
+        .collect()
+}
+
+/// Applies the permutation shared as (perm_a, perm_b) to the rows of the input. ID0 has (pi_1, pi_3), ID1 has (pi_2, pi_1), and ID2 has (pi_3, pi_2).
+fn shuffle_with_perm<F: PrimeField, N: Rep3Network>(
+    perm_a: &[usize],
+    perm_b: &[usize],
+    input: &[FieldShare<F>],
+    width: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "shuffle")?;
+    let len = input.len();
+    debug_assert_eq!(len, perm_a.len() * width);
+    debug_assert_eq!(len, perm_b.len() * width);
+    let result = match io_context.id {
+        PartyID::ID0 => {
+            // has p1, p3
+            let mut alpha_1 = Vec::with_capacity(len);
+            let mut alpha_3 = Vec::with_capacity(len);
+            let mut beta_1 = Vec::with_capacity(len);
+            for a in input {
+                let (alpha_1_, alpha_3_) = io_context.random_fes::<F>();
+                alpha_1.push(alpha_1_);
+                alpha_3.push(alpha_3_);
+                beta_1.push(a.a + a.b);
+            }
+            // first shuffle
+            let mut shuffled_1 = permute_rows(&beta_1, perm_a, width);
+            for (des, alpha) in shuffled_1.iter_mut().zip(alpha_1) {
+                *des -= alpha;
+            }
+            // second shuffle
+            let mut shuffled_3 = permute_rows(&shuffled_1, perm_b, width);
+            for (des, alpha) in shuffled_3.iter_mut().zip(alpha_3) {
+                *des -= alpha;
+            }
+            io_context.network.send_next_many(&shuffled_3)?;
+
+            // Opt Reshare
+            let mut result = Vec::with_capacity(len);
+            for _ in 0..len {
+                let (a, b) = io_context.random_fes::<F>();
+                result.push(FieldShare::new(a, b));
+            }
+            result
+        }
+        PartyID::ID1 => {
+            // has p2, p1
+            let mut alpha_1 = Vec::with_capacity(len);
+            let mut beta_2 = Vec::with_capacity(len);
+            for a in input {
+                let alpha_1_ = io_context.rngs.rand.random_field_element_rng2::<F>();
+                alpha_1.push(alpha_1_);
+                beta_2.push(a.a);
+            }
+            // first shuffle
+            let mut shuffled_1 = permute_rows(&beta_2, perm_b, width);
+            for (des, alpha) in shuffled_1.iter_mut().zip(alpha_1) {
+                *des += alpha;
+            }
+            let delta = io_context.network.reshare_many(&shuffled_1)?;
+            if delta.len() != len {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "During execution of shuffle in MPC: Invalid number of elements received",
+                ));
+            }
+            // second shuffle
+            let beta_2_prime = permute_rows(&delta, perm_a, width);
+
+            // Opt Reshare
+            let mut result = Vec::with_capacity(len);
+            let mut rand = Vec::with_capacity(len);
+            for beta in beta_2_prime {
+                let b = io_context.rngs.rand.random_field_element_rng2::<F>();
+                rand.push(beta - b);
+                result.push(FieldShare::new(F::zero(), b));
+            }
+            io_context.network.send_next_many(&rand)?;
+            let rcv: Vec<F> = io_context.network.recv_many(PartyID::ID2)?;
+            for (res, (r1, r2)) in result.iter_mut().zip(rcv.into_iter().zip(rand)) {
+                res.a = r1 + r2;
+            }
+            result
+        }
+        PartyID::ID2 => {
+            // has p3, p2
+            let mut alpha_3 = Vec::with_capacity(len);
+            for _ in 0..len {
+                let alpha_3_ = io_context.rngs.rand.random_field_element_rng1::<F>();
+                alpha_3.push(alpha_3_);
+            }
+            let gamma: Vec<F> = io_context.network.recv_prev_many()?;
+            if gamma.len() != len {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "During execution of shuffle in MPC: Invalid number of elements received",
+                ));
+            }
+            // first shuffle
+            let mut shuffled_1 = permute_rows(&gamma, perm_a, width);
+            for (des, alpha) in shuffled_1.iter_mut().zip(alpha_3) {
+                *des += alpha;
+            }
+            // second shuffle
+            let beta_3_prime = permute_rows(&shuffled_1, perm_b, width);
+
+            // Opt Reshare
+            let mut result = Vec::with_capacity(len);
+            let mut rand = Vec::with_capacity(len);
+            for beta in beta_3_prime {
+                let a = io_context.rngs.rand.random_field_element_rng1::<F>();
+                rand.push(beta - a);
+                result.push(FieldShare::new(a, F::zero()));
+            }
+            io_context.network.send_many(PartyID::ID1, &rand)?;
+            let rcv: Vec<F> = io_context.network.recv_prev_many()?;
+            for (res, (r1, r2)) in result.iter_mut().zip(rcv.into_iter().zip(rand)) {
+                res.b = r1 + r2;
+            }
+            result
+        }
+    };
+    Ok(result)
+}
@@ -2,8 +2,9 @@
 //!
 //! This module contains some oblivious sorting algorithms for the Rep3 protocol.
 
+use super::shuffle;
 use crate::protocols::rep3::{
-    arithmetic::FieldShare,
+    arithmetic::{self, FieldShare},
     network::{IoContext, Rep3Network},
     yao::{self, circuits::GarbledCircuits},
     IoResult,
@@ -32,3 +33,99 @@ pub fn batcher_odd_even_merge_sort_yao<F: PrimeField, N: Rep3Network>(
         (bitsize)
     )
 }
+
+/// Sorts the inputs using a shuffle-then-sort approach: The inputs are first obliviously shuffled (see [`shuffle::shuffle_rows`]) and then sorted with a quicksort, whose comparison results are opened. Since the inputs are shuffled and ties are broken using the (shuffled) original positions, the opened comparison results do not leak anything about the inputs. Thereby, only the lowest `bitsize` bits are considered, i.e., the inputs have to lie in [0, 2^bitsize).
+pub fn quicksort<F: PrimeField, N: Rep3Network>(
+    inputs: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+    bitsize: usize,
+) -> IoResult<Vec<FieldShare<F>>> {
+    let (sorted, _) = quicksort_with_permutation(inputs, io_context, bitsize)?;
+    Ok(sorted)
+}
+
+/// Sorts the inputs like [`quicksort`], but additionally returns the shared permutation, i.e., the i-th sorted value is the input at the shared index perm[i].
+#[expect(clippy::type_complexity)]
+pub fn quicksort_with_permutation<F: PrimeField, N: Rep3Network>(
+    inputs: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+    bitsize: usize,
+) -> IoResult<(Vec<FieldShare<F>>, Vec<FieldShare<F>>)> {
+    let len = inputs.len();
+    let index_bits = usize::BITS as usize - len.leading_zeros() as usize;
+    if bitsize + index_bits >= F::MODULUS_BIT_SIZE as usize {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Bit size is too large to break ties in the field",
+        ))?;
+    }
+    if len == 0 {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    // Shuffle the values together with their original positions
+    let id = io_context.id;
+    let rows = inputs
+        .iter()
+        .enumerate()
+        .flat_map(|(i, x)| {
+            [
+                *x,
+                arithmetic::promote_to_trivial_share(id, F::from(i as u64)),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let shuffled = shuffle::shuffle_rows(&rows, 2, io_context)?;
+    let (values, indices): (Vec<_>, Vec<_>) =
+        shuffled.chunks_exact(2).map(|row| (row[0], row[1])).unzip();
+
+    // The keys are unique, so the opened comparisons only reveal the order of the shuffled keys
+    let shift = F::from(2u64).pow([index_bits as u64]);
+    let keys = values
+        .iter()
+        .zip(indices.iter())
+        .map(|(value, index)| arithmetic::mul_public(*value, shift) + *index)
+        .collect::<Vec<_>>();
+
+    // Quicksort, where all partitions of one level are computed at once
+    let mut segments = vec![(0..len).collect::<Vec<_>>()];
+    while segments.iter().any(|segment| segment.len() > 1) {
+        let mut lhs = Vec::new();
+        let mut rhs = Vec::new();
+        for segment in segments.iter().filter(|segment| segment.len() > 1) {
+            let pivot = keys[segment[0]];
+            for i in segment.iter().skip(1) {
+                lhs.push(keys[*i]);
+                rhs.push(pivot);
+            }
+        }
+        let lt = arithmetic::lt_many(&lhs, &rhs, io_context)?;
+        let lt = arithmetic::open_vec(&lt, io_context)?;
+
+        let mut lt = lt.into_iter();
+        let mut next_segments = Vec::with_capacity(segments.len() * 3);
+        for segment in segments {
+            if segment.len() <= 1 {
+                next_segments.push(segment);
+                continue;
+            }
+            let mut smaller = Vec::new();
+            let mut larger = Vec::new();
+            for i in segment.iter().skip(1) {
+                if lt.next().expect("one comparison per element").is_one() {
+                    smaller.push(*i);
+                } else {
+                    larger.push(*i);
+                }
+            }
+            next_segments.push(smaller);
+            next_segments.push(vec![segment[0]]);
+            next_segments.push(larger);
+        }
+        next_segments.retain(|segment| !segment.is_empty());
+        segments = next_segments;
+    }
+
+    let order = segments.into_iter().flatten();
+    Ok(order.map(|i| (values[i], indices[i])).unzip())
+}
@@ -0,0 +1,167 @@
+//! Malicious Security
+//!
+//! This module contains the checks of the actively secure mode of the rep3 protocol, which is selected by setting the [`SecurityLevel`] of the [`IoContext`] to [`SecurityLevel::Malicious`]. In this mode, the openings of arithmetic shares and point shares are checked for consistency by receiving the missing share from both neighbors (see [`arithmetic::open_many_checked`] and [`pointshare::open_point_many_checked`]), and the results of [`arithmetic::mul`], [`arithmetic::mul_vec`] and [`pointshare::scalar_mul`] are verified by sacrificing a random multiplication triple (see [Furukawa et al.](https://eprint.iacr.org/2016/944.pdf)). If a check fails, the operation returns an error and the parties have to abort.
+//!
+//! Keep in mind: Binary operations, garbled circuits, conversions and the gadgets built on them do not have an actively secure variant yet. They return an error of kind [`std::io::ErrorKind::Unsupported`] if they are called in the malicious mode (see [`ensure_semi_honest`]).
+
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use itertools::{izip, Itertools};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    arithmetic::{self, CheckedOpenError, FieldShare},
+    network::{IoContext, Rep3Network},
+    pointshare::{self, Rep3PointShare},
+    IoResult,
+};
+
+/// The security level of the rep3 protocol against a corrupted party.
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash,
+)]
+pub enum SecurityLevel {
+    /// The corrupted party follows the protocol, but tries to learn additional information.
+    #[default]
+    SemiHonest,
+    /// The corrupted party may deviate arbitrarily from the protocol. Deviations during multiplications and openings are detected (see the [module documentation](self)).
+    Malicious,
+}
+
+/// Returns an error of kind [`std::io::ErrorKind::Unsupported`] if the [`SecurityLevel`] of the [`IoContext`] is [`SecurityLevel::Malicious`]. Every operation without an actively secure variant calls this first, so it cannot silently run with semi-honest security.
+pub fn ensure_semi_honest<N: Rep3Network>(
+    io_context: &IoContext<N>,
+    operation: &str,
+) -> IoResult<()> {
+    if io_context.security_level == SecurityLevel::Malicious {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("{operation} is not supported in the malicious security mode"),
+        ));
+    }
+    Ok(())
+}
+
+impl From<CheckedOpenError> for std::io::Error {
+    fn from(err: CheckedOpenError) -> Self {
+        match err {
+            CheckedOpenError::Io(err) => err,
+            err @ CheckedOpenError::Inconsistent(_) => {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+            }
+        }
+    }
+}
+
+/// Verifies that z = x * y holds for all given triples of shared values by sacrificing a random multiplication triple for each of them. The check requires three communication rounds and returns an error if it fails, i.e., if a party cheated during the multiplication.
+pub fn verify_mul_triples<F: PrimeField, N: Rep3Network>(
+    x: &[FieldShare<F>],
+    y: &[FieldShare<F>],
+    z: &[FieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<()> {
+    debug_assert_eq!(x.len(), y.len());
+    debug_assert_eq!(x.len(), z.len());
+    if x.is_empty() {
+        return Ok(());
+    }
+    // the random triples (a, b, c = a * b) to sacrifice
+    let a = (0..x.len())
+        .map(|_| arithmetic::rand(io_context))
+        .collect_vec();
+    let b = (0..x.len())
+        .map(|_| arithmetic::rand(io_context))
+        .collect_vec();
+    let c = izip!(&a, &b)
+        .map(|(a, b)| a * b + io_context.rngs.rand.masking_field_element::<F>())
+        .collect_vec();
+    let c = arithmetic::io_mul_vec(c, io_context)?;
+
+    // the challenge is only revealed after all triples are fixed
+    let r: FieldShare<F> = arithmetic::rand(io_context);
+    let r = arithmetic::open_many_checked(&[r], io_context)?[0];
+
+    // rho = r * x - a, sigma = y - b
+    let masked = izip!(x, y, &a, &b)
+        .flat_map(|(x, y, a, b)| [*x * r - *a, *y - *b])
+        .collect_vec();
+    let masked = arithmetic::open_many_checked(&masked, io_context)?;
+
+    // r * z - c - sigma * a - rho * b - rho * sigma = r * (z - x * y) - (c - a * b) has to be zero
+    let check = izip!(z, &c, &a, &b, masked.chunks_exact(2))
+        .map(|(z, c, a, b, masked)| {
+            let (rho, sigma) = (masked[0], masked[1]);
+            let t = *z * r - *c - *a * sigma - *b * rho;
+            arithmetic::add_public(t, -(rho * sigma), io_context.id)
+        })
+        .collect_vec();
+    let check = arithmetic::open_many_checked(&check, io_context)?;
+    if check.iter().any(|t| !t.is_zero()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "verification of multiplications failed, a party cheated",
+        ));
+    }
+    Ok(())
+}
+
+/// Verifies that z = x * p holds for all given shared scalars x and shared points p. For each of them, a random triple (a, B = b * G, C = a * B) is sacrificed, where (a, b, a * b) is a checked multiplication triple of field elements and G is the generator of the group. Returns an error if the check fails, i.e., if a party cheated during the scalar multiplication.
+pub fn verify_scalar_mul_points<C: CurveGroup, N: Rep3Network>(
+    x: &[FieldShare<C::ScalarField>],
+    p: &[Rep3PointShare<C>],
+    z: &[Rep3PointShare<C>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<()> {
+    debug_assert_eq!(x.len(), p.len());
+    debug_assert_eq!(x.len(), z.len());
+    if x.is_empty() {
+        return Ok(());
+    }
+    let generator = C::generator();
+    // the random triples (a, B, C = a * B) to sacrifice, the multiplication of the scalars is checked itself
+    let a = (0..x.len())
+        .map(|_| arithmetic::rand(io_context))
+        .collect_vec();
+    let b = (0..x.len())
+        .map(|_| arithmetic::rand(io_context))
+        .collect_vec();
+    let c = arithmetic::mul_vec(&a, &b, io_context)?;
+    let b = b
+        .iter()
+        .map(|b| pointshare::scalar_mul_public_point(&generator, *b))
+        .collect_vec();
+    let c = c
+        .iter()
+        .map(|c| pointshare::scalar_mul_public_point(&generator, *c))
+        .collect_vec();
+
+    // the challenge is only revealed after all triples are fixed
+    let r: FieldShare<C::ScalarField> = arithmetic::rand(io_context);
+    let r = arithmetic::open_many_checked(&[r], io_context)?[0];
+
+    // rho = r * x - a, sigma = p - B
+    let rho = izip!(x, &a).map(|(x, a)| *x * r - *a).collect_vec();
+    let rho = arithmetic::open_many_checked(&rho, io_context)?;
+    let sigma = izip!(p, &b).map(|(p, b)| p - b).collect_vec();
+    let sigma = pointshare::open_point_many_checked(&sigma, io_context)?;
+
+    // r * z - C - sigma * a - rho * B - rho * sigma = r * (z - x * p) has to be the identity
+    let check = izip!(z, &c, &a, &b, rho, sigma)
+        .map(|(z, c, a, b, rho, sigma)| {
+            let mut t = pointshare::scalar_mul_public_scalar(z, r);
+            pointshare::sub_assign(&mut t, c);
+            pointshare::sub_assign(&mut t, &pointshare::scalar_mul_public_point(&sigma, *a));
+            pointshare::sub_assign(&mut t, &pointshare::scalar_mul_public_scalar(b, rho));
+            pointshare::sub_assign_public(&mut t, &(sigma * rho), io_context.id);
+            t
+        })
+        .collect_vec();
+    let check = pointshare::open_point_many_checked(&check, io_context)?;
+    if check.iter().any(|t| !t.is_zero()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "verification of scalar multiplications failed, a party cheated",
+        ));
+    }
+    Ok(())
+}
@@ -2,21 +2,31 @@
 //!
 //! This module contains implementation of the rep3 mpc network
 
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use crate::RngType;
+use crate::{protocols::broadcast, RngType};
 use ark_ff::PrimeField;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use bytes::{Bytes, BytesMut};
 use eyre::{bail, eyre, Report};
 use mpc_net::{
-    channel::ChannelHandle, config::NetworkConfig, MpcNetworkHandler, MpcNetworkHandlerWrapper,
+    abort::AbortReason,
+    channel::ChannelHandle,
+    config::{NetworkConfig, Phase},
+    stats::{NetworkStats, DEFAULT_PHASE},
+    transcript::SignedTranscript,
+    MpcNetworkHandler, MpcNetworkHandlerWrapper,
 };
 
 use super::{
-    conversion::A2BType,
+    conversion::{A2BAutoNetwork, A2BType},
     id::PartyID,
-    rngs::{Rep3CorrelatedRng, Rep3Rand, Rep3RandBitComp},
+    malicious::SecurityLevel,
+    rngs::{Rep3CorrelatedRng, Rep3CorrelatedRngSeeds, Rep3PrssState, Rep3Rand, Rep3RandBitComp},
+    yao::roles::{GCRoleNetwork, GCRoles},
     IoResult,
 };
 use rand::{distributions::Standard, prelude::Distribution, CryptoRng, Rng, SeedableRng};
@@ -34,6 +44,10 @@ pub struct IoContext<N: Rep3Network> {
     pub network: N,
     /// The used arithmetic/binary conversion protocol
     pub a2b_type: A2BType,
+    /// The network assumed when the conversion protocol is chosen automatically, see [`A2BType::Auto`]
+    pub a2b_auto_network: A2BAutoNetwork,
+    /// The security level against corrupted parties
+    pub security_level: SecurityLevel,
 }
 
 impl<N: Rep3Network> IoContext<N> {
@@ -91,14 +105,62 @@ impl<N: Rep3Network> IoContext<N> {
             rngs,
             rng,
             a2b_type: A2BType::default(),
+            a2b_auto_network: A2BAutoNetwork::default(),
+            security_level: SecurityLevel::default(),
         })
     }
 
+    /// Construct a new [`IoContext`] with the given network, whose correlated randomness was already set up in an offline phase (see [`Rep3Preprocessing`](super::preprocessing::Rep3Preprocessing)). In contrast to [`Self::init`], this requires no interaction.
+    pub fn init_with_seeds(network: N, seeds: &Rep3CorrelatedRngSeeds) -> Self {
+        Self {
+            id: network.get_id(), //shorthand access
+            network,
+            rngs: Rep3CorrelatedRng::from_seeds(seeds),
+            rng: RngType::from_entropy(),
+            a2b_type: A2BType::default(),
+            a2b_auto_network: A2BAutoNetwork::default(),
+            security_level: SecurityLevel::default(),
+        }
+    }
+
+    /// Construct a new [`IoContext`] with the given network for the session with the given ID, whose correlated randomness is derived from the persisted `state` without any interaction. The state is ratcheted forward, so it has to be persisted again afterwards (see [`Rep3PrssState`]).
+    pub fn init_session(network: N, state: &mut Rep3PrssState, session_id: &str) -> IoResult<Self> {
+        if network.get_id() != state.party_id() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "PRSS state belongs to a different party",
+            ));
+        }
+        let seeds = state.next_session(session_id);
+        Ok(Self::init_with_seeds(network, &seeds))
+    }
+
+    /// Ratchets and reseeds the rngs of the party, domain separated by `job_id` (see [`Rep3CorrelatedRng::ratchet`]). Long-lived parties should call this after each job and periodically in between, such that a compromise of the memory of the party does not reveal the randomness of earlier jobs. All parties have to call this at the same point of the protocol. Forks created before are not affected.
+    pub fn ratchet(&mut self, job_id: &str) {
+        self.rngs.ratchet(job_id.as_bytes());
+        self.rng = RngType::from_entropy();
+    }
+
+    /// Allows to change the security level against corrupted parties, see [`SecurityLevel`]
+    pub fn set_security_level(&mut self, security_level: SecurityLevel) {
+        self.security_level = security_level;
+    }
+
     /// Allows to change the used arithmetic/binary conversion protocol
     pub fn set_a2b_type(&mut self, a2b_type: A2BType) {
         self.a2b_type = a2b_type;
     }
 
+    /// Allows to change the network assumed when the conversion protocol is chosen automatically, see [`A2BType::Auto`]
+    pub fn set_a2b_auto_network(&mut self, network: A2BAutoNetwork) {
+        self.a2b_auto_network = network;
+    }
+
+    /// Returns the arithmetic/binary conversion protocol used for a batch of `num` values with `bitlen` bits (see [`A2BType::select`]).
+    pub fn select_a2b_type(&self, num: usize, bitlen: usize) -> A2BType {
+        self.a2b_type.select(num, bitlen, &self.a2b_auto_network)
+    }
+
     /// Cronstruct a fork of the [`IoContext`]. This fork can be used concurrently with its parent.
     pub fn fork(&mut self) -> IoResult<Self> {
         let network = self.network.fork()?;
@@ -106,6 +168,8 @@ impl<N: Rep3Network> IoContext<N> {
         let rng = RngType::from_seed(self.rng.gen());
         let id = self.id;
         let a2b_type = self.a2b_type;
+        let a2b_auto_network = self.a2b_auto_network;
+        let security_level = self.security_level;
 
         Ok(Self {
             id,
@@ -113,9 +177,49 @@ impl<N: Rep3Network> IoContext<N> {
             network,
             rng,
             a2b_type,
+            a2b_auto_network,
+            security_level,
         })
     }
 
+    /// Construct a fork of the [`IoContext`] in which the garbled circuits are executed with the given role assignment (see [`GCRoles`]). The id of the fork is the role of the party. Since the correlated randomness for the bit compositions depends on the id, it is set up again, which requires one round of communication.
+    pub fn fork_with_gc_roles(&mut self, roles: GCRoles) -> IoResult<IoContext<GCRoleNetwork<N>>> {
+        let mut network = GCRoleNetwork::new(self.network.fork()?, roles);
+        let mut rand = self.rngs.rand.fork();
+        let bitcomps = IoContext::setup_bitcomp(&mut network, &mut rand)?;
+        let rngs = Rep3CorrelatedRng::new(rand, bitcomps.0, bitcomps.1);
+
+        Ok(IoContext {
+            id: network.get_id(), //shorthand access
+            network,
+            rngs,
+            rng: RngType::from_seed(self.rng.gen()),
+            a2b_type: self.a2b_type,
+            a2b_auto_network: self.a2b_auto_network,
+            security_level: self.security_level,
+        })
+    }
+
+    /// Starts the given protocol phase with the timeout of the phase (see [`Rep3Network::start_phase`]).
+    pub fn start_phase(&mut self, phase: Phase) -> IoResult<()> {
+        self.network.start_phase(phase)
+    }
+
+    /// Ends the current protocol phase, such that its timeout no longer applies.
+    pub fn end_phase(&mut self) {
+        self.network.end_phase()
+    }
+
+    /// Returns a snapshot of the communication statistics of the party (see [`Rep3Network::network_stats`]).
+    pub fn network_stats(&self) -> NetworkStats {
+        self.network.network_stats()
+    }
+
+    /// Aborts the protocol on all parties with the given reason (see [`Rep3Network::abort`]).
+    pub fn abort(&mut self, reason: AbortReason) {
+        self.network.abort(reason)
+    }
+
     /// Generate two random elements
     pub fn random_elements<T>(&mut self) -> (T, T)
     where
@@ -239,10 +343,80 @@ pub trait Rep3Network: Send {
         self.recv_many(self.get_id().prev_id())
     }
 
+    /// Sends `data` to the other two parties, which receive it with [`Self::recv_broadcast`]. This function has a default implementation for calling [Rep3Network::send_broadcast_many].
+    fn send_broadcast<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: F,
+    ) -> std::io::Result<()> {
+        self.send_broadcast_many(&[data])
+    }
+
+    /// Sends `data` to the other two parties, which receive it with [`Self::recv_broadcast_many`]. Afterwards, all parties exchange a hash of the data and fail with [`std::io::ErrorKind::InvalidData`] if the hashes differ, such that a corrupted sender cannot send different data to the two other parties. This costs one additional round.
+    fn send_broadcast_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        let id = self.get_id();
+        self.send_many(id.next_id(), data)?;
+        self.send_many(id.prev_id(), data)?;
+        let digest = broadcast::digest(id.into(), data)?;
+        let (prev, next) = self.broadcast(digest.clone())?;
+        if prev != digest || next != digest {
+            return Err(broadcast::inconsistent(id.into()));
+        }
+        Ok(())
+    }
+
+    /// Receives data the party with the given id sent with [`Self::send_broadcast`]. This function has a default implementation for calling [Rep3Network::recv_broadcast_many] and checking for the correct length of 1.
+    fn recv_broadcast<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        from: PartyID,
+    ) -> std::io::Result<F> {
+        let mut res = self.recv_broadcast_many(from)?;
+        if res.len() != 1 {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Expected 1 element, got more",
+            ))
+        } else {
+            Ok(res.pop().unwrap())
+        }
+    }
+
+    /// Receives data the party with the given id sent with [`Self::send_broadcast_many`] and checks that the other receiver got the same data.
+    fn recv_broadcast_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        from: PartyID,
+    ) -> std::io::Result<Vec<F>> {
+        let data = self.recv_many(from)?;
+        let digest = broadcast::digest(from.into(), &data)?;
+        let (prev, next) = self.broadcast(digest.clone())?;
+        if prev != digest || next != digest {
+            return Err(broadcast::inconsistent(from.into()));
+        }
+        Ok(data)
+    }
+
     /// Fork the network into two separate instances with their own connections
     fn fork(&mut self) -> std::io::Result<Self>
     where
         Self: Sized;
+
+    /// Starts the given protocol phase. The parties agree on the smallest configured timeout of the phase, after which all receives fail with [`std::io::ErrorKind::TimedOut`], such that all parties abort at the same time instead of waiting forever. All parties have to call this at the same point of the protocol. The default implementation does not support timeouts.
+    fn start_phase(&mut self, _phase: Phase) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    /// Ends the current protocol phase, such that its timeout no longer applies.
+    fn end_phase(&mut self) {}
+
+    /// Returns a snapshot of the communication statistics of the party, which are shared by all forks of the network. The default implementation does not record any statistics.
+    fn network_stats(&self) -> NetworkStats {
+        NetworkStats::default()
+    }
+
+    /// Aborts the protocol with the given reason, such that the pending and future receives of all parties fail with the [`Abort`](mpc_net::abort::Abort) instead of waiting for messages which will never arrive. The default implementation does not propagate aborts.
+    fn abort(&mut self, _reason: AbortReason) {}
 }
 
 // TODO make generic over codec?
@@ -267,7 +441,7 @@ impl Rep3MpcNet {
             .build()?;
         let (net_handler, chan_next, chan_prev) = runtime.block_on(async {
             let net_handler = MpcNetworkHandler::establish(config).await?;
-            let mut channels = net_handler.get_byte_channels().await?;
+            let mut channels = net_handler.get_managed_byte_channels().await?;
             let chan_next = channels
                 .remove(&id.next_id().into())
                 .ok_or(eyre!("no next channel found"))?;
@@ -278,8 +452,6 @@ impl Rep3MpcNet {
                 bail!("unexpected channels found")
             }
 
-            let chan_next = ChannelHandle::manage(chan_next);
-            let chan_prev = ChannelHandle::manage(chan_prev);
             Ok((net_handler, chan_next, chan_prev))
         })?;
         Ok(Self {
@@ -290,37 +462,77 @@ impl Rep3MpcNet {
         })
     }
 
+    /// Returns the transcript of all messages sent and received by all forks of the network so far, signed with the private key of the party. Fails if the transcript is not recorded (see [`NetworkConfig::transcript`]).
+    pub fn sign_transcript(&self) -> Result<SignedTranscript, Report> {
+        self.net_handler.inner.sign_transcript()
+    }
+
     /// Sends bytes over the network to the target party.
     pub fn send_bytes(&mut self, target: PartyID, data: Bytes) -> std::io::Result<()> {
+        let len = data.len();
         if target == self.id.next_id() {
             std::mem::drop(self.chan_next.blocking_send(data));
-            Ok(())
         } else if target == self.id.prev_id() {
             std::mem::drop(self.chan_prev.blocking_send(data));
-            Ok(())
         } else {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 "Cannot send to self",
             ));
         }
+        self.net_handler
+            .inner
+            .stats()
+            .record_sent(target.into(), len);
+        Ok(())
     }
 
-    /// Receives bytes over the network from the party with the given id.
+    /// Receives bytes over the network from the party with the given id. Fails with [`std::io::ErrorKind::TimedOut`] if the message does not arrive in time (see [`MpcNetworkHandler::recv_timeout`]), and with the [`Abort`](mpc_net::abort::Abort) if a party aborted the protocol.
     pub fn recv_bytes(&mut self, from: PartyID) -> std::io::Result<BytesMut> {
-        let data = if from == self.id.prev_id() {
-            self.chan_prev.blocking_recv().blocking_recv()
+        let recv = if from == self.id.prev_id() {
+            self.chan_prev.blocking_recv()
         } else if from == self.id.next_id() {
-            self.chan_next.blocking_recv().blocking_recv()
+            self.chan_next.blocking_recv()
         } else {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 "Cannot recv from self",
             ));
         };
+        let start = Instant::now();
+        let handler = &self.net_handler.inner;
+        let data = self.net_handler.runtime.block_on(async {
+            let recv = async {
+                match handler.recv_timeout() {
+                    Some(timeout) => tokio::time::timeout(timeout, recv).await.map_err(|_| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::TimedOut,
+                            format!("no message from party {} in time", from),
+                        )
+                    }),
+                    None => Ok(recv.await),
+                }
+            };
+            // an abort takes precedence over the message, such that all parties unwind
+            tokio::select! {
+                biased;
+                abort = handler.wait_for_abort() => Err(abort.into()),
+                data = recv => {
+                    if data.is_err() {
+                        // let the other parties fail right away instead of after their own timeout
+                        let _ = handler.abort(AbortReason::Timeout).await;
+                    }
+                    data
+                }
+            }
+        })?;
         let data = data.map_err(|_| {
             std::io::Error::new(std::io::ErrorKind::BrokenPipe, "receive channel end died")
         })??;
+        self.net_handler
+            .inner
+            .stats()
+            .record_received(from.into(), data.len(), start.elapsed());
         Ok(data)
     }
 }
@@ -374,7 +586,7 @@ impl Rep3Network for Rep3MpcNet {
         let id = self.id;
         let net_handler = Arc::clone(&self.net_handler);
         let (chan_next, chan_prev) = net_handler.runtime.block_on(async {
-            let mut channels = net_handler.inner.get_byte_channels().await?;
+            let mut channels = net_handler.inner.get_managed_byte_channels().await?;
 
             let chan_next = channels
                 .remove(&id.next_id().into())
@@ -386,8 +598,6 @@ impl Rep3Network for Rep3MpcNet {
                 panic!("unexpected channels found")
             }
 
-            let chan_next = ChannelHandle::manage(chan_next);
-            let chan_prev = ChannelHandle::manage(chan_prev);
             Ok::<_, std::io::Error>((chan_next, chan_prev))
         })?;
 
@@ -398,4 +608,38 @@ impl Rep3Network for Rep3MpcNet {
             chan_prev,
         })
     }
+
+    fn start_phase(&mut self, phase: Phase) -> std::io::Result<()> {
+        self.net_handler.inner.stats().set_phase(phase.name());
+        // agree on the smallest timeout, such that all parties abort at the same time
+        let timeout = self
+            .net_handler
+            .inner
+            .timeouts()
+            .phase(phase)
+            .map_or(u64::MAX, |timeout| timeout.as_secs());
+        let (prev, next) = self.broadcast(timeout)?;
+        let timeout = timeout.min(prev).min(next);
+        self.net_handler.inner.set_deadline(
+            (timeout != u64::MAX).then(|| Instant::now() + Duration::from_secs(timeout)),
+        );
+        Ok(())
+    }
+
+    fn end_phase(&mut self) {
+        self.net_handler.inner.set_deadline(None);
+        self.net_handler.inner.stats().set_phase(DEFAULT_PHASE);
+    }
+
+    fn network_stats(&self) -> NetworkStats {
+        self.net_handler.inner.stats().snapshot()
+    }
+
+    fn abort(&mut self, reason: AbortReason) {
+        // the abort is recorded even if it cannot be sent to all parties
+        let _ = self
+            .net_handler
+            .runtime
+            .block_on(self.net_handler.inner.abort(reason));
+    }
 }
@@ -12,7 +12,9 @@ use rayon::prelude::*;
 pub use types::Rep3PointShare;
 
 use super::{
+    arithmetic,
     id::PartyID,
+    malicious::{self, SecurityLevel},
     network::{IoContext, Rep3Network},
     IoResult, Rep3PrimeFieldShare,
 };
@@ -87,10 +89,19 @@ pub fn scalar_mul<C: CurveGroup, N: Rep3Network>(
 ) -> IoResult<PointShare<C>> {
     let local_a = b * a + io_context.rngs.rand.masking_ec_element::<C>();
     let local_b = io_context.network.reshare(local_a)?;
-    Ok(PointShare {
+    let res = PointShare {
         a: local_a,
         b: local_b,
-    })
+    };
+    if io_context.security_level == SecurityLevel::Malicious {
+        malicious::verify_scalar_mul_points(
+            &[b],
+            std::slice::from_ref(a),
+            std::slice::from_ref(&res),
+            io_context,
+        )?;
+    }
+    Ok(res)
 }
 
 /// Open the shared point
@@ -98,6 +109,9 @@ pub fn open_point<C: CurveGroup, N: Rep3Network>(
     a: &PointShare<C>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<C> {
+    if io_context.security_level == SecurityLevel::Malicious {
+        return Ok(open_point_many_checked(std::slice::from_ref(a), io_context)?[0]);
+    }
     let c = io_context.network.reshare(a.b)?;
     Ok(a.a + a.b + c)
 }
@@ -107,11 +121,48 @@ pub fn open_point_many<C: CurveGroup, N: Rep3Network>(
     a: &[PointShare<C>],
     io_context: &mut IoContext<N>,
 ) -> IoResult<Vec<C>> {
+    if io_context.security_level == SecurityLevel::Malicious {
+        return open_point_many_checked(a, io_context);
+    }
     let bs = a.iter().map(|x| x.b).collect_vec();
     let cs = io_context.network.reshare(bs)?;
     Ok(izip!(a, cs).map(|(x, c)| x.a + x.b + c).collect_vec())
 }
 
+/// Opens the vector of [`Rep3PointShare`]s in one round and checks the consistency of the received values, analogous to
+/// [`arithmetic::open_many_checked`]. Returns an error if the two neighbors sent different values for the missing share.
+pub fn open_point_many_checked<C: CurveGroup, N: Rep3Network>(
+    a: &[PointShare<C>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<C>> {
+    let (a, b) = a
+        .iter()
+        .map(|share| (share.a, share.b))
+        .collect::<(Vec<C>, Vec<C>)>();
+    let next_id = io_context.id.next_id();
+    let prev_id = io_context.id.prev_id();
+    // our next party misses our a, our previous party misses our b
+    io_context.network.send_many(prev_id, &a)?;
+    io_context.network.send_many(next_id, &b)?;
+    let from_next = io_context.network.recv_many::<C>(next_id)?;
+    let from_prev = io_context.network.recv_many::<C>(prev_id)?;
+    if from_next.len() != a.len() || from_prev.len() != a.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "received wrong number of point shares while opening",
+        ));
+    }
+    if from_next != from_prev {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "inconsistent point shares during opening, a party cheated",
+        ));
+    }
+    Ok(izip!(a, b, from_next)
+        .map(|(a, b, c)| a + b + c)
+        .collect_vec())
+}
+
 /// Perform msm between `points` and `scalars`
 pub fn msm_public_points<C: CurveGroup>(
     points: &[C::Affine],
@@ -133,3 +184,33 @@ pub fn msm_public_points<C: CurveGroup>(
     //we can unwrap as the we have Some values after rayon scope
     PointShare::new(res_a.unwrap(), res_b.unwrap())
 }
+
+/// Checks whether the shared `values` together with the shared `randomness` open the public Pedersen commitment
+/// `commitment` w.r.t. the generators `generators` and `h`. The recomputed commitment C' is never revealed. Instead,
+/// the parties open rho * (C' - C) for a jointly sampled random rho, which is the identity if the commitments match
+/// and a uniformly random point otherwise.
+pub fn verify_pedersen_commitment<C: CurveGroup, N: Rep3Network>(
+    values: &[FieldShare<C::ScalarField>],
+    randomness: FieldShare<C::ScalarField>,
+    generators: &[C::Affine],
+    h: &C::Affine,
+    commitment: &C,
+    io_context: &mut IoContext<N>,
+) -> IoResult<bool> {
+    if values.len() != generators.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "number of values and generators differ",
+        ));
+    }
+    let mut shared_commitment = msm_public_points::<C>(generators, values);
+    add_assign(
+        &mut shared_commitment,
+        &scalar_mul_public_point(&C::from(*h), randomness),
+    );
+    sub_assign_public(&mut shared_commitment, commitment, io_context.id);
+    let rho = arithmetic::rand(io_context);
+    let masked = scalar_mul(&shared_commitment, rho, io_context)?;
+    let opened = open_point(&masked, io_context)?;
+    Ok(opened.is_zero())
+}
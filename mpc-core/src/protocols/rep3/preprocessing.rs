@@ -0,0 +1,274 @@
+//! Preprocessing
+//!
+//! This module contains the generation of correlated randomness for the rep3 protocol which is independent of the actual inputs, i.e., daBits and edaBits (see [Escudero et al.](https://eprint.iacr.org/2020/338.pdf)). A daBit is a random bit which is shared in the arithmetic and in the binary domain. An edaBit is a random value of a fixed bit size which is shared in the arithmetic domain, together with a binary sharing of the same value. Both can be generated in batches ahead of time, persisted in a [`Rep3PreprocessedBits`] store, and are then consumed by conversions and comparisons to reduce their number of communication rounds.
+//!
+//! Furthermore, it contains an explicit offline phase for a session (see [`Rep3Preprocessing`]), which additionally sets up the correlated randomness for the online phase, such that the online phase does not require any interactive setup. Multiplications in rep3 do not require preprocessed triples, since their masks are derived from the correlated randomness. Garbled circuits are still garbled in the online phase.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use num_bigint::BigUint;
+
+use super::{
+    conversion,
+    id::PartyID,
+    network::{IoContext, Rep3Network},
+    rngs::Rep3CorrelatedRngSeeds,
+    IoResult, Rep3BigUintShare, Rep3PrimeFieldShare,
+};
+
+/// A daBit, i.e., a random bit which is shared in the arithmetic and in the binary domain.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Rep3DaBit<F: PrimeField> {
+    /// The arithmetic sharing of the bit
+    pub arithmetic: Rep3PrimeFieldShare<F>,
+    /// The binary sharing of the bit
+    pub binary: Rep3BigUintShare<F>,
+}
+
+/// An edaBit, i.e., a random value in [0, 2^bitsize) which is shared in the arithmetic domain, together with a binary sharing of the same value.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Rep3EdaBit<F: PrimeField> {
+    /// The arithmetic sharing of the value
+    pub arithmetic: Rep3PrimeFieldShare<F>,
+    /// The binary sharing of the value
+    pub binary: Rep3BigUintShare<F>,
+}
+
+/// Generates `amount` daBits. The bits are sampled in the binary domain from the correlated randomness and are then injected into the arithmetic domain. All daBits are generated at once, so the number of communication rounds does not depend on `amount`.
+pub fn generate_dabits<F: PrimeField, N: Rep3Network>(
+    amount: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3DaBit<F>>> {
+    if amount == 0 {
+        return Ok(Vec::new());
+    }
+    let (a, b) = io_context.rngs.rand.random_biguint(amount);
+    let binary = (0..amount as u64)
+        .map(|i| {
+            Rep3BigUintShare::new(
+                BigUint::from(u8::from(a.bit(i))),
+                BigUint::from(u8::from(b.bit(i))),
+            )
+        })
+        .collect::<Vec<_>>();
+    let arithmetic = conversion::bit_inject_many(&binary, io_context)?;
+    Ok(arithmetic
+        .into_iter()
+        .zip(binary)
+        .map(|(arithmetic, binary)| Rep3DaBit { arithmetic, binary })
+        .collect())
+}
+
+/// Generates `amount` edaBits of the given bit size. Each edaBit is composed from `bitsize` daBits, which are all generated at once (see [`generate_dabits`]).
+pub fn generate_edabits<F: PrimeField, N: Rep3Network>(
+    amount: usize,
+    bitsize: usize,
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3EdaBit<F>>> {
+    assert!(
+        bitsize > 0 && bitsize < F::MODULUS_BIT_SIZE as usize,
+        "bitsize must be in [1, {})",
+        F::MODULUS_BIT_SIZE
+    );
+    let dabits = generate_dabits::<F, N>(amount * bitsize, io_context)?;
+    Ok(dabits.chunks_exact(bitsize).map(compose_dabits).collect())
+}
+
+/// Composes the daBits, least significant bit first, into an edaBit.
+fn compose_dabits<F: PrimeField>(dabits: &[Rep3DaBit<F>]) -> Rep3EdaBit<F> {
+    let mut arithmetic = Rep3PrimeFieldShare::zero_share();
+    let mut binary = Rep3BigUintShare::zero_share();
+    let mut pow = F::one();
+    for (i, dabit) in dabits.iter().enumerate() {
+        arithmetic += dabit.arithmetic * pow;
+        binary ^= &dabit.binary << i;
+        pow.double_in_place();
+    }
+    Rep3EdaBit { arithmetic, binary }
+}
+
+/// A store of preprocessed daBits and edaBits. The store can be filled ahead of time and persisted using [`CanonicalSerialize`]. Since the stored values are shares, all parties have to generate and consume the same amounts in the same order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Rep3PreprocessedBits<F: PrimeField> {
+    dabits: Vec<Rep3DaBit<F>>,
+    edabits: BTreeMap<u64, Vec<Rep3EdaBit<F>>>,
+}
+
+impl<F: PrimeField> Rep3PreprocessedBits<F> {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of stored daBits.
+    pub fn num_dabits(&self) -> usize {
+        self.dabits.len()
+    }
+
+    /// Returns the number of stored edaBits of the given bit size.
+    pub fn num_edabits(&self, bitsize: usize) -> usize {
+        self.edabits
+            .get(&(bitsize as u64))
+            .map_or(0, |edabits| edabits.len())
+    }
+
+    /// Generates `amount` daBits and adds them to the store. See [`generate_dabits`] for details.
+    pub fn generate_dabits<N: Rep3Network>(
+        &mut self,
+        amount: usize,
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<()> {
+        let dabits = generate_dabits(amount, io_context)?;
+        self.dabits.extend(dabits);
+        Ok(())
+    }
+
+    /// Generates `amount` edaBits of the given bit size and adds them to the store. See [`generate_edabits`] for details.
+    pub fn generate_edabits<N: Rep3Network>(
+        &mut self,
+        amount: usize,
+        bitsize: usize,
+        io_context: &mut IoContext<N>,
+    ) -> IoResult<()> {
+        let edabits = generate_edabits(amount, bitsize, io_context)?;
+        self.edabits
+            .entry(bitsize as u64)
+            .or_default()
+            .extend(edabits);
+        Ok(())
+    }
+
+    /// Removes `amount` daBits from the store and returns them. Fails if the store does not contain enough daBits.
+    pub fn take_dabits(&mut self, amount: usize) -> IoResult<Vec<Rep3DaBit<F>>> {
+        let len = self.dabits.len();
+        if len < amount {
+            return Err(std::io::Error::other(format!(
+                "Not enough preprocessed daBits: requested {amount}, but only {len} left"
+            )));
+        }
+        Ok(self.dabits.split_off(len - amount))
+    }
+
+    /// Removes `amount` edaBits of the given bit size from the store and returns them. Fails if the store does not contain enough edaBits of this size.
+    pub fn take_edabits(&mut self, amount: usize, bitsize: usize) -> IoResult<Vec<Rep3EdaBit<F>>> {
+        if amount == 0 {
+            return Ok(Vec::new());
+        }
+        let len = self.num_edabits(bitsize);
+        if len < amount {
+            return Err(std::io::Error::other(format!(
+                "Not enough preprocessed edaBits of size {bitsize}: requested {amount}, but only {len} left"
+            )));
+        }
+        let edabits = self
+            .edabits
+            .get_mut(&(bitsize as u64))
+            .expect("edaBits of this size are present");
+        Ok(edabits.split_off(len - amount))
+    }
+}
+
+/// The result of the offline phase of a party for one session: the seeds for the correlated randomness of the online phase and the preprocessed daBits and edaBits. The material can be stored on disk keyed by a session ID, and is consumed by the online phase using [`Self::into_online`].
+///
+/// The material contains the secret PRF keys of the party, so it has to be stored as securely as the shares of the inputs. Furthermore, the material of a session must only be used once.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Rep3Preprocessing<F: PrimeField> {
+    party_id: u8,
+    seeds: Rep3CorrelatedRngSeeds,
+    bits: Rep3PreprocessedBits<F>,
+}
+
+impl<F: PrimeField> Rep3Preprocessing<F> {
+    /// Runs the offline phase using an interactively set up [`IoContext`]: Generates `num_dabits` daBits and, for each `(amount, bitsize)` in `edabits`, `amount` edaBits of size `bitsize`. Afterwards, fresh seeds for the correlated randomness of the online phase are derived, which are independent of the randomness used in the offline phase.
+    pub fn generate<N: Rep3Network>(
+        io_context: &mut IoContext<N>,
+        num_dabits: usize,
+        edabits: &[(usize, usize)],
+    ) -> IoResult<Self> {
+        tracing::debug!("generating {num_dabits} daBits and edaBits {edabits:?}");
+        let mut bits = Rep3PreprocessedBits::new();
+        bits.generate_dabits(num_dabits, io_context)?;
+        for (amount, bitsize) in edabits {
+            bits.generate_edabits(*amount, *bitsize, io_context)?;
+        }
+        Ok(Self {
+            party_id: usize::from(io_context.id) as u8,
+            seeds: io_context.rngs.fork_seeds(),
+            bits,
+        })
+    }
+
+    /// Returns the preprocessed daBits and edaBits.
+    pub fn bits(&self) -> &Rep3PreprocessedBits<F> {
+        &self.bits
+    }
+
+    /// Returns the path of the file storing the material of the given party for the given session in `dir`.
+    pub fn path(dir: impl AsRef<Path>, session_id: &str, party_id: PartyID) -> PathBuf {
+        dir.as_ref()
+            .join(format!("{session_id}.party{party_id}.preprocessing"))
+    }
+
+    /// Writes the material to the directory `dir`, keyed by the session ID. See [`Self::path`].
+    pub fn save(&self, dir: impl AsRef<Path>, session_id: &str) -> IoResult<PathBuf> {
+        check_session_id(session_id)?;
+        let party_id = PartyID::try_from(self.party_id).expect("valid party id");
+        let path = Self::path(dir, session_id, party_id);
+        let mut writer = BufWriter::new(File::create(&path)?);
+        self.serialize_uncompressed(&mut writer)
+            .map_err(std::io::Error::other)?;
+        Ok(path)
+    }
+
+    /// Reads the material of the given party for the given session from the directory `dir`. See [`Self::path`].
+    pub fn load(dir: impl AsRef<Path>, session_id: &str, party_id: PartyID) -> IoResult<Self> {
+        check_session_id(session_id)?;
+        let path = Self::path(dir, session_id, party_id);
+        let reader = BufReader::new(File::open(path)?);
+        let preprocessing =
+            Self::deserialize_uncompressed(reader).map_err(std::io::Error::other)?;
+        if preprocessing.party_id != usize::from(party_id) as u8 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Preprocessing material belongs to a different party",
+            ));
+        }
+        Ok(preprocessing)
+    }
+
+    /// Starts the online phase with the given network: Sets up an [`IoContext`] from the preprocessed seeds without any interaction, and returns it together with the preprocessed daBits and edaBits.
+    pub fn into_online<N: Rep3Network>(
+        self,
+        network: N,
+    ) -> IoResult<(IoContext<N>, Rep3PreprocessedBits<F>)> {
+        if usize::from(network.get_id()) != usize::from(self.party_id) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Preprocessing material belongs to a different party",
+            ));
+        }
+        Ok((IoContext::init_with_seeds(network, &self.seeds), self.bits))
+    }
+}
+
+/// Session IDs are used in file names, so we only allow a safe subset of characters.
+fn check_session_id(session_id: &str) -> IoResult<()> {
+    if session_id.is_empty()
+        || !session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid session ID {session_id:?}, only [a-zA-Z0-9_-] are allowed"),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! Result Receiver
+//!
+//! This module contains the types to reconstruct outputs towards an external result receiver. The receiver is not
+//! one of the three computing parties and supplies a public key. Each party encrypts its additive share under this key,
+//! so that only the receiver can reconstruct the outputs.
+
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha3::{Digest, Sha3_512};
+
+use super::id::PartyID;
+
+const OUTPUT_MASK_DOMAIN: &[u8] = b"co-snarks rep3 result receiver";
+
+/// The additive shares of a party, encrypted under the public key of a result receiver.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct EncryptedOutputShares<C: CurveGroup, F: PrimeField> {
+    /// The id of the party that encrypted the shares.
+    pub party_id: u8,
+    /// The ephemeral public key of the encryption.
+    pub ephemeral: C,
+    /// The masked additive shares.
+    pub values: Vec<F>,
+}
+
+impl<C: CurveGroup, F: PrimeField> EncryptedOutputShares<C, F> {
+    /// Returns the [PartyID] of the party that encrypted the shares.
+    pub fn party_id(&self) -> eyre::Result<PartyID> {
+        Ok(PartyID::try_from(usize::from(self.party_id))?)
+    }
+}
+
+/// Derives the mask for the value at position `index` from the Diffie-Hellman key shared between a party and
+/// the result receiver.
+pub fn output_mask<C: CurveGroup, F: PrimeField>(shared_key: &C, index: usize) -> F {
+    let mut key_bytes = Vec::with_capacity(shared_key.compressed_size());
+    shared_key
+        .into_affine()
+        .serialize_compressed(&mut key_bytes)
+        .expect("can serialize into vec");
+    let mut hasher = Sha3_512::new();
+    hasher.update(OUTPUT_MASK_DOMAIN);
+    hasher.update(&key_bytes);
+    hasher.update((index as u64).to_le_bytes());
+    F::from_le_bytes_mod_order(&hasher.finalize())
+}
@@ -5,13 +5,20 @@
 use super::{id::PartyID, yao::GCUtils};
 use crate::RngType;
 use ark_ec::CurveGroup;
-use ark_ff::{One, PrimeField};
+use ark_ff::{Field, One, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use fancy_garbling::WireMod2;
 use num_bigint::BigUint;
 use rand::{
     distributions::Standard, prelude::Distribution, seq::SliceRandom, Rng, RngCore, SeedableRng,
 };
 use rayon::prelude::*;
+use sha3::{Digest, Sha3_256};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
 
 #[derive(Debug)]
 /// A correlated rng for rep3
@@ -43,6 +50,31 @@ impl Rep3CorrelatedRng {
         }
     }
 
+    /// Derives the seeds of a fresh [`Rep3CorrelatedRng`], which is independent of this one. Like [`Self::fork`], this is consistent among the parties without any interaction, so the seeds can be persisted and used to set up the correlated randomness of a later session using [`Self::from_seeds`].
+    pub fn fork_seeds(&mut self) -> Rep3CorrelatedRngSeeds {
+        Rep3CorrelatedRngSeeds {
+            rand: self.rand.random_seeds(),
+            bitcomp1: self.bitcomp1.random_seeds(),
+            bitcomp2: self.bitcomp2.random_seeds(),
+        }
+    }
+
+    /// Construct a new [`Rep3CorrelatedRng`] from seeds created by [`Self::fork_seeds`]
+    pub fn from_seeds(seeds: &Rep3CorrelatedRngSeeds) -> Self {
+        let (seed1, seed2) = seeds.rand;
+        Self {
+            rand: Rep3Rand::new(seed1, seed2),
+            bitcomp1: Rep3RandBitComp::from_seeds(seeds.bitcomp1),
+            bitcomp2: Rep3RandBitComp::from_seeds(seeds.bitcomp2),
+        }
+    }
+
+    /// Ratchets the correlated randomness forward: The rngs are replaced by new ones, whose seeds are derived from the output of the current rngs with a one-way function domain separated by `job_id`. Thus, a compromise of the new state does not reveal the randomness generated before. Like [`Self::fork`], this is consistent among the parties without any interaction, so all parties have to ratchet at the same point of the protocol.
+    pub fn ratchet(&mut self, job_id: &[u8]) {
+        let seeds = self.fork_seeds().derive(b"rep3 rng ratchet", job_id);
+        *self = Self::from_seeds(&seeds);
+    }
+
     /// Generate a value that is equal on all three parties
     pub fn generate_shared<T>(&mut self, id: PartyID) -> T
     where
@@ -77,6 +109,105 @@ impl Rep3CorrelatedRng {
     }
 }
 
+type BitCompSeeds = (
+    [u8; crate::SEED_SIZE],
+    [u8; crate::SEED_SIZE],
+    Option<[u8; crate::SEED_SIZE]>,
+);
+
+/// The seeds of a [`Rep3CorrelatedRng`], see [`Rep3CorrelatedRng::fork_seeds`]. Since they are the keys of the PRFs, they have to be kept secret.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Rep3CorrelatedRngSeeds {
+    rand: ([u8; crate::SEED_SIZE], [u8; crate::SEED_SIZE]),
+    bitcomp1: BitCompSeeds,
+    bitcomp2: BitCompSeeds,
+}
+
+impl Rep3CorrelatedRngSeeds {
+    /// Derives new seeds by hashing each seed together with the domain separation `label` and `data`. Since all parties hash the seeds they share in the same way, the derived seeds are consistent among the parties.
+    fn derive(&self, label: &[u8], data: &[u8]) -> Self {
+        let derive = |seed: &[u8; crate::SEED_SIZE]| {
+            let mut hasher = Sha3_256::new();
+            hasher.update(label);
+            hasher.update((data.len() as u64).to_le_bytes());
+            hasher.update(data);
+            hasher.update(seed);
+            let mut derived = [0u8; crate::SEED_SIZE];
+            derived.copy_from_slice(&hasher.finalize());
+            derived
+        };
+        let derive_bitcomp = |(seed1, seed2, seed3): &BitCompSeeds| {
+            (derive(seed1), derive(seed2), seed3.as_ref().map(derive))
+        };
+        Self {
+            rand: (derive(&self.rand.0), derive(&self.rand.1)),
+            bitcomp1: derive_bitcomp(&self.bitcomp1),
+            bitcomp2: derive_bitcomp(&self.bitcomp2),
+        }
+    }
+}
+
+/// The persistent state of the correlated randomness setup of a party, which allows to resume sessions without redoing the interactive setup of [`IoContext::init`](super::network::IoContext::init).
+///
+/// The seeds of every session are derived from the state and the session ID, so different sessions use independent randomness. Afterwards, the state is ratcheted forward with a one-way function, such that a leaked state does not reveal the randomness of previous sessions. Thus, all parties have to start their sessions in the same order and the state has to be persisted again after every session. Since the state contains the keys of the PRFs, it has to be stored as securely as the shares of the inputs.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Rep3PrssState {
+    party_id: u8,
+    epoch: u64,
+    seeds: Rep3CorrelatedRngSeeds,
+}
+
+impl Rep3PrssState {
+    /// Creates the state from already set up correlated randomness, e.g., of an [`IoContext`](super::network::IoContext) created with [`IoContext::init`](super::network::IoContext::init). The seeds of the state are independent of the randomness used afterwards by `rngs`.
+    pub fn new(id: PartyID, rngs: &mut Rep3CorrelatedRng) -> Self {
+        Self {
+            party_id: usize::from(id) as u8,
+            epoch: 0,
+            seeds: rngs.fork_seeds(),
+        }
+    }
+
+    /// Returns the id of the party this state belongs to.
+    pub fn party_id(&self) -> PartyID {
+        PartyID::try_from(self.party_id).expect("valid party id")
+    }
+
+    /// Returns the number of sessions derived from this state so far.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Derives the seeds for the session with the given ID and ratchets the state forward.
+    pub fn next_session(&mut self, session_id: &str) -> Rep3CorrelatedRngSeeds {
+        let mut data = self.epoch.to_le_bytes().to_vec();
+        data.extend_from_slice(session_id.as_bytes());
+        let seeds = self.seeds.derive(b"rep3 prss session", &data);
+        self.seeds = self.seeds.derive(b"rep3 prss ratchet", &[]);
+        self.epoch += 1;
+        seeds
+    }
+
+    /// Writes the state to the file at `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.serialize_uncompressed(&mut writer)
+            .map_err(std::io::Error::other)
+    }
+
+    /// Reads the state of the given party from the file at `path`.
+    pub fn load(path: impl AsRef<Path>, party_id: PartyID) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let state = Self::deserialize_uncompressed(reader).map_err(std::io::Error::other)?;
+        if state.party_id != usize::from(party_id) as u8 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "PRSS state belongs to a different party",
+            ));
+        }
+        Ok(state)
+    }
+}
+
 #[derive(Debug)]
 /// Rep3 rng with this party's rng and the prev party's rng
 pub struct Rep3Rand {
@@ -99,13 +230,13 @@ impl Rep3Rand {
     }
 
     /// Generate a masking field element
-    pub fn masking_field_element<F: PrimeField>(&mut self) -> F {
+    pub fn masking_field_element<F: Field>(&mut self) -> F {
         let (a, b) = self.random_fes::<F>();
         a - b
     }
 
     /// Generate two random field elements
-    pub fn random_fes<F: PrimeField>(&mut self) -> (F, F) {
+    pub fn random_fes<F: Field>(&mut self) -> (F, F) {
         let a = F::rand(&mut self.rng1);
         let b = F::rand(&mut self.rng2);
         (a, b)
@@ -311,6 +442,21 @@ impl Rep3RandBitComp {
         (a, b, c)
     }
 
+    fn random_seeds(&mut self) -> BitCompSeeds {
+        let seed1 = self.rng1.gen();
+        let seed2 = self.rng2.gen();
+        let seed3 = self.rng3.as_mut().map(|rng| rng.gen());
+        (seed1, seed2, seed3)
+    }
+
+    fn from_seeds((seed1, seed2, seed3): BitCompSeeds) -> Self {
+        Self {
+            rng1: RngType::from_seed(seed1),
+            rng2: RngType::from_seed(seed2),
+            rng3: seed3.map(RngType::from_seed),
+        }
+    }
+
     /// Create a fork of this rng
     pub fn fork(&mut self) -> Self {
         let rng1 = RngType::from_seed(self.rng1.gen());
@@ -0,0 +1,83 @@
+//! Rep3 WebSocket Network
+//!
+//! This module contains a WebSocket-based transport for the rep3 mpc network, such that one of the parties (typically the data owner) can run inside of a browser and participate in the input sharing and lightweight protocol steps. Since browsers can only open outgoing connections, a party without an url in the [`WebSocketConfig`] connects to the other parties, whereas the parties with an url listen on their bind address.
+//!
+//! On native targets, [`Rep3WebSocketNet`] implements the blocking [`Rep3Network`](super::network::Rep3Network) using [tungstenite]. On wasm, [`Rep3BrowserRelay`] owns the connections using the WebSocket API of the browser and forwards the messages to [`Rep3BrowserNet`], which implements [`Rep3Network`](super::network::Rep3Network) in a Web Worker, since a browser cannot block on the main thread.
+
+use eyre::{bail, Report};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+use super::id::PartyID;
+
+#[cfg(target_arch = "wasm32")]
+mod browser;
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+
+#[cfg(target_arch = "wasm32")]
+pub use browser::{Rep3BrowserNet, Rep3BrowserRelay};
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::Rep3WebSocketNet;
+
+/// A party in the [`WebSocketConfig`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebSocketParty {
+    /// The id of the party.
+    pub id: usize,
+    /// The url under which the party accepts WebSocket connections, e.g., `ws://party1.example.com:10000`. Parties running in a browser cannot accept connections and have no url.
+    pub url: Option<String>,
+}
+
+/// The network configuration of the WebSocket transport.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebSocketConfig {
+    /// The id of this party.
+    pub my_id: usize,
+    /// The address this party listens on. Required if this party has an url.
+    pub bind_addr: Option<SocketAddr>,
+    /// All parties of the network, including this party.
+    pub parties: Vec<WebSocketParty>,
+}
+
+impl WebSocketConfig {
+    /// Checks the config and returns the id of this party. The network needs to contain exactly 3 parties with ids 0, 1, and 2.
+    pub(crate) fn check_config(&self) -> Result<PartyID, Report> {
+        if self.parties.len() != 3 {
+            bail!("REP3 protocol requires exactly 3 parties")
+        }
+        for id in 0..3 {
+            if !self.parties.iter().any(|party| party.id == id) {
+                bail!("no party with id={} found", id)
+            }
+        }
+        let id = PartyID::try_from(self.my_id)?;
+        if self.url(id).is_some() && self.bind_addr.is_none() {
+            bail!("a party with an url requires a bind address")
+        }
+        for other in [id.next_id(), id.prev_id()] {
+            if self.url(id).is_none() && self.url(other).is_none() {
+                bail!(
+                    "parties {} and {} cannot connect, as neither of them has an url",
+                    id,
+                    other
+                )
+            }
+        }
+        Ok(id)
+    }
+
+    /// Returns the url of the party with the given id.
+    pub(crate) fn url(&self, id: PartyID) -> Option<&str> {
+        self.parties
+            .iter()
+            .find(|party| party.id == usize::from(id))
+            .and_then(|party| party.url.as_deref())
+    }
+
+    /// Returns whether the party `from` opens the connection to the party `to`. A party connects to all parties with an url if it has no url itself, otherwise only to the ones with a larger id. This order ensures that establishing the connections never deadlocks.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn connects_to(&self, from: PartyID, to: PartyID) -> bool {
+        self.url(to).is_some() && (self.url(from).is_none() || usize::from(from) < usize::from(to))
+    }
+}
@@ -0,0 +1,596 @@
+//! The browser WebSocket transport using [web_sys].
+//!
+//! A browser cannot block while it waits for the WebSocket events, so the transport is split in two halves. The [`Rep3BrowserRelay`] owns the WebSocket connections in an async context, typically the main thread, and forwards the messages through mailboxes in [`SharedArrayBuffer`]s. The [`Rep3BrowserNet`] runs in a dedicated Web Worker, where it implements the blocking [`Rep3Network`] by waiting on the mailboxes with `Atomics.wait`. Sharing the buffers with a worker requires the page to be cross-origin isolated.
+
+use std::collections::VecDeque;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use eyre::{bail, eyre, Report};
+use futures::{
+    channel::{
+        mpsc::{self, TryRecvError, UnboundedReceiver},
+        oneshot,
+    },
+    StreamExt,
+};
+use js_sys::{Array, Atomics, Function, Int32Array, SharedArrayBuffer, Uint8Array};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{BinaryType, MessageEvent, WebSocket};
+
+use super::WebSocketConfig;
+use crate::protocols::rep3::{
+    async_network::{deserialize_many, serialize},
+    id::PartyID,
+    network::Rep3Network,
+};
+
+/// The capacity of the ring buffer of a [`Mailbox`] in bytes.
+const MAILBOX_CAPACITY: u32 = 1 << 20;
+
+/// The indices of the header of a [`Mailbox`]: the total number of bytes written and read so far (modulo 2^32) and whether the mailbox was closed.
+const WRITTEN: u32 = 0;
+const READ: u32 = 1;
+const CLOSED: u32 = 2;
+const HEADER_LEN: u32 = 3;
+
+/// The time in milliseconds a blocked worker waits before it checks again whether the mailbox was closed.
+const WAIT_TIMEOUT_MS: f64 = 100.0;
+
+/// The interval in milliseconds in which the relay forwards the messages.
+const POLL_INTERVAL_MS: i32 = 1;
+
+/// Converts an exception of a JavaScript API into an error.
+fn js_error(err: JsValue) -> std::io::Error {
+    std::io::Error::other(format!("JavaScript error: {:?}", err))
+}
+
+fn closed_error() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::BrokenPipe, "mailbox was closed")
+}
+
+/// A byte stream from one JavaScript thread to another in a [SharedArrayBuffer]. The buffer starts with the header (see [`WRITTEN`], [`READ`], [`CLOSED`]), followed by a ring buffer of the data. The header is only accessed with atomic operations, such that the reader and the writer can wait for each other.
+#[derive(Clone)]
+struct Mailbox {
+    header: Int32Array,
+    data: Uint8Array,
+}
+
+impl Mailbox {
+    /// Creates a new mailbox. The capacity has to be a power of two, such that the positions stay consistent when the counters wrap around.
+    fn new(capacity: u32) -> Self {
+        debug_assert!(capacity.is_power_of_two() && capacity <= 1 << 30);
+        Self::from_buffer(&SharedArrayBuffer::new(HEADER_LEN * 4 + capacity))
+    }
+
+    fn from_buffer(buffer: &SharedArrayBuffer) -> Self {
+        Self {
+            header: Int32Array::new_with_byte_offset_and_length(buffer, 0, HEADER_LEN),
+            data: Uint8Array::new_with_byte_offset(buffer, HEADER_LEN * 4),
+        }
+    }
+
+    /// Wraps a buffer created by [`Mailbox::new`], e.g., after it was sent to a worker.
+    fn try_from_buffer(buffer: JsValue) -> Result<Self, Report> {
+        let buffer = buffer
+            .dyn_into::<SharedArrayBuffer>()
+            .map_err(|_| eyre!("expected a SharedArrayBuffer"))?;
+        let capacity = buffer
+            .byte_length()
+            .checked_sub(HEADER_LEN * 4)
+            .ok_or(eyre!("buffer too small for a mailbox"))?;
+        if !capacity.is_power_of_two() {
+            bail!("the capacity of a mailbox has to be a power of two")
+        }
+        Ok(Self::from_buffer(&buffer))
+    }
+
+    fn buffer(&self) -> JsValue {
+        self.header.buffer().into()
+    }
+
+    fn capacity(&self) -> u32 {
+        self.data.length()
+    }
+
+    fn get(&self, index: u32) -> u32 {
+        Atomics::load(&self.header, index).expect("header is a shared Int32Array") as u32
+    }
+
+    fn set(&self, index: u32, value: u32) {
+        Atomics::store(&self.header, index, value as i32).expect("header is a shared Int32Array");
+        Atomics::notify(&self.header, index).expect("header is a shared Int32Array");
+    }
+
+    fn is_closed(&self) -> bool {
+        self.get(CLOSED) != 0
+    }
+
+    /// Closes the mailbox. The reader can still read the bytes written before, afterwards reads and writes fail.
+    fn close(&self) {
+        self.set(CLOSED, 1);
+        Atomics::notify(&self.header, WRITTEN).expect("header is a shared Int32Array");
+        Atomics::notify(&self.header, READ).expect("header is a shared Int32Array");
+    }
+
+    /// Writes as many bytes as fit into the ring buffer without blocking and returns their number.
+    fn try_write(&self, bytes: &[u8]) -> usize {
+        let capacity = self.capacity();
+        let written = self.get(WRITTEN);
+        let free = capacity - written.wrapping_sub(self.get(READ));
+        let len = bytes.len().min(free as usize) as u32;
+        let start = written % capacity;
+        let first = len.min(capacity - start);
+        self.data
+            .subarray(start, start + first)
+            .copy_from(&bytes[..first as usize]);
+        self.data
+            .subarray(0, len - first)
+            .copy_from(&bytes[first as usize..len as usize]);
+        self.set(WRITTEN, written.wrapping_add(len));
+        len as usize
+    }
+
+    /// Reads up to `max` bytes from the ring buffer without blocking.
+    fn try_read(&self, max: usize) -> Vec<u8> {
+        let capacity = self.capacity();
+        let read = self.get(READ);
+        let available = self.get(WRITTEN).wrapping_sub(read);
+        let len = max.min(available as usize) as u32;
+        let start = read % capacity;
+        let first = len.min(capacity - start);
+        let mut bytes = self.data.subarray(start, start + first).to_vec();
+        bytes.extend(self.data.subarray(0, len - first).to_vec());
+        self.set(READ, read.wrapping_add(len));
+        bytes
+    }
+
+    /// Blocks until the header entry at `index` no longer has the given value. This is only allowed in a Web Worker.
+    fn wait(&self, index: u32, value: u32) -> std::io::Result<()> {
+        Atomics::wait_with_timeout(&self.header, index, value as i32, WAIT_TIMEOUT_MS)
+            .map_err(js_error)?;
+        Ok(())
+    }
+
+    /// Writes all bytes, blocking while the ring buffer is full.
+    fn write_all(&self, mut bytes: &[u8]) -> std::io::Result<()> {
+        while !bytes.is_empty() {
+            if self.is_closed() {
+                return Err(closed_error());
+            }
+            let read = self.get(READ);
+            let len = self.try_write(bytes);
+            bytes = &bytes[len..];
+            if len == 0 {
+                self.wait(READ, read)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads exactly `len` bytes, blocking while the ring buffer is empty.
+    fn read_exact(&self, len: usize) -> std::io::Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(len);
+        while bytes.len() < len {
+            // The writer closes the mailbox only after writing, so no bytes are missed
+            let closed = self.is_closed();
+            let written = self.get(WRITTEN);
+            let chunk = self.try_read(len - bytes.len());
+            if chunk.is_empty() {
+                if closed {
+                    return Err(closed_error());
+                }
+                self.wait(WRITTEN, written)?;
+            }
+            bytes.extend(chunk);
+        }
+        Ok(bytes)
+    }
+
+    /// Writes one message, prefixed with its length.
+    fn send(&self, data: &[u8]) -> std::io::Result<()> {
+        let len = u32::try_from(data.len())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        self.write_all(&len.to_le_bytes())?;
+        self.write_all(data)
+    }
+
+    /// Reads one message written by [`Mailbox::send`].
+    fn recv(&self) -> std::io::Result<Vec<u8>> {
+        let len = self.read_exact(4)?;
+        let len = u32::from_le_bytes(len.try_into().expect("4 bytes"));
+        self.read_exact(len as usize)
+    }
+}
+
+/// Resolves after the given number of milliseconds, using `setTimeout` of the global scope (the window or a worker).
+async fn sleep(ms: i32) -> std::io::Result<()> {
+    let set_timeout = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("setTimeout"))
+        .map_err(js_error)?
+        .dyn_into::<Function>()
+        .map_err(js_error)?;
+    let (sender, receiver) = oneshot::channel();
+    let callback = Closure::once_into_js(move || {
+        let _ = sender.send(());
+    });
+    set_timeout
+        .call2(&JsValue::NULL, &callback, &JsValue::from(ms))
+        .map_err(js_error)?;
+    receiver
+        .await
+        .map_err(|_| std::io::Error::other("timer was dropped"))
+}
+
+/// A WebSocket connection of the browser to another party.
+struct Connection {
+    socket: WebSocket,
+    receiver: UnboundedReceiver<Vec<u8>>,
+    // The callbacks have to live as long as the socket
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_close: Closure<dyn FnMut(JsValue)>,
+}
+
+impl Connection {
+    /// Opens the WebSocket connection to the party with the given url and announces the own id.
+    async fn connect(url: &str, id: PartyID) -> std::io::Result<Self> {
+        let socket = WebSocket::new(url).map_err(js_error)?;
+        socket.set_binary_type(BinaryType::Arraybuffer);
+
+        let (sender, receiver) = mpsc::unbounded();
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new({
+            let sender = sender.clone();
+            move |event: MessageEvent| {
+                if let Ok(buffer) = event.data().dyn_into::<js_sys::ArrayBuffer>() {
+                    let data = js_sys::Uint8Array::new(&buffer).to_vec();
+                    // The receiver is gone if the relay was dropped
+                    let _ = sender.unbounded_send(data);
+                }
+            }
+        });
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        // Wait until the connection is open, the socket closes on errors as well
+        let (open_sender, open_receiver) = oneshot::channel();
+        let mut open_sender = Some(open_sender);
+        let on_open = Closure::<dyn FnMut(JsValue)>::new(move |_| {
+            if let Some(open_sender) = open_sender.take() {
+                let _ = open_sender.send(());
+            }
+        });
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        let on_close = Closure::<dyn FnMut(JsValue)>::new(move |_| sender.close_channel());
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+        let mut connection = Self {
+            socket,
+            receiver,
+            _on_message: on_message,
+            _on_close: on_close,
+        };
+        let opened = matches!(
+            futures::future::select(open_receiver, connection.receiver.next()).await,
+            futures::future::Either::Left((Ok(()), _))
+        );
+        connection.socket.set_onopen(None);
+        if !opened {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::ConnectionRefused,
+                format!("could not connect to {}", url),
+            ));
+        }
+        connection.send(&[id as u8])?;
+        Ok(connection)
+    }
+
+    fn send(&mut self, data: &[u8]) -> std::io::Result<()> {
+        self.socket.send_with_u8_array(data).map_err(js_error)
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.socket.set_onmessage(None);
+        self.socket.set_onclose(None);
+        // ignore errors in drop
+        let _ = self.socket.close();
+    }
+}
+
+/// The relay half of the connection to another party. The inbox carries the messages to the worker, the outbox the messages from the worker.
+struct Link {
+    connection: Connection,
+    socket_open: bool,
+    inbox: Mailbox,
+    outbox: Mailbox,
+    // The framed messages which did not fit into the inbox yet
+    incoming: VecDeque<u8>,
+    // The bytes of the outbox which do not form a complete message yet
+    outgoing: Vec<u8>,
+}
+
+impl Link {
+    fn new(connection: Connection) -> Self {
+        Self {
+            connection,
+            socket_open: true,
+            inbox: Mailbox::new(MAILBOX_CAPACITY),
+            outbox: Mailbox::new(MAILBOX_CAPACITY),
+            incoming: VecDeque::new(),
+            outgoing: Vec::new(),
+        }
+    }
+
+    /// Forwards the messages in both directions without blocking. Returns whether the link is still open.
+    fn forward(&mut self) -> std::io::Result<bool> {
+        while self.socket_open {
+            match self.connection.receiver.try_recv() {
+                Ok(data) => {
+                    self.incoming.extend((data.len() as u32).to_le_bytes());
+                    self.incoming.extend(data);
+                }
+                Err(TryRecvError::Closed) => self.socket_open = false,
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+        while !self.incoming.is_empty() {
+            let len = self.inbox.try_write(self.incoming.as_slices().0);
+            if len == 0 {
+                break;
+            }
+            self.incoming.drain(..len);
+        }
+
+        // The worker closes the outbox only after writing, so all messages are forwarded
+        let worker_closed = self.outbox.is_closed();
+        self.outgoing.extend(self.outbox.try_read(usize::MAX));
+        while self.outgoing.len() >= 4 {
+            let len = u32::from_le_bytes(self.outgoing[..4].try_into().expect("4 bytes")) as usize;
+            if self.outgoing.len() < 4 + len {
+                break;
+            }
+            self.connection.send(&self.outgoing[4..4 + len])?;
+            self.outgoing.drain(..4 + len);
+        }
+
+        Ok(!worker_closed && (self.socket_open || !self.incoming.is_empty()))
+    }
+}
+
+impl Drop for Link {
+    fn drop(&mut self) {
+        // The worker can still read the messages already in the inbox
+        self.inbox.close();
+        self.outbox.close();
+    }
+}
+
+/// The half of the browser transport which owns the WebSocket connections to the other parties (see the [module documentation](self)). It runs in an async context, typically the main thread, and forwards the messages to and from the [`Rep3BrowserNet`] in a Web Worker.
+pub struct Rep3BrowserRelay {
+    id: PartyID,
+    next: Link,
+    prev: Link,
+}
+
+impl Rep3BrowserRelay {
+    /// Takes a [WebSocketConfig] struct and connects to the other parties. The party itself must not have an url, since a browser cannot accept connections.
+    pub async fn new(config: WebSocketConfig) -> Result<Self, Report> {
+        let id = config.check_config()?;
+        if config.url(id).is_some() {
+            bail!("a party in the browser cannot accept connections")
+        }
+        let next_url = config
+            .url(id.next_id())
+            .ok_or(eyre!("no url for next party"))?;
+        let prev_url = config
+            .url(id.prev_id())
+            .ok_or(eyre!("no url for prev party"))?;
+        let next = Link::new(Connection::connect(next_url, id).await?);
+        let prev = Link::new(Connection::connect(prev_url, id).await?);
+        Ok(Self { id, next, prev })
+    }
+
+    /// Returns the shared buffers, which have to be passed to the worker (e.g., with `postMessage`) and used to construct the [`Rep3BrowserNet`] with [`Rep3BrowserNet::from_shared_buffers`].
+    pub fn shared_buffers(&self) -> Array {
+        Array::of5(
+            &JsValue::from(usize::from(self.id) as u32),
+            &self.next.inbox.buffer(),
+            &self.next.outbox.buffer(),
+            &self.prev.inbox.buffer(),
+            &self.prev.outbox.buffer(),
+        )
+    }
+
+    /// Forwards the messages until the worker dropped the [`Rep3BrowserNet`] or both connections were closed.
+    pub async fn run(mut self) -> std::io::Result<()> {
+        let mut next_open = true;
+        let mut prev_open = true;
+        while next_open || prev_open {
+            if next_open {
+                next_open = self.next.forward()?;
+            }
+            if prev_open {
+                prev_open = self.prev.forward()?;
+            }
+            sleep(POLL_INTERVAL_MS).await?;
+        }
+        Ok(())
+    }
+}
+
+/// The worker half of the connection to another party.
+struct Channel {
+    inbox: Mailbox,
+    outbox: Mailbox,
+}
+
+/// This struct can be used by a party running in a browser to take part in the REP3 MPC protocol with parties using the [`Rep3WebSocketNet`](super::Rep3WebSocketNet). It has to run in a dedicated Web Worker, since it blocks while waiting for messages, which the [`Rep3BrowserRelay`] forwards from the WebSocket connections (see the [module documentation](self)). Forking requires new connections and is not supported.
+pub struct Rep3BrowserNet {
+    id: PartyID,
+    next: Channel,
+    prev: Channel,
+}
+
+// SAFETY: Without the atomics target feature, a wasm instance only has a single thread, so the JavaScript objects of the network are never accessed from another thread. Each Web Worker runs its own instance.
+#[cfg(not(target_feature = "atomics"))]
+unsafe impl Send for Rep3BrowserNet {}
+
+impl Rep3BrowserNet {
+    /// Constructs the network from the buffers returned by [`Rep3BrowserRelay::shared_buffers`].
+    pub fn from_shared_buffers(buffers: &JsValue) -> Result<Self, Report> {
+        let buffers = buffers
+            .dyn_ref::<Array>()
+            .ok_or(eyre!("expected an array of shared buffers"))?;
+        if buffers.length() != 5 {
+            bail!("expected the id and four shared buffers")
+        }
+        let id = buffers
+            .get(0)
+            .as_f64()
+            .ok_or(eyre!("expected the id of the party"))?;
+        let id = PartyID::try_from(id as usize)?;
+        Ok(Self {
+            id,
+            next: Channel {
+                inbox: Mailbox::try_from_buffer(buffers.get(1))?,
+                outbox: Mailbox::try_from_buffer(buffers.get(2))?,
+            },
+            prev: Channel {
+                inbox: Mailbox::try_from_buffer(buffers.get(3))?,
+                outbox: Mailbox::try_from_buffer(buffers.get(4))?,
+            },
+        })
+    }
+
+    fn channel(&self, other: PartyID) -> std::io::Result<&Channel> {
+        if other == self.id.next_id() {
+            Ok(&self.next)
+        } else if other == self.id.prev_id() {
+            Ok(&self.prev)
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Cannot communicate with self",
+            ))
+        }
+    }
+}
+
+impl Drop for Rep3BrowserNet {
+    fn drop(&mut self) {
+        // Tells the relay to close the connections after forwarding the pending messages
+        self.next.outbox.close();
+        self.prev.outbox.close();
+    }
+}
+
+impl Rep3Network for Rep3BrowserNet {
+    fn get_id(&self) -> PartyID {
+        self.id
+    }
+
+    fn reshare_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<Vec<F>> {
+        self.send_many(self.id.next_id(), data)?;
+        self.recv_many(self.id.prev_id())
+    }
+
+    fn broadcast_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<(Vec<F>, Vec<F>)> {
+        self.send_many(self.id.next_id(), data)?;
+        self.send_many(self.id.prev_id(), data)?;
+        let recv_next = self.recv_many(self.id.next_id())?;
+        let recv_prev = self.recv_many(self.id.prev_id())?;
+        Ok((recv_prev, recv_next))
+    }
+
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: PartyID,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        let data = serialize(data)?;
+        self.channel(target)?.outbox.send(&data)
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(&mut self, from: PartyID) -> std::io::Result<Vec<F>> {
+        let data = self.channel(from)?.inbox.recv()?;
+        deserialize_many(&data)
+    }
+
+    fn fork(&mut self) -> std::io::Result<Self> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "the browser network cannot be forked",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    /// Connects the networks of three parties directly through small mailboxes, such that the ring buffers wrap around.
+    fn loopback_nets() -> [Rep3BrowserNet; 3] {
+        // forward[i] carries the messages from party i to party i + 1, backward[i] the ones back
+        let forward = [0; 3].map(|_| Mailbox::new(64));
+        let backward = [0; 3].map(|_| Mailbox::new(64));
+        [PartyID::ID0, PartyID::ID1, PartyID::ID2].map(|id| {
+            let i = usize::from(id);
+            let p = usize::from(id.prev_id());
+            Rep3BrowserNet {
+                id,
+                next: Channel {
+                    inbox: backward[i].clone(),
+                    outbox: forward[i].clone(),
+                },
+                prev: Channel {
+                    inbox: forward[p].clone(),
+                    outbox: backward[p].clone(),
+                },
+            }
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn browser_net_send_recv() {
+        let mut nets = loopback_nets();
+        for round in 0..10u64 {
+            for net in nets.iter_mut() {
+                let id = usize::from(net.get_id()) as u64;
+                net.send_next_many(&[id, round, id + round]).unwrap();
+            }
+            for net in nets.iter_mut() {
+                let prev = usize::from(net.get_id().prev_id()) as u64;
+                let data = net.recv_prev_many::<u64>().unwrap();
+                assert_eq!(data, vec![prev, round, prev + round]);
+            }
+        }
+        for net in nets.iter_mut() {
+            let id = usize::from(net.get_id()) as u64;
+            net.send_many(net.get_id().next_id(), &[id]).unwrap();
+            net.send_many(net.get_id().prev_id(), &[id]).unwrap();
+        }
+        for net in nets.iter_mut() {
+            let prev = net.recv::<u64>(net.get_id().prev_id()).unwrap();
+            let next = net.recv::<u64>(net.get_id().next_id()).unwrap();
+            assert_eq!(prev, usize::from(net.get_id().prev_id()) as u64);
+            assert_eq!(next, usize::from(net.get_id().next_id()) as u64);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn browser_net_closed() {
+        let [net0, mut net1, _net2] = loopback_nets();
+        let mut net0 = net0;
+        net0.send_next_many(&[1u64]).unwrap();
+        drop(net0);
+        // The message sent before closing is still delivered
+        assert_eq!(net1.recv_prev_many::<u64>().unwrap(), vec![1]);
+        let err = net1.recv_prev_many::<u64>().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+    }
+}
@@ -0,0 +1,315 @@
+//! The native WebSocket transport using [tungstenite].
+
+use std::{
+    io::{Read, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use eyre::Report;
+use tungstenite::{client::IntoClientRequest, protocol::Role, Message, WebSocket};
+
+use super::WebSocketConfig;
+use crate::protocols::rep3::{
+    async_network::{deserialize_many, serialize},
+    id::PartyID,
+    network::Rep3Network,
+};
+
+/// The time a party keeps retrying to connect to a party that is not listening yet.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The stream of the reading socket of a [`Connection`]. It is writable during the handshake, afterwards all writes are discarded, such that the pongs and close replies tungstenite queues while reading never interleave with the frames of the writer. The pongs are sent by the writer instead.
+#[derive(Debug)]
+struct ReaderStream {
+    stream: TcpStream,
+    discard_writes: bool,
+}
+
+impl ReaderStream {
+    fn new(stream: TcpStream) -> Self {
+        Self {
+            stream,
+            discard_writes: false,
+        }
+    }
+}
+
+impl Read for ReaderStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.stream.read(buf)
+    }
+}
+
+impl Write for ReaderStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.discard_writes {
+            Ok(buf.len())
+        } else {
+            self.stream.write(buf)
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+/// A WebSocket connection to another party. The messages are read by a dedicated thread, such that sending never blocks on the other party sending as well. All frames, including the pongs answering pings, are written by the single writer.
+#[derive(Debug)]
+struct Connection {
+    writer: Arc<Mutex<WebSocket<TcpStream>>>,
+    reader: Receiver<std::io::Result<Vec<u8>>>,
+}
+
+impl Connection {
+    /// Splits the established WebSocket into a writer and a thread reading from it.
+    fn new(mut socket: WebSocket<ReaderStream>, role: Role) -> std::io::Result<Self> {
+        // The writer never reads, so it does not miss messages the socket has already buffered
+        let writer = WebSocket::from_raw_socket(socket.get_ref().stream.try_clone()?, role, None);
+        let writer = Arc::new(Mutex::new(writer));
+        socket.get_mut().discard_writes = true;
+        let (sender, reader) = mpsc::channel();
+        let pong_writer = Arc::clone(&writer);
+        std::thread::spawn(move || loop {
+            // Text messages are ignored, the parties only send binary messages
+            let data = match socket.read() {
+                Ok(Message::Binary(data)) => Ok(data),
+                Ok(Message::Ping(payload)) => {
+                    // A failed pong means the connection is broken, which the next read reports
+                    let mut writer = pong_writer.lock().expect("writer not poisoned");
+                    let _ = writer.send(Message::Pong(payload));
+                    continue;
+                }
+                Ok(Message::Close(_)) => break,
+                Ok(_) => continue,
+                Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                    break
+                }
+                Err(err) => Err(std::io::Error::other(err)),
+            };
+            let failed = data.is_err();
+            if sender.send(data).is_err() || failed {
+                break;
+            }
+        });
+        Ok(Self { writer, reader })
+    }
+
+    fn send(&mut self, data: Vec<u8>) -> std::io::Result<()> {
+        self.writer
+            .lock()
+            .expect("writer not poisoned")
+            .send(Message::Binary(data))
+            .map_err(std::io::Error::other)
+    }
+
+    fn recv(&mut self) -> std::io::Result<Vec<u8>> {
+        self.reader.recv().map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::BrokenPipe, "receive channel end died")
+        })?
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // ignore errors in drop
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writer.close(None);
+            let _ = writer.flush();
+            // Also stops the reading thread
+            let _ = writer.get_ref().shutdown(Shutdown::Both);
+        }
+    }
+}
+
+/// Opens the WebSocket connection to the party with the given url and announces the own id.
+fn connect(url: &str, id: PartyID) -> std::io::Result<Connection> {
+    let request = url
+        .into_client_request()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let uri = request.uri();
+    if uri.scheme_str() != Some("ws") {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "only ws urls are supported on native targets, TLS has to be terminated by a proxy",
+        ));
+    }
+    let host = uri.host().unwrap_or_default();
+    let addr = (host, uri.port_u16().unwrap_or(80));
+
+    let start = Instant::now();
+    let stream = loop {
+        match TcpStream::connect(addr) {
+            Ok(stream) => break stream,
+            Err(err) if start.elapsed() > CONNECT_TIMEOUT => return Err(err),
+            Err(_) => std::thread::sleep(Duration::from_millis(100)),
+        }
+    };
+    stream.set_nodelay(true)?;
+    let (mut socket, _) =
+        tungstenite::client(request, ReaderStream::new(stream)).map_err(std::io::Error::other)?;
+    socket
+        .send(Message::Binary(vec![id as u8]))
+        .map_err(std::io::Error::other)?;
+    Connection::new(socket, Role::Client)
+}
+
+/// Accepts a WebSocket connection and returns it together with the id of the connected party.
+fn accept(listener: &TcpListener) -> std::io::Result<(PartyID, Connection)> {
+    let (stream, _) = listener.accept()?;
+    stream.set_nodelay(true)?;
+    let mut socket =
+        tungstenite::accept(ReaderStream::new(stream)).map_err(std::io::Error::other)?;
+    let their_id = match socket.read().map_err(std::io::Error::other)? {
+        Message::Binary(data) if data.len() == 1 => PartyID::try_from(data[0])
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?,
+        _ => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "expected the id of the connecting party",
+            ))
+        }
+    };
+    Ok((their_id, Connection::new(socket, Role::Server)?))
+}
+
+/// Establishes the connections to the next and the previous party. All outgoing connections are opened before accepting the incoming ones (see [WebSocketConfig::connects_to]).
+fn connect_all(
+    id: PartyID,
+    config: &WebSocketConfig,
+    listener: Option<&TcpListener>,
+) -> std::io::Result<(Connection, Connection)> {
+    let mut next = None;
+    let mut prev = None;
+    for other in [id.next_id(), id.prev_id()] {
+        if config.connects_to(id, other) {
+            let url = config.url(other).expect("party with url");
+            let connection = connect(url, id)?;
+            if other == id.next_id() {
+                next = Some(connection);
+            } else {
+                prev = Some(connection);
+            }
+        }
+    }
+    while next.is_none() || prev.is_none() {
+        let listener = listener.expect("parties with incoming connections listen");
+        let (their_id, connection) = accept(listener)?;
+        let slot = if their_id == id.next_id() {
+            &mut next
+        } else if their_id == id.prev_id() {
+            &mut prev
+        } else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "party connected to itself",
+            ));
+        };
+        if slot.replace(connection).is_some() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("party {} connected twice", their_id),
+            ));
+        }
+    }
+    Ok((next.expect("connected"), prev.expect("connected")))
+}
+
+/// This struct can be used to facilitate network communication for the REP3 MPC protocol over WebSockets, e.g., with a party running in a browser (see [`Rep3BrowserNet`](super::Rep3BrowserNet)).
+#[derive(Debug)]
+pub struct Rep3WebSocketNet {
+    id: PartyID,
+    config: Arc<WebSocketConfig>,
+    listener: Option<Arc<TcpListener>>,
+    next: Connection,
+    prev: Connection,
+}
+
+impl Rep3WebSocketNet {
+    /// Takes a [WebSocketConfig] struct and establishes the connections to the other parties.
+    pub fn new(config: WebSocketConfig) -> Result<Self, Report> {
+        let id = config.check_config()?;
+        let listener = match (config.url(id), config.bind_addr) {
+            (Some(_), Some(bind_addr)) => Some(Arc::new(TcpListener::bind(bind_addr)?)),
+            _ => None,
+        };
+        let (next, prev) = connect_all(id, &config, listener.as_deref())?;
+        Ok(Self {
+            id,
+            config: Arc::new(config),
+            listener,
+            next,
+            prev,
+        })
+    }
+
+    fn connection(&mut self, other: PartyID) -> std::io::Result<&mut Connection> {
+        if other == self.id.next_id() {
+            Ok(&mut self.next)
+        } else if other == self.id.prev_id() {
+            Ok(&mut self.prev)
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Cannot communicate with self",
+            ))
+        }
+    }
+}
+
+impl Rep3Network for Rep3WebSocketNet {
+    fn get_id(&self) -> PartyID {
+        self.id
+    }
+
+    fn reshare_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<Vec<F>> {
+        self.send_many(self.id.next_id(), data)?;
+        self.recv_many(self.id.prev_id())
+    }
+
+    fn broadcast_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<(Vec<F>, Vec<F>)> {
+        self.send_many(self.id.next_id(), data)?;
+        self.send_many(self.id.prev_id(), data)?;
+        let recv_next = self.recv_many(self.id.next_id())?;
+        let recv_prev = self.recv_many(self.id.prev_id())?;
+        Ok((recv_prev, recv_next))
+    }
+
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: PartyID,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        let data = serialize(data)?;
+        self.connection(target)?.send(data.into())
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(&mut self, from: PartyID) -> std::io::Result<Vec<F>> {
+        let data = self.connection(from)?.recv()?;
+        deserialize_many(&data)
+    }
+
+    /// Forks the network by establishing new connections. All parties have to fork at the same point of the protocol, and forks of the same network must not be created concurrently.
+    fn fork(&mut self) -> std::io::Result<Self> {
+        let (next, prev) = connect_all(self.id, &self.config, self.listener.as_deref())?;
+        Ok(Self {
+            id: self.id,
+            config: Arc::clone(&self.config),
+            listener: self.listener.clone(),
+            next,
+            prev,
+        })
+    }
+}
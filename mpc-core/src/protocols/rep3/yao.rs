@@ -2,20 +2,27 @@
 //!
 //! This module contains operations with Yao's garbled circuits
 
+pub mod arithmetic;
+pub mod bristol_fashion;
 pub mod circuits;
 pub mod evaluator;
 pub mod garbler;
+mod hash;
+pub mod roles;
 pub mod streaming_evaluator;
 pub mod streaming_garbler;
+pub mod topology;
 
 use super::{
+    malicious,
     network::{IoContext, Rep3Network},
     IoResult, Rep3BigUintShare, Rep3PrimeFieldShare,
 };
 use crate::protocols::rep3::id::PartyID;
 use ark_ff::{PrimeField, Zero};
 use circuits::GarbledCircuits;
-use fancy_garbling::{hash_wires, util::tweak2, BinaryBundle, WireLabel, WireMod2};
+use fancy_garbling::{util::tweak2, BinaryBundle, FancyBinary, WireLabel, WireMod2};
+use hash::hash_wires;
 use itertools::{izip, Itertools};
 use num_bigint::BigUint;
 use rand::{CryptoRng, Rng};
@@ -32,10 +39,88 @@ pub struct GCInputs<F> {
     pub delta: F,
 }
 
+/// The error returned if the garbled circuits of the two garblers are inconsistent, i.e., at least one of them misbehaved. Functions returning an [IoResult] wrap it into an [std::io::Error] of kind [std::io::ErrorKind::InvalidData], from which it can be recovered with [GcConsistencyError::from_io_error].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GcConsistencyError {
+    /// The hash sent by ID2 does not match the part of the circuit sent by ID1. Contains the index of the first mismatching hash (i.e., the chunk) and the range of the blocks (garbled gates and wires) of the circuit it covers.
+    HashMismatch {
+        /// The index of the mismatching chunk
+        chunk: usize,
+        /// The range of blocks covered by the chunk
+        blocks: std::ops::Range<usize>,
+    },
+    /// The decoding information of an output sent by the two garblers does not match.
+    DecodingMismatch,
+}
+
+impl GcConsistencyError {
+    /// Returns the [GcConsistencyError] wrapped in the given error, if any.
+    pub fn from_io_error(err: &std::io::Error) -> Option<&Self> {
+        err.get_ref()?.downcast_ref()
+    }
+}
+
+impl std::fmt::Display for GcConsistencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GcConsistencyError::HashMismatch { chunk, blocks } => write!(
+                f,
+                "Inconsistent Garbled Circuits: Hashes do not match in chunk {chunk} (blocks {}..{})!",
+                blocks.start, blocks.end
+            ),
+            GcConsistencyError::DecodingMismatch => write!(
+                f,
+                "Inconsistent Garbled Circuits: Decoding information does not match!"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GcConsistencyError {}
+
+impl From<GcConsistencyError> for std::io::Error {
+    fn from(err: GcConsistencyError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// A [FancyBinary] which can process a whole layer of independent AND gates at once. Implementations may garble or evaluate the gates of a layer in parallel, as long as the garbled gates are transmitted in the order of the layer.
+pub trait FancyBinaryLayer: FancyBinary {
+    /// Computes the AND of each pair of wires. The gates of a layer must not depend on each other.
+    fn and_layer(
+        &mut self,
+        a: &[Self::Item],
+        b: &[Self::Item],
+    ) -> Result<Vec<Self::Item>, Self::Error> {
+        debug_assert_eq!(a.len(), b.len());
+        izip!(a, b).map(|(a, b)| self.and(a, b)).collect()
+    }
+}
+
 /// This struct contains some useful utility functions for garbled circuits.
 pub struct GCUtils {}
 
 impl GCUtils {
+    /// Decodes an output for a receiver outside of the three parties. The inputs are the color bits of the evaluator's output labels and the decoding information sent by both garblers, which has to be consistent.
+    pub fn decode_external_output(
+        evaluator: &[bool],
+        garbler_id1: &[bool],
+        garbler_id2: &[bool],
+    ) -> IoResult<Vec<bool>> {
+        if evaluator.len() != garbler_id1.len() || evaluator.len() != garbler_id2.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "During decoding of an external output: Invalid number of elements received",
+            ));
+        }
+        if garbler_id1 != garbler_id2 {
+            return Err(GcConsistencyError::DecodingMismatch.into());
+        }
+        Ok(izip!(evaluator, garbler_id1)
+            .map(|(label, zero)| label ^ zero)
+            .collect())
+    }
+
     /// Evaluates an 'and' gate given two inputs wires and two half-gates from the garbler.
     ///
     /// Outputs C = A & B
@@ -117,6 +202,57 @@ impl GCUtils {
         (gate0, gate1, x.plus_mov(&y))
     }
 
+    /// Clears the color bit of a hash, such that the zero labels of the outputs of privacy-free gates have color 0.
+    fn clear_color(block: Block) -> Block {
+        let one = Block::from(1u128);
+        Block::conditional_select(&block, &(block ^ one), (block.as_ref()[0] & 1).into())
+    }
+
+    /// Garbles a privacy-free 'and' gate given two input wires, whose zero labels have color 0, and the delta.
+    ///
+    /// Outputs a tuple consisting of the single gate (that should be transfered to the evaluator) and the next wire label for the garbler, which has color 0 as well.
+    ///
+    /// Used internally as a subroutine to implement 'and' gates in the privacy-free mode.
+    pub(crate) fn garble_and_gate_privacy_free(
+        gate_num: usize,
+        a: &WireMod2,
+        b: &WireMod2,
+        delta: &WireMod2,
+    ) -> (Block, WireMod2) {
+        debug_assert_eq!(a.color(), 0);
+        debug_assert_eq!(b.color(), 0);
+        let g = tweak2(gate_num as u64, 0);
+
+        let a1 = a.plus(delta);
+        let [hash_a0, hash_a1] = hash_wires([a, &a1], g);
+        let hash_a0 = Self::clear_color(hash_a0);
+        let hash_a1 = Self::clear_color(hash_a1);
+
+        let gate = hash_a0 ^ hash_a1 ^ b.as_block();
+        (gate, WireMod2::from_block(hash_a0, 2))
+    }
+
+    /// Evaluates a privacy-free 'and' gate given two input wires and the gate from the garbler. The color of the labels is the value of the wires.
+    ///
+    /// Outputs C = A & B
+    ///
+    /// Used internally as a subroutine to implement 'and' gates in the privacy-free mode.
+    pub(crate) fn evaluate_and_gate_privacy_free(
+        gate_num: usize,
+        a: &WireMod2,
+        b: &WireMod2,
+        gate: &Block,
+    ) -> WireMod2 {
+        let g = tweak2(gate_num as u64, 0);
+        let [hash_a] = hash_wires([a], g);
+        let hash_a = Self::clear_color(hash_a);
+        if a.color() == 0 {
+            WireMod2::from_block(hash_a, 2)
+        } else {
+            WireMod2::from_block(hash_a ^ *gate, 2).plus_mov(b)
+        }
+    }
+
     pub(crate) fn garbled_circuits_error<G, T>(input: Result<T, G>) -> IoResult<T> {
         input.or(Err(std::io::Error::new(
             std::io::ErrorKind::Other,
@@ -354,6 +490,7 @@ pub fn joint_input_arithmetic<F: PrimeField, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<[BinaryBundle<WireMod2>; 3]> {
+    malicious::ensure_semi_honest(io_context, "joint_input_arithmetic")?;
     let id = io_context.id;
     let n_bits = F::MODULUS_BIT_SIZE as usize;
 
@@ -436,6 +573,7 @@ pub fn joint_input_arithmetic_added<F: PrimeField, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<[BinaryBundle<WireMod2>; 2]> {
+    malicious::ensure_semi_honest(io_context, "joint_input_arithmetic_added")?;
     joint_input_arithmetic_added_many(&[x], delta, io_context)
 }
 
@@ -445,6 +583,7 @@ pub fn joint_input_arithmetic_added_many<F: PrimeField, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<[BinaryBundle<WireMod2>; 2]> {
+    malicious::ensure_semi_honest(io_context, "joint_input_arithmetic_added_many")?;
     let id = io_context.id;
     let n_inputs = x.len();
     let n_bits = F::MODULUS_BIT_SIZE as usize;
@@ -532,6 +671,7 @@ pub fn joint_input_binary_xored<F: PrimeField, N: Rep3Network>(
     io_context: &mut IoContext<N>,
     bitlen: usize,
 ) -> IoResult<[BinaryBundle<WireMod2>; 2]> {
+    malicious::ensure_semi_honest(io_context, "joint_input_binary_xored")?;
     let id = io_context.id;
 
     let (x01, x2) = match id {
@@ -596,6 +736,7 @@ pub fn input_field_id2_many<F: PrimeField, N: Rep3Network>(
     n_inputs: usize,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "input_field_id2_many")?;
     let id = io_context.id;
     let n_bits = F::MODULUS_BIT_SIZE as usize;
     let bits = n_inputs * n_bits;
@@ -656,6 +797,7 @@ pub fn input_field_id2<F: PrimeField, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "input_field_id2")?;
     let x = x.map(|x| vec![x]);
     input_field_id2_many(x, delta, 1, io_context)
 }
@@ -667,6 +809,7 @@ pub fn decompose_arithmetic<F: PrimeField, N: Rep3Network>(
     total_bit_size_per_field: usize,
     decompose_bit_size: usize,
 ) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "decompose_arithmetic")?;
     decompose_arithmetic_many(
         &[input],
         io_context,
@@ -680,6 +823,7 @@ pub fn field_int_div_power_2_many<F: PrimeField, N: Rep3Network>(
     io_context: &mut IoContext<N>,
     divisor_bit: usize,
 ) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "field_int_div_power_2_many")?;
     let num_inputs = inputs.len();
 
     if divisor_bit == 0 {
@@ -704,10 +848,69 @@ pub fn field_int_div_power_2<F: PrimeField, N: Rep3Network>(
     io_context: &mut IoContext<N>,
     divisor_bit: usize,
 ) -> IoResult<Rep3PrimeFieldShare<F>> {
+    malicious::ensure_semi_honest(io_context, "field_int_div_power_2")?;
     let res = field_int_div_power_2_many(&[inputs], io_context, divisor_bit)?;
     Ok(res[0])
 }
 
+/// Compares two vectors of field elements element-wise and returns shared bits, which are 1 if lhs >= rhs and 0 otherwise. All comparisons are done in a single garbled circuit.
+pub fn field_ge_many<F: PrimeField, N: Rep3Network>(
+    lhs: &[Rep3PrimeFieldShare<F>],
+    rhs: &[Rep3PrimeFieldShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "field_ge_many")?;
+    let num_inputs = lhs.len();
+    assert_eq!(num_inputs, rhs.len());
+
+    if num_inputs == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut inputs = Vec::with_capacity(2 * num_inputs);
+    inputs.extend_from_slice(lhs);
+    inputs.extend_from_slice(rhs);
+
+    decompose_circuit_compose_blueprint!(
+        &inputs,
+        io_context,
+        num_inputs,
+        GarbledCircuits::field_ge_many::<_, F>,
+        ()
+    )
+}
+
+/// Divides a vector of field elements by another vector of field elements, rounding down. Only the lowest dividend_bitlen bits of the dividends and divisor_bitlen bits of the divisors are considered. A division by zero results in 2^dividend_bitlen - 1.
+pub fn field_int_div_many<F: PrimeField, N: Rep3Network>(
+    dividends: &[Rep3PrimeFieldShare<F>],
+    divisors: &[Rep3PrimeFieldShare<F>],
+    io_context: &mut IoContext<N>,
+    dividend_bitlen: usize,
+    divisor_bitlen: usize,
+) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "field_int_div_many")?;
+    let num_inputs = dividends.len();
+    assert_eq!(num_inputs, divisors.len());
+    assert!(dividend_bitlen > 0 && dividend_bitlen <= F::MODULUS_BIT_SIZE as usize);
+    assert!(divisor_bitlen > 0 && divisor_bitlen <= F::MODULUS_BIT_SIZE as usize);
+
+    if num_inputs == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut inputs = Vec::with_capacity(2 * num_inputs);
+    inputs.extend_from_slice(dividends);
+    inputs.extend_from_slice(divisors);
+
+    decompose_circuit_compose_blueprint!(
+        &inputs,
+        io_context,
+        num_inputs,
+        GarbledCircuits::field_int_div_many::<_, F>,
+        (dividend_bitlen, divisor_bitlen)
+    )
+}
+
 macro_rules! decompose_circuit_compose_blueprint {
     ($inputs:expr, $io_context:expr, $output_size:expr, $circuit:expr, ($( $args:expr ),*)) => {{
         use $crate::protocols::rep3::id::PartyID;
@@ -817,6 +1020,7 @@ pub fn decompose_arithmetic_many<F: PrimeField, N: Rep3Network>(
     total_bit_size_per_field: usize,
     decompose_bit_size: usize,
 ) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "decompose_arithmetic_many")?;
     let num_inputs = inputs.len();
     let num_decomps_per_field = total_bit_size_per_field.div_ceil(decompose_bit_size);
     let total_output_elements = num_decomps_per_field * num_inputs;
@@ -0,0 +1,364 @@
+//! Arithmetic
+//!
+//! This module extends the replicated 3-party garbled circuits by wires mod a small modulus q ([WireModQ]). Additions, subtractions and multiplications by constants are free, multiplications of two wires with the same modulus are garbled with two half gates and projection gates (i.e., lookup tables) change the modulus. Projections from and to binary wires allow to mix the arithmetic gates with the binary gates of the [Rep3Garbler] and the [Rep3Evaluator], e.g., for small-range multiplications without a boolean multiplier.
+//!
+//! The garbled gates are added to the circuit of the wrapped garbler (evaluator) in the order they are created, so binary and arithmetic gates can be used interchangeably. The deltas of the moduli are sampled from the randomness shared by the two garblers, thus they garble the same circuit.
+//!
+//! This implementation is heavily inspired by [fancy-garbling](https://github.com/GaloisInc/swanky/blob/dev/fancy-garbling/src/garble/garbler.rs)
+
+use super::{evaluator::Rep3Evaluator, garbler::Rep3Garbler};
+use crate::protocols::rep3::network::Rep3Network;
+use fancy_garbling::{
+    errors::{EvaluatorError, FancyError, GarblerError},
+    util::{output_tweak, tweak2},
+    Fancy, FancyArithmetic, HasModulus, WireLabel, WireMod2, WireModQ,
+};
+use scuttlebutt::Block;
+use std::collections::HashMap;
+
+/// A garbler or evaluator for wires mod q, which can be mixed with binary wires.
+pub trait FancyModQ: FancyArithmetic<Item = WireModQ> {
+    /// Projects the binary wire `x` to a wire mod `q`, i.e., the result encodes `tt[x]`.
+    fn proj_from_binary(
+        &mut self,
+        x: &WireMod2,
+        q: u16,
+        tt: &[u16],
+    ) -> Result<WireModQ, Self::Error>;
+
+    /// Projects the wire `x` mod q to a binary wire, i.e., the result encodes `tt[x]`.
+    fn proj_to_binary(&mut self, x: &WireModQ, tt: &[u16]) -> Result<WireMod2, Self::Error>;
+}
+
+/// Garbles a projection gate from the zero label `a` to a wire mod `q_out` encoding `tt[a]`. Returns the garbled table and the zero label of the output.
+fn garble_proj_gate<A: WireLabel, C: WireLabel>(
+    gate_num: usize,
+    a: &A,
+    delta_in: &A,
+    delta_out: &C,
+    q_out: u16,
+    tt: &[u16],
+) -> (Vec<Block>, C) {
+    let q_in = a.modulus();
+    assert_eq!(tt.len(), q_in as usize, "Truth table has the wrong size");
+    let g = tweak2(gate_num as u64, 0);
+    let color = a.color();
+
+    // The row of color 0 is not sent, its output label is the hash of the input label
+    let x0 = (q_in - color) % q_in;
+    let a0 = a.plus(&delta_in.cmul(x0));
+    let c = C::from_block(a0.hash(g), q_out)
+        .plus_mov(&delta_out.cmul((q_out - tt[x0 as usize]) % q_out));
+
+    let mut gate = vec![Block::default(); q_in as usize - 1];
+    for x in 0..q_in {
+        let row = (color + x) % q_in;
+        if row == 0 {
+            continue;
+        }
+        let a_x = a.plus(&delta_in.cmul(x));
+        let c_x = c.plus(&delta_out.cmul(tt[x as usize]));
+        gate[row as usize - 1] = a_x.hash(g) ^ c_x.as_block();
+    }
+    (gate, c)
+}
+
+/// Evaluates a projection gate given the input label `a` and the garbled table.
+fn evaluate_proj_gate<A: WireLabel, C: WireLabel>(
+    gate_num: usize,
+    a: &A,
+    q_out: u16,
+    gate: &[Block],
+) -> C {
+    let g = tweak2(gate_num as u64, 0);
+    let row = match a.color() {
+        0 => Block::default(),
+        color => gate[color as usize - 1],
+    };
+    C::from_block(row ^ a.hash(g), q_out)
+}
+
+/// Garbles a multiplication of two wires with the same modulus using two half gates. Returns the garbled gates and the zero label of the output.
+fn garble_mul_gate(
+    gate_num: usize,
+    a: &WireModQ,
+    b: &WireModQ,
+    delta: &WireModQ,
+) -> (Vec<Block>, WireModQ) {
+    let q = a.modulus();
+    let mul_mod = |x: u16, y: u16| ((x as u32 * y as u32) % q as u32) as u16;
+    let g = tweak2(gate_num as u64, 0);
+    let r = b.color(); // secret value known only to the garbler (ev knows r+b)
+
+    // X = H(A+aD) + arD such that a + A.color == 0
+    let alpha = (q - a.color()) % q;
+    let x = WireModQ::from_block(a.plus(&delta.cmul(alpha)).hash(g), q)
+        .plus_mov(&delta.cmul(mul_mod(alpha, r)));
+    // Y = H(B+bD) + (b+r)A such that b + B.color == 0
+    let beta = (q - b.color()) % q;
+    let y = WireModQ::from_block(b.plus(&delta.cmul(beta)).hash(g), q)
+        .plus_mov(&a.cmul((beta + r) % q));
+
+    let mut gate = vec![Block::default(); 2 * (q as usize - 1)];
+    for i in 0..q {
+        // garbler's half gate: outputs X-arD
+        let a_i = a.plus(&delta.cmul(i));
+        if a_i.color() != 0 {
+            let x_i = x.plus(&delta.cmul((q - mul_mod(i, r)) % q));
+            gate[a_i.color() as usize - 1] = a_i.hash(g) ^ x_i.as_block();
+        }
+        // evaluator's half gate: outputs Y-(b+r)A
+        let b_i = b.plus(&delta.cmul(i));
+        if b_i.color() != 0 {
+            let y_i = y.plus(&a.cmul((q - (i + r) % q) % q));
+            gate[q as usize - 1 + b_i.color() as usize - 1] = b_i.hash(g) ^ y_i.as_block();
+        }
+    }
+    (gate, x.plus_mov(&y))
+}
+
+/// Evaluates a multiplication of two wires with the same modulus given the two half gates.
+fn evaluate_mul_gate(gate_num: usize, a: &WireModQ, b: &WireModQ, gate: &[Block]) -> WireModQ {
+    let q = a.modulus();
+    let g = tweak2(gate_num as u64, 0);
+
+    // garbler's half gate
+    let l = match a.color() {
+        0 => WireModQ::from_block(a.hash(g), q),
+        color => WireModQ::from_block(gate[color as usize - 1] ^ a.hash(g), q),
+    };
+    // evaluator's half gate
+    let r = match b.color() {
+        0 => WireModQ::from_block(b.hash(g), q),
+        color => WireModQ::from_block(gate[q as usize - 1 + color as usize - 1] ^ b.hash(g), q),
+    };
+    l.plus_mov(&r.plus_mov(&a.cmul(b.color())))
+}
+
+/// This struct extends a [Rep3Garbler] by wires mod q.
+pub struct Rep3ArithmeticGarbler<'a, 'b, N: Rep3Network> {
+    garbler: &'a mut Rep3Garbler<'b, N>,
+    deltas: HashMap<u16, WireModQ>,
+}
+
+impl<'a, 'b, N: Rep3Network> Rep3ArithmeticGarbler<'a, 'b, N> {
+    /// Create a new garbler for wires mod q, which adds its gates to the circuit of `garbler`.
+    pub fn new(garbler: &'a mut Rep3Garbler<'b, N>) -> Self {
+        Self {
+            garbler,
+            deltas: HashMap::new(),
+        }
+    }
+
+    /// Returns the delta for the modulus `q`. The deltas are sampled on first use, so both garblers have to use the moduli in the same order.
+    fn delta(&mut self, q: u16) -> WireModQ {
+        self.deltas
+            .entry(q)
+            .or_insert_with(|| WireModQ::rand_delta(&mut self.garbler.rng, q))
+            .clone()
+    }
+
+    fn add_gate_to_circuit(&mut self, gate: &[Block]) -> Result<(), GarblerError> {
+        for block in gate {
+            self.garbler.add_block_to_circuit(block)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Rep3Network> Fancy for Rep3ArithmeticGarbler<'_, '_, N> {
+    type Item = WireModQ;
+    type Error = GarblerError;
+
+    fn constant(&mut self, x: u16, q: u16) -> Result<WireModQ, GarblerError> {
+        let delta = self.delta(q);
+        let zero = WireModQ::rand(&mut self.garbler.rng, q);
+        let wire = zero.plus(&delta.cmul(x % q));
+        self.garbler.add_block_to_circuit(&wire.as_block())?;
+        Ok(zero)
+    }
+
+    fn output(&mut self, x: &WireModQ) -> Result<Option<u16>, GarblerError> {
+        let q = x.modulus();
+        let i = self.garbler.current_output();
+        let d = self.delta(q);
+        for k in 0..q {
+            let block = x.plus(&d.cmul(k)).hash(output_tweak(i, k));
+            self.garbler.add_block_to_circuit(&block)?;
+        }
+        Ok(None)
+    }
+}
+
+impl<N: Rep3Network> FancyArithmetic for Rep3ArithmeticGarbler<'_, '_, N> {
+    fn add(&mut self, x: &WireModQ, y: &WireModQ) -> Result<WireModQ, GarblerError> {
+        if x.modulus() != y.modulus() {
+            return Err(GarblerError::from(FancyError::UnequalModuli));
+        }
+        Ok(x.plus(y))
+    }
+
+    fn sub(&mut self, x: &WireModQ, y: &WireModQ) -> Result<WireModQ, GarblerError> {
+        if x.modulus() != y.modulus() {
+            return Err(GarblerError::from(FancyError::UnequalModuli));
+        }
+        Ok(x.plus(&y.cmul(y.modulus() - 1)))
+    }
+
+    fn cmul(&mut self, x: &WireModQ, c: u16) -> Result<WireModQ, GarblerError> {
+        Ok(x.cmul(c % x.modulus()))
+    }
+
+    fn mul(&mut self, x: &WireModQ, y: &WireModQ) -> Result<WireModQ, GarblerError> {
+        if x.modulus() != y.modulus() {
+            return Err(GarblerError::from(FancyError::UnequalModuli));
+        }
+        let gate_num = self.garbler.current_gate();
+        let delta = self.delta(x.modulus());
+        let (gate, res) = garble_mul_gate(gate_num, x, y, &delta);
+        self.add_gate_to_circuit(&gate)?;
+        Ok(res)
+    }
+
+    fn proj(
+        &mut self,
+        x: &WireModQ,
+        q: u16,
+        tt: Option<Vec<u16>>,
+    ) -> Result<WireModQ, GarblerError> {
+        let tt = tt.ok_or(GarblerError::TruthTableRequired)?;
+        let gate_num = self.garbler.current_gate();
+        let delta_in = self.delta(x.modulus());
+        let delta_out = self.delta(q);
+        let (gate, res) = garble_proj_gate(gate_num, x, &delta_in, &delta_out, q, &tt);
+        self.add_gate_to_circuit(&gate)?;
+        Ok(res)
+    }
+}
+
+impl<N: Rep3Network> FancyModQ for Rep3ArithmeticGarbler<'_, '_, N> {
+    fn proj_from_binary(
+        &mut self,
+        x: &WireMod2,
+        q: u16,
+        tt: &[u16],
+    ) -> Result<WireModQ, GarblerError> {
+        let gate_num = self.garbler.current_gate();
+        let delta_in = self.garbler.delta;
+        let delta_out = self.delta(q);
+        let (gate, res) = garble_proj_gate(gate_num, x, &delta_in, &delta_out, q, tt);
+        self.add_gate_to_circuit(&gate)?;
+        Ok(res)
+    }
+
+    fn proj_to_binary(&mut self, x: &WireModQ, tt: &[u16]) -> Result<WireMod2, GarblerError> {
+        let gate_num = self.garbler.current_gate();
+        let delta_in = self.delta(x.modulus());
+        let delta_out = self.garbler.delta;
+        let (gate, res) = garble_proj_gate(gate_num, x, &delta_in, &delta_out, 2, tt);
+        self.add_gate_to_circuit(&gate)?;
+        Ok(res)
+    }
+}
+
+/// This struct extends a [Rep3Evaluator] by wires mod q.
+pub struct Rep3ArithmeticEvaluator<'a, 'b, N: Rep3Network> {
+    evaluator: &'a mut Rep3Evaluator<'b, N>,
+}
+
+impl<'a, 'b, N: Rep3Network> Rep3ArithmeticEvaluator<'a, 'b, N> {
+    /// Create a new evaluator for wires mod q, which reads its gates from the circuit of `evaluator`.
+    pub fn new(evaluator: &'a mut Rep3Evaluator<'b, N>) -> Self {
+        Self { evaluator }
+    }
+}
+
+impl<N: Rep3Network> Fancy for Rep3ArithmeticEvaluator<'_, '_, N> {
+    type Item = WireModQ;
+    type Error = EvaluatorError;
+
+    fn constant(&mut self, _: u16, q: u16) -> Result<WireModQ, EvaluatorError> {
+        let block = self.evaluator.get_block_from_circuit()?;
+        Ok(WireModQ::from_block(block, q))
+    }
+
+    fn output(&mut self, x: &WireModQ) -> Result<Option<u16>, EvaluatorError> {
+        let q = x.modulus();
+        let i = self.evaluator.current_output();
+
+        // Receive the output ciphertext from the garbler
+        let ct = self.evaluator.read_blocks_from_circuit(q as usize)?;
+
+        // Attempt to brute force x using the output ciphertext
+        let decoded = (0..q).find(|k| x.hash(output_tweak(i, *k)) == ct[*k as usize]);
+        match decoded {
+            Some(output) => Ok(Some(output)),
+            None => Err(EvaluatorError::DecodingFailed),
+        }
+    }
+}
+
+impl<N: Rep3Network> FancyArithmetic for Rep3ArithmeticEvaluator<'_, '_, N> {
+    fn add(&mut self, x: &WireModQ, y: &WireModQ) -> Result<WireModQ, EvaluatorError> {
+        if x.modulus() != y.modulus() {
+            return Err(EvaluatorError::from(FancyError::UnequalModuli));
+        }
+        Ok(x.plus(y))
+    }
+
+    fn sub(&mut self, x: &WireModQ, y: &WireModQ) -> Result<WireModQ, EvaluatorError> {
+        if x.modulus() != y.modulus() {
+            return Err(EvaluatorError::from(FancyError::UnequalModuli));
+        }
+        Ok(x.plus(&y.cmul(y.modulus() - 1)))
+    }
+
+    fn cmul(&mut self, x: &WireModQ, c: u16) -> Result<WireModQ, EvaluatorError> {
+        Ok(x.cmul(c % x.modulus()))
+    }
+
+    fn mul(&mut self, x: &WireModQ, y: &WireModQ) -> Result<WireModQ, EvaluatorError> {
+        if x.modulus() != y.modulus() {
+            return Err(EvaluatorError::from(FancyError::UnequalModuli));
+        }
+        let gate_num = self.evaluator.current_gate();
+        let gate = self
+            .evaluator
+            .read_blocks_from_circuit(2 * (x.modulus() as usize - 1))?;
+        Ok(evaluate_mul_gate(gate_num, x, y, &gate))
+    }
+
+    fn proj(
+        &mut self,
+        x: &WireModQ,
+        q: u16,
+        _tt: Option<Vec<u16>>,
+    ) -> Result<WireModQ, EvaluatorError> {
+        let gate_num = self.evaluator.current_gate();
+        let gate = self
+            .evaluator
+            .read_blocks_from_circuit(x.modulus() as usize - 1)?;
+        Ok(evaluate_proj_gate(gate_num, x, q, &gate))
+    }
+}
+
+impl<N: Rep3Network> FancyModQ for Rep3ArithmeticEvaluator<'_, '_, N> {
+    fn proj_from_binary(
+        &mut self,
+        x: &WireMod2,
+        q: u16,
+        _tt: &[u16],
+    ) -> Result<WireModQ, EvaluatorError> {
+        let gate_num = self.evaluator.current_gate();
+        let gate = self.evaluator.read_blocks_from_circuit(1)?;
+        Ok(evaluate_proj_gate(gate_num, x, q, &gate))
+    }
+
+    fn proj_to_binary(&mut self, x: &WireModQ, _tt: &[u16]) -> Result<WireMod2, EvaluatorError> {
+        let gate_num = self.evaluator.current_gate();
+        let gate = self
+            .evaluator
+            .read_blocks_from_circuit(x.modulus() as usize - 1)?;
+        Ok(evaluate_proj_gate(gate_num, x, 2, &gate))
+    }
+}
@@ -0,0 +1,258 @@
+//! Bristol Fashion
+//!
+//! This module contains a loader for boolean circuits in the [Bristol Fashion](https://nigelsmart.github.io/MPC-Circuits/) format and their execution as replicated 3-party garbled circuits. This allows to use custom boolean functions (e.g., hash functions or comparators) without writing the circuit by hand.
+//!
+//! The i-th wire of an input or output corresponds to the i-th least significant bit of the value.
+
+use super::{
+    circuits::GarbledCircuits,
+    evaluator::Rep3Evaluator,
+    garbler::Rep3Garbler,
+    topology::{CircuitTopology, TopologyRecorder, TopologyWire},
+    FancyBinaryLayer, GCUtils,
+};
+use crate::protocols::rep3::{
+    conversion, id::PartyID, network::IoContext, network::Rep3Network, yao, IoResult,
+    Rep3BigUintShare,
+};
+use ark_ff::PrimeField;
+use fancy_garbling::{BinaryBundle, Fancy, FancyBinary};
+use itertools::izip;
+use std::{io::BufRead, path::Path};
+
+/// A boolean circuit loaded from the Bristol Fashion format.
+#[derive(Debug)]
+pub struct BristolFashionCircuit {
+    topology: CircuitTopology,
+    input_sizes: Vec<usize>,
+    output_sizes: Vec<usize>,
+}
+
+fn invalid_circuit(msg: impl std::fmt::Display) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("Invalid Bristol Fashion circuit: {msg}"),
+    )
+}
+
+fn next_number<'a>(tokens: &mut impl Iterator<Item = &'a str>, what: &str) -> IoResult<usize> {
+    tokens
+        .next()
+        .ok_or_else(|| invalid_circuit(format!("missing {what}")))?
+        .parse()
+        .map_err(|_| invalid_circuit(format!("invalid {what}")))
+}
+
+impl BristolFashionCircuit {
+    /// Loads a circuit from a file in the Bristol Fashion format.
+    pub fn from_file(path: impl AsRef<Path>) -> IoResult<Self> {
+        let file = std::fs::File::open(path)?;
+        Self::from_reader(std::io::BufReader::new(file))
+    }
+
+    /// Loads a circuit in the Bristol Fashion format. Supported gates are XOR, AND, INV, EQ, EQW and MAND.
+    pub fn from_reader<R: BufRead>(reader: R) -> IoResult<Self> {
+        let mut content = String::new();
+        for line in reader.lines() {
+            content.push_str(&line?);
+            content.push('\n');
+        }
+        let mut tokens = content.split_whitespace();
+
+        let num_gates = next_number(&mut tokens, "number of gates")?;
+        let num_wires = next_number(&mut tokens, "number of wires")?;
+        let num_input_values = next_number(&mut tokens, "number of inputs")?;
+        let input_sizes = (0..num_input_values)
+            .map(|_| next_number(&mut tokens, "input size"))
+            .collect::<IoResult<Vec<_>>>()?;
+        let num_output_values = next_number(&mut tokens, "number of outputs")?;
+        let output_sizes = (0..num_output_values)
+            .map(|_| next_number(&mut tokens, "output size"))
+            .collect::<IoResult<Vec<_>>>()?;
+        let num_inputs = input_sizes.iter().sum::<usize>();
+        let num_outputs = output_sizes.iter().sum::<usize>();
+        if num_inputs + num_outputs > num_wires {
+            return Err(invalid_circuit("more inputs and outputs than wires"));
+        }
+
+        let (mut recorder, inputs) = TopologyRecorder::new(num_inputs);
+        let mut wires: Vec<Option<TopologyWire>> = vec![None; num_wires];
+        for (wire, input) in wires.iter_mut().zip(inputs) {
+            *wire = Some(input);
+        }
+
+        for _ in 0..num_gates {
+            let num_in = next_number(&mut tokens, "number of gate inputs")?;
+            let num_out = next_number(&mut tokens, "number of gate outputs")?;
+            let ids = (0..num_in + num_out)
+                .map(|_| next_number(&mut tokens, "wire id"))
+                .collect::<IoResult<Vec<_>>>()?;
+            if ids.iter().any(|&id| id >= num_wires) {
+                return Err(invalid_circuit("wire id out of range"));
+            }
+            let (ins, outs) = ids.split_at(num_in);
+            let kind = tokens
+                .next()
+                .ok_or_else(|| invalid_circuit("missing gate type"))?;
+            let wire = |wires: &[Option<TopologyWire>], id: usize| {
+                wires[id].ok_or_else(|| invalid_circuit(format!("wire {id} used before set")))
+            };
+
+            match (kind, num_in, num_out) {
+                ("XOR", 2, 1) => {
+                    let (a, b) = (wire(&wires, ins[0])?, wire(&wires, ins[1])?);
+                    wires[outs[0]] = Some(recorder.xor(&a, &b).map_err(invalid_circuit)?);
+                }
+                ("AND", 2, 1) => {
+                    let (a, b) = (wire(&wires, ins[0])?, wire(&wires, ins[1])?);
+                    wires[outs[0]] = Some(recorder.and(&a, &b).map_err(invalid_circuit)?);
+                }
+                ("INV", 1, 1) => {
+                    let a = wire(&wires, ins[0])?;
+                    wires[outs[0]] = Some(recorder.negate(&a).map_err(invalid_circuit)?);
+                }
+                ("EQW", 1, 1) => {
+                    wires[outs[0]] = Some(wire(&wires, ins[0])?);
+                }
+                ("EQ", 1, 1) => {
+                    // The input of EQ is the constant itself, not a wire
+                    let constant = match ins[0] {
+                        0 | 1 => ins[0] as u16,
+                        _ => return Err(invalid_circuit("EQ gate with non-binary constant")),
+                    };
+                    let res = recorder.constant(constant, 2).map_err(invalid_circuit)?;
+                    wires[outs[0]] = Some(res);
+                }
+                ("MAND", _, _) if num_in == 2 * num_out => {
+                    let (a, b) = ins.split_at(num_out);
+                    for (a, b, out) in izip!(a, b, outs) {
+                        let (a, b) = (wire(&wires, *a)?, wire(&wires, *b)?);
+                        wires[*out] = Some(recorder.and(&a, &b).map_err(invalid_circuit)?);
+                    }
+                }
+                _ => {
+                    return Err(invalid_circuit(format!(
+                        "unsupported gate {kind} with {num_in} inputs and {num_out} outputs"
+                    )))
+                }
+            }
+        }
+        if tokens.next().is_some() {
+            return Err(invalid_circuit("more gates than specified"));
+        }
+
+        let outputs = wires[num_wires - num_outputs..]
+            .iter()
+            .enumerate()
+            .map(|(i, wire)| wire.ok_or_else(|| invalid_circuit(format!("output {i} not set"))))
+            .collect::<IoResult<Vec<_>>>()?;
+
+        Ok(Self {
+            topology: recorder.finish(&outputs),
+            input_sizes,
+            output_sizes,
+        })
+    }
+
+    /// Returns the number of bits of each input value.
+    pub fn input_sizes(&self) -> &[usize] {
+        &self.input_sizes
+    }
+
+    /// Returns the number of bits of each output value.
+    pub fn output_sizes(&self) -> &[usize] {
+        &self.output_sizes
+    }
+
+    /// Executes the circuit on the given garbler or evaluator. There has to be one bundle per input value with the corresponding size.
+    pub fn execute<G: FancyBinaryLayer>(
+        &self,
+        g: &mut G,
+        inputs: &[BinaryBundle<G::Item>],
+    ) -> Result<Vec<BinaryBundle<G::Item>>, G::Error> {
+        assert_eq!(inputs.len(), self.input_sizes.len());
+        let mut input_wires = Vec::with_capacity(self.topology.num_inputs());
+        for (input, size) in izip!(inputs, &self.input_sizes) {
+            assert_eq!(input.size(), *size);
+            input_wires.extend_from_slice(input.wires());
+        }
+
+        let mut output_wires = self.topology.execute(g, &input_wires)?.into_iter();
+        Ok(self
+            .output_sizes
+            .iter()
+            .map(|size| BinaryBundle::new(output_wires.by_ref().take(*size).collect()))
+            .collect())
+    }
+}
+
+/// Executes a boolean circuit on binary shared inputs using replicated 3-party garbled circuits and returns binary sharings of the outputs. The i-th input has to be smaller than 2^`circuit.input_sizes()[i]`.
+pub fn execute_bristol_fashion<F: PrimeField, N: Rep3Network>(
+    circuit: &BristolFashionCircuit,
+    inputs: &[Rep3BigUintShare<F>],
+    io_context: &mut IoContext<N>,
+) -> IoResult<Vec<Rep3BigUintShare<F>>> {
+    if inputs.len() != circuit.input_sizes().len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "During execution of a Bristol Fashion circuit: Invalid number of inputs",
+        ));
+    }
+    if circuit
+        .input_sizes()
+        .iter()
+        .chain(circuit.output_sizes())
+        .any(|size| *size > F::MODULUS_BIT_SIZE as usize)
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "During execution of a Bristol Fashion circuit: Inputs and outputs must fit into a field element",
+        ));
+    }
+
+    let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
+    let mut shared_inputs = Vec::with_capacity(inputs.len());
+    for (x, bitlen) in izip!(inputs, circuit.input_sizes()) {
+        shared_inputs.push(yao::joint_input_binary_xored(
+            x, delta, io_context, *bitlen,
+        )?);
+    }
+
+    let outputs = match io_context.id {
+        PartyID::ID0 => {
+            let mut evaluator = Rep3Evaluator::new(io_context);
+            evaluator.receive_circuit()?;
+            let inputs = shared_inputs
+                .iter()
+                .map(|[x01, x2]| GarbledCircuits::xor_many(&mut evaluator, x01, x2))
+                .collect::<Result<Vec<_>, _>>();
+            let inputs = GCUtils::garbled_circuits_error(inputs)?;
+            let res = circuit.execute(&mut evaluator, &inputs);
+            GCUtils::garbled_circuits_error(res)?
+        }
+        PartyID::ID1 | PartyID::ID2 => {
+            let delta = match delta {
+                Some(delta) => delta,
+                None => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "No delta provided",
+                ))?,
+            };
+            let mut garbler = Rep3Garbler::new_with_delta(io_context, delta);
+            let inputs = shared_inputs
+                .iter()
+                .map(|[x01, x2]| GarbledCircuits::xor_many(&mut garbler, x01, x2))
+                .collect::<Result<Vec<_>, _>>();
+            let inputs = GCUtils::garbled_circuits_error(inputs)?;
+            let res = circuit.execute(&mut garbler, &inputs);
+            let res = GCUtils::garbled_circuits_error(res)?;
+            garbler.send_circuit()?;
+            res
+        }
+    };
+
+    outputs
+        .into_iter()
+        .map(|y| conversion::y2b::<F, N>(y, io_context))
+        .collect()
+}
@@ -2,9 +2,11 @@
 //!
 //! This module contains some garbled circuit implementations.
 
-use crate::protocols::rep3::yao::GCUtils;
+use crate::protocols::rep3::yao::{
+    arithmetic::FancyModQ, topology::TopologyCache, FancyBinaryLayer, GCUtils,
+};
 use ark_ff::PrimeField;
-use fancy_garbling::{BinaryBundle, FancyBinary};
+use fancy_garbling::{BinaryBundle, FancyBinary, HasModulus, WireMod2, WireModQ};
 use itertools::izip;
 use num_bigint::BigUint;
 
@@ -158,7 +160,7 @@ impl GarbledCircuits {
 
     /// Binary subtraction. Returns the result and whether it underflowed.
     /// I.e., calculates 2^k + x1 - x2
-    #[expect(dead_code, clippy::type_complexity)]
+    #[expect(clippy::type_complexity)]
     fn bin_subtraction<G: FancyBinary>(
         g: &mut G,
         xs: &[G::Item],
@@ -297,6 +299,145 @@ impl GarbledCircuits {
         Ok(BinaryBundle::new(res))
     }
 
+    /// Adds two shared field elements mod p, like [Self::adder_mod_p]. The topology of the circuit is only recorded once per field and then replayed from the [TopologyCache], where the AND gates of the same AND-depth are processed as one layer (see [FancyBinaryLayer::and_layer]).
+    pub fn adder_mod_p_cached<G: FancyBinaryLayer, F: PrimeField>(
+        g: &mut G,
+        wires_a: &BinaryBundle<G::Item>,
+        wires_b: &BinaryBundle<G::Item>,
+    ) -> Result<BinaryBundle<G::Item>, G::Error> {
+        let bitlen = wires_a.size();
+        debug_assert_eq!(bitlen, wires_b.size());
+        let topology = TopologyCache::global()
+            .get_or_record::<F>("adder_mod_p", 2 * bitlen, |recorder, inputs| {
+                let (a, b) = inputs.split_at(bitlen);
+                let res = Self::adder_mod_p::<_, F>(
+                    recorder,
+                    &BinaryBundle::new(a.to_vec()),
+                    &BinaryBundle::new(b.to_vec()),
+                )?;
+                Ok(res.wires().to_vec())
+            })
+            .expect("recording a topology does not fail");
+
+        let inputs = wires_a
+            .wires()
+            .iter()
+            .chain(wires_b.wires())
+            .cloned()
+            .collect::<Vec<_>>();
+        Ok(BinaryBundle::new(topology.execute(g, &inputs)?))
+    }
+
+    /// Adds many pairs of shared field elements mod p. The i-th field element is encoded by the i-th chunk of [F::MODULUS_BIT_SIZE] wires of a bundle. In contrast to calling [Self::adder_mod_p] for each pair, the circuit is built layer by layer, such that the AND gates of the same bit of all values form one layer (see [FancyBinaryLayer::and_layer]) and can be garbled in parallel.
+    pub fn adder_mod_p_many<G: FancyBinaryLayer, F: PrimeField>(
+        g: &mut G,
+        wires_a: &BinaryBundle<G::Item>,
+        wires_b: &BinaryBundle<G::Item>,
+    ) -> Result<BinaryBundle<G::Item>, G::Error> {
+        let bitlen = F::MODULUS_BIT_SIZE as usize;
+        debug_assert_eq!(wires_a.size(), wires_b.size());
+        debug_assert_eq!(wires_a.size() % bitlen, 0);
+        let wires_a = wires_a.wires();
+        let wires_b = wires_b.wires();
+        let num = wires_a.len() / bitlen;
+        if num == 0 {
+            return Ok(BinaryBundle::new(Vec::new()));
+        }
+        // The i-th layer consists of the i-th bit of all values
+        let bit = |wires: &[G::Item], i: usize| -> Vec<G::Item> {
+            (0..num).map(|j| wires[j * bitlen + i].to_owned()).collect()
+        };
+
+        // First addition: Layer-wise ripple carry adder
+        let mut added = Vec::with_capacity(bitlen);
+        let (a, b) = (bit(wires_a, 0), bit(wires_b, 0));
+        let s = izip!(&a, &b)
+            .map(|(a, b)| g.xor(a, b))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut c = g.and_layer(&a, &b)?;
+        added.push(s);
+        for i in 1..bitlen {
+            let (a, b) = (bit(wires_a, i), bit(wires_b, i));
+            let mut s = Vec::with_capacity(num);
+            let mut z1 = Vec::with_capacity(num);
+            let mut z3 = Vec::with_capacity(num);
+            for (a, b, c) in izip!(&a, &b, &c) {
+                let z = g.xor(a, b)?;
+                s.push(g.xor(&z, c)?);
+                z1.push(z);
+                z3.push(g.xor(a, c)?);
+            }
+            let z4 = g.and_layer(&z1, &z3)?;
+            c = izip!(&z4, &a)
+                .map(|(z4, a)| g.xor(z4, a))
+                .collect::<Result<Vec<_>, _>>()?;
+            added.push(s);
+        }
+        let carry_add = c;
+
+        // Prepare p for subtraction
+        let new_bitlen = bitlen + 1;
+        let p_ = (BigUint::from(1u64) << new_bitlen) - F::MODULUS.into();
+        let p_bits = GCUtils::biguint_to_bits(p_, new_bitlen);
+
+        // Subtract p, again layer-wise
+        let mut subtracted = Vec::with_capacity(bitlen);
+        debug_assert!(p_bits[0]);
+        subtracted.push(
+            added[0]
+                .iter()
+                .map(|a| g.negate(a))
+                .collect::<Result<Vec<_>, _>>()?,
+        );
+        let mut c = added[0].to_owned();
+        for (a, b) in added.iter().zip(p_bits.iter()).skip(1) {
+            let mut s = Vec::with_capacity(num);
+            let mut z1 = Vec::with_capacity(num);
+            let mut z3 = Vec::with_capacity(num);
+            for (a, c) in izip!(a, &c) {
+                if *b {
+                    let z = g.negate(a)?;
+                    s.push(g.xor(&z, c)?);
+                    z1.push(z);
+                    z3.push(g.xor(a, c)?);
+                } else {
+                    let s_ = g.xor(a, c)?;
+                    z1.push(a.to_owned());
+                    z3.push(s_.to_owned());
+                    s.push(s_);
+                }
+            }
+            let z4 = g.and_layer(&z1, &z3)?;
+            c = izip!(&z4, a)
+                .map(|(z4, a)| g.xor(z4, a))
+                .collect::<Result<Vec<_>, _>>()?;
+            subtracted.push(s);
+        }
+        let mut ov = Vec::with_capacity(num);
+        for (carry, c) in izip!(carry_add, &c) {
+            let z = if p_bits[bitlen] {
+                g.negate(&carry)?
+            } else {
+                carry
+            };
+            ov.push(g.xor(&z, c)?);
+        }
+
+        // Multiplex for the result, i.e., mux(ov, subtracted, added)
+        let mut result = vec![Vec::with_capacity(bitlen); num];
+        for (s, a) in izip!(&subtracted, &added) {
+            let xor = izip!(s, a)
+                .map(|(s, a)| g.xor(s, a))
+                .collect::<Result<Vec<_>, _>>()?;
+            let and = g.and_layer(&ov, &xor)?;
+            for (res, and, s) in izip!(result.iter_mut(), &and, s) {
+                res.push(g.xor(and, s)?);
+            }
+        }
+
+        Ok(BinaryBundle::new(result.into_iter().flatten().collect()))
+    }
+
     /// Adds two shared ring elements mod 2^k. The ring elements are encoded as Yao shared wires
     pub fn adder_mod_2k<G: FancyBinary>(
         g: &mut G,
@@ -1017,6 +1158,203 @@ impl GarbledCircuits {
 
         Ok(BinaryBundle::new(results))
     }
+
+    /// Compares two field elements, i.e., computes lhs >= rhs. The field elements are represented as two bitdecompositions which need to be added first. The resulting bit is composed to a field element using wires_c.
+    fn field_ge<G: FancyBinary, F: PrimeField>(
+        g: &mut G,
+        lhs_a: &[G::Item],
+        lhs_b: &[G::Item],
+        rhs_a: &[G::Item],
+        rhs_b: &[G::Item],
+        wires_c: &[G::Item],
+    ) -> Result<Vec<G::Item>, G::Error> {
+        let input_bitlen = F::MODULUS_BIT_SIZE as usize;
+        debug_assert_eq!(lhs_a.len(), input_bitlen);
+        debug_assert_eq!(rhs_a.len(), input_bitlen);
+        debug_assert_eq!(wires_c.len(), input_bitlen);
+
+        let lhs = Self::adder_mod_p_with_output_size::<_, F>(g, lhs_a, lhs_b, input_bitlen)?;
+        let rhs = Self::adder_mod_p_with_output_size::<_, F>(g, rhs_a, rhs_b, input_bitlen)?;
+        let ge = Self::unsigned_ge(g, &lhs, &rhs)?;
+
+        // compose the bit again
+        Self::compose_field_element::<G, F>(g, &[ge], wires_c)
+    }
+
+    /// Compares two vectors of field elements element-wise, i.e., computes lhs >= rhs. The first half of wires_a and wires_b contains the bitdecompositions of lhs, the second half the ones of rhs, which need to be added first. The resulting bits are composed to field elements using wires_c, whereas wires_c contains one field element per comparison.
+    pub(crate) fn field_ge_many<G: FancyBinary, F: PrimeField>(
+        g: &mut G,
+        wires_a: &BinaryBundle<G::Item>,
+        wires_b: &BinaryBundle<G::Item>,
+        wires_c: &BinaryBundle<G::Item>,
+    ) -> Result<BinaryBundle<G::Item>, G::Error> {
+        debug_assert_eq!(wires_a.size(), wires_b.size());
+        let input_size = wires_a.size();
+        let input_bitlen = F::MODULUS_BIT_SIZE as usize;
+
+        debug_assert_eq!(input_size % (2 * input_bitlen), 0);
+        debug_assert_eq!(wires_c.size() * 2, input_size);
+
+        let (lhs_a, rhs_a) = wires_a.wires().split_at(input_size / 2);
+        let (lhs_b, rhs_b) = wires_b.wires().split_at(input_size / 2);
+
+        let mut results = Vec::with_capacity(wires_c.size());
+
+        for (lhs_a, lhs_b, rhs_a, rhs_b, chunk_c) in izip!(
+            lhs_a.chunks(input_bitlen),
+            lhs_b.chunks(input_bitlen),
+            rhs_a.chunks(input_bitlen),
+            rhs_b.chunks(input_bitlen),
+            wires_c.wires().chunks(input_bitlen),
+        ) {
+            results.extend(Self::field_ge::<G, F>(
+                g, lhs_a, lhs_b, rhs_a, rhs_b, chunk_c,
+            )?);
+        }
+
+        Ok(BinaryBundle::new(results))
+    }
+
+    /// Divides two field elements, rounding down. The dividend and the divisor are each represented as two bitdecompositions which need to be added first. Only the lowest dividend_bitlen bits of the dividend and divisor_bitlen bits of the divisor are considered. The output is composed using wires_c.
+    #[expect(clippy::too_many_arguments)]
+    fn field_int_div<G: FancyBinary, F: PrimeField>(
+        g: &mut G,
+        dividend_a: &[G::Item],
+        dividend_b: &[G::Item],
+        divisor_a: &[G::Item],
+        divisor_b: &[G::Item],
+        wires_c: &[G::Item],
+        dividend_bitlen: usize,
+        divisor_bitlen: usize,
+    ) -> Result<Vec<G::Item>, G::Error> {
+        let input_bitlen = F::MODULUS_BIT_SIZE as usize;
+        debug_assert_eq!(dividend_a.len(), input_bitlen);
+        debug_assert_eq!(divisor_a.len(), input_bitlen);
+        debug_assert_eq!(wires_c.len(), input_bitlen);
+        debug_assert!(dividend_bitlen <= input_bitlen);
+        debug_assert!(divisor_bitlen <= input_bitlen);
+
+        let dividend =
+            Self::adder_mod_p_with_output_size::<_, F>(g, dividend_a, dividend_b, dividend_bitlen)?;
+        let mut divisor =
+            Self::adder_mod_p_with_output_size::<_, F>(g, divisor_a, divisor_b, divisor_bitlen)?;
+
+        // Restoring division: The remainder is smaller than the divisor, so it fits into one more bit than the divisor after the shift
+        let zero = g.xor(&dividend[0], &dividend[0])?;
+        divisor.push(zero.to_owned());
+        let mut remainder = vec![zero.to_owned(); divisor_bitlen + 1];
+        let mut quotient = vec![zero; dividend_bitlen];
+        for (q, n) in quotient.iter_mut().zip(dividend.iter()).rev() {
+            // The msb of the remainder is zero, so we can just shift it out
+            remainder.pop();
+            remainder.insert(0, n.to_owned());
+            let (subtracted, ge) = Self::bin_subtraction(g, &remainder, &divisor)?;
+            for (r, s) in remainder.iter_mut().zip(subtracted.iter()) {
+                // CMUX
+                *r = g.mux(&ge, r, s)?;
+            }
+            *q = ge;
+        }
+
+        // compose the quotient again
+        Self::compose_field_element::<G, F>(g, &quotient, wires_c)
+    }
+
+    /// Divides a vector of field elements by another one, rounding down. The first half of wires_a and wires_b contains the bitdecompositions of the dividends, the second half the ones of the divisors, which need to be added first. The output is composed using wires_c, whereas wires_c contains one field element per division.
+    pub(crate) fn field_int_div_many<G: FancyBinary, F: PrimeField>(
+        g: &mut G,
+        wires_a: &BinaryBundle<G::Item>,
+        wires_b: &BinaryBundle<G::Item>,
+        wires_c: &BinaryBundle<G::Item>,
+        dividend_bitlen: usize,
+        divisor_bitlen: usize,
+    ) -> Result<BinaryBundle<G::Item>, G::Error> {
+        debug_assert_eq!(wires_a.size(), wires_b.size());
+        let input_size = wires_a.size();
+        let input_bitlen = F::MODULUS_BIT_SIZE as usize;
+
+        debug_assert_eq!(input_size % (2 * input_bitlen), 0);
+        debug_assert_eq!(wires_c.size() * 2, input_size);
+
+        let (dividends_a, divisors_a) = wires_a.wires().split_at(input_size / 2);
+        let (dividends_b, divisors_b) = wires_b.wires().split_at(input_size / 2);
+
+        let mut results = Vec::with_capacity(wires_c.size());
+
+        for (dividend_a, dividend_b, divisor_a, divisor_b, chunk_c) in izip!(
+            dividends_a.chunks(input_bitlen),
+            dividends_b.chunks(input_bitlen),
+            divisors_a.chunks(input_bitlen),
+            divisors_b.chunks(input_bitlen),
+            wires_c.wires().chunks(input_bitlen),
+        ) {
+            results.extend(Self::field_int_div::<G, F>(
+                g,
+                dividend_a,
+                dividend_b,
+                divisor_a,
+                divisor_b,
+                chunk_c,
+                dividend_bitlen,
+                divisor_bitlen,
+            )?);
+        }
+
+        Ok(BinaryBundle::new(results))
+    }
+
+    /// Composes the binary wires `bits` (least significant bit first) to a wire mod q, i.e., the result encodes the value of the bits mod q. Each bit is projected to the wire mod q with the corresponding power of two, so the composition requires one projection gate per bit.
+    pub fn bits_to_mod_q<G: FancyModQ>(
+        g: &mut G,
+        bits: &[WireMod2],
+        q: u16,
+    ) -> Result<WireModQ, G::Error> {
+        assert!(!bits.is_empty(), "At least one bit is required");
+        let mut res: Option<WireModQ> = None;
+        let mut power = 1 % q;
+        for bit in bits {
+            let summand = g.proj_from_binary(bit, q, &[0, power])?;
+            res = Some(match res {
+                Some(res) => g.add(&res, &summand)?,
+                None => summand,
+            });
+            power = ((power as u32 * 2) % q as u32) as u16;
+        }
+        Ok(res.expect("At least one bit is required"))
+    }
+
+    /// Decomposes the wire `x` mod q into its `n_bits` least significant bits. Each bit requires one projection gate.
+    pub fn mod_q_to_bits<G: FancyModQ>(
+        g: &mut G,
+        x: &WireModQ,
+        n_bits: usize,
+    ) -> Result<BinaryBundle<WireMod2>, G::Error> {
+        let q = x.modulus();
+        let mut res = Vec::with_capacity(n_bits);
+        for i in 0..n_bits {
+            let tt = (0..q)
+                .map(|v| (v as u32 >> i) as u16 & 1)
+                .collect::<Vec<_>>();
+            res.push(g.proj_to_binary(x, &tt)?);
+        }
+        Ok(BinaryBundle::new(res))
+    }
+
+    /// Multiplies two binary encoded values of k bits (least significant bit first) mod 2^k using wires mod 2^k (with 2 <= k <= 8). Instead of a boolean multiplier with O(k^2) AND gates, the values are composed to wires mod 2^k, multiplied with one arithmetic gate and decomposed again.
+    pub fn mul_mod_2k<G: FancyModQ>(
+        g: &mut G,
+        a: &[WireMod2],
+        b: &[WireMod2],
+    ) -> Result<BinaryBundle<WireMod2>, G::Error> {
+        let k = a.len();
+        assert_eq!(k, b.len());
+        assert!((2..=8).contains(&k), "Only 2 <= k <= 8 is supported");
+        let q = 1 << k;
+        let a = Self::bits_to_mod_q(g, a, q)?;
+        let b = Self::bits_to_mod_q(g, b, q)?;
+        let c = g.mul(&a, &b)?;
+        Self::mod_q_to_bits(g, &c, k)
+    }
 }
 
 #[cfg(test)]
@@ -4,7 +4,7 @@
 //!
 //! This file is heavily inspired by [fancy-garbling](https://github.com/GaloisInc/swanky/blob/dev/fancy-garbling/src/garble/evaluator.rs)
 
-use super::GCUtils;
+use super::{FancyBinaryLayer, GCUtils, GcConsistencyError};
 use crate::protocols::rep3::{
     id::PartyID,
     network::{IoContext, Rep3Network},
@@ -14,6 +14,7 @@ use fancy_garbling::{
     errors::EvaluatorError, util::output_tweak, BinaryBundle, Fancy, FancyBinary, WireLabel,
     WireMod2,
 };
+use rayon::prelude::*;
 use scuttlebutt::Block;
 use sha3::{Digest, Sha3_256};
 
@@ -24,6 +25,12 @@ pub struct Rep3Evaluator<'a, N: Rep3Network> {
     current_gate: usize,
     circuit: Vec<[u8; 16]>,
     current_circuit_element: usize,
+    chunk_size: Option<usize>,
+    circuit_finished: bool, // In chunked mode: whether the last chunk of the circuit was received
+    privacy_free: bool,
+    privacy_free_one: Option<WireMod2>, // The constant 1, used to negate in the privacy-free mode
+    received_blocks: usize,             // Number of blocks received and checked so far
+    checked_chunks: usize,              // Number of hashes of ID2 checked so far
 }
 
 impl<'a, N: Rep3Network> Rep3Evaluator<'a, N> {
@@ -40,11 +47,49 @@ impl<'a, N: Rep3Network> Rep3Evaluator<'a, N> {
             current_gate: 0,
             circuit: Vec::new(),
             current_circuit_element: 0,
+            chunk_size: None,
+            circuit_finished: true,
+            privacy_free: false,
+            privacy_free_one: None,
+            received_blocks: 0,
+            checked_chunks: 0,
+        }
+    }
+
+    /// Enables the chunked transmission mode: Instead of receiving the whole garbled circuit at once, the chunks of `chunk_size` blocks are received from ID1 when they are required for the evaluation and each chunk is checked against the hash sent by ID2. The garblers have to be created with the same chunk size using [Rep3Garbler::with_chunk_size](super::garbler::Rep3Garbler::with_chunk_size).
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "Chunk size must be greater than 0");
+        self.chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// Enables or disables the privacy-free mode, in which AND gates consist of a single ciphertext and the color of each label is the value of the wire. The garblers have to switch the mode at the same point of the circuit using [Rep3Garbler::set_privacy_free](super::garbler::Rep3Garbler::set_privacy_free), which also describes when the mode is safe to use.
+    pub fn set_privacy_free(&mut self, privacy_free: bool) {
+        self.privacy_free = privacy_free;
+    }
+
+    /// Returns whether the privacy-free mode is enabled.
+    pub fn is_privacy_free(&self) -> bool {
+        self.privacy_free
+    }
+
+    /// Returns the constant 1 used to negate in the privacy-free mode. It is read from the circuit on first use.
+    fn privacy_free_one(&mut self) -> Result<WireMod2, EvaluatorError> {
+        match self.privacy_free_one {
+            Some(one) => Ok(one),
+            None => {
+                let one = self.constant(1, 2)?;
+                self.privacy_free_one = Some(one);
+                Ok(one)
+            }
         }
     }
 
     /// Get a gate from the circuit.
-    fn get_block_from_circuit(&mut self) -> IoResult<Block> {
+    pub(crate) fn get_block_from_circuit(&mut self) -> IoResult<Block> {
+        if self.current_circuit_element >= self.circuit.len() && !self.circuit_finished {
+            self.receive_chunk()?;
+        }
         if self.current_circuit_element >= self.circuit.len() {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
@@ -59,11 +104,62 @@ impl<'a, N: Rep3Network> Rep3Evaluator<'a, N> {
         Ok(block)
     }
 
-    /// Receive the garbled circuit from the garblers.
+    /// Receive the garbled circuit from the garblers. In chunked mode, this only prepares receiving the chunks, which are then received lazily during the evaluation.
     pub fn receive_circuit(&mut self) -> IoResult<()> {
         debug_assert!(self.circuit.is_empty());
-        self.circuit = self.io_context.network.recv_many(PartyID::ID1)?;
         self.current_circuit_element = 0;
+        if self.chunk_size.is_some() {
+            debug_assert!(self.circuit_finished);
+            self.circuit_finished = false;
+            return Ok(());
+        }
+        self.receive_and_check_blocks()
+    }
+
+    /// Receives the remaining chunks of the garbled circuit in chunked mode. This has to be called after the evaluation if the circuit is not opened via [Self::output_all_parties] or [Self::output_to_id0_and_id1], since the garblers always finish a circuit with a (possibly empty) chunk. Does nothing if the chunked mode is not enabled.
+    pub fn finish_circuit(&mut self) -> IoResult<()> {
+        if self.chunk_size.is_none() {
+            return Ok(());
+        }
+        while !self.circuit_finished {
+            self.receive_chunk()?;
+        }
+        if self.current_circuit_element != self.circuit.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Too many gates in circuits.",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Receives the next chunk of the garbled circuit from ID1 and its hash from ID2. A chunk smaller than the chunk size marks the end of the circuit.
+    fn receive_chunk(&mut self) -> IoResult<()> {
+        let chunk_size = self.chunk_size.expect("chunked mode is enabled");
+        if self.current_circuit_element != self.circuit.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Too many gates in circuits.",
+            ));
+        }
+        self.current_circuit_element = 0;
+        self.receive_and_check_blocks()?;
+        match self.circuit.len().cmp(&chunk_size) {
+            std::cmp::Ordering::Less => self.circuit_finished = true,
+            std::cmp::Ordering::Equal => {}
+            std::cmp::Ordering::Greater => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "During receiving a chunk of the garbled circuit: Invalid number of elements received",
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Receives blocks from ID1 and checks them against the hash sent by ID2. Returns a [GcConsistencyError] identifying the blocks if the check fails.
+    fn receive_and_check_blocks(&mut self) -> IoResult<()> {
+        self.circuit = self.io_context.network.recv_many(PartyID::ID1)?;
 
         let mut hasher = Sha3_256::default();
         for block in &self.circuit {
@@ -72,25 +168,29 @@ impl<'a, N: Rep3Network> Rep3Evaluator<'a, N> {
         let is_hash = hasher.finalize();
         let should_hash: Vec<u8> = self.io_context.network.recv(PartyID::ID2)?;
 
+        let blocks = self.received_blocks..self.received_blocks + self.circuit.len();
         if should_hash != is_hash.as_slice() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Inconsistent Garbled Circuits: Hashes do not match!",
-            ));
+            return Err(GcConsistencyError::HashMismatch {
+                chunk: self.checked_chunks,
+                blocks,
+            }
+            .into());
         }
+        self.received_blocks = blocks.end;
+        self.checked_chunks += 1;
 
         Ok(())
     }
 
     /// The current non-free gate index of the garbling computation.
-    fn current_gate(&mut self) -> usize {
+    pub(crate) fn current_gate(&mut self) -> usize {
         let current = self.current_gate;
         self.current_gate += 1;
         current
     }
 
     /// The current output index of the garbling computation.
-    fn current_output(&mut self) -> usize {
+    pub(crate) fn current_output(&mut self) -> usize {
         let current = self.current_output;
         self.current_output += 1;
         current
@@ -123,23 +223,8 @@ impl<'a, N: Rep3Network> Rep3Evaluator<'a, N> {
         }
     }
 
-    /// Outputs the values to the garbler.
-    fn output_garbler(&mut self, x: &[WireMod2]) -> IoResult<()> {
-        let mut blocks = Vec::with_capacity(x.len());
-        for val in x {
-            let block = val.as_block();
-            let mut gate = [0; 16];
-            gate.copy_from_slice(block.as_ref());
-            blocks.push(gate);
-        }
-        self.io_context.network.send_many(PartyID::ID1, &blocks)?;
-        self.io_context.network.send_many(PartyID::ID2, &blocks)?;
-
-        Ok(())
-    }
-
-    /// Outputs the values to the garbler with id1.
-    fn output_garbler_id1(&mut self, x: &[WireMod2]) -> IoResult<()> {
+    /// Sends the output labels to the garbler with the given id.
+    fn output_garbler(&mut self, x: &[WireMod2], id: PartyID) -> IoResult<()> {
         let mut blocks = Vec::with_capacity(x.len());
         for val in x {
             let block = val.as_block();
@@ -147,36 +232,60 @@ impl<'a, N: Rep3Network> Rep3Evaluator<'a, N> {
             gate.copy_from_slice(block.as_ref());
             blocks.push(gate);
         }
-        self.io_context.network.send_many(PartyID::ID1, &blocks)?;
+        self.io_context.network.send_many(id, &blocks)?;
 
         Ok(())
     }
 
     /// Outputs the value to all parties
     pub fn output_all_parties(&mut self, x: &[WireMod2]) -> IoResult<Vec<bool>> {
-        // Garbler's to evaluator
-        let res = self.output_evaluator(x)?;
-
-        // Evaluator to garbler
-        self.output_garbler(x)?;
-
-        Ok(res)
+        let res = self.output_to(x, &[PartyID::ID0, PartyID::ID1, PartyID::ID2])?;
+        Ok(res.expect("all parties receive the output"))
     }
 
     /// Outputs the value to parties ID0 and ID1
     pub fn output_to_id0_and_id1(&mut self, x: &[WireMod2]) -> IoResult<Vec<bool>> {
+        let res = self.output_to(x, &[PartyID::ID0, PartyID::ID1])?;
+        Ok(res.expect("ID0 receives the output"))
+    }
+
+    /// Outputs the value to the given parties. Returns the result if the evaluator (ID0) is one of them. All parties have to call this function with the same parties.
+    pub fn output_to(
+        &mut self,
+        x: &[WireMod2],
+        parties: &[PartyID],
+    ) -> IoResult<Option<Vec<bool>>> {
         // Garbler's to evaluator
-        let res = self.output_evaluator(x)?;
+        let res = if parties.contains(&PartyID::ID0) {
+            Some(self.output_evaluator(x)?)
+        } else {
+            None
+        };
+
+        // Receive the rest of a chunked circuit before releasing the result
+        self.finish_circuit()?;
 
         // Evaluator to garbler
-        self.output_garbler_id1(x)?;
+        for id in [PartyID::ID1, PartyID::ID2] {
+            if parties.contains(&id) {
+                self.output_garbler(x, id)?;
+            }
+        }
 
         Ok(res)
     }
 
+    /// Outputs the value to a receiver outside of the three parties. Returns the color bits of the output labels, which have to be sent to the receiver together with the decoding information of both garblers (see [Rep3Garbler::output_to_external](super::garbler::Rep3Garbler::output_to_external)).
+    pub fn output_to_external(&mut self, x: &[WireMod2]) -> IoResult<Vec<bool>> {
+        // Receive the rest of a chunked circuit before releasing the labels
+        self.finish_circuit()?;
+
+        Ok(x.iter().map(|label| label.color() == 1).collect())
+    }
+
     /// Read `n` `Block`s from the channel.
     #[inline(always)]
-    fn read_blocks_from_circuit(&mut self, n: usize) -> IoResult<Vec<Block>> {
+    pub(crate) fn read_blocks_from_circuit(&mut self, n: usize) -> IoResult<Vec<Block>> {
         (0..n).map(|_| self.get_block_from_circuit()).collect()
     }
 
@@ -248,8 +357,12 @@ impl<N: Rep3Network> Fancy for Rep3Evaluator<'_, N> {
 }
 
 impl<N: Rep3Network> FancyBinary for Rep3Evaluator<'_, N> {
-    /// Negate is a noop for the evaluator
+    /// Negate is a noop for the evaluator, except for the privacy-free mode
     fn negate(&mut self, x: &Self::Item) -> Result<Self::Item, Self::Error> {
+        if self.privacy_free {
+            let one = self.privacy_free_one()?;
+            return self.xor(&one, x);
+        }
         Ok(*x)
     }
 
@@ -258,8 +371,42 @@ impl<N: Rep3Network> FancyBinary for Rep3Evaluator<'_, N> {
     }
 
     fn and(&mut self, a: &Self::Item, b: &Self::Item) -> Result<Self::Item, Self::Error> {
+        if self.privacy_free {
+            let gate = self.get_block_from_circuit()?;
+            let gate_num = self.current_gate();
+            return Ok(GCUtils::evaluate_and_gate_privacy_free(
+                gate_num, a, b, &gate,
+            ));
+        }
         let gate0 = self.get_block_from_circuit()?;
         let gate1 = self.get_block_from_circuit()?;
         Ok(self.evaluate_and_gate(a, b, &gate0, &gate1))
     }
 }
+
+impl<N: Rep3Network> FancyBinaryLayer for Rep3Evaluator<'_, N> {
+    fn and_layer(
+        &mut self,
+        a: &[Self::Item],
+        b: &[Self::Item],
+    ) -> Result<Vec<Self::Item>, Self::Error> {
+        debug_assert_eq!(a.len(), b.len());
+        if self.privacy_free {
+            return a.iter().zip(b).map(|(a, b)| self.and(a, b)).collect();
+        }
+        let gates = self.read_blocks_from_circuit(2 * a.len())?;
+        let first_gate = self.current_gate;
+        self.current_gate += a.len();
+
+        let result = a
+            .par_iter()
+            .zip(b.par_iter())
+            .zip(gates.par_chunks_exact(2))
+            .enumerate()
+            .map(|(i, ((a, b), gates))| {
+                GCUtils::evaluate_and_gate(first_gate + i, a, b, &gates[0], &gates[1])
+            })
+            .collect();
+        Ok(result)
+    }
+}
@@ -4,7 +4,7 @@
 //!
 //! This implementation is heavily inspired by [fancy-garbling](https://github.com/GaloisInc/swanky/blob/dev/fancy-garbling/src/garble/garbler.rs)
 
-use super::{GCInputs, GCUtils};
+use super::{FancyBinaryLayer, GCInputs, GCUtils};
 use crate::{
     protocols::rep3::{
         id::PartyID,
@@ -19,8 +19,15 @@ use fancy_garbling::{
     errors::GarblerError, util::output_tweak, BinaryBundle, Fancy, FancyBinary, WireLabel, WireMod2,
 };
 use rand::SeedableRng;
+use rayon::prelude::*;
 use scuttlebutt::Block;
 use sha3::{Digest, Sha3_256};
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 /// This struct implements the garbler for replicated 3-party garbled circuits as described in [ABY3](https://eprint.iacr.org/2018/403.pdf).
 pub struct Rep3Garbler<'a, N: Rep3Network> {
@@ -31,6 +38,13 @@ pub struct Rep3Garbler<'a, N: Rep3Network> {
     pub(crate) rng: RngType,
     hash: Sha3_256, // For the ID2 to match everything sent with one hash
     circuit: Vec<[u8; 16]>,
+    chunk_size: Option<usize>,
+    hashed_blocks: usize, // Number of blocks ID2 has hashed for the current chunk
+    thread_pool: Option<rayon::ThreadPool>,
+    privacy_free: bool,
+    privacy_free_one: Option<WireMod2>, // The constant 1 with color 0, used to negate in the privacy-free mode
+    memory_budget: Option<usize>,       // Maximum number of blocks ID1 buffers in memory
+    spill_file: Option<SpillFile>,      // The blocks of the circuit that exceeded the memory budget
 }
 
 impl<'a, N: Rep3Network> Rep3Garbler<'a, N> {
@@ -55,11 +69,53 @@ impl<'a, N: Rep3Network> Rep3Garbler<'a, N> {
             rng,
             hash: Sha3_256::default(),
             circuit: Vec::new(),
+            chunk_size: None,
+            hashed_blocks: 0,
+            thread_pool: None,
+            privacy_free: false,
+            privacy_free_one: None,
+            memory_budget: None,
+            spill_file: None,
         }
     }
 
+    /// Garbles the layers of independent AND gates (see [FancyBinaryLayer::and_layer]) with a dedicated pool of `num_threads` worker threads instead of the global rayon thread pool. The garbled gates are still given to the network in gate order.
+    pub fn with_num_threads(mut self, num_threads: usize) -> IoResult<Self> {
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .map_err(std::io::Error::other)?;
+        self.thread_pool = Some(thread_pool);
+        Ok(self)
+    }
+
+    /// Enables the chunked transmission mode: Instead of buffering the whole garbled circuit, ID1 sends the garbled gates to the evaluator in chunks of `chunk_size` blocks as soon as they are produced, while ID2 sends a hash for each chunk. Sending a chunk blocks once the buffers of the network layer are full, which bounds the memory of the garbler. The evaluator has to be created with the same chunk size using [Rep3Evaluator::with_chunk_size](super::evaluator::Rep3Evaluator::with_chunk_size).
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "Chunk size must be greater than 0");
+        self.chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// Sets a memory budget of `memory_budget` bytes for the buffered garbled circuit: Once the gates buffered by ID1 exceed the budget, they are spilled to a temporary file and only read back when the circuit is sent to the evaluator. This allows garbling huge circuits (e.g., conversions of large batches) on machines with modest RAM. In the chunked mode (see [Self::with_chunk_size]), the buffer is flushed over the network after every chunk anyway, so the budget only takes effect for chunks larger than it. The [StreamingRep3Garbler](super::streaming_garbler::StreamingRep3Garbler) does not buffer the circuit at all.
+    pub fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        let max_blocks = memory_budget / std::mem::size_of::<[u8; 16]>();
+        assert!(max_blocks > 0, "Memory budget must hold at least one block");
+        self.memory_budget = Some(max_blocks);
+        self
+    }
+
+    /// Enables or disables the privacy-free mode. In this mode, AND gates are garbled with a single ciphertext instead of two, but the evaluator learns the values of all wires used in these gates. Thus, the calling protocol may only enable it for subcircuits whose values the evaluator is allowed to learn (e.g., consistency checks on opened values). The inputs of the AND gates garbled in this mode have to be encoded (see [Self::encode_field] and [Fancy::constant]) or computed in this mode. The evaluator has to switch the mode at the same point of the circuit using [Rep3Evaluator::set_privacy_free](super::evaluator::Rep3Evaluator::set_privacy_free).
+    pub fn set_privacy_free(&mut self, privacy_free: bool) {
+        self.privacy_free = privacy_free;
+    }
+
+    /// Returns whether the privacy-free mode is enabled.
+    pub fn is_privacy_free(&self) -> bool {
+        self.privacy_free
+    }
+
     /// Add the gate to the circuit
-    fn add_block_to_circuit(&mut self, block: &Block) {
+    pub(crate) fn add_block_to_circuit(&mut self, block: &Block) -> IoResult<()> {
         match self.io_context.id {
             PartyID::ID0 => {
                 panic!("Garbler should not be PartyID::ID0");
@@ -68,26 +124,93 @@ impl<'a, N: Rep3Network> Rep3Garbler<'a, N> {
                 let mut gate = [0; 16];
                 gate.copy_from_slice(block.as_ref());
                 self.circuit.push(gate);
+                if self.chunk_size == Some(self.buffered_blocks()) {
+                    self.flush_chunk()?;
+                } else if self.memory_budget == Some(self.circuit.len()) {
+                    self.spill_circuit()?;
+                }
             }
             PartyID::ID2 => {
                 self.hash.update(block.as_ref());
+                self.hashed_blocks += 1;
+                if self.chunk_size == Some(self.hashed_blocks) {
+                    self.flush_chunk()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The number of blocks ID1 has buffered for the evaluator, including the ones spilled to disk.
+    fn buffered_blocks(&self) -> usize {
+        self.circuit.len()
+            + self
+                .spill_file
+                .as_ref()
+                .map_or(0, |spill_file| spill_file.num_blocks)
+    }
+
+    /// Moves the blocks buffered in memory to the spill file, which is created on first use.
+    fn spill_circuit(&mut self) -> IoResult<()> {
+        if self.spill_file.is_none() {
+            self.spill_file = Some(SpillFile::create()?);
+        }
+        let spill_file = self.spill_file.as_mut().expect("spill file was created");
+        spill_file.write_blocks(&self.circuit)?;
+        self.circuit.clear();
+        Ok(())
+    }
+
+    /// Takes the buffered part of the circuit, reading back the blocks spilled to disk first.
+    fn take_circuit(&mut self) -> IoResult<Vec<[u8; 16]>> {
+        let capacity = self.chunk_size.or(self.memory_budget).unwrap_or_default();
+        let buffered = std::mem::replace(&mut self.circuit, Vec::with_capacity(capacity));
+        match self.spill_file.take() {
+            Some(mut spill_file) => {
+                let mut circuit = spill_file.read_blocks(buffered.len())?;
+                circuit.extend(buffered);
+                Ok(circuit)
             }
+            None => Ok(buffered),
         }
     }
 
-    /// Sends the circuit to the evaluator
-    pub fn send_circuit(&mut self) -> IoResult<()> {
+    /// Sends the currently buffered part of the circuit (ID1), or its hash (ID2), to the evaluator.
+    fn flush_chunk(&mut self) -> IoResult<()> {
         match self.io_context.id {
             PartyID::ID0 => {
                 panic!("Garbler should not be PartyID::ID0");
             }
             PartyID::ID1 => {
-                // Send the prepared circuit over the network to the evaluator
-                let mut empty_circuit = Vec::new();
-                std::mem::swap(&mut empty_circuit, &mut self.circuit);
+                let chunk = self.take_circuit()?;
+                self.io_context.network.send_many(PartyID::ID0, &chunk)?;
+            }
+            PartyID::ID2 => {
+                let hash = std::mem::take(&mut self.hash);
+                self.hashed_blocks = 0;
+                let digest = hash.finalize();
                 self.io_context
                     .network
-                    .send_many(PartyID::ID0, &empty_circuit)?;
+                    .send(PartyID::ID0, digest.as_slice())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends the circuit to the evaluator. In chunked mode, this sends the remaining (possibly empty) chunk, which also marks the end of the circuit.
+    pub fn send_circuit(&mut self) -> IoResult<()> {
+        if self.chunk_size.is_some() {
+            return self.flush_chunk();
+        }
+
+        match self.io_context.id {
+            PartyID::ID0 => {
+                panic!("Garbler should not be PartyID::ID0");
+            }
+            PartyID::ID1 => {
+                // Send the prepared circuit over the network to the evaluator
+                let circuit = self.take_circuit()?;
+                self.io_context.network.send_many(PartyID::ID0, &circuit)?;
             }
             PartyID::ID2 => {
                 // Send the hash of the circuit to the evaluator
@@ -104,7 +227,45 @@ impl<'a, N: Rep3Network> Rep3Garbler<'a, N> {
 
     /// This puts the X_0 values into garbler_wires and X_c values into evaluator_wires
     pub fn encode_field<F: PrimeField>(&mut self, field: F) -> GCInputs<WireMod2> {
-        GCUtils::encode_field(field, &mut self.rng, self.delta)
+        let inputs = GCUtils::encode_field(field, &mut self.rng, self.delta);
+        if self.privacy_free {
+            self.clear_colors(inputs)
+        } else {
+            inputs
+        }
+    }
+
+    /// Changes the zero labels of the inputs to have color 0, as required by the privacy-free mode. Adding delta to both labels of a wire keeps the encoded value.
+    fn clear_colors(&self, inputs: GCInputs<WireMod2>) -> GCInputs<WireMod2> {
+        let (garbler_wires, evaluator_wires) = inputs
+            .garbler_wires
+            .iter()
+            .zip(inputs.evaluator_wires.iter())
+            .map(|(zero, wire)| {
+                if zero.color() == 1 {
+                    (zero.plus(&self.delta), wire.plus(&self.delta))
+                } else {
+                    (*zero, *wire)
+                }
+            })
+            .unzip();
+        GCInputs {
+            garbler_wires: BinaryBundle::new(garbler_wires),
+            evaluator_wires: BinaryBundle::new(evaluator_wires),
+            delta: inputs.delta,
+        }
+    }
+
+    /// Returns the constant 1 used to negate in the privacy-free mode. It is added to the circuit on first use.
+    fn privacy_free_one(&mut self) -> Result<WireMod2, GarblerError> {
+        match self.privacy_free_one {
+            Some(one) => Ok(one),
+            None => {
+                let one = self.constant(1, 2)?;
+                self.privacy_free_one = Some(one);
+                Ok(one)
+            }
+        }
     }
 
     /// Consumes the Garbler and returns the delta.
@@ -113,14 +274,14 @@ impl<'a, N: Rep3Network> Rep3Garbler<'a, N> {
     }
 
     /// The current non-free gate index of the garbling computation
-    fn current_gate(&mut self) -> usize {
+    pub(crate) fn current_gate(&mut self) -> usize {
         let current = self.current_gate;
         self.current_gate += 1;
         current
     }
 
     /// The current output index of the garbling computation.
-    fn current_output(&mut self) -> usize {
+    pub(crate) fn current_output(&mut self) -> usize {
         let current = self.current_output;
         self.current_output += 1;
         current
@@ -163,32 +324,45 @@ impl<'a, N: Rep3Network> Rep3Garbler<'a, N> {
 
     /// Outputs the value to all parties
     pub fn output_all_parties(&mut self, x: &[WireMod2]) -> IoResult<Vec<bool>> {
-        // Garbler's to evaluator
-        self.output_evaluator(x)?;
-
-        // Check consistency with the second garbled circuit before receiving the result
-        self.send_circuit()?;
-
-        // Evaluator to garbler
-        self.output_garbler(x)
+        let res = self.output_to(x, &[PartyID::ID0, PartyID::ID1, PartyID::ID2])?;
+        Ok(res.expect("all parties receive the output"))
     }
 
     /// Outputs the value to parties ID0 and ID1
     pub fn output_to_id0_and_id1(&mut self, x: &[WireMod2]) -> IoResult<Option<Vec<bool>>> {
+        self.output_to(x, &[PartyID::ID0, PartyID::ID1])
+    }
+
+    /// Outputs the value to the given parties. Returns the result if this garbler is one of them. All parties have to call this function with the same parties.
+    pub fn output_to(
+        &mut self,
+        x: &[WireMod2],
+        parties: &[PartyID],
+    ) -> IoResult<Option<Vec<bool>>> {
         // Garbler's to evaluator
-        self.output_evaluator(x)?;
+        if parties.contains(&PartyID::ID0) {
+            self.output_evaluator(x)?;
+        }
 
         // Check consistency with the second garbled circuit before receiving the result
         self.send_circuit()?;
 
         // Evaluator to garbler
-        if self.io_context.id == PartyID::ID1 {
+        if parties.contains(&self.io_context.id) {
             Ok(Some(self.output_garbler(x)?))
         } else {
             Ok(None)
         }
     }
 
+    /// Outputs the value to a receiver outside of the three parties. Returns the decoding information, i.e., the color bits of the zero labels, which has to be sent to the receiver together with the result of [Rep3Evaluator::output_to_external](super::evaluator::Rep3Evaluator::output_to_external). The receiver then gets the output with [GCUtils::decode_external_output].
+    pub fn output_to_external(&mut self, x: &[WireMod2]) -> IoResult<Vec<bool>> {
+        // Check consistency with the second garbled circuit before the evaluator releases its labels
+        self.send_circuit()?;
+
+        Ok(x.iter().map(|zero| zero.color() == 1).collect())
+    }
+
     // Read `Block`s from the channel.
     #[inline(always)]
     fn read_blocks(&mut self) -> IoResult<Vec<Block>> {
@@ -203,15 +377,16 @@ impl<'a, N: Rep3Network> Rep3Garbler<'a, N> {
     }
 
     /// Send a wire over the established channel.
-    fn add_wire_to_circuit(&mut self, wire: &WireMod2) {
-        self.add_block_to_circuit(&wire.as_block());
+    fn add_wire_to_circuit(&mut self, wire: &WireMod2) -> IoResult<()> {
+        self.add_block_to_circuit(&wire.as_block())
     }
 
     /// Send a bundle of wires over the established channel.
-    pub fn add_bundle_to_circuit(&mut self, wires: &BinaryBundle<WireMod2>) {
+    pub fn add_bundle_to_circuit(&mut self, wires: &BinaryBundle<WireMod2>) -> IoResult<()> {
         for wire in wires.wires() {
-            self.add_wire_to_circuit(wire);
+            self.add_wire_to_circuit(wire)?;
         }
+        Ok(())
     }
 
     /// Encode a wire, producing the zero wire as well as the encoded value.
@@ -231,14 +406,76 @@ impl<'a, N: Rep3Network> Rep3Garbler<'a, N> {
     }
 }
 
+/// A temporary file holding the blocks of the garbled circuit that exceeded the memory budget of the garbler. The file is removed on drop.
+struct SpillFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    num_blocks: usize,
+}
+
+impl SpillFile {
+    /// Creates a new spill file in the temporary directory of the system.
+    fn create() -> IoResult<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "co-snarks-gc-{}-{}.bin",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+            num_blocks: 0,
+        })
+    }
+
+    /// Appends the blocks to the file.
+    fn write_blocks(&mut self, blocks: &[[u8; 16]]) -> IoResult<()> {
+        for block in blocks {
+            self.writer.write_all(block)?;
+        }
+        self.num_blocks += blocks.len();
+        Ok(())
+    }
+
+    /// Reads all blocks back from the file, reserving space for `additional` blocks.
+    fn read_blocks(&mut self, additional: usize) -> IoResult<Vec<[u8; 16]>> {
+        self.writer.flush()?;
+        let file = self.writer.get_mut();
+        file.seek(SeekFrom::Start(0))?;
+        let mut blocks = Vec::with_capacity(self.num_blocks + additional);
+        let mut block = [0; 16];
+        for _ in 0..self.num_blocks {
+            file.read_exact(&mut block)?;
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        // Removing the file is best effort, it lives in the temporary directory anyway
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
 impl<N: Rep3Network> Fancy for Rep3Garbler<'_, N> {
     type Item = WireMod2;
     type Error = GarblerError;
 
     fn constant(&mut self, x: u16, q: u16) -> Result<WireMod2, GarblerError> {
-        let zero = WireMod2::rand(&mut self.rng, q);
+        let mut zero = WireMod2::rand(&mut self.rng, q);
+        if self.privacy_free && zero.color() == 1 {
+            zero.plus_eq(&self.delta);
+        }
         let wire = zero.plus(self.delta.cmul_eq(x));
-        self.add_wire_to_circuit(&wire);
+        self.add_wire_to_circuit(&wire)?;
         Ok(zero)
     }
 
@@ -247,7 +484,7 @@ impl<N: Rep3Network> Fancy for Rep3Garbler<'_, N> {
         let d = self.delta;
         for k in 0..2 {
             let block = x.plus(&d.cmul(k)).hash(output_tweak(i, k));
-            self.add_block_to_circuit(&block);
+            self.add_block_to_circuit(&block)?;
         }
         Ok(None)
     }
@@ -255,9 +492,15 @@ impl<N: Rep3Network> Fancy for Rep3Garbler<'_, N> {
 
 impl<N: Rep3Network> FancyBinary for Rep3Garbler<'_, N> {
     fn and(&mut self, a: &Self::Item, b: &Self::Item) -> Result<Self::Item, Self::Error> {
+        if self.privacy_free {
+            let gate_num = self.current_gate();
+            let (gate, c) = GCUtils::garble_and_gate_privacy_free(gate_num, a, b, &self.delta);
+            self.add_block_to_circuit(&gate)?;
+            return Ok(c);
+        }
         let (gate0, gate1, c) = self.garble_and_gate(a, b);
-        self.add_block_to_circuit(&gate0);
-        self.add_block_to_circuit(&gate1);
+        self.add_block_to_circuit(&gate0)?;
+        self.add_block_to_circuit(&gate1)?;
         Ok(c)
     }
 
@@ -269,8 +512,52 @@ impl<N: Rep3Network> FancyBinary for Rep3Garbler<'_, N> {
     ///
     /// Since we treat all garbler wires as zero,
     /// xoring with delta conceptually negates the value of the wire
+    ///
+    /// In the privacy-free mode, the zero labels have to keep color 0, so the garbler and the evaluator xor the wire with the constant 1 instead
     fn negate(&mut self, x: &Self::Item) -> Result<Self::Item, Self::Error> {
+        if self.privacy_free {
+            let one = self.privacy_free_one()?;
+            return self.xor(&one, x);
+        }
         let delta = self.delta;
         self.xor(&delta, x)
     }
 }
+
+impl<N: Rep3Network> FancyBinaryLayer for Rep3Garbler<'_, N> {
+    fn and_layer(
+        &mut self,
+        a: &[Self::Item],
+        b: &[Self::Item],
+    ) -> Result<Vec<Self::Item>, Self::Error> {
+        debug_assert_eq!(a.len(), b.len());
+        if self.privacy_free {
+            // Privacy-free gates require a single hash per row, so they are garbled sequentially
+            return a.iter().zip(b).map(|(a, b)| self.and(a, b)).collect();
+        }
+        // Reserve the gate numbers of the whole layer
+        let first_gate = self.current_gate;
+        self.current_gate += a.len();
+
+        let delta = self.delta;
+        let garble = || {
+            a.par_iter()
+                .zip(b.par_iter())
+                .enumerate()
+                .map(|(i, (a, b))| GCUtils::garble_and_gate(first_gate + i, a, b, &delta))
+                .collect::<Vec<_>>()
+        };
+        let garbled = match &self.thread_pool {
+            Some(thread_pool) => thread_pool.install(garble),
+            None => garble(),
+        };
+
+        let mut result = Vec::with_capacity(garbled.len());
+        for (gate0, gate1, c) in garbled {
+            self.add_block_to_circuit(&gate0)?;
+            self.add_block_to_circuit(&gate1)?;
+            result.push(c);
+        }
+        Ok(result)
+    }
+}
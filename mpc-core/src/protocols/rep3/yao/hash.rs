@@ -0,0 +1,135 @@
+//! Hash
+//!
+//! This module contains the hash function used for garbling and evaluating AND gates. With the `aes-gc-hash` feature, the wires are hashed with the tweakable circular correlation robust hash H(x, t) = π(π(x) ⊕ t) ⊕ π(x) from [GKWY20](https://eprint.iacr.org/2019/074.pdf), where π is AES-128 with a fixed key. The [aes] crate uses AES-NI if it is detected at runtime and falls back to a constant-time software implementation otherwise. Without the feature, the hash of [fancy_garbling] is used.
+//!
+//! Keep in mind: All parties have to use the same backend, otherwise the evaluation of the garbled circuit fails.
+
+use fancy_garbling::WireMod2;
+use scuttlebutt::Block;
+
+#[cfg(feature = "aes-gc-hash")]
+mod fixed_key {
+    use aes::{cipher::KeyInit, Aes128};
+    use std::sync::OnceLock;
+
+    // The first 128 bits of the fractional part of pi
+    const FIXED_KEY: [u8; 16] = [
+        0x24, 0x3f, 0x6a, 0x88, 0x85, 0xa3, 0x08, 0xd3, 0x13, 0x19, 0x8a, 0x2e, 0x03, 0x70, 0x73,
+        0x44,
+    ];
+
+    pub(super) fn cipher() -> &'static Aes128 {
+        static CIPHER: OnceLock<Aes128> = OnceLock::new();
+        CIPHER.get_or_init(|| Aes128::new(&FIXED_KEY.into()))
+    }
+}
+
+/// Hashes the wires with the given tweak using fixed-key AES.
+#[cfg(feature = "aes-gc-hash")]
+pub(crate) fn hash_wires<const N: usize>(wires: [&WireMod2; N], tweak: Block) -> [Block; N] {
+    use aes::cipher::BlockEncrypt;
+    use fancy_garbling::WireLabel;
+
+    let cipher = fixed_key::cipher();
+
+    // π(x)
+    let mut pi_x = wires.map(|wire| aes::Block::clone_from_slice(wire.as_block().as_ref()));
+    cipher.encrypt_blocks(&mut pi_x);
+
+    // π(π(x) ⊕ t)
+    let mut pi_pi_x = pi_x;
+    for block in pi_pi_x.iter_mut() {
+        for (b, t) in block.iter_mut().zip(tweak.as_ref()) {
+            *b ^= t;
+        }
+    }
+    cipher.encrypt_blocks(&mut pi_pi_x);
+
+    std::array::from_fn(|i| {
+        let mut res = Block::default();
+        for (r, (a, b)) in res
+            .as_mut()
+            .iter_mut()
+            .zip(pi_pi_x[i].iter().zip(pi_x[i].iter()))
+        {
+            *r = a ^ b;
+        }
+        res
+    })
+}
+
+/// Hashes the wires with the given tweak using the hash of [fancy_garbling].
+#[cfg(not(feature = "aes-gc-hash"))]
+pub(crate) fn hash_wires<const N: usize>(wires: [&WireMod2; N], tweak: Block) -> [Block; N] {
+    fancy_garbling::hash_wires(wires, tweak)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocols::rep3::yao::GCUtils;
+    use fancy_garbling::WireLabel;
+    use rand::thread_rng;
+
+    #[cfg(feature = "aes-gc-hash")]
+    #[test]
+    fn aes_hash_known_answer() {
+        // H(x, t) = π(π(x) ⊕ t) ⊕ π(x), computed with an independent AES-128 implementation
+        let x = WireMod2::from_block(
+            Block::from(std::array::from_fn::<u8, 16, _>(|i| i as u8)),
+            2,
+        );
+        let tweak = Block::from(std::array::from_fn::<u8, 16, _>(|i| 16 + i as u8));
+        let expected: [u8; 16] = [
+            0xe9, 0x36, 0x27, 0x3e, 0x6b, 0x3a, 0xd3, 0x0a, 0x47, 0x15, 0xb0, 0x95, 0x60, 0xe9,
+            0xdf, 0x5b,
+        ];
+        let [hash] = hash_wires([&x], tweak);
+        assert_eq!(<[u8; 16]>::from(hash), expected);
+        // the wires are hashed independently
+        let [hash_x, hash_y] = hash_wires([&x, &x.plus(&x)], tweak);
+        assert_eq!(hash_x, hash);
+        assert_ne!(hash_y, hash);
+    }
+
+    #[test]
+    fn garble_and_evaluate_and_gates() {
+        let mut rng = thread_rng();
+        let delta = GCUtils::random_delta(&mut rng);
+        let label = |zero: &WireMod2, bit: bool| {
+            if bit {
+                zero.plus(&delta)
+            } else {
+                zero.to_owned()
+            }
+        };
+        for (gate_num, (a, b)) in [(false, false), (false, true), (true, false), (true, true)]
+            .into_iter()
+            .enumerate()
+        {
+            let a0 = WireMod2::rand(&mut rng, 2);
+            let b0 = WireMod2::rand(&mut rng, 2);
+            let (gate0, gate1, c0) = GCUtils::garble_and_gate(gate_num, &a0, &b0, &delta);
+            let c = GCUtils::evaluate_and_gate(
+                gate_num,
+                &label(&a0, a),
+                &label(&b0, b),
+                &gate0,
+                &gate1,
+            );
+            assert_eq!(c, label(&c0, a & b));
+
+            // privacy-free gates require zero labels with color 0
+            let a0 = label(&a0, a0.color() == 1);
+            let b0 = label(&b0, b0.color() == 1);
+            let (gate, c0) = GCUtils::garble_and_gate_privacy_free(gate_num, &a0, &b0, &delta);
+            let c = GCUtils::evaluate_and_gate_privacy_free(
+                gate_num,
+                &label(&a0, a),
+                &label(&b0, b),
+                &gate,
+            );
+            assert_eq!(c, label(&c0, a & b));
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Roles
+//!
+//! This module contains the assignment of the roles in the replicated 3-party garbled circuits. By default, [PartyID::ID0] is the evaluator, [PartyID::ID1] is the garbler sending the circuit and [PartyID::ID2] is the garbler sending the hash of the circuit. Since the evaluator has a considerably higher workload than the garblers, long running jobs can rotate the roles between executions to balance the load.
+//!
+//! The garbled circuits are always implemented in terms of the roles, i.e., the evaluator acts as [PartyID::ID0]. A [GCRoleNetwork] translates between the roles and the actual parties, such that the garbled circuits can be executed on an [IoContext](crate::protocols::rep3::network::IoContext) with any role assignment (see [IoContext::fork_with_gc_roles](crate::protocols::rep3::network::IoContext::fork_with_gc_roles)). Since a rotation keeps the order of the parties, the shares and the correlated randomness stay consistent.
+
+use crate::protocols::rep3::{id::PartyID, network::Rep3Network};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mpc_net::{abort::AbortReason, config::Phase, stats::NetworkStats};
+
+/// The assignment of the roles of a garbled circuit execution to the parties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GCRoles {
+    evaluator: PartyID,
+}
+
+impl Default for GCRoles {
+    fn default() -> Self {
+        Self::new(PartyID::ID0)
+    }
+}
+
+impl GCRoles {
+    /// Creates a new role assignment with the given party as evaluator. The next party sends the garbled circuit and the previous party sends its hash.
+    pub fn new(evaluator: PartyID) -> Self {
+        Self { evaluator }
+    }
+
+    /// Returns the role assignment for the given execution, such that the evaluator rotates through all parties.
+    pub fn rotating(execution: usize) -> Self {
+        let evaluator = PartyID::try_from(execution % 3).expect("is in range 0..3");
+        Self::new(evaluator)
+    }
+
+    /// Returns the role assignment of the next execution.
+    pub fn next(&self) -> Self {
+        Self::new(self.evaluator.next_id())
+    }
+
+    /// Returns the party that evaluates the garbled circuit.
+    pub fn evaluator(&self) -> PartyID {
+        self.evaluator
+    }
+
+    /// Returns the garbler that sends the garbled circuit to the evaluator.
+    pub fn circuit_garbler(&self) -> PartyID {
+        self.evaluator.next_id()
+    }
+
+    /// Returns the garbler that sends the hash of the garbled circuit to the evaluator.
+    pub fn hash_garbler(&self) -> PartyID {
+        self.evaluator.prev_id()
+    }
+
+    /// Returns the role of the given party, i.e., the id it acts as in the garbled circuit.
+    pub fn role_of(&self, party: PartyID) -> PartyID {
+        let role = (usize::from(party) + 3 - usize::from(self.evaluator)) % 3;
+        PartyID::try_from(role).expect("is in range 0..3")
+    }
+
+    /// Returns the party that has the given role.
+    pub fn party_of(&self, role: PartyID) -> PartyID {
+        let party = (usize::from(role) + usize::from(self.evaluator)) % 3;
+        PartyID::try_from(party).expect("is in range 0..3")
+    }
+}
+
+/// A network that translates between the roles of a garbled circuit execution and the actual parties. The party ids used with this network are the roles given by [GCRoles].
+#[derive(Debug)]
+pub struct GCRoleNetwork<N: Rep3Network> {
+    network: N,
+    roles: GCRoles,
+}
+
+impl<N: Rep3Network> GCRoleNetwork<N> {
+    /// Wraps the given network with the given role assignment.
+    pub fn new(network: N, roles: GCRoles) -> Self {
+        Self { network, roles }
+    }
+
+    /// Returns the role assignment of this network.
+    pub fn roles(&self) -> GCRoles {
+        self.roles
+    }
+
+    /// Returns the underlying network.
+    pub fn into_inner(self) -> N {
+        self.network
+    }
+}
+
+impl<N: Rep3Network> Rep3Network for GCRoleNetwork<N> {
+    fn get_id(&self) -> PartyID {
+        self.roles.role_of(self.network.get_id())
+    }
+
+    // The rotation keeps the next and previous parties, so reshare and broadcast do not have to be translated
+    fn reshare_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<Vec<F>> {
+        self.network.reshare_many(data)
+    }
+
+    fn broadcast_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<(Vec<F>, Vec<F>)> {
+        self.network.broadcast_many(data)
+    }
+
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: PartyID,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        self.network.send_many(self.roles.party_of(target), data)
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(&mut self, from: PartyID) -> std::io::Result<Vec<F>> {
+        self.network.recv_many(self.roles.party_of(from))
+    }
+
+    fn fork(&mut self) -> std::io::Result<Self> {
+        Ok(Self::new(self.network.fork()?, self.roles))
+    }
+
+    fn start_phase(&mut self, phase: Phase) -> std::io::Result<()> {
+        self.network.start_phase(phase)
+    }
+
+    fn end_phase(&mut self) {
+        self.network.end_phase()
+    }
+
+    fn network_stats(&self) -> NetworkStats {
+        self.network.network_stats()
+    }
+
+    fn abort(&mut self, reason: AbortReason) {
+        self.network.abort(reason)
+    }
+}
@@ -4,7 +4,7 @@
 //!
 //! This file is heavily inspired by [fancy-garbling](https://github.com/GaloisInc/swanky/blob/dev/fancy-garbling/src/garble/evaluator.rs)
 
-use super::GCUtils;
+use super::{FancyBinaryLayer, GCUtils, GcConsistencyError};
 use crate::protocols::rep3::{
     id::PartyID,
     network::{IoContext, Rep3Network},
@@ -23,6 +23,10 @@ pub struct StreamingRep3Evaluator<'a, N: Rep3Network> {
     current_output: usize,
     current_gate: usize,
     hash: Sha3_256, // For the ID2 to match everything sent with one hash
+    hash_chunk_size: Option<usize>,
+    hashed_blocks: usize,   // Number of blocks hashed since the last check
+    received_blocks: usize, // Number of blocks received and checked so far
+    checked_chunks: usize,  // Number of hashes of ID2 checked so far
 }
 
 impl<'a, N: Rep3Network> StreamingRep3Evaluator<'a, N> {
@@ -38,9 +42,20 @@ impl<'a, N: Rep3Network> StreamingRep3Evaluator<'a, N> {
             current_output: 0,
             current_gate: 0,
             hash: Sha3_256::default(),
+            hash_chunk_size: None,
+            hashed_blocks: 0,
+            received_blocks: 0,
+            checked_chunks: 0,
         }
     }
 
+    /// Enables the incremental consistency check: The received blocks are checked against a hash of ID2 after every `hash_chunk_size` blocks, such that an inconsistent circuit is detected early. The garblers have to be created with the same chunk size using [StreamingRep3Garbler::with_hash_chunk_size](super::streaming_garbler::StreamingRep3Garbler::with_hash_chunk_size).
+    pub fn with_hash_chunk_size(mut self, hash_chunk_size: usize) -> Self {
+        assert!(hash_chunk_size > 0, "Chunk size must be greater than 0");
+        self.hash_chunk_size = Some(hash_chunk_size);
+        self
+    }
+
     /// The current non-free gate index of the garbling computation.
     fn current_gate(&mut self) -> usize {
         let current = self.current_gate;
@@ -82,75 +97,82 @@ impl<'a, N: Rep3Network> StreamingRep3Evaluator<'a, N> {
         }
     }
 
-    /// Outputs the values to the garbler.
-    fn output_garbler(&mut self, x: &[WireMod2]) -> IoResult<()> {
+    /// Sends the output labels to the garbler with the given id.
+    fn output_garbler(&mut self, x: &[WireMod2], id: PartyID) -> IoResult<()> {
         for val in x {
             let block = val.as_block();
-            self.send_block(&block)?;
-        }
-        Ok(())
-    }
-
-    /// Outputs the values to the garbler with id1.
-    fn output_garbler_id1(&mut self, x: &[WireMod2]) -> IoResult<()> {
-        for val in x {
-            let block = val.as_block();
-            self.io_context.network.send(PartyID::ID1, block.as_ref())?;
+            self.io_context.network.send(id, block.as_ref())?;
         }
         Ok(())
     }
 
     /// Outputs the value to all parties
     pub fn output_all_parties(&mut self, x: &[WireMod2]) -> IoResult<Vec<bool>> {
-        // Garbler's to evaluator
-        let res = self.output_evaluator(x)?;
-
-        // Check consistency with the second garbled circuit before releasing the result
-        self.receive_hash()?;
-
-        // Evaluator to garbler
-        self.output_garbler(x)?;
-
-        Ok(res)
+        let res = self.output_to(x, &[PartyID::ID0, PartyID::ID1, PartyID::ID2])?;
+        Ok(res.expect("all parties receive the output"))
     }
 
     /// Outputs the value to parties ID0 and ID1
     pub fn output_to_id0_and_id1(&mut self, x: &[WireMod2]) -> IoResult<Vec<bool>> {
+        let res = self.output_to(x, &[PartyID::ID0, PartyID::ID1])?;
+        Ok(res.expect("ID0 receives the output"))
+    }
+
+    /// Outputs the value to the given parties. Returns the result if the evaluator (ID0) is one of them. All parties have to call this function with the same parties.
+    pub fn output_to(
+        &mut self,
+        x: &[WireMod2],
+        parties: &[PartyID],
+    ) -> IoResult<Option<Vec<bool>>> {
         // Garbler's to evaluator
-        let res = self.output_evaluator(x)?;
+        let res = if parties.contains(&PartyID::ID0) {
+            Some(self.output_evaluator(x)?)
+        } else {
+            None
+        };
 
         // Check consistency with the second garbled circuit before releasing the result
         self.receive_hash()?;
 
         // Evaluator to garbler
-        self.output_garbler_id1(x)?;
+        for id in [PartyID::ID1, PartyID::ID2] {
+            if parties.contains(&id) {
+                self.output_garbler(x, id)?;
+            }
+        }
 
         Ok(res)
     }
 
-    /// Receive a hash of ID2 (the second garbler) to verify the garbled circuit.
+    /// Outputs the value to a receiver outside of the three parties. Returns the color bits of the output labels, which have to be sent to the receiver together with the decoding information of both garblers (see [StreamingRep3Garbler::output_to_external](super::streaming_garbler::StreamingRep3Garbler::output_to_external)).
+    pub fn output_to_external(&mut self, x: &[WireMod2]) -> IoResult<Vec<bool>> {
+        // Check consistency with the second garbled circuit before releasing the labels
+        self.receive_hash()?;
+
+        Ok(x.iter().map(|label| label.color() == 1).collect())
+    }
+
+    /// Receive a hash of ID2 (the second garbler) to verify the blocks received since the last hash. Returns a [GcConsistencyError] identifying the blocks if the check fails.
     pub fn receive_hash(&mut self) -> IoResult<()> {
         let data: Vec<u8> = self.io_context.network.recv(PartyID::ID2)?;
         let mut hash = Sha3_256::default();
         std::mem::swap(&mut hash, &mut self.hash);
         let digest = hash.finalize();
+        let blocks = self.received_blocks..self.received_blocks + self.hashed_blocks;
         if data != digest.as_slice() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Inconsistent Garbled Circuits: Hashes do not match!",
-            ));
+            return Err(GcConsistencyError::HashMismatch {
+                chunk: self.checked_chunks,
+                blocks,
+            }
+            .into());
         }
+        self.received_blocks = blocks.end;
+        self.hashed_blocks = 0;
+        self.checked_chunks += 1;
 
         Ok(())
     }
 
-    /// Send a block over the network to the garblers.
-    fn send_block(&mut self, block: &Block) -> IoResult<()> {
-        self.io_context.network.send(PartyID::ID1, block.as_ref())?;
-        self.io_context.network.send(PartyID::ID2, block.as_ref())?;
-        Ok(())
-    }
-
     /// Receive a block from a specific party.
     fn receive_block_from(&mut self, id: PartyID) -> IoResult<Block> {
         GCUtils::receive_block_from(&mut self.io_context.network, id)
@@ -160,6 +182,10 @@ impl<'a, N: Rep3Network> StreamingRep3Evaluator<'a, N> {
     fn receive_block(&mut self) -> IoResult<Block> {
         let block = self.receive_block_from(PartyID::ID1)?;
         self.hash.update(block.as_ref()); // "Receive" from ID2
+        self.hashed_blocks += 1;
+        if self.hash_chunk_size == Some(self.hashed_blocks) {
+            self.receive_hash()?;
+        }
 
         Ok(block)
     }
@@ -253,3 +279,5 @@ impl<N: Rep3Network> FancyBinary for StreamingRep3Evaluator<'_, N> {
         Ok(self.evaluate_and_gate(a, b, &gate0, &gate1))
     }
 }
+
+impl<N: Rep3Network> FancyBinaryLayer for StreamingRep3Evaluator<'_, N> {}
@@ -4,7 +4,7 @@
 //!
 //! This implementation is heavily inspired by [fancy-garbling](https://github.com/GaloisInc/swanky/blob/dev/fancy-garbling/src/garble/garbler.rs)
 
-use super::{GCInputs, GCUtils};
+use super::{FancyBinaryLayer, GCInputs, GCUtils};
 use crate::{
     protocols::rep3::{
         id::PartyID,
@@ -30,6 +30,8 @@ pub struct StreamingRep3Garbler<'a, N: Rep3Network> {
     current_gate: usize,
     pub(crate) rng: RngType,
     hash: Sha3_256, // For the ID2 to match everything sent with one hash
+    hash_chunk_size: Option<usize>,
+    hashed_blocks: usize, // Number of blocks ID2 has hashed since the last hash was sent
 }
 
 impl<'a, N: Rep3Network> StreamingRep3Garbler<'a, N> {
@@ -53,9 +55,18 @@ impl<'a, N: Rep3Network> StreamingRep3Garbler<'a, N> {
             current_gate: 0,
             rng,
             hash: Sha3_256::default(),
+            hash_chunk_size: None,
+            hashed_blocks: 0,
         }
     }
 
+    /// Enables the incremental consistency check: Instead of a single hash of the whole circuit, ID2 sends a hash after every `hash_chunk_size` blocks, such that the evaluator detects an inconsistent circuit early. The evaluator has to be created with the same chunk size using [StreamingRep3Evaluator::with_hash_chunk_size](super::streaming_evaluator::StreamingRep3Evaluator::with_hash_chunk_size).
+    pub fn with_hash_chunk_size(mut self, hash_chunk_size: usize) -> Self {
+        assert!(hash_chunk_size > 0, "Chunk size must be greater than 0");
+        self.hash_chunk_size = Some(hash_chunk_size);
+        self
+    }
+
     /// This puts the X_0 values into garbler_wires and X_c values into evaluator_wires
     pub fn encode_field<F: PrimeField>(&mut self, field: F) -> GCInputs<WireMod2> {
         GCUtils::encode_field(field, &mut self.rng, self.delta)
@@ -111,37 +122,51 @@ impl<'a, N: Rep3Network> StreamingRep3Garbler<'a, N> {
 
     /// Outputs the value to all parties
     pub fn output_all_parties(&mut self, x: &[WireMod2]) -> IoResult<Vec<bool>> {
-        // Garbler's to evaluator
-        self.output_evaluator(x)?;
-
-        // Check consistency with the second garbled circuit before receiving the result
-        self.send_hash()?;
-
-        // Evaluator to garbler
-        self.output_garbler(x)
+        let res = self.output_to(x, &[PartyID::ID0, PartyID::ID1, PartyID::ID2])?;
+        Ok(res.expect("all parties receive the output"))
     }
 
     /// Outputs the value to parties ID0 and ID1
     pub fn output_to_id0_and_id1(&mut self, x: &[WireMod2]) -> IoResult<Option<Vec<bool>>> {
+        self.output_to(x, &[PartyID::ID0, PartyID::ID1])
+    }
+
+    /// Outputs the value to the given parties. Returns the result if this garbler is one of them. All parties have to call this function with the same parties.
+    pub fn output_to(
+        &mut self,
+        x: &[WireMod2],
+        parties: &[PartyID],
+    ) -> IoResult<Option<Vec<bool>>> {
         // Garbler's to evaluator
-        self.output_evaluator(x)?;
+        if parties.contains(&PartyID::ID0) {
+            self.output_evaluator(x)?;
+        }
 
         // Check consistency with the second garbled circuit before receiving the result
         self.send_hash()?;
 
         // Evaluator to garbler
-        if self.io_context.id == PartyID::ID1 {
+        if parties.contains(&self.io_context.id) {
             Ok(Some(self.output_garbler(x)?))
         } else {
             Ok(None)
         }
     }
 
+    /// Outputs the value to a receiver outside of the three parties. Returns the decoding information, i.e., the color bits of the zero labels, which has to be sent to the receiver together with the result of [StreamingRep3Evaluator::output_to_external](super::streaming_evaluator::StreamingRep3Evaluator::output_to_external). The receiver then gets the output with [GCUtils::decode_external_output].
+    pub fn output_to_external(&mut self, x: &[WireMod2]) -> IoResult<Vec<bool>> {
+        // Check consistency with the second garbled circuit before the evaluator releases its labels
+        self.send_hash()?;
+
+        Ok(x.iter().map(|zero| zero.color() == 1).collect())
+    }
+
     /// As ID2, send a hash of the sended data to the evaluator.
     pub fn send_hash(&mut self) -> IoResult<()> {
         if self.io_context.id == PartyID::ID2 {
             let mut hash = Sha3_256::default();
             std::mem::swap(&mut hash, &mut self.hash);
+            self.hashed_blocks = 0;
             let digest = hash.finalize();
             self.io_context
                 .network
@@ -161,6 +186,10 @@ impl<'a, N: Rep3Network> StreamingRep3Garbler<'a, N> {
             }
             PartyID::ID2 => {
                 self.hash.update(block.as_ref());
+                self.hashed_blocks += 1;
+                if self.hash_chunk_size == Some(self.hashed_blocks) {
+                    self.send_hash()?;
+                }
             }
         }
         Ok(())
@@ -252,3 +281,5 @@ impl<N: Rep3Network> FancyBinary for StreamingRep3Garbler<'_, N> {
         self.xor(&delta, x)
     }
 }
+
+impl<N: Rep3Network> FancyBinaryLayer for StreamingRep3Garbler<'_, N> {}
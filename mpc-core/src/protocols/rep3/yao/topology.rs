@@ -0,0 +1,251 @@
+//! Topology
+//!
+//! This module contains a cache for the topology of garbled circuits. Many conversions garble the same subcircuits (e.g., the addition mod p) over and over again. Garbled gates can not be reused, since every instance needs fresh wire labels, but the structure of the circuit only depends on the function. Thus, the topology of a circuit is recorded once with a [TopologyRecorder] and then replayed on the actual garbler or evaluator for every instance. While replaying, the AND gates of the same AND-depth are given to [FancyBinaryLayer::and_layer] at once.
+
+use super::FancyBinaryLayer;
+use fancy_garbling::{errors::FancyError, Fancy, FancyBinary, HasModulus};
+use itertools::izip;
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+/// A wire of a circuit that is being recorded. It just holds the index of the wire in the topology.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TopologyWire(usize);
+
+impl HasModulus for TopologyWire {
+    fn modulus(&self) -> u16 {
+        2
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Gate {
+    Constant(u16),
+    Negate(usize),
+    Xor(usize, usize),
+    And(usize, usize),
+}
+
+/// The gates of one AND-depth. First the free gates are computed in order, then all AND gates at once.
+#[derive(Debug, Default)]
+struct Layer {
+    free: Vec<usize>,
+    and: Vec<usize>,
+}
+
+/// This struct implements [FancyBinary] to record the topology of a circuit instead of garbling it.
+#[derive(Debug)]
+pub struct TopologyRecorder {
+    num_inputs: usize,
+    gates: Vec<Gate>,
+}
+
+impl TopologyRecorder {
+    /// Creates a new recorder and returns it together with the input wires of the circuit.
+    pub fn new(num_inputs: usize) -> (Self, Vec<TopologyWire>) {
+        let recorder = Self {
+            num_inputs,
+            gates: Vec::new(),
+        };
+        (recorder, (0..num_inputs).map(TopologyWire).collect())
+    }
+
+    fn add_gate(&mut self, gate: Gate) -> TopologyWire {
+        self.gates.push(gate);
+        TopologyWire(self.num_inputs + self.gates.len() - 1)
+    }
+
+    /// Finishes the recording, where `outputs` are the output wires of the circuit.
+    pub fn finish(self, outputs: &[TopologyWire]) -> CircuitTopology {
+        // The AND-depth of each wire
+        let mut depths = vec![0; self.num_inputs + self.gates.len()];
+        let mut layers = vec![Layer::default()];
+        for (i, gate) in self.gates.iter().enumerate() {
+            let wire = self.num_inputs + i;
+            depths[wire] = match *gate {
+                Gate::Constant(_) => 0,
+                Gate::Negate(x) => depths[x],
+                Gate::Xor(x, y) => depths[x].max(depths[y]),
+                Gate::And(x, y) => depths[x].max(depths[y]) + 1,
+            };
+            let depth = depths[wire];
+            if depth >= layers.len() {
+                layers.resize_with(depth + 1, Layer::default);
+            }
+            match gate {
+                Gate::And(_, _) => layers[depth - 1].and.push(i),
+                _ => layers[depth].free.push(i),
+            }
+        }
+
+        CircuitTopology {
+            num_inputs: self.num_inputs,
+            gates: self.gates,
+            layers,
+            outputs: outputs.iter().map(|wire| wire.0).collect(),
+        }
+    }
+}
+
+impl Fancy for TopologyRecorder {
+    type Item = TopologyWire;
+    type Error = FancyError;
+
+    fn constant(&mut self, x: u16, q: u16) -> Result<Self::Item, Self::Error> {
+        debug_assert_eq!(q, 2);
+        Ok(self.add_gate(Gate::Constant(x)))
+    }
+
+    fn output(&mut self, _x: &Self::Item) -> Result<Option<u16>, Self::Error> {
+        // The outputs of the circuit are given to TopologyRecorder::finish
+        Ok(None)
+    }
+}
+
+impl FancyBinary for TopologyRecorder {
+    fn negate(&mut self, x: &Self::Item) -> Result<Self::Item, Self::Error> {
+        Ok(self.add_gate(Gate::Negate(x.0)))
+    }
+
+    fn xor(&mut self, x: &Self::Item, y: &Self::Item) -> Result<Self::Item, Self::Error> {
+        Ok(self.add_gate(Gate::Xor(x.0, y.0)))
+    }
+
+    fn and(&mut self, a: &Self::Item, b: &Self::Item) -> Result<Self::Item, Self::Error> {
+        Ok(self.add_gate(Gate::And(a.0, b.0)))
+    }
+}
+
+/// The recorded topology of a circuit, which can be replayed on any [FancyBinaryLayer].
+#[derive(Debug)]
+pub struct CircuitTopology {
+    num_inputs: usize,
+    gates: Vec<Gate>,
+    layers: Vec<Layer>,
+    outputs: Vec<usize>,
+}
+
+impl CircuitTopology {
+    /// Returns the number of input wires of the circuit.
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    /// Returns the number of AND gates of the circuit.
+    pub fn num_and_gates(&self) -> usize {
+        self.layers.iter().map(|layer| layer.and.len()).sum()
+    }
+
+    /// Replays the circuit on the given garbler or evaluator and returns the output wires.
+    pub fn execute<G: FancyBinaryLayer>(
+        &self,
+        g: &mut G,
+        inputs: &[G::Item],
+    ) -> Result<Vec<G::Item>, G::Error> {
+        assert_eq!(inputs.len(), self.num_inputs);
+        let mut wires = inputs
+            .iter()
+            .cloned()
+            .map(Some)
+            .chain((0..self.gates.len()).map(|_| None))
+            .collect::<Vec<_>>();
+        let wire = |wires: &[Option<G::Item>], i: usize| {
+            wires[i]
+                .to_owned()
+                .expect("wire is computed in an earlier layer")
+        };
+
+        for layer in self.layers.iter() {
+            for &i in layer.free.iter() {
+                let res = match self.gates[i] {
+                    Gate::Constant(x) => g.constant(x, 2)?,
+                    Gate::Negate(x) => g.negate(&wire(&wires, x))?,
+                    Gate::Xor(x, y) => g.xor(&wire(&wires, x), &wire(&wires, y))?,
+                    Gate::And(_, _) => unreachable!("AND gates are not free"),
+                };
+                wires[self.num_inputs + i] = Some(res);
+            }
+
+            if layer.and.is_empty() {
+                continue;
+            }
+            let (a, b): (Vec<_>, Vec<_>) = layer
+                .and
+                .iter()
+                .map(|&i| match self.gates[i] {
+                    Gate::And(x, y) => (wire(&wires, x), wire(&wires, y)),
+                    _ => unreachable!("only AND gates are in the AND layer"),
+                })
+                .unzip();
+            let res = g.and_layer(&a, &b)?;
+            for (&i, res) in izip!(layer.and.iter(), res) {
+                wires[self.num_inputs + i] = Some(res);
+            }
+        }
+
+        Ok(self.outputs.iter().map(|&i| wire(&wires, i)).collect())
+    }
+}
+
+type TopologyKey = (&'static str, TypeId, usize);
+
+/// A cache of circuit topologies keyed by the name of the function, a type (e.g., the prime field) and the number of input wires.
+#[derive(Debug, Default)]
+pub struct TopologyCache {
+    topologies: Mutex<HashMap<TopologyKey, Arc<CircuitTopology>>>,
+}
+
+impl TopologyCache {
+    /// Returns the process-wide cache used by [GarbledCircuits](super::circuits::GarbledCircuits).
+    pub fn global() -> &'static Self {
+        static CACHE: OnceLock<TopologyCache> = OnceLock::new();
+        CACHE.get_or_init(TopologyCache::default)
+    }
+
+    /// Returns the cached topology of the function `name` instantiated with `T` for `num_inputs` input wires. If it is not cached yet, it is recorded by calling `build` with a [TopologyRecorder] and the input wires.
+    pub fn get_or_record<T: 'static>(
+        &self,
+        name: &'static str,
+        num_inputs: usize,
+        build: impl FnOnce(
+            &mut TopologyRecorder,
+            &[TopologyWire],
+        ) -> Result<Vec<TopologyWire>, FancyError>,
+    ) -> Result<Arc<CircuitTopology>, FancyError> {
+        let key = (name, TypeId::of::<T>(), num_inputs);
+        if let Some(topology) = self.lock().get(&key) {
+            return Ok(Arc::clone(topology));
+        }
+
+        // Record without holding the lock, another thread may record the same topology in the meantime
+        let (mut recorder, inputs) = TopologyRecorder::new(num_inputs);
+        let outputs = build(&mut recorder, &inputs)?;
+        let topology = Arc::new(recorder.finish(&outputs));
+        Ok(Arc::clone(self.lock().entry(key).or_insert(topology)))
+    }
+
+    /// Returns the number of cached topologies.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if no topology is cached.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Removes all cached topologies.
+    pub fn clear(&self) {
+        self.lock().clear()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<TopologyKey, Arc<CircuitTopology>>> {
+        // The map stays consistent even if a thread panicked while holding the lock
+        self.topologies
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::protocols::rep3::{
     id::PartyID,
+    malicious,
     network::{IoContext, Rep3Network},
     rngs::Rep3CorrelatedRng,
     IoResult,
@@ -114,6 +115,7 @@ pub fn mul<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring mul")?;
     let local_a = a * b + io_context.rngs.rand.masking_element::<RingElement<T>>();
     let local_b = io_context.network.reshare(local_a)?;
     Ok(RingShare {
@@ -161,6 +163,7 @@ pub fn io_mul_vec<T: IntRing2k, N: Rep3Network>(
     local_a: Vec<RingElement<T>>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<Vec<RingShare<T>>> {
+    malicious::ensure_semi_honest(io_context, "ring io_mul_vec")?;
     let local_b = io_context.network.reshare_many(&local_a)?;
     if local_b.len() != local_a.len() {
         return Err(std::io::Error::new(
@@ -205,6 +208,7 @@ pub fn open<T: IntRing2k, N: Rep3Network>(
     a: RingShare<T>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<RingElement<T>> {
+    malicious::ensure_semi_honest(io_context, "ring open")?;
     let c = io_context.network.reshare(a.b)?;
     Ok(a.a + a.b + c)
 }
@@ -214,6 +218,7 @@ pub fn open_bit<T: IntRing2k, N: Rep3Network>(
     a: RingShare<T>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<RingElement<T>> {
+    malicious::ensure_semi_honest(io_context, "ring open_bit")?;
     let c = io_context.network.reshare(a.b.to_owned())?;
     Ok(a.a ^ a.b ^ c)
 }
@@ -223,6 +228,7 @@ pub fn open_vec<T: IntRing2k, N: Rep3Network>(
     a: &[RingShare<T>],
     io_context: &mut IoContext<N>,
 ) -> IoResult<Vec<RingElement<T>>> {
+    malicious::ensure_semi_honest(io_context, "ring open_vec")?;
     // TODO think about something better... it is not so bad
     // because we use it exactly once in PLONK where we do it for 4
     // shares..
@@ -294,6 +300,7 @@ pub fn mul_open<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring mul_open")?;
     let a = a * b + io_context.rngs.rand.masking_element::<RingElement<T>>();
     let (b, c) = io_context.network.broadcast(a)?;
     Ok(a + b + c)
@@ -308,6 +315,7 @@ pub fn mul_open_vec<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring mul_open_vec")?;
     let mut a = izip!(a, b)
         .map(|(a, b)| a * b + io_context.rngs.rand.masking_element::<RingElement<T>>())
         .collect_vec();
@@ -557,6 +565,7 @@ pub(crate) fn arithmetic_xor<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring arithmetic_xor")?;
     let mut d = x * y + io_context.rngs.rand.masking_element::<RingElement<T>>();
     d <<= 1;
     let e = x.a + y.a;
@@ -574,6 +583,7 @@ pub(crate) fn arithmetic_xor_many<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring arithmetic_xor_many")?;
     debug_assert_eq!(x.len(), y.len());
 
     let mut a = Vec::with_capacity(x.len());
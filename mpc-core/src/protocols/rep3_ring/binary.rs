@@ -8,6 +8,7 @@ use super::{
 };
 use crate::protocols::rep3::{
     id::PartyID,
+    malicious,
     network::{IoContext, Rep3Network},
     IoResult,
 };
@@ -71,6 +72,7 @@ pub fn and<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring and")?;
     let (mut mask, mask_b) = io_context.rngs.rand.random_elements::<RingElement<T>>();
     mask ^= mask_b;
     let local_a = (a & b) ^ mask;
@@ -125,6 +127,7 @@ pub fn open<T: IntRing2k, N: Rep3Network>(
     a: &RingShare<T>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<RingElement<T>> {
+    malicious::ensure_semi_honest(io_context, "ring open")?;
     let c = io_context.network.reshare(a.b)?;
     Ok(a.a ^ a.b ^ c)
 }
@@ -12,6 +12,7 @@ use crate::protocols::{
     rep3::{
         self,
         conversion::A2BType,
+        malicious,
         network::{IoContext, Rep3Network},
         Rep3BigUintShare, Rep3PrimeFieldShare,
     },
@@ -34,8 +35,8 @@ where
     N: Rep3Network,
     Standard: Distribution<T> + Distribution<U>,
 {
-    match io_context.a2b_type {
-        A2BType::Direct => cast_a2b(x, io_context),
+    match io_context.select_a2b_type(1, T::K.max(U::K)) {
+        A2BType::Direct | A2BType::Auto => cast_a2b(x, io_context),
         A2BType::Yao => cast_gc(x, io_context),
     }
 }
@@ -48,8 +49,9 @@ pub fn ring_to_field_selector<T: IntRing2k, F: PrimeField, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
-    match io_context.a2b_type {
-        A2BType::Direct => ring_to_field_a2b(x, io_context),
+    malicious::ensure_semi_honest(io_context, "ring ring_to_field_selector")?;
+    match io_context.select_a2b_type(1, F::MODULUS_BIT_SIZE as usize) {
+        A2BType::Direct | A2BType::Auto => ring_to_field_a2b(x, io_context),
         A2BType::Yao => Ok(yao::ring_to_field_many(&[x], io_context)?[0]),
     }
 }
@@ -62,8 +64,9 @@ pub fn field_to_ring_selector<F: PrimeField, T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
-    match io_context.a2b_type {
-        A2BType::Direct => field_to_ring_a2b(x, io_context),
+    malicious::ensure_semi_honest(io_context, "ring field_to_ring_selector")?;
+    match io_context.select_a2b_type(1, F::MODULUS_BIT_SIZE as usize) {
+        A2BType::Direct | A2BType::Auto => field_to_ring_a2b(x, io_context),
         A2BType::Yao => Ok(yao::field_to_ring_many(&[x], io_context)?[0]),
     }
 }
@@ -156,6 +159,7 @@ pub fn field_to_ring_a2b<F: PrimeField, T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring field_to_ring_a2b")?;
     let binary = rep3::conversion::a2b(share, io_context)?;
     let ring_share = Rep3RingShare {
         a: RingElement(T::cast_from_biguint(&binary.a)),
@@ -172,6 +176,7 @@ pub fn ring_to_field_a2b<T: IntRing2k, F: PrimeField, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring ring_to_field_a2b")?;
     // A special case for Bit
     if TypeId::of::<T>() == TypeId::of::<Bit>() {
         // SAFTEY: We already checked that the type matches
@@ -191,3 +196,33 @@ where
     );
     rep3::conversion::b2a(&biguint_share, io_context)
 }
+
+/// Transforms a Rep3PrimeFieldShare into a binary Rep3RingShare of its lowest `T::K` bits, truncating the excess bits. This allows to perform bitwise operations on values which are known to fit into `T` on the smaller ring shares instead of the shares of the full field element.
+pub fn field_to_binary_ring<F: PrimeField, T: IntRing2k, N: Rep3Network>(
+    share: Rep3PrimeFieldShare<F>,
+    io_context: &mut IoContext<N>,
+) -> std::io::Result<Rep3RingShare<T>> {
+    malicious::ensure_semi_honest(io_context, "ring field_to_binary_ring")?;
+    let binary = rep3::conversion::a2b_selector(share, io_context)?;
+    Ok(Rep3RingShare {
+        a: RingElement(T::cast_from_biguint(&binary.a)),
+        b: RingElement(T::cast_from_biguint(&binary.b)),
+    })
+}
+
+/// Transforms a binary Rep3RingShare into a Rep3PrimeFieldShare of the same value. This is the inverse of [field_to_binary_ring] for values fitting into `T`.
+pub fn binary_ring_to_field<T: IntRing2k, F: PrimeField, N: Rep3Network>(
+    share: &Rep3RingShare<T>,
+    io_context: &mut IoContext<N>,
+) -> std::io::Result<Rep3PrimeFieldShare<F>> {
+    malicious::ensure_semi_honest(io_context, "ring binary_ring_to_field")?;
+    assert!(
+        T::K < F::MODULUS_BIT_SIZE as usize,
+        "ring values must fit into the field"
+    );
+    let biguint_share = Rep3BigUintShare::new(
+        T::cast_to_biguint(&share.a.0),
+        T::cast_to_biguint(&share.b.0),
+    );
+    rep3::conversion::b2a_selector(&biguint_share, io_context)
+}
@@ -12,6 +12,7 @@ use crate::protocols::{
     rep3::{
         conversion::A2BType,
         id::PartyID,
+        malicious,
         network::{IoContext, Rep3Network},
         yao::{
             circuits::GarbledCircuits, evaluator::Rep3Evaluator, garbler::Rep3Garbler,
@@ -35,8 +36,9 @@ pub fn a2b_selector<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
-    match io_context.a2b_type {
-        A2BType::Direct => a2b(x, io_context),
+    malicious::ensure_semi_honest(io_context, "ring a2b_selector")?;
+    match io_context.select_a2b_type(1, T::K) {
+        A2BType::Direct | A2BType::Auto => a2b(x, io_context),
         A2BType::Yao => a2y2b(x, io_context),
     }
 }
@@ -49,8 +51,9 @@ pub fn b2a_selector<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
-    match io_context.a2b_type {
-        A2BType::Direct => b2a(x, io_context),
+    malicious::ensure_semi_honest(io_context, "ring b2a_selector")?;
+    match io_context.select_a2b_type(1, T::K) {
+        A2BType::Direct | A2BType::Auto => b2a(x, io_context),
         A2BType::Yao => b2y2a(x, io_context),
     }
 }
@@ -63,6 +66,7 @@ pub fn a2b<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring a2b")?;
     let mut x01 = Rep3RingShare::zero_share();
     let mut x2 = Rep3RingShare::zero_share();
 
@@ -100,6 +104,7 @@ pub fn b2a<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring b2a")?;
     let mut y = Rep3RingShare::zero_share();
     let mut res = Rep3RingShare::zero_share();
 
@@ -176,6 +181,7 @@ pub fn bit_inject<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring bit_inject")?;
     // standard bit inject
     assert!(x.a.bits() <= 1);
 
@@ -211,6 +217,7 @@ pub fn bit_inject_many<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring bit_inject_many")?;
     // standard bit inject
     assert!(x.iter().all(|a| a.a.bits() <= 1));
 
@@ -252,6 +259,7 @@ pub fn bit_inject_from_bit<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring bit_inject_from_bit")?;
     // standard bit inject
 
     let mut b0 = Rep3RingShare::default();
@@ -286,6 +294,7 @@ pub fn bit_inject_from_bits_many<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring bit_inject_from_bits_many")?;
     let mut b0 = vec![Rep3RingShare::default(); x.len()];
     let mut b1 = vec![Rep3RingShare::default(); x.len()];
     let mut b2 = vec![Rep3RingShare::default(); x.len()];
@@ -322,6 +331,7 @@ pub fn a2y<T: IntRing2k, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "ring a2y")?;
     let [x01, x2] = yao::joint_input_arithmetic_added(x, delta, io_context)?;
 
     let converted = match io_context.id {
@@ -356,6 +366,7 @@ pub fn a2y_streaming<T: IntRing2k, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "ring a2y_streaming")?;
     let [x01, x2] = yao::joint_input_arithmetic_added(x, delta, io_context)?;
 
     let converted = match io_context.id {
@@ -464,6 +475,7 @@ pub fn y2a<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring y2a")?;
     let mut res = Rep3RingShare::zero_share();
 
     match io_context.id {
@@ -502,6 +514,7 @@ pub fn y2a_streaming<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring y2a_streaming")?;
     let mut res = Rep3RingShare::zero_share();
 
     match io_context.id {
@@ -536,6 +549,7 @@ pub fn b2y<T: IntRing2k, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "ring b2y")?;
     let [x01, x2] = yao::joint_input_binary_xored(x, delta, io_context)?;
 
     let converted = match io_context.id {
@@ -573,6 +587,7 @@ pub fn y2b<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring y2b")?;
     let collapsed = GCUtils::collapse_bundle_to_lsb_bits_as_ring(x)?;
 
     let converted = match io_context.id {
@@ -608,6 +623,7 @@ pub fn a2y2b<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring a2y2b")?;
     let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
     let y = a2y(x, delta, io_context)?;
     y2b(y, io_context)
@@ -621,6 +637,7 @@ pub fn a2y2b_streaming<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring a2y2b_streaming")?;
     let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
     let y = a2y_streaming(x, delta, io_context)?;
     y2b(y, io_context)
@@ -634,6 +651,7 @@ pub fn b2y2a<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring b2y2a")?;
     let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
     let y = b2y(x, delta, io_context)?;
     y2a(y, delta, io_context)
@@ -647,6 +665,7 @@ pub fn b2y2a_streaming<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring b2y2a_streaming")?;
     let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
     let y = b2y(x, delta, io_context)?;
     y2a_streaming(y, delta, io_context)
@@ -4,6 +4,7 @@ use super::{
     ring::{bit::Bit, int_ring::IntRing2k, ring_impl::RingElement},
 };
 use crate::protocols::rep3::{
+    malicious,
     network::{IoContext, Rep3Network},
     IoResult,
 };
@@ -90,6 +91,7 @@ fn and_twice<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring and_twice")?;
     let (mut mask1, mask_b) = io_context.rngs.rand.random_elements::<RingElement<T>>();
     mask1 ^= mask_b;
 
@@ -11,6 +11,7 @@ use crate::protocols::{
     rep3::{
         self,
         arithmetic::FieldShare,
+        malicious,
         network::{IoContext, Rep3Network},
         IoResult,
     },
@@ -259,6 +260,7 @@ fn shuffle<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring shuffle")?;
     let len = pi.len();
     debug_assert_eq!(len, input.len());
     let result = match io_context.id {
@@ -378,6 +380,7 @@ fn shuffle_field<F: PrimeField, N: Rep3Network>(
     input: &[Rep3PrimeFieldShare<F>],
     io_context: &mut IoContext<N>,
 ) -> IoResult<Vec<Rep3PrimeFieldShare<F>>> {
+    malicious::ensure_semi_honest(io_context, "ring shuffle_field")?;
     let len = pi.len();
     debug_assert_eq!(len, input.len());
     let result = match io_context.id {
@@ -500,6 +503,7 @@ fn shuffle_reveal<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring shuffle_reveal")?;
     let len = pi.len();
     debug_assert_eq!(len, input.len());
     let result = match io_context.id {
@@ -566,6 +570,7 @@ fn unshuffle<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring unshuffle")?;
     let len = pi.len();
     debug_assert_eq!(len, input.len());
     let result = match io_context.id {
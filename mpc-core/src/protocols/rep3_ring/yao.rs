@@ -10,6 +10,7 @@ use crate::protocols::{
     rep3::{
         self,
         id::PartyID,
+        malicious,
         network::{IoContext, Rep3Network},
         yao::{
             circuits::GarbledCircuits, evaluator::Rep3Evaluator, garbler::Rep3Garbler, GCInputs,
@@ -101,6 +102,7 @@ pub fn joint_input_arithmetic_added<T: IntRing2k, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<[BinaryBundle<WireMod2>; 2]> {
+    malicious::ensure_semi_honest(io_context, "ring joint_input_arithmetic_added")?;
     joint_input_arithmetic_added_many(&[x], delta, io_context)
 }
 
@@ -110,6 +112,7 @@ pub fn joint_input_arithmetic_added_many<T: IntRing2k, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<[BinaryBundle<WireMod2>; 2]> {
+    malicious::ensure_semi_honest(io_context, "ring joint_input_arithmetic_added_many")?;
     let id = io_context.id;
     let n_inputs = x.len();
     let n_bits = T::K;
@@ -197,6 +200,7 @@ pub fn input_ring_id2_many<T: IntRing2k, N: Rep3Network>(
     n_inputs: usize,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "ring input_ring_id2_many")?;
     let id = io_context.id;
     let n_bits = T::K;
     let bits = n_inputs * n_bits;
@@ -257,6 +261,7 @@ pub fn input_ring_id2<T: IntRing2k, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<BinaryBundle<WireMod2>> {
+    malicious::ensure_semi_honest(io_context, "ring input_ring_id2")?;
     let x = x.map(|x| vec![x]);
     input_ring_id2_many(x, delta, 1, io_context)
 }
@@ -267,6 +272,7 @@ pub fn joint_input_binary_xored<T: IntRing2k, N: Rep3Network>(
     delta: Option<WireMod2>,
     io_context: &mut IoContext<N>,
 ) -> IoResult<[BinaryBundle<WireMod2>; 2]> {
+    malicious::ensure_semi_honest(io_context, "ring joint_input_binary_xored")?;
     let id = io_context.id;
     let bitlen = T::K;
 
@@ -333,6 +339,7 @@ pub fn ring_to_field_many<T: IntRing2k, F: PrimeField, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring ring_to_field_many")?;
     // Special case for Bit
     if TypeId::of::<T>() == TypeId::of::<Bit>() {
         // SAFTEY: We already checked that the type matches
@@ -453,6 +460,7 @@ pub fn field_to_ring_many<F: PrimeField, T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring field_to_ring_many")?;
     let num_inputs = inputs.len();
     let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
 
@@ -666,6 +674,7 @@ pub fn upcast_many<T: IntRing2k, U: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<U>,
 {
+    malicious::ensure_semi_honest(io_context, "ring upcast_many")?;
     assert!(T::K < U::K);
 
     // Special case for Bit
@@ -698,6 +707,7 @@ pub fn ring_div_power_2_many<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring ring_div_power_2_many")?;
     let num_inputs = inputs.len();
 
     if divisor_bit == 0 {
@@ -726,6 +736,7 @@ pub fn ring_div_power_2<T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring ring_div_power_2")?;
     let res = ring_div_power_2_many(&[inputs], io_context, divisor_bit)?;
     Ok(res[0])
 }
@@ -740,6 +751,7 @@ pub fn decompose_field_to_rings_many<F: PrimeField, T: IntRing2k, N: Rep3Network
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring decompose_field_to_rings_many")?;
     let num_inputs = inputs.len();
     let total_output_elements = num_decomps_per_field * num_inputs;
     let delta = io_context.rngs.generate_random_garbler_delta(io_context.id);
@@ -877,6 +889,7 @@ pub fn decompose_field_to_rings<F: PrimeField, T: IntRing2k, N: Rep3Network>(
 where
     Standard: Distribution<T>,
 {
+    malicious::ensure_semi_honest(io_context, "ring decompose_field_to_rings")?;
     decompose_field_to_rings_many(
         &[inputs],
         io_context,
@@ -0,0 +1,271 @@
+//! # RSS
+//!
+//! This module implements semi-honest replicated secret sharing for a small number of n parties tolerating t < n / 2 corrupted parties, generalizing the 3-party replicated sharing of [`super::rep3`]. A value x is split into additive shares x_T, one for each set T of t parties, such that x is the sum of all x_T. Each party holds the additive shares x_T of all sets T it is not part of. Thus, any t parties miss at least one additive share, while any t + 1 parties know all of them. Since the number of additive shares is n choose t, this is only practical for small n, e.g., 4 parties tolerating one corruption, which allows to add a fourth (observer) party to a REP3 deployment.
+//!
+//! Multiplications are computed locally by assigning each product x_T1 * y_T2 to a party which is neither part of T1 nor of T2, which results in an additive sharing of the product. Each party then reshares its summand with fresh replicated shares as proposed by [Maurer](https://crypto.ethz.ch/publications/files/Maurer06.pdf).
+
+use ark_ff::PrimeField;
+use itertools::{izip, Itertools};
+use rand::{CryptoRng, Rng, SeedableRng};
+
+use crate::RngType;
+
+use super::shamir::network::ShamirNetwork;
+
+pub mod arithmetic;
+
+pub use arithmetic::types::RssPrimeFieldShare;
+
+type IoResult<T> = std::io::Result<T>;
+
+/// Returns all sets of `threshold` parties in lexicographic order. The i-th additive share of a value belongs to the i-th set.
+pub fn unqualified_sets(num_parties: usize, threshold: usize) -> Vec<Vec<usize>> {
+    (0..num_parties).combinations(threshold).collect()
+}
+
+// Returns the indices of the sets the party is not part of, i.e., of the additive shares it holds.
+fn held_sets(id: usize, sets: &[Vec<usize>]) -> Vec<usize> {
+    sets.iter()
+        .enumerate()
+        .filter(|(_, set)| !set.contains(&id))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+// Splits the value into the given number of random additive shares.
+fn split<F: PrimeField, R: Rng>(val: F, num_sets: usize, rng: &mut R) -> Vec<F> {
+    let mut shares = (0..num_sets - 1).map(|_| F::rand(rng)).collect_vec();
+    let sum = shares.iter().sum::<F>();
+    shares.push(val - sum);
+    shares
+}
+
+fn check_parameters(num_parties: usize, threshold: usize) -> eyre::Result<()> {
+    if 2 * threshold >= num_parties {
+        eyre::bail!(
+            "Replicated sharing requires an honest majority, but the threshold is {} for {} parties",
+            threshold,
+            num_parties
+        );
+    }
+    Ok(())
+}
+
+/// Secret shares a field element using replicated secret sharing for `num_parties` parties and the provided random number generator. The i-th output is the share of party i.
+pub fn share_field_element<F: PrimeField, R: Rng + CryptoRng>(
+    val: F,
+    num_parties: usize,
+    threshold: usize,
+    rng: &mut R,
+) -> Vec<RssPrimeFieldShare<F>> {
+    let sets = unqualified_sets(num_parties, threshold);
+    let additive = split(val, sets.len(), rng);
+    (0..num_parties)
+        .map(|id| {
+            RssPrimeFieldShare::new(
+                held_sets(id, &sets)
+                    .into_iter()
+                    .map(|s| additive[s])
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Secret shares a vector of field elements using replicated secret sharing for `num_parties` parties and the provided random number generator. The i-th output contains the shares of party i.
+pub fn share_field_elements<F: PrimeField, R: Rng + CryptoRng>(
+    vals: &[F],
+    num_parties: usize,
+    threshold: usize,
+    rng: &mut R,
+) -> Vec<Vec<RssPrimeFieldShare<F>>> {
+    let mut result = (0..num_parties)
+        .map(|_| Vec::with_capacity(vals.len()))
+        .collect_vec();
+    for val in vals {
+        let shares = share_field_element(*val, num_parties, threshold, rng);
+        for (r, s) in izip!(&mut result, shares) {
+            r.push(s);
+        }
+    }
+    result
+}
+
+/// Reconstructs a field element from the replicated shares of all parties, where `shares[i]` is the share of party i.
+pub fn combine_field_element<F: PrimeField>(
+    shares: &[RssPrimeFieldShare<F>],
+    threshold: usize,
+) -> eyre::Result<F> {
+    let res = combine_field_elements(
+        &shares.iter().map(|s| vec![s.to_owned()]).collect_vec(),
+        threshold,
+    )?;
+    Ok(res[0])
+}
+
+/// Reconstructs a vector of field elements from the replicated shares of all parties, where `shares[i]` contains the shares of party i.
+pub fn combine_field_elements<F: PrimeField>(
+    shares: &[Vec<RssPrimeFieldShare<F>>],
+    threshold: usize,
+) -> eyre::Result<Vec<F>> {
+    let num_parties = shares.len();
+    check_parameters(num_parties, threshold)?;
+    let sets = unqualified_sets(num_parties, threshold);
+    let plan = OpenPlan::new(&sets, num_parties);
+    let num_vals = shares[0].len();
+    if shares.iter().any(|s| s.len() != num_vals) {
+        eyre::bail!("All parties must provide the same number of shares");
+    }
+    (0..num_vals)
+        .map(|i| {
+            let values = shares.iter().map(|s| s[i].values.as_slice()).collect_vec();
+            if izip!(&values, &plan.num_held).any(|(v, n)| v.len() != *n) {
+                eyre::bail!(
+                    "Share of value {} has an invalid number of additive shares",
+                    i
+                );
+            }
+            Ok(plan.reconstruct(&values))
+        })
+        .collect()
+}
+
+// For each additive share, the party which provides it during opening and its position in the share of that party.
+struct OpenPlan {
+    sources: Vec<(usize, usize)>,
+    num_held: Vec<usize>,
+}
+
+impl OpenPlan {
+    fn new(sets: &[Vec<usize>], num_parties: usize) -> Self {
+        let held = (0..num_parties).map(|id| held_sets(id, sets)).collect_vec();
+        let sources = (0..sets.len())
+            .map(|s| {
+                let party = (0..num_parties)
+                    .find(|p| !sets[s].contains(p))
+                    .expect("honest majority");
+                let pos = held[party].binary_search(&s).expect("party holds the set");
+                (party, pos)
+            })
+            .collect();
+        Self {
+            sources,
+            num_held: held.iter().map(|h| h.len()).collect(),
+        }
+    }
+
+    fn reconstruct<F: PrimeField>(&self, values: &[&[F]]) -> F {
+        self.sources
+            .iter()
+            .map(|(party, pos)| values[*party][*pos])
+            .sum()
+    }
+}
+
+/// This struct holds all necessary information for the replicated sharing protocol. It contains a [`ShamirNetwork`], which is used for the communication between all parties, the threshold and the precomputed assignment of the additive shares.
+pub struct RssProtocol<N: ShamirNetwork> {
+    /// The threshold, i.e., the maximum number of corrupted parties
+    pub threshold: usize,
+    /// The underlying [`ShamirNetwork`]
+    pub network: N,
+    rng: RngType,
+    // for each party, the indices of the additive shares it holds
+    held: Vec<Vec<usize>>,
+    // the positions of the factors of the products this party computes during multiplication
+    mul_terms: Vec<(usize, usize)>,
+    open_plan: OpenPlan,
+}
+
+impl<N: ShamirNetwork> RssProtocol<N> {
+    /// Creates a new [`RssProtocol`] from a network. Fails if the threshold does not allow for an honest majority.
+    pub fn new(threshold: usize, network: N) -> eyre::Result<Self> {
+        let num_parties = network.get_num_parties();
+        check_parameters(num_parties, threshold)?;
+        let id = network.get_id();
+        let sets = unqualified_sets(num_parties, threshold);
+        let held = (0..num_parties)
+            .map(|id| held_sets(id, &sets))
+            .collect_vec();
+
+        // each product of two additive shares is computed by the first party which holds both of them
+        let mut mul_terms = Vec::new();
+        for (s1, set1) in sets.iter().enumerate() {
+            for (s2, set2) in sets.iter().enumerate() {
+                let party = (0..num_parties)
+                    .find(|p| !set1.contains(p) && !set2.contains(p))
+                    .expect("honest majority");
+                if party == id {
+                    let pos1 = held[id].binary_search(&s1).expect("party holds the set");
+                    let pos2 = held[id].binary_search(&s2).expect("party holds the set");
+                    mul_terms.push((pos1, pos2));
+                }
+            }
+        }
+        let open_plan = OpenPlan::new(&sets, num_parties);
+
+        Ok(Self {
+            threshold,
+            network,
+            rng: RngType::from_entropy(),
+            held,
+            mul_terms,
+            open_plan,
+        })
+    }
+
+    /// Returns the id of this party.
+    pub fn id(&self) -> usize {
+        self.network.get_id()
+    }
+
+    /// Generates a random shared field element.
+    pub fn rand<F: PrimeField>(&mut self) -> IoResult<RssPrimeFieldShare<F>> {
+        Ok(self.rand_vec(1)?.pop().expect("one element"))
+    }
+
+    /// Generates a vector of random shared field elements.
+    pub fn rand_vec<F: PrimeField>(&mut self, len: usize) -> IoResult<Vec<RssPrimeFieldShare<F>>> {
+        let values = (0..len).map(|_| F::rand(&mut self.rng)).collect_vec();
+        self.reshare_sum(&values)
+    }
+
+    // Holds the share of the public value, which is added to the first additive share.
+    fn holds_first_share(&self) -> bool {
+        self.held[self.id()].first() == Some(&0)
+    }
+
+    // Every party reshares its value with fresh replicated shares, and the resulting shares are summed up.
+    fn reshare_sum<F: PrimeField>(&mut self, values: &[F]) -> IoResult<Vec<RssPrimeFieldShare<F>>> {
+        let num_sets = self.open_plan.sources.len();
+        let num_parties = self.network.get_num_parties();
+        let mut data = self
+            .held
+            .iter()
+            .map(|h| Vec::with_capacity(h.len() * values.len()))
+            .collect_vec();
+        for val in values {
+            let additive = split(*val, num_sets, &mut self.rng);
+            for (d, held) in izip!(&mut data, &self.held) {
+                d.extend(held.iter().map(|s| additive[*s]));
+            }
+        }
+        let rcv = self.network.send_and_recv_each_many(data)?;
+        let num_held = self.held[self.id()].len();
+        if rcv.len() != num_parties || rcv.iter().any(|r| r.len() != num_held * values.len()) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "During resharing in RSS: Invalid number of elements received",
+            ));
+        }
+        let mut result = vec![F::zero(); num_held * values.len()];
+        for r in rcv {
+            for (res, r) in izip!(&mut result, r) {
+                *res += r;
+            }
+        }
+        Ok(result
+            .chunks_exact(num_held)
+            .map(|c| RssPrimeFieldShare::new(c.to_vec()))
+            .collect())
+    }
+}
@@ -0,0 +1,139 @@
+//! Arithmetic
+//!
+//! This module contains operations with arithmetic shares
+
+use ark_ff::PrimeField;
+use itertools::{izip, Itertools};
+
+use super::{IoResult, RssProtocol, ShamirNetwork};
+
+mod ops;
+pub(super) mod types;
+
+type FieldShare<F> = types::RssPrimeFieldShare<F>;
+
+/// Performs addition between two shares.
+pub fn add<F: PrimeField>(a: FieldShare<F>, b: &FieldShare<F>) -> FieldShare<F> {
+    a + b
+}
+
+/// Performs addition between two shares where the result is stored in `a`.
+pub fn add_assign<F: PrimeField>(a: &mut FieldShare<F>, b: &FieldShare<F>) {
+    *a += b;
+}
+
+/// Performs subtraction between two shares.
+pub fn sub<F: PrimeField>(a: FieldShare<F>, b: &FieldShare<F>) -> FieldShare<F> {
+    a - b
+}
+
+/// Performs subtraction between two shares where the result is stored in `a`.
+pub fn sub_assign<F: PrimeField>(a: &mut FieldShare<F>, b: &FieldShare<F>) {
+    *a -= b;
+}
+
+/// Performs negation of a share.
+pub fn neg<F: PrimeField>(a: FieldShare<F>) -> FieldShare<F> {
+    -a
+}
+
+/// Performs addition between a share and a public value. The value is added to the first additive share, i.e., only the parties holding it change their share.
+pub fn add_public<F: PrimeField, N: ShamirNetwork>(
+    shared: FieldShare<F>,
+    public: F,
+    rss: &RssProtocol<N>,
+) -> FieldShare<F> {
+    let mut res = shared;
+    add_assign_public(&mut res, public, rss);
+    res
+}
+
+/// Performs addition between a share and a public value where the result is stored in `shared`.
+pub fn add_assign_public<F: PrimeField, N: ShamirNetwork>(
+    shared: &mut FieldShare<F>,
+    public: F,
+    rss: &RssProtocol<N>,
+) {
+    if rss.holds_first_share() {
+        shared.values[0] += public;
+    }
+}
+
+/// Performs multiplication between a share and a public value.
+pub fn mul_public<F: PrimeField>(shared: FieldShare<F>, public: F) -> FieldShare<F> {
+    shared * public
+}
+
+/// Performs multiplication between a share and a public value where the result is stored in `shared`.
+pub fn mul_assign_public<F: PrimeField>(shared: &mut FieldShare<F>, public: F) {
+    *shared *= public;
+}
+
+/// Transforms a public value into a shared value.
+pub fn promote_to_trivial_share<F: PrimeField, N: ShamirNetwork>(
+    public: F,
+    rss: &RssProtocol<N>,
+) -> FieldShare<F> {
+    let num_held = rss.held[rss.id()].len();
+    add_public(FieldShare::zero_share(num_held), public, rss)
+}
+
+/// Performs multiplication between two shares.
+pub fn mul<F: PrimeField, N: ShamirNetwork>(
+    a: &FieldShare<F>,
+    b: &FieldShare<F>,
+    rss: &mut RssProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let res = mul_vec(std::slice::from_ref(a), std::slice::from_ref(b), rss)?;
+    Ok(res.into_iter().next().expect("one element"))
+}
+
+/// Performs element-wise multiplication of two slices of shares. Each party computes its assigned products of additive shares locally and reshares the sum, which requires one communication round.
+pub fn mul_vec<F: PrimeField, N: ShamirNetwork>(
+    a: &[FieldShare<F>],
+    b: &[FieldShare<F>],
+    rss: &mut RssProtocol<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    debug_assert_eq!(a.len(), b.len());
+    let local = izip!(a, b)
+        .map(|(a, b)| {
+            rss.mul_terms
+                .iter()
+                .map(|(i, j)| a.values[*i] * b.values[*j])
+                .sum::<F>()
+        })
+        .collect_vec();
+    rss.reshare_sum(&local)
+}
+
+/// Opens a shared value and returns the corresponding field element.
+pub fn open<F: PrimeField, N: ShamirNetwork>(
+    a: &FieldShare<F>,
+    rss: &mut RssProtocol<N>,
+) -> IoResult<F> {
+    let res = open_vec(std::slice::from_ref(a), rss)?;
+    Ok(res[0])
+}
+
+/// Opens a vector of shared values and returns the corresponding field elements.
+pub fn open_vec<F: PrimeField, N: ShamirNetwork>(
+    a: &[FieldShare<F>],
+    rss: &mut RssProtocol<N>,
+) -> IoResult<Vec<F>> {
+    let values = a.iter().flat_map(|a| a.values.to_owned()).collect_vec();
+    let rcv = rss.network.broadcast(values)?;
+    if izip!(&rcv, &rss.open_plan.num_held).any(|(r, n)| r.len() != n * a.len()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "During opening in RSS: Invalid number of elements received",
+        ));
+    }
+    Ok((0..a.len())
+        .map(|i| {
+            let values = izip!(&rcv, &rss.open_plan.num_held)
+                .map(|(r, n)| &r[i * n..(i + 1) * n])
+                .collect_vec();
+            rss.open_plan.reconstruct(&values)
+        })
+        .collect())
+}
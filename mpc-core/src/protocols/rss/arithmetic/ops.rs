@@ -0,0 +1,114 @@
+use ark_ff::PrimeField;
+use itertools::izip;
+
+use super::types::RssPrimeFieldShare;
+
+impl<F: PrimeField> std::ops::Add for RssPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += &rhs;
+        self
+    }
+}
+
+impl<F: PrimeField> std::ops::Add<&RssPrimeFieldShare<F>> for RssPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn add(mut self, rhs: &Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<F: PrimeField> std::ops::Add<&RssPrimeFieldShare<F>> for &'_ RssPrimeFieldShare<F> {
+    type Output = RssPrimeFieldShare<F>;
+
+    fn add(self, rhs: &RssPrimeFieldShare<F>) -> Self::Output {
+        self.clone() + rhs
+    }
+}
+
+impl<F: PrimeField> std::ops::AddAssign for RssPrimeFieldShare<F> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self += &rhs;
+    }
+}
+
+impl<F: PrimeField> std::ops::AddAssign<&RssPrimeFieldShare<F>> for RssPrimeFieldShare<F> {
+    fn add_assign(&mut self, rhs: &Self) {
+        debug_assert_eq!(self.values.len(), rhs.values.len());
+        for (a, b) in izip!(&mut self.values, &rhs.values) {
+            *a += b;
+        }
+    }
+}
+
+impl<F: PrimeField> std::ops::Sub for RssPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        self -= &rhs;
+        self
+    }
+}
+
+impl<F: PrimeField> std::ops::Sub<&RssPrimeFieldShare<F>> for RssPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: &Self) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<F: PrimeField> std::ops::Sub<&RssPrimeFieldShare<F>> for &'_ RssPrimeFieldShare<F> {
+    type Output = RssPrimeFieldShare<F>;
+
+    fn sub(self, rhs: &RssPrimeFieldShare<F>) -> Self::Output {
+        self.clone() - rhs
+    }
+}
+
+impl<F: PrimeField> std::ops::SubAssign for RssPrimeFieldShare<F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self -= &rhs;
+    }
+}
+
+impl<F: PrimeField> std::ops::SubAssign<&RssPrimeFieldShare<F>> for RssPrimeFieldShare<F> {
+    fn sub_assign(&mut self, rhs: &Self) {
+        debug_assert_eq!(self.values.len(), rhs.values.len());
+        for (a, b) in izip!(&mut self.values, &rhs.values) {
+            *a -= b;
+        }
+    }
+}
+
+impl<F: PrimeField> std::ops::Mul<F> for RssPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: F) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+impl<F: PrimeField> std::ops::MulAssign<F> for RssPrimeFieldShare<F> {
+    fn mul_assign(&mut self, rhs: F) {
+        for a in self.values.iter_mut() {
+            *a *= rhs;
+        }
+    }
+}
+
+impl<F: PrimeField> std::ops::Neg for RssPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn neg(mut self) -> Self::Output {
+        for a in self.values.iter_mut() {
+            *a = -*a;
+        }
+        self
+    }
+}
@@ -0,0 +1,27 @@
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/// This type represents a replicated share of the RSS protocol, i.e., the additive shares of the value which belong to the sets of parties this party is not part of.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RssPrimeFieldShare<F: PrimeField> {
+    pub(crate) values: Vec<F>,
+}
+
+impl<F: PrimeField> RssPrimeFieldShare<F> {
+    /// Wraps the additive shares held by a party, ordered by the index of their set (see [`unqualified_sets`](crate::protocols::rss::unqualified_sets)).
+    pub fn new(values: Vec<F>) -> Self {
+        Self { values }
+    }
+
+    /// Returns a zero share consisting of `num_held` additive shares.
+    pub fn zero_share(num_held: usize) -> Self {
+        Self {
+            values: vec![F::zero(); num_held],
+        }
+    }
+
+    /// Unwraps the additive shares held by a party.
+    pub fn inner(self) -> Vec<F> {
+        self.values
+    }
+}
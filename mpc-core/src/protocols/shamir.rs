@@ -7,20 +7,25 @@ use ark_ff::PrimeField;
 use itertools::izip;
 use network::ShamirNetwork;
 use rngs::ShamirRng;
-use std::time::Instant;
+use std::{collections::BTreeSet, time::Instant};
 
 use rand::{CryptoRng, Rng, SeedableRng};
 
 use crate::RngType;
 
 pub mod arithmetic;
+pub mod async_network;
 pub mod core;
 pub mod network;
+pub mod packed;
 pub mod pointshare;
 pub mod poly;
+pub mod reshare;
 mod rngs;
+pub mod triples;
 
 pub use arithmetic::types::ShamirPrimeFieldShare;
+pub use packed::ShamirPackedShare;
 pub use pointshare::types::ShamirPointShare;
 
 type IoResult<T> = std::io::Result<T>;
@@ -172,6 +177,53 @@ pub fn combine_curve_point<C: CurveGroup>(
     Ok(rec)
 }
 
+/// Reconstructs a field element from its Shamir shares using Berlekamp-Welch decoding. In contrast to [`combine_field_element`], all given shares are used, such that up to (`shares.len()` - `degree` - 1) / 2 corrupted shares are corrected. Returns the secret and the party indices (taken from `coeffs`) of the corrupted shares.
+pub fn combine_field_element_robust<F: PrimeField>(
+    shares: &[ShamirShare<F>],
+    coeffs: &[usize],
+    degree: usize,
+) -> eyre::Result<(F, Vec<usize>)> {
+    if shares.len() != coeffs.len() {
+        eyre::bail!(
+            "Number of shares ({}) does not match number of party indices ({})",
+            shares.len(),
+            coeffs.len()
+        );
+    }
+    let shares = ShamirShare::convert_slice(shares);
+    let Some((poly, errors)) = core::berlekamp_welch(shares, coeffs, degree) else {
+        eyre::bail!("Too many corrupted shares to reconstruct the secret");
+    };
+    Ok((poly[0], errors.into_iter().map(|i| coeffs[i]).collect()))
+}
+
+/// Reconstructs a curve point from its Shamir shares after checking that all given shares lie on a polynomial of degree `degree`. In contrast to [`combine_field_element_robust`], corrupted shares are only detected but cannot be corrected, since Berlekamp-Welch decoding is not possible over the group. Returns an error if the shares are inconsistent.
+pub fn combine_curve_point_robust<C: CurveGroup>(
+    shares: &[ShamirPointShare<C>],
+    coeffs: &[usize],
+    degree: usize,
+) -> eyre::Result<C> {
+    if shares.len() != coeffs.len() {
+        eyre::bail!(
+            "Number of shares ({}) does not match number of party indices ({})",
+            shares.len(),
+            coeffs.len()
+        );
+    }
+    if shares.len() <= degree {
+        eyre::bail!(
+            "Not enough shares to reconstruct the secret: {} <= {degree}",
+            shares.len()
+        );
+    }
+    let shares = ShamirPointShare::convert_slice(shares);
+    if !core::is_consistent_point(shares, coeffs, degree) {
+        eyre::bail!("Inconsistent shares, cannot reconstruct the secret");
+    }
+    let lagrange = core::lagrange_from_coeff(&coeffs[..=degree]);
+    Ok(core::reconstruct_point(&shares[..=degree], &lagrange))
+}
+
 /// This type is used to construct a [`ShamirProtocol`].
 /// Preprocess `amount` number of corre;ated randomness pairs that are consumed while using the protocol.
 pub struct ShamirPreprocessing<F: PrimeField, N: ShamirNetwork> {
@@ -249,6 +301,8 @@ impl<F: PrimeField, N: ShamirNetwork> From<ShamirPreprocessing<F, N>> for Shamir
             network: value.network,
             rng_buffer: value.rng_buffer,
             generation_amount: Self::DEFAULT_PAIR_GEN_AMOUNT,
+            robust_open: false,
+            corrupted_parties: BTreeSet::new(),
         }
     }
 }
@@ -269,6 +323,8 @@ pub struct ShamirProtocol<F: PrimeField, N: ShamirNetwork> {
     pub network: N,
     rng_buffer: ShamirRng<F>,
     generation_amount: usize,
+    robust_open: bool,
+    corrupted_parties: BTreeSet<usize>,
 }
 
 impl<F: PrimeField, N: ShamirNetwork> ShamirProtocol<F, N> {
@@ -286,9 +342,34 @@ impl<F: PrimeField, N: ShamirNetwork> ShamirProtocol<F, N> {
             network: self.network.fork()?,
             rng_buffer: self.rng_buffer.fork_with_pairs(amount),
             generation_amount: self.generation_amount,
+            robust_open: self.robust_open,
+            corrupted_parties: BTreeSet::new(),
         })
     }
 
+    /// Enables or disables robust openings. If enabled, openings receive the shares of all parties and reconstruct with Berlekamp-Welch decoding, such that they succeed even if up to (num_parties - threshold - 1) / 2 parties send corrupted shares. The parties that sent corrupted shares are recorded and can be queried with [`Self::corrupted_parties`].
+    pub fn set_robust_open(&mut self, robust_open: bool) {
+        self.robust_open = robust_open;
+    }
+
+    /// Returns the ids of the parties which were identified to have sent corrupted shares during robust openings.
+    pub fn corrupted_parties(&self) -> &BTreeSet<usize> {
+        &self.corrupted_parties
+    }
+
+    // Records the parties which sent corrupted shares, where errors contains the party indices starting at 1.
+    pub(crate) fn record_corrupted_parties(&mut self, errors: Vec<usize>) {
+        for e in errors {
+            if self.corrupted_parties.insert(e - 1) {
+                tracing::warn!(
+                    "Party {}: party {} sent a corrupted share during opening",
+                    self.network.get_id(),
+                    e - 1
+                );
+            }
+        }
+    }
+
     /// Get a correlated randomness pair
     pub fn get_pair(&mut self) -> std::io::Result<(F, F)> {
         if self.rng_buffer.r_t.is_empty() {
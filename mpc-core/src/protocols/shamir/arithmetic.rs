@@ -173,6 +173,10 @@ pub fn open<F: PrimeField, N: ShamirNetwork>(
     a: ShamirShare<F>,
     shamir: &mut ShamirProtocol<F, N>,
 ) -> IoResult<F> {
+    if shamir.robust_open {
+        let res = open_vec_robust(vec![a.a], shamir)?;
+        return Ok(res[0]);
+    }
     let rcv = shamir.network.broadcast_next(a.a, shamir.threshold + 1)?;
     let res = core::reconstruct(&rcv, &shamir.open_lagrange_t);
     Ok(res)
@@ -184,6 +188,9 @@ pub fn open_vec<F: PrimeField, N: ShamirNetwork>(
     shamir: &mut ShamirProtocol<F, N>,
 ) -> IoResult<Vec<F>> {
     let a_a = ShamirShare::convert_slice(a);
+    if shamir.robust_open {
+        return open_vec_robust(a_a.to_owned(), shamir);
+    }
 
     let rcv = shamir
         .network
@@ -204,6 +211,120 @@ pub fn open_vec<F: PrimeField, N: ShamirNetwork>(
     Ok(res)
 }
 
+// Opens the shares by receiving the shares of all parties. The whole batch is first checked with a single random linear combination, whose coefficients are sampled locally after all shares were received. Only if this check fails, every value is decoded with Berlekamp-Welch and the parties which sent corrupted shares are recorded in the protocol.
+fn open_vec_robust<F: PrimeField, N: ShamirNetwork>(
+    a: Vec<F>,
+    shamir: &mut ShamirProtocol<F, N>,
+) -> IoResult<Vec<F>> {
+    let len = a.len();
+    let rcv = shamir.network.broadcast(a)?;
+    if rcv.iter().any(|r| r.len() != len) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "During robust opening: Invalid number of elements received",
+        ));
+    }
+    let points = (1..=rcv.len()).collect::<Vec<_>>();
+    if core::is_consistent_batch(&rcv, &points, shamir.threshold, &mut rand::thread_rng()) {
+        let lagrange = core::lagrange_from_coeff(&points[..=shamir.threshold]);
+        let res = (0..len)
+            .map(|i| {
+                let shares = rcv[..=shamir.threshold]
+                    .iter()
+                    .map(|r| r[i])
+                    .collect::<Vec<_>>();
+                core::reconstruct(&shares, &lagrange)
+            })
+            .collect();
+        return Ok(res);
+    }
+    let mut res = Vec::with_capacity(len);
+    for i in 0..len {
+        let shares = rcv.iter().map(|r| r[i]).collect::<Vec<_>>();
+        let (poly, errors) =
+            core::berlekamp_welch(&shares, &points, shamir.threshold).ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "During robust opening: Too many corrupted shares received",
+                )
+            })?;
+        shamir.record_corrupted_parties(errors.into_iter().map(|e| points[e]).collect());
+        res.push(poly[0]);
+    }
+    Ok(res)
+}
+
+/// Verifies that the given values are shared with polynomials of degree threshold, e.g., before inputs of a dealer are used in multiplications. To that end, a random linear combination of all shares, masked with a random share, is opened towards all parties, who check that the received shares lie on a polynomial of degree threshold. The coefficients are the powers of a random challenge, which is only revealed after the shares are fixed, such that sharings of a larger degree are detected except with probability a.len() / |F|. Requires a single communication round after opening the challenge and returns an error if the check fails.
+pub fn verify_degree_vec<F: PrimeField, N: ShamirNetwork>(
+    a: &[ShamirShare<F>],
+    shamir: &mut ShamirProtocol<F, N>,
+) -> IoResult<()> {
+    if a.is_empty() {
+        return Ok(());
+    }
+    let mask = shamir.rand()?;
+    let challenge = shamir.rand()?;
+    let challenge = open(challenge, shamir)?;
+
+    let mut combined = mask.a;
+    let mut pow = challenge;
+    for x in a {
+        combined += x.a * pow;
+        pow *= challenge;
+    }
+
+    let rcv = shamir.network.broadcast(combined)?;
+    let points = (1..=rcv.len()).collect::<Vec<_>>();
+    match core::berlekamp_welch(&rcv, &points, shamir.threshold) {
+        Some((_, errors)) if errors.is_empty() => Ok(()),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Degree check of Shamir shares failed",
+        )),
+    }
+}
+
+/// Opens a vector of shared values towards a single party. Only `target` learns the corresponding field elements,
+/// all other parties return `None`.
+pub fn open_vec_to<F: PrimeField, N: ShamirNetwork>(
+    a: &[ShamirShare<F>],
+    target: usize,
+    shamir: &mut ShamirProtocol<F, N>,
+) -> IoResult<Option<Vec<F>>> {
+    let id = shamir.network.get_id();
+    let num_parties = shamir.network.get_num_parties();
+    let a_a = ShamirShare::convert_slice(a);
+    // the target reconstructs with the shares of the threshold parties before it, matching open_lagrange_t
+    let distance = (target + num_parties - id) % num_parties;
+    if id == target {
+        let mut rcv = Vec::with_capacity(shamir.threshold + 1);
+        rcv.push(a_a.to_owned());
+        for r in 1..=shamir.threshold {
+            let other_id = (id + num_parties - r) % num_parties;
+            let shares = shamir.network.recv_many::<F>(other_id)?;
+            if shares.len() != a.len() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "received wrong number of shares while opening",
+                ));
+            }
+            rcv.push(shares);
+        }
+        let res = (0..a.len())
+            .map(|i| {
+                let shares = rcv.iter().map(|r| r[i]).collect::<Vec<_>>();
+                core::reconstruct(&shares, &shamir.open_lagrange_t)
+            })
+            .collect();
+        Ok(Some(res))
+    } else {
+        if distance <= shamir.threshold {
+            shamir.network.send_many(target, a_a)?;
+        }
+        Ok(None)
+    }
+}
+
 /*
 fn neg_vec_in_place(vec: &mut ShamirShare<F>Vec) {
     for a in vec.a.iter_mut() {
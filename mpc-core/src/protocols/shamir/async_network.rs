@@ -0,0 +1,420 @@
+//! Shamir Async Network
+//!
+//! This module contains an async (tokio) variant of the network interface for the Shamir MPC protocol, an implementation using the [mpc_net] crate, and an adapter to run the existing blocking protocols on top of it.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use bytes::{Bytes, BytesMut};
+use eyre::{bail, eyre, Report};
+use mpc_net::{
+    abort::AbortReason, channel::ChannelHandle, config::NetworkConfig, stats::NetworkStats,
+    MpcNetworkHandler,
+};
+use std::{collections::HashMap, future::Future, sync::Arc, time::Instant};
+use tokio::runtime::Handle;
+
+use super::network::ShamirNetwork;
+
+/// Serializes the data to be sent over the network.
+fn serialize<F: CanonicalSerialize + ?Sized>(data: &F) -> std::io::Result<Bytes> {
+    let size = data.serialized_size(ark_serialize::Compress::No);
+    let mut ser_data = Vec::with_capacity(size);
+    data.serialize_uncompressed(&mut ser_data)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    Ok(Bytes::from(ser_data))
+}
+
+/// Deserializes data received over the network.
+fn deserialize<F: CanonicalDeserialize>(data: &[u8]) -> std::io::Result<F> {
+    F::deserialize_uncompressed(data)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// This trait defines the async network interface for the Shamir protocol. It mirrors [`ShamirNetwork`], but only the transmission of bytes has to be implemented, the (de)serialization is done by the default implementations. The data is serialized before the returned futures are created, so they do not borrow it.
+pub trait ShamirAsyncNetwork: Send {
+    /// Returns the id of the party. The id is in the range 0 <= id < num_parties
+    fn get_id(&self) -> usize;
+
+    /// Returns the number of parties participating in the MPC protocol.
+    fn get_num_parties(&self) -> usize;
+
+    /// Sends bytes over the network to the target party.
+    fn send_bytes(
+        &mut self,
+        target: usize,
+        data: Bytes,
+    ) -> impl Future<Output = std::io::Result<()>> + Send;
+
+    /// Receives bytes over the network from the party with the given id.
+    fn recv_bytes(&mut self, from: usize)
+        -> impl Future<Output = std::io::Result<BytesMut>> + Send;
+
+    /// Fork the network into two separate instances with their own connections
+    fn fork(&mut self) -> impl Future<Output = std::io::Result<Self>> + Send
+    where
+        Self: Sized;
+
+    /// Returns a snapshot of the communication statistics of the party, which are shared by all forks of the network. The default implementation does not record any statistics.
+    fn network_stats(&self) -> NetworkStats {
+        NetworkStats::default()
+    }
+
+    /// Aborts the protocol with the given reason, such that the pending and future receives of all parties fail with the [`Abort`](mpc_net::abort::Abort). The default implementation does not propagate aborts.
+    fn abort(&mut self, _reason: AbortReason) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+
+    /// Sends data to the target party.
+    fn send<F: CanonicalSerialize>(
+        &mut self,
+        target: usize,
+        data: F,
+    ) -> impl Future<Output = std::io::Result<()>> + Send {
+        let data = serialize(&[data][..]);
+        async move { self.send_bytes(target, data?).await }
+    }
+
+    /// Sends a vector of data to the target party.
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: usize,
+        data: &[F],
+    ) -> impl Future<Output = std::io::Result<()>> + Send {
+        let data = serialize(data);
+        async move { self.send_bytes(target, data?).await }
+    }
+
+    /// Receives data from the party with the given id. This function has a default implementation for calling [ShamirAsyncNetwork::recv_many] and checking for the correct length of 1.
+    fn recv<F: CanonicalDeserialize>(
+        &mut self,
+        from: usize,
+    ) -> impl Future<Output = std::io::Result<F>> + Send {
+        async move {
+            let mut res = self.recv_many(from).await?;
+            if res.len() != 1 {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Expected 1 element, got more",
+                ))
+            } else {
+                Ok(res.pop().unwrap())
+            }
+        }
+    }
+
+    /// Receives a vector of data from the party with the given id.
+    fn recv_many<F: CanonicalDeserialize>(
+        &mut self,
+        from: usize,
+    ) -> impl Future<Output = std::io::Result<Vec<F>>> + Send {
+        async move { deserialize(&self.recv_bytes(from).await?[..]) }
+    }
+
+    /// Sends data to all parties and receives data from all other parties. The result is a vector where the data from party i is at index i, including my own data.
+    fn broadcast<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+    ) -> impl Future<Output = std::io::Result<Vec<F>>> + Send {
+        let send_data = serialize(&data);
+        async move {
+            let send_data = send_data?;
+            let id = self.get_id();
+            let num_parties = self.get_num_parties();
+            for other_id in (0..num_parties).filter(|other_id| *other_id != id) {
+                self.send_bytes(other_id, send_data.clone()).await?;
+            }
+            let mut received = Vec::with_capacity(num_parties);
+            for other_id in 0..num_parties {
+                if other_id != id {
+                    received.push(self.recv_bytes(other_id).await?);
+                } else {
+                    received.push(BytesMut::from(&send_data[..]));
+                }
+            }
+            // Only deserialize after receiving, such that the future does not hold an `F`
+            received.iter().map(|data| deserialize(data)).collect()
+        }
+    }
+
+    /// Sends data to the next num - 1 parties and receives from the previous num -1 parties. Thus, the result is a vector of length num, where the data from party my_id + num_partes - i mod num_parties is at index i, including my own data.
+    fn broadcast_next<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+        num: usize,
+    ) -> impl Future<Output = std::io::Result<Vec<F>>> + Send {
+        let send_data = serialize(&data);
+        async move {
+            let send_data = send_data?;
+            let id = self.get_id();
+            let num_parties = self.get_num_parties();
+            for s in 1..num {
+                let other_id = (id + s) % num_parties;
+                self.send_bytes(other_id, send_data.clone()).await?;
+            }
+            let mut received = Vec::with_capacity(num);
+            received.push(BytesMut::from(&send_data[..]));
+            for r in 1..num {
+                let other_id = (id + num_parties - r) % num_parties;
+                received.push(self.recv_bytes(other_id).await?);
+            }
+            received.iter().map(|data| deserialize(data)).collect()
+        }
+    }
+
+    /// Sends and receives to and from each party. Data must be of shape num_parties x n. The element that is "sent" to yourself is passed back directly.
+    fn send_and_recv_each_many<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: Vec<Vec<F>>,
+    ) -> impl Future<Output = std::io::Result<Vec<Vec<F>>>> + Send {
+        debug_assert_eq!(data.len(), self.get_num_parties());
+        let id = self.get_id();
+        let send_data = data
+            .iter()
+            .map(|data| serialize(&data[..]))
+            .collect::<std::io::Result<Vec<_>>>();
+        async move {
+            let mut received = Vec::with_capacity(self.get_num_parties());
+            for (other_id, send_data) in send_data?.into_iter().enumerate() {
+                if other_id != id {
+                    self.send_bytes(other_id, send_data).await?;
+                    received.push(self.recv_bytes(other_id).await?);
+                } else {
+                    received.push(BytesMut::from(&send_data[..]));
+                }
+            }
+            received.iter().map(|data| deserialize(data)).collect()
+        }
+    }
+}
+
+/// This struct can be used to facilitate async network communication for the Shamir MPC protocol. In contrast to [`ShamirMpcNet`](super::network::ShamirMpcNet), it does not own a runtime, but runs on the tokio runtime of the caller.
+pub struct ShamirAsyncMpcNet {
+    pub(crate) id: usize, // 0 <= id < num_parties
+    pub(crate) num_parties: usize,
+    pub(crate) channels: HashMap<usize, ChannelHandle<Bytes, BytesMut>>,
+    pub(crate) net_handler: Arc<MpcNetworkHandler>,
+}
+
+impl ShamirAsyncMpcNet {
+    /// Takes a [NetworkConfig] struct and constructs the network interface. The network needs to contain at least 3 parties and all ids need to be in the range of 0 <= id < num_parties.
+    pub async fn new(config: NetworkConfig) -> Result<Self, Report> {
+        let num_parties = config.parties.len();
+
+        if config.parties.len() <= 2 {
+            bail!("Shamir protocol requires at least 3 parties")
+        }
+        let id = config.my_id;
+        if id >= num_parties {
+            bail!("Invalid party id={} for {} parties", id, num_parties)
+        }
+
+        let net_handler = MpcNetworkHandler::establish(config).await?;
+        let mut channels = net_handler.get_managed_byte_channels().await?;
+
+        let mut channels_ = HashMap::with_capacity(num_parties - 1);
+        for other_id in 0..num_parties {
+            if other_id != id {
+                let chan = channels
+                    .remove(&other_id)
+                    .ok_or_else(|| eyre!("no channel found for party id={}", other_id))?;
+                channels_.insert(other_id, chan);
+            }
+        }
+
+        if !channels.is_empty() {
+            bail!("unexpected channels found")
+        }
+
+        Ok(Self {
+            id,
+            num_parties,
+            channels: channels_,
+            net_handler: Arc::new(net_handler),
+        })
+    }
+
+    /// Gracefully shuts down the connections to the other parties. This also affects all forks of the network.
+    pub async fn shutdown(self) -> std::io::Result<()> {
+        self.net_handler.shutdown().await
+    }
+}
+
+impl ShamirAsyncNetwork for ShamirAsyncMpcNet {
+    fn get_id(&self) -> usize {
+        self.id
+    }
+
+    fn get_num_parties(&self) -> usize {
+        self.num_parties
+    }
+
+    async fn send_bytes(&mut self, target: usize, data: Bytes) -> std::io::Result<()> {
+        if let Some(chan) = self.channels.get_mut(&target) {
+            self.net_handler.stats().record_sent(target, data.len());
+            std::mem::drop(chan.send(data).await);
+            Ok(())
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("No channel found for party id={}", target),
+            ))
+        }
+    }
+
+    async fn recv_bytes(&mut self, from: usize) -> std::io::Result<BytesMut> {
+        let recv = if let Some(chan) = self.channels.get_mut(&from) {
+            chan.recv().await
+        } else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("No channel found for party id={}", from),
+            ));
+        };
+        let start = Instant::now();
+        let handler = &self.net_handler;
+        let recv = async {
+            match handler.recv_timeout() {
+                Some(timeout) => tokio::time::timeout(timeout, recv).await.map_err(|_| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        format!("no message from party {} in time", from),
+                    )
+                }),
+                None => Ok(recv.await),
+            }
+        };
+        // an abort takes precedence over the message, such that all parties unwind
+        let data = tokio::select! {
+            biased;
+            abort = handler.wait_for_abort() => Err(abort.into()),
+            data = recv => {
+                if data.is_err() {
+                    // let the other parties fail right away instead of after their own timeout
+                    let _ = handler.abort(AbortReason::Timeout).await;
+                }
+                data
+            }
+        }?;
+
+        let data = data.map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::BrokenPipe, "receive channel end died")
+        })??;
+        self.net_handler
+            .stats()
+            .record_received(from, data.len(), start.elapsed());
+        Ok(data)
+    }
+
+    async fn fork(&mut self) -> std::io::Result<Self> {
+        let id = self.id;
+        let num_parties = self.num_parties;
+        let net_handler = Arc::clone(&self.net_handler);
+        let mut channels = net_handler.get_managed_byte_channels().await?;
+
+        let mut channels_ = HashMap::with_capacity(num_parties - 1);
+        for other_id in 0..num_parties {
+            if other_id != id {
+                let chan = channels.remove(&other_id).expect("to find channel");
+                channels_.insert(other_id, chan);
+            }
+        }
+
+        if !channels.is_empty() {
+            panic!("unexpected channels found")
+        }
+
+        Ok(Self {
+            id,
+            num_parties,
+            net_handler,
+            channels: channels_,
+        })
+    }
+
+    fn network_stats(&self) -> NetworkStats {
+        self.net_handler.stats().snapshot()
+    }
+
+    async fn abort(&mut self, reason: AbortReason) {
+        // the abort is recorded even if it cannot be sent to all parties
+        let _ = self.net_handler.abort(reason).await;
+    }
+}
+
+/// This struct adapts a [`ShamirAsyncNetwork`] to the blocking [`ShamirNetwork`] by blocking on the tokio runtime given by the [`Handle`], such that it can be used for the existing protocols, e.g., in a [`ShamirProtocol`](super::ShamirProtocol). Since blocking inside of an async task panics, it may only be used outside of the runtime, e.g., in [`tokio::task::spawn_blocking`].
+#[derive(Debug)]
+pub struct BlockingShamirNetwork<N: ShamirAsyncNetwork> {
+    inner: N,
+    runtime: Handle,
+}
+
+impl<N: ShamirAsyncNetwork> BlockingShamirNetwork<N> {
+    /// Wraps the async network, blocking on the given runtime.
+    pub fn new(inner: N, runtime: Handle) -> Self {
+        Self { inner, runtime }
+    }
+
+    /// Returns the wrapped async network.
+    pub fn into_inner(self) -> N {
+        self.inner
+    }
+}
+
+impl<N: ShamirAsyncNetwork> ShamirNetwork for BlockingShamirNetwork<N> {
+    fn get_id(&self) -> usize {
+        self.inner.get_id()
+    }
+
+    fn get_num_parties(&self) -> usize {
+        self.inner.get_num_parties()
+    }
+
+    fn send_many<F: CanonicalSerialize>(
+        &mut self,
+        target: usize,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        self.runtime.block_on(self.inner.send_many(target, data))
+    }
+
+    fn recv_many<F: CanonicalDeserialize>(&mut self, from: usize) -> std::io::Result<Vec<F>> {
+        self.runtime.block_on(self.inner.recv_many(from))
+    }
+
+    fn broadcast<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+    ) -> std::io::Result<Vec<F>> {
+        self.runtime.block_on(self.inner.broadcast(data))
+    }
+
+    fn broadcast_next<F: CanonicalSerialize + CanonicalDeserialize + Clone>(
+        &mut self,
+        data: F,
+        num: usize,
+    ) -> std::io::Result<Vec<F>> {
+        self.runtime.block_on(self.inner.broadcast_next(data, num))
+    }
+
+    fn send_and_recv_each_many<
+        F: CanonicalSerialize + CanonicalDeserialize + Clone + Send + 'static,
+    >(
+        &mut self,
+        data: Vec<Vec<F>>,
+    ) -> std::io::Result<Vec<Vec<F>>> {
+        self.runtime
+            .block_on(self.inner.send_and_recv_each_many(data))
+    }
+
+    fn fork(&mut self) -> std::io::Result<Self> {
+        let inner = self.runtime.block_on(self.inner.fork())?;
+        Ok(Self::new(inner, self.runtime.clone()))
+    }
+
+    fn network_stats(&self) -> NetworkStats {
+        self.inner.network_stats()
+    }
+
+    fn abort(&mut self, reason: AbortReason) {
+        self.runtime.block_on(self.inner.abort(reason))
+    }
+}
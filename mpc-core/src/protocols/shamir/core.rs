@@ -3,7 +3,7 @@
 //! This module implements core functionality of shamir share and combine operations
 
 use ark_ec::CurveGroup;
-use ark_ff::PrimeField;
+use ark_ff::{PrimeField, UniformRand};
 use rand::Rng;
 
 pub(crate) fn evaluate_poly<F: PrimeField>(poly: &[F], x: F) -> F {
@@ -278,6 +278,203 @@ pub fn reconstruct_point<C: CurveGroup>(shares: &[C], lagrange: &[C::ScalarField
     res
 }
 
+// Solves the linear system given by the augmented matrix with gaussian elimination. Free variables are set to zero. Returns None if the system is inconsistent.
+fn solve_linear_system<F: PrimeField>(mut matrix: Vec<Vec<F>>, num_vars: usize) -> Option<Vec<F>> {
+    let mut pivot_cols = Vec::with_capacity(num_vars);
+    let mut row = 0;
+    for col in 0..num_vars {
+        let Some(pivot) = (row..matrix.len()).find(|r| !matrix[*r][col].is_zero()) else {
+            continue;
+        };
+        matrix.swap(row, pivot);
+        let inv = matrix[row][col].inverse().expect("pivot is not zero");
+        for v in matrix[row].iter_mut() {
+            *v *= inv;
+        }
+        let pivot_row = matrix[row].clone();
+        for (r, other) in matrix.iter_mut().enumerate() {
+            if r != row && !other[col].is_zero() {
+                let factor = other[col];
+                for (v, p) in other[col..].iter_mut().zip(&pivot_row[col..]) {
+                    *v -= *p * factor;
+                }
+            }
+        }
+        pivot_cols.push(col);
+        row += 1;
+    }
+    if matrix[row..].iter().any(|r| !r[num_vars].is_zero()) {
+        return None;
+    }
+    let mut res = vec![F::zero(); num_vars];
+    for (r, col) in pivot_cols.into_iter().enumerate() {
+        res[col] = matrix[r][num_vars];
+    }
+    Some(res)
+}
+
+// Divides the polynomial num by the monic polynomial den. Returns the quotient and the remainder.
+fn poly_div_monic<F: PrimeField>(num: &[F], den: &[F]) -> (Vec<F>, Vec<F>) {
+    debug_assert_eq!(den.last(), Some(&F::one()));
+    let den_deg = den.len() - 1;
+    if num.len() <= den_deg {
+        return (vec![F::zero()], num.to_vec());
+    }
+    let mut rem = num.to_vec();
+    let mut quot = vec![F::zero(); num.len() - den_deg];
+    for i in (0..quot.len()).rev() {
+        let c = rem[i + den_deg];
+        quot[i] = c;
+        for (j, d) in den.iter().enumerate() {
+            rem[i + j] -= c * d;
+        }
+    }
+    rem.truncate(den_deg);
+    (quot, rem)
+}
+
+// Decodes the shares at the given points with the Berlekamp-Welch algorithm, i.e., it finds the polynomial of the given degree which agrees with all but at most (shares.len() - degree - 1) / 2 shares. Returns the coefficients of the polynomial and the positions of the shares which do not lie on it, or None if there is no such polynomial.
+pub(crate) fn berlekamp_welch<F: PrimeField>(
+    shares: &[F],
+    points: &[usize],
+    degree: usize,
+) -> Option<(Vec<F>, Vec<usize>)> {
+    debug_assert_eq!(shares.len(), points.len());
+    if shares.len() <= degree {
+        return None;
+    }
+    let num_errors = (shares.len() - degree - 1) / 2;
+    let points = points
+        .iter()
+        .map(|p| F::from(*p as u64))
+        .collect::<Vec<_>>();
+
+    // Q(x_i) = y_i * E(x_i), where Q has degree degree + num_errors and E is monic of degree num_errors
+    let num_q = degree + num_errors + 1;
+    let num_vars = num_q + num_errors;
+    let matrix = points
+        .iter()
+        .zip(shares.iter())
+        .map(|(x, y)| {
+            let mut row = Vec::with_capacity(num_vars + 1);
+            let mut pow = F::one();
+            for _ in 0..num_q {
+                row.push(pow);
+                pow *= x;
+            }
+            let mut pow = F::one();
+            for _ in 0..num_errors {
+                row.push(-*y * pow);
+                pow *= x;
+            }
+            row.push(*y * pow);
+            row
+        })
+        .collect::<Vec<_>>();
+    let solution = solve_linear_system(matrix, num_vars)?;
+
+    let mut e = solution[num_q..].to_vec();
+    e.push(F::one());
+    let (mut poly, rem) = poly_div_monic(&solution[..num_q], &e);
+    if rem.iter().any(|r| !r.is_zero()) {
+        return None;
+    }
+    poly.resize(degree + 1, F::zero());
+
+    let errors = points
+        .iter()
+        .zip(shares.iter())
+        .enumerate()
+        .filter(|(_, (x, y))| evaluate_poly(&poly, **x) != **y)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    if errors.len() > num_errors {
+        return None;
+    }
+    Some((poly, errors))
+}
+
+// Returns the lagrange coefficients for evaluating the polynomial interpolated from the shares at the given points at x.
+fn lagrange_at<F: PrimeField>(points: &[usize], x: usize) -> Vec<F> {
+    let x = F::from(x as u64);
+    points
+        .iter()
+        .map(|j| {
+            let j_ = F::from(*j as u64);
+            let mut num = F::one();
+            let mut den = F::one();
+            for k in points.iter().filter(|k| *k != j) {
+                let k_ = F::from(*k as u64);
+                num *= x - k_;
+                den *= j_ - k_;
+            }
+            num * den.inverse().expect("points are distinct")
+        })
+        .collect()
+}
+
+// Checks whether the shares at the given points lie on a polynomial of the given degree, by interpolating the first degree + 1 shares and evaluating the polynomial at the remaining points.
+pub(crate) fn is_consistent<F: PrimeField>(shares: &[F], points: &[usize], degree: usize) -> bool {
+    debug_assert_eq!(shares.len(), points.len());
+    points[degree + 1..]
+        .iter()
+        .zip(shares[degree + 1..].iter())
+        .all(|(x, y)| {
+            let lagrange = lagrange_at::<F>(&points[..=degree], *x);
+            reconstruct(&shares[..=degree], &lagrange) == *y
+        })
+}
+
+// Checks a whole batch of sharings at once with a random linear combination, where shares[j][i] is the share of the party at points[j] of the i-th value. If one of the sharings does not lie on a polynomial of the given degree, the check fails except with probability 1 / |F|. The coefficients are sampled from rng and must not be known before the shares are fixed.
+pub(crate) fn is_consistent_batch<F: PrimeField, R: Rng>(
+    shares: &[Vec<F>],
+    points: &[usize],
+    degree: usize,
+    rng: &mut R,
+) -> bool {
+    let len = shares.first().map(Vec::len).unwrap_or_default();
+    let coeffs = (0..len).map(|_| F::rand(rng)).collect::<Vec<_>>();
+    let combined = shares
+        .iter()
+        .map(|r| r.iter().zip(coeffs.iter()).map(|(s, c)| *s * c).sum::<F>())
+        .collect::<Vec<_>>();
+    is_consistent(&combined, points, degree)
+}
+
+// Like [is_consistent_batch], but for shares of curve points.
+pub(crate) fn is_consistent_batch_point<C: CurveGroup, R: Rng>(
+    shares: &[Vec<C>],
+    points: &[usize],
+    degree: usize,
+    rng: &mut R,
+) -> bool {
+    let len = shares.first().map(Vec::len).unwrap_or_default();
+    let coeffs = (0..len)
+        .map(|_| C::ScalarField::rand(rng))
+        .collect::<Vec<_>>();
+    let combined = shares
+        .iter()
+        .map(|r| C::msm_unchecked(&C::normalize_batch(r), &coeffs))
+        .collect::<Vec<_>>();
+    is_consistent_point(&combined, points, degree)
+}
+
+// Like [is_consistent], but for shares of a curve point.
+pub(crate) fn is_consistent_point<C: CurveGroup>(
+    shares: &[C],
+    points: &[usize],
+    degree: usize,
+) -> bool {
+    debug_assert_eq!(shares.len(), points.len());
+    points[degree + 1..]
+        .iter()
+        .zip(shares[degree + 1..].iter())
+        .all(|(x, y)| {
+            let lagrange = lagrange_at::<C::ScalarField>(&points[..=degree], *x);
+            reconstruct_point(&shares[..=degree], &lagrange) == *y
+        })
+}
+
 #[cfg(test)]
 mod shamir_test {
     use super::*;
@@ -390,6 +587,40 @@ mod shamir_test {
         }
     }
 
+    fn test_shamir_robust<C: CurveGroup, const NUM_PARTIES: usize, const DEGREE: usize>() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let num_errors = (NUM_PARTIES - DEGREE - 1) / 2;
+        let points = (1..=NUM_PARTIES).collect::<Vec<_>>();
+
+        for _ in 0..TESTRUNS {
+            let secret = C::ScalarField::rand(&mut rng);
+            let mut shares = super::share(secret, NUM_PARTIES, DEGREE, &mut rng);
+            let mut corrupted = (0..NUM_PARTIES).choose_multiple(&mut rng, num_errors);
+            corrupted.sort();
+            for i in corrupted.iter() {
+                shares[*i] += C::ScalarField::rand(&mut rng);
+            }
+
+            assert_eq!(
+                super::is_consistent(&shares, &points, DEGREE),
+                corrupted.is_empty()
+            );
+            let (poly, errors) = super::berlekamp_welch(&shares, &points, DEGREE).unwrap();
+            assert_eq!(poly[0], secret);
+            assert_eq!(errors, corrupted);
+
+            // To point, corrupted shares are only detected
+            let shares = shares
+                .into_iter()
+                .map(|s| C::generator().mul(s))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                super::is_consistent_point(&shares, &points, DEGREE),
+                corrupted.is_empty()
+            );
+        }
+    }
+
     #[test]
     fn test_shamir_3_1() {
         const NUM_PARTIES: usize = 3;
@@ -398,6 +629,7 @@ mod shamir_test {
         test_shamir_point::<ark_bn254::G1Projective, NUM_PARTIES, DEGREE>();
         test_shamir_field_to_point::<ark_bn254::G1Projective, NUM_PARTIES, DEGREE>();
         test_shamir_poly::<ark_bn254::Fr, NUM_PARTIES, DEGREE>();
+        test_shamir_robust::<ark_bn254::G1Projective, NUM_PARTIES, DEGREE>();
     }
 
     #[test]
@@ -408,5 +640,6 @@ mod shamir_test {
         test_shamir_point::<ark_bn254::G1Projective, NUM_PARTIES, DEGREE>();
         test_shamir_field_to_point::<ark_bn254::G1Projective, NUM_PARTIES, DEGREE>();
         test_shamir_poly::<ark_bn254::Fr, NUM_PARTIES, DEGREE>();
+        test_shamir_robust::<ark_bn254::G1Projective, NUM_PARTIES, DEGREE>();
     }
 }
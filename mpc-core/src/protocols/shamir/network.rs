@@ -2,13 +2,23 @@
 //!
 //! This module contains the trait for specifying a network interface for the Shamir MPC protocol. It also contains an implementation of the trait using the [mpc_net] crate.
 
+use crate::protocols::broadcast;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use bytes::{Bytes, BytesMut};
 use eyre::{bail, eyre, Report};
 use mpc_net::{
-    channel::ChannelHandle, config::NetworkConfig, MpcNetworkHandler, MpcNetworkHandlerWrapper,
+    abort::AbortReason,
+    channel::ChannelHandle,
+    config::{NetworkConfig, Phase},
+    stats::NetworkStats,
+    transcript::SignedTranscript,
+    MpcNetworkHandler, MpcNetworkHandlerWrapper,
+};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
 };
-use std::{collections::HashMap, sync::Arc};
 
 /// This trait defines the network interface for the Shamir protocol.
 pub trait ShamirNetwork: Send {
@@ -67,10 +77,81 @@ pub trait ShamirNetwork: Send {
         data: Vec<Vec<F>>,
     ) -> std::io::Result<Vec<Vec<F>>>;
 
+    /// Sends `data` to all other parties, which receive it with [`Self::recv_broadcast`]. This function has a default implementation for calling [ShamirNetwork::send_broadcast_many].
+    fn send_broadcast<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: F,
+    ) -> std::io::Result<()> {
+        self.send_broadcast_many(&[data])
+    }
+
+    /// Sends `data` to all other parties, which receive it with [`Self::recv_broadcast_many`]. Afterwards, all parties exchange a hash of the data and fail with [`std::io::ErrorKind::InvalidData`] if any hash differs, such that a corrupted sender cannot send different data to different parties. This costs one additional round.
+    fn send_broadcast_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        data: &[F],
+    ) -> std::io::Result<()> {
+        let id = self.get_id();
+        for other in (0..self.get_num_parties()).filter(|other| *other != id) {
+            self.send_many(other, data)?;
+        }
+        let digest = broadcast::digest(id, data)?;
+        let digests = self.broadcast(digest.clone())?;
+        if digests.iter().any(|d| *d != digest) {
+            return Err(broadcast::inconsistent(id));
+        }
+        Ok(())
+    }
+
+    /// Receives data the party with the given id sent with [`Self::send_broadcast`]. This function has a default implementation for calling [ShamirNetwork::recv_broadcast_many] and checking for the correct length of 1.
+    fn recv_broadcast<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        from: usize,
+    ) -> std::io::Result<F> {
+        let mut res = self.recv_broadcast_many(from)?;
+        if res.len() != 1 {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Expected 1 element, got more",
+            ))
+        } else {
+            Ok(res.pop().unwrap())
+        }
+    }
+
+    /// Receives data the party with the given id sent with [`Self::send_broadcast_many`] and checks that all other parties received the same data.
+    fn recv_broadcast_many<F: CanonicalSerialize + CanonicalDeserialize>(
+        &mut self,
+        from: usize,
+    ) -> std::io::Result<Vec<F>> {
+        let data = self.recv_many(from)?;
+        let digest = broadcast::digest(from, &data)?;
+        let digests = self.broadcast(digest.clone())?;
+        if digests.iter().any(|d| *d != digest) {
+            return Err(broadcast::inconsistent(from));
+        }
+        Ok(data)
+    }
+
     /// Fork the network into two separate instances with their own connections
     fn fork(&mut self) -> std::io::Result<Self>
     where
         Self: Sized;
+
+    /// Starts the given protocol phase. The parties agree on the smallest configured timeout of the phase, after which all receives fail with [`std::io::ErrorKind::TimedOut`], such that all parties abort at the same time instead of waiting forever. All parties have to call this at the same point of the protocol. The default implementation does not support timeouts.
+    fn start_phase(&mut self, _phase: Phase) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    /// Ends the current protocol phase, such that its timeout no longer applies.
+    fn end_phase(&mut self) {}
+
+    /// Returns a snapshot of the communication statistics of the party, which are shared by all forks of the network. The default implementation does not record any statistics.
+    fn network_stats(&self) -> NetworkStats {
+        NetworkStats::default()
+    }
+
+    /// Aborts the protocol with the given reason, such that the pending and future receives of all parties fail with the [`Abort`](mpc_net::abort::Abort) instead of waiting for messages which will never arrive. The default implementation does not propagate aborts.
+    fn abort(&mut self, _reason: AbortReason) {}
 }
 
 /// This struct can be used to facilitate network communication for the Shamir MPC protocol.
@@ -84,11 +165,25 @@ pub struct ShamirMpcNet {
 impl ShamirMpcNet {
     /// Takes a [NetworkConfig] struct and constructs the network interface. The network needs to contain at least 3 parties and all ids need to be in the range of 0 <= id < num_parties.
     pub fn new(config: NetworkConfig) -> Result<Self, Report> {
-        let num_parties = config.parties.len();
-
         if config.parties.len() <= 2 {
             bail!("Shamir protocol requires at least 3 parties")
         }
+        Self::establish(config)
+    }
+
+    /// Takes a [NetworkConfig] struct and constructs the network interface for the 2PC protocol (see [`TwoPcProtocol`](crate::protocols::twopc::TwoPcProtocol)). The network needs to contain exactly 2 parties.
+    pub fn new_two_party(config: NetworkConfig) -> Result<Self, Report> {
+        if config.parties.len() != 2 {
+            bail!(
+                "2PC protocol requires exactly 2 parties, but the network has {}",
+                config.parties.len()
+            )
+        }
+        Self::establish(config)
+    }
+
+    fn establish(config: NetworkConfig) -> Result<Self, Report> {
+        let num_parties = config.parties.len();
         let id = config.my_id;
         if id >= num_parties {
             bail!("Invalid party id={} for {} parties", id, num_parties)
@@ -99,7 +194,7 @@ impl ShamirMpcNet {
             .build()?;
         let (net_handler, channels) = runtime.block_on(async {
             let net_handler = MpcNetworkHandler::establish(config).await?;
-            let mut channels = net_handler.get_byte_channels().await?;
+            let mut channels = net_handler.get_managed_byte_channels().await?;
 
             let mut channels_ = HashMap::with_capacity(num_parties - 1);
 
@@ -108,7 +203,7 @@ impl ShamirMpcNet {
                     let chan = channels
                         .remove(&other_id)
                         .ok_or_else(|| eyre!("no channel found for party id={}", other_id))?;
-                    channels_.insert(other_id, ChannelHandle::manage(chan));
+                    channels_.insert(other_id, chan);
                 }
             }
 
@@ -126,9 +221,18 @@ impl ShamirMpcNet {
         })
     }
 
+    /// Returns the transcript of all messages sent and received by all forks of the network so far, signed with the private key of the party. Fails if the transcript is not recorded (see [`NetworkConfig::transcript`]).
+    pub fn sign_transcript(&self) -> Result<SignedTranscript, Report> {
+        self.net_handler.inner.sign_transcript()
+    }
+
     /// Sends bytes over the network to the target party.
     pub fn send_bytes(&mut self, target: usize, data: Bytes) -> std::io::Result<()> {
         if let Some(chan) = self.channels.get_mut(&target) {
+            self.net_handler
+                .inner
+                .stats()
+                .record_sent(target, data.len());
             std::mem::drop(chan.blocking_send(data));
             Ok(())
         } else {
@@ -139,20 +243,51 @@ impl ShamirMpcNet {
         }
     }
 
-    /// Receives bytes over the network from the party with the given id.
+    /// Receives bytes over the network from the party with the given id. Fails with [`std::io::ErrorKind::TimedOut`] if the message does not arrive in time (see [`MpcNetworkHandler::recv_timeout`]), and with the [`Abort`](mpc_net::abort::Abort) if a party aborted the protocol.
     pub fn recv_bytes(&mut self, from: usize) -> std::io::Result<BytesMut> {
-        let data = if let Some(chan) = self.channels.get_mut(&from) {
-            chan.blocking_recv().blocking_recv()
+        let recv = if let Some(chan) = self.channels.get_mut(&from) {
+            chan.blocking_recv()
         } else {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 format!("No channel found for party id={}", from),
             ));
         };
+        let start = Instant::now();
+        let handler = &self.net_handler.inner;
+        let data = self.net_handler.runtime.block_on(async {
+            let recv = async {
+                match handler.recv_timeout() {
+                    Some(timeout) => tokio::time::timeout(timeout, recv).await.map_err(|_| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::TimedOut,
+                            format!("no message from party {} in time", from),
+                        )
+                    }),
+                    None => Ok(recv.await),
+                }
+            };
+            // an abort takes precedence over the message, such that all parties unwind
+            tokio::select! {
+                biased;
+                abort = handler.wait_for_abort() => Err(abort.into()),
+                data = recv => {
+                    if data.is_err() {
+                        // let the other parties fail right away instead of after their own timeout
+                        let _ = handler.abort(AbortReason::Timeout).await;
+                    }
+                    data
+                }
+            }
+        })?;
 
         let data = data.map_err(|_| {
             std::io::Error::new(std::io::ErrorKind::BrokenPipe, "receive channel end died")
         })??;
+        self.net_handler
+            .inner
+            .stats()
+            .record_received(from, data.len(), start.elapsed());
         Ok(data)
     }
 
@@ -266,14 +401,14 @@ impl ShamirNetwork for ShamirMpcNet {
         let num_parties = self.num_parties;
         let net_handler = Arc::clone(&self.net_handler);
         let channels = net_handler.runtime.block_on(async {
-            let mut channels = net_handler.inner.get_byte_channels().await?;
+            let mut channels = net_handler.inner.get_managed_byte_channels().await?;
 
             let mut channels_ = HashMap::with_capacity(num_parties - 1);
 
             for other_id in 0..num_parties {
                 if other_id != id {
                     let chan = channels.remove(&other_id).expect("to find channel");
-                    channels_.insert(other_id, ChannelHandle::manage(chan));
+                    channels_.insert(other_id, chan);
                 }
             }
 
@@ -292,6 +427,38 @@ impl ShamirNetwork for ShamirMpcNet {
         })
     }
 
+    fn start_phase(&mut self, phase: Phase) -> std::io::Result<()> {
+        self.net_handler.inner.stats().set_phase(phase.name());
+        // agree on the smallest timeout, such that all parties abort at the same time
+        let timeout = self
+            .net_handler
+            .inner
+            .timeouts()
+            .phase(phase)
+            .map_or(u64::MAX, |timeout| timeout.as_secs());
+        let timeout = self
+            .broadcast(timeout)?
+            .into_iter()
+            .min()
+            .expect("at least one party");
+        self.net_handler.inner.set_deadline(
+            (timeout != u64::MAX).then(|| Instant::now() + Duration::from_secs(timeout)),
+        );
+        Ok(())
+    }
+
+    fn end_phase(&mut self) {
+        self.net_handler.inner.set_deadline(None);
+    }
+
+    fn abort(&mut self, reason: AbortReason) {
+        // the abort is recorded even if it cannot be sent to all parties
+        let _ = self
+            .net_handler
+            .runtime
+            .block_on(self.net_handler.inner.abort(reason));
+    }
+
     fn send_and_recv_each_many<
         F: CanonicalSerialize + CanonicalDeserialize + Clone + Send + 'static,
     >(
@@ -0,0 +1,436 @@
+//! Packed Shamir
+//!
+//! This module implements packed Shamir secret sharing as proposed by [Franklin and Yung](https://dl.acm.org/doi/10.1145/129712.129780). A single sharing polynomial hides `pack_size` secrets at once, which are placed at the points 0, -1, ..., -(pack_size - 1), while the parties hold the evaluations at the points 1, ..., num_parties. To tolerate `threshold` corrupted parties, the polynomial has degree threshold + pack_size - 1. Thus, all operations work on `pack_size` values in parallel (SIMD-style) for the communication cost of a single value, as long as the number of parties is large enough to reconstruct a product of two packed sharings, i.e., num_parties >= 2 * (threshold + pack_size - 1) + 1.
+//!
+//! Since packed shares can only be combined with packed shares that use the same packing, the values can be transformed into regular Shamir shares with [`unpack_vec`] (e.g., to use them in the witness extension).
+
+pub(super) mod types;
+
+use ark_ff::PrimeField;
+use itertools::{izip, Itertools};
+use rand::{CryptoRng, Rng, SeedableRng};
+
+use super::{network::ShamirNetwork, IoResult, ShamirPrimeFieldShare};
+use crate::RngType;
+
+pub use types::ShamirPackedShare;
+
+type PackedShare<F> = ShamirPackedShare<F>;
+
+/// Returns the degree of the sharing polynomial for the given threshold and pack size.
+pub fn packed_degree(threshold: usize, pack_size: usize) -> usize {
+    threshold + pack_size - 1
+}
+
+// The points at which the secrets are placed.
+fn secret_points<F: PrimeField>(pack_size: usize) -> Vec<F> {
+    (0..pack_size).map(|j| -F::from(j as u64)).collect()
+}
+
+// The point at which the party with the given id is evaluated.
+fn party_point<F: PrimeField>(id: usize) -> F {
+    F::from(id as u64 + 1)
+}
+
+// Computes the lagrange coefficients for interpolating the polynomial through the given points at x.
+fn lagrange_coeffs<F: PrimeField>(points: &[F], x: F) -> Vec<F> {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, xi)| {
+            let mut num = F::one();
+            let mut den = F::one();
+            for (j, xj) in points.iter().enumerate() {
+                if i != j {
+                    num *= x - xj;
+                    den *= *xi - xj;
+                }
+            }
+            num * den.inverse().expect("points are distinct")
+        })
+        .collect()
+}
+
+// Computes for each secret point the lagrange coefficients for interpolating the secret from the shares at the given points.
+fn reconstruction_coeffs<F: PrimeField>(share_points: &[F], pack_size: usize) -> Vec<Vec<F>> {
+    secret_points(pack_size)
+        .into_iter()
+        .map(|x| lagrange_coeffs(share_points, x))
+        .collect()
+}
+
+fn reconstruct<F: PrimeField>(shares: &[F], coeffs: &[Vec<F>]) -> Vec<F> {
+    coeffs
+        .iter()
+        .map(|coeffs| izip!(shares, coeffs).map(|(s, c)| *s * c).sum())
+        .collect()
+}
+
+// Shares the secrets with a polynomial of the given degree. The polynomial is fixed by the secrets and random evaluations at the points of the first degree + 1 - secrets.len() parties.
+fn share<F: PrimeField, R: Rng>(
+    secrets: &[F],
+    degree: usize,
+    num_parties: usize,
+    rng: &mut R,
+) -> Vec<F> {
+    debug_assert!(degree + 1 >= secrets.len());
+    let num_random = degree + 1 - secrets.len();
+    debug_assert!(num_random <= num_parties);
+    let mut points = secret_points::<F>(secrets.len());
+    let mut values = secrets.to_vec();
+    let mut shares = Vec::with_capacity(num_parties);
+    for id in 0..num_random {
+        let r = F::rand(rng);
+        points.push(party_point(id));
+        values.push(r);
+        shares.push(r);
+    }
+    for id in num_random..num_parties {
+        let coeffs = lagrange_coeffs(&points, party_point(id));
+        shares.push(izip!(&values, coeffs).map(|(v, c)| *v * c).sum());
+    }
+    shares
+}
+
+/// Secret shares a vector of field elements using packed Shamir secret sharing, where each sharing polynomial hides `pack_size` values. The last polynomial is padded with zeros. The output is a `Vec` of `Vecs` of [`ShamirPackedShare`], where the i-th `Vec` contains the shares of party i.
+pub fn share_field_elements<F: PrimeField, R: Rng + CryptoRng>(
+    vals: &[F],
+    threshold: usize,
+    pack_size: usize,
+    num_parties: usize,
+    rng: &mut R,
+) -> Vec<Vec<PackedShare<F>>> {
+    let degree = packed_degree(threshold, pack_size);
+    let mut result = (0..num_parties)
+        .map(|_| Vec::with_capacity(vals.len().div_ceil(pack_size)))
+        .collect::<Vec<_>>();
+    for chunk in vals.chunks(pack_size) {
+        let mut secrets = chunk.to_vec();
+        secrets.resize(pack_size, F::zero());
+        let shares = share(&secrets, degree, num_parties, rng);
+        for (r, s) in izip!(&mut result, shares) {
+            r.push(PackedShare::new(s));
+        }
+    }
+    result
+}
+
+/// Reconstructs the first `num_values` field elements from packed Shamir shares. The input is a slice of `Vecs` of [`ShamirPackedShare`] per party, where `coeffs` contains the indices (starting at 1) of the parties. Thereby at least threshold + pack_size shares need to be present per packed value.
+pub fn combine_field_elements<F: PrimeField>(
+    shares: &[Vec<PackedShare<F>>],
+    coeffs: &[usize],
+    threshold: usize,
+    pack_size: usize,
+    num_values: usize,
+) -> eyre::Result<Vec<F>> {
+    let degree = packed_degree(threshold, pack_size);
+    if shares.len() != coeffs.len() {
+        eyre::bail!(
+            "Number of shares ({}) does not match number of party indices ({})",
+            shares.len(),
+            coeffs.len()
+        );
+    }
+    if shares.len() <= degree {
+        eyre::bail!(
+            "Not enough shares to reconstruct the secret. Expected {}, got {}",
+            degree + 1,
+            shares.len()
+        );
+    }
+    let num_packed = num_values.div_ceil(pack_size);
+    if shares.iter().any(|s| s.len() < num_packed) {
+        eyre::bail!(
+            "Not enough packed shares to reconstruct {} values. Expected {}",
+            num_values,
+            num_packed
+        );
+    }
+    let share_points = coeffs[..=degree]
+        .iter()
+        .map(|c| F::from(*c as u64))
+        .collect_vec();
+    let rec_coeffs = reconstruction_coeffs(&share_points, pack_size);
+    let mut result = Vec::with_capacity(num_packed * pack_size);
+    for i in 0..num_packed {
+        let s = shares[..=degree].iter().map(|s| s[i].a).collect_vec();
+        result.extend(reconstruct(&s, &rec_coeffs));
+    }
+    result.truncate(num_values);
+    Ok(result)
+}
+
+/// This struct holds all necessary information for computing on packed Shamir shares. It contains a [`ShamirNetwork`], the threshold, the pack size and the precomputed lagrange coefficients.
+pub struct ShamirPackedProtocol<F: PrimeField, N: ShamirNetwork> {
+    /// The threshold, i.e., the maximum number of corrupted parties
+    pub threshold: usize,
+    /// The number of secrets per sharing polynomial
+    pub pack_size: usize,
+    /// The underlying [`ShamirNetwork`]
+    pub network: N,
+    rng: RngType,
+    // lagrange coefficients for reconstructing the secrets from the shares of the parties 0..=degree
+    open_coeffs: Vec<Vec<F>>,
+    // lagrange coefficients for reconstructing the secrets from the shares of the parties 0..=2 * degree
+    mul_coeffs: Vec<Vec<F>>,
+    // lagrange coefficients for evaluating the polynomial of degree pack_size - 1 through public values at my point
+    public_coeffs: Vec<F>,
+}
+
+impl<F: PrimeField, N: ShamirNetwork> ShamirPackedProtocol<F, N> {
+    const KING_ID: usize = 0;
+
+    /// Creates a new [`ShamirPackedProtocol`]. Fails if the number of parties is too small to multiply packed sharings with the given threshold and pack size.
+    pub fn new(threshold: usize, pack_size: usize, network: N) -> eyre::Result<Self> {
+        if pack_size == 0 {
+            eyre::bail!("Pack size must be at least 1");
+        }
+        let num_parties = network.get_num_parties();
+        let degree = packed_degree(threshold, pack_size);
+        if 2 * degree + 1 > num_parties {
+            eyre::bail!(
+                "Threshold {} and pack size {} too large for {} parties",
+                threshold,
+                pack_size,
+                num_parties
+            )
+        }
+        let share_points = |num: usize| (0..num).map(party_point).collect_vec();
+        let open_coeffs = reconstruction_coeffs(&share_points(degree + 1), pack_size);
+        let mul_coeffs = reconstruction_coeffs(&share_points(2 * degree + 1), pack_size);
+        let public_coeffs =
+            lagrange_coeffs(&secret_points(pack_size), party_point(network.get_id()));
+        Ok(Self {
+            threshold,
+            pack_size,
+            network,
+            rng: RngType::from_entropy(),
+            open_coeffs,
+            mul_coeffs,
+            public_coeffs,
+        })
+    }
+
+    /// Returns the degree of the sharing polynomials.
+    pub fn degree(&self) -> usize {
+        packed_degree(self.threshold, self.pack_size)
+    }
+
+    // Computes my share of the polynomial of degree pack_size - 1 through the public values.
+    fn public_share(&self, public: &[F]) -> F {
+        izip!(public, &self.public_coeffs)
+            .map(|(p, c)| *p * c)
+            .sum()
+    }
+
+    // Every party shares its random contributions with all other parties, where share_fn returns the shares for all parties, and the received shares are summed up.
+    fn sum_random_contributions(
+        &mut self,
+        num: usize,
+        share_fn: impl Fn(&mut RngType, usize) -> Vec<Vec<F>>,
+    ) -> IoResult<Vec<F>> {
+        let num_parties = self.network.get_num_parties();
+        let mut data = vec![Vec::new(); num_parties];
+        for _ in 0..num {
+            let shares = share_fn(&mut self.rng, num_parties);
+            for (d, s) in izip!(&mut data, shares) {
+                d.extend(s);
+            }
+        }
+        let len = data[0].len();
+        let rcv = self.network.send_and_recv_each_many(data)?;
+        let mut sum = vec![F::zero(); len];
+        for r in rcv {
+            if r.len() != len {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "During generation of packed randomness: Invalid number of elements received",
+                ));
+            }
+            for (s, r) in izip!(&mut sum, r) {
+                *s += r;
+            }
+        }
+        Ok(sum)
+    }
+
+    // Generates num random packed sharings, each with degree and 2 * degree.
+    fn rand_double_pairs(&mut self, num: usize) -> IoResult<(Vec<F>, Vec<F>)> {
+        let pack_size = self.pack_size;
+        let degree = self.degree();
+        let sum = self.sum_random_contributions(num, |rng, num_parties| {
+            let secrets = (0..pack_size).map(|_| F::rand(rng)).collect_vec();
+            let r_d = share(&secrets, degree, num_parties, rng);
+            let r_2d = share(&secrets, 2 * degree, num_parties, rng);
+            izip!(r_d, r_2d).map(|(a, b)| vec![a, b]).collect()
+        })?;
+        Ok(sum.chunks_exact(2).map(|c| (c[0], c[1])).unzip())
+    }
+
+    // Generates num random packed sharings together with regular sharings of degree threshold of the packed secrets.
+    fn rand_unpack_pairs(&mut self, num: usize) -> IoResult<(Vec<F>, Vec<Vec<F>>)> {
+        let pack_size = self.pack_size;
+        let degree = self.degree();
+        let threshold = self.threshold;
+        let sum = self.sum_random_contributions(num, |rng, num_parties| {
+            let secrets = (0..pack_size).map(|_| F::rand(rng)).collect_vec();
+            let packed = share(&secrets, degree, num_parties, rng);
+            let regular = secrets
+                .iter()
+                .map(|s| super::core::share(*s, num_parties, threshold, rng))
+                .collect_vec();
+            (0..num_parties)
+                .map(|i| {
+                    std::iter::once(packed[i])
+                        .chain(regular.iter().map(|r| r[i]))
+                        .collect()
+                })
+                .collect()
+        })?;
+        Ok(sum
+            .chunks_exact(pack_size + 1)
+            .map(|c| (c[0], c[1..].to_vec()))
+            .unzip())
+    }
+
+    // Reveals the secrets of masked sharings of the given degree: The shares are sent to the king, who reconstructs the secrets and sends them to all parties.
+    fn reveal_masked(&mut self, masked: Vec<F>, degree: usize) -> IoResult<Vec<F>> {
+        let len = masked.len() * self.pack_size;
+        let my_id = self.network.get_id();
+        let num_parties = self.network.get_num_parties();
+        let values = if my_id == Self::KING_ID {
+            let mut shares = vec![masked];
+            for other_id in 1..=degree {
+                let r = self.network.recv_many::<F>(other_id)?;
+                if r.len() != shares[0].len() {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "During opening of packed shares: Invalid number of elements received",
+                    ));
+                }
+                shares.push(r);
+            }
+            let coeffs = if degree == self.degree() {
+                &self.open_coeffs
+            } else {
+                &self.mul_coeffs
+            };
+            let mut values = Vec::with_capacity(len);
+            for i in 0..shares[0].len() {
+                let s = shares.iter().map(|s| s[i]).collect_vec();
+                values.extend(reconstruct(&s, coeffs));
+            }
+            for other_id in 1..num_parties {
+                self.network.send_many(other_id, &values)?;
+            }
+            values
+        } else {
+            if my_id <= degree {
+                self.network.send_many(Self::KING_ID, &masked)?;
+            }
+            let values = self.network.recv_many::<F>(Self::KING_ID)?;
+            if values.len() != len {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "During opening of packed shares: Invalid number of elements received",
+                ));
+            }
+            values
+        };
+        Ok(values)
+    }
+}
+
+/// Performs addition between two packed shares.
+pub fn add<F: PrimeField>(a: PackedShare<F>, b: PackedShare<F>) -> PackedShare<F> {
+    PackedShare::new(a.a + b.a)
+}
+
+/// Performs subtraction between two packed shares.
+pub fn sub<F: PrimeField>(a: PackedShare<F>, b: PackedShare<F>) -> PackedShare<F> {
+    PackedShare::new(a.a - b.a)
+}
+
+/// Performs addition between a packed share and `pack_size` public values, which are added to the respective secrets.
+pub fn add_public<F: PrimeField, N: ShamirNetwork>(
+    shared: PackedShare<F>,
+    public: &[F],
+    packed: &ShamirPackedProtocol<F, N>,
+) -> PackedShare<F> {
+    debug_assert_eq!(public.len(), packed.pack_size);
+    PackedShare::new(shared.a + packed.public_share(public))
+}
+
+/// Multiplies all secrets of a packed share with the same public value.
+pub fn mul_public<F: PrimeField>(shared: PackedShare<F>, public: F) -> PackedShare<F> {
+    PackedShare::new(shared.a * public)
+}
+
+/// Transforms `pack_size` public values into a packed share.
+pub fn promote_to_trivial_share<F: PrimeField, N: ShamirNetwork>(
+    public: &[F],
+    packed: &ShamirPackedProtocol<F, N>,
+) -> PackedShare<F> {
+    debug_assert_eq!(public.len(), packed.pack_size);
+    PackedShare::new(packed.public_share(public))
+}
+
+/// Performs element-wise multiplication of two vectors of packed shares, i.e., `pack_size` multiplications per pair of shares. The products of degree 2 * degree are masked with random double sharings and reduced by the king party.
+pub fn mul_vec<F: PrimeField, N: ShamirNetwork>(
+    a: &[PackedShare<F>],
+    b: &[PackedShare<F>],
+    packed: &mut ShamirPackedProtocol<F, N>,
+) -> IoResult<Vec<PackedShare<F>>> {
+    debug_assert_eq!(a.len(), b.len());
+    let (r_d, r_2d) = packed.rand_double_pairs(a.len())?;
+    let masked = izip!(a, b, r_2d)
+        .map(|(a, b, r)| a.a * b.a + r)
+        .collect_vec();
+    let values = packed.reveal_masked(masked, 2 * packed.degree())?;
+    Ok(izip!(values.chunks_exact(packed.pack_size), r_d)
+        .map(|(v, r)| PackedShare::new(packed.public_share(v) - r))
+        .collect())
+}
+
+/// Opens a vector of packed shares, which results in `pack_size` values per share.
+pub fn open_vec<F: PrimeField, N: ShamirNetwork>(
+    a: &[PackedShare<F>],
+    packed: &mut ShamirPackedProtocol<F, N>,
+) -> IoResult<Vec<F>> {
+    let shares = a.iter().map(|a| a.a).collect_vec();
+    let rcv = packed.network.broadcast(shares)?;
+    let degree = packed.degree();
+    if rcv.iter().take(degree + 1).any(|r| r.len() != a.len()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "During opening of packed shares: Invalid number of elements received",
+        ));
+    }
+    let mut result = Vec::with_capacity(a.len() * packed.pack_size);
+    for i in 0..a.len() {
+        let s = rcv.iter().take(degree + 1).map(|r| r[i]).collect_vec();
+        result.extend(reconstruct(&s, &packed.open_coeffs));
+    }
+    Ok(result)
+}
+
+/// Transforms a vector of packed shares into regular Shamir shares of degree threshold of the first `num_values` secrets. The packed shares are masked with random packed sharings, for which the parties also know regular sharings of the secrets, and opened by the king party.
+pub fn unpack_vec<F: PrimeField, N: ShamirNetwork>(
+    a: &[PackedShare<F>],
+    num_values: usize,
+    packed: &mut ShamirPackedProtocol<F, N>,
+) -> IoResult<Vec<ShamirPrimeFieldShare<F>>> {
+    if num_values > a.len() * packed.pack_size {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Not enough packed shares for the number of values",
+        ));
+    }
+    let (r_packed, r_regular) = packed.rand_unpack_pairs(a.len())?;
+    let masked = izip!(a, r_packed).map(|(a, r)| a.a + r).collect_vec();
+    let values = packed.reveal_masked(masked, packed.degree())?;
+    Ok(izip!(values, r_regular.into_iter().flatten())
+        .take(num_values)
+        .map(|(v, r)| ShamirPrimeFieldShare::new(v - r))
+        .collect())
+}
@@ -0,0 +1,28 @@
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/// This type represents a packed Shamir-share, i.e., the evaluation of a sharing polynomial which hides multiple secrets at once. Since such a share is a field element, this is a wrapper over a field element.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, CanonicalSerialize, CanonicalDeserialize,
+)]
+#[repr(transparent)]
+pub struct ShamirPackedShare<F: PrimeField> {
+    pub(crate) a: F,
+}
+
+impl<F: PrimeField> ShamirPackedShare<F> {
+    /// Wraps the field element into a ShamirPackedShare
+    pub fn new(a: F) -> Self {
+        Self { a }
+    }
+
+    /// Unwraps a ShamirPackedShare into a field element
+    pub fn inner(self) -> F {
+        self.a
+    }
+
+    /// Returns a zero share.
+    pub fn zero_share() -> Self {
+        Self { a: F::zero() }
+    }
+}
@@ -94,6 +94,10 @@ pub fn open_point<C: CurveGroup, N: ShamirNetwork>(
     a: &PointShare<C>,
     shamir: &mut ShamirProtocol<C::ScalarField, N>,
 ) -> IoResult<C> {
+    if shamir.robust_open {
+        let res = open_point_many_robust(vec![a.a], shamir)?;
+        return Ok(res[0]);
+    }
     let rcv = shamir.network.broadcast_next(a.a, shamir.threshold + 1)?;
     let res = core::reconstruct_point(&rcv, &shamir.open_lagrange_t);
     Ok(res)
@@ -105,6 +109,9 @@ pub fn open_point_many<C: CurveGroup, N: ShamirNetwork>(
     shamir: &mut ShamirProtocol<C::ScalarField, N>,
 ) -> IoResult<Vec<C>> {
     let a_a = ShamirPointShare::convert_slice(a);
+    if shamir.robust_open {
+        return open_point_many_robust(a_a.to_owned(), shamir);
+    }
 
     let rcv = shamir
         .network
@@ -125,6 +132,39 @@ pub fn open_point_many<C: CurveGroup, N: ShamirNetwork>(
     Ok(res)
 }
 
+// Opens the point shares by receiving the shares of all parties and checking the whole batch with a single random linear combination, like for field elements. In contrast to field elements, corrupted shares can only be detected but not corrected, since Berlekamp-Welch requires solving a linear system, which is not possible over the group. Hence, an error is returned if the check fails and no parties are recorded as corrupted.
+fn open_point_many_robust<C: CurveGroup, N: ShamirNetwork>(
+    a: Vec<C>,
+    shamir: &mut ShamirProtocol<C::ScalarField, N>,
+) -> IoResult<Vec<C>> {
+    let len = a.len();
+    let rcv = shamir.network.broadcast(a)?;
+    if rcv.iter().any(|r| r.len() != len) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "During robust opening: Invalid number of elements received",
+        ));
+    }
+    let points = (1..=rcv.len()).collect::<Vec<_>>();
+    if !core::is_consistent_batch_point(&rcv, &points, shamir.threshold, &mut rand::thread_rng()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "During robust opening: Inconsistent point shares received",
+        ));
+    }
+    let lagrange = core::lagrange_from_coeff(&points[..=shamir.threshold]);
+    let res = (0..len)
+        .map(|i| {
+            let shares = rcv[..=shamir.threshold]
+                .iter()
+                .map(|r| r[i])
+                .collect::<Vec<_>>();
+            core::reconstruct_point(&shares, &lagrange)
+        })
+        .collect();
+    Ok(res)
+}
+
 /// Perfoms MSM between curve points and field shares.
 pub fn msm_public_points<C: CurveGroup>(
     points: &[C::Affine],
@@ -0,0 +1,100 @@
+//! Reshare
+//!
+//! This module implements the handoff of Shamir-shared values from one committee of parties to another, e.g., to rotate the MPC nodes of a long-running service. The old committee consists of n1 parties with threshold t1, the new committee of n2 parties with threshold t2. Any t1 + 1 parties of the old committee multiply their share with their lagrange coefficient and share the result with a fresh polynomial of degree t2 towards the new committee. Each party of the new committee then sums up the received shares, which results in a sharing of degree t2 of the original values. Thereby, the values are never reconstructed.
+//!
+//! The committees do not need to share a network, instead the old parties produce a [`ShamirReshareMessage`] per new party, which can be serialized and delivered over an arbitrary channel.
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use itertools::izip;
+use rand::{CryptoRng, Rng};
+
+use super::{core, ShamirPrimeFieldShare};
+
+type ShamirShare<F> = ShamirPrimeFieldShare<F>;
+
+/// The message a party of the old committee sends to a party of the new committee during resharing.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ShamirReshareMessage<F: PrimeField> {
+    /// The id of the sending party in the old committee
+    pub sender: usize,
+    /// The id of the receiving party in the new committee
+    pub receiver: usize,
+    /// The shares of the receiving party of the sender's contribution
+    pub shares: Vec<F>,
+}
+
+/// Computes the messages of the party with id `old_id` of the old committee, which reshare its `shares` to a new committee of `new_num_parties` parties with threshold `new_threshold`. `old_committee` contains the ids of the t1 + 1 parties of the old committee which participate in the resharing and has to be the same for all of them. Returns one message per party of the new committee, where the i-th message has to be delivered to party i.
+pub fn reshare_field_elements<F: PrimeField, R: Rng + CryptoRng>(
+    shares: &[ShamirShare<F>],
+    old_id: usize,
+    old_committee: &[usize],
+    new_threshold: usize,
+    new_num_parties: usize,
+    rng: &mut R,
+) -> eyre::Result<Vec<ShamirReshareMessage<F>>> {
+    let Some(pos) = old_committee.iter().position(|id| *id == old_id) else {
+        eyre::bail!("Party {} is not part of the resharing committee", old_id);
+    };
+    if 2 * new_threshold + 1 > new_num_parties {
+        eyre::bail!("Threshold too large for number of parties")
+    }
+    let lagrange =
+        core::lagrange_from_coeff::<F>(&old_committee.iter().map(|id| id + 1).collect::<Vec<_>>());
+
+    let mut messages = (0..new_num_parties)
+        .map(|receiver| ShamirReshareMessage {
+            sender: old_id,
+            receiver,
+            shares: Vec::with_capacity(shares.len()),
+        })
+        .collect::<Vec<_>>();
+    for share in shares {
+        let new_shares = core::share(share.a * lagrange[pos], new_num_parties, new_threshold, rng);
+        for (m, s) in izip!(&mut messages, new_shares) {
+            m.shares.push(s);
+        }
+    }
+    Ok(messages)
+}
+
+/// Combines the messages received by the party with id `new_id` of the new committee into its shares of the reshared values. Exactly one message of each of the `old_threshold` + 1 participating parties of the old committee is required.
+pub fn combine_reshare_messages<F: PrimeField>(
+    messages: &[ShamirReshareMessage<F>],
+    new_id: usize,
+    old_threshold: usize,
+) -> eyre::Result<Vec<ShamirShare<F>>> {
+    if messages.len() != old_threshold + 1 {
+        eyre::bail!(
+            "Expected {} reshare messages, got {}",
+            old_threshold + 1,
+            messages.len()
+        );
+    }
+    let mut senders = messages.iter().map(|m| m.sender).collect::<Vec<_>>();
+    senders.sort();
+    senders.dedup();
+    if senders.len() != messages.len() {
+        eyre::bail!("Received multiple reshare messages from the same party");
+    }
+    if let Some(m) = messages.iter().find(|m| m.receiver != new_id) {
+        eyre::bail!(
+            "Reshare message of party {} is meant for party {}, not {}",
+            m.sender,
+            m.receiver,
+            new_id
+        );
+    }
+    let len = messages[0].shares.len();
+    if messages.iter().any(|m| m.shares.len() != len) {
+        eyre::bail!("Reshare messages contain different numbers of shares");
+    }
+
+    let mut result = vec![F::zero(); len];
+    for m in messages {
+        for (r, s) in izip!(&mut result, &m.shares) {
+            *r += s;
+        }
+    }
+    Ok(ShamirShare::convert_vec_rev(result))
+}
@@ -0,0 +1,220 @@
+//! Triples
+//!
+//! This module contains a pool of DN07 multiplication triples for the Shamir protocol. A triple consists of two random shared values a and b and the product c = a * b, which is computed with the degree reduction of [Damgård and Nielsen](https://www.iacr.org/archive/crypto2007/46220565/46220565.pdf). The triples can be generated ahead of time, e.g., while the service is idle, and are persisted on disk in a [`ShamirTriplePool`]. The online phase consumes them with [`mul_vec_with_triples`], which only requires opening two masked values per multiplication and no fresh randomness.
+//!
+//! All parties have to perform the same calls on their pools, such that they stay in sync. Since a triple must only be used once, consumed triples are removed from the file on disk immediately.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use itertools::izip;
+use serde::{Deserialize, Serialize};
+
+use super::{arithmetic, network::ShamirNetwork, IoResult, ShamirPrimeFieldShare, ShamirProtocol};
+
+type ShamirShare<F> = ShamirPrimeFieldShare<F>;
+
+/// A multiplication triple, i.e., shares of two random values a and b and of their product c = a * b.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ShamirTriple<F: PrimeField> {
+    /// The share of the first random value
+    pub a: ShamirShare<F>,
+    /// The share of the second random value
+    pub b: ShamirShare<F>,
+    /// The share of the product of a and b
+    pub c: ShamirShare<F>,
+}
+
+/// The configuration of a [`ShamirTriplePool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShamirTriplePoolConfig {
+    /// The number of triples the pool is filled up to
+    pub pool_size: usize,
+    /// If less than this number of triples are left, [`ShamirTriplePool::refill_if_needed`] refills the pool
+    pub refill_threshold: usize,
+}
+
+impl Default for ShamirTriplePoolConfig {
+    fn default() -> Self {
+        Self {
+            pool_size: 1 << 16,
+            refill_threshold: 1 << 12,
+        }
+    }
+}
+
+/// A pool of multiplication triples which is backed by a file on disk.
+#[derive(Debug)]
+pub struct ShamirTriplePool<F: PrimeField> {
+    path: PathBuf,
+    config: ShamirTriplePoolConfig,
+    triples: Vec<ShamirTriple<F>>,
+}
+
+impl<F: PrimeField> ShamirTriplePool<F> {
+    /// Opens the pool stored at `path`. If the file does not exist, the pool starts empty and is created on the first refill.
+    pub fn open(path: impl AsRef<Path>, config: ShamirTriplePoolConfig) -> IoResult<Self> {
+        if config.refill_threshold > config.pool_size {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "The refill threshold must not exceed the pool size",
+            ));
+        }
+        let path = path.as_ref().to_path_buf();
+        let triples = if path.exists() {
+            let reader = BufReader::new(File::open(&path)?);
+            Vec::deserialize_uncompressed(reader).map_err(std::io::Error::other)?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            path,
+            config,
+            triples,
+        })
+    }
+
+    /// Returns the path of the file backing the pool.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the configuration of the pool.
+    pub fn config(&self) -> ShamirTriplePoolConfig {
+        self.config
+    }
+
+    /// Returns the number of triples left in the pool.
+    pub fn len(&self) -> usize {
+        self.triples.len()
+    }
+
+    /// Returns true if there are no triples left in the pool.
+    pub fn is_empty(&self) -> bool {
+        self.triples.is_empty()
+    }
+
+    /// Returns true if less than the refill threshold of triples are left in the pool.
+    pub fn needs_refill(&self) -> bool {
+        self.triples.len() < self.config.refill_threshold
+    }
+
+    /// Generates triples until the pool contains the configured pool size and writes them to disk.
+    pub fn fill<N: ShamirNetwork>(&mut self, shamir: &mut ShamirProtocol<F, N>) -> IoResult<()> {
+        let amount = self.config.pool_size.saturating_sub(self.triples.len());
+        if amount == 0 {
+            return Ok(());
+        }
+        tracing::debug!(
+            "Party {}: generating {amount} triples",
+            shamir.network.get_id()
+        );
+        let triples = generate_triples(amount, shamir)?;
+        self.triples.extend(triples);
+        self.persist()
+    }
+
+    /// Fills the pool (see [`Self::fill`]) if less than the refill threshold of triples are left. Returns true if the pool was refilled.
+    pub fn refill_if_needed<N: ShamirNetwork>(
+        &mut self,
+        shamir: &mut ShamirProtocol<F, N>,
+    ) -> IoResult<bool> {
+        if !self.needs_refill() {
+            return Ok(false);
+        }
+        self.fill(shamir)?;
+        Ok(true)
+    }
+
+    /// Removes `amount` triples from the pool and returns them. The remaining triples are written to disk, such that the returned triples cannot be used again. Fails if the pool does not contain enough triples.
+    pub fn take(&mut self, amount: usize) -> IoResult<Vec<ShamirTriple<F>>> {
+        if amount == 0 {
+            return Ok(Vec::new());
+        }
+        let len = self.triples.len();
+        if len < amount {
+            return Err(std::io::Error::other(format!(
+                "Not enough preprocessed triples: requested {amount}, but only {len} left"
+            )));
+        }
+        let triples = self.triples.split_off(len - amount);
+        self.persist()?;
+        Ok(triples)
+    }
+
+    /// Performs element-wise multiplication of two slices of shares with triples taken from the pool (see [`mul_vec_with_triples`]).
+    pub fn mul_vec<N: ShamirNetwork>(
+        &mut self,
+        a: &[ShamirShare<F>],
+        b: &[ShamirShare<F>],
+        shamir: &mut ShamirProtocol<F, N>,
+    ) -> IoResult<Vec<ShamirShare<F>>> {
+        let triples = self.take(a.len())?;
+        mul_vec_with_triples(a, b, &triples, shamir)
+    }
+
+    // Writes the triples to a temporary file first and moves it afterwards, such that the pool on disk is never left in an inconsistent state. The temporary file gets the full file name of the pool plus a suffix, so pools which only differ in their extension do not share it.
+    fn persist(&self) -> IoResult<()> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        {
+            let mut writer = BufWriter::new(File::create(&tmp)?);
+            self.triples
+                .serialize_uncompressed(&mut writer)
+                .map_err(std::io::Error::other)?;
+        }
+        std::fs::rename(tmp, &self.path)
+    }
+}
+
+/// Generates `amount` multiplication triples, where the products are computed with the degree reduction of the [`ShamirProtocol`].
+pub fn generate_triples<F: PrimeField, N: ShamirNetwork>(
+    amount: usize,
+    shamir: &mut ShamirProtocol<F, N>,
+) -> IoResult<Vec<ShamirTriple<F>>> {
+    let a = (0..amount)
+        .map(|_| shamir.rand())
+        .collect::<IoResult<Vec<_>>>()?;
+    let b = (0..amount)
+        .map(|_| shamir.rand())
+        .collect::<IoResult<Vec<_>>>()?;
+    let c = arithmetic::mul_vec(&a, &b, shamir)?;
+    Ok(izip!(a, b, c)
+        .map(|(a, b, c)| ShamirTriple { a, b, c })
+        .collect())
+}
+
+/// Performs element-wise multiplication of two slices of shares using Beaver's technique: For each triple, the masked values x - a and y - b are opened, and the product is computed locally as c + (x - a) * b + (y - b) * a + (x - a) * (y - b). Requires one triple per multiplication.
+pub fn mul_vec_with_triples<F: PrimeField, N: ShamirNetwork>(
+    x: &[ShamirShare<F>],
+    y: &[ShamirShare<F>],
+    triples: &[ShamirTriple<F>],
+    shamir: &mut ShamirProtocol<F, N>,
+) -> IoResult<Vec<ShamirShare<F>>> {
+    debug_assert_eq!(x.len(), y.len());
+    if triples.len() != x.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Number of triples does not match the number of multiplications",
+        ));
+    }
+    let masked = izip!(x, triples)
+        .map(|(x, t)| *x - t.a)
+        .chain(izip!(y, triples).map(|(y, t)| *y - t.b))
+        .collect::<Vec<_>>();
+    let opened = arithmetic::open_vec(&masked, shamir)?;
+    let (d, e) = opened.split_at(x.len());
+    Ok(izip!(triples, d, e)
+        .map(|(t, d, e)| {
+            let mut res = t.c + arithmetic::mul_public(t.b, *d) + arithmetic::mul_public(t.a, *e);
+            arithmetic::add_assign_public(&mut res, *d * e);
+            res
+        })
+        .collect())
+}
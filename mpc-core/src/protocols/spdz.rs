@@ -0,0 +1,185 @@
+//! # SPDZ
+//!
+//! This module implements the [SPDZ](https://eprint.iacr.org/2011/535.pdf) protocol for n parties, which is secure against an active adversary corrupting all but one of the parties. In contrast to REP3 and Shamir, it does not rely on an honest majority, so two mutually-distrusting parties can run it.
+//!
+//! Values are additively shared together with additive shares of a MAC under a global MAC key, which itself is additively shared between the parties (see [`SpdzPrimeFieldShare`]). Multiplications consume authenticated Beaver triples. The values opened during the computation are recorded and verified in a MAC-check phase ([`SpdzProtocol::mac_check`]), which is run before an opened output is returned.
+//!
+//! The correlated randomness, i.e., the MAC key shares, triples and input masks, is provided as [`SpdzPreprocessedData`], which the parties generate interactively with oblivious transfers as in [MASCOT](https://eprint.iacr.org/2016/505.pdf) (see [`SpdzPreprocessor`]). Since the MAC key is never known to a single party, the inputs are shared with the input masks of their owner (see [`arithmetic::input_vec`]).
+//!
+//! Keep in mind: The OT extension used by the preprocessing is only secure against semi-honest adversaries, so the preprocessing does not yet achieve the full active security of MASCOT (see [`preprocessing`]).
+
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use rand::SeedableRng;
+
+use crate::RngType;
+
+use super::shamir::network::ShamirNetwork;
+
+pub mod arithmetic;
+mod core;
+pub mod pointshare;
+pub mod preprocessing;
+
+pub use arithmetic::types::SpdzPrimeFieldShare;
+pub use pointshare::types::SpdzPointShare;
+pub use preprocessing::{SpdzPreprocessedData, SpdzPreprocessor};
+
+type IoResult<T> = std::io::Result<T>;
+type SpdzShare<F> = SpdzPrimeFieldShare<F>;
+
+/// Reconstructs a field element from the SPDZ shares of all parties. The MAC is *NOT* checked.
+pub fn combine_field_element<F: PrimeField>(shares: &[SpdzShare<F>]) -> F {
+    shares.iter().map(|s| s.share).sum()
+}
+
+/// Reconstructs a vector of field elements from the SPDZ shares of all parties. Thereby, shares\[i\]\[j\] represents the j-th share of party i. The MACs are *NOT* checked.
+pub fn combine_field_elements<F: PrimeField>(shares: &[Vec<SpdzShare<F>>]) -> Vec<F> {
+    let mut result = vec![F::zero(); shares.first().map(|s| s.len()).unwrap_or_default()];
+    for party_shares in shares {
+        debug_assert_eq!(party_shares.len(), result.len());
+        for (r, s) in result.iter_mut().zip(party_shares) {
+            *r += s.share;
+        }
+    }
+    result
+}
+
+/// Reconstructs a curve point from the SPDZ shares of all parties. The MAC is *NOT* checked.
+pub fn combine_curve_point<C: CurveGroup>(shares: &[SpdzPointShare<C>]) -> C {
+    shares.iter().map(|s| s.share).sum()
+}
+
+/// This struct holds all necessary information for an MPC protocol based on SPDZ. It contains a [`ShamirNetwork`], which is used for the n-party communication, the [`SpdzPreprocessedData`] of the party and the values that were opened, but not yet checked.
+pub struct SpdzProtocol<F: PrimeField, N: ShamirNetwork> {
+    /// The underlying [`ShamirNetwork`]
+    pub network: N,
+    preprocessing: SpdzPreprocessedData<F>,
+    // the opened values (including the public offset) together with the own MAC share
+    unchecked: Vec<(F, F)>,
+    rng: RngType,
+}
+
+impl<F: PrimeField, N: ShamirNetwork> SpdzProtocol<F, N> {
+    /// Creates a new [`SpdzProtocol`] from a network and the [`SpdzPreprocessedData`] of the party.
+    pub fn new(network: N, preprocessing: SpdzPreprocessedData<F>) -> eyre::Result<Self> {
+        if preprocessing.party_id() != network.get_id() {
+            eyre::bail!(
+                "Preprocessed data of party {} provided to party {}",
+                preprocessing.party_id(),
+                network.get_id()
+            );
+        }
+        if preprocessing.num_parties() != network.get_num_parties() {
+            eyre::bail!(
+                "Preprocessed data is for {} parties, but the network has {}",
+                preprocessing.num_parties(),
+                network.get_num_parties()
+            );
+        }
+        Ok(Self {
+            network,
+            preprocessing,
+            unchecked: Vec::new(),
+            rng: RngType::from_entropy(),
+        })
+    }
+
+    /// Returns the share of the MAC key.
+    pub fn mac_key_share(&self) -> F {
+        self.preprocessing.mac_key_share
+    }
+
+    /// Returns the remaining [`SpdzPreprocessedData`].
+    pub fn preprocessing(&self) -> &SpdzPreprocessedData<F> {
+        &self.preprocessing
+    }
+
+    /// Generates a random shared field element. Consumes a multiplication triple.
+    pub fn rand(&mut self) -> IoResult<SpdzShare<F>> {
+        self.next_triple().map(|(a, _, _)| a)
+    }
+
+    pub(crate) fn next_triple(&mut self) -> IoResult<(SpdzShare<F>, SpdzShare<F>, SpdzShare<F>)> {
+        let pre = &mut self.preprocessing;
+        match (
+            pre.triples_a.pop(),
+            pre.triples_b.pop(),
+            pre.triples_c.pop(),
+        ) {
+            (Some(a), Some(b), Some(c)) => Ok((a, b, c)),
+            _ => Err(std::io::Error::other(
+                "Not enough preprocessed multiplication triples",
+            )),
+        }
+    }
+
+    /// Returns the next input mask of party `owner`, and its value if we are the owner.
+    pub(crate) fn next_mask(&mut self, owner: usize) -> IoResult<(SpdzShare<F>, Option<F>)> {
+        let pre = &mut self.preprocessing;
+        let mask = pre
+            .masks
+            .get_mut(owner)
+            .and_then(|masks| masks.pop())
+            .ok_or_else(|| std::io::Error::other("Not enough preprocessed input masks"))?;
+        let value = if owner == pre.party_id {
+            Some(
+                pre.mask_values
+                    .pop()
+                    .ok_or_else(|| std::io::Error::other("Not enough preprocessed input masks"))?,
+            )
+        } else {
+            None
+        };
+        Ok((mask, value))
+    }
+
+    /// Opens the shared values without checking the MACs. The opened values are recorded and checked during the next [`SpdzProtocol::mac_check`].
+    pub(crate) fn open_unchecked(&mut self, a: &[SpdzShare<F>]) -> IoResult<Vec<F>> {
+        let shares = a.iter().map(|a| a.share).collect::<Vec<_>>();
+        let rcv = self.network.broadcast(shares)?;
+        let mut opened = vec![F::zero(); a.len()];
+        for r in rcv {
+            if r.len() != a.len() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "During opening in SPDZ: Invalid number of elements received",
+                ));
+            }
+            for (o, r) in opened.iter_mut().zip(r) {
+                *o += r;
+            }
+        }
+        self.unchecked.extend(
+            opened
+                .iter()
+                .zip(a)
+                .map(|(opened, a)| (*opened + a.offset, a.mac)),
+        );
+        Ok(opened)
+    }
+
+    /// Checks the MACs of all values opened since the last check. Returns an error if the check fails, i.e., if a party cheated, in which case the parties have to abort.
+    pub fn mac_check(&mut self) -> IoResult<()> {
+        if self.unchecked.is_empty() {
+            return Ok(());
+        }
+        let mac_key_share = self.mac_key_share();
+        let sigmas = self
+            .unchecked
+            .drain(..)
+            .map(|(value, mac)| mac - mac_key_share * value)
+            .collect::<Vec<_>>();
+        core::check_zero_sum(sigmas, &mut self.rng, &mut self.network)
+    }
+
+    /// Checks that the MAC shares of an opened point sum up to the MAC of the point.
+    pub(crate) fn point_mac_check<C: CurveGroup<ScalarField = F>>(
+        &mut self,
+        opened: C,
+        a: &SpdzPointShare<C>,
+    ) -> IoResult<()> {
+        let sigma = a.mac - (opened + a.offset) * self.mac_key_share();
+        core::check_zero_sum(vec![sigma], &mut self.rng, &mut self.network)
+    }
+}
@@ -0,0 +1,233 @@
+//! Arithmetic
+//!
+//! This module contains operations with arithmetic shares
+
+use ark_ff::PrimeField;
+use itertools::{izip, Itertools};
+
+use super::{IoResult, ShamirNetwork, SpdzProtocol};
+
+mod ops;
+pub(super) mod types;
+
+type SpdzShare<F> = types::SpdzPrimeFieldShare<F>;
+
+/// Performs addition between two shares.
+pub fn add<F: PrimeField>(a: SpdzShare<F>, b: SpdzShare<F>) -> SpdzShare<F> {
+    a + b
+}
+
+/// Performs addition between two shares where the result is stored in `a`.
+pub fn add_assign<F: PrimeField>(a: &mut SpdzShare<F>, b: SpdzShare<F>) {
+    *a += b;
+}
+
+/// Performs subtraction between two shares.
+pub fn sub<F: PrimeField>(a: SpdzShare<F>, b: SpdzShare<F>) -> SpdzShare<F> {
+    a - b
+}
+
+/// Performs subtraction between two shares where the result is stored in `a`.
+pub fn sub_assign<F: PrimeField>(a: &mut SpdzShare<F>, b: SpdzShare<F>) {
+    *a -= b;
+}
+
+/// Performs addition between a share and a public value. Only party 0 adds the value to its share, all parties subtract it from the public offset, so the MAC stays valid.
+pub fn add_public<F: PrimeField>(shared: SpdzShare<F>, public: F, id: usize) -> SpdzShare<F> {
+    let mut res = shared;
+    add_assign_public(&mut res, public, id);
+    res
+}
+
+/// Performs addition between a share and a public value where the result is stored in `shared`.
+pub fn add_assign_public<F: PrimeField>(shared: &mut SpdzShare<F>, public: F, id: usize) {
+    if id == 0 {
+        shared.share += public;
+    }
+    shared.offset -= public;
+}
+
+/// Performs multiplication between a share and a public value.
+pub fn mul_public<F: PrimeField>(shared: SpdzShare<F>, public: F) -> SpdzShare<F> {
+    shared * public
+}
+
+/// Performs multiplication between a share and a public value where the result is stored in `shared`.
+pub fn mul_assign_public<F: PrimeField>(shared: &mut SpdzShare<F>, public: F) {
+    *shared *= public;
+}
+
+/// Negates a shared value.
+pub fn neg<F: PrimeField>(a: SpdzShare<F>) -> SpdzShare<F> {
+    -a
+}
+
+/// Transforms a public value into a shared value: \[a\] = a.
+pub fn promote_to_trivial_share<F: PrimeField>(id: usize, public_value: F) -> SpdzShare<F> {
+    add_public(SpdzShare::zero_share(), public_value, id)
+}
+
+/// Elementwise transformation of a vector of public values into a vector of shared values: \[a_i\] = a_i.
+pub fn promote_to_trivial_shares<F: PrimeField>(
+    id: usize,
+    public_values: &[F],
+) -> Vec<SpdzShare<F>> {
+    public_values
+        .iter()
+        .map(|value| promote_to_trivial_share(id, *value))
+        .collect()
+}
+
+/// Performs multiplication between two shares using a Beaver triple.
+pub fn mul<F: PrimeField, N: ShamirNetwork>(
+    a: SpdzShare<F>,
+    b: SpdzShare<F>,
+    spdz: &mut SpdzProtocol<F, N>,
+) -> IoResult<SpdzShare<F>> {
+    let res = mul_vec(&[a], &[b], spdz)?;
+    Ok(res[0])
+}
+
+/// Performs element-wise multiplication of two slices of shares. Each multiplication consumes a Beaver triple (x, y, z = x * y): The parties open d = a - x and e = b - y and compute a * b = z + d * y + e * x + d * e. The opened values are checked during the next MAC check.
+pub fn mul_vec<F: PrimeField, N: ShamirNetwork>(
+    a: &[SpdzShare<F>],
+    b: &[SpdzShare<F>],
+    spdz: &mut SpdzProtocol<F, N>,
+) -> IoResult<Vec<SpdzShare<F>>> {
+    debug_assert_eq!(a.len(), b.len());
+    let triples = (0..a.len())
+        .map(|_| spdz.next_triple())
+        .collect::<IoResult<Vec<_>>>()?;
+    let masked = izip!(a, b, &triples)
+        .flat_map(|(a, b, (x, y, _))| [a - x, b - y])
+        .collect_vec();
+    let opened = spdz.open_unchecked(&masked)?;
+    let id = spdz.network.get_id();
+    let res = izip!(&triples, opened.chunks_exact(2))
+        .map(|((x, y, z), opened)| {
+            let (d, e) = (opened[0], opened[1]);
+            add_public(*z + *y * d + *x * e, d * e, id)
+        })
+        .collect();
+    Ok(res)
+}
+
+/// Turns additive shares without MACs, e.g., the results of a local computation on the additive shares of SPDZ-shared values, into SPDZ shares. For each value v, the parties open v - r for a random shared value r and compute \[v\] = \[r\] + (v - r).
+///
+/// # Security
+/// A corrupted party can add an arbitrary error to the results, which is *NOT* detected by the MAC check. The caller has to ensure the correctness of the final output in a different way, e.g., by verifying the resulting proof.
+pub fn authenticate_vec<F: PrimeField, N: ShamirNetwork>(
+    additive: &[F],
+    spdz: &mut SpdzProtocol<F, N>,
+) -> IoResult<Vec<SpdzShare<F>>> {
+    let masks = (0..additive.len())
+        .map(|_| spdz.rand())
+        .collect::<IoResult<Vec<_>>>()?;
+    let masked = izip!(additive, &masks)
+        .map(|(v, r)| *v - r.share)
+        .collect_vec();
+    let rcv = spdz.network.broadcast(masked)?;
+    let mut opened = vec![F::zero(); additive.len()];
+    for r in rcv {
+        if r.len() != opened.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "During authentication in SPDZ: Invalid number of elements received",
+            ));
+        }
+        for (o, r) in opened.iter_mut().zip(r) {
+            *o += r;
+        }
+    }
+    let id = spdz.network.get_id();
+    Ok(izip!(masks, opened)
+        .map(|(r, opened)| add_public(r, opened, id))
+        .collect())
+}
+
+/// Secret shares the values of the party with id `owner`. Only the owner has to provide the `values`, all other parties provide `None` and only need to know the number of values. The owner broadcasts the values masked with its input masks, and all parties check that they received the same masked values.
+pub fn input_vec<F: PrimeField, N: ShamirNetwork>(
+    values: Option<&[F]>,
+    owner: usize,
+    len: usize,
+    spdz: &mut SpdzProtocol<F, N>,
+) -> IoResult<Vec<SpdzShare<F>>> {
+    let masks = (0..len)
+        .map(|_| spdz.next_mask(owner))
+        .collect::<IoResult<Vec<_>>>()?;
+    let masked = if spdz.network.get_id() == owner {
+        let values = values.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "The owner has to provide the values",
+            )
+        })?;
+        if values.len() != len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Invalid number of values provided",
+            ));
+        }
+        let masked = izip!(values, &masks)
+            .map(|(v, (_, r))| *v - r.expect("owner knows its masks"))
+            .collect_vec();
+        // a corrupted owner could send different values to different parties
+        spdz.network.send_broadcast_many(&masked)?;
+        masked
+    } else {
+        let masked = spdz.network.recv_broadcast_many::<F>(owner)?;
+        if masked.len() != len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "During input in SPDZ: Invalid number of elements received",
+            ));
+        }
+        masked
+    };
+
+    let id = spdz.network.get_id();
+    Ok(izip!(masks, masked)
+        .map(|((r, _), m)| add_public(r, m, id))
+        .collect())
+}
+
+/// Reconstructs a shared value: a = Open(\[a\]). Runs the MAC check before returning the value.
+pub fn open<F: PrimeField, N: ShamirNetwork>(
+    a: SpdzShare<F>,
+    spdz: &mut SpdzProtocol<F, N>,
+) -> IoResult<F> {
+    let res = open_vec(&[a], spdz)?;
+    Ok(res[0])
+}
+
+/// Reconstructs many shared values: a_i = Open(\[a_i\]). Runs the MAC check before returning the values.
+pub fn open_vec<F: PrimeField, N: ShamirNetwork>(
+    a: &[SpdzShare<F>],
+    spdz: &mut SpdzProtocol<F, N>,
+) -> IoResult<Vec<F>> {
+    let res = spdz.open_unchecked(a)?;
+    spdz.mac_check()?;
+    Ok(res)
+}
+
+/// Reconstructs many shared values towards the party with the given id. Only this party learns a_i, all other parties get `None`. The values are masked with the input masks of the receiving party before they are opened.
+pub fn open_vec_to<F: PrimeField, N: ShamirNetwork>(
+    a: &[SpdzShare<F>],
+    party_id: usize,
+    spdz: &mut SpdzProtocol<F, N>,
+) -> IoResult<Option<Vec<F>>> {
+    let masks = (0..a.len())
+        .map(|_| spdz.next_mask(party_id))
+        .collect::<IoResult<Vec<_>>>()?;
+    let masked = izip!(a, &masks).map(|(a, (r, _))| *a + *r).collect_vec();
+    let opened = open_vec(&masked, spdz)?;
+    if spdz.network.get_id() == party_id {
+        Ok(Some(
+            izip!(opened, masks)
+                .map(|(o, (_, r))| o - r.expect("receiver knows its masks"))
+                .collect(),
+        ))
+    } else {
+        Ok(None)
+    }
+}
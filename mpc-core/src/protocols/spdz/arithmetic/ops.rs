@@ -0,0 +1,139 @@
+use ark_ff::PrimeField;
+
+use super::types::SpdzPrimeFieldShare;
+
+impl<F: PrimeField> std::ops::Add for SpdzPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            share: self.share + rhs.share,
+            mac: self.mac + rhs.mac,
+            offset: self.offset + rhs.offset,
+        }
+    }
+}
+
+impl<F: PrimeField> std::ops::Add<&SpdzPrimeFieldShare<F>> for SpdzPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn add(self, rhs: &Self) -> Self::Output {
+        self + *rhs
+    }
+}
+
+impl<F: PrimeField> std::ops::Add<&SpdzPrimeFieldShare<F>> for &'_ SpdzPrimeFieldShare<F> {
+    type Output = SpdzPrimeFieldShare<F>;
+
+    fn add(self, rhs: &SpdzPrimeFieldShare<F>) -> Self::Output {
+        *self + *rhs
+    }
+}
+
+impl<F: PrimeField> std::ops::AddAssign for SpdzPrimeFieldShare<F> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.share += rhs.share;
+        self.mac += rhs.mac;
+        self.offset += rhs.offset;
+    }
+}
+
+impl<F: PrimeField> std::ops::AddAssign<&SpdzPrimeFieldShare<F>> for SpdzPrimeFieldShare<F> {
+    fn add_assign(&mut self, rhs: &Self) {
+        *self += *rhs;
+    }
+}
+
+impl<F: PrimeField> std::ops::Sub for SpdzPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            share: self.share - rhs.share,
+            mac: self.mac - rhs.mac,
+            offset: self.offset - rhs.offset,
+        }
+    }
+}
+
+impl<F: PrimeField> std::ops::Sub<&SpdzPrimeFieldShare<F>> for SpdzPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: &Self) -> Self::Output {
+        self - *rhs
+    }
+}
+
+impl<F: PrimeField> std::ops::Sub<&SpdzPrimeFieldShare<F>> for &'_ SpdzPrimeFieldShare<F> {
+    type Output = SpdzPrimeFieldShare<F>;
+
+    fn sub(self, rhs: &SpdzPrimeFieldShare<F>) -> Self::Output {
+        *self - *rhs
+    }
+}
+
+impl<F: PrimeField> std::ops::SubAssign for SpdzPrimeFieldShare<F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.share -= rhs.share;
+        self.mac -= rhs.mac;
+        self.offset -= rhs.offset;
+    }
+}
+
+impl<F: PrimeField> std::ops::SubAssign<&SpdzPrimeFieldShare<F>> for SpdzPrimeFieldShare<F> {
+    fn sub_assign(&mut self, rhs: &Self) {
+        *self -= *rhs;
+    }
+}
+
+impl<F: PrimeField> std::ops::Mul<F> for SpdzPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: F) -> Self::Output {
+        Self {
+            share: self.share * rhs,
+            mac: self.mac * rhs,
+            offset: self.offset * rhs,
+        }
+    }
+}
+
+impl<F: PrimeField> std::ops::Mul<&F> for &'_ SpdzPrimeFieldShare<F> {
+    type Output = SpdzPrimeFieldShare<F>;
+
+    fn mul(self, rhs: &F) -> Self::Output {
+        *self * *rhs
+    }
+}
+
+impl<F: PrimeField> std::ops::MulAssign<F> for SpdzPrimeFieldShare<F> {
+    fn mul_assign(&mut self, rhs: F) {
+        self.share *= rhs;
+        self.mac *= rhs;
+        self.offset *= rhs;
+    }
+}
+
+impl<F: PrimeField> std::ops::Neg for SpdzPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            share: -self.share,
+            mac: -self.mac,
+            offset: -self.offset,
+        }
+    }
+}
+
+impl<F: PrimeField> ark_ff::Zero for SpdzPrimeFieldShare<F> {
+    fn zero() -> Self {
+        Self::zero_share()
+    }
+
+    fn is_zero(&self) -> bool {
+        panic!(
+            "is_zero is not a meaningful operation for SpdzPrimeFieldShare, open the value instead"
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/// This type represents a SPDZ-shared value. It consists of an additive share of the value x and an additive share of its MAC α·(x + δ), where α is the global MAC key and δ is a public offset which is the same for all parties. The offset allows adding public values without knowledge of the MAC key.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, CanonicalSerialize, CanonicalDeserialize,
+)]
+pub struct SpdzPrimeFieldShare<F: PrimeField> {
+    pub(crate) share: F,
+    pub(crate) mac: F,
+    pub(crate) offset: F,
+}
+
+impl<F: PrimeField> SpdzPrimeFieldShare<F> {
+    /// Constructs the type from an additive share of the value, an additive share of its MAC and the public offset.
+    pub fn new(share: F, mac: F, offset: F) -> Self {
+        Self { share, mac, offset }
+    }
+
+    /// Returns a zero share.
+    pub fn zero_share() -> Self {
+        Self {
+            share: F::zero(),
+            mac: F::zero(),
+            offset: F::zero(),
+        }
+    }
+
+    /// Returns the additive share of the value.
+    pub fn share(&self) -> F {
+        self.share
+    }
+
+    /// Returns the additive share of the MAC.
+    pub fn mac(&self) -> F {
+        self.mac
+    }
+
+    /// Returns the public offset.
+    pub fn offset(&self) -> F {
+        self.offset
+    }
+}
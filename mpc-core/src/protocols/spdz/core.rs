@@ -0,0 +1,100 @@
+//! Core
+//!
+//! This module contains the commit-and-open procedure, which is used by the MAC check and to toss common coins.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use itertools::izip;
+use rand::{CryptoRng, Rng, SeedableRng};
+use sha3::{Digest, Sha3_256};
+
+use crate::{protocols::shamir::network::ShamirNetwork, RngType, SEED_SIZE};
+
+use super::IoResult;
+
+const SALT_SIZE: usize = 32;
+
+/// Commits to `payload`, then opens the commitments of all parties and checks them. Returns the payloads of all parties, including our own. The commitments ensure that no party can choose its payload after seeing the payloads of the other parties.
+fn commit_and_open<N, R>(payload: &[u8], rng: &mut R, network: &mut N) -> IoResult<Vec<Vec<u8>>>
+where
+    N: ShamirNetwork,
+    R: Rng + CryptoRng,
+{
+    let mut salted = vec![0u8; SALT_SIZE];
+    rng.fill_bytes(&mut salted);
+    salted.extend_from_slice(payload);
+    let commitment = Sha3_256::digest(&salted).to_vec();
+
+    let commitments = network.broadcast(commitment)?;
+    let openings = network.broadcast(salted)?;
+
+    izip!(commitments, openings)
+        .map(|(commitment, mut opening)| {
+            if opening.len() < SALT_SIZE || Sha3_256::digest(&opening).as_slice() != commitment {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "A party did not open its commitment",
+                ));
+            }
+            Ok(opening.split_off(SALT_SIZE))
+        })
+        .collect()
+}
+
+/// Commits to `values`, opens the commitments of all parties and checks that the values of all parties sum up to zero element-wise.
+pub(super) fn check_zero_sum<T, N, R>(values: Vec<T>, rng: &mut R, network: &mut N) -> IoResult<()>
+where
+    T: CanonicalSerialize + CanonicalDeserialize + ark_ff::Zero + std::ops::AddAssign + Clone,
+    N: ShamirNetwork,
+    R: Rng + CryptoRng,
+{
+    let mut payload = Vec::new();
+    values
+        .serialize_uncompressed(&mut payload)
+        .map_err(std::io::Error::other)?;
+    let payloads = commit_and_open(&payload, rng, network)
+        .map_err(|err| std::io::Error::new(err.kind(), format!("MAC check failed: {err}")))?;
+
+    let mut sum = vec![T::zero(); values.len()];
+    for payload in payloads {
+        let other = Vec::<T>::deserialize_uncompressed(payload.as_slice())
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        if other.len() != sum.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "MAC check failed, invalid number of elements received",
+            ));
+        }
+        for (s, o) in izip!(&mut sum, other) {
+            *s += o;
+        }
+    }
+    if sum.iter().any(|s| !s.is_zero()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "MAC check failed, a party cheated",
+        ));
+    }
+    Ok(())
+}
+
+/// Tosses common coins with all parties: Every party commits to a random seed, and the opened seeds are combined with XOR. Returns an RNG seeded with the result, which no party can bias as long as one party is honest.
+pub(super) fn coin_toss<N, R>(rng: &mut R, network: &mut N) -> IoResult<RngType>
+where
+    N: ShamirNetwork,
+    R: Rng + CryptoRng,
+{
+    let seed: [u8; SEED_SIZE] = rng.gen();
+    let mut common = [0u8; SEED_SIZE];
+    for other in commit_and_open(&seed, rng, network)? {
+        if other.len() != SEED_SIZE {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "During coin tossing in SPDZ: Invalid seed received",
+            ));
+        }
+        for (c, o) in izip!(&mut common, other) {
+            *c ^= o;
+        }
+    }
+    Ok(RngType::from_seed(common))
+}
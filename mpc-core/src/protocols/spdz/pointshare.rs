@@ -0,0 +1,114 @@
+//! Pointshare
+//!
+//! This module contains operations with point shares
+
+mod ops;
+pub(super) mod types;
+
+use ark_ec::CurveGroup;
+use itertools::Itertools;
+
+use super::{IoResult, ShamirNetwork, SpdzPointShare, SpdzPrimeFieldShare, SpdzProtocol};
+
+type FieldShare<F> = SpdzPrimeFieldShare<F>;
+type PointShare<C> = SpdzPointShare<C>;
+
+/// Performs addition between two shares.
+pub fn add<C: CurveGroup>(a: &PointShare<C>, b: &PointShare<C>) -> PointShare<C> {
+    a + b
+}
+
+/// Performs subtraction between two shares.
+pub fn sub<C: CurveGroup>(a: &PointShare<C>, b: &PointShare<C>) -> PointShare<C> {
+    a - b
+}
+
+/// Performs addition between two shares and stores the result in `a`.
+pub fn add_assign<C: CurveGroup>(a: &mut PointShare<C>, b: &PointShare<C>) {
+    *a += b;
+}
+
+/// Performs subtraction between two shares and stores the result in `a`.
+pub fn sub_assign<C: CurveGroup>(a: &mut PointShare<C>, b: &PointShare<C>) {
+    *a -= b;
+}
+
+/// Performs addition between a share and a public value and stores the result in `a`. Only party 0 adds the value to its share, all parties subtract it from the public offset.
+pub fn add_assign_public<C: CurveGroup>(a: &mut PointShare<C>, b: &C, id: usize) {
+    if id == 0 {
+        a.share += b;
+    }
+    a.offset -= b;
+}
+
+/// Performs subtraction between a share and a public value and stores the result in `a`.
+pub fn sub_assign_public<C: CurveGroup>(a: &mut PointShare<C>, b: &C, id: usize) {
+    add_assign_public(a, &-*b, id)
+}
+
+/// Performs multiplication between a field share and a public curve group value.
+pub fn scalar_mul_public_point<C: CurveGroup>(
+    shared: FieldShare<C::ScalarField>,
+    public: &C,
+) -> PointShare<C> {
+    PointShare {
+        share: *public * shared.share,
+        mac: *public * shared.mac,
+        offset: *public * shared.offset,
+    }
+}
+
+/// Performs scalar multiplication between a point share and a public scalar.
+pub fn scalar_mul_public_scalar<C: CurveGroup>(
+    a: &PointShare<C>,
+    b: &C::ScalarField,
+) -> PointShare<C> {
+    a * *b
+}
+
+/// Perfoms MSM between curve points and field shares.
+pub fn msm_public_points<C: CurveGroup>(
+    points: &[C::Affine],
+    scalars: &[FieldShare<C::ScalarField>],
+) -> PointShare<C> {
+    tracing::trace!("> MSM public points for {} elements", points.len());
+    debug_assert_eq!(points.len(), scalars.len());
+    let share = C::msm_unchecked(points, &scalars.iter().map(|s| s.share).collect_vec());
+    let mac = C::msm_unchecked(points, &scalars.iter().map(|s| s.mac).collect_vec());
+    let offset = C::msm_unchecked(points, &scalars.iter().map(|s| s.offset).collect_vec());
+    tracing::trace!("< MSM public points for {} elements", points.len());
+    PointShare { share, mac, offset }
+}
+
+/// Performs scalar multiplication between a point share and a field share. Consumes a Beaver triple (x, y, z = x * y), which is lifted to the curve by multiplying y and z with the generator G: The parties open d = b - x and D = A - y·G and compute b·A = z·G + d·(y·G) + x·D + d·D.
+pub fn scalar_mul<C: CurveGroup, N: ShamirNetwork>(
+    a: &PointShare<C>,
+    b: FieldShare<C::ScalarField>,
+    spdz: &mut SpdzProtocol<C::ScalarField, N>,
+) -> IoResult<PointShare<C>> {
+    let (x, y, z) = spdz.next_triple()?;
+    let generator = C::generator();
+    let y_g = scalar_mul_public_point(y, &generator);
+    let z_g = scalar_mul_public_point(z, &generator);
+
+    let d = spdz.open_unchecked(&[b - x])?[0];
+    let d_point = open_point(&sub(a, &y_g), spdz)?;
+
+    let mut res = z_g;
+    add_assign(&mut res, &scalar_mul_public_scalar(&y_g, &d));
+    add_assign(&mut res, &scalar_mul_public_point(x, &d_point));
+    add_assign_public(&mut res, &(d_point * d), spdz.network.get_id());
+    Ok(res)
+}
+
+/// Performs opening of a point share. Checks the MAC of the point and of all values opened since the last MAC check before returning the point.
+pub fn open_point<C: CurveGroup, N: ShamirNetwork>(
+    a: &PointShare<C>,
+    spdz: &mut SpdzProtocol<C::ScalarField, N>,
+) -> IoResult<C> {
+    let rcv = spdz.network.broadcast(a.share)?;
+    let opened = rcv.into_iter().sum::<C>();
+    spdz.point_mac_check(opened, a)?;
+    spdz.mac_check()?;
+    Ok(opened)
+}
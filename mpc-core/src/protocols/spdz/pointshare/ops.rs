@@ -0,0 +1,79 @@
+use ark_ec::CurveGroup;
+
+use super::types::SpdzPointShare;
+
+impl<C: CurveGroup> std::ops::Add for SpdzPointShare<C> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            share: self.share + rhs.share,
+            mac: self.mac + rhs.mac,
+            offset: self.offset + rhs.offset,
+        }
+    }
+}
+
+impl<C: CurveGroup> std::ops::Add<&SpdzPointShare<C>> for &'_ SpdzPointShare<C> {
+    type Output = SpdzPointShare<C>;
+
+    fn add(self, rhs: &SpdzPointShare<C>) -> Self::Output {
+        SpdzPointShare {
+            share: self.share + rhs.share,
+            mac: self.mac + rhs.mac,
+            offset: self.offset + rhs.offset,
+        }
+    }
+}
+
+impl<C: CurveGroup> std::ops::AddAssign<&SpdzPointShare<C>> for SpdzPointShare<C> {
+    fn add_assign(&mut self, rhs: &Self) {
+        self.share += rhs.share;
+        self.mac += rhs.mac;
+        self.offset += rhs.offset;
+    }
+}
+
+impl<C: CurveGroup> std::ops::Sub for SpdzPointShare<C> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            share: self.share - rhs.share,
+            mac: self.mac - rhs.mac,
+            offset: self.offset - rhs.offset,
+        }
+    }
+}
+
+impl<C: CurveGroup> std::ops::Sub<&SpdzPointShare<C>> for &'_ SpdzPointShare<C> {
+    type Output = SpdzPointShare<C>;
+
+    fn sub(self, rhs: &SpdzPointShare<C>) -> Self::Output {
+        SpdzPointShare {
+            share: self.share - rhs.share,
+            mac: self.mac - rhs.mac,
+            offset: self.offset - rhs.offset,
+        }
+    }
+}
+
+impl<C: CurveGroup> std::ops::SubAssign<&SpdzPointShare<C>> for SpdzPointShare<C> {
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.share -= rhs.share;
+        self.mac -= rhs.mac;
+        self.offset -= rhs.offset;
+    }
+}
+
+impl<C: CurveGroup> std::ops::Mul<C::ScalarField> for &'_ SpdzPointShare<C> {
+    type Output = SpdzPointShare<C>;
+
+    fn mul(self, scalar: C::ScalarField) -> Self::Output {
+        SpdzPointShare {
+            share: self.share * scalar,
+            mac: self.mac * scalar,
+            offset: self.offset * scalar,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use ark_ec::CurveGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/// This type represents a SPDZ-shared EC point. Analogous to [`SpdzPrimeFieldShare`](crate::protocols::spdz::SpdzPrimeFieldShare), it consists of an additive share of the point X, an additive share of its MAC α·(X + Δ) and a public offset Δ.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SpdzPointShare<C: CurveGroup> {
+    pub(crate) share: C,
+    pub(crate) mac: C,
+    pub(crate) offset: C,
+}
+
+impl<C: CurveGroup> Default for SpdzPointShare<C> {
+    fn default() -> Self {
+        Self::zero_share()
+    }
+}
+
+impl<C: CurveGroup> SpdzPointShare<C> {
+    /// Constructs the type from an additive share of the point, an additive share of its MAC and the public offset.
+    pub fn new(share: C, mac: C, offset: C) -> Self {
+        Self { share, mac, offset }
+    }
+
+    /// Returns a zero share.
+    pub fn zero_share() -> Self {
+        Self {
+            share: C::zero(),
+            mac: C::zero(),
+            offset: C::zero(),
+        }
+    }
+
+    /// Returns the additive share of the point.
+    pub fn share(&self) -> C {
+        self.share
+    }
+
+    /// Returns the additive share of the MAC.
+    pub fn mac(&self) -> C {
+        self.mac
+    }
+
+    /// Returns the public offset.
+    pub fn offset(&self) -> C {
+        self.offset
+    }
+}
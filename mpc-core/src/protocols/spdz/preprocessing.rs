@@ -0,0 +1,337 @@
+//! Preprocessing
+//!
+//! This module contains the correlated randomness consumed by the [`SpdzProtocol`](super::SpdzProtocol), and the [`SpdzPreprocessor`], which generates it interactively following [MASCOT](https://eprint.iacr.org/2016/505.pdf), such that no party has to be trusted.
+//!
+//! Every party samples its share of the MAC key locally. The values are authenticated with the correlated oblivious product evaluation (COPE) of MASCOT: For each pair of parties, the base OTs are random OTs from the [OT extension](crate::ot) with the bits of the MAC key share of the receiver as choices. From the keys of these OTs, both parties derive pseudorandom field elements, such that the sender only has to send one correction per bit to obtain additive shares of the product of its value with the MAC key share of the receiver. The products of the triples are computed with correlated OTs as proposed by [Gilboa](https://link.springer.com/chapter/10.1007/3-540-48405-1_8), as in the [2PC protocol](crate::protocols::twopc).
+//!
+//! Each batch is checked before it is returned: The MACs of a random linear combination of all authenticated values are checked, which detects a party using inconsistent MAC key shares, and every triple is checked by sacrificing a second triple with the same second factor.
+//!
+//! Keep in mind: The OT extension is only secure against semi-honest adversaries, and the consistency checks of the OTs proposed by MASCOT are not implemented. While incorrect triples and MACs are detected, a corrupted party deviating in the OTs may learn information about the shares of the other parties.
+
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use itertools::{izip, Itertools};
+use rand::SeedableRng;
+
+use crate::{
+    ot::{OtExtension, Pad},
+    protocols::shamir::network::ShamirNetwork,
+    RngType, SEED_SIZE,
+};
+
+use super::{core, IoResult, SpdzPrimeFieldShare};
+
+type SpdzShare<F> = SpdzPrimeFieldShare<F>;
+
+/// The correlated randomness of a single party: its share of the MAC key, authenticated multiplication triples and authenticated input masks. The input masks of a party are random shared values, whose value is only known to this party. They are used to provide inputs and to receive outputs privately.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SpdzPreprocessedData<F: PrimeField> {
+    pub(crate) party_id: usize,
+    pub(crate) mac_key_share: F,
+    pub(crate) triples_a: Vec<SpdzShare<F>>,
+    pub(crate) triples_b: Vec<SpdzShare<F>>,
+    pub(crate) triples_c: Vec<SpdzShare<F>>,
+    pub(crate) masks: Vec<Vec<SpdzShare<F>>>,
+    pub(crate) mask_values: Vec<F>,
+}
+
+impl<F: PrimeField> SpdzPreprocessedData<F> {
+    /// Returns the id of the party this data belongs to.
+    pub fn party_id(&self) -> usize {
+        self.party_id
+    }
+
+    /// Returns the number of parties.
+    pub fn num_parties(&self) -> usize {
+        self.masks.len()
+    }
+
+    /// Returns the share of the MAC key.
+    pub fn mac_key_share(&self) -> F {
+        self.mac_key_share
+    }
+
+    /// Returns the number of remaining multiplication triples.
+    pub fn num_triples(&self) -> usize {
+        self.triples_a.len()
+    }
+
+    /// Returns the number of remaining input masks of the party with the given id.
+    pub fn num_masks(&self, owner: usize) -> usize {
+        self.masks[owner].len()
+    }
+}
+
+/// The state of the preprocessing with one other party.
+struct PairwisePreprocessing {
+    ot: OtExtension,
+    // the PRGs seeded with both keys of the base OTs of the COPE, in which we authenticate our values under the MAC key share of the other party
+    cope_sender: Vec<(RngType, RngType)>,
+    // the PRGs seeded with the keys selected by the bits of our MAC key share, with which the other party authenticates its values
+    cope_receiver: Vec<RngType>,
+}
+
+/// Generates the [`SpdzPreprocessedData`] of a party interactively with all other parties (see the [module documentation](self)). The MAC key share is sampled once, such that the values of all batches generated with the same preprocessor can be used together.
+pub struct SpdzPreprocessor<F: PrimeField> {
+    party_id: usize,
+    num_parties: usize,
+    mac_key_share: F,
+    // one per other party, ordered by the id of the other party
+    pairs: Vec<PairwisePreprocessing>,
+    rng: RngType,
+}
+
+impl<F: PrimeField> SpdzPreprocessor<F> {
+    /// Samples the MAC key share and sets up the OT extension and the COPE with every other party. All parties have to call this function at the same time.
+    pub fn new<N: ShamirNetwork>(network: &mut N) -> IoResult<Self> {
+        let party_id = network.get_id();
+        let num_parties = network.get_num_parties();
+        if num_parties < 2 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "SPDZ requires at least two parties",
+            ));
+        }
+        let mut rng = RngType::from_entropy();
+        let mac_key_share = F::rand(&mut rng);
+        let key_bits = to_bits(mac_key_share);
+        let mut pairs = Vec::with_capacity(num_parties - 1);
+        for other in (0..num_parties).filter(|other| *other != party_id) {
+            let mut ot = OtExtension::setup(network, other, &mut rng)?;
+            let (sent, received) = ot.random_ot(network, key_bits.len(), &key_bits)?;
+            pairs.push(PairwisePreprocessing {
+                ot,
+                cope_sender: sent.iter().map(|(k0, k1)| (prg(k0), prg(k1))).collect(),
+                cope_receiver: received.iter().map(prg).collect(),
+            });
+        }
+        Ok(Self {
+            party_id,
+            num_parties,
+            mac_key_share,
+            pairs,
+            rng,
+        })
+    }
+
+    /// Returns the share of the MAC key.
+    pub fn mac_key_share(&self) -> F {
+        self.mac_key_share
+    }
+
+    /// Generates the [`SpdzPreprocessedData`] of the party, containing `num_triples` multiplication triples and `num_masks` input masks for each party. All parties have to call this function with the same arguments at the same time. Fails if the checks of the triples or MACs fail, i.e., if a party cheated, in which case the parties have to abort.
+    pub fn preprocess<N: ShamirNetwork>(
+        &mut self,
+        network: &mut N,
+        num_triples: usize,
+        num_masks: usize,
+    ) -> IoResult<SpdzPreprocessedData<F>> {
+        let mut random = |num: usize| (0..num).map(|_| F::rand(&mut self.rng)).collect_vec();
+        // the triples (a_hat, b, c_hat) are sacrificed to check the triples (a, b, c)
+        let a = random(num_triples);
+        let a_hat = random(num_triples);
+        let b = random(num_triples);
+        let mask_values = random(num_masks);
+        let products = self.multiply(
+            network,
+            &[a.as_slice(), &a_hat].concat(),
+            &[b.as_slice(), &b].concat(),
+        )?;
+        let (c, c_hat) = products.split_at(num_triples);
+
+        // we know the values of our masks, so the other parties hold zero shares of them
+        let mut values = [a.as_slice(), &a_hat, &b, c, c_hat].concat();
+        for owner in 0..self.num_parties {
+            if owner == self.party_id {
+                values.extend(&mask_values);
+            } else {
+                values.resize(values.len() + num_masks, F::zero());
+            }
+        }
+        let mut shares = self.authenticate(network, &values)?.into_iter();
+        let mut take = |num: usize| shares.by_ref().take(num).collect_vec();
+        let triples_a = take(num_triples);
+        let a_hat = take(num_triples);
+        let triples_b = take(num_triples);
+        let triples_c = take(num_triples);
+        let c_hat = take(num_triples);
+        let masks = (0..self.num_parties).map(|_| take(num_masks)).collect();
+
+        self.sacrifice(network, &triples_a, &triples_b, &triples_c, &a_hat, &c_hat)?;
+
+        Ok(SpdzPreprocessedData {
+            party_id: self.party_id,
+            mac_key_share: self.mac_key_share,
+            triples_a,
+            triples_b,
+            triples_c,
+            masks,
+            mask_values,
+        })
+    }
+
+    /// Computes additive shares of the products of the values shared additively by a and b. We have a·b = Σ_i a_i·b_i + Σ_{i≠j} a_i·b_j, where the cross terms of each pair of parties are computed with correlated OTs as in [`twopc::arithmetic::mul_vec`](crate::protocols::twopc::arithmetic::mul_vec).
+    fn multiply<N: ShamirNetwork>(
+        &mut self,
+        network: &mut N,
+        a: &[F],
+        b: &[F],
+    ) -> IoResult<Vec<F>> {
+        debug_assert_eq!(a.len(), b.len());
+        let bits = F::MODULUS_BIT_SIZE as usize;
+        let mut deltas = Vec::with_capacity(a.len() * bits);
+        for a in a {
+            let mut pow = *a;
+            for _ in 0..bits {
+                deltas.push(pow);
+                pow.double_in_place();
+            }
+        }
+        let choices = b.iter().flat_map(|b| to_bits(*b)).collect_vec();
+        let mut products = izip!(a, b).map(|(a, b)| *a * b).collect_vec();
+        for pair in self.pairs.iter_mut() {
+            let (sent, received) = pair.ot.correlated_ot(network, &deltas, &choices, |pad| {
+                F::from_le_bytes_mod_order(pad)
+            })?;
+            for (product, sent, received) in
+                izip!(&mut products, sent.chunks(bits), received.chunks(bits))
+            {
+                *product += received.iter().sum::<F>() - sent.iter().sum::<F>();
+            }
+        }
+        Ok(products)
+    }
+
+    /// Computes additive shares of the MACs of the values shared additively by `values` with the COPE. We have α·x = Σ_i α_i·x_i + Σ_{i≠j} α_j·x_i, where we compute shares of α_j·x_i with party j: For each bit k of α_j, we derive t0_k and t1_k from the keys of the base OTs and send u_k = t0_k - t1_k + x_i, while party j derives t_k = t0_k or t1_k according to the bit and computes w_k = t_k + α_j\[k\]·u_k = t0_k + α_j\[k\]·x_i. Then -Σ_k 2^k·t0_k and Σ_k 2^k·w_k are shares of α_j·x_i.
+    fn cope<N: ShamirNetwork>(&mut self, network: &mut N, values: &[F]) -> IoResult<Vec<F>> {
+        let bits = F::MODULUS_BIT_SIZE as usize;
+        let key_bits = to_bits(self.mac_key_share);
+        let mut macs = values
+            .iter()
+            .map(|value| self.mac_key_share * value)
+            .collect_vec();
+        for pair in self.pairs.iter_mut() {
+            let mut corrections = Vec::with_capacity(values.len() * bits);
+            for (value, mac) in izip!(values, &mut macs) {
+                let mut pow = F::one();
+                for (prg0, prg1) in pair.cope_sender.iter_mut() {
+                    let t0 = F::rand(prg0);
+                    let t1 = F::rand(prg1);
+                    corrections.push(t0 - t1 + value);
+                    *mac -= pow * t0;
+                    pow.double_in_place();
+                }
+            }
+            let other = pair.ot.other();
+            network.send_many(other, &corrections)?;
+            let other_corrections: Vec<F> = network.recv_many(other)?;
+            if other_corrections.len() != corrections.len() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "During SPDZ preprocessing: Invalid number of elements received",
+                ));
+            }
+            for (mac, corrections) in izip!(&mut macs, other_corrections.chunks_exact(bits)) {
+                let mut pow = F::one();
+                for (prg, bit, correction) in izip!(&mut pair.cope_receiver, &key_bits, corrections)
+                {
+                    let mut w = F::rand(prg);
+                    if *bit {
+                        w += correction;
+                    }
+                    *mac += pow * w;
+                    pow.double_in_place();
+                }
+            }
+        }
+        Ok(macs)
+    }
+
+    /// Authenticates the values shared additively by `values` and checks the MACs of a random linear combination of them, which is masked with an additional random value.
+    fn authenticate<N: ShamirNetwork>(
+        &mut self,
+        network: &mut N,
+        values: &[F],
+    ) -> IoResult<Vec<SpdzShare<F>>> {
+        let mut values = values.to_vec();
+        values.push(F::rand(&mut self.rng));
+        let mut macs = self.cope(network, &values)?;
+        let (mask, mask_mac) = (
+            values.pop().expect("pushed above"),
+            macs.pop().expect("authenticated the mask"),
+        );
+
+        let mut coins = core::coin_toss(&mut self.rng, network)?;
+        let (mut combined, mut combined_mac) = (mask, mask_mac);
+        for (value, mac) in izip!(&values, &macs) {
+            let chi = F::rand(&mut coins);
+            combined += chi * value;
+            combined_mac += chi * mac;
+        }
+        let opened = network.broadcast(combined)?.into_iter().sum::<F>();
+        core::check_zero_sum(
+            vec![combined_mac - self.mac_key_share * opened],
+            &mut self.rng,
+            network,
+        )?;
+
+        Ok(izip!(values, macs)
+            .map(|(value, mac)| SpdzShare::new(value, mac, F::zero()))
+            .collect())
+    }
+
+    /// Checks the triples (a, b, c) by sacrificing the triples (a_hat, b, c_hat): For a random r, the parties open rho = r·a - a_hat and check that sigma = r·c - c_hat - rho·b is zero, together with the MACs of rho and sigma.
+    fn sacrifice<N: ShamirNetwork>(
+        &mut self,
+        network: &mut N,
+        a: &[SpdzShare<F>],
+        b: &[SpdzShare<F>],
+        c: &[SpdzShare<F>],
+        a_hat: &[SpdzShare<F>],
+        c_hat: &[SpdzShare<F>],
+    ) -> IoResult<()> {
+        let mut coins = core::coin_toss(&mut self.rng, network)?;
+        let r = (0..a.len()).map(|_| F::rand(&mut coins)).collect_vec();
+        let rho_shares = izip!(&r, a, a_hat)
+            .map(|(r, a, a_hat)| *a * *r - a_hat)
+            .collect_vec();
+        let mut rho = vec![F::zero(); a.len()];
+        for other in network.broadcast(rho_shares.iter().map(|s| s.share).collect_vec())? {
+            if other.len() != rho.len() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "During SPDZ preprocessing: Invalid number of elements received",
+                ));
+            }
+            for (rho, other) in izip!(&mut rho, other) {
+                *rho += other;
+            }
+        }
+        let sigma = izip!(&r, &rho, b, c, c_hat)
+            .map(|(r, rho, b, c, c_hat)| *c * *r - c_hat - *b * *rho)
+            .collect_vec();
+
+        let mut checks = Vec::with_capacity(3 * a.len());
+        checks.extend(sigma.iter().map(|sigma| sigma.share));
+        checks.extend(sigma.iter().map(|sigma| sigma.mac));
+        checks.extend(
+            izip!(&rho_shares, &rho).map(|(share, rho)| share.mac - self.mac_key_share * rho),
+        );
+        core::check_zero_sum(checks, &mut self.rng, network)
+    }
+}
+
+/// Returns the bits of the field element, starting with the least significant bit.
+fn to_bits<F: PrimeField>(value: F) -> Vec<bool> {
+    let value = value.into_bigint();
+    (0..F::MODULUS_BIT_SIZE as usize)
+        .map(|i| value.get_bit(i))
+        .collect()
+}
+
+/// Seeds a PRG with the key of an OT.
+fn prg(key: &Pad) -> RngType {
+    let mut seed = [0u8; SEED_SIZE];
+    seed.copy_from_slice(&key[..SEED_SIZE]);
+    RngType::from_seed(seed)
+}
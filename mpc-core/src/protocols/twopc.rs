@@ -0,0 +1,170 @@
+//! # 2PC
+//!
+//! This module implements a semi-honest protocol for exactly two parties based on additive secret sharing. In contrast to REP3 and Shamir, it does not require an honest majority, so it can be used if only two compute nodes are available.
+//!
+//! Multiplications of arithmetic shares are computed with correlated oblivious transfers (OT) as proposed by [Gilboa](https://link.springer.com/chapter/10.1007/3-540-48405-1_8). The OTs are generated with the [IKNP](https://www.iacr.org/archive/crypto2003/27290145/27290145.pdf) OT extension from 128 base OTs per direction, which are computed once during the setup with the protocol of [Chou and Orlandi](https://eprint.iacr.org/2015/267.pdf). Thus, no correlated randomness has to be provided by a dealer.
+//!
+//! Binary operations, such as comparisons and bit decompositions, use XOR-shares, where the AND gates are computed with OTs as well (see [`binary`] and [`conversion`]).
+
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use rand::{CryptoRng, Rng, SeedableRng};
+
+use crate::{ot::OtExtension, RngType};
+
+use super::shamir::network::ShamirNetwork;
+
+pub mod arithmetic;
+pub mod binary;
+pub mod conversion;
+pub mod gadgets;
+pub mod pointshare;
+
+pub use arithmetic::types::TwoPcPrimeFieldShare;
+pub use pointshare::types::TwoPcPointShare;
+
+type IoResult<T> = std::io::Result<T>;
+
+/// Secret shares a field element additively between the two parties.
+pub fn share_field_element<F: PrimeField, R: Rng + CryptoRng>(
+    val: F,
+    rng: &mut R,
+) -> [TwoPcPrimeFieldShare<F>; 2] {
+    let a = F::rand(rng);
+    [
+        TwoPcPrimeFieldShare::new(a),
+        TwoPcPrimeFieldShare::new(val - a),
+    ]
+}
+
+/// Secret shares a vector of field elements additively between the two parties.
+pub fn share_field_elements<F: PrimeField, R: Rng + CryptoRng>(
+    vals: &[F],
+    rng: &mut R,
+) -> [Vec<TwoPcPrimeFieldShare<F>>; 2] {
+    let mut shares0 = Vec::with_capacity(vals.len());
+    let mut shares1 = Vec::with_capacity(vals.len());
+    for val in vals {
+        let [a, b] = share_field_element(*val, rng);
+        shares0.push(a);
+        shares1.push(b);
+    }
+    [shares0, shares1]
+}
+
+/// Secret shares a curve point additively between the two parties.
+pub fn share_curve_point<C: CurveGroup, R: Rng + CryptoRng>(
+    val: C,
+    rng: &mut R,
+) -> [TwoPcPointShare<C>; 2] {
+    let a = C::rand(rng);
+    [TwoPcPointShare::new(a), TwoPcPointShare::new(val - a)]
+}
+
+/// Reconstructs a field element from the shares of the two parties.
+pub fn combine_field_element<F: PrimeField>(
+    share0: TwoPcPrimeFieldShare<F>,
+    share1: TwoPcPrimeFieldShare<F>,
+) -> F {
+    share0.a + share1.a
+}
+
+/// Reconstructs a vector of field elements from the shares of the two parties.
+pub fn combine_field_elements<F: PrimeField>(
+    shares0: &[TwoPcPrimeFieldShare<F>],
+    shares1: &[TwoPcPrimeFieldShare<F>],
+) -> Vec<F> {
+    debug_assert_eq!(shares0.len(), shares1.len());
+    shares0
+        .iter()
+        .zip(shares1)
+        .map(|(a, b)| combine_field_element(*a, *b))
+        .collect()
+}
+
+/// Reconstructs a curve point from the shares of the two parties.
+pub fn combine_curve_point<C: CurveGroup>(
+    share0: TwoPcPointShare<C>,
+    share1: TwoPcPointShare<C>,
+) -> C {
+    share0.a + share1.a
+}
+
+/// This struct holds all necessary information for the 2PC protocol. It contains a [`ShamirNetwork`] with exactly two parties, which is used for the communication, and the state of the OT extension in both directions.
+pub struct TwoPcProtocol<N: ShamirNetwork> {
+    /// The underlying [`ShamirNetwork`]
+    pub network: N,
+    ot: OtExtension,
+    rng: RngType,
+}
+
+impl<N: ShamirNetwork> TwoPcProtocol<N> {
+    /// Creates a new [`TwoPcProtocol`] from a network with two parties. This computes the base OTs with the other party.
+    pub fn new(mut network: N) -> eyre::Result<Self> {
+        if network.get_num_parties() != 2 {
+            eyre::bail!(
+                "The 2PC protocol requires exactly 2 parties, but the network has {}",
+                network.get_num_parties()
+            );
+        }
+        let mut rng = RngType::from_entropy();
+        let other = 1 - network.get_id();
+        let ot = OtExtension::setup(&mut network, other, &mut rng)?;
+        Ok(Self { network, ot, rng })
+    }
+
+    /// Returns the id of this party, which is either 0 or 1.
+    pub fn id(&self) -> usize {
+        self.network.get_id()
+    }
+
+    /// Returns the id of the other party.
+    pub fn other_id(&self) -> usize {
+        1 - self.network.get_id()
+    }
+
+    /// Generates a random shared field element. This does not require communication, since each party samples its share locally.
+    pub fn rand<F: PrimeField>(&mut self) -> TwoPcPrimeFieldShare<F> {
+        TwoPcPrimeFieldShare::new(F::rand(&mut self.rng))
+    }
+
+    /// Forks the protocol, such that the fork can be used independently. The fork runs its own base OTs.
+    pub fn fork(&mut self) -> eyre::Result<Self> {
+        let network = self.network.fork()?;
+        Self::new(network)
+    }
+
+    /// Computes correlated OTs of field elements in both directions (see [`OtExtension::correlated_ot`]).
+    pub(crate) fn correlated_ot_field<F: PrimeField>(
+        &mut self,
+        deltas: &[F],
+        choices: &[bool],
+    ) -> IoResult<(Vec<F>, Vec<F>)> {
+        self.ot
+            .correlated_ot(&mut self.network, deltas, choices, |pad| {
+                F::from_le_bytes_mod_order(pad)
+            })
+    }
+
+    /// Computes correlated OTs of curve points in both directions (see [`OtExtension::correlated_ot`]).
+    pub(crate) fn correlated_ot_points<C: CurveGroup>(
+        &mut self,
+        deltas: &[C],
+        choices: &[bool],
+    ) -> IoResult<(Vec<C>, Vec<C>)> {
+        self.ot
+            .correlated_ot(&mut self.network, deltas, choices, |pad| {
+                C::generator() * C::ScalarField::from_le_bytes_mod_order(pad)
+            })
+    }
+
+    /// Computes correlated OTs of bits in both directions (see [`OtExtension::correlated_ot_bits`]).
+    pub(crate) fn correlated_ot_bits(
+        &mut self,
+        deltas: &[bool],
+        choices: &[bool],
+    ) -> IoResult<(Vec<bool>, Vec<bool>)> {
+        self.ot
+            .correlated_ot_bits(&mut self.network, deltas, choices)
+    }
+}
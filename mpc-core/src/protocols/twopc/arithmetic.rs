@@ -0,0 +1,537 @@
+//! Arithmetic
+//!
+//! This module contains operations with arithmetic shares
+
+use ark_ff::{BigInteger, PrimeField};
+use itertools::{izip, Itertools};
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+
+use super::{binary, conversion, IoResult, ShamirNetwork, TwoPcProtocol};
+
+mod ops;
+pub(super) mod types;
+
+type FieldShare<F> = types::TwoPcPrimeFieldShare<F>;
+
+// the maximum number of multiplications which are computed with one batch of OTs, bounding the memory consumption of the OT extension
+const MUL_BATCH_SIZE: usize = 1 << 12;
+
+/// Performs addition between two shares.
+pub fn add<F: PrimeField>(a: FieldShare<F>, b: FieldShare<F>) -> FieldShare<F> {
+    a + b
+}
+
+/// Performs addition between two shares where the result is stored in `a`.
+pub fn add_assign<F: PrimeField>(a: &mut FieldShare<F>, b: FieldShare<F>) {
+    *a += b;
+}
+
+/// Performs subtraction between two shares.
+pub fn sub<F: PrimeField>(a: FieldShare<F>, b: FieldShare<F>) -> FieldShare<F> {
+    a - b
+}
+
+/// Performs subtraction between two shares where the result is stored in `a`.
+pub fn sub_assign<F: PrimeField>(a: &mut FieldShare<F>, b: FieldShare<F>) {
+    *a -= b;
+}
+
+/// Performs addition between a share and a public value. Only party 0 adds the value to its share.
+pub fn add_public<F: PrimeField>(shared: FieldShare<F>, public: F, id: usize) -> FieldShare<F> {
+    let mut res = shared;
+    add_assign_public(&mut res, public, id);
+    res
+}
+
+/// Performs addition between a share and a public value where the result is stored in `shared`.
+pub fn add_assign_public<F: PrimeField>(shared: &mut FieldShare<F>, public: F, id: usize) {
+    if id == 0 {
+        shared.a += public;
+    }
+}
+
+/// Performs subtraction between a shared value and a public value, returning shared - public.
+pub fn sub_shared_by_public<F: PrimeField>(
+    shared: FieldShare<F>,
+    public: F,
+    id: usize,
+) -> FieldShare<F> {
+    add_public(shared, -public, id)
+}
+
+/// Performs subtraction between a public value and a shared value, returning public - shared.
+pub fn sub_public_by_shared<F: PrimeField>(
+    public: F,
+    shared: FieldShare<F>,
+    id: usize,
+) -> FieldShare<F> {
+    add_public(-shared, public, id)
+}
+
+/// Performs multiplication between a share and a public value.
+pub fn mul_public<F: PrimeField>(shared: FieldShare<F>, public: F) -> FieldShare<F> {
+    shared * public
+}
+
+/// Performs multiplication between a share and a public value where the result is stored in `shared`.
+pub fn mul_assign_public<F: PrimeField>(shared: &mut FieldShare<F>, public: F) {
+    *shared *= public;
+}
+
+/// Negates a shared value.
+pub fn neg<F: PrimeField>(a: FieldShare<F>) -> FieldShare<F> {
+    -a
+}
+
+/// Transforms a public value into a shared value: \[a\] = a.
+pub fn promote_to_trivial_share<F: PrimeField>(id: usize, public: F) -> FieldShare<F> {
+    if id == 0 {
+        FieldShare::new(public)
+    } else {
+        FieldShare::zero_share()
+    }
+}
+
+/// Transforms a vector of public values into a vector of shared values: \[a_i\] = a_i.
+pub fn promote_to_trivial_shares<F: PrimeField>(id: usize, public: &[F]) -> Vec<FieldShare<F>> {
+    public
+        .iter()
+        .map(|p| promote_to_trivial_share(id, *p))
+        .collect()
+}
+
+/// Performs multiplication of two shared values.
+pub fn mul<F: PrimeField, N: ShamirNetwork>(
+    a: FieldShare<F>,
+    b: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let res = mul_vec(&[a], &[b], twopc)?;
+    Ok(res[0])
+}
+
+/// Performs element-wise multiplication of two vectors of shared values.
+///
+/// We have a·b = a_0·b_0 + a_1·b_1 + a_0·b_1 + a_1·b_0, where the cross terms are computed with correlated OTs following Gilboa: For each bit b_1\[j\] of the share b_1, party 0 inputs the correlation a_0·2^j and gets a random x_j, while party 1 chooses with b_1\[j\] and gets x_j + b_1\[j\]·a_0·2^j. Thus, -Σx_j and Σ(x_j + b_1\[j\]·a_0·2^j) are shares of a_0·b_1. The other cross term is computed with the roles swapped in the same round.
+pub fn mul_vec<F: PrimeField, N: ShamirNetwork>(
+    a: &[FieldShare<F>],
+    b: &[FieldShare<F>],
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    debug_assert_eq!(a.len(), b.len());
+    let bits = F::MODULUS_BIT_SIZE as usize;
+    let mut res = Vec::with_capacity(a.len());
+    for (a, b) in a.chunks(MUL_BATCH_SIZE).zip(b.chunks(MUL_BATCH_SIZE)) {
+        let mut deltas = Vec::with_capacity(a.len() * bits);
+        for a in a {
+            let mut pow = a.a;
+            for _ in 0..bits {
+                deltas.push(pow);
+                pow.double_in_place();
+            }
+        }
+        let choices = b
+            .iter()
+            .flat_map(|b| {
+                let b = b.a.into_bigint();
+                (0..bits).map(move |j| b.get_bit(j))
+            })
+            .collect_vec();
+        let (sent, received) = twopc.correlated_ot_field(&deltas, &choices)?;
+        for (a, b, sent, received) in izip!(a, b, sent.chunks(bits), received.chunks(bits)) {
+            let cross = received.iter().sum::<F>() - sent.iter().sum::<F>();
+            res.push(FieldShare::new(a.a * b.a + cross));
+        }
+    }
+    Ok(res)
+}
+
+/// Performs the opening of a shared value and returns the equivalent public value.
+pub fn open<F: PrimeField, N: ShamirNetwork>(
+    a: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<F> {
+    let rcv = twopc.network.broadcast(a.a)?;
+    Ok(rcv.into_iter().sum())
+}
+
+/// Performs the opening of a vector of shared values and returns the equivalent public values.
+pub fn open_vec<F: PrimeField, N: ShamirNetwork>(
+    a: &[FieldShare<F>],
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<F>> {
+    let shares = a.iter().map(|a| a.a).collect_vec();
+    let rcv = twopc.network.broadcast(shares)?;
+    let mut opened = vec![F::zero(); a.len()];
+    for r in rcv {
+        if r.len() != a.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "During opening in 2PC: Invalid number of elements received",
+            ));
+        }
+        for (o, r) in opened.iter_mut().zip(r) {
+            *o += r;
+        }
+    }
+    Ok(opened)
+}
+
+/// Opens a vector of shared values towards the party with the given id. Only this party learns the values, the other party gets `None`.
+pub fn open_vec_to<F: PrimeField, N: ShamirNetwork>(
+    a: &[FieldShare<F>],
+    party_id: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Option<Vec<F>>> {
+    if party_id > 1 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Cannot open towards party {party_id} in 2PC"),
+        ));
+    }
+    let shares = a.iter().map(|a| a.a).collect_vec();
+    if twopc.id() == party_id {
+        let rcv: Vec<F> = twopc.network.recv_many(twopc.other_id())?;
+        if rcv.len() != a.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "During opening in 2PC: Invalid number of elements received",
+            ));
+        }
+        Ok(Some(izip!(shares, rcv).map(|(a, b)| a + b).collect()))
+    } else {
+        twopc.network.send_many(party_id, &shares)?;
+        Ok(None)
+    }
+}
+
+/// Computes the inverse of a shared value. Multiplies the value with a random mask r and opens the product, such that the inverse is r · (a·r)^-1. Returns an error if the value is zero.
+pub fn inv<F: PrimeField, N: ShamirNetwork>(
+    a: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let res = inv_vec(&[a], twopc)?;
+    Ok(res[0])
+}
+
+/// Computes the inverse of a vector of shared values. Returns an error if any of the values is zero.
+pub fn inv_vec<F: PrimeField, N: ShamirNetwork>(
+    a: &[FieldShare<F>],
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    let r = (0..a.len()).map(|_| twopc.rand()).collect_vec();
+    let masked = mul_vec(a, &r, twopc)?;
+    let masked = open_vec(&masked, twopc)?;
+    izip!(r, masked)
+        .map(|(r, masked)| match masked.inverse() {
+            Some(inv) => Ok(r * inv),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "During execution of inverse in MPC: cannot compute inverse of zero",
+            )),
+        })
+        .collect()
+}
+
+/// Performs a pow operation with a shared base and a public exponent using square-and-multiply. The squaring of the base and the multiplication into the result are batched into a single round per exponent bit.
+pub fn pow_public<F: PrimeField, N: ShamirNetwork>(
+    shared: FieldShare<F>,
+    public: F,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let mut public: BigUint = public.into_bigint().into();
+    let mut base = shared;
+    // None represents the trivial result 1, which we do not have to multiply with
+    let mut res: Option<FieldShare<F>> = None;
+    while !public.is_zero() {
+        let bit = public.bit(0);
+        public >>= 1;
+        let square = !public.is_zero();
+        match (bit, res, square) {
+            (true, Some(r), true) => {
+                let mul = mul_vec(&[r, base], &[base, base], twopc)?;
+                res = Some(mul[0]);
+                base = mul[1];
+            }
+            (true, Some(r), false) => res = Some(mul(r, base, twopc)?),
+            (true, None, _) => {
+                res = Some(base);
+                if square {
+                    base = mul(base, base, twopc)?;
+                }
+            }
+            (false, _, true) => base = mul(base, base, twopc)?,
+            (false, _, false) => {}
+        }
+    }
+    Ok(res.unwrap_or_else(|| promote_to_trivial_share(twopc.id(), F::one())))
+}
+
+/// Multiplies a shared value with 2^public, i.e., computes a left shift by a public amount.
+pub fn pow_2_public<F: PrimeField>(shared: FieldShare<F>, public: F) -> FieldShare<F> {
+    if public.is_zero() {
+        shared
+    } else {
+        let shift: BigUint = public.into();
+        let shift = shift.to_u32().expect("can cast shift operand to u32");
+        if shift >= F::MODULUS_BIT_SIZE {
+            panic!(
+                "Expected left shift to be maximal {}, but was {}",
+                F::MODULUS_BIT_SIZE,
+                shift
+            );
+        } else {
+            mul_public(shared, F::from(2u64).pow(public.into_bigint()))
+        }
+    }
+}
+
+/// Computes arithmetic shares of the F::MODULUS_BIT_SIZE bits of a shared value, starting with the least significant bit.
+fn to_bits<F: PrimeField, N: ShamirNetwork>(
+    a: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    let bits = conversion::a2b(a, twopc)?;
+    let bits = (0..F::MODULUS_BIT_SIZE as usize)
+        .map(|i| (&bits >> i) & BigUint::one())
+        .collect_vec();
+    conversion::bit_inject_many(&bits, twopc)
+}
+
+/// Multiplies all shared values with a tree of multiplications, which requires log2(values.len()) rounds of communication.
+fn product<F: PrimeField, N: ShamirNetwork>(
+    mut values: Vec<FieldShare<F>>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    while values.len() > 1 {
+        let rest = if values.len() % 2 == 1 {
+            values.pop()
+        } else {
+            None
+        };
+        let (lhs, rhs): (Vec<_>, Vec<_>) = values.chunks_exact(2).map(|x| (x[0], x[1])).unzip();
+        values = mul_vec(&lhs, &rhs, twopc)?;
+        values.extend(rest);
+    }
+    Ok(values
+        .pop()
+        .unwrap_or_else(|| promote_to_trivial_share(twopc.id(), F::one())))
+}
+
+/// Performs a pow operation with a public base and a shared exponent. With the bits e_i of the exponent, we have base^e = Π (1 + e_i·(base^(2^i) - 1)), where the factors are local and the product is computed with a tree of multiplications.
+pub fn pow_public_base<F: PrimeField, N: ShamirNetwork>(
+    base: F,
+    exponent: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let id = twopc.id();
+    let bits = to_bits(exponent, twopc)?;
+    let mut pow = base;
+    let mut factors = Vec::with_capacity(bits.len());
+    for bit in bits {
+        factors.push(add_public(mul_public(bit, pow - F::one()), F::one(), id));
+        pow.square_in_place();
+    }
+    product(factors, twopc)
+}
+
+/// Performs a pow operation with a shared base and a shared exponent. As in [`pow_public_base`], we have base^e = Π (1 + e_i·(base^(2^i) - 1)), where the powers base^(2^i) are computed by repeated squaring and the factors require one additional round of multiplications.
+pub fn pow_shared_exponent<F: PrimeField, N: ShamirNetwork>(
+    base: FieldShare<F>,
+    exponent: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let id = twopc.id();
+    let bits = to_bits(exponent, twopc)?;
+    let mut pows = Vec::with_capacity(bits.len());
+    let mut pow = base;
+    for _ in 0..bits.len() {
+        pows.push(sub_shared_by_public(pow, F::one(), id));
+        pow = mul(pow, pow, twopc)?;
+    }
+    let factors = mul_vec(&bits, &pows, twopc)?
+        .into_iter()
+        .map(|x| add_public(x, F::one(), id))
+        .collect();
+    product(factors, twopc)
+}
+
+/// Computes a square root of a shared value. The value is masked with the square of a random r and opened, such that sqrt(a) = sqrt(a·r^2) · r^-1 up to the sign. Returns an error if the value is not a square.
+pub fn sqrt<F: PrimeField, N: ShamirNetwork>(
+    share: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let r_squ = twopc.rand();
+    let r_inv = twopc.rand();
+
+    let rr = mul(r_squ, r_squ, twopc)?;
+
+    // parallel mul of rr with a and r_squ with r_inv
+    let mul = mul_vec(&[rr, r_squ], &[share, r_inv], twopc)?;
+    let opened = open_vec(&mul, twopc)?;
+
+    let Some(y_sq) = opened[0].sqrt() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "During execution of square root in MPC: cannot compute square root",
+        ));
+    };
+    let Some(y_inv) = opened[1].inverse() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "During execution of square root in MPC: cannot compute inverse of zero",
+        ));
+    };
+
+    // r_inv · (r·r_inv)^-1 = r^-1
+    Ok(mul_public(r_inv, y_inv * y_sq))
+}
+
+/// Returns 1 if lhs >= rhs and 0 otherwise, where both values are interpreted as unsigned integers in \[0, p). The result is a shared value.
+pub fn ge<F: PrimeField, N: ShamirNetwork>(
+    lhs: FieldShare<F>,
+    rhs: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let bits = conversion::a2b_many(&[lhs, rhs], twopc)?;
+    let res =
+        binary::unsigned_ge_many(&bits[..1], &bits[1..], F::MODULUS_BIT_SIZE as usize, twopc)?;
+    conversion::bit_inject(&res[0], twopc)
+}
+
+/// Returns 1 if lhs >= rhs and 0 otherwise, where lhs is shared and rhs is public.
+pub fn ge_public<F: PrimeField, N: ShamirNetwork>(
+    lhs: FieldShare<F>,
+    rhs: F,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let lhs = conversion::a2b_many(&[lhs], twopc)?;
+    let rhs = binary::promote_to_trivial_share(twopc.id(), &rhs.into());
+    let res = binary::unsigned_ge_many(&lhs, &[rhs], F::MODULUS_BIT_SIZE as usize, twopc)?;
+    conversion::bit_inject(&res[0], twopc)
+}
+
+/// Returns 1 if lhs <= rhs and 0 otherwise, where lhs is shared and rhs is public.
+pub fn le_public<F: PrimeField, N: ShamirNetwork>(
+    lhs: FieldShare<F>,
+    rhs: F,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let lhs = conversion::a2b_many(&[lhs], twopc)?;
+    let rhs = binary::promote_to_trivial_share(twopc.id(), &rhs.into());
+    let res = binary::unsigned_ge_many(&[rhs], &lhs, F::MODULUS_BIT_SIZE as usize, twopc)?;
+    conversion::bit_inject(&res[0], twopc)
+}
+
+/// Returns 1 if lhs < rhs and 0 otherwise.
+pub fn lt<F: PrimeField, N: ShamirNetwork>(
+    lhs: FieldShare<F>,
+    rhs: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    // a < b is equivalent to !(a >= b)
+    let tmp = ge(lhs, rhs, twopc)?;
+    Ok(sub_public_by_shared(F::one(), tmp, twopc.id()))
+}
+
+/// Returns 1 if lhs < rhs and 0 otherwise, where lhs is shared and rhs is public.
+pub fn lt_public<F: PrimeField, N: ShamirNetwork>(
+    lhs: FieldShare<F>,
+    rhs: F,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let tmp = ge_public(lhs, rhs, twopc)?;
+    Ok(sub_public_by_shared(F::one(), tmp, twopc.id()))
+}
+
+/// Returns 1 if lhs <= rhs and 0 otherwise.
+pub fn le<F: PrimeField, N: ShamirNetwork>(
+    lhs: FieldShare<F>,
+    rhs: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    // a <= b is equivalent to b >= a
+    ge(rhs, lhs, twopc)
+}
+
+/// Returns 1 if lhs > rhs and 0 otherwise.
+pub fn gt<F: PrimeField, N: ShamirNetwork>(
+    lhs: FieldShare<F>,
+    rhs: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    // a > b is equivalent to !(a <= b)
+    let tmp = le(lhs, rhs, twopc)?;
+    Ok(sub_public_by_shared(F::one(), tmp, twopc.id()))
+}
+
+/// Returns 1 if lhs > rhs and 0 otherwise, where lhs is shared and rhs is public.
+pub fn gt_public<F: PrimeField, N: ShamirNetwork>(
+    lhs: FieldShare<F>,
+    rhs: F,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let tmp = le_public(lhs, rhs, twopc)?;
+    Ok(sub_public_by_shared(F::one(), tmp, twopc.id()))
+}
+
+/// Checks if two shared values are equal. The result is a shared value that has value 1 if the two values are equal and 0 otherwise.
+pub fn eq<F: PrimeField, N: ShamirNetwork>(
+    a: FieldShare<F>,
+    b: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let res = is_zero_many(&[a - b], twopc)?;
+    Ok(res[0])
+}
+
+/// Checks if a shared value is equal to a public value. The result is a shared value that has value 1 if the two values are equal and 0 otherwise.
+pub fn eq_public<F: PrimeField, N: ShamirNetwork>(
+    shared: FieldShare<F>,
+    public: F,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let diff = sub_shared_by_public(shared, public, twopc.id());
+    let res = is_zero_many(&[diff], twopc)?;
+    Ok(res[0])
+}
+
+/// Checks if two shared values are not equal. The result is a shared value that has value 1 if the two values are not equal and 0 otherwise.
+pub fn neq<F: PrimeField, N: ShamirNetwork>(
+    a: FieldShare<F>,
+    b: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let eq = eq(a, b, twopc)?;
+    Ok(sub_public_by_shared(F::one(), eq, twopc.id()))
+}
+
+/// Checks if a shared value is not equal to a public value. The result is a shared value that has value 1 if the two values are not equal and 0 otherwise.
+pub fn neq_public<F: PrimeField, N: ShamirNetwork>(
+    shared: FieldShare<F>,
+    public: F,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let eq = eq_public(shared, public, twopc)?;
+    Ok(sub_public_by_shared(F::one(), eq, twopc.id()))
+}
+
+/// Checks for each shared value whether it is zero. The results are shared values that have value 1 if the input is zero and 0 otherwise.
+pub fn is_zero_many<F: PrimeField, N: ShamirNetwork>(
+    a: &[FieldShare<F>],
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    let bits = conversion::a2b_many(a, twopc)?;
+    let is_zero = binary::is_zero_many(&bits, F::MODULUS_BIT_SIZE as usize, twopc)?;
+    conversion::bit_inject_many(&is_zero, twopc)
+}
+
+/// Checks whether a shared value is zero. The result is opened, so it is a public bool.
+pub fn is_zero<F: PrimeField, N: ShamirNetwork>(
+    a: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<bool> {
+    let res = is_zero_many(&[a], twopc)?;
+    Ok(open(res[0], twopc)?.is_one())
+}
@@ -0,0 +1,117 @@
+use ark_ff::PrimeField;
+
+use super::types::TwoPcPrimeFieldShare;
+
+impl<F: PrimeField> std::ops::Add for TwoPcPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self { a: self.a + rhs.a }
+    }
+}
+
+impl<F: PrimeField> std::ops::Add<&TwoPcPrimeFieldShare<F>> for TwoPcPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn add(self, rhs: &Self) -> Self::Output {
+        self + *rhs
+    }
+}
+
+impl<F: PrimeField> std::ops::Add<&TwoPcPrimeFieldShare<F>> for &'_ TwoPcPrimeFieldShare<F> {
+    type Output = TwoPcPrimeFieldShare<F>;
+
+    fn add(self, rhs: &TwoPcPrimeFieldShare<F>) -> Self::Output {
+        *self + *rhs
+    }
+}
+
+impl<F: PrimeField> std::ops::AddAssign for TwoPcPrimeFieldShare<F> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.a += rhs.a;
+    }
+}
+
+impl<F: PrimeField> std::ops::AddAssign<&TwoPcPrimeFieldShare<F>> for TwoPcPrimeFieldShare<F> {
+    fn add_assign(&mut self, rhs: &Self) {
+        *self += *rhs;
+    }
+}
+
+impl<F: PrimeField> std::ops::Sub for TwoPcPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self { a: self.a - rhs.a }
+    }
+}
+
+impl<F: PrimeField> std::ops::Sub<&TwoPcPrimeFieldShare<F>> for TwoPcPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: &Self) -> Self::Output {
+        self - *rhs
+    }
+}
+
+impl<F: PrimeField> std::ops::Sub<&TwoPcPrimeFieldShare<F>> for &'_ TwoPcPrimeFieldShare<F> {
+    type Output = TwoPcPrimeFieldShare<F>;
+
+    fn sub(self, rhs: &TwoPcPrimeFieldShare<F>) -> Self::Output {
+        *self - *rhs
+    }
+}
+
+impl<F: PrimeField> std::ops::SubAssign for TwoPcPrimeFieldShare<F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.a -= rhs.a;
+    }
+}
+
+impl<F: PrimeField> std::ops::SubAssign<&TwoPcPrimeFieldShare<F>> for TwoPcPrimeFieldShare<F> {
+    fn sub_assign(&mut self, rhs: &Self) {
+        *self -= *rhs;
+    }
+}
+
+impl<F: PrimeField> std::ops::Mul<F> for TwoPcPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: F) -> Self::Output {
+        Self { a: self.a * rhs }
+    }
+}
+
+impl<F: PrimeField> std::ops::Mul<&F> for &'_ TwoPcPrimeFieldShare<F> {
+    type Output = TwoPcPrimeFieldShare<F>;
+
+    fn mul(self, rhs: &F) -> Self::Output {
+        *self * *rhs
+    }
+}
+
+impl<F: PrimeField> std::ops::MulAssign<F> for TwoPcPrimeFieldShare<F> {
+    fn mul_assign(&mut self, rhs: F) {
+        self.a *= rhs;
+    }
+}
+
+impl<F: PrimeField> std::ops::Neg for TwoPcPrimeFieldShare<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self { a: -self.a }
+    }
+}
+
+impl<F: PrimeField> ark_ff::Zero for TwoPcPrimeFieldShare<F> {
+    fn zero() -> Self {
+        Self::zero_share()
+    }
+
+    fn is_zero(&self) -> bool {
+        panic!(
+            "is_zero is not a meaningful operation for TwoPcPrimeFieldShare, open the value instead"
+        );
+    }
+}
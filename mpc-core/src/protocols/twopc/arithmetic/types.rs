@@ -0,0 +1,27 @@
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/// This type represents an additively shared value of the 2PC protocol, i.e., the value is the sum of the shares of both parties.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, CanonicalSerialize, CanonicalDeserialize,
+)]
+pub struct TwoPcPrimeFieldShare<F: PrimeField> {
+    pub(crate) a: F,
+}
+
+impl<F: PrimeField> TwoPcPrimeFieldShare<F> {
+    /// Wraps the additive share of the value.
+    pub fn new(a: F) -> Self {
+        Self { a }
+    }
+
+    /// Returns a zero share.
+    pub fn zero_share() -> Self {
+        Self { a: F::zero() }
+    }
+
+    /// Unwraps the additive share of the value.
+    pub fn inner(self) -> F {
+        self.a
+    }
+}
@@ -0,0 +1,270 @@
+//! Binary
+//!
+//! This module contains operations with binary shares. A binary share is an XOR-share of a bit string, which is represented as a [`BigUint`]. Since the bit strings can have leading zeros, the operations which require communication take the bit length as an additional argument.
+
+use itertools::{izip, Itertools};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use super::{IoResult, ShamirNetwork, TwoPcProtocol};
+use crate::ot;
+
+/// Returns the public bit mask with the lowest bitlen bits set.
+pub fn mask(bitlen: usize) -> BigUint {
+    (BigUint::one() << bitlen) - BigUint::one()
+}
+
+/// Transforms a public value into a binary share. Only party 0 holds the value.
+pub fn promote_to_trivial_share(id: usize, public: &BigUint) -> BigUint {
+    if id == 0 {
+        public.to_owned()
+    } else {
+        BigUint::zero()
+    }
+}
+
+/// Computes the XOR of two binary shares.
+pub fn xor(a: &BigUint, b: &BigUint) -> BigUint {
+    a ^ b
+}
+
+/// Computes the XOR of a binary share and a public value.
+pub fn xor_public(shared: &BigUint, public: &BigUint, id: usize) -> BigUint {
+    if id == 0 {
+        shared ^ public
+    } else {
+        shared.to_owned()
+    }
+}
+
+/// Computes the bitwise NOT of the lowest bitlen bits of a binary share.
+pub fn not(shared: &BigUint, bitlen: usize, id: usize) -> BigUint {
+    xor_public(shared, &mask(bitlen), id)
+}
+
+/// Computes the AND of a binary share and a public value.
+pub fn and_with_public(shared: &BigUint, public: &BigUint) -> BigUint {
+    shared & public
+}
+
+/// Computes the OR of a binary share and a public value.
+pub fn or_public(shared: &BigUint, public: &BigUint, id: usize) -> BigUint {
+    // a | b = a ^ b ^ (a & b)
+    let tmp = shared ^ and_with_public(shared, public);
+    xor_public(&tmp, public, id)
+}
+
+/// Computes the AND of two binary shares of bitlen bits.
+pub fn and<N: ShamirNetwork>(
+    a: &BigUint,
+    b: &BigUint,
+    bitlen: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<BigUint> {
+    let res = and_many(&[a.to_owned()], &[b.to_owned()], bitlen, twopc)?;
+    Ok(res.into_iter().next().expect("one result"))
+}
+
+/// Computes the element-wise AND of two vectors of binary shares of bitlen bits.
+///
+/// We have a & b = (a_0 & b_0) ^ (a_1 & b_1) ^ (a_0 & b_1) ^ (a_1 & b_0), where the cross terms are computed with one correlated OT per bit: Party 0 inputs the bits of a_0 as correlations and party 1 chooses with the bits of b_1, and vice versa in the same round.
+pub fn and_many<N: ShamirNetwork>(
+    a: &[BigUint],
+    b: &[BigUint],
+    bitlen: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<BigUint>> {
+    debug_assert_eq!(a.len(), b.len());
+    if bitlen == 0 {
+        return Ok(vec![BigUint::zero(); a.len()]);
+    }
+    let to_bits = |x: &[BigUint]| {
+        x.iter()
+            .flat_map(|x| (0..bitlen as u64).map(move |i| x.bit(i)))
+            .collect_vec()
+    };
+    let (sent, received) = twopc.correlated_ot_bits(&to_bits(a), &to_bits(b))?;
+    Ok(izip!(a, b, sent.chunks(bitlen), received.chunks(bitlen))
+        .map(|(a, b, sent, received)| {
+            let cross = izip!(sent, received).map(|(s, r)| s ^ r).collect_vec();
+            (a & b) ^ BigUint::from_bytes_le(&ot::pack_bits(&cross))
+        })
+        .collect())
+}
+
+/// Computes the OR of two binary shares of bitlen bits.
+pub fn or<N: ShamirNetwork>(
+    a: &BigUint,
+    b: &BigUint,
+    bitlen: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<BigUint> {
+    // a | b = a ^ b ^ (a & b)
+    let and = and(a, b, bitlen, twopc)?;
+    Ok(a ^ b ^ and)
+}
+
+/// Computes the carries of the additions a + b + carry_in of binary shares of bitlen bits with a Kogge-Stone adder, i.e., bit i of the result is the carry out of bit position i. This requires 1 + log2(bitlen) rounds of communication.
+fn carries_many<N: ShamirNetwork>(
+    a: &[BigUint],
+    b: &[BigUint],
+    carry_in: bool,
+    bitlen: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<BigUint>> {
+    let len = a.len();
+    let mask = mask(bitlen);
+    let mut p = izip!(a, b).map(|(a, b)| a ^ b).collect_vec();
+    let mut g = and_many(a, b, bitlen, twopc)?;
+    if carry_in {
+        // the carry into bit 0 makes bit 0 generate a carry iff a_0 | b_0, where a_0 & b_0 and a_0 ^ b_0 are exclusive
+        for (g, p) in izip!(g.iter_mut(), &p) {
+            *g ^= p & BigUint::one();
+        }
+    }
+    let mut shift = 1;
+    while shift < bitlen {
+        // g = g ^ (p & (g << shift)) and p = p & (p << shift) are computed in a single round
+        let lhs = p.iter().chain(p.iter()).cloned().collect_vec();
+        let rhs = g
+            .iter()
+            .chain(p.iter())
+            .map(|x| (x << shift) & &mask)
+            .collect_vec();
+        let mut res = and_many(&lhs, &rhs, bitlen, twopc)?;
+        p = res.split_off(len);
+        for (g, res) in izip!(g.iter_mut(), res) {
+            *g ^= res;
+        }
+        shift <<= 1;
+    }
+    Ok(g)
+}
+
+/// Computes the element-wise sum of two vectors of binary shares of bitlen bits. The results have bitlen + 1 bits.
+pub fn add_many<N: ShamirNetwork>(
+    a: &[BigUint],
+    b: &[BigUint],
+    bitlen: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<BigUint>> {
+    let carries = carries_many(a, b, false, bitlen, twopc)?;
+    Ok(izip!(a, b, carries)
+        .map(|(a, b, c)| a ^ b ^ (c << 1))
+        .collect())
+}
+
+/// Returns a binary share of the bit a >= b for each pair of binary shares of bitlen bits, where the values are interpreted as unsigned integers. The comparison is computed as the carry out of a + !b + 1.
+pub fn unsigned_ge_many<N: ShamirNetwork>(
+    a: &[BigUint],
+    b: &[BigUint],
+    bitlen: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<BigUint>> {
+    let id = twopc.id();
+    let not_b = b.iter().map(|b| not(b, bitlen, id)).collect_vec();
+    let carries = carries_many(a, &not_b, true, bitlen, twopc)?;
+    Ok(carries
+        .into_iter()
+        .map(|c| (c >> (bitlen - 1)) & BigUint::one())
+        .collect())
+}
+
+/// Returns a binary share of the bit a == 0 for each binary share of bitlen bits. The bits of !a are combined with a tree of ANDs, which requires log2(bitlen) rounds of communication.
+pub fn is_zero_many<N: ShamirNetwork>(
+    a: &[BigUint],
+    bitlen: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<BigUint>> {
+    let id = twopc.id();
+    let mut len = bitlen;
+    let mut x = a.iter().map(|a| not(a, len, id)).collect_vec();
+    while len > 1 {
+        if len % 2 == 1 {
+            // pad with a one, which does not change the result of the AND
+            x = x
+                .iter()
+                .map(|x| xor_public(x, &(BigUint::one() << len), id))
+                .collect();
+            len += 1;
+        }
+        len /= 2;
+        let mask = mask(len);
+        let lo = x.iter().map(|x| x & &mask).collect_vec();
+        let hi = x.iter().map(|x| x >> len).collect_vec();
+        x = and_many(&lo, &hi, len, twopc)?;
+    }
+    Ok(x)
+}
+
+/// Returns a binary share of bitlen bits, which has all bits set iff bit i of the binary share is set.
+fn expand_bit(shared: &BigUint, i: usize, bitlen: usize) -> BigUint {
+    if shared.bit(i as u64) {
+        mask(bitlen)
+    } else {
+        BigUint::zero()
+    }
+}
+
+/// Shifts a binary share of bitlen bits to the right by a shared amount, which is given as a binary share of bitlen bits as well. The shift is computed with a barrel shifter, i.e., the value is conditionally shifted by 2^i for each of the lowest log2(bitlen) bits of the amount. If any of the remaining bits of the amount is set, the result is zero.
+pub fn shift_r_by_shared<N: ShamirNetwork>(
+    shared: &BigUint,
+    amount: &BigUint,
+    bitlen: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<BigUint> {
+    let levels = (usize::BITS - bitlen.leading_zeros()) as usize;
+    let mut res = shared.to_owned();
+    for i in 0..levels.min(bitlen) {
+        let select = expand_bit(amount, i, bitlen);
+        let diff = &res ^ (&res >> (1usize << i));
+        res ^= and(&select, &diff, bitlen, twopc)?;
+    }
+    if bitlen > levels {
+        let in_range = is_zero_many(&[amount >> levels], bitlen - levels, twopc)?;
+        let in_range = expand_bit(&in_range[0], 0, bitlen);
+        res = and(&res, &in_range, bitlen, twopc)?;
+    }
+    Ok(res)
+}
+
+/// Computes the element-wise quotients and remainders of the integer divisions a / b of binary shares of bitlen bits, which are interpreted as unsigned integers. The division is computed bit by bit with the restoring division algorithm, where the subtraction of b is computed as the addition of its two's complement and the carry out decides whether the subtraction is applied. Thus, every bit of the quotient requires one addition.
+///
+/// If b is zero, the quotient is zero and the remainder is a.
+pub fn divmod_many<N: ShamirNetwork>(
+    a: &[BigUint],
+    b: &[BigUint],
+    bitlen: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<(Vec<BigUint>, Vec<BigUint>)> {
+    debug_assert_eq!(a.len(), b.len());
+    let id = twopc.id();
+    // the partial remainders have up to bitlen + 1 bits before the subtraction
+    let len = bitlen + 1;
+    let mask = mask(len);
+    let not_b = b.iter().map(|b| not(b, len, id)).collect_vec();
+    let one = vec![promote_to_trivial_share(id, &BigUint::one()); b.len()];
+    let neg_b = add_many(&not_b, &one, len, twopc)?
+        .into_iter()
+        .map(|x| x & &mask)
+        .collect_vec();
+
+    let mut quotient = vec![BigUint::zero(); a.len()];
+    let mut remainder = vec![BigUint::zero(); a.len()];
+    for i in (0..bitlen).rev() {
+        for (r, a) in izip!(remainder.iter_mut(), a) {
+            *r = (&*r << 1) ^ ((a >> i) & BigUint::one());
+        }
+        let sum = add_many(&remainder, &neg_b, len, twopc)?;
+        let select = sum.iter().map(|s| expand_bit(s, len, len)).collect_vec();
+        let diff = izip!(&remainder, &sum)
+            .map(|(r, s)| r ^ (s & &mask))
+            .collect_vec();
+        let update = and_many(&select, &diff, len, twopc)?;
+        for (q, r, s, u) in izip!(quotient.iter_mut(), remainder.iter_mut(), &sum, update) {
+            *q ^= ((s >> len) & BigUint::one()) << i;
+            *r ^= u;
+        }
+    }
+    Ok((quotient, remainder))
+}
@@ -0,0 +1,124 @@
+//! Conversions
+//!
+//! This module contains conversions between arithmetic and binary shares
+
+use ark_ff::PrimeField;
+use itertools::{izip, Itertools};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use super::{binary, IoResult, ShamirNetwork, TwoPcPrimeFieldShare, TwoPcProtocol};
+
+type FieldShare<F> = TwoPcPrimeFieldShare<F>;
+
+/// Transforms arithmetic shares into binary shares of the values in \[0, p) with F::MODULUS_BIT_SIZE bits.
+///
+/// The additive shares are interpreted as binary shares of two summands, where each party knows one of them. The parties add them with a binary adder and subtract the modulus if the sum is not smaller than p.
+pub fn a2b_many<F: PrimeField, N: ShamirNetwork>(
+    x: &[FieldShare<F>],
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<BigUint>> {
+    let bitlen = F::MODULUS_BIT_SIZE as usize;
+    let id = twopc.id();
+    let own = x.iter().map(|x| x.a.into()).collect::<Vec<BigUint>>();
+    let zeros = vec![BigUint::zero(); x.len()];
+    let (lhs, rhs) = if id == 0 { (own, zeros) } else { (zeros, own) };
+    let sum = binary::add_many(&lhs, &rhs, bitlen, twopc)?;
+
+    // bit bitlen + 1 of sum + 2^(bitlen + 1) - p is set iff sum >= p
+    let modulus: BigUint = F::MODULUS.into();
+    let offset = (BigUint::one() << (bitlen + 1)) - modulus;
+    let offset = vec![binary::promote_to_trivial_share(id, &offset); x.len()];
+    let reduced = binary::add_many(&sum, &offset, bitlen + 1, twopc)?;
+
+    let mask = binary::mask(bitlen);
+    let overflow = reduced
+        .iter()
+        .map(|r| {
+            if r.bit(bitlen as u64 + 1) {
+                mask.to_owned()
+            } else {
+                BigUint::zero()
+            }
+        })
+        .collect_vec();
+    let diff = izip!(&sum, &reduced)
+        .map(|(s, r)| (s ^ r) & &mask)
+        .collect_vec();
+    let select = binary::and_many(&overflow, &diff, bitlen, twopc)?;
+    Ok(izip!(sum, select).map(|(s, d)| (s & &mask) ^ d).collect())
+}
+
+/// Transforms an arithmetic share into a binary share of the value in \[0, p) with F::MODULUS_BIT_SIZE bits.
+pub fn a2b<F: PrimeField, N: ShamirNetwork>(
+    x: FieldShare<F>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<BigUint> {
+    let res = a2b_many(&[x], twopc)?;
+    Ok(res.into_iter().next().expect("one result"))
+}
+
+/// Transforms binary shares of bitlen bits into arithmetic shares. The values are reduced modulo p.
+pub fn b2a_many<F: PrimeField, N: ShamirNetwork>(
+    x: &[BigUint],
+    bitlen: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    if bitlen == 0 {
+        return Ok(vec![FieldShare::zero_share(); x.len()]);
+    }
+    let bits = x
+        .iter()
+        .flat_map(|x| (0..bitlen).map(move |i| (x >> i) & BigUint::one()))
+        .collect_vec();
+    let injected = bit_inject_many::<F, N>(&bits, twopc)?;
+    Ok(injected
+        .chunks(bitlen)
+        .map(|bits| {
+            bits.iter()
+                .rev()
+                .fold(FieldShare::zero_share(), |acc, bit| acc + acc + *bit)
+        })
+        .collect())
+}
+
+/// Transforms a binary share of bitlen bits into an arithmetic share. The value is reduced modulo p.
+pub fn b2a<F: PrimeField, N: ShamirNetwork>(
+    x: &BigUint,
+    bitlen: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let res = b2a_many(&[x.to_owned()], bitlen, twopc)?;
+    Ok(res[0])
+}
+
+/// Translates binary shares of single bits into arithmetic shares of the same bits.
+///
+/// We have b_0 ^ b_1 = b_0 + b_1 - 2·b_0·b_1, where the product is computed with one correlated OT per bit: Party 0 inputs b_0 as correlation and gets a random x, while party 1 chooses with b_1 and gets x + b_0·b_1.
+pub fn bit_inject_many<F: PrimeField, N: ShamirNetwork>(
+    bits: &[BigUint],
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    let own = bits.iter().map(|b| b.bit(0)).collect_vec();
+    if twopc.id() == 0 {
+        let deltas = own.iter().map(|b| F::from(*b)).collect_vec();
+        let (sent, _) = twopc.correlated_ot_field(&deltas, &[])?;
+        Ok(izip!(deltas, sent)
+            .map(|(b, x)| FieldShare::new(b + x.double()))
+            .collect())
+    } else {
+        let (_, received) = twopc.correlated_ot_field::<F>(&[], &own)?;
+        Ok(izip!(own, received)
+            .map(|(b, x)| FieldShare::new(F::from(b) - x.double()))
+            .collect())
+    }
+}
+
+/// Translates a binary share of a single bit into an arithmetic share of the same bit.
+pub fn bit_inject<F: PrimeField, N: ShamirNetwork>(
+    bit: &BigUint,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<FieldShare<F>> {
+    let res = bit_inject_many(&[bit.to_owned()], twopc)?;
+    Ok(res[0])
+}
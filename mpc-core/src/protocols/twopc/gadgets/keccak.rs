@@ -0,0 +1,139 @@
+//! Keccak
+//!
+//! This module contains the Keccak-f\[1600\] permutation for the 2PC protocol. As in the [Rep3 variant](crate::protocols::rep3::gadgets::keccak), the 64-bit lanes of all permutation calls are bit-sliced into packed binary shares, such that theta, rho, pi, and iota are local and the AND gates of chi require one round of correlated OTs per round of the permutation for all calls at once.
+
+use crate::protocols::{
+    rep3::gadgets::keccak::{lane_mask, LANE_BITS, RC, ROTATIONS, STATE_BITS},
+    twopc::{binary, conversion, IoResult, ShamirNetwork, TwoPcPrimeFieldShare, TwoPcProtocol},
+};
+use ark_ff::PrimeField;
+use itertools::{izip, Itertools};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+type FieldShare<F> = TwoPcPrimeFieldShare<F>;
+
+/// Packs values of `num_lanes` lanes each into a single value.
+fn pack(values: &[BigUint], num_lanes: usize) -> BigUint {
+    values
+        .iter()
+        .enumerate()
+        .fold(BigUint::zero(), |acc, (i, value)| {
+            acc ^ (value << (i * num_lanes * LANE_BITS))
+        })
+}
+
+/// Splits a value into `amount` values of `num_lanes` lanes each. Inverse of [`pack`].
+fn unpack(value: &BigUint, amount: usize, num_lanes: usize) -> Vec<BigUint> {
+    let mask = binary::mask(num_lanes * LANE_BITS);
+    (0..amount)
+        .map(|i| (value >> (i * num_lanes * LANE_BITS)) & &mask)
+        .collect()
+}
+
+fn rotl(x: &BigUint, r: usize, num_lanes: usize) -> BigUint {
+    if r == 0 {
+        return x.to_owned();
+    }
+    let low = (x & lane_mask(num_lanes, LANE_BITS - r)) << r;
+    let high = (x >> (LANE_BITS - r)) & lane_mask(num_lanes, r);
+    low ^ high
+}
+
+/// Computes the Keccak-f\[1600\] permutation on `num_instances` states at once. Each of the given binary shares packs the respective lane of all states, i.e., lane j of state i is stored in bits 64i..64(i+1) of the j-th value, where lane (x, y) is stored at index x + 5y.
+pub fn keccak_f1600_packed<N: ShamirNetwork>(
+    state: &[BigUint; 25],
+    num_instances: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<[BigUint; 25]> {
+    let n = num_instances;
+    let id = twopc.id();
+    let ones = lane_mask(n, LANE_BITS);
+    let mut state = state.to_owned();
+    for rc in RC {
+        // Theta
+        let c: [BigUint; 5] = std::array::from_fn(|x| {
+            (1..5).fold(state[x].to_owned(), |acc, y| acc ^ &state[x + 5 * y])
+        });
+        for x in 0..5 {
+            let d = &c[(x + 4) % 5] ^ rotl(&c[(x + 1) % 5], 1, n);
+            for y in 0..5 {
+                state[x + 5 * y] ^= &d;
+            }
+        }
+        // Rho and pi
+        let mut b: [BigUint; 25] = Default::default();
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = rotl(&state[x + 5 * y], ROTATIONS[x + 5 * y], n);
+            }
+        }
+        // Chi, the ANDs of all lanes are computed in one communication round
+        let (lhs, rhs): (Vec<_>, Vec<_>) = (0..25)
+            .map(|i| {
+                let (x, y) = (i % 5, i / 5);
+                (
+                    binary::xor_public(&b[(x + 1) % 5 + 5 * y], &ones, id),
+                    b[(x + 2) % 5 + 5 * y].to_owned(),
+                )
+            })
+            .unzip();
+        let ands = binary::and(&pack(&lhs, n), &pack(&rhs, n), 25 * n * LANE_BITS, twopc)?;
+        for (state, b, and) in izip!(state.iter_mut(), b, unpack(&ands, 25, n)) {
+            *state = b ^ and;
+        }
+        // Iota
+        let rc = (0..n).fold(BigUint::zero(), |acc, i| {
+            acc | (BigUint::from(rc) << (i * LANE_BITS))
+        });
+        state[0] = binary::xor_public(&state[0], &rc, id);
+    }
+    Ok(state)
+}
+
+/// Computes the Keccak-f\[1600\] permutation on all states, which are given as arithmetic shares of their [`STATE_BITS`] bits each. The bits of a state are ordered by lane and bit, starting with the least significant bit of each lane, where lane (x, y) is at position x + 5y. The result is given as arithmetic shares of bits in the same order.
+///
+/// The bits are composed to lanes, which are converted to binary shares in one batched conversion. The resulting lanes are decomposed into bits again with one batched bit injection.
+///
+/// The inputs have to be bits. Otherwise, the result is not specified.
+pub fn keccak_f1600_bits_many<F: PrimeField, N: ShamirNetwork>(
+    bits: &[FieldShare<F>],
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    if bits.len() % STATE_BITS != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Keccak-f[1600] requires a multiple of {STATE_BITS} bits"),
+        ));
+    }
+    let n = bits.len() / STATE_BITS;
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    // lane j of all states is at positions j·n..(j+1)·n
+    let lanes = (0..25)
+        .flat_map(|j| {
+            bits.chunks_exact(STATE_BITS).map(move |bits| {
+                bits[j * LANE_BITS..(j + 1) * LANE_BITS]
+                    .iter()
+                    .rev()
+                    .fold(FieldShare::zero_share(), |acc, bit| acc + acc + *bit)
+            })
+        })
+        .collect_vec();
+    let lanes = conversion::a2b_many(&lanes, twopc)?;
+    let mask = binary::mask(LANE_BITS);
+    let state = lanes
+        .chunks_exact(n)
+        .map(|lanes| pack(&lanes.iter().map(|l| l & &mask).collect_vec(), 1))
+        .collect_vec();
+    let state = keccak_f1600_packed(&state.try_into().expect("25 lanes"), n, twopc)?;
+    let bits = (0..n)
+        .flat_map(|i| {
+            state.iter().flat_map(move |lane| {
+                (0..LANE_BITS).map(move |j| (lane >> (i * LANE_BITS + j)) & BigUint::one())
+            })
+        })
+        .collect_vec();
+    conversion::bit_inject_many(&bits, twopc)
+}
@@ -0,0 +1,165 @@
+//! Lookup tables
+//!
+//! This module contains oblivious lookup tables (LUTs) for the 2PC protocol, i.e., reading from and writing to a public or shared table at secret-shared indices. As in the [Rep3 variant](crate::protocols::rep3::gadgets::lut), the accesses use one-hot encodings of the indices.
+
+use crate::protocols::twopc::{
+    arithmetic, conversion, IoResult, ShamirNetwork, TwoPcPrimeFieldShare, TwoPcProtocol,
+};
+use ark_ff::PrimeField;
+use itertools::Itertools;
+use num_bigint::BigUint;
+use num_traits::One;
+
+type FieldShare<F> = TwoPcPrimeFieldShare<F>;
+
+/// Returns the number of bits required to represent all indices of a table of size `len`.
+fn index_bits(len: usize) -> usize {
+    let bits = usize::BITS - (len - 1).leading_zeros();
+    (bits as usize).max(1)
+}
+
+/// Computes the one-hot encodings of the shared indices, i.e., for each index a vector of size 2^`num_bits` which is one at the position of the index and zero everywhere else. The lowest `num_bits` bits of the indices are decomposed with one batched conversion, and the encodings are built from them with one multiplication round per bit.
+fn one_hot_many<F: PrimeField, N: ShamirNetwork>(
+    indices: &[FieldShare<F>],
+    num_bits: usize,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<Vec<FieldShare<F>>>> {
+    let id = twopc.id();
+    let bits = conversion::a2b_many(indices, twopc)?
+        .into_iter()
+        .flat_map(|x| (0..num_bits).map(move |i| (&x >> i) & BigUint::one()))
+        .collect_vec();
+    let bits = conversion::bit_inject_many::<F, N>(&bits, twopc)?;
+    let bits = bits.chunks_exact(num_bits).collect_vec();
+
+    // The first bit splits the trivial one-hot encoding [1] without a multiplication
+    let mut one_hots = bits
+        .iter()
+        .map(|bits| {
+            vec![
+                arithmetic::sub_public_by_shared(F::one(), bits[0], id),
+                bits[0],
+            ]
+        })
+        .collect::<Vec<_>>();
+    for i in 1..num_bits {
+        let (lhs, rhs): (Vec<_>, Vec<_>) = one_hots
+            .iter()
+            .zip(bits.iter())
+            .flat_map(|(one_hot, bits)| one_hot.iter().map(|x| (*x, bits[i])))
+            .unzip();
+        let set = arithmetic::mul_vec(&lhs, &rhs, twopc)?;
+        let mut set = set.into_iter();
+        for one_hot in one_hots.iter_mut() {
+            let len = one_hot.len();
+            for j in 0..len {
+                let set = set.next().expect("one product per entry");
+                one_hot[j] -= set;
+                one_hot.push(set);
+            }
+        }
+    }
+    Ok(one_hots)
+}
+
+/// Reads the public table at the given shared indices. All lookups are batched, such that the number of communication rounds only depends on the size of the table.
+///
+/// The indices have to be smaller than the size of the table. Otherwise, the result is not specified.
+pub fn read_public_lut_many<F: PrimeField, N: ShamirNetwork>(
+    table: &[F],
+    indices: &[FieldShare<F>],
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    if table.is_empty() {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Cannot read from an empty lookup table",
+        ))?;
+    }
+    if indices.is_empty() {
+        return Ok(Vec::new());
+    }
+    let one_hots = one_hot_many(indices, index_bits(table.len()), twopc)?;
+    Ok(one_hots
+        .into_iter()
+        .map(|one_hot| {
+            one_hot
+                .into_iter()
+                .zip(table.iter())
+                .fold(FieldShare::zero_share(), |acc, (bit, value)| {
+                    acc + arithmetic::mul_public(bit, *value)
+                })
+        })
+        .collect())
+}
+
+/// Reads the shared table at the given shared indices. All lookups are batched, such that the number of communication rounds only depends on the size of the table.
+///
+/// The indices have to be smaller than the size of the table. Otherwise, the result is not specified.
+pub fn read_shared_lut_many<F: PrimeField, N: ShamirNetwork>(
+    table: &[FieldShare<F>],
+    indices: &[FieldShare<F>],
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    if table.is_empty() {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Cannot read from an empty lookup table",
+        ))?;
+    }
+    if indices.is_empty() {
+        return Ok(Vec::new());
+    }
+    let one_hots = one_hot_many(indices, index_bits(table.len()), twopc)?;
+    // The products of all lookups are computed in one round
+    let (lhs, rhs): (Vec<_>, Vec<_>) = one_hots
+        .iter()
+        .flat_map(|one_hot| one_hot.iter().copied().zip(table.iter().copied()))
+        .unzip();
+    let products = arithmetic::mul_vec(&lhs, &rhs, twopc)?;
+    Ok(products
+        .chunks_exact(table.len())
+        .map(|products| {
+            products
+                .iter()
+                .fold(FieldShare::zero_share(), |acc, x| acc + *x)
+        })
+        .collect())
+}
+
+/// Writes the shared values to the shared table at the given shared indices and returns the updated table. Each write multiplexes between the old entries and the new value using the one-hot encoding of its index. The writes are applied in order, such that later writes overwrite earlier writes to the same index. Thus, the one-hot encodings of all indices are computed at once, but every write requires an additional communication round.
+///
+/// The indices have to be smaller than the size of the table. Otherwise, the result is not specified.
+pub fn write_lut_many<F: PrimeField, N: ShamirNetwork>(
+    table: &[FieldShare<F>],
+    indices: &[FieldShare<F>],
+    values: &[FieldShare<F>],
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<FieldShare<F>>> {
+    if indices.len() != values.len() {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "The number of indices and values of the lookup table writes do not match",
+        ))?;
+    }
+    if table.is_empty() {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Cannot write to an empty lookup table",
+        ))?;
+    }
+    let mut table = table.to_vec();
+    if indices.is_empty() {
+        return Ok(table);
+    }
+    let one_hots = one_hot_many(indices, index_bits(table.len()), twopc)?;
+    for (mut one_hot, value) in one_hots.into_iter().zip(values.iter()) {
+        one_hot.truncate(table.len());
+        let diff = table.iter().map(|x| *value - *x).collect::<Vec<_>>();
+        let update = arithmetic::mul_vec(&one_hot, &diff, twopc)?;
+        for (x, u) in table.iter_mut().zip(update) {
+            *x += u;
+        }
+    }
+    Ok(table)
+}
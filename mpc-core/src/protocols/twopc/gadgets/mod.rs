@@ -0,0 +1,6 @@
+//! Gadgets
+//!
+//! This module contains some commonly used gadgets for the 2PC protocol.
+
+pub mod keccak;
+pub mod lut;
@@ -0,0 +1,126 @@
+//! Pointshare
+//!
+//! This module contains operations with point shares
+
+mod ops;
+pub(super) mod types;
+
+use ark_ec::CurveGroup;
+use ark_ff::{BigInteger, PrimeField};
+use itertools::{izip, Itertools};
+
+use super::{IoResult, ShamirNetwork, TwoPcPointShare, TwoPcPrimeFieldShare, TwoPcProtocol};
+
+type FieldShare<F> = TwoPcPrimeFieldShare<F>;
+type PointShare<C> = TwoPcPointShare<C>;
+
+/// Performs addition between two shares.
+pub fn add<C: CurveGroup>(a: &PointShare<C>, b: &PointShare<C>) -> PointShare<C> {
+    a + b
+}
+
+/// Performs subtraction between two shares.
+pub fn sub<C: CurveGroup>(a: &PointShare<C>, b: &PointShare<C>) -> PointShare<C> {
+    a - b
+}
+
+/// Performs addition between two shares and stores the result in `a`.
+pub fn add_assign<C: CurveGroup>(a: &mut PointShare<C>, b: &PointShare<C>) {
+    *a += b;
+}
+
+/// Performs subtraction between two shares and stores the result in `a`.
+pub fn sub_assign<C: CurveGroup>(a: &mut PointShare<C>, b: &PointShare<C>) {
+    *a -= b;
+}
+
+/// Performs addition between a share and a public value and stores the result in `a`. Only party 0 adds the value to its share.
+pub fn add_assign_public<C: CurveGroup>(a: &mut PointShare<C>, b: &C, id: usize) {
+    if id == 0 {
+        a.a += b;
+    }
+}
+
+/// Performs subtraction between a share and a public value and stores the result in `a`.
+pub fn sub_assign_public<C: CurveGroup>(a: &mut PointShare<C>, b: &C, id: usize) {
+    add_assign_public(a, &-*b, id)
+}
+
+/// Performs multiplication between a field share and a public curve group value.
+pub fn scalar_mul_public_point<C: CurveGroup>(
+    shared: FieldShare<C::ScalarField>,
+    public: &C,
+) -> PointShare<C> {
+    PointShare::new(*public * shared.a)
+}
+
+/// Performs scalar multiplication between a point share and a public scalar.
+pub fn scalar_mul_public_scalar<C: CurveGroup>(
+    a: &PointShare<C>,
+    b: &C::ScalarField,
+) -> PointShare<C> {
+    a * *b
+}
+
+/// Perfoms MSM between curve points and field shares.
+pub fn msm_public_points<C: CurveGroup>(
+    points: &[C::Affine],
+    scalars: &[FieldShare<C::ScalarField>],
+) -> PointShare<C> {
+    tracing::trace!("> MSM public points for {} elements", points.len());
+    debug_assert_eq!(points.len(), scalars.len());
+    let res = C::msm_unchecked(points, &scalars.iter().map(|s| s.a).collect_vec());
+    tracing::trace!("< MSM public points for {} elements", points.len());
+    PointShare::new(res)
+}
+
+/// Performs scalar multiplication between a point share and a field share. Analogous to [`mul_vec`](super::arithmetic::mul_vec), the cross terms b_0·A_1 and b_1·A_0 are computed with one correlated OT of curve points per bit of the scalar shares.
+pub fn scalar_mul<C: CurveGroup, N: ShamirNetwork>(
+    a: &PointShare<C>,
+    b: FieldShare<C::ScalarField>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<PointShare<C>> {
+    let bits = C::ScalarField::MODULUS_BIT_SIZE as usize;
+    let mut deltas = Vec::with_capacity(bits);
+    let mut pow = a.a;
+    for _ in 0..bits {
+        deltas.push(pow);
+        pow.double_in_place();
+    }
+    let b_bigint = b.a.into_bigint();
+    let choices = (0..bits).map(|j| b_bigint.get_bit(j)).collect_vec();
+    let (sent, received) = twopc.correlated_ot_points(&deltas, &choices)?;
+    let cross = received.into_iter().sum::<C>() - sent.into_iter().sum::<C>();
+    Ok(PointShare::new(a.a * b.a + cross))
+}
+
+/// Performs opening of a point share.
+pub fn open_point<C: CurveGroup, N: ShamirNetwork>(
+    a: &PointShare<C>,
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<C> {
+    let rcv = twopc.network.broadcast(a.a)?;
+    Ok(rcv.into_iter().sum())
+}
+
+/// Performs opening of a vector of point shares.
+pub fn open_point_many<C: CurveGroup, N: ShamirNetwork>(
+    a: &[PointShare<C>],
+    twopc: &mut TwoPcProtocol<N>,
+) -> IoResult<Vec<C>> {
+    let shares = a.iter().map(|a| a.a).collect_vec();
+    let rcv = twopc.network.broadcast(shares)?;
+    let mut opened = vec![C::zero(); a.len()];
+    for r in rcv {
+        if r.len() != a.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "During opening in 2PC: Invalid number of elements received",
+            ));
+        }
+        for (o, r) in izip!(opened.iter_mut(), r) {
+            *o += r;
+        }
+    }
+    Ok(opened)
+}
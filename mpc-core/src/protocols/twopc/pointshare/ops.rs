@@ -0,0 +1,55 @@
+use ark_ec::CurveGroup;
+
+use super::types::TwoPcPointShare;
+
+impl<C: CurveGroup> std::ops::Add for TwoPcPointShare<C> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self { a: self.a + rhs.a }
+    }
+}
+
+impl<C: CurveGroup> std::ops::Add<&TwoPcPointShare<C>> for &'_ TwoPcPointShare<C> {
+    type Output = TwoPcPointShare<C>;
+
+    fn add(self, rhs: &TwoPcPointShare<C>) -> Self::Output {
+        TwoPcPointShare { a: self.a + rhs.a }
+    }
+}
+
+impl<C: CurveGroup> std::ops::AddAssign<&TwoPcPointShare<C>> for TwoPcPointShare<C> {
+    fn add_assign(&mut self, rhs: &Self) {
+        self.a += rhs.a;
+    }
+}
+
+impl<C: CurveGroup> std::ops::Sub for TwoPcPointShare<C> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self { a: self.a - rhs.a }
+    }
+}
+
+impl<C: CurveGroup> std::ops::Sub<&TwoPcPointShare<C>> for &'_ TwoPcPointShare<C> {
+    type Output = TwoPcPointShare<C>;
+
+    fn sub(self, rhs: &TwoPcPointShare<C>) -> Self::Output {
+        TwoPcPointShare { a: self.a - rhs.a }
+    }
+}
+
+impl<C: CurveGroup> std::ops::SubAssign<&TwoPcPointShare<C>> for TwoPcPointShare<C> {
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.a -= rhs.a;
+    }
+}
+
+impl<C: CurveGroup> std::ops::Mul<C::ScalarField> for &'_ TwoPcPointShare<C> {
+    type Output = TwoPcPointShare<C>;
+
+    fn mul(self, scalar: C::ScalarField) -> Self::Output {
+        TwoPcPointShare { a: self.a * scalar }
+    }
+}
@@ -0,0 +1,31 @@
+use ark_ec::CurveGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/// This type represents an additively shared EC point of the 2PC protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct TwoPcPointShare<C: CurveGroup> {
+    pub(crate) a: C,
+}
+
+impl<C: CurveGroup> Default for TwoPcPointShare<C> {
+    fn default() -> Self {
+        Self::zero_share()
+    }
+}
+
+impl<C: CurveGroup> TwoPcPointShare<C> {
+    /// Wraps the additive share of the point.
+    pub fn new(a: C) -> Self {
+        Self { a }
+    }
+
+    /// Returns a zero share.
+    pub fn zero_share() -> Self {
+        Self { a: C::zero() }
+    }
+
+    /// Unwraps the additive share of the point.
+    pub fn inner(self) -> C {
+        self.a
+    }
+}
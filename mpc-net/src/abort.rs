@@ -0,0 +1,103 @@
+//! Aborts, which a party sends to all other parties when it cannot continue the protocol.
+//!
+//! Without an explicit abort, the other parties of a failed party would wait for its messages until they time out, or fail with an opaque IO error once the connections are closed. Instead, the failing party calls [`MpcNetworkHandler::abort`](crate::MpcNetworkHandler::abort), which sends the [`AbortReason`] over a dedicated control channel. All receives of the other parties then fail with an [`io::ErrorKind::ConnectionAborted`] error carrying the [`Abort`], which can be retrieved with [`Abort::from_io_error`].
+
+use std::{collections::BTreeMap, io};
+
+use futures::SinkExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::{channel::WriteChannel, codecs::BincodeCodec, NetworkSendStream};
+
+/// The reason of a party to abort the protocol.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AbortReason {
+    /// A receive or phase timed out.
+    Timeout,
+    /// A consistency check failed, e.g., the check of a garbled circuit or a broadcast.
+    Inconsistency(String),
+    /// The party panicked.
+    Panic,
+    /// Any other error.
+    Error(String),
+}
+
+impl std::fmt::Display for AbortReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AbortReason::Timeout => write!(f, "timeout"),
+            AbortReason::Inconsistency(msg) => write!(f, "inconsistency: {msg}"),
+            AbortReason::Panic => write!(f, "panic"),
+            AbortReason::Error(msg) => write!(f, "error: {msg}"),
+        }
+    }
+}
+
+/// An abort of the protocol by a party.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Abort {
+    /// The id of the party which aborted.
+    pub party: usize,
+    /// The reason of the party.
+    pub reason: AbortReason,
+}
+
+impl Abort {
+    /// Returns the abort which caused the IO error, if any.
+    pub fn from_io_error(err: &io::Error) -> Option<&Abort> {
+        err.get_ref().and_then(|err| err.downcast_ref::<Abort>())
+    }
+}
+
+impl std::fmt::Display for Abort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "party {} aborted the protocol ({})",
+            self.party, self.reason
+        )
+    }
+}
+
+impl std::error::Error for Abort {}
+
+impl From<Abort> for io::Error {
+    fn from(abort: Abort) -> Self {
+        io::Error::new(io::ErrorKind::ConnectionAborted, abort)
+    }
+}
+
+/// The control channels to the other parties, over which an abort is sent.
+#[derive(Default)]
+pub(crate) struct AbortChannels {
+    channels: Mutex<BTreeMap<usize, WriteChannel<NetworkSendStream, BincodeCodec<AbortReason>>>>,
+}
+
+impl std::fmt::Debug for AbortChannels {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AbortChannels").finish_non_exhaustive()
+    }
+}
+
+impl AbortChannels {
+    pub(crate) fn new(
+        channels: BTreeMap<usize, WriteChannel<NetworkSendStream, BincodeCodec<AbortReason>>>,
+    ) -> Self {
+        Self {
+            channels: Mutex::new(channels),
+        }
+    }
+
+    /// Sends the reason to all other parties. Tries all parties, even if sending to one of them fails.
+    pub(crate) async fn send(&self, reason: AbortReason) -> io::Result<()> {
+        let mut result = Ok(());
+        for (id, write) in self.channels.lock().await.iter_mut() {
+            if let Err(err) = write.send(reason.clone()).await {
+                tracing::debug!("could not send abort to party {id}: {err}");
+                result = Err(err);
+            }
+        }
+        result
+    }
+}
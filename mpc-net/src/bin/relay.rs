@@ -0,0 +1,28 @@
+use color_eyre::{eyre::Context, Result};
+use mpc_net::relay::RelayServer;
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+
+use clap::Parser;
+
+/// Relay server for MPC-NET, which forwards the traffic between parties that cannot accept inbound connections
+#[derive(Debug, PartialEq, Parser)]
+struct CliArgs {
+    /// The address the relay listens on
+    #[clap(short, long, default_value = "0.0.0.0:10000")]
+    bind_addr: SocketAddr,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = CliArgs::parse();
+
+    let listener = TcpListener::bind(args.bind_addr)
+        .await
+        .context("binding relay listener")?;
+    RelayServer::new()
+        .run(listener)
+        .await
+        .context("running relay")?;
+    Ok(())
+}
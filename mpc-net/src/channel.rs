@@ -1,4 +1,5 @@
 //! A channel abstraction for sending and receiving messages.
+use bytes::{Buf, Bytes, BytesMut};
 use futures::{Sink, SinkExt, Stream, StreamExt};
 use std::{io, marker::Unpin, pin::Pin};
 use tokio::{
@@ -22,6 +23,9 @@ pub struct Channel<R, W, C> {
 /// A channel that uses a [`LengthDelimitedCodec`] to send and receive messages.
 pub type BytesChannel<R, W> = Channel<R, W, LengthDelimitedCodec>;
 
+/// The minimum length of the chunks a message is split into by [`ChannelHandle::manage_parallel`]. Shorter messages are sent over a single stream, as splitting them only adds overhead.
+pub const MIN_CHUNK_LEN: usize = 64 * 1024;
+
 impl<R, W, C> Channel<R, W, C> {
     /// Create a new [`Channel`], backed by a read and write half. Read and write buffers
     /// are automatically handled by [`LengthDelimitedCodec`].
@@ -133,20 +137,20 @@ where
     }
 }
 
-struct WriteJob<MSend> {
-    data: MSend,
-    ret: oneshot::Sender<Result<(), io::Error>>,
+pub(crate) struct WriteJob<MSend> {
+    pub(crate) data: MSend,
+    pub(crate) ret: oneshot::Sender<Result<(), io::Error>>,
 }
 
-struct ReadJob<MRecv> {
-    ret: oneshot::Sender<Result<MRecv, io::Error>>,
+pub(crate) struct ReadJob<MRecv> {
+    pub(crate) ret: oneshot::Sender<Result<MRecv, io::Error>>,
 }
 
 /// A handle to a channel that allows sending and receiving messages.
 #[derive(Debug)]
 pub struct ChannelHandle<MSend, MRecv> {
-    write_job_queue: mpsc::Sender<WriteJob<MSend>>,
-    read_job_queue: mpsc::Sender<ReadJob<MRecv>>,
+    pub(crate) write_job_queue: mpsc::Sender<WriteJob<MSend>>,
+    pub(crate) read_job_queue: mpsc::Sender<ReadJob<MRecv>>,
 }
 
 impl<MSend, MRecv> ChannelHandle<MSend, MRecv>
@@ -281,3 +285,132 @@ where
         recv
     }
 }
+
+impl ChannelHandle<Bytes, BytesMut> {
+    /// Create a new [`ChannelHandle`] from several [`BytesChannel`]s to the same party, which are used in parallel to saturate the bandwidth of fast links. Every message is split into chunks of at least [`MIN_CHUNK_LEN`] bytes, which are sent over the channels concurrently and joined again on the receiving side. The other party has to manage the same number of channels in the same order.
+    pub fn manage_parallel<R, W>(mut chans: Vec<BytesChannel<R, W>>) -> Self
+    where
+        R: AsyncReadExt + Unpin + Send + 'static,
+        W: AsyncWriteExt + Unpin + Send + 'static,
+    {
+        assert!(!chans.is_empty(), "at least one channel is required");
+        if chans.len() == 1 {
+            // nothing to split, so the messages are sent as they are
+            return Self::manage(chans.pop().expect("checked above"));
+        }
+        let (write_send, mut write_recv) = mpsc::channel::<WriteJob<Bytes>>(1024);
+        let (read_send, mut read_recv) = mpsc::channel::<ReadJob<BytesMut>>(1024);
+
+        let (mut writes, mut reads): (Vec<_>, Vec<_>) =
+            chans.into_iter().map(Channel::split).unzip();
+
+        tokio::spawn(async move {
+            while let Some(frame) = gather(&mut reads).await {
+                let failed = frame.is_err();
+                let job = read_recv.recv().await;
+                match job {
+                    Some(job) => {
+                        if job.ret.send(frame).is_err() {
+                            tracing::warn!("Warning: Read Job finished but receiver is gone!");
+                        }
+                    }
+                    None => {
+                        if !failed {
+                            tracing::warn!("Warning: received Ok frame but receiver is gone!");
+                        }
+                        break;
+                    }
+                }
+                if failed {
+                    // the channels are out of sync after an error
+                    break;
+                }
+            }
+        });
+        tokio::spawn(async move {
+            while let Some(write_job) = write_recv.recv().await {
+                match scatter(&mut writes, write_job.data).await {
+                    Ok(_) => {
+                        if write_job.ret.send(Ok(())).is_err() {
+                            tracing::trace!("Debug: Write Job finished but receiver is gone!");
+                        }
+                    }
+                    Err(err) => {
+                        tracing::error!("Write job failed: {err}");
+                    }
+                }
+            }
+        });
+
+        ChannelHandle {
+            write_job_queue: write_send,
+            read_job_queue: read_send,
+        }
+    }
+}
+
+/// Splits the message into chunks and sends them over the channels concurrently. The first channel additionally carries the number of chunks.
+async fn scatter<W: AsyncWriteExt + Unpin>(
+    writes: &mut [WriteChannel<W, LengthDelimitedCodec>],
+    data: Bytes,
+) -> Result<(), io::Error> {
+    let num_chunks = data.len().div_ceil(MIN_CHUNK_LEN).clamp(1, writes.len());
+    let chunk_len = data.len().div_ceil(num_chunks);
+    writes[0]
+        .feed(Bytes::copy_from_slice(&(num_chunks as u32).to_be_bytes()))
+        .await?;
+    futures::future::try_join_all(writes.iter_mut().take(num_chunks).enumerate().map(
+        |(i, write)| {
+            let start = (i * chunk_len).min(data.len());
+            let end = ((i + 1) * chunk_len).min(data.len());
+            write.send(data.slice(start..end))
+        },
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Receives the chunks of the next message from the channels and joins them. Returns `None` if the first channel was closed between two messages.
+async fn gather<R: AsyncReadExt + Unpin>(
+    reads: &mut [ReadChannel<R, LengthDelimitedCodec>],
+) -> Option<Result<BytesMut, io::Error>> {
+    let mut header = match reads[0].next().await? {
+        Ok(header) => header,
+        Err(err) => return Some(Err(err)),
+    };
+    if header.len() != 4 {
+        return Some(Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid header of a split message",
+        )));
+    }
+    let num_chunks = header.get_u32() as usize;
+    if num_chunks == 0 || num_chunks > reads.len() {
+        return Some(Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "message was split into {num_chunks} chunks, but there are {} channels",
+                reads.len()
+            ),
+        )));
+    }
+    let chunks =
+        futures::future::try_join_all(reads.iter_mut().take(num_chunks).map(|read| async move {
+            read.next().await.unwrap_or_else(|| {
+                Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "channel closed before the end of the message",
+                ))
+            })
+        }))
+        .await;
+    let mut chunks = match chunks {
+        Ok(chunks) => chunks.into_iter(),
+        Err(err) => return Some(Err(err)),
+    };
+    let mut data = chunks.next().expect("at least one chunk");
+    for chunk in chunks {
+        data.extend_from_slice(&chunk);
+    }
+    Some(Ok(data))
+}
@@ -0,0 +1,67 @@
+//! Transparent compression of the streams between the parties.
+//!
+//! Serialized field elements are highly compressible, so compressing the streams saves bandwidth on slow networks. The streams are compressed as a whole instead of per message, which gives a better ratio for many small messages. Since the channels flush after every message, no message is held back by the compressor.
+
+use async_compression::tokio::{
+    bufread::{Lz4Decoder, ZstdDecoder},
+    write::{Lz4Encoder, ZstdEncoder},
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::{config::Compression, NetworkRecvStream, NetworkSendStream};
+
+impl Compression {
+    fn to_byte(self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Zstd => 1,
+            Compression::Lz4 => 2,
+        }
+    }
+
+    fn from_byte(byte: u8) -> std::io::Result<Self> {
+        match byte {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Zstd),
+            2 => Ok(Compression::Lz4),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unknown compression {byte}"),
+            )),
+        }
+    }
+}
+
+/// Negotiates the compression with the other party of the stream and wraps the stream halves accordingly. Both parties announce the compression they request, and the stream is only compressed if they agree.
+pub(crate) async fn negotiate<R, W>(
+    mut recv_stream: R,
+    mut send_stream: W,
+    ours: Compression,
+) -> std::io::Result<(NetworkRecvStream, NetworkSendStream)>
+where
+    R: AsyncRead + Send + Unpin + 'static,
+    W: AsyncWrite + Send + Unpin + 'static,
+{
+    send_stream.write_u8(ours.to_byte()).await?;
+    send_stream.flush().await?;
+    let theirs = Compression::from_byte(recv_stream.read_u8().await?)?;
+    let compression = if ours == theirs {
+        ours
+    } else {
+        tracing::warn!(
+            "requested compression {ours:?}, but the other party requested {theirs:?}, not compressing"
+        );
+        Compression::None
+    };
+    Ok(match compression {
+        Compression::None => (Box::new(recv_stream), Box::new(send_stream)),
+        Compression::Zstd => (
+            Box::new(ZstdDecoder::new(BufReader::new(recv_stream))),
+            Box::new(ZstdEncoder::new(send_stream)),
+        ),
+        Compression::Lz4 => (
+            Box::new(Lz4Decoder::new(BufReader::new(recv_stream))),
+            Box::new(Lz4Encoder::new(send_stream)),
+        ),
+    })
+}
@@ -8,6 +8,7 @@ use std::{
     num::ParseIntError,
     path::PathBuf,
     str::FromStr,
+    time::Duration,
 };
 
 /// A network address wrapper.
@@ -89,6 +90,9 @@ pub struct NetworkPartyConfig {
     pub dns_name: Address,
     /// The path to the public certificate of the party.
     pub cert_path: PathBuf,
+    /// If set, we connect to the party through this proxy.
+    #[serde(default)]
+    pub proxy: Option<ProxyConfig>,
 }
 
 /// A party in the network.
@@ -100,6 +104,8 @@ pub struct NetworkParty {
     pub dns_name: Address,
     /// The public certificate of the party.
     pub cert: CertificateDer<'static>,
+    /// If set, we connect to the party through this proxy.
+    pub proxy: Option<ProxyConfig>,
 }
 
 impl TryFrom<NetworkPartyConfig> for NetworkParty {
@@ -110,10 +116,138 @@ impl TryFrom<NetworkPartyConfig> for NetworkParty {
             id: value.id,
             dns_name: value.dns_name,
             cert,
+            proxy: value.proxy,
         })
     }
 }
 
+/// The configuration of a relay, which forwards the traffic between the parties (see [`relay`](crate::relay)).
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub struct RelayConfig {
+    /// The address of the relay server.
+    pub address: Address,
+    /// The session id, which has to be the same for all parties and unique among the sessions using the relay.
+    pub session: String,
+}
+
+/// The protocol spoken by a [`ProxyConfig`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyProtocol {
+    /// A SOCKS5 proxy.
+    Socks5,
+    /// An HTTP proxy supporting the CONNECT method.
+    Http,
+}
+
+/// A proxy through which the TCP connections to a party are established, e.g., because all egress traffic has to pass the proxy of an enterprise (see [`proxy`](crate::proxy)).
+///
+/// Only connections via the [`relay`](crate::relay) run over TCP, so a proxy requires a [`RelayConfig`].
+#[derive(Clone, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub struct ProxyConfig {
+    /// The protocol of the proxy.
+    pub protocol: ProxyProtocol,
+    /// The address of the proxy.
+    pub address: Address,
+    /// The username to authenticate to the proxy, if required.
+    #[serde(default)]
+    pub username: Option<String>,
+    /// The password to authenticate to the proxy, if required.
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl std::fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyConfig")
+            .field("protocol", &self.protocol)
+            .field("address", &self.address)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+/// The compression applied to the streams between the parties.
+#[derive(
+    Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// No compression.
+    #[default]
+    None,
+    /// Zstandard compression, which achieves a better ratio.
+    Zstd,
+    /// LZ4 compression, which is faster.
+    Lz4,
+}
+
+/// A phase of an MPC protocol, which can be given its own timeout (see [`TimeoutConfig`]).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Phase {
+    /// The extension of the witness.
+    WitnessExtension,
+    /// The generation of the proof.
+    ProofAssembly,
+}
+
+impl Phase {
+    /// Returns the name of the phase, e.g., to attribute the [`NetworkStats`](crate::stats::NetworkStats) to it.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::WitnessExtension => "witness_extension",
+            Phase::ProofAssembly => "proof_assembly",
+        }
+    }
+}
+
+/// The timeouts of the network, given in seconds. Phases and receives without a timeout can take arbitrarily long.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    /// The time for establishing the connections to the other parties.
+    pub handshake: u64,
+    /// The time a party waits for a single message.
+    pub round_trip: Option<u64>,
+    /// The time for the witness extension.
+    pub witness_extension: Option<u64>,
+    /// The time for the proof generation.
+    pub proof_assembly: Option<u64>,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            handshake: 60,
+            round_trip: None,
+            witness_extension: None,
+            proof_assembly: None,
+        }
+    }
+}
+
+impl TimeoutConfig {
+    /// Returns the timeout for establishing the connections.
+    pub fn handshake(&self) -> Duration {
+        Duration::from_secs(self.handshake)
+    }
+
+    /// Returns the timeout for receiving a single message.
+    pub fn round_trip(&self) -> Option<Duration> {
+        self.round_trip.map(Duration::from_secs)
+    }
+
+    /// Returns the timeout for the given phase.
+    pub fn phase(&self, phase: Phase) -> Option<Duration> {
+        match phase {
+            Phase::WitnessExtension => self.witness_extension,
+            Phase::ProofAssembly => self.proof_assembly,
+        }
+        .map(Duration::from_secs)
+    }
+}
+
 /// The network configuration file.
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub struct NetworkConfigFile {
@@ -125,6 +259,31 @@ pub struct NetworkConfigFile {
     pub bind_addr: SocketAddr,
     /// The path to our private key file.
     pub key_path: PathBuf,
+    /// If set, the parties connect to each other via the relay instead of directly, such that no party needs to accept inbound connections.
+    #[serde(default)]
+    pub relay: Option<RelayConfig>,
+    /// The compression we request for the streams. A stream is only compressed if both parties request the same compression.
+    #[serde(default)]
+    pub compression: Compression,
+    /// The timeouts of the network.
+    #[serde(default)]
+    pub timeouts: TimeoutConfig,
+    /// Whether to record a transcript of the communication, which can be signed with our private key (see [`transcript`](crate::transcript)).
+    #[serde(default)]
+    pub transcript: bool,
+    /// The session of the parties, which has to be the same for all parties. Channels are only established between parties in the same session (see [`MpcNetworkHandler::set_session`](crate::MpcNetworkHandler::set_session)).
+    #[serde(default)]
+    pub session: String,
+    /// The number of parallel streams to each other party, which has to be the same for all parties. Large messages are split over the streams, which helps to saturate fast links (see [`MpcNetworkHandler::get_managed_byte_channels`](crate::MpcNetworkHandler::get_managed_byte_channels)).
+    #[serde(default = "default_streams")]
+    pub streams: usize,
+    /// Whether all channels to a party are multiplexed over a single stream, which has to be the same for all parties. The messages of concurrent channels, e.g., of forked networks, are interleaved in chunks, such that a large message on one channel does not block the messages on the others. Opening a multiplexed channel needs no round trip (see [`MpcNetworkHandler::get_managed_byte_channels`](crate::MpcNetworkHandler::get_managed_byte_channels)). Since the channels are identified by the order in which they are opened, all parties have to request the channels, e.g., fork their networks, in the same order. Forks created concurrently by several tasks have to be sequenced, otherwise the messages end up on the wrong channels.
+    #[serde(default)]
+    pub multiplex: bool,
+}
+
+fn default_streams() -> usize {
+    1
 }
 
 /// The network configuration.
@@ -138,6 +297,20 @@ pub struct NetworkConfig {
     pub bind_addr: SocketAddr,
     /// The private key.
     pub key: PrivateKeyDer<'static>,
+    /// If set, the parties connect to each other via the relay instead of directly.
+    pub relay: Option<RelayConfig>,
+    /// The compression we request for the streams.
+    pub compression: Compression,
+    /// The timeouts of the network.
+    pub timeouts: TimeoutConfig,
+    /// Whether to record a transcript of the communication.
+    pub transcript: bool,
+    /// The session of the parties.
+    pub session: String,
+    /// The number of parallel streams to each other party.
+    pub streams: usize,
+    /// Whether all channels to a party are multiplexed over a single stream. All parties have to open the channels in the same order (see [`NetworkConfigFile::multiplex`]).
+    pub multiplex: bool,
 }
 
 impl TryFrom<NetworkConfigFile> for NetworkConfig {
@@ -155,6 +328,13 @@ impl TryFrom<NetworkConfigFile> for NetworkConfig {
             my_id: value.my_id,
             bind_addr: value.bind_addr,
             key,
+            relay: value.relay,
+            compression: value.compression,
+            timeouts: value.timeouts,
+            transcript: value.transcript,
+            session: value.session,
+            streams: value.streams,
+            multiplex: value.multiplex,
         })
     }
 }
@@ -166,6 +346,13 @@ impl Clone for NetworkConfig {
             my_id: self.my_id,
             bind_addr: self.bind_addr,
             key: self.key.clone_key(),
+            relay: self.relay.clone(),
+            compression: self.compression,
+            timeouts: self.timeouts,
+            transcript: self.transcript,
+            session: self.session.clone(),
+            streams: self.streams,
+            multiplex: self.multiplex,
         }
     }
 }
@@ -192,6 +379,25 @@ impl NetworkConfig {
         if ids.len() != self.parties.len() {
             return Err(eyre::eyre!("duplicate party ids found"));
         }
+        // 3. check that there is at least one stream to each party
+        if self.streams == 0 {
+            return Err(eyre::eyre!("the number of streams must be at least 1"));
+        }
+        // 4. check that multiplexed channels use a single stream
+        if self.multiplex && self.streams > 1 {
+            return Err(eyre::eyre!(
+                "multiplexed channels cannot be split over several streams"
+            ));
+        }
+        // 5. check that proxies are only used for connections via the relay, since direct connections run over UDP
+        if self.relay.is_none() {
+            if let Some(party) = self.parties.iter().find(|p| p.proxy.is_some()) {
+                return Err(eyre::eyre!(
+                    "party {} is configured with a proxy, which requires a relay",
+                    party.id
+                ));
+            }
+        }
         Ok(())
     }
 }
@@ -4,32 +4,57 @@ use std::{
     collections::{BTreeMap, HashMap},
     io,
     net::{SocketAddr, ToSocketAddrs},
-    sync::Arc,
-    time::Duration,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
 
-use channel::{BytesChannel, Channel};
+use abort::{Abort, AbortChannels, AbortReason};
+use bytes::{Bytes, BytesMut};
+use channel::{BytesChannel, Channel, ChannelHandle};
 use codecs::BincodeCodec;
 use color_eyre::eyre::{self, Context, Report};
-use config::NetworkConfig;
+use config::{Compression, NetworkConfig, TimeoutConfig};
+use futures::StreamExt;
+use mux::Multiplexer;
 use quinn::{
-    crypto::rustls::QuicClientConfig,
-    rustls::{pki_types::CertificateDer, RootCertStore},
-};
-use quinn::{
-    ClientConfig, Connection, Endpoint, IdleTimeout, RecvStream, SendStream, TransportConfig,
-    VarInt,
+    crypto::rustls::{QuicClientConfig, QuicServerConfig},
+    rustls::{pki_types::CertificateDer, server::WebPkiClientVerifier, RootCertStore},
 };
+use quinn::{ClientConfig, Connection, Endpoint, IdleTimeout, TransportConfig, VarInt};
+use relay::RelayClient;
 use serde::{de::DeserializeOwned, Serialize};
+use session::SessionId;
+use stats::StatsRecorder;
 use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
     runtime::Runtime,
+    sync::watch,
+    task::JoinHandle,
 };
 use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
+use transcript::{SignedTranscript, Transcript, TranscriptRecorder};
 
+pub mod abort;
 pub mod channel;
 pub mod codecs;
+mod compression;
 pub mod config;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+mod mux;
+pub mod proxy;
+pub mod relay;
+mod session;
+pub mod stats;
+pub mod transcript;
+
+/// The receiving half of a stream between two parties.
+pub type NetworkRecvStream = Box<dyn AsyncRead + Send + Unpin>;
+/// The sending half of a stream between two parties.
+pub type NetworkSendStream = Box<dyn AsyncWrite + Send + Unpin>;
 
 /// A warapper for a runtime and a network handler for MPC protocols.
 /// Ensures a gracefull shutdown on drop
@@ -51,23 +76,84 @@ impl MpcNetworkHandlerWrapper {
 impl Drop for MpcNetworkHandlerWrapper {
     fn drop(&mut self) {
         // ignore errors in drop
+        if std::thread::panicking() {
+            let _ = self.runtime.block_on(self.inner.abort(AbortReason::Panic));
+        }
         let _ = self.runtime.block_on(self.inner.shutdown());
     }
 }
 
+/// Checks that the other party of the connection authenticated with the certificate of the party with the given id. All certificates are trusted, so otherwise a party could impersonate another party.
+fn check_peer_identity(
+    conn: &Connection,
+    id: usize,
+    expected: &CertificateDer<'static>,
+) -> eyre::Result<()> {
+    let certs = conn
+        .peer_identity()
+        .and_then(|identity| identity.downcast::<Vec<CertificateDer<'static>>>().ok());
+    if certs.as_ref().and_then(|certs| certs.first()) != Some(expected) {
+        eyre::bail!("party {} presented an unexpected certificate", id);
+    }
+    Ok(())
+}
+
 /// A network handler for MPC protocols.
 #[derive(Debug)]
 pub struct MpcNetworkHandler {
     // this is a btreemap because we rely on iteration order
     connections: BTreeMap<usize, Connection>,
     endpoints: Vec<Endpoint>,
+    relay: Option<RelayClient>,
+    compression: Compression,
+    timeouts: TimeoutConfig,
+    // the deadline of the current protocol phase, shared by all networks using this handler
+    deadline: Mutex<Option<Instant>>,
+    stats: StatsRecorder,
+    transcript: Option<TranscriptRecorder>,
+    // the session announced when opening a channel, and the index of the next channel
+    session: Mutex<SessionId>,
+    next_channel: AtomicU64,
+    streams: usize,
+    // the multiplexers of the channels to each party, if enabled, which are set up with the first channels
+    multiplexers: Option<Mutex<BTreeMap<usize, Multiplexer>>>,
+    // the control channels to send aborts, the tasks receiving them, and the first abort
+    abort_channels: AbortChannels,
+    abort_tasks: Vec<JoinHandle<()>>,
+    aborted: Arc<watch::Sender<Option<Abort>>>,
     my_id: usize,
 }
 
 impl MpcNetworkHandler {
-    /// Tries to establish a connection to other parties in the network based on the provided [NetworkConfig].
+    /// Tries to establish a connection to other parties in the network based on the provided [NetworkConfig]. If the config contains a relay, the connections are established lazily via the relay when the channels are requested.
     pub async fn establish(config: NetworkConfig) -> Result<Self, Report> {
         config.check_config()?;
+        let transcript = config
+            .transcript
+            .then(|| TranscriptRecorder::new(config.my_id, &config.key))
+            .transpose()?;
+        if let Some(relay) = config.relay.clone() {
+            let mut handler = MpcNetworkHandler {
+                connections: BTreeMap::new(),
+                endpoints: Vec::new(),
+                relay: Some(RelayClient::new(&config, relay)?),
+                compression: config.compression,
+                timeouts: config.timeouts,
+                deadline: Mutex::new(None),
+                stats: StatsRecorder::new(),
+                transcript,
+                session: Mutex::new(session::session_id(&config.session)),
+                next_channel: AtomicU64::new(0),
+                streams: config.streams,
+                multiplexers: config.multiplex.then(Default::default),
+                abort_channels: Default::default(),
+                abort_tasks: Vec::new(),
+                aborted: Arc::new(watch::channel(None).0),
+                my_id: config.my_id,
+            };
+            handler.open_abort_channels().await?;
+            return Ok(handler);
+        }
         let certs: HashMap<usize, CertificateDer> = config
             .parties
             .iter()
@@ -80,9 +166,12 @@ impl MpcNetworkHandler {
                 .add(cert.clone())
                 .with_context(|| format!("adding certificate for party {} to root store", id))?;
         }
+        let root_store = Arc::new(root_store);
+        // both sides authenticate with their certificates, such that a party cannot claim the id of another party
         let crypto = quinn::rustls::ClientConfig::builder()
-            .with_root_certificates(root_store)
-            .with_no_client_auth();
+            .with_root_certificates(Arc::clone(&root_store))
+            .with_client_auth_cert(vec![certs[&config.my_id].clone()], config.key.clone_key())
+            .context("creating our client config")?;
 
         let client_config = {
             let mut transport_config = TransportConfig::default();
@@ -97,9 +186,15 @@ impl MpcNetworkHandler {
             client_config
         };
 
+        let client_verifier = WebPkiClientVerifier::builder(root_store)
+            .build()
+            .context("creating client certificate verifier")?;
+        let server_crypto = quinn::rustls::ServerConfig::builder()
+            .with_client_cert_verifier(client_verifier)
+            .with_single_cert(vec![certs[&config.my_id].clone()], config.key)
+            .context("creating our server config")?;
         let server_config =
-            quinn::ServerConfig::with_single_cert(vec![certs[&config.my_id].clone()], config.key)
-                .context("creating our server config")?;
+            quinn::ServerConfig::with_crypto(Arc::new(QuicServerConfig::try_from(server_crypto)?));
         let our_socket_addr = config.bind_addr;
 
         let mut endpoints = Vec::new();
@@ -139,6 +234,7 @@ impl MpcNetworkHandler {
                     })?
                     .await
                     .with_context(|| format!("connecting as a client to party {}", party.id))?;
+                check_peer_identity(&conn, party.id, &party.cert)?;
                 let mut uni = conn.open_uni().await?;
                 uni.write_u32(u32::try_from(config.my_id).expect("party id fits into u32"))
                     .await?;
@@ -154,7 +250,8 @@ impl MpcNetworkHandler {
                 endpoints.push(endpoint);
             } else {
                 // we are the server, accept a connection
-                match tokio::time::timeout(Duration::from_secs(60), server_endpoint.accept()).await
+                match tokio::time::timeout(config.timeouts.handshake(), server_endpoint.accept())
+                    .await
                 {
                     Ok(Some(maybe_conn)) => {
                         let conn = maybe_conn.await?;
@@ -165,13 +262,13 @@ impl MpcNetworkHandler {
                             conn.remote_address(),
                         );
                         let mut uni = conn.accept_uni().await?;
-                        let other_party_id = uni.read_u32().await?;
-                        assert!(connections
-                            .insert(
-                                usize::try_from(other_party_id).expect("u32 fits into usize"),
-                                conn
-                            )
-                            .is_none());
+                        let other_party_id =
+                            usize::try_from(uni.read_u32().await?).expect("u32 fits into usize");
+                        let other_cert = certs.get(&other_party_id).ok_or_else(|| {
+                            eyre::eyre!("party with unknown id {} connected", other_party_id)
+                        })?;
+                        check_peer_identity(&conn, other_party_id, other_cert)?;
+                        assert!(connections.insert(other_party_id, conn).is_none());
                     }
                     Ok(None) => {
                         return Err(eyre::eyre!(
@@ -181,8 +278,9 @@ impl MpcNetworkHandler {
                     }
                     Err(_) => {
                         return Err(eyre::eyre!(
-                            "party {} did not connect within 60 seconds - timeout",
-                            party.id
+                            "party {} did not connect within {} seconds - timeout",
+                            party.id,
+                            config.timeouts.handshake
                         ))
                     }
                 }
@@ -190,14 +288,113 @@ impl MpcNetworkHandler {
         }
         endpoints.push(server_endpoint);
 
-        Ok(MpcNetworkHandler {
+        let mut handler = MpcNetworkHandler {
             connections,
             endpoints,
+            relay: None,
+            compression: config.compression,
+            timeouts: config.timeouts,
+            deadline: Mutex::new(None),
+            stats: StatsRecorder::new(),
+            transcript,
+            session: Mutex::new(session::session_id(&config.session)),
+            next_channel: AtomicU64::new(0),
+            streams: config.streams,
+            multiplexers: config.multiplex.then(Default::default),
+            abort_channels: Default::default(),
+            abort_tasks: Vec::new(),
+            aborted: Arc::new(watch::channel(None).0),
             my_id: config.my_id,
-        })
+        };
+        handler.open_abort_channels().await?;
+        Ok(handler)
+    }
+
+    /// Opens the control channels to the other parties, over which aborts are sent, and spawns the tasks receiving them.
+    async fn open_abort_channels(&mut self) -> std::io::Result<()> {
+        let mut writers = BTreeMap::new();
+        for (id, channel) in self.get_serde_bincode_channels::<AbortReason>().await? {
+            let (write, mut read) = channel.split();
+            writers.insert(id, write);
+            let aborted = Arc::clone(&self.aborted);
+            self.abort_tasks.push(tokio::spawn(async move {
+                // the channel is closed or fails at shutdown, which is not an abort
+                if let Some(Ok(reason)) = read.next().await {
+                    let abort = Abort { party: id, reason };
+                    tracing::warn!("{abort}");
+                    record_abort(&aborted, abort);
+                }
+            }));
+        }
+        self.abort_channels = AbortChannels::new(writers);
+        Ok(())
+    }
+
+    /// Aborts the protocol, such that all receives of all parties fail with the [`Abort`] (see [`abort`]). Returns immediately if the protocol was already aborted.
+    pub async fn abort(&self, reason: AbortReason) -> std::io::Result<()> {
+        let abort = Abort {
+            party: self.my_id,
+            reason: reason.clone(),
+        };
+        if !record_abort(&self.aborted, abort) {
+            return Ok(());
+        }
+        self.abort_channels.send(reason).await
+    }
+
+    /// Returns the first abort of the protocol, if any party aborted.
+    pub fn aborted(&self) -> Option<Abort> {
+        self.aborted.borrow().clone()
+    }
+
+    /// Waits until a party aborts the protocol and returns the first abort.
+    pub async fn wait_for_abort(&self) -> Abort {
+        let mut aborted = self.aborted.subscribe();
+        let abort = aborted
+            .wait_for(Option::is_some)
+            .await
+            .expect("the sender is owned by the handler");
+        abort.clone().expect("waited for an abort")
+    }
+
+    /// Returns the timeouts of the network.
+    pub fn timeouts(&self) -> TimeoutConfig {
+        self.timeouts
+    }
+
+    /// Sets the deadline of the current protocol phase, after which all receives fail with [`io::ErrorKind::TimedOut`]. The deadline applies to all networks using this handler.
+    pub fn set_deadline(&self, deadline: Option<Instant>) {
+        *self.deadline.lock().expect("not poisoned") = deadline;
+    }
+
+    /// Returns how long a receive may wait for its message, which is bounded by the round trip timeout and the deadline of the current phase.
+    pub fn recv_timeout(&self) -> Option<Duration> {
+        let until_deadline = self
+            .deadline
+            .lock()
+            .expect("not poisoned")
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        match (self.timeouts.round_trip(), until_deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Sets the session of the channels requested from now on, e.g., a unique id of the job, which is agreed on by the parties. The parties check that they are in the same session whenever a channel is opened, such that messages of different jobs using the same handler can never be mixed up.
+    pub fn set_session(&self, session: &str) {
+        *self.session.lock().expect("not poisoned") = session::session_id(session);
+        if let Some(multiplexers) = &self.multiplexers {
+            // the channels of the new session are multiplexed over new streams
+            multiplexers.lock().expect("not poisoned").clear();
+        }
     }
 
-    /// Returns the number of sent and received bytes.
+    /// Returns the recorder of the communication statistics, which is shared by all networks using this handler.
+    pub fn stats(&self) -> &StatsRecorder {
+        &self.stats
+    }
+
+    /// Returns the number of sent and received bytes. The statistics are not available if the parties are connected via a relay.
     pub fn get_send_receive(&self, i: usize) -> std::io::Result<(u64, u64)> {
         let conn = self
             .connections
@@ -223,7 +420,7 @@ impl MpcNetworkHandler {
     /// Sets up a new [BytesChannel] between each party. The resulting map maps the id of the party to its respective [BytesChannel].
     pub async fn get_byte_channels(
         &self,
-    ) -> std::io::Result<HashMap<usize, BytesChannel<RecvStream, SendStream>>> {
+    ) -> std::io::Result<HashMap<usize, BytesChannel<NetworkRecvStream, NetworkSendStream>>> {
         // set max frame length to 1Tb and length_field_length to 5 bytes
         const NUM_BYTES: usize = 5;
         let codec = LengthDelimitedCodec::builder()
@@ -234,10 +431,47 @@ impl MpcNetworkHandler {
         self.get_custom_channels(codec).await
     }
 
+    /// Sets up the configured number of parallel [BytesChannel]s to each party (see [`NetworkConfig::streams`]) and manages them with [`ChannelHandle::manage_parallel`], such that large messages are split over the channels. The resulting map maps the id of the party to its respective [ChannelHandle]. If the parties are connected via a relay, every stream uses its own connection.
+    ///
+    /// If the channels are multiplexed (see [`NetworkConfig::multiplex`]), the first call sets up a single stream to each party, and all channels are opened on these streams without any communication.
+    pub async fn get_managed_byte_channels(
+        &self,
+    ) -> std::io::Result<HashMap<usize, ChannelHandle<Bytes, BytesMut>>> {
+        if let Some(multiplexers) = &self.multiplexers {
+            // the lock is not held across the await, as all parties request their channels in the same order anyway
+            let mut current = multiplexers.lock().expect("not poisoned").clone();
+            if current.is_empty() {
+                current = self
+                    .get_byte_channels()
+                    .await?
+                    .into_iter()
+                    .map(|(id, chan)| (id, Multiplexer::new(chan)))
+                    .collect();
+                *multiplexers.lock().expect("not poisoned") = current.clone();
+            }
+            return Ok(current
+                .iter()
+                .map(|(id, multiplexer)| (*id, multiplexer.open()))
+                .collect());
+        }
+        let mut streams: HashMap<usize, Vec<_>> = HashMap::with_capacity(self.connections.len());
+        for _ in 0..self.streams {
+            for (id, chan) in self.get_byte_channels().await? {
+                streams.entry(id).or_default().push(chan);
+            }
+        }
+        Ok(streams
+            .into_iter()
+            .map(|(id, chans)| (id, ChannelHandle::manage_parallel(chans)))
+            .collect())
+    }
+
     /// Set up a new [Channel] using [BincodeCodec] between each party. The resulting map maps the id of the party to its respective [Channel].
     pub async fn get_serde_bincode_channels<M: Serialize + DeserializeOwned + 'static>(
         &self,
-    ) -> std::io::Result<HashMap<usize, Channel<RecvStream, SendStream, BincodeCodec<M>>>> {
+    ) -> std::io::Result<
+        HashMap<usize, Channel<NetworkRecvStream, NetworkSendStream, BincodeCodec<M>>>,
+    > {
         let bincodec = BincodeCodec::<M>::new();
         self.get_custom_channels(bincodec).await
     }
@@ -253,8 +487,22 @@ impl MpcNetworkHandler {
     >(
         &self,
         codec: C,
-    ) -> std::io::Result<HashMap<usize, Channel<RecvStream, SendStream, C>>> {
-        let mut channels = HashMap::with_capacity(self.connections.len() - 1);
+    ) -> std::io::Result<HashMap<usize, Channel<NetworkRecvStream, NetworkSendStream, C>>> {
+        let mut channels = HashMap::with_capacity(self.connections.len());
+        // all parties request their channels in the same order, so the indices match
+        let channel = self.next_channel.fetch_add(1, Ordering::SeqCst);
+        let session = *self.session.lock().expect("not poisoned");
+        if let Some(relay) = &self.relay {
+            for (id, stream) in relay.connect_all().await? {
+                let (recv_stream, send_stream) = tokio::io::split(stream);
+                let (recv_stream, send_stream) = self
+                    .wrap_streams(channel, &session, id, recv_stream, send_stream)
+                    .await?;
+                let conn = Channel::new(recv_stream, send_stream, codec.clone());
+                assert!(channels.insert(id, conn).is_none());
+            }
+            return Ok(channels);
+        }
         for (&id, conn) in self.connections.iter() {
             if id < self.my_id {
                 // we are the client, so we are the receiver
@@ -262,6 +510,9 @@ impl MpcNetworkHandler {
                 send_stream.write_u32(self.my_id as u32).await?;
                 let their_id = recv_stream.read_u32().await?;
                 assert!(their_id == id as u32);
+                let (recv_stream, send_stream) = self
+                    .wrap_streams(channel, &session, id, recv_stream, send_stream)
+                    .await?;
                 let conn = Channel::new(recv_stream, send_stream, codec.clone());
                 assert!(channels.insert(id, conn).is_none());
             } else {
@@ -270,6 +521,9 @@ impl MpcNetworkHandler {
                 let their_id = recv_stream.read_u32().await?;
                 assert!(their_id == id as u32);
                 send_stream.write_u32(self.my_id as u32).await?;
+                let (recv_stream, send_stream) = self
+                    .wrap_streams(channel, &session, id, recv_stream, send_stream)
+                    .await?;
                 let conn = Channel::new(recv_stream, send_stream, codec.clone());
                 assert!(channels.insert(id, conn).is_none());
             }
@@ -277,8 +531,52 @@ impl MpcNetworkHandler {
         Ok(channels)
     }
 
+    /// Checks that the other party opened the stream in the same session and negotiates the compression. The streams are recorded in the transcript, if enabled.
+    async fn wrap_streams<R, W>(
+        &self,
+        channel: u64,
+        session: &SessionId,
+        id: usize,
+        mut recv_stream: R,
+        mut send_stream: W,
+    ) -> std::io::Result<(NetworkRecvStream, NetworkSendStream)>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        session::handshake(&mut recv_stream, &mut send_stream, session, channel)
+            .await
+            .map_err(|e| io::Error::new(e.kind(), format!("party {id}: {e}")))?;
+        let (recv_stream, send_stream) =
+            compression::negotiate(recv_stream, send_stream, self.compression).await?;
+        Ok(match &self.transcript {
+            Some(transcript) => transcript.record(channel, id, recv_stream, send_stream),
+            None => (recv_stream, send_stream),
+        })
+    }
+
+    /// Returns the [`Transcript`] of all bytes sent and received over the channels of this handler so far, or `None` if the transcript is not recorded (see [`NetworkConfig::transcript`]).
+    pub fn transcript(&self) -> Option<Transcript> {
+        self.transcript.as_ref().map(TranscriptRecorder::transcript)
+    }
+
+    /// Returns the [`Transcript`] of all bytes sent and received so far, signed with our private key. The other parties can verify it with our certificate, e.g., to settle a dispute about the messages of a session.
+    pub fn sign_transcript(&self) -> eyre::Result<SignedTranscript> {
+        self.transcript
+            .as_ref()
+            .ok_or_else(|| eyre::eyre!("the transcript is not recorded"))?
+            .sign()
+    }
+
     /// Shutdown all connections, and call [`quinn::Endpoint::wait_idle`] on all of them
     pub async fn shutdown(&self) -> std::io::Result<()> {
+        for task in &self.abort_tasks {
+            task.abort();
+        }
+        if let Some(relay) = &self.relay {
+            tracing::debug!("party {} shutting down via relay", self.my_id);
+            return relay.shutdown().await;
+        }
         tracing::debug!(
             "party {} shutting down, conns = {:?}",
             self.my_id,
@@ -311,3 +609,23 @@ impl MpcNetworkHandler {
         Ok(())
     }
 }
+
+impl Drop for MpcNetworkHandler {
+    fn drop(&mut self) {
+        for task in &self.abort_tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Records the abort if it is the first one, and returns whether it was.
+fn record_abort(aborted: &watch::Sender<Option<Abort>>, abort: Abort) -> bool {
+    aborted.send_if_modified(|aborted| {
+        if aborted.is_none() {
+            *aborted = Some(abort);
+            true
+        } else {
+            false
+        }
+    })
+}
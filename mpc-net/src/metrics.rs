@@ -0,0 +1,280 @@
+//! An exporter of the [`NetworkStats`] and job outcomes of long-running party processes in the Prometheus/OpenMetrics text format.
+//!
+//! A party records every finished job with [`PartyMetrics::record_job`] and serves the metrics with [`PartyMetrics::serve`], such that they can be scraped from `http://<addr>/metrics`. The counters carry the phase and the id of the other party as labels, which allows to spot asymmetric load between the parties.
+
+use std::{io, sync::Arc, time::Duration};
+
+use prometheus_client::{
+    encoding::EncodeLabelSet,
+    metrics::{
+        counter::Counter,
+        family::Family,
+        histogram::{exponential_buckets, Histogram},
+    },
+    registry::{Registry, Unit},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::stats::NetworkStats;
+
+/// The maximum size of the head of an HTTP request to the metrics endpoint.
+const MAX_REQUEST_LEN: usize = 8 * 1024;
+
+/// The time a client of the metrics endpoint has to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The content type of the OpenMetrics text format.
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// The outcome of a job of a party.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JobOutcome {
+    /// The job finished successfully.
+    Success,
+    /// The job failed, e.g., because another party aborted.
+    Failure,
+    /// The job was aborted because a phase or receive timed out.
+    Timeout,
+}
+
+impl JobOutcome {
+    /// Returns the name of the outcome, which is used as label value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            JobOutcome::Success => "success",
+            JobOutcome::Failure => "failure",
+            JobOutcome::Timeout => "timeout",
+        }
+    }
+
+    /// Returns the outcome of a job with the given result.
+    pub fn of<T>(result: &io::Result<T>) -> Self {
+        match result {
+            Ok(_) => JobOutcome::Success,
+            Err(err) if err.kind() == io::ErrorKind::TimedOut => JobOutcome::Timeout,
+            Err(_) => JobOutcome::Failure,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+struct PartyLabels {
+    phase: String,
+    party: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+struct PhaseLabels {
+    phase: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+struct OutcomeLabels {
+    outcome: &'static str,
+}
+
+type HistogramFamily<L> = Family<L, Histogram, fn() -> Histogram>;
+
+/// The metrics of a party, aggregated over all of its jobs.
+#[derive(Debug)]
+pub struct PartyMetrics {
+    registry: Registry,
+    messages_sent: Family<PartyLabels, Counter>,
+    bytes_sent: Family<PartyLabels, Counter>,
+    messages_received: Family<PartyLabels, Counter>,
+    bytes_received: Family<PartyLabels, Counter>,
+    rounds: Family<PartyLabels, Counter>,
+    round_latency: HistogramFamily<PartyLabels>,
+    phase_duration: HistogramFamily<PhaseLabels>,
+    jobs: Family<OutcomeLabels, Counter>,
+}
+
+impl Default for PartyMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartyMetrics {
+    /// Creates the metrics of a party, which are prefixed with `mpc`.
+    pub fn new() -> Self {
+        let mut registry = Registry::with_prefix("mpc");
+        let messages_sent = Family::default();
+        registry.register(
+            "messages_sent",
+            "Number of messages sent to the other party",
+            Family::clone(&messages_sent),
+        );
+        let bytes_sent = Family::default();
+        registry.register_with_unit(
+            "sent",
+            "Number of bytes sent to the other party",
+            Unit::Bytes,
+            Family::clone(&bytes_sent),
+        );
+        let messages_received = Family::default();
+        registry.register(
+            "messages_received",
+            "Number of messages received from the other party",
+            Family::clone(&messages_received),
+        );
+        let bytes_received = Family::default();
+        registry.register_with_unit(
+            "received",
+            "Number of bytes received from the other party",
+            Unit::Bytes,
+            Family::clone(&bytes_received),
+        );
+        let rounds = Family::default();
+        registry.register(
+            "rounds",
+            "Number of communication rounds with the other party",
+            Family::clone(&rounds),
+        );
+        // from 1ms to about 33s
+        let round_latency: HistogramFamily<PartyLabels> =
+            Family::new_with_constructor(|| Histogram::new(exponential_buckets(0.001, 2.0, 16)));
+        registry.register_with_unit(
+            "round_latency",
+            "Mean time a job waited for the messages of the other party per round",
+            Unit::Seconds,
+            Family::clone(&round_latency),
+        );
+        // from 100ms to about 30h
+        let phase_duration: HistogramFamily<PhaseLabels> =
+            Family::new_with_constructor(|| Histogram::new(exponential_buckets(0.1, 2.0, 21)));
+        registry.register_with_unit(
+            "phase_duration",
+            "Time a job spent in the phase",
+            Unit::Seconds,
+            Family::clone(&phase_duration),
+        );
+        let jobs = Family::default();
+        registry.register("jobs", "Number of finished jobs", Family::clone(&jobs));
+        Self {
+            registry,
+            messages_sent,
+            bytes_sent,
+            messages_received,
+            bytes_received,
+            rounds,
+            round_latency,
+            phase_duration,
+            jobs,
+        }
+    }
+
+    /// Records a finished job with the given outcome and the [`NetworkStats`] of its network. The network must not be shared with other jobs, as the stats would be recorded more than once otherwise.
+    pub fn record_job(&self, outcome: JobOutcome, stats: &NetworkStats) {
+        for (phase, parties) in &stats.phases {
+            for (party, stats) in parties {
+                let labels = PartyLabels {
+                    phase: phase.clone(),
+                    party: *party,
+                };
+                self.messages_sent
+                    .get_or_create(&labels)
+                    .inc_by(stats.messages_sent);
+                self.bytes_sent
+                    .get_or_create(&labels)
+                    .inc_by(stats.bytes_sent);
+                self.messages_received
+                    .get_or_create(&labels)
+                    .inc_by(stats.messages_received);
+                self.bytes_received
+                    .get_or_create(&labels)
+                    .inc_by(stats.bytes_received);
+                self.rounds.get_or_create(&labels).inc_by(stats.rounds);
+                if stats.rounds > 0 {
+                    self.round_latency
+                        .get_or_create(&labels)
+                        .observe(stats.recv_wait.as_secs_f64() / stats.rounds as f64);
+                }
+            }
+        }
+        for (phase, duration) in &stats.durations {
+            self.phase_duration
+                .get_or_create(&PhaseLabels {
+                    phase: phase.clone(),
+                })
+                .observe(duration.as_secs_f64());
+        }
+        self.jobs
+            .get_or_create(&OutcomeLabels {
+                outcome: outcome.name(),
+            })
+            .inc();
+    }
+
+    /// Returns the metrics in the OpenMetrics text format.
+    pub fn encode(&self) -> String {
+        let mut buffer = String::new();
+        prometheus_client::encoding::text::encode(&mut buffer, &self.registry)
+            .expect("writing to a string does not fail");
+        buffer
+    }
+
+    /// Serves the metrics on `/metrics` to all clients connecting to the listener until an error occurs.
+    pub async fn serve(self: Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        loop {
+            let (stream, addr) = listener.accept().await?;
+            let metrics = Arc::clone(&self);
+            tokio::spawn(async move {
+                if let Err(err) = metrics.respond(stream).await {
+                    tracing::debug!("serving metrics to {addr} failed: {err}");
+                }
+            });
+        }
+    }
+
+    /// Answers a single HTTP request and closes the connection.
+    async fn respond(&self, mut stream: TcpStream) -> io::Result<()> {
+        let head = tokio::time::timeout(REQUEST_TIMEOUT, read_request_head(&mut stream))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "no request received"))??;
+        let mut request_line = head.lines().next().unwrap_or_default().split(' ');
+        let response = match (request_line.next(), request_line.next()) {
+            (Some("GET"), Some("/metrics")) => {
+                let body = self.encode();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: {CONTENT_TYPE}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+            }
+            (Some("GET"), _) => {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_owned()
+            }
+            _ => "HTTP/1.1 405 Method Not Allowed\r\nAllow: GET\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_owned(),
+        };
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+}
+
+/// Reads the head of an HTTP request, i.e., everything up to the first empty line.
+async fn read_request_head(stream: &mut TcpStream) -> io::Result<String> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request too long",
+            ));
+        }
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed before end of request",
+            ));
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+    String::from_utf8(head).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
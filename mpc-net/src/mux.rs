@@ -0,0 +1,315 @@
+//! Multiplexing of many channels to a party over a single stream.
+//!
+//! Without multiplexing, every channel, e.g., of a forked network, is a stream of its own, which costs a round trip to open and competes with the streams of concurrent subprotocols. With multiplexing, all channels to a party share one stream. Messages are split into chunks of at most [`MUX_CHUNK_LEN`] bytes, which are tagged with the id of their channel and written in turns for all channels with pending messages. Hence, a large message on one channel, e.g., a garbled circuit, does not block the small messages on another one, e.g., of the multiplications of a concurrent subprotocol.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    sync::{Arc, Mutex},
+};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use futures::{SinkExt, StreamExt};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    sync::mpsc,
+};
+use tokio_util::codec::LengthDelimitedCodec;
+
+use crate::channel::{BytesChannel, ChannelHandle, ReadChannel, ReadJob, WriteChannel, WriteJob};
+
+/// The maximum length of the chunks the messages of multiplexed channels are split into.
+pub(crate) const MUX_CHUNK_LEN: usize = 16 * 1024;
+
+/// The length of the header of a chunk, i.e., the id of the channel and the flags.
+const HEADER_LEN: usize = 5;
+
+/// Marks the last chunk of a message.
+const LAST_CHUNK: u8 = 1;
+
+/// The maximum number of channels beyond the ones we opened, for which messages are buffered.
+const MAX_UNOPENED_CHANNELS: u32 = 64;
+
+/// The maximum number of bytes buffered for the channels we did not open yet.
+const MAX_UNOPENED_BYTES: usize = 64 * 1024 * 1024;
+
+type Inbound = mpsc::UnboundedReceiver<io::Result<BytesMut>>;
+
+/// The channels to which the received messages are delivered.
+#[derive(Debug, Default)]
+struct Registry {
+    // the senders of the open channels, and the receivers of the channels we did not open yet
+    channels: HashMap<u32, (mpsc::UnboundedSender<io::Result<BytesMut>>, Option<Inbound>)>,
+    // the number of channels we opened
+    opened: u32,
+    // the number of bytes received for each channel we did not open yet, and their sum
+    unopened: HashMap<u32, usize>,
+    unopened_bytes: usize,
+    // set once the stream is closed, such that no messages are delivered anymore
+    closed: bool,
+}
+
+impl Registry {
+    /// Opens the next channel and returns its id and the receiver of its messages.
+    fn open(&mut self) -> (u32, Inbound) {
+        let id = self.opened;
+        self.opened += 1;
+        if let Some(len) = self.unopened.remove(&id) {
+            self.unopened_bytes -= len;
+        }
+        if self.closed {
+            // the stream is gone, so all receives fail
+            return (id, mpsc::unbounded_channel().1);
+        }
+        let (_, receiver) = self.channels.entry(id).or_insert_with(|| {
+            let (sender, receiver) = mpsc::unbounded_channel();
+            (sender, Some(receiver))
+        });
+        (id, receiver.take().expect("channels are opened once"))
+    }
+
+    /// Accounts for a chunk of `len` bytes received for the channel. The data of the channels we did not open yet is limited, such that the other party cannot make us buffer arbitrarily much of it.
+    fn account(&mut self, id: u32, len: usize) -> io::Result<()> {
+        if id < self.opened {
+            return Ok(());
+        }
+        if id - self.opened >= MAX_UNOPENED_CHANNELS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "received a message for channel {id}, but only {} channels are open",
+                    self.opened
+                ),
+            ));
+        }
+        *self.unopened.entry(id).or_default() += len;
+        self.unopened_bytes += len;
+        if self.unopened_bytes > MAX_UNOPENED_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "received too much data for channels which are not open yet",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Delivers the message to the channel. The messages of a channel we did not open yet are buffered.
+    fn deliver(&mut self, id: u32, message: BytesMut) {
+        if id < self.opened && !self.channels.contains_key(&id) {
+            tracing::warn!("received a message for closed channel {id}");
+            return;
+        }
+        let (sender, _) = self.channels.entry(id).or_insert_with(|| {
+            let (sender, receiver) = mpsc::unbounded_channel();
+            (sender, Some(receiver))
+        });
+        // the channel may have been dropped since, which is checked with the next message
+        let _ = sender.send(Ok(message));
+    }
+
+    /// Fails all channels with the error and closes them.
+    fn close(&mut self, err: Option<io::Error>) {
+        self.closed = true;
+        if let Some(err) = err {
+            for (sender, _) in self.channels.values() {
+                let _ = sender.send(Err(io::Error::new(err.kind(), err.to_string())));
+            }
+        }
+        self.channels.clear();
+    }
+}
+
+/// Multiplexes the channels to a single party over one [`BytesChannel`]. The parties have to open the channels in the same order, as the channels are identified by their index. The messages for channels which are not open yet are buffered up to [`MAX_UNOPENED_BYTES`] for at most [`MAX_UNOPENED_CHANNELS`] channels, beyond that the stream fails.
+#[derive(Debug, Clone)]
+pub(crate) struct Multiplexer {
+    writes: mpsc::Sender<(u32, WriteJob<Bytes>)>,
+    registry: Arc<Mutex<Registry>>,
+}
+
+impl Multiplexer {
+    /// Spawns the tasks which write the chunks of all channels to the stream and deliver the received chunks to their channels.
+    pub(crate) fn new<R, W>(chan: BytesChannel<R, W>) -> Self
+    where
+        R: AsyncReadExt + Unpin + Send + 'static,
+        W: AsyncWriteExt + Unpin + Send + 'static,
+    {
+        let (write, read) = chan.split();
+        let (write_send, write_recv) = mpsc::channel(1024);
+        let registry = Arc::new(Mutex::new(Registry::default()));
+        tokio::spawn(multiplex(write, write_recv));
+        tokio::spawn(demultiplex(read, Arc::clone(&registry)));
+        Self {
+            writes: write_send,
+            registry,
+        }
+    }
+
+    /// Opens the next channel to the party.
+    pub(crate) fn open(&self) -> ChannelHandle<Bytes, BytesMut> {
+        let (id, mut inbound) = self.registry.lock().expect("not poisoned").open();
+
+        let (write_send, mut write_recv) = mpsc::channel::<WriteJob<Bytes>>(1024);
+        let (read_send, mut read_recv) = mpsc::channel::<ReadJob<BytesMut>>(1024);
+
+        let writes = self.writes.clone();
+        tokio::spawn(async move {
+            while let Some(write_job) = write_recv.recv().await {
+                if let Err(mpsc::error::SendError((_, write_job))) =
+                    writes.send((id, write_job)).await
+                {
+                    let _ = write_job.ret.send(Err(io::Error::new(
+                        io::ErrorKind::BrokenPipe,
+                        "multiplexed stream is gone",
+                    )));
+                }
+            }
+        });
+        let registry = Arc::clone(&self.registry);
+        tokio::spawn(async move {
+            while let Some(job) = read_recv.recv().await {
+                let frame = inbound.recv().await.unwrap_or_else(|| {
+                    Err(io::Error::new(
+                        io::ErrorKind::BrokenPipe,
+                        "multiplexed stream is gone",
+                    ))
+                });
+                if job.ret.send(frame).is_err() {
+                    tracing::warn!("Warning: Read Job finished but receiver is gone!");
+                }
+            }
+            // the handle was dropped, so later messages are discarded
+            registry.lock().expect("not poisoned").channels.remove(&id);
+        });
+
+        ChannelHandle {
+            write_job_queue: write_send,
+            read_job_queue: read_send,
+        }
+    }
+}
+
+/// Writes the messages of all channels to the stream. The channels with pending messages take turns in writing a chunk.
+async fn multiplex<W: AsyncWriteExt + Unpin>(
+    mut write: WriteChannel<W, LengthDelimitedCodec>,
+    mut jobs: mpsc::Receiver<(u32, WriteJob<Bytes>)>,
+) {
+    let mut pending: HashMap<u32, VecDeque<WriteJob<Bytes>>> = HashMap::new();
+    // the channels with pending messages, in the order of their turns
+    let mut turns = VecDeque::new();
+    // the messages which are completely written, but not flushed yet
+    let mut written = Vec::new();
+    loop {
+        if turns.is_empty() {
+            let Some((id, job)) = jobs.recv().await else {
+                break;
+            };
+            enqueue(&mut pending, &mut turns, id, job);
+        }
+        while let Ok((id, job)) = jobs.try_recv() {
+            enqueue(&mut pending, &mut turns, id, job);
+        }
+        let id = turns.pop_front().expect("a channel has pending messages");
+        let queue = pending
+            .get_mut(&id)
+            .expect("the channel has pending messages");
+        let job = queue.front_mut().expect("the channel has pending messages");
+        let chunk = job.data.split_to(job.data.len().min(MUX_CHUNK_LEN));
+        let last = job.data.is_empty();
+        let mut frame = BytesMut::with_capacity(HEADER_LEN + chunk.len());
+        frame.put_u32(id);
+        frame.put_u8(if last { LAST_CHUNK } else { 0 });
+        frame.put_slice(&chunk);
+        if last {
+            written.push(queue.pop_front().expect("checked above").ret);
+        }
+        if queue.is_empty() {
+            pending.remove(&id);
+        } else {
+            turns.push_back(id);
+        }
+        let mut result = write.feed(frame.freeze()).await;
+        if result.is_ok() && turns.is_empty() {
+            result = SinkExt::<Bytes>::flush(&mut write).await;
+        }
+        if result.is_ok() && turns.is_empty() {
+            for ret in written.drain(..) {
+                if ret.send(Ok(())).is_err() {
+                    tracing::trace!("Debug: Write Job finished but receiver is gone!");
+                }
+            }
+        }
+        if let Err(err) = result {
+            tracing::error!("Write job failed: {err}");
+            let rets = written.drain(..).chain(
+                pending
+                    .drain()
+                    .flat_map(|(_, queue)| queue)
+                    .map(|job| job.ret),
+            );
+            for ret in rets {
+                let _ = ret.send(Err(io::Error::new(err.kind(), err.to_string())));
+            }
+            break;
+        }
+    }
+}
+
+fn enqueue(
+    pending: &mut HashMap<u32, VecDeque<WriteJob<Bytes>>>,
+    turns: &mut VecDeque<u32>,
+    id: u32,
+    job: WriteJob<Bytes>,
+) {
+    let queue = pending.entry(id).or_default();
+    if queue.is_empty() {
+        turns.push_back(id);
+    }
+    queue.push_back(job);
+}
+
+/// Reads the chunks from the stream and delivers the joined messages to their channels.
+async fn demultiplex<R: AsyncReadExt + Unpin>(
+    mut read: ReadChannel<R, LengthDelimitedCodec>,
+    registry: Arc<Mutex<Registry>>,
+) {
+    // the chunks of the messages which were not received completely yet
+    let mut partial: HashMap<u32, BytesMut> = HashMap::new();
+    let err = loop {
+        let mut frame = match read.next().await {
+            Some(Ok(frame)) => frame,
+            Some(Err(err)) => break Some(err),
+            None => break None,
+        };
+        if frame.len() < HEADER_LEN {
+            break Some(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid chunk of a multiplexed channel",
+            ));
+        }
+        let id = frame.get_u32();
+        let flags = frame.get_u8();
+        if let Err(err) = registry
+            .lock()
+            .expect("not poisoned")
+            .account(id, frame.len())
+        {
+            break Some(err);
+        }
+        if flags & LAST_CHUNK == 0 {
+            partial.entry(id).or_default().extend_from_slice(&frame);
+            continue;
+        }
+        let message = match partial.remove(&id) {
+            Some(mut message) => {
+                message.extend_from_slice(&frame);
+                message
+            }
+            None => frame,
+        };
+        registry.lock().expect("not poisoned").deliver(id, message);
+    };
+    if !partial.is_empty() && err.is_none() {
+        tracing::warn!("multiplexed stream closed in the middle of a message");
+    }
+    registry.lock().expect("not poisoned").close(err);
+}
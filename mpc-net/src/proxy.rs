@@ -0,0 +1,233 @@
+//! Establishing TCP connections through a SOCKS5 or HTTP CONNECT proxy.
+//!
+//! Parties which must not connect to the internet directly can configure a [`ProxyConfig`] per party. The proxy only forwards the TCP stream, and TLS is run end-to-end over the tunnel, so the proxy cannot read or modify the MPC messages. SOCKS5 proxies are authenticated with a username and password as in RFC 1929, HTTP proxies with the basic authentication scheme.
+
+use std::{io, net::IpAddr};
+
+use base64::Engine;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::config::{Address, ProxyConfig, ProxyProtocol};
+
+const SOCKS_VERSION: u8 = 5;
+const SOCKS_AUTH_VERSION: u8 = 1;
+const SOCKS_NO_AUTH: u8 = 0;
+const SOCKS_USERNAME_PASSWORD: u8 = 2;
+const SOCKS_NO_ACCEPTABLE_METHOD: u8 = 0xff;
+const SOCKS_CONNECT: u8 = 1;
+const SOCKS_IPV4: u8 = 1;
+const SOCKS_DOMAIN_NAME: u8 = 3;
+const SOCKS_IPV6: u8 = 4;
+
+/// The maximum length of the response of an HTTP proxy to the CONNECT request.
+const MAX_HTTP_RESPONSE_LEN: usize = 8 * 1024;
+
+/// Connects to the target, through the proxy if one is given.
+pub async fn connect(proxy: Option<&ProxyConfig>, target: &Address) -> io::Result<TcpStream> {
+    let Some(proxy) = proxy else {
+        return TcpStream::connect(target.to_string()).await;
+    };
+    let mut stream = TcpStream::connect(proxy.address.to_string()).await?;
+    match proxy.protocol {
+        ProxyProtocol::Socks5 => socks5_handshake(&mut stream, proxy, target).await?,
+        ProxyProtocol::Http => http_handshake(&mut stream, proxy, target).await?,
+    }
+    tracing::trace!("connected to {target} via proxy {}", proxy.address);
+    Ok(stream)
+}
+
+/// Returns the username and password to authenticate to the proxy, if any.
+fn credentials(proxy: &ProxyConfig) -> Option<(&str, &str)> {
+    match (&proxy.username, &proxy.password) {
+        (None, None) => None,
+        (username, password) => Some((
+            username.as_deref().unwrap_or_default(),
+            password.as_deref().unwrap_or_default(),
+        )),
+    }
+}
+
+async fn socks5_handshake(
+    stream: &mut TcpStream,
+    proxy: &ProxyConfig,
+    target: &Address,
+) -> io::Result<()> {
+    let credentials = credentials(proxy);
+    let method = if credentials.is_some() {
+        SOCKS_USERNAME_PASSWORD
+    } else {
+        SOCKS_NO_AUTH
+    };
+    stream.write_all(&[SOCKS_VERSION, 1, method]).await?;
+    stream.flush().await?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != SOCKS_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "proxy does not speak SOCKS5",
+        ));
+    }
+    match reply[1] {
+        SOCKS_NO_AUTH if method == SOCKS_NO_AUTH => {}
+        SOCKS_USERNAME_PASSWORD if method == SOCKS_USERNAME_PASSWORD => {
+            let (username, password) = credentials.expect("checked above");
+            let username = socks_field(username, "username")?;
+            let password = socks_field(password, "password")?;
+            let mut request = vec![SOCKS_AUTH_VERSION, username.len() as u8];
+            request.extend_from_slice(username);
+            request.push(password.len() as u8);
+            request.extend_from_slice(password);
+            stream.write_all(&request).await?;
+            stream.flush().await?;
+            let mut reply = [0u8; 2];
+            stream.read_exact(&mut reply).await?;
+            if reply[1] != 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "SOCKS5 proxy rejected the credentials",
+                ));
+            }
+        }
+        SOCKS_NO_ACCEPTABLE_METHOD => {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "SOCKS5 proxy requires an unsupported authentication",
+            ))
+        }
+        method => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("SOCKS5 proxy chose an unexpected authentication method {method}"),
+            ))
+        }
+    }
+
+    let mut request = vec![SOCKS_VERSION, SOCKS_CONNECT, 0];
+    match target.hostname.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(SOCKS_IPV4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(SOCKS_IPV6);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            // the proxy resolves the hostname, which may not be resolvable from within the enterprise network
+            let hostname = socks_field(&target.hostname, "hostname")?;
+            request.push(SOCKS_DOMAIN_NAME);
+            request.push(hostname.len() as u8);
+            request.extend_from_slice(hostname);
+        }
+    }
+    request.extend_from_slice(&target.port.to_be_bytes());
+    stream.write_all(&request).await?;
+    stream.flush().await?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!(
+                "SOCKS5 proxy failed to connect to {target}: {}",
+                socks_error(reply[1])
+            ),
+        ));
+    }
+    // skip the address the proxy bound to
+    let len = match reply[3] {
+        SOCKS_IPV4 => 4,
+        SOCKS_IPV6 => 16,
+        SOCKS_DOMAIN_NAME => stream.read_u8().await? as usize,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "SOCKS5 proxy replied with an invalid address",
+            ))
+        }
+    };
+    let mut bound = vec![0u8; len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
+
+/// Returns the field as bytes, which have to fit into a length byte.
+fn socks_field<'a>(field: &'a str, name: &str) -> io::Result<&'a [u8]> {
+    if field.len() > u8::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{name} too long for SOCKS5"),
+        ));
+    }
+    Ok(field.as_bytes())
+}
+
+fn socks_error(reply: u8) -> &'static str {
+    match reply {
+        1 => "general failure",
+        2 => "connection not allowed by ruleset",
+        3 => "network unreachable",
+        4 => "host unreachable",
+        5 => "connection refused",
+        6 => "TTL expired",
+        7 => "command not supported",
+        8 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+async fn http_handshake(
+    stream: &mut TcpStream,
+    proxy: &ProxyConfig,
+    target: &Address,
+) -> io::Result<()> {
+    let mut request = format!("CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n");
+    if let Some((username, password)) = credentials(proxy) {
+        let token =
+            base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"));
+        request.push_str(&format!("Proxy-Authorization: Basic {token}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+    stream.flush().await?;
+
+    // read byte by byte, as everything after the header already belongs to the tunnel
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_HTTP_RESPONSE_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "HTTP proxy response too long",
+            ));
+        }
+        response.push(stream.read_u8().await?);
+    }
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    let status = status_line
+        .strip_prefix("HTTP/1.")
+        .and_then(|rest| rest.split_whitespace().nth(1))
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid HTTP proxy response: {status_line}"),
+            )
+        })?;
+    match status {
+        200..=299 => Ok(()),
+        407 => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("HTTP proxy requires authentication: {status_line}"),
+        )),
+        _ => Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!("HTTP proxy failed to connect to {target}: {status_line}"),
+        )),
+    }
+}
@@ -0,0 +1,335 @@
+//! A relay for parties which cannot accept inbound connections, e.g., because they are behind a NAT.
+//!
+//! Instead of connecting to each other, all parties connect out to a rendezvous server, the [`RelayServer`], which pairs the connections of two parties and forwards the data between them. The parties run TLS with their certificates from the [`NetworkConfig`](crate::config::NetworkConfig) over the forwarded connection, so the MPC messages are end-to-end encrypted and the relay cannot read or modify them.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use color_eyre::eyre::{self, Context};
+use rustls::{
+    pki_types::{CertificateDer, ServerName},
+    server::WebPkiClientVerifier,
+    ClientConfig, RootCertStore, ServerConfig,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::Mutex,
+};
+use tokio_rustls::{TlsAcceptor, TlsConnector, TlsStream};
+
+use crate::{
+    config::{NetworkConfig, NetworkParty, ProxyConfig, RelayConfig},
+    proxy,
+};
+
+/// The maximum length of a session id.
+const MAX_SESSION_LEN: usize = 1024;
+
+/// The time the relay waits for the second party of a connection.
+const PAIRING_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Identifies a connection between two parties, which is announced by both of them when connecting to the relay.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ConnectionRequest {
+    session: String,
+    from: u32,
+    to: u32,
+    index: u64,
+}
+
+impl ConnectionRequest {
+    /// The request the other party of the connection sends.
+    fn counterpart(&self) -> Self {
+        Self {
+            session: self.session.clone(),
+            from: self.to,
+            to: self.from,
+            index: self.index,
+        }
+    }
+
+    async fn write(&self, stream: &mut TcpStream) -> io::Result<()> {
+        let session = self.session.as_bytes();
+        if session.len() > MAX_SESSION_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "session id too long",
+            ));
+        }
+        stream.write_u32(session.len() as u32).await?;
+        stream.write_all(session).await?;
+        stream.write_u32(self.from).await?;
+        stream.write_u32(self.to).await?;
+        stream.write_u64(self.index).await?;
+        stream.flush().await
+    }
+
+    async fn read(stream: &mut TcpStream) -> io::Result<Self> {
+        let len = stream.read_u32().await? as usize;
+        if len > MAX_SESSION_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "session id too long",
+            ));
+        }
+        let mut session = vec![0; len];
+        stream.read_exact(&mut session).await?;
+        let session = String::from_utf8(session)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let from = stream.read_u32().await?;
+        let to = stream.read_u32().await?;
+        let index = stream.read_u64().await?;
+        Ok(Self {
+            session,
+            from,
+            to,
+            index,
+        })
+    }
+}
+
+/// Connects to the relay, through the proxy if one is given, and requests the connection with the given index between the two parties. The returned stream is forwarded to the other party once it connected as well.
+async fn connect(
+    relay: &RelayConfig,
+    proxy: Option<&ProxyConfig>,
+    my_id: usize,
+    their_id: usize,
+    index: u64,
+) -> io::Result<TcpStream> {
+    let mut stream = proxy::connect(proxy, &relay.address).await?;
+    stream.set_nodelay(true)?;
+    ConnectionRequest {
+        session: relay.session.clone(),
+        from: u32::try_from(my_id).expect("party id fits into u32"),
+        to: u32::try_from(their_id).expect("party id fits into u32"),
+        index,
+    }
+    .write(&mut stream)
+    .await?;
+    Ok(stream)
+}
+
+/// Establishes the connections of a party to the other parties via the relay.
+pub(crate) struct RelayClient {
+    config: RelayConfig,
+    my_id: usize,
+    // this is a btreemap because we rely on iteration order
+    parties: BTreeMap<usize, NetworkParty>,
+    connector: TlsConnector,
+    acceptor: TlsAcceptor,
+    handshake_timeout: Duration,
+    next_index: AtomicU64,
+}
+
+impl std::fmt::Debug for RelayClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RelayClient")
+            .field("config", &self.config)
+            .field("my_id", &self.my_id)
+            .field("next_index", &self.next_index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RelayClient {
+    /// Sets up the TLS configs of the party based on the provided [NetworkConfig]. Both sides of a connection authenticate with their certificates, since the relay could otherwise impersonate a party.
+    pub(crate) fn new(config: &NetworkConfig, relay: RelayConfig) -> eyre::Result<Self> {
+        let mut root_store = RootCertStore::empty();
+        for party in &config.parties {
+            root_store.add(party.cert.clone()).with_context(|| {
+                format!("adding certificate for party {} to root store", party.id)
+            })?;
+        }
+        let root_store = Arc::new(root_store);
+        let my_cert = config
+            .parties
+            .iter()
+            .find(|p| p.id == config.my_id)
+            .expect("checked in config")
+            .cert
+            .clone();
+
+        let client_config = ClientConfig::builder()
+            .with_root_certificates(Arc::clone(&root_store))
+            .with_client_auth_cert(vec![my_cert.clone()], config.key.clone_key())
+            .context("creating our client config")?;
+        let client_verifier = WebPkiClientVerifier::builder(root_store)
+            .build()
+            .context("creating client certificate verifier")?;
+        let server_config = ServerConfig::builder()
+            .with_client_cert_verifier(client_verifier)
+            .with_single_cert(vec![my_cert], config.key.clone_key())
+            .context("creating our server config")?;
+
+        Ok(Self {
+            config: relay,
+            my_id: config.my_id,
+            parties: config
+                .parties
+                .iter()
+                .filter(|p| p.id != config.my_id)
+                .map(|p| (p.id, p.clone()))
+                .collect(),
+            connector: TlsConnector::from(Arc::new(client_config)),
+            acceptor: TlsAcceptor::from(Arc::new(server_config)),
+            handshake_timeout: config.timeouts.handshake(),
+            next_index: AtomicU64::new(0),
+        })
+    }
+
+    /// Establishes a new TLS connection to each other party. All parties have to call this the same number of times, since the connections are paired by their index.
+    pub(crate) async fn connect_all(&self) -> io::Result<BTreeMap<usize, TlsStream<TcpStream>>> {
+        let index = self.next_index.fetch_add(1, Ordering::SeqCst);
+        let connections = futures::future::try_join_all(
+            self.parties
+                .values()
+                .map(|party| self.connect_to(party, index)),
+        );
+        let connections = tokio::time::timeout(self.handshake_timeout, connections)
+            .await
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!(
+                        "parties did not connect via the relay within {} seconds - timeout",
+                        self.handshake_timeout.as_secs()
+                    ),
+                )
+            })??;
+        Ok(self.parties.keys().copied().zip(connections).collect())
+    }
+
+    /// Waits until all parties are done, such that no party closes its connections while messages to it are still in flight.
+    pub(crate) async fn shutdown(&self) -> io::Result<()> {
+        let mut connections = self.connect_all().await?;
+        for stream in connections.values_mut() {
+            stream.write_all(b"done").await?;
+            stream.flush().await?;
+        }
+        for (id, stream) in connections.iter_mut() {
+            let mut buffer = vec![0u8; b"done".len()];
+            stream.read_exact(&mut buffer).await.map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::BrokenPipe,
+                    format!("failed to recv done msg from party {id}"),
+                )
+            })?;
+            // ignore errors, the other party might have closed the connection already
+            let _ = stream.shutdown().await;
+        }
+        Ok(())
+    }
+
+    async fn connect_to(
+        &self,
+        party: &NetworkParty,
+        index: u64,
+    ) -> io::Result<TlsStream<TcpStream>> {
+        let stream = connect(
+            &self.config,
+            party.proxy.as_ref(),
+            self.my_id,
+            party.id,
+            index,
+        )
+        .await?;
+        let stream: TlsStream<TcpStream> = if party.id < self.my_id {
+            // we are the client, same as for direct connections
+            let server_name = ServerName::try_from(party.dns_name.hostname.clone())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            self.connector.connect(server_name, stream).await?.into()
+        } else {
+            self.acceptor.accept(stream).await?.into()
+        };
+        // all certificates are trusted, so check that we are connected to the right party
+        let (_, session) = stream.get_ref();
+        let their_cert: Option<&CertificateDer> =
+            session.peer_certificates().and_then(|certs| certs.first());
+        if their_cert != Some(&party.cert) {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("party {} presented an unexpected certificate", party.id),
+            ));
+        }
+        Ok(stream)
+    }
+}
+
+/// A rendezvous server, which forwards the data between the parties connected to it. The connections are paired by their session id, the ids of both parties, and the index of the connection.
+#[derive(Debug, Default)]
+pub struct RelayServer {
+    pending: Mutex<HashMap<ConnectionRequest, TcpStream>>,
+}
+
+impl RelayServer {
+    /// Create a new relay server.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts connections on the listener and forwards them until an error occurs.
+    pub async fn run(self, listener: TcpListener) -> io::Result<()> {
+        let server = Arc::new(self);
+        loop {
+            let (stream, addr) = listener.accept().await?;
+            let server = Arc::clone(&server);
+            tokio::spawn(async move {
+                if let Err(err) = server.handle(stream).await {
+                    tracing::warn!("relaying connection from {addr} failed: {err}");
+                }
+            });
+        }
+    }
+
+    /// Pairs the connection with the pending connection of the other party, or waits for it.
+    async fn handle(self: Arc<Self>, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_nodelay(true)?;
+        let request = tokio::time::timeout(PAIRING_TIMEOUT, ConnectionRequest::read(&mut stream))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "no request received"))??;
+        if request.from == request.to {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "party cannot connect to itself",
+            ));
+        }
+
+        let mut pending = self.pending.lock().await;
+        let Some(mut other) = pending.remove(&request.counterpart()) else {
+            if pending.contains_key(&request) {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    "connection was requested twice",
+                ));
+            }
+            pending.insert(request.clone(), stream);
+            drop(pending);
+            // Drop the connection if the other party does not show up
+            let server = Arc::clone(&self);
+            tokio::spawn(async move {
+                tokio::time::sleep(PAIRING_TIMEOUT).await;
+                server.pending.lock().await.remove(&request);
+            });
+            return Ok(());
+        };
+        drop(pending);
+
+        tracing::debug!(
+            "relaying connection {} between parties {} and {} of session {}",
+            request.index,
+            request.from,
+            request.to,
+            request.session
+        );
+        tokio::io::copy_bidirectional(&mut stream, &mut other).await?;
+        Ok(())
+    }
+}
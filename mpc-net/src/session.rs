@@ -0,0 +1,57 @@
+//! Session ids for the streams between the parties.
+//!
+//! If a handler is reused for many jobs, or several jobs share the connections of a handler concurrently, a stream of one job could be paired with a stream of another job, and messages of an old session could be fed into a new one. To rule this out, both parties announce the session and the index of the channel when a stream is opened and abort if they disagree. Within a stream, QUIC and TLS already deliver the data in order and authenticated, so it needs no further framing.
+
+use std::io;
+
+use sha3::{Digest, Sha3_256};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Separates the session ids from other hashes of the session string.
+const SESSION_CONTEXT: &[u8] = b"co-snarks mpc-net session v1\0";
+
+/// The id of a session, which is the hash of the session string agreed on by the parties.
+pub(crate) type SessionId = [u8; 32];
+
+/// Returns the id of the session with the given name.
+pub(crate) fn session_id(session: &str) -> SessionId {
+    Sha3_256::new()
+        .chain_update(SESSION_CONTEXT)
+        .chain_update(session.as_bytes())
+        .finalize()
+        .into()
+}
+
+/// Announces the session and the index of the channel to the other party of the stream and checks that it announces the same.
+pub(crate) async fn handshake<R, W>(
+    recv_stream: &mut R,
+    send_stream: &mut W,
+    session: &SessionId,
+    channel: u64,
+) -> io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    send_stream.write_all(session).await?;
+    send_stream.write_u64(channel).await?;
+    send_stream.flush().await?;
+    let mut their_session = SessionId::default();
+    recv_stream.read_exact(&mut their_session).await?;
+    let their_channel = recv_stream.read_u64().await?;
+    if &their_session != session {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the other party opened the channel in another session",
+        ));
+    }
+    if their_channel != channel {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "the other party opened channel {their_channel}, but we expected channel {channel}"
+            ),
+        ));
+    }
+    Ok(())
+}
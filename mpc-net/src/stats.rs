@@ -0,0 +1,196 @@
+//! Statistics of the communication of a party, which help to plan the capacity of a deployment and to spot asymmetric load between the parties.
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+/// The name of the phase to which the communication is attributed if no phase was set.
+pub const DEFAULT_PHASE: &str = "default";
+
+/// The communication of a party with one other party.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartyStats {
+    /// The number of sent messages.
+    pub messages_sent: u64,
+    /// The number of sent bytes, excluding the framing of the messages.
+    pub bytes_sent: u64,
+    /// The number of received messages.
+    pub messages_received: u64,
+    /// The number of received bytes, excluding the framing of the messages.
+    pub bytes_received: u64,
+    /// The number of communication rounds, i.e., the number of times a message was received from the party after the own party sent messages.
+    pub rounds: u64,
+    /// The total time spent waiting for messages. This includes the latency of the network as well as the time the other party spent computing.
+    pub recv_wait: Duration,
+    /// The longest time spent waiting for a single message.
+    pub max_recv_wait: Duration,
+}
+
+impl std::ops::AddAssign for PartyStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.messages_sent += rhs.messages_sent;
+        self.bytes_sent += rhs.bytes_sent;
+        self.messages_received += rhs.messages_received;
+        self.bytes_received += rhs.bytes_received;
+        self.rounds += rhs.rounds;
+        self.recv_wait += rhs.recv_wait;
+        self.max_recv_wait = self.max_recv_wait.max(rhs.max_recv_wait);
+    }
+}
+
+/// A snapshot of the communication of a party, per phase and per other party.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkStats {
+    /// Maps the name of each phase to the communication with each other party in this phase.
+    pub phases: BTreeMap<String, BTreeMap<usize, PartyStats>>,
+    /// Maps the name of each phase to the time the party spent in this phase.
+    pub durations: BTreeMap<String, Duration>,
+}
+
+impl NetworkStats {
+    /// Returns the communication with all other parties in the given phase.
+    pub fn phase_total(&self, phase: &str) -> PartyStats {
+        self.phases
+            .get(phase)
+            .into_iter()
+            .flat_map(|parties| parties.values())
+            .fold(PartyStats::default(), |mut acc, stats| {
+                acc += *stats;
+                acc
+            })
+    }
+
+    /// Returns the communication with the given party in all phases.
+    pub fn party_total(&self, party: usize) -> PartyStats {
+        self.phases
+            .values()
+            .filter_map(|parties| parties.get(&party))
+            .fold(PartyStats::default(), |mut acc, stats| {
+                acc += *stats;
+                acc
+            })
+    }
+
+    /// Returns the communication with all other parties in all phases.
+    pub fn total(&self) -> PartyStats {
+        self.phases
+            .values()
+            .flat_map(|parties| parties.values())
+            .fold(PartyStats::default(), |mut acc, stats| {
+                acc += *stats;
+                acc
+            })
+    }
+}
+
+#[derive(Debug)]
+struct StatsRecorderInner {
+    phase: String,
+    phase_start: Instant,
+    stats: NetworkStats,
+    // incremented whenever the party sends after receiving, such that a round is counted once per party
+    round: u64,
+    sending: bool,
+    last_round: BTreeMap<usize, u64>,
+}
+
+impl StatsRecorderInner {
+    fn party_stats(&mut self, party: usize) -> &mut PartyStats {
+        // avoid allocating the name of the phase for every message
+        if !self.stats.phases.contains_key(self.phase.as_str()) {
+            self.stats
+                .phases
+                .insert(self.phase.clone(), BTreeMap::new());
+        }
+        self.stats
+            .phases
+            .get_mut(self.phase.as_str())
+            .expect("inserted above")
+            .entry(party)
+            .or_default()
+    }
+}
+
+/// Records the [`NetworkStats`] of a party. The recorder of an [`MpcNetworkHandler`](crate::MpcNetworkHandler) is shared by all networks using the handler, including their forks.
+#[derive(Debug)]
+pub struct StatsRecorder {
+    inner: Mutex<StatsRecorderInner>,
+}
+
+impl Default for StatsRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatsRecorder {
+    /// Creates a new recorder in the [`DEFAULT_PHASE`].
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(StatsRecorderInner {
+                phase: DEFAULT_PHASE.to_owned(),
+                phase_start: Instant::now(),
+                stats: NetworkStats::default(),
+                round: 0,
+                sending: false,
+                last_round: BTreeMap::new(),
+            }),
+        }
+    }
+
+    /// Sets the phase to which all following communication is attributed.
+    pub fn set_phase(&self, phase: impl Into<String>) {
+        let mut inner = self.lock();
+        let now = Instant::now();
+        let elapsed = now - inner.phase_start;
+        let previous = std::mem::replace(&mut inner.phase, phase.into());
+        *inner.stats.durations.entry(previous).or_default() += elapsed;
+        inner.phase_start = now;
+    }
+
+    /// Returns the current phase.
+    pub fn phase(&self) -> String {
+        self.lock().phase.clone()
+    }
+
+    /// Records a message of `bytes` bytes sent to the party with the given id.
+    pub fn record_sent(&self, to: usize, bytes: usize) {
+        let mut inner = self.lock();
+        if !inner.sending {
+            inner.sending = true;
+            inner.round += 1;
+        }
+        let stats = inner.party_stats(to);
+        stats.messages_sent += 1;
+        stats.bytes_sent += bytes as u64;
+    }
+
+    /// Records a message of `bytes` bytes received from the party with the given id, for which the party waited for `wait`.
+    pub fn record_received(&self, from: usize, bytes: usize, wait: Duration) {
+        let mut inner = self.lock();
+        inner.sending = false;
+        let round = inner.round;
+        let new_round = inner.last_round.insert(from, round) != Some(round);
+        let stats = inner.party_stats(from);
+        stats.messages_received += 1;
+        stats.bytes_received += bytes as u64;
+        stats.rounds += u64::from(new_round);
+        stats.recv_wait += wait;
+        stats.max_recv_wait = stats.max_recv_wait.max(wait);
+    }
+
+    /// Returns a snapshot of the recorded statistics. The duration of the current phase includes the time up to now.
+    pub fn snapshot(&self) -> NetworkStats {
+        let inner = self.lock();
+        let mut stats = inner.stats.clone();
+        *stats.durations.entry(inner.phase.clone()).or_default() += inner.phase_start.elapsed();
+        stats
+    }
+
+    fn lock(&self) -> MutexGuard<'_, StatsRecorderInner> {
+        // the recorder only contains counters, so it is fine to continue after a panic of another thread
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
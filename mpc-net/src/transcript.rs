@@ -0,0 +1,309 @@
+//! Transcripts of the communication of a party, which can be signed with the private key of the party.
+//!
+//! If enabled in the [`NetworkConfig`](crate::config::NetworkConfig), all bytes sent and received over the channels of an [`MpcNetworkHandler`](crate::MpcNetworkHandler) are hashed, separately for every channel and direction. A [`SignedTranscript`] commits the party to these hashes, so in a disputed session, a party can later prove what it sent and received by revealing the messages, and the other parties can check the signature with the certificate of the party.
+
+use std::{
+    collections::BTreeMap,
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{ready, Context, Poll},
+};
+
+use color_eyre::eyre::{self, Context as _};
+use rustls::{
+    crypto::aws_lc_rs,
+    pki_types::{CertificateDer, PrivateKeyDer},
+    sign::SigningKey,
+    SignatureScheme,
+};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{NetworkRecvStream, NetworkSendStream};
+
+/// Separates the signatures of transcripts from other signatures with the keys of the parties.
+const SIGNATURE_CONTEXT: &[u8] = b"co-snarks mpc-net transcript v1\0";
+
+/// The direction of the messages of a [`TranscriptEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    /// The messages sent to the other party.
+    Sent,
+    /// The messages received from the other party.
+    Received,
+}
+
+/// The hash of all bytes sent to or received from a party over a single channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    /// The index of the channel, i.e., the number of channels the parties requested from the handler before.
+    pub channel: u64,
+    /// The id of the other party.
+    pub party: usize,
+    /// Whether the bytes were sent or received.
+    pub direction: Direction,
+    /// The number of bytes.
+    pub len: u64,
+    /// The SHA3-256 hash of the bytes.
+    pub digest: [u8; 32],
+}
+
+/// The transcript of the communication of a party.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transcript {
+    /// The id of the party.
+    pub party: usize,
+    /// The hashes of the communication, sorted by channel, other party, and direction.
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl Transcript {
+    /// Returns the hash of the transcript, which is signed in a [`SignedTranscript`].
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Sha3_256::new();
+        hasher.update((self.party as u64).to_le_bytes());
+        hasher.update((self.entries.len() as u64).to_le_bytes());
+        for entry in &self.entries {
+            hasher.update(entry.channel.to_le_bytes());
+            hasher.update((entry.party as u64).to_le_bytes());
+            hasher.update([entry.direction as u8]);
+            hasher.update(entry.len.to_le_bytes());
+            hasher.update(entry.digest);
+        }
+        hasher.finalize().into()
+    }
+
+    /// Returns whether the messages the party sent to the other party match the messages the other party received, and vice versa. Channels only one of the parties knows about are ignored, e.g., if the transcript of one party was taken before the last fork.
+    pub fn matches(&self, other: &Transcript) -> bool {
+        let flip = |direction| match direction {
+            Direction::Sent => Direction::Received,
+            Direction::Received => Direction::Sent,
+        };
+        self.entries
+            .iter()
+            .filter(|entry| entry.party == other.party)
+            .all(|entry| {
+                other
+                    .entries
+                    .iter()
+                    .find(|e| {
+                        e.channel == entry.channel
+                            && e.party == self.party
+                            && e.direction == flip(entry.direction)
+                    })
+                    .map_or(true, |e| e.len == entry.len && e.digest == entry.digest)
+            })
+    }
+}
+
+/// A [`Transcript`] signed with the private key of the party.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedTranscript {
+    /// The signed transcript.
+    pub transcript: Transcript,
+    /// The TLS signature scheme of the signature.
+    pub scheme: u16,
+    /// The signature over the hash of the transcript.
+    pub signature: Vec<u8>,
+}
+
+impl SignedTranscript {
+    /// Verifies the signature with the certificate of the party.
+    pub fn verify(&self, cert: &CertificateDer) -> io::Result<()> {
+        let invalid = |err: String| io::Error::new(io::ErrorKind::InvalidData, err);
+        let scheme = SignatureScheme::from(self.scheme);
+        let algorithms = aws_lc_rs::default_provider()
+            .signature_verification_algorithms
+            .mapping
+            .iter()
+            .find(|(s, _)| *s == scheme)
+            .map(|(_, algorithms)| *algorithms)
+            .ok_or_else(|| invalid(format!("unsupported signature scheme {scheme:?}")))?;
+        let cert = webpki::EndEntityCert::try_from(cert)
+            .map_err(|e| invalid(format!("invalid certificate: {e}")))?;
+        let message = signed_message(&self.transcript);
+        if algorithms.iter().any(|alg| {
+            cert.verify_signature(*alg, &message, &self.signature)
+                .is_ok()
+        }) {
+            Ok(())
+        } else {
+            Err(invalid(format!(
+                "invalid signature of the transcript of party {}",
+                self.transcript.party
+            )))
+        }
+    }
+}
+
+fn signed_message(transcript: &Transcript) -> Vec<u8> {
+    [SIGNATURE_CONTEXT, &transcript.digest()].concat()
+}
+
+#[derive(Debug, Default)]
+struct StreamDigest {
+    hasher: Sha3_256,
+    len: u64,
+}
+
+impl StreamDigest {
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+        self.len += data.len() as u64;
+    }
+}
+
+type SharedDigest = Arc<Mutex<StreamDigest>>;
+
+fn lock(digest: &SharedDigest) -> MutexGuard<'_, StreamDigest> {
+    // the digest is only updated after the data was read or written, so it is consistent after a panic
+    digest.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Records the hashes of the channels of a handler and signs them.
+pub(crate) struct TranscriptRecorder {
+    my_id: usize,
+    key: Arc<dyn SigningKey>,
+    digests: Mutex<BTreeMap<(u64, usize, Direction), SharedDigest>>,
+}
+
+impl std::fmt::Debug for TranscriptRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TranscriptRecorder")
+            .field("my_id", &self.my_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TranscriptRecorder {
+    pub(crate) fn new(my_id: usize, key: &PrivateKeyDer) -> eyre::Result<Self> {
+        let key = aws_lc_rs::sign::any_supported_type(key)
+            .context("loading our private key for signing transcripts")?;
+        Ok(Self {
+            my_id,
+            key,
+            digests: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Wraps the streams of a channel to the party, such that all bytes are hashed.
+    pub(crate) fn record(
+        &self,
+        channel: u64,
+        party: usize,
+        recv: NetworkRecvStream,
+        send: NetworkSendStream,
+    ) -> (NetworkRecvStream, NetworkSendStream) {
+        let recv_digest = SharedDigest::default();
+        let send_digest = SharedDigest::default();
+        let mut digests = self.digests.lock().unwrap_or_else(|err| err.into_inner());
+        digests.insert(
+            (channel, party, Direction::Received),
+            Arc::clone(&recv_digest),
+        );
+        digests.insert((channel, party, Direction::Sent), Arc::clone(&send_digest));
+        (
+            Box::new(HashingReader {
+                inner: recv,
+                digest: recv_digest,
+            }),
+            Box::new(HashingWriter {
+                inner: send,
+                digest: send_digest,
+            }),
+        )
+    }
+
+    /// Returns the transcript of all bytes sent and received so far.
+    pub(crate) fn transcript(&self) -> Transcript {
+        let digests = self.digests.lock().unwrap_or_else(|err| err.into_inner());
+        let entries = digests
+            .iter()
+            .map(|((channel, party, direction), digest)| {
+                let digest = lock(digest);
+                TranscriptEntry {
+                    channel: *channel,
+                    party: *party,
+                    direction: *direction,
+                    len: digest.len,
+                    digest: digest.hasher.clone().finalize().into(),
+                }
+            })
+            .collect();
+        Transcript {
+            party: self.my_id,
+            entries,
+        }
+    }
+
+    /// Returns the transcript of all bytes sent and received so far, signed with our private key.
+    pub(crate) fn sign(&self) -> eyre::Result<SignedTranscript> {
+        let transcript = self.transcript();
+        let signer = self
+            .key
+            .choose_scheme(&[
+                SignatureScheme::ED25519,
+                SignatureScheme::ECDSA_NISTP256_SHA256,
+                SignatureScheme::ECDSA_NISTP384_SHA384,
+                SignatureScheme::RSA_PSS_SHA256,
+            ])
+            .ok_or_else(|| eyre::eyre!("no supported signature scheme for our private key"))?;
+        let signature = signer
+            .sign(&signed_message(&transcript))
+            .context("signing the transcript")?;
+        Ok(SignedTranscript {
+            transcript,
+            scheme: u16::from(signer.scheme()),
+            signature,
+        })
+    }
+}
+
+/// Hashes all bytes read from the inner stream.
+struct HashingReader<R> {
+    inner: R,
+    digest: SharedDigest,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for HashingReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let before = buf.filled().len();
+        ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        lock(&this.digest).update(&buf.filled()[before..]);
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Hashes all bytes written to the inner stream.
+struct HashingWriter<W> {
+    inner: W,
+    digest: SharedDigest,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for HashingWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let written = ready!(Pin::new(&mut this.inner).poll_write(cx, buf))?;
+        lock(&this.digest).update(&buf[..written]);
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
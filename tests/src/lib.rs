@@ -1,6 +1,7 @@
 use bytes::Bytes;
 use std::sync::mpsc::Receiver;
 
+pub mod rep3_async_network;
 pub mod rep3_network;
 pub mod shamir_network;
 
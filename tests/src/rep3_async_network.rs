@@ -0,0 +1,124 @@
+use bytes::{Bytes, BytesMut};
+use mpc_core::protocols::rep3::{async_network::Rep3AsyncNetwork, id::PartyID};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+#[derive(Debug)]
+pub enum AsyncMsg {
+    Data(Bytes),
+    Recv(UnboundedReceiver<AsyncMsg>),
+}
+
+impl AsyncMsg {
+    fn into_recv(self) -> Option<UnboundedReceiver<AsyncMsg>> {
+        if let AsyncMsg::Recv(x) = self {
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    fn into_data(self) -> Option<Bytes> {
+        if let AsyncMsg::Data(x) = self {
+            Some(x)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Rep3AsyncTestNetwork {
+    pub id: PartyID,
+    pub send_prev: UnboundedSender<AsyncMsg>,
+    pub send_next: UnboundedSender<AsyncMsg>,
+    pub recv_prev: UnboundedReceiver<AsyncMsg>,
+    pub recv_next: UnboundedReceiver<AsyncMsg>,
+}
+
+impl Rep3AsyncTestNetwork {
+    pub fn get_party_networks() -> [Self; 3] {
+        let (p1_p2_sender, p1_p2_receiver) = mpsc::unbounded_channel();
+        let (p1_p3_sender, p1_p3_receiver) = mpsc::unbounded_channel();
+        let (p2_p3_sender, p2_p3_receiver) = mpsc::unbounded_channel();
+        let (p2_p1_sender, p2_p1_receiver) = mpsc::unbounded_channel();
+        let (p3_p1_sender, p3_p1_receiver) = mpsc::unbounded_channel();
+        let (p3_p2_sender, p3_p2_receiver) = mpsc::unbounded_channel();
+
+        let party1 = Self {
+            id: PartyID::ID0,
+            send_prev: p1_p3_sender,
+            recv_prev: p3_p1_receiver,
+            send_next: p1_p2_sender,
+            recv_next: p2_p1_receiver,
+        };
+
+        let party2 = Self {
+            id: PartyID::ID1,
+            send_prev: p2_p1_sender,
+            recv_prev: p1_p2_receiver,
+            send_next: p2_p3_sender,
+            recv_next: p3_p2_receiver,
+        };
+
+        let party3 = Self {
+            id: PartyID::ID2,
+            send_prev: p3_p2_sender,
+            recv_prev: p2_p3_receiver,
+            send_next: p3_p1_sender,
+            recv_next: p1_p3_receiver,
+        };
+
+        [party1, party2, party3]
+    }
+}
+
+impl Rep3AsyncNetwork for Rep3AsyncTestNetwork {
+    fn get_id(&self) -> PartyID {
+        self.id
+    }
+
+    async fn send_bytes(&mut self, target: PartyID, data: Bytes) -> std::io::Result<()> {
+        if self.id.next_id() == target {
+            self.send_next
+                .send(AsyncMsg::Data(data))
+                .expect("can send to next")
+        } else if self.id.prev_id() == target {
+            self.send_prev
+                .send(AsyncMsg::Data(data))
+                .expect("can send to prev");
+        } else {
+            panic!("You want to send to yourself?")
+        }
+        Ok(())
+    }
+
+    async fn recv_bytes(&mut self, from: PartyID) -> std::io::Result<BytesMut> {
+        let msg = if self.id.next_id() == from {
+            self.recv_next.recv().await.unwrap()
+        } else if self.id.prev_id() == from {
+            self.recv_prev.recv().await.unwrap()
+        } else {
+            panic!("You want to read from yourself?")
+        };
+        Ok(BytesMut::from(&msg.into_data().unwrap()[..]))
+    }
+
+    async fn fork(&mut self) -> std::io::Result<Self> {
+        let ch_prev = mpsc::unbounded_channel();
+        let ch_next = mpsc::unbounded_channel();
+
+        self.send_next.send(AsyncMsg::Recv(ch_next.1)).unwrap();
+        self.send_prev.send(AsyncMsg::Recv(ch_prev.1)).unwrap();
+
+        let recv_prev = self.recv_prev.recv().await.unwrap().into_recv().unwrap();
+        let recv_next = self.recv_next.recv().await.unwrap().into_recv().unwrap();
+
+        Ok(Self {
+            id: self.id,
+            send_prev: ch_prev.0,
+            send_next: ch_next.0,
+            recv_prev,
+            recv_next,
+        })
+    }
+}
@@ -2,3 +2,7 @@
 mod rep3;
 #[cfg(test)]
 mod shamir;
+#[cfg(test)]
+mod spdz;
+#[cfg(test)]
+mod twopc;
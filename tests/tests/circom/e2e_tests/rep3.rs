@@ -1,5 +1,7 @@
 use ark_bls12_381::Bls12_381;
 use ark_bn254::Bn254;
+use circom_mpc_compiler::{CoCircomCompiler, CompilerConfig};
+use circom_mpc_vm::mpc_vm::VMConfig;
 use circom_types::plonk::PlonkProof;
 use circom_types::Witness;
 use circom_types::{
@@ -7,12 +9,13 @@ use circom_types::{
     plonk::{JsonVerificationKey as PlonkVK, ZKey as PlonkZK},
     R1CS,
 };
-use mpc_core::protocols::rep3::network::IoContext;
+use mpc_core::protocols::rep3::{malicious::SecurityLevel, network::IoContext};
 use std::sync::Arc;
 
 use circom_types::traits::CheckElement;
-use co_circom_snarks::SharedWitness;
-use co_groth16::mpc::Rep3Groth16Driver;
+use co_circom::{Rep3WitnessShare, SeedRng};
+use co_circom_snarks::{ShareCurve, SharedInput, SharedWitness};
+use co_groth16::mpc::{FftMode, Rep3Groth16Driver};
 use co_groth16::CoGroth16;
 use co_groth16::Groth16;
 use co_plonk::mpc::Rep3PlonkDriver;
@@ -106,3 +109,238 @@ macro_rules! add_test_impl {
 }
 e2e_test!("multiplier2");
 e2e_test!("poseidon");
+
+#[test]
+fn e2e_proof_batch_poseidon_bn254_groth16() {
+    let zkey_file = File::open("../test_vectors/Groth16/bn254/poseidon/circuit.zkey").unwrap();
+    let r1cs_file = File::open("../test_vectors/Groth16/bn254/poseidon/circuit.r1cs").unwrap();
+    let witness_file = File::open("../test_vectors/Groth16/bn254/poseidon/witness.wtns").unwrap();
+    let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+    let zkey = Arc::new(Groth16ZK::<Bn254>::from_reader(zkey_file, CheckElement::No).unwrap());
+    let r1cs = R1CS::<Bn254>::from_reader(r1cs_file).unwrap();
+    //ignore leading 1 for verification
+    let public_input = witness.values[1..r1cs.num_inputs].to_vec();
+    let mut rng = thread_rng();
+    // two independent sharings of the same witness, which are proven in one batch
+    let [a1, a2, a3] = SharedWitness::share_rep3(witness.clone(), r1cs.num_inputs, &mut rng);
+    let [b1, b2, b3] = SharedWitness::share_rep3(witness, r1cs.num_inputs, &mut rng);
+    let test_network = Rep3TestNetwork::default();
+    let mut threads = vec![];
+    for (net, witnesses) in izip!(
+        test_network.get_party_networks(),
+        [vec![a1, b1], vec![a2, b2], vec![a3, b3]]
+    ) {
+        let zkey = Arc::clone(&zkey);
+        threads.push(thread::spawn(move || {
+            let mut io_context0 = IoContext::init(net).unwrap();
+            let io_context1 = io_context0.fork().unwrap();
+            let rep3 = Rep3Groth16Driver::new(io_context0, io_context1);
+            let mut prover = CoGroth16::<Bn254, Rep3Groth16Driver<PartyTestNetwork>>::new(rep3);
+            prover.prove_batch(zkey, witnesses).unwrap()
+        }));
+    }
+    let result3 = threads.pop().unwrap().join().unwrap();
+    let result2 = threads.pop().unwrap().join().unwrap();
+    let result1 = threads.pop().unwrap().join().unwrap();
+    assert_eq!(result1, result2);
+    assert_eq!(result2, result3);
+    assert_eq!(result1.len(), 2);
+    let vk: Groth16VK<Bn254> = serde_json::from_reader(
+        File::open("../test_vectors/Groth16/bn254/poseidon/verification_key.json").unwrap(),
+    )
+    .unwrap();
+    for (proof, opened) in result1 {
+        assert_eq!(opened, public_input);
+        Groth16::<Bn254>::verify(&vk, &proof, &public_input).expect("can verify");
+    }
+}
+
+#[test]
+fn e2e_proof_fft_modes_poseidon_bn254_groth16() {
+    let zkey_file = File::open("../test_vectors/Groth16/bn254/poseidon/circuit.zkey").unwrap();
+    let r1cs_file = File::open("../test_vectors/Groth16/bn254/poseidon/circuit.r1cs").unwrap();
+    let witness_file = File::open("../test_vectors/Groth16/bn254/poseidon/witness.wtns").unwrap();
+    let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+    let zkey = Arc::new(Groth16ZK::<Bn254>::from_reader(zkey_file, CheckElement::No).unwrap());
+    let r1cs = R1CS::<Bn254>::from_reader(r1cs_file).unwrap();
+    //ignore leading 1 for verification
+    let public_input = witness.values[1..r1cs.num_inputs].to_vec();
+    let vk: Groth16VK<Bn254> = serde_json::from_reader(
+        File::open("../test_vectors/Groth16/bn254/poseidon/verification_key.json").unwrap(),
+    )
+    .unwrap();
+    let mut rng = thread_rng();
+    for fft_mode in [FftMode::Distributed, FftMode::Replicated] {
+        let shares = SharedWitness::share_rep3(witness.clone(), r1cs.num_inputs, &mut rng);
+        let test_network = Rep3TestNetwork::default();
+        let mut threads = vec![];
+        for (net, x) in izip!(test_network.get_party_networks(), shares) {
+            let zkey = Arc::clone(&zkey);
+            threads.push(thread::spawn(move || {
+                let mut io_context0 = IoContext::init(net).unwrap();
+                let io_context1 = io_context0.fork().unwrap();
+                let mut rep3 = Rep3Groth16Driver::new(io_context0, io_context1);
+                rep3.set_fft_mode(fft_mode);
+                let prover = CoGroth16::<Bn254, Rep3Groth16Driver<PartyTestNetwork>>::new(rep3);
+                prover.prove(zkey, x).unwrap()
+            }));
+        }
+        let result3 = threads.pop().unwrap().join().unwrap();
+        let result2 = threads.pop().unwrap().join().unwrap();
+        let result1 = threads.pop().unwrap().join().unwrap();
+        assert_eq!(result1, result2);
+        assert_eq!(result2, result3);
+        Groth16::<Bn254>::verify(&vk, &result1, &public_input).expect("can verify");
+    }
+}
+
+#[test]
+fn e2e_proof_malicious_poseidon_bn254_groth16() {
+    let zkey_file = File::open("../test_vectors/Groth16/bn254/poseidon/circuit.zkey").unwrap();
+    let r1cs_file = File::open("../test_vectors/Groth16/bn254/poseidon/circuit.r1cs").unwrap();
+    let witness_file = File::open("../test_vectors/Groth16/bn254/poseidon/witness.wtns").unwrap();
+    let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+    let zkey = Arc::new(Groth16ZK::<Bn254>::from_reader(zkey_file, CheckElement::No).unwrap());
+    let r1cs = R1CS::<Bn254>::from_reader(r1cs_file).unwrap();
+    //ignore leading 1 for verification
+    let public_input = witness.values[1..r1cs.num_inputs].to_vec();
+    let vk: Groth16VK<Bn254> = serde_json::from_reader(
+        File::open("../test_vectors/Groth16/bn254/poseidon/verification_key.json").unwrap(),
+    )
+    .unwrap();
+    let mut rng = thread_rng();
+    let shares = SharedWitness::share_rep3(witness, r1cs.num_inputs, &mut rng);
+    let test_network = Rep3TestNetwork::default();
+    let mut threads = vec![];
+    for (net, x) in izip!(test_network.get_party_networks(), shares) {
+        let zkey = Arc::clone(&zkey);
+        threads.push(thread::spawn(move || {
+            let mut io_context0 = IoContext::init(net).unwrap();
+            io_context0.set_security_level(SecurityLevel::Malicious);
+            let io_context1 = io_context0.fork().unwrap();
+            let rep3 = Rep3Groth16Driver::new(io_context0, io_context1);
+            let prover = CoGroth16::<Bn254, Rep3Groth16Driver<PartyTestNetwork>>::new(rep3);
+            prover.prove(zkey, x).unwrap()
+        }));
+    }
+    let result3 = threads.pop().unwrap().join().unwrap();
+    let result2 = threads.pop().unwrap().join().unwrap();
+    let result1 = threads.pop().unwrap().join().unwrap();
+    assert_eq!(result1, result2);
+    assert_eq!(result2, result3);
+    Groth16::<Bn254>::verify(&vk, &result1, &public_input).expect("can verify");
+}
+
+#[test]
+fn e2e_proof_malicious_poseidon_bn254_plonk() {
+    let zkey_file = File::open("../test_vectors/Plonk/bn254/poseidon/circuit.zkey").unwrap();
+    let r1cs_file = File::open("../test_vectors/Plonk/bn254/poseidon/circuit.r1cs").unwrap();
+    let witness_file = File::open("../test_vectors/Plonk/bn254/poseidon/witness.wtns").unwrap();
+    let witness = Witness::<ark_bn254::Fr>::from_reader(witness_file).unwrap();
+    let zkey = Arc::new(PlonkZK::<Bn254>::from_reader(zkey_file, CheckElement::No).unwrap());
+    let r1cs = R1CS::<Bn254>::from_reader(r1cs_file).unwrap();
+    //ignore leading 1 for verification
+    let public_input = witness.values[1..r1cs.num_inputs].to_vec();
+    let vk: PlonkVK<Bn254> = serde_json::from_reader(
+        File::open("../test_vectors/Plonk/bn254/poseidon/verification_key.json").unwrap(),
+    )
+    .unwrap();
+    let mut rng = thread_rng();
+    let shares = SharedWitness::share_rep3(witness, r1cs.num_inputs, &mut rng);
+    let test_network = Rep3TestNetwork::default();
+    let mut threads = vec![];
+    for (net, x) in izip!(test_network.get_party_networks(), shares) {
+        let zkey = Arc::clone(&zkey);
+        threads.push(thread::spawn(move || {
+            let mut io_context0 = IoContext::init(net).unwrap();
+            io_context0.set_security_level(SecurityLevel::Malicious);
+            let io_context1 = io_context0.fork().unwrap();
+            let rep3 = Rep3PlonkDriver::new(io_context0, io_context1);
+            let prover = CoPlonk::<Bn254, Rep3PlonkDriver<PartyTestNetwork>>::new(rep3);
+            prover.prove(zkey, x).unwrap()
+        }));
+    }
+    let result3 = threads.pop().unwrap().join().unwrap();
+    let result2 = threads.pop().unwrap().join().unwrap();
+    let result1 = threads.pop().unwrap().join().unwrap();
+    assert_eq!(result1, result2);
+    assert_eq!(result2, result3);
+    Plonk::<Bn254>::verify(&vk, &result1, &public_input).expect("can verify");
+}
+
+/// Runs the witness extension of the multiplier2 example with public inputs only and returns the witness file of
+/// every party.
+fn public_witness_files_multiplier2(proof_system: &str) -> Vec<Vec<u8>> {
+    let test_network = Rep3TestNetwork::default();
+    let mut threads = vec![];
+    for net in test_network.get_party_networks() {
+        let circuit = format!(
+            "../co-circom/co-circom/examples/{proof_system}/test_vectors/multiplier2/circuit.circom"
+        );
+        threads.push(thread::spawn(move || {
+            let mut input = SharedInput::default();
+            input
+                .public_inputs
+                .insert("a".to_owned(), vec![ark_bn254::Fr::from(3)]);
+            input
+                .public_inputs
+                .insert("b".to_owned(), vec![-ark_bn254::Fr::from(11)]);
+            let finalized = CoCircomCompiler::<Bn254>::parse(circuit, CompilerConfig::default())
+                .unwrap()
+                .to_rep3_vm_with_network(net, VMConfig::default())
+                .unwrap()
+                .run(input)
+                .unwrap();
+            let witness = Rep3WitnessShare::<_, SeedRng>::from_finalized(finalized);
+            assert!(matches!(witness, Rep3WitnessShare::Public(_)));
+            let mut file = Vec::new();
+            co_circom::write_generated_witness_rep3(&mut file, &witness, ShareCurve::Bn254)
+                .unwrap();
+            file
+        }));
+    }
+    threads.into_iter().map(|t| t.join().unwrap()).collect()
+}
+
+#[test]
+fn e2e_proof_public_witness_multiplier2_bn254() {
+    // outputs c = a * b and public input b
+    let public_input = vec![-ark_bn254::Fr::from(33), -ark_bn254::Fr::from(11)];
+    let path = "../co-circom/co-circom/examples/groth16/test_vectors/multiplier2";
+    let zkey_file = File::open(format!("{path}/multiplier2.zkey")).unwrap();
+    let zkey = Arc::new(Groth16ZK::<Bn254>::from_reader(zkey_file, CheckElement::No).unwrap());
+    let vk: Groth16VK<Bn254> =
+        serde_json::from_reader(File::open(format!("{path}/verification_key.json")).unwrap())
+            .unwrap();
+    // the test network is dropped after the witness extension, so proving cannot run any MPC rounds
+    for file in public_witness_files_multiplier2("groth16") {
+        let witness = co_circom::parse_public_witness_rep3(file.as_slice())
+            .unwrap()
+            .expect("witness is fully public");
+        let (proof, proof_public_input) =
+            Groth16::<Bn254>::plain_prove_with_public_inputs(Arc::clone(&zkey), witness).unwrap();
+        assert_eq!(proof_public_input, public_input);
+        Groth16::<Bn254>::verify(&vk, &proof, &public_input).expect("can verify");
+        // a fully public witness cannot be mistaken for a share
+        assert!(
+            co_circom::parse_serializeable_witness_share_rep3::<_, ark_bn254::Fr>(file.as_slice())
+                .is_err()
+        );
+    }
+
+    let path = "../co-circom/co-circom/examples/plonk/test_vectors/multiplier2";
+    let zkey_file = File::open(format!("{path}/multiplier2.zkey")).unwrap();
+    let zkey = Arc::new(PlonkZK::<Bn254>::from_reader(zkey_file, CheckElement::No).unwrap());
+    let vk: PlonkVK<Bn254> =
+        serde_json::from_reader(File::open(format!("{path}/verification_key.json")).unwrap())
+            .unwrap();
+    for file in public_witness_files_multiplier2("plonk") {
+        let witness = co_circom::parse_public_witness_rep3(file.as_slice())
+            .unwrap()
+            .expect("witness is fully public");
+        let (proof, proof_public_input) =
+            Plonk::<Bn254>::plain_prove_with_public_inputs(Arc::clone(&zkey), witness).unwrap();
+        assert_eq!(proof_public_input, public_input);
+        Plonk::<Bn254>::verify(&vk, &proof, &public_input).expect("can verify");
+    }
+}
@@ -0,0 +1,77 @@
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
+use circom_types::Witness;
+use circom_types::{
+    groth16::{JsonVerificationKey as Groth16VK, ZKey as Groth16ZK},
+    R1CS,
+};
+use mpc_core::protocols::twopc::TwoPcProtocol;
+use std::sync::Arc;
+
+use circom_types::traits::CheckElement;
+use co_circom_snarks::SharedWitness;
+use co_groth16::mpc::TwoPcGroth16Driver;
+use co_groth16::CoGroth16;
+use co_groth16::Groth16;
+use itertools::izip;
+use rand::thread_rng;
+use std::{fs::File, thread};
+use tests::shamir_network::{PartyTestNetwork, ShamirTestNetwork};
+
+macro_rules! e2e_test {
+    ($name: expr) => {
+        add_test_impl!(Groth16, Bn254, $name);
+        add_test_impl!(Groth16, Bls12_381, $name);
+    };
+}
+
+macro_rules! add_test_impl {
+    ($proof_system: ident, $curve: ident, $name: expr) => {
+        paste::item! {
+            #[test]
+            fn [< e2e_proof_ $name _ $curve:lower _ $proof_system:lower>] () {
+                let zkey_file =
+                    File::open(format!("../test_vectors/{}/{}/{}/circuit.zkey", stringify!($proof_system), stringify!([< $curve:lower >]), $name)).unwrap();
+                let r1cs_file =
+                    File::open(format!("../test_vectors/{}/{}/{}/circuit.r1cs", stringify!($proof_system), stringify!([< $curve:lower >]), $name)).unwrap();
+                let witness_file =
+                    File::open(format!("../test_vectors/{}/{}/{}/witness.wtns", stringify!($proof_system), stringify!([< $curve:lower >]), $name)).unwrap();
+                let witness = Witness::<[< ark_ $curve:lower >]::Fr>::from_reader(witness_file).unwrap();
+                let zkey1 = Arc::new([< $proof_system ZK >]::<$curve>::from_reader(zkey_file, CheckElement::No).unwrap());
+                let zkey2 = Arc::clone(&zkey1);
+                let r1cs = R1CS::<$curve>::from_reader(r1cs_file).unwrap();
+                //ignore leading 1 for verification
+                let public_input = witness.values[1..r1cs.num_inputs].to_vec();
+                let mut rng = thread_rng();
+                let witness_shares =
+                    SharedWitness::share_twopc(witness, r1cs.num_inputs, &mut rng);
+                let test_network = ShamirTestNetwork::new(2);
+                let mut threads = vec![];
+                for (net, x, zkey) in izip!(
+                    test_network.get_party_networks(),
+                    witness_shares.into_iter(),
+                    [zkey1, zkey2].into_iter()
+                ) {
+                    threads.push(thread::spawn(move || {
+                        let protocol = TwoPcProtocol::new(net).unwrap();
+                        let twopc = [< TwoPc $proof_system Driver>]::new(protocol);
+                        let  prover = [< Co $proof_system>]::<
+                            $curve, [< TwoPc $proof_system Driver>]<[< ark_ $curve:lower >]::Fr, PartyTestNetwork>
+                        >::new(twopc);
+                        prover.prove(zkey, x).unwrap()
+                    }));
+                }
+                let result2 = threads.pop().unwrap().join().unwrap();
+                let result1 = threads.pop().unwrap().join().unwrap();
+                assert_eq!(result1, result2);
+                let vk: [ < $proof_system VK > ]<$curve> = serde_json::from_reader(
+                    File::open(format!("../test_vectors/{}/{}/{}/verification_key.json", stringify!($proof_system), stringify!([< $curve:lower >]), $name)).unwrap(),
+                )
+                .unwrap();
+                $proof_system::<$curve>::verify(&vk, &result1, &public_input).expect("can verify");
+            }
+        }
+    };
+}
+e2e_test!("multiplier2");
+e2e_test!("poseidon");
@@ -1,2 +1,3 @@
 mod plain_vm;
 mod rep3;
+mod twopc;
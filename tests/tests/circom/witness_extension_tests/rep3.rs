@@ -30,8 +30,8 @@ fn install_tracing() {
 }
 
 pub struct TestInputs {
-    inputs: Vec<Vec<ark_bn254::Fr>>,
-    witnesses: Vec<Witness<ark_ff::Fp<ark_ff::MontBackend<ark_bn254::FrConfig, 4>, 4>>>,
+    pub(super) inputs: Vec<Vec<ark_bn254::Fr>>,
+    pub(super) witnesses: Vec<Witness<ark_ff::Fp<ark_ff::MontBackend<ark_bn254::FrConfig, 4>, 4>>>,
 }
 
 fn combine_field_elements_for_vm(
@@ -261,3 +261,57 @@ witness_extension_test_rep3!(winner);
 witness_extension_test_rep3!(bitonic_sort);
 witness_extension_test_rep3!(num2bits_accelerator);
 witness_extension_test_rep3!(reclaim_addbits_accelerator);
+
+fn run_poseidon3_test(
+    input: Vec<[Rep3VmType<ark_bn254::Fr>; 3]>,
+) -> Vec<Option<SharedWitness<ark_bn254::Fr, ark_bn254::Fr>>> {
+    let test_network = Rep3TestNetwork::default();
+    let mut threads = vec![];
+    for (i, net) in test_network.get_party_networks().into_iter().enumerate() {
+        let input = input.iter().map(|x| x[i].clone()).collect::<Vec<_>>();
+        threads.push(thread::spawn(move || {
+            let mut compiler_config = CompilerConfig::default();
+            compiler_config
+                .link_library
+                .push("../test_vectors/WitnessExtension/tests/libs/".into());
+            CoCircomCompiler::<Bn254>::parse(
+                "../test_vectors/WitnessExtension/tests/poseidon3_test.circom".to_owned(),
+                compiler_config,
+            )
+            .unwrap()
+            .to_rep3_vm_with_network(net, VMConfig::default())
+            .unwrap()
+            .run_with_flat(input, 0)
+            .unwrap()
+            .into_public_witness()
+        }));
+    }
+    threads.into_iter().map(|t| t.join().unwrap()).collect()
+}
+
+#[test]
+fn fully_public_witness_is_detected() {
+    let inp: TestInputs = from_test_name("poseidon3_test");
+    let mut rng = thread_rng();
+    for (input, should_witness) in izip!(inp.inputs, inp.witnesses) {
+        // with public inputs only, the whole witness stays public
+        let public_input = input
+            .iter()
+            .map(|x| [0; 3].map(|_| Rep3VmType::Public(*x)))
+            .collect();
+        for public_witness in run_poseidon3_test(public_input) {
+            let public_witness = public_witness.expect("witness is fully public");
+            let mut is_witness = public_witness.public_inputs;
+            is_witness.extend(public_witness.witness);
+            assert_eq!(is_witness, should_witness.values);
+        }
+
+        let [a, b, c] = rep3::share_field_elements(&input, &mut rng);
+        let shared_input = izip!(a, b, c)
+            .map(|(a, b, c)| [a, b, c].map(Rep3VmType::Arithmetic))
+            .collect();
+        for public_witness in run_poseidon3_test(shared_input) {
+            assert!(public_witness.is_none());
+        }
+    }
+}
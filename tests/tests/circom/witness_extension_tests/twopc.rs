@@ -0,0 +1,98 @@
+use ark_bn254::Bn254;
+use circom_mpc_compiler::{CoCircomCompiler, CompilerConfig};
+use circom_mpc_vm::{mpc_vm::VMConfig, TwoPcVmType};
+use co_circom_snarks::SharedWitness;
+use itertools::izip;
+use mpc_core::protocols::twopc::{self, TwoPcPrimeFieldShare};
+use rand::thread_rng;
+use std::thread;
+use tests::shamir_network::ShamirTestNetwork;
+
+use super::rep3::{from_test_name, TestInputs};
+
+fn combine_field_elements_for_vm(
+    a: SharedWitness<ark_bn254::Fr, TwoPcPrimeFieldShare<ark_bn254::Fr>>,
+    b: SharedWitness<ark_bn254::Fr, TwoPcPrimeFieldShare<ark_bn254::Fr>>,
+) -> Vec<ark_bn254::Fr> {
+    let mut res = Vec::with_capacity(a.public_inputs.len() + a.witness.len());
+    for (a, b) in izip!(a.public_inputs, b.public_inputs) {
+        assert_eq!(a, b);
+        res.push(a);
+    }
+    res.extend(twopc::combine_field_elements(&a.witness, &b.witness));
+    res
+}
+
+macro_rules! run_test {
+    ($file: expr, $input: expr) => {{
+        let mut rng = thread_rng();
+        let inputs = twopc::share_field_elements($input, &mut rng);
+        let test_network = ShamirTestNetwork::new(2);
+        let mut threads = vec![];
+
+        for (net, input) in izip!(test_network.get_party_networks(), inputs) {
+            threads.push(thread::spawn(move || {
+                let mut compiler_config = CompilerConfig::default();
+                compiler_config.simplification =
+                    circom_mpc_compiler::SimplificationLevel::O2(usize::MAX);
+                compiler_config
+                    .link_library
+                    .push("../test_vectors/WitnessExtension/tests/libs/".into());
+                let witness_extension =
+                    CoCircomCompiler::<Bn254>::parse($file.to_owned(), compiler_config)
+                        .unwrap()
+                        .to_twopc_vm_with_network(net, VMConfig::default())
+                        .unwrap();
+                witness_extension
+                    .run_with_flat(
+                        input
+                            .into_iter()
+                            .map(|x| TwoPcVmType::Arithmetic(x))
+                            .collect(),
+                        0,
+                    )
+                    .unwrap()
+                    .into_shared_witness()
+            }));
+        }
+        let result2 = threads.pop().unwrap().join().unwrap();
+        let result1 = threads.pop().unwrap().join().unwrap();
+        combine_field_elements_for_vm(result1, result2)
+    }};
+}
+
+macro_rules! witness_extension_test_twopc {
+    ($name: ident) => {
+        paste::item! {
+            #[test]
+            fn [< twopc_ $name >]() {
+                let inp: TestInputs = from_test_name(stringify!($name));
+                for i in 0..inp.inputs.len() {
+                    let is_witness = run_test!(
+                        format!(
+                            "../test_vectors/WitnessExtension/tests/{}.circom",
+                            stringify!($name)
+                        ),
+                        &inp.inputs[i]
+                    );
+                    assert_eq!(is_witness, inp.witnesses[i].values);
+                }
+            }
+        }
+    };
+}
+
+witness_extension_test_twopc!(binsum_test);
+witness_extension_test_twopc!(greatereqthan);
+witness_extension_test_twopc!(greaterthan);
+witness_extension_test_twopc!(isequal);
+witness_extension_test_twopc!(iszero);
+witness_extension_test_twopc!(lesseqthan);
+witness_extension_test_twopc!(lessthan);
+witness_extension_test_twopc!(mimc_test);
+witness_extension_test_twopc!(multiplier16);
+witness_extension_test_twopc!(mux1_1);
+witness_extension_test_twopc!(num2bits_accelerator);
+witness_extension_test_twopc!(poseidon3_test);
+witness_extension_test_twopc!(sqrt_test);
+witness_extension_test_twopc!(sum_test);
@@ -1,9 +1,9 @@
 mod translate_share {
     use ark_std::UniformRand;
-    use itertools::Itertools;
+    use itertools::{izip, Itertools};
     use mpc_core::protocols::{
-        bridges::network::RepToShamirNetwork,
-        rep3::{self},
+        bridges::{network::RepToShamirNetwork, translate_shamir_to_addshare_vec},
+        rep3::{self, id::PartyID},
         shamir::{self, ShamirPreprocessing, ShamirProtocol},
     };
     use rand::thread_rng;
@@ -113,4 +113,23 @@ mod translate_share {
 
         assert_eq!(is_result, x);
     }
+
+    #[test]
+    fn shamir_to_addshare_vec() {
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        for degree in 1..=2 {
+            let x_shares = shamir::share_field_elements(&x, degree, 3, &mut rng);
+            let [result1, result2, result3] = [PartyID::ID0, PartyID::ID1, PartyID::ID2]
+                .map(|id| translate_shamir_to_addshare_vec(x_shares[usize::from(id)].clone(), id));
+
+            let is_result = izip!(result1, result2, result3)
+                .map(|(a, b, c)| a + b + c)
+                .collect_vec();
+
+            assert_eq!(is_result, x);
+        }
+    }
 }
@@ -1,8 +1,18 @@
 #[cfg(test)]
 mod bridges;
 #[cfg(test)]
+mod net;
+#[cfg(test)]
+mod ot;
+#[cfg(test)]
 mod rep3;
 #[cfg(test)]
 mod rep3_ring;
 #[cfg(test)]
+mod rss;
+#[cfg(test)]
 mod shamir;
+#[cfg(test)]
+mod spdz;
+#[cfg(test)]
+mod twopc;
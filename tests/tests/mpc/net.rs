@@ -0,0 +1,839 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Barrier,
+    },
+    time::{Duration, Instant},
+};
+
+use bytes::{Bytes, BytesMut};
+use itertools::Itertools;
+use mpc_core::protocols::rep3::network::{Rep3MpcNet, Rep3Network};
+use mpc_net::{
+    abort::{Abort, AbortReason},
+    channel::{ChannelHandle, MIN_CHUNK_LEN},
+    config::{
+        Address, Compression, NetworkConfig, NetworkParty, Phase, ProxyConfig, ProxyProtocol,
+        RelayConfig, TimeoutConfig,
+    },
+    metrics::{JobOutcome, PartyMetrics},
+    relay::RelayServer,
+    stats::NetworkStats,
+    MpcNetworkHandler,
+};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Returns the configs of `num_parties` parties listening on free local ports, each with its own self-signed certificate.
+fn local_configs(num_parties: usize) -> Vec<NetworkConfig> {
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    let keys = (0..num_parties)
+        .map(|_| rcgen::generate_simple_self_signed(vec!["127.0.0.1".to_string()]).unwrap())
+        .collect_vec();
+    let parties = keys
+        .iter()
+        .enumerate()
+        .map(|(id, key)| {
+            let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+            NetworkParty {
+                id,
+                dns_name: Address {
+                    hostname: "127.0.0.1".to_string(),
+                    port: socket.local_addr().unwrap().port(),
+                },
+                cert: key.cert.der().clone(),
+                proxy: None,
+            }
+        })
+        .collect_vec();
+    keys.iter()
+        .enumerate()
+        .map(|(my_id, key)| NetworkConfig {
+            parties: parties.clone(),
+            my_id,
+            bind_addr: format!("127.0.0.1:{}", parties[my_id].dns_name.port)
+                .parse()
+                .unwrap(),
+            key: PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.key_pair.serialize_der())),
+            relay: None,
+            compression: Compression::None,
+            timeouts: TimeoutConfig::default(),
+            transcript: false,
+            session: String::new(),
+            streams: 1,
+            multiplex: false,
+        })
+        .collect()
+}
+
+/// Establishes the handlers of all parties concurrently.
+async fn establish(configs: Vec<NetworkConfig>) -> Vec<eyre::Result<MpcNetworkHandler>> {
+    let tasks = configs
+        .into_iter()
+        .map(|config| tokio::spawn(MpcNetworkHandler::establish(config)))
+        .collect_vec();
+    let mut handlers = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        handlers.push(task.await.unwrap());
+    }
+    handlers
+}
+
+async fn shutdown(handlers: &[MpcNetworkHandler]) {
+    futures::future::try_join_all(handlers.iter().map(MpcNetworkHandler::shutdown))
+        .await
+        .unwrap();
+}
+
+/// Opens new managed channels, sends `data` to every other party and checks that every other party sent the same.
+async fn exchange(handler: &MpcNetworkHandler, data: &[u8]) -> std::io::Result<()> {
+    let mut channels = handler.get_managed_byte_channels().await?;
+    for channel in channels.values_mut() {
+        channel
+            .send(Bytes::copy_from_slice(data))
+            .await
+            .await
+            .unwrap()?;
+    }
+    for channel in channels.values_mut() {
+        let received = channel.recv().await.await.unwrap()?;
+        assert_eq!(&received[..], data);
+    }
+    Ok(())
+}
+
+async fn exchange_all(handlers: &[MpcNetworkHandler], data: &[u8]) -> Vec<std::io::Result<()>> {
+    futures::future::join_all(handlers.iter().map(|handler| exchange(handler, data))).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn net_session_reconnect() {
+    let configs = local_configs(3);
+    let handlers = establish(configs.clone())
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .collect_vec();
+    for result in exchange_all(&handlers, b"first job").await {
+        result.unwrap();
+    }
+    // a new session on the same connections
+    for handler in handlers.iter() {
+        handler.set_session("second job");
+    }
+    for result in exchange_all(&handlers, b"second job").await {
+        result.unwrap();
+    }
+    shutdown(&handlers).await;
+    drop(handlers);
+
+    // reconnect on the same addresses
+    let handlers = establish(configs)
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .collect_vec();
+    for handler in handlers.iter() {
+        handler.set_session("third job");
+    }
+    for result in exchange_all(&handlers, b"third job").await {
+        result.unwrap();
+    }
+    shutdown(&handlers).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn net_session_mismatch() {
+    let handlers = establish(local_configs(2))
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .collect_vec();
+    handlers[0].set_session("job a");
+    handlers[1].set_session("job b");
+    for result in exchange_all(&handlers, b"job").await {
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+    // the next channels are in the same session again
+    for handler in handlers.iter() {
+        handler.set_session("job c");
+    }
+    for result in exchange_all(&handlers, b"job c").await {
+        result.unwrap();
+    }
+    shutdown(&handlers).await;
+}
+
+/// Establishes the handlers of two parties, where party 1 authenticates with the given certificate and key instead of its own.
+async fn establish_with_identity(
+    mut configs: Vec<NetworkConfig>,
+    cert: CertificateDer<'static>,
+    key: PrivateKeyDer<'static>,
+) -> Vec<eyre::Result<MpcNetworkHandler>> {
+    for config in configs.iter_mut() {
+        config.timeouts.handshake = 5;
+    }
+    configs[1].parties[1].cert = cert;
+    configs[1].key = key;
+    establish(configs).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn net_mutual_tls() {
+    // a certificate none of the parties trusts
+    let configs = local_configs(2);
+    let unknown = rcgen::generate_simple_self_signed(vec!["127.0.0.1".to_string()]).unwrap();
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(unknown.key_pair.serialize_der()));
+    let established = establish_with_identity(configs, unknown.cert.der().clone(), key).await;
+    assert!(established[0].is_err());
+
+    // the trusted certificate of another party
+    let configs = local_configs(2);
+    let cert = configs[0].parties[0].cert.clone();
+    let key = configs[0].key.clone_key();
+    let established = establish_with_identity(configs, cert, key).await;
+    let err = established[0].as_ref().unwrap_err();
+    assert!(err.to_string().contains("unexpected certificate"));
+
+    // no certificate at all
+    let mut configs = local_configs(2);
+    configs[0].timeouts.handshake = 5;
+    let server = tokio::spawn(MpcNetworkHandler::establish(configs.remove(0)));
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add(configs[0].parties[0].cert.clone()).unwrap();
+    let crypto = rustls::ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let client_config = quinn::ClientConfig::new(std::sync::Arc::new(
+        quinn::crypto::rustls::QuicClientConfig::try_from(crypto).unwrap(),
+    ));
+    let endpoint = quinn::Endpoint::client("127.0.0.1:0".parse().unwrap()).unwrap();
+    let server_addr = format!("127.0.0.1:{}", configs[0].parties[0].dns_name.port)
+        .parse()
+        .unwrap();
+    let client = async {
+        let conn = endpoint
+            .connect_with(client_config, server_addr, "127.0.0.1")
+            .unwrap()
+            .await?;
+        let mut uni = conn.open_uni().await?;
+        uni.write_all(&1u32.to_be_bytes()).await?;
+        uni.finish()?;
+        conn.closed().await;
+        eyre::Ok(())
+    };
+    let _ = client.await;
+    assert!(server.await.unwrap().is_err());
+
+    // the correct certificates
+    let handlers = establish(local_configs(2))
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .collect_vec();
+    for result in exchange_all(&handlers, b"authenticated").await {
+        result.unwrap();
+    }
+    shutdown(&handlers).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn net_signed_transcript() {
+    let mut configs = local_configs(2);
+    for config in configs.iter_mut() {
+        config.transcript = true;
+    }
+    let certs = configs[0]
+        .parties
+        .iter()
+        .map(|party| party.cert.clone())
+        .collect_vec();
+    let handlers = establish(configs)
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .collect_vec();
+    for result in exchange_all(&handlers, b"disputed message").await {
+        result.unwrap();
+    }
+    let transcripts = handlers
+        .iter()
+        .map(|handler| handler.sign_transcript().unwrap())
+        .collect_vec();
+    shutdown(&handlers).await;
+
+    transcripts[0].verify(&certs[0]).unwrap();
+    transcripts[1].verify(&certs[1]).unwrap();
+    assert!(transcripts[0]
+        .transcript
+        .matches(&transcripts[1].transcript));
+    // the signature does not verify with the certificate of another party
+    assert!(transcripts[0].verify(&certs[1]).is_err());
+    // nor after the transcript was changed
+    let mut tampered = transcripts[0].clone();
+    tampered.transcript.entries[0].digest[0] ^= 1;
+    assert!(tampered.verify(&certs[0]).is_err());
+    assert!(!tampered.transcript.matches(&transcripts[1].transcript));
+}
+
+/// The length of the chunks the multiplexer splits the messages into.
+const MUX_CHUNK_LEN: usize = 16 * 1024;
+
+async fn establish_multiplexed(num_parties: usize) -> Vec<MpcNetworkHandler> {
+    let mut configs = local_configs(num_parties);
+    for config in configs.iter_mut() {
+        config.multiplex = true;
+    }
+    establish(configs)
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .collect()
+}
+
+/// Opens the next channel of a party in a network of two parties.
+async fn open_channel(handler: &MpcNetworkHandler) -> ChannelHandle<Bytes, BytesMut> {
+    let channels = handler.get_managed_byte_channels().await.unwrap();
+    channels.into_values().next().unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn net_multiplex_concurrent_channels() {
+    const CHANNELS: usize = 8;
+    let handlers = establish_multiplexed(2).await;
+    let results = futures::future::join_all(handlers.iter().map(|handler| async move {
+        // like forked networks, which are used concurrently afterwards
+        let mut channels = Vec::with_capacity(CHANNELS);
+        for _ in 0..CHANNELS {
+            channels.push(open_channel(handler).await);
+        }
+        let tasks = channels.into_iter().enumerate().map(|(i, mut channel)| {
+            tokio::spawn(async move {
+                // the large messages are split into several chunks, the small ones fit into one
+                let len = if i % 2 == 0 {
+                    5 * MUX_CHUNK_LEN + i
+                } else {
+                    i + 1
+                };
+                let message = (0..len).map(|j| (i + j) as u8).collect::<Bytes>();
+                for _ in 0..3 {
+                    channel.send(message.clone()).await.await.unwrap()?;
+                }
+                for _ in 0..3 {
+                    let received = channel.recv().await.await.unwrap()?;
+                    assert_eq!(received, message);
+                }
+                std::io::Result::Ok(())
+            })
+        });
+        for result in futures::future::join_all(tasks).await {
+            result.unwrap()?;
+        }
+        std::io::Result::Ok(())
+    }))
+    .await;
+    for result in results {
+        result.unwrap();
+    }
+    shutdown(&handlers).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn net_multiplex_unopened_channels() {
+    // messages for a channel too far beyond the open ones
+    let handlers = establish_multiplexed(2).await;
+    let (mut far, mut open) = tokio::join!(open_channel(&handlers[0]), open_channel(&handlers[1]));
+    for _ in 0..65 {
+        far = open_channel(&handlers[0]).await;
+    }
+    far.send(Bytes::from_static(b"too far"))
+        .await
+        .await
+        .unwrap()
+        .unwrap();
+    let err = open.recv().await.await.unwrap().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // too much data for a channel which is not open yet
+    let handlers = establish_multiplexed(2).await;
+    let (_, mut open) = tokio::join!(open_channel(&handlers[0]), open_channel(&handlers[1]));
+    let mut ahead = open_channel(&handlers[0]).await;
+    // the send does not complete, as the receiver drops the connection
+    let _pending = ahead.send(Bytes::from(vec![0u8; 65 * 1024 * 1024])).await;
+    let err = open.recv().await.await.unwrap().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+/// Starts a relay server on a free local port and returns its address.
+async fn start_relay() -> Address {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(RelayServer::new().run(listener));
+    Address {
+        hostname: "127.0.0.1".to_string(),
+        port,
+    }
+}
+
+fn relayed_configs(num_parties: usize, address: &Address, session: &str) -> Vec<NetworkConfig> {
+    let mut configs = local_configs(num_parties);
+    for config in configs.iter_mut() {
+        config.relay = Some(RelayConfig {
+            address: address.clone(),
+            session: session.to_string(),
+        });
+    }
+    configs
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn net_relay() {
+    let address = start_relay().await;
+    // two sessions of the same party ids share the relay
+    let (first, second) = tokio::join!(
+        establish(relayed_configs(3, &address, "first session")),
+        establish(relayed_configs(3, &address, "second session"))
+    );
+    let first = first.into_iter().map(Result::unwrap).collect_vec();
+    let second = second.into_iter().map(Result::unwrap).collect_vec();
+    let (first_results, second_results) = tokio::join!(
+        exchange_all(&first, b"first session"),
+        exchange_all(&second, b"second session")
+    );
+    for result in first_results.into_iter().chain(second_results) {
+        result.unwrap();
+    }
+    // every request of channels uses new connections
+    for result in exchange_all(&first, b"next channels").await {
+        result.unwrap();
+    }
+    tokio::join!(shutdown(&first), shutdown(&second));
+
+    // a party cannot claim the id of another party via the relay
+    let mut configs = relayed_configs(2, &address, "wrong certificate");
+    for config in configs.iter_mut() {
+        config.timeouts.handshake = 5;
+    }
+    configs[1].key = configs[0].key.clone_key();
+    for established in establish(configs).await {
+        assert!(established.is_err());
+    }
+}
+
+/// Exchanges a highly compressible message between two parties requesting the given compressions and returns the number of bytes party 0 sent over UDP.
+async fn compressed_traffic(compressions: [Compression; 2]) -> u64 {
+    let mut configs = local_configs(2);
+    for (config, compression) in configs.iter_mut().zip(compressions) {
+        config.compression = compression;
+    }
+    let handlers = establish(configs)
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .collect_vec();
+    let data = vec![42u8; 1024 * 1024];
+    // several messages on the same stream
+    for _ in 0..3 {
+        for result in exchange_all(&handlers, &data).await {
+            result.unwrap();
+        }
+    }
+    let (sent, _) = handlers[0].get_send_receive(1).unwrap();
+    shutdown(&handlers).await;
+    sent
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn net_compression() {
+    const PAYLOAD: u64 = 3 * 1024 * 1024;
+    let uncompressed = compressed_traffic([Compression::None, Compression::None]).await;
+    assert!(uncompressed > PAYLOAD);
+    for compression in [Compression::Zstd, Compression::Lz4] {
+        let compressed = compressed_traffic([compression, compression]).await;
+        assert!(
+            compressed < PAYLOAD / 10,
+            "{compression:?} sent {compressed} bytes"
+        );
+    }
+    // the parties fall back to uncompressed streams if they disagree
+    let fallback = compressed_traffic([Compression::Zstd, Compression::Lz4]).await;
+    assert!(fallback > PAYLOAD);
+}
+
+/// Runs the three REP3 parties with the given configs in their own threads and returns their results.
+fn run_rep3<T, F>(configs: Vec<NetworkConfig>, party: F) -> Vec<T>
+where
+    T: Send + 'static,
+    F: Fn(&mut Rep3MpcNet) -> T + Send + Sync + 'static,
+{
+    let party = Arc::new(party);
+    // the parties shut down together, so no party closes its connections while the others still wait
+    let done = Arc::new(Barrier::new(configs.len()));
+    let threads = configs
+        .into_iter()
+        .map(|config| {
+            let party = Arc::clone(&party);
+            let done = Arc::clone(&done);
+            std::thread::spawn(move || {
+                let mut net = Rep3MpcNet::new(config).unwrap();
+                let result = party(&mut net);
+                done.wait();
+                result
+            })
+        })
+        .collect_vec();
+    threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .collect()
+}
+
+/// Runs the three REP3 parties until all of them failed to receive from the previous party, which never sends, and returns the errors together with the time until the failures.
+fn rep3_recv_timeouts(
+    configs: Vec<NetworkConfig>,
+    phase: Option<Phase>,
+) -> Vec<(std::io::Error, Duration)> {
+    run_rep3(configs, move |net| {
+        let start = Instant::now();
+        if let Some(phase) = phase {
+            net.start_phase(phase).unwrap();
+        }
+        let prev = net.get_id().prev_id();
+        let err = net.recv_bytes(prev).unwrap_err();
+        (err, start.elapsed())
+    })
+}
+
+#[test]
+fn net_timeouts() {
+    // only party 0 has a round trip timeout, the others are aborted by it
+    let mut configs = local_configs(3);
+    configs[0].timeouts.round_trip = Some(1);
+    let results = rep3_recv_timeouts(configs, None);
+    assert_eq!(results[0].0.kind(), std::io::ErrorKind::TimedOut);
+    for (err, elapsed) in &results[1..] {
+        let abort = Abort::from_io_error(err).expect("an abort");
+        assert_eq!(abort.party, 0);
+        assert_eq!(abort.reason, AbortReason::Timeout);
+        assert!(*elapsed < Duration::from_secs(10));
+    }
+
+    // the parties agree on the smallest timeout of the phase
+    let mut configs = local_configs(3);
+    configs[2].timeouts.witness_extension = Some(1);
+    configs[1].timeouts.witness_extension = Some(3600);
+    let results = rep3_recv_timeouts(configs, Some(Phase::WitnessExtension));
+    for (err, elapsed) in results {
+        let timed_out = err.kind() == std::io::ErrorKind::TimedOut
+            || Abort::from_io_error(&err).is_some_and(|abort| abort.reason == AbortReason::Timeout);
+        assert!(timed_out, "{err}");
+        assert!(elapsed < Duration::from_secs(10));
+    }
+}
+
+/// A job of a REP3 party, which sends two messages to the next party in the witness extension and one message to each other party in the proof assembly.
+fn rep3_job(net: &mut Rep3MpcNet) -> std::io::Result<NetworkStats> {
+    let (next, prev) = (net.get_id().next_id(), net.get_id().prev_id());
+    net.start_phase(Phase::WitnessExtension)?;
+    for _ in 0..2 {
+        net.send_bytes(next, Bytes::from(vec![1u8; 100]))?;
+    }
+    for _ in 0..2 {
+        net.recv_bytes(prev)?;
+    }
+    net.end_phase();
+    net.start_phase(Phase::ProofAssembly)?;
+    for party in [next, prev] {
+        net.send_bytes(party, Bytes::from(vec![2u8; 1000]))?;
+    }
+    for party in [next, prev] {
+        net.recv_bytes(party)?;
+    }
+    net.end_phase();
+    Ok(net.network_stats())
+}
+
+#[test]
+fn net_stats() {
+    let stats = run_rep3(local_configs(3), |net| rep3_job(net).unwrap());
+    let witness_extension = Phase::WitnessExtension.name();
+    let proof_assembly = Phase::ProofAssembly.name();
+    for (id, party) in stats.iter().enumerate() {
+        // the other parties record the same messages
+        for (phase, others) in &party.phases {
+            for (other, ours) in others {
+                let theirs = stats[*other].phases[phase][&id];
+                assert_eq!(ours.messages_sent, theirs.messages_received);
+                assert_eq!(ours.bytes_sent, theirs.bytes_received);
+            }
+        }
+        assert!(party.durations.contains_key(witness_extension));
+        assert!(party.durations.contains_key(proof_assembly));
+
+        // the timeouts of the phases are broadcast, so the difference is the payload
+        let (next, prev) = ((id + 1) % 3, (id + 2) % 3);
+        let to_next = party.phases[witness_extension][&next];
+        let to_prev = party.phases[witness_extension][&prev];
+        assert_eq!(to_next.messages_sent - to_prev.messages_sent, 2);
+        assert_eq!(to_next.bytes_sent - to_prev.bytes_sent, 200);
+        // the two messages from the previous party arrive in a single round after the broadcast
+        assert_eq!(to_prev.rounds, 2);
+        assert_eq!(to_next.rounds, 1);
+        assert_eq!(
+            party.phase_total(proof_assembly).messages_sent,
+            party.phase_total(proof_assembly).messages_received
+        );
+        assert!(party.phase_total(proof_assembly).bytes_sent >= 2000);
+        assert_eq!(
+            party.total().bytes_sent,
+            party.party_total(next).bytes_sent + party.party_total(prev).bytes_sent
+        );
+    }
+}
+
+/// Sends an HTTP request with the given method and path to the server and returns the response.
+async fn http_request(addr: std::net::SocketAddr, method: &str, path: &str) -> String {
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let request = format!("{method} {path} HTTP/1.1\r\nHost: {addr}\r\n\r\n");
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn net_metrics() {
+    let stats = tokio::task::spawn_blocking(|| run_rep3(local_configs(3), rep3_job))
+        .await
+        .unwrap();
+    let metrics = Arc::new(PartyMetrics::new());
+    let result = &stats[0];
+    let stats = result.as_ref().unwrap();
+    metrics.record_job(JobOutcome::of(result), stats);
+    let timed_out: std::io::Result<()> = Err(std::io::ErrorKind::TimedOut.into());
+    metrics.record_job(JobOutcome::of(&timed_out), &NetworkStats::default());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(Arc::clone(&metrics).serve(listener));
+    let response = http_request(addr, "GET", "/metrics").await;
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    assert_eq!(body, metrics.encode());
+    assert!(body.contains("mpc_jobs_total{outcome=\"success\"} 1\n"));
+    assert!(body.contains("mpc_jobs_total{outcome=\"timeout\"} 1\n"));
+    let witness_extension = Phase::WitnessExtension.name();
+    for (party, party_stats) in &stats.phases[witness_extension] {
+        let sent = format!(
+            "mpc_sent_bytes_total{{phase=\"{witness_extension}\",party=\"{party}\"}} {}\n",
+            party_stats.bytes_sent
+        );
+        assert!(body.contains(&sent), "{body}");
+    }
+
+    let response = http_request(addr, "GET", "/other").await;
+    assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    let response = http_request(addr, "POST", "/metrics").await;
+    assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+}
+
+/// Sends all messages to every other party over the same channels and checks that every other party sent the same messages in the same order.
+async fn exchange_messages(handler: &MpcNetworkHandler, messages: &[Vec<u8>]) {
+    let mut channels = handler.get_managed_byte_channels().await.unwrap();
+    for channel in channels.values_mut() {
+        for message in messages {
+            channel
+                .send(Bytes::copy_from_slice(message))
+                .await
+                .await
+                .unwrap()
+                .unwrap();
+        }
+    }
+    for channel in channels.values_mut() {
+        for message in messages {
+            let received = channel.recv().await.await.unwrap().unwrap();
+            assert_eq!(&received[..], &message[..]);
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn net_parallel_streams() {
+    // messages split into fewer, as many as, and more chunks than there are streams
+    let messages = [
+        0,
+        1,
+        MIN_CHUNK_LEN - 1,
+        MIN_CHUNK_LEN + 1,
+        4 * MIN_CHUNK_LEN,
+        4 * 1024 * 1024 + 3,
+    ]
+    .map(|len| (0..len).map(|i| (i % 251) as u8).collect_vec());
+    let address = start_relay().await;
+    for relay in [None, Some(&address)] {
+        let mut configs = match relay {
+            Some(address) => relayed_configs(3, address, "parallel streams"),
+            None => local_configs(3),
+        };
+        for config in configs.iter_mut() {
+            config.streams = 4;
+        }
+        let handlers = establish(configs)
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect_vec();
+        futures::future::join_all(
+            handlers
+                .iter()
+                .map(|handler| exchange_messages(handler, &messages)),
+        )
+        .await;
+        shutdown(&handlers).await;
+    }
+}
+
+const PROXY_USERNAME: &str = "user";
+const PROXY_PASSWORD: &str = "secret";
+
+/// Answers the handshake of a SOCKS5 client, which has to authenticate with the proxy credentials, and returns the requested target.
+async fn socks5_accept(stream: &mut tokio::net::TcpStream) -> std::io::Result<String> {
+    let denied = || std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).await?;
+    let mut methods = vec![0u8; header[1] as usize];
+    stream.read_exact(&mut methods).await?;
+    if !methods.contains(&2) {
+        stream.write_all(&[5, 0xff]).await?;
+        return Err(denied());
+    }
+    stream.write_all(&[5, 2]).await?;
+    // the version of the authentication and the length of the username
+    let mut auth = [0u8; 2];
+    stream.read_exact(&mut auth).await?;
+    let mut username = vec![0u8; auth[1] as usize];
+    stream.read_exact(&mut username).await?;
+    let mut password = vec![0u8; stream.read_u8().await? as usize];
+    stream.read_exact(&mut password).await?;
+    if username != PROXY_USERNAME.as_bytes() || password != PROXY_PASSWORD.as_bytes() {
+        stream.write_all(&[1, 1]).await?;
+        return Err(denied());
+    }
+    stream.write_all(&[1, 0]).await?;
+
+    let mut request = [0u8; 4];
+    stream.read_exact(&mut request).await?;
+    let hostname = match request[3] {
+        1 => {
+            let mut ip = [0u8; 4];
+            stream.read_exact(&mut ip).await?;
+            std::net::Ipv4Addr::from(ip).to_string()
+        }
+        3 => {
+            let mut hostname = vec![0u8; stream.read_u8().await? as usize];
+            stream.read_exact(&mut hostname).await?;
+            String::from_utf8(hostname).unwrap()
+        }
+        _ => return Err(std::io::ErrorKind::InvalidData.into()),
+    };
+    let port = stream.read_u16().await?;
+    stream.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).await?;
+    Ok(format!("{hostname}:{port}"))
+}
+
+/// Answers the CONNECT request of an HTTP client, which has to authenticate with the proxy credentials, and returns the requested target.
+async fn http_accept(stream: &mut tokio::net::TcpStream) -> std::io::Result<String> {
+    let mut request = Vec::new();
+    while !request.ends_with(b"\r\n\r\n") {
+        request.push(stream.read_u8().await?);
+    }
+    let request = String::from_utf8(request).unwrap();
+    // base64 of the credentials "user:secret"
+    if !request.contains("\r\nProxy-Authorization: Basic dXNlcjpzZWNyZXQ=\r\n") {
+        stream
+            .write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+            .await?;
+        return Err(std::io::ErrorKind::PermissionDenied.into());
+    }
+    let target = request
+        .strip_prefix("CONNECT ")
+        .and_then(|rest| rest.split(' ').next())
+        .unwrap()
+        .to_owned();
+    stream
+        .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+        .await?;
+    Ok(target)
+}
+
+/// Starts a proxy speaking the given protocol on a free local port. Returns its address and the number of tunnels it established.
+async fn start_proxy(protocol: ProxyProtocol) -> (Address, Arc<AtomicUsize>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let tunnels = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&tunnels);
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let counter = Arc::clone(&counter);
+            tokio::spawn(async move {
+                let target = match protocol {
+                    ProxyProtocol::Socks5 => socks5_accept(&mut stream).await?,
+                    ProxyProtocol::Http => http_accept(&mut stream).await?,
+                };
+                let mut target = tokio::net::TcpStream::connect(target).await?;
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::io::copy_bidirectional(&mut stream, &mut target).await?;
+                std::io::Result::Ok(())
+            });
+        }
+    });
+    let address = Address {
+        hostname: "127.0.0.1".to_string(),
+        port,
+    };
+    (address, tunnels)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn net_proxy() {
+    let relay = start_relay().await;
+    for protocol in [ProxyProtocol::Socks5, ProxyProtocol::Http] {
+        let (address, tunnels) = start_proxy(protocol).await;
+        let proxied_configs = |password: &str| {
+            let mut configs = relayed_configs(3, &relay, &format!("{protocol:?} {password}"));
+            let proxy = ProxyConfig {
+                protocol,
+                address: address.clone(),
+                username: Some(PROXY_USERNAME.to_owned()),
+                password: Some(password.to_owned()),
+            };
+            for config in configs.iter_mut() {
+                config.timeouts.handshake = 5;
+                for party in config.parties.iter_mut() {
+                    party.proxy = Some(proxy.clone());
+                }
+            }
+            configs
+        };
+
+        let handlers = establish(proxied_configs(PROXY_PASSWORD))
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect_vec();
+        for result in exchange_all(&handlers, b"via proxy").await {
+            result.unwrap();
+        }
+        shutdown(&handlers).await;
+        assert!(tunnels.load(Ordering::SeqCst) > 0);
+
+        // the proxy rejects wrong credentials
+        for established in establish(proxied_configs("wrong")).await {
+            let err = established.unwrap_err();
+            let err = err.root_cause().downcast_ref::<std::io::Error>().unwrap();
+            assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use ark_ff::PrimeField;
+use ark_std::UniformRand;
+use itertools::{izip, Itertools};
+use mpc_core::ot::OtExtension;
+use mpc_core::protocols::shamir::network::ShamirNetwork;
+use rand::{thread_rng, Rng};
+use std::thread;
+use tests::shamir_network::ShamirTestNetwork;
+
+// runs the OT extension between the parties 0 and 2 of a network with 3 parties, party 1 is idle
+fn run_ots<T, F>(f: F) -> [T; 2]
+where
+    T: Send + 'static,
+    F: Fn(&mut OtExtension, &mut tests::shamir_network::PartyTestNetwork) -> T
+        + Send
+        + Clone
+        + 'static,
+{
+    let test_network = ShamirTestNetwork::new(3);
+    let mut threads = Vec::with_capacity(2);
+    for mut net in test_network.get_party_networks() {
+        if net.get_id() == 1 {
+            continue;
+        }
+        let f = f.clone();
+        threads.push(thread::spawn(move || {
+            let mut rng = thread_rng();
+            let other = 2 - net.get_id();
+            let mut ot = OtExtension::setup(&mut net, other, &mut rng).unwrap();
+            f(&mut ot, &mut net)
+        }));
+    }
+    let result2 = threads.pop().unwrap().join().unwrap();
+    let result0 = threads.pop().unwrap().join().unwrap();
+    [result0, result2]
+}
+
+#[test]
+fn random_ot() {
+    let mut rng = thread_rng();
+    let choices0 = (0..100).map(|_| rng.gen::<bool>()).collect_vec();
+    let choices2 = (0..50).map(|_| rng.gen::<bool>()).collect_vec();
+    let (c0, c2) = (choices0.clone(), choices2.clone());
+    let [(sent0, received0), (sent2, received2)] = run_ots(move |ot, net| {
+        let (num_send, choices) = if net.get_id() == 0 {
+            (50, choices0.clone())
+        } else {
+            (100, choices2.clone())
+        };
+        ot.random_ot(net, num_send, &choices).unwrap()
+    });
+    assert_eq!(sent0.len(), 50);
+    assert_eq!(sent2.len(), 100);
+    for (c, (m0, m1), m) in izip!(c0, &sent2, received0) {
+        assert_ne!(m0, m1);
+        assert_eq!(m, if c { *m1 } else { *m0 });
+    }
+    for (c, (m0, m1), m) in izip!(c2, &sent0, received2) {
+        assert_eq!(m, if c { *m1 } else { *m0 });
+    }
+}
+
+#[test]
+fn correlated_ot() {
+    let mut rng = thread_rng();
+    let deltas = (0..100)
+        .map(|_| ark_bn254::Fr::rand(&mut rng))
+        .collect_vec();
+    let choices = (0..100).map(|_| rng.gen::<bool>()).collect_vec();
+    let (d, c) = (deltas.clone(), choices.clone());
+    let [(sent, _), (_, received)] = run_ots(move |ot, net| {
+        let (deltas, choices) = if net.get_id() == 0 {
+            (deltas.clone(), vec![])
+        } else {
+            (vec![], choices.clone())
+        };
+        ot.correlated_ot(net, &deltas, &choices, |pad| {
+            ark_bn254::Fr::from_le_bytes_mod_order(pad)
+        })
+        .unwrap()
+    });
+    for (x, y, delta, c) in izip!(sent, received, d, c) {
+        if c {
+            assert_eq!(y, x + delta);
+        } else {
+            assert_eq!(y, x);
+        }
+    }
+}
+
+#[test]
+fn correlated_ot_bits() {
+    let mut rng = thread_rng();
+    let deltas = (0..100).map(|_| rng.gen::<bool>()).collect_vec();
+    let choices = (0..100).map(|_| rng.gen::<bool>()).collect_vec();
+    let (d, c) = (deltas.clone(), choices.clone());
+    let [(sent0, received0), (sent2, received2)] =
+        run_ots(move |ot, net| ot.correlated_ot_bits(net, &deltas, &choices).unwrap());
+    // both parties send with the deltas d and choose with the bits c
+    for (x, y, d, c) in izip!(sent0, received2, &d, &c) {
+        assert_eq!(y, x ^ (d & c));
+    }
+    for (x, y, d, c) in izip!(sent2, received0, &d, &c) {
+        assert_eq!(y, x ^ (d & c));
+    }
+}
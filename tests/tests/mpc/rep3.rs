@@ -2,27 +2,54 @@ mod field_share {
     use ark_ff::Field;
     use ark_ff::One;
     use ark_ff::PrimeField;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
     use ark_std::{UniformRand, Zero};
+    use co_circom_snarks::{InputCommitment, ResultReceiverKey, SharedInput};
     use itertools::izip;
     use itertools::Itertools;
+    use mpc_core::protocols::cost::{
+        CostTracker, LinkConfig, Rep3CountingNetwork, Rep3LocalNetwork,
+    };
+    use mpc_core::protocols::faults::{Fault, FaultSchedule, Rep3FaultyNetwork};
+    use mpc_core::protocols::provisioning::{self, ProvisioningConfig, ProvisioningError};
+    use mpc_core::protocols::rep3::async_network::{AsyncIoContext, Rep3AsyncNetwork};
     use mpc_core::protocols::rep3::conversion;
+    use mpc_core::protocols::rep3::fixed_point;
     use mpc_core::protocols::rep3::gadgets;
     use mpc_core::protocols::rep3::id::PartyID;
+    use mpc_core::protocols::rep3::malicious::{self, SecurityLevel};
+    use mpc_core::protocols::rep3::network::Rep3Network;
+    use mpc_core::protocols::rep3::preprocessing::{Rep3PreprocessedBits, Rep3Preprocessing};
+    use mpc_core::protocols::rep3::rngs::Rep3PrssState;
+    use mpc_core::protocols::rep3::websocket::{Rep3WebSocketNet, WebSocketConfig, WebSocketParty};
     use mpc_core::protocols::rep3::yao;
+    use mpc_core::protocols::rep3::yao::arithmetic::{
+        Rep3ArithmeticEvaluator, Rep3ArithmeticGarbler,
+    };
+    use mpc_core::protocols::rep3::yao::bristol_fashion::BristolFashionCircuit;
     use mpc_core::protocols::rep3::yao::circuits::GarbledCircuits;
     use mpc_core::protocols::rep3::yao::evaluator::Rep3Evaluator;
     use mpc_core::protocols::rep3::yao::garbler::Rep3Garbler;
+    use mpc_core::protocols::rep3::yao::roles::GCRoles;
     use mpc_core::protocols::rep3::yao::streaming_evaluator::StreamingRep3Evaluator;
     use mpc_core::protocols::rep3::yao::streaming_garbler::StreamingRep3Garbler;
+    use mpc_core::protocols::rep3::yao::topology::TopologyCache;
     use mpc_core::protocols::rep3::yao::GCUtils;
+    use mpc_core::protocols::rep3::yao::GcConsistencyError;
     use mpc_core::protocols::rep3::{self, arithmetic, network::IoContext};
     use mpc_core::protocols::rep3_ring;
+    use mpc_net::abort::{Abort, AbortReason};
     use num_bigint::BigUint;
+    use rand::rngs::StdRng;
     use rand::thread_rng;
     use rand::Rng;
+    use rand::SeedableRng;
+    use std::collections::BTreeMap;
     use std::str::FromStr;
     use std::sync::mpsc;
     use std::thread;
+    use std::time::{Duration, Instant};
+    use tests::rep3_async_network::Rep3AsyncTestNetwork;
     use tests::rep3_network::Rep3TestNetwork;
 
     // TODO we dont need channels, we can just join
@@ -148,6 +175,178 @@ mod field_share {
         assert_eq!(is_result, should_result);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rep3_mul_async() {
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let x_shares = rep3::share_field_element(x, &mut rng);
+        let y_shares = rep3::share_field_element(y, &mut rng);
+        let should_result = x * y;
+        let mut tasks = Vec::with_capacity(3);
+        for (net, x, y) in izip!(
+            Rep3AsyncTestNetwork::get_party_networks(),
+            x_shares.into_iter(),
+            y_shares.into_iter()
+        ) {
+            tasks.push(tokio::spawn(async move {
+                let ctx = AsyncIoContext::init(net).await.unwrap();
+                let (mul, mut ctx) = ctx
+                    .run_blocking(move |io| arithmetic::mul(x, y, io).unwrap())
+                    .await
+                    .unwrap();
+                // Open the result with the async network
+                let mut fork = ctx.fork().await.unwrap();
+                let (prev, next) = fork.network().broadcast_many(&[mul]).await.unwrap();
+                rep3::combine_field_element(mul, prev[0], next[0])
+            }));
+        }
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), should_result);
+        }
+    }
+
+    #[test]
+    fn rep3_mul_websocket() {
+        // Party 0 has no url and connects to the others, like a party running in a browser
+        let ports = [0; 2].map(|_| {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        });
+        let parties = vec![
+            WebSocketParty { id: 0, url: None },
+            WebSocketParty {
+                id: 1,
+                url: Some(format!("ws://127.0.0.1:{}", ports[0])),
+            },
+            WebSocketParty {
+                id: 2,
+                url: Some(format!("ws://127.0.0.1:{}", ports[1])),
+            },
+        ];
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let x_shares = rep3::share_field_element(x, &mut rng);
+        let y_shares = rep3::share_field_element(y, &mut rng);
+        let should_result = x * y;
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (my_id, tx, x, y) in izip!(
+            0..3,
+            [tx1, tx2, tx3],
+            x_shares.into_iter(),
+            y_shares.into_iter()
+        ) {
+            let config = WebSocketConfig {
+                my_id,
+                bind_addr: (my_id > 0)
+                    .then(|| format!("127.0.0.1:{}", ports[my_id - 1]).parse().unwrap()),
+                parties: parties.clone(),
+            };
+            thread::spawn(move || {
+                let net = Rep3WebSocketNet::new(config).unwrap();
+                let mut ctx = IoContext::init(net).unwrap();
+                let mul = arithmetic::mul(x, y, &mut ctx).unwrap();
+                tx.send(mul)
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_element(result1, result2, result3);
+        assert_eq!(is_result, should_result);
+    }
+
+    #[test]
+    fn rep3_websocket_ping() {
+        // Party 0 is a raw WebSocket client, which pings the other parties while they send large messages
+        let ports = [0; 2].map(|_| {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        });
+        let parties = vec![
+            WebSocketParty { id: 0, url: None },
+            WebSocketParty {
+                id: 1,
+                url: Some(format!("ws://127.0.0.1:{}", ports[0])),
+            },
+            WebSocketParty {
+                id: 2,
+                url: Some(format!("ws://127.0.0.1:{}", ports[1])),
+            },
+        ];
+        const ROUNDS: usize = 10;
+        let mut rng = thread_rng();
+        let data = (0..10_000)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let mut handles = Vec::new();
+        for my_id in 1..3 {
+            let config = WebSocketConfig {
+                my_id,
+                bind_addr: Some(format!("127.0.0.1:{}", ports[my_id - 1]).parse().unwrap()),
+                parties: parties.clone(),
+            };
+            let data = data.clone();
+            handles.push(thread::spawn(move || {
+                let mut net = Rep3WebSocketNet::new(config).unwrap();
+                for _ in 0..ROUNDS {
+                    net.send_many(PartyID::ID0, &data).unwrap();
+                    let echo = net.recv_many::<ark_bn254::Fr>(PartyID::ID0).unwrap();
+                    assert_eq!(echo, data);
+                }
+                // Keep the connection open until party 0 received all pongs
+                net.recv_many::<u8>(PartyID::ID0).unwrap();
+            }));
+        }
+
+        for port in ports {
+            let url = format!("ws://127.0.0.1:{}", port);
+            let stream = loop {
+                match std::net::TcpStream::connect(("127.0.0.1", port)) {
+                    Ok(stream) => break stream,
+                    Err(_) => thread::sleep(std::time::Duration::from_millis(10)),
+                }
+            };
+            let (mut socket, _) = tungstenite::client(url, stream).unwrap();
+            socket.send(tungstenite::Message::Binary(vec![0])).unwrap();
+            let mut pongs = 0;
+            for round in 0..ROUNDS {
+                socket
+                    .send(tungstenite::Message::Ping(vec![round as u8]))
+                    .unwrap();
+                let data = loop {
+                    match socket.read().unwrap() {
+                        tungstenite::Message::Binary(data) => break data,
+                        tungstenite::Message::Pong(payload) => {
+                            assert_eq!(payload, vec![pongs as u8]);
+                            pongs += 1;
+                        }
+                        msg => panic!("unexpected message {:?}", msg),
+                    }
+                };
+                socket.send(tungstenite::Message::Binary(data)).unwrap();
+            }
+            while pongs < ROUNDS {
+                match socket.read().unwrap() {
+                    tungstenite::Message::Pong(payload) => {
+                        assert_eq!(payload, vec![pongs as u8]);
+                        pongs += 1;
+                    }
+                    msg => panic!("unexpected message {:?}", msg),
+                }
+            }
+            let mut done = Vec::new();
+            Vec::<u8>::new().serialize_uncompressed(&mut done).unwrap();
+            socket.send(tungstenite::Message::Binary(done)).unwrap();
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
     #[test]
     fn rep3_div() {
         let test_network = Rep3TestNetwork::default();
@@ -375,6 +574,134 @@ mod field_share {
         assert_eq!(is_result, should_result);
     }
 
+    #[test]
+    fn rep3_open_vec_to() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..10)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let target = PartyID::ID1;
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+
+        for (net, tx, x) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares.into_iter()
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(arithmetic::open_vec_to(&x, target, &mut rep3).unwrap())
+            });
+        }
+        assert_eq!(rx1.recv().unwrap(), None);
+        assert_eq!(rx2.recv().unwrap(), Some(x));
+        assert_eq!(rx3.recv().unwrap(), None);
+    }
+
+    #[test]
+    fn rep3_range_check_vec() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let bit_width = 32;
+        let x = vec![
+            ark_bn254::Fr::from(0u64),
+            ark_bn254::Fr::from(u32::MAX),
+            ark_bn254::Fr::from(1u64 << 32),
+            -ark_bn254::Fr::from(1u64),
+            ark_bn254::Fr::from(rng.gen::<u32>()),
+        ];
+        let should_result = vec![true, true, false, false, true];
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+
+        for (net, tx, x) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares.into_iter()
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(arithmetic::range_check_vec(&x, bit_width, &mut rep3).unwrap())
+            });
+        }
+        assert_eq!(rx1.recv().unwrap(), should_result);
+        assert_eq!(rx2.recv().unwrap(), should_result);
+        assert_eq!(rx3.recv().unwrap(), should_result);
+    }
+
+    #[test]
+    fn rep3_verify_input_commitment() {
+        let mut rng = thread_rng();
+        let inputs = BTreeMap::from([(
+            "a".to_owned(),
+            (0..10)
+                .map(|_| ark_bn254::Fr::rand(&mut rng))
+                .collect::<Vec<_>>(),
+        )]);
+        let (commitment, randomness) =
+            InputCommitment::<ark_bn254::G1Projective>::commit(&inputs, &mut rng);
+
+        // the same committed input is accepted in multiple sessions, a different input is rejected
+        let mut other = inputs.clone();
+        other.get_mut("a").unwrap()[0] += ark_bn254::Fr::one();
+        for (inputs, should_result) in [(&inputs, true), (&inputs, true), (&other, false)] {
+            let test_network = Rep3TestNetwork::default();
+            let x_shares = rep3::share_field_elements(&inputs["a"], &mut rng);
+            let r_shares = rep3::share_field_element(randomness, &mut rng);
+            let (tx1, rx1) = mpsc::channel();
+            let (tx2, rx2) = mpsc::channel();
+            let (tx3, rx3) = mpsc::channel();
+
+            for (net, tx, x, r) in izip!(
+                test_network.get_party_networks(),
+                [tx1, tx2, tx3],
+                x_shares.into_iter(),
+                r_shares.into_iter()
+            ) {
+                let commitment = commitment.clone();
+                thread::spawn(move || {
+                    let mut rep3 = IoContext::init(net).unwrap();
+                    let mut input = SharedInput::default();
+                    input.add_shared_input("a".to_owned(), x);
+                    tx.send(commitment.verify_shared(&input, r, &mut rep3).is_ok())
+                });
+            }
+            assert_eq!(rx1.recv().unwrap(), should_result);
+            assert_eq!(rx2.recv().unwrap(), should_result);
+            assert_eq!(rx3.recv().unwrap(), should_result);
+        }
+    }
+
+    #[test]
+    fn rep3_open_vec_to_receiver() {
+        let mut rng = thread_rng();
+        let x = (0..10)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let receiver = ResultReceiverKey::<ark_bn254::G1Projective>::generate(&mut rng);
+        let public_key = receiver.public_key();
+
+        let encrypted = izip!(
+            [PartyID::ID0, PartyID::ID1, PartyID::ID2],
+            x_shares.into_iter()
+        )
+        .map(|(id, x)| arithmetic::open_vec_to_receiver(&x, &public_key, id, &mut thread_rng()))
+        .collect::<Vec<_>>();
+        // no single party can reconstruct the values
+        for encrypted in encrypted.iter() {
+            assert_ne!(encrypted.values, x);
+        }
+        let is_result = receiver.decrypt(encrypted.try_into().unwrap()).unwrap();
+        assert_eq!(is_result, x);
+    }
+
     #[test]
     fn rep3_neg() {
         let mut rng = thread_rng();
@@ -578,6 +905,62 @@ mod field_share {
     bool_op_test!(gt, >);
     bool_op_test!(ge, >=);
 
+    macro_rules! bool_op_many_test {
+        ($name: ident, $op: tt) => {
+            paste::item! {
+                #[test]
+                fn [< $name _many >]() {
+                    const VEC_SIZE: usize = 10;
+
+                    let test_network = Rep3TestNetwork::default();
+                    let mut rng = thread_rng();
+                    let x = (0..VEC_SIZE)
+                        .map(|_| ark_bn254::Fr::rand(&mut rng))
+                        .collect_vec();
+                    // also compare some equal and neighbouring values
+                    let y = x
+                        .iter()
+                        .enumerate()
+                        .map(|(i, x)| match i % 4 {
+                            0 => *x,
+                            1 => *x + ark_bn254::Fr::one(),
+                            2 => *x - ark_bn254::Fr::one(),
+                            _ => ark_bn254::Fr::rand(&mut rng),
+                        })
+                        .collect_vec();
+                    let should_result = izip!(x.iter(), y.iter())
+                        .map(|(x, y)| ark_bn254::Fr::from(x $op y))
+                        .collect_vec();
+                    let x_shares = rep3::share_field_elements(&x, &mut rng);
+                    let y_shares = rep3::share_field_elements(&y, &mut rng);
+                    let (tx1, rx1) = mpsc::channel();
+                    let (tx2, rx2) = mpsc::channel();
+                    let (tx3, rx3) = mpsc::channel();
+                    for (net, tx, x, y) in izip!(
+                        test_network.get_party_networks(),
+                        [tx1, tx2, tx3],
+                        x_shares,
+                        y_shares
+                    ) {
+                        thread::spawn(move || {
+                            let mut rep3 = IoContext::init(net).unwrap();
+                            tx.send(arithmetic::[< $name _many >](&x, &y, &mut rep3).unwrap())
+                        });
+                    }
+                    let result1 = rx1.recv().unwrap();
+                    let result2 = rx2.recv().unwrap();
+                    let result3 = rx3.recv().unwrap();
+                    let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+                    assert_eq!(is_result, should_result);
+                }
+            }
+        };
+    }
+    bool_op_many_test!(lt, <);
+    bool_op_many_test!(le, <=);
+    bool_op_many_test!(gt, >);
+    bool_op_many_test!(ge, >=);
+
     #[test]
     fn rep3_a2b_zero() {
         let test_network = Rep3TestNetwork::default();
@@ -736,11 +1119,39 @@ mod field_share {
     }
 
     #[test]
-    fn rep3_a2y2b_streaming() {
+    fn rep3_a2b_auto_select() {
+        use conversion::{A2BAutoNetwork, A2BType};
+        let bitlen = ark_bn254::Fr::MODULUS_BIT_SIZE as usize;
+        let network = A2BAutoNetwork::default();
+        assert_eq!(A2BType::Auto.select(1, bitlen, &network), A2BType::Yao);
+        assert_eq!(
+            A2BType::Auto.select(10_000, bitlen, &network),
+            A2BType::Direct
+        );
+        assert_eq!(A2BType::Direct.select(1, bitlen, &network), A2BType::Direct);
+        assert_eq!(A2BType::Yao.select(10_000, bitlen, &network), A2BType::Yao);
+        // on a fast local network, the bandwidth dominates already for a single value
+        let local = A2BAutoNetwork {
+            latency_us: 10,
+            bandwidth_mbit: 1_000,
+        };
+        assert_eq!(A2BType::Auto.select(1, bitlen, &local), A2BType::Direct);
+        // on a slow wide area network, the rounds dominate even for larger batches
+        let wan = A2BAutoNetwork {
+            latency_us: 100_000,
+            bandwidth_mbit: 1_000,
+        };
+        assert_eq!(A2BType::Auto.select(1_000, bitlen, &wan), A2BType::Yao);
+    }
+
+    #[test]
+    fn rep3_a2b_b2a_auto() {
         let test_network = Rep3TestNetwork::default();
         let mut rng = thread_rng();
-        let x = ark_bn254::Fr::rand(&mut rng);
-        let x_shares = rep3::share_field_element(x, &mut rng);
+        let x = (0..100)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
 
         let (tx1, rx1) = mpsc::channel();
         let (tx2, rx2) = mpsc::channel();
@@ -753,26 +1164,109 @@ mod field_share {
         {
             thread::spawn(move || {
                 let mut rep3 = IoContext::init(net).unwrap();
-                tx.send(conversion::a2y2b_streaming(x, &mut rep3).unwrap())
+                rep3.set_a2b_type(conversion::A2BType::Auto);
+                let binary = conversion::a2b_selector_many(&x, &mut rep3).unwrap();
+                let arithmetic = binary
+                    .iter()
+                    .map(|x| conversion::b2a_selector(x, &mut rep3).unwrap())
+                    .collect_vec();
+                tx.send((binary, arithmetic))
             });
         }
-        let result1 = rx1.recv().unwrap();
-        let result2 = rx2.recv().unwrap();
-        let result3 = rx3.recv().unwrap();
-        let is_result = rep3::combine_binary_element(result1, result2, result3);
+        let (binary1, arithmetic1) = rx1.recv().unwrap();
+        let (binary2, arithmetic2) = rx2.recv().unwrap();
+        let (binary3, arithmetic3) = rx3.recv().unwrap();
+        for (x, b1, b2, b3) in izip!(&x, binary1, binary2, binary3) {
+            let should_result: BigUint = (*x).into();
+            assert_eq!(rep3::combine_binary_element(b1, b2, b3), should_result);
+        }
+        let is_result = rep3::combine_field_elements(&arithmetic1, &arithmetic2, &arithmetic3);
+        assert_eq!(is_result, x);
+    }
 
-        let should_result = x.into();
-        assert_eq!(is_result, should_result);
-        let is_result_f: ark_bn254::Fr = is_result.into();
-        assert_eq!(is_result_f, x);
+    #[test]
+    fn rep3_batched_ops_direct_and_yao() {
+        const VEC_SIZE: usize = 10;
+        const BIT_WIDTH: usize = 32;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let mut x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let mut y = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        // equal values and the extremes of the field
+        y[0] = x[0];
+        x[1] = ark_bn254::Fr::zero();
+        y[1] = -ark_bn254::Fr::one();
+        x[2] = ark_bn254::Fr::from(rng.gen::<u32>());
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let y_shares = rep3::share_field_elements(&y, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, y) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let mut results = Vec::new();
+                for a2b_type in [conversion::A2BType::Direct, conversion::A2BType::Yao] {
+                    rep3.set_a2b_type(a2b_type);
+                    let ge = arithmetic::ge_many(&x, &y, &mut rep3).unwrap();
+                    let bits = arithmetic::to_bits_many(&x, BIT_WIDTH, &mut rep3).unwrap();
+                    let range = arithmetic::range_check_vec(&x, BIT_WIDTH, &mut rep3).unwrap();
+                    results.push((ge, bits, range));
+                }
+                tx.send(results)
+            });
+        }
+        let results1 = rx1.recv().unwrap();
+        let results2 = rx2.recv().unwrap();
+        let results3 = rx3.recv().unwrap();
+        let mut combined = Vec::new();
+        for ((ge1, bits1, range1), (ge2, bits2, range2), (ge3, bits3, range3)) in
+            izip!(results1, results2, results3)
+        {
+            let ge = rep3::combine_field_elements(&ge1, &ge2, &ge3);
+            let bits = izip!(bits1, bits2, bits3)
+                .map(|(b1, b2, b3)| rep3::combine_field_elements(&b1, &b2, &b3))
+                .collect_vec();
+            assert_eq!(range1, range2);
+            assert_eq!(range1, range3);
+            combined.push((ge, bits, range1));
+        }
+        // both backends give the same results
+        assert_eq!(combined[0], combined[1]);
+
+        let (ge, bits, range) = &combined[0];
+        for (x, y, ge, bits, range) in izip!(&x, &y, ge, bits, range) {
+            assert_eq!(*ge, ark_bn254::Fr::from(x >= y));
+            let x: BigUint = (*x).into();
+            let should_bits = (0..BIT_WIDTH as u64)
+                .map(|i| ark_bn254::Fr::from(x.bit(i)))
+                .collect_vec();
+            assert_eq!(*bits, should_bits);
+            assert_eq!(*range, x.bits() <= BIT_WIDTH as u64);
+        }
     }
 
     #[test]
-    fn rep3_b2a() {
+    fn rep3_a2y2b_many() {
+        const VEC_SIZE: usize = 10;
+
         let test_network = Rep3TestNetwork::default();
         let mut rng = thread_rng();
-        let x = ark_bn254::Fr::rand(&mut rng);
-        let x_shares = rep3::share_biguint(x, &mut rng);
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
 
         let (tx1, rx1) = mpsc::channel();
         let (tx2, rx2) = mpsc::channel();
@@ -785,22 +1279,32 @@ mod field_share {
         {
             thread::spawn(move || {
                 let mut rep3 = IoContext::init(net).unwrap();
-                tx.send(conversion::b2a(&x, &mut rep3).unwrap())
+                tx.send(conversion::a2y2b_many(&x, &mut rep3).unwrap())
             });
         }
         let result1 = rx1.recv().unwrap();
         let result2 = rx2.recv().unwrap();
         let result3 = rx3.recv().unwrap();
-        let is_result = rep3::combine_field_element(result1, result2, result3);
-        assert_eq!(is_result, x);
+        assert_eq!(result1.len(), VEC_SIZE);
+        for (x, r1, r2, r3) in izip!(x, result1, result2, result3) {
+            let is_result = rep3::combine_binary_element(r1, r2, r3);
+            let should_result: BigUint = x.into();
+            assert_eq!(is_result, should_result);
+        }
     }
 
     #[test]
-    fn rep3_b2y2a() {
+    fn rep3_a2b_many() {
+        const VEC_SIZE: usize = 10;
+
         let test_network = Rep3TestNetwork::default();
         let mut rng = thread_rng();
-        let x = ark_bn254::Fr::rand(&mut rng);
-        let x_shares = rep3::share_biguint(x, &mut rng);
+        let mut x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        x.push(ark_bn254::Fr::zero());
+        x.push(-ark_bn254::Fr::one());
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
 
         let (tx1, rx1) = mpsc::channel();
         let (tx2, rx2) = mpsc::channel();
@@ -813,51 +1317,2533 @@ mod field_share {
         {
             thread::spawn(move || {
                 let mut rep3 = IoContext::init(net).unwrap();
-                tx.send(conversion::b2y2a(&x, &mut rep3).unwrap())
+                tx.send(conversion::a2b_many(&x, &mut rep3).unwrap())
             });
         }
         let result1 = rx1.recv().unwrap();
         let result2 = rx2.recv().unwrap();
         let result3 = rx3.recv().unwrap();
-        let is_result = rep3::combine_field_element(result1, result2, result3);
-        assert_eq!(is_result, x);
+        assert_eq!(result1.len(), x.len());
+        for (x, r1, r2, r3) in izip!(x, result1, result2, result3) {
+            let is_result = rep3::combine_binary_element(r1, r2, r3);
+            let should_result: BigUint = x.into();
+            assert_eq!(is_result, should_result);
+        }
     }
 
     #[test]
-    fn rep3_b2y2a_streaming() {
+    fn rep3_preprocessed_bits() {
+        const VEC_SIZE: usize = 20;
+        const BITSIZE: usize = 32;
+
         let test_network = Rep3TestNetwork::default();
         let mut rng = thread_rng();
-        let x = ark_bn254::Fr::rand(&mut rng);
-        let x_shares = rep3::share_biguint(x, &mut rng);
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::from(rng.gen::<u32>()))
+            .collect_vec();
+        // some equal values to test the boundary
+        let y = x
+            .iter()
+            .map(|x| {
+                if rng.gen() {
+                    *x
+                } else {
+                    ark_bn254::Fr::from(rng.gen::<u32>())
+                }
+            })
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let y_shares = rep3::share_field_elements(&y, &mut rng);
+        let mut x_binary = [Vec::new(), Vec::new(), Vec::new()];
+        for x in x.iter() {
+            for (des, share) in x_binary.iter_mut().zip(rep3::share_biguint(*x, &mut rng)) {
+                des.push(share);
+            }
+        }
+        let should_ge = x
+            .iter()
+            .zip(y.iter())
+            .map(|(x, y)| ark_bn254::Fr::from(x >= y))
+            .collect_vec();
 
         let (tx1, rx1) = mpsc::channel();
         let (tx2, rx2) = mpsc::channel();
         let (tx3, rx3) = mpsc::channel();
-        for ((net, tx), x) in test_network
-            .get_party_networks()
-            .into_iter()
-            .zip([tx1, tx2, tx3])
-            .zip(x_shares.into_iter())
-        {
+        for (net, tx, x, y, x_binary) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares,
+            x_binary
+        ) {
             thread::spawn(move || {
                 let mut rep3 = IoContext::init(net).unwrap();
-                tx.send(conversion::b2y2a_streaming(&x, &mut rep3).unwrap())
+                let mut preprocessed = Rep3PreprocessedBits::<ark_bn254::Fr>::new();
+                preprocessed
+                    .generate_dabits(VEC_SIZE * (BITSIZE + 1), &mut rep3)
+                    .unwrap();
+                preprocessed
+                    .generate_edabits(VEC_SIZE, BITSIZE, &mut rep3)
+                    .unwrap();
+
+                // persist and restore the preprocessed bits
+                let mut bytes = Vec::new();
+                preprocessed.serialize_uncompressed(&mut bytes).unwrap();
+                let mut preprocessed =
+                    Rep3PreprocessedBits::deserialize_uncompressed(bytes.as_slice()).unwrap();
+
+                let ge =
+                    arithmetic::ge_many_with_edabits(&x, &y, BITSIZE, &mut preprocessed, &mut rep3)
+                        .unwrap();
+                let b2a = conversion::b2a_many_with_dabits(
+                    &x_binary,
+                    BITSIZE,
+                    &mut preprocessed,
+                    &mut rep3,
+                )
+                .unwrap();
+                assert_eq!(preprocessed.num_dabits(), 0);
+                assert_eq!(preprocessed.num_edabits(BITSIZE), 0);
+                tx.send((ge, b2a))
             });
         }
+        let (ge1, b2a1) = rx1.recv().unwrap();
+        let (ge2, b2a2) = rx2.recv().unwrap();
+        let (ge3, b2a3) = rx3.recv().unwrap();
+        let is_ge = rep3::combine_field_elements(&ge1, &ge2, &ge3);
+        let is_b2a = rep3::combine_field_elements(&b2a1, &b2a2, &b2a3);
+        assert_eq!(is_ge, should_ge);
+        assert_eq!(is_b2a, x);
+    }
+
+    #[test]
+    fn rep3_b2a_selector_many() {
+        const VEC_SIZE: usize = 10;
+        const BITSIZE: usize = 16;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::from(rng.gen_range(0u64..1 << BITSIZE)))
+            .collect_vec();
+        let mut x_binary = [Vec::new(), Vec::new(), Vec::new()];
+        for x in x.iter() {
+            for (des, share) in x_binary.iter_mut().zip(rep3::share_biguint(*x, &mut rng)) {
+                des.push(share);
+            }
+        }
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x) in izip!(test_network.get_party_networks(), [tx1, tx2, tx3], x_binary) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let mut preprocessed = Rep3PreprocessedBits::new();
+                preprocessed
+                    .generate_dabits(VEC_SIZE * BITSIZE, &mut rep3)
+                    .unwrap();
+                let context = conversion::b2a_selector_many(
+                    &x,
+                    BITSIZE,
+                    conversion::B2AMethod::Context,
+                    &mut rep3,
+                )
+                .unwrap();
+                let dabits = conversion::b2a_selector_many(
+                    &x,
+                    BITSIZE,
+                    conversion::B2AMethod::DaBits(&mut preprocessed),
+                    &mut rep3,
+                )
+                .unwrap();
+                assert_eq!(preprocessed.num_dabits(), 0);
+                tx.send((context, dabits))
+            });
+        }
+        let (context1, dabits1) = rx1.recv().unwrap();
+        let (context2, dabits2) = rx2.recv().unwrap();
+        let (context3, dabits3) = rx3.recv().unwrap();
+        let is_context = rep3::combine_field_elements(&context1, &context2, &context3);
+        let is_dabits = rep3::combine_field_elements(&dabits1, &dabits2, &dabits3);
+        assert_eq!(is_context, x);
+        assert_eq!(is_dabits, x);
+    }
+
+    #[test]
+    fn rep3_malicious_security() {
+        const VEC_SIZE: usize = 10;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let y = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let y_shares = rep3::share_field_elements(&y, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, y) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                rep3.set_security_level(SecurityLevel::Malicious);
+                let mul = arithmetic::mul_vec(&x, &y, &mut rep3).unwrap();
+                let single = arithmetic::mul(x[0], y[0], &mut rep3).unwrap();
+                let opened = arithmetic::open_vec(&mul, &mut rep3).unwrap();
+                let opened_single = arithmetic::open(single, &mut rep3).unwrap();
+                // an additive error in the result of a multiplication is detected
+                let cheated = mul
+                    .iter()
+                    .map(|z| arithmetic::add_public(*z, ark_bn254::Fr::one(), rep3.id))
+                    .collect_vec();
+                let detected = malicious::verify_mul_triples(&x, &y, &cheated, &mut rep3).is_err();
+                tx.send((opened, opened_single, detected))
+            });
+        }
+        let should_result = izip!(&x, &y).map(|(x, y)| x * y).collect_vec();
+        for rx in [rx1, rx2, rx3] {
+            let (opened, opened_single, detected) = rx.recv().unwrap();
+            assert_eq!(opened, should_result);
+            assert_eq!(opened_single, should_result[0]);
+            assert!(detected);
+        }
+    }
+
+    #[test]
+    fn rep3_malicious_security_openings_and_unsupported() {
+        const VEC_SIZE: usize = 10;
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let y = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let y_shares = rep3::share_field_elements(&y, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, y) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                rep3.set_security_level(SecurityLevel::Malicious);
+                let mul_opened = arithmetic::mul_open_vec(&x, &y, &mut rep3).unwrap();
+                let opened_to = arithmetic::open_vec_to(&x, PartyID::ID1, &mut rep3).unwrap();
+                // operations without an actively secure variant are rejected
+                let a2b = conversion::a2b(x[0], &mut rep3).unwrap_err().kind();
+                let shuffle = gadgets::shuffle::shuffle(&x, &mut rep3).unwrap_err().kind();
+                tx.send((mul_opened, opened_to, a2b, shuffle))
+            });
+        }
+        let should_result = izip!(&x, &y).map(|(x, y)| x * y).collect_vec();
+        for (id, rx) in [rx1, rx2, rx3].into_iter().enumerate() {
+            let (mul_opened, opened_to, a2b, shuffle) = rx.recv().unwrap();
+            assert_eq!(mul_opened, should_result);
+            if id == 1 {
+                assert_eq!(opened_to, Some(x.clone()));
+            } else {
+                assert_eq!(opened_to, None);
+            }
+            assert_eq!(a2b, std::io::ErrorKind::Unsupported);
+            assert_eq!(shuffle, std::io::ErrorKind::Unsupported);
+        }
+    }
+
+    #[test]
+    fn rep3_cost_estimation() {
+        const VEC_SIZE: usize = 10;
+
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let y = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let y_shares = rep3::share_field_elements(&y, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, y) in izip!(
+            Rep3LocalNetwork::new_parties(),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares
+        ) {
+            thread::spawn(move || {
+                let tracker = CostTracker::new();
+                let net = Rep3CountingNetwork::new(net, tracker.clone());
+                tracker.set_phase("setup");
+                let mut rep3 = IoContext::init(net).unwrap();
+                tracker.set_phase("mul");
+                let mul = arithmetic::mul_vec(&x, &y, &mut rep3).unwrap();
+                tracker.set_phase("open");
+                let opened = arithmetic::open_vec(&mul, &mut rep3).unwrap();
+                tx.send((opened, tracker.report()))
+            });
+        }
+        let should_result = izip!(&x, &y).map(|(x, y)| x * y).collect_vec();
+        for rx in [rx1, rx2, rx3] {
+            let (opened, report) = rx.recv().unwrap();
+            assert_eq!(opened, should_result);
+            assert_eq!(report["setup"].rounds, 2);
+            for phase in ["mul", "open"] {
+                assert_eq!(report[phase].rounds, 1);
+                assert_eq!(report[phase].messages_sent, 1);
+                assert_eq!(report[phase].elements_sent, VEC_SIZE);
+                assert_eq!(report[phase].elements_received, VEC_SIZE);
+            }
+        }
+    }
+
+    #[test]
+    fn rep3_simulated_wan() {
+        const VEC_SIZE: usize = 10;
+        const LATENCY: Duration = Duration::from_millis(50);
+
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let y = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let y_shares = rep3::share_field_elements(&y, &mut rng);
+
+        let link = LinkConfig::new(LATENCY, Some(10_000_000))
+            .with_jitter(Duration::from_millis(5))
+            .with_drop_rate(0.01);
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, y) in izip!(
+            Rep3LocalNetwork::with_link(link),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares
+        ) {
+            thread::spawn(move || {
+                let start = Instant::now();
+                let mut rep3 = IoContext::init(net).unwrap();
+                let mul = arithmetic::mul_vec(&x, &y, &mut rep3).unwrap();
+                let opened = arithmetic::open_vec(&mul, &mut rep3).unwrap();
+                tx.send((opened, start.elapsed()))
+            });
+        }
+        let should_result = izip!(&x, &y).map(|(x, y)| x * y).collect_vec();
+        for rx in [rx1, rx2, rx3] {
+            let (opened, elapsed) = rx.recv().unwrap();
+            assert_eq!(opened, should_result);
+            // two rounds for the setup, one for the multiplication, and one for the opening
+            assert!(elapsed >= LATENCY * 4);
+        }
+    }
+
+    #[test]
+    fn rep3_provisioning() {
+        const VEC_SIZE: usize = 10;
+        const LATENCY: Duration = Duration::from_millis(20);
+
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let y = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let y_shares = rep3::share_field_elements(&y, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (dry_net, net, tx, x, y) in izip!(
+            Rep3LocalNetwork::new_parties(),
+            Rep3LocalNetwork::with_link(LinkConfig::new(LATENCY, None)),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares
+        ) {
+            thread::spawn(move || {
+                // the dry run derives the schedule of the job
+                let tracker = CostTracker::new();
+                let mut rep3 =
+                    IoContext::init(Rep3CountingNetwork::new(dry_net, tracker.clone())).unwrap();
+                arithmetic::mul_vec(&x, &y, &mut rep3).unwrap();
+                let schedule = tracker.total();
+
+                let mut net = net;
+                let config = ProvisioningConfig::new(Duration::from_secs(10))
+                    .with_probe_len(1024)
+                    .with_latency_probes(2);
+                let report = provisioning::provision_rep3(&mut net, &schedule, &config).unwrap();
+                let tight = ProvisioningConfig {
+                    budget: LATENCY,
+                    ..config
+                };
+                let err = provisioning::provision_rep3(&mut net, &schedule, &tight).unwrap_err();
+                tx.send((report, ProvisioningError::from_io_error(&err).cloned()))
+            });
+        }
+        for rx in [rx1, rx2, rx3] {
+            let (report, err) = rx.recv().unwrap();
+            // the parties do not start the probes at exactly the same time
+            assert!(report.latency >= LATENCY / 2);
+            // two rounds for the setup and one for the multiplication
+            assert!(report.estimate >= report.latency * 3);
+            assert!(matches!(
+                err,
+                Some(ProvisioningError::BudgetExceeded { budget, .. }) if budget == LATENCY
+            ));
+        }
+    }
+
+    #[test]
+    fn rep3_fault_injection() {
+        let schedule = FaultSchedule::new()
+            .with_fault(0, 0, Fault::Corrupt { offset: 8, mask: 1 })
+            .with_fault(0, 1, Fault::Truncate { len: 4 })
+            .with_fault(0, 2, Fault::Drop)
+            .with_fault(0, 3, Fault::Reorder);
+        let [net0, net1, _] = Rep3LocalNetwork::new_parties();
+        let mut net0 = Rep3FaultyNetwork::new(net0, FaultSchedule::new());
+        let mut net1 = Rep3FaultyNetwork::new(net1, schedule);
+        for i in 0..5u64 {
+            net0.send_many(PartyID::ID1, &[i, i]).unwrap();
+        }
+        assert_eq!(net1.recv_many::<u64>(PartyID::ID0).unwrap(), vec![1, 0]);
+        let err = net1.recv_many::<u64>(PartyID::ID0).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        let err = net1.recv_many::<u64>(PartyID::ID0).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        assert_eq!(net1.recv_many::<u64>(PartyID::ID0).unwrap(), vec![4, 4]);
+        assert_eq!(net1.recv_many::<u64>(PartyID::ID0).unwrap(), vec![3, 3]);
+    }
+
+    #[test]
+    fn rep3_abort() {
+        let [net0, net1, net2] = Rep3LocalNetwork::new_parties();
+        let mut net0 = Rep3FaultyNetwork::new(net0, FaultSchedule::new());
+        net0.abort(AbortReason::Inconsistency("bad commitment".to_owned()));
+        let threads = [net1, net2].map(|mut net| {
+            thread::spawn(move || {
+                let err = net.broadcast(1u64).unwrap_err();
+                // return the network, such that it is not dropped before the other party is done
+                (net, Abort::from_io_error(&err).cloned())
+            })
+        });
+        let results = threads.map(|thread| thread.join().unwrap());
+        for (_, abort) in results {
+            let abort = abort.expect("an abort");
+            assert_eq!(abort.party, 0);
+            assert_eq!(
+                abort.reason,
+                AbortReason::Inconsistency("bad commitment".to_owned())
+            );
+        }
+    }
+
+    #[test]
+    fn rep3_consistent_broadcast() {
+        let mut rng = thread_rng();
+        let x = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+
+        let test_network = Rep3TestNetwork::default();
+        let mut threads = Vec::with_capacity(3);
+        for mut net in test_network.get_party_networks() {
+            let x = x.clone();
+            threads.push(thread::spawn(move || {
+                if net.get_id() == PartyID::ID1 {
+                    net.send_broadcast_many(&x).unwrap();
+                    x
+                } else {
+                    net.recv_broadcast_many(PartyID::ID1).unwrap()
+                }
+            }));
+        }
+        for t in threads {
+            assert_eq!(t.join().unwrap(), x);
+        }
+    }
+
+    #[test]
+    fn rep3_inconsistent_broadcast_detected() {
+        let test_network = Rep3TestNetwork::default();
+        let mut threads = Vec::with_capacity(3);
+        for mut net in test_network.get_party_networks() {
+            threads.push(thread::spawn(move || {
+                if net.get_id() == PartyID::ID0 {
+                    // the sender sends different values to the other parties
+                    net.send(PartyID::ID1, ark_bn254::Fr::from(1u64))?;
+                    net.send(PartyID::ID2, ark_bn254::Fr::from(2u64))?;
+                    net.broadcast(vec![0u8; 32])?;
+                    Ok(None)
+                } else {
+                    net.recv_broadcast::<ark_bn254::Fr>(PartyID::ID0).map(Some)
+                }
+            }));
+        }
+        let results = threads.into_iter().map(|t| t.join().unwrap()).collect_vec();
+        assert!(results[1].is_err());
+        assert!(results[2].is_err());
+    }
+
+    #[test]
+    fn rep3_ratchet_rngs() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let x_shares = rep3::share_field_element(x, &mut rng);
+        let y_shares = rep3::share_field_element(y, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, y) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let mut results = vec![arithmetic::mul(x, y, &mut rep3).unwrap()];
+                for job_id in ["job-0", "job-1"] {
+                    rep3.ratchet(job_id);
+                    results.push(arithmetic::mul(x, y, &mut rep3).unwrap());
+                }
+                tx.send(results)
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        assert_eq!(is_result, vec![x * y; 3]);
+        // the masks of the multiplications differ after ratcheting
+        assert_ne!(result1[0], result1[1]);
+        assert_ne!(result1[1], result1[2]);
+    }
+
+    #[test]
+    fn rep3_prss_state_sessions() {
+        const VEC_SIZE: usize = 10;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let y = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let y_shares = rep3::share_field_elements(&y, &mut rng);
+        let should_mul = x.iter().zip(y.iter()).map(|(x, y)| x * y).collect_vec();
+        let state_name = format!("rep3-prss-test-{}", rng.gen::<u64>());
+        let dir = std::env::temp_dir();
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, y) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares
+        ) {
+            let state_name = state_name.clone();
+            let dir = dir.clone();
+            thread::spawn(move || {
+                // setup once
+                let mut rep3 = IoContext::init(net).unwrap();
+                let id = rep3.id;
+                let path = dir.join(format!("{state_name}.party{id}"));
+                Rep3PrssState::new(id, &mut rep3.rngs).save(&path).unwrap();
+                let mut net = rep3.network;
+
+                // resume two sessions from the persisted state
+                let mut results = Vec::new();
+                for session_id in ["session-a", "session-b"] {
+                    let mut state = Rep3PrssState::load(&path, id).unwrap();
+                    let mut rep3 = IoContext::init_session(net, &mut state, session_id).unwrap();
+                    state.save(&path).unwrap();
+                    results.push(arithmetic::mul_vec(&x, &y, &mut rep3).unwrap());
+                    net = rep3.network;
+                }
+                let state = Rep3PrssState::load(&path, id).unwrap();
+                assert_eq!(state.epoch(), 2);
+                std::fs::remove_file(path).unwrap();
+                tx.send(results)
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        for (r1, r2, r3) in izip!(result1, result2, result3) {
+            let is_result = rep3::combine_field_elements(&r1, &r2, &r3);
+            assert_eq!(is_result, should_mul);
+        }
+    }
+
+    #[test]
+    fn rep3_offline_online_preprocessing() {
+        const VEC_SIZE: usize = 10;
+        const BITSIZE: usize = 16;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::from(rng.gen::<u16>()))
+            .collect_vec();
+        let y = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::from(rng.gen::<u16>()))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let y_shares = rep3::share_field_elements(&y, &mut rng);
+        let should_lt = x
+            .iter()
+            .zip(y.iter())
+            .map(|(x, y)| ark_bn254::Fr::from(x < y))
+            .collect_vec();
+        let should_mul = x.iter().zip(y.iter()).map(|(x, y)| x * y).collect_vec();
+        let session_id = format!("rep3-test-{}", rng.gen::<u64>());
+        let dir = std::env::temp_dir();
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, y) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares
+        ) {
+            let session_id = session_id.clone();
+            let dir = dir.clone();
+            thread::spawn(move || {
+                // offline phase
+                let mut rep3 = IoContext::init(net).unwrap();
+                let id = rep3.id;
+                let preprocessing = Rep3Preprocessing::<ark_bn254::Fr>::generate(
+                    &mut rep3,
+                    VEC_SIZE,
+                    &[(VEC_SIZE, BITSIZE)],
+                )
+                .unwrap();
+                let path = preprocessing.save(&dir, &session_id).unwrap();
+
+                // online phase
+                let preprocessing = Rep3Preprocessing::load(&dir, &session_id, id).unwrap();
+                std::fs::remove_file(path).unwrap();
+                let (mut rep3, mut preprocessed) = preprocessing.into_online(rep3.network).unwrap();
+                let lt =
+                    arithmetic::lt_many_with_edabits(&x, &y, BITSIZE, &mut preprocessed, &mut rep3)
+                        .unwrap();
+                let mul = arithmetic::mul_vec(&x, &y, &mut rep3).unwrap();
+                tx.send((lt, mul))
+            });
+        }
+        let (lt1, mul1) = rx1.recv().unwrap();
+        let (lt2, mul2) = rx2.recv().unwrap();
+        let (lt3, mul3) = rx3.recv().unwrap();
+        let is_lt = rep3::combine_field_elements(&lt1, &lt2, &lt3);
+        let is_mul = rep3::combine_field_elements(&mul1, &mul2, &mul3);
+        assert_eq!(is_lt, should_lt);
+        assert_eq!(is_mul, should_mul);
+    }
+
+    #[test]
+    fn rep3_pow_public_many() {
+        const VEC_SIZE: usize = 10;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let exponents = [0u64, 1, 2, 5, rng.gen()];
+        let should_result = exponents
+            .iter()
+            .map(|e| x.iter().map(|x| x.pow([*e])).collect_vec())
+            .collect_vec();
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x) in izip!(test_network.get_party_networks(), [tx1, tx2, tx3], x_shares) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let results = exponents
+                    .iter()
+                    .map(|e| {
+                        arithmetic::pow_public_many(&x, ark_bn254::Fr::from(*e), &mut rep3).unwrap()
+                    })
+                    .collect_vec();
+                tx.send(results)
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        for (r1, r2, r3, should_result) in izip!(result1, result2, result3, should_result) {
+            let is_result = rep3::combine_field_elements(&r1, &r2, &r3);
+            assert_eq!(is_result, should_result);
+        }
+    }
+
+    #[test]
+    fn rep3_ecc_add_and_msm() {
+        use gadgets::ecc::{PointShare, ProjectivePoint, ShortWeierstrassCurve};
+        const VEC_SIZE: usize = 4;
+        const NUM_BITS: usize = 32;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        // Grumpkin
+        let curve = ShortWeierstrassCurve::new(-ark_bn254::Fr::from(17u64));
+        let generator = ProjectivePoint::from_affine(
+            ark_bn254::Fr::one(),
+            ark_bn254::Fr::from_str("17631683881184975370165255887551781615748388533673675138860")
+                .unwrap(),
+            false,
+        );
+        let mut points = (0..VEC_SIZE - 1)
+            .map(|_| curve.scalar_mul(&generator, &BigUint::from(rng.gen::<u64>())))
+            .collect_vec();
+        points.push(ProjectivePoint::infinity());
+        let scalars = (0..VEC_SIZE)
+            .map(|_| BigUint::from(rng.gen::<u32>()))
+            .collect_vec();
+        // Covers additions of distinct points, doublings, and the point at infinity
+        let rhs = [points[1], points[1], points[3], points[0]];
+
+        let to_affine = |p: &ProjectivePoint<ark_bn254::Fr>| {
+            let (x, y, is_infinity) = p.to_affine();
+            [x, y, ark_bn254::Fr::from(is_infinity)]
+        };
+        let mut should_result = izip!(&points, &rhs)
+            .flat_map(|(p, q)| to_affine(&curve.add(p, q)))
+            .collect_vec();
+        should_result
+            .extend(izip!(&points, &scalars).flat_map(|(p, s)| to_affine(&curve.scalar_mul(p, s))));
+        should_result.extend(to_affine(&curve.msm(&points, &scalars)));
+        should_result.extend(to_affine(&curve.msm(&points, &scalars)));
+
+        let coordinate_shares = rep3::share_field_elements(
+            &points
+                .iter()
+                .chain(rhs.iter())
+                .flat_map(to_affine)
+                .collect_vec(),
+            &mut rng,
+        );
+        let scalar_shares = rep3::share_field_elements(
+            &scalars
+                .iter()
+                .map(|s| ark_bn254::Fr::from(s.to_owned()))
+                .collect_vec(),
+            &mut rng,
+        );
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, coordinates, scalars) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            coordinate_shares,
+            scalar_shares
+        ) {
+            let public_points = points.clone();
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let id = rep3.id;
+                let shared_points = coordinates
+                    .chunks_exact(3)
+                    .map(|c| PointShare::from_affine_shared(c[0], c[1], c[2], id))
+                    .collect_vec();
+                let (lhs, rhs) = shared_points.split_at(VEC_SIZE);
+                let mut result = curve.rep3_add_many(lhs, rhs, &mut rep3).unwrap();
+                result.extend(
+                    curve
+                        .rep3_scalar_mul_public_point_many(
+                            &public_points,
+                            &scalars,
+                            NUM_BITS,
+                            &mut rep3,
+                        )
+                        .unwrap(),
+                );
+                result.push(
+                    curve
+                        .rep3_msm_public_points(&public_points, &scalars, NUM_BITS, &mut rep3)
+                        .unwrap(),
+                );
+                result.push(curve.rep3_msm(lhs, &scalars, NUM_BITS, &mut rep3).unwrap());
+                let result = ShortWeierstrassCurve::rep3_to_affine_many(&result, &mut rep3)
+                    .unwrap()
+                    .into_iter()
+                    .flat_map(|(x, y, is_infinity)| [x, y, is_infinity])
+                    .collect_vec();
+                tx.send(result)
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        assert_eq!(is_result, should_result);
+    }
+
+    #[test]
+    fn rep3_ecdsa_verify() {
+        use ark_ec::{AffineRepr, CurveGroup, Group};
+        use ark_secp256k1::{Config, Fr, Projective};
+        use gadgets::ecdsa::{self, NUM_BYTES};
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let to_bytes = |x: &BigUint| {
+            let bytes = x.to_bytes_be();
+            let mut res = vec![0u8; NUM_BYTES - bytes.len()];
+            res.extend(bytes);
+            res
+        };
+
+        let secret_key = Fr::rand(&mut rng);
+        let public_key = (Projective::generator() * secret_key).into_affine();
+        let (x, y) = public_key.xy().unwrap();
+        let (x, y): (BigUint, BigUint) = ((*x).into(), (*y).into());
+        let hashed_message = BigUint::from_bytes_be(&rng.gen::<[u8; NUM_BYTES]>());
+        let nonce = Fr::rand(&mut rng);
+        let r: BigUint = (*(Projective::generator() * nonce).into_affine().x().unwrap()).into();
+        let r_scalar = Fr::from(r.to_owned());
+        let s = nonce.inverse().unwrap()
+            * (Fr::from(hashed_message.to_owned()) + r_scalar * secret_key);
+        let s: BigUint = s.into();
+
+        // A valid signature, a modified signature, a modified message, and s = 0
+        let inputs = [
+            (r.to_owned(), s.to_owned(), hashed_message.to_owned()),
+            (r.to_owned(), &s + 1u64, hashed_message.to_owned()),
+            (r.to_owned(), s.to_owned(), &hashed_message ^ BigUint::one()),
+            (r.to_owned(), BigUint::zero(), hashed_message.to_owned()),
+        ];
+        let should_result = inputs
+            .iter()
+            .map(|(r, s, e)| ark_bn254::Fr::from(ecdsa::verify::<Config>(&x, &y, r, s, e)))
+            .collect_vec();
+        assert_eq!(
+            should_result,
+            [1u64, 0, 0, 0].map(ark_bn254::Fr::from).to_vec()
+        );
+
+        let bytes = inputs
+            .iter()
+            .flat_map(|(r, s, e)| {
+                [&x, &y, r, s, e]
+                    .into_iter()
+                    .flat_map(to_bytes)
+                    .map(ark_bn254::Fr::from)
+                    .collect_vec()
+            })
+            .collect_vec();
+        let byte_shares = rep3::share_field_elements(&bytes, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, bytes) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            byte_shares
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let result = bytes
+                    .chunks_exact(5 * NUM_BYTES)
+                    .map(|b| {
+                        let (x, rest) = b.split_at(NUM_BYTES);
+                        let (y, rest) = rest.split_at(NUM_BYTES);
+                        let (signature, e) = rest.split_at(2 * NUM_BYTES);
+                        ecdsa::rep3_verify_bytes::<Config, _, _>(
+                            x.try_into().unwrap(),
+                            y.try_into().unwrap(),
+                            signature.try_into().unwrap(),
+                            e.try_into().unwrap(),
+                            &mut rep3,
+                        )
+                        .unwrap()
+                    })
+                    .collect_vec();
+                tx.send(result)
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        assert_eq!(is_result, should_result);
+    }
+
+    #[test]
+    fn rep3_extension_field_arithmetic() {
+        use ark_bls12_381::{Fq, Fq12, Fq2};
+        use rep3::extension_field;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = Fq12::rand(&mut rng);
+        let y = Fq12::rand(&mut rng);
+        let public = Fq12::rand(&mut rng);
+        let u = Fq2::rand(&mut rng);
+        let v = Fq2::rand(&mut rng);
+        let should_result12 = vec![
+            x * y,
+            x.inverse().unwrap(),
+            x.frobenius_map(1) * public + y,
+            x.square() - public,
+        ];
+        let should_result2 = vec![u * v, u.inverse().unwrap()];
+        let coefficients = x.to_base_prime_field_elements().collect_vec();
+
+        let x_shares = rep3::share_extension_field_element(x, &mut rng);
+        let y_shares = rep3::share_extension_field_element(y, &mut rng);
+        let u_shares = rep3::share_extension_field_element(u, &mut rng);
+        let v_shares = rep3::share_extension_field_element(v, &mut rng);
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, y, u, v) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares,
+            u_shares,
+            v_shares
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let id = rep3.id;
+                let result12 = vec![
+                    extension_field::mul(x, y, &mut rep3).unwrap(),
+                    extension_field::inv(x, &mut rep3).unwrap(),
+                    extension_field::add(
+                        extension_field::mul_public(extension_field::frobenius_map(x, 1), public),
+                        y,
+                    ),
+                    extension_field::add_public(
+                        extension_field::square(x, &mut rep3).unwrap(),
+                        -public,
+                        id,
+                    ),
+                ];
+                let mut result2 = extension_field::mul_vec(&[u], &[v], &mut rep3).unwrap();
+                result2.extend(extension_field::inv_vec(&[u], &mut rep3).unwrap());
+                let coefficients = extension_field::to_base_field_shares(x);
+                let roundtrip =
+                    extension_field::from_base_field_shares::<Fq12>(&coefficients).unwrap();
+                tx.send((result12, result2, coefficients, roundtrip == x))
+            });
+        }
+        let results = [rx1, rx2, rx3].map(|rx| rx.recv().unwrap());
+        let [r1, r2, r3] = results;
+        let is_result12 = izip!(r1.0, r2.0, r3.0)
+            .map(|(a, b, c)| rep3::combine_extension_field_element(a, b, c))
+            .collect_vec();
+        let is_result2 = izip!(r1.1, r2.1, r3.1)
+            .map(|(a, b, c)| rep3::combine_extension_field_element(a, b, c))
+            .collect_vec();
+        let is_coefficients: Vec<Fq> = rep3::combine_field_elements(&r1.2, &r2.2, &r3.2);
+        assert_eq!(is_result12, should_result12);
+        assert_eq!(is_result2, should_result2);
+        assert_eq!(is_coefficients, coefficients);
+        assert!(r1.3 && r2.3 && r3.3);
+    }
+
+    #[test]
+    fn rep3_mat_mul_and_conv2d() {
+        use gadgets::linear_algebra::{self, Conv2dShape};
+        const M: usize = 3;
+        const K: usize = 4;
+        const N: usize = 2;
+        let shape = Conv2dShape {
+            in_channels: 2,
+            height: 5,
+            width: 4,
+            out_channels: 3,
+            kernel_height: 3,
+            kernel_width: 2,
+            stride: 2,
+            padding: 1,
+        };
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let mut random_vec = |len: usize| {
+            (0..len)
+                .map(|_| ark_bn254::Fr::rand(&mut rng))
+                .collect_vec()
+        };
+        let a = random_vec(M * K);
+        let b = random_vec(K * N);
+        let input = random_vec(shape.in_channels * shape.height * shape.width);
+        let kernel = random_vec(
+            shape.out_channels * shape.in_channels * shape.kernel_height * shape.kernel_width,
+        );
+
+        let mut should_result = (0..M)
+            .flat_map(|i| (0..N).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                (0..K)
+                    .map(|l| a[i * K + l] * b[l * N + j])
+                    .sum::<ark_bn254::Fr>()
+            })
+            .collect_vec();
+        should_result.extend((0..M).map(|i| {
+            (0..K)
+                .map(|l| a[i * K + l] * b[l * N])
+                .sum::<ark_bn254::Fr>()
+        }));
+        let (out_height, out_width) = (shape.output_height(), shape.output_width());
+        assert_eq!((out_height, out_width), (3, 3));
+        let mut conv = vec![ark_bn254::Fr::zero(); shape.out_channels * out_height * out_width];
+        for (o, out) in conv.chunks_exact_mut(out_height * out_width).enumerate() {
+            for (pos, out) in out.iter_mut().enumerate() {
+                let (oy, ox) = (pos / out_width, pos % out_width);
+                for c in 0..shape.in_channels {
+                    for dy in 0..shape.kernel_height {
+                        for dx in 0..shape.kernel_width {
+                            let y = (oy * shape.stride + dy) as isize - shape.padding as isize;
+                            let x = (ox * shape.stride + dx) as isize - shape.padding as isize;
+                            if y < 0
+                                || x < 0
+                                || y >= shape.height as isize
+                                || x >= shape.width as isize
+                            {
+                                continue;
+                            }
+                            let (y, x) = (y as usize, x as usize);
+                            let k = ((o * shape.in_channels + c) * shape.kernel_height + dy)
+                                * shape.kernel_width
+                                + dx;
+                            *out += kernel[k] * input[(c * shape.height + y) * shape.width + x];
+                        }
+                    }
+                }
+            }
+        }
+        should_result.extend(conv.iter());
+        should_result.extend(conv.iter());
+
+        let a_shares = rep3::share_field_elements(&a, &mut rng);
+        let b_shares = rep3::share_field_elements(&b, &mut rng);
+        let input_shares = rep3::share_field_elements(&input, &mut rng);
+        let kernel_shares = rep3::share_field_elements(&kernel, &mut rng);
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, a, b, input, kernel_share) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            a_shares,
+            b_shares,
+            input_shares,
+            kernel_shares
+        ) {
+            let kernel = kernel.clone();
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let mut result = linear_algebra::mat_mul(&a, &b, M, K, N, &mut rep3).unwrap();
+                let x = b.iter().step_by(N).copied().collect_vec();
+                result.extend(linear_algebra::mat_vec_mul(&a, &x, M, K, &mut rep3).unwrap());
+                result.extend(
+                    linear_algebra::conv2d(&input, &kernel_share, &shape, &mut rep3).unwrap(),
+                );
+                result.extend(linear_algebra::conv2d_public_kernel(
+                    &input, &kernel, &shape,
+                ));
+                tx.send(result)
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        assert_eq!(is_result, should_result);
+    }
+
+    #[test]
+    fn rep3_mimc_prf_many() {
+        const VEC_SIZE: usize = 10;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let prf = gadgets::mimc::MimcPrf::<ark_bn254::Fr>::default();
+        let key = ark_bn254::Fr::rand(&mut rng);
+        let mut inputs = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        // Duplicates result in equal outputs
+        inputs.push(inputs[0]);
+        inputs.push(inputs[3]);
+        let key_shares = rep3::share_field_element(key, &mut rng);
+        let x_shares = rep3::share_field_elements(&inputs, &mut rng);
+        let should_result = inputs.iter().map(|x| prf.evaluate(key, *x)).collect_vec();
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, key, x, prf) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            key_shares,
+            x_shares,
+            [prf.clone(), prf.clone(), prf]
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let shared = prf.rep3_evaluate_many(key, &x, &mut rep3).unwrap();
+                let opened = prf.rep3_evaluate_and_open_many(key, &x, &mut rep3).unwrap();
+                tx.send((shared, opened))
+            });
+        }
+        let (result1, opened1) = rx1.recv().unwrap();
+        let (result2, opened2) = rx2.recv().unwrap();
+        let (result3, opened3) = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        assert_eq!(is_result, should_result);
+        assert_eq!(opened1, should_result);
+        assert_eq!(opened2, should_result);
+        assert_eq!(opened3, should_result);
+        assert_eq!(should_result[VEC_SIZE], should_result[0]);
+        assert_eq!(should_result[VEC_SIZE + 1], should_result[3]);
+        assert_eq!(should_result.iter().unique().count(), VEC_SIZE);
+    }
+
+    #[test]
+    fn rep3_poseidon_many() {
+        const NUM_STATES: usize = 5;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let mds: [[ark_bn254::Fr; 3]; 3] =
+            std::array::from_fn(|_| std::array::from_fn(|_| ark_bn254::Fr::rand(&mut rng)));
+        let round_constants = (0..8 + 57)
+            .map(|_| std::array::from_fn(|_| ark_bn254::Fr::rand(&mut rng)))
+            .collect_vec();
+        let poseidon = gadgets::poseidon::Poseidon::<_, 3, 5>::new(8, 57, mds, round_constants);
+        let states = (0..NUM_STATES)
+            .map(|_| std::array::from_fn::<_, 3, _>(|_| ark_bn254::Fr::rand(&mut rng)))
+            .collect_vec();
+        let x_shares =
+            rep3::share_field_elements(&states.iter().flatten().copied().collect_vec(), &mut rng);
+        let should_result = states
+            .iter()
+            .flat_map(|state| poseidon.permutation(state))
+            .collect_vec();
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, poseidon) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares,
+            [poseidon.clone(), poseidon.clone(), poseidon]
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let states = x
+                    .chunks_exact(3)
+                    .map(|state| state.try_into().unwrap())
+                    .collect_vec();
+                let result = poseidon.rep3_permutation_many(&states, &mut rep3).unwrap();
+                tx.send(result.into_iter().flatten().collect_vec())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        assert_eq!(is_result, should_result);
+    }
+
+    #[test]
+    fn rep3_poseidon2_many() {
+        const NUM_STATES: usize = 5;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let mat_internal_diag_m_1 = std::array::from_fn(|_| ark_bn254::Fr::rand(&mut rng));
+        let round_constants_external = (0..8)
+            .map(|_| std::array::from_fn(|_| ark_bn254::Fr::rand(&mut rng)))
+            .collect_vec();
+        let round_constants_internal = (0..56).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let poseidon2 = gadgets::poseidon2::Poseidon2::<_, 4, 5>::new(
+            8,
+            56,
+            mat_internal_diag_m_1,
+            round_constants_external,
+            round_constants_internal,
+        );
+        let states = (0..NUM_STATES)
+            .map(|_| std::array::from_fn::<_, 4, _>(|_| ark_bn254::Fr::rand(&mut rng)))
+            .collect_vec();
+        let x_shares =
+            rep3::share_field_elements(&states.iter().flatten().copied().collect_vec(), &mut rng);
+        let should_result = states
+            .iter()
+            .flat_map(|state| poseidon2.permutation(state))
+            .collect_vec();
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, poseidon2) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares,
+            [poseidon2.clone(), poseidon2.clone(), poseidon2]
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let states = x
+                    .chunks_exact(4)
+                    .map(|state| state.try_into().unwrap())
+                    .collect_vec();
+                let result = poseidon2.rep3_permutation_many(&states, &mut rep3).unwrap();
+                tx.send(result.into_iter().flatten().collect_vec())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        assert_eq!(is_result, should_result);
+    }
+
+    #[test]
+    fn rep3_sha256_compress_many() {
+        use sha2::Digest;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let messages = [
+            b"abc".to_vec(),
+            Vec::new(),
+            (0..55).map(|_| rng.gen()).collect_vec(),
+        ];
+        let mut states = Vec::new();
+        let mut blocks = Vec::new();
+        for message in messages.iter() {
+            // A single block fits messages of up to 55 bytes
+            let mut padded = message.clone();
+            padded.push(0x80);
+            padded.resize(56, 0);
+            padded.extend((message.len() as u64 * 8).to_be_bytes());
+            states.extend(gadgets::sha256::IV.iter().map(|x| ark_bn254::Fr::from(*x)));
+            blocks.extend(
+                padded
+                    .chunks_exact(4)
+                    .map(|x| ark_bn254::Fr::from(u32::from_be_bytes(x.try_into().unwrap()))),
+            );
+        }
+        let state_shares = rep3::share_field_elements(&states, &mut rng);
+        let block_shares = rep3::share_field_elements(&blocks, &mut rng);
+        let should_result = messages
+            .iter()
+            .flat_map(|message| {
+                sha2::Sha256::digest(message)
+                    .chunks_exact(4)
+                    .map(|x| ark_bn254::Fr::from(u32::from_be_bytes(x.try_into().unwrap())))
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, states, blocks) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            state_shares,
+            block_shares
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let states = states
+                    .chunks_exact(8)
+                    .map(|x| x.try_into().unwrap())
+                    .collect_vec();
+                let blocks = blocks
+                    .chunks_exact(16)
+                    .map(|x| x.try_into().unwrap())
+                    .collect_vec();
+                let result = gadgets::sha256::compress_many(&states, &blocks, &mut rep3).unwrap();
+                tx.send(result.into_iter().flatten().collect_vec())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        assert_eq!(is_result, should_result);
+    }
+
+    #[test]
+    fn rep3_keccak_f1600_many() {
+        use sha3::Digest;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let messages = [
+            b"abc".to_vec(),
+            Vec::new(),
+            (0..135).map(|_| rng.gen()).collect_vec(),
+        ];
+        let mut lanes = Vec::new();
+        for message in messages.iter() {
+            // A single block of Keccak-256 fits messages of up to 135 bytes
+            let mut padded = message.clone();
+            padded.push(0x01);
+            padded.resize(136, 0);
+            padded[135] |= 0x80;
+            padded.resize(200, 0);
+            lanes.extend(
+                padded
+                    .chunks_exact(8)
+                    .map(|x| ark_bn254::Fr::from(u64::from_le_bytes(x.try_into().unwrap()))),
+            );
+        }
+        let bits = lanes
+            .iter()
+            .flat_map(|lane| {
+                let lane: BigUint = (*lane).into();
+                (0..64).map(move |i| ark_bn254::Fr::from(lane.bit(i)))
+            })
+            .collect_vec();
+        let lane_shares = rep3::share_field_elements(&lanes, &mut rng);
+        let bit_shares = rep3::share_field_elements(&bits, &mut rng);
+        let digests = messages
+            .iter()
+            .map(|message| sha3::Keccak256::digest(message).to_vec())
+            .collect_vec();
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, lanes, bits) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            lane_shares,
+            bit_shares
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let states = lanes
+                    .chunks_exact(25)
+                    .map(|x| x.try_into().unwrap())
+                    .collect_vec();
+                let lanes = gadgets::keccak::keccak_f1600_many(&states, &mut rep3).unwrap();
+                let bits = gadgets::keccak::keccak_f1600_bits_many(&bits, &mut rep3).unwrap();
+                tx.send((lanes.into_iter().flatten().collect_vec(), bits))
+            });
+        }
+        let (lanes1, bits1) = rx1.recv().unwrap();
+        let (lanes2, bits2) = rx2.recv().unwrap();
+        let (lanes3, bits3) = rx3.recv().unwrap();
+        let lanes = rep3::combine_field_elements(&lanes1, &lanes2, &lanes3);
+        let bits = rep3::combine_field_elements(&bits1, &bits2, &bits3);
+        for (lanes, bits, digest) in izip!(lanes.chunks_exact(25), bits.chunks_exact(1600), digests)
+        {
+            let is_digest = lanes[..4]
+                .iter()
+                .flat_map(|lane| {
+                    let lane: BigUint = (*lane).into();
+                    u64::try_from(lane).unwrap().to_le_bytes()
+                })
+                .collect_vec();
+            assert_eq!(is_digest, digest);
+            let should_bits = lanes
+                .iter()
+                .flat_map(|lane| {
+                    let lane: BigUint = (*lane).into();
+                    (0..64).map(move |i| ark_bn254::Fr::from(lane.bit(i)))
+                })
+                .collect_vec();
+            assert_eq!(bits, should_bits);
+        }
+    }
+
+    #[test]
+    fn rep3_aes128_many() {
+        // Test vectors from FIPS-197
+        let vectors: [(u128, u128, u128); 2] = [
+            (
+                0x000102030405060708090a0b0c0d0e0f,
+                0x00112233445566778899aabbccddeeff,
+                0x69c4e0d86a7b0430d8cdb78070b4c55a,
+            ),
+            (
+                0x2b7e151628aed2a6abf7158809cf4f3c,
+                0x3243f6a8885a308d313198a2e0370734,
+                0x3925841d02dc09fbdc118597196a0b32,
+            ),
+        ];
+        let to_field = |x: &u128| x.to_be_bytes().map(ark_bn254::Fr::from).to_vec();
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let keys = vectors
+            .iter()
+            .flat_map(|(k, _, _)| to_field(k))
+            .collect_vec();
+        let plaintexts = vectors
+            .iter()
+            .flat_map(|(_, p, _)| to_field(p))
+            .collect_vec();
+        let public_plaintexts = vectors
+            .iter()
+            .map(|(_, p, _)| p.to_be_bytes())
+            .collect_vec();
+        let should_result = vectors
+            .iter()
+            .flat_map(|(_, _, c)| to_field(c))
+            .collect_vec();
+        let key_shares = rep3::share_field_elements(&keys, &mut rng);
+        let plaintext_shares = rep3::share_field_elements(&plaintexts, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, keys, plaintexts) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            key_shares,
+            plaintext_shares
+        ) {
+            let public_plaintexts = public_plaintexts.clone();
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let keys = keys
+                    .chunks_exact(16)
+                    .map(|x| x.try_into().unwrap())
+                    .collect_vec();
+                let plaintexts = plaintexts
+                    .chunks_exact(16)
+                    .map(|x| x.try_into().unwrap())
+                    .collect_vec();
+                let shared =
+                    gadgets::aes::aes128_encrypt_many(&keys, &plaintexts, &mut rep3).unwrap();
+                let public = gadgets::aes::aes128_encrypt_public_plaintext_many(
+                    &keys,
+                    &public_plaintexts,
+                    &mut rep3,
+                )
+                .unwrap();
+                tx.send((
+                    shared.into_iter().flatten().collect_vec(),
+                    public.into_iter().flatten().collect_vec(),
+                ))
+            });
+        }
+        let (shared1, public1) = rx1.recv().unwrap();
+        let (shared2, public2) = rx2.recv().unwrap();
+        let (shared3, public3) = rx3.recv().unwrap();
+        let is_shared = rep3::combine_field_elements(&shared1, &shared2, &shared3);
+        let is_public = rep3::combine_field_elements(&public1, &public2, &public3);
+        assert_eq!(is_shared, should_result);
+        assert_eq!(is_public, should_result);
+    }
+
+    #[test]
+    fn rep3_is_zero_many() {
+        const VEC_SIZE: usize = 10;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| {
+                if rng.gen() {
+                    ark_bn254::Fr::zero()
+                } else {
+                    ark_bn254::Fr::rand(&mut rng)
+                }
+            })
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+        let should_result = x
+            .iter()
+            .map(|x| ark_bn254::Fr::from(x.is_zero()))
+            .collect_vec();
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x) in izip!(test_network.get_party_networks(), [tx1, tx2, tx3], x_shares) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(arithmetic::is_zero_many(&x, &mut rep3).unwrap())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        assert_eq!(is_result, should_result);
+    }
+
+    #[test]
+    fn rep3_to_bits_many() {
+        const VEC_SIZE: usize = 10;
+        const NUM_BITS: usize = 32;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE).map(|_| rng.gen::<u32>()).collect_vec();
+        let x_shares = rep3::share_field_elements(
+            &x.iter().map(|x| ark_bn254::Fr::from(*x)).collect_vec(),
+            &mut rng,
+        );
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for ((net, tx), x) in test_network
+            .get_party_networks()
+            .into_iter()
+            .zip([tx1, tx2, tx3])
+            .zip(x_shares.into_iter())
+        {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(arithmetic::to_bits_many(&x, NUM_BITS, &mut rep3).unwrap())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        assert_eq!(result1.len(), VEC_SIZE);
+        for (x, r1, r2, r3) in izip!(x, result1, result2, result3) {
+            let is_result = rep3::combine_field_elements(&r1, &r2, &r3);
+            let should_result = (0..NUM_BITS)
+                .map(|i| ark_bn254::Fr::from((x >> i) & 1))
+                .collect_vec();
+            assert_eq!(is_result, should_result);
+        }
+    }
+
+    #[test]
+    fn rep3_to_bits_many_invalid_num_bits() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x_shares = rep3::share_field_elements(&[ark_bn254::Fr::from(42u64)], &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for ((net, tx), x) in test_network
+            .get_party_networks()
+            .into_iter()
+            .zip([tx1, tx2, tx3])
+            .zip(x_shares.into_iter())
+        {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let too_many = ark_bn254::Fr::MODULUS_BIT_SIZE as usize + 1;
+                let errors = [0, too_many].map(|num_bits| {
+                    arithmetic::to_bits_many(&x, num_bits, &mut rep3)
+                        .unwrap_err()
+                        .kind()
+                });
+                tx.send(errors)
+            });
+        }
+        for rx in [rx1, rx2, rx3] {
+            assert_eq!(
+                rx.recv().unwrap(),
+                [
+                    std::io::ErrorKind::InvalidInput,
+                    std::io::ErrorKind::InvalidInput
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn rep3_fixed_point_mul_div() {
+        const VEC_SIZE: usize = 10;
+
+        let config = fixed_point::FixedPointConfig::default();
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| rng.gen_range(-1000.0..1000.0))
+            .collect_vec();
+        let y = (0..VEC_SIZE)
+            .map(|_| rng.gen_range(1.0..100.0) * if rng.gen() { 1.0 } else { -1.0 })
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(
+            &x.iter()
+                .map(|x| config.encode::<ark_bn254::Fr>(*x))
+                .collect_vec(),
+            &mut rng,
+        );
+        let y_shares = rep3::share_field_elements(
+            &y.iter()
+                .map(|y| config.encode::<ark_bn254::Fr>(*y))
+                .collect_vec(),
+            &mut rng,
+        );
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (((net, tx), x), y) in test_network
+            .get_party_networks()
+            .into_iter()
+            .zip([tx1, tx2, tx3])
+            .zip(x_shares.into_iter())
+            .zip(y_shares.into_iter())
+        {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let mul = fixed_point::mul_vec(&x, &y, &config, &mut rep3).unwrap();
+                let div = fixed_point::div_vec(&x, &y, &config, &mut rep3).unwrap();
+                tx.send((mul, div))
+            });
+        }
+        let (mul1, div1) = rx1.recv().unwrap();
+        let (mul2, div2) = rx2.recv().unwrap();
+        let (mul3, div3) = rx3.recv().unwrap();
+        let mul = rep3::combine_field_elements(&mul1, &mul2, &mul3);
+        let div = rep3::combine_field_elements(&div1, &div2, &div3);
+        // the probabilistic truncation and the rounding introduce an error in the least significant bits
+        let eps = 4.0 / 2f64.powi(config.frac_bits as i32);
+        for (x, y, mul, div) in izip!(x, y, mul, div) {
+            let x = config.decode(config.encode::<ark_bn254::Fr>(x));
+            let y = config.decode(config.encode::<ark_bn254::Fr>(y));
+            assert!((config.decode(mul) - x * y).abs() <= eps);
+            assert!((config.decode(div) - x / y).abs() <= eps);
+        }
+    }
+
+    #[test]
+    fn rep3_fixed_point_mul_trunc() {
+        const VEC_SIZE: usize = 10;
+
+        let config = fixed_point::FixedPointConfig::new(32, 8);
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| rng.gen_range(-(1i64 << 20)..1i64 << 20))
+            .collect_vec();
+        let y = (0..VEC_SIZE)
+            .map(|_| rng.gen_range(-(1i64 << 20)..1i64 << 20))
+            .collect_vec();
+        let to_field = |x: i64| {
+            let abs = ark_bn254::Fr::from(x.unsigned_abs());
+            if x < 0 {
+                -abs
+            } else {
+                abs
+            }
+        };
+        let x_shares =
+            rep3::share_field_elements(&x.iter().map(|x| to_field(*x)).collect_vec(), &mut rng);
+        let y_shares =
+            rep3::share_field_elements(&y.iter().map(|y| to_field(*y)).collect_vec(), &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, y) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let mut res = fixed_point::mul_trunc_vec(&x, &y, &config, &mut rep3).unwrap();
+                res.push(fixed_point::mul_trunc(x[0], y[0], &config, &mut rep3).unwrap());
+                tx.send(res)
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        let mut should_result = izip!(&x, &y)
+            .map(|(x, y)| (x * y).div_euclid(1 << config.frac_bits))
+            .collect_vec();
+        should_result.push(should_result[0]);
+        // the probabilistic truncation can be larger than the exact result by up to 3
+        for (is, should) in izip!(is_result, should_result) {
+            let diffs = (0..4).map(|d| to_field(should + d)).collect_vec();
+            assert!(diffs.contains(&is));
+        }
+    }
+
+    #[test]
+    fn rep3_open_many_checked() {
+        const VEC_SIZE: usize = 10;
+        const TAMPERED: usize = 2;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x) in izip!(test_network.get_party_networks(), [tx1, tx2, tx3], x_shares) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let honest = arithmetic::open_many_checked(&x, &mut rep3).unwrap();
+                let mut tampered = x;
+                if rep3.id == PartyID::ID1 {
+                    tampered[TAMPERED].b += ark_bn254::Fr::one();
+                }
+                let tampered = arithmetic::open_many_checked(&tampered, &mut rep3);
+                tx.send((honest, tampered))
+            });
+        }
+        let (honest1, tampered1) = rx1.recv().unwrap();
+        let (honest2, tampered2) = rx2.recv().unwrap();
+        let (honest3, tampered3) = rx3.recv().unwrap();
+        assert_eq!(honest1, x);
+        assert_eq!(honest2, x);
+        assert_eq!(honest3, x);
+        // the b component of party 1 is only sent to party 2, party 1 itself opens its tampered share
+        assert_eq!(tampered1.unwrap(), x);
+        assert!(tampered2.is_ok());
+        match tampered3 {
+            Err(arithmetic::CheckedOpenError::Inconsistent(indices)) => {
+                assert_eq!(indices, vec![TAMPERED])
+            }
+            _ => panic!("expected an inconsistent opening"),
+        }
+    }
+
+    #[test]
+    fn rep3_fixed_point_lt() {
+        let config = fixed_point::FixedPointConfig::default();
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let values = [-3.5, -0.25, 0.0, 0.25, 3.5];
+        let pairs = values
+            .iter()
+            .cartesian_product(values.iter())
+            .map(|(x, y)| (*x, *y))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(
+            &pairs
+                .iter()
+                .map(|(x, _)| config.encode::<ark_bn254::Fr>(*x))
+                .collect_vec(),
+            &mut rng,
+        );
+        let y_shares = rep3::share_field_elements(
+            &pairs
+                .iter()
+                .map(|(_, y)| config.encode::<ark_bn254::Fr>(*y))
+                .collect_vec(),
+            &mut rng,
+        );
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (((net, tx), x), y) in test_network
+            .get_party_networks()
+            .into_iter()
+            .zip([tx1, tx2, tx3])
+            .zip(x_shares.into_iter())
+            .zip(y_shares.into_iter())
+        {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let res = izip!(x, y)
+                    .map(|(x, y)| fixed_point::lt(x, y, &config, &mut rep3).unwrap())
+                    .collect_vec();
+                tx.send(res)
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        for ((x, y), is_result) in izip!(pairs, is_result) {
+            assert_eq!(is_result, ark_bn254::Fr::from(x < y));
+        }
+    }
+
+    #[test]
+    fn rep3_a2y2b_streaming() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let x_shares = rep3::share_field_element(x, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for ((net, tx), x) in test_network
+            .get_party_networks()
+            .into_iter()
+            .zip([tx1, tx2, tx3])
+            .zip(x_shares.into_iter())
+        {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(conversion::a2y2b_streaming(x, &mut rep3).unwrap())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_binary_element(result1, result2, result3);
+
+        let should_result = x.into();
+        assert_eq!(is_result, should_result);
+        let is_result_f: ark_bn254::Fr = is_result.into();
+        assert_eq!(is_result_f, x);
+    }
+
+    #[test]
+    fn rep3_a2y2b_pipelined() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let x_shares = rep3::share_field_element(x, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for ((net, tx), x) in test_network
+            .get_party_networks()
+            .into_iter()
+            .zip([tx1, tx2, tx3])
+            .zip(x_shares.into_iter())
+        {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(conversion::a2y2b_pipelined(x, 64, &mut rep3).unwrap())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_binary_element(result1, result2, result3);
+
+        let should_result = x.into();
+        assert_eq!(is_result, should_result);
+        let is_result_f: ark_bn254::Fr = is_result.into();
+        assert_eq!(is_result_f, x);
+    }
+
+    #[test]
+    fn rep3_a2y2b_rotating_roles() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let x_shares = rep3::share_field_element(x, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for ((net, tx), x) in test_network
+            .get_party_networks()
+            .into_iter()
+            .zip([tx1, tx2, tx3])
+            .zip(x_shares.into_iter())
+        {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let mut results = Vec::with_capacity(3);
+                for execution in 0..3 {
+                    let roles = GCRoles::rotating(execution);
+                    let mut rotated = rep3.fork_with_gc_roles(roles).unwrap();
+                    let b = conversion::a2y2b(x, &mut rotated).unwrap();
+                    let a = conversion::b2y2a(&b, &mut rotated).unwrap();
+                    results.push((b, a));
+                }
+                tx.send(results)
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        for ((b1, a1), (b2, a2), (b3, a3)) in izip!(result1, result2, result3) {
+            let is_result = rep3::combine_binary_element(b1, b2, b3);
+            assert_eq!(is_result, x.into());
+            let is_result = rep3::combine_field_element(a1, a2, a3);
+            assert_eq!(is_result, x);
+        }
+    }
+
+    #[test]
+    fn rep3_b2a() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let x_shares = rep3::share_biguint(x, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for ((net, tx), x) in test_network
+            .get_party_networks()
+            .into_iter()
+            .zip([tx1, tx2, tx3])
+            .zip(x_shares.into_iter())
+        {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(conversion::b2a(&x, &mut rep3).unwrap())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_element(result1, result2, result3);
+        assert_eq!(is_result, x);
+    }
+
+    #[test]
+    fn rep3_b2y2a() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let x_shares = rep3::share_biguint(x, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for ((net, tx), x) in test_network
+            .get_party_networks()
+            .into_iter()
+            .zip([tx1, tx2, tx3])
+            .zip(x_shares.into_iter())
+        {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(conversion::b2y2a(&x, &mut rep3).unwrap())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_element(result1, result2, result3);
+        assert_eq!(is_result, x);
+    }
+
+    #[test]
+    fn rep3_b2y2a_streaming() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let x_shares = rep3::share_biguint(x, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for ((net, tx), x) in test_network
+            .get_party_networks()
+            .into_iter()
+            .zip([tx1, tx2, tx3])
+            .zip(x_shares.into_iter())
+        {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(conversion::b2y2a_streaming(&x, &mut rep3).unwrap())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_element(result1, result2, result3);
+        assert_eq!(is_result, x);
+    }
+
+    #[test]
+    fn rep3_b2y2a_pipelined() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let x_shares = rep3::share_biguint(x, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for ((net, tx), x) in test_network
+            .get_party_networks()
+            .into_iter()
+            .zip([tx1, tx2, tx3])
+            .zip(x_shares.into_iter())
+        {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(conversion::b2y2a_pipelined(&x, 64, &mut rep3).unwrap())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_element(result1, result2, result3);
+        assert_eq!(is_result, x);
+    }
+
+    #[test]
+    fn rep3_gc() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let should_result = x + y;
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+
+        let [net1, net2, net3] = test_network.get_party_networks();
+
+        // Both Garblers
+        for (net, tx) in izip!([net2, net3], [tx2, tx3]) {
+            thread::spawn(move || {
+                let mut ctx = IoContext::init(net).unwrap();
+
+                let mut garbler = Rep3Garbler::new(&mut ctx);
+                let x_ = garbler.encode_field(x);
+                let y_ = garbler.encode_field(y);
+
+                // This is without OT, just a simulation
+                garbler.add_bundle_to_circuit(&x_.evaluator_wires).unwrap();
+                garbler.add_bundle_to_circuit(&y_.evaluator_wires).unwrap();
+
+                let circuit_output = GarbledCircuits::adder_mod_p::<_, ark_bn254::Fr>(
+                    &mut garbler,
+                    &x_.garbler_wires,
+                    &y_.garbler_wires,
+                )
+                .unwrap();
+
+                let output = garbler.output_all_parties(circuit_output.wires()).unwrap();
+                let add = GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap();
+                tx.send(add)
+            });
+        }
+
+        // The evaluator (ID0)
+        thread::spawn(move || {
+            let mut ctx = IoContext::init(net1).unwrap();
+
+            let mut evaluator = Rep3Evaluator::new(&mut ctx);
+            let n_bits = ark_bn254::Fr::MODULUS_BIT_SIZE as usize;
+
+            // This is without OT, just a simulation
+            evaluator.receive_circuit().unwrap();
+            let x_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+            let y_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+
+            let circuit_output =
+                GarbledCircuits::adder_mod_p::<_, ark_bn254::Fr>(&mut evaluator, &x_, &y_).unwrap();
+
+            let output = evaluator
+                .output_all_parties(circuit_output.wires())
+                .unwrap();
+            let add = GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap();
+            tx1.send(add)
+        });
+
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        assert_eq!(result1, should_result);
+        assert_eq!(result2, should_result);
+        assert_eq!(result3, should_result);
+    }
+
+    #[test]
+    fn rep3_gc_output_to_id2() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let should_result = x + y;
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+
+        let [net1, net2, net3] = test_network.get_party_networks();
+
+        // Both Garblers
+        for (net, tx) in izip!([net2, net3], [tx2, tx3]) {
+            thread::spawn(move || {
+                let mut ctx = IoContext::init(net).unwrap();
+
+                let mut garbler = Rep3Garbler::new(&mut ctx);
+                let x_ = garbler.encode_field(x);
+                let y_ = garbler.encode_field(y);
+
+                // This is without OT, just a simulation
+                garbler.add_bundle_to_circuit(&x_.evaluator_wires).unwrap();
+                garbler.add_bundle_to_circuit(&y_.evaluator_wires).unwrap();
+
+                let circuit_output = GarbledCircuits::adder_mod_p::<_, ark_bn254::Fr>(
+                    &mut garbler,
+                    &x_.garbler_wires,
+                    &y_.garbler_wires,
+                )
+                .unwrap();
+
+                let output = garbler
+                    .output_to(circuit_output.wires(), &[PartyID::ID2])
+                    .unwrap();
+                let add =
+                    output.map(|output| GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap());
+                tx.send(add)
+            });
+        }
+
+        // The evaluator (ID0)
+        thread::spawn(move || {
+            let mut ctx = IoContext::init(net1).unwrap();
+
+            let mut evaluator = Rep3Evaluator::new(&mut ctx);
+            let n_bits = ark_bn254::Fr::MODULUS_BIT_SIZE as usize;
+
+            // This is without OT, just a simulation
+            evaluator.receive_circuit().unwrap();
+            let x_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+            let y_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+
+            let circuit_output =
+                GarbledCircuits::adder_mod_p::<_, ark_bn254::Fr>(&mut evaluator, &x_, &y_).unwrap();
+
+            let output = evaluator
+                .output_to(circuit_output.wires(), &[PartyID::ID2])
+                .unwrap();
+            tx1.send(output.map(|output| GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap()))
+        });
+
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        assert_eq!(result1, None);
+        assert_eq!(result2, None);
+        assert_eq!(result3, Some(should_result));
+    }
+
+    #[test]
+    fn rep3_gc_privacy_free() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let should_result = x + y;
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+
+        let [net1, net2, net3] = test_network.get_party_networks();
+
+        // Both Garblers
+        for (net, tx) in izip!([net2, net3], [tx2, tx3]) {
+            thread::spawn(move || {
+                let mut ctx = IoContext::init(net).unwrap();
+
+                let mut garbler = Rep3Garbler::new(&mut ctx);
+                garbler.set_privacy_free(true);
+                let x_ = garbler.encode_field(x);
+                let y_ = garbler.encode_field(y);
+
+                // This is without OT, just a simulation
+                garbler.add_bundle_to_circuit(&x_.evaluator_wires).unwrap();
+                garbler.add_bundle_to_circuit(&y_.evaluator_wires).unwrap();
+
+                let circuit_output = GarbledCircuits::adder_mod_p::<_, ark_bn254::Fr>(
+                    &mut garbler,
+                    &x_.garbler_wires,
+                    &y_.garbler_wires,
+                )
+                .unwrap();
+
+                let output = garbler.output_all_parties(circuit_output.wires()).unwrap();
+                let add = GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap();
+                tx.send(add)
+            });
+        }
+
+        // The evaluator (ID0)
+        thread::spawn(move || {
+            let mut ctx = IoContext::init(net1).unwrap();
+
+            let mut evaluator = Rep3Evaluator::new(&mut ctx);
+            evaluator.set_privacy_free(true);
+            let n_bits = ark_bn254::Fr::MODULUS_BIT_SIZE as usize;
+
+            // This is without OT, just a simulation
+            evaluator.receive_circuit().unwrap();
+            let x_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+            let y_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+
+            let circuit_output =
+                GarbledCircuits::adder_mod_p::<_, ark_bn254::Fr>(&mut evaluator, &x_, &y_).unwrap();
+
+            let output = evaluator
+                .output_all_parties(circuit_output.wires())
+                .unwrap();
+            let add = GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap();
+            tx1.send(add)
+        });
+
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        assert_eq!(result1, should_result);
+        assert_eq!(result2, should_result);
+        assert_eq!(result3, should_result);
+    }
+
+    #[test]
+    fn rep3_gc_mul_mod_2k() {
+        const K: usize = 4;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = rng.gen_range(0..1u64 << K);
+        let y = rng.gen_range(0..1u64 << K);
+        let should_result = (x * y) % (1 << K);
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+
+        let [net1, net2, net3] = test_network.get_party_networks();
+
+        // Both Garblers
+        for (net, tx) in izip!([net2, net3], [tx2, tx3]) {
+            thread::spawn(move || {
+                let mut ctx = IoContext::init(net).unwrap();
+
+                let mut garbler = Rep3Garbler::new(&mut ctx);
+                let x_ = garbler.encode_field(ark_bn254::Fr::from(x));
+                let y_ = garbler.encode_field(ark_bn254::Fr::from(y));
+
+                // This is without OT, just a simulation
+                garbler.add_bundle_to_circuit(&x_.evaluator_wires).unwrap();
+                garbler.add_bundle_to_circuit(&y_.evaluator_wires).unwrap();
+
+                let mut arithmetic_garbler = Rep3ArithmeticGarbler::new(&mut garbler);
+                let circuit_output = GarbledCircuits::mul_mod_2k(
+                    &mut arithmetic_garbler,
+                    &x_.garbler_wires.wires()[..K],
+                    &y_.garbler_wires.wires()[..K],
+                )
+                .unwrap();
+
+                let output = garbler.output_all_parties(circuit_output.wires()).unwrap();
+                tx.send(output)
+            });
+        }
+
+        // The evaluator (ID0)
+        thread::spawn(move || {
+            let mut ctx = IoContext::init(net1).unwrap();
+
+            let mut evaluator = Rep3Evaluator::new(&mut ctx);
+            let n_bits = ark_bn254::Fr::MODULUS_BIT_SIZE as usize;
+
+            // This is without OT, just a simulation
+            evaluator.receive_circuit().unwrap();
+            let x_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+            let y_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+
+            let mut arithmetic_evaluator = Rep3ArithmeticEvaluator::new(&mut evaluator);
+            let circuit_output = GarbledCircuits::mul_mod_2k(
+                &mut arithmetic_evaluator,
+                &x_.wires()[..K],
+                &y_.wires()[..K],
+            )
+            .unwrap();
+
+            let output = evaluator
+                .output_all_parties(circuit_output.wires())
+                .unwrap();
+            tx1.send(output)
+        });
+
+        for rx in [rx1, rx2, rx3] {
+            let output = rx.recv().unwrap();
+            let is_result = output
+                .iter()
+                .rev()
+                .fold(0u64, |acc, bit| (acc << 1) | *bit as u64);
+            assert_eq!(is_result, should_result);
+        }
+    }
+
+    #[test]
+    fn rep3_gc_cached_topology() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let should_result = x + y;
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+
+        let [net1, net2, net3] = test_network.get_party_networks();
+
+        // Both Garblers
+        for (net, tx) in izip!([net2, net3], [tx2, tx3]) {
+            thread::spawn(move || {
+                let mut ctx = IoContext::init(net).unwrap();
+
+                let mut garbler = Rep3Garbler::new(&mut ctx);
+                let x_ = garbler.encode_field(x);
+                let y_ = garbler.encode_field(y);
+
+                // This is without OT, just a simulation
+                garbler.add_bundle_to_circuit(&x_.evaluator_wires).unwrap();
+                garbler.add_bundle_to_circuit(&y_.evaluator_wires).unwrap();
+
+                let circuit_output = GarbledCircuits::adder_mod_p_cached::<_, ark_bn254::Fr>(
+                    &mut garbler,
+                    &x_.garbler_wires,
+                    &y_.garbler_wires,
+                )
+                .unwrap();
+
+                let output = garbler.output_all_parties(circuit_output.wires()).unwrap();
+                let add = GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap();
+                tx.send(add)
+            });
+        }
+
+        // The evaluator (ID0)
+        thread::spawn(move || {
+            let mut ctx = IoContext::init(net1).unwrap();
+
+            let mut evaluator = Rep3Evaluator::new(&mut ctx);
+            let n_bits = ark_bn254::Fr::MODULUS_BIT_SIZE as usize;
+
+            // This is without OT, just a simulation
+            evaluator.receive_circuit().unwrap();
+            let x_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+            let y_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+
+            let circuit_output =
+                GarbledCircuits::adder_mod_p_cached::<_, ark_bn254::Fr>(&mut evaluator, &x_, &y_)
+                    .unwrap();
+
+            let output = evaluator
+                .output_all_parties(circuit_output.wires())
+                .unwrap();
+            let add = GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap();
+            tx1.send(add)
+        });
+
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        assert_eq!(result1, should_result);
+        assert_eq!(result2, should_result);
+        assert_eq!(result3, should_result);
+        assert!(!TopologyCache::global().is_empty());
+    }
+
+    #[test]
+    fn rep3_gc_chunked() {
+        const CHUNK_SIZE: usize = 100;
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let should_result = x + y;
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+
+        let [net1, net2, net3] = test_network.get_party_networks();
+
+        // Both Garblers
+        for (net, tx) in izip!([net2, net3], [tx2, tx3]) {
+            thread::spawn(move || {
+                let mut ctx = IoContext::init(net).unwrap();
+
+                let mut garbler = Rep3Garbler::new(&mut ctx).with_chunk_size(CHUNK_SIZE);
+                let x_ = garbler.encode_field(x);
+                let y_ = garbler.encode_field(y);
+
+                // This is without OT, just a simulation
+                garbler.add_bundle_to_circuit(&x_.evaluator_wires).unwrap();
+                garbler.add_bundle_to_circuit(&y_.evaluator_wires).unwrap();
+
+                let circuit_output = GarbledCircuits::adder_mod_p::<_, ark_bn254::Fr>(
+                    &mut garbler,
+                    &x_.garbler_wires,
+                    &y_.garbler_wires,
+                )
+                .unwrap();
+
+                let output = garbler.output_all_parties(circuit_output.wires()).unwrap();
+                let add = GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap();
+                tx.send(add)
+            });
+        }
+
+        // The evaluator (ID0)
+        thread::spawn(move || {
+            let mut ctx = IoContext::init(net1).unwrap();
+
+            let mut evaluator = Rep3Evaluator::new(&mut ctx).with_chunk_size(CHUNK_SIZE);
+            let n_bits = ark_bn254::Fr::MODULUS_BIT_SIZE as usize;
+
+            // This is without OT, just a simulation
+            evaluator.receive_circuit().unwrap();
+            let x_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+            let y_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+
+            let circuit_output =
+                GarbledCircuits::adder_mod_p::<_, ark_bn254::Fr>(&mut evaluator, &x_, &y_).unwrap();
+
+            let output = evaluator
+                .output_all_parties(circuit_output.wires())
+                .unwrap();
+            let add = GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap();
+            tx1.send(add)
+        });
+
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        assert_eq!(result1, should_result);
+        assert_eq!(result2, should_result);
+        assert_eq!(result3, should_result);
+    }
+
+    #[test]
+    fn rep3_gc_memory_budget() {
+        const MEMORY_BUDGET: usize = 100 * 16;
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let should_result = x + y;
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+
+        let [net1, net2, net3] = test_network.get_party_networks();
+
+        // Both Garblers
+        for (net, tx) in izip!([net2, net3], [tx2, tx3]) {
+            thread::spawn(move || {
+                let mut ctx = IoContext::init(net).unwrap();
+
+                let mut garbler = Rep3Garbler::new(&mut ctx).with_memory_budget(MEMORY_BUDGET);
+                let x_ = garbler.encode_field(x);
+                let y_ = garbler.encode_field(y);
+
+                // This is without OT, just a simulation
+                garbler.add_bundle_to_circuit(&x_.evaluator_wires).unwrap();
+                garbler.add_bundle_to_circuit(&y_.evaluator_wires).unwrap();
+
+                let circuit_output = GarbledCircuits::adder_mod_p::<_, ark_bn254::Fr>(
+                    &mut garbler,
+                    &x_.garbler_wires,
+                    &y_.garbler_wires,
+                )
+                .unwrap();
+
+                let output = garbler.output_all_parties(circuit_output.wires()).unwrap();
+                let add = GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap();
+                tx.send(add)
+            });
+        }
+
+        // The evaluator (ID0)
+        thread::spawn(move || {
+            let mut ctx = IoContext::init(net1).unwrap();
+
+            let mut evaluator = Rep3Evaluator::new(&mut ctx);
+            let n_bits = ark_bn254::Fr::MODULUS_BIT_SIZE as usize;
+
+            // This is without OT, just a simulation
+            evaluator.receive_circuit().unwrap();
+            let x_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+            let y_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+
+            let circuit_output =
+                GarbledCircuits::adder_mod_p::<_, ark_bn254::Fr>(&mut evaluator, &x_, &y_).unwrap();
+
+            let output = evaluator
+                .output_all_parties(circuit_output.wires())
+                .unwrap();
+            let add = GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap();
+            tx1.send(add)
+        });
+
         let result1 = rx1.recv().unwrap();
         let result2 = rx2.recv().unwrap();
         let result3 = rx3.recv().unwrap();
-        let is_result = rep3::combine_field_element(result1, result2, result3);
-        assert_eq!(is_result, x);
+        assert_eq!(result1, should_result);
+        assert_eq!(result2, should_result);
+        assert_eq!(result3, should_result);
     }
 
     #[test]
-    fn rep3_gc() {
+    fn rep3_gc_many_multithreaded() {
+        const VEC_SIZE: usize = 10;
         let test_network = Rep3TestNetwork::default();
         let mut rng = thread_rng();
-        let x = ark_bn254::Fr::rand(&mut rng);
-        let y = ark_bn254::Fr::rand(&mut rng);
-        let should_result = x + y;
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let y = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let should_result = izip!(&x, &y).map(|(x, y)| x + y).collect_vec();
+        let n_bits = ark_bn254::Fr::MODULUS_BIT_SIZE as usize;
+        // Pack the values such that the i-th value is encoded by the i-th chunk of n_bits wires
+        let pack = |values: &[ark_bn254::Fr]| {
+            values.iter().rev().fold(BigUint::zero(), |acc, v| {
+                (acc << n_bits) + BigUint::from(*v)
+            })
+        };
+        let x = pack(&x);
+        let y = pack(&y);
+        let seed = rng.gen();
         let (tx1, rx1) = mpsc::channel();
         let (tx2, rx2) = mpsc::channel();
         let (tx3, rx3) = mpsc::channel();
@@ -866,18 +3852,23 @@ mod field_share {
 
         // Both Garblers
         for (net, tx) in izip!([net2, net3], [tx2, tx3]) {
+            let (x, y) = (x.clone(), y.clone());
             thread::spawn(move || {
                 let mut ctx = IoContext::init(net).unwrap();
-
-                let mut garbler = Rep3Garbler::new(&mut ctx);
-                let x_ = garbler.encode_field(x);
-                let y_ = garbler.encode_field(y);
+                // Both garblers need the same randomness
+                let mut rng = StdRng::seed_from_u64(seed);
+                let delta = GCUtils::random_delta(&mut rng);
+                let mut garbler = Rep3Garbler::new_with_delta(&mut ctx, delta)
+                    .with_num_threads(4)
+                    .unwrap();
+                let x_ = GCUtils::encode_bigint(&x, n_bits * VEC_SIZE, &mut rng, delta);
+                let y_ = GCUtils::encode_bigint(&y, n_bits * VEC_SIZE, &mut rng, delta);
 
                 // This is without OT, just a simulation
-                garbler.add_bundle_to_circuit(&x_.evaluator_wires);
-                garbler.add_bundle_to_circuit(&y_.evaluator_wires);
+                garbler.add_bundle_to_circuit(&x_.evaluator_wires).unwrap();
+                garbler.add_bundle_to_circuit(&y_.evaluator_wires).unwrap();
 
-                let circuit_output = GarbledCircuits::adder_mod_p::<_, ark_bn254::Fr>(
+                let circuit_output = GarbledCircuits::adder_mod_p_many::<_, ark_bn254::Fr>(
                     &mut garbler,
                     &x_.garbler_wires,
                     &y_.garbler_wires,
@@ -885,7 +3876,10 @@ mod field_share {
                 .unwrap();
 
                 let output = garbler.output_all_parties(circuit_output.wires()).unwrap();
-                let add = GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap();
+                let add = output
+                    .chunks(n_bits)
+                    .map(|x| GCUtils::bits_to_field::<ark_bn254::Fr>(x).unwrap())
+                    .collect_vec();
                 tx.send(add)
             });
         }
@@ -895,20 +3889,27 @@ mod field_share {
             let mut ctx = IoContext::init(net1).unwrap();
 
             let mut evaluator = Rep3Evaluator::new(&mut ctx);
-            let n_bits = ark_bn254::Fr::MODULUS_BIT_SIZE as usize;
 
             // This is without OT, just a simulation
             evaluator.receive_circuit().unwrap();
-            let x_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
-            let y_ = evaluator.receive_bundle_from_circuit(n_bits).unwrap();
+            let x_ = evaluator
+                .receive_bundle_from_circuit(n_bits * VEC_SIZE)
+                .unwrap();
+            let y_ = evaluator
+                .receive_bundle_from_circuit(n_bits * VEC_SIZE)
+                .unwrap();
 
             let circuit_output =
-                GarbledCircuits::adder_mod_p::<_, ark_bn254::Fr>(&mut evaluator, &x_, &y_).unwrap();
+                GarbledCircuits::adder_mod_p_many::<_, ark_bn254::Fr>(&mut evaluator, &x_, &y_)
+                    .unwrap();
 
             let output = evaluator
                 .output_all_parties(circuit_output.wires())
                 .unwrap();
-            let add = GCUtils::bits_to_field::<ark_bn254::Fr>(&output).unwrap();
+            let add = output
+                .chunks(n_bits)
+                .map(|x| GCUtils::bits_to_field::<ark_bn254::Fr>(x).unwrap())
+                .collect_vec();
             tx1.send(add)
         });
 
@@ -920,6 +3921,67 @@ mod field_share {
         assert_eq!(result3, should_result);
     }
 
+    #[test]
+    fn rep3_bristol_fashion() {
+        // Computes x AND y and x XNOR y bitwise for 4-bit values
+        const CIRCUIT: &str = "12 20
+2 4 4
+2 4 4
+
+2 1 0 4 8 XOR
+2 1 1 5 9 XOR
+2 1 2 6 10 XOR
+2 1 3 7 11 XOR
+2 1 0 4 12 AND
+2 1 1 5 13 AND
+2 1 2 6 14 AND
+2 1 3 7 15 AND
+1 1 8 16 INV
+1 1 9 17 INV
+1 1 10 18 INV
+1 1 11 19 INV
+";
+        let circuit = BristolFashionCircuit::from_reader(CIRCUIT.as_bytes()).unwrap();
+        assert_eq!(circuit.input_sizes(), &[4, 4]);
+        assert_eq!(circuit.output_sizes(), &[4, 4]);
+        let circuit = std::sync::Arc::new(circuit);
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = rng.gen_range(0..16u64);
+        let y = rng.gen_range(0..16u64);
+        let x_shares = rep3::share_biguint(ark_bn254::Fr::from(x), &mut rng);
+        let y_shares = rep3::share_biguint(ark_bn254::Fr::from(y), &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, y) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            x_shares,
+            y_shares
+        ) {
+            let circuit = circuit.clone();
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(
+                    yao::bristol_fashion::execute_bristol_fashion(&circuit, &[x, y], &mut rep3)
+                        .unwrap(),
+                )
+            });
+        }
+        let results = izip!(
+            rx1.recv().unwrap(),
+            rx2.recv().unwrap(),
+            rx3.recv().unwrap()
+        )
+        .map(|(r1, r2, r3)| rep3::combine_binary_element(r1, r2, r3))
+        .collect_vec();
+        assert_eq!(results[0], BigUint::from(x & y));
+        assert_eq!(results[1], BigUint::from(!(x ^ y) & 0xf));
+    }
+
     #[test]
     fn rep3_gc_streaming() {
         let test_network = Rep3TestNetwork::default();
@@ -988,6 +4050,59 @@ mod field_share {
         assert_eq!(result3, should_result);
     }
 
+    #[test]
+    fn rep3_gc_streaming_inconsistent() {
+        const HASH_CHUNK_SIZE: usize = 16;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let n_bits = ark_bn254::Fr::MODULUS_BIT_SIZE as usize;
+        let (tx, rx) = mpsc::channel();
+
+        let [net1, net2, net3] = test_network.get_party_networks();
+
+        // Both Garblers, ID2 garbles a different y
+        for (net, y) in izip!([net2, net3], [y, y + ark_bn254::Fr::one()]) {
+            thread::spawn(move || {
+                let mut ctx = IoContext::init(net).unwrap();
+
+                let mut garbler =
+                    StreamingRep3Garbler::new(&mut ctx).with_hash_chunk_size(HASH_CHUNK_SIZE);
+                let x_ = garbler.encode_field(x);
+                let y_ = garbler.encode_field(y);
+
+                // This is without OT, just a simulation
+                garbler.send_bundle(&x_.evaluator_wires).unwrap();
+                garbler.send_bundle(&y_.evaluator_wires).unwrap();
+            });
+        }
+
+        // The evaluator (ID0)
+        thread::spawn(move || {
+            let mut ctx = IoContext::init(net1).unwrap();
+
+            let mut evaluator =
+                StreamingRep3Evaluator::new(&mut ctx).with_hash_chunk_size(HASH_CHUNK_SIZE);
+
+            // This is without OT, just a simulation
+            evaluator.receive_bundle(n_bits).unwrap();
+            let Err(err) = evaluator.receive_bundle(n_bits) else {
+                panic!("the circuits are inconsistent");
+            };
+            tx.send(GcConsistencyError::from_io_error(&err).cloned())
+        });
+
+        // The first block of y is the first mismatching block
+        let chunk = n_bits / HASH_CHUNK_SIZE;
+        let should_result = GcConsistencyError::HashMismatch {
+            chunk,
+            blocks: chunk * HASH_CHUNK_SIZE..(chunk + 1) * HASH_CHUNK_SIZE,
+        };
+        assert_eq!(rx.recv().unwrap(), Some(should_result));
+    }
+
     #[test]
     fn rep3_a2y() {
         let test_network = Rep3TestNetwork::default();
@@ -1036,6 +4151,78 @@ mod field_share {
         assert_eq!(result3, x);
     }
 
+    #[test]
+    fn rep3_a2y_y2a_many() {
+        const VEC_SIZE: usize = 10;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+
+        for (net, tx, x) in izip!(
+            test_network.get_party_networks().into_iter(),
+            [tx1, tx2, tx3],
+            x_shares.into_iter()
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let delta = rep3.rngs.generate_random_garbler_delta(rep3.id);
+
+                let converted = conversion::a2y_many(&x, delta, &mut rep3).unwrap();
+                let converted =
+                    conversion::y2a_many::<ark_bn254::Fr, _>(converted, delta, &mut rep3).unwrap();
+                tx.send(converted)
+            });
+        }
+
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        assert_eq!(is_result, x);
+    }
+
+    #[test]
+    fn rep3_a2y_many_streaming() {
+        const VEC_SIZE: usize = 10;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+
+        for (net, tx, x) in izip!(test_network.get_party_networks(), [tx1, tx2, tx3], x_shares) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let delta = rep3.rngs.generate_random_garbler_delta(rep3.id);
+
+                let converted = conversion::a2y_many_streaming(&x, delta, &mut rep3).unwrap();
+                let converted =
+                    conversion::y2a_many::<ark_bn254::Fr, _>(converted, delta, &mut rep3).unwrap();
+                tx.send(converted)
+            });
+        }
+
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        assert_eq!(is_result, x);
+    }
+
     #[test]
     fn rep3_a2y_streaming() {
         let test_network = Rep3TestNetwork::default();
@@ -1346,6 +4533,177 @@ mod field_share {
         assert_eq!(is_result, should_result);
     }
 
+    #[test]
+    fn rep3_shuffle() {
+        const VEC_SIZE: usize = 100;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x) in izip!(test_network.get_party_networks(), [tx1, tx2, tx3], x_shares) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(gadgets::shuffle::shuffle(&x, &mut rep3).unwrap())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let mut is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        // the probability of the identity permutation is negligible
+        assert_ne!(is_result, x);
+        let mut should_result = x;
+        is_result.sort();
+        should_result.sort();
+        assert_eq!(is_result, should_result);
+    }
+
+    #[test]
+    fn rep3_lut() {
+        const TABLE_SIZE: usize = 13;
+        const NUM_LOOKUPS: usize = 20;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let table = (0..TABLE_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let indices = (0..NUM_LOOKUPS)
+            .map(|_| rng.gen_range(0..TABLE_SIZE))
+            .collect_vec();
+        let table_shares = rep3::share_field_elements(&table, &mut rng);
+        let index_shares = rep3::share_field_elements(
+            &indices
+                .iter()
+                .map(|i| ark_bn254::Fr::from(*i as u64))
+                .collect_vec(),
+            &mut rng,
+        );
+        let should_result = indices.iter().map(|i| table[*i]).collect_vec();
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, t, i) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            table_shares,
+            index_shares
+        ) {
+            let table = table.clone();
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                let public = gadgets::lut::read_public_lut_many(&table, &i, &mut rep3).unwrap();
+                let shared = gadgets::lut::read_shared_lut_many(&t, &i, &mut rep3).unwrap();
+                tx.send((public, shared))
+            });
+        }
+        let (public1, shared1) = rx1.recv().unwrap();
+        let (public2, shared2) = rx2.recv().unwrap();
+        let (public3, shared3) = rx3.recv().unwrap();
+        let is_public = rep3::combine_field_elements(&public1, &public2, &public3);
+        let is_shared = rep3::combine_field_elements(&shared1, &shared2, &shared3);
+        assert_eq!(is_public, should_result);
+        assert_eq!(is_shared, should_result);
+    }
+
+    #[test]
+    fn rep3_lut_write() {
+        const TABLE_SIZE: usize = 9;
+        const NUM_WRITES: usize = 15;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let table = (0..TABLE_SIZE)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        // more writes than entries, so some indices are written multiple times
+        let indices = (0..NUM_WRITES)
+            .map(|_| rng.gen_range(0..TABLE_SIZE))
+            .collect_vec();
+        let values = (0..NUM_WRITES)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let table_shares = rep3::share_field_elements(&table, &mut rng);
+        let index_shares = rep3::share_field_elements(
+            &indices
+                .iter()
+                .map(|i| ark_bn254::Fr::from(*i as u64))
+                .collect_vec(),
+            &mut rng,
+        );
+        let value_shares = rep3::share_field_elements(&values, &mut rng);
+        let mut should_result = table;
+        for (i, v) in indices.iter().zip(values.iter()) {
+            should_result[*i] = *v;
+        }
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, t, i, v) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            table_shares,
+            index_shares,
+            value_shares
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(gadgets::lut::write_lut_many(&t, &i, &v, &mut rep3).unwrap())
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&result1, &result2, &result3);
+        assert_eq!(is_result, should_result);
+    }
+
+    #[test]
+    fn rep3_quicksort() {
+        const VEC_SIZE: usize = 50;
+        const BITSIZE: usize = 8;
+
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        // small values to provoke ties
+        let x = (0..VEC_SIZE)
+            .map(|_| ark_bn254::Fr::from(rng.gen_range(0u64..1 << BITSIZE)))
+            .collect_vec();
+        let x_shares = rep3::share_field_elements(&x, &mut rng);
+
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x) in izip!(test_network.get_party_networks(), [tx1, tx2, tx3], x_shares) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                tx.send(gadgets::sort::quicksort_with_permutation(&x, &mut rep3, BITSIZE).unwrap())
+            });
+        }
+        let (sorted1, perm1) = rx1.recv().unwrap();
+        let (sorted2, perm2) = rx2.recv().unwrap();
+        let (sorted3, perm3) = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_elements(&sorted1, &sorted2, &sorted3);
+        let perm = rep3::combine_field_elements(&perm1, &perm2, &perm3);
+        let mut should_result = x.clone();
+        should_result.sort();
+        assert_eq!(is_result, should_result);
+        for (sorted, index) in is_result.iter().zip(perm) {
+            let index: BigUint = index.into();
+            let index = usize::try_from(index).unwrap();
+            assert_eq!(*sorted, x[index]);
+        }
+    }
+
     #[test]
     fn rep3_batcher_odd_even_merge_sort_via_yao() {
         const VEC_SIZE: usize = 10;
@@ -1493,11 +4851,18 @@ mod field_share {
 mod curve_share {
     use std::{sync::mpsc, thread};
 
+    use ark_ec::Group;
     use ark_std::UniformRand;
     use itertools::izip;
 
-    use mpc_core::protocols::rep3::{self, pointshare};
+    use mpc_core::protocols::rep3::{
+        self,
+        malicious::{self, SecurityLevel},
+        network::IoContext,
+        pointshare,
+    };
     use rand::thread_rng;
+    use tests::rep3_network::Rep3TestNetwork;
 
     #[test]
     fn rep3_add() {
@@ -1588,4 +4953,47 @@ mod curve_share {
         let is_result = rep3::combine_curve_point(result1, result2, result3);
         assert_eq!(is_result, should_result);
     }
+
+    #[test]
+    fn rep3_malicious_scalar_mul() {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let point = ark_bn254::G1Projective::rand(&mut rng);
+        let scalar = ark_bn254::Fr::rand(&mut rng);
+        let point_shares = rep3::share_curve_point(point, &mut rng);
+        let scalar_shares = rep3::share_field_element(scalar, &mut rng);
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+
+        for (net, tx, point, scalar) in izip!(
+            test_network.get_party_networks(),
+            [tx1, tx2, tx3],
+            point_shares,
+            scalar_shares
+        ) {
+            thread::spawn(move || {
+                let mut rep3 = IoContext::init(net).unwrap();
+                rep3.set_security_level(SecurityLevel::Malicious);
+                let mul = pointshare::scalar_mul(&point, scalar, &mut rep3).unwrap();
+                let opened = pointshare::open_point(&mul, &mut rep3).unwrap();
+                // an additive error in the result of a scalar multiplication is detected
+                let mut cheated = mul.clone();
+                pointshare::add_assign_public(
+                    &mut cheated,
+                    &ark_bn254::G1Projective::generator(),
+                    rep3.id,
+                );
+                let detected =
+                    malicious::verify_scalar_mul_points(&[scalar], &[point], &[cheated], &mut rep3)
+                        .is_err();
+                tx.send((opened, detected))
+            });
+        }
+        for rx in [rx1, rx2, rx3] {
+            let (opened, detected) = rx.recv().unwrap();
+            assert_eq!(opened, point * scalar);
+            assert!(detected);
+        }
+    }
 }
@@ -858,8 +858,8 @@ mod ring_share {
                 let y_ = garbler.encode_ring(y);
 
                 // This is without OT, just a simulation
-                garbler.add_bundle_to_circuit(&x_.evaluator_wires);
-                garbler.add_bundle_to_circuit(&y_.evaluator_wires);
+                garbler.add_bundle_to_circuit(&x_.evaluator_wires).unwrap();
+                garbler.add_bundle_to_circuit(&y_.evaluator_wires).unwrap();
 
                 let circuit_output = GarbledCircuits::adder_mod_2k(
                     &mut garbler,
@@ -1448,6 +1448,47 @@ mod ring_share {
         );
     }
 
+    fn rep3_binary_ring_bridge_t<T: IntRing2k>()
+    where
+        Standard: Distribution<T>,
+    {
+        let test_network = Rep3TestNetwork::default();
+        let mut rng = thread_rng();
+        let x = T::cast_to_biguint(&rng.gen::<T>());
+        let y = T::cast_to_biguint(&rng.gen::<T>());
+        let x_shares = rep3::share_field_element(ark_bn254::Fr::from(x.clone()), &mut rng);
+        let y_shares = rep3::share_field_element(ark_bn254::Fr::from(y.clone()), &mut rng);
+        let should_result = ark_bn254::Fr::from(x & y);
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
+        let (tx3, rx3) = mpsc::channel();
+        for (net, tx, x, y) in izip!(
+            test_network.get_party_networks().into_iter(),
+            [tx1, tx2, tx3],
+            x_shares.into_iter(),
+            y_shares.into_iter(),
+        ) {
+            thread::spawn(move || {
+                let mut ctx = IoContext::init(net).unwrap();
+                let x = casts::field_to_binary_ring::<_, T, _>(x, &mut ctx).unwrap();
+                let y = casts::field_to_binary_ring::<_, T, _>(y, &mut ctx).unwrap();
+                let and = rep3_ring::binary::and(&x, &y, &mut ctx).unwrap();
+                let z = casts::binary_ring_to_field::<_, ark_bn254::Fr, _>(&and, &mut ctx).unwrap();
+                tx.send(z)
+            });
+        }
+        let result1 = rx1.recv().unwrap();
+        let result2 = rx2.recv().unwrap();
+        let result3 = rx3.recv().unwrap();
+        let is_result = rep3::combine_field_element(result1, result2, result3);
+        assert_eq!(is_result, should_result);
+    }
+
+    #[test]
+    fn rep3_binary_ring_bridge() {
+        apply_to_all!(rep3_binary_ring_bridge_t, [u8, u16, u32, u64]);
+    }
+
     fn rep3_field_to_ring_cast_gc_t<T: IntRing2k>()
     where
         Standard: Distribution<T>,
@@ -0,0 +1,85 @@
+mod field_share {
+    use ark_std::UniformRand;
+    use itertools::{izip, Itertools};
+    use mpc_core::protocols::rss::{self, arithmetic, RssProtocol};
+    use rand::thread_rng;
+    use std::thread;
+    use tests::shamir_network::ShamirTestNetwork;
+
+    #[test]
+    fn rss_share_combine() {
+        let mut rng = thread_rng();
+        let x = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        for (num_parties, threshold) in [(3, 1), (4, 1), (5, 2)] {
+            let shares = rss::share_field_elements(&x, num_parties, threshold, &mut rng);
+            assert_eq!(rss::combine_field_elements(&shares, threshold).unwrap(), x);
+        }
+    }
+
+    fn rss_mul_then_add_inner(num_parties: usize, threshold: usize) {
+        let mut rng = thread_rng();
+        let x = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let y = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let public = ark_bn254::Fr::rand(&mut rng);
+        let x_shares = rss::share_field_elements(&x, num_parties, threshold, &mut rng);
+        let y_shares = rss::share_field_elements(&y, num_parties, threshold, &mut rng);
+        let should_result = izip!(&x, &y)
+            .map(|(x, y)| *x * y * y + x + public)
+            .collect_vec();
+
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut threads = Vec::with_capacity(num_parties);
+        for (net, x, y) in izip!(test_network.get_party_networks(), x_shares, y_shares) {
+            threads.push(thread::spawn(move || {
+                let mut rss = RssProtocol::new(threshold, net).unwrap();
+                let mul = arithmetic::mul_vec(&x, &y, &mut rss).unwrap();
+                let mul = arithmetic::mul_vec(&mul, &y, &mut rss).unwrap();
+                let res = izip!(mul, &x)
+                    .map(|(m, x)| arithmetic::add_public(arithmetic::add(m, x), public, &rss))
+                    .collect_vec();
+                let opened = arithmetic::open_vec(&res, &mut rss).unwrap();
+                (res, opened)
+            }));
+        }
+        let mut results = Vec::with_capacity(num_parties);
+        for t in threads {
+            let (res, opened) = t.join().unwrap();
+            assert_eq!(opened, should_result);
+            results.push(res);
+        }
+        assert_eq!(
+            rss::combine_field_elements(&results, threshold).unwrap(),
+            should_result
+        );
+    }
+
+    #[test]
+    fn rss_mul_then_add() {
+        rss_mul_then_add_inner(3, 1);
+        rss_mul_then_add_inner(4, 1);
+        rss_mul_then_add_inner(5, 2);
+    }
+
+    #[test]
+    fn rss_rand() {
+        let num_parties = 4;
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut threads = Vec::with_capacity(num_parties);
+        for net in test_network.get_party_networks() {
+            threads.push(thread::spawn(move || {
+                let mut rss = RssProtocol::new(1, net).unwrap();
+                let r = rss.rand_vec::<ark_bn254::Fr>(10).unwrap();
+                let promoted =
+                    arithmetic::promote_to_trivial_share(ark_bn254::Fr::from(5u64), &rss);
+                let opened = arithmetic::open_vec(&r, &mut rss).unwrap();
+                let five = arithmetic::open(&promoted, &mut rss).unwrap();
+                (opened, five)
+            }));
+        }
+        let results = threads.into_iter().map(|t| t.join().unwrap()).collect_vec();
+        for (opened, five) in &results {
+            assert_eq!(opened, &results[0].0);
+            assert_eq!(*five, ark_bn254::Fr::from(5u64));
+        }
+    }
+}
@@ -2,8 +2,13 @@ mod field_share {
     use ark_ff::Field;
     use ark_std::{UniformRand, Zero};
     use itertools::{izip, Itertools};
-    use mpc_core::protocols::shamir::{self, arithmetic, ShamirPreprocessing};
-    use rand::thread_rng;
+    use mpc_core::protocols::shamir::{
+        self, arithmetic,
+        network::ShamirNetwork,
+        triples::{ShamirTriplePool, ShamirTriplePoolConfig},
+        ShamirPreprocessing, ShamirProtocol,
+    };
+    use rand::{thread_rng, Rng};
     use std::{str::FromStr, sync::mpsc, thread};
     use tests::shamir_network::ShamirTestNetwork;
 
@@ -83,6 +88,141 @@ mod field_share {
         shamir_sub_inner(10, 4);
     }
 
+    fn shamir_open_vec_to_inner(num_parties: usize, threshold: usize) {
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut rng = thread_rng();
+        let x = (0..10)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let x_shares = shamir::share_field_elements(&x, threshold, num_parties, &mut rng);
+        let target = num_parties - 1;
+
+        let mut tx = Vec::with_capacity(num_parties);
+        let mut rx = Vec::with_capacity(num_parties);
+        for _ in 0..num_parties {
+            let (t, r) = mpsc::channel();
+            tx.push(t);
+            rx.push(r);
+        }
+
+        for (net, tx, x) in izip!(test_network.get_party_networks(), tx, x_shares) {
+            thread::spawn(move || {
+                let mut shamir = ShamirPreprocessing::new(threshold, net, 1).unwrap().into();
+                tx.send(arithmetic::open_vec_to(&x, target, &mut shamir).unwrap())
+            });
+        }
+
+        for (i, r) in rx.into_iter().enumerate() {
+            let result = r.recv().unwrap();
+            if i == target {
+                assert_eq!(result, Some(x.clone()));
+            } else {
+                assert_eq!(result, None);
+            }
+        }
+    }
+
+    #[test]
+    fn shamir_open_vec_to() {
+        shamir_open_vec_to_inner(3, 1);
+        shamir_open_vec_to_inner(10, 4);
+    }
+
+    fn shamir_open_vec_robust_inner(num_parties: usize, threshold: usize, num_corrupted: usize) {
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut rng = thread_rng();
+        let x = (0..10)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let x_shares = shamir::share_field_elements(&x, threshold, num_parties, &mut rng);
+
+        let mut tx = Vec::with_capacity(num_parties);
+        let mut rx = Vec::with_capacity(num_parties);
+        for _ in 0..num_parties {
+            let (t, r) = mpsc::channel();
+            tx.push(t);
+            rx.push(r);
+        }
+
+        for (id, net, tx, x) in izip!(0.., test_network.get_party_networks(), tx, x_shares) {
+            thread::spawn(move || {
+                let mut shamir: ShamirProtocol<_, _> =
+                    ShamirPreprocessing::new(threshold, net, 1).unwrap().into();
+                shamir.set_robust_open(true);
+                // the last parties send corrupted shares
+                let x = if id >= num_parties - num_corrupted {
+                    x.into_iter()
+                        .map(|x| arithmetic::add_public(x, ark_bn254::Fr::from(id as u64)))
+                        .collect()
+                } else {
+                    x
+                };
+                let opened = arithmetic::open_vec(&x, &mut shamir).unwrap();
+                let corrupted = shamir.corrupted_parties().iter().copied().collect_vec();
+                tx.send((opened, corrupted))
+            });
+        }
+
+        for r in rx {
+            let (opened, corrupted) = r.recv().unwrap();
+            assert_eq!(opened, x);
+            assert_eq!(
+                corrupted,
+                (num_parties - num_corrupted..num_parties).collect_vec()
+            );
+        }
+    }
+
+    #[test]
+    fn shamir_open_vec_robust() {
+        shamir_open_vec_robust_inner(3, 1, 0);
+        shamir_open_vec_robust_inner(4, 1, 1);
+        shamir_open_vec_robust_inner(10, 3, 3);
+    }
+
+    fn shamir_verify_degree_vec_inner(num_parties: usize, threshold: usize, malformed: bool) {
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut rng = thread_rng();
+        let x = (0..10)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let mut x_shares = shamir::share_field_elements(&x, threshold, num_parties, &mut rng);
+        if malformed {
+            // replace the sharing of one value by a sharing of degree threshold + 1
+            let shares = shamir::share_field_element(x[3], threshold + 1, num_parties, &mut rng);
+            for (x, s) in izip!(&mut x_shares, shares) {
+                x[3] = s;
+            }
+        }
+
+        let mut tx = Vec::with_capacity(num_parties);
+        let mut rx = Vec::with_capacity(num_parties);
+        for _ in 0..num_parties {
+            let (t, r) = mpsc::channel();
+            tx.push(t);
+            rx.push(r);
+        }
+
+        for (net, tx, x) in izip!(test_network.get_party_networks(), tx, x_shares) {
+            thread::spawn(move || {
+                let mut shamir = ShamirPreprocessing::new(threshold, net, 2).unwrap().into();
+                tx.send(arithmetic::verify_degree_vec(&x, &mut shamir).is_ok())
+            });
+        }
+
+        for r in rx {
+            assert_eq!(r.recv().unwrap(), !malformed);
+        }
+    }
+
+    #[test]
+    fn shamir_verify_degree_vec() {
+        shamir_verify_degree_vec_inner(3, 1, false);
+        shamir_verify_degree_vec_inner(3, 1, true);
+        shamir_verify_degree_vec_inner(10, 4, false);
+        shamir_verify_degree_vec_inner(10, 4, true);
+    }
+
     fn shamir_mul2_then_add_inner(num_parties: usize, threshold: usize) {
         let test_network = ShamirTestNetwork::new(num_parties);
         let mut rng = thread_rng();
@@ -279,6 +419,81 @@ mod field_share {
         shamir_mul_vec_inner(10, 4);
     }
 
+    fn shamir_triple_pool_inner(num_parties: usize, threshold: usize) {
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut rng = thread_rng();
+        let x = (0..10)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let y = (0..x.len())
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let should_result = izip!(&x, &y).map(|(x, y)| x * y).collect_vec();
+        let x_shares = shamir::share_field_elements(&x, threshold, num_parties, &mut rng);
+        let y_shares = shamir::share_field_elements(&y, threshold, num_parties, &mut rng);
+        let pool_name = format!("shamir-triple-pool-test-{}", rng.gen::<u64>());
+        let dir = std::env::temp_dir();
+        let config = ShamirTriplePoolConfig {
+            pool_size: 16,
+            refill_threshold: 8,
+        };
+
+        let mut tx = Vec::with_capacity(num_parties);
+        let mut rx = Vec::with_capacity(num_parties);
+        for _ in 0..num_parties {
+            let (t, r) = mpsc::channel();
+            tx.push(t);
+            rx.push(r);
+        }
+
+        for (id, net, tx, x, y) in izip!(
+            0..,
+            test_network.get_party_networks(),
+            tx,
+            x_shares,
+            y_shares
+        ) {
+            let path = dir.join(format!("{pool_name}.party{id}"));
+            thread::spawn(move || {
+                let mut shamir = ShamirPreprocessing::new(threshold, net, 16).unwrap().into();
+                // fill the pool ahead of time
+                let mut pool = ShamirTriplePool::open(&path, config).unwrap();
+                assert!(pool.refill_if_needed(&mut shamir).unwrap());
+                assert_eq!(pool.len(), 16);
+
+                // the online phase reopens the pool and consumes the triples
+                let mut pool = ShamirTriplePool::open(&path, config).unwrap();
+                let mul = pool.mul_vec(&x, &y, &mut shamir).unwrap();
+                assert!(pool.needs_refill());
+                assert_eq!(
+                    ShamirTriplePool::<ark_bn254::Fr>::open(&path, config)
+                        .unwrap()
+                        .len(),
+                    6
+                );
+                std::fs::remove_file(path).unwrap();
+                tx.send(mul)
+            });
+        }
+
+        let mut results = Vec::with_capacity(num_parties);
+        for r in rx {
+            results.push(r.recv().unwrap());
+        }
+
+        let is_result =
+            shamir::combine_field_elements(&results, &(1..=num_parties).collect_vec(), threshold)
+                .unwrap();
+
+        assert_eq!(is_result, should_result);
+    }
+
+    #[test]
+    fn shamir_triple_pool() {
+        shamir_triple_pool_inner(3, 1);
+        shamir_triple_pool_inner(10, 4);
+    }
+
     fn shamir_neg_inner(num_parties: usize, threshold: usize) {
         let mut rng = thread_rng();
         let x = ark_bn254::Fr::rand(&mut rng);
@@ -357,15 +572,64 @@ mod field_share {
         shamir_inv_inner(3, 1);
         shamir_inv_inner(10, 4);
     }
+
+    #[test]
+    fn shamir_consistent_broadcast() {
+        let num_parties = 4;
+        let mut rng = thread_rng();
+        let x = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut threads = Vec::with_capacity(num_parties);
+        for mut net in test_network.get_party_networks() {
+            let x = x.clone();
+            threads.push(thread::spawn(move || {
+                if net.get_id() == 1 {
+                    net.send_broadcast_many(&x).unwrap();
+                    x
+                } else {
+                    net.recv_broadcast_many(1).unwrap()
+                }
+            }));
+        }
+        for t in threads {
+            assert_eq!(t.join().unwrap(), x);
+        }
+    }
+
+    #[test]
+    fn shamir_inconsistent_broadcast_detected() {
+        let num_parties = 3;
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut threads = Vec::with_capacity(num_parties);
+        for mut net in test_network.get_party_networks() {
+            threads.push(thread::spawn(move || {
+                if net.get_id() == 0 {
+                    // the sender sends different values to the other parties
+                    net.send(1, ark_bn254::Fr::from(1u64))?;
+                    net.send(2, ark_bn254::Fr::from(2u64))?;
+                    net.broadcast(vec![0u8; 32])?;
+                    Ok(None)
+                } else {
+                    net.recv_broadcast::<ark_bn254::Fr>(0).map(Some)
+                }
+            }));
+        }
+        let results = threads.into_iter().map(|t| t.join().unwrap()).collect_vec();
+        assert!(results[1].is_err());
+        assert!(results[2].is_err());
+    }
 }
 
 mod curve_share {
     use std::{sync::mpsc, thread};
 
+    use ark_ec::Group;
     use ark_ff::UniformRand;
     use itertools::{izip, Itertools};
-    use mpc_core::protocols::shamir::{self, pointshare};
+    use mpc_core::protocols::shamir::{self, pointshare, ShamirPreprocessing, ShamirProtocol};
     use rand::thread_rng;
+    use tests::shamir_network::ShamirTestNetwork;
 
     fn shamir_add_inner(num_parties: usize, threshold: usize) {
         let mut rng = thread_rng();
@@ -520,4 +784,197 @@ mod curve_share {
         shamir_scalar_mul_public_scalar_inner(3, 1);
         shamir_scalar_mul_public_scalar_inner(10, 4);
     }
+
+    fn shamir_open_point_many_robust_inner(num_parties: usize, threshold: usize, corrupt: bool) {
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut rng = thread_rng();
+        let x = (0..10)
+            .map(|_| ark_bn254::G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let mut x_shares = vec![Vec::with_capacity(x.len()); num_parties];
+        for x in x.iter() {
+            let shares = shamir::share_curve_point(*x, threshold, num_parties, &mut rng);
+            for (party, share) in x_shares.iter_mut().zip(shares) {
+                party.push(share);
+            }
+        }
+
+        let mut tx = Vec::with_capacity(num_parties);
+        let mut rx = Vec::with_capacity(num_parties);
+        for _ in 0..num_parties {
+            let (t, r) = mpsc::channel();
+            tx.push(t);
+            rx.push(r);
+        }
+
+        for (id, net, tx, mut x) in izip!(0.., test_network.get_party_networks(), tx, x_shares) {
+            thread::spawn(move || {
+                let mut shamir: ShamirProtocol<ark_bn254::Fr, _> =
+                    ShamirPreprocessing::new(threshold, net, 1).unwrap().into();
+                shamir.set_robust_open(true);
+                // the last party sends a corrupted share
+                if corrupt && id == num_parties - 1 {
+                    pointshare::add_assign_public(&mut x[0], &ark_bn254::G1Projective::generator());
+                }
+                tx.send(pointshare::open_point_many(&x, &mut shamir).ok())
+            });
+        }
+
+        for r in rx {
+            let opened = r.recv().unwrap();
+            if corrupt {
+                // corrupted point shares are detected, but cannot be corrected
+                assert!(opened.is_none());
+            } else {
+                assert_eq!(opened.unwrap(), x);
+            }
+        }
+    }
+
+    #[test]
+    fn shamir_open_point_many_robust() {
+        shamir_open_point_many_robust_inner(3, 1, false);
+        shamir_open_point_many_robust_inner(4, 1, true);
+        shamir_open_point_many_robust_inner(10, 3, false);
+        shamir_open_point_many_robust_inner(10, 3, true);
+    }
+}
+
+mod packed_share {
+    use ark_std::UniformRand;
+    use itertools::{izip, Itertools};
+    use mpc_core::protocols::shamir::{
+        self,
+        packed::{self, ShamirPackedProtocol},
+    };
+    use rand::thread_rng;
+    use std::{sync::mpsc, thread};
+    use tests::shamir_network::ShamirTestNetwork;
+
+    fn shamir_packed_share_combine_inner(num_parties: usize, threshold: usize, pack_size: usize) {
+        let mut rng = thread_rng();
+        let x = (0..7).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let shares = packed::share_field_elements(&x, threshold, pack_size, num_parties, &mut rng);
+        let degree = packed::packed_degree(threshold, pack_size);
+
+        let is_result = packed::combine_field_elements(
+            &shares[num_parties - degree - 1..],
+            &(num_parties - degree..=num_parties).collect_vec(),
+            threshold,
+            pack_size,
+            x.len(),
+        )
+        .unwrap();
+        assert_eq!(is_result, x);
+    }
+
+    #[test]
+    fn shamir_packed_share_combine() {
+        shamir_packed_share_combine_inner(3, 1, 1);
+        shamir_packed_share_combine_inner(7, 1, 3);
+        shamir_packed_share_combine_inner(10, 2, 3);
+    }
+
+    fn shamir_packed_mul_vec_inner(num_parties: usize, threshold: usize, pack_size: usize) {
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut rng = thread_rng();
+        let x = (0..5).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let y = (0..x.len())
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let public = (0..pack_size)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect_vec();
+        let should_result = izip!(&x, &y)
+            .enumerate()
+            .map(|(i, (x, y))| x * y * y + public[i % pack_size])
+            .collect_vec();
+
+        let x_shares =
+            packed::share_field_elements(&x, threshold, pack_size, num_parties, &mut rng);
+        let y_shares =
+            packed::share_field_elements(&y, threshold, pack_size, num_parties, &mut rng);
+
+        let mut tx = Vec::with_capacity(num_parties);
+        let mut rx = Vec::with_capacity(num_parties);
+        for _ in 0..num_parties {
+            let (t, r) = mpsc::channel();
+            tx.push(t);
+            rx.push(r);
+        }
+
+        for (net, tx, x, y) in izip!(test_network.get_party_networks(), tx, x_shares, y_shares) {
+            let public = public.clone();
+            thread::spawn(move || {
+                let mut protocol = ShamirPackedProtocol::new(threshold, pack_size, net).unwrap();
+                let mul = packed::mul_vec(&x, &y, &mut protocol).unwrap();
+                let mul = packed::mul_vec(&mul, &y, &mut protocol).unwrap();
+                let res = mul
+                    .into_iter()
+                    .map(|m| packed::add_public(m, &public, &protocol))
+                    .collect_vec();
+                let opened = packed::open_vec(&res, &mut protocol).unwrap();
+                tx.send(opened)
+            });
+        }
+
+        for r in rx {
+            let mut is_result = r.recv().unwrap();
+            is_result.truncate(should_result.len());
+            assert_eq!(is_result, should_result);
+        }
+    }
+
+    #[test]
+    fn shamir_packed_mul_vec() {
+        shamir_packed_mul_vec_inner(3, 1, 1);
+        shamir_packed_mul_vec_inner(7, 1, 2);
+        shamir_packed_mul_vec_inner(10, 2, 3);
+    }
+
+    fn shamir_packed_unpack_vec_inner(num_parties: usize, threshold: usize, pack_size: usize) {
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut rng = thread_rng();
+        let x = (0..5).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let x_shares =
+            packed::share_field_elements(&x, threshold, pack_size, num_parties, &mut rng);
+
+        let mut tx = Vec::with_capacity(num_parties);
+        let mut rx = Vec::with_capacity(num_parties);
+        for _ in 0..num_parties {
+            let (t, r) = mpsc::channel();
+            tx.push(t);
+            rx.push(r);
+        }
+
+        let num_values = x.len();
+        for (net, tx, x) in izip!(test_network.get_party_networks(), tx, x_shares) {
+            thread::spawn(move || {
+                let mut protocol = ShamirPackedProtocol::new(threshold, pack_size, net).unwrap();
+                tx.send(packed::unpack_vec(&x, num_values, &mut protocol).unwrap())
+            });
+        }
+
+        let mut results = Vec::with_capacity(num_parties);
+        for r in rx {
+            results.push(r.recv().unwrap());
+        }
+
+        assert!(results.iter().all(|r| r.len() == num_values));
+        let is_result = (0..num_values)
+            .map(|i| {
+                let shares = results.iter().map(|r| r[i]).collect_vec();
+                shamir::combine_field_element(&shares, &(1..=num_parties).collect_vec(), threshold)
+                    .unwrap()
+            })
+            .collect_vec();
+        assert_eq!(is_result, x);
+    }
+
+    #[test]
+    fn shamir_packed_unpack_vec() {
+        shamir_packed_unpack_vec_inner(3, 1, 1);
+        shamir_packed_unpack_vec_inner(7, 1, 2);
+        shamir_packed_unpack_vec_inner(10, 2, 3);
+    }
 }
@@ -0,0 +1,229 @@
+mod field_share {
+    use ark_ec::Group;
+    use ark_std::UniformRand;
+    use itertools::{izip, Itertools};
+    use mpc_core::protocols::shamir::network::ShamirNetwork;
+    use mpc_core::protocols::spdz::{self, arithmetic, pointshare, SpdzPreprocessor, SpdzProtocol};
+    use rand::thread_rng;
+    use std::thread;
+    use tests::shamir_network::{PartyTestNetwork, ShamirTestNetwork};
+
+    /// Generates the preprocessed data with the other parties and creates the protocol.
+    fn setup(
+        mut net: PartyTestNetwork,
+        num_triples: usize,
+        num_masks: usize,
+    ) -> SpdzProtocol<ark_bn254::Fr, PartyTestNetwork> {
+        let mut preprocessor = SpdzPreprocessor::new(&mut net).unwrap();
+        let preprocessing = preprocessor
+            .preprocess(&mut net, num_triples, num_masks)
+            .unwrap();
+        SpdzProtocol::new(net, preprocessing).unwrap()
+    }
+
+    #[test]
+    fn spdz_preprocessing() {
+        let num_parties = 3;
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut threads = Vec::with_capacity(num_parties);
+        for mut net in test_network.get_party_networks() {
+            threads.push(thread::spawn(move || {
+                let mut preprocessor = SpdzPreprocessor::<ark_bn254::Fr>::new(&mut net).unwrap();
+                // the batches are authenticated under the same MAC key
+                let first = preprocessor.preprocess(&mut net, 10, 2).unwrap();
+                let second = preprocessor.preprocess(&mut net, 5, 0).unwrap();
+                (first, second)
+            }));
+        }
+        let results = threads.into_iter().map(|t| t.join().unwrap()).collect_vec();
+        let mac_key = results
+            .iter()
+            .map(|(first, _)| first.mac_key_share())
+            .sum::<ark_bn254::Fr>();
+        for (first, second) in results.iter() {
+            assert_eq!(first.mac_key_share(), second.mac_key_share());
+        }
+        let check = |shares: Vec<spdz::SpdzPrimeFieldShare<ark_bn254::Fr>>| {
+            let value = spdz::combine_field_element(&shares);
+            let mac = shares.iter().map(|s| s.mac()).sum::<ark_bn254::Fr>();
+            // the offset is public, so all parties hold the same one
+            assert_eq!(mac, mac_key * (value + shares[0].offset()));
+            value
+        };
+
+        for pre in [
+            results.iter().map(|(first, _)| first.clone()).collect_vec(),
+            results
+                .iter()
+                .map(|(_, second)| second.clone())
+                .collect_vec(),
+        ] {
+            let test_network = ShamirTestNetwork::new(num_parties);
+            let mut threads = Vec::with_capacity(num_parties);
+            let num_triples = pre[0].num_triples();
+            let num_masks = pre[0].num_masks(0);
+            for (net, pre) in izip!(test_network.get_party_networks(), pre) {
+                threads.push(thread::spawn(move || {
+                    let mut spdz = SpdzProtocol::new(net, pre).unwrap();
+                    let id = spdz.network.get_id();
+                    // every multiplication of a random value consumes two triples
+                    let triples = (0..num_triples / 2)
+                        .map(|_| {
+                            let a = spdz.rand().unwrap();
+                            let ab = arithmetic::mul(a, a, &mut spdz).unwrap();
+                            (a, ab)
+                        })
+                        .collect_vec();
+                    let masks = (0..num_parties)
+                        .map(|owner| {
+                            let values = vec![ark_bn254::Fr::from(owner as u64); num_masks];
+                            let values = (id == owner).then_some(values.as_slice());
+                            arithmetic::input_vec(values, owner, num_masks, &mut spdz).unwrap()
+                        })
+                        .collect_vec();
+                    (triples, masks)
+                }));
+            }
+            let results = threads.into_iter().map(|t| t.join().unwrap()).collect_vec();
+            let triples = (0..num_triples / 2).map(|i| {
+                let a = check(results.iter().map(|(t, _)| t[i].0).collect());
+                let ab = check(results.iter().map(|(t, _)| t[i].1).collect());
+                (a, ab)
+            });
+            for (a, ab) in triples {
+                assert_eq!(a * a, ab);
+            }
+            for owner in 0..num_parties {
+                for i in 0..num_masks {
+                    let value = check(results.iter().map(|(_, m)| m[owner][i]).collect());
+                    assert_eq!(value, ark_bn254::Fr::from(owner as u64));
+                }
+            }
+        }
+    }
+
+    fn spdz_mul_inner(num_parties: usize) {
+        let mut rng = thread_rng();
+        let x = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let y = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let should_result = izip!(&x, &y).map(|(x, y)| *x * y).collect_vec();
+
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut threads = Vec::with_capacity(num_parties);
+        for net in test_network.get_party_networks() {
+            let (x, y) = (x.clone(), y.clone());
+            threads.push(thread::spawn(move || {
+                let mut spdz = setup(net, 10, 20);
+                let id = spdz.network.get_id();
+                let inputs = [x, y].concat();
+                let inputs = (id == 0).then_some(inputs.as_slice());
+                let inputs = arithmetic::input_vec(inputs, 0, 20, &mut spdz).unwrap();
+                let (x, y) = inputs.split_at(10);
+                let mul = arithmetic::mul_vec(x, y, &mut spdz).unwrap();
+                let opened = arithmetic::open_vec(&mul, &mut spdz).unwrap();
+                (mul, opened)
+            }));
+        }
+        let results = threads.into_iter().map(|t| t.join().unwrap()).collect_vec();
+        let shares = results.iter().map(|(mul, _)| mul.to_owned()).collect_vec();
+        assert_eq!(spdz::combine_field_elements(&shares), should_result);
+        for (_, opened) in results {
+            assert_eq!(opened, should_result);
+        }
+    }
+
+    #[test]
+    fn spdz_mul() {
+        spdz_mul_inner(2);
+        spdz_mul_inner(3);
+    }
+
+    #[test]
+    fn spdz_input_and_open_to() {
+        let num_parties = 2;
+        let mut rng = thread_rng();
+        let x = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let y = ark_bn254::Fr::rand(&mut rng);
+        let should_result = x.iter().map(|x| *x + y).collect_vec();
+
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut threads = Vec::with_capacity(num_parties);
+        for net in test_network.get_party_networks() {
+            let x = x.clone();
+            threads.push(thread::spawn(move || {
+                let mut spdz = setup(net, 0, 10);
+                let id = spdz.network.get_id();
+                let values = (id == 0).then_some(x.as_slice());
+                let shares = arithmetic::input_vec(values, 0, x.len(), &mut spdz).unwrap();
+                let sum = shares
+                    .into_iter()
+                    .map(|s| arithmetic::add_public(s, y, id))
+                    .collect_vec();
+                arithmetic::open_vec_to(&sum, 1, &mut spdz).unwrap()
+            }));
+        }
+        let results = threads.into_iter().map(|t| t.join().unwrap()).collect_vec();
+        assert_eq!(results[0], None);
+        assert_eq!(results[1], Some(should_result));
+    }
+
+    #[test]
+    fn spdz_mac_check_detects_cheating() {
+        let num_parties = 2;
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let y = ark_bn254::Fr::rand(&mut rng);
+
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut threads = Vec::with_capacity(num_parties);
+        for net in test_network.get_party_networks() {
+            threads.push(thread::spawn(move || {
+                let mut spdz = setup(net, 1, 2);
+                let id = spdz.network.get_id();
+                let inputs = (id == 0).then_some([x, y]);
+                let inputs =
+                    arithmetic::input_vec(inputs.as_ref().map(|i| i.as_slice()), 0, 2, &mut spdz)
+                        .unwrap();
+                let mut mul = arithmetic::mul(inputs[0], inputs[1], &mut spdz).unwrap();
+                if id == 1 {
+                    // the second party adds an error to its share
+                    mul = arithmetic::add_public(mul, ark_bn254::Fr::from(1u64), 0);
+                }
+                arithmetic::open(mul, &mut spdz)
+            }));
+        }
+        for t in threads {
+            assert!(t.join().unwrap().is_err());
+        }
+    }
+
+    #[test]
+    fn spdz_scalar_mul() {
+        let num_parties = 3;
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let k = ark_bn254::Fr::rand(&mut rng);
+        let g = ark_bn254::G1Projective::generator();
+        let should_result = g * k * x;
+
+        let test_network = ShamirTestNetwork::new(num_parties);
+        let mut threads = Vec::with_capacity(num_parties);
+        for net in test_network.get_party_networks() {
+            threads.push(thread::spawn(move || {
+                let mut spdz = setup(net, 1, 2);
+                let id = spdz.network.get_id();
+                let inputs = (id == 0).then_some([x, k]);
+                let inputs =
+                    arithmetic::input_vec(inputs.as_ref().map(|i| i.as_slice()), 0, 2, &mut spdz)
+                        .unwrap();
+                // the point p = k * g is shared without revealing k
+                let p = pointshare::scalar_mul_public_point(inputs[1], &g);
+                let mul = pointshare::scalar_mul(&p, inputs[0], &mut spdz).unwrap();
+                pointshare::open_point(&mul, &mut spdz).unwrap()
+            }));
+        }
+        for t in threads {
+            assert_eq!(t.join().unwrap(), should_result);
+        }
+    }
+}
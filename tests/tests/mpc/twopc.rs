@@ -0,0 +1,312 @@
+mod field_share {
+    use ark_ff::{Field, One, Zero};
+    use ark_std::UniformRand;
+    use itertools::{izip, Itertools};
+    use mpc_core::protocols::{
+        rep3::gadgets::keccak::{keccak_f1600, STATE_BITS},
+        twopc::{
+            self, arithmetic, binary, conversion,
+            gadgets::{keccak, lut},
+            TwoPcProtocol,
+        },
+    };
+    use num_bigint::BigUint;
+    use rand::thread_rng;
+    use rand::Rng;
+    use std::thread;
+    use tests::shamir_network::ShamirTestNetwork;
+
+    #[test]
+    fn twopc_mul() {
+        let mut rng = thread_rng();
+        let x = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let y = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let x_shares = twopc::share_field_elements(&x, &mut rng);
+        let y_shares = twopc::share_field_elements(&y, &mut rng);
+        let should_result = izip!(&x, &y).map(|(x, y)| *x * y).collect_vec();
+
+        let test_network = ShamirTestNetwork::new(2);
+        let mut threads = Vec::with_capacity(2);
+        for (net, x, y) in izip!(test_network.get_party_networks(), x_shares, y_shares) {
+            threads.push(thread::spawn(move || {
+                let mut twopc = TwoPcProtocol::new(net).unwrap();
+                arithmetic::mul_vec(&x, &y, &mut twopc).unwrap()
+            }));
+        }
+        let result2 = threads.pop().unwrap().join().unwrap();
+        let result1 = threads.pop().unwrap().join().unwrap();
+        assert_eq!(
+            twopc::combine_field_elements(&result1, &result2),
+            should_result
+        );
+    }
+
+    #[test]
+    fn twopc_inv() {
+        let mut rng = thread_rng();
+        let x = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let x_shares = twopc::share_field_elements(&x, &mut rng);
+        let should_result = x.iter().map(|x| x.inverse().unwrap()).collect_vec();
+
+        let test_network = ShamirTestNetwork::new(2);
+        let mut threads = Vec::with_capacity(2);
+        for (net, x) in izip!(test_network.get_party_networks(), x_shares) {
+            threads.push(thread::spawn(move || {
+                let mut twopc = TwoPcProtocol::new(net).unwrap();
+                let inv = arithmetic::inv_vec(&x, &mut twopc).unwrap();
+                arithmetic::open_vec(&inv, &mut twopc).unwrap()
+            }));
+        }
+        for t in threads {
+            assert_eq!(t.join().unwrap(), should_result);
+        }
+    }
+
+    #[test]
+    fn twopc_comparisons() {
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::from(17u64);
+        let values = [ark_bn254::Fr::from(3u64), x, ark_bn254::Fr::from(42u64)];
+        let x_shares = twopc::share_field_element(x, &mut rng);
+        let y_shares = twopc::share_field_elements(&values, &mut rng);
+        let to_field = |b: bool| {
+            if b {
+                ark_bn254::Fr::one()
+            } else {
+                ark_bn254::Fr::zero()
+            }
+        };
+        let should_result = values
+            .iter()
+            .flat_map(|y| [to_field(x >= *y), to_field(x < *y), to_field(x == *y)])
+            .collect_vec();
+
+        let test_network = ShamirTestNetwork::new(2);
+        let mut threads = Vec::with_capacity(2);
+        for (net, x, y) in izip!(test_network.get_party_networks(), x_shares, y_shares) {
+            threads.push(thread::spawn(move || {
+                let mut twopc = TwoPcProtocol::new(net).unwrap();
+                let mut res = Vec::with_capacity(3 * y.len());
+                for y in y {
+                    res.push(arithmetic::ge(x, y, &mut twopc).unwrap());
+                    res.push(arithmetic::lt(x, y, &mut twopc).unwrap());
+                    res.push(arithmetic::eq(x, y, &mut twopc).unwrap());
+                }
+                arithmetic::open_vec(&res, &mut twopc).unwrap()
+            }));
+        }
+        for t in threads {
+            assert_eq!(t.join().unwrap(), should_result);
+        }
+    }
+
+    #[test]
+    fn twopc_a2b_b2a() {
+        let mut rng = thread_rng();
+        let x = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let x_shares = twopc::share_field_elements(&x, &mut rng);
+
+        let test_network = ShamirTestNetwork::new(2);
+        let mut threads = Vec::with_capacity(2);
+        for (net, x) in izip!(test_network.get_party_networks(), x_shares) {
+            threads.push(thread::spawn(move || {
+                let mut twopc = TwoPcProtocol::new(net).unwrap();
+                let bits = conversion::a2b_many(&x, &mut twopc).unwrap();
+                let bitlen = <ark_bn254::Fr as ark_ff::PrimeField>::MODULUS_BIT_SIZE as usize;
+                let back =
+                    conversion::b2a_many::<ark_bn254::Fr, _>(&bits, bitlen, &mut twopc).unwrap();
+                (bits, back)
+            }));
+        }
+        let (bits2, back2) = threads.pop().unwrap().join().unwrap();
+        let (bits1, back1) = threads.pop().unwrap().join().unwrap();
+        for (x, b1, b2) in izip!(&x, bits1, bits2) {
+            let should: num_bigint::BigUint = (*x).into();
+            assert_eq!(b1 ^ b2, should);
+        }
+        assert_eq!(twopc::combine_field_elements(&back1, &back2), x);
+    }
+
+    #[test]
+    fn twopc_scalar_mul() {
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let p = ark_bn254::G1Projective::rand(&mut rng);
+        let x_shares = twopc::share_field_element(x, &mut rng);
+        let p_shares = twopc::share_curve_point(p, &mut rng);
+        let should_result = p * x;
+
+        let test_network = ShamirTestNetwork::new(2);
+        let mut threads = Vec::with_capacity(2);
+        for (net, x, p) in izip!(test_network.get_party_networks(), x_shares, p_shares) {
+            threads.push(thread::spawn(move || {
+                let mut twopc = TwoPcProtocol::new(net).unwrap();
+                let mul = twopc::pointshare::scalar_mul(&p, x, &mut twopc).unwrap();
+                twopc::pointshare::open_point(&mul, &mut twopc).unwrap()
+            }));
+        }
+        for t in threads {
+            assert_eq!(t.join().unwrap(), should_result);
+        }
+    }
+
+    #[test]
+    fn twopc_sqrt_pow() {
+        let mut rng = thread_rng();
+        let x = ark_bn254::Fr::rand(&mut rng);
+        let e = rng.gen::<u64>();
+        let base = ark_bn254::Fr::from(3u64);
+        let shares = twopc::share_field_elements(&[x * x, x, ark_bn254::Fr::from(e)], &mut rng);
+        let should_result = [base.pow([e]), x.pow([e])];
+
+        let test_network = ShamirTestNetwork::new(2);
+        let mut threads = Vec::with_capacity(2);
+        for (net, shares) in izip!(test_network.get_party_networks(), shares) {
+            threads.push(thread::spawn(move || {
+                let mut twopc = TwoPcProtocol::new(net).unwrap();
+                let sqrt = arithmetic::sqrt(shares[0], &mut twopc).unwrap();
+                let pow_public_base =
+                    arithmetic::pow_public_base(base, shares[2], &mut twopc).unwrap();
+                let pow_shared =
+                    arithmetic::pow_shared_exponent(shares[1], shares[2], &mut twopc).unwrap();
+                arithmetic::open_vec(&[sqrt, pow_public_base, pow_shared], &mut twopc).unwrap()
+            }));
+        }
+        for t in threads {
+            let result = t.join().unwrap();
+            assert!(result[0] == x || result[0] == -x);
+            assert_eq!(result[1..], should_result);
+        }
+    }
+
+    #[test]
+    fn twopc_divmod_shift() {
+        let mut rng = thread_rng();
+        let a = (0..4).map(|_| rng.gen::<u128>()).collect_vec();
+        let mut b = (0..4).map(|_| rng.gen::<u64>() as u128).collect_vec();
+        b[0] = 1;
+        let shifts = [0u128, 5, 100, 300];
+        let to_field = |x: &[u128]| x.iter().map(|x| ark_bn254::Fr::from(*x)).collect_vec();
+        let a_shares = twopc::share_field_elements(&to_field(&a), &mut rng);
+        let b_shares = twopc::share_field_elements(&to_field(&b), &mut rng);
+        let shift_shares = twopc::share_field_elements(&to_field(&shifts), &mut rng);
+
+        let test_network = ShamirTestNetwork::new(2);
+        let mut threads = Vec::with_capacity(2);
+        for (net, a, b, shifts) in izip!(
+            test_network.get_party_networks(),
+            a_shares,
+            b_shares,
+            shift_shares
+        ) {
+            threads.push(thread::spawn(move || {
+                let mut twopc = TwoPcProtocol::new(net).unwrap();
+                let bitlen = <ark_bn254::Fr as ark_ff::PrimeField>::MODULUS_BIT_SIZE as usize;
+                let a = conversion::a2b_many(&a, &mut twopc).unwrap();
+                let b = conversion::a2b_many(&b, &mut twopc).unwrap();
+                let shifts = conversion::a2b_many(&shifts, &mut twopc).unwrap();
+                let (q, r) = binary::divmod_many(&a, &b, bitlen, &mut twopc).unwrap();
+                let shifted = izip!(&a, &shifts)
+                    .map(|(a, s)| binary::shift_r_by_shared(a, s, bitlen, &mut twopc).unwrap())
+                    .collect_vec();
+                (q, r, shifted)
+            }));
+        }
+        let (q2, r2, s2) = threads.pop().unwrap().join().unwrap();
+        let (q1, r1, s1) = threads.pop().unwrap().join().unwrap();
+        for (i, (a, b, shift)) in izip!(&a, &b, shifts).enumerate() {
+            assert_eq!(&q1[i] ^ &q2[i], BigUint::from(a / b));
+            assert_eq!(&r1[i] ^ &r2[i], BigUint::from(a % b));
+            let should = if shift < 128 { a >> shift } else { 0 };
+            assert_eq!(&s1[i] ^ &s2[i], BigUint::from(should));
+        }
+    }
+
+    #[test]
+    fn twopc_lut() {
+        let mut rng = thread_rng();
+        let table = (0..10).map(|_| ark_bn254::Fr::rand(&mut rng)).collect_vec();
+        let indices = [7u64, 0, 9, 7];
+        let value = ark_bn254::Fr::rand(&mut rng);
+        let index_shares = twopc::share_field_elements(&indices.map(ark_bn254::Fr::from), &mut rng);
+        let table_shares = twopc::share_field_elements(&table, &mut rng);
+        let value_shares = twopc::share_field_element(value, &mut rng);
+        let mut should_table = table.clone();
+        should_table[indices[0] as usize] = value;
+
+        let test_network = ShamirTestNetwork::new(2);
+        let mut threads = Vec::with_capacity(2);
+        for (net, indices, shared_table, value) in izip!(
+            test_network.get_party_networks(),
+            index_shares,
+            table_shares,
+            value_shares
+        ) {
+            let table = table.clone();
+            threads.push(thread::spawn(move || {
+                let mut twopc = TwoPcProtocol::new(net).unwrap();
+                let public = lut::read_public_lut_many(&table, &indices, &mut twopc).unwrap();
+                let shared =
+                    lut::read_shared_lut_many(&shared_table, &indices, &mut twopc).unwrap();
+                let written =
+                    lut::write_lut_many(&shared_table, &indices[..1], &[value], &mut twopc)
+                        .unwrap();
+                let public = arithmetic::open_vec(&public, &mut twopc).unwrap();
+                let shared = arithmetic::open_vec(&shared, &mut twopc).unwrap();
+                let written = arithmetic::open_vec(&written, &mut twopc).unwrap();
+                (public, shared, written)
+            }));
+        }
+        let should_read = indices.iter().map(|i| table[*i as usize]).collect_vec();
+        for t in threads {
+            let (public, shared, written) = t.join().unwrap();
+            assert_eq!(public, should_read);
+            assert_eq!(shared, should_read);
+            assert_eq!(written, should_table);
+        }
+    }
+
+    #[test]
+    fn twopc_keccak() {
+        let mut rng = thread_rng();
+        let states = (0..2)
+            .map(|_| std::array::from_fn::<u64, 25, _>(|_| rng.gen()))
+            .collect_vec();
+        let to_bits = |states: &[[u64; 25]]| {
+            states
+                .iter()
+                .flat_map(|state| {
+                    state
+                        .iter()
+                        .flat_map(|lane| (0..64).map(move |i| ark_bn254::Fr::from((lane >> i) & 1)))
+                })
+                .collect_vec()
+        };
+        let bits = to_bits(&states);
+        assert_eq!(bits.len(), 2 * STATE_BITS);
+        let should_result = to_bits(
+            &states
+                .iter()
+                .map(|state| {
+                    let mut state = *state;
+                    keccak_f1600(&mut state);
+                    state
+                })
+                .collect_vec(),
+        );
+        let bit_shares = twopc::share_field_elements(&bits, &mut rng);
+
+        let test_network = ShamirTestNetwork::new(2);
+        let mut threads = Vec::with_capacity(2);
+        for (net, bits) in izip!(test_network.get_party_networks(), bit_shares) {
+            threads.push(thread::spawn(move || {
+                let mut twopc = TwoPcProtocol::new(net).unwrap();
+                let res = keccak::keccak_f1600_bits_many(&bits, &mut twopc).unwrap();
+                arithmetic::open_vec(&res, &mut twopc).unwrap()
+            }));
+        }
+        for t in threads {
+            assert_eq!(t.join().unwrap(), should_result);
+        }
+    }
+}